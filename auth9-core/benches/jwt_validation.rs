@@ -0,0 +1,145 @@
+//! JWT validation throughput
+//!
+//! Compares the request-authentication path (`verify_bearer_session`, one
+//! signature check picked by token type) against the sequential chain it
+//! replaced (service client, identity, then tenant access verification), for
+//! HS256 and RS256 keys, single-threaded and with concurrent verifiers.
+//!
+//! Run with `cargo bench --bench jwt_validation`.
+
+use auth9_core::config::JwtConfig;
+use auth9_core::jwt::JwtManager;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const CONCURRENT_THREADS: usize = 8;
+
+fn hmac_manager() -> JwtManager {
+    JwtManager::new(JwtConfig {
+        secret: "benchmark-secret-key-for-jwt-validation".to_string(),
+        issuer: "https://auth9.bench".to_string(),
+        access_token_ttl_secs: 3600,
+        refresh_token_ttl_secs: 86400,
+        private_key_pem: None,
+        public_key_pem: None,
+        previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
+    })
+}
+
+fn rsa_manager() -> JwtManager {
+    let private_key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048).unwrap();
+    let private_pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    let public_pem = rsa::RsaPublicKey::from(&private_key)
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    JwtManager::new(JwtConfig {
+        secret: String::new(),
+        issuer: "https://auth9.bench".to_string(),
+        access_token_ttl_secs: 3600,
+        refresh_token_ttl_secs: 86400,
+        private_key_pem: Some(private_pem.to_string()),
+        public_key_pem: Some(public_pem),
+        previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
+    })
+}
+
+/// Tenant access token with custom claims, the worst case for the old chain
+fn tenant_access_token(manager: &JwtManager) -> String {
+    let custom_claims = (0..8)
+        .map(|i| (format!("claim_{}", i), serde_json::json!({ "value": i })))
+        .collect();
+    manager
+        .create_tenant_access_token_with_claims(
+            Uuid::new_v4(),
+            "bench@example.com",
+            Uuid::new_v4(),
+            "bench-app",
+            vec!["admin".to_string(), "editor".to_string()],
+            vec!["user:read".to_string(), "user:write".to_string()],
+            Some(Uuid::new_v4().to_string()),
+            Some(custom_claims),
+        )
+        .unwrap()
+}
+
+/// The verification chain used before token-type dispatch
+fn sequential_chain(manager: &JwtManager, token: &str) -> bool {
+    manager.verify_service_client_token(token).is_ok()
+        || manager.verify_identity_token(token).is_ok()
+        || manager
+            .verify_tenant_access_token_any_audience(token)
+            .is_ok()
+}
+
+fn bench_single_thread(c: &mut Criterion) {
+    let mut group = c.benchmark_group("jwt_validation");
+    group.throughput(Throughput::Elements(1));
+    for (name, manager) in [("hs256", hmac_manager()), ("rs256", rsa_manager())] {
+        let token = tenant_access_token(&manager);
+        group.bench_with_input(
+            BenchmarkId::new("sequential_chain", name),
+            &token,
+            |b, t| b.iter(|| assert!(sequential_chain(&manager, t))),
+        );
+        group.bench_with_input(BenchmarkId::new("bearer_session", name), &token, |b, t| {
+            b.iter(|| manager.verify_bearer_session(t).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("bearer_token", name), &token, |b, t| {
+            b.iter(|| manager.verify_bearer_token(t).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Split `iters` verifications across threads sharing clones of one manager
+fn run_concurrent(
+    manager: &JwtManager,
+    token: &str,
+    iters: u64,
+    f: fn(&JwtManager, &str),
+) -> Duration {
+    let per_thread = iters.div_ceil(CONCURRENT_THREADS as u64);
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..CONCURRENT_THREADS {
+            let manager = manager.clone();
+            scope.spawn(move || {
+                for _ in 0..per_thread {
+                    f(&manager, token);
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn bench_concurrent(c: &mut Criterion) {
+    let mut group = c.benchmark_group("jwt_validation_concurrent");
+    group.throughput(Throughput::Elements(1));
+    let manager = hmac_manager();
+    let token = tenant_access_token(&manager);
+    group.bench_function("sequential_chain", |b| {
+        b.iter_custom(|iters| {
+            run_concurrent(&manager, &token, iters, |m, t| {
+                assert!(sequential_chain(m, t));
+            })
+        })
+    });
+    group.bench_function("bearer_session", |b| {
+        b.iter_custom(|iters| {
+            run_concurrent(&manager, &token, iters, |m, t| {
+                m.verify_bearer_session(t).unwrap();
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_thread, bench_concurrent);
+criterion_main!(benches);
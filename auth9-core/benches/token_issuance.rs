@@ -0,0 +1,162 @@
+//! Token issuance and permission check throughput
+//!
+//! Measures the CPU cost of what the token endpoints sign per request
+//! (identity, tenant access, refresh and service client tokens) for HS256
+//! and RS256 keys, and of the permission check done on every authorized
+//! request (tenant access token verification, claims extraction and
+//! wildcard permission matching).
+//!
+//! End-to-end throughput against a running stack, including session
+//! creation, is measured by the `auth9-loadgen` binary instead.
+//!
+//! Run with `cargo bench --bench token_issuance`.
+
+use auth9_core::config::JwtConfig;
+use auth9_core::jwt::JwtManager;
+use auth9_core::middleware::auth::AuthUser;
+use auth9_core::models::rbac::permission_matches;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+use uuid::Uuid;
+
+/// Permissions in the benchmarked tenant access token, a large role set
+const GRANTED_PERMISSIONS: usize = 64;
+
+fn hmac_manager() -> JwtManager {
+    JwtManager::new(JwtConfig {
+        secret: "benchmark-secret-key-for-token-issuance".to_string(),
+        issuer: "https://auth9.bench".to_string(),
+        access_token_ttl_secs: 3600,
+        refresh_token_ttl_secs: 86400,
+        private_key_pem: None,
+        public_key_pem: None,
+        previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
+    })
+}
+
+fn rsa_manager() -> JwtManager {
+    let private_key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048).unwrap();
+    let private_pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    let public_pem = rsa::RsaPublicKey::from(&private_key)
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    JwtManager::new(JwtConfig {
+        secret: String::new(),
+        issuer: "https://auth9.bench".to_string(),
+        access_token_ttl_secs: 3600,
+        refresh_token_ttl_secs: 86400,
+        private_key_pem: Some(private_pem.to_string()),
+        public_key_pem: Some(public_pem),
+        previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
+    })
+}
+
+/// `resource_N:action` codes plus one wildcard grant at the end
+fn granted_permissions() -> Vec<String> {
+    let mut permissions: Vec<String> = (0..GRANTED_PERMISSIONS - 1)
+        .map(|i| format!("resource_{}:read", i))
+        .collect();
+    permissions.push("billing:invoices:*".to_string());
+    permissions
+}
+
+fn bench_issuance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("token_issuance");
+    group.throughput(Throughput::Elements(1));
+    let user_id = Uuid::new_v4();
+    let tenant_id = Uuid::new_v4();
+    for (name, manager) in [("hs256", hmac_manager()), ("rs256", rsa_manager())] {
+        group.bench_function(BenchmarkId::new("identity", name), |b| {
+            b.iter(|| {
+                manager
+                    .create_identity_token_with_session(
+                        user_id,
+                        "bench@example.com",
+                        Some("Bench User"),
+                        Some(Uuid::new_v4()),
+                    )
+                    .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("tenant_access", name), |b| {
+            b.iter(|| {
+                manager
+                    .create_tenant_access_token_with_session(
+                        user_id,
+                        "bench@example.com",
+                        tenant_id,
+                        "bench-app",
+                        vec!["admin".to_string(), "editor".to_string()],
+                        granted_permissions(),
+                        Some(Uuid::new_v4().to_string()),
+                    )
+                    .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("refresh", name), |b| {
+            b.iter(|| {
+                manager
+                    .create_refresh_token(user_id, tenant_id, "bench-app")
+                    .unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("service_client", name), |b| {
+            b.iter(|| {
+                manager
+                    .create_service_client_token(
+                        Uuid::new_v4(),
+                        "service+bench-app@auth9.local",
+                        Some(tenant_id),
+                    )
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_permission_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permission_check");
+    group.throughput(Throughput::Elements(1));
+    let granted = granted_permissions();
+    for (name, required) in [
+        ("first", "resource_0:read"),
+        ("last_exact", "resource_62:read"),
+        ("wildcard", "billing:invoices:export"),
+        ("denied", "user:delete"),
+    ] {
+        group.bench_with_input(BenchmarkId::new("match", name), &required, |b, r| {
+            b.iter(|| granted.iter().any(|g| permission_matches(g, r)))
+        });
+    }
+
+    // The full per-request path: verify the bearer token, build the
+    // extractor value, check one permission
+    for (name, manager) in [("hs256", hmac_manager()), ("rs256", rsa_manager())] {
+        let token = manager
+            .create_tenant_access_token(
+                Uuid::new_v4(),
+                "bench@example.com",
+                Uuid::new_v4(),
+                "bench-app",
+                vec!["admin".to_string()],
+                granted.clone(),
+            )
+            .unwrap();
+        group.bench_with_input(BenchmarkId::new("request", name), &token, |b, t| {
+            b.iter(|| {
+                let claims = manager.verify_tenant_access_token_any_audience(t).unwrap();
+                let auth = AuthUser::from_tenant_access_claims(claims).unwrap();
+                auth.has_permission("billing:invoices:export")
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_issuance, bench_permission_check);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+-- Tenant custom domains for hosted login/consent pages.
+-- A domain is routed to its tenant only after the DNS TXT challenge succeeds.
+-- Certificates are either issued on demand via ACME by the edge proxy or uploaded
+-- by the tenant (private key stored AES-GCM encrypted).
+CREATE TABLE IF NOT EXISTS tenant_custom_domains (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    domain VARCHAR(253) NOT NULL,
+    verification_token VARCHAR(64) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    last_checked_at TIMESTAMP NULL,
+    verified_at TIMESTAMP NULL,
+    certificate_source VARCHAR(16) NOT NULL DEFAULT 'acme',
+    certificate_pem TEXT NULL,
+    private_key_encrypted TEXT NULL,
+    certificate_expires_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE INDEX idx_tcd_domain (domain),
+    INDEX idx_tcd_tenant (tenant_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
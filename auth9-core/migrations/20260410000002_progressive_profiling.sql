@@ -0,0 +1,23 @@
+-- Progressive profiling: services declare profile fields they need per OAuth scope.
+-- When a token is requested and the user lacks one of these fields, the token
+-- endpoint answers `interaction_required` and the hosted form collects it.
+-- scope = '*' applies the requirement regardless of the requested scopes.
+CREATE TABLE IF NOT EXISTS service_profile_requirements (
+    id CHAR(36) PRIMARY KEY,
+    service_id CHAR(36) NOT NULL,
+    scope VARCHAR(128) NOT NULL DEFAULT '*',
+    field_name VARCHAR(64) NOT NULL,
+    label VARCHAR(255) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE INDEX idx_spr_service_scope_field (service_id, scope, field_name),
+    INDEX idx_spr_service (service_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Attributes collected from users via the progressive profiling form.
+CREATE TABLE IF NOT EXISTS user_profile_attributes (
+    user_id CHAR(36) NOT NULL,
+    attr_key VARCHAR(64) NOT NULL,
+    attr_value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, attr_key)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
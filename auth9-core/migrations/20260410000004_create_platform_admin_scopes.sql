@@ -0,0 +1,13 @@
+-- Scoped platform admin permissions. Platform admins listed in
+-- PLATFORM_ADMIN_EMAILS keep every scope; other platform admins
+-- (auth9-platform tenant admins) are limited to the scopes granted here
+-- once PLATFORM_ADMIN_SCOPES_ENFORCED is enabled.
+CREATE TABLE IF NOT EXISTS platform_admin_scopes (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    scope VARCHAR(64) NOT NULL,
+    granted_by CHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_platform_admin_scopes_user_scope (user_id, scope),
+    INDEX idx_platform_admin_scopes_user (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
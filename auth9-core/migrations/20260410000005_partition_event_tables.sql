@@ -0,0 +1,95 @@
+-- Partition login_events and audit_logs by month.
+--
+-- Partitioned tables need the partition column in every unique key, so the
+-- primary key becomes (id, created_at). TiDB cannot drop a clustered primary
+-- key in place, so each table is rebuilt and swapped.
+--
+-- Rows before 2026-05 land in p_history; everything else starts in p_future.
+-- Monthly partitions are split out of p_future, and partitions past the
+-- retention horizon are dropped, by migration::partition (run after
+-- migrations and periodically by the server).
+
+CREATE TABLE login_events_partitioned (
+    id BIGINT AUTO_INCREMENT,
+    user_id CHAR(36),
+    email VARCHAR(320),
+    tenant_id CHAR(36),
+    event_type VARCHAR(50) NOT NULL,
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    device_type VARCHAR(50),
+    location VARCHAR(255),
+    session_id CHAR(36),
+    failure_reason VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    provider_alias VARCHAR(255) DEFAULT NULL,
+    provider_type VARCHAR(50) DEFAULT NULL,
+    latitude DOUBLE NULL,
+    longitude DOUBLE NULL,
+    country_code VARCHAR(2) NULL,
+    risk_score TINYINT UNSIGNED NULL,
+
+    PRIMARY KEY (id, created_at),
+    INDEX idx_login_events_user_id (user_id),
+    INDEX idx_login_events_tenant_id (tenant_id),
+    INDEX idx_login_events_created_at (created_at),
+    INDEX idx_login_events_event_type (event_type),
+    INDEX idx_login_events_ip_address (ip_address),
+    INDEX idx_login_events_provider (provider_alias, provider_type)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+PARTITION BY RANGE (UNIX_TIMESTAMP(created_at)) (
+    PARTITION p_history VALUES LESS THAN (1777593600),
+    PARTITION p_future VALUES LESS THAN MAXVALUE
+);
+
+INSERT INTO login_events_partitioned (
+    id, user_id, email, tenant_id, event_type, ip_address, user_agent,
+    device_type, location, session_id, failure_reason, created_at,
+    provider_alias, provider_type, latitude, longitude, country_code, risk_score
+)
+SELECT
+    id, user_id, email, tenant_id, event_type, ip_address, user_agent,
+    device_type, location, session_id, failure_reason, created_at,
+    provider_alias, provider_type, latitude, longitude, country_code, risk_score
+FROM login_events;
+
+RENAME TABLE login_events TO login_events_unpartitioned,
+             login_events_partitioned TO login_events;
+
+DROP TABLE login_events_unpartitioned;
+
+CREATE TABLE audit_logs_partitioned (
+    id BIGINT AUTO_INCREMENT,
+    actor_id CHAR(36),
+    action VARCHAR(100) NOT NULL,
+    resource_type VARCHAR(50) NOT NULL,
+    resource_id CHAR(36),
+    old_value JSON,
+    new_value JSON,
+    ip_address VARCHAR(45),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (id, created_at),
+    INDEX idx_audit_logs_actor (actor_id),
+    INDEX idx_audit_logs_action (action),
+    INDEX idx_audit_logs_resource (resource_type, resource_id),
+    INDEX idx_audit_logs_created_at (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci
+PARTITION BY RANGE (UNIX_TIMESTAMP(created_at)) (
+    PARTITION p_history VALUES LESS THAN (1777593600),
+    PARTITION p_future VALUES LESS THAN MAXVALUE
+);
+
+INSERT INTO audit_logs_partitioned (
+    id, actor_id, action, resource_type, resource_id, old_value, new_value,
+    ip_address, created_at
+)
+SELECT
+    id, actor_id, action, resource_type, resource_id, old_value, new_value,
+    ip_address, created_at
+FROM audit_logs;
+
+RENAME TABLE audit_logs TO audit_logs_unpartitioned,
+             audit_logs_partitioned TO audit_logs;
+
+DROP TABLE audit_logs_unpartitioned;
@@ -0,0 +1,47 @@
+-- OAuth 2.0 Dynamic Client Registration (RFC 7591 / RFC 7592)
+
+-- Per-tenant registration policy. Registration is disabled unless a row
+-- exists with enabled = TRUE.
+CREATE TABLE IF NOT EXISTS client_registration_policies (
+    tenant_id CHAR(36) PRIMARY KEY,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    require_software_statement BOOLEAN NOT NULL DEFAULT FALSE,
+    software_statement_issuers JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Initial access tokens handed to partners by tenant admins
+CREATE TABLE IF NOT EXISTS client_registration_initial_tokens (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    token_prefix VARCHAR(16) NOT NULL,
+    description VARCHAR(255) NULL,
+    max_uses INT NULL,
+    use_count INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NULL,
+    revoked_at TIMESTAMP NULL,
+    created_by CHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_client_registration_initial_tokens_hash (token_hash),
+    INDEX idx_client_registration_initial_tokens_tenant (tenant_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Dynamically registered clients and their registration access tokens
+CREATE TABLE IF NOT EXISTS client_registrations (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    service_id CHAR(36) NOT NULL,
+    client_id VARCHAR(255) NOT NULL,
+    registration_token_hash CHAR(64) NOT NULL,
+    initial_token_id CHAR(36) NULL,
+    software_id VARCHAR(255) NULL,
+    metadata JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_client_registrations_client_id (client_id),
+    UNIQUE KEY uk_client_registrations_token_hash (registration_token_hash),
+    INDEX idx_client_registrations_tenant (tenant_id),
+    INDEX idx_client_registrations_service (service_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,17 @@
+-- Per-service permission namespaces. When a service has a namespace, new
+-- permission codes are prefixed with it (`invoices:read` is stored as
+-- `billing:invoices:read`).
+CREATE TABLE IF NOT EXISTS service_permission_namespaces (
+    service_id CHAR(36) PRIMARY KEY,
+    namespace VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Normalize existing permission codes to the structured `resource:action`
+-- form: lowercase, trimmed, with `.` and `/` separators replaced by `:`.
+-- IGNORE leaves a code untouched when its normalized form already exists
+-- in the same service.
+UPDATE IGNORE permissions
+SET code = LOWER(TRIM(REPLACE(REPLACE(code, '.', ':'), '/', ':')))
+WHERE BINARY code <> BINARY LOWER(TRIM(REPLACE(REPLACE(code, '.', ':'), '/', ':')));
@@ -0,0 +1,12 @@
+-- Per-user notification preferences. Users without a row get the defaults:
+-- security alerts on, login notifications and digest off.
+CREATE TABLE IF NOT EXISTS user_notification_preferences (
+    user_id CHAR(36) PRIMARY KEY,
+    security_alerts BOOLEAN NOT NULL DEFAULT TRUE,
+    login_notifications BOOLEAN NOT NULL DEFAULT FALSE,
+    digest_frequency VARCHAR(16) NOT NULL DEFAULT 'none',
+    last_digest_sent_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_notification_prefs_digest (digest_frequency, last_digest_sent_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,14 @@
+-- Claims enrichment plugins enabled per service. Plugins are compiled into
+-- auth9-core; this table only selects them and carries their settings.
+CREATE TABLE IF NOT EXISTS service_claims_enrichers (
+    id CHAR(36) PRIMARY KEY,
+    service_id CHAR(36) NOT NULL,
+    plugin VARCHAR(64) NOT NULL,
+    settings JSON NOT NULL,
+    timeout_ms INT UNSIGNED NOT NULL DEFAULT 500,
+    failure_policy VARCHAR(16) NOT NULL DEFAULT 'skip',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_service_claims_enrichers (service_id, plugin)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
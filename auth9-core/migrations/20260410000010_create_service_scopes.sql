@@ -0,0 +1,12 @@
+-- OAuth scopes a service declares for its clients. Requests for scopes that
+-- are neither standard OIDC scopes nor declared here are rejected.
+CREATE TABLE IF NOT EXISTS service_scopes (
+    id CHAR(36) PRIMARY KEY,
+    service_id CHAR(36) NOT NULL,
+    name VARCHAR(128) NOT NULL,
+    description VARCHAR(512) NOT NULL,
+    required_permissions JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_service_scopes (service_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,29 @@
+-- Persistent outbound email queue. Rows are claimed by the email worker on any
+-- replica and retried with exponential backoff until they are delivered or
+-- exhaust their attempts.
+CREATE TABLE IF NOT EXISTS email_queue (
+    id CHAR(36) PRIMARY KEY,
+    recipients JSON NOT NULL,
+    payload JSON NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT NULL,
+    provider VARCHAR(16) NULL,
+    provider_message_id VARCHAR(255) NULL,
+    claim_id VARCHAR(64) NULL,
+    claimed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at TIMESTAMP NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_email_queue_due (status, next_attempt_at),
+    INDEX idx_email_queue_claim (claim_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Addresses that must not receive mail (hard bounces, complaints, manual blocks)
+CREATE TABLE IF NOT EXISTS email_suppressions (
+    email VARCHAR(320) PRIMARY KEY,
+    reason VARCHAR(16) NOT NULL,
+    detail VARCHAR(512) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
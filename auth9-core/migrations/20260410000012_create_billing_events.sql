@@ -0,0 +1,23 @@
+-- Billing event ledger. Each usage or lifecycle event is recorded once per
+-- idempotency key and delivered to the configured billing sink by the
+-- billing dispatcher, retrying with exponential backoff.
+CREATE TABLE IF NOT EXISTS billing_events (
+    id CHAR(36) PRIMARY KEY,
+    idempotency_key VARCHAR(191) NOT NULL,
+    tenant_id CHAR(36) NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    data JSON NOT NULL,
+    occurred_at TIMESTAMP NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT NULL,
+    claim_id VARCHAR(64) NULL,
+    claimed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    delivered_at TIMESTAMP NULL,
+    UNIQUE KEY uq_billing_events_idempotency_key (idempotency_key),
+    INDEX idx_billing_events_due (status, next_attempt_at),
+    INDEX idx_billing_events_tenant (tenant_id, created_at),
+    INDEX idx_billing_events_claim (claim_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
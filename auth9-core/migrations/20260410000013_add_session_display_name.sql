@@ -0,0 +1,3 @@
+-- User-chosen device name shown in the self-service device list. When unset,
+-- the name parsed from the user agent (device_name) is shown instead.
+ALTER TABLE sessions ADD COLUMN display_name VARCHAR(100) NULL AFTER device_name;
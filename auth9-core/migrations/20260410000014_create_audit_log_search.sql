@@ -0,0 +1,21 @@
+-- Full-text search over audit logs.
+-- MySQL does not support FULLTEXT indexes on partitioned tables, so the
+-- searchable text of each audit_logs row lives in this side table. The ngram
+-- parser indexes substrings, which also matches parts of emails, IDs and
+-- dotted action names.
+CREATE TABLE IF NOT EXISTS audit_log_search (
+    audit_log_id BIGINT NOT NULL PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL,
+    document TEXT NOT NULL,
+
+    INDEX idx_audit_log_search_created_at (created_at),
+    FULLTEXT INDEX ft_audit_log_search_document (document) WITH PARSER ngram
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO audit_log_search (audit_log_id, created_at, document)
+SELECT
+    id,
+    created_at,
+    LEFT(CONCAT_WS(' ', action, resource_type, resource_id,
+                   CAST(old_value AS CHAR), CAST(new_value AS CHAR)), 16000)
+FROM audit_logs;
@@ -0,0 +1,31 @@
+-- Shareable invitation links. A link is not bound to an email address; each
+-- join is recorded in invitation_link_redemptions for attribution. Tokens
+-- are stored as SHA-256 hashes so a link can be looked up directly.
+CREATE TABLE IF NOT EXISTS invitation_links (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NULL,
+    role_ids JSON NOT NULL,
+    allowed_email_domains JSON NOT NULL,
+    max_uses INT NULL,
+    use_count INT NOT NULL DEFAULT 0,
+    created_by CHAR(36) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_invitation_links_token_hash (token_hash),
+    INDEX idx_invitation_links_tenant (tenant_id, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS invitation_link_redemptions (
+    id CHAR(36) PRIMARY KEY,
+    link_id CHAR(36) NOT NULL,
+    tenant_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_invitation_link_redemptions_user (link_id, user_id),
+    INDEX idx_invitation_link_redemptions_tenant_user (tenant_id, user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,22 @@
+-- Webhook delivery attempts. Every attempt (including retries, tests, pings,
+-- replays and synthetic events) is recorded with its outcome, latency and a
+-- truncated response body. The signed payload is kept so a delivery can be
+-- replayed verbatim.
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id CHAR(36) PRIMARY KEY,
+    webhook_id CHAR(36) NOT NULL,
+    tenant_id CHAR(36) NOT NULL,
+    event_type VARCHAR(128) NOT NULL,
+    payload MEDIUMTEXT NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    attempt INT NOT NULL DEFAULT 1,
+    success BOOLEAN NOT NULL,
+    status_code INT NULL,
+    response_time_ms BIGINT NULL,
+    response_snippet TEXT NULL,
+    error TEXT NULL,
+    replay_of CHAR(36) NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    INDEX idx_webhook_deliveries_webhook (webhook_id, created_at),
+    INDEX idx_webhook_deliveries_tenant (tenant_id, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,16 @@
+-- Tenant conditional access policies. Each policy combines client, network,
+-- device trust and time-of-day conditions (stored as JSON) and resolves to an
+-- allow / require_mfa / block outcome evaluated at token issuance.
+CREATE TABLE IF NOT EXISTS conditional_access_policies (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    priority INT NOT NULL DEFAULT 0,
+    conditions JSON NOT NULL,
+    outcome VARCHAR(20) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_conditional_access_tenant (tenant_id, priority)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
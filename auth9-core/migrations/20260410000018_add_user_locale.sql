@@ -0,0 +1,3 @@
+-- Preferred locale for emails and messages (BCP 47 tag, e.g. 'zh-CN').
+-- NULL means the locale is negotiated per request, then the tenant default.
+ALTER TABLE users ADD COLUMN locale VARCHAR(16) NULL AFTER locked_until;
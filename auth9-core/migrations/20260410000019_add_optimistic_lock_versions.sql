@@ -0,0 +1,6 @@
+-- Optimistic concurrency: every update bumps `version`; REST updates accept
+-- the version read by the client (If-Match) and fail if it has moved on.
+ALTER TABLE tenants ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE users ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE services ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE roles ADD COLUMN version INT NOT NULL DEFAULT 1;
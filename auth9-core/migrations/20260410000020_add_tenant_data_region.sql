@@ -0,0 +1,4 @@
+-- Data residency: tenants can be pinned to a region whose database holds
+-- their tenant-scoped rows. NULL means the deployment's home region.
+ALTER TABLE tenants ADD COLUMN data_region VARCHAR(32) NULL;
+CREATE INDEX idx_tenants_data_region ON tenants (data_region);
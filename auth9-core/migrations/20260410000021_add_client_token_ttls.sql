@@ -0,0 +1,4 @@
+-- Per-client token lifetime overrides (NULL falls back to the tenant
+-- setting, then the platform default)
+ALTER TABLE clients ADD COLUMN access_token_ttl_secs BIGINT NULL;
+ALTER TABLE clients ADD COLUMN refresh_token_ttl_secs BIGINT NULL;
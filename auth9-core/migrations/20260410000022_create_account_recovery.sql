@@ -0,0 +1,28 @@
+-- Admin-mediated account recovery for users who lost access to both their
+-- password and their email. A request needs approvals from two distinct
+-- platform admins before the requester can claim a password reset token.
+CREATE TABLE IF NOT EXISTS account_recovery_requests (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    reason TEXT NULL,
+    claim_secret_hash CHAR(64) NOT NULL,
+    requester_ip VARCHAR(45) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    decided_at TIMESTAMP NULL,
+    claimed_at TIMESTAMP NULL,
+    INDEX idx_account_recovery_user_status (user_id, status),
+    INDEX idx_account_recovery_status_created (status, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS account_recovery_approvals (
+    id CHAR(36) PRIMARY KEY,
+    request_id CHAR(36) NOT NULL,
+    admin_id CHAR(36) NOT NULL,
+    decision VARCHAR(16) NOT NULL,
+    comment TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_account_recovery_approval (request_id, admin_id),
+    INDEX idx_account_recovery_approvals_request (request_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,2 @@
+-- Optional CEL filter evaluated against each event before delivery
+ALTER TABLE webhooks ADD COLUMN filter_expression VARCHAR(1024) NULL AFTER events;
@@ -0,0 +1,28 @@
+-- History of LDAP/Active Directory directory syncs. The cursor of the latest
+-- successful non-dry run (highest committed USN on Active Directory, latest
+-- modifyTimestamp elsewhere) is where the next incremental sync resumes.
+CREATE TABLE IF NOT EXISTS ldap_sync_runs (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    connector_id CHAR(36) NOT NULL,
+    triggered_by VARCHAR(16) NOT NULL,
+    mode VARCHAR(16) NOT NULL,
+    dry_run BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(16) NOT NULL DEFAULT 'running',
+    conflict_policy VARCHAR(16) NOT NULL,
+    cursor_before VARCHAR(64) NULL,
+    cursor_after VARCHAR(64) NULL,
+    users_seen INT NOT NULL DEFAULT 0,
+    users_created INT NOT NULL DEFAULT 0,
+    users_updated INT NOT NULL DEFAULT 0,
+    users_linked INT NOT NULL DEFAULT 0,
+    conflicts INT NOT NULL DEFAULT 0,
+    roles_assigned INT NOT NULL DEFAULT 0,
+    roles_removed INT NOT NULL DEFAULT 0,
+    changes JSON NULL,
+    error TEXT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP NULL,
+    INDEX idx_ldap_sync_runs_connector_started (connector_id, started_at),
+    INDEX idx_ldap_sync_runs_tenant (tenant_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,18 @@
+-- Per-account outcomes of password breach campaigns. Passwords stored as
+-- unsalted SHA-1 (imported from legacy systems) are checked while the campaign
+-- runs; all others stay `pending` until the user's next password login.
+CREATE TABLE IF NOT EXISTS password_breach_checks (
+    id CHAR(36) PRIMARY KEY,
+    job_id CHAR(36) NOT NULL,
+    tenant_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    email VARCHAR(320) NOT NULL,
+    status VARCHAR(16) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    breach_count BIGINT NOT NULL DEFAULT 0,
+    action_taken VARCHAR(32) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    checked_at TIMESTAMP NULL,
+    UNIQUE KEY uk_password_breach_checks_job_user (job_id, user_id),
+    INDEX idx_password_breach_checks_user_status (user_id, status)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
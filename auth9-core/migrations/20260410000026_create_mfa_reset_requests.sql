@@ -0,0 +1,21 @@
+-- Admin-assisted MFA reset for users who lost their second factor. The user
+-- proves their password, describes how a tenant admin can verify them, and
+-- a tenant admin approves or rejects the request. Approval opens a short
+-- window in which the user can clear their factors and enroll new ones.
+CREATE TABLE IF NOT EXISTS mfa_reset_requests (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    identity_proof TEXT NOT NULL,
+    requester_ip VARCHAR(45) NULL,
+    reviewed_by CHAR(36) NULL,
+    review_comment TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP NOT NULL,
+    reviewed_at TIMESTAMP NULL,
+    window_expires_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    INDEX idx_mfa_reset_tenant_status_created (tenant_id, status, created_at),
+    INDEX idx_mfa_reset_user_status (user_id, status)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
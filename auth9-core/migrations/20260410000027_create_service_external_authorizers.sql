@@ -0,0 +1,16 @@
+-- External authorizer (OPA or OpenFGA) that a service's permission checks
+-- are delegated to. In shadow mode the built-in RBAC result still decides
+-- and the external decision is only compared against it.
+CREATE TABLE IF NOT EXISTS service_external_authorizers (
+    service_id CHAR(36) PRIMARY KEY,
+    provider VARCHAR(16) NOT NULL,
+    endpoint VARCHAR(512) NOT NULL,
+    settings JSON NOT NULL,
+    mode VARCHAR(16) NOT NULL DEFAULT 'shadow',
+    fallback VARCHAR(16) NOT NULL DEFAULT 'local',
+    timeout_ms INT UNSIGNED NOT NULL DEFAULT 200,
+    cache_ttl_secs INT UNSIGNED NOT NULL DEFAULT 30,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
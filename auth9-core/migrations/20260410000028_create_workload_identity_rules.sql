@@ -0,0 +1,19 @@
+-- Workload identity federation rules. A CI pipeline (GitHub Actions, GitLab
+-- CI, ...) exchanges an OIDC token from a trusted issuer for a short-lived
+-- service token when the token's subject and claims match a rule.
+CREATE TABLE IF NOT EXISTS workload_identity_rules (
+    id CHAR(36) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    service_id CHAR(36) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    issuer VARCHAR(512) NOT NULL,
+    audience VARCHAR(512) NOT NULL,
+    subject_pattern VARCHAR(512) NOT NULL,
+    claim_conditions JSON NOT NULL,
+    token_ttl_secs INT UNSIGNED NOT NULL DEFAULT 900,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_workload_identity_tenant (tenant_id),
+    INDEX idx_workload_identity_service (service_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
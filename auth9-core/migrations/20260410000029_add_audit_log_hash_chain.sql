@@ -0,0 +1,35 @@
+-- Audit log tamper evidence.
+-- Each new audit_logs row stores its position in a hash chain, the hash of
+-- the previous entry and its own hash, so editing, deleting or reordering
+-- rows breaks the chain. The hash covers a digest of actor_id rather than
+-- actor_id itself, which keeps the chain intact when a deleted user's
+-- actor_id is nullified. Rows written before this migration stay unchained.
+ALTER TABLE audit_logs ADD COLUMN chain_seq BIGINT NULL;
+ALTER TABLE audit_logs ADD COLUMN actor_digest CHAR(64) NULL;
+ALTER TABLE audit_logs ADD COLUMN prev_hash CHAR(64) NULL;
+ALTER TABLE audit_logs ADD COLUMN row_hash CHAR(64) NULL;
+ALTER TABLE audit_logs ADD INDEX idx_audit_logs_chain_seq (chain_seq);
+
+-- Head of the chain. Writers lock this single row, which serializes
+-- appends to the chain.
+CREATE TABLE IF NOT EXISTS audit_chain_head (
+    id TINYINT PRIMARY KEY,
+    last_seq BIGINT NOT NULL,
+    last_hash CHAR(64) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO audit_chain_head (id, last_seq, last_hash)
+VALUES (1, 0, '0000000000000000000000000000000000000000000000000000000000000000');
+
+-- Periodic checkpoints of the chain head, signed with AUDIT_CHECKPOINT_KEY.
+-- A checkpoint proves the chain reached chain_seq with hash row_hash, so
+-- truncating the newest entries (or rewriting the whole chain) is detected.
+CREATE TABLE IF NOT EXISTS audit_checkpoints (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    chain_seq BIGINT NOT NULL,
+    row_hash CHAR(64) NOT NULL,
+    signature CHAR(64) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_audit_checkpoints_chain_seq (chain_seq)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,4 @@
+-- Platform webhooks are not bound to a tenant: they have no tenant_id and
+-- receive platform events (tenant lifecycle, platform security alerts).
+ALTER TABLE webhooks MODIFY tenant_id CHAR(36) NULL;
+ALTER TABLE webhook_deliveries MODIFY tenant_id CHAR(36) NULL;
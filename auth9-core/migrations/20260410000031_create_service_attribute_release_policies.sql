@@ -0,0 +1,20 @@
+-- Attribute release policies: the user attributes a service may receive in
+-- ID tokens and userinfo. Release is deny-by-default; services without a row
+-- receive no claims beyond `sub`.
+CREATE TABLE IF NOT EXISTS service_attribute_release_policies (
+    service_id CHAR(36) PRIMARY KEY,
+    released_attributes JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Existing services keep receiving the standard OIDC claims. Custom scope
+-- attributes must be allowed explicitly.
+INSERT IGNORE INTO service_attribute_release_policies (service_id, released_attributes)
+SELECT id, JSON_ARRAY(
+    'name', 'family_name', 'given_name', 'middle_name', 'nickname',
+    'preferred_username', 'profile', 'picture', 'website', 'gender',
+    'birthdate', 'zoneinfo', 'locale', 'updated_at',
+    'email', 'email_verified', 'phone_number', 'phone_number_verified', 'address'
+)
+FROM services;
@@ -0,0 +1,10 @@
+-- Retired tenant slugs. After a rename the old slug keeps resolving to the
+-- tenant (with a deprecation signal) until expires_at, and cannot be claimed
+-- by another tenant in the meantime.
+CREATE TABLE IF NOT EXISTS tenant_slug_redirects (
+    old_slug VARCHAR(63) PRIMARY KEY,
+    tenant_id CHAR(36) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_tenant_slug_redirects_tenant (tenant_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,12 @@
+-- Sign-in notification emails become opt-out. Users without a preferences
+-- row get them by default; existing rows keep the value they stored.
+ALTER TABLE user_notification_preferences
+    ALTER COLUMN login_notifications SET DEFAULT TRUE,
+    ALGORITHM=INSTANT;
+
+-- Alerts raised by users reporting a sign-in as not theirs
+ALTER TABLE security_alerts MODIFY COLUMN alert_type
+    ENUM('brute_force','slow_brute_force','password_spray','new_device',
+         'impossible_travel','suspicious_ip','high_risk_login',
+         'user_reported_sign_in') NOT NULL,
+    ALGORITHM=INSTANT;
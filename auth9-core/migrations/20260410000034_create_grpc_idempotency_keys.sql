@@ -0,0 +1,14 @@
+-- Idempotency keys of gRPC provisioning calls. A retried call with the same
+-- key replays the stored response instead of provisioning twice. `response`
+-- stays NULL while the first call is still running.
+CREATE TABLE IF NOT EXISTS grpc_idempotency_keys (
+    client_id VARCHAR(255) NOT NULL,
+    method VARCHAR(64) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    request_hash CHAR(64) NOT NULL,
+    response BLOB NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP NULL,
+    PRIMARY KEY (client_id, method, idempotency_key),
+    INDEX idx_grpc_idempotency_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
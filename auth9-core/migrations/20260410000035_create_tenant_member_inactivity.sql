@@ -0,0 +1,12 @@
+-- Progress of tenant members through their tenant's inactivity policy. A row
+-- is written when a member is warned and updated when the account is locked;
+-- steps recorded before the member's latest login are ignored.
+CREATE TABLE IF NOT EXISTS tenant_member_inactivity (
+    tenant_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    notified_at TIMESTAMP NULL,
+    disabled_at TIMESTAMP NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant_id, user_id),
+    INDEX idx_tenant_member_inactivity_user (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
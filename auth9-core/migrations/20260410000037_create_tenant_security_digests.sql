@@ -0,0 +1,7 @@
+-- When each tenant's last security alert digest went out. The next digest
+-- covers the alerts raised since then.
+CREATE TABLE IF NOT EXISTS tenant_security_digests (
+    tenant_id CHAR(36) PRIMARY KEY,
+    last_sent_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,13 @@
+-- Current data key version of each tenant. Data keys are derived from the
+-- master key and never stored; tenants without a row use version 1.
+CREATE TABLE IF NOT EXISTS tenant_encryption_keys (
+    tenant_id CHAR(36) PRIMARY KEY,
+    key_version INT UNSIGNED NOT NULL DEFAULT 1,
+    rotated_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Sealed webhook secrets are longer than their plaintext. Both lengths need
+-- a two-byte length prefix, so the column is widened in place.
+ALTER TABLE webhooks MODIFY COLUMN secret VARCHAR(1024) NULL, ALGORITHM=INPLACE, LOCK=NONE;
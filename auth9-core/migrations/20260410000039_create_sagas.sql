@@ -0,0 +1,19 @@
+-- Persisted state of multi-step operations spanning the identity engine and
+-- the database (user creation, tenant provisioning, service deletion). Step
+-- progress is written after every step so failed or interrupted operations
+-- can be resumed or compensated by an operator.
+CREATE TABLE IF NOT EXISTS sagas (
+    id CHAR(36) PRIMARY KEY,
+    kind VARCHAR(64) NOT NULL,
+    idempotency_key VARCHAR(512) NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'running',
+    direction VARCHAR(16) NOT NULL DEFAULT 'forward',
+    current_step INT NOT NULL DEFAULT 0,
+    steps JSON NOT NULL,
+    context JSON NOT NULL,
+    error TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_sagas_kind_key (kind, idempotency_key),
+    INDEX idx_sagas_status_updated (status, updated_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
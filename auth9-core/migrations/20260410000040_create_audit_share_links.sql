@@ -0,0 +1,27 @@
+-- Read-only, expiring links to a filtered audit log view for external
+-- auditors. Tokens are stored as SHA-256 hashes; every view through a link
+-- is recorded in audit_share_accesses.
+CREATE TABLE IF NOT EXISTS audit_share_links (
+    id CHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NULL,
+    filter JSON NOT NULL,
+    created_by CHAR(36) NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    access_count INT NOT NULL DEFAULT 0,
+    last_accessed_at TIMESTAMP NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_audit_share_links_token_hash (token_hash),
+    INDEX idx_audit_share_links_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS audit_share_accesses (
+    id CHAR(36) PRIMARY KEY,
+    link_id CHAR(36) NOT NULL,
+    ip_address VARCHAR(45) NULL,
+    user_agent VARCHAR(512) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_audit_share_accesses_link (link_id, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
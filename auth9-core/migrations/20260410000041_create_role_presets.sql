@@ -0,0 +1,49 @@
+-- Role preset templates: named sets of permissions and roles that can be
+-- applied when a service is registered. Every edit inserts a new version;
+-- applying a preset copies its roles into the service, so later versions
+-- never change services created from earlier ones.
+CREATE TABLE IF NOT EXISTS role_presets (
+    id CHAR(36) PRIMARY KEY,
+    preset_key VARCHAR(64) NOT NULL,
+    version INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    definition JSON NOT NULL,
+    created_by CHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_role_presets_key_version (preset_key, version)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO role_presets (id, preset_key, version, name, description, definition)
+VALUES (
+    '5d0c8f3e-2b1a-4c7e-9f6d-3a8b1e2c4d5f',
+    'standard-saas',
+    1,
+    'Standard SaaS',
+    'Administrator, editor and viewer roles over generic resources',
+    JSON_OBJECT(
+        'permissions', JSON_ARRAY(
+            JSON_OBJECT('code', 'resource:read', 'name', 'Read resources'),
+            JSON_OBJECT('code', 'resource:write', 'name', 'Create and edit resources'),
+            JSON_OBJECT('code', 'resource:delete', 'name', 'Delete resources'),
+            JSON_OBJECT('code', 'settings:manage', 'name', 'Manage settings')
+        ),
+        'roles', JSON_ARRAY(
+            JSON_OBJECT(
+                'name', 'administrator',
+                'description', 'Full access including settings',
+                'permissions', JSON_ARRAY('resource:read', 'resource:write', 'resource:delete', 'settings:manage')
+            ),
+            JSON_OBJECT(
+                'name', 'editor',
+                'description', 'Read and edit resources',
+                'permissions', JSON_ARRAY('resource:read', 'resource:write')
+            ),
+            JSON_OBJECT(
+                'name', 'viewer',
+                'description', 'Read-only access',
+                'permissions', JSON_ARRAY('resource:read')
+            )
+        )
+    )
+);
@@ -0,0 +1,9 @@
+-- Token validation profile assignments: which named profile from
+-- JWT_VALIDATION_PROFILES applies to tenant access tokens issued for a
+-- service. Services without a row use the `default` profile.
+CREATE TABLE IF NOT EXISTS service_token_validation_profiles (
+    service_id CHAR(36) PRIMARY KEY,
+    profile_name VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
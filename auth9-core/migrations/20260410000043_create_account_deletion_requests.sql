@@ -0,0 +1,10 @@
+-- Self-service account deletion requests. The account stays restorable until
+-- purge_after; a job queued after that deletes it and records purge_job_id.
+CREATE TABLE IF NOT EXISTS account_deletion_requests (
+    user_id CHAR(36) PRIMARY KEY,
+    requested_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    purge_after TIMESTAMP NOT NULL,
+    reminder_sent_at TIMESTAMP NULL,
+    purge_job_id CHAR(36) NULL,
+    INDEX idx_account_deletion_requests_purge_after (purge_after)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,19 @@
+-- Forensic captures of failed sign-ins. Kept apart from login_events so they
+-- can have a short retention and be restricted to platform admins. The sign-in
+-- identifier is stored as a SHA-256 hash only.
+CREATE TABLE IF NOT EXISTS login_failure_forensics (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NULL,
+    identifier_hash CHAR(64) NULL,
+    tenant_id CHAR(36) NULL,
+    client_id VARCHAR(255) NULL,
+    ip_address VARCHAR(45) NULL,
+    user_agent VARCHAR(512) NULL,
+    failure_stage VARCHAR(32) NOT NULL,
+    failure_reason VARCHAR(255) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_login_failure_forensics_ip (ip_address, created_at),
+    INDEX idx_login_failure_forensics_user (user_id, created_at),
+    INDEX idx_login_failure_forensics_identifier (identifier_hash, created_at),
+    INDEX idx_login_failure_forensics_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,11 @@
+-- Feed of revoked sessions pushed to relying parties. The auto-increment
+-- sequence orders the feed; services catch up with `since=<sequence>`.
+CREATE TABLE IF NOT EXISTS session_revocations (
+    sequence BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    session_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    reason VARCHAR(32) NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_session_revocations_user (user_id),
+    INDEX idx_session_revocations_revoked_at (revoked_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,10 @@
+-- Tenant password deny lists. Terms are stored only as a Bloom filter;
+-- term_lengths is a bitmask of the normalized term lengths present.
+CREATE TABLE IF NOT EXISTS tenant_password_deny_lists (
+    tenant_id CHAR(36) NOT NULL PRIMARY KEY,
+    filter_bits MEDIUMBLOB NOT NULL,
+    hash_count INT UNSIGNED NOT NULL,
+    term_lengths BIGINT UNSIGNED NOT NULL,
+    term_count INT UNSIGNED NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,13 @@
+-- Usernames and phone numbers users can sign in with besides email. At most
+-- one of each kind per user; values are unique within their scope, the
+-- assigning tenant's ID or '*' for platform-wide uniqueness.
+CREATE TABLE IF NOT EXISTS user_login_identifiers (
+    user_id CHAR(36) NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    value VARCHAR(64) NOT NULL,
+    scope VARCHAR(36) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, kind),
+    UNIQUE KEY uq_login_identifiers_scope_value (kind, scope, value),
+    INDEX idx_login_identifiers_value (kind, value)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
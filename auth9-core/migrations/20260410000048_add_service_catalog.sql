@@ -0,0 +1,4 @@
+-- Catalog metadata shown in the service directory and the app launcher
+-- Structure: { description, owner_team, docs_url, environment_tags }
+-- NULL (rows inserted without it) reads as an empty catalog.
+ALTER TABLE services ADD COLUMN catalog JSON;
@@ -0,0 +1,3 @@
+-- Resume point saved by batched jobs with their progress (e.g. the last row
+-- of a keyset scan), so a requeued job continues where it stopped.
+ALTER TABLE jobs ADD COLUMN resume_after VARCHAR(255) NULL;
@@ -0,0 +1,6 @@
+-- Optimistic concurrency for configuration documents: webhooks and ABAC
+-- policy drafts are exposed with an ETag and updates honour If-Match.
+-- Policy versions already use `version_no` for the published sequence, so the
+-- edit counter of a draft is `revision`.
+ALTER TABLE webhooks ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE abac_policy_set_versions ADD COLUMN revision INT NOT NULL DEFAULT 1;
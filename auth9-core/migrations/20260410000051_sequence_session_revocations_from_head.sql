@@ -0,0 +1,40 @@
+-- Allocate session revocation sequences from a locked counter row.
+--
+-- AUTO_INCREMENT values are handed out at insert time but become visible at
+-- commit, in any order, and TiDB caches them per node. A relying party that
+-- read up to sequence 12 while 11 was still uncommitted would never see 11.
+-- Writers now take the next sequence from session_revocation_head under a
+-- row lock held until commit, so sequences commit in order.
+--
+-- TiDB cannot drop AUTO_INCREMENT in place, so the table is rebuilt and
+-- swapped.
+
+CREATE TABLE session_revocations_sequenced (
+    sequence BIGINT NOT NULL PRIMARY KEY,
+    session_id CHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    reason VARCHAR(32) NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_session_revocations_user (user_id),
+    INDEX idx_session_revocations_revoked_at (revoked_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT INTO session_revocations_sequenced (sequence, session_id, user_id, reason, revoked_at)
+SELECT sequence, session_id, user_id, reason, revoked_at
+FROM session_revocations;
+
+RENAME TABLE session_revocations TO session_revocations_auto_increment,
+             session_revocations_sequenced TO session_revocations;
+
+DROP TABLE session_revocations_auto_increment;
+
+-- Last allocated sequence. Writers lock this single row, which serializes
+-- appends to the feed.
+CREATE TABLE IF NOT EXISTS session_revocation_head (
+    id TINYINT PRIMARY KEY,
+    last_sequence BIGINT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO session_revocation_head (id, last_sequence)
+SELECT 1, COALESCE(MAX(sequence), 0) FROM session_revocations;
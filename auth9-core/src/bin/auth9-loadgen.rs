@@ -0,0 +1,536 @@
+//! Auth9 load generator
+//!
+//! Drives a running Auth9 stack (see `docker-compose.perf.yml`) with
+//! concurrent clients and reports throughput and latency per scenario:
+//!
+//!   session_creation   - password sign-in (`/api/v1/hosted-login/password`)
+//!   token_exchange     - identity to tenant access token (`/api/v1/auth/tenant-token`)
+//!   client_credentials - service client token (`/api/v1/auth/token`)
+//!   permission_check   - RBAC decision (`/api/v1/users/{id}/tenants/{id}/permissions/check`)
+//!
+//! With `--budget`, results are compared against the performance budget and
+//! the process exits non-zero when a scenario misses it.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+#[command(name = "auth9-loadgen")]
+#[command(about = "Load generator and performance budget check for Auth9", long_about = None)]
+struct Cli {
+    /// Auth9 HTTP API base URL
+    #[arg(
+        long,
+        env = "AUTH9_LOADGEN_URL",
+        default_value = "http://localhost:8080"
+    )]
+    base_url: String,
+    /// Email of the user signing in
+    #[arg(long, env = "AUTH9_LOADGEN_EMAIL", default_value = "admin@auth9.local")]
+    email: String,
+    /// Password of the user signing in
+    #[arg(long, env = "AUTH9_ADMIN_PASSWORD")]
+    password: String,
+    /// Tenant (ID or slug) tokens are exchanged for
+    #[arg(long, default_value = "auth9-platform")]
+    tenant: String,
+    /// Client ID tenant access tokens are issued to
+    #[arg(long, default_value = "auth9-portal")]
+    service: String,
+    /// Service client for the client_credentials scenario
+    #[arg(long, default_value = "auth9-m2m-test")]
+    client_id: String,
+    #[arg(
+        long,
+        env = "AUTH9_LOADGEN_CLIENT_SECRET",
+        default_value = "m2m-test-secret-do-not-use-in-production"
+    )]
+    client_secret: String,
+    /// Permission code used by the permission_check scenario
+    #[arg(long, default_value = "user:read")]
+    permission: String,
+    /// Scenarios to run (all when omitted)
+    #[arg(long = "scenario", value_enum)]
+    scenarios: Vec<Scenario>,
+    /// Concurrent clients per scenario
+    #[arg(long, default_value_t = 16)]
+    concurrency: usize,
+    /// Measured seconds per scenario
+    #[arg(long, default_value_t = 30)]
+    duration_secs: u64,
+    /// Seconds of unmeasured load before each scenario
+    #[arg(long, default_value_t = 5)]
+    warmup_secs: u64,
+    /// Performance budget (JSON); exits with status 1 when it is missed
+    #[arg(long)]
+    budget: Option<PathBuf>,
+    /// Write the results as JSON to this file
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+enum Scenario {
+    SessionCreation,
+    TokenExchange,
+    ClientCredentials,
+    PermissionCheck,
+}
+
+impl Scenario {
+    const ALL: [Scenario; 4] = [
+        Scenario::SessionCreation,
+        Scenario::TokenExchange,
+        Scenario::ClientCredentials,
+        Scenario::PermissionCheck,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SessionCreation => "session_creation",
+            Self::TokenExchange => "token_exchange",
+            Self::ClientCredentials => "client_credentials",
+            Self::PermissionCheck => "permission_check",
+        }
+    }
+}
+
+/// Limits one scenario must stay within
+#[derive(Debug, Deserialize)]
+struct ScenarioBudget {
+    min_rps: Option<f64>,
+    max_p99_ms: Option<f64>,
+    #[serde(default)]
+    max_error_rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Budget {
+    scenarios: BTreeMap<String, ScenarioBudget>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScenarioResult {
+    scenario: &'static str,
+    requests: u64,
+    errors: u64,
+    error_rate: f64,
+    rps: f64,
+    p50_ms: f64,
+    p95_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+}
+
+impl ScenarioResult {
+    /// Budget violations, empty when the budget is met
+    fn violations(&self, budget: &ScenarioBudget) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(min_rps) = budget.min_rps {
+            if self.rps < min_rps {
+                violations.push(format!("{:.1} req/s < {:.1}", self.rps, min_rps));
+            }
+        }
+        if let Some(max_p99_ms) = budget.max_p99_ms {
+            if self.p99_ms > max_p99_ms {
+                violations.push(format!("p99 {:.1} ms > {:.1}", self.p99_ms, max_p99_ms));
+            }
+        }
+        if self.error_rate > budget.max_error_rate {
+            violations.push(format!(
+                "error rate {:.2}% > {:.2}%",
+                self.error_rate * 100.0,
+                budget.max_error_rate * 100.0
+            ));
+        }
+        violations
+    }
+}
+
+/// Tokens and IDs obtained once before the load starts
+struct Fixture {
+    identity_token: String,
+    tenant_token: String,
+    user_id: String,
+    tenant_id: String,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct TenantClaims {
+    sub: String,
+    tenant_id: String,
+}
+
+/// Claims of a token we were just issued; the signature is not checked
+fn decode_claims<T: for<'de> Deserialize<'de>>(token: &str) -> Result<T> {
+    let payload = token.split('.').nth(1).context("malformed JWT")?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(payload)
+        .context("malformed JWT payload")?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+struct Client {
+    http: reqwest::Client,
+    cli: Cli,
+}
+
+impl Client {
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.cli.base_url.trim_end_matches('/'), path)
+    }
+
+    async fn sign_in(&self) -> Result<reqwest::Response> {
+        Ok(self
+            .http
+            .post(self.url("/api/v1/hosted-login/password"))
+            .json(&serde_json::json!({
+                "email": self.cli.email,
+                "password": self.cli.password,
+            }))
+            .send()
+            .await?)
+    }
+
+    async fn exchange(&self, identity_token: &str) -> Result<reqwest::Response> {
+        Ok(self
+            .http
+            .post(self.url("/api/v1/auth/tenant-token"))
+            .bearer_auth(identity_token)
+            .json(&serde_json::json!({
+                "tenant_id": self.cli.tenant,
+                "service_id": self.cli.service,
+            }))
+            .send()
+            .await?)
+    }
+
+    async fn client_credentials(&self) -> Result<reqwest::Response> {
+        Ok(self
+            .http
+            .post(self.url("/api/v1/auth/token"))
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.cli.client_id.as_str()),
+                ("client_secret", self.cli.client_secret.as_str()),
+            ])
+            .send()
+            .await?)
+    }
+
+    async fn check_permission(&self, fixture: &Fixture) -> Result<reqwest::Response> {
+        Ok(self
+            .http
+            .post(self.url(&format!(
+                "/api/v1/users/{}/tenants/{}/permissions/check",
+                fixture.user_id, fixture.tenant_id
+            )))
+            .bearer_auth(&fixture.tenant_token)
+            .json(&serde_json::json!({ "permission": self.cli.permission }))
+            .send()
+            .await?)
+    }
+
+    async fn fixture(&self) -> Result<Fixture> {
+        let response = self.sign_in().await?;
+        if !response.status().is_success() {
+            bail!(
+                "sign-in as {} failed: {} {}",
+                self.cli.email,
+                response.status(),
+                response.text().await.unwrap_or_default()
+            );
+        }
+        let identity_token = response.json::<AccessTokenResponse>().await?.access_token;
+
+        let response = self.exchange(&identity_token).await?;
+        if !response.status().is_success() {
+            bail!(
+                "token exchange for tenant {} failed: {} {}",
+                self.cli.tenant,
+                response.status(),
+                response.text().await.unwrap_or_default()
+            );
+        }
+        let tenant_token = response.json::<AccessTokenResponse>().await?.access_token;
+        let claims: TenantClaims = decode_claims(&tenant_token)?;
+
+        Ok(Fixture {
+            identity_token,
+            tenant_token,
+            user_id: claims.sub,
+            tenant_id: claims.tenant_id,
+        })
+    }
+
+    /// One request of `scenario`; `Ok(false)` on a non-success status
+    async fn request(&self, scenario: Scenario, fixture: &Fixture) -> Result<bool> {
+        let response = match scenario {
+            Scenario::SessionCreation => self.sign_in().await?,
+            Scenario::TokenExchange => self.exchange(&fixture.identity_token).await?,
+            Scenario::ClientCredentials => self.client_credentials().await?,
+            Scenario::PermissionCheck => self.check_permission(fixture).await?,
+        };
+        let ok = response.status().is_success();
+        // Read the body so the connection goes back to the pool
+        let _ = response.bytes().await;
+        Ok(ok)
+    }
+}
+
+/// Latency at quantile `q` of sorted samples, in milliseconds
+fn percentile_ms(sorted: &[Duration], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = ((sorted.len() as f64 * q).ceil() as usize).clamp(1, sorted.len()) - 1;
+    sorted[index].as_secs_f64() * 1000.0
+}
+
+async fn run_scenario(
+    client: Arc<Client>,
+    fixture: Arc<Fixture>,
+    scenario: Scenario,
+) -> ScenarioResult {
+    let warmup = Duration::from_secs(client.cli.warmup_secs);
+    let measured = Duration::from_secs(client.cli.duration_secs);
+    let start = Instant::now();
+    let measure_from = start + warmup;
+    let deadline = measure_from + measured;
+
+    let workers: Vec<_> = (0..client.cli.concurrency)
+        .map(|_| {
+            let client = client.clone();
+            let fixture = fixture.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                let mut errors = 0u64;
+                loop {
+                    let sent = Instant::now();
+                    if sent >= deadline {
+                        break;
+                    }
+                    let ok = matches!(client.request(scenario, &fixture).await, Ok(true));
+                    if sent < measure_from {
+                        continue;
+                    }
+                    latencies.push(sent.elapsed());
+                    if !ok {
+                        errors += 1;
+                    }
+                }
+                (latencies, errors)
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    for worker in workers {
+        if let Ok((worker_latencies, worker_errors)) = worker.await {
+            latencies.extend(worker_latencies);
+            errors += worker_errors;
+        }
+    }
+    latencies.sort_unstable();
+
+    let requests = latencies.len() as u64;
+    ScenarioResult {
+        scenario: scenario.as_str(),
+        requests,
+        errors,
+        error_rate: if requests == 0 {
+            1.0
+        } else {
+            errors as f64 / requests as f64
+        },
+        rps: (requests - errors) as f64 / measured.as_secs_f64(),
+        p50_ms: percentile_ms(&latencies, 0.50),
+        p95_ms: percentile_ms(&latencies, 0.95),
+        p99_ms: percentile_ms(&latencies, 0.99),
+        max_ms: percentile_ms(&latencies, 1.0),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.concurrency == 0 || cli.duration_secs == 0 {
+        bail!("--concurrency and --duration-secs must be positive");
+    }
+    let budget: Option<Budget> = match &cli.budget {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read budget {}", path.display()))?;
+            Some(serde_json::from_str(&raw).context("invalid budget")?)
+        }
+        None => None,
+    };
+    let scenarios = if cli.scenarios.is_empty() {
+        Scenario::ALL.to_vec()
+    } else {
+        cli.scenarios.clone()
+    };
+
+    let http = reqwest::Client::builder()
+        .pool_max_idle_per_host(cli.concurrency)
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let client = Arc::new(Client { http, cli });
+    let fixture = Arc::new(client.fixture().await?);
+
+    println!(
+        "{:<20} {:>9} {:>7} {:>10} {:>9} {:>9} {:>9}",
+        "scenario", "requests", "errors", "req/s", "p50 ms", "p95 ms", "p99 ms"
+    );
+    let mut results = Vec::new();
+    for scenario in scenarios {
+        let result = run_scenario(client.clone(), fixture.clone(), scenario).await;
+        println!(
+            "{:<20} {:>9} {:>7} {:>10.1} {:>9.1} {:>9.1} {:>9.1}",
+            result.scenario,
+            result.requests,
+            result.errors,
+            result.rps,
+            result.p50_ms,
+            result.p95_ms,
+            result.p99_ms
+        );
+        results.push(result);
+    }
+
+    if let Some(path) = &client.cli.output {
+        std::fs::write(path, serde_json::to_vec_pretty(&results)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+    let mut missed = false;
+    for result in &results {
+        let Some(limits) = budget.scenarios.get(result.scenario) else {
+            continue;
+        };
+        let violations = result.violations(limits);
+        if violations.is_empty() {
+            println!("[ok] {} within budget", result.scenario);
+        } else {
+            missed = true;
+            println!(
+                "[x] {} over budget: {}",
+                result.scenario,
+                violations.join(", ")
+            );
+        }
+    }
+    if missed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    fn result(rps: f64, p99_ms: f64, error_rate: f64) -> ScenarioResult {
+        ScenarioResult {
+            scenario: "token_exchange",
+            requests: 1000,
+            errors: (error_rate * 1000.0) as u64,
+            error_rate,
+            rps,
+            p50_ms: p99_ms / 2.0,
+            p95_ms: p99_ms,
+            p99_ms,
+            max_ms: p99_ms,
+        }
+    }
+
+    fn budget(
+        min_rps: Option<f64>,
+        max_p99_ms: Option<f64>,
+        max_error_rate: f64,
+    ) -> ScenarioBudget {
+        ScenarioBudget {
+            min_rps,
+            max_p99_ms,
+            max_error_rate,
+        }
+    }
+
+    #[test]
+    fn test_percentile_of_empty_samples_is_zero() {
+        assert_eq!(percentile_ms(&[], 0.99), 0.0);
+    }
+
+    #[test]
+    fn test_percentile_of_single_sample() {
+        let samples = ms(&[42]);
+        for q in [0.0, 0.5, 0.99, 1.0] {
+            assert_eq!(percentile_ms(&samples, q), 42.0);
+        }
+    }
+
+    #[test]
+    fn test_percentile_rank_boundaries() {
+        let samples = ms(&[10, 20, 30, 40]);
+        assert_eq!(percentile_ms(&samples, 0.0), 10.0);
+        assert_eq!(percentile_ms(&samples, 0.25), 10.0);
+        assert_eq!(percentile_ms(&samples, 0.26), 20.0);
+        assert_eq!(percentile_ms(&samples, 0.5), 20.0);
+        assert_eq!(percentile_ms(&samples, 0.75), 30.0);
+        assert_eq!(percentile_ms(&samples, 0.76), 40.0);
+        assert_eq!(percentile_ms(&samples, 1.0), 40.0);
+    }
+
+    #[test]
+    fn test_violations_when_budget_exceeded() {
+        let violations =
+            result(50.0, 250.0, 0.02).violations(&budget(Some(100.0), Some(200.0), 0.01));
+        assert_eq!(violations.len(), 3);
+        assert!(violations[0].contains("req/s"));
+        assert!(violations[1].starts_with("p99"));
+        assert!(violations[2].starts_with("error rate"));
+    }
+
+    #[test]
+    fn test_no_violations_when_budget_exactly_met() {
+        let violations =
+            result(100.0, 200.0, 0.01).violations(&budget(Some(100.0), Some(200.0), 0.01));
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn test_missing_limits_are_not_enforced() {
+        let limits: ScenarioBudget = serde_json::from_str("{}").unwrap();
+        assert!(result(0.1, 10_000.0, 0.0).violations(&limits).is_empty());
+        // max_error_rate defaults to zero, so any error is a violation
+        assert_eq!(result(0.1, 10_000.0, 0.001).violations(&limits).len(), 1);
+    }
+
+    #[test]
+    fn test_budget_without_scenario_has_no_limits() {
+        let budget: Budget =
+            serde_json::from_str(r#"{"scenarios":{"session_creation":{"min_rps":10}}}"#).unwrap();
+        assert!(budget.scenarios.contains_key("session_creation"));
+        assert!(!budget.scenarios.contains_key("token_exchange"));
+    }
+}
@@ -3,7 +3,7 @@
 use super::{keys, ttl};
 use crate::config::RedisConfig;
 use crate::error::{AppError, Result};
-use crate::models::rbac::UserRolesInTenant;
+use crate::models::rbac::{RoleTree, UserRolesInTenant};
 use redis::{aio::ConnectionManager, AsyncCommands};
 use serde::{de::DeserializeOwned, Serialize};
 use sha2::{Digest, Sha256};
@@ -37,7 +37,7 @@ impl CacheManager {
     }
 
     pub async fn ping(&self) -> Result<()> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: String = redis::cmd("PING").query_async(&mut conn).await?;
         Ok(())
     }
@@ -47,10 +47,17 @@ impl CacheManager {
         self.conn.clone()
     }
 
+    /// Connection for one operation, subject to injected Redis faults
+    async fn connection(&self) -> Result<ConnectionManager> {
+        #[cfg(feature = "fault-injection")]
+        crate::fault_injection::inject(crate::fault_injection::FaultTarget::Redis).await?;
+        Ok(self.conn.clone())
+    }
+
     /// Get a value from cache
     pub(crate) async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
         let start = std::time::Instant::now();
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(key).await?;
 
         metrics::counter!("auth9_redis_operations_total", "operation" => "get").increment(1);
@@ -76,7 +83,7 @@ impl CacheManager {
         ttl: Duration,
     ) -> Result<()> {
         let start = std::time::Instant::now();
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let serialized = serde_json::to_string(value)
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Cache serialize error: {}", e)))?;
 
@@ -90,7 +97,7 @@ impl CacheManager {
     /// Delete a key from cache
     pub(crate) async fn delete(&self, key: &str) -> Result<()> {
         let start = std::time::Instant::now();
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.del(key).await?;
         metrics::counter!("auth9_redis_operations_total", "operation" => "del").increment(1);
         metrics::histogram!("auth9_redis_operation_duration_seconds", "operation" => "del")
@@ -101,7 +108,7 @@ impl CacheManager {
     /// Delete keys matching a pattern using SCAN (non-blocking)
     pub(crate) async fn delete_pattern(&self, pattern: &str) -> Result<()> {
         let start = std::time::Instant::now();
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let mut cursor: u64 = 0;
         let mut total_deleted = 0;
 
@@ -259,6 +266,27 @@ impl CacheManager {
         self.delete(&key).await
     }
 
+    // ==================== Role Tree Cache ====================
+
+    /// Get the cached role hierarchy of a service
+    pub async fn get_role_tree(&self, service_id: Uuid) -> Result<Option<RoleTree>> {
+        let key = format!("{}:{}", keys::ROLE_TREE, service_id);
+        self.get(&key).await
+    }
+
+    /// Cache the role hierarchy of a service
+    pub async fn set_role_tree(&self, tree: &RoleTree) -> Result<()> {
+        let key = format!("{}:{}", keys::ROLE_TREE, tree.service_id);
+        self.set(&key, tree, Duration::from_secs(ttl::ROLE_TREE_SECS))
+            .await
+    }
+
+    /// Invalidate the cached role hierarchy of a service
+    pub async fn invalidate_role_tree(&self, service_id: Uuid) -> Result<()> {
+        let key = format!("{}:{}", keys::ROLE_TREE, service_id);
+        self.delete(&key).await
+    }
+
     // ==================== Tenant Config Cache ====================
 
     /// Get cached tenant config
@@ -301,7 +329,7 @@ impl CacheManager {
     /// blacklist values are simple flags, not JSON objects.
     pub async fn is_token_blacklisted(&self, jti: &str) -> Result<bool> {
         let key = format!("{}:{}", keys::TOKEN_BLACKLIST, jti);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let exists: bool = redis::cmd("EXISTS")
             .arg(&key)
             .query_async(&mut conn)
@@ -318,14 +346,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::WEBAUTHN_REG, user_id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, state, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn get_webauthn_reg_state(&self, user_id: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::WEBAUTHN_REG, user_id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(&key).await?;
         Ok(value)
     }
@@ -342,14 +370,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::WEBAUTHN_AUTH, challenge_id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, state, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn get_webauthn_auth_state(&self, challenge_id: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::WEBAUTHN_AUTH, challenge_id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(&key).await?;
         Ok(value)
     }
@@ -368,14 +396,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::OIDC_STATE, state_nonce);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, payload, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn consume_oidc_state(&self, state_nonce: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::OIDC_STATE, state_nonce);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         redis::cmd("GETDEL")
             .arg(&key)
             .query_async(&mut conn)
@@ -393,12 +421,12 @@ impl CacheManager {
     ) -> Result<()> {
         let token_hash = Self::refresh_token_hash(refresh_token);
         let key = format!("{}:{}", keys::REFRESH_TOKEN_SESSION, token_hash);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, session_id, ttl_secs).await?;
 
         // Maintain reverse index: session_id → set of token hashes
         let set_key = format!("{}:{}", keys::SESSION_REFRESH_TOKENS, session_id);
-        let mut conn2 = self.conn.clone();
+        let mut conn2 = conn.clone();
         let _: () = redis::cmd("SADD")
             .arg(&set_key)
             .arg(&token_hash)
@@ -419,7 +447,7 @@ impl CacheManager {
     pub async fn get_refresh_token_session(&self, refresh_token: &str) -> Result<Option<String>> {
         let token_hash = Self::refresh_token_hash(refresh_token);
         let key = format!("{}:{}", keys::REFRESH_TOKEN_SESSION, token_hash);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         conn.get(&key).await.map_err(AppError::from)
     }
 
@@ -431,7 +459,7 @@ impl CacheManager {
 
     pub async fn remove_all_refresh_sessions_for_session(&self, session_id: &str) -> Result<()> {
         let set_key = format!("{}:{}", keys::SESSION_REFRESH_TOKENS, session_id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
 
         // Get all token hashes for this session
         let token_hashes: Vec<String> = redis::cmd("SMEMBERS")
@@ -457,13 +485,13 @@ impl CacheManager {
     // ==================== OTP ====================
 
     pub async fn store_otp(&self, key: &str, code: &str, ttl_secs: u64) -> Result<()> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(key, code, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn get_otp(&self, key: &str) -> Result<Option<String>> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(key).await?;
         Ok(value)
     }
@@ -473,7 +501,7 @@ impl CacheManager {
     }
 
     pub async fn increment_counter(&self, key: &str, ttl_secs: u64) -> Result<u64> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: u64 = redis::cmd("INCR")
             .arg(key)
             .query_async(&mut conn)
@@ -492,13 +520,13 @@ impl CacheManager {
     }
 
     pub async fn get_counter(&self, key: &str) -> Result<u64> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(key).await?;
         Ok(value.and_then(|v| v.parse().ok()).unwrap_or(0))
     }
 
     pub async fn set_flag(&self, key: &str, ttl_secs: u64) -> Result<bool> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let result: Option<String> = redis::cmd("SET")
             .arg(key)
             .arg("1")
@@ -518,14 +546,14 @@ impl CacheManager {
 
     pub async fn store_totp_setup(&self, token: &str, data: &str, ttl_secs: u64) -> Result<()> {
         let key = format!("{}:{}", keys::TOTP_SETUP, token);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, data, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn get_totp_setup(&self, token: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::TOTP_SETUP, token);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let value: Option<String> = conn.get(&key).await?;
         Ok(value)
     }
@@ -537,7 +565,7 @@ impl CacheManager {
 
     pub async fn is_totp_code_used(&self, user_id: &str, time_step: u64) -> Result<bool> {
         let key = format!("{}:{}:{}", keys::TOTP_USED, user_id, time_step);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let exists: bool = redis::cmd("EXISTS")
             .arg(&key)
             .query_async(&mut conn)
@@ -552,54 +580,25 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}:{}", keys::TOTP_USED, user_id, time_step);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, "1", ttl_secs).await?;
         Ok(())
     }
 
-    // ==================== MFA Session ====================
-
-    pub async fn store_mfa_session(&self, token: &str, data: &str, ttl_secs: u64) -> Result<()> {
-        let key = format!("{}:{}", keys::MFA_SESSION, token);
-        let mut conn = self.conn.clone();
-        let _: () = conn.set_ex(&key, data, ttl_secs).await?;
-        Ok(())
-    }
-
-    pub async fn get_mfa_session(&self, token: &str) -> Result<Option<String>> {
-        let key = format!("{}:{}", keys::MFA_SESSION, token);
-        let mut conn = self.conn.clone();
-        let value: Option<String> = conn.get(&key).await?;
-        Ok(value)
-    }
-
-    pub async fn consume_mfa_session(&self, token: &str) -> Result<Option<String>> {
-        let key = format!("{}:{}", keys::MFA_SESSION, token);
-        let mut conn = self.conn.clone();
-        redis::cmd("GETDEL")
-            .arg(&key)
-            .query_async(&mut conn)
-            .await
-            .map_err(AppError::from)
-    }
-
-    // ==================== Login Challenge ====================
-
-    pub async fn store_login_challenge(&self, id: &str, data: &str, ttl_secs: u64) -> Result<()> {
-        let key = format!("{}:{}", keys::LOGIN_CHALLENGE, id);
-        let mut conn = self.conn.clone();
-        let _: () = conn.set_ex(&key, data, ttl_secs).await?;
-        Ok(())
-    }
+    // ==================== Flow State ====================
 
-    pub async fn consume_login_challenge(&self, id: &str) -> Result<Option<String>> {
-        let key = format!("{}:{}", keys::LOGIN_CHALLENGE, id);
-        let mut conn = self.conn.clone();
-        redis::cmd("GETDEL")
+    pub async fn claim_flow_state(&self, jti: &str, ttl_secs: u64) -> Result<bool> {
+        let key = format!("{}:{}", keys::FLOW_STATE_USED, jti);
+        let mut conn = self.connection().await?;
+        let claimed: Option<String> = redis::cmd("SET")
             .arg(&key)
+            .arg("1")
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_secs)
             .query_async(&mut conn)
-            .await
-            .map_err(AppError::from)
+            .await?;
+        Ok(claimed.is_some())
     }
 
     // ==================== Authorization Code ====================
@@ -611,14 +610,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::AUTH_CODE, code);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, data, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::AUTH_CODE, code);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         redis::cmd("GETDEL")
             .arg(&key)
             .query_async(&mut conn)
@@ -626,6 +625,51 @@ impl CacheManager {
             .map_err(AppError::from)
     }
 
+    pub async fn mark_authorization_code_redeemed(
+        &self,
+        code: &str,
+        session_id: &str,
+        ttl_secs: u64,
+    ) -> Result<()> {
+        let key = format!("{}:{}", keys::AUTH_CODE_REDEEMED, code);
+        let mut conn = self.connection().await?;
+        let _: () = conn.set_ex(&key, session_id, ttl_secs).await?;
+        Ok(())
+    }
+
+    pub async fn get_authorization_code_redemption(&self, code: &str) -> Result<Option<String>> {
+        let key = format!("{}:{}", keys::AUTH_CODE_REDEEMED, code);
+        let mut conn = self.connection().await?;
+        let session_id: Option<String> = conn.get(&key).await?;
+        Ok(session_id)
+    }
+
+    // ==================== Session Consent ====================
+
+    pub async fn store_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+        scope: &str,
+        ttl_secs: u64,
+    ) -> Result<()> {
+        let key = format!("{}:{}:{}", keys::SESSION_CONSENT, session_id, client_id);
+        let mut conn = self.connection().await?;
+        let _: () = conn.set_ex(&key, scope, ttl_secs).await?;
+        Ok(())
+    }
+
+    pub async fn get_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+    ) -> Result<Option<String>> {
+        let key = format!("{}:{}:{}", keys::SESSION_CONSENT, session_id, client_id);
+        let mut conn = self.connection().await?;
+        let scope: Option<String> = conn.get(&key).await?;
+        Ok(scope)
+    }
+
     // ==================== Social Login State ====================
 
     pub async fn store_social_login_state(
@@ -635,14 +679,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::SOCIAL_STATE, id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, data, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn consume_social_login_state(&self, id: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::SOCIAL_STATE, id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         redis::cmd("GETDEL")
             .arg(&key)
             .query_async(&mut conn)
@@ -659,14 +703,14 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<()> {
         let key = format!("{}:{}", keys::ENTERPRISE_SSO_STATE, id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, data, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn consume_enterprise_sso_state(&self, id: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::ENTERPRISE_SSO_STATE, id);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         redis::cmd("GETDEL")
             .arg(&key)
             .query_async(&mut conn)
@@ -678,14 +722,14 @@ impl CacheManager {
 
     pub async fn store_pending_merge(&self, token: &str, data: &str, ttl_secs: u64) -> Result<()> {
         let key = format!("{}:{}", keys::PENDING_MERGE, token);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = conn.set_ex(&key, data, ttl_secs).await?;
         Ok(())
     }
 
     pub async fn consume_pending_merge(&self, token: &str) -> Result<Option<String>> {
         let key = format!("{}:{}", keys::PENDING_MERGE, token);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         redis::cmd("GETDEL")
             .arg(&key)
             .query_async(&mut conn)
@@ -696,7 +740,7 @@ impl CacheManager {
     // ==================== Audience Validation ====================
 
     pub async fn is_valid_audience(&self, client_id: &str) -> Result<bool> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let result: bool = redis::cmd("SISMEMBER")
             .arg(keys::VALID_AUDIENCES)
             .arg(client_id)
@@ -707,7 +751,7 @@ impl CacheManager {
     }
 
     pub async fn refresh_audience_set(&self, client_ids: &[String]) -> Result<()> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         // Atomic replace: delete then add all
         let _: () = redis::cmd("DEL")
             .arg(keys::VALID_AUDIENCES)
@@ -727,7 +771,7 @@ impl CacheManager {
     }
 
     pub async fn add_audience(&self, client_id: &str) -> Result<()> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = redis::cmd("SADD")
             .arg(keys::VALID_AUDIENCES)
             .arg(client_id)
@@ -738,7 +782,7 @@ impl CacheManager {
     }
 
     pub async fn remove_audience(&self, client_id: &str) -> Result<()> {
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         let _: () = redis::cmd("SREM")
             .arg(keys::VALID_AUDIENCES)
             .arg(client_id)
@@ -748,6 +792,107 @@ impl CacheManager {
         Ok(())
     }
 
+    // ==================== Session Activity ====================
+
+    pub async fn mark_session_active(
+        &self,
+        session_id: &str,
+        at: i64,
+        precision_secs: u64,
+    ) -> Result<bool> {
+        let throttle_key = format!("{}:{}", keys::SESSION_ACTIVE, session_id);
+        let mut conn = self.connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(&throttle_key)
+            .arg("1")
+            .arg("NX")
+            .arg("EX")
+            .arg(precision_secs.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(AppError::from)?;
+        if result.is_none() {
+            // Already marked within the precision window
+            return Ok(false);
+        }
+
+        let _: () = conn
+            .hset(keys::SESSION_ACTIVITY_DIRTY, session_id, at)
+            .await?;
+        Ok(true)
+    }
+
+    pub async fn drain_session_activity(&self, max: usize) -> Result<Vec<(String, i64)>> {
+        // Pick and delete in one script so a marker written in between is never lost
+        const DRAIN_SCRIPT: &str = r#"
+            local entries = redis.call('HRANDFIELD', KEYS[1], ARGV[1], 'WITHVALUES')
+            for i = 1, #entries, 2 do
+                redis.call('HDEL', KEYS[1], entries[i])
+            end
+            return entries
+        "#;
+        let mut conn = self.connection().await?;
+        let entries: Vec<String> = redis::cmd("EVAL")
+            .arg(DRAIN_SCRIPT)
+            .arg(1)
+            .arg(keys::SESSION_ACTIVITY_DIRTY)
+            .arg(max.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(entries
+            .chunks_exact(2)
+            .filter_map(|pair| {
+                let at = pair[1].parse::<i64>().ok()?;
+                Some((pair[0].clone(), at))
+            })
+            .collect())
+    }
+
+    // ==================== Session Limit ====================
+
+    pub async fn acquire_session_limit_lock(
+        &self,
+        user_id: &str,
+        token: &str,
+        ttl_ms: u64,
+    ) -> Result<bool> {
+        let key = format!("{}:{}", keys::SESSION_LIMIT_LOCK, user_id);
+        let mut conn = self.connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms.max(1))
+            .query_async(&mut conn)
+            .await
+            .map_err(AppError::from)?;
+        Ok(result.is_some())
+    }
+
+    pub async fn release_session_limit_lock(&self, user_id: &str, token: &str) -> Result<()> {
+        // Only the holder may release; an expired lock may already be someone else's
+        const RELEASE_SCRIPT: &str = r#"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                return redis.call('DEL', KEYS[1])
+            end
+            return 0
+        "#;
+        let key = format!("{}:{}", keys::SESSION_LIMIT_LOCK, user_id);
+        let mut conn = self.connection().await?;
+        let _: i64 = redis::cmd("EVAL")
+            .arg(RELEASE_SCRIPT)
+            .arg(1)
+            .arg(&key)
+            .arg(token)
+            .query_async(&mut conn)
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+
     /// Atomically check if a webhook event key exists and set it if not (SETNX).
     /// Returns true if the event was already processed (duplicate).
     pub async fn check_and_mark_webhook_event(
@@ -756,7 +901,7 @@ impl CacheManager {
         ttl_secs: u64,
     ) -> Result<bool> {
         let key = format!("{}:{}", keys::WEBHOOK_EVENT_DEDUP, event_key);
-        let mut conn = self.conn.clone();
+        let mut conn = self.connection().await?;
         // SET key "1" NX EX ttl — returns Some("OK") if key was newly set, None if existed.
         // Use Option<String> instead of bool: redis SET NX returns OK (success) or nil (key
         // exists), and the redis crate's bool parsing of nil is unreliable across versions.
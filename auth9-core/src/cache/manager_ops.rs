@@ -186,38 +186,53 @@ impl CacheOperations for CacheManager {
         CacheManager::mark_totp_code_used(self, user_id, time_step, ttl_secs).await
     }
 
-    // ==================== MFA Session ====================
+    // ==================== Flow State ====================
 
-    async fn store_mfa_session(&self, token: &str, data: &str, ttl_secs: u64) -> Result<()> {
-        CacheManager::store_mfa_session(self, token, data, ttl_secs).await
+    async fn claim_flow_state(&self, jti: &str, ttl_secs: u64) -> Result<bool> {
+        CacheManager::claim_flow_state(self, jti, ttl_secs).await
     }
 
-    async fn get_mfa_session(&self, token: &str) -> Result<Option<String>> {
-        CacheManager::get_mfa_session(self, token).await
-    }
+    // ==================== Authorization Code ====================
 
-    async fn consume_mfa_session(&self, token: &str) -> Result<Option<String>> {
-        CacheManager::consume_mfa_session(self, token).await
+    async fn store_authorization_code(&self, code: &str, data: &str, ttl_secs: u64) -> Result<()> {
+        CacheManager::store_authorization_code(self, code, data, ttl_secs).await
     }
 
-    // ==================== Login Challenge ====================
+    async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>> {
+        CacheManager::consume_authorization_code(self, code).await
+    }
 
-    async fn store_login_challenge(&self, id: &str, data: &str, ttl_secs: u64) -> Result<()> {
-        CacheManager::store_login_challenge(self, id, data, ttl_secs).await
+    async fn mark_authorization_code_redeemed(
+        &self,
+        code: &str,
+        session_id: &str,
+        ttl_secs: u64,
+    ) -> Result<()> {
+        CacheManager::mark_authorization_code_redeemed(self, code, session_id, ttl_secs).await
     }
 
-    async fn consume_login_challenge(&self, id: &str) -> Result<Option<String>> {
-        CacheManager::consume_login_challenge(self, id).await
+    async fn get_authorization_code_redemption(&self, code: &str) -> Result<Option<String>> {
+        CacheManager::get_authorization_code_redemption(self, code).await
     }
 
-    // ==================== Authorization Code ====================
+    // ==================== Session Consent ====================
 
-    async fn store_authorization_code(&self, code: &str, data: &str, ttl_secs: u64) -> Result<()> {
-        CacheManager::store_authorization_code(self, code, data, ttl_secs).await
+    async fn store_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+        scope: &str,
+        ttl_secs: u64,
+    ) -> Result<()> {
+        CacheManager::store_session_consent(self, session_id, client_id, scope, ttl_secs).await
     }
 
-    async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>> {
-        CacheManager::consume_authorization_code(self, code).await
+    async fn get_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+    ) -> Result<Option<String>> {
+        CacheManager::get_session_consent(self, session_id, client_id).await
     }
 
     // ==================== Social Login State ====================
@@ -267,4 +282,34 @@ impl CacheOperations for CacheManager {
     async fn remove_audience(&self, client_id: &str) -> Result<()> {
         CacheManager::remove_audience(self, client_id).await
     }
+
+    // ==================== Session Activity ====================
+
+    async fn mark_session_active(
+        &self,
+        session_id: &str,
+        at: i64,
+        precision_secs: u64,
+    ) -> Result<bool> {
+        CacheManager::mark_session_active(self, session_id, at, precision_secs).await
+    }
+
+    async fn drain_session_activity(&self, max: usize) -> Result<Vec<(String, i64)>> {
+        CacheManager::drain_session_activity(self, max).await
+    }
+
+    // ==================== Session Limit ====================
+
+    async fn acquire_session_limit_lock(
+        &self,
+        user_id: &str,
+        token: &str,
+        ttl_ms: u64,
+    ) -> Result<bool> {
+        CacheManager::acquire_session_limit_lock(self, user_id, token, ttl_ms).await
+    }
+
+    async fn release_session_limit_lock(&self, user_id: &str, token: &str) -> Result<()> {
+        CacheManager::release_session_limit_lock(self, user_id, token).await
+    }
 }
@@ -134,24 +134,11 @@ pub trait CacheOperations: Send + Sync {
     async fn mark_totp_code_used(&self, user_id: &str, time_step: u64, ttl_secs: u64)
         -> Result<()>;
 
-    // ==================== MFA Session ====================
+    // ==================== Flow State ====================
 
-    /// Store MFA session data (temporary token after password auth, before MFA verification)
-    async fn store_mfa_session(&self, token: &str, data: &str, ttl_secs: u64) -> Result<()>;
-
-    /// Get MFA session data
-    async fn get_mfa_session(&self, token: &str) -> Result<Option<String>>;
-
-    /// Consume (get + delete) MFA session data
-    async fn consume_mfa_session(&self, token: &str) -> Result<Option<String>>;
-
-    // ==================== Login Challenge ====================
-
-    /// Store a login challenge (OIDC authorize → hosted login → authorize_complete)
-    async fn store_login_challenge(&self, id: &str, data: &str, ttl_secs: u64) -> Result<()>;
-
-    /// Consume (get + delete) a login challenge
-    async fn consume_login_challenge(&self, id: &str) -> Result<Option<String>>;
+    /// Claim a sealed flow-state token id for single use.
+    /// Returns false if the id was already claimed (replay).
+    async fn claim_flow_state(&self, jti: &str, ttl_secs: u64) -> Result<bool>;
 
     // ==================== Authorization Code ====================
 
@@ -161,6 +148,37 @@ pub trait CacheOperations: Send + Sync {
     /// Consume (get + delete) an authorization code (one-time use)
     async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>>;
 
+    /// Remember which session a consumed authorization code was redeemed
+    /// for, so a replay of the code can revoke it
+    async fn mark_authorization_code_redeemed(
+        &self,
+        code: &str,
+        session_id: &str,
+        ttl_secs: u64,
+    ) -> Result<()>;
+
+    /// Session an authorization code was already redeemed for, if any
+    async fn get_authorization_code_redemption(&self, code: &str) -> Result<Option<String>>;
+
+    // ==================== Session Consent ====================
+
+    /// Record the scope a client was granted within a login session, so later
+    /// silent (`prompt=none`) authorizations can be granted without the user
+    async fn store_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+        scope: &str,
+        ttl_secs: u64,
+    ) -> Result<()>;
+
+    /// Scope previously granted to a client within a login session
+    async fn get_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+    ) -> Result<Option<String>>;
+
     // ==================== Social Login State ====================
 
     /// Store social login state (social authorize → provider → callback)
@@ -198,6 +216,36 @@ pub trait CacheOperations: Send + Sync {
 
     /// Remove a single audience from the set (SREM).
     async fn remove_audience(&self, client_id: &str) -> Result<()>;
+
+    // ==================== Session Activity ====================
+
+    /// Mark a session as active at `at` (unix seconds). At most one marker is
+    /// recorded per session every `precision_secs`; returns true if recorded.
+    async fn mark_session_active(
+        &self,
+        session_id: &str,
+        at: i64,
+        precision_secs: u64,
+    ) -> Result<bool>;
+
+    /// Remove and return up to `max` pending activity markers
+    /// as (session_id, unix seconds) pairs.
+    async fn drain_session_activity(&self, max: usize) -> Result<Vec<(String, i64)>>;
+
+    // ==================== Session Limit ====================
+
+    /// Take the per-user lock serializing the session limit check with
+    /// session creation. Returns false if another holder has it; the lock
+    /// expires after `ttl_ms` should its holder never release it.
+    async fn acquire_session_limit_lock(
+        &self,
+        user_id: &str,
+        token: &str,
+        ttl_ms: u64,
+    ) -> Result<bool>;
+
+    /// Release the per-user session limit lock if `token` still holds it
+    async fn release_session_limit_lock(&self, user_id: &str, token: &str) -> Result<()>;
 }
 
 /// Cache key prefixes
@@ -205,6 +253,7 @@ pub(crate) mod keys {
     pub const USER_ROLES: &str = "auth9:user_roles";
     pub const USER_ROLES_SERVICE: &str = "auth9:user_roles_service";
     pub const SERVICE_CONFIG: &str = "auth9:service";
+    pub const ROLE_TREE: &str = "auth9:role_tree";
     pub const TENANT_CONFIG: &str = "auth9:tenant";
     pub const TOKEN_BLACKLIST: &str = "auth9:token_blacklist";
     pub const WEBAUTHN_REG: &str = "auth9:webauthn_reg";
@@ -219,13 +268,18 @@ pub(crate) mod keys {
     pub const OTP_FAIL: &str = "auth9:otp_fail";
     pub const TOTP_SETUP: &str = "auth9:totp_setup";
     pub const TOTP_USED: &str = "auth9:totp_used";
-    pub const MFA_SESSION: &str = "auth9:mfa_session";
-    pub const LOGIN_CHALLENGE: &str = "auth9:login_challenge";
+    pub const FLOW_STATE_USED: &str = "auth9:flow_state_used";
     pub const AUTH_CODE: &str = "auth9:auth_code";
+    pub const AUTH_CODE_REDEEMED: &str = "auth9:auth_code_redeemed";
+    pub const SESSION_CONSENT: &str = "auth9:session_consent";
     pub const SOCIAL_STATE: &str = "auth9:social_state";
     pub const ENTERPRISE_SSO_STATE: &str = "auth9:enterprise_sso_state";
     pub const PENDING_MERGE: &str = "auth9:pending_merge";
     pub const VALID_AUDIENCES: &str = "auth9:valid_audiences";
+    pub const SESSION_ACTIVE: &str = "auth9:session_active";
+    pub const SESSION_ACTIVITY_DIRTY: &str = "auth9:session_activity_dirty";
+    pub const REQUEST_NONCE: &str = "auth9:request_nonce";
+    pub const SESSION_LIMIT_LOCK: &str = "auth9:session_limit_lock";
 }
 
 /// Default TTLs
@@ -234,4 +288,5 @@ pub(crate) mod ttl {
     pub const USER_ROLES_SERVICE_SECS: u64 = 300;
     pub const SERVICE_CONFIG_SECS: u64 = 600; // 10 minutes
     pub const TENANT_CONFIG_SECS: u64 = 600; // 10 minutes
+    pub const ROLE_TREE_SECS: u64 = 300;
 }
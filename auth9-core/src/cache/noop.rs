@@ -17,6 +17,10 @@ pub struct NoOpCacheManager {
     counters: Arc<RwLock<HashMap<String, u64>>>,
     flags: Arc<RwLock<HashMap<String, bool>>>,
     audiences: Arc<RwLock<HashSet<String>>>,
+    /// session_id -> (marked_at, pending flush value)
+    session_activity: Arc<RwLock<HashMap<String, (i64, Option<i64>)>>>,
+    /// user_id -> token of the session limit lock holder
+    session_limit_locks: Arc<RwLock<HashMap<String, String>>>,
 }
 
 impl NoOpCacheManager {
@@ -28,6 +32,8 @@ impl NoOpCacheManager {
             counters: Arc::new(RwLock::new(HashMap::new())),
             flags: Arc::new(RwLock::new(HashMap::new())),
             audiences: Arc::new(RwLock::new(HashSet::new())),
+            session_activity: Arc::new(RwLock::new(HashMap::new())),
+            session_limit_locks: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -277,72 +283,89 @@ impl NoOpCacheManager {
         Ok(())
     }
 
-    // ==================== MFA Session ====================
+    // ==================== Flow State ====================
 
-    pub async fn store_mfa_session(&self, token: &str, data: &str, _ttl_secs: u64) -> Result<()> {
-        self.otp_store
+    pub async fn claim_flow_state(&self, jti: &str, _ttl_secs: u64) -> Result<bool> {
+        Ok(self
+            .flags
             .write()
             .await
-            .insert(format!("mfa_session:{}", token), data.to_string());
-        Ok(())
+            .insert(format!("flow_state_used:{}", jti), true)
+            .is_none())
     }
 
-    pub async fn get_mfa_session(&self, token: &str) -> Result<Option<String>> {
-        Ok(self
-            .otp_store
-            .read()
+    // ==================== Authorization Code ====================
+
+    pub async fn store_authorization_code(
+        &self,
+        code: &str,
+        data: &str,
+        _ttl_secs: u64,
+    ) -> Result<()> {
+        self.oidc_states
+            .write()
             .await
-            .get(&format!("mfa_session:{}", token))
-            .cloned())
+            .insert(format!("auth_code:{}", code), data.to_string());
+        Ok(())
     }
 
-    pub async fn consume_mfa_session(&self, token: &str) -> Result<Option<String>> {
+    pub async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>> {
         Ok(self
-            .otp_store
+            .oidc_states
             .write()
             .await
-            .remove(&format!("mfa_session:{}", token)))
+            .remove(&format!("auth_code:{}", code)))
     }
 
-    // ==================== Login Challenge ====================
-
-    pub async fn store_login_challenge(&self, id: &str, data: &str, _ttl_secs: u64) -> Result<()> {
-        self.oidc_states
-            .write()
-            .await
-            .insert(format!("login_challenge:{}", id), data.to_string());
+    pub async fn mark_authorization_code_redeemed(
+        &self,
+        code: &str,
+        session_id: &str,
+        _ttl_secs: u64,
+    ) -> Result<()> {
+        self.oidc_states.write().await.insert(
+            format!("auth_code_redeemed:{}", code),
+            session_id.to_string(),
+        );
         Ok(())
     }
 
-    pub async fn consume_login_challenge(&self, id: &str) -> Result<Option<String>> {
+    pub async fn get_authorization_code_redemption(&self, code: &str) -> Result<Option<String>> {
         Ok(self
             .oidc_states
-            .write()
+            .read()
             .await
-            .remove(&format!("login_challenge:{}", id)))
+            .get(&format!("auth_code_redeemed:{}", code))
+            .cloned())
     }
 
-    // ==================== Authorization Code ====================
+    // ==================== Session Consent ====================
 
-    pub async fn store_authorization_code(
+    pub async fn store_session_consent(
         &self,
-        code: &str,
-        data: &str,
+        session_id: &str,
+        client_id: &str,
+        scope: &str,
         _ttl_secs: u64,
     ) -> Result<()> {
-        self.oidc_states
-            .write()
-            .await
-            .insert(format!("auth_code:{}", code), data.to_string());
+        self.oidc_states.write().await.insert(
+            format!("session_consent:{}:{}", session_id, client_id),
+            scope.to_string(),
+        );
         Ok(())
     }
 
-    pub async fn consume_authorization_code(&self, code: &str) -> Result<Option<String>> {
+    pub async fn get_session_consent(
+        &self,
+        session_id: &str,
+        client_id: &str,
+    ) -> Result<Option<String>> {
         Ok(self
             .oidc_states
-            .write()
+            .read()
             .await
-            .remove(&format!("auth_code:{}", code)))
+            .get(&format!("session_consent:{}:{}", session_id, client_id))
+            .cloned())
     }
 
     // ==================== Social Login State ====================
@@ -437,6 +460,63 @@ impl NoOpCacheManager {
         self.audiences.write().await.remove(client_id);
         Ok(())
     }
+
+    // ==================== Session Activity ====================
+
+    pub async fn mark_session_active(
+        &self,
+        session_id: &str,
+        at: i64,
+        precision_secs: u64,
+    ) -> Result<bool> {
+        let mut activity = self.session_activity.write().await;
+        if let Some((marked_at, _)) = activity.get(session_id) {
+            if at < marked_at + precision_secs.max(1) as i64 {
+                return Ok(false);
+            }
+        }
+        activity.insert(session_id.to_string(), (at, Some(at)));
+        Ok(true)
+    }
+
+    pub async fn drain_session_activity(&self, max: usize) -> Result<Vec<(String, i64)>> {
+        let mut activity = self.session_activity.write().await;
+        let drained: Vec<(String, i64)> = activity
+            .iter()
+            .filter_map(|(session_id, (_, pending))| pending.map(|at| (session_id.clone(), at)))
+            .take(max)
+            .collect();
+        for (session_id, _) in &drained {
+            if let Some((_, pending)) = activity.get_mut(session_id) {
+                *pending = None;
+            }
+        }
+        Ok(drained)
+    }
+
+    // ==================== Session Limit ====================
+
+    pub async fn acquire_session_limit_lock(
+        &self,
+        user_id: &str,
+        token: &str,
+        _ttl_ms: u64,
+    ) -> Result<bool> {
+        let mut locks = self.session_limit_locks.write().await;
+        if locks.contains_key(user_id) {
+            return Ok(false);
+        }
+        locks.insert(user_id.to_string(), token.to_string());
+        Ok(true)
+    }
+
+    pub async fn release_session_limit_lock(&self, user_id: &str, token: &str) -> Result<()> {
+        let mut locks = self.session_limit_locks.write().await;
+        if locks.get(user_id).is_some_and(|holder| holder == token) {
+            locks.remove(user_id);
+        }
+        Ok(())
+    }
 }
 
 impl Default for NoOpCacheManager {
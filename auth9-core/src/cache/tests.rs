@@ -28,6 +28,7 @@ fn test_cache_ttl_constants() {
     assert_eq!(ttl::USER_ROLES_SERVICE_SECS, 300);
     assert_eq!(ttl::SERVICE_CONFIG_SECS, 600);
     assert_eq!(ttl::TENANT_CONFIG_SECS, 600);
+    assert_eq!(ttl::ROLE_TREE_SECS, 300);
 }
 
 #[test]
@@ -37,6 +38,13 @@ fn test_service_config_key_format() {
     assert_eq!(key, "auth9:service:550e8400-e29b-41d4-a716-446655440000");
 }
 
+#[test]
+fn test_role_tree_key_format() {
+    let service_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+    let key = format!("{}:{}", keys::ROLE_TREE, service_id);
+    assert_eq!(key, "auth9:role_tree:550e8400-e29b-41d4-a716-446655440000");
+}
+
 #[test]
 fn test_tenant_config_key_format() {
     let tenant_id = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
@@ -490,3 +498,88 @@ fn test_refresh_token_session_key_format() {
     let key = format!("{}:{}", keys::REFRESH_TOKEN_SESSION, "hash-abc");
     assert_eq!(key, "auth9:refresh_session:hash-abc");
 }
+
+#[tokio::test]
+async fn test_noop_cache_session_activity_precision_and_drain() {
+    let cache: &dyn CacheOperations = &NoOpCacheManager::new();
+
+    assert!(cache.mark_session_active("s1", 1000, 60).await.unwrap());
+    // Within the precision window: no new marker
+    assert!(!cache.mark_session_active("s1", 1030, 60).await.unwrap());
+    assert!(cache.mark_session_active("s2", 1030, 60).await.unwrap());
+
+    let mut drained = cache.drain_session_activity(10).await.unwrap();
+    drained.sort();
+    assert_eq!(
+        drained,
+        vec![("s1".to_string(), 1000), ("s2".to_string(), 1030)]
+    );
+    assert!(cache.drain_session_activity(10).await.unwrap().is_empty());
+
+    // After the window a new marker is recorded
+    assert!(cache.mark_session_active("s1", 1061, 60).await.unwrap());
+    assert_eq!(
+        cache.drain_session_activity(10).await.unwrap(),
+        vec![("s1".to_string(), 1061)]
+    );
+}
+
+#[tokio::test]
+async fn test_noop_cache_session_limit_lock() {
+    let cache: &dyn CacheOperations = &NoOpCacheManager::new();
+
+    assert!(cache
+        .acquire_session_limit_lock("u1", "a", 5000)
+        .await
+        .unwrap());
+    assert!(!cache
+        .acquire_session_limit_lock("u1", "b", 5000)
+        .await
+        .unwrap());
+    assert!(cache
+        .acquire_session_limit_lock("u2", "b", 5000)
+        .await
+        .unwrap());
+
+    // Only the holder's token releases the lock
+    cache.release_session_limit_lock("u1", "b").await.unwrap();
+    assert!(!cache
+        .acquire_session_limit_lock("u1", "b", 5000)
+        .await
+        .unwrap());
+    cache.release_session_limit_lock("u1", "a").await.unwrap();
+    assert!(cache
+        .acquire_session_limit_lock("u1", "b", 5000)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_noop_cache_session_consent() {
+    let cache: &dyn CacheOperations = &NoOpCacheManager::new();
+
+    assert!(cache
+        .get_session_consent("sid-1", "app")
+        .await
+        .unwrap()
+        .is_none());
+    cache
+        .store_session_consent("sid-1", "app", "openid email", 3600)
+        .await
+        .unwrap();
+    assert_eq!(
+        cache.get_session_consent("sid-1", "app").await.unwrap(),
+        Some("openid email".to_string())
+    );
+    // Scoped to the session and the client
+    assert!(cache
+        .get_session_consent("sid-1", "other")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(cache
+        .get_session_consent("sid-2", "app")
+        .await
+        .unwrap()
+        .is_none());
+}
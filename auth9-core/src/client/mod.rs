@@ -0,0 +1,266 @@
+//! Typed async client for the Auth9 REST API
+//!
+//! Enabled with the `client` feature, for Rust services that manage tenants,
+//! users and roles or validate Auth9 tokens:
+//!
+//! ```no_run
+//! # async fn example() -> auth9_core::client::ClientResult<()> {
+//! use auth9_core::client::Auth9Client;
+//!
+//! let client = Auth9Client::new("https://auth9.example.com").with_token("service-token");
+//! let tenant = client.tenants().get(uuid::Uuid::new_v4()).await?;
+//!
+//! let verifier = client.token_verifier().await?;
+//! let claims = verifier.verify_tenant_access_token("eyJ...", "my-service")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests and responses use the crate's own models, so the client cannot
+//! drift from the server it is built with. API errors keep the server's
+//! status, error code and message (see [`ClientError::Api`]).
+
+pub mod roles;
+pub mod tenants;
+pub mod tokens;
+pub mod users;
+
+pub use roles::RolesApi;
+pub use tenants::TenantsApi;
+pub use tokens::TokenVerifier;
+pub use users::UsersApi;
+
+use crate::http_support::{MessageResponse, SuccessResponse};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Default request timeout
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Client result type
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
+
+/// Client error types
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Error response of the API
+    #[error("API error ({status}): {message}")]
+    Api {
+        status: StatusCode,
+        /// Error code, e.g. `not_found` or `version_conflict`
+        error: String,
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Http(err) => err.status(),
+            Self::InvalidToken(_) => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+}
+
+/// Error response body of the API
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+    message: String,
+    #[serde(default)]
+    details: Option<serde_json::Value>,
+}
+
+/// Auth9 REST API client
+///
+/// Cheap to clone; clones share the connection pool.
+#[derive(Clone)]
+pub struct Auth9Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Auth9Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .build()
+            .expect("Failed to create HTTP client for Auth9");
+        Self::with_http_client(base_url, http)
+    }
+
+    /// Use a preconfigured HTTP client (proxies, TLS roots, timeouts)
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Send `token` as the bearer token of every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn tenants(&self) -> TenantsApi<'_> {
+        TenantsApi { client: self }
+    }
+
+    pub fn users(&self) -> UsersApi<'_> {
+        UsersApi { client: self }
+    }
+
+    pub fn roles(&self) -> RolesApi<'_> {
+        RolesApi { client: self }
+    }
+
+    /// Token verifier with the signing keys the server currently publishes
+    pub async fn token_verifier(&self) -> ClientResult<TokenVerifier> {
+        TokenVerifier::discover(self).await
+    }
+
+    pub(crate) fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    /// Send the request and decode the whole response body
+    pub(crate) async fn send<T: DeserializeOwned>(
+        &self,
+        builder: RequestBuilder,
+    ) -> ClientResult<T> {
+        let response = builder.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+        let body = response.bytes().await?;
+        Err(match serde_json::from_slice::<ErrorBody>(&body) {
+            Ok(err) => ClientError::Api {
+                status,
+                error: err.error,
+                message: err.message,
+                details: err.details,
+            },
+            Err(_) => ClientError::Api {
+                status,
+                error: status.canonical_reason().unwrap_or("error").to_string(),
+                message: String::from_utf8_lossy(&body).into_owned(),
+                details: None,
+            },
+        })
+    }
+
+    /// Send the request and unwrap the `data` of a success response
+    pub(crate) async fn send_data<T: DeserializeOwned>(
+        &self,
+        builder: RequestBuilder,
+    ) -> ClientResult<T> {
+        Ok(self.send::<SuccessResponse<T>>(builder).await?.data)
+    }
+
+    /// Send a request answered with a message
+    pub(crate) async fn send_message(&self, builder: RequestBuilder) -> ClientResult<()> {
+        self.send::<MessageResponse>(builder).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tenant::Tenant;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_success_response_is_unwrapped() {
+        let mock_server = MockServer::start().await;
+        let tenant = Tenant {
+            name: "Acme".to_string(),
+            slug: "acme".to_string(),
+            ..Default::default()
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/tenants/{}", tenant.id)))
+            .and(header("authorization", "Bearer test-token"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": tenant })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = Auth9Client::new(format!("{}/", mock_server.uri())).with_token("test-token");
+        let fetched = client.tenants().get(*tenant.id).await.unwrap();
+        assert_eq!(fetched.id, tenant.id);
+        assert_eq!(fetched.slug, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_error_response_keeps_code_and_message() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": "not_found",
+                "message": "Tenant not found",
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = Auth9Client::new(mock_server.uri());
+        let err = client
+            .tenants()
+            .get(uuid::Uuid::new_v4())
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+        match err {
+            ClientError::Api { error, message, .. } => {
+                assert_eq!(error, "not_found");
+                assert_eq!(message, "Tenant not found");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_json_error_body() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("bad gateway"))
+            .mount(&mock_server)
+            .await;
+
+        let client = Auth9Client::new(mock_server.uri());
+        let err = client.users().get(uuid::Uuid::new_v4()).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+    }
+}
@@ -0,0 +1,111 @@
+//! Role and permission endpoints
+
+use super::{Auth9Client, ClientResult};
+use crate::models::rbac::{
+    AssignRolesInput, CheckPermissionInput, CreateRoleInput, PermissionCheckResult, Role,
+    RoleWithPermissions, UpdateRoleInput, UserRolesInTenant,
+};
+use reqwest::Method;
+use uuid::Uuid;
+
+/// `/api/v1/roles`, role assignments and permission checks
+pub struct RolesApi<'a> {
+    pub(super) client: &'a Auth9Client,
+}
+
+impl RolesApi<'_> {
+    /// Roles of a service
+    pub async fn list(&self, service_id: Uuid) -> ClientResult<Vec<Role>> {
+        self.client
+            .send_data(self.client.request(
+                Method::GET,
+                &format!("/api/v1/services/{}/roles", service_id),
+            ))
+            .await
+    }
+
+    pub async fn get(&self, id: Uuid) -> ClientResult<RoleWithPermissions> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::GET, &format!("/api/v1/roles/{}", id)),
+            )
+            .await
+    }
+
+    pub async fn create(&self, input: &CreateRoleInput) -> ClientResult<Role> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::POST, "/api/v1/roles")
+                    .json(input),
+            )
+            .await
+    }
+
+    pub async fn update(&self, id: Uuid, input: &UpdateRoleInput) -> ClientResult<Role> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::PUT, &format!("/api/v1/roles/{}", id))
+                    .json(input),
+            )
+            .await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> ClientResult<()> {
+        self.client
+            .send_message(
+                self.client
+                    .request(Method::DELETE, &format!("/api/v1/roles/{}", id)),
+            )
+            .await
+    }
+
+    /// Assign roles to a user in a tenant
+    pub async fn assign(&self, input: &AssignRolesInput) -> ClientResult<()> {
+        self.client
+            .send_message(
+                self.client
+                    .request(Method::POST, "/api/v1/rbac/assign")
+                    .json(input),
+            )
+            .await
+    }
+
+    /// Roles and effective permissions of a user in a tenant
+    pub async fn user_roles(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+    ) -> ClientResult<UserRolesInTenant> {
+        self.client
+            .send_data(self.client.request(
+                Method::GET,
+                &format!("/api/v1/users/{}/tenants/{}/roles", user_id, tenant_id),
+            ))
+            .await
+    }
+
+    /// Whether a user holds a permission in a tenant
+    pub async fn check_permission(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        input: &CheckPermissionInput,
+    ) -> ClientResult<PermissionCheckResult> {
+        self.client
+            .send_data(
+                self.client
+                    .request(
+                        Method::POST,
+                        &format!(
+                            "/api/v1/users/{}/tenants/{}/permissions/check",
+                            user_id, tenant_id
+                        ),
+                    )
+                    .json(input),
+            )
+            .await
+    }
+}
@@ -0,0 +1,78 @@
+//! Tenant endpoints
+
+use super::{Auth9Client, ClientResult};
+use crate::http_support::PaginatedResponse;
+use crate::models::tenant::{CreateTenantInput, Tenant, UpdateTenantInput};
+use crate::models::user::User;
+use reqwest::Method;
+use uuid::Uuid;
+
+/// `/api/v1/tenants`
+pub struct TenantsApi<'a> {
+    pub(super) client: &'a Auth9Client,
+}
+
+impl TenantsApi<'_> {
+    /// Tenants visible to the caller
+    pub async fn list(&self, page: i64, per_page: i64) -> ClientResult<PaginatedResponse<Tenant>> {
+        self.client
+            .send(
+                self.client
+                    .request(Method::GET, "/api/v1/tenants")
+                    .query(&[("page", page), ("per_page", per_page)]),
+            )
+            .await
+    }
+
+    pub async fn get(&self, id: Uuid) -> ClientResult<Tenant> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::GET, &format!("/api/v1/tenants/{}", id)),
+            )
+            .await
+    }
+
+    /// Create a tenant (platform admins); the caller becomes its owner
+    pub async fn create(&self, input: &CreateTenantInput) -> ClientResult<Tenant> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::POST, "/api/v1/tenants")
+                    .json(input),
+            )
+            .await
+    }
+
+    pub async fn update(&self, id: Uuid, input: &UpdateTenantInput) -> ClientResult<Tenant> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::PUT, &format!("/api/v1/tenants/{}", id))
+                    .json(input),
+            )
+            .await
+    }
+
+    /// Delete a tenant and everything in it (platform admins)
+    pub async fn delete(&self, id: Uuid) -> ClientResult<()> {
+        self.client
+            .send_message(
+                self.client
+                    .request(Method::DELETE, &format!("/api/v1/tenants/{}", id))
+                    .header("X-Confirm-Destructive", "true"),
+            )
+            .await
+    }
+
+    /// Members of a tenant (tenant access tokens only)
+    pub async fn users(&self, id: Uuid, page: i64, per_page: i64) -> ClientResult<Vec<User>> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::GET, &format!("/api/v1/tenants/{}/users", id))
+                    .query(&[("page", page), ("per_page", per_page)]),
+            )
+            .await
+    }
+}
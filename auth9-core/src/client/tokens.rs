@@ -0,0 +1,277 @@
+//! Offline validation of Auth9-issued tokens
+//!
+//! Verifies RS256 signatures against the keys published at
+//! `/.well-known/jwks.json` (current and, during a rotation, previous key)
+//! and checks issuer, audience, expiry and token type. Deployments signing
+//! with HS256 publish no keys and cannot be verified this way.
+
+use super::{Auth9Client, ClientError, ClientResult};
+use crate::jwt::{IdentityClaims, TenantAccessClaims};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Audience of identity tokens
+const IDENTITY_AUDIENCE: &str = "auth9";
+
+#[derive(Deserialize)]
+struct Discovery {
+    issuer: String,
+}
+
+#[derive(Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    n: String,
+    e: String,
+}
+
+/// Verifies tokens with the signing keys of one Auth9 issuer
+///
+/// Keys are fetched once; build a new verifier after a key rotation
+/// completes.
+#[derive(Clone)]
+pub struct TokenVerifier {
+    issuer: String,
+    keys: Vec<DecodingKey>,
+}
+
+impl TokenVerifier {
+    /// Fetch the issuer and signing keys from the server
+    pub async fn discover(client: &Auth9Client) -> ClientResult<Self> {
+        let discovery: Discovery = client
+            .send(client.request(Method::GET, "/.well-known/openid-configuration"))
+            .await?;
+        let jwks: Jwks = client
+            .send(client.request(Method::GET, "/.well-known/jwks.json"))
+            .await?;
+
+        let keys = jwks
+            .keys
+            .iter()
+            .filter(|jwk| jwk.kty == "RSA")
+            .map(|jwk| {
+                DecodingKey::from_rsa_components(&jwk.n, &jwk.e)
+                    .map_err(|e| ClientError::InvalidToken(format!("Invalid signing key: {}", e)))
+            })
+            .collect::<ClientResult<Vec<_>>>()?;
+        if keys.is_empty() {
+            return Err(ClientError::InvalidToken(
+                "The server publishes no RS256 signing keys".to_string(),
+            ));
+        }
+        Ok(Self {
+            issuer: discovery.issuer,
+            keys,
+        })
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Verify an identity token (issued at sign-in)
+    pub fn verify_identity_token(&self, token: &str) -> ClientResult<IdentityClaims> {
+        let claims: IdentityClaims = self.decode(token, IDENTITY_AUDIENCE)?;
+        if claims.token_type != "identity" {
+            return Err(ClientError::InvalidToken(
+                "Not an identity token".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+
+    /// Verify a tenant access token issued to the service with client ID
+    /// `audience`
+    pub fn verify_tenant_access_token(
+        &self,
+        token: &str,
+        audience: &str,
+    ) -> ClientResult<TenantAccessClaims> {
+        let claims: TenantAccessClaims = self.decode(token, audience)?;
+        if claims.token_type != "access" {
+            return Err(ClientError::InvalidToken(
+                "Not a tenant access token".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, token: &str, audience: &str) -> ClientResult<T> {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[audience]);
+
+        let mut last_error = None;
+        for key in &self.keys {
+            match decode::<T>(token, key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                // Signed with another published key
+                Err(e) if matches!(e.kind(), ErrorKind::InvalidSignature) => last_error = Some(e),
+                Err(e) => return Err(ClientError::InvalidToken(e.to_string())),
+            }
+        }
+        Err(ClientError::InvalidToken(last_error.map_or_else(
+            || "No signing keys".to_string(),
+            |e| e.to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::JwtConfig;
+    use crate::jwt::JwtManager;
+    use base64::Engine;
+    use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+    use rsa::traits::PublicKeyParts;
+    use uuid::Uuid;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ISSUER: &str = "https://auth9.test";
+
+    fn rsa_key() -> rsa::RsaPrivateKey {
+        rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048).unwrap()
+    }
+
+    fn manager(key: &rsa::RsaPrivateKey) -> JwtManager {
+        JwtManager::new(JwtConfig {
+            secret: String::new(),
+            issuer: ISSUER.to_string(),
+            access_token_ttl_secs: 3600,
+            refresh_token_ttl_secs: 86400,
+            private_key_pem: Some(key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string()),
+            public_key_pem: Some(
+                rsa::RsaPublicKey::from(key)
+                    .to_public_key_pem(LineEnding::LF)
+                    .unwrap(),
+            ),
+            previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
+        })
+    }
+
+    fn jwk(key: &rsa::RsaPrivateKey) -> serde_json::Value {
+        let encode =
+            |bytes: Vec<u8>| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        serde_json::json!({
+            "kty": "RSA",
+            "use": "sig",
+            "alg": "RS256",
+            "kid": "auth9-current",
+            "n": encode(key.n().to_bytes_be()),
+            "e": encode(key.e().to_bytes_be()),
+        })
+    }
+
+    async fn verifier(keys: Vec<serde_json::Value>) -> TokenVerifier {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "issuer": ISSUER })),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/jwks.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "keys": keys })),
+            )
+            .mount(&mock_server)
+            .await;
+        Auth9Client::new(mock_server.uri())
+            .token_verifier()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verifies_tokens_with_published_keys() {
+        let (current, previous) = (rsa_key(), rsa_key());
+        let verifier = verifier(vec![jwk(&current), jwk(&previous)]).await;
+        assert_eq!(verifier.issuer(), ISSUER);
+
+        let user_id = Uuid::new_v4();
+        let identity = manager(&current)
+            .create_identity_token(user_id, "user@example.com", None)
+            .unwrap();
+        let claims = verifier.verify_identity_token(&identity).unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+
+        // Tokens signed before a rotation still verify
+        let access = manager(&previous)
+            .create_tenant_access_token(
+                user_id,
+                "user@example.com",
+                Uuid::new_v4(),
+                "my-service",
+                vec!["admin".to_string()],
+                vec!["user:read".to_string()],
+            )
+            .unwrap();
+        let claims = verifier
+            .verify_tenant_access_token(&access, "my-service")
+            .unwrap();
+        assert_eq!(claims.permissions, vec!["user:read"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_audience_type_and_key() {
+        let key = rsa_key();
+        let verifier = verifier(vec![jwk(&key)]).await;
+        let jwt = manager(&key);
+
+        let access = jwt
+            .create_tenant_access_token(
+                Uuid::new_v4(),
+                "user@example.com",
+                Uuid::new_v4(),
+                "my-service",
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        assert!(verifier
+            .verify_tenant_access_token(&access, "other-service")
+            .is_err());
+        assert!(verifier.verify_identity_token(&access).is_err());
+
+        let foreign = manager(&rsa_key())
+            .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+            .unwrap();
+        assert!(verifier.verify_identity_token(&foreign).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hs256_deployment_cannot_be_verified() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/openid-configuration"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "issuer": ISSUER })),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/.well-known/jwks.json"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "keys": [] })),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let result = Auth9Client::new(mock_server.uri()).token_verifier().await;
+        assert!(matches!(result, Err(ClientError::InvalidToken(_))));
+    }
+}
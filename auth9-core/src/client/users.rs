@@ -0,0 +1,73 @@
+//! User endpoints
+
+use super::{Auth9Client, ClientResult};
+use crate::domains::tenant_access::api::user::CreateUserRequest;
+use crate::http_support::PaginatedResponse;
+use crate::models::user::{UpdateUserInput, User};
+use reqwest::Method;
+use uuid::Uuid;
+
+/// `/api/v1/users`
+pub struct UsersApi<'a> {
+    pub(super) client: &'a Auth9Client,
+}
+
+impl UsersApi<'_> {
+    /// Users visible to the caller, optionally only those matching `search`
+    /// (email or display name)
+    pub async fn list(
+        &self,
+        page: i64,
+        per_page: i64,
+        search: Option<&str>,
+    ) -> ClientResult<PaginatedResponse<User>> {
+        let mut request = self
+            .client
+            .request(Method::GET, "/api/v1/users")
+            .query(&[("page", page), ("per_page", per_page)]);
+        if let Some(search) = search {
+            request = request.query(&[("search", search)]);
+        }
+        self.client.send(request).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> ClientResult<User> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::GET, &format!("/api/v1/users/{}", id)),
+            )
+            .await
+    }
+
+    /// Create a user, optionally with a password and as a member of
+    /// `input.tenant_id`
+    pub async fn create(&self, input: &CreateUserRequest) -> ClientResult<User> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::POST, "/api/v1/users")
+                    .json(input),
+            )
+            .await
+    }
+
+    pub async fn update(&self, id: Uuid, input: &UpdateUserInput) -> ClientResult<User> {
+        self.client
+            .send_data(
+                self.client
+                    .request(Method::PUT, &format!("/api/v1/users/{}", id))
+                    .json(input),
+            )
+            .await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> ClientResult<()> {
+        self.client
+            .send_message(
+                self.client
+                    .request(Method::DELETE, &format!("/api/v1/users/{}", id)),
+            )
+            .await
+    }
+}
@@ -0,0 +1,369 @@
+//! External secrets providers
+//!
+//! With `SECRETS_PROVIDER=vault` or `SECRETS_PROVIDER=aws-secrets-manager`
+//! the secrets listed in [`MANAGED_SECRETS`] are read from HashiCorp Vault
+//! (KV engine) or one AWS Secrets Manager secret holding a JSON object,
+//! instead of being passed as environment variables. They are fetched once
+//! at startup and passed to [`super::Config::from_env_with_secrets`], which
+//! leaves the process environment untouched, and again on `SIGHUP` to rotate
+//! the JWT signing keys without a restart. Other secrets take effect on the
+//! next restart.
+
+use super::JwtConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Secrets a provider may supply, by their environment variable name
+pub const MANAGED_SECRETS: [&str; 16] = [
+    "JWT_SECRET",
+    "JWT_PRIVATE_KEY",
+    "JWT_PUBLIC_KEY",
+    "JWT_PREVIOUS_PUBLIC_KEY",
+    "PASSWORD_RESET_HMAC_KEY",
+    "SETTINGS_ENCRYPTION_KEY",
+    "SETTINGS_ENCRYPTION_KEY_PREVIOUS",
+    "FLOW_STATE_SECRET",
+    "IDENTITY_WEBHOOK_SECRET",
+    "GRPC_API_KEYS",
+    "CAPTCHA_SECRET_KEY",
+    "EMAIL_FEEDBACK_WEBHOOK_SECRET",
+    "BILLING_SIGNING_SECRET",
+    "METRICS_TOKEN",
+    "AUDIT_CHECKPOINT_KEY",
+    "BACKUP_ENCRYPTION_KEY",
+];
+
+/// Default location of the Kubernetes service account token
+const K8S_SERVICE_ACCOUNT_TOKEN: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// Secret values keyed by environment variable name. Keys outside
+/// [`MANAGED_SECRETS`] are dropped.
+#[derive(Clone, Default)]
+pub struct SecretValues(BTreeMap<String, String>);
+
+impl SecretValues {
+    /// Keep the managed secrets of a flat JSON object; non-string values are
+    /// serialized as JSON
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let object = value
+            .as_object()
+            .context("secret payload must be a JSON object")?;
+        let values = object
+            .iter()
+            .filter(|(key, _)| MANAGED_SECRETS.contains(&key.as_str()))
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+        Ok(Self(values))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Names of the secrets supplied, never their values
+    pub fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `current` with the JWT keys replaced by the ones in these secrets
+    pub fn apply_to_jwt(&self, current: &JwtConfig) -> JwtConfig {
+        let pem = |key: &str, fallback: &Option<String>| {
+            self.get(key)
+                .map(|value| value.replace("\\n", "\n"))
+                .or_else(|| fallback.clone())
+        };
+        JwtConfig {
+            secret: self
+                .get("JWT_SECRET")
+                .map(str::to_string)
+                .unwrap_or_else(|| current.secret.clone()),
+            private_key_pem: pem("JWT_PRIVATE_KEY", &current.private_key_pem),
+            public_key_pem: pem("JWT_PUBLIC_KEY", &current.public_key_pem),
+            previous_public_key_pem: pem(
+                "JWT_PREVIOUS_PUBLIC_KEY",
+                &current.previous_public_key_pem,
+            ),
+            ..current.clone()
+        }
+    }
+}
+
+impl std::fmt::Debug for SecretValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.keys()).finish()
+    }
+}
+
+/// Source of the managed secrets
+#[async_trait]
+pub trait SecretsProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn fetch(&self) -> Result<SecretValues>;
+}
+
+/// Provider selected by `SECRETS_PROVIDER`; `None` keeps secrets in the
+/// environment (the default, `env`)
+pub fn provider_from_env() -> Result<Option<Box<dyn SecretsProvider>>> {
+    match env::var("SECRETS_PROVIDER")
+        .unwrap_or_else(|_| "env".to_string())
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "" | "env" => Ok(None),
+        "vault" => Ok(Some(Box::new(VaultSecretsProvider::from_env()?))),
+        "aws-secrets-manager" => Ok(Some(Box::new(AwsSecretsManagerProvider::from_env()?))),
+        other => anyhow::bail!(
+            "SECRETS_PROVIDER must be one of env, vault, aws-secrets-manager (got '{}')",
+            other
+        ),
+    }
+}
+
+/// How auth9-core authenticates to Vault
+#[derive(Debug, Clone)]
+enum VaultAuth {
+    /// Token read from `VAULT_TOKEN_FILE`, or `VAULT_TOKEN`
+    Token,
+    /// Kubernetes auth method with the pod's service account token
+    Kubernetes { role: String, mount: String },
+}
+
+/// Reads the secrets from a Vault KV secret (`VAULT_ADDR`,
+/// `VAULT_KV_MOUNT`, `VAULT_SECRET_PATH`)
+pub struct VaultSecretsProvider {
+    http_client: reqwest::Client,
+    addr: String,
+    namespace: Option<String>,
+    kv_mount: String,
+    kv_version: u8,
+    secret_path: String,
+    auth: VaultAuth,
+}
+
+impl VaultSecretsProvider {
+    pub fn from_env() -> Result<Self> {
+        let addr = env::var("VAULT_ADDR")
+            .context("VAULT_ADDR is required with SECRETS_PROVIDER=vault")?
+            .trim_end_matches('/')
+            .to_string();
+        let auth = match env::var("VAULT_K8S_ROLE") {
+            Ok(role) if !role.trim().is_empty() => VaultAuth::Kubernetes {
+                role: role.trim().to_string(),
+                mount: env::var("VAULT_K8S_AUTH_MOUNT")
+                    .unwrap_or_else(|_| "kubernetes".to_string()),
+            },
+            _ => VaultAuth::Token,
+        };
+        let kv_version = match env::var("VAULT_KV_VERSION").as_deref() {
+            Ok("1") => 1,
+            Ok("2") | Err(_) => 2,
+            Ok(other) => anyhow::bail!("VAULT_KV_VERSION must be 1 or 2 (got '{}')", other),
+        };
+        Ok(Self {
+            http_client: reqwest::Client::builder()
+                .user_agent("Auth9-Core")
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            addr,
+            namespace: env::var("VAULT_NAMESPACE").ok().filter(|s| !s.is_empty()),
+            kv_mount: env::var("VAULT_KV_MOUNT").unwrap_or_else(|_| "secret".to_string()),
+            kv_version,
+            secret_path: env::var("VAULT_SECRET_PATH").unwrap_or_else(|_| "auth9".to_string()),
+            auth,
+        })
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.namespace {
+            Some(namespace) => builder.header("X-Vault-Namespace", namespace),
+            None => builder,
+        }
+    }
+
+    async fn token(&self) -> Result<String> {
+        match &self.auth {
+            VaultAuth::Token => {
+                if let Ok(path) = env::var("VAULT_TOKEN_FILE") {
+                    let token = tokio::fs::read_to_string(&path)
+                        .await
+                        .with_context(|| format!("Failed to read VAULT_TOKEN_FILE {}", path))?;
+                    return Ok(token.trim().to_string());
+                }
+                env::var("VAULT_TOKEN")
+                    .context("VAULT_TOKEN, VAULT_TOKEN_FILE or VAULT_K8S_ROLE is required")
+            }
+            VaultAuth::Kubernetes { role, mount } => {
+                let jwt_path = env::var("VAULT_K8S_TOKEN_PATH")
+                    .unwrap_or_else(|_| K8S_SERVICE_ACCOUNT_TOKEN.to_string());
+                let jwt = tokio::fs::read_to_string(&jwt_path)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to read service account token {}", jwt_path)
+                    })?;
+                let response: Value = self
+                    .request(
+                        self.http_client
+                            .post(format!("{}/v1/auth/{}/login", self.addr, mount)),
+                    )
+                    .json(&serde_json::json!({ "role": role, "jwt": jwt.trim() }))
+                    .send()
+                    .await?
+                    .error_for_status()
+                    .context("Vault Kubernetes login failed")?
+                    .json()
+                    .await?;
+                response["auth"]["client_token"]
+                    .as_str()
+                    .map(str::to_string)
+                    .context("Vault login response has no client_token")
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecretsProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    async fn fetch(&self) -> Result<SecretValues> {
+        let token = self.token().await?;
+        let url = match self.kv_version {
+            1 => format!("{}/v1/{}/{}", self.addr, self.kv_mount, self.secret_path),
+            _ => format!(
+                "{}/v1/{}/data/{}",
+                self.addr, self.kv_mount, self.secret_path
+            ),
+        };
+        let response: Value = self
+            .request(self.http_client.get(&url))
+            .header("X-Vault-Token", token)
+            .send()
+            .await?
+            .error_for_status()
+            .with_context(|| format!("Failed to read Vault secret {}", self.secret_path))?
+            .json()
+            .await?;
+        let data = match self.kv_version {
+            1 => &response["data"],
+            _ => &response["data"]["data"],
+        };
+        SecretValues::from_json(data)
+    }
+}
+
+/// Reads the secrets from one AWS Secrets Manager secret whose value is a
+/// JSON object (`AWS_SECRETS_MANAGER_SECRET_ID`, optional
+/// `AWS_SECRETS_MANAGER_REGION`); credentials come from the default chain
+pub struct AwsSecretsManagerProvider {
+    secret_id: String,
+    region: Option<String>,
+}
+
+impl AwsSecretsManagerProvider {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            secret_id: env::var("AWS_SECRETS_MANAGER_SECRET_ID").context(
+                "AWS_SECRETS_MANAGER_SECRET_ID is required with SECRETS_PROVIDER=aws-secrets-manager",
+            )?,
+            region: env::var("AWS_SECRETS_MANAGER_REGION").ok(),
+        })
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    fn name(&self) -> &'static str {
+        "aws-secrets-manager"
+    }
+
+    async fn fetch(&self) -> Result<SecretValues> {
+        let mut loader = aws_config::from_env();
+        if let Some(region) = &self.region {
+            loader = loader.region(aws_config::Region::new(region.clone()));
+        }
+        let client = aws_sdk_secretsmanager::Client::new(&loader.load().await);
+        let output = client
+            .get_secret_value()
+            .secret_id(&self.secret_id)
+            .send()
+            .await
+            .with_context(|| format!("Failed to read AWS secret {}", self.secret_id))?;
+        let payload = output
+            .secret_string()
+            .context("AWS secret has no string value")?;
+        let value: Value =
+            serde_json::from_str(payload).context("AWS secret value is not a JSON object")?;
+        SecretValues::from_json(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn jwt_config() -> JwtConfig {
+        JwtConfig {
+            secret: "old-secret".to_string(),
+            issuer: "https://auth9.example.com".to_string(),
+            access_token_ttl_secs: 3600,
+            refresh_token_ttl_secs: 604800,
+            private_key_pem: Some("old-private".to_string()),
+            public_key_pem: Some("old-public".to_string()),
+            previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
+        }
+    }
+
+    #[test]
+    fn test_from_json_keeps_managed_secrets_only() {
+        let values = SecretValues::from_json(&json!({
+            "JWT_SECRET": "s3cret",
+            "GRPC_API_KEYS": ["a", "b"],
+            "DATABASE_URL": "mysql://elsewhere"
+        }))
+        .unwrap();
+        assert_eq!(values.keys(), vec!["GRPC_API_KEYS", "JWT_SECRET"]);
+        assert_eq!(values.get("JWT_SECRET"), Some("s3cret"));
+        assert!(values.get("DATABASE_URL").is_none());
+        assert!(!format!("{:?}", values).contains("s3cret"));
+        assert!(SecretValues::from_json(&json!("flat")).is_err());
+    }
+
+    #[test]
+    fn test_apply_to_jwt_replaces_supplied_keys() {
+        let values = SecretValues::from_json(&json!({
+            "JWT_PRIVATE_KEY": "new\\nprivate",
+            "JWT_PREVIOUS_PUBLIC_KEY": "old-public"
+        }))
+        .unwrap();
+        let rotated = values.apply_to_jwt(&jwt_config());
+        assert_eq!(rotated.private_key_pem.as_deref(), Some("new\nprivate"));
+        assert_eq!(rotated.public_key_pem.as_deref(), Some("old-public"));
+        assert_eq!(
+            rotated.previous_public_key_pem.as_deref(),
+            Some("old-public")
+        );
+        assert_eq!(rotated.secret, "old-secret");
+        assert_eq!(rotated.issuer, "https://auth9.example.com");
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides encryption/decryption for sensitive settings
 //! like SMTP passwords, API keys, and other secrets stored in the database.
 
+use crate::config::SettingsEncryptionConfig;
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Nonce,
@@ -53,11 +54,14 @@ impl EncryptionKey {
         Ok(Self { key })
     }
 
-    /// Create encryption key from environment variable
-    pub fn from_env() -> Result<Self, EncryptionError> {
-        let encoded = std::env::var("SETTINGS_ENCRYPTION_KEY")
-            .map_err(|_| EncryptionError::InvalidKeyLength)?;
-        Self::from_base64(&encoded)
+    /// Create encryption key from `SETTINGS_ENCRYPTION_KEY` as loaded into
+    /// the configuration
+    pub fn from_config(config: &SettingsEncryptionConfig) -> Result<Self, EncryptionError> {
+        let encoded = config
+            .key
+            .as_deref()
+            .ok_or(EncryptionError::InvalidKeyLength)?;
+        Self::from_base64(encoded)
     }
 
     /// Get the raw key bytes
@@ -0,0 +1,323 @@
+//! Sealed flow-state tokens for multi-step login flows
+//!
+//! Instead of parking flow state (login challenge, MFA session) in a
+//! replica-local or shared store, the state itself is handed to the client as
+//! an AES-256-GCM sealed envelope. Any replica holding the same secret can
+//! open it. The sealing key is derived with HKDF-SHA256 under its own label
+//! from `FLOW_STATE_SECRET`, or from the JWT secret when that is unset, so it
+//! is never the key material used to sign tokens. The flow kind is bound as
+//! associated data so a token minted for one step cannot be fed into another,
+//! and the envelope carries its own expiry and a `jti` that callers claim
+//! once to reject replays.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL, Engine};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::config::FlowStateConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Version prefix of sealed flow-state tokens
+pub const FLOW_STATE_PREFIX: &str = "fs1.";
+
+/// HKDF info label of the sealing key; no other key is derived under it
+const FLOW_STATE_KEY_LABEL: &[u8] = b"auth9 flow-state sealing key v1";
+
+/// Multi-step flow a sealed token belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowKind {
+    /// OIDC authorize → hosted login → authorize complete
+    LoginChallenge,
+    /// Password verified, second factor pending
+    MfaSession,
+}
+
+impl FlowKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LoginChallenge => "login_challenge",
+            Self::MfaSession => "mfa_session",
+        }
+    }
+}
+
+/// Flow-state error types
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FlowStateError {
+    #[error("Malformed flow state")]
+    Malformed,
+
+    #[error("Flow state failed authentication")]
+    InvalidSignature,
+
+    #[error("Flow state expired")]
+    Expired,
+
+    #[error("Failed to seal flow state")]
+    SealFailed,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    jti: String,
+    exp: i64,
+    data: T,
+}
+
+/// Verified contents of a flow-state token
+#[derive(Debug, Clone)]
+pub struct OpenedFlowState<T> {
+    /// Unique token id, claimed once for replay protection
+    pub jti: String,
+    /// Unix timestamp after which the token is rejected
+    pub expires_at: i64,
+    pub data: T,
+}
+
+impl<T> OpenedFlowState<T> {
+    /// Seconds until expiry (at least 1, for use as a cache TTL)
+    pub fn remaining_ttl_secs(&self) -> u64 {
+        (self.expires_at - chrono::Utc::now().timestamp()).max(1) as u64
+    }
+}
+
+/// Seals and opens flow-state tokens
+#[derive(Clone)]
+pub struct FlowStateCodec {
+    key: [u8; 32],
+}
+
+impl FlowStateCodec {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Derive the sealing key from a secret shared by all replicas
+    /// (HKDF-SHA256, RFC 5869, with a flow-state specific info label)
+    pub fn from_secret(secret: &str) -> Self {
+        Self {
+            key: hkdf_sha256(secret.as_bytes(), FLOW_STATE_KEY_LABEL),
+        }
+    }
+
+    /// Codec for the configured flow-state secret, falling back to the JWT
+    /// secret. Both go through the same labelled derivation.
+    pub fn from_config(config: &FlowStateConfig, jwt_secret: &str) -> Self {
+        Self::from_secret(config.secret.as_deref().unwrap_or(jwt_secret))
+    }
+
+    /// Seal `data` into a token valid for `ttl_secs`
+    pub fn seal<T: Serialize>(
+        &self,
+        kind: FlowKind,
+        data: &T,
+        ttl_secs: u64,
+    ) -> Result<String, FlowStateError> {
+        let envelope = Envelope {
+            jti: uuid::Uuid::new_v4().to_string(),
+            exp: chrono::Utc::now().timestamp() + ttl_secs as i64,
+            data,
+        };
+        let plaintext = serde_json::to_vec(&envelope).map_err(|_| FlowStateError::SealFailed)?;
+
+        let cipher =
+            Aes256Gcm::new_from_slice(&self.key).map_err(|_| FlowStateError::SealFailed)?;
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: &plaintext,
+                    aad: kind.as_str().as_bytes(),
+                },
+            )
+            .map_err(|_| FlowStateError::SealFailed)?;
+
+        let mut blob = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
+        blob.extend_from_slice(&nonce_bytes);
+        blob.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", FLOW_STATE_PREFIX, BASE64URL.encode(blob)))
+    }
+
+    /// Verify and decrypt a token of the given kind. Does not check replay.
+    pub fn open<T: DeserializeOwned>(
+        &self,
+        kind: FlowKind,
+        token: &str,
+    ) -> Result<OpenedFlowState<T>, FlowStateError> {
+        let encoded = token
+            .strip_prefix(FLOW_STATE_PREFIX)
+            .ok_or(FlowStateError::Malformed)?;
+        let blob = BASE64URL
+            .decode(encoded)
+            .map_err(|_| FlowStateError::Malformed)?;
+        if blob.len() <= 12 {
+            return Err(FlowStateError::Malformed);
+        }
+        let (nonce_bytes, ciphertext) = blob.split_at(12);
+
+        let cipher =
+            Aes256Gcm::new_from_slice(&self.key).map_err(|_| FlowStateError::InvalidSignature)?;
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(nonce_bytes),
+                Payload {
+                    msg: ciphertext,
+                    aad: kind.as_str().as_bytes(),
+                },
+            )
+            .map_err(|_| FlowStateError::InvalidSignature)?;
+
+        let envelope: Envelope<T> =
+            serde_json::from_slice(&plaintext).map_err(|_| FlowStateError::Malformed)?;
+        if envelope.exp <= chrono::Utc::now().timestamp() {
+            return Err(FlowStateError::Expired);
+        }
+
+        Ok(OpenedFlowState {
+            jti: envelope.jti,
+            expires_at: envelope.exp,
+            data: envelope.data,
+        })
+    }
+}
+
+/// HKDF-SHA256 extract-and-expand of a single 32 byte block, zero salt
+fn hkdf_sha256(ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut extract = HmacSha256::new_from_slice(&[0u8; 32]).expect("HMAC accepts any key length");
+    extract.update(ikm);
+    let prk = extract.finalize().into_bytes();
+
+    let mut expand = HmacSha256::new_from_slice(&prk).expect("HMAC accepts any key length");
+    expand.update(info);
+    expand.update(&[1u8]);
+    expand.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Step {
+        client_id: String,
+    }
+
+    fn step() -> Step {
+        Step {
+            client_id: "app".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let codec = FlowStateCodec::from_secret("secret");
+        let token = codec.seal(FlowKind::LoginChallenge, &step(), 60).unwrap();
+        assert!(token.starts_with(FLOW_STATE_PREFIX));
+        assert!(!token.contains('+') && !token.contains('/') && !token.contains('='));
+
+        let opened: OpenedFlowState<Step> = codec.open(FlowKind::LoginChallenge, &token).unwrap();
+        assert_eq!(opened.data, step());
+        assert!(opened.remaining_ttl_secs() <= 60);
+    }
+
+    #[test]
+    fn test_tokens_are_unique() {
+        let codec = FlowStateCodec::from_secret("secret");
+        let a = codec.seal(FlowKind::MfaSession, &step(), 60).unwrap();
+        let b = codec.seal(FlowKind::MfaSession, &step(), 60).unwrap();
+        assert_ne!(a, b);
+        let a: OpenedFlowState<Step> = codec.open(FlowKind::MfaSession, &a).unwrap();
+        let b: OpenedFlowState<Step> = codec.open(FlowKind::MfaSession, &b).unwrap();
+        assert_ne!(a.jti, b.jti);
+    }
+
+    #[test]
+    fn test_other_replica_with_same_secret_can_open() {
+        let token = FlowStateCodec::from_secret("shared")
+            .seal(FlowKind::LoginChallenge, &step(), 60)
+            .unwrap();
+        let opened: Result<OpenedFlowState<Step>, _> =
+            FlowStateCodec::from_secret("shared").open(FlowKind::LoginChallenge, &token);
+        assert!(opened.is_ok());
+
+        let opened: Result<OpenedFlowState<Step>, _> =
+            FlowStateCodec::from_secret("other").open(FlowKind::LoginChallenge, &token);
+        assert_eq!(opened.unwrap_err(), FlowStateError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_kind_is_bound() {
+        let codec = FlowStateCodec::from_secret("secret");
+        let token = codec.seal(FlowKind::LoginChallenge, &step(), 60).unwrap();
+        let opened: Result<OpenedFlowState<Step>, _> = codec.open(FlowKind::MfaSession, &token);
+        assert_eq!(opened.unwrap_err(), FlowStateError::InvalidSignature);
+    }
+
+    #[test]
+    fn test_expired_token_rejected() {
+        let codec = FlowStateCodec::from_secret("secret");
+        let token = codec.seal(FlowKind::LoginChallenge, &step(), 0).unwrap();
+        let opened: Result<OpenedFlowState<Step>, _> = codec.open(FlowKind::LoginChallenge, &token);
+        assert_eq!(opened.unwrap_err(), FlowStateError::Expired);
+    }
+
+    #[test]
+    fn test_tampered_or_malformed_token_rejected() {
+        let codec = FlowStateCodec::from_secret("secret");
+        let token = codec.seal(FlowKind::LoginChallenge, &step(), 60).unwrap();
+        let mut tampered = token.clone().into_bytes();
+        let last = tampered.len() - 1;
+        tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+
+        let opened: Result<OpenedFlowState<Step>, _> =
+            codec.open(FlowKind::LoginChallenge, &tampered);
+        assert!(opened.is_err());
+        let opened: Result<OpenedFlowState<Step>, _> = codec.open(
+            FlowKind::LoginChallenge,
+            "550e8400-e29b-41d4-a716-446655440000",
+        );
+        assert_eq!(opened.unwrap_err(), FlowStateError::Malformed);
+        let opened: Result<OpenedFlowState<Step>, _> = codec.open(FlowKind::LoginChallenge, "fs1.");
+        assert_eq!(opened.unwrap_err(), FlowStateError::Malformed);
+    }
+
+    #[test]
+    fn test_hkdf_matches_rfc5869_vector() {
+        // RFC 5869 A.3: zero-length salt and info, first 32 bytes of OKM
+        let okm = hkdf_sha256(&[0x0b; 22], b"");
+        assert_eq!(
+            hex::encode(okm),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d"
+        );
+    }
+
+    #[test]
+    fn test_dedicated_secret_replaces_jwt_secret() {
+        let dedicated = FlowStateConfig {
+            secret: Some("flow-secret".to_string()),
+        };
+        let token = FlowStateCodec::from_config(&dedicated, "jwt-secret")
+            .seal(FlowKind::LoginChallenge, &step(), 60)
+            .unwrap();
+
+        let opened: Result<OpenedFlowState<Step>, _> =
+            FlowStateCodec::from_config(&FlowStateConfig::default(), "jwt-secret")
+                .open(FlowKind::LoginChallenge, &token);
+        assert_eq!(opened.unwrap_err(), FlowStateError::InvalidSignature);
+        let opened: OpenedFlowState<Step> = FlowStateCodec::from_secret("flow-secret")
+            .open(FlowKind::LoginChallenge, &token)
+            .unwrap();
+        assert_eq!(opened.data, step());
+    }
+}
@@ -2,6 +2,10 @@
 
 pub mod aes;
 pub mod argon2;
+pub mod flow_state;
+pub mod tenant_keys;
 
 pub use aes::{decrypt, encrypt, EncryptionKey};
 pub use argon2::owasp_argon2;
+pub use flow_state::{FlowKind, FlowStateCodec};
+pub use tenant_keys::TenantKeyring;
@@ -0,0 +1,186 @@
+//! Per-tenant data keys for secrets stored in the database
+//!
+//! Each tenant's secrets are encrypted with a data key derived from the master
+//! key (`SETTINGS_ENCRYPTION_KEY`), the tenant ID and the tenant's key
+//! version, so a leaked data key only exposes one tenant and one version.
+//! Data keys are never stored; rotating a tenant's key bumps its version and
+//! re-encrypts its secrets.
+//!
+//! Sealed values look like `tk1:<version>:<nonce>:<ciphertext>`. Values
+//! without the prefix are legacy plaintext and are returned as-is.
+
+use super::aes::{self, EncryptionError, EncryptionKey};
+use crate::config::SettingsEncryptionConfig;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// Prefix of values sealed with a tenant data key
+const SEALED_PREFIX: &str = "tk1:";
+
+/// Version of a tenant's first data key
+pub const INITIAL_KEY_VERSION: u32 = 1;
+
+/// Derives tenant data keys from the master key
+#[derive(Clone)]
+pub struct TenantKeyring {
+    master: EncryptionKey,
+    /// Master key in use before the last master key change; only used to
+    /// open values not yet re-encrypted
+    previous_master: Option<EncryptionKey>,
+}
+
+impl TenantKeyring {
+    pub fn new(master: EncryptionKey) -> Self {
+        Self {
+            master,
+            previous_master: None,
+        }
+    }
+
+    pub fn with_previous_master(mut self, previous: EncryptionKey) -> Self {
+        self.previous_master = Some(previous);
+        self
+    }
+
+    /// Keyring from `SETTINGS_ENCRYPTION_KEY` (and
+    /// `SETTINGS_ENCRYPTION_KEY_PREVIOUS` during a master key change);
+    /// `None` when no master key is configured
+    pub fn from_config(config: &SettingsEncryptionConfig) -> Option<Self> {
+        let master = EncryptionKey::from_config(config).ok()?;
+        let keyring = Self::new(master);
+        Some(
+            match config
+                .previous_key
+                .as_deref()
+                .filter(|encoded| !encoded.is_empty())
+                .and_then(|encoded| EncryptionKey::from_base64(encoded).ok())
+            {
+                Some(previous) => keyring.with_previous_master(previous),
+                None => keyring,
+            },
+        )
+    }
+
+    /// Encrypt `plaintext` with the data key of `tenant_id` at `version`.
+    /// Platform-wide secrets (`None`) use a key of their own.
+    pub fn seal(
+        &self,
+        tenant_id: Option<Uuid>,
+        version: u32,
+        plaintext: &str,
+    ) -> Result<String, EncryptionError> {
+        let key = derive_data_key(&self.master, tenant_id, version);
+        Ok(format!(
+            "{}{}:{}",
+            SEALED_PREFIX,
+            version,
+            aes::encrypt(&key, plaintext)?
+        ))
+    }
+
+    /// Decrypt a value stored for `tenant_id`; legacy plaintext is returned
+    /// unchanged
+    pub fn open(&self, tenant_id: Option<Uuid>, stored: &str) -> Result<String, EncryptionError> {
+        let Some((version, ciphertext)) = split_sealed(stored)? else {
+            return Ok(stored.to_string());
+        };
+        let key = derive_data_key(&self.master, tenant_id, version);
+        match (aes::decrypt(&key, ciphertext), &self.previous_master) {
+            (Err(EncryptionError::DecryptionFailed), Some(previous)) => {
+                aes::decrypt(&derive_data_key(previous, tenant_id, version), ciphertext)
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+/// Key version a stored value was sealed with; `None` for plaintext
+pub fn sealed_version(stored: &str) -> Option<u32> {
+    split_sealed(stored)
+        .ok()
+        .flatten()
+        .map(|(version, _)| version)
+}
+
+fn split_sealed(stored: &str) -> Result<Option<(u32, &str)>, EncryptionError> {
+    let Some(rest) = stored.strip_prefix(SEALED_PREFIX) else {
+        return Ok(None);
+    };
+    let (version, ciphertext) = rest
+        .split_once(':')
+        .ok_or(EncryptionError::InvalidCiphertextFormat)?;
+    let version = version
+        .parse()
+        .map_err(|_| EncryptionError::InvalidCiphertextFormat)?;
+    Ok(Some((version, ciphertext)))
+}
+
+/// HMAC-SHA256 of the tenant and version under the master key
+fn derive_data_key(master: &EncryptionKey, tenant_id: Option<Uuid>, version: u32) -> EncryptionKey {
+    let scope = tenant_id.map_or_else(|| "platform".to_string(), |id| id.to_string());
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(master.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("auth9-tenant-data-key:{}:{}", scope, version).as_bytes());
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&mac.finalize().into_bytes());
+    EncryptionKey::new(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring(byte: u8) -> TenantKeyring {
+        TenantKeyring::new(EncryptionKey::new([byte; 32]))
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let keyring = keyring(7);
+        let tenant = Some(Uuid::new_v4());
+        let sealed = keyring.seal(tenant, 3, "whsec_123").unwrap();
+        assert!(sealed.starts_with("tk1:3:"));
+        assert_eq!(sealed_version(&sealed), Some(3));
+        assert_eq!(keyring.open(tenant, &sealed).unwrap(), "whsec_123");
+
+        let platform = keyring.seal(None, 1, "platform-secret").unwrap();
+        assert_eq!(keyring.open(None, &platform).unwrap(), "platform-secret");
+    }
+
+    #[test]
+    fn test_keys_are_tenant_and_version_specific() {
+        let keyring = keyring(7);
+        let tenant = Some(Uuid::new_v4());
+        let sealed = keyring.seal(tenant, 1, "whsec_123").unwrap();
+
+        // Another tenant's key cannot open it
+        assert!(keyring.open(Some(Uuid::new_v4()), &sealed).is_err());
+        assert!(keyring.open(None, &sealed).is_err());
+
+        // Neither can the tenant's key at another version
+        let relabeled = sealed.replacen("tk1:1:", "tk1:2:", 1);
+        assert!(keyring.open(tenant, &relabeled).is_err());
+    }
+
+    #[test]
+    fn test_plaintext_passes_through() {
+        let keyring = keyring(7);
+        assert_eq!(
+            keyring.open(None, "legacy-secret").unwrap(),
+            "legacy-secret"
+        );
+        assert_eq!(sealed_version("legacy-secret"), None);
+        assert!(keyring.open(None, "tk1:x:abc").is_err());
+    }
+
+    #[test]
+    fn test_previous_master_opens_old_values() {
+        let tenant = Some(Uuid::new_v4());
+        let sealed = keyring(1).seal(tenant, 1, "whsec_123").unwrap();
+
+        assert!(keyring(2).open(tenant, &sealed).is_err());
+        let rotated = keyring(2).with_previous_master(EncryptionKey::new([1; 32]));
+        assert_eq!(rotated.open(tenant, &sealed).unwrap(), "whsec_123");
+    }
+}
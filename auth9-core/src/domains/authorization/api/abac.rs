@@ -2,15 +2,19 @@
 
 use crate::domains::authorization::service::abac::AbacPolicyService;
 use crate::error::Result;
+use crate::http_support::etag::{if_match_version, with_etag};
 use crate::http_support::{MessageResponse, SuccessResponse};
 use crate::middleware::auth::AuthUser;
-use crate::models::abac::{AbacMode, AbacPolicyDocument, AbacSimulationInput};
+use crate::models::abac::{
+    AbacMode, AbacPolicyDocument, AbacPolicyVersionDetail, AbacSimulationInput,
+};
 use crate::models::common::StringUuid;
 use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
 use crate::repository::abac::AbacRepositoryImpl;
 use crate::state::{HasDbPool, HasServices};
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
     response::IntoResponse,
     Json,
 };
@@ -136,6 +140,36 @@ pub async fn create_policy<S: HasServices + HasDbPool>(
     }))))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/abac/policies/{version_id}",
+    tag = "Authorization",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("version_id" = String, Path, description = "Policy version ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "ABAC policy version with its document; `ETag` carries the draft revision", body = AbacPolicyVersionDetail),
+        (status = 404, description = "Policy version not found")
+    )
+)]
+pub async fn get_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, version_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    ensure_abac_permission(&state, &auth, tenant_id, PolicyAction::AbacRead).await?;
+    let detail = abac_service(&state)
+        .get_policy(StringUuid::from(tenant_id), StringUuid::from(version_id))
+        .await?;
+    Ok(with_etag(
+        detail.version.revision,
+        Json(SuccessResponse::new(detail)),
+    ))
+}
+
+/// Replace a draft policy document. With `If-Match` the update only applies
+/// if the draft has not been edited since it was read.
 #[utoipa::path(
     put,
     path = "/api/v1/tenants/{tenant_id}/abac/policies/{version_id}",
@@ -143,29 +177,38 @@ pub async fn create_policy<S: HasServices + HasDbPool>(
     request_body = UpdateAbacPolicyInput,
     params(
         ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
-        ("version_id" = String, Path, description = "Policy version ID (UUID)")
+        ("version_id" = String, Path, description = "Policy version ID (UUID)"),
+        ("If-Match" = Option<String>, Header, description = "Expected draft revision (ETag)")
     ),
     responses(
-        (status = 200, description = "ABAC draft policy updated")
+        (status = 200, description = "ABAC draft policy updated; `ETag` carries the new revision"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current revision")
     )
 )]
 pub async fn update_policy<S: HasServices + HasDbPool>(
     State(state): State<S>,
     auth: AuthUser,
+    headers: HeaderMap,
     Path((tenant_id, version_id)): Path<(Uuid, Uuid)>,
     Json(input): Json<UpdateAbacPolicyInput>,
 ) -> Result<impl IntoResponse> {
     ensure_abac_permission(&state, &auth, tenant_id, PolicyAction::AbacWrite).await?;
     input.policy.validate()?;
-    abac_service(&state)
+    let expected_revision = if_match_version(&headers)?;
+    let revision = abac_service(&state)
         .update_policy(
             StringUuid::from(tenant_id),
             StringUuid::from(version_id),
             input.policy,
             input.change_note,
+            expected_revision,
         )
         .await?;
-    Ok(Json(MessageResponse::new("ABAC draft policy updated")))
+    Ok(with_etag(
+        revision,
+        Json(MessageResponse::new("ABAC draft policy updated")),
+    ))
 }
 
 #[utoipa::path(
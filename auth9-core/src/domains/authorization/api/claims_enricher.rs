@@ -0,0 +1,143 @@
+//! Per-service claims enrichment plugin API handlers (platform admin)
+
+use crate::domains::identity::service::claims_enrichment::ClaimsEnrichmentService;
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, MessageResponse, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::claims_enrichment::{ServiceClaimsEnricher, UpsertClaimsEnricherInput};
+use crate::models::common::StringUuid;
+use crate::repository::claims_enricher::ClaimsEnricherRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+fn claims_enrichment_service<S: HasDbPool>(
+    state: &S,
+) -> ClaimsEnrichmentService<ClaimsEnricherRepositoryImpl> {
+    ClaimsEnrichmentService::from_pool(state.db_pool().clone())
+}
+
+/// Enrichment plugins of a service
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClaimsEnricherList {
+    /// Plugins compiled into this build
+    pub available_plugins: Vec<String>,
+    pub enrichers: Vec<ServiceClaimsEnricher>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/claims-enrichers",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "Enrichment plugins of the service", body = ClaimsEnricherList)
+    )
+)]
+/// List the claims enrichment plugins enabled for a service
+pub async fn list_claims_enrichers<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let service = claims_enrichment_service(&state);
+    let enrichers = service.list(StringUuid::from(service_id)).await?;
+    Ok(Json(SuccessResponse::new(ClaimsEnricherList {
+        available_plugins: service
+            .available_plugins()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        enrichers,
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/claims-enrichers/{plugin}",
+    tag = "Authorization",
+    params(
+        ("service_id" = String, Path, description = "Service ID (UUID)"),
+        ("plugin" = String, Path, description = "Plugin name")
+    ),
+    request_body = UpsertClaimsEnricherInput,
+    responses(
+        (status = 200, description = "Enrichment plugin configured", body = ServiceClaimsEnricher)
+    )
+)]
+/// Enable a claims enrichment plugin for a service or update its configuration
+pub async fn upsert_claims_enricher<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((service_id, plugin)): Path<(Uuid, String)>,
+    Json(input): Json<UpsertClaimsEnricherInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let enricher = claims_enrichment_service(&state)
+        .upsert(StringUuid::from(service_id), &plugin, input)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "claims_enricher.update",
+        "service",
+        Some(service_id),
+        None,
+        serde_json::to_value(&enricher).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(enricher)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/services/{service_id}/claims-enrichers/{plugin}",
+    tag = "Authorization",
+    params(
+        ("service_id" = String, Path, description = "Service ID (UUID)"),
+        ("plugin" = String, Path, description = "Plugin name")
+    ),
+    responses(
+        (status = 200, description = "Enrichment plugin removed")
+    )
+)]
+/// Remove a claims enrichment plugin from a service
+pub async fn delete_claims_enricher<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((service_id, plugin)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    claims_enrichment_service(&state)
+        .delete(StringUuid::from(service_id), &plugin)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "claims_enricher.delete",
+        "service",
+        Some(service_id),
+        Some(serde_json::json!({ "plugin": plugin })),
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new("Claims enricher removed")))
+}
@@ -0,0 +1,505 @@
+//! OAuth 2.0 Dynamic Client Registration API handlers
+//!
+//! Protocol endpoints (RFC 7591 / RFC 7592) authenticate with initial and
+//! registration access tokens rather than Auth9 JWTs and answer with
+//! RFC-shaped errors. Tenant admins manage the registration policy and the
+//! initial access tokens through the regular authenticated API.
+
+use super::service::{
+    build_oidc_client_for_update, build_oidc_client_from_create_input, merge_service_update,
+};
+use crate::domains::authorization::service::ClientRegistrationService;
+use crate::error::client_registration::ClientRegistrationError;
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::client_registration::{
+    ClientInformationResponse, ClientMetadata, ClientRegistration, ClientRegistrationPolicy,
+    CreateInitialAccessTokenInput, InitialAccessToken, InitialAccessTokenCreated,
+    UpdateClientRegistrationPolicyInput,
+};
+use crate::models::common::StringUuid;
+use crate::models::service::{CreateServiceInput, UpdateServiceInput};
+use crate::policy::{enforce, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::client_registration::ClientRegistrationRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+
+type RegistrationResult<T> = std::result::Result<T, ClientRegistrationError>;
+
+fn client_registration_service<S: HasDbPool>(
+    state: &S,
+) -> ClientRegistrationService<ClientRegistrationRepositoryImpl> {
+    ClientRegistrationService::new(Arc::new(ClientRegistrationRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+fn registration_client_uri<S: HasServices>(
+    state: &S,
+    tenant_id: StringUuid,
+    client_id: &str,
+) -> String {
+    format!(
+        "{}/api/v1/oauth/tenants/{}/register/{}",
+        state.config().jwt.issuer,
+        tenant_id,
+        client_id
+    )
+}
+
+fn client_information<S: HasServices>(
+    state: &S,
+    registration: ClientRegistration,
+    client_secret: Option<String>,
+    registration_access_token: Option<String>,
+) -> ClientInformationResponse {
+    ClientInformationResponse {
+        registration_client_uri: registration_client_uri(
+            state,
+            registration.tenant_id,
+            &registration.client_id,
+        ),
+        client_id: registration.client_id,
+        client_secret,
+        client_id_issued_at: registration.created_at.timestamp(),
+        client_secret_expires_at: 0,
+        registration_access_token,
+        metadata: registration.metadata,
+    }
+}
+
+fn require_registration_admin<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+) -> Result<()> {
+    enforce(
+        state.config(),
+        auth,
+        &PolicyInput {
+            action: PolicyAction::ServiceWrite,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+}
+
+// ============================================================================
+// Protocol endpoints (RFC 7591 / RFC 7592)
+// ============================================================================
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/oauth/tenants/{tenant_id}/register",
+    tag = "Authorization",
+    params(("tenant_id" = String, Path, description = "Tenant ID")),
+    request_body = ClientMetadata,
+    responses(
+        (status = 201, description = "Client registered", body = ClientInformationResponse),
+        (status = 400, description = "Invalid client metadata or software statement"),
+        (status = 401, description = "Invalid initial access token"),
+        (status = 403, description = "Registration disabled for the tenant")
+    )
+)]
+/// Register a client (RFC 7591)
+pub async fn register_client<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+    Json(request): Json<ClientMetadata>,
+) -> RegistrationResult<impl IntoResponse> {
+    let service = client_registration_service(&state);
+    let (policy, initial_token) = service
+        .authorize_registration(tenant_id, bearer_token(&headers))
+        .await?;
+    let metadata = service.prepare_metadata(&policy, request)?;
+    service.consume_initial_token(&initial_token).await?;
+
+    let input = CreateServiceInput {
+        tenant_id: Some(tenant_id.0),
+        name: metadata
+            .client_name
+            .clone()
+            .unwrap_or_else(|| "Registered client".to_string()),
+        client_id: uuid::Uuid::new_v4().to_string(),
+        base_url: metadata.client_uri.clone(),
+        redirect_uris: metadata.redirect_uris.clone(),
+        logout_uris: Some(metadata.post_logout_redirect_uris.clone()),
+        role_preset: None,
+        role_preset_version: None,
+        catalog: Default::default(),
+    };
+    let client_uuid = state
+        .identity_engine()
+        .client_store()
+        .create_oidc_client(&build_oidc_client_from_create_input(&input))
+        .await?;
+    let client_secret = state
+        .identity_engine()
+        .client_store()
+        .get_client_secret(&client_uuid)
+        .await?;
+    let created = state
+        .client_service()
+        .create_with_secret(input, client_secret)
+        .await?;
+
+    let (registration, registration_access_token) = service
+        .record_registration(
+            tenant_id,
+            created.service.id,
+            &created.client.client.client_id,
+            initial_token.id,
+            metadata,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.create",
+        "service",
+        Some(created.service.id.0),
+        None,
+        serde_json::to_value(&registration).ok(),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(client_information(
+            &state,
+            registration,
+            Some(created.client.client_secret),
+            Some(registration_access_token),
+        )),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/oauth/tenants/{tenant_id}/register/{client_id}",
+    tag = "Authorization",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("client_id" = String, Path, description = "Registered client ID")
+    ),
+    responses(
+        (status = 200, description = "Client information", body = ClientInformationResponse),
+        (status = 401, description = "Invalid registration access token")
+    )
+)]
+/// Read a registered client's configuration (RFC 7592)
+pub async fn get_registered_client<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path((tenant_id, client_id)): Path<(StringUuid, String)>,
+) -> RegistrationResult<impl IntoResponse> {
+    let registration = client_registration_service(&state)
+        .authenticate_client(tenant_id, &client_id, bearer_token(&headers))
+        .await?;
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(client_information(&state, registration, None, None)),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/oauth/tenants/{tenant_id}/register/{client_id}",
+    tag = "Authorization",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("client_id" = String, Path, description = "Registered client ID")
+    ),
+    request_body = ClientMetadata,
+    responses(
+        (status = 200, description = "Client updated", body = ClientInformationResponse),
+        (status = 400, description = "Invalid client metadata"),
+        (status = 401, description = "Invalid registration access token")
+    )
+)]
+/// Replace a registered client's metadata (RFC 7592)
+pub async fn update_registered_client<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path((tenant_id, client_id)): Path<(StringUuid, String)>,
+    Json(request): Json<ClientMetadata>,
+) -> RegistrationResult<impl IntoResponse> {
+    let service = client_registration_service(&state);
+    let mut registration = service
+        .authenticate_client(tenant_id, &client_id, bearer_token(&headers))
+        .await?;
+    let policy = service.get_policy(tenant_id).await?;
+    let metadata = service.prepare_metadata(&policy, request)?;
+
+    let service_id = registration.service_id.0;
+    let before = state.client_service().get(service_id).await?;
+    let input = UpdateServiceInput {
+        name: metadata.client_name.clone(),
+        base_url: metadata.client_uri.clone(),
+        redirect_uris: Some(metadata.redirect_uris.clone()),
+        logout_uris: Some(metadata.post_logout_redirect_uris.clone()),
+        status: None,
+        catalog: None,
+    };
+    let merged = merge_service_update(&before, &input);
+    if let Ok(kc_uuid) = state
+        .identity_engine()
+        .client_store()
+        .get_client_uuid_by_client_id(&client_id)
+        .await
+    {
+        let _ = state
+            .identity_engine()
+            .client_store()
+            .update_oidc_client(&kc_uuid, &build_oidc_client_for_update(&client_id, &merged))
+            .await;
+    }
+    state
+        .client_service()
+        .update(service_id, input, None)
+        .await?;
+    service.update_metadata(&registration, &metadata).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.update",
+        "service",
+        Some(service_id),
+        serde_json::to_value(&registration.metadata).ok(),
+        serde_json::to_value(&metadata).ok(),
+    )
+    .await;
+
+    registration.software_id = metadata.software_id.clone();
+    registration.metadata = metadata;
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(client_information(&state, registration, None, None)),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/oauth/tenants/{tenant_id}/register/{client_id}",
+    tag = "Authorization",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("client_id" = String, Path, description = "Registered client ID")
+    ),
+    responses(
+        (status = 204, description = "Client deleted"),
+        (status = 401, description = "Invalid registration access token")
+    )
+)]
+/// Delete a registered client (RFC 7592)
+pub async fn delete_registered_client<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path((tenant_id, client_id)): Path<(StringUuid, String)>,
+) -> RegistrationResult<StatusCode> {
+    let service = client_registration_service(&state);
+    let registration = service
+        .authenticate_client(tenant_id, &client_id, bearer_token(&headers))
+        .await?;
+
+    if let Ok(kc_uuid) = state
+        .identity_engine()
+        .client_store()
+        .get_client_uuid_by_client_id(&client_id)
+        .await
+    {
+        let _ = state
+            .identity_engine()
+            .client_store()
+            .delete_oidc_client(&kc_uuid)
+            .await;
+    }
+    state
+        .client_service()
+        .delete(registration.service_id.0)
+        .await?;
+    service.delete_registration(&registration).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.delete",
+        "service",
+        Some(registration.service_id.0),
+        serde_json::to_value(&registration).ok(),
+        None,
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Tenant administration
+// ============================================================================
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/client-registration/policy",
+    tag = "Authorization",
+    params(("tenant_id" = String, Path, description = "Tenant ID")),
+    responses(
+        (status = 200, description = "Registration policy", body = ClientRegistrationPolicy)
+    )
+)]
+pub async fn get_registration_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<StringUuid>,
+) -> Result<Json<SuccessResponse<ClientRegistrationPolicy>>> {
+    require_registration_admin(&state, &auth, tenant_id)?;
+    let policy = client_registration_service(&state)
+        .get_policy(tenant_id)
+        .await?;
+    Ok(Json(SuccessResponse::new(policy)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/client-registration/policy",
+    tag = "Authorization",
+    params(("tenant_id" = String, Path, description = "Tenant ID")),
+    request_body = UpdateClientRegistrationPolicyInput,
+    responses(
+        (status = 200, description = "Registration policy updated", body = ClientRegistrationPolicy)
+    )
+)]
+pub async fn update_registration_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+    Json(input): Json<UpdateClientRegistrationPolicyInput>,
+) -> Result<Json<SuccessResponse<ClientRegistrationPolicy>>> {
+    require_registration_admin(&state, &auth, tenant_id)?;
+    let service = client_registration_service(&state);
+    let before = service.get_policy(tenant_id).await?;
+    let policy = service.set_policy(tenant_id, input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.policy.update",
+        "tenant",
+        Some(tenant_id.0),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&policy).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(policy)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/client-registration/initial-access-tokens",
+    tag = "Authorization",
+    params(("tenant_id" = String, Path, description = "Tenant ID")),
+    responses(
+        (status = 200, description = "Initial access tokens", body = Vec<InitialAccessToken>)
+    )
+)]
+pub async fn list_initial_access_tokens<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<StringUuid>,
+) -> Result<Json<SuccessResponse<Vec<InitialAccessToken>>>> {
+    require_registration_admin(&state, &auth, tenant_id)?;
+    let tokens = client_registration_service(&state)
+        .list_initial_tokens(tenant_id)
+        .await?;
+    Ok(Json(SuccessResponse::new(tokens)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/client-registration/initial-access-tokens",
+    tag = "Authorization",
+    params(("tenant_id" = String, Path, description = "Tenant ID")),
+    request_body = CreateInitialAccessTokenInput,
+    responses(
+        (status = 201, description = "Initial access token issued (shown once)", body = InitialAccessTokenCreated)
+    )
+)]
+pub async fn create_initial_access_token<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+    Json(input): Json<CreateInitialAccessTokenInput>,
+) -> Result<impl IntoResponse> {
+    require_registration_admin(&state, &auth, tenant_id)?;
+    let created = client_registration_service(&state)
+        .issue_initial_token(tenant_id, input, Some(StringUuid::from(auth.user_id)))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.initial_token.create",
+        "tenant",
+        Some(tenant_id.0),
+        None,
+        serde_json::to_value(&created.token).ok(),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(created))))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/client-registration/initial-access-tokens/{token_id}",
+    tag = "Authorization",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("token_id" = String, Path, description = "Initial access token ID")
+    ),
+    responses(
+        (status = 200, description = "Initial access token revoked")
+    )
+)]
+pub async fn revoke_initial_access_token<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, token_id)): Path<(StringUuid, StringUuid)>,
+) -> Result<Json<MessageResponse>> {
+    require_registration_admin(&state, &auth, tenant_id)?;
+    client_registration_service(&state)
+        .revoke_initial_token(tenant_id, token_id)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "client_registration.initial_token.revoke",
+        "tenant",
+        Some(tenant_id.0),
+        None,
+        Some(serde_json::json!({ "token_id": token_id })),
+    )
+    .await;
+    Ok(Json(MessageResponse::new("Initial access token revoked")))
+}
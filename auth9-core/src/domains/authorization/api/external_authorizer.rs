@@ -0,0 +1,115 @@
+//! Per-service external authorizer (OPA/OpenFGA) API handlers (platform admin)
+
+use crate::domains::authorization::service::ExternalAuthzService;
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, MessageResponse, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::external_authz::{ServiceExternalAuthorizer, UpsertExternalAuthorizerInput};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/external-authorizer",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "External authorizer of the service", body = ServiceExternalAuthorizer),
+        (status = 404, description = "No external authorizer configured")
+    )
+)]
+/// Get the external authorizer permission checks of a service are delegated to
+pub async fn get_external_authorizer<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let authorizer = ExternalAuthzService::from_pool(state.db_pool().clone())
+        .get(StringUuid::from(service_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(authorizer)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/external-authorizer",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    request_body = UpsertExternalAuthorizerInput,
+    responses(
+        (status = 200, description = "External authorizer configured", body = ServiceExternalAuthorizer)
+    )
+)]
+/// Delegate the permission checks of a service to an OPA or OpenFGA authorizer
+pub async fn upsert_external_authorizer<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(service_id): Path<Uuid>,
+    Json(input): Json<UpsertExternalAuthorizerInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let authorizer = ExternalAuthzService::from_pool(state.db_pool().clone())
+        .upsert(StringUuid::from(service_id), input)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "external_authorizer.update",
+        "service",
+        Some(service_id),
+        None,
+        serde_json::to_value(&authorizer).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(authorizer)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/services/{service_id}/external-authorizer",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "External authorizer removed; built-in RBAC decides again")
+    )
+)]
+/// Stop delegating the permission checks of a service
+pub async fn delete_external_authorizer<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    ExternalAuthzService::from_pool(state.db_pool().clone())
+        .delete(StringUuid::from(service_id))
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "external_authorizer.delete",
+        "service",
+        Some(service_id),
+        None,
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new("External authorizer removed")))
+}
@@ -1,6 +1,12 @@
 //! Authorization domain API facade.
 
 pub mod abac;
+pub mod claims_enricher;
+pub mod client_registration;
+pub mod external_authorizer;
 pub mod role;
+pub mod role_preset;
+pub mod scope;
 pub mod service;
 pub mod tenant_service;
+pub mod token_validation;
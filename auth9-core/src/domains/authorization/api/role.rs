@@ -1,31 +1,40 @@
 //! Role and permission API handlers
 
+use crate::domains::authorization::service::ExternalAuthzService;
 use crate::error::{AppError, Result};
+use crate::http_support::etag::{if_match_version, with_etag};
 use crate::http_support::{
     extract_actor_id_generic, require_platform_admin_with_db, write_audit_log_generic,
     MessageResponse, SuccessResponse,
 };
 use crate::middleware::auth::AuthUser;
-use crate::models::common::StringUuid;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
+use crate::models::external_authz::ExternalAuthzRequest;
+use crate::models::list_query::ListGrammarUnsupported;
 use crate::models::rbac::{
-    AssignRolesInput, CreatePermissionInput, CreateRoleInput, UpdateRoleInput,
+    AssignRolesInput, CheckPermissionInput, CreatePermissionInput, CreateRoleInput,
+    PermissionCheckResult, PermissionNamespace, RoleTree, SetPermissionNamespaceInput,
+    UpdateRoleInput,
 };
-use crate::policy::{enforce, enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
-use crate::state::HasServices;
+use crate::policy::{
+    enforce, enforce_admin_unit_boundary, enforce_with_state, AuthzContext, PolicyAction,
+    PolicyInput, ResourceScope,
+};
+use crate::state::{HasDbPool, HasServices};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 /// Check if user can read RBAC resources within a service's tenant
 /// Platform admin can always read, tenant users can read their own tenant's data
-fn require_rbac_read_access<S: HasServices>(
+pub(super) fn require_rbac_read_access<S: HasServices>(
     state: &S,
     auth: &AuthUser,
     service_tenant_id: Option<Uuid>,
@@ -52,7 +61,7 @@ fn require_rbac_read_access<S: HasServices>(
 
 /// Check if user can manage RBAC within a tenant
 /// Platform admin can always manage, tenant owner can manage their tenant
-async fn require_rbac_management_permission<S: HasServices>(
+pub(super) async fn require_rbac_management_permission<S: HasServices>(
     state: &S,
     auth: &AuthUser,
     tenant_id: Uuid,
@@ -92,6 +101,73 @@ pub async fn list_permissions<S: HasServices>(
     Ok(Json(SuccessResponse::new(permissions)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/permission-namespace",
+    tag = "Authorization",
+    responses(
+        (status = 200, description = "Permission namespace of the service", body = PermissionNamespace)
+    )
+)]
+/// Get the namespace prefixed to a service's permission codes
+pub async fn get_permission_namespace<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_rbac_read_access(&state, &auth, service.tenant_id.as_ref().map(|t| t.0))?;
+
+    let namespace = state
+        .rbac_service()
+        .get_permission_namespace(StringUuid::from(service_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(namespace)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/permission-namespace",
+    tag = "Authorization",
+    request_body = SetPermissionNamespaceInput,
+    responses(
+        (status = 200, description = "Permission namespace updated", body = PermissionNamespace)
+    )
+)]
+/// Set the namespace prefixed to new permission codes of a service
+/// Requires platform admin, like permission creation
+pub async fn set_permission_namespace<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(service_id): Path<Uuid>,
+    Json(input): Json<SetPermissionNamespaceInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let service_id = StringUuid::from(service_id);
+    let before = state
+        .rbac_service()
+        .get_permission_namespace(service_id)
+        .await?;
+    let namespace = state
+        .rbac_service()
+        .set_permission_namespace(service_id, input)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "permission_namespace.update",
+        "service",
+        Some(*service_id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&namespace).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(namespace)))
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/permissions",
@@ -178,13 +254,15 @@ pub async fn delete_permission<S: HasServices>(
     path = "/api/v1/services/{service_id}/roles",
     tag = "Authorization",
     responses(
-        (status = 200, description = "List of roles")
+        (status = 200, description = "List of roles"),
+        (status = 400, description = "filter, sort or fields passed")
     )
 )]
 /// List roles for a service
 pub async fn list_roles<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
+    _: ListGrammarUnsupported,
     Path(service_id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     let service = state.client_service().get(service_id).await?;
@@ -195,6 +273,30 @@ pub async fn list_roles<S: HasServices>(
     Ok(Json(SuccessResponse::new(roles)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/roles/tree",
+    tag = "Authorization",
+    responses(
+        (status = 200, description = "Role hierarchy with effective permissions", body = RoleTree)
+    )
+)]
+/// Get the role hierarchy of a service
+pub async fn get_role_tree<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_rbac_read_access(&state, &auth, service.tenant_id.as_ref().map(|t| t.0))?;
+
+    let tree = state
+        .rbac_service()
+        .get_role_tree(StringUuid::from(service_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(tree)))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/roles/{id}",
@@ -215,7 +317,10 @@ pub async fn get_role<S: HasServices>(
     let service = state.client_service().get(*role.role.service_id).await?;
     require_rbac_read_access(&state, &auth, service.tenant_id.as_ref().map(|t| t.0))?;
 
-    Ok(Json(SuccessResponse::new(role)))
+    Ok(with_etag(
+        role.role.version,
+        Json(SuccessResponse::new(role)),
+    ))
 }
 
 #[utoipa::path(
@@ -264,8 +369,11 @@ pub async fn create_role<S: HasServices>(
     put,
     path = "/api/v1/roles/{id}",
     tag = "Authorization",
+    params(("If-Match" = Option<String>, Header, description = "Expected role version (ETag)")),
     responses(
-        (status = 200, description = "Role updated")
+        (status = 200, description = "Role updated"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
 /// Update role
@@ -281,8 +389,12 @@ pub async fn update_role<S: HasServices>(
     require_platform_admin_with_db(&state, &auth).await?;
 
     let id = StringUuid::from(id);
+    let expected_version = if_match_version(&headers)?;
     let before = state.rbac_service().get_role(id).await?;
-    let role = state.rbac_service().update_role(id, input).await?;
+    let role = state
+        .rbac_service()
+        .update_role(id, input, expected_version)
+        .await?;
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -293,7 +405,7 @@ pub async fn update_role<S: HasServices>(
         serde_json::to_value(&role).ok(),
     )
     .await;
-    Ok(Json(SuccessResponse::new(role)))
+    Ok(with_etag(role.version, Json(SuccessResponse::new(role))))
 }
 
 #[utoipa::path(
@@ -429,18 +541,26 @@ pub async fn remove_permission<S: HasServices>(
 /// Requires platform admin or tenant owner to assign roles
 pub async fn assign_roles<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Json(input): Json<AssignRolesInput>,
 ) -> Result<impl IntoResponse> {
+    let auth = authz.auth();
     // Check authorization: require platform admin or tenant owner
-    require_rbac_management_permission(&state, &auth, input.tenant_id).await?;
+    require_rbac_management_permission(&state, auth, input.tenant_id).await?;
+    enforce_admin_unit_boundary(
+        &state,
+        &authz,
+        StringUuid::from(input.tenant_id),
+        StringUuid::from(input.user_id),
+    )
+    .await?;
 
     // Prevent self-assignment for non-platform-admin users
     if auth.user_id == input.user_id {
         enforce_with_state(
             &state,
-            &auth,
+            auth,
             &PolicyInput {
                 action: PolicyAction::RbacAssignSelf,
                 scope: ResourceScope::Global,
@@ -449,7 +569,28 @@ pub async fn assign_roles<S: HasServices>(
         .await?;
     }
 
-    // Validate that all role_ids belong to services within the target tenant
+    validate_roles_in_tenant(&state, &input).await?;
+
+    let granted_by = extract_actor_id_generic(&state, &headers).map(StringUuid::from);
+    state.rbac_service().assign_roles(input, granted_by).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "rbac.assign_roles",
+        "user_roles",
+        None,
+        None,
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new("Roles assigned successfully")))
+}
+
+/// Validate that all role_ids belong to services within the target tenant
+pub(crate) async fn validate_roles_in_tenant<S: HasServices>(
+    state: &S,
+    input: &AssignRolesInput,
+) -> Result<()> {
     let target_tenant = StringUuid::from(input.tenant_id);
     for role_id in &input.role_ids {
         let role = state
@@ -466,20 +607,7 @@ pub async fn assign_roles<S: HasServices>(
             }
         }
     }
-
-    let granted_by = extract_actor_id_generic(&state, &headers).map(StringUuid::from);
-    state.rbac_service().assign_roles(input, granted_by).await?;
-    let _ = write_audit_log_generic(
-        &state,
-        &headers,
-        "rbac.assign_roles",
-        "user_roles",
-        None,
-        None,
-        None,
-    )
-    .await;
-    Ok(Json(MessageResponse::new("Roles assigned successfully")))
+    Ok(())
 }
 
 #[utoipa::path(
@@ -494,12 +622,10 @@ pub async fn assign_roles<S: HasServices>(
 pub async fn get_user_roles<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
-    Path((user_id, tenant_id)): Path<(Uuid, Uuid)>,
+    Path((user_id, tenant_id)): Path<(UserId, TenantId)>,
 ) -> Result<impl IntoResponse> {
-    require_rbac_management_permission(&state, &auth, tenant_id).await?;
+    require_rbac_management_permission(&state, &auth, *tenant_id).await?;
 
-    let user_id = StringUuid::from(user_id);
-    let tenant_id = StringUuid::from(tenant_id);
     let roles = state
         .rbac_service()
         .get_user_roles(user_id, tenant_id)
@@ -507,6 +633,55 @@ pub async fn get_user_roles<S: HasServices>(
     Ok(Json(SuccessResponse::new(roles)))
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CheckPermissionQuery {
+    /// Return the evaluation trace: which roles were evaluated and which
+    /// granted the permission (default false)
+    #[serde(default)]
+    pub explain: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/tenants/{tenant_id}/permissions/check",
+    tag = "Authorization",
+    params(CheckPermissionQuery),
+    request_body = CheckPermissionInput,
+    responses(
+        (status = 200, description = "Permission decision", body = PermissionCheckResult)
+    )
+)]
+/// Check whether a user holds a permission in tenant
+pub async fn check_user_permission<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((user_id, tenant_id)): Path<(UserId, TenantId)>,
+    Query(query): Query<CheckPermissionQuery>,
+    Json(input): Json<CheckPermissionInput>,
+) -> Result<impl IntoResponse> {
+    require_rbac_management_permission(&state, &auth, *tenant_id).await?;
+
+    let mut result = state
+        .rbac_service()
+        .check_permission(user_id, tenant_id, &input, query.explain)
+        .await?;
+    // Services with an external authorizer get its decision on top of RBAC
+    if let Some(service_id) = input.service_id {
+        let request = ExternalAuthzRequest {
+            user_id,
+            tenant_id,
+            service_id: ServiceId::from(service_id),
+            permission: input.permission.clone(),
+        };
+        let (allowed, external) = ExternalAuthzService::from_pool(state.db_pool().clone())
+            .decide(&request, result.allowed)
+            .await?;
+        result.allowed = allowed;
+        result.external = external;
+    }
+    Ok(Json(SuccessResponse::new(result)))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/users/{user_id}/tenants/{tenant_id}/assigned-roles",
@@ -519,12 +694,10 @@ pub async fn get_user_roles<S: HasServices>(
 pub async fn get_user_assigned_roles<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
-    Path((user_id, tenant_id)): Path<(Uuid, Uuid)>,
+    Path((user_id, tenant_id)): Path<(UserId, TenantId)>,
 ) -> Result<impl IntoResponse> {
-    require_rbac_management_permission(&state, &auth, tenant_id).await?;
+    require_rbac_management_permission(&state, &auth, *tenant_id).await?;
 
-    let user_id = StringUuid::from(user_id);
-    let tenant_id = StringUuid::from(tenant_id);
     let roles = state
         .rbac_service()
         .get_user_role_records(user_id, tenant_id)
@@ -544,15 +717,21 @@ pub async fn get_user_assigned_roles<S: HasServices>(
 /// Requires platform admin or tenant owner to unassign roles
 pub async fn unassign_role<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
-    Path((user_id, tenant_id, role_id)): Path<(Uuid, Uuid, Uuid)>,
+    Path((user_id, tenant_id, role_id)): Path<(UserId, TenantId, Uuid)>,
 ) -> Result<impl IntoResponse> {
+    let auth = authz.auth();
     // Check authorization: require platform admin or tenant owner
-    require_rbac_management_permission(&state, &auth, tenant_id).await?;
+    require_rbac_management_permission(&state, auth, *tenant_id).await?;
+    enforce_admin_unit_boundary(
+        &state,
+        &authz,
+        StringUuid::from(*tenant_id),
+        StringUuid::from(*user_id),
+    )
+    .await?;
 
-    let user_id = StringUuid::from(user_id);
-    let tenant_id = StringUuid::from(tenant_id);
     let role_id = StringUuid::from(role_id);
     state
         .rbac_service()
@@ -0,0 +1,197 @@
+//! Role preset template API handlers
+
+use crate::domains::authorization::service::RolePresetService;
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, MessageResponse, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::role_preset::{CreateRolePresetInput, RolePreset, UpdateRolePresetInput};
+use crate::repository::role_preset::RolePresetRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+fn role_preset_service<S: HasDbPool>(state: &S) -> RolePresetService<RolePresetRepositoryImpl> {
+    RolePresetService::from_pool(state.db_pool().clone())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RolePresetVersionQuery {
+    /// Preset version (latest when omitted)
+    pub version: Option<i32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/role-presets",
+    tag = "Authorization",
+    responses(
+        (status = 200, description = "Latest version of every role preset", body = Vec<RolePreset>)
+    )
+)]
+/// List role presets that can be applied when registering a service
+pub async fn list_role_presets<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    _auth: AuthUser,
+) -> Result<impl IntoResponse> {
+    let presets = role_preset_service(&state).list().await?;
+    Ok(Json(SuccessResponse::new(presets)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/role-presets/{key}",
+    tag = "Authorization",
+    params(("key" = String, Path, description = "Preset key"), RolePresetVersionQuery),
+    responses(
+        (status = 200, description = "Role preset", body = RolePreset),
+        (status = 404, description = "Preset or version not found")
+    )
+)]
+/// Get a role preset, at its latest or a given version
+pub async fn get_role_preset<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    _auth: AuthUser,
+    Path(key): Path<String>,
+    Query(query): Query<RolePresetVersionQuery>,
+) -> Result<impl IntoResponse> {
+    let preset = role_preset_service(&state).get(&key, query.version).await?;
+    Ok(Json(SuccessResponse::new(preset)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/role-presets/{key}/versions",
+    tag = "Authorization",
+    params(("key" = String, Path, description = "Preset key")),
+    responses(
+        (status = 200, description = "All versions, newest first", body = Vec<RolePreset>)
+    )
+)]
+/// List the versions of a role preset
+pub async fn list_role_preset_versions<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    _auth: AuthUser,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse> {
+    let versions = role_preset_service(&state).versions(&key).await?;
+    Ok(Json(SuccessResponse::new(versions)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/role-presets",
+    tag = "Authorization",
+    request_body = CreateRolePresetInput,
+    responses(
+        (status = 201, description = "Role preset created as version 1", body = RolePreset),
+        (status = 409, description = "Preset key already exists")
+    )
+)]
+/// Create a role preset (platform admin)
+pub async fn create_role_preset<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CreateRolePresetInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+
+    let preset = role_preset_service(&state)
+        .create(input, Some(StringUuid::from(auth.user_id)))
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "role_preset.create",
+        "role_preset",
+        Some(preset.id.0),
+        None,
+        serde_json::to_value(&preset).ok(),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(preset))))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/role-presets/{key}",
+    tag = "Authorization",
+    params(("key" = String, Path, description = "Preset key")),
+    request_body = UpdateRolePresetInput,
+    responses(
+        (status = 200, description = "New preset version published", body = RolePreset),
+        (status = 404, description = "Preset not found")
+    )
+)]
+/// Publish a new version of a role preset (platform admin). Services already
+/// created from earlier versions are not changed.
+pub async fn update_role_preset<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Json(input): Json<UpdateRolePresetInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+
+    let service = role_preset_service(&state);
+    let before = service.get(&key, None).await?;
+    let preset = service
+        .update(&key, input, Some(StringUuid::from(auth.user_id)))
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "role_preset.update",
+        "role_preset",
+        Some(preset.id.0),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&preset).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(preset)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/role-presets/{key}",
+    tag = "Authorization",
+    params(("key" = String, Path, description = "Preset key")),
+    responses(
+        (status = 200, description = "Preset and all its versions deleted; existing roles are kept"),
+        (status = 404, description = "Preset not found")
+    )
+)]
+/// Delete a role preset (platform admin)
+pub async fn delete_role_preset<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+
+    let service = role_preset_service(&state);
+    let before = service.get(&key, None).await?;
+    service.delete(&key).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "role_preset.delete",
+        "role_preset",
+        Some(before.id.0),
+        serde_json::to_value(&before).ok(),
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new("Role preset deleted")))
+}
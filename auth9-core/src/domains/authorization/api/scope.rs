@@ -0,0 +1,136 @@
+//! Service OAuth scope catalog API handlers
+
+use super::role::{require_rbac_management_permission, require_rbac_read_access};
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, MessageResponse, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::{ServiceScope, UpsertServiceScopeInput};
+use crate::models::service::Service;
+use crate::state::HasServices;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+/// Tenant services are managed by tenant RBAC admins, global services by platform admins
+async fn require_scope_management<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    service: &Service,
+) -> Result<()> {
+    match service.tenant_id {
+        Some(tenant_id) => require_rbac_management_permission(state, auth, *tenant_id).await,
+        None => require_platform_admin_with_db(state, auth).await,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/scopes",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "Scopes declared by the service", body = Vec<ServiceScope>)
+    )
+)]
+/// List the OAuth scopes a service declares
+pub async fn list_scopes<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_rbac_read_access(&state, &auth, service.tenant_id.as_ref().map(|t| t.0))?;
+
+    let scopes = state
+        .scope_catalog_service()
+        .list(StringUuid::from(service_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(scopes)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/scopes/{name}",
+    tag = "Authorization",
+    params(
+        ("service_id" = String, Path, description = "Service ID (UUID)"),
+        ("name" = String, Path, description = "Scope name")
+    ),
+    request_body = UpsertServiceScopeInput,
+    responses(
+        (status = 200, description = "Scope declared", body = ServiceScope)
+    )
+)]
+/// Declare a scope for a service or update its declaration
+pub async fn upsert_scope<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((service_id, name)): Path<(Uuid, String)>,
+    Json(input): Json<UpsertServiceScopeInput>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_scope_management(&state, &auth, &service).await?;
+
+    let scope = state
+        .scope_catalog_service()
+        .upsert(StringUuid::from(service_id), &name, input)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "service_scope.update",
+        "service",
+        Some(service_id),
+        None,
+        serde_json::to_value(&scope).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(scope)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/services/{service_id}/scopes/{name}",
+    tag = "Authorization",
+    params(
+        ("service_id" = String, Path, description = "Service ID (UUID)"),
+        ("name" = String, Path, description = "Scope name")
+    ),
+    responses(
+        (status = 200, description = "Scope removed")
+    )
+)]
+/// Remove a scope from a service's catalog
+pub async fn delete_scope<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((service_id, name)): Path<(Uuid, String)>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_scope_management(&state, &auth, &service).await?;
+
+    state
+        .scope_catalog_service()
+        .delete(StringUuid::from(service_id), &name)
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "service_scope.delete",
+        "service",
+        Some(service_id),
+        Some(serde_json::json!({ "scope": name })),
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new("Scope removed")))
+}
@@ -1,24 +1,31 @@
 //! Service/Client API handlers
 
+use super::role::require_rbac_read_access;
 use crate::config::Config;
+use crate::domains::authorization::service::sagas::run_service_delete;
+use crate::domains::authorization::service::RolePresetService;
 use crate::error::{AppError, Result};
+use crate::http_support::etag::{if_match_version, with_etag};
 use crate::http_support::{
-    deserialize_page, deserialize_per_page, extract_actor_id_generic, extract_ip,
-    write_audit_log_generic, MessageResponse, PaginatedResponse, SuccessResponse,
+    extract_actor_id_generic, extract_ip, write_audit_log_generic, MessageResponse,
+    PaginatedResponse, SuccessResponse,
 };
 use crate::identity_engine::OidcClientRepresentation;
 use crate::middleware::auth::AuthUser;
-use crate::models::common::StringUuid;
-use crate::models::service::{
-    CreateClientInput, CreateServiceInput, Service, ServiceStatus, UpdateServiceInput,
+use crate::models::common::{StringUuid, TokenTtlOverrides};
+use crate::models::expand::{
+    expanded, expanded_list, expansion_forbidden, ExpandParams, ExpandQuery, MAX_EXPANDED_ITEMS,
 };
-use crate::policy::{
-    enforce, enforce_with_state, is_platform_admin_with_db, PolicyAction, PolicyInput,
-    ResourceScope,
+use crate::models::list_query::{ListGrammarUnsupported, ListQuery, ListQueryParams};
+use crate::models::service::{
+    Client, CreateClientInput, CreateServiceInput, Service, ServiceCatalog, ServiceStatus,
+    UpdateServiceInput,
 };
+use crate::models::user::TenantInfo;
+use crate::policy::{enforce, AuthzContext, PolicyAction, PolicyInput, ResourceScope};
 use crate::repository::audit::CreateAuditLogInput;
 use crate::repository::AuditRepository;
-use crate::state::HasServices;
+use crate::state::{HasDbPool, HasServices};
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
@@ -29,7 +36,7 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
@@ -137,7 +144,7 @@ pub fn build_oidc_client_for_update(
 /// Check if the authenticated user can manage a service.
 /// - Platform admin (any token type with platform admin email): can manage any service
 /// - TenantAccess tokens: can only manage services in their own tenant
-fn require_service_access(
+pub(crate) fn require_service_access(
     config: &Config,
     auth: &AuthUser,
     service_tenant_id: Option<Uuid>,
@@ -166,33 +173,21 @@ fn require_service_access(
 // API Handlers
 // ============================================================================
 
+/// Endpoint-specific parameters of the service list; paging, search,
+/// filters and sorting come from [`ListQuery`]
 #[derive(Debug, Deserialize)]
 pub struct ListServicesQuery {
-    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
-    pub page: i64,
-    #[serde(
-        default = "default_per_page",
-        deserialize_with = "deserialize_per_page",
-        alias = "limit"
-    )]
-    pub per_page: i64,
     pub tenant_id: Option<Uuid>,
 }
 
-fn default_page() -> i64 {
-    1
-}
-
-fn default_per_page() -> i64 {
-    20
-}
-
 #[utoipa::path(
     get,
     path = "/api/v1/services",
     tag = "Authorization",
+    params(ListQueryParams),
     responses(
-        (status = 200, description = "List of services")
+        (status = 200, description = "List of services"),
+        (status = 400, description = "Filter or sort field not allowed")
     )
 )]
 /// List services
@@ -200,25 +195,27 @@ fn default_per_page() -> i64 {
 /// - Tenant user (TenantAccess token): can only list services in their tenant
 pub async fn list<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
-    Query(query): Query<ListServicesQuery>,
+    Query(scope): Query<ListServicesQuery>,
+    query: ListQuery,
 ) -> Result<impl IntoResponse> {
-    let tenant_filter = if let Some(requested_tenant) = query.tenant_id {
-        let auth_result = enforce_with_state(
-            &state,
-            &auth,
-            &PolicyInput {
-                action: PolicyAction::ServiceList,
-                scope: ResourceScope::Tenant(StringUuid::from(requested_tenant)),
-            },
-        )
-        .await;
+    let auth = authz.auth();
+    let tenant_filter = if let Some(requested_tenant) = scope.tenant_id {
+        let auth_result = authz
+            .enforce(
+                &state,
+                &PolicyInput {
+                    action: PolicyAction::ServiceList,
+                    scope: ResourceScope::Tenant(StringUuid::from(requested_tenant)),
+                },
+            )
+            .await;
         if auth_result.is_err() {
             let _ = log_access_denied(
                 &state,
                 &headers,
-                &auth,
+                auth,
                 "service.list",
                 "Cannot list services in another tenant",
             )
@@ -226,26 +223,26 @@ pub async fn list<S: HasServices>(
         }
         auth_result?;
         Some(requested_tenant)
-    } else if is_platform_admin_with_db(&state, &auth).await {
+    } else if authz.is_platform_admin(&state).await {
         None
     } else {
         let token_tenant = auth
             .tenant_id
             .ok_or_else(|| AppError::Forbidden("No tenant context in token".to_string()))?;
-        let auth_result = enforce_with_state(
-            &state,
-            &auth,
-            &PolicyInput {
-                action: PolicyAction::ServiceList,
-                scope: ResourceScope::Tenant(StringUuid::from(token_tenant)),
-            },
-        )
-        .await;
+        let auth_result = authz
+            .enforce(
+                &state,
+                &PolicyInput {
+                    action: PolicyAction::ServiceList,
+                    scope: ResourceScope::Tenant(StringUuid::from(token_tenant)),
+                },
+            )
+            .await;
         if auth_result.is_err() {
             let _ = log_access_denied(
                 &state,
                 &headers,
-                &auth,
+                auth,
                 "service.list",
                 "Platform admin or tenant-scoped token required",
             )
@@ -257,23 +254,84 @@ pub async fn list<S: HasServices>(
 
     let (services, total) = state
         .client_service()
-        .list(tenant_filter, query.page, query.per_page)
+        .list_filtered(tenant_filter, &query)
         .await?;
 
     Ok(Json(PaginatedResponse::new(
-        services,
+        query.select_fields(services)?,
         query.page,
         query.per_page,
         total,
     )))
 }
 
+/// Paths `GET /api/v1/services/{id}` can expand
+pub(crate) const SERVICE_EXPANSIONS: &[&str] =
+    &["clients", "permissions", "roles", "roles.permissions"];
+
+/// Inline the expansions requested for a service. `path` is where the
+/// service sits in the response (`services` when expanded from a tenant).
+pub(crate) async fn expand_service<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    service: &Service,
+    expand: &ExpandQuery,
+    path: Option<&str>,
+) -> Result<serde_json::Value> {
+    let full_path = |name: &str| match path {
+        Some(path) => format!("{}.{}", path, name),
+        None => name.to_string(),
+    };
+    let tenant_id = service.tenant_id.as_ref().map(|t| t.0);
+    let mut expansions = Vec::new();
+
+    if expand.contains("clients") {
+        require_service_access(state.config(), auth, tenant_id)
+            .map_err(|e| expansion_forbidden(&full_path("clients"), e))?;
+        let clients = state.client_service().list_clients(service.id.0).await?;
+        expansions.push(("clients", expanded_list(&clients, clients.len())?));
+    }
+    if expand.contains("permissions") {
+        require_rbac_read_access(state, auth, tenant_id)
+            .map_err(|e| expansion_forbidden(&full_path("permissions"), e))?;
+        let permissions = state.rbac_service().list_permissions(service.id).await?;
+        expansions.push((
+            "permissions",
+            expanded_list(&permissions, permissions.len())?,
+        ));
+    }
+    if expand.contains("roles") {
+        require_rbac_read_access(state, auth, tenant_id)
+            .map_err(|e| expansion_forbidden(&full_path("roles"), e))?;
+        let roles = state.rbac_service().list_roles(service.id).await?;
+        let value = if expand.contains("roles.permissions") {
+            let mut with_permissions = Vec::new();
+            for role in roles.iter().take(MAX_EXPANDED_ITEMS as usize) {
+                with_permissions.push(
+                    state
+                        .rbac_service()
+                        .get_role_with_permissions(role.id)
+                        .await?,
+                );
+            }
+            expanded_list(&with_permissions, roles.len())?
+        } else {
+            expanded_list(&roles, roles.len())?
+        };
+        expansions.push(("roles", value));
+    }
+
+    expanded(service, expansions)
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/services/{id}",
     tag = "Authorization",
+    params(ExpandParams),
     responses(
-        (status = 200, description = "Service details")
+        (status = 200, description = "Service details; `expand[]` inlines clients, permissions, roles and roles.permissions"),
+        (status = 400, description = "Unknown or too deep expansion")
     )
 )]
 /// Get service by ID
@@ -281,14 +339,26 @@ pub async fn get<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
     Path(id): Path<Uuid>,
+    expand: ExpandQuery,
 ) -> Result<impl IntoResponse> {
+    expand.allow(SERVICE_EXPANSIONS)?;
     let service = state.client_service().get(id).await?;
     require_service_access(
         state.config(),
         &auth,
         service.tenant_id.as_ref().map(|t| t.0),
     )?;
-    Ok(Json(SuccessResponse::new(service)))
+    if expand.is_empty() {
+        return Ok(with_etag(
+            service.version,
+            Json(SuccessResponse::new(service)),
+        ));
+    }
+    let value = expand_service(&state, &auth, &service, &expand, None).await?;
+    Ok(with_etag(
+        service.version,
+        Json(SuccessResponse::new(value)),
+    ))
 }
 
 #[utoipa::path(
@@ -296,11 +366,12 @@ pub async fn get<S: HasServices>(
     path = "/api/v1/services",
     tag = "Authorization",
     responses(
-        (status = 201, description = "Service created")
+        (status = 201, description = "Service created"),
+        (status = 404, description = "Role preset not found")
     )
 )]
-/// Create service
-pub async fn create<S: HasServices>(
+/// Create service, optionally creating its roles from a role preset
+pub async fn create<S: HasServices + HasDbPool>(
     State(state): State<S>,
     auth: AuthUser,
     headers: HeaderMap,
@@ -309,6 +380,15 @@ pub async fn create<S: HasServices>(
     input.client_id = uuid::Uuid::new_v4().to_string();
     input.validate()?;
     require_service_access(state.config(), &auth, input.tenant_id)?;
+    // Resolve the preset before anything is created so an unknown key fails cleanly
+    let preset = match &input.role_preset {
+        Some(key) => Some(
+            RolePresetService::from_pool(state.db_pool().clone())
+                .get(key, input.role_preset_version)
+                .await?,
+        ),
+        None => None,
+    };
     let oidc_client = build_oidc_client_from_create_input(&input);
 
     let client_uuid = state
@@ -323,11 +403,28 @@ pub async fn create<S: HasServices>(
         .await?;
 
     // create_with_secret now creates the service AND an initial client
-    let service_with_client = state
+    let mut service_with_client = state
         .client_service()
         .create_with_secret(input, client_secret)
         .await?;
+    if let Some(preset) = &preset {
+        service_with_client.role_preset = Some(
+            state
+                .rbac_service()
+                .apply_role_preset(service_with_client.service.id, preset)
+                .await?,
+        );
+    }
 
+    let mut audit_value = serde_json::to_value(&service_with_client.service).ok();
+    if let (Some(serde_json::Value::Object(map)), Some(applied)) =
+        (audit_value.as_mut(), &service_with_client.role_preset)
+    {
+        map.insert(
+            "role_preset".to_string(),
+            serde_json::json!({ "key": applied.key, "version": applied.version }),
+        );
+    }
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -335,7 +432,7 @@ pub async fn create<S: HasServices>(
         "service",
         Some(service_with_client.service.id.0),
         None,
-        serde_json::to_value(&service_with_client.service).ok(),
+        audit_value,
     )
     .await;
     Ok((
@@ -348,8 +445,11 @@ pub async fn create<S: HasServices>(
     put,
     path = "/api/v1/services/{id}",
     tag = "Authorization",
+    params(("If-Match" = Option<String>, Header, description = "Expected service version (ETag)")),
     responses(
-        (status = 200, description = "Service updated")
+        (status = 200, description = "Service updated"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
 /// Update service
@@ -367,6 +467,9 @@ pub async fn update<S: HasServices>(
         &auth,
         before.tenant_id.as_ref().map(|t| t.0),
     )?;
+    let expected_version = if_match_version(&headers)?;
+    // Fail fast before pushing the change to the identity engine
+    AppError::check_version("service", expected_version, before.version)?;
     let merged = merge_service_update(&before, &input);
 
     // Update all associated identity engine clients with new service settings
@@ -387,7 +490,10 @@ pub async fn update<S: HasServices>(
         }
     }
 
-    let service = state.client_service().update(id, input).await?;
+    let service = state
+        .client_service()
+        .update(id, input, expected_version)
+        .await?;
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -398,7 +504,10 @@ pub async fn update<S: HasServices>(
         serde_json::to_value(&service).ok(),
     )
     .await;
-    Ok(Json(SuccessResponse::new(service)))
+    Ok(with_etag(
+        service.version,
+        Json(SuccessResponse::new(service)),
+    ))
 }
 
 #[utoipa::path(
@@ -406,13 +515,15 @@ pub async fn update<S: HasServices>(
     path = "/api/v1/services/{id}/clients",
     tag = "Authorization",
     responses(
-        (status = 200, description = "List of clients")
+        (status = 200, description = "List of clients"),
+        (status = 400, description = "filter, sort or fields passed")
     )
 )]
 /// List clients of a service
 pub async fn list_clients<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
+    _: ListGrammarUnsupported,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     let service = state.client_service().get(id).await?;
@@ -585,6 +696,58 @@ pub async fn delete_client<S: HasServices>(
     Ok(Json(MessageResponse::new("Client deleted successfully")))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/clients/{client_id}/token-ttls",
+    tag = "Authorization",
+    request_body = TokenTtlOverrides,
+    responses(
+        (status = 200, description = "Client token lifetimes updated", body = Client),
+        (status = 422, description = "Lifetime outside the platform bounds")
+    )
+)]
+/// Replace a client's access/refresh token lifetime overrides
+pub async fn update_client_token_ttls<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((service_id, client_id)): Path<(Uuid, String)>,
+    Json(input): Json<TokenTtlOverrides>,
+) -> Result<impl IntoResponse> {
+    let service = state.client_service().get(service_id).await?;
+    require_service_access(
+        state.config(),
+        &auth,
+        service.tenant_id.as_ref().map(|t| t.0),
+    )?;
+    let jwt = &state.config().jwt;
+    input
+        .validate_bounds(
+            jwt.max_access_token_ttl_secs,
+            jwt.max_refresh_token_ttl_secs,
+        )
+        .map_err(AppError::Validation)?;
+
+    let before = state.client_service().get_client_record(&client_id).await?;
+    let client = state
+        .client_service()
+        .update_client_token_ttls(service_id, &client_id, input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "service.client.token_ttls.update",
+        "client",
+        Some(service_id),
+        serde_json::to_value(before.token_ttls).ok(),
+        serde_json::to_value(client.token_ttls).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(client)))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/v1/services/{id}",
@@ -606,27 +769,9 @@ pub async fn delete<S: HasServices>(
         &auth,
         service.tenant_id.as_ref().map(|t| t.0),
     )?;
-    // Also delete all identity engine clients associated with this service
-    // If we assume a name prefix or just delete clients in DB...
-    // The clients in DB have `client_id` which maps to identity engine.
-    // Ideally we should iterate clients and delete them from identity engine first.
-    let clients = state.client_service().list_clients(id).await?;
-    for client in clients {
-        if let Ok(kc_uuid) = state
-            .identity_engine()
-            .client_store()
-            .get_client_uuid_by_client_id(&client.client_id)
-            .await
-        {
-            let _ = state
-                .identity_engine()
-                .client_store()
-                .delete_oidc_client(&kc_uuid)
-                .await;
-        }
-    }
-
-    state.client_service().delete(id).await?;
+    // Identity engine clients first, then the service; a failure part-way
+    // leaves a `service_delete` saga for an operator to resume
+    run_service_delete(&state, id).await?;
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -640,6 +785,102 @@ pub async fn delete<S: HasServices>(
     Ok(Json(MessageResponse::new("Service deleted successfully")))
 }
 
+// ============================================================================
+// App Launcher
+// ============================================================================
+
+/// Filters of the app launcher
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MyAppsQuery {
+    /// Only apps tagged with this environment, e.g. `production`
+    pub environment: Option<String>,
+    /// Only apps of this tenant
+    pub tenant_id: Option<Uuid>,
+}
+
+/// An application the user can open from the app launcher
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LauncherApp {
+    pub service_id: StringUuid,
+    pub name: String,
+    /// Tenant the user reaches the app through
+    pub tenant: TenantInfo,
+    /// Deep link into the app (the service's base URL)
+    pub launch_url: Option<String>,
+    pub catalog: ServiceCatalog,
+    /// The user's roles in the app
+    pub roles: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/apps",
+    tag = "Authorization",
+    params(MyAppsQuery),
+    responses(
+        (status = 200, description = "Apps the user can launch", body = Vec<LauncherApp>)
+    )
+)]
+/// List the apps the authenticated user can launch: the active services of
+/// every active tenant they belong to, with catalog metadata, a deep link
+/// and their roles, sorted by tenant and name
+pub async fn list_my_apps<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(query): Query<MyAppsQuery>,
+) -> Result<impl IntoResponse> {
+    let user_id = StringUuid::from(auth.user_id);
+    let memberships = state
+        .user_service()
+        .get_user_tenants_with_tenant(user_id)
+        .await?;
+
+    let mut apps = Vec::new();
+    for membership in memberships {
+        if membership.tenant.status != "active"
+            || query
+                .tenant_id
+                .is_some_and(|id| membership.tenant_id != StringUuid::from(id))
+        {
+            continue;
+        }
+        let services = state
+            .client_service()
+            .list_by_tenant(membership.tenant_id.into())
+            .await?;
+        for service in services {
+            if service.status != ServiceStatus::Active {
+                continue;
+            }
+            if let Some(environment) = &query.environment {
+                if !service.catalog.environment_tags.contains(environment) {
+                    continue;
+                }
+            }
+            let roles = state
+                .rbac_service()
+                .get_user_roles_for_service(
+                    user_id.into(),
+                    membership.tenant_id.into(),
+                    service.id.into(),
+                )
+                .await?
+                .roles;
+            apps.push(LauncherApp {
+                service_id: service.id,
+                name: service.name,
+                tenant: membership.tenant.clone(),
+                launch_url: service.base_url,
+                catalog: service.catalog,
+                roles,
+            });
+        }
+    }
+    apps.sort_by(|a, b| (&a.tenant.name, &a.name).cmp(&(&b.tenant.name, &b.name)));
+
+    Ok(Json(SuccessResponse::new(apps)))
+}
+
 // ============================================================================
 // Integration Info Types & Handler
 // ============================================================================
@@ -1030,22 +1271,18 @@ mod tests {
     #[test]
     fn test_list_services_query_defaults() {
         let query: ListServicesQuery = serde_json::from_str("{}").unwrap();
-        assert_eq!(query.page, 1);
-        assert_eq!(query.per_page, 20);
         assert!(query.tenant_id.is_none());
     }
 
     #[test]
     fn test_list_services_query_with_tenant() {
-        let json = r#"{
-            "page": 2,
-            "per_page": 50,
-            "tenant_id": "550e8400-e29b-41d4-a716-446655440000"
-        }"#;
-        let query: ListServicesQuery = serde_json::from_str(json).unwrap();
+        let raw = "page=2&per_page=50&tenant_id=550e8400-e29b-41d4-a716-446655440000&filter[status]=active";
+        let scope: ListServicesQuery = serde_urlencoded::from_str(raw).unwrap();
+        assert!(scope.tenant_id.is_some());
+        let query = ListQuery::from_query_string(raw).unwrap();
         assert_eq!(query.page, 2);
         assert_eq!(query.per_page, 50);
-        assert!(query.tenant_id.is_some());
+        assert_eq!(query.filters.len(), 1);
     }
 
     #[test]
@@ -1426,6 +1663,9 @@ mod tests {
             base_url: Some("https://test.example.com".to_string()),
             redirect_uris: vec!["https://test.example.com/callback".to_string()],
             logout_uris: Some(vec!["https://test.example.com/logout".to_string()]),
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let kc_client = build_oidc_client_from_create_input(&input);
@@ -1452,6 +1692,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let kc_client = build_oidc_client_from_create_input(&input);
@@ -1473,6 +1716,8 @@ mod tests {
             redirect_uris: vec!["https://old.example.com/cb".to_string()],
             logout_uris: vec!["https://old.example.com/logout".to_string()],
             status: ServiceStatus::Active,
+            version: 1,
+            catalog: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -1483,6 +1728,7 @@ mod tests {
             redirect_uris: Some(vec!["https://new.example.com/cb".to_string()]),
             logout_uris: Some(vec!["https://new.example.com/logout".to_string()]),
             status: Some(ServiceStatus::Inactive),
+            catalog: None,
         };
 
         let merged = merge_service_update(&before, &input);
@@ -1504,6 +1750,8 @@ mod tests {
             redirect_uris: vec!["https://original.com/cb".to_string()],
             logout_uris: vec![],
             status: ServiceStatus::Active,
+            version: 1,
+            catalog: Default::default(),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -1514,6 +1762,7 @@ mod tests {
             redirect_uris: None, // Keep original
             logout_uris: None,   // Keep original
             status: None,        // Keep original
+            catalog: None,
         };
 
         let merged = merge_service_update(&before, &input);
@@ -3,7 +3,7 @@
 use crate::error::{AppError, Result};
 use crate::http_support::SuccessResponse;
 use crate::middleware::auth::AuthUser;
-use crate::models::common::StringUuid;
+use crate::models::common::{ServiceId, StringUuid};
 use crate::models::tenant::{ServiceWithStatus, ToggleServiceInput};
 use crate::policy::{enforce, PolicyAction, PolicyInput, ResourceScope};
 use crate::state::{HasDbPool, HasServices};
@@ -82,7 +82,7 @@ pub async fn toggle_service<S: HasServices + HasDbPool>(
     Json(input): Json<ToggleServiceInput>,
 ) -> Result<impl IntoResponse> {
     let tenant_id = StringUuid::from(tenant_id);
-    let service_id = StringUuid::from(input.service_id);
+    let service_id = ServiceId::from(input.service_id);
 
     enforce(
         state.config(),
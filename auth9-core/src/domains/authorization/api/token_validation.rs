@@ -0,0 +1,88 @@
+//! Per-service token validation profile API handlers (platform admin)
+
+use crate::domains::authorization::service::TokenValidationProfileService;
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::token_validation::{
+    ServiceTokenValidationProfile, SetTokenValidationProfileInput,
+};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{service_id}/token-validation-profile",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "Validation profile applied to the service's tenant access tokens", body = ServiceTokenValidationProfile)
+    )
+)]
+/// Get the validation profile applied to tenant access tokens of a service
+pub async fn get_token_validation_profile<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(service_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let profile = TokenValidationProfileService::from_pool(
+        state.db_pool().clone(),
+        &state.config().token_validation,
+    )
+    .get(StringUuid::from(service_id))
+    .await?;
+    Ok(Json(SuccessResponse::new(profile)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{service_id}/token-validation-profile",
+    tag = "Authorization",
+    params(("service_id" = String, Path, description = "Service ID (UUID)")),
+    request_body = SetTokenValidationProfileInput,
+    responses(
+        (status = 200, description = "Profile assigned; verifiers pick it up within a minute", body = ServiceTokenValidationProfile),
+        (status = 400, description = "Profile is not configured")
+    )
+)]
+/// Assign a configured validation profile to a service
+pub async fn set_token_validation_profile<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(service_id): Path<Uuid>,
+    Json(input): Json<SetTokenValidationProfileInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    state.client_service().get(service_id).await?;
+
+    let service = TokenValidationProfileService::from_pool(
+        state.db_pool().clone(),
+        &state.config().token_validation,
+    );
+    let before = service.get(StringUuid::from(service_id)).await?;
+    let profile = service.set(StringUuid::from(service_id), input).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "token_validation_profile.update",
+        "service",
+        Some(service_id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&profile).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(profile)))
+}
@@ -1,10 +1,28 @@
 use crate::domains::authorization::api as authorization_api;
 use crate::domains::authorization::context::AuthorizationContext;
 use axum::{
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
+pub fn public_routes<S>() -> Router<S>
+where
+    S: AuthorizationContext,
+{
+    // Dynamic client registration authenticates with its own bearer tokens
+    Router::new()
+        .route(
+            "/api/v1/oauth/tenants/{tenant_id}/register",
+            post(authorization_api::client_registration::register_client::<S>),
+        )
+        .route(
+            "/api/v1/oauth/tenants/{tenant_id}/register/{client_id}",
+            get(authorization_api::client_registration::get_registered_client::<S>)
+                .put(authorization_api::client_registration::update_registered_client::<S>)
+                .delete(authorization_api::client_registration::delete_registered_client::<S>),
+        )
+}
+
 pub fn protected_routes<S>() -> Router<S>
 where
     S: AuthorizationContext,
@@ -34,10 +52,18 @@ where
             "/api/v1/services/{service_id}/clients/{client_id}",
             delete(authorization_api::service::delete_client::<S>),
         )
+        .route(
+            "/api/v1/services/{service_id}/clients/{client_id}/token-ttls",
+            put(authorization_api::service::update_client_token_ttls::<S>),
+        )
         .route(
             "/api/v1/services/{service_id}/clients/{client_id}/regenerate-secret",
             post(authorization_api::service::regenerate_client_secret::<S>),
         )
+        .route(
+            "/api/v1/users/me/apps",
+            get(authorization_api::service::list_my_apps::<S>),
+        )
         .route(
             "/api/v1/permissions",
             post(authorization_api::role::create_permission::<S>),
@@ -50,6 +76,11 @@ where
             "/api/v1/services/{service_id}/permissions",
             get(authorization_api::role::list_permissions::<S>),
         )
+        .route(
+            "/api/v1/services/{service_id}/permission-namespace",
+            get(authorization_api::role::get_permission_namespace::<S>)
+                .put(authorization_api::role::set_permission_namespace::<S>),
+        )
         .route(
             "/api/v1/roles",
             post(authorization_api::role::create_role::<S>),
@@ -64,6 +95,54 @@ where
             "/api/v1/services/{service_id}/roles",
             get(authorization_api::role::list_roles::<S>),
         )
+        .route(
+            "/api/v1/services/{service_id}/claims-enrichers",
+            get(authorization_api::claims_enricher::list_claims_enrichers::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/claims-enrichers/{plugin}",
+            axum::routing::put(authorization_api::claims_enricher::upsert_claims_enricher::<S>)
+                .delete(authorization_api::claims_enricher::delete_claims_enricher::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/external-authorizer",
+            get(authorization_api::external_authorizer::get_external_authorizer::<S>)
+                .put(authorization_api::external_authorizer::upsert_external_authorizer::<S>)
+                .delete(authorization_api::external_authorizer::delete_external_authorizer::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/token-validation-profile",
+            get(authorization_api::token_validation::get_token_validation_profile::<S>)
+                .put(authorization_api::token_validation::set_token_validation_profile::<S>),
+        )
+        .route(
+            "/api/v1/role-presets",
+            get(authorization_api::role_preset::list_role_presets::<S>)
+                .post(authorization_api::role_preset::create_role_preset::<S>),
+        )
+        .route(
+            "/api/v1/role-presets/{key}",
+            get(authorization_api::role_preset::get_role_preset::<S>)
+                .put(authorization_api::role_preset::update_role_preset::<S>)
+                .delete(authorization_api::role_preset::delete_role_preset::<S>),
+        )
+        .route(
+            "/api/v1/role-presets/{key}/versions",
+            get(authorization_api::role_preset::list_role_preset_versions::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/scopes",
+            get(authorization_api::scope::list_scopes::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/scopes/{name}",
+            axum::routing::put(authorization_api::scope::upsert_scope::<S>)
+                .delete(authorization_api::scope::delete_scope::<S>),
+        )
+        .route(
+            "/api/v1/services/{service_id}/roles/tree",
+            get(authorization_api::role::get_role_tree::<S>),
+        )
         .route(
             "/api/v1/roles/{role_id}/permissions",
             post(authorization_api::role::assign_permission::<S>),
@@ -80,6 +159,10 @@ where
             "/api/v1/users/{user_id}/tenants/{tenant_id}/roles",
             get(authorization_api::role::get_user_roles::<S>),
         )
+        .route(
+            "/api/v1/users/{user_id}/tenants/{tenant_id}/permissions/check",
+            post(authorization_api::role::check_user_permission::<S>),
+        )
         .route(
             "/api/v1/users/{user_id}/tenants/{tenant_id}/assigned-roles",
             get(authorization_api::role::get_user_assigned_roles::<S>),
@@ -104,7 +187,8 @@ where
         )
         .route(
             "/api/v1/tenants/{tenant_id}/abac/policies/{version_id}",
-            axum::routing::put(authorization_api::abac::update_policy::<S>),
+            get(authorization_api::abac::get_policy::<S>)
+                .put(authorization_api::abac::update_policy::<S>),
         )
         .route(
             "/api/v1/tenants/{tenant_id}/abac/policies/{version_id}/publish",
@@ -118,4 +202,18 @@ where
             "/api/v1/tenants/{tenant_id}/abac/simulate",
             post(authorization_api::abac::simulate_policy::<S>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/client-registration/policy",
+            get(authorization_api::client_registration::get_registration_policy::<S>)
+                .put(authorization_api::client_registration::update_registration_policy::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/client-registration/initial-access-tokens",
+            get(authorization_api::client_registration::list_initial_access_tokens::<S>)
+                .post(authorization_api::client_registration::create_initial_access_token::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/client-registration/initial-access-tokens/{token_id}",
+            delete(authorization_api::client_registration::revoke_initial_access_token::<S>),
+        )
 }
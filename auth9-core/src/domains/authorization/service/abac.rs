@@ -1,7 +1,7 @@
 use crate::error::{AppError, Result};
 use crate::models::abac::{
-    AbacMode, AbacPolicyDocument, AbacPolicySetSummary, AbacPolicyVersionSummary,
-    AbacSimulationInput, AbacSimulationResult,
+    AbacMode, AbacPolicyDocument, AbacPolicySetSummary, AbacPolicyVersionDetail,
+    AbacPolicyVersionSummary, AbacSimulationInput, AbacSimulationResult,
 };
 use crate::models::common::StringUuid;
 use crate::policy::abac::simulate_document;
@@ -57,25 +57,67 @@ impl<R: AbacRepository> AbacPolicyService<R> {
             .await
     }
 
+    pub async fn get_policy(
+        &self,
+        tenant_id: StringUuid,
+        version_id: StringUuid,
+    ) -> Result<AbacPolicyVersionDetail> {
+        let record = self
+            .repo
+            .fetch_version_for_tenant(tenant_id, version_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Policy version not found".to_string()))?;
+        let policy =
+            serde_json::from_str(&record.policy_json).map_err(|e| AppError::Internal(e.into()))?;
+        Ok(AbacPolicyVersionDetail {
+            version: map_version_summary(record.version),
+            policy,
+        })
+    }
+
+    /// Replace a draft and return its new revision. `expected_revision`
+    /// (from `If-Match`) rejects the write if the draft was edited by someone
+    /// else in the meantime.
     pub async fn update_policy(
         &self,
         tenant_id: StringUuid,
         version_id: StringUuid,
         policy: AbacPolicyDocument,
         change_note: Option<String>,
-    ) -> Result<()> {
+        expected_revision: Option<i32>,
+    ) -> Result<i32> {
+        let not_draft = || {
+            AppError::BadRequest("Draft policy version not found or already published".to_string())
+        };
+        let current = self
+            .repo
+            .fetch_version_for_tenant(tenant_id, version_id)
+            .await?
+            .filter(|record| record.version.status == "draft")
+            .ok_or_else(not_draft)?;
+        let revision = current.version.revision;
+        AppError::check_version("abac_policy", expected_revision, revision)?;
+
         let policy_json =
             serde_json::to_string(&policy).map_err(|e| AppError::Internal(e.into()))?;
         let updated = self
             .repo
-            .update_draft_for_tenant(tenant_id, version_id, policy_json, change_note)
+            .update_draft_for_tenant(tenant_id, version_id, revision, policy_json, change_note)
             .await?;
         if !updated {
-            return Err(AppError::BadRequest(
-                "Draft policy version not found or already published".to_string(),
-            ));
+            // Edited or published between the read above and the write
+            let current = self
+                .repo
+                .fetch_version_for_tenant(tenant_id, version_id)
+                .await?
+                .filter(|record| record.version.status == "draft")
+                .ok_or_else(not_draft)?;
+            return Err(AppError::VersionConflict {
+                resource: "abac_policy".to_string(),
+                current_version: Some(current.version.revision),
+            });
         }
-        Ok(())
+        Ok(revision + 1)
     }
 
     pub async fn publish_policy(
@@ -195,6 +237,7 @@ fn map_version_summary(v: AbacPolicyVersionRecord) -> AbacPolicyVersionSummary {
         created_by: v.created_by.map(|x| x.to_string()),
         created_at: v.created_at.to_rfc3339(),
         published_at: v.published_at.map(|t| t.to_rfc3339()),
+        revision: v.revision,
     }
 }
 
@@ -228,7 +271,7 @@ mod tests {
     use super::*;
     use crate::models::abac::AbacEffect;
     use crate::models::abac::AbacRule;
-    use crate::repository::abac::MockAbacRepository;
+    use crate::repository::abac::{AbacPolicyVersionDocumentRecord, MockAbacRepository};
     use mockall::predicate::eq;
 
     fn sample_policy() -> AbacPolicyDocument {
@@ -277,6 +320,7 @@ mod tests {
                     created_by: None,
                     created_at: chrono::Utc::now(),
                     published_at: None,
+                    revision: 1,
                 }])
             });
 
@@ -322,6 +366,79 @@ mod tests {
         assert_eq!(out.status, "draft");
     }
 
+    fn draft(version_id: StringUuid, revision: i32) -> AbacPolicyVersionDocumentRecord {
+        AbacPolicyVersionDocumentRecord {
+            version: AbacPolicyVersionRecord {
+                id: version_id,
+                policy_set_id: StringUuid::new_v4(),
+                version_no: 3,
+                status: "draft".to_string(),
+                change_note: None,
+                created_by: None,
+                created_at: chrono::Utc::now(),
+                published_at: None,
+                revision,
+            },
+            policy_json: serde_json::to_string(&sample_policy()).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_policy_bumps_revision() {
+        let mut repo = MockAbacRepository::new();
+        let tenant_id = StringUuid::new_v4();
+        let version_id = StringUuid::new_v4();
+        repo.expect_fetch_version_for_tenant()
+            .with(eq(tenant_id), eq(version_id))
+            .return_once(move |_, _| Ok(Some(draft(version_id, 4))));
+        repo.expect_update_draft_for_tenant()
+            .withf(move |tid, vid, revision, _, _| {
+                *tid == tenant_id && *vid == version_id && *revision == 4
+            })
+            .return_once(|_, _, _, _, _| Ok(true));
+        let svc = AbacPolicyService::new(Arc::new(repo));
+        let revision = svc
+            .update_policy(tenant_id, version_id, sample_policy(), None, Some(4))
+            .await
+            .unwrap();
+        assert_eq!(revision, 5);
+    }
+
+    #[tokio::test]
+    async fn test_update_policy_rejects_stale_revision() {
+        let mut repo = MockAbacRepository::new();
+        let tenant_id = StringUuid::new_v4();
+        let version_id = StringUuid::new_v4();
+        repo.expect_fetch_version_for_tenant()
+            .return_once(move |_, _| Ok(Some(draft(version_id, 4))));
+        repo.expect_update_draft_for_tenant().never();
+        let svc = AbacPolicyService::new(Arc::new(repo));
+        let err = svc
+            .update_policy(tenant_id, version_id, sample_policy(), None, Some(3))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::PreconditionFailed {
+                current_version: Some(4),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_policy_returns_document() {
+        let mut repo = MockAbacRepository::new();
+        let tenant_id = StringUuid::new_v4();
+        let version_id = StringUuid::new_v4();
+        repo.expect_fetch_version_for_tenant()
+            .return_once(move |_, _| Ok(Some(draft(version_id, 2))));
+        let svc = AbacPolicyService::new(Arc::new(repo));
+        let detail = svc.get_policy(tenant_id, version_id).await.unwrap();
+        assert_eq!(detail.version.revision, 2);
+        assert_eq!(detail.policy.rules[0].id, "allow_admin");
+    }
+
     #[tokio::test]
     async fn test_publish_policy_maps_not_found() {
         let mut repo = MockAbacRepository::new();
@@ -2,7 +2,8 @@
 
 use crate::cache::CacheManager;
 use crate::error::{AppError, Result};
-use crate::models::common::StringUuid;
+use crate::models::common::{StringUuid, TokenTtlOverrides};
+use crate::models::list_query::ListQuery;
 use crate::models::service::{
     Client, ClientWithSecret, CreateServiceInput, Service, ServiceWithClient, UpdateServiceInput,
 };
@@ -96,6 +97,7 @@ impl<
                 client,
                 client_secret,
             },
+            role_preset: None,
         })
     }
 
@@ -143,6 +145,7 @@ impl<
                 client,
                 client_secret,
             },
+            role_preset: None,
         })
     }
 
@@ -279,18 +282,40 @@ impl<
         Ok((services, total))
     }
 
+    /// List services with the standard filter / sort grammar
+    pub async fn list_filtered(
+        &self,
+        tenant_id: Option<Uuid>,
+        query: &ListQuery,
+    ) -> Result<(Vec<Service>, i64)> {
+        self.repo.list_filtered(tenant_id, query).await
+    }
+
+    /// All services of a tenant, unpaginated
+    pub async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<Service>> {
+        self.repo.list_by_tenant(tenant_id).await
+    }
+
     pub async fn list_clients(&self, service_id: Uuid) -> Result<Vec<Client>> {
         self.repo.list_clients(service_id).await
     }
 
-    pub async fn update(&self, id: Uuid, input: UpdateServiceInput) -> Result<Service> {
+    /// Update a service. `expected_version` (from `If-Match`) rejects the
+    /// write with a version conflict if the service changed in the meantime.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        input: UpdateServiceInput,
+        expected_version: Option<i32>,
+    ) -> Result<Service> {
         input.validate()?;
         let _ = self.get(id).await?;
-        let service = self.repo.update(id, &input).await?;
+        let result = self.repo.update(id, &input, expected_version).await;
+        // Invalidate on conflicts too so the client's reload sees the current version
         if let Some(cache) = &self.cache_manager {
             let _ = cache.invalidate_service_config(id).await;
         }
-        Ok(service)
+        result
     }
 
     /// Regenerate the client secret for a specific client.
@@ -425,6 +450,18 @@ impl<
             .await
     }
 
+    /// Replace a client's token lifetime overrides
+    pub async fn update_client_token_ttls(
+        &self,
+        service_id: Uuid,
+        client_id: &str,
+        token_ttls: TokenTtlOverrides,
+    ) -> Result<Client> {
+        self.repo
+            .update_client_token_ttls(service_id, client_id, &token_ttls)
+            .await
+    }
+
     pub async fn verify_secret(&self, client_id: &str, secret: &str) -> Result<Service> {
         // 1. Find Client to get hash
         let client = self
@@ -578,6 +615,8 @@ mod tests {
                 redirect_uris: input.redirect_uris.clone(),
                 logout_uris: input.logout_uris.clone().unwrap_or_default(),
                 status: crate::models::service::ServiceStatus::Active,
+                version: 1,
+                catalog: input.catalog.clone(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             })
@@ -593,6 +632,7 @@ mod tests {
                     name,
                     public_client,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 })
             });
 
@@ -605,6 +645,9 @@ mod tests {
             base_url: Some("https://example.com".to_string()),
             redirect_uris: vec!["https://example.com/callback".to_string()],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let result = service.create(input).await;
@@ -629,6 +672,7 @@ mod tests {
                     name: None,
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -641,6 +685,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let result = service.create(input).await;
@@ -659,6 +706,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let result = service.create(input).await;
@@ -690,6 +740,7 @@ mod tests {
                     name: None,
                     public_client,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 })
             });
 
@@ -702,6 +753,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         let result = service
@@ -731,6 +785,8 @@ mod tests {
                     redirect_uris: vec![],
                     logout_uris: vec![],
                     status: crate::models::service::ServiceStatus::Active,
+                    version: 1,
+                    catalog: Default::default(),
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
                 }))
@@ -746,6 +802,7 @@ mod tests {
                     name,
                     public_client,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 })
             },
         );
@@ -806,6 +863,7 @@ mod tests {
                     name,
                     public_client,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 })
             });
 
@@ -849,6 +907,7 @@ mod tests {
                     name: None,
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -958,6 +1017,7 @@ mod tests {
                     name: Some("Test Client".to_string()),
                     public_client: true,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -1059,6 +1119,7 @@ mod tests {
                     name: Some("Client 1".to_string()),
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }])
             });
 
@@ -1087,7 +1148,7 @@ mod tests {
                 }))
             });
 
-        mock.expect_update().returning(|_, input| {
+        mock.expect_update().returning(|_, input, _| {
             Ok(Service {
                 name: input.name.clone().unwrap_or_default(),
                 status: input
@@ -1106,9 +1167,10 @@ mod tests {
             redirect_uris: None,
             logout_uris: None,
             status: None,
+            catalog: None,
         };
 
-        let result = service.update(service_id, input).await;
+        let result = service.update(service_id, input, None).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().name, "New Name");
     }
@@ -1130,9 +1192,10 @@ mod tests {
             redirect_uris: None,
             logout_uris: None,
             status: None,
+            catalog: None,
         };
 
-        let result = service.update(service_id, input).await;
+        let result = service.update(service_id, input, None).await;
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
@@ -1156,6 +1219,7 @@ mod tests {
                     name: None,
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -1287,6 +1351,7 @@ mod tests {
                     name: None,
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -1336,6 +1401,7 @@ mod tests {
                     name: None,
                     public_client: false,
                     created_at: chrono::Utc::now(),
+                    token_ttls: Default::default(),
                 }))
             });
 
@@ -1361,6 +1427,7 @@ mod tests {
                 name: None,
                 public_client: false,
                 created_at: chrono::Utc::now(),
+                token_ttls: Default::default(),
             }))
         });
 
@@ -0,0 +1,755 @@
+//! OAuth 2.0 Dynamic Client Registration (RFC 7591) and client
+//! configuration management (RFC 7592)
+//!
+//! Tenant admins enable registration per tenant and hand out initial access
+//! tokens. A partner presents one to register a client and receives a
+//! registration access token that authorizes later read/update/delete calls
+//! on that client only. Tokens are stored as SHA-256 hashes.
+
+use crate::error::client_registration::ClientRegistrationError;
+use crate::error::{AppError, Result};
+use crate::models::client_registration::{
+    ClientMetadata, ClientRegistration, ClientRegistrationPolicy, CreateInitialAccessTokenInput,
+    InitialAccessToken, InitialAccessTokenCreated, UpdateClientRegistrationPolicyInput,
+    SUPPORTED_AUTH_METHODS, SUPPORTED_GRANT_TYPES, SUPPORTED_RESPONSE_TYPES,
+};
+use crate::models::common::StringUuid;
+use crate::models::service::validate_single_redirect_uri;
+use crate::repository::ClientRegistrationRepository;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::Arc;
+use validator::Validate;
+
+type RegistrationResult<T> = std::result::Result<T, ClientRegistrationError>;
+
+const INITIAL_TOKEN_PREFIX: &str = "dcr_iat_";
+const REGISTRATION_TOKEN_PREFIX: &str = "dcr_rat_";
+const TOKEN_RANDOM_BYTES: usize = 32;
+
+/// Software statement claims that are not client metadata
+const NON_METADATA_CLAIMS: &[&str] = &[
+    "iss",
+    "sub",
+    "aud",
+    "exp",
+    "iat",
+    "nbf",
+    "jti",
+    "software_statement",
+];
+
+pub struct ClientRegistrationService<R: ClientRegistrationRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: ClientRegistrationRepository> ClientRegistrationService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    // ------------------------------------------------------------------
+    // Tenant administration
+    // ------------------------------------------------------------------
+
+    pub async fn get_policy(&self, tenant_id: StringUuid) -> Result<ClientRegistrationPolicy> {
+        Ok(self
+            .repo
+            .find_policy(tenant_id)
+            .await?
+            .unwrap_or_else(|| ClientRegistrationPolicy::disabled(tenant_id)))
+    }
+
+    pub async fn set_policy(
+        &self,
+        tenant_id: StringUuid,
+        input: UpdateClientRegistrationPolicyInput,
+    ) -> Result<ClientRegistrationPolicy> {
+        input.validate()?;
+        for issuer in &input.software_statement_issuers {
+            if issuer.issuer.trim().is_empty() {
+                return Err(AppError::Validation(
+                    "Software statement issuer must not be empty".to_string(),
+                ));
+            }
+            DecodingKey::from_rsa_pem(issuer.public_key_pem.as_bytes()).map_err(|_| {
+                AppError::Validation(format!(
+                    "Public key for issuer '{}' is not a valid RSA PEM key",
+                    issuer.issuer
+                ))
+            })?;
+        }
+        if input.require_software_statement && input.software_statement_issuers.is_empty() {
+            return Err(AppError::Validation(
+                "At least one trusted issuer is required when software statements are required"
+                    .to_string(),
+            ));
+        }
+        self.repo.upsert_policy(tenant_id, &input).await
+    }
+
+    /// Issue an initial access token. The raw token is returned only here.
+    pub async fn issue_initial_token(
+        &self,
+        tenant_id: StringUuid,
+        input: CreateInitialAccessTokenInput,
+        created_by: Option<StringUuid>,
+    ) -> Result<InitialAccessTokenCreated> {
+        input.validate()?;
+        let raw = generate_token(INITIAL_TOKEN_PREFIX);
+        let now = Utc::now();
+        let token = InitialAccessToken {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            token_hash: hash_token(&raw),
+            token_prefix: raw[..INITIAL_TOKEN_PREFIX.len() + 4].to_string(),
+            description: input.description,
+            max_uses: input.max_uses,
+            use_count: 0,
+            expires_at: input
+                .expires_in_secs
+                .map(|secs| now + Duration::seconds(secs)),
+            revoked_at: None,
+            created_by,
+            created_at: now,
+        };
+        self.repo.create_initial_token(&token).await?;
+        Ok(InitialAccessTokenCreated {
+            token,
+            access_token: raw,
+        })
+    }
+
+    pub async fn list_initial_tokens(
+        &self,
+        tenant_id: StringUuid,
+    ) -> Result<Vec<InitialAccessToken>> {
+        self.repo.list_initial_tokens(tenant_id).await
+    }
+
+    pub async fn revoke_initial_token(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()> {
+        if !self.repo.revoke_initial_token(tenant_id, id).await? {
+            return Err(AppError::NotFound(format!(
+                "Initial access token {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Registration protocol
+    // ------------------------------------------------------------------
+
+    /// Check that registration is enabled for the tenant and that the
+    /// initial access token is valid for it.
+    pub async fn authorize_registration(
+        &self,
+        tenant_id: StringUuid,
+        raw_token: Option<&str>,
+    ) -> RegistrationResult<(ClientRegistrationPolicy, InitialAccessToken)> {
+        let policy = self.get_policy(tenant_id).await?;
+        if !policy.enabled {
+            return Err(ClientRegistrationError::AccessDenied(
+                "Dynamic client registration is not enabled for this tenant".to_string(),
+            ));
+        }
+
+        let raw_token = raw_token.ok_or_else(|| {
+            ClientRegistrationError::InvalidToken("Initial access token required".to_string())
+        })?;
+        let token = self
+            .repo
+            .find_initial_token_by_hash(&hash_token(raw_token))
+            .await?
+            .filter(|t| t.tenant_id == tenant_id && t.is_usable(Utc::now()))
+            .ok_or_else(|| {
+                ClientRegistrationError::InvalidToken("Invalid initial access token".to_string())
+            })?;
+        Ok((policy, token))
+    }
+
+    /// Count a registration against the initial access token
+    pub async fn consume_initial_token(
+        &self,
+        token: &InitialAccessToken,
+    ) -> RegistrationResult<()> {
+        if !self.repo.consume_initial_token(token.id).await? {
+            return Err(ClientRegistrationError::InvalidToken(
+                "Initial access token has no remaining uses".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply the software statement, fill in defaults and validate the
+    /// requested client metadata.
+    pub fn prepare_metadata(
+        &self,
+        policy: &ClientRegistrationPolicy,
+        metadata: ClientMetadata,
+    ) -> RegistrationResult<ClientMetadata> {
+        let metadata = match metadata.software_statement.clone() {
+            Some(statement) => {
+                let claims = verify_software_statement(policy, &statement)?;
+                apply_software_statement(metadata, claims)?
+            }
+            None if policy.require_software_statement => {
+                return Err(ClientRegistrationError::UnapprovedSoftwareStatement(
+                    "A software statement is required by this tenant".to_string(),
+                ));
+            }
+            None => metadata,
+        };
+        normalize_metadata(metadata)
+    }
+
+    /// Record a registered client and issue its registration access token
+    pub async fn record_registration(
+        &self,
+        tenant_id: StringUuid,
+        service_id: StringUuid,
+        client_id: &str,
+        initial_token_id: StringUuid,
+        metadata: ClientMetadata,
+    ) -> Result<(ClientRegistration, String)> {
+        let raw = generate_token(REGISTRATION_TOKEN_PREFIX);
+        let now = Utc::now();
+        let registration = ClientRegistration {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            service_id,
+            client_id: client_id.to_string(),
+            registration_token_hash: hash_token(&raw),
+            initial_token_id: Some(initial_token_id),
+            software_id: metadata.software_id.clone(),
+            metadata,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create_registration(&registration).await?;
+        Ok((registration, raw))
+    }
+
+    /// Resolve the registration a registration access token was issued for.
+    /// Any mismatch is reported as an invalid token (RFC 7592 Section 2).
+    pub async fn authenticate_client(
+        &self,
+        tenant_id: StringUuid,
+        client_id: &str,
+        raw_token: Option<&str>,
+    ) -> RegistrationResult<ClientRegistration> {
+        let raw_token = raw_token.ok_or_else(|| {
+            ClientRegistrationError::InvalidToken("Registration access token required".to_string())
+        })?;
+        self.repo
+            .find_registration_by_token_hash(&hash_token(raw_token))
+            .await?
+            .filter(|r| r.tenant_id == tenant_id && r.client_id == client_id)
+            .ok_or_else(|| {
+                ClientRegistrationError::InvalidToken(
+                    "Invalid registration access token".to_string(),
+                )
+            })
+    }
+
+    pub async fn update_metadata(
+        &self,
+        registration: &ClientRegistration,
+        metadata: &ClientMetadata,
+    ) -> Result<()> {
+        self.repo
+            .update_registration_metadata(registration.id, metadata)
+            .await
+    }
+
+    pub async fn delete_registration(&self, registration: &ClientRegistration) -> Result<()> {
+        self.repo.delete_registration(registration.id).await
+    }
+}
+
+fn generate_token(prefix: &str) -> String {
+    let mut bytes = [0u8; TOKEN_RANDOM_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", prefix, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn hash_token(raw: &str) -> String {
+    hex::encode(Sha256::digest(raw.as_bytes()))
+}
+
+/// Verify a software statement against the tenant's trusted issuers and
+/// return its claims
+fn verify_software_statement(
+    policy: &ClientRegistrationPolicy,
+    statement: &str,
+) -> RegistrationResult<Map<String, Value>> {
+    let header = jsonwebtoken::decode_header(statement).map_err(|_| {
+        ClientRegistrationError::InvalidSoftwareStatement(
+            "Software statement is not a signed JWT".to_string(),
+        )
+    })?;
+    if header.alg != Algorithm::RS256 {
+        return Err(ClientRegistrationError::InvalidSoftwareStatement(
+            "Software statement must be signed with RS256".to_string(),
+        ));
+    }
+
+    for trusted in &policy.software_statement_issuers {
+        let Ok(key) = DecodingKey::from_rsa_pem(trusted.public_key_pem.as_bytes()) else {
+            continue;
+        };
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&trusted.issuer]);
+        validation.set_required_spec_claims(&["iss"]);
+        validation.validate_aud = false;
+
+        match jsonwebtoken::decode::<Map<String, Value>>(statement, &key, &validation) {
+            Ok(data) => return Ok(data.claims),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::InvalidIssuer | ErrorKind::InvalidSignature
+                ) =>
+            {
+                continue
+            }
+            Err(e) => {
+                return Err(ClientRegistrationError::InvalidSoftwareStatement(format!(
+                    "Software statement rejected: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    Err(ClientRegistrationError::UnapprovedSoftwareStatement(
+        "Software statement is not signed by a trusted issuer".to_string(),
+    ))
+}
+
+/// Values in the software statement take precedence over plain request
+/// values (RFC 7591 Section 2.3)
+fn apply_software_statement(
+    metadata: ClientMetadata,
+    claims: Map<String, Value>,
+) -> RegistrationResult<ClientMetadata> {
+    let mut merged = match serde_json::to_value(&metadata) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    for (key, value) in claims {
+        if !NON_METADATA_CLAIMS.contains(&key.as_str()) {
+            merged.insert(key, value);
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|e| {
+        ClientRegistrationError::InvalidSoftwareStatement(format!(
+            "Software statement contains invalid metadata: {}",
+            e
+        ))
+    })
+}
+
+fn normalize_metadata(mut metadata: ClientMetadata) -> RegistrationResult<ClientMetadata> {
+    let invalid = |msg: String| ClientRegistrationError::InvalidClientMetadata(msg);
+
+    let auth_method = metadata
+        .token_endpoint_auth_method
+        .get_or_insert_with(|| "client_secret_basic".to_string());
+    if !SUPPORTED_AUTH_METHODS.contains(&auth_method.as_str()) {
+        return Err(invalid(format!(
+            "token_endpoint_auth_method '{}' is not supported; registered clients are confidential",
+            auth_method
+        )));
+    }
+
+    if metadata.grant_types.is_empty() {
+        metadata.grant_types = vec!["authorization_code".to_string()];
+    }
+    let mut seen = HashSet::new();
+    metadata.grant_types.retain(|g| seen.insert(g.clone()));
+    if let Some(grant) = metadata
+        .grant_types
+        .iter()
+        .find(|g| !SUPPORTED_GRANT_TYPES.contains(&g.as_str()))
+    {
+        return Err(invalid(format!("grant_type '{}' is not supported", grant)));
+    }
+
+    let uses_code = metadata
+        .grant_types
+        .iter()
+        .any(|g| g == "authorization_code");
+    if metadata.response_types.is_empty() && uses_code {
+        metadata.response_types = vec!["code".to_string()];
+    }
+    if let Some(response_type) = metadata
+        .response_types
+        .iter()
+        .find(|r| !SUPPORTED_RESPONSE_TYPES.contains(&r.as_str()))
+    {
+        return Err(invalid(format!(
+            "response_type '{}' is not supported",
+            response_type
+        )));
+    }
+    if uses_code != metadata.response_types.iter().any(|r| r == "code") {
+        return Err(invalid(
+            "The authorization_code grant type and the code response type must be used together"
+                .to_string(),
+        ));
+    }
+
+    if uses_code && metadata.redirect_uris.is_empty() {
+        return Err(ClientRegistrationError::InvalidRedirectUri(
+            "redirect_uris is required for the authorization_code grant type".to_string(),
+        ));
+    }
+    for uri in metadata
+        .redirect_uris
+        .iter()
+        .chain(metadata.post_logout_redirect_uris.iter())
+    {
+        validate_single_redirect_uri(uri).map_err(|e| {
+            ClientRegistrationError::InvalidRedirectUri(
+                e.message
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("Invalid redirect URI: {}", uri)),
+            )
+        })?;
+    }
+
+    if let Some(name) = &metadata.client_name {
+        if name.trim().is_empty() || name.len() > 255 {
+            return Err(invalid(
+                "client_name must be between 1 and 255 characters".to_string(),
+            ));
+        }
+    }
+    for (field, value) in [
+        ("client_uri", &metadata.client_uri),
+        ("logo_uri", &metadata.logo_uri),
+        ("tos_uri", &metadata.tos_uri),
+        ("policy_uri", &metadata.policy_uri),
+    ] {
+        if let Some(value) = value {
+            let valid = url::Url::parse(value)
+                .map(|u| u.scheme() == "https" || u.scheme() == "http")
+                .unwrap_or(false);
+            if !valid {
+                return Err(invalid(format!("{} must be an http(s) URL", field)));
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::client_registration::SoftwareStatementIssuer;
+    use crate::repository::client_registration::MockClientRegistrationRepository;
+    use mockall::predicate::*;
+
+    fn policy(enabled: bool) -> ClientRegistrationPolicy {
+        ClientRegistrationPolicy {
+            enabled,
+            ..ClientRegistrationPolicy::disabled(StringUuid::new_v4())
+        }
+    }
+
+    fn initial_token(tenant_id: StringUuid, raw: &str) -> InitialAccessToken {
+        InitialAccessToken {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            token_hash: hash_token(raw),
+            token_prefix: raw[..12].to_string(),
+            description: None,
+            max_uses: Some(1),
+            use_count: 0,
+            expires_at: None,
+            revoked_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn request() -> ClientMetadata {
+        ClientMetadata {
+            redirect_uris: vec!["https://partner.example.com/callback".to_string()],
+            client_name: Some("Partner".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorize_registration_requires_enabled_policy() {
+        let tenant_id = StringUuid::new_v4();
+        let mut mock = MockClientRegistrationRepository::new();
+        mock.expect_find_policy().returning(|_| Ok(None));
+
+        let service = ClientRegistrationService::new(Arc::new(mock));
+        let err = service
+            .authorize_registration(tenant_id, Some("dcr_iat_x"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientRegistrationError::AccessDenied(_)));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_registration_checks_token_tenant() {
+        let tenant_id = StringUuid::new_v4();
+        let raw = generate_token(INITIAL_TOKEN_PREFIX);
+        let other_tenant_token = initial_token(StringUuid::new_v4(), &raw);
+        let own_token = initial_token(tenant_id, &raw);
+
+        let mut mock = MockClientRegistrationRepository::new();
+        mock.expect_find_policy().returning(move |id| {
+            Ok(Some(ClientRegistrationPolicy {
+                tenant_id: id,
+                ..policy(true)
+            }))
+        });
+        let mut tokens = vec![own_token.clone(), other_tenant_token];
+        mock.expect_find_initial_token_by_hash()
+            .with(eq(hash_token(&raw)))
+            .returning(move |_| Ok(tokens.pop()));
+
+        let service = ClientRegistrationService::new(Arc::new(mock));
+        let err = service
+            .authorize_registration(tenant_id, Some(&raw))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientRegistrationError::InvalidToken(_)));
+
+        let (_, token) = service
+            .authorize_registration(tenant_id, Some(&raw))
+            .await
+            .unwrap();
+        assert_eq!(token.id, own_token.id);
+
+        let err = service
+            .authorize_registration(tenant_id, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientRegistrationError::InvalidToken(_)));
+    }
+
+    #[tokio::test]
+    async fn test_consume_exhausted_token() {
+        let mut mock = MockClientRegistrationRepository::new();
+        mock.expect_consume_initial_token().returning(|_| Ok(false));
+
+        let service = ClientRegistrationService::new(Arc::new(mock));
+        let token = initial_token(StringUuid::new_v4(), "dcr_iat_abcdefgh");
+        assert!(matches!(
+            service.consume_initial_token(&token).await.unwrap_err(),
+            ClientRegistrationError::InvalidToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_client_rejects_other_client() {
+        let tenant_id = StringUuid::new_v4();
+        let mut mock = MockClientRegistrationRepository::new();
+        mock.expect_find_registration_by_token_hash()
+            .returning(move |hash| {
+                Ok(Some(ClientRegistration {
+                    id: StringUuid::new_v4(),
+                    tenant_id,
+                    service_id: StringUuid::new_v4(),
+                    client_id: "client-a".to_string(),
+                    registration_token_hash: hash.to_string(),
+                    initial_token_id: None,
+                    software_id: None,
+                    metadata: request(),
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                }))
+            });
+
+        let service = ClientRegistrationService::new(Arc::new(mock));
+        assert!(service
+            .authenticate_client(tenant_id, "client-a", Some("dcr_rat_x"))
+            .await
+            .is_ok());
+        assert!(matches!(
+            service
+                .authenticate_client(tenant_id, "client-b", Some("dcr_rat_x"))
+                .await
+                .unwrap_err(),
+            ClientRegistrationError::InvalidToken(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_issue_initial_token_stores_hash_only() {
+        let tenant_id = StringUuid::new_v4();
+        let mut mock = MockClientRegistrationRepository::new();
+        mock.expect_create_initial_token()
+            .withf(|t| t.token_hash.len() == 64 && t.token_prefix.starts_with("dcr_iat_"))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = ClientRegistrationService::new(Arc::new(mock));
+        let created = service
+            .issue_initial_token(
+                tenant_id,
+                CreateInitialAccessTokenInput {
+                    description: Some("Partner onboarding".to_string()),
+                    expires_in_secs: Some(3600),
+                    max_uses: Some(5),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.token.token_hash, hash_token(&created.access_token));
+        assert!(created.token.expires_at.is_some());
+    }
+
+    #[test]
+    fn test_prepare_metadata_applies_defaults() {
+        let service =
+            ClientRegistrationService::new(Arc::new(MockClientRegistrationRepository::new()));
+        let metadata = service.prepare_metadata(&policy(true), request()).unwrap();
+        assert_eq!(metadata.grant_types, vec!["authorization_code"]);
+        assert_eq!(metadata.response_types, vec!["code"]);
+        assert_eq!(
+            metadata.token_endpoint_auth_method.as_deref(),
+            Some("client_secret_basic")
+        );
+    }
+
+    #[test]
+    fn test_normalize_metadata_rejections() {
+        let public_client = ClientMetadata {
+            token_endpoint_auth_method: Some("none".to_string()),
+            ..request()
+        };
+        assert!(matches!(
+            normalize_metadata(public_client).unwrap_err(),
+            ClientRegistrationError::InvalidClientMetadata(_)
+        ));
+
+        let insecure = ClientMetadata {
+            redirect_uris: vec!["http://partner.example.com/callback".to_string()],
+            ..request()
+        };
+        assert!(matches!(
+            normalize_metadata(insecure).unwrap_err(),
+            ClientRegistrationError::InvalidRedirectUri(_)
+        ));
+
+        let implicit = ClientMetadata {
+            response_types: vec!["token".to_string()],
+            ..request()
+        };
+        assert!(matches!(
+            normalize_metadata(implicit).unwrap_err(),
+            ClientRegistrationError::InvalidClientMetadata(_)
+        ));
+
+        // Machine-to-machine clients need no redirect URIs
+        let m2m = ClientMetadata {
+            grant_types: vec!["client_credentials".to_string()],
+            redirect_uris: vec![],
+            ..request()
+        };
+        let normalized = normalize_metadata(m2m).unwrap();
+        assert!(normalized.response_types.is_empty());
+    }
+
+    #[test]
+    fn test_software_statement_required_and_verified() {
+        use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+
+        let private_key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048).unwrap();
+        let private_pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let public_pem = rsa::RsaPublicKey::from(&private_key)
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let sign = |claims: Value| {
+            jsonwebtoken::encode(
+                &jsonwebtoken::Header::new(Algorithm::RS256),
+                &claims,
+                &jsonwebtoken::EncodingKey::from_rsa_pem(private_pem.as_bytes()).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let policy = ClientRegistrationPolicy {
+            require_software_statement: true,
+            software_statement_issuers: vec![SoftwareStatementIssuer {
+                issuer: "https://partners.example.com".to_string(),
+                public_key_pem: public_pem,
+            }],
+            ..policy(true)
+        };
+        let service =
+            ClientRegistrationService::new(Arc::new(MockClientRegistrationRepository::new()));
+
+        // Missing statement
+        assert!(matches!(
+            service.prepare_metadata(&policy, request()).unwrap_err(),
+            ClientRegistrationError::UnapprovedSoftwareStatement(_)
+        ));
+
+        // Trusted statement overrides request values
+        let statement = sign(serde_json::json!({
+            "iss": "https://partners.example.com",
+            "software_id": "partner-app",
+            "client_name": "Certified Partner App",
+        }));
+        let metadata = service
+            .prepare_metadata(
+                &policy,
+                ClientMetadata {
+                    software_statement: Some(statement),
+                    ..request()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            metadata.client_name.as_deref(),
+            Some("Certified Partner App")
+        );
+        assert_eq!(metadata.software_id.as_deref(), Some("partner-app"));
+
+        // Untrusted issuer
+        let statement = sign(serde_json::json!({ "iss": "https://evil.example.com" }));
+        assert!(matches!(
+            service
+                .prepare_metadata(
+                    &policy,
+                    ClientMetadata {
+                        software_statement: Some(statement),
+                        ..request()
+                    },
+                )
+                .unwrap_err(),
+            ClientRegistrationError::UnapprovedSoftwareStatement(_)
+        ));
+
+        // Garbage
+        assert!(matches!(
+            service
+                .prepare_metadata(
+                    &policy,
+                    ClientMetadata {
+                        software_statement: Some("not-a-jwt".to_string()),
+                        ..request()
+                    },
+                )
+                .unwrap_err(),
+            ClientRegistrationError::InvalidSoftwareStatement(_)
+        ));
+    }
+}
@@ -0,0 +1,490 @@
+//! External authorizer bridge
+//!
+//! Permission checks of a service can be delegated to an OPA sidecar or an
+//! OpenFGA server. The built-in RBAC result is always computed first: in
+//! `shadow` mode it decides and the external decision is only compared
+//! against it, in `enforce` mode the external decision decides and the
+//! service's fallback policy applies when the authorizer fails. External
+//! decisions are cached in-process for the configured TTL.
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::external_authz::{
+    ExternalAuthzFallback, ExternalAuthzMode, ExternalAuthzOutcome, ExternalAuthzProvider,
+    ExternalAuthzRequest, ServiceExternalAuthorizer, UpsertExternalAuthorizerInput,
+    DEFAULT_EXTERNAL_AUTHZ_CACHE_TTL_SECS, DEFAULT_EXTERNAL_AUTHZ_TIMEOUT_MS,
+};
+use crate::repository::external_authorizer::{
+    ExternalAuthorizerRepository, ExternalAuthorizerRepositoryImpl,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use lru::LruCache;
+use serde_json::{json, Value};
+use sqlx::MySqlPool;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use validator::Validate;
+
+const DECISION_CACHE_CAPACITY: usize = 10_000;
+
+type DecisionCache = Mutex<LruCache<String, (bool, Instant)>>;
+
+lazy_static::lazy_static! {
+    static ref SHARED_DECISION_CACHE: Arc<DecisionCache> = Arc::new(new_decision_cache());
+}
+
+fn new_decision_cache() -> DecisionCache {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(DECISION_CACHE_CAPACITY).expect("non-zero capacity"),
+    ))
+}
+
+/// Asks an external authorizer for a decision
+#[async_trait]
+pub trait ExternalAuthorizerClient: Send + Sync {
+    async fn check(
+        &self,
+        authorizer: &ServiceExternalAuthorizer,
+        request: &ExternalAuthzRequest,
+    ) -> Result<bool>;
+}
+
+/// Talks to OPA's data API and OpenFGA's check API over HTTP
+pub struct HttpExternalAuthorizerClient {
+    http_client: reqwest::Client,
+}
+
+impl HttpExternalAuthorizerClient {
+    pub fn new() -> Self {
+        Self {
+            http_client: reqwest::Client::builder()
+                .user_agent("Auth9-Core")
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn post(&self, url: &str, body: &Value) -> Result<Value> {
+        self.http_client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))?
+            .error_for_status()
+            .map_err(|e| AppError::Internal(e.into()))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+impl Default for HttpExternalAuthorizerClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// OpenFGA relation for a permission code. Relation names cannot contain
+/// `:`, so `report:export` becomes `report_export`.
+pub fn openfga_relation(permission: &str) -> String {
+    permission.replace([':', '#', '@'], "_")
+}
+
+#[async_trait]
+impl ExternalAuthorizerClient for HttpExternalAuthorizerClient {
+    async fn check(
+        &self,
+        authorizer: &ServiceExternalAuthorizer,
+        request: &ExternalAuthzRequest,
+    ) -> Result<bool> {
+        let endpoint = authorizer.endpoint.trim_end_matches('/');
+        match authorizer.authorizer_provider() {
+            // {"result": true} or {"result": {"allow": true}}
+            Some(ExternalAuthzProvider::Opa) => {
+                let path = authorizer.setting("policy_path").unwrap_or("auth9/allow");
+                let url = format!("{}/v1/data/{}", endpoint, path.trim_matches('/'));
+                let body = self.post(&url, &json!({ "input": request })).await?;
+                match &body["result"] {
+                    Value::Bool(allowed) => Ok(*allowed),
+                    Value::Object(result) => {
+                        Ok(result.get("allow").and_then(Value::as_bool) == Some(true))
+                    }
+                    // Undefined result: the policy did not match the input
+                    Value::Null => Ok(false),
+                    _ => Err(AppError::Internal(anyhow::anyhow!(
+                        "OPA result is neither a boolean nor an object with 'allow'"
+                    ))),
+                }
+            }
+            Some(ExternalAuthzProvider::Openfga) => {
+                let store_id = authorizer.setting("store_id").ok_or_else(|| {
+                    AppError::Internal(anyhow::anyhow!("OpenFGA authorizer has no store_id"))
+                })?;
+                let object_type = authorizer.setting("object_type").unwrap_or("tenant");
+                let mut body = json!({
+                    "tuple_key": {
+                        "user": format!("user:{}", request.user_id),
+                        "relation": openfga_relation(&request.permission),
+                        "object": format!("{}:{}", object_type, request.tenant_id),
+                    }
+                });
+                if let Some(model_id) = authorizer.setting("authorization_model_id") {
+                    body["authorization_model_id"] = json!(model_id);
+                }
+                let url = format!("{}/stores/{}/check", endpoint, store_id);
+                let response = self.post(&url, &body).await?;
+                response["allowed"].as_bool().ok_or_else(|| {
+                    AppError::Internal(anyhow::anyhow!("OpenFGA response has no 'allowed'"))
+                })
+            }
+            None => Err(AppError::Internal(anyhow::anyhow!(
+                "Unknown external authorizer provider '{}'",
+                authorizer.provider
+            ))),
+        }
+    }
+}
+
+pub struct ExternalAuthzService<R: ExternalAuthorizerRepository> {
+    repo: Arc<R>,
+    client: Arc<dyn ExternalAuthorizerClient>,
+    cache: Arc<DecisionCache>,
+}
+
+impl ExternalAuthzService<ExternalAuthorizerRepositoryImpl> {
+    /// Service over the process-wide decision cache
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self {
+            repo: Arc::new(ExternalAuthorizerRepositoryImpl::new(pool)),
+            client: Arc::new(HttpExternalAuthorizerClient::new()),
+            cache: SHARED_DECISION_CACHE.clone(),
+        }
+    }
+}
+
+impl<R: ExternalAuthorizerRepository> ExternalAuthzService<R> {
+    /// Service with its own decision cache
+    pub fn new(repo: Arc<R>, client: Arc<dyn ExternalAuthorizerClient>) -> Self {
+        Self {
+            repo,
+            client,
+            cache: Arc::new(new_decision_cache()),
+        }
+    }
+
+    pub async fn get(&self, service_id: StringUuid) -> Result<ServiceExternalAuthorizer> {
+        self.repo.find_by_service(service_id).await?.ok_or_else(|| {
+            AppError::NotFound("No external authorizer is configured for this service".into())
+        })
+    }
+
+    /// Configure the external authorizer of a service
+    pub async fn upsert(
+        &self,
+        service_id: StringUuid,
+        input: UpsertExternalAuthorizerInput,
+    ) -> Result<ServiceExternalAuthorizer> {
+        input.validate()?;
+        let settings = input.settings.unwrap_or_else(|| json!({}));
+        if input.provider == ExternalAuthzProvider::Openfga
+            && settings.get("store_id").and_then(Value::as_str).is_none()
+        {
+            return Err(AppError::Validation(
+                "openfga authorizer requires a 'store_id' setting".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let authorizer = ServiceExternalAuthorizer {
+            service_id,
+            provider: input.provider.as_str().to_string(),
+            endpoint: input.endpoint,
+            settings,
+            mode: input.mode.unwrap_or_default().as_str().to_string(),
+            fallback: input.fallback.unwrap_or_default().as_str().to_string(),
+            timeout_ms: input
+                .timeout_ms
+                .unwrap_or(DEFAULT_EXTERNAL_AUTHZ_TIMEOUT_MS),
+            cache_ttl_secs: input
+                .cache_ttl_secs
+                .unwrap_or(DEFAULT_EXTERNAL_AUTHZ_CACHE_TTL_SECS),
+            enabled: input.enabled.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.upsert(&authorizer).await?;
+        self.clear_cache();
+        self.get(service_id).await
+    }
+
+    pub async fn delete(&self, service_id: StringUuid) -> Result<()> {
+        self.repo.delete(service_id).await?;
+        self.clear_cache();
+        Ok(())
+    }
+
+    fn clear_cache(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.clear();
+        }
+    }
+
+    /// Combine the built-in RBAC decision with the service's external
+    /// authorizer. Returns the final decision and, when the service has an
+    /// enabled authorizer, how it took part.
+    pub async fn decide(
+        &self,
+        request: &ExternalAuthzRequest,
+        local_allowed: bool,
+    ) -> Result<(bool, Option<ExternalAuthzOutcome>)> {
+        let Some(authorizer) = self
+            .repo
+            .find_by_service(StringUuid::from(request.service_id))
+            .await?
+            .filter(|a| a.enabled)
+        else {
+            return Ok((local_allowed, None));
+        };
+        let Some(provider) = authorizer.authorizer_provider() else {
+            return Ok((local_allowed, None));
+        };
+        let mode = authorizer.authorizer_mode();
+
+        let (external, cached) = self.external_decision(&authorizer, request).await;
+        let result = match external {
+            Some(true) => "allow",
+            Some(false) => "deny",
+            None => "error",
+        };
+        metrics::counter!(
+            "auth9_external_authz_checks_total",
+            "provider" => provider.as_str(),
+            "result" => result,
+            "cached" => if cached { "true" } else { "false" }
+        )
+        .increment(1);
+
+        // Shadow comparison against the built-in RBAC result
+        let comparison = match external {
+            Some(allowed) if allowed == local_allowed => "match",
+            Some(_) => "mismatch",
+            None => "error",
+        };
+        metrics::counter!(
+            "auth9_external_authz_shadow_comparisons_total",
+            "service_id" => request.service_id.to_string(),
+            "mode" => mode.as_str(),
+            "outcome" => comparison
+        )
+        .increment(1);
+        if comparison == "mismatch" {
+            tracing::info!(
+                service_id = %request.service_id,
+                user_id = %request.user_id,
+                permission = %request.permission,
+                local_allowed,
+                external_allowed = !local_allowed,
+                "External authorizer disagrees with built-in RBAC"
+            );
+        }
+
+        let (allowed, fallback_applied) = match (mode, external) {
+            (ExternalAuthzMode::Shadow, _) => (local_allowed, false),
+            (ExternalAuthzMode::Enforce, Some(allowed)) => (allowed, false),
+            (ExternalAuthzMode::Enforce, None) => match authorizer.authorizer_fallback() {
+                ExternalAuthzFallback::Local => (local_allowed, true),
+                ExternalAuthzFallback::Deny => (false, true),
+            },
+        };
+        Ok((
+            allowed,
+            Some(ExternalAuthzOutcome {
+                provider,
+                mode,
+                external_allowed: external,
+                local_allowed,
+                cached,
+                fallback_applied,
+            }),
+        ))
+    }
+
+    /// External decision (from cache when fresh) and whether it was cached.
+    /// `None` when the authorizer failed or timed out.
+    async fn external_decision(
+        &self,
+        authorizer: &ServiceExternalAuthorizer,
+        request: &ExternalAuthzRequest,
+    ) -> (Option<bool>, bool) {
+        let ttl = Duration::from_secs(u64::from(authorizer.cache_ttl_secs));
+        let key = format!(
+            "{}:{}:{}:{}",
+            request.service_id, request.tenant_id, request.user_id, request.permission
+        );
+        if !ttl.is_zero() {
+            if let Ok(mut cache) = self.cache.lock() {
+                if let Some((allowed, cached_at)) = cache.get(&key) {
+                    if cached_at.elapsed() < ttl {
+                        return (Some(*allowed), true);
+                    }
+                }
+            }
+        }
+
+        let outcome = tokio::time::timeout(
+            Duration::from_millis(u64::from(authorizer.timeout_ms)),
+            self.client.check(authorizer, request),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(AppError::Internal(anyhow::anyhow!(
+                "timed out after {}ms",
+                authorizer.timeout_ms
+            )))
+        });
+        match outcome {
+            Ok(allowed) => {
+                if !ttl.is_zero() {
+                    if let Ok(mut cache) = self.cache.lock() {
+                        cache.put(key, (allowed, Instant::now()));
+                    }
+                }
+                (Some(allowed), false)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    service_id = %request.service_id,
+                    provider = %authorizer.provider,
+                    error = %e,
+                    "External authorizer check failed"
+                );
+                (None, false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::{ServiceId, TenantId, UserId};
+    use crate::repository::external_authorizer::MockExternalAuthorizerRepository;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answers with a fixed decision (or fails) and counts calls
+    struct FixedClient {
+        answer: Option<bool>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ExternalAuthorizerClient for FixedClient {
+        async fn check(
+            &self,
+            _authorizer: &ServiceExternalAuthorizer,
+            _request: &ExternalAuthzRequest,
+        ) -> Result<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.answer
+                .ok_or_else(|| AppError::Internal(anyhow::anyhow!("connection refused")))
+        }
+    }
+
+    fn authorizer(mode: &str, fallback: &str, cache_ttl_secs: u32) -> ServiceExternalAuthorizer {
+        ServiceExternalAuthorizer {
+            service_id: StringUuid::new_v4(),
+            provider: "opa".to_string(),
+            endpoint: "http://localhost:8181".to_string(),
+            settings: json!({}),
+            mode: mode.to_string(),
+            fallback: fallback.to_string(),
+            timeout_ms: 50,
+            cache_ttl_secs,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn request() -> ExternalAuthzRequest {
+        ExternalAuthzRequest {
+            user_id: UserId::new_v4(),
+            tenant_id: TenantId::new_v4(),
+            service_id: ServiceId::new_v4(),
+            permission: "report:export".to_string(),
+        }
+    }
+
+    fn service_with(
+        config: Option<ServiceExternalAuthorizer>,
+        answer: Option<bool>,
+    ) -> (
+        ExternalAuthzService<MockExternalAuthorizerRepository>,
+        Arc<FixedClient>,
+    ) {
+        let mut repo = MockExternalAuthorizerRepository::new();
+        repo.expect_find_by_service()
+            .returning(move |_| Ok(config.clone()));
+        let client = Arc::new(FixedClient {
+            answer,
+            calls: AtomicUsize::new(0),
+        });
+        let service = ExternalAuthzService::new(Arc::new(repo), client.clone());
+        (service, client)
+    }
+
+    #[tokio::test]
+    async fn test_without_authorizer_local_decides() {
+        let (service, client) = service_with(None, Some(false));
+        let (allowed, outcome) = service.decide(&request(), true).await.unwrap();
+        assert!(allowed);
+        assert!(outcome.is_none());
+        assert_eq!(client.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shadow_mode_keeps_local_decision() {
+        let (service, _) = service_with(Some(authorizer("shadow", "local", 30)), Some(false));
+        let (allowed, outcome) = service.decide(&request(), true).await.unwrap();
+        assert!(allowed);
+        let outcome = outcome.unwrap();
+        assert_eq!(outcome.external_allowed, Some(false));
+        assert!(outcome.local_allowed);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_mode_uses_cached_external_decision() {
+        let (service, client) = service_with(Some(authorizer("enforce", "local", 30)), Some(false));
+        let request = request();
+        let (allowed, _) = service.decide(&request, true).await.unwrap();
+        assert!(!allowed);
+        let (allowed, outcome) = service.decide(&request, true).await.unwrap();
+        assert!(!allowed);
+        assert!(outcome.unwrap().cached);
+        assert_eq!(client.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_mode_fallback_on_failure() {
+        let (service, _) = service_with(Some(authorizer("enforce", "local", 0)), None);
+        let (allowed, outcome) = service.decide(&request(), true).await.unwrap();
+        assert!(allowed);
+        assert!(outcome.unwrap().fallback_applied);
+
+        let (service, _) = service_with(Some(authorizer("enforce", "deny", 0)), None);
+        let (allowed, _) = service.decide(&request(), true).await.unwrap();
+        assert!(!allowed);
+    }
+
+    #[test]
+    fn test_openfga_relation_strips_separators() {
+        assert_eq!(openfga_relation("report:export"), "report_export");
+        assert_eq!(
+            openfga_relation("billing:invoice:read"),
+            "billing_invoice_read"
+        );
+    }
+}
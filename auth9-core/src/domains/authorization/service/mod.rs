@@ -1,7 +1,18 @@
 pub mod abac;
 pub mod client;
+pub mod client_registration;
+pub mod external_authz;
 pub mod rbac;
+pub mod role_preset;
+pub mod sagas;
+pub mod scope_catalog;
+pub mod token_validation;
 
 pub use abac::AbacPolicyService;
 pub use client::ClientService;
+pub use client_registration::ClientRegistrationService;
+pub use external_authz::ExternalAuthzService;
 pub use rbac::RbacService;
+pub use role_preset::RolePresetService;
+pub use scope_catalog::ScopeCatalogService;
+pub use token_validation::{TokenValidationPolicies, TokenValidationProfileService};
@@ -1,19 +1,30 @@
 //! RBAC business logic
 
 use crate::cache::CacheManager;
+use crate::config::CardinalityConfig;
+use crate::domains::events::{DomainEvent, EventBus};
 use crate::error::{AppError, Result};
-use crate::models::common::StringUuid;
+use crate::models::cardinality::CardinalityCap;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
 use crate::models::rbac::{
-    AssignRolesInput, CreatePermissionInput, CreateRoleInput, Permission, Role,
-    RoleWithPermissions, UpdateRoleInput, UserRolesInTenant,
+    build_role_tree, namespaced_permission_code, permission_matches, validate_permission_code,
+    AssignRolesInput, CheckPermissionInput, CreatePermissionInput, CreateRoleInput, Permission,
+    PermissionCheckResult, PermissionCheckStep, PermissionNamespace, Role, RoleTree,
+    RoleWithPermissions, SetPermissionNamespaceInput, UpdateRoleInput, UserRolesInTenant,
+    RESERVED_ROLE_NAMES,
 };
+use crate::models::role_preset::{AppliedRolePreset, RolePreset};
 use crate::repository::RbacRepository;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use validator::Validate;
 
 pub struct RbacService<R: RbacRepository> {
     repo: Arc<R>,
     cache_manager: Option<CacheManager>,
+    /// Role assignment and permission caps (not enforced when unset)
+    cardinality: Option<CardinalityConfig>,
+    event_bus: Option<Arc<EventBus>>,
 }
 
 impl<R: RbacRepository> RbacService<R> {
@@ -21,13 +32,38 @@ impl<R: RbacRepository> RbacService<R> {
         Self {
             repo,
             cache_manager,
+            cardinality: None,
+            event_bus: None,
         }
     }
 
+    /// Enforce caps on roles per user and permissions per role
+    pub fn with_cardinality_limits(mut self, config: CardinalityConfig) -> Self {
+        self.cardinality = Some(config);
+        self
+    }
+
+    /// Publish role assignment events to the domain event bus, which then
+    /// owns invalidating the affected role cache
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     // ==================== Permissions ====================
 
-    pub async fn create_permission(&self, input: CreatePermissionInput) -> Result<Permission> {
+    pub async fn create_permission(&self, mut input: CreatePermissionInput) -> Result<Permission> {
         input.validate()?;
+        let service_id = StringUuid::from(input.service_id);
+        if let Some(namespace) = self.repo.find_permission_namespace(service_id).await? {
+            input.code = namespaced_permission_code(Some(&namespace), &input.code);
+            if validate_permission_code(&input.code).is_err() || input.code.len() > 100 {
+                return Err(AppError::Validation(format!(
+                    "Permission code '{}' must be 'resource:action' within namespace '{}'",
+                    input.code, namespace
+                )));
+            }
+        }
         let permission = self.repo.create_permission(&input).await.map_err(|e| {
             // Convert database unique constraint error to user-friendly message
             if let AppError::Database(ref db_err) = e {
@@ -47,6 +83,33 @@ impl<R: RbacRepository> RbacService<R> {
         Ok(permission)
     }
 
+    pub async fn get_permission_namespace(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<PermissionNamespace> {
+        Ok(PermissionNamespace {
+            service_id,
+            namespace: self.repo.find_permission_namespace(service_id).await?,
+        })
+    }
+
+    /// Set the namespace for new permission codes. Existing codes are left
+    /// unchanged so tokens already issued keep working.
+    pub async fn set_permission_namespace(
+        &self,
+        service_id: StringUuid,
+        input: SetPermissionNamespaceInput,
+    ) -> Result<PermissionNamespace> {
+        input.validate()?;
+        self.repo
+            .set_permission_namespace(service_id, input.namespace.clone())
+            .await?;
+        Ok(PermissionNamespace {
+            service_id,
+            namespace: input.namespace,
+        })
+    }
+
     pub async fn get_permission(&self, id: StringUuid) -> Result<Permission> {
         self.repo
             .find_permission_by_id(id)
@@ -59,8 +122,11 @@ impl<R: RbacRepository> RbacService<R> {
     }
 
     pub async fn delete_permission(&self, id: StringUuid) -> Result<()> {
-        let _ = self.get_permission(id).await?;
+        let permission = self.get_permission(id).await?;
         self.repo.delete_permission(id).await?;
+        if let Some(cache) = &self.cache_manager {
+            let _ = cache.invalidate_role_tree(*permission.service_id).await;
+        }
         // Note: We don't invalidate user role cache when a permission is deleted.
         // Cached roles remain valid - the deleted permission simply won't be available.
         // Cache will naturally expire after TTL (5 minutes), and future queries will
@@ -70,12 +136,10 @@ impl<R: RbacRepository> RbacService<R> {
 
     // ==================== Roles ====================
 
-    /// System-reserved role names that cannot be used for custom RBAC roles.
-    const RESERVED_ROLE_NAMES: &[&str] = &["platform_admin", "owner", "admin", "member"];
-
+    /// Reject system-reserved role names for custom RBAC roles.
     fn check_reserved_role_name(name: &str) -> Result<()> {
         let normalized = name.trim().to_lowercase();
-        if Self::RESERVED_ROLE_NAMES.contains(&normalized.as_str()) {
+        if RESERVED_ROLE_NAMES.contains(&normalized.as_str()) {
             return Err(AppError::BadRequest(format!(
                 "Reserved role name: '{}' is reserved for system use",
                 name
@@ -90,8 +154,11 @@ impl<R: RbacRepository> RbacService<R> {
 
         // Check inheritance depth if a parent role is specified
         if let Some(parent_id) = input.parent_role_id {
-            self.check_parent_chain_depth(StringUuid::from(parent_id))
-                .await?;
+            self.check_parent_chain_depth(
+                StringUuid::from(input.service_id),
+                StringUuid::from(parent_id),
+            )
+            .await?;
         }
 
         // Validate that all permission_ids belong to the same service as the role
@@ -108,10 +175,24 @@ impl<R: RbacRepository> RbacService<R> {
             }
         }
 
-        let role = self.repo.create_role(&input).await?;
-        if let Some(cache) = &self.cache_manager {
-            let _ = cache.invalidate_all_user_roles().await;
+        if let (Some(limits), Some(permission_ids)) = (&self.cardinality, &input.permission_ids) {
+            let service_id = StringUuid::from(input.service_id);
+            let requested: HashSet<_> = permission_ids.iter().collect();
+            let overrides = self
+                .repo
+                .find_service_cardinality_limits(service_id)
+                .await?;
+            limits.check(
+                CardinalityCap::PermissionsPerRole,
+                overrides.as_ref(),
+                service_id,
+                0,
+                requested.len() as u64,
+            )?;
         }
+
+        let role = self.repo.create_role(&input).await?;
+        self.invalidate_role_caches(role.service_id).await;
         Ok(role)
     }
 
@@ -132,12 +213,55 @@ impl<R: RbacRepository> RbacService<R> {
         self.repo.find_roles_by_service(service_id).await
     }
 
-    pub async fn update_role(&self, id: StringUuid, input: UpdateRoleInput) -> Result<Role> {
+    /// Role hierarchy of a service with each role's effective permissions.
+    /// Cached per service and invalidated whenever roles or their permissions change.
+    pub async fn get_role_tree(&self, service_id: StringUuid) -> Result<RoleTree> {
+        if let Some(cache) = &self.cache_manager {
+            if let Ok(Some(tree)) = cache.get_role_tree(*service_id).await {
+                return Ok(tree);
+            }
+        }
+
+        let roles = self.repo.find_roles_by_service(service_id).await?;
+        let mut direct_permissions = HashMap::with_capacity(roles.len());
+        for role in &roles {
+            let codes = self
+                .repo
+                .find_role_permissions(role.id)
+                .await?
+                .into_iter()
+                .map(|permission| permission.code)
+                .collect::<Vec<_>>();
+            direct_permissions.insert(role.id, codes);
+        }
+        let tree = build_role_tree(service_id, &roles, &direct_permissions);
+
+        if let Some(cache) = &self.cache_manager {
+            let _ = cache.set_role_tree(&tree).await;
+        }
+        Ok(tree)
+    }
+
+    async fn invalidate_role_caches(&self, service_id: StringUuid) {
+        if let Some(cache) = &self.cache_manager {
+            let _ = cache.invalidate_all_user_roles().await;
+            let _ = cache.invalidate_role_tree(*service_id).await;
+        }
+    }
+
+    /// Update a role. `expected_version` (from `If-Match`) rejects the write
+    /// with a version conflict if the role changed in the meantime.
+    pub async fn update_role(
+        &self,
+        id: StringUuid,
+        input: UpdateRoleInput,
+        expected_version: Option<i32>,
+    ) -> Result<Role> {
         input.validate()?;
         if let Some(ref name) = input.name {
             Self::check_reserved_role_name(name)?;
         }
-        let _ = self.get_role(id).await?;
+        let existing = self.get_role(id).await?;
 
         // Check for circular inheritance if parent_role_id is being updated
         // input.parent_role_id is Option<Option<Uuid>>:
@@ -146,13 +270,12 @@ impl<R: RbacRepository> RbacService<R> {
         // - None = not provided, keep existing
         if let Some(Some(parent_id)) = input.parent_role_id {
             let parent_uuid = StringUuid::from(parent_id);
-            self.check_circular_inheritance(id, parent_uuid).await?;
+            self.check_circular_inheritance(existing.service_id, id, parent_uuid)
+                .await?;
         }
 
-        let role = self.repo.update_role(id, &input).await?;
-        if let Some(cache) = &self.cache_manager {
-            let _ = cache.invalidate_all_user_roles().await;
-        }
+        let role = self.repo.update_role(id, &input, expected_version).await?;
+        self.invalidate_role_caches(role.service_id).await;
         Ok(role)
     }
 
@@ -160,10 +283,12 @@ impl<R: RbacRepository> RbacService<R> {
     const MAX_ROLE_INHERITANCE_DEPTH: usize = 10;
 
     /// Check for circular inheritance by traversing the parent chain.
-    /// Returns error if setting `new_parent_id` as parent of `role_id` would create a cycle
-    /// or if the resulting chain would exceed the maximum depth limit.
+    /// Returns error if setting `new_parent_id` as parent of `role_id` would create a cycle,
+    /// if the resulting chain would exceed the maximum depth limit, or if the parent
+    /// belongs to another service.
     async fn check_circular_inheritance(
         &self,
+        service_id: StringUuid,
         role_id: StringUuid,
         new_parent_id: StringUuid,
     ) -> Result<()> {
@@ -180,6 +305,7 @@ impl<R: RbacRepository> RbacService<R> {
         let mut visited = std::collections::HashSet::new();
         visited.insert(role_id); // The role we're updating
         let mut depth: usize = 1; // Start at 1 since we already have role_id -> new_parent_id
+        let mut parent_service_id = None;
 
         while let Some(parent_id) = current_id {
             if visited.contains(&parent_id) {
@@ -201,6 +327,7 @@ impl<R: RbacRepository> RbacService<R> {
             // Get the parent role to find its parent
             match self.repo.find_role_by_id(parent_id).await? {
                 Some(parent_role) => {
+                    parent_service_id.get_or_insert(parent_role.service_id);
                     current_id = parent_role.parent_role_id;
                 }
                 None => {
@@ -213,16 +340,37 @@ impl<R: RbacRepository> RbacService<R> {
             }
         }
 
-        Ok(())
+        Self::check_parent_service(service_id, parent_service_id)
+    }
+
+    /// A parent role must belong to the same service as its child.
+    fn check_parent_service(
+        service_id: StringUuid,
+        parent_service_id: Option<StringUuid>,
+    ) -> Result<()> {
+        match parent_service_id {
+            Some(parent_service_id) if parent_service_id != service_id => {
+                Err(AppError::BadRequest(format!(
+                    "Parent role belongs to service {}, not {}",
+                    parent_service_id, service_id
+                )))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Check that the parent chain depth from `parent_id` upward does not exceed
-    /// the maximum allowed depth. Used during role creation where the new role
-    /// does not yet have an ID.
-    async fn check_parent_chain_depth(&self, parent_id: StringUuid) -> Result<()> {
+    /// the maximum allowed depth and that the parent belongs to `service_id`.
+    /// Used during role creation where the new role does not yet have an ID.
+    async fn check_parent_chain_depth(
+        &self,
+        service_id: StringUuid,
+        parent_id: StringUuid,
+    ) -> Result<()> {
         let mut current_id = Some(parent_id);
         let mut depth: usize = 1; // The new role itself counts as depth 1
         let mut visited = std::collections::HashSet::new();
+        let mut parent_service_id = None;
 
         while let Some(id) = current_id {
             depth += 1;
@@ -241,6 +389,7 @@ impl<R: RbacRepository> RbacService<R> {
 
             match self.repo.find_role_by_id(id).await? {
                 Some(role) => {
+                    parent_service_id.get_or_insert(role.service_id);
                     current_id = role.parent_role_id;
                 }
                 None => {
@@ -249,7 +398,7 @@ impl<R: RbacRepository> RbacService<R> {
             }
         }
 
-        Ok(())
+        Self::check_parent_service(service_id, parent_service_id)
     }
 
     /// Delete a role with cascade handling.
@@ -259,7 +408,7 @@ impl<R: RbacRepository> RbacService<R> {
     /// 2. Delete role (repository handles role_permissions and user_tenant_roles)
     /// 3. Invalidate cache
     pub async fn delete_role(&self, id: StringUuid) -> Result<()> {
-        let _ = self.get_role(id).await?;
+        let role = self.get_role(id).await?;
 
         // 1. Clear parent_role_id references from other roles
         self.repo.clear_parent_role_reference_by_id(id).await?;
@@ -268,12 +417,61 @@ impl<R: RbacRepository> RbacService<R> {
         self.repo.delete_role(id).await?;
 
         // 3. Invalidate cache
-        if let Some(cache) = &self.cache_manager {
-            let _ = cache.invalidate_all_user_roles().await;
-        }
+        self.invalidate_role_caches(role.service_id).await;
         Ok(())
     }
 
+    /// Create a preset's permissions and roles in a service. The roles are
+    /// copies: later versions of the preset don't change them.
+    pub async fn apply_role_preset(
+        &self,
+        service_id: StringUuid,
+        preset: &RolePreset,
+    ) -> Result<AppliedRolePreset> {
+        // Codes may be namespaced on creation, so grants resolve through ids
+        let mut permission_ids = Vec::with_capacity(preset.definition.permissions.len());
+        let mut ids_by_code = HashMap::new();
+        for permission in &preset.definition.permissions {
+            let created = self
+                .create_permission(CreatePermissionInput {
+                    service_id: service_id.into(),
+                    code: permission.code.clone(),
+                    name: permission.name.clone(),
+                    description: permission.description.clone(),
+                })
+                .await?;
+            ids_by_code.insert(permission.code.as_str(), created.id);
+            permission_ids.push(created.id);
+        }
+
+        let mut role_ids = Vec::with_capacity(preset.definition.roles.len());
+        for role in &preset.definition.roles {
+            let created = self
+                .create_role(CreateRoleInput {
+                    service_id: service_id.into(),
+                    name: role.name.clone(),
+                    description: role.description.clone(),
+                    parent_role_id: None,
+                    permission_ids: Some(
+                        role.permissions
+                            .iter()
+                            .filter_map(|code| ids_by_code.get(code.as_str()))
+                            .map(|id| (*id).into())
+                            .collect(),
+                    ),
+                })
+                .await?;
+            role_ids.push(created.id);
+        }
+
+        Ok(AppliedRolePreset {
+            key: preset.key.clone(),
+            version: preset.version,
+            permission_ids,
+            role_ids,
+        })
+    }
+
     // ==================== Role-Permission ====================
 
     pub async fn assign_permission_to_role(
@@ -292,12 +490,27 @@ impl<R: RbacRepository> RbacService<R> {
             )));
         }
 
+        if let Some(limits) = &self.cardinality {
+            let current = self.repo.find_role_permissions(role_id).await?;
+            if !current.iter().any(|p| p.id == permission_id) {
+                let overrides = self
+                    .repo
+                    .find_service_cardinality_limits(role.service_id)
+                    .await?;
+                limits.check(
+                    CardinalityCap::PermissionsPerRole,
+                    overrides.as_ref(),
+                    role_id,
+                    current.len() as u64,
+                    current.len() as u64 + 1,
+                )?;
+            }
+        }
+
         self.repo
             .assign_permission_to_role(role_id, permission_id)
             .await?;
-        if let Some(cache) = &self.cache_manager {
-            let _ = cache.invalidate_all_user_roles().await;
-        }
+        self.invalidate_role_caches(role.service_id).await;
         Ok(())
     }
 
@@ -311,6 +524,9 @@ impl<R: RbacRepository> RbacService<R> {
             .await?;
         if let Some(cache) = &self.cache_manager {
             let _ = cache.invalidate_all_user_roles().await;
+            if let Ok(Some(role)) = self.repo.find_role_by_id(role_id).await {
+                let _ = cache.invalidate_role_tree(*role.service_id).await;
+            }
         }
         Ok(())
     }
@@ -323,8 +539,24 @@ impl<R: RbacRepository> RbacService<R> {
         granted_by: Option<StringUuid>,
     ) -> Result<()> {
         input.validate()?;
+        if let Some(limits) = &self.cardinality {
+            self.check_roles_per_user(limits, &input).await?;
+        }
         self.repo.assign_roles_to_user(&input, granted_by).await?;
-        if let Some(cache) = &self.cache_manager {
+        if let Some(bus) = &self.event_bus {
+            bus.publish(DomainEvent::RoleAssigned {
+                tenant_id: input.tenant_id.into(),
+                user_id: input.user_id.into(),
+                role_ids: input
+                    .role_ids
+                    .iter()
+                    .copied()
+                    .map(StringUuid::from)
+                    .collect(),
+                granted_by,
+            })
+            .await;
+        } else if let Some(cache) = &self.cache_manager {
             let _ = cache
                 .invalidate_user_roles_for_tenant(input.user_id, input.tenant_id)
                 .await;
@@ -332,10 +564,50 @@ impl<R: RbacRepository> RbacService<R> {
         Ok(())
     }
 
+    /// Count the user's roles after an assignment. Assignments replace the
+    /// user's roles in every service the new roles belong to, so only roles
+    /// of other services are kept.
+    async fn check_roles_per_user(
+        &self,
+        limits: &CardinalityConfig,
+        input: &AssignRolesInput,
+    ) -> Result<()> {
+        let requested: HashSet<StringUuid> = input
+            .role_ids
+            .iter()
+            .map(|id| StringUuid::from(*id))
+            .collect();
+        let mut replaced_services: HashSet<StringUuid> =
+            input.service_id.map(StringUuid::from).into_iter().collect();
+        for role_id in &requested {
+            if let Some(role) = self.repo.find_role_by_id(*role_id).await? {
+                replaced_services.insert(role.service_id);
+            }
+        }
+
+        let tenant_id = TenantId::from(input.tenant_id);
+        let current = self
+            .repo
+            .find_user_role_records_in_tenant(UserId::from(input.user_id), tenant_id, None)
+            .await?;
+        let kept = current
+            .iter()
+            .filter(|role| !replaced_services.contains(&role.service_id))
+            .count();
+        let overrides = self.repo.find_tenant_cardinality_limits(tenant_id).await?;
+        limits.check(
+            CardinalityCap::RolesPerUser,
+            overrides.as_ref(),
+            StringUuid::from(input.user_id),
+            current.len() as u64,
+            (kept + requested.len()) as u64,
+        )
+    }
+
     pub async fn get_user_roles(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<UserRolesInTenant> {
         self.repo
             .find_user_roles_in_tenant(user_id, tenant_id)
@@ -344,19 +616,106 @@ impl<R: RbacRepository> RbacService<R> {
 
     pub async fn get_user_roles_for_service(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> Result<UserRolesInTenant> {
         self.repo
             .find_user_roles_in_tenant_for_service(user_id, tenant_id, service_id)
             .await
     }
 
+    /// Check whether a user holds a permission in a tenant.
+    ///
+    /// Without `explain` this resolves the user's effective permissions like
+    /// token issuance does. With `explain` every assigned role and the roles
+    /// it inherits are walked so the result names which role granted the
+    /// permission, or why none did.
+    pub async fn check_permission(
+        &self,
+        user_id: UserId,
+        tenant_id: TenantId,
+        input: &CheckPermissionInput,
+        explain: bool,
+    ) -> Result<PermissionCheckResult> {
+        input.validate()?;
+        let service_id = input.service_id.map(ServiceId::from);
+        let member = self
+            .repo
+            .find_tenant_user_id(user_id, tenant_id)
+            .await?
+            .is_some();
+
+        if !explain {
+            let allowed = member && {
+                let roles = match service_id {
+                    Some(service_id) => {
+                        self.get_user_roles_for_service(user_id, tenant_id, service_id)
+                            .await?
+                    }
+                    None => self.get_user_roles(user_id, tenant_id).await?,
+                };
+                roles
+                    .permissions
+                    .iter()
+                    .any(|granted| permission_matches(granted, &input.permission))
+            };
+            return Ok(PermissionCheckResult {
+                allowed,
+                permission: input.permission.clone(),
+                explanation: None,
+                external: None,
+            });
+        }
+
+        let mut steps = Vec::new();
+        if member {
+            let assigned = self
+                .repo
+                .find_user_role_records_in_tenant(user_id, tenant_id, service_id)
+                .await?;
+            let mut visited: HashSet<StringUuid> = assigned.iter().map(|r| r.id).collect();
+            // Assigned roles first, then their ancestors breadth-first
+            let mut queue: VecDeque<(Role, Vec<String>)> =
+                assigned.into_iter().map(|role| (role, vec![])).collect();
+            while let Some((role, inherited_via)) = queue.pop_front() {
+                if let Some(parent_id) = role.parent_role_id {
+                    if visited.insert(parent_id) {
+                        if let Some(parent) = self.repo.find_role_by_id(parent_id).await? {
+                            let mut via = inherited_via.clone();
+                            via.push(role.name.clone());
+                            queue.push_back((parent, via));
+                        }
+                    }
+                }
+                let matched_permissions = self
+                    .repo
+                    .find_role_permissions(role.id)
+                    .await?
+                    .into_iter()
+                    .map(|p| p.code)
+                    .filter(|code| permission_matches(code, &input.permission))
+                    .collect();
+                steps.push(PermissionCheckStep {
+                    role_id: role.id,
+                    role: role.name,
+                    service_id: role.service_id,
+                    inherited_via,
+                    matched_permissions,
+                });
+            }
+        }
+        Ok(PermissionCheckResult::explained(
+            &input.permission,
+            member,
+            steps,
+        ))
+    }
+
     pub async fn ensure_tenant_membership(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<()> {
         let membership = self.repo.find_tenant_user_id(user_id, tenant_id).await?;
         if membership.is_none() {
@@ -369,8 +728,8 @@ impl<R: RbacRepository> RbacService<R> {
 
     pub async fn get_user_role_records(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<Vec<Role>> {
         self.repo
             .find_user_role_records_in_tenant(user_id, tenant_id, None)
@@ -380,8 +739,8 @@ impl<R: RbacRepository> RbacService<R> {
     /// Remove role from user in tenant
     pub async fn unassign_role(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
         role_id: StringUuid,
     ) -> Result<()> {
         // The repository method needs tenant_user_id, so we need to look it up
@@ -407,7 +766,8 @@ impl<R: RbacRepository> RbacService<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::rbac::{Permission, Role};
+    use crate::models::cardinality::CardinalityLimits;
+    use crate::models::rbac::{Permission, PermissionCheckReason, Role};
     use crate::repository::rbac::MockRbacRepository;
     use mockall::predicate::*;
     use uuid::Uuid;
@@ -419,6 +779,9 @@ mod tests {
         let mut mock = MockRbacRepository::new();
         let service_id = Uuid::new_v4();
 
+        mock.expect_find_permission_namespace()
+            .returning(|_| Ok(None));
+
         mock.expect_create_permission().returning(|input| {
             Ok(Permission {
                 service_id: StringUuid::from(input.service_id),
@@ -443,6 +806,75 @@ mod tests {
         assert_eq!(result.unwrap().code, "user:read");
     }
 
+    #[tokio::test]
+    async fn test_create_permission_applies_service_namespace() {
+        let mut mock = MockRbacRepository::new();
+        let service_id = Uuid::new_v4();
+
+        mock.expect_find_permission_namespace()
+            .returning(|_| Ok(Some("billing".to_string())));
+        mock.expect_create_permission()
+            .withf(|input| input.code == "billing:invoices:read")
+            .returning(|input| {
+                Ok(Permission {
+                    service_id: StringUuid::from(input.service_id),
+                    code: input.code.clone(),
+                    name: input.name.clone(),
+                    ..Default::default()
+                })
+            });
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let input = CreatePermissionInput {
+            service_id,
+            code: "invoices:read".to_string(),
+            name: "Read Invoices".to_string(),
+            description: None,
+        };
+
+        let result = service.create_permission(input).await.unwrap();
+        assert_eq!(result.code, "billing:invoices:read");
+    }
+
+    #[tokio::test]
+    async fn test_create_permission_rejects_code_too_deep_for_namespace() {
+        let mut mock = MockRbacRepository::new();
+        mock.expect_find_permission_namespace()
+            .returning(|_| Ok(Some("billing".to_string())));
+        mock.expect_create_permission().never();
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let input = CreatePermissionInput {
+            service_id: Uuid::new_v4(),
+            code: "report:export:pdf".to_string(),
+            name: "Export".to_string(),
+            description: None,
+        };
+
+        let result = service.create_permission(input).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_permission_namespace_rejects_invalid() {
+        let mut mock = MockRbacRepository::new();
+        mock.expect_set_permission_namespace().never();
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let result = service
+            .set_permission_namespace(
+                StringUuid::new_v4(),
+                SetPermissionNamespaceInput {
+                    namespace: Some("Billing".to_string()),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
     #[tokio::test]
     async fn test_create_permission_invalid_code() {
         let mock = MockRbacRepository::new();
@@ -711,7 +1143,7 @@ mod tests {
             .with(eq(id))
             .returning(move |_| Ok(Some(role_clone.clone())));
 
-        mock.expect_update_role().returning(|_, input| {
+        mock.expect_update_role().returning(|_, input, _| {
             Ok(Role {
                 name: input.name.clone().unwrap_or_default(),
                 ..Default::default()
@@ -726,7 +1158,7 @@ mod tests {
             parent_role_id: None,
         };
 
-        let result = service.update_role(id, input).await;
+        let result = service.update_role(id, input, None).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().name, "Super Admin");
     }
@@ -748,7 +1180,7 @@ mod tests {
             parent_role_id: None,
         };
 
-        let result = service.update_role(id, input).await;
+        let result = service.update_role(id, input, None).await;
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
@@ -907,11 +1339,146 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn cardinality_config() -> CardinalityConfig {
+        CardinalityConfig {
+            max_roles_per_user: 2,
+            max_permissions_per_role: 1,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assign_roles_rejects_exceeding_cap() {
+        let mut mock = MockRbacRepository::new();
+        let other_service = Role {
+            service_id: StringUuid::new_v4(),
+            ..Default::default()
+        };
+        mock.expect_find_role_by_id()
+            .returning(|_| Ok(Some(Role::default())));
+        mock.expect_find_user_role_records_in_tenant()
+            .returning(move |_, _, _| Ok(vec![other_service.clone()]));
+        mock.expect_find_tenant_cardinality_limits()
+            .returning(|_| Ok(None));
+        mock.expect_assign_roles_to_user().never();
+
+        let service =
+            RbacService::new(Arc::new(mock), None).with_cardinality_limits(cardinality_config());
+        let input = AssignRolesInput {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            role_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+            service_id: None,
+        };
+
+        let result = service.assign_roles(input, None).await;
+        assert!(matches!(
+            result,
+            Err(AppError::CardinalityLimitExceeded {
+                cap: CardinalityCap::RolesPerUser,
+                limit: 2,
+                requested: 3,
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_assign_roles_replacing_same_service_within_cap() {
+        let mut mock = MockRbacRepository::new();
+        let role = Role::default();
+        let existing = vec![role.clone(), role.clone()];
+        mock.expect_find_role_by_id()
+            .returning(move |_| Ok(Some(role.clone())));
+        mock.expect_find_user_role_records_in_tenant()
+            .returning(move |_, _, _| Ok(existing.clone()));
+        mock.expect_find_tenant_cardinality_limits()
+            .returning(|_| Ok(None));
+        mock.expect_assign_roles_to_user().returning(|_, _| Ok(()));
+
+        let service =
+            RbacService::new(Arc::new(mock), None).with_cardinality_limits(cardinality_config());
+        let input = AssignRolesInput {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            role_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+            service_id: None,
+        };
+
+        assert!(service.assign_roles(input, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_assign_roles_tenant_override_raises_cap() {
+        let mut mock = MockRbacRepository::new();
+        mock.expect_find_role_by_id()
+            .returning(|_| Ok(Some(Role::default())));
+        mock.expect_find_user_role_records_in_tenant()
+            .returning(|_, _, _| Ok(vec![]));
+        mock.expect_find_tenant_cardinality_limits().returning(|_| {
+            Ok(Some(CardinalityLimits {
+                max_roles_per_user: Some(5),
+                ..Default::default()
+            }))
+        });
+        mock.expect_assign_roles_to_user().returning(|_, _| Ok(()));
+
+        let service =
+            RbacService::new(Arc::new(mock), None).with_cardinality_limits(cardinality_config());
+        let input = AssignRolesInput {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            role_ids: vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()],
+            service_id: None,
+        };
+
+        assert!(service.assign_roles(input, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_assign_permission_to_role_rejects_exceeding_cap() {
+        let mut mock = MockRbacRepository::new();
+        let role = Role::default();
+        let role_id = role.id;
+        let permission = Permission {
+            service_id: role.service_id,
+            ..Default::default()
+        };
+        let permission_id = permission.id;
+        let existing = Permission {
+            service_id: role.service_id,
+            ..Default::default()
+        };
+
+        mock.expect_find_role_by_id()
+            .returning(move |_| Ok(Some(role.clone())));
+        mock.expect_find_permission_by_id()
+            .returning(move |_| Ok(Some(permission.clone())));
+        mock.expect_find_role_permissions()
+            .returning(move |_| Ok(vec![existing.clone()]));
+        mock.expect_find_service_cardinality_limits()
+            .returning(|_| Ok(None));
+        mock.expect_assign_permission_to_role().never();
+
+        let service =
+            RbacService::new(Arc::new(mock), None).with_cardinality_limits(cardinality_config());
+
+        let result = service
+            .assign_permission_to_role(role_id, permission_id)
+            .await;
+        assert!(matches!(
+            result,
+            Err(AppError::CardinalityLimitExceeded {
+                cap: CardinalityCap::PermissionsPerRole,
+                ..
+            })
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_user_roles() {
         let mut mock = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
 
         mock.expect_find_user_roles_in_tenant()
             .with(eq(user_id), eq(tenant_id))
@@ -937,8 +1504,8 @@ mod tests {
     #[tokio::test]
     async fn test_get_user_role_records() {
         let mut mock = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
 
         mock.expect_find_user_role_records_in_tenant()
             .with(eq(user_id), eq(tenant_id), eq(None))
@@ -959,8 +1526,8 @@ mod tests {
     #[tokio::test]
     async fn test_unassign_role_success() {
         let mut mock = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
         let role_id = StringUuid::new_v4();
         let tenant_user_id = StringUuid::new_v4();
 
@@ -981,8 +1548,8 @@ mod tests {
     #[tokio::test]
     async fn test_unassign_role_user_not_in_tenant() {
         let mut mock = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
         let role_id = StringUuid::new_v4();
 
         mock.expect_find_tenant_user_id()
@@ -995,6 +1562,126 @@ mod tests {
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
+    // ==================== Permission Check Tests ====================
+
+    fn check_input(permission: &str) -> CheckPermissionInput {
+        CheckPermissionInput {
+            permission: permission.to_string(),
+            service_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_permission_explains_inherited_grant() {
+        let mut mock = MockRbacRepository::new();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
+        let parent = Role {
+            name: "viewer".to_string(),
+            ..Default::default()
+        };
+        let child = Role {
+            name: "editor".to_string(),
+            parent_role_id: Some(parent.id),
+            ..Default::default()
+        };
+        let (parent_id, child_id) = (parent.id, child.id);
+
+        mock.expect_find_tenant_user_id()
+            .returning(|_, _| Ok(Some(StringUuid::new_v4())));
+        mock.expect_find_user_role_records_in_tenant()
+            .returning(move |_, _, _| Ok(vec![child.clone()]));
+        mock.expect_find_role_by_id()
+            .with(eq(parent_id))
+            .returning(move |_| Ok(Some(parent.clone())));
+        mock.expect_find_role_permissions()
+            .returning(move |role_id| {
+                let code = if role_id == parent_id {
+                    "report:*"
+                } else {
+                    "report:write"
+                };
+                Ok(vec![Permission {
+                    code: code.to_string(),
+                    ..Default::default()
+                }])
+            });
+
+        let service = RbacService::new(Arc::new(mock), None);
+        let result = service
+            .check_permission(user_id, tenant_id, &check_input("report:read"), true)
+            .await
+            .unwrap();
+
+        assert!(result.allowed);
+        let explanation = result.explanation.unwrap();
+        assert_eq!(explanation.reason, PermissionCheckReason::Granted);
+        assert_eq!(explanation.granted_by, vec!["viewer"]);
+        assert_eq!(explanation.evaluated_roles.len(), 2);
+        assert_eq!(explanation.evaluated_roles[0].role_id, child_id);
+        assert!(explanation.evaluated_roles[0]
+            .matched_permissions
+            .is_empty());
+        assert_eq!(explanation.evaluated_roles[1].inherited_via, vec!["editor"]);
+        assert_eq!(
+            explanation.evaluated_roles[1].matched_permissions,
+            vec!["report:*"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_permission_not_member() {
+        let mut mock = MockRbacRepository::new();
+        mock.expect_find_tenant_user_id().returning(|_, _| Ok(None));
+
+        let service = RbacService::new(Arc::new(mock), None);
+        let result = service
+            .check_permission(
+                UserId::new_v4(),
+                TenantId::new_v4(),
+                &check_input("report:read"),
+                true,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.allowed);
+        assert_eq!(
+            result.explanation.unwrap().reason,
+            PermissionCheckReason::NotMember
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_permission_without_explain() {
+        let mut mock = MockRbacRepository::new();
+        mock.expect_find_tenant_user_id()
+            .returning(|_, _| Ok(Some(StringUuid::new_v4())));
+        mock.expect_find_user_roles_in_tenant()
+            .returning(|uid, tid| {
+                Ok(UserRolesInTenant {
+                    user_id: *uid,
+                    tenant_id: *tid,
+                    roles: vec!["editor".to_string()],
+                    permissions: vec!["report:write".to_string()],
+                })
+            });
+
+        let service = RbacService::new(Arc::new(mock), None);
+        let result = service
+            .check_permission(
+                UserId::new_v4(),
+                TenantId::new_v4(),
+                &check_input("report:read"),
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.allowed);
+        assert!(result.explanation.is_none());
+    }
+
     // ==================== Reserved Role Name Tests ====================
 
     #[tokio::test]
@@ -1062,7 +1749,7 @@ mod tests {
             parent_role_id: None,
         };
 
-        let result = service.update_role(id, input).await;
+        let result = service.update_role(id, input, None).await;
         assert!(matches!(result, Err(AppError::BadRequest(_))));
     }
 
@@ -1120,7 +1807,7 @@ mod tests {
             parent_role_id: Some(Some(*role_id)), // Self-reference
         };
 
-        let result = service.update_role(role_id, input).await;
+        let result = service.update_role(role_id, input, None).await;
         assert!(matches!(result, Err(AppError::BadRequest(_))));
         if let Err(AppError::BadRequest(msg)) = result {
             assert!(msg.contains("own parent"));
@@ -1172,7 +1859,7 @@ mod tests {
             parent_role_id: Some(Some(*editor_id)),
         };
 
-        let result = service.update_role(viewer_id, input).await;
+        let result = service.update_role(viewer_id, input, None).await;
         assert!(matches!(result, Err(AppError::BadRequest(_))));
         if let Err(AppError::BadRequest(msg)) = result {
             assert!(msg.contains("Circular inheritance"));
@@ -1230,13 +1917,125 @@ mod tests {
             parent_role_id: Some(Some(*role_ids[1])),
         };
 
-        let result = service.update_role(target_role_id, input).await;
+        let result = service.update_role(target_role_id, input, None).await;
         assert!(matches!(result, Err(AppError::BadRequest(_))));
         if let Err(AppError::BadRequest(msg)) = result {
             assert!(msg.contains("depth exceeds maximum"));
         }
     }
 
+    #[tokio::test]
+    async fn test_update_role_parent_from_other_service_rejected() {
+        let mut mock = MockRbacRepository::new();
+        let role = Role {
+            service_id: StringUuid::new_v4(),
+            name: "Editor".to_string(),
+            ..Default::default()
+        };
+        let parent = Role {
+            service_id: StringUuid::new_v4(),
+            name: "Viewer".to_string(),
+            ..Default::default()
+        };
+        let (role_id, parent_id) = (role.id, parent.id);
+
+        mock.expect_find_role_by_id()
+            .with(eq(role_id))
+            .returning(move |_| Ok(Some(role.clone())));
+        mock.expect_find_role_by_id()
+            .with(eq(parent_id))
+            .returning(move |_| Ok(Some(parent.clone())));
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let input = UpdateRoleInput {
+            name: None,
+            description: None,
+            parent_role_id: Some(Some(*parent_id)),
+        };
+
+        let result = service.update_role(role_id, input, None).await;
+        assert!(
+            matches!(result, Err(AppError::BadRequest(msg)) if msg.contains("belongs to service"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_role_parent_from_other_service_rejected() {
+        let mut mock = MockRbacRepository::new();
+        let parent = Role {
+            service_id: StringUuid::new_v4(),
+            name: "Viewer".to_string(),
+            ..Default::default()
+        };
+        let parent_id = parent.id;
+
+        mock.expect_find_role_by_id()
+            .with(eq(parent_id))
+            .returning(move |_| Ok(Some(parent.clone())));
+        mock.expect_create_role().never();
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let input = CreateRoleInput {
+            service_id: Uuid::new_v4(),
+            name: "Editor".to_string(),
+            description: None,
+            parent_role_id: Some(*parent_id),
+            permission_ids: None,
+        };
+
+        let result = service.create_role(input).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_role_tree() {
+        let mut mock = MockRbacRepository::new();
+        let service_id = StringUuid::new_v4();
+        let viewer = Role {
+            service_id,
+            name: "Viewer".to_string(),
+            ..Default::default()
+        };
+        let editor = Role {
+            service_id,
+            name: "Editor".to_string(),
+            parent_role_id: Some(viewer.id),
+            ..Default::default()
+        };
+        let viewer_id = viewer.id;
+        let roles = vec![editor.clone(), viewer.clone()];
+
+        mock.expect_find_roles_by_service()
+            .with(eq(service_id))
+            .returning(move |_| Ok(roles.clone()));
+        mock.expect_find_role_permissions()
+            .returning(move |role_id| {
+                let code = if role_id == viewer_id {
+                    "doc:read"
+                } else {
+                    "doc:write"
+                };
+                Ok(vec![Permission {
+                    code: code.to_string(),
+                    ..Default::default()
+                }])
+            });
+
+        let service = RbacService::new(Arc::new(mock), None);
+
+        let tree = service.get_role_tree(service_id).await.unwrap();
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].name, "Viewer");
+        let editor_node = &tree.roots[0].children[0];
+        assert_eq!(editor_node.id, editor.id);
+        assert_eq!(
+            editor_node.effective_permissions,
+            vec!["doc:read", "doc:write"]
+        );
+    }
+
     // ==================== Cross-Service Permission Assignment Tests ====================
 
     #[tokio::test]
@@ -1321,6 +2120,95 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // ==================== Role Preset Tests ====================
+
+    #[tokio::test]
+    async fn test_apply_role_preset_grants_namespaced_permissions() {
+        use crate::models::role_preset::{PresetPermission, PresetRole, RolePresetDefinition};
+
+        let service_id = StringUuid::new_v4();
+        let mut mock = MockRbacRepository::new();
+        mock.expect_find_permission_namespace()
+            .returning(|_| Ok(Some("docs".to_string())));
+        mock.expect_create_permission()
+            .withf(|input| input.code.starts_with("docs:"))
+            .times(2)
+            .returning(|input| {
+                Ok(Permission {
+                    service_id: StringUuid::from(input.service_id),
+                    code: input.code.clone(),
+                    name: input.name.clone(),
+                    ..Default::default()
+                })
+            });
+        mock.expect_find_permission_by_id().returning(move |id| {
+            Ok(Some(Permission {
+                id,
+                service_id,
+                ..Default::default()
+            }))
+        });
+        mock.expect_create_role()
+            .withf(|input| match input.name.as_str() {
+                "editor" => input.permission_ids.as_ref().map(Vec::len) == Some(2),
+                "viewer" => input.permission_ids.as_ref().map(Vec::len) == Some(1),
+                _ => false,
+            })
+            .times(2)
+            .returning(|input| {
+                Ok(Role {
+                    service_id: StringUuid::from(input.service_id),
+                    name: input.name.clone(),
+                    ..Default::default()
+                })
+            });
+
+        let preset = RolePreset {
+            id: StringUuid::new_v4(),
+            key: "docs".to_string(),
+            version: 2,
+            name: "Docs".to_string(),
+            description: None,
+            definition: RolePresetDefinition {
+                permissions: vec![
+                    PresetPermission {
+                        code: "doc:read".to_string(),
+                        name: "Read".to_string(),
+                        description: None,
+                    },
+                    PresetPermission {
+                        code: "doc:write".to_string(),
+                        name: "Write".to_string(),
+                        description: None,
+                    },
+                ],
+                roles: vec![
+                    PresetRole {
+                        name: "editor".to_string(),
+                        description: None,
+                        permissions: vec!["doc:read".to_string(), "doc:write".to_string()],
+                    },
+                    PresetRole {
+                        name: "viewer".to_string(),
+                        description: None,
+                        permissions: vec!["doc:read".to_string()],
+                    },
+                ],
+            },
+            created_by: None,
+            created_at: chrono::Utc::now(),
+        };
+
+        let service = RbacService::new(Arc::new(mock), None);
+        let applied = service
+            .apply_role_preset(service_id, &preset)
+            .await
+            .unwrap();
+        assert_eq!(applied.version, 2);
+        assert_eq!(applied.permission_ids.len(), 2);
+        assert_eq!(applied.role_ids.len(), 2);
+    }
+
     // ==================== Duplicate Permission Error Message Tests ====================
 
     #[tokio::test]
@@ -1328,6 +2216,9 @@ mod tests {
         let mut mock = MockRbacRepository::new();
         let service_id = Uuid::new_v4();
 
+        mock.expect_find_permission_namespace()
+            .returning(|_| Ok(None));
+
         mock.expect_create_permission().returning(|_| {
             Err(AppError::Database(sqlx::Error::Database(Box::new(
                 TestDbError("Duplicate entry 'user:read' for key 'permissions.idx_permissions_service_code'".to_string()),
@@ -0,0 +1,247 @@
+//! Versioned role preset templates
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::role_preset::{CreateRolePresetInput, RolePreset, UpdateRolePresetInput};
+use crate::repository::role_preset::{RolePresetRepository, RolePresetRepositoryImpl};
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct RolePresetService<R: RolePresetRepository> {
+    repo: Arc<R>,
+}
+
+impl RolePresetService<RolePresetRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(RolePresetRepositoryImpl::new(pool)))
+    }
+}
+
+impl<R: RolePresetRepository> RolePresetService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// Latest version of every preset
+    pub async fn list(&self) -> Result<Vec<RolePreset>> {
+        self.repo.list_latest().await
+    }
+
+    /// A preset at `version`, or its latest version
+    pub async fn get(&self, key: &str, version: Option<i32>) -> Result<RolePreset> {
+        let preset = match version {
+            Some(version) => self.repo.find_version(key, version).await?,
+            None => self.repo.find_latest(key).await?,
+        };
+        preset.ok_or_else(|| match version {
+            Some(version) => AppError::NotFound(format!(
+                "Role preset '{}' version {} not found",
+                key, version
+            )),
+            None => AppError::NotFound(format!("Role preset '{}' not found", key)),
+        })
+    }
+
+    /// All versions of a preset, newest first
+    pub async fn versions(&self, key: &str) -> Result<Vec<RolePreset>> {
+        let versions = self.repo.list_versions(key).await?;
+        if versions.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "Role preset '{}' not found",
+                key
+            )));
+        }
+        Ok(versions)
+    }
+
+    /// Create a preset as version 1
+    pub async fn create(
+        &self,
+        input: CreateRolePresetInput,
+        created_by: Option<StringUuid>,
+    ) -> Result<RolePreset> {
+        input.validate()?;
+        if self.repo.find_latest(&input.key).await?.is_some() {
+            return Err(AppError::Conflict(format!(
+                "Role preset '{}' already exists",
+                input.key
+            )));
+        }
+        let preset = RolePreset {
+            id: StringUuid::new_v4(),
+            key: input.key,
+            version: 1,
+            name: input.name,
+            description: input.description,
+            definition: input.definition,
+            created_by,
+            created_at: Utc::now(),
+        };
+        self.repo.create(&preset).await?;
+        Ok(preset)
+    }
+
+    /// Publish a new version of a preset. Services created from earlier
+    /// versions keep the roles they were given.
+    pub async fn update(
+        &self,
+        key: &str,
+        input: UpdateRolePresetInput,
+        created_by: Option<StringUuid>,
+    ) -> Result<RolePreset> {
+        input.validate()?;
+        let latest = self.get(key, None).await?;
+        let preset = RolePreset {
+            id: StringUuid::new_v4(),
+            key: latest.key,
+            version: latest.version + 1,
+            name: input.name.unwrap_or(latest.name),
+            description: input.description.or(latest.description),
+            definition: input.definition.unwrap_or(latest.definition),
+            created_by,
+            created_at: Utc::now(),
+        };
+        self.repo.create(&preset).await?;
+        Ok(preset)
+    }
+
+    /// Delete a preset and all its versions
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        if self.repo.delete(key).await? == 0 {
+            return Err(AppError::NotFound(format!(
+                "Role preset '{}' not found",
+                key
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::role_preset::{PresetPermission, PresetRole, RolePresetDefinition};
+    use crate::repository::role_preset::MockRolePresetRepository;
+
+    fn definition(role: &str) -> RolePresetDefinition {
+        RolePresetDefinition {
+            permissions: vec![PresetPermission {
+                code: "doc:read".to_string(),
+                name: "Read documents".to_string(),
+                description: None,
+            }],
+            roles: vec![PresetRole {
+                name: role.to_string(),
+                description: None,
+                permissions: vec!["doc:read".to_string()],
+            }],
+        }
+    }
+
+    fn preset(version: i32) -> RolePreset {
+        RolePreset {
+            id: StringUuid::new_v4(),
+            key: "docs".to_string(),
+            version,
+            name: "Docs".to_string(),
+            description: Some("Document access".to_string()),
+            definition: definition("viewer"),
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_starts_at_version_one() {
+        let mut repo = MockRolePresetRepository::new();
+        repo.expect_find_latest().returning(|_| Ok(None));
+        repo.expect_create()
+            .withf(|p| p.key == "docs" && p.version == 1)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let created = RolePresetService::new(Arc::new(repo))
+            .create(
+                CreateRolePresetInput {
+                    key: "docs".to_string(),
+                    name: "Docs".to_string(),
+                    description: None,
+                    definition: definition("viewer"),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_existing_key_conflicts() {
+        let mut repo = MockRolePresetRepository::new();
+        repo.expect_find_latest().returning(|_| Ok(Some(preset(2))));
+
+        let result = RolePresetService::new(Arc::new(repo))
+            .create(
+                CreateRolePresetInput {
+                    key: "docs".to_string(),
+                    name: "Docs".to_string(),
+                    description: None,
+                    definition: definition("viewer"),
+                },
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_publishes_next_version_keeping_omitted_fields() {
+        let mut repo = MockRolePresetRepository::new();
+        repo.expect_find_latest().returning(|_| Ok(Some(preset(3))));
+        repo.expect_create()
+            .withf(|p| {
+                p.version == 4
+                    && p.name == "Docs"
+                    && p.description.as_deref() == Some("Document access")
+                    && p.definition.roles[0].name == "reader"
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let updated = RolePresetService::new(Arc::new(repo))
+            .update(
+                "docs",
+                UpdateRolePresetInput {
+                    name: None,
+                    description: None,
+                    definition: Some(definition("reader")),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.version, 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_version_not_found() {
+        let mut repo = MockRolePresetRepository::new();
+        repo.expect_find_version().returning(|_, _| Ok(None));
+
+        let result = RolePresetService::new(Arc::new(repo))
+            .get("docs", Some(7))
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_unknown_preset_not_found() {
+        let mut repo = MockRolePresetRepository::new();
+        repo.expect_delete().returning(|_| Ok(0));
+
+        let result = RolePresetService::new(Arc::new(repo)).delete("docs").await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,90 @@
+//! Saga for service deletion
+//!
+//! Spans the identity engine and the database; see [`crate::saga`].
+
+use crate::error::{AppError, Result};
+use crate::saga::{Recovery, Saga, SagaRunner};
+use crate::state::HasServices;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const SERVICE_DELETE: &str = "service_delete";
+
+/// State of a service deletion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDeleteContext {
+    pub service_id: Uuid,
+    /// OIDC clients of the service, removed from the identity engine first
+    pub client_ids: Vec<String>,
+    /// Clients already removed from the identity engine
+    #[serde(default)]
+    pub deleted_client_ids: Vec<String>,
+}
+
+/// Identity engine clients, then the service and its data. A deleted client
+/// cannot be restored, so a failure leaves the saga to be resumed rather
+/// than undone.
+pub fn service_delete_saga<S: HasServices>(state: &S) -> Saga<ServiceDeleteContext> {
+    let (delete_clients, delete_service) = (state.clone(), state.clone());
+
+    Saga::new(SERVICE_DELETE, Recovery::RetryForward)
+        .step(
+            "delete_identity_clients",
+            move |mut ctx: ServiceDeleteContext| {
+                let state = delete_clients.clone();
+                async move {
+                    let client_store = state.identity_engine().client_store();
+                    let pending: Vec<String> = ctx
+                        .client_ids
+                        .iter()
+                        .filter(|id| !ctx.deleted_client_ids.contains(id))
+                        .cloned()
+                        .collect();
+                    for client_id in pending {
+                        // A client missing from the identity engine is already gone
+                        match client_store.get_client_uuid_by_client_id(&client_id).await {
+                            Ok(client_uuid) => {
+                                match client_store.delete_oidc_client(&client_uuid).await {
+                                    Ok(()) | Err(AppError::NotFound(_)) => {}
+                                    Err(e) => return Err(e),
+                                }
+                            }
+                            Err(AppError::NotFound(_)) => {}
+                            Err(e) => return Err(e),
+                        }
+                        ctx.deleted_client_ids.push(client_id);
+                    }
+                    Ok(ctx)
+                }
+            },
+        )
+        .step("delete_service", move |ctx: ServiceDeleteContext| {
+            let state = delete_service.clone();
+            async move {
+                match state.client_service().delete(ctx.service_id).await {
+                    Ok(()) | Err(AppError::NotFound(_)) => Ok(ctx),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+}
+
+/// Delete a service, its clients and their identity engine counterparts
+pub async fn run_service_delete<S: HasServices>(state: &S, service_id: Uuid) -> Result<()> {
+    let client_ids = state
+        .client_service()
+        .list_clients(service_id)
+        .await?
+        .into_iter()
+        .map(|client| client.client_id)
+        .collect();
+    let ctx = ServiceDeleteContext {
+        service_id,
+        client_ids,
+        deleted_client_ids: Vec::new(),
+    };
+    SagaRunner::new(state.saga_store())
+        .run(&service_delete_saga(state), None, ctx)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! Per-service OAuth scope catalog
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::{
+    consent_items, grantable_scopes, has_custom_scopes, resolve_requested_scopes,
+    validate_scope_name, ScopeConsentItem, ServiceScope, UpsertServiceScopeInput,
+};
+use crate::repository::service_scope::{ServiceScopeRepository, ServiceScopeRepositoryImpl};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct ScopeCatalogService<R: ServiceScopeRepository> {
+    repo: Arc<R>,
+}
+
+impl ScopeCatalogService<ServiceScopeRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(ServiceScopeRepositoryImpl::new(pool)))
+    }
+}
+
+impl<R: ServiceScopeRepository> ScopeCatalogService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list(&self, service_id: StringUuid) -> Result<Vec<ServiceScope>> {
+        self.repo.list_by_service(service_id).await
+    }
+
+    /// Declare a scope or update its declaration
+    pub async fn upsert(
+        &self,
+        service_id: StringUuid,
+        name: &str,
+        input: UpsertServiceScopeInput,
+    ) -> Result<ServiceScope> {
+        validate_scope_name(name).map_err(AppError::Validation)?;
+        input.validate()?;
+        self.repo
+            .upsert(
+                service_id,
+                name,
+                input.description.trim(),
+                &input.required_permissions,
+            )
+            .await
+    }
+
+    pub async fn delete(&self, service_id: StringUuid, name: &str) -> Result<()> {
+        self.repo.delete(service_id, name).await
+    }
+
+    /// Catalog entries needed to interpret `scope`; empty without a lookup
+    /// when only standard scopes are involved
+    async fn catalog_for(&self, service_id: StringUuid, scope: &str) -> Result<Vec<ServiceScope>> {
+        if has_custom_scopes(scope) {
+            self.repo.list_by_service(service_id).await
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Validate the scopes of an authorization request, returning the
+    /// normalized scope string
+    pub async fn resolve(&self, service_id: StringUuid, requested: &str) -> Result<String> {
+        let catalog = self.catalog_for(service_id, requested).await?;
+        resolve_requested_scopes(requested, &catalog).map_err(AppError::BadRequest)
+    }
+
+    /// Consent screen lines for a resolved scope string
+    pub async fn consent(
+        &self,
+        service_id: StringUuid,
+        scope: &str,
+    ) -> Result<Vec<ScopeConsentItem>> {
+        let catalog = self.catalog_for(service_id, scope).await?;
+        Ok(consent_items(scope, &catalog))
+    }
+
+    /// Narrow a resolved scope string to the scopes whose required
+    /// permissions are covered by `permissions`
+    pub async fn grantable(
+        &self,
+        service_id: StringUuid,
+        scope: &str,
+        permissions: &[String],
+    ) -> Result<String> {
+        let catalog = self.catalog_for(service_id, scope).await?;
+        Ok(grantable_scopes(scope, &catalog, permissions))
+    }
+
+    /// Whether any scope in `scope` requires permissions
+    pub async fn requires_permissions(&self, service_id: StringUuid, scope: &str) -> Result<bool> {
+        let catalog = self.catalog_for(service_id, scope).await?;
+        Ok(scope.split_whitespace().any(|name| {
+            catalog
+                .iter()
+                .any(|entry| entry.name == name && !entry.required_permissions.is_empty())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::service_scope::MockServiceScopeRepository;
+    use chrono::Utc;
+
+    fn declared(service_id: StringUuid, name: &str, required: &[&str]) -> ServiceScope {
+        ServiceScope {
+            id: StringUuid::new_v4(),
+            service_id,
+            name: name.to_string(),
+            description: format!("Access {}", name),
+            required_permissions: required.iter().map(|s| s.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_standard_scopes_skips_lookup() {
+        let mut repo = MockServiceScopeRepository::new();
+        repo.expect_list_by_service().never();
+        let service = ScopeCatalogService::new(Arc::new(repo));
+
+        let scope = service
+            .resolve(StringUuid::new_v4(), "openid profile")
+            .await
+            .unwrap();
+        assert_eq!(scope, "openid profile");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_undeclared_scope() {
+        let service_id = StringUuid::new_v4();
+        let mut repo = MockServiceScopeRepository::new();
+        repo.expect_list_by_service()
+            .returning(move |_| Ok(vec![declared(service_id, "invoices:read", &[])]));
+        let service = ScopeCatalogService::new(Arc::new(repo));
+
+        assert_eq!(
+            service
+                .resolve(service_id, "openid invoices:read")
+                .await
+                .unwrap(),
+            "openid invoices:read"
+        );
+        let err = service
+            .resolve(service_id, "openid invoices:write")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(msg) if msg.contains("invoices:write")));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_rejects_standard_scope_name() {
+        let mut repo = MockServiceScopeRepository::new();
+        repo.expect_upsert().never();
+        let service = ScopeCatalogService::new(Arc::new(repo));
+
+        let result = service
+            .upsert(
+                StringUuid::new_v4(),
+                "email",
+                UpsertServiceScopeInput {
+                    description: "Email".to_string(),
+                    required_permissions: vec![],
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_grantable_and_requires_permissions() {
+        let service_id = StringUuid::new_v4();
+        let mut repo = MockServiceScopeRepository::new();
+        repo.expect_list_by_service().returning(move |_| {
+            Ok(vec![declared(
+                service_id,
+                "invoices:write",
+                &["billing:invoices:write"],
+            )])
+        });
+        let service = ScopeCatalogService::new(Arc::new(repo));
+
+        assert!(service
+            .requires_permissions(service_id, "openid invoices:write")
+            .await
+            .unwrap());
+        assert_eq!(
+            service
+                .grantable(service_id, "openid invoices:write", &[])
+                .await
+                .unwrap(),
+            "openid"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Per-service token validation profiles
+
+use crate::config::TokenValidationConfig;
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::token_validation::{
+    ServiceTokenValidationProfile, SetTokenValidationProfileInput, TokenValidationProfile,
+    DEFAULT_VALIDATION_PROFILE,
+};
+use crate::repository::token_validation_profile::{
+    TokenValidationProfileRepository, TokenValidationProfileRepositoryImpl,
+};
+use lru::LruCache;
+use sqlx::MySqlPool;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const CACHE_CAPACITY: usize = 1024;
+/// Assignment changes reach running verifiers within this delay
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+type ClientProfileCache = LruCache<String, (Option<String>, Instant)>;
+
+/// Assigning profiles to services
+pub struct TokenValidationProfileService<R: TokenValidationProfileRepository> {
+    repo: Arc<R>,
+    config: TokenValidationConfig,
+}
+
+impl TokenValidationProfileService<TokenValidationProfileRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool, config: &TokenValidationConfig) -> Self {
+        Self::new(
+            Arc::new(TokenValidationProfileRepositoryImpl::new(pool)),
+            config.clone(),
+        )
+    }
+}
+
+impl<R: TokenValidationProfileRepository> TokenValidationProfileService<R> {
+    pub fn new(repo: Arc<R>, config: TokenValidationConfig) -> Self {
+        Self { repo, config }
+    }
+
+    /// The profile in effect for a service
+    pub async fn get(&self, service_id: StringUuid) -> Result<ServiceTokenValidationProfile> {
+        let assignment = self.repo.find_by_service(service_id).await?;
+        let (profile_name, updated_at) = match assignment {
+            Some(a) => (a.profile_name, Some(a.updated_at)),
+            None => (DEFAULT_VALIDATION_PROFILE.to_string(), None),
+        };
+        let profile = self
+            .config
+            .profile(&profile_name)
+            .unwrap_or_else(|| self.config.default_profile())
+            .clone();
+        Ok(ServiceTokenValidationProfile {
+            service_id,
+            profile_name,
+            profile,
+            updated_at,
+        })
+    }
+
+    /// Assign a configured profile to a service; `None` or `default` removes
+    /// the assignment
+    pub async fn set(
+        &self,
+        service_id: StringUuid,
+        input: SetTokenValidationProfileInput,
+    ) -> Result<ServiceTokenValidationProfile> {
+        match input.profile.as_deref() {
+            None | Some(DEFAULT_VALIDATION_PROFILE) => {
+                self.repo.delete(service_id).await?;
+            }
+            Some(name) => {
+                if self.config.profile(name).is_none() {
+                    return Err(AppError::BadRequest(format!(
+                        "Unknown token validation profile '{}'",
+                        name
+                    )));
+                }
+                self.repo.upsert(service_id, name).await?;
+            }
+        }
+        self.get(service_id).await
+    }
+}
+
+/// Resolves the validation profile for tenant access tokens by audience
+/// (client_id), shared by the REST auth middleware and gRPC token services.
+///
+/// Assignments are cached per client. When only the `default` profile is
+/// configured nothing is looked up.
+pub struct TokenValidationPolicies {
+    config: TokenValidationConfig,
+    repo: Option<Arc<dyn TokenValidationProfileRepository>>,
+    cache: Mutex<ClientProfileCache>,
+}
+
+impl TokenValidationPolicies {
+    pub fn new(config: TokenValidationConfig) -> Self {
+        Self {
+            config,
+            repo: None,
+            cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(CACHE_CAPACITY).expect("non-zero capacity"),
+            )),
+        }
+    }
+
+    pub fn with_repository(mut self, repo: Arc<dyn TokenValidationProfileRepository>) -> Self {
+        self.repo = Some(repo);
+        self
+    }
+
+    pub fn from_pool(pool: MySqlPool, config: &TokenValidationConfig) -> Self {
+        Self::new(config.clone())
+            .with_repository(Arc::new(TokenValidationProfileRepositoryImpl::new(pool)))
+    }
+
+    /// Profile for tokens issued to `client_id`. Lookup failures fall back to
+    /// `default`, the platform's standard validation.
+    pub async fn for_client(&self, client_id: &str) -> TokenValidationProfile {
+        let name = self.profile_name(client_id).await;
+        match name.as_deref().and_then(|n| self.config.profile(n)) {
+            Some(profile) => profile.clone(),
+            None => {
+                if let Some(name) = name {
+                    tracing::warn!(
+                        client_id,
+                        profile = %name,
+                        "Assigned token validation profile is not configured, using default"
+                    );
+                }
+                self.config.default_profile().clone()
+            }
+        }
+    }
+
+    async fn profile_name(&self, client_id: &str) -> Option<String> {
+        let repo = self.repo.as_ref()?;
+        if self.config.profiles.len() <= 1 {
+            return None;
+        }
+
+        if let Some((name, cached_at)) = self.cache.lock().unwrap().get(client_id).cloned() {
+            if cached_at.elapsed() < CACHE_TTL {
+                return name;
+            }
+        }
+
+        match repo.find_profile_name_by_client_id(client_id).await {
+            Ok(name) => {
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .put(client_id.to_string(), (name.clone(), Instant::now()));
+                name
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, client_id, "Token validation profile lookup failed");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_validation::TokenValidationAssignment;
+    use crate::repository::token_validation_profile::MockTokenValidationProfileRepository;
+    use chrono::Utc;
+
+    fn config_with_strict() -> TokenValidationConfig {
+        let mut config = TokenValidationConfig::default();
+        config.profiles.insert(
+            "strict".to_string(),
+            TokenValidationProfile {
+                clock_skew_secs: 0,
+                required_claims: vec!["sid".to_string()],
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[tokio::test]
+    async fn test_set_unknown_profile_rejected() {
+        let repo = MockTokenValidationProfileRepository::new();
+        let result = TokenValidationProfileService::new(Arc::new(repo), config_with_strict())
+            .set(
+                StringUuid::new_v4(),
+                SetTokenValidationProfileInput {
+                    profile: Some("lenient".to_string()),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_default_removes_assignment() {
+        let mut repo = MockTokenValidationProfileRepository::new();
+        repo.expect_delete().times(1).returning(|_| Ok(true));
+        repo.expect_find_by_service().returning(|_| Ok(None));
+
+        let profile = TokenValidationProfileService::new(Arc::new(repo), config_with_strict())
+            .set(
+                StringUuid::new_v4(),
+                SetTokenValidationProfileInput {
+                    profile: Some("default".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(profile.profile_name, "default");
+        assert!(profile.updated_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_assigned_profile() {
+        let mut repo = MockTokenValidationProfileRepository::new();
+        repo.expect_find_by_service().returning(|service_id| {
+            Ok(Some(TokenValidationAssignment {
+                service_id,
+                profile_name: "strict".to_string(),
+                updated_at: Utc::now(),
+            }))
+        });
+
+        let profile = TokenValidationProfileService::new(Arc::new(repo), config_with_strict())
+            .get(StringUuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(profile.profile_name, "strict");
+        assert_eq!(profile.profile.clock_skew_secs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_policies_cache_client_lookups() {
+        let mut repo = MockTokenValidationProfileRepository::new();
+        repo.expect_find_profile_name_by_client_id()
+            .times(1)
+            .returning(|_| Ok(Some("strict".to_string())));
+
+        let policies =
+            TokenValidationPolicies::new(config_with_strict()).with_repository(Arc::new(repo));
+        assert_eq!(policies.for_client("app").await.clock_skew_secs, 0);
+        assert_eq!(policies.for_client("app").await.clock_skew_secs, 0);
+    }
+
+    #[tokio::test]
+    async fn test_policies_skip_lookup_with_only_default_profile() {
+        let repo = MockTokenValidationProfileRepository::new();
+        let policies = TokenValidationPolicies::new(TokenValidationConfig::default())
+            .with_repository(Arc::new(repo));
+        assert_eq!(
+            policies.for_client("app").await,
+            TokenValidationProfile::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policies_fall_back_to_default_on_error() {
+        let mut repo = MockTokenValidationProfileRepository::new();
+        repo.expect_find_profile_name_by_client_id()
+            .returning(|_| Err(AppError::Internal(anyhow::anyhow!("db down"))));
+
+        let policies =
+            TokenValidationPolicies::new(config_with_strict()).with_repository(Arc::new(repo));
+        assert_eq!(
+            policies.for_client("app").await,
+            TokenValidationProfile::default()
+        );
+    }
+}
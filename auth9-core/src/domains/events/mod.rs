@@ -0,0 +1,187 @@
+//! In-process domain event bus
+//!
+//! Services publish typed events after a change has been committed instead of
+//! calling into other modules directly. Subscribers (webhooks, audit, cache
+//! invalidation) react to those events independently, so a failing side
+//! effect never fails the operation that raised it and every side effect can
+//! be tested against the event alone.
+
+mod subscribers;
+
+pub use subscribers::{
+    AuditSubscriber, CacheInvalidationSubscriber, RevocationFeedSubscriber, WebhookSubscriber,
+};
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::session_revocation::SessionRevocationReason;
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+
+/// Something that happened in a bounded context that others may react to
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainEvent {
+    UserCreated {
+        user_id: StringUuid,
+        email: String,
+        display_name: Option<String>,
+    },
+    RoleAssigned {
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        role_ids: Vec<StringUuid>,
+        granted_by: Option<StringUuid>,
+    },
+    SessionRevoked {
+        session_id: StringUuid,
+        user_id: StringUuid,
+        device_type: Option<String>,
+        device_name: Option<String>,
+        reason: SessionRevocationReason,
+    },
+}
+
+impl DomainEvent {
+    /// Event name, matching the webhook event type
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserCreated { .. } => "user.created",
+            Self::RoleAssigned { .. } => "role.assigned",
+            Self::SessionRevoked { .. } => "session.revoked",
+        }
+    }
+}
+
+/// Reacts to published domain events
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    /// Subscriber name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Handle an event; events a subscriber doesn't care about are ignored
+    async fn handle(&self, event: &DomainEvent) -> Result<()>;
+}
+
+/// Delivers events to every subscriber in registration order
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RwLock<Vec<Arc<dyn EventSubscriber>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a subscriber for all subsequently published events
+    pub fn subscribe(&self, subscriber: Arc<dyn EventSubscriber>) {
+        self.subscribers
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(subscriber);
+    }
+
+    /// Builder-style variant of [`EventBus::subscribe`]
+    pub fn with_subscriber(self, subscriber: Arc<dyn EventSubscriber>) -> Self {
+        self.subscribe(subscriber);
+        self
+    }
+
+    /// Deliver an event to all subscribers. Subscriber failures are logged
+    /// and counted but never returned to the publisher.
+    pub async fn publish(&self, event: DomainEvent) {
+        let subscribers = self
+            .subscribers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        for subscriber in subscribers {
+            let outcome = match subscriber.handle(&event).await {
+                Ok(()) => "success",
+                Err(e) => {
+                    tracing::warn!(
+                        event = event.name(),
+                        subscriber = subscriber.name(),
+                        error = %e,
+                        "Domain event subscriber failed"
+                    );
+                    "failure"
+                }
+            };
+            metrics::counter!(
+                "auth9_domain_events_total",
+                "event" => event.name(),
+                "subscriber" => subscriber.name(),
+                "outcome" => outcome
+            )
+            .increment(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+
+    fn session_revoked() -> DomainEvent {
+        DomainEvent::SessionRevoked {
+            session_id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            device_type: None,
+            device_name: None,
+            reason: SessionRevocationReason::UserRequest,
+        }
+    }
+
+    #[test]
+    fn test_event_names() {
+        let user_created = DomainEvent::UserCreated {
+            user_id: StringUuid::new_v4(),
+            email: "a@example.com".to_string(),
+            display_name: None,
+        };
+        assert_eq!(user_created.name(), "user.created");
+        assert_eq!(session_revoked().name(), "session.revoked");
+    }
+
+    #[tokio::test]
+    async fn test_publish_delivers_to_subscriber() {
+        let event = session_revoked();
+        let expected = event.clone();
+        let mut subscriber = MockEventSubscriber::new();
+        subscriber.expect_name().return_const("test");
+        subscriber
+            .expect_handle()
+            .withf(move |e| *e == expected)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let bus = EventBus::new().with_subscriber(Arc::new(subscriber));
+        bus.publish(event).await;
+    }
+
+    #[tokio::test]
+    async fn test_failing_subscriber_does_not_stop_others() {
+        let mut failing = MockEventSubscriber::new();
+        failing.expect_name().return_const("failing");
+        failing
+            .expect_handle()
+            .times(1)
+            .returning(|_| Err(AppError::Internal(anyhow::anyhow!("boom"))));
+        let mut healthy = MockEventSubscriber::new();
+        healthy.expect_name().return_const("healthy");
+        healthy.expect_handle().times(1).returning(|_| Ok(()));
+
+        let bus = EventBus::new();
+        bus.subscribe(Arc::new(failing));
+        bus.subscribe(Arc::new(healthy));
+        bus.publish(session_revoked()).await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers() {
+        EventBus::new().publish(session_revoked()).await;
+    }
+}
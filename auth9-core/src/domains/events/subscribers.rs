@@ -0,0 +1,460 @@
+//! Built-in domain event subscribers
+
+use super::{DomainEvent, EventSubscriber};
+use crate::cache::CacheOperations;
+use crate::domains::integration::service::WebhookEventPublisher;
+use crate::error::Result;
+use crate::models::analytics::WebhookEvent;
+use crate::models::session_revocation::SessionRevocationReason;
+use crate::repository::audit::CreateAuditLogInput;
+use crate::repository::{AuditRepository, SessionRevocationRepository};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Forwards domain events to subscribed webhooks
+pub struct WebhookSubscriber {
+    publisher: Arc<dyn WebhookEventPublisher>,
+}
+
+impl WebhookSubscriber {
+    pub fn new(publisher: Arc<dyn WebhookEventPublisher>) -> Self {
+        Self { publisher }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<()> {
+        let data = match event {
+            DomainEvent::UserCreated {
+                user_id,
+                email,
+                display_name,
+            } => serde_json::json!({
+                "user_id": user_id.to_string(),
+                "email": email,
+                "display_name": display_name,
+            }),
+            DomainEvent::RoleAssigned {
+                tenant_id,
+                user_id,
+                role_ids,
+                granted_by,
+            } => {
+                let webhook_event = WebhookEvent {
+                    event_type: event.name().to_string(),
+                    timestamp: Utc::now(),
+                    data: serde_json::json!({
+                        "tenant_id": tenant_id.to_string(),
+                        "user_id": user_id.to_string(),
+                        "role_ids": role_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                        "granted_by": granted_by.map(|id| id.to_string()),
+                    }),
+                };
+                // Role assignments are tenant-scoped; only that tenant's hooks see them
+                return self
+                    .publisher
+                    .trigger_tenant_event(*tenant_id, webhook_event)
+                    .await;
+            }
+            DomainEvent::SessionRevoked {
+                session_id,
+                user_id,
+                device_type,
+                device_name,
+                reason,
+            } => serde_json::json!({
+                "session_id": session_id.to_string(),
+                "user_id": user_id.to_string(),
+                "device_type": device_type,
+                "device_name": device_name,
+                "reason": reason,
+            }),
+        };
+        self.publisher
+            .trigger_event(WebhookEvent {
+                event_type: event.name().to_string(),
+                timestamp: Utc::now(),
+                data,
+            })
+            .await
+    }
+}
+
+/// Drops cached state made stale by an event
+pub struct CacheInvalidationSubscriber {
+    cache: Arc<dyn CacheOperations>,
+    /// How long a revoked session's access tokens stay blacklisted
+    access_token_ttl_secs: u64,
+}
+
+impl CacheInvalidationSubscriber {
+    pub fn new(cache: Arc<dyn CacheOperations>, access_token_ttl_secs: u64) -> Self {
+        Self {
+            cache,
+            access_token_ttl_secs,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for CacheInvalidationSubscriber {
+    fn name(&self) -> &'static str {
+        "cache_invalidation"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<()> {
+        match event {
+            DomainEvent::RoleAssigned {
+                tenant_id, user_id, ..
+            } => {
+                self.cache
+                    .invalidate_user_roles_for_tenant(**user_id, **tenant_id)
+                    .await
+            }
+            DomainEvent::SessionRevoked { session_id, .. } => {
+                let session_id = session_id.to_string();
+                self.cache
+                    .add_to_token_blacklist(&session_id, self.access_token_ttl_secs)
+                    .await?;
+                self.cache
+                    .remove_all_refresh_sessions_for_session(&session_id)
+                    .await
+            }
+            DomainEvent::UserCreated { .. } => Ok(()),
+        }
+    }
+}
+
+/// Records security-relevant events that have no audited API call of their own
+pub struct AuditSubscriber {
+    audit_repo: Arc<dyn AuditRepository>,
+}
+
+impl AuditSubscriber {
+    pub fn new(audit_repo: Arc<dyn AuditRepository>) -> Self {
+        Self { audit_repo }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for AuditSubscriber {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<()> {
+        match event {
+            DomainEvent::SessionRevoked {
+                session_id,
+                user_id,
+                device_type,
+                device_name,
+                reason: SessionRevocationReason::UserRequest,
+            } => {
+                self.audit_repo
+                    .create(&CreateAuditLogInput {
+                        actor_id: Some(**user_id),
+                        action: event.name().to_string(),
+                        resource_type: "session".to_string(),
+                        resource_id: Some(**session_id),
+                        old_value: None,
+                        new_value: Some(serde_json::json!({
+                            "device_type": device_type,
+                            "device_name": device_name,
+                        })),
+                        ip_address: None,
+                    })
+                    .await
+            }
+            // Already audited by the API handlers that trigger them; bulk
+            // revocations are audited once per sign-out, not per session
+            DomainEvent::UserCreated { .. }
+            | DomainEvent::RoleAssigned { .. }
+            | DomainEvent::SessionRevoked { .. } => Ok(()),
+        }
+    }
+}
+
+/// Appends revoked sessions to the revocation feed and pushes each notice
+/// to the webhooks of the user's tenants
+pub struct RevocationFeedSubscriber {
+    repo: Arc<dyn SessionRevocationRepository>,
+    publisher: Arc<dyn WebhookEventPublisher>,
+}
+
+impl RevocationFeedSubscriber {
+    pub fn new(
+        repo: Arc<dyn SessionRevocationRepository>,
+        publisher: Arc<dyn WebhookEventPublisher>,
+    ) -> Self {
+        Self { repo, publisher }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for RevocationFeedSubscriber {
+    fn name(&self) -> &'static str {
+        "revocation_feed"
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<()> {
+        let DomainEvent::SessionRevoked {
+            session_id,
+            user_id,
+            reason,
+            ..
+        } = event
+        else {
+            return Ok(());
+        };
+        let notice = self
+            .repo
+            .append(*session_id, *user_id, *reason, Utc::now())
+            .await?;
+        let data = serde_json::to_value(&notice)
+            .map_err(|e| crate::error::AppError::Internal(anyhow::anyhow!(e)))?;
+        for tenant_id in self.repo.list_user_tenant_ids(*user_id).await? {
+            self.publisher
+                .trigger_tenant_event(
+                    tenant_id,
+                    WebhookEvent {
+                        event_type: "session.revocation".to_string(),
+                        timestamp: notice.revoked_at,
+                        data: data.clone(),
+                    },
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::MockCacheOperations;
+    use crate::domains::integration::service::webhook::MockWebhookEventPublisher;
+    use crate::models::common::StringUuid;
+    use crate::repository::audit::MockAuditRepository;
+    use crate::repository::session_revocation::MockSessionRevocationRepository;
+
+    #[tokio::test]
+    async fn test_webhook_subscriber_keeps_user_created_payload() {
+        let user_id = StringUuid::new_v4();
+        let mut publisher = MockWebhookEventPublisher::new();
+        publisher
+            .expect_trigger_event()
+            .withf(move |e| {
+                e.event_type == "user.created"
+                    && e.data["user_id"] == user_id.to_string()
+                    && e.data["email"] == "a@example.com"
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        WebhookSubscriber::new(Arc::new(publisher))
+            .handle(&DomainEvent::UserCreated {
+                user_id,
+                email: "a@example.com".to_string(),
+                display_name: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_subscriber_scopes_role_assigned_to_tenant() {
+        let tenant_id = StringUuid::new_v4();
+        let mut publisher = MockWebhookEventPublisher::new();
+        publisher
+            .expect_trigger_tenant_event()
+            .withf(move |t, e| *t == tenant_id && e.event_type == "role.assigned")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        WebhookSubscriber::new(Arc::new(publisher))
+            .handle(&DomainEvent::RoleAssigned {
+                tenant_id,
+                user_id: StringUuid::new_v4(),
+                role_ids: vec![StringUuid::new_v4()],
+                granted_by: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cache_subscriber_invalidates_roles_on_assignment() {
+        let tenant_id = StringUuid::new_v4();
+        let user_id = StringUuid::new_v4();
+        let mut cache = MockCacheOperations::new();
+        cache
+            .expect_invalidate_user_roles_for_tenant()
+            .withf(move |u, t| *u == *user_id && *t == *tenant_id)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        CacheInvalidationSubscriber::new(Arc::new(cache), 900)
+            .handle(&DomainEvent::RoleAssigned {
+                tenant_id,
+                user_id,
+                role_ids: vec![],
+                granted_by: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cache_subscriber_blacklists_revoked_session() {
+        let session_id = StringUuid::new_v4();
+        let sid = session_id.to_string();
+        let sid_clone = sid.clone();
+        let mut cache = MockCacheOperations::new();
+        cache
+            .expect_add_to_token_blacklist()
+            .withf(move |jti, ttl| jti == sid && *ttl == 900)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        cache
+            .expect_remove_all_refresh_sessions_for_session()
+            .withf(move |s| s == sid_clone)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        CacheInvalidationSubscriber::new(Arc::new(cache), 900)
+            .handle(&DomainEvent::SessionRevoked {
+                session_id,
+                user_id: StringUuid::new_v4(),
+                device_type: None,
+                device_name: None,
+                reason: SessionRevocationReason::SessionLimit,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_audit_subscriber_records_session_revocation() {
+        let session_id = StringUuid::new_v4();
+        let mut audit = MockAuditRepository::new();
+        audit
+            .expect_create()
+            .withf(move |input| {
+                input.action == "session.revoked"
+                    && input.resource_type == "session"
+                    && input.resource_id == Some(*session_id)
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        AuditSubscriber::new(Arc::new(audit))
+            .handle(&DomainEvent::SessionRevoked {
+                session_id,
+                user_id: StringUuid::new_v4(),
+                device_type: Some("desktop".to_string()),
+                device_name: None,
+                reason: SessionRevocationReason::UserRequest,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_audit_subscriber_ignores_user_created() {
+        let audit = MockAuditRepository::new();
+        AuditSubscriber::new(Arc::new(audit))
+            .handle(&DomainEvent::UserCreated {
+                user_id: StringUuid::new_v4(),
+                email: "a@example.com".to_string(),
+                display_name: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_audit_subscriber_skips_bulk_revocations() {
+        let mut audit = MockAuditRepository::new();
+        audit.expect_create().never();
+
+        AuditSubscriber::new(Arc::new(audit))
+            .handle(&DomainEvent::SessionRevoked {
+                session_id: StringUuid::new_v4(),
+                user_id: StringUuid::new_v4(),
+                device_type: None,
+                device_name: None,
+                reason: SessionRevocationReason::SignOutEverywhere,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_revocation_feed_subscriber_pushes_notice_to_user_tenants() {
+        let session_id = StringUuid::new_v4();
+        let user_id = StringUuid::new_v4();
+        let tenants = vec![StringUuid::new_v4(), StringUuid::new_v4()];
+        let tenants_clone = tenants.clone();
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_append()
+            .withf(move |s, u, r, _| {
+                *s == session_id && *u == user_id && *r == SessionRevocationReason::AdminForceLogout
+            })
+            .times(1)
+            .returning(|session_id, user_id, reason, revoked_at| {
+                Ok(crate::models::session_revocation::RevocationNotice {
+                    sequence: 7,
+                    session_id,
+                    user_id,
+                    reason,
+                    revoked_at,
+                })
+            });
+        repo.expect_list_user_tenant_ids()
+            .returning(move |_| Ok(tenants_clone.clone()));
+        let mut publisher = MockWebhookEventPublisher::new();
+        publisher
+            .expect_trigger_tenant_event()
+            .withf(move |t, e| {
+                tenants.contains(t)
+                    && e.event_type == "session.revocation"
+                    && e.data["sequence"] == 7
+                    && e.data["reason"] == "admin_force_logout"
+            })
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        RevocationFeedSubscriber::new(Arc::new(repo), Arc::new(publisher))
+            .handle(&DomainEvent::SessionRevoked {
+                session_id,
+                user_id,
+                device_type: None,
+                device_name: None,
+                reason: SessionRevocationReason::AdminForceLogout,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_revocation_feed_subscriber_ignores_other_events() {
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_append().never();
+
+        RevocationFeedSubscriber::new(Arc::new(repo), Arc::new(MockWebhookEventPublisher::new()))
+            .handle(&DomainEvent::UserCreated {
+                user_id: StringUuid::new_v4(),
+                email: "a@example.com".to_string(),
+                display_name: None,
+            })
+            .await
+            .unwrap();
+    }
+}
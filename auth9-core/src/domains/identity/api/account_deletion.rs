@@ -0,0 +1,149 @@
+//! Self-service account deletion API handlers
+
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::account_deletion::AccountDeletionRequest;
+use crate::models::common::StringUuid;
+use crate::models::user::User;
+use crate::state::{HasServices, HasSessionManagement, HasSystemSettings};
+use axum::{extract::State, http::HeaderMap, http::StatusCode, response::IntoResponse, Json};
+use chrono::Utc;
+
+/// Deletion notices are best effort; the request itself has been recorded
+async fn notify<S: HasSystemSettings>(state: &S, user: &User, message: &str) {
+    if let Err(e) = state
+        .email_service()
+        .send_account_deletion_notice(
+            &user.email,
+            user.display_name.as_deref(),
+            crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+            message,
+        )
+        .await
+    {
+        tracing::warn!(user_id = %user.id, "Failed to send account deletion notice: {}", e);
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/deletion",
+    tag = "Identity",
+    responses(
+        (status = 202, description = "Account deletion scheduled", body = AccountDeletionRequest),
+        (status = 400, description = "Platform administrators cannot delete their own account"),
+        (status = 409, description = "Deletion already scheduled")
+    )
+)]
+/// Schedule deletion of the signed-in user's account.
+///
+/// All sessions are revoked. Signing in again before `purge_after` offers a
+/// restore; afterwards the account is removed permanently.
+pub async fn schedule_account_deletion<S>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse>
+where
+    S: HasServices + HasSessionManagement + HasSystemSettings,
+{
+    if state.config().is_platform_admin_email(&auth.email) {
+        return Err(AppError::BadRequest(
+            "Platform administrators cannot delete their own account".to_string(),
+        ));
+    }
+    let user_id = StringUuid::from(auth.user_id);
+    let user = state.user_service().get(user_id).await?;
+    let request = state
+        .account_deletion_service()
+        .schedule(user_id, Utc::now())
+        .await?;
+
+    if let Err(e) = state.session_service().force_logout_user(user_id).await {
+        tracing::warn!(%user_id, "Failed to revoke sessions after deletion request: {}", e);
+    }
+    notify(
+        &state,
+        &user,
+        &format!(
+            "Your account is scheduled for deletion on {}. Sign in before then to restore it",
+            request.purge_after.format("%Y-%m-%d")
+        ),
+    )
+    .await;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.deletion.schedule",
+        "user",
+        Some(auth.user_id),
+        None,
+        serde_json::to_value(&request).ok(),
+    )
+    .await;
+    Ok((StatusCode::ACCEPTED, Json(SuccessResponse::new(request))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/deletion",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Scheduled account deletion", body = AccountDeletionRequest),
+        (status = 404, description = "No deletion scheduled")
+    )
+)]
+/// Pending deletion of the signed-in user's account
+pub async fn get_account_deletion<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<Json<SuccessResponse<AccountDeletionRequest>>> {
+    let request = state
+        .account_deletion_service()
+        .status(StringUuid::from(auth.user_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(request)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/deletion/restore",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Account restored", body = AccountDeletionRequest),
+        (status = 404, description = "No deletion scheduled"),
+        (status = 409, description = "Grace period has ended")
+    )
+)]
+/// Cancel the scheduled deletion of the signed-in user's account
+pub async fn restore_account<S>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<AccountDeletionRequest>>>
+where
+    S: HasServices + HasSystemSettings,
+{
+    let user_id = StringUuid::from(auth.user_id);
+    let user = state.user_service().get(user_id).await?;
+    let request = state.account_deletion_service().restore(user_id).await?;
+
+    notify(
+        &state,
+        &user,
+        "Your account has been restored and will not be deleted",
+    )
+    .await;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.deletion.restore",
+        "user",
+        Some(auth.user_id),
+        serde_json::to_value(&request).ok(),
+        None,
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(request)))
+}
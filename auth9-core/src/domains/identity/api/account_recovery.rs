@@ -0,0 +1,470 @@
+//! Account recovery without email access.
+//!
+//! Public endpoints let a user trade a recovery code, a verified secondary
+//! factor or an approved admin-mediated request for a password reset token.
+//! Platform admins review the admin-mediated requests. Failed attempts are
+//! counted per account and every step is audited under the
+//! `account_recovery` resource type.
+
+use crate::cache::CacheOperations;
+use crate::domains::identity::service::AccountRecoveryService;
+use crate::error::{AppError, Result};
+use crate::http_support::{
+    extract_ip, write_audit_log_generic, write_audit_log_with_actor, SuccessResponse,
+};
+use crate::models::account_recovery::{
+    AccountRecoveryDecisionInput, AccountRecoveryRequest, AccountRecoveryRequestCreated,
+    AccountRecoveryRequestDetail, AccountRecoveryStatus, AccountRecoveryTokenResponse,
+    ClaimAccountRecoveryInput, CreateAccountRecoveryRequestInput, RecoveryCodeRecoveryInput,
+    RecoveryFactor, SecondaryFactorRecoveryInput, RECOVERY_REQUEST_TTL_HOURS,
+};
+use crate::models::common::StringUuid;
+use crate::models::user::User;
+use crate::policy::{AuthzContext, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::account_recovery::AccountRecoveryRepositoryImpl;
+use crate::state::{HasCache, HasDbPool, HasMfa, HasPasswordManagement, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Failed recovery attempts allowed per account within the window
+const MAX_FAILED_ATTEMPTS: u64 = 5;
+const FAILED_ATTEMPT_WINDOW_SECS: u64 = 3600;
+
+/// Audit resource type shared by every recovery event
+const AUDIT_RESOURCE: &str = "account_recovery";
+
+fn recovery_service<S: HasDbPool>(
+    state: &S,
+) -> AccountRecoveryService<AccountRecoveryRepositoryImpl> {
+    AccountRecoveryService::new(Arc::new(AccountRecoveryRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+fn failed_attempts_key(user_id: StringUuid) -> String {
+    format!("auth9:account_recovery_fail:{}", user_id)
+}
+
+/// Reject the attempt up front when the account exhausted its failures
+async fn ensure_attempts_left<S: HasCache>(state: &S, user_id: StringUuid) -> Result<()> {
+    let failures = state
+        .cache()
+        .get_counter(&failed_attempts_key(user_id))
+        .await
+        .unwrap_or(0);
+    if failures >= MAX_FAILED_ATTEMPTS {
+        return Err(AppError::TooManyRequests(
+            "Too many failed recovery attempts. Please try again later.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn record_failed_attempt<S: HasServices + HasCache>(
+    state: &S,
+    headers: &HeaderMap,
+    user: &User,
+    method: &str,
+) {
+    let failures = state
+        .cache()
+        .increment_counter(&failed_attempts_key(user.id), FAILED_ATTEMPT_WINDOW_SECS)
+        .await
+        .unwrap_or(0);
+    if failures >= MAX_FAILED_ATTEMPTS {
+        tracing::warn!(
+            user_id = %user.id,
+            failures = failures,
+            "Account recovery blocked after too many failed attempts"
+        );
+    }
+    let _ = write_audit_log_with_actor(
+        state,
+        headers,
+        None,
+        "account_recovery.failed",
+        AUDIT_RESOURCE,
+        Some(*user.id),
+        None,
+        Some(serde_json::json!({ "method": method, "failures": failures })),
+    )
+    .await;
+}
+
+/// Issue the reset token for a recovered account and audit the recovery
+async fn complete_recovery<S: HasServices + HasPasswordManagement>(
+    state: &S,
+    headers: &HeaderMap,
+    user: &User,
+    details: serde_json::Value,
+) -> Result<Json<SuccessResponse<AccountRecoveryTokenResponse>>> {
+    let reset_token = state.password_service().issue_reset_token(user.id).await?;
+    let _ = write_audit_log_with_actor(
+        state,
+        headers,
+        Some(*user.id),
+        "account_recovery.completed",
+        AUDIT_RESOURCE,
+        Some(*user.id),
+        None,
+        Some(details),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(AccountRecoveryTokenResponse {
+        reset_token,
+    })))
+}
+
+/// Look up the account behind a recovery attempt. Unknown emails get the
+/// same error as a wrong code so accounts cannot be enumerated.
+async fn find_recovering_user<S: HasServices>(state: &S, email: &str) -> Result<User> {
+    state
+        .user_service()
+        .get_by_email(email)
+        .await
+        .map_err(|_| invalid_credentials())
+}
+
+fn invalid_credentials() -> AppError {
+    AppError::Unauthorized("Invalid email or recovery credentials.".to_string())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/account-recovery/recovery-code",
+    tag = "Identity",
+    request_body = RecoveryCodeRecoveryInput,
+    responses(
+        (status = 200, description = "Password reset token", body = AccountRecoveryTokenResponse),
+        (status = 401, description = "Unknown email or invalid code"),
+        (status = 429, description = "Too many failed attempts")
+    )
+)]
+/// Recover an account with a one-time recovery code.
+pub async fn recover_with_code<S: HasServices + HasPasswordManagement + HasMfa + HasCache>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Json(input): Json<RecoveryCodeRecoveryInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryTokenResponse>>> {
+    input.validate()?;
+    let user = find_recovering_user(&state, &input.email).await?;
+    ensure_attempts_left(&state, user.id).await?;
+
+    let valid = state
+        .recovery_code_service()
+        .verify_and_consume(&user.id.to_string(), input.code.trim())
+        .await?;
+    if !valid {
+        record_failed_attempt(&state, &headers, &user, "recovery_code").await;
+        return Err(invalid_credentials());
+    }
+
+    complete_recovery(
+        &state,
+        &headers,
+        &user,
+        serde_json::json!({ "method": "recovery_code" }),
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/account-recovery/secondary-factor",
+    tag = "Identity",
+    request_body = SecondaryFactorRecoveryInput,
+    responses(
+        (status = 200, description = "Password reset token", body = AccountRecoveryTokenResponse),
+        (status = 401, description = "Unknown email, factor not enrolled or invalid code"),
+        (status = 429, description = "Too many failed attempts")
+    )
+)]
+/// Recover an account with a verified secondary factor.
+pub async fn recover_with_factor<S: HasServices + HasPasswordManagement + HasMfa + HasCache>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Json(input): Json<SecondaryFactorRecoveryInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryTokenResponse>>> {
+    input.validate()?;
+    let user = find_recovering_user(&state, &input.email).await?;
+    ensure_attempts_left(&state, user.id).await?;
+
+    let user_id = user.id.to_string();
+    let valid = match input.factor {
+        RecoveryFactor::Totp => {
+            state.totp_service().has_totp(&user_id).await?
+                && state
+                    .totp_service()
+                    .verify_code(&user_id, input.code.trim())
+                    .await?
+        }
+    };
+    if !valid {
+        record_failed_attempt(&state, &headers, &user, input.factor.as_str()).await;
+        return Err(invalid_credentials());
+    }
+
+    complete_recovery(
+        &state,
+        &headers,
+        &user,
+        serde_json::json!({ "method": "secondary_factor", "factor": input.factor }),
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/account-recovery/requests",
+    tag = "Identity",
+    request_body = CreateAccountRecoveryRequestInput,
+    responses(
+        (status = 200, description = "Request opened; keep the claim secret to collect the reset token after approval", body = AccountRecoveryRequestCreated)
+    )
+)]
+/// Ask platform admins to recover an account.
+///
+/// The response looks the same whether or not the email belongs to an
+/// account or already has an open request; only a real, newly opened request
+/// can ever be claimed.
+pub async fn create_recovery_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Json(input): Json<CreateAccountRecoveryRequestInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryRequestCreated>>> {
+    input.validate()?;
+
+    let opened = match state.user_service().get_by_email(&input.email).await {
+        Ok(user) => {
+            let opened = recovery_service(&state)
+                .open_request(user.id, input.reason.clone(), extract_ip(&headers))
+                .await?;
+            let _ = write_audit_log_with_actor(
+                &state,
+                &headers,
+                None,
+                if opened.is_some() {
+                    "account_recovery.requested"
+                } else {
+                    "account_recovery.request_suppressed"
+                },
+                AUDIT_RESOURCE,
+                Some(*user.id),
+                None,
+                opened
+                    .as_ref()
+                    .map(|created| serde_json::json!({ "request_id": created.request_id })),
+            )
+            .await;
+            opened
+        }
+        Err(AppError::NotFound(_)) => None,
+        Err(e) => return Err(e),
+    };
+
+    Ok(Json(SuccessResponse::new(opened.unwrap_or_else(|| {
+        AccountRecoveryRequestCreated {
+            request_id: StringUuid::new_v4(),
+            claim_secret:
+                AccountRecoveryService::<AccountRecoveryRepositoryImpl>::generate_claim_secret(),
+            expires_at: Utc::now() + chrono::Duration::hours(RECOVERY_REQUEST_TTL_HOURS),
+        }
+    }))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/account-recovery/requests/{id}/claim",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Recovery request ID (UUID)")),
+    request_body = ClaimAccountRecoveryInput,
+    responses(
+        (status = 200, description = "Password reset token", body = AccountRecoveryTokenResponse),
+        (status = 401, description = "Unknown request or wrong claim secret"),
+        (status = 409, description = "Request not approved, expired or already claimed")
+    )
+)]
+/// Collect the reset token of an approved recovery request.
+pub async fn claim_recovery_request<S: HasServices + HasPasswordManagement + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<ClaimAccountRecoveryInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryTokenResponse>>> {
+    input.validate()?;
+    let request = recovery_service(&state)
+        .claim(StringUuid::from(id), &input.claim_secret)
+        .await?;
+    let user = state.user_service().get(request.user_id).await?;
+
+    complete_recovery(
+        &state,
+        &headers,
+        &user,
+        serde_json::json!({ "method": "admin_approval", "request_id": request.id }),
+    )
+    .await
+}
+
+async fn require_platform_admin<S: HasServices>(state: &S, authz: &AuthzContext) -> Result<()> {
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::PlatformAdmin,
+                scope: ResourceScope::Global,
+            },
+        )
+        .await
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListRecoveryRequestsQuery {
+    /// Filter by status (`pending`, `approved`, `rejected`, `claimed`)
+    pub status: Option<String>,
+    /// Maximum number of requests to return (1-100, default 50)
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/account-recovery/requests",
+    tag = "Identity",
+    params(ListRecoveryRequestsQuery),
+    responses(
+        (status = 200, description = "Recovery requests, newest first", body = Vec<AccountRecoveryRequest>),
+        (status = 403, description = "Platform admin required")
+    )
+)]
+pub async fn list_recovery_requests<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Query(query): Query<ListRecoveryRequestsQuery>,
+) -> Result<Json<SuccessResponse<Vec<AccountRecoveryRequest>>>> {
+    require_platform_admin(&state, &authz).await?;
+    let status = query
+        .status
+        .as_deref()
+        .map(|s| {
+            AccountRecoveryStatus::parse(s)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown recovery status '{}'", s)))
+        })
+        .transpose()?;
+
+    let requests = recovery_service(&state)
+        .list(status, query.limit.unwrap_or(50))
+        .await?;
+    Ok(Json(SuccessResponse::new(requests)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/account-recovery/requests/{id}",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Recovery request ID (UUID)")),
+    responses(
+        (status = 200, description = "Recovery request with admin decisions", body = AccountRecoveryRequestDetail),
+        (status = 404, description = "Request not found")
+    )
+)]
+pub async fn get_recovery_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<AccountRecoveryRequestDetail>>> {
+    require_platform_admin(&state, &authz).await?;
+    let detail = recovery_service(&state).get(StringUuid::from(id)).await?;
+    Ok(Json(SuccessResponse::new(detail)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/account-recovery/requests/{id}/approve",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Recovery request ID (UUID)")),
+    request_body = AccountRecoveryDecisionInput,
+    responses(
+        (status = 200, description = "Approval recorded; the request is approved after two distinct admins", body = AccountRecoveryRequestDetail),
+        (status = 409, description = "Already decided by this admin, or no longer pending")
+    )
+)]
+pub async fn approve_recovery_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<AccountRecoveryDecisionInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryRequestDetail>>> {
+    decide(state, authz, headers, id, input, true).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/account-recovery/requests/{id}/reject",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Recovery request ID (UUID)")),
+    request_body = AccountRecoveryDecisionInput,
+    responses(
+        (status = 200, description = "Request rejected", body = AccountRecoveryRequestDetail),
+        (status = 409, description = "Already decided by this admin, or no longer pending")
+    )
+)]
+pub async fn reject_recovery_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<AccountRecoveryDecisionInput>,
+) -> Result<Json<SuccessResponse<AccountRecoveryRequestDetail>>> {
+    decide(state, authz, headers, id, input, false).await
+}
+
+async fn decide<S: HasServices + HasDbPool>(
+    state: S,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    id: Uuid,
+    input: AccountRecoveryDecisionInput,
+    approve: bool,
+) -> Result<Json<SuccessResponse<AccountRecoveryRequestDetail>>> {
+    require_platform_admin(&state, &authz).await?;
+    input.validate()?;
+
+    let detail = recovery_service(&state)
+        .decide(
+            StringUuid::from(id),
+            authz.user_id(),
+            approve,
+            input.comment.clone(),
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        if approve {
+            "account_recovery.approved"
+        } else {
+            "account_recovery.rejected"
+        },
+        AUDIT_RESOURCE,
+        Some(*detail.request.user_id),
+        None,
+        Some(serde_json::json!({
+            "request_id": detail.request.id,
+            "status": detail.request.status,
+            "comment": input.comment,
+        })),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(detail)))
+}
@@ -0,0 +1,93 @@
+//! Per-service attribute release policy API handlers.
+//!
+//! Service administrators choose which user attributes the service may
+//! receive in ID tokens and userinfo; anything not listed is withheld.
+
+use crate::domains::authorization::api::service::require_service_access;
+use crate::domains::identity::api::progressive_profiling::tenant_custom_scope_claims;
+use crate::domains::identity::service::AttributeReleaseService;
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::attribute_release::{AttributeReleasePolicy, SetAttributeReleasePolicyInput};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{id}/attribute-release",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Service ID (UUID)")),
+    responses(
+        (status = 200, description = "Attributes released to the service", body = AttributeReleasePolicy)
+    )
+)]
+/// Get the attribute release policy of a service. Services that never
+/// configured one get an empty allowlist.
+pub async fn get_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<AttributeReleasePolicy>>> {
+    let service = state.client_service().get(id).await?;
+    require_service_access(
+        state.config(),
+        &auth,
+        service.tenant_id.as_ref().map(|t| t.0),
+    )?;
+
+    let policy = AttributeReleaseService::from_pool(state.db_pool().clone())
+        .get(service.id)
+        .await?;
+    Ok(Json(SuccessResponse::new(policy)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{id}/attribute-release",
+    tag = "Identity",
+    params(("id" = String, Path, description = "Service ID (UUID)")),
+    request_body = SetAttributeReleasePolicyInput,
+    responses(
+        (status = 200, description = "Attribute release policy replaced", body = AttributeReleasePolicy),
+        (status = 422, description = "Unknown attribute name")
+    )
+)]
+/// Replace the attributes released to a service
+pub async fn set_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<SetAttributeReleasePolicyInput>,
+) -> Result<Json<SuccessResponse<AttributeReleasePolicy>>> {
+    let service = state.client_service().get(id).await?;
+    require_service_access(
+        state.config(),
+        &auth,
+        service.tenant_id.as_ref().map(|t| t.0),
+    )?;
+
+    let custom = tenant_custom_scope_claims(&state, service.tenant_id).await?;
+    let release = AttributeReleaseService::from_pool(state.db_pool().clone());
+    let before = release.get(service.id).await?;
+    let after = release.set(service.id, input, &custom).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "service.attribute_release.update",
+        "service",
+        Some(id),
+        Some(serde_json::json!({ "released_attributes": before.released_attributes })),
+        Some(serde_json::json!({ "released_attributes": after.released_attributes })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(after)))
+}
@@ -1,6 +1,9 @@
 //! OpenID Connect discovery and JWKS endpoints.
 
+use super::silent::PROMPT_VALUES;
 use crate::error::Result;
+use crate::models::oauth_scope::{STANDARD_SCOPES, STANDARD_SCOPE_CLAIMS};
+use crate::models::workload_identity::TOKEN_EXCHANGE_GRANT_TYPE;
 use crate::state::HasServices;
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use base64::Engine;
@@ -26,6 +29,9 @@ pub struct OpenIdConfiguration {
     pub scopes_supported: Vec<String>,
     pub token_endpoint_auth_methods_supported: Vec<String>,
     pub claims_supported: Vec<String>,
+    pub prompt_values_supported: Vec<String>,
+    #[serde(default)]
+    pub code_challenge_methods_supported: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -74,27 +80,29 @@ pub async fn openid_configuration<S: HasServices>(State(state): State<S>) -> imp
             "authorization_code".to_string(),
             "client_credentials".to_string(),
             "refresh_token".to_string(),
+            TOKEN_EXCHANGE_GRANT_TYPE.to_string(),
         ],
         subject_types_supported: vec!["public".to_string()],
         id_token_signing_alg_values_supported: algs,
-        scopes_supported: vec![
-            "openid".to_string(),
-            "profile".to_string(),
-            "email".to_string(),
-        ],
+        scopes_supported: STANDARD_SCOPES
+            .iter()
+            .map(|(scope, _)| scope.to_string())
+            .collect(),
         token_endpoint_auth_methods_supported: vec![
             "client_secret_post".to_string(),
             "client_secret_basic".to_string(),
         ],
-        claims_supported: vec![
-            "sub".to_string(),
-            "email".to_string(),
-            "name".to_string(),
-            "iss".to_string(),
-            "aud".to_string(),
-            "exp".to_string(),
-            "iat".to_string(),
-        ],
+        claims_supported: ["sub", "iss", "aud", "exp", "iat"]
+            .into_iter()
+            .chain(
+                STANDARD_SCOPE_CLAIMS
+                    .iter()
+                    .flat_map(|(_, claims)| claims.iter().copied()),
+            )
+            .map(String::from)
+            .collect(),
+        prompt_values_supported: PROMPT_VALUES.iter().map(|p| p.to_string()).collect(),
+        code_challenge_methods_supported: vec!["S256".to_string()],
     })
 }
 
@@ -115,7 +123,7 @@ pub async fn jwks<S: HasServices>(State(state): State<S>) -> impl IntoResponse {
         }
     };
 
-    let public_key = match RsaPublicKey::from_public_key_pem(public_key_pem) {
+    let public_key = match RsaPublicKey::from_public_key_pem(&public_key_pem) {
         Ok(key) => key,
         Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     };
@@ -134,7 +142,7 @@ pub async fn jwks<S: HasServices>(State(state): State<S>) -> impl IntoResponse {
 
     // Include previous key for rotation support (allows verifying tokens signed with old key)
     if let Some(prev_pem) = state.jwt_manager().previous_public_key_pem() {
-        if let Ok(prev_key) = RsaPublicKey::from_public_key_pem(prev_pem) {
+        if let Ok(prev_key) = RsaPublicKey::from_public_key_pem(&prev_pem) {
             let prev_n =
                 base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(prev_key.n().to_bytes_be());
             let prev_e =
@@ -177,6 +185,8 @@ mod tests {
             scopes_supported: vec!["openid".to_string()],
             token_endpoint_auth_methods_supported: vec!["client_secret_post".to_string()],
             claims_supported: vec!["sub".to_string()],
+            prompt_values_supported: vec![],
+            code_challenge_methods_supported: vec![],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -201,6 +211,8 @@ mod tests {
             scopes_supported: vec![],
             token_endpoint_auth_methods_supported: vec![],
             claims_supported: vec![],
+            prompt_values_supported: vec![],
+            code_challenge_methods_supported: vec![],
         };
 
         assert!(config.jwks_uri.is_none());
@@ -236,6 +248,8 @@ mod tests {
                 "client_secret_post".to_string(),
             ],
             claims_supported: vec!["sub".to_string(), "email".to_string(), "name".to_string()],
+            prompt_values_supported: vec![],
+            code_challenge_methods_supported: vec![],
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -1,11 +1,14 @@
 //! Shared helper functions for auth API handlers.
 
+use crate::cache::CacheOperations;
+use crate::crypto::{FlowKind, FlowStateCodec};
 use crate::error::{AppError, Result};
-use crate::jwt::IdentityClaims;
-use crate::state::HasServices;
+use crate::jwt::{IdentityClaims, JwtManager};
+use crate::models::service::{Client, Service};
+use crate::state::{HasCache, HasServices};
 use axum::http::HeaderMap;
 use base64::Engine;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,9 +48,65 @@ pub(crate) struct AuthorizationCodeData {
 /// Authorization code TTL (2 minutes, per OIDC spec recommendation)
 pub(crate) const AUTH_CODE_TTL_SECS: u64 = 120;
 
+/// How long a redeemed authorization code is remembered, so a replay of it
+/// is recognized and the session it was redeemed for revoked (RFC 6749 §4.1.2)
+pub(crate) const AUTH_CODE_REDEMPTION_TTL_SECS: u64 = 600;
+
 /// Login challenge TTL (10 minutes, generous for password + MFA flow)
 pub(crate) const LOGIN_CHALLENGE_TTL_SECS: u64 = 600;
 
+/// Codec for sealed flow-state tokens, keyed from the flow-state secret every
+/// replica shares
+pub(crate) fn flow_state_codec<S: HasServices>(state: &S) -> FlowStateCodec {
+    let config = state.config();
+    FlowStateCodec::from_config(&config.flow_state, &config.jwt.secret)
+}
+
+/// Seal multi-step flow state into a token handed to the client
+pub(crate) fn seal_flow_state<S: HasServices, T: Serialize>(
+    state: &S,
+    kind: FlowKind,
+    data: &T,
+    ttl_secs: u64,
+) -> Result<String> {
+    flow_state_codec(state)
+        .seal(kind, data, ttl_secs)
+        .map_err(|e| AppError::Internal(e.into()))
+}
+
+/// Open a flow-state token without consuming it (e.g. before redirecting to an IdP).
+/// Returns None if the token is invalid or expired.
+pub(crate) fn peek_flow_state<S: HasServices, T: DeserializeOwned>(
+    state: &S,
+    kind: FlowKind,
+    token: &str,
+) -> Option<T> {
+    flow_state_codec(state)
+        .open(kind, token)
+        .ok()
+        .map(|opened| opened.data)
+}
+
+/// Open a flow-state token and claim it for single use.
+/// Returns None if the token is invalid, expired or has already been used.
+pub(crate) async fn consume_flow_state<S: HasServices + HasCache, T: DeserializeOwned>(
+    state: &S,
+    kind: FlowKind,
+    token: &str,
+) -> Result<Option<T>> {
+    let Ok(opened) = flow_state_codec(state).open::<T>(kind, token) else {
+        return Ok(None);
+    };
+    if !state
+        .cache()
+        .claim_flow_state(&opened.jti, opened.remaining_ttl_secs())
+        .await?
+    {
+        return Ok(None);
+    }
+    Ok(Some(opened.data))
+}
+
 /// Verify PKCE S256 code_verifier against stored code_challenge.
 /// Returns true if BASE64URL(SHA256(code_verifier)) == code_challenge.
 pub(crate) fn verify_pkce_s256(code_verifier: &str, code_challenge: &str) -> bool {
@@ -56,32 +115,57 @@ pub(crate) fn verify_pkce_s256(code_verifier: &str, code_challenge: &str) -> boo
     computed == code_challenge
 }
 
-/// Enforce PKCE requirement for public clients.
-/// Public clients (SPAs, mobile, CLI) must use PKCE with S256 per OAuth 2.1.
-/// Confidential clients are not affected (PKCE remains optional).
-pub(crate) fn enforce_pkce_for_public_client(
-    is_public: bool,
+/// Check the PKCE parameters of an authorization request.
+/// Public clients (SPAs, mobile, CLI) must use PKCE per OAuth 2.1, and so must
+/// confidential clients when `OAUTH_REQUIRE_PKCE` is set. A challenge, when
+/// given, must be a well-formed S256 challenge whatever the client type.
+pub(crate) fn enforce_pkce(
+    required: bool,
     code_challenge: &Option<String>,
     code_challenge_method: &Option<String>,
 ) -> Result<()> {
-    if !is_public {
+    let Some(challenge) = code_challenge else {
+        if required {
+            return Err(AppError::BadRequest(
+                "This client must use PKCE (code_challenge required)".to_string(),
+            ));
+        }
         return Ok(());
+    };
+    match code_challenge_method.as_deref() {
+        Some("S256") => {}
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "Only S256 code challenge method is supported, got: {}",
+                other
+            )))
+        }
+        None => {
+            return Err(AppError::BadRequest(
+                "code_challenge_method is required and must be S256".to_string(),
+            ))
+        }
     }
-    if code_challenge.is_none() {
+    // BASE64URL(SHA256(verifier)) without padding is always 43 characters
+    if challenge.len() != 43
+        || !challenge
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
         return Err(AppError::BadRequest(
-            "Public clients must use PKCE (code_challenge required)".to_string(),
+            "code_challenge must be a base64url-encoded SHA-256 hash".to_string(),
         ));
     }
-    match code_challenge_method.as_deref() {
-        Some("S256") => Ok(()),
-        Some(other) => Err(AppError::BadRequest(format!(
-            "Only S256 code challenge method is supported, got: {}",
-            other
-        ))),
-        None => Err(AppError::BadRequest(
-            "code_challenge_method is required and must be S256".to_string(),
-        )),
-    }
+    Ok(())
+}
+
+/// Whether a code_verifier has the form RFC 7636 requires: 43-128
+/// characters from the unreserved set
+pub(crate) fn is_valid_code_verifier(code_verifier: &str) -> bool {
+    (43..=128).contains(&code_verifier.len())
+        && code_verifier
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
 }
 
 /// Validate that a redirect URI is allowed for the service
@@ -142,6 +226,23 @@ pub(crate) fn extract_identity_claims_from_headers<S: HasServices>(
         .map_err(|e| AppError::Unauthorized(format!("Invalid identity token: {}", e)))
 }
 
+/// Token manager for tokens issued to `client`: its own lifetime overrides,
+/// then those of its service's tenant, then the platform defaults.
+pub(crate) async fn client_jwt_manager<S: HasServices>(
+    state: &S,
+    client: &Client,
+    service: &Service,
+) -> Result<JwtManager> {
+    let mut token_ttls = client.token_ttls;
+    if token_ttls.access_token_ttl_secs.is_none() || token_ttls.refresh_token_ttl_secs.is_none() {
+        if let Some(tenant_id) = service.tenant_id {
+            let tenant = state.tenant_service().get(tenant_id).await?;
+            token_ttls = token_ttls.or(tenant.settings.token_ttls);
+        }
+    }
+    Ok(state.jwt_manager().with_ttl_overrides(token_ttls))
+}
+
 // Legacy helpers kept for unit tests and backward-compatibility checks.
 #[cfg(test)]
 pub(crate) fn encode_state(state_payload: &CallbackState) -> Result<String> {
@@ -506,9 +607,12 @@ mod tests {
 
     // ==================== PKCE Enforcement Tests ====================
 
+    /// S256 challenge of the RFC 7636 Appendix B verifier
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
     #[test]
     fn test_enforce_pkce_public_client_no_challenge() {
-        let result = enforce_pkce_for_public_client(true, &None, &None);
+        let result = enforce_pkce(true, &None, &None);
         assert!(
             matches!(result, Err(AppError::BadRequest(ref msg)) if msg.contains("code_challenge required"))
         );
@@ -516,9 +620,9 @@ mod tests {
 
     #[test]
     fn test_enforce_pkce_public_client_with_s256() {
-        let result = enforce_pkce_for_public_client(
+        let result = enforce_pkce(
             true,
-            &Some("challenge-value".to_string()),
+            &Some(CHALLENGE.to_string()),
             &Some("S256".to_string()),
         );
         assert!(result.is_ok());
@@ -526,9 +630,9 @@ mod tests {
 
     #[test]
     fn test_enforce_pkce_public_client_with_plain_method() {
-        let result = enforce_pkce_for_public_client(
+        let result = enforce_pkce(
             true,
-            &Some("challenge-value".to_string()),
+            &Some(CHALLENGE.to_string()),
             &Some("plain".to_string()),
         );
         assert!(matches!(result, Err(AppError::BadRequest(ref msg)) if msg.contains("S256")));
@@ -536,17 +640,53 @@ mod tests {
 
     #[test]
     fn test_enforce_pkce_public_client_no_method() {
-        let result =
-            enforce_pkce_for_public_client(true, &Some("challenge-value".to_string()), &None);
+        let result = enforce_pkce(true, &Some(CHALLENGE.to_string()), &None);
         assert!(matches!(result, Err(AppError::BadRequest(ref msg)) if msg.contains("S256")));
     }
 
     #[test]
     fn test_enforce_pkce_confidential_client_no_challenge() {
-        let result = enforce_pkce_for_public_client(false, &None, &None);
+        let result = enforce_pkce(false, &None, &None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_enforce_pkce_checks_challenge_of_any_client() {
+        for challenge in [
+            "challenge-value",
+            format!("{}=", &CHALLENGE[..42]).as_str(),
+            "",
+        ] {
+            let result = enforce_pkce(
+                false,
+                &Some(challenge.to_string()),
+                &Some("S256".to_string()),
+            );
+            assert!(
+                matches!(result, Err(AppError::BadRequest(ref msg)) if msg.contains("base64url")),
+                "accepted {:?}",
+                challenge
+            );
+        }
+        let result = enforce_pkce(
+            false,
+            &Some(CHALLENGE.to_string()),
+            &Some("plain".to_string()),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_is_valid_code_verifier() {
+        assert!(is_valid_code_verifier(
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk" // pragma: allowlist secret
+        ));
+        assert!(is_valid_code_verifier(&"a~b.".repeat(32)));
+        assert!(!is_valid_code_verifier("too-short"));
+        assert!(!is_valid_code_verifier(&"a".repeat(129)));
+        assert!(!is_valid_code_verifier(&format!("{} ", "a".repeat(43))));
+    }
+
     #[test]
     fn test_authorization_code_data_roundtrip() {
         let data = AuthorizationCodeData {
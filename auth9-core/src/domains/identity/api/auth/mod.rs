@@ -6,14 +6,12 @@ pub(crate) mod helpers;
 pub mod discovery;
 pub mod logout;
 pub mod oidc_flow;
+pub mod silent;
 pub mod token_exchange;
 pub mod types;
 
-/// Allowed OIDC scopes whitelist
-const ALLOWED_SCOPES: &[&str] = &["openid", "profile", "email"];
 #[allow(dead_code)]
 const OIDC_STATE_TTL_SECS: u64 = 300;
-pub(crate) use helpers::LOGIN_CHALLENGE_TTL_SECS;
 
 // Re-export all public items so that `auth::function_name` paths remain valid.
 
@@ -30,12 +28,18 @@ pub use discovery::{__path_jwks, __path_openid_configuration};
 
 // OIDC flow handlers
 pub use oidc_flow::{
-    __path_authorize, __path_authorize_complete, __path_callback, __path_enterprise_sso_discovery,
+    __path_authorize, __path_authorize_complete, __path_authorize_consent, __path_callback,
+    __path_enterprise_sso_discovery,
 };
 pub use oidc_flow::{
-    authorize, authorize_complete, authorize_post, callback, enterprise_sso_discovery, token,
+    authorize, authorize_complete, authorize_consent, authorize_post, callback,
+    enterprise_sso_discovery, token, ConsentQuery,
 };
 
+// Silent authentication
+pub use silent::__path_session_check;
+pub use silent::{session_check, SessionCheckQuery, SessionCheckResponse};
+
 // Token exchange
 pub use token_exchange::{__path_tenant_token, __path_userinfo};
 pub use token_exchange::{tenant_token, userinfo};
@@ -2,21 +2,36 @@
 
 use super::action_helpers::discover_connector_by_domain;
 use super::helpers::{
-    enforce_pkce_for_public_client, validate_redirect_uri, verify_pkce_s256, AuthorizationCodeData,
-    CallbackState, LoginChallengeData, AUTH_CODE_TTL_SECS, LOGIN_CHALLENGE_TTL_SECS,
+    client_jwt_manager, consume_flow_state, enforce_pkce, is_valid_code_verifier, peek_flow_state,
+    seal_flow_state, validate_redirect_uri, verify_pkce_s256, AuthorizationCodeData, CallbackState,
+    LoginChallengeData, AUTH_CODE_REDEMPTION_TTL_SECS, AUTH_CODE_TTL_SECS,
+    LOGIN_CHALLENGE_TTL_SECS,
+};
+use super::silent::{
+    authorization_error_redirect, is_silent_prompt, silent_authorize, SilentAuthorization,
 };
 use super::types::{
     AuthorizeCompleteRequest, AuthorizeCompleteResponse, AuthorizeRequest, CallbackRequest,
     EnterpriseSsoDiscoveryResponse, TokenRequest, TokenResponse,
 };
-use super::ALLOWED_SCOPES;
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
+use crate::domains::identity::api::conditional_access;
+use crate::domains::identity::api::progressive_profiling::{
+    profile_interaction_uri, progressive_profiling_service, scoped_user_claims,
+};
+use crate::domains::identity::service::workload_identity::WorkloadIdentityService;
 use crate::error::oauth::OAuthTokenError;
 use crate::error::{AppError, Result};
 use crate::http_support::SuccessResponse;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
 use crate::models::enterprise_sso::EnterpriseSsoDiscoveryInput;
+use crate::models::oauth_scope::ConsentScreen;
+use crate::models::service::ServiceStatus;
+use crate::models::workload_identity::{FEDERATED_SUBJECT_TOKEN_TYPES, TOKEN_EXCHANGE_GRANT_TYPE};
 use crate::state::{
-    HasAnalytics, HasCache, HasIdentityProviders, HasServices, HasSessionManagement,
+    HasAnalytics, HasCache, HasDbPool, HasIdentityProviders, HasServices, HasSessionManagement,
+    HasTrustedDevices,
 };
 use axum::{
     extract::{Query, State},
@@ -28,33 +43,20 @@ use base64::Engine;
 use url::Url;
 use validator::Validate;
 
-/// Filter and validate scope parameter against whitelist
-pub(super) fn filter_scopes(requested_scope: &str) -> Result<String> {
-    let scopes: Vec<&str> = requested_scope
-        .split_whitespace()
-        .filter(|s| ALLOWED_SCOPES.contains(s))
-        .collect();
-
-    // At minimum, openid scope is required
-    if !scopes.contains(&"openid") {
-        return Err(AppError::BadRequest(
-            "scope must include 'openid'".to_string(),
-        ));
-    }
-
-    Ok(scopes.join(" "))
-}
+/// Scope assumed for refresh tokens that predate scope recording; matches
+/// the claims ID tokens carried before they were scoped
+const LEGACY_REFRESH_SCOPE: &str = "openid profile email";
 
 #[utoipa::path(
     get,
     path = "/api/v1/auth/authorize",
     tag = "Identity",
     responses(
-        (status = 302, description = "Redirect to OIDC provider")
+        (status = 302, description = "Redirect to hosted login, or back to the client for prompt=none")
     )
 )]
 /// Login redirect (initiates OIDC flow) - GET handler
-pub async fn authorize<S: HasServices + HasCache + crate::state::HasDbPool>(
+pub async fn authorize<S: HasServices + HasCache + HasDbPool + HasSessionManagement>(
     State(state): State<S>,
     Query(params): Query<AuthorizeRequest>,
 ) -> Result<Response> {
@@ -62,7 +64,7 @@ pub async fn authorize<S: HasServices + HasCache + crate::state::HasDbPool>(
 }
 
 /// Login redirect (initiates OIDC flow) - POST handler (OIDC Core Section 3.1.2.1)
-pub async fn authorize_post<S: HasServices + HasCache + crate::state::HasDbPool>(
+pub async fn authorize_post<S: HasServices + HasCache + HasDbPool + HasSessionManagement>(
     State(state): State<S>,
     axum::extract::Form(params): axum::extract::Form<AuthorizeRequest>,
 ) -> Result<Response> {
@@ -70,7 +72,7 @@ pub async fn authorize_post<S: HasServices + HasCache + crate::state::HasDbPool>
 }
 
 /// Shared authorize logic for both GET (Query) and POST (Form) handlers.
-async fn authorize_inner<S: HasServices + HasCache + crate::state::HasDbPool>(
+async fn authorize_inner<S: HasServices + HasCache + HasDbPool + HasSessionManagement>(
     state: S,
     params: AuthorizeRequest,
 ) -> Result<Response> {
@@ -87,13 +89,14 @@ async fn authorize_inner<S: HasServices + HasCache + crate::state::HasDbPool>(
         .get_by_client_id(&params.client_id)
         .await?;
 
-    // Enforce PKCE for public clients (OAuth 2.1 / RFC 7636)
+    // Enforce PKCE for public clients, and for all clients when configured
+    // (OAuth 2.1 / RFC 7636)
     let client_record = state
         .client_service()
         .get_client_record(&params.client_id)
         .await?;
-    enforce_pkce_for_public_client(
-        client_record.public_client,
+    enforce_pkce(
+        client_record.public_client || state.config().oauth.require_pkce,
         &params.code_challenge,
         &params.code_challenge_method,
     )?;
@@ -107,8 +110,41 @@ async fn authorize_inner<S: HasServices + HasCache + crate::state::HasDbPool>(
         ));
     }
 
-    // Validate and filter scope against whitelist
-    let filtered_scope = filter_scopes(&params.scope)?;
+    // Only standard OIDC scopes and scopes declared by the service are accepted
+    let filtered_scope = state
+        .scope_catalog_service()
+        .resolve(service.id, &params.scope)
+        .await?;
+
+    // prompt=none never shows a page: answer from the existing session or
+    // redirect back with an error the client can act on
+    match is_silent_prompt(params.prompt.as_deref()) {
+        Ok(false) => {}
+        Ok(true) => {
+            return silent_authorize(
+                &state,
+                SilentAuthorization {
+                    client_id: params.client_id,
+                    redirect_uri: params.redirect_uri,
+                    scope: filtered_scope,
+                    state: params.state,
+                    nonce: params.nonce,
+                    code_challenge: params.code_challenge,
+                    code_challenge_method: params.code_challenge_method,
+                    id_token_hint: params.id_token_hint,
+                },
+            )
+            .await;
+        }
+        Err(description) => {
+            return authorization_error_redirect(
+                &params.redirect_uri,
+                "invalid_request",
+                &description,
+                Some(&params.state),
+            );
+        }
+    }
 
     // Resolve connector_alias: both OIDC and SAML connectors go to Auth9 enterprise broker.
     if let Some(alias) = params.connector_alias.as_deref() {
@@ -132,13 +168,12 @@ async fn authorize_inner<S: HasServices + HasCache + crate::state::HasDbPool>(
                 code_challenge: params.code_challenge,
                 code_challenge_method: params.code_challenge_method,
             };
-            let challenge_id = uuid::Uuid::new_v4().to_string();
-            let challenge_json =
-                serde_json::to_string(&challenge_data).map_err(|e| AppError::Internal(e.into()))?;
-            state
-                .cache()
-                .store_login_challenge(&challenge_id, &challenge_json, LOGIN_CHALLENGE_TTL_SECS)
-                .await?;
+            let challenge_id = seal_flow_state(
+                &state,
+                FlowKind::LoginChallenge,
+                &challenge_data,
+                LOGIN_CHALLENGE_TTL_SECS,
+            )?;
 
             let base = state
                 .config()
@@ -166,13 +201,12 @@ async fn authorize_inner<S: HasServices + HasCache + crate::state::HasDbPool>(
         code_challenge: params.code_challenge,
         code_challenge_method: params.code_challenge_method,
     };
-    let challenge_id = uuid::Uuid::new_v4().to_string();
-    let challenge_json =
-        serde_json::to_string(&challenge_data).map_err(|e| AppError::Internal(e.into()))?;
-    state
-        .cache()
-        .store_login_challenge(&challenge_id, &challenge_json, LOGIN_CHALLENGE_TTL_SECS)
-        .await?;
+    let challenge_id = seal_flow_state(
+        &state,
+        FlowKind::LoginChallenge,
+        &challenge_data,
+        LOGIN_CHALLENGE_TTL_SECS,
+    )?;
 
     let portal_url = state
         .config()
@@ -212,7 +246,14 @@ pub async fn enterprise_sso_discovery<S: HasServices + HasCache + crate::state::
     let discovery = discover_connector_by_domain(state.db_pool(), domain).await?;
 
     // Both OIDC and SAML: Auth9 enterprise broker handles natively
-    let filtered_scope = filter_scopes(&params.scope)?;
+    let service = state
+        .client_service()
+        .get_by_client_id(&params.client_id)
+        .await?;
+    let filtered_scope = state
+        .scope_catalog_service()
+        .resolve(service.id, &params.scope)
+        .await?;
     let challenge_data = LoginChallengeData {
         client_id: params.client_id,
         redirect_uri: params.redirect_uri,
@@ -222,13 +263,12 @@ pub async fn enterprise_sso_discovery<S: HasServices + HasCache + crate::state::
         code_challenge: params.code_challenge,
         code_challenge_method: params.code_challenge_method,
     };
-    let challenge_id = uuid::Uuid::new_v4().to_string();
-    let challenge_json =
-        serde_json::to_string(&challenge_data).map_err(|e| AppError::Internal(e.into()))?;
-    state
-        .cache()
-        .store_login_challenge(&challenge_id, &challenge_json, LOGIN_CHALLENGE_TTL_SECS)
-        .await?;
+    let challenge_id = seal_flow_state(
+        &state,
+        FlowKind::LoginChallenge,
+        &challenge_data,
+        LOGIN_CHALLENGE_TTL_SECS,
+    )?;
 
     let base = state
         .config()
@@ -316,7 +356,7 @@ pub async fn callback<S: HasServices + HasCache>(
 /// Complete the OIDC authorization flow after hosted login.
 /// The caller must provide a valid identity token (from hosted-login) and the login_challenge_id.
 /// Returns a redirect URL containing the authorization code and original state.
-pub async fn authorize_complete<S: HasServices + HasCache>(
+pub async fn authorize_complete<S: HasServices + HasCache + HasDbPool>(
     State(state): State<S>,
     headers: HeaderMap,
     Json(params): Json<AuthorizeCompleteRequest>,
@@ -328,18 +368,41 @@ pub async fn authorize_complete<S: HasServices + HasCache>(
     })?;
 
     // 2. Consume login challenge
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(&params.login_challenge_id)
-        .await?
-        .ok_or_else(|| AppError::BadRequest("Invalid or expired login challenge".to_string()))?;
     let challenge: LoginChallengeData =
-        serde_json::from_str(&challenge_json).map_err(|e| AppError::Internal(e.into()))?;
+        consume_flow_state(&state, FlowKind::LoginChallenge, &params.login_challenge_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest("Invalid or expired login challenge".to_string())
+            })?;
 
-    // 3. Generate authorization code
+    // 3. Drop scopes whose required permissions the user does not hold
+    let scope = grantable_scope(
+        &state,
+        &identity_claims.sub,
+        &challenge.client_id,
+        &challenge.scope,
+    )
+    .await?;
+
+    // Remember the grant so later prompt=none requests in this session
+    // can be answered without the user
+    if let Err(e) = state
+        .cache()
+        .store_session_consent(
+            &session_id,
+            &challenge.client_id,
+            &challenge.scope,
+            state.config().jwt.refresh_token_ttl_secs.max(0) as u64,
+        )
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to record session consent");
+    }
+
+    // 4. Generate authorization code
     let code = uuid::Uuid::new_v4().to_string();
 
-    // 4. Store authorization code data
+    // 5. Store authorization code data
     let code_data = AuthorizationCodeData {
         user_id: identity_claims.sub,
         email: identity_claims.email,
@@ -347,7 +410,7 @@ pub async fn authorize_complete<S: HasServices + HasCache>(
         session_id,
         client_id: challenge.client_id.clone(),
         redirect_uri: challenge.redirect_uri.clone(),
-        scope: challenge.scope,
+        scope,
         nonce: challenge.nonce,
         code_challenge: challenge.code_challenge,
         code_challenge_method: challenge.code_challenge_method,
@@ -358,7 +421,7 @@ pub async fn authorize_complete<S: HasServices + HasCache>(
         .store_authorization_code(&code, &code_json, AUTH_CODE_TTL_SECS)
         .await?;
 
-    // 5. Build redirect URL
+    // 6. Build redirect URL
     let mut redirect_url = Url::parse(&challenge.redirect_uri)
         .map_err(|e| AppError::BadRequest(format!("Invalid redirect_uri: {}", e)))?;
     {
@@ -376,13 +439,146 @@ pub async fn authorize_complete<S: HasServices + HasCache>(
     )))
 }
 
+/// A code that was already redeemed is presented again: the first
+/// redemption may have been an attacker's, so revoke the session its tokens
+/// belong to (RFC 6749 §4.1.2)
+async fn revoke_replayed_code_session<S: HasServices + HasSessionManagement + HasCache>(
+    state: &S,
+    code: &str,
+) {
+    let session_id = match state.cache().get_authorization_code_redemption(code).await {
+        Ok(Some(session_id)) => session_id,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to look up authorization code redemption");
+            return;
+        }
+    };
+    metrics::counter!("auth9_auth_code_replay_total").increment(1);
+    tracing::warn!(
+        session_id = %session_id,
+        "Authorization code replayed; revoking the session it was redeemed for"
+    );
+
+    let Ok(sid) = StringUuid::parse_str(&session_id) else {
+        return;
+    };
+    match state.session_service().find_session(sid).await {
+        Ok(Some(session)) if session.revoked_at.is_none() => {
+            if let Err(e) = state
+                .session_service()
+                .revoke_session(session.id, session.user_id)
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to revoke session of replayed authorization code");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load session of replayed authorization code");
+        }
+    }
+    let blacklist_ttl = state.config().jwt.access_token_ttl_secs.unsigned_abs();
+    if let Err(e) = state
+        .cache()
+        .add_to_token_blacklist(&session_id, blacklist_ttl)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to blacklist session of replayed authorization code");
+    }
+    if let Err(e) = state
+        .cache()
+        .remove_all_refresh_sessions_for_session(&session_id)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to clean up refresh sessions of replayed authorization code");
+    }
+}
+
+/// Narrow a requested scope to what the user may be granted. Permission-gated
+/// scopes are only granted on tenant services, from the user's roles there.
+pub(super) async fn grantable_scope<S: HasServices>(
+    state: &S,
+    user_id: &str,
+    client_id: &str,
+    scope: &str,
+) -> Result<String> {
+    let service = state.client_service().get_by_client_id(client_id).await?;
+    let catalog = state.scope_catalog_service();
+    if !catalog.requires_permissions(service.id, scope).await? {
+        return Ok(scope.to_string());
+    }
+
+    let permissions = match (service.tenant_id, StringUuid::parse_str(user_id)) {
+        (Some(tenant_id), Ok(user_id)) => {
+            state
+                .rbac_service()
+                .get_user_roles_for_service(
+                    UserId::from(user_id),
+                    TenantId::from(tenant_id),
+                    ServiceId::from(service.id),
+                )
+                .await?
+                .permissions
+        }
+        _ => Vec::new(),
+    };
+    catalog.grantable(service.id, scope, &permissions).await
+}
+
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams)]
+pub struct ConsentQuery {
+    pub login_challenge: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/authorize/consent",
+    tag = "Identity",
+    params(ConsentQuery),
+    responses(
+        (status = 200, description = "Scopes requested by the pending authorization", body = ConsentScreen),
+        (status = 400, description = "Invalid or expired login challenge")
+    )
+)]
+/// Describe the scopes of a pending authorization request for the consent screen
+pub async fn authorize_consent<S: HasServices>(
+    State(state): State<S>,
+    Query(query): Query<ConsentQuery>,
+) -> Result<Json<SuccessResponse<ConsentScreen>>> {
+    let challenge: LoginChallengeData =
+        peek_flow_state(&state, FlowKind::LoginChallenge, &query.login_challenge).ok_or_else(
+            || AppError::BadRequest("Invalid or expired login challenge".to_string()),
+        )?;
+    let service = state
+        .client_service()
+        .get_by_client_id(&challenge.client_id)
+        .await?;
+    let scopes = state
+        .scope_catalog_service()
+        .consent(service.id, &challenge.scope)
+        .await?;
+
+    Ok(Json(SuccessResponse::new(ConsentScreen {
+        client_id: challenge.client_id,
+        service_name: service.name,
+        scopes,
+    })))
+}
+
 /// OIDC Token endpoint.
 ///
 /// Accepts both `application/x-www-form-urlencoded` (per OIDC Core spec) and
 /// `application/json` (backwards compatibility). Supports `client_secret_basic`
 /// (HTTP Basic) and `client_secret_post` authentication methods.
 pub async fn token<
-    S: HasServices + HasSessionManagement + HasCache + HasAnalytics + HasIdentityProviders,
+    S: HasServices
+        + HasSessionManagement
+        + HasCache
+        + HasAnalytics
+        + HasIdentityProviders
+        + HasDbPool
+        + HasTrustedDevices,
 >(
     State(state): State<S>,
     headers: HeaderMap,
@@ -461,10 +657,11 @@ pub async fn token<
             let code_data_json = match state.cache().consume_authorization_code(&code).await {
                 Ok(Some(json)) => json,
                 Ok(None) => {
+                    revoke_replayed_code_session(&state, &code).await;
                     return Ok(OAuthTokenError::InvalidGrant(
                         "Invalid or expired authorization code".into(),
                     )
-                    .into_response())
+                    .into_response());
                 }
                 Err(e) => {
                     return Ok(
@@ -474,6 +671,17 @@ pub async fn token<
             };
             let code_data: AuthorizationCodeData =
                 serde_json::from_str(&code_data_json).map_err(|e| AppError::Internal(e.into()))?;
+            if let Err(e) = state
+                .cache()
+                .mark_authorization_code_redeemed(
+                    &code,
+                    &code_data.session_id,
+                    AUTH_CODE_REDEMPTION_TTL_SECS,
+                )
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to record authorization code redemption");
+            }
 
             // Validate client_id and redirect_uri match
             if code_data.client_id != client_id {
@@ -489,11 +697,13 @@ pub async fn token<
                 .into_response());
             }
 
-            // Defensive: public clients must have PKCE (enforced at authorize, belt-and-suspenders)
+            // Defensive: PKCE is enforced at authorize, belt-and-suspenders
             let client_record = state.client_service().get_client_record(&client_id).await?;
-            if client_record.public_client && code_data.code_challenge.is_none() {
+            if (client_record.public_client || state.config().oauth.require_pkce)
+                && code_data.code_challenge.is_none()
+            {
                 return Ok(OAuthTokenError::InvalidRequest(
-                    "Public clients must use PKCE (code_challenge required)".into(),
+                    "This client must use PKCE (code_challenge required)".into(),
                 )
                 .into_response());
             }
@@ -509,6 +719,12 @@ pub async fn token<
                         .into_response())
                     }
                 };
+                if !is_valid_code_verifier(&verifier) {
+                    return Ok(OAuthTokenError::InvalidGrant(
+                        "code_verifier must be 43-128 unreserved characters".into(),
+                    )
+                    .into_response());
+                }
                 let method = code_data.code_challenge_method.as_deref().unwrap_or("S256");
                 if method != "S256" {
                     return Ok(OAuthTokenError::InvalidRequest(format!(
@@ -523,6 +739,14 @@ pub async fn token<
                             .into_response(),
                     );
                 }
+            } else if code_verifier.is_some() {
+                // A verifier without a challenge means the authorization
+                // request lost its PKCE parameters on the way (downgrade)
+                return Ok(OAuthTokenError::InvalidGrant(
+                    "code_verifier was sent but the authorization request had no code_challenge"
+                        .into(),
+                )
+                .into_response());
             }
 
             let user_id: uuid::Uuid = code_data
@@ -533,19 +757,75 @@ pub async fn token<
                 AppError::Internal(anyhow::anyhow!("Invalid session_id in auth code"))
             })?;
 
+            // Conditional access: tenant policies may block or require MFA for this request
+            let service = state
+                .client_service()
+                .get(*client_record.service_id)
+                .await?;
+            let jwt_manager = &client_jwt_manager(&state, &client_record, &service).await?;
+            if let Some(tenant_id) = service.tenant_id {
+                match conditional_access::check_token_request(
+                    &state,
+                    tenant_id,
+                    &client_id,
+                    user_id.into(),
+                    session_id.into(),
+                )
+                .await
+                {
+                    Ok(Some(denied)) => return Ok(denied.into_response()),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to evaluate conditional access policies");
+                    }
+                }
+            }
+
+            // Progressive profiling: the service may require profile fields for this scope
+            match progressive_profiling_service(&state)
+                .missing_fields(
+                    client_record.service_id,
+                    &code_data.scope,
+                    user_id.into(),
+                    code_data.display_name.as_deref(),
+                )
+                .await
+            {
+                Ok(missing_fields) if !missing_fields.is_empty() => {
+                    return Ok(OAuthTokenError::InteractionRequired {
+                        description: "Additional profile information is required".into(),
+                        interaction_uri: profile_interaction_uri(
+                            state.config(),
+                            &client_id,
+                            &code_data.scope,
+                        ),
+                        missing_fields,
+                    }
+                    .into_response());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to evaluate profile requirements");
+                }
+            }
+
             // Create identity token
-            let identity_token = jwt_manager.create_identity_token_with_session(
+            let identity_token = jwt_manager.create_oidc_access_token(
                 user_id,
                 &code_data.email,
                 code_data.display_name.as_deref(),
-                Some(session_id),
+                session_id,
+                &client_id,
+                &code_data.scope,
             )?;
 
-            // Create id_token (OIDC spec)
+            // Create id_token (OIDC spec) with the claims the granted scope releases
+            let user = state.user_service().get(user_id.into()).await?;
+            let user_claims =
+                scoped_user_claims(&state, Some(&service), &user, &code_data.scope).await?;
             let id_token = jwt_manager.create_id_token(
                 user_id,
-                &code_data.email,
-                code_data.display_name.as_deref(),
+                user_claims,
                 code_data.nonce.as_deref(),
                 &client_id,
                 Some(session_id),
@@ -553,11 +833,15 @@ pub async fn token<
             )?;
 
             // Create OIDC refresh token
-            let refresh_token =
-                jwt_manager.create_oidc_refresh_token(user_id, &client_id, session_id)?;
+            let refresh_token = jwt_manager.create_oidc_refresh_token(
+                user_id,
+                &client_id,
+                session_id,
+                &code_data.scope,
+            )?;
 
             // Bind refresh token to session
-            let refresh_ttl = state.config().jwt.refresh_token_ttl_secs.max(1) as u64;
+            let refresh_ttl = jwt_manager.refresh_token_ttl().max(1) as u64;
             state
                 .cache()
                 .bind_refresh_token_session(&refresh_token, &session_id.to_string(), refresh_ttl)
@@ -621,6 +905,8 @@ pub async fn token<
                     .into_response())
                 }
             };
+            let client_record = state.client_service().get_client_record(&client_id).await?;
+            let jwt_manager = &client_jwt_manager(&state, &client_record, &service).await?;
 
             let email = format!("service+{}@auth9.local", client_id);
             let tenant_id = service.tenant_id.map(|t| t.0);
@@ -711,18 +997,34 @@ pub async fn token<
 
             let user = state.user_service().get(user_id).await?;
 
+            let client_record = state.client_service().get_client_record(&client_id).await?;
+            let service = state
+                .client_service()
+                .get(*client_record.service_id)
+                .await?;
+            let jwt_manager = &client_jwt_manager(&state, &client_record, &service).await?;
+
+            // Refresh tokens issued before scopes were recorded keep the
+            // claims they always carried
+            let scope = refresh_claims
+                .scope
+                .as_deref()
+                .unwrap_or(LEGACY_REFRESH_SCOPE);
+
             // Issue new tokens (rotation)
-            let new_identity_token = jwt_manager.create_identity_token_with_session(
+            let new_identity_token = jwt_manager.create_oidc_access_token(
                 *user.id,
                 &user.email,
                 user.display_name.as_deref(),
-                Some(session_id),
+                session_id,
+                &client_id,
+                scope,
             )?;
 
+            let user_claims = scoped_user_claims(&state, Some(&service), &user, scope).await?;
             let new_id_token = jwt_manager.create_id_token(
                 *user.id,
-                &user.email,
-                user.display_name.as_deref(),
+                user_claims,
                 None, // nonce is only for initial token issuance
                 &client_id,
                 Some(session_id),
@@ -730,10 +1032,10 @@ pub async fn token<
             )?;
 
             let new_refresh_token =
-                jwt_manager.create_oidc_refresh_token(*user.id, &client_id, session_id)?;
+                jwt_manager.create_oidc_refresh_token(*user.id, &client_id, session_id, scope)?;
 
             // Rotate: unbind old, bind new, and blacklist old token's JTI
-            let refresh_ttl = state.config().jwt.refresh_token_ttl_secs.max(1) as u64;
+            let refresh_ttl = jwt_manager.refresh_token_ttl().max(1) as u64;
             state
                 .cache()
                 .remove_refresh_token_session(&refresh_token)
@@ -759,6 +1061,99 @@ pub async fn token<
             })
             .into_response())
         }
+        TOKEN_EXCHANGE_GRANT_TYPE => {
+            let client_id = match params.client_id {
+                Some(c) => c,
+                None => {
+                    return Ok(OAuthTokenError::InvalidRequest(
+                        "Missing required parameter: client_id".into(),
+                    )
+                    .into_response())
+                }
+            };
+            let subject_token = match params.subject_token {
+                Some(t) => t,
+                None => {
+                    return Ok(OAuthTokenError::InvalidRequest(
+                        "Missing required parameter: subject_token".into(),
+                    )
+                    .into_response())
+                }
+            };
+            if !params
+                .subject_token_type
+                .as_deref()
+                .is_some_and(|t| FEDERATED_SUBJECT_TOKEN_TYPES.contains(&t))
+            {
+                return Ok(OAuthTokenError::InvalidRequest(format!(
+                    "subject_token_type must be one of: {}",
+                    FEDERATED_SUBJECT_TOKEN_TYPES.join(", ")
+                ))
+                .into_response());
+            }
+
+            let service = match state.client_service().get_by_client_id(&client_id).await {
+                Ok(s) if s.status == ServiceStatus::Active => s,
+                Ok(_) | Err(AppError::NotFound(_)) => {
+                    return Ok(
+                        OAuthTokenError::InvalidClient("Unknown or inactive client".into())
+                            .into_response(),
+                    )
+                }
+                Err(e) => return Err(e),
+            };
+
+            let workload = match WorkloadIdentityService::from_pool(state.db_pool().clone())
+                .authenticate(service.id, &subject_token)
+                .await
+            {
+                Ok(w) => w,
+                Err(AppError::Unauthorized(reason)) => {
+                    metrics::counter!(
+                        "auth9_workload_identity_exchanges_total",
+                        "result" => "rejected"
+                    )
+                    .increment(1);
+                    tracing::info!(client_id = %client_id, reason = %reason, "Workload token rejected");
+                    return Ok(OAuthTokenError::InvalidGrant(reason).into_response());
+                }
+                Err(e) => return Err(e),
+            };
+            metrics::counter!(
+                "auth9_workload_identity_exchanges_total",
+                "result" => "issued"
+            )
+            .increment(1);
+            tracing::info!(
+                client_id = %client_id,
+                rule_id = %workload.rule.id,
+                subject = %workload.subject,
+                "Issued service token for federated workload"
+            );
+
+            let client_record = state.client_service().get_client_record(&client_id).await?;
+            let jwt_manager = &client_jwt_manager(&state, &client_record, &service).await?;
+            // Federated tokens never outlive the client's regular access tokens
+            let ttl = i64::from(workload.rule.token_ttl_secs).min(jwt_manager.access_token_ttl());
+            let email = format!("service+{}@auth9.local", client_id);
+            let service_token = jwt_manager.create_service_client_token_with_ttl(
+                service.id.0,
+                &email,
+                Some(workload.rule.tenant_id.0),
+                ttl,
+            )?;
+
+            let mut body = serde_json::to_value(TokenResponse {
+                access_token: service_token,
+                token_type: "Bearer".to_string(),
+                expires_in: ttl,
+                refresh_token: None,
+                id_token: None,
+            })
+            .map_err(|e| AppError::Internal(e.into()))?;
+            body["issued_token_type"] = "urn:ietf:params:oauth:token-type:access_token".into();
+            Ok(Json(body).into_response())
+        }
         _ => Ok(OAuthTokenError::UnsupportedGrantType(format!(
             "Unsupported grant_type: {}",
             params.grant_type
@@ -770,6 +1165,7 @@ pub async fn token<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::oauth_scope::resolve_requested_scopes;
 
     #[test]
     fn test_authorize_request_deserialization() {
@@ -822,26 +1218,26 @@ mod tests {
     }
 
     #[test]
-    fn test_filter_scopes_valid() {
-        let result = filter_scopes("openid profile email").unwrap();
+    fn test_resolve_scopes_valid() {
+        let result = resolve_requested_scopes("openid profile email", &[]).unwrap();
         assert_eq!(result, "openid profile email");
     }
 
     #[test]
-    fn test_filter_scopes_removes_invalid() {
-        let result = filter_scopes("openid admin offline_access profile").unwrap();
-        assert_eq!(result, "openid profile");
+    fn test_resolve_scopes_rejects_undeclared() {
+        let result = resolve_requested_scopes("openid admin offline_access profile", &[]);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_filter_scopes_requires_openid() {
-        let result = filter_scopes("profile email");
+    fn test_resolve_scopes_requires_openid() {
+        let result = resolve_requested_scopes("profile email", &[]);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_filter_scopes_only_openid() {
-        let result = filter_scopes("openid").unwrap();
+    fn test_resolve_scopes_only_openid() {
+        let result = resolve_requested_scopes("openid", &[]).unwrap();
         assert_eq!(result, "openid");
     }
 
@@ -873,6 +1269,21 @@ mod tests {
         assert_eq!(request.client_secret, Some("secret123".to_string()));
     }
 
+    #[test]
+    fn test_token_request_token_exchange_form() {
+        let body = "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Atoken-exchange\
+                    &client_id=deployer&subject_token=eyJ.x.y\
+                    &subject_token_type=urn%3Aietf%3Aparams%3Aoauth%3Atoken-type%3Aid_token";
+
+        let request: TokenRequest = serde_urlencoded::from_str(body).unwrap();
+        assert_eq!(request.grant_type, TOKEN_EXCHANGE_GRANT_TYPE);
+        assert_eq!(request.subject_token.as_deref(), Some("eyJ.x.y"));
+        assert!(
+            FEDERATED_SUBJECT_TOKEN_TYPES.contains(&request.subject_token_type.as_deref().unwrap())
+        );
+        assert!(request.client_secret.is_none());
+    }
+
     #[test]
     fn test_token_request_refresh_token() {
         let json = r#"{
@@ -1013,22 +1424,20 @@ mod tests {
     }
 
     #[test]
-    fn test_filter_scopes_all_invalid_except_openid() {
-        let result = filter_scopes("openid admin root superuser").unwrap();
-        assert_eq!(result, "openid");
+    fn test_resolve_scopes_all_undeclared_except_openid() {
+        let result = resolve_requested_scopes("openid admin root superuser", &[]);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_filter_scopes_duplicate_openid() {
-        let result = filter_scopes("openid openid profile").unwrap();
-        // Both openid entries pass the filter
-        assert!(result.contains("openid"));
-        assert!(result.contains("profile"));
+    fn test_resolve_scopes_duplicate_openid() {
+        let result = resolve_requested_scopes("openid openid profile", &[]).unwrap();
+        assert_eq!(result, "openid profile");
     }
 
     #[test]
-    fn test_filter_scopes_empty_string() {
-        let result = filter_scopes("");
+    fn test_resolve_scopes_empty_string() {
+        let result = resolve_requested_scopes("", &[]);
         assert!(result.is_err());
     }
 
@@ -0,0 +1,318 @@
+//! Silent authentication (`prompt=none`) and session checks for SPAs.
+//!
+//! Auth9 keeps no browser cookie of its own: the login session is identified
+//! by the `sid` of an ID token the client already holds, passed back as
+//! `id_token_hint`. A silent authorization succeeds only while that session
+//! is live and the client was already granted every requested scope in it;
+//! otherwise the client is redirected back with `login_required` or
+//! `consent_required` instead of being sent to the login page.
+
+use super::helpers::{AuthorizationCodeData, AUTH_CODE_TTL_SECS};
+use super::oidc_flow::grantable_scope;
+use crate::cache::CacheOperations;
+use crate::error::{AppError, Result};
+use crate::http_support::SuccessResponse;
+use crate::jwt::IdTokenClaims;
+use crate::models::common::StringUuid;
+use crate::models::user::User;
+use crate::state::{HasCache, HasDbPool, HasServices, HasSessionManagement};
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use utoipa::{IntoParams, ToSchema};
+
+/// `prompt` values accepted by the authorization endpoint
+pub(crate) const PROMPT_VALUES: &[&str] = &["none", "login", "consent", "select_account"];
+
+/// Whether an authorization request asks for silent authentication.
+/// `none` must be the only value (OIDC Core 3.1.2.1).
+pub(crate) fn is_silent_prompt(prompt: Option<&str>) -> std::result::Result<bool, String> {
+    let Some(prompt) = prompt else {
+        return Ok(false);
+    };
+    let values: Vec<&str> = prompt.split_whitespace().collect();
+    if let Some(unknown) = values.iter().find(|v| !PROMPT_VALUES.contains(v)) {
+        return Err(format!("Unsupported prompt value '{}'", unknown));
+    }
+    let silent = values.contains(&"none");
+    if silent && values.len() > 1 {
+        return Err("prompt=none cannot be combined with other values".to_string());
+    }
+    Ok(silent)
+}
+
+/// Why a silent authorization could not complete without the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SilentAuthError {
+    /// No live login session for the hint
+    LoginRequired,
+    /// The session never granted this client some requested scope
+    ConsentRequired,
+}
+
+impl SilentAuthError {
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Self::LoginRequired => "login_required",
+            Self::ConsentRequired => "consent_required",
+        }
+    }
+}
+
+/// Redirect an authorization error back to the client (RFC 6749 4.1.2.1).
+/// Only used once `redirect_uri` has been validated against the client.
+pub(crate) fn authorization_error_redirect(
+    redirect_uri: &str,
+    error: &str,
+    description: &str,
+    state: Option<&str>,
+) -> Result<Response> {
+    let mut url = Url::parse(redirect_uri)
+        .map_err(|e| AppError::BadRequest(format!("Invalid redirect_uri: {}", e)))?;
+    {
+        let mut pairs = url.query_pairs_mut();
+        pairs.append_pair("error", error);
+        pairs.append_pair("error_description", description);
+        if let Some(state) = state {
+            pairs.append_pair("state", state);
+        }
+    }
+    Ok(no_store(Redirect::temporary(url.as_str()).into_response()))
+}
+
+fn no_store(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, "no-store".parse().unwrap());
+    response
+}
+
+/// Whether every scope in `requested` is in `granted`
+pub(crate) fn scope_covered(requested: &str, granted: &str) -> bool {
+    let granted: Vec<&str> = granted.split_whitespace().collect();
+    requested
+        .split_whitespace()
+        .all(|scope| granted.contains(&scope))
+}
+
+/// A login session still usable for silent authentication
+pub(crate) struct LiveSession {
+    pub session_id: StringUuid,
+    pub user: User,
+}
+
+/// Resolve `id_token_hint` to the live session it was issued in
+pub(crate) async fn live_session<S: HasServices + HasCache + HasSessionManagement>(
+    state: &S,
+    client_id: &str,
+    id_token_hint: Option<&str>,
+) -> std::result::Result<LiveSession, SilentAuthError> {
+    let hint = id_token_hint.ok_or(SilentAuthError::LoginRequired)?;
+    let claims: IdTokenClaims = HasServices::jwt_manager(state)
+        .verify_id_token_hint(hint, client_id)
+        .map_err(|_| SilentAuthError::LoginRequired)?;
+    let session_id: StringUuid = claims
+        .sid
+        .as_deref()
+        .and_then(|sid| sid.parse().ok())
+        .ok_or(SilentAuthError::LoginRequired)?;
+    let user_id: StringUuid = claims
+        .sub
+        .parse()
+        .map_err(|_| SilentAuthError::LoginRequired)?;
+
+    // Logged out sessions are blacklisted before their DB record is revoked
+    if state
+        .cache()
+        .is_token_blacklisted(&session_id.to_string())
+        .await
+        .unwrap_or(true)
+    {
+        return Err(SilentAuthError::LoginRequired);
+    }
+    let session = state
+        .session_service()
+        .find_session(session_id)
+        .await
+        .ok()
+        .flatten()
+        .ok_or(SilentAuthError::LoginRequired)?;
+    if session.revoked_at.is_some() || session.user_id != user_id {
+        return Err(SilentAuthError::LoginRequired);
+    }
+
+    let user = state
+        .user_service()
+        .get(user_id)
+        .await
+        .map_err(|_| SilentAuthError::LoginRequired)?;
+    if user.locked_until.is_some_and(|until| until > Utc::now()) {
+        return Err(SilentAuthError::LoginRequired);
+    }
+    Ok(LiveSession { session_id, user })
+}
+
+/// Parameters of a `prompt=none` authorization, after client, redirect URI
+/// and scope validation
+pub(crate) struct SilentAuthorization {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: String,
+    pub nonce: Option<String>,
+    pub code_challenge: Option<String>,
+    pub code_challenge_method: Option<String>,
+    pub id_token_hint: Option<String>,
+}
+
+/// Issue an authorization code for a live session, or redirect back with
+/// `login_required` / `consent_required`
+pub(crate) async fn silent_authorize<
+    S: HasServices + HasCache + HasSessionManagement + HasDbPool,
+>(
+    state: &S,
+    request: SilentAuthorization,
+) -> Result<Response> {
+    let fail = |error: SilentAuthError, description: &str| {
+        metrics::counter!("auth9_auth_silent_total", "result" => error.code()).increment(1);
+        authorization_error_redirect(
+            &request.redirect_uri,
+            error.code(),
+            description,
+            Some(&request.state),
+        )
+    };
+
+    let session =
+        match live_session(state, &request.client_id, request.id_token_hint.as_deref()).await {
+            Ok(session) => session,
+            Err(error) => return fail(error, "No active login session for this client"),
+        };
+
+    let consented = state
+        .cache()
+        .get_session_consent(&session.session_id.to_string(), &request.client_id)
+        .await?;
+    if !consented.is_some_and(|granted| scope_covered(&request.scope, &granted)) {
+        return fail(
+            SilentAuthError::ConsentRequired,
+            "The requested scopes have not been granted in this session",
+        );
+    }
+
+    let scope = grantable_scope(
+        state,
+        &session.user.id.to_string(),
+        &request.client_id,
+        &request.scope,
+    )
+    .await?;
+    let code = uuid::Uuid::new_v4().to_string();
+    let code_data = AuthorizationCodeData {
+        user_id: session.user.id.to_string(),
+        email: session.user.email.clone(),
+        display_name: session.user.display_name.clone(),
+        session_id: session.session_id.to_string(),
+        client_id: request.client_id,
+        redirect_uri: request.redirect_uri.clone(),
+        scope,
+        nonce: request.nonce,
+        code_challenge: request.code_challenge,
+        code_challenge_method: request.code_challenge_method,
+    };
+    let code_json = serde_json::to_string(&code_data).map_err(|e| AppError::Internal(e.into()))?;
+    state
+        .cache()
+        .store_authorization_code(&code, &code_json, AUTH_CODE_TTL_SECS)
+        .await?;
+    metrics::counter!("auth9_auth_silent_total", "result" => "success").increment(1);
+
+    let mut redirect_url = Url::parse(&request.redirect_uri)
+        .map_err(|e| AppError::BadRequest(format!("Invalid redirect_uri: {}", e)))?;
+    redirect_url
+        .query_pairs_mut()
+        .append_pair("code", &code)
+        .append_pair("state", &request.state);
+    Ok(no_store(
+        Redirect::temporary(redirect_url.as_str()).into_response(),
+    ))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SessionCheckQuery {
+    pub client_id: String,
+    /// ID token issued to the client in the session being checked
+    pub id_token_hint: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionCheckResponse {
+    /// Whether `prompt=none` would find a live session for this client
+    pub active: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/session/check",
+    tag = "Identity",
+    params(SessionCheckQuery),
+    responses(
+        (status = 200, description = "Whether the login session of the ID token is still active", body = SessionCheckResponse)
+    )
+)]
+/// Check whether the login session an ID token was issued in is still
+/// active, so SPAs can poll for sign-out without a hidden iframe
+pub async fn session_check<S: HasServices + HasCache + HasSessionManagement>(
+    State(state): State<S>,
+    Query(query): Query<SessionCheckQuery>,
+) -> Result<Json<SuccessResponse<SessionCheckResponse>>> {
+    let active = live_session(&state, &query.client_id, Some(&query.id_token_hint))
+        .await
+        .is_ok();
+    Ok(Json(SuccessResponse::new(SessionCheckResponse { active })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_silent_prompt() {
+        assert_eq!(is_silent_prompt(None), Ok(false));
+        assert_eq!(is_silent_prompt(Some("none")), Ok(true));
+        assert_eq!(is_silent_prompt(Some("login consent")), Ok(false));
+        assert!(is_silent_prompt(Some("none login")).is_err());
+        assert!(is_silent_prompt(Some("create")).is_err());
+    }
+
+    #[test]
+    fn test_scope_covered() {
+        assert!(scope_covered("openid", "openid email"));
+        assert!(scope_covered("openid email", "email openid"));
+        assert!(!scope_covered("openid orders:read", "openid email"));
+        assert!(scope_covered("", "openid"));
+    }
+
+    #[test]
+    fn test_authorization_error_redirect_keeps_state() {
+        let response = authorization_error_redirect(
+            "https://app.example.com/silent-renew?x=1",
+            "login_required",
+            "No session",
+            Some("abc"),
+        )
+        .unwrap();
+        let location = response.headers()[header::LOCATION].to_str().unwrap();
+        assert_eq!(
+            location,
+            "https://app.example.com/silent-renew?x=1&error=login_required&error_description=No+session&state=abc"
+        );
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+    }
+}
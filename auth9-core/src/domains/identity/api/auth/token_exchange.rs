@@ -2,14 +2,19 @@
 
 use super::helpers::{extract_client_ip, extract_identity_claims_from_headers};
 use super::types::{TenantTokenExchangeRequest, TokenResponse};
+use crate::domains::identity::api::progressive_profiling::scoped_user_claims;
+use crate::domains::identity::service::claims_enrichment::{merge_claims, ClaimsEnrichmentService};
+use crate::domains::tenant_access::service::MfaEnrollmentService;
 use crate::error::{AppError, Result};
+use crate::http_support::deprecation::tenant_slug_redirect_headers;
 use crate::http_support::{write_audit_log_generic, SuccessResponse};
 use crate::jwt::claims::sanitize_action_claims;
 use crate::models::action::{
     ActionContext, ActionContextRequest, ActionContextTenant, ActionContextUser,
 };
-use crate::models::common::StringUuid;
-use crate::state::HasServices;
+use crate::models::claims_enrichment::EnrichmentContext;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
+use crate::state::{HasDbPool, HasServices};
 use axum::{
     extract::State,
     http::HeaderMap,
@@ -26,7 +31,7 @@ use chrono::Utc;
         (status = 200, description = "Tenant access token")
     )
 )]
-pub async fn tenant_token<S: HasServices>(
+pub async fn tenant_token<S: HasServices + HasDbPool>(
     State(state): State<S>,
     headers: HeaderMap,
     Json(params): Json<TenantTokenExchangeRequest>,
@@ -46,9 +51,16 @@ pub async fn tenant_token<S: HasServices>(
         .parse::<StringUuid>()
         .map_err(|_| AppError::Unauthorized("Invalid user ID in identity token".to_string()))?;
 
-    let tenant_id = match tenant_ref.parse::<StringUuid>() {
-        Ok(id) => id,
-        Err(_) => state.tenant_service().get_by_slug(tenant_ref).await?.id,
+    // A retired slug still works during its grace period, flagged as deprecated
+    let (tenant_id, deprecation) = match tenant_ref.parse::<StringUuid>() {
+        Ok(id) => (id, HeaderMap::new()),
+        Err(_) => {
+            let (tenant, redirect) = state.tenant_service().resolve_slug(tenant_ref).await?;
+            let deprecation = redirect
+                .map(|redirect| tenant_slug_redirect_headers(&redirect, &tenant.slug))
+                .unwrap_or_default();
+            (tenant.id, deprecation)
+        }
     };
 
     // Verify tenant is active before allowing token exchange
@@ -62,7 +74,18 @@ pub async fn tenant_token<S: HasServices>(
 
     state
         .rbac_service()
-        .ensure_tenant_membership(user_id, tenant_id)
+        .ensure_tenant_membership(UserId::from(user_id), TenantId::from(tenant_id))
+        .await?;
+
+    // Members past the deadline of the tenant's MFA enrollment campaign
+    // must enroll before getting tenant tokens
+    MfaEnrollmentService::from_pool(state.db_pool().clone())
+        .ensure_token_allowed(
+            tenant_id,
+            tenant.settings.mfa_enrollment_campaign.as_ref(),
+            user_id,
+            Utc::now(),
+        )
         .await?;
 
     let service = state
@@ -85,11 +108,28 @@ pub async fn tenant_token<S: HasServices>(
 
     let user_roles = state
         .rbac_service()
-        .get_user_roles_for_service(user_id, tenant_id, service.id)
+        .get_user_roles_for_service(
+            UserId::from(user_id),
+            TenantId::from(tenant_id),
+            ServiceId::from(service.id),
+        )
+        .await?;
+
+    // Claims from the service's enrichment plugins
+    let enriched_claims = ClaimsEnrichmentService::from_pool(state.db_pool().clone())
+        .enrich(&EnrichmentContext {
+            user_id,
+            email: identity_claims.email.clone(),
+            tenant_id,
+            service_id: service.id,
+            client_id: service_id.to_string(),
+            roles: user_roles.roles.clone(),
+            permissions: user_roles.permissions.clone(),
+        })
         .await?;
 
     // Execute post-login actions for the target service and inject sanitized claims
-    let custom_claims = {
+    let action_claims = {
         let user = state.user_service().get(user_id).await?;
         let ip_address = extract_client_ip(&headers);
         let user_agent = headers
@@ -122,8 +162,13 @@ pub async fn tenant_token<S: HasServices>(
             .await?;
         modified.claims.and_then(sanitize_action_claims)
     };
+    let custom_claims = merge_claims(enriched_claims, action_claims);
 
-    let jwt_manager = state.jwt_manager();
+    // Client lifetime overrides win over the tenant's
+    let client = state.client_service().get_client_record(service_id).await?;
+    let jwt_manager = state
+        .jwt_manager()
+        .with_ttl_overrides(client.token_ttls.or(tenant.settings.token_ttls));
     let access_token = jwt_manager.create_tenant_access_token_with_claims(
         *user_id,
         &identity_claims.email,
@@ -152,14 +197,17 @@ pub async fn tenant_token<S: HasServices>(
     )
     .await;
 
-    Ok(Json(TokenResponse {
-        access_token,
-        token_type: "Bearer".to_string(),
-        expires_in: jwt_manager.access_token_ttl(),
-        refresh_token: Some(refresh_token),
-        id_token: None,
-    })
-    .into_response())
+    Ok((
+        deprecation,
+        Json(TokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: jwt_manager.access_token_ttl(),
+            refresh_token: Some(refresh_token),
+            id_token: None,
+        }),
+    )
+        .into_response())
 }
 
 #[utoipa::path(
@@ -173,9 +221,29 @@ pub async fn tenant_token<S: HasServices>(
 /// UserInfo endpoint
 ///
 /// Accepts Identity tokens, Tenant Access tokens, and Service Client tokens
-/// via the standard AuthUser middleware chain.
-pub async fn userinfo<S: HasServices>(auth: crate::middleware::auth::AuthUser) -> Result<Response> {
-    Ok(Json(auth).into_response())
+/// via the standard AuthUser middleware chain. Access tokens issued by the
+/// OIDC token endpoint get the claims released by their granted scope and
+/// the client's attribute release policy; other tokens get their
+/// authenticated principal.
+pub async fn userinfo<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: crate::middleware::auth::AuthUser,
+) -> Result<Response> {
+    let Some(scope) = auth.scope.as_deref() else {
+        return Ok(Json(auth).into_response());
+    };
+
+    let user = state.user_service().get(auth.user_id.into()).await?;
+    let service = match auth.aud.as_deref() {
+        Some(client_id) => {
+            let client = state.client_service().get_client_record(client_id).await?;
+            Some(state.client_service().get(*client.service_id).await?)
+        }
+        None => None,
+    };
+    let mut claims = scoped_user_claims(&state, service.as_ref(), &user, scope).await?;
+    claims.insert("sub".to_string(), auth.user_id.to_string().into());
+    Ok(Json(serde_json::Value::Object(claims)).into_response())
 }
 
 // Suppress unused import warning -- SuccessResponse is used by related modules
@@ -21,6 +21,10 @@ pub struct AuthorizeRequest {
     pub code_challenge: Option<String>,
     /// PKCE code challenge method (e.g. "S256")
     pub code_challenge_method: Option<String>,
+    /// `none` requests silent authentication against an existing session
+    pub prompt: Option<String>,
+    /// ID token of the session to use for `prompt=none`
+    pub id_token_hint: Option<String>,
 }
 
 /// OIDC callback handler
@@ -41,6 +45,10 @@ pub struct TokenRequest {
     pub refresh_token: Option<String>,
     /// PKCE code verifier (RFC 7636)
     pub code_verifier: Option<String>,
+    /// External workload token (RFC 8693 token exchange)
+    pub subject_token: Option<String>,
+    /// Type of `subject_token` (RFC 8693)
+    pub subject_token_type: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
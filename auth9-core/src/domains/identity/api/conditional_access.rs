@@ -0,0 +1,347 @@
+//! Conditional access API handlers.
+//!
+//! Tenant administrators manage policies that combine client, network,
+//! device trust and time-of-day conditions; the token endpoint evaluates
+//! them before issuing tokens and admins can dry-run them via simulation.
+
+use crate::cache::CacheOperations;
+use crate::config::Config;
+use crate::domains::identity::service::conditional_access::session_mfa_key;
+use crate::domains::identity::service::trusted_device::compute_device_fingerprint;
+use crate::domains::identity::service::ConditionalAccessService;
+use crate::error::oauth::OAuthTokenError;
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::conditional_access::{
+    ConditionalAccessContext, ConditionalAccessDecision, ConditionalAccessOutcome,
+    ConditionalAccessPolicy, CreateConditionalAccessPolicyInput, SimulateConditionalAccessInput,
+    UpdateConditionalAccessPolicyInput,
+};
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::conditional_access::ConditionalAccessRepositoryImpl;
+use crate::state::{HasCache, HasDbPool, HasServices, HasSessionManagement, HasTrustedDevices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub(crate) fn conditional_access_service<S: HasDbPool>(
+    state: &S,
+) -> ConditionalAccessService<ConditionalAccessRepositoryImpl> {
+    ConditionalAccessService::new(Arc::new(ConditionalAccessRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+/// Hosted login URL returned when a policy requires step-up MFA
+pub(crate) fn mfa_interaction_uri(config: &Config, client_id: &str) -> String {
+    let portal = config.portal_url.as_deref().unwrap_or(&config.jwt.issuer);
+    format!(
+        "{}/login?client_id={}&mfa_required=true",
+        portal.trim_end_matches('/'),
+        urlencoding::encode(client_id)
+    )
+}
+
+/// Record that a session was established with a second factor so that
+/// `require_mfa` policies are satisfied for tokens issued from it.
+pub(crate) async fn mark_session_mfa<S: HasServices + HasCache>(state: &S, session_id: StringUuid) {
+    let ttl = state.config().jwt.refresh_token_ttl_secs.max(0) as u64;
+    if let Err(e) = state
+        .cache()
+        .increment_counter(&session_mfa_key(&session_id.to_string()), ttl)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to record MFA completion for session");
+    }
+}
+
+/// Evaluate the tenant's conditional access policies for a token request.
+///
+/// Returns the OAuth error to send instead of tokens, or `None` when the
+/// request may proceed. Network and device attributes come from the session
+/// the authorization code was issued for.
+pub(crate) async fn check_token_request<
+    S: HasServices + HasSessionManagement + HasCache + HasTrustedDevices + HasDbPool,
+>(
+    state: &S,
+    tenant_id: StringUuid,
+    client_id: &str,
+    user_id: StringUuid,
+    session_id: StringUuid,
+) -> Result<Option<OAuthTokenError>> {
+    let session = state.session_service().find_session(session_id).await?;
+    let ip_address = session.as_ref().and_then(|s| s.ip_address.clone());
+    let user_agent = session.and_then(|s| s.user_agent);
+
+    let device_trusted = match (user_agent.as_deref(), ip_address.as_deref()) {
+        (Some(ua), Some(ip)) => state
+            .trusted_device_service()
+            .is_trusted(user_id, &compute_device_fingerprint(ua, ip))
+            .await
+            .unwrap_or(false),
+        _ => false,
+    };
+
+    let ctx = ConditionalAccessContext {
+        client_id: client_id.to_string(),
+        ip_address,
+        device_trusted,
+        at: Utc::now(),
+    };
+    let decision = conditional_access_service(state)
+        .evaluate(tenant_id, &ctx)
+        .await?;
+
+    metrics::counter!(
+        "auth9_conditional_access_decisions_total",
+        "outcome" => decision.outcome.to_string()
+    )
+    .increment(1);
+
+    match decision.outcome {
+        ConditionalAccessOutcome::Allow => Ok(None),
+        ConditionalAccessOutcome::Block => {
+            tracing::info!(
+                tenant_id = %tenant_id,
+                client_id,
+                policy_id = ?decision.deciding_policy_id,
+                "Token request blocked by conditional access policy"
+            );
+            Ok(Some(OAuthTokenError::AccessDenied(
+                "Access blocked by conditional access policy".into(),
+            )))
+        }
+        ConditionalAccessOutcome::RequireMfa => {
+            let mfa_completed = state
+                .cache()
+                .get_counter(&session_mfa_key(&session_id.to_string()))
+                .await?
+                > 0;
+            if mfa_completed {
+                return Ok(None);
+            }
+            Ok(Some(OAuthTokenError::InteractionRequired {
+                description: "Multi-factor authentication is required by tenant policy".into(),
+                interaction_uri: mfa_interaction_uri(state.config(), client_id),
+                missing_fields: Vec::new(),
+            }))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/policies",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Conditional access policies in evaluation order", body = Vec<ConditionalAccessPolicy>)
+    )
+)]
+pub async fn list_policies<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<ConditionalAccessPolicy>>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantRead).await?;
+    let policies = conditional_access_service(&state)
+        .list(StringUuid::from(tenant_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(policies)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/policies",
+    tag = "Identity",
+    request_body = CreateConditionalAccessPolicyInput,
+    responses(
+        (status = 200, description = "Policy created", body = ConditionalAccessPolicy),
+        (status = 400, description = "Invalid conditions or policy limit reached")
+    )
+)]
+pub async fn create_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<CreateConditionalAccessPolicyInput>,
+) -> Result<Json<SuccessResponse<ConditionalAccessPolicy>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let created = conditional_access_service(&state)
+        .create(StringUuid::from(tenant_id), input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.conditional_access.create",
+        "conditional_access_policy",
+        Some(*created.id),
+        None,
+        serde_json::to_value(&created).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/policies/{policy_id}",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Conditional access policy", body = ConditionalAccessPolicy),
+        (status = 404, description = "Policy not found")
+    )
+)]
+pub async fn get_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, policy_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<ConditionalAccessPolicy>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantRead).await?;
+    let policy = conditional_access_service(&state)
+        .get(StringUuid::from(tenant_id), StringUuid::from(policy_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(policy)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/policies/{policy_id}",
+    tag = "Identity",
+    request_body = UpdateConditionalAccessPolicyInput,
+    responses(
+        (status = 200, description = "Policy updated", body = ConditionalAccessPolicy),
+        (status = 404, description = "Policy not found")
+    )
+)]
+pub async fn update_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, policy_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateConditionalAccessPolicyInput>,
+) -> Result<Json<SuccessResponse<ConditionalAccessPolicy>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let service = conditional_access_service(&state);
+    let tenant_id = StringUuid::from(tenant_id);
+    let policy_id = StringUuid::from(policy_id);
+    let before = service.get(tenant_id, policy_id).await?;
+    let after = service.update(tenant_id, policy_id, input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.conditional_access.update",
+        "conditional_access_policy",
+        Some(*policy_id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&after).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(after)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/policies/{policy_id}",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Policy deleted"),
+        (status = 404, description = "Policy not found")
+    )
+)]
+pub async fn delete_policy<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, policy_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let service = conditional_access_service(&state);
+    let tenant_id = StringUuid::from(tenant_id);
+    let policy_id = StringUuid::from(policy_id);
+    let removed = service.get(tenant_id, policy_id).await?;
+    service.delete(tenant_id, policy_id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.conditional_access.delete",
+        "conditional_access_policy",
+        Some(*policy_id),
+        serde_json::to_value(&removed).ok(),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new(
+        "Conditional access policy deleted",
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/conditional-access/simulate",
+    tag = "Identity",
+    request_body = SimulateConditionalAccessInput,
+    responses(
+        (status = 200, description = "Decision with a per-policy evaluation trace", body = ConditionalAccessDecision)
+    )
+)]
+/// Dry-run the tenant's policies against hypothetical request attributes.
+///
+/// POST /api/v1/tenants/{tenant_id}/conditional-access/simulate
+pub async fn simulate<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<SimulateConditionalAccessInput>,
+) -> Result<Json<SuccessResponse<ConditionalAccessDecision>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantRead).await?;
+    let decision = conditional_access_service(&state)
+        .simulate(StringUuid::from(tenant_id), input)
+        .await?;
+    Ok(Json(SuccessResponse::new(decision)))
+}
+
+async fn ensure_tenant_access<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: Uuid,
+    action: PolicyAction,
+) -> Result<()> {
+    policy::enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mfa_interaction_uri_encodes_client_id() {
+        let mut config = Config::for_tests();
+        config.portal_url = Some("https://portal.example.com/".to_string());
+        assert_eq!(
+            mfa_interaction_uri(&config, "my app"),
+            "https://portal.example.com/login?client_id=my%20app&mfa_required=true"
+        );
+    }
+}
@@ -6,8 +6,9 @@
 //! here to either confirm the merge or create a new account.
 
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
 use crate::domains::identity::api::auth::helpers::{
-    AuthorizationCodeData, LoginChallengeData, AUTH_CODE_TTL_SECS,
+    consume_flow_state, AuthorizationCodeData, LoginChallengeData, AUTH_CODE_TTL_SECS,
 };
 use crate::error::AppError;
 use crate::models::linked_identity::{CreateLinkedIdentityInput, PendingMergeData};
@@ -140,17 +141,15 @@ pub async fn confirm_link<
         .await?;
 
     // 6. Consume login challenge and generate authorization code
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(&pending.login_challenge_id)
-        .await?
-        .ok_or_else(|| {
-            AppError::BadRequest(
-                "Login challenge expired during identity confirmation.".to_string(),
-            )
-        })?;
-    let challenge: LoginChallengeData =
-        serde_json::from_str(&challenge_json).map_err(|e| AppError::Internal(e.into()))?;
+    let challenge: LoginChallengeData = consume_flow_state(
+        &state,
+        FlowKind::LoginChallenge,
+        &pending.login_challenge_id,
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::BadRequest("Login challenge expired during identity confirmation.".to_string())
+    })?;
 
     let auth_code = uuid::Uuid::new_v4().to_string();
     let code_data = AuthorizationCodeData {
@@ -2,6 +2,7 @@
 
 use crate::domains::identity::service::otp::{OtpChannelType, OtpManager, OtpRateLimitConfig};
 use crate::error::{AppError, Result};
+use crate::i18n;
 use crate::models::email::{EmailAddress, EmailMessage};
 use crate::models::email_template::EmailTemplateType;
 use crate::state::{HasBranding, HasCache, HasServices, HasSessionManagement, HasSystemSettings};
@@ -79,6 +80,7 @@ fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
 /// POST /api/v1/auth/email-otp/send
 pub async fn send_email_otp<S: HasCache + HasSystemSettings + HasBranding>(
     State(state): State<S>,
+    headers: HeaderMap,
     Json(input): Json<SendEmailOtpRequest>,
 ) -> Result<Json<SendEmailOtpResponse>> {
     check_email_otp_enabled(&state).await?;
@@ -111,15 +113,15 @@ pub async fn send_email_otp<S: HasCache + HasSystemSettings + HasBranding>(
         "expires_in_minutes".to_string(),
         OTP_TTL_MINUTES.to_string(),
     );
-    vars.insert("app_name".to_string(), "Auth9".to_string());
     vars.insert(
         "year".to_string(),
         chrono::Utc::now().format("%Y").to_string(),
     );
 
+    let locale = i18n::negotiate_headers(&headers).unwrap_or(i18n::DEFAULT_LOCALE);
     if let Ok(rendered) = state
         .email_service()
-        .resolve_and_render(EmailTemplateType::EmailMfa, &vars)
+        .resolve_and_render_localized(EmailTemplateType::EmailMfa, locale, &vars)
         .await
     {
         let message = EmailMessage::new(
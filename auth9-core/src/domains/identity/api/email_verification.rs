@@ -4,6 +4,7 @@
 
 use crate::error::{AppError, Result};
 use crate::http_support::{write_audit_log_generic, MessageResponse};
+use crate::i18n;
 use crate::state::{HasEmailVerification, HasServices, HasSystemSettings};
 use axum::{extract::State, http::HeaderMap, Json};
 use serde::Deserialize;
@@ -72,11 +73,15 @@ pub async fn send_verification<S: HasServices + HasEmailVerification + HasSystem
         "year".to_string(),
         chrono::Utc::now().format("%Y").to_string(),
     );
-    vars.insert("app_name".to_string(), "Auth9".to_string());
+
+    let accept_language = headers
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok());
+    let locale = i18n::resolve_locale(user.locale.as_deref(), accept_language, None);
 
     match state
         .email_service()
-        .resolve_and_render(EmailTemplateType::EmailVerification, &vars)
+        .resolve_and_render_localized(EmailTemplateType::EmailVerification, locale, &vars)
         .await
     {
         Ok(rendered) => {
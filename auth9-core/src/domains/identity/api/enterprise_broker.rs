@@ -5,6 +5,8 @@
 //! SAML uses a separate ACS endpoint in `enterprise_saml_broker`.
 
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
+use crate::domains::identity::api::auth::helpers::{peek_flow_state, LoginChallengeData};
 use crate::domains::identity::api::enterprise_common::{
     self, ConnectorRecord, EnterpriseSsoLoginState, UserResolution, ENTERPRISE_SSO_STATE_TTL_SECS,
 };
@@ -224,22 +226,13 @@ pub async fn authorize<S: HasServices + HasCache + HasDbPool>(
     Path(alias): Path<String>,
     Query(params): Query<EnterpriseSsoAuthorizeQuery>,
 ) -> Result<Response> {
-    // 1. Verify login_challenge exists (peek, do NOT consume)
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(&params.login_challenge)
-        .await?;
-    let challenge_json = challenge_json
-        .ok_or_else(|| AppError::BadRequest("Invalid or expired login challenge".to_string()))?;
-    // Re-store it immediately (peek pattern)
-    state
-        .cache()
-        .store_login_challenge(
-            &params.login_challenge,
-            &challenge_json,
-            super::auth::LOGIN_CHALLENGE_TTL_SECS,
-        )
-        .await?;
+    // 1. Verify login_challenge is valid (peek, do NOT consume)
+    peek_flow_state::<_, LoginChallengeData>(
+        &state,
+        FlowKind::LoginChallenge,
+        &params.login_challenge,
+    )
+    .ok_or_else(|| AppError::BadRequest("Invalid or expired login challenge".to_string()))?;
 
     // 2. Load connector (any provider_type)
     let connector = enterprise_common::load_connector(state.db_pool(), &alias).await?;
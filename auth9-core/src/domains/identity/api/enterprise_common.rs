@@ -1,8 +1,9 @@
 //! Shared types and helpers for enterprise SSO brokers (OIDC + SAML).
 
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
 use crate::domains::identity::api::auth::helpers::{
-    AuthorizationCodeData, LoginChallengeData, AUTH_CODE_TTL_SECS,
+    consume_flow_state, AuthorizationCodeData, LoginChallengeData, AUTH_CODE_TTL_SECS,
 };
 use crate::error::{AppError, Result};
 use crate::models::linked_identity::{
@@ -270,15 +271,14 @@ pub async fn complete_login_flow<S: HasServices + HasCache>(
     user: &crate::models::user::User,
     session_id: crate::models::common::StringUuid,
 ) -> Result<String> {
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(login_challenge_id)
-        .await?
-        .ok_or_else(|| {
-            AppError::BadRequest("Login challenge expired during enterprise SSO login".to_string())
-        })?;
     let challenge: LoginChallengeData =
-        serde_json::from_str(&challenge_json).map_err(|e| AppError::Internal(e.into()))?;
+        consume_flow_state(state, FlowKind::LoginChallenge, login_challenge_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    "Login challenge expired during enterprise SSO login".to_string(),
+                )
+            })?;
 
     let auth_code = uuid::Uuid::new_v4().to_string();
     let code_data = AuthorizationCodeData {
@@ -6,6 +6,8 @@
 //! returning JSON responses instead of OIDC redirects.
 
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
+use crate::domains::identity::api::auth::helpers::seal_flow_state;
 use crate::domains::identity::api::mfa::{
     MfaChallengeResponse, MfaSessionData, MFA_SESSION_TTL_SECS,
 };
@@ -14,11 +16,20 @@ use crate::domains::identity::service::adaptive_mfa::{
 };
 use crate::domains::identity::service::required_actions::PendingActionResponse;
 use crate::domains::identity::service::trusted_device::compute_device_fingerprint;
+use crate::domains::security_observability::service::login_forensics::capture_login_failure;
 use crate::domains::security_observability::service::risk_engine::{RiskEngine, RiskInput};
+use crate::domains::tenant_access::service::mfa_enrollment::enrollment_requirements;
 use crate::error::{AppError, Result};
 use crate::http_support::{write_audit_log_generic, write_audit_log_with_actor, MessageResponse};
+use crate::models::common::StringUuid;
+use crate::models::login_forensics::{LoginFailureCapture, LoginFailureStage};
+use crate::models::login_identifier::{ParsedLoginIdentifier, GLOBAL_SCOPE};
+use crate::models::mfa_enrollment::MfaEnrollmentStatus;
 use crate::models::password::{ForgotPasswordInput, ResetPasswordInput};
+use crate::models::password_breach::{PasswordBreachSource, PasswordBreachStatus};
+use crate::models::user::User;
 use crate::repository::adaptive_mfa_policy::AdaptiveMfaPolicyRepository;
+use crate::repository::AccountDeletionRepository;
 use crate::state::{
     HasAdaptiveMfa, HasAnalytics, HasCache, HasMfa, HasPasswordManagement, HasRequiredActions,
     HasServices, HasSessionManagement, HasTrustedDevices, HasWebAuthn,
@@ -34,8 +45,20 @@ use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct HostedLoginPasswordRequest {
+    #[serde(default)]
     pub email: String,
+    /// Email, username or E.164 phone number; takes precedence over `email`
+    #[serde(default)]
+    pub identifier: Option<String>,
+    /// Tenant to sign in to, needed when a tenant-unique username or phone
+    /// number is used by members of several tenants
+    #[serde(default)]
+    pub tenant_id: Option<StringUuid>,
     pub password: String,
+    /// Sessions to end when the tenant's session limit asks the user to
+    /// choose (retry after a `session_limit_reached` response)
+    #[serde(default)]
+    pub revoke_session_ids: Vec<StringUuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -69,6 +92,74 @@ fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
         })
 }
 
+/// Forensic context of a failed hosted password sign-in
+fn failed_login_capture(
+    stage: LoginFailureStage,
+    email: &str,
+    user_id: Option<StringUuid>,
+    headers: &HeaderMap,
+) -> LoginFailureCapture {
+    LoginFailureCapture {
+        identifier: Some(email.to_string()),
+        user_id,
+        ip_address: extract_client_ip(headers),
+        user_agent: headers
+            .get(axum::http::header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from),
+        ..LoginFailureCapture::new(stage)
+    }
+}
+
+/// User a sign-in identifier refers to. A tenant-unique username or phone
+/// number only works while its tenant still allows signing in with it.
+async fn resolve_login_user<S: HasServices>(
+    state: &S,
+    parsed: &ParsedLoginIdentifier,
+    tenant_id: Option<StringUuid>,
+) -> Result<Option<User>> {
+    let (kind, value) = match parsed {
+        ParsedLoginIdentifier::Email(email) => {
+            return not_found_as_none(state.user_service().get_by_email(email).await)
+        }
+        ParsedLoginIdentifier::Other(kind, value) => (*kind, value),
+    };
+    let Some(identifier) = state
+        .login_identifier_service()
+        .resolve(kind, value, tenant_id)
+        .await?
+    else {
+        return Ok(None);
+    };
+    if identifier.scope != GLOBAL_SCOPE {
+        let scope_tenant: StringUuid = identifier
+            .scope
+            .parse()
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalid identifier scope")))?;
+        let Some(tenant) = not_found_as_none(state.tenant_service().get(scope_tenant).await)?
+        else {
+            return Ok(None);
+        };
+        if !tenant
+            .settings
+            .login_identifiers
+            .is_some_and(|s| s.allows(kind))
+        {
+            return Ok(None);
+        }
+    }
+    not_found_as_none(state.user_service().get(identifier.user_id).await)
+}
+
+/// Unknown users are a failed sign-in; any other lookup error is not
+fn not_found_as_none<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(found) => Ok(Some(found)),
+        Err(AppError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
 // ==================== Handlers ====================
 
 #[utoipa::path(
@@ -80,9 +171,11 @@ fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
         (status = 200, description = "Authentication token", body = HostedLoginTokenResponse),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Invalid credentials"),
+        (status = 409, description = "Concurrent session limit reached"),
     )
 )]
-/// Authenticate with email and password, returning an identity token directly.
+/// Authenticate with email, username or phone number and password, returning
+/// an identity token directly.
 ///
 /// POST /api/v1/hosted-login/password
 pub async fn password_login<
@@ -101,14 +194,29 @@ pub async fn password_login<
     Json(input): Json<HostedLoginPasswordRequest>,
 ) -> Result<axum::response::Response> {
     let start = std::time::Instant::now();
-    let email = input.email.trim().to_lowercase();
     let password = input.password.clone();
-
-    if email.is_empty() || !email.contains('@') {
+    let parsed = match input.identifier.as_deref() {
+        Some(identifier) => ParsedLoginIdentifier::parse(identifier),
+        None => {
+            let email = input.email.trim().to_lowercase();
+            (!email.is_empty() && email.contains('@'))
+                .then_some(ParsedLoginIdentifier::Email(email))
+        }
+    };
+    let Some(parsed) = parsed else {
         metrics::counter!("auth9_auth_login_total", "result" => "failure", "backend" => "hosted")
             .increment(1);
-        return Err(AppError::BadRequest("Invalid email address.".to_string()));
-    }
+        return Err(AppError::BadRequest(if input.identifier.is_some() {
+            "Invalid email, username or phone number.".to_string()
+        } else {
+            "Invalid email address.".to_string()
+        }));
+    };
+    // What the user typed, for forensics
+    let email = match &parsed {
+        ParsedLoginIdentifier::Email(email) => email.clone(),
+        ParsedLoginIdentifier::Other(_, value) => value.clone(),
+    };
     if password.is_empty() {
         metrics::counter!("auth9_auth_login_total", "result" => "failure", "backend" => "hosted")
             .increment(1);
@@ -116,10 +224,15 @@ pub async fn password_login<
     }
 
     // Look up user — return generic error to prevent email enumeration
-    let user = match state.user_service().get_by_email(&email).await {
-        Ok(user) => user,
-        Err(_) => {
+    let user = match resolve_login_user(&state, &parsed, input.tenant_id).await? {
+        Some(user) => user,
+        None => {
             metrics::counter!("auth9_auth_login_total", "result" => "failure", "backend" => "hosted").increment(1);
+            capture_login_failure(
+                &state,
+                failed_login_capture(LoginFailureStage::UnknownUser, &email, None, &headers),
+            )
+            .await?;
             return Err(AppError::Unauthorized(
                 "Invalid email or password.".to_string(),
             ));
@@ -131,6 +244,16 @@ pub async fn password_login<
         if locked_until > Utc::now() {
             metrics::counter!("auth9_auth_login_total", "result" => "locked", "backend" => "hosted")
                 .increment(1);
+            capture_login_failure(
+                &state,
+                failed_login_capture(
+                    LoginFailureStage::AccountLocked,
+                    &email,
+                    Some(user.id),
+                    &headers,
+                ),
+            )
+            .await?;
             return Err(AppError::TooManyRequests(
                 "Account is temporarily locked due to too many failed login attempts. Please try again later.".to_string(),
             ));
@@ -151,6 +274,12 @@ pub async fn password_login<
     if !valid {
         metrics::counter!("auth9_auth_login_total", "result" => "failure", "backend" => "hosted")
             .increment(1);
+        // Failed attempts count towards lockout even if the capture fails
+        let captured = capture_login_failure(
+            &state,
+            failed_login_capture(LoginFailureStage::Password, &email, Some(user.id), &headers),
+        )
+        .await;
 
         // Track failed login attempt for brute force protection
         let fail_key = format!("auth9:login_fail:{}", user.id);
@@ -176,6 +305,7 @@ pub async fn password_login<
             }
         }
 
+        captured?;
         return Err(AppError::Unauthorized(
             "Invalid email or password.".to_string(),
         ));
@@ -211,10 +341,18 @@ pub async fn password_login<
 
     // Async breach check: after successful password auth, check HIBP in background.
     // If breached, create a required action to force password change on next login.
-    // Respects tenant-level breach_check_on_login and min_breach_count settings.
+    // Respects tenant-level breach_check_on_login and min_breach_count settings;
+    // accounts a breach campaign is waiting on are checked regardless.
     if let Some(breach_svc) = state.breached_password_service() {
-        if tenant_password_policy.breach_check_on_login
-            && tenant_password_policy.breach_check_mode != "disabled"
+        let campaign_checks = if breach_svc.is_enabled() {
+            let checks = state.password_breach_check_repo();
+            checks.has_pending(user.id).await?.then(|| checks.clone())
+        } else {
+            None
+        };
+        if campaign_checks.is_some()
+            || (tenant_password_policy.breach_check_on_login
+                && tenant_password_policy.breach_check_mode != "disabled")
         {
             let breach_svc = breach_svc.clone();
             let password_clone = password.clone();
@@ -224,7 +362,27 @@ pub async fn password_login<
             let min_breach_count = tenant_password_policy.min_breach_count;
             tokio::spawn(async move {
                 let result = breach_svc.check_password(&password_clone).await;
-                if result.is_breached && result.breach_count >= min_breach_count {
+                let breached = result.is_breached && result.breach_count >= min_breach_count;
+                if let Some(checks) = campaign_checks {
+                    let status = if breached {
+                        PasswordBreachStatus::Breached
+                    } else {
+                        PasswordBreachStatus::Clean
+                    };
+                    if let Err(e) = checks
+                        .resolve_pending(user_id, status, result.breach_count)
+                        .await
+                    {
+                        tracing::warn!(error = %e, "Failed to record breach campaign check");
+                    }
+                    metrics::counter!(
+                        "auth9_password_breach_checks_total",
+                        "source" => PasswordBreachSource::Login.as_str(),
+                        "status" => status.as_str()
+                    )
+                    .increment(1);
+                }
+                if breached {
                     tracing::warn!(
                         user_id = %user_id,
                         breach_count = result.breach_count,
@@ -379,7 +537,6 @@ pub async fn password_login<
 
     if let MfaDecision::Required { methods, reason: _ } = decision {
         // MFA required — issue temporary MFA session token
-        let mfa_token = uuid::Uuid::new_v4().to_string();
         let mfa_data = MfaSessionData {
             user_id: user.id.to_string(),
             email: user.email.clone(),
@@ -388,15 +545,14 @@ pub async fn password_login<
             ip_address: ip_address.clone(),
             user_agent: user_agent.clone(),
             device_fingerprint: device_fingerprint.clone(),
+            revoke_session_ids: input.revoke_session_ids.clone(),
         };
-        let mfa_json = serde_json::to_string(&mfa_data).map_err(|e| {
-            AppError::Internal(anyhow::anyhow!("Failed to serialize MFA session: {}", e))
-        })?;
-
-        state
-            .cache()
-            .store_mfa_session(&mfa_token, &mfa_json, MFA_SESSION_TTL_SECS)
-            .await?;
+        let mfa_token = seal_flow_state(
+            &state,
+            FlowKind::MfaSession,
+            &mfa_data,
+            MFA_SESSION_TTL_SECS,
+        )?;
 
         let _ = write_audit_log_generic(
             &state,
@@ -410,8 +566,11 @@ pub async fn password_login<
         .await;
 
         metrics::counter!("auth9_auth_login_total", "result" => "mfa_required", "backend" => "hosted").increment(1);
-        metrics::histogram!("auth9_hosted_login_duration_seconds", "method" => "password")
-            .record(start.elapsed().as_secs_f64());
+        crate::telemetry::exemplars::record_latency(
+            "auth9_hosted_login_duration_seconds",
+            &[("method", "password".to_string())],
+            start.elapsed().as_secs_f64(),
+        );
 
         let response = MfaChallengeResponse {
             mfa_required: true,
@@ -469,7 +628,13 @@ pub async fn password_login<
     // Actions passed — now create session and token
     let session = state
         .session_service()
-        .create_session(user.id, None, ip_address.clone(), user_agent.clone())
+        .create_session_with_prompt(
+            user.id,
+            None,
+            ip_address.clone(),
+            user_agent.clone(),
+            input.revoke_session_ids.clone(),
+        )
         .await?;
 
     let _ = write_audit_log_with_actor(
@@ -485,7 +650,7 @@ pub async fn password_login<
     .await;
 
     // Check for pending required actions
-    let pending_actions = match state
+    let mut pending_actions = match state
         .required_actions_service()
         .check_post_login_actions(
             &user.identity_subject,
@@ -502,6 +667,28 @@ pub async fn password_login<
             Vec::new()
         }
     };
+    if state
+        .account_deletion_service()
+        .repo()
+        .find(user.id)
+        .await?
+        .is_some()
+    {
+        pending_actions.push(PendingActionResponse::restore_account());
+    }
+    if !(user.mfa_enabled || has_mfa_enrolled) {
+        match enrollment_requirements(&state, user.id, false, Utc::now()).await {
+            Ok(requirements) => {
+                if requirements
+                    .iter()
+                    .any(|r| r.status != MfaEnrollmentStatus::Exempt)
+                {
+                    pending_actions.push(PendingActionResponse::enroll_mfa());
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to check MFA enrollment campaigns"),
+        }
+    }
 
     // Create identity token (no custom claims — action claims are injected at token exchange)
     let jwt_manager = HasServices::jwt_manager(&state);
@@ -539,8 +726,11 @@ pub async fn password_login<
 
     metrics::counter!("auth9_auth_login_total", "result" => "success", "backend" => "hosted")
         .increment(1);
-    metrics::histogram!("auth9_hosted_login_duration_seconds", "method" => "password")
-        .record(start.elapsed().as_secs_f64());
+    crate::telemetry::exemplars::record_latency(
+        "auth9_hosted_login_duration_seconds",
+        &[("method", "password".to_string())],
+        start.elapsed().as_secs_f64(),
+    );
 
     Ok(axum::Json(HostedLoginTokenResponse {
         access_token: identity_token,
@@ -0,0 +1,166 @@
+//! Username and phone number login identifier API handlers
+
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::login_identifier::{
+    LoginIdentifier, LoginIdentifierKind, SetLoginIdentifierInput,
+};
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+/// Caller manages the tenant's members and the user is one of them
+async fn require_member_manager<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+    user_id: StringUuid,
+) -> Result<()> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::LoginIdentifierManage,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+    .await?;
+    let is_member = state
+        .user_service()
+        .get_user_tenants(user_id)
+        .await?
+        .iter()
+        .any(|tu| tu.tenant_id == tenant_id);
+    if !is_member {
+        return Err(AppError::NotFound(format!("User {} not found", user_id)));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/users/{user_id}/login-identifiers",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("user_id" = String, Path, description = "User ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "The user's usernames and phone numbers", body = Vec<LoginIdentifier>),
+        (status = 404, description = "User is not a member of the tenant")
+    )
+)]
+/// List a tenant member's login identifiers
+pub async fn list_login_identifiers<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, user_id)): Path<(StringUuid, StringUuid)>,
+) -> Result<Json<SuccessResponse<Vec<LoginIdentifier>>>> {
+    require_member_manager(&state, &auth, tenant_id, user_id).await?;
+
+    let identifiers = state.login_identifier_service().list(user_id).await?;
+    Ok(Json(SuccessResponse::new(identifiers)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/users/{user_id}/login-identifiers/{kind}",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("user_id" = String, Path, description = "User ID (UUID)"),
+        ("kind" = LoginIdentifierKind, Path, description = "`username` or `phone`")
+    ),
+    request_body = SetLoginIdentifierInput,
+    responses(
+        (status = 200, description = "Login identifier assigned", body = LoginIdentifier),
+        (status = 400, description = "Tenant does not allow signing in with this kind of identifier"),
+        (status = 403, description = "The user's identifier is managed by another tenant"),
+        (status = 409, description = "Identifier already in use"),
+        (status = 422, description = "Invalid username or phone number")
+    )
+)]
+/// Assign a tenant member's username or phone number
+pub async fn set_login_identifier<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, user_id, kind)): Path<(StringUuid, StringUuid, LoginIdentifierKind)>,
+    Json(input): Json<SetLoginIdentifierInput>,
+) -> Result<Json<SuccessResponse<LoginIdentifier>>> {
+    require_member_manager(&state, &auth, tenant_id, user_id).await?;
+
+    let tenant = state.tenant_service().get(tenant_id).await?;
+    let identifier = state
+        .login_identifier_service()
+        .assign(
+            tenant_id,
+            tenant.settings.login_identifiers.as_ref(),
+            user_id,
+            kind,
+            &input.value,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.login_identifier.set",
+        "user",
+        Some(*user_id),
+        None,
+        serde_json::to_value(&identifier).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(identifier)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/users/{user_id}/login-identifiers/{kind}",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("user_id" = String, Path, description = "User ID (UUID)"),
+        ("kind" = LoginIdentifierKind, Path, description = "`username` or `phone`")
+    ),
+    responses(
+        (status = 200, description = "Login identifier removed"),
+        (status = 403, description = "The user's identifier is managed by another tenant"),
+        (status = 404, description = "User has no identifier of this kind")
+    )
+)]
+/// Remove a tenant member's username or phone number
+pub async fn delete_login_identifier<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, user_id, kind)): Path<(StringUuid, StringUuid, LoginIdentifierKind)>,
+) -> Result<Json<MessageResponse>> {
+    require_member_manager(&state, &auth, tenant_id, user_id).await?;
+
+    state
+        .login_identifier_service()
+        .remove(tenant_id, user_id, kind)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.login_identifier.remove",
+        "user",
+        Some(*user_id),
+        Some(serde_json::json!({ "kind": kind })),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Login identifier removed.")))
+}
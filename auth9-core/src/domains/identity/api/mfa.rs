@@ -4,7 +4,9 @@
 //! MFA status, MFA challenge verification during login, trusted device
 //! management, and adaptive MFA policy configuration.
 
-use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
+use crate::domains::identity::api::auth::helpers::consume_flow_state;
+use crate::domains::identity::api::conditional_access;
 use crate::domains::identity::service::adaptive_mfa::{AdaptiveMfaMode, AdaptiveMfaPolicy};
 use crate::domains::identity::service::required_actions::PendingActionResponse;
 use crate::domains::identity::service::totp::TotpEnrollmentResponse;
@@ -80,6 +82,9 @@ pub struct MfaSessionData {
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub device_fingerprint: Option<String>,
+    /// Sessions the user chose to end at the session limit prompt
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revoke_session_ids: Vec<StringUuid>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -602,19 +607,17 @@ pub async fn update_adaptive_mfa_policy<S: HasServices + HasAdaptiveMfa>(
 
 // ==================== Helpers ====================
 
-async fn consume_mfa_session<S: HasCache>(state: &S, token: &str) -> Result<MfaSessionData> {
-    let session_json = state
-        .cache()
-        .consume_mfa_session(token)
+pub(super) async fn consume_mfa_session<S: HasServices + HasCache>(
+    state: &S,
+    token: &str,
+) -> Result<MfaSessionData> {
+    consume_flow_state(state, FlowKind::MfaSession, token)
         .await?
         .ok_or_else(|| {
             AppError::Unauthorized(
                 "MFA session expired or invalid. Please log in again.".to_string(),
             )
-        })?;
-
-    serde_json::from_str(&session_json)
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to parse MFA session data: {}", e)))
+        })
 }
 
 /// After successful MFA verification, optionally trust the device.
@@ -682,7 +685,9 @@ async fn maybe_trust_device<S: HasServices + HasTrustedDevices + HasAdaptiveMfa>
     }
 }
 
-async fn issue_token_after_mfa<S: HasServices + HasSessionManagement + HasRequiredActions>(
+async fn issue_token_after_mfa<
+    S: HasServices + HasSessionManagement + HasRequiredActions + HasCache,
+>(
     state: &S,
     session_data: &MfaSessionData,
 ) -> Result<Json<HostedLoginTokenResponse>> {
@@ -693,13 +698,15 @@ async fn issue_token_after_mfa<S: HasServices + HasSessionManagement + HasRequir
 
     let session = state
         .session_service()
-        .create_session(
+        .create_session_with_prompt(
             user_id_su,
             None,
             session_data.ip_address.clone(),
             session_data.user_agent.clone(),
+            session_data.revoke_session_ids.clone(),
         )
         .await?;
+    conditional_access::mark_session_mfa(state, session.id).await;
 
     // Check for pending required actions (password expiry, temporary password, etc.)
     let pending_actions = {
@@ -772,6 +779,7 @@ mod tests {
             ip_address: Some("127.0.0.1".to_string()),
             user_agent: Some("Mozilla/5.0".to_string()),
             device_fingerprint: Some("abc123".to_string()),
+            revoke_session_ids: vec![],
         };
         let json = serde_json::to_string(&data).unwrap();
         let parsed: MfaSessionData = serde_json::from_str(&json).unwrap();
@@ -786,6 +794,7 @@ mod tests {
         let json = r#"{"user_id":"u1","email":"e@e.com","display_name":null,"identity_subject":"s1","ip_address":null,"user_agent":null}"#;
         let parsed: MfaSessionData = serde_json::from_str(json).unwrap();
         assert_eq!(parsed.device_fingerprint, None);
+        assert!(parsed.revoke_session_ids.is_empty());
     }
 
     #[test]
@@ -0,0 +1,430 @@
+//! Admin-assisted MFA reset APIs.
+//!
+//! A user stuck at the MFA challenge submits a reset request with the MFA
+//! session token of their password login and an identity proof. Tenant
+//! admins work through the request queue; an approval opens a time-boxed
+//! window in which the user, after logging in with their password again,
+//! clears their factors and enrolls new ones. Every step is audited under
+//! the `mfa_reset` resource type and the user is notified by email.
+
+use super::mfa::consume_mfa_session;
+use crate::domains::identity::service::MfaResetService;
+use crate::domains::platform::service::NotificationPreferenceService;
+use crate::error::{AppError, Result};
+use crate::http_support::{
+    extract_ip, write_audit_log_generic, write_audit_log_with_actor, MessageResponse,
+    SuccessResponse,
+};
+use crate::models::common::StringUuid;
+use crate::models::email_template::EmailTemplateType;
+use crate::models::mfa_reset::{
+    CompleteMfaResetInput, CreateMfaResetRequestInput, MfaResetDecisionInput, MfaResetRequest,
+    MfaResetStatus,
+};
+use crate::models::notification_preference::NotificationCategory;
+use crate::models::user::User;
+use crate::policy::{AuthzContext, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::mfa_reset::MfaResetRepositoryImpl;
+use crate::state::{HasCache, HasDbPool, HasMfa, HasServices, HasTrustedDevices, HasWebAuthn};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Audit resource type shared by every MFA reset event
+const AUDIT_RESOURCE: &str = "mfa_reset";
+
+fn reset_service<S: HasDbPool>(state: &S) -> MfaResetService<MfaResetRepositoryImpl> {
+    MfaResetService::new(Arc::new(MfaResetRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+/// User behind the MFA session token of a password login
+async fn session_user<S: HasServices + HasCache>(state: &S, token: &str) -> Result<User> {
+    let session = consume_mfa_session(state, token).await?;
+    let user_id = Uuid::parse_str(&session.user_id)
+        .map_err(|_| AppError::Internal(anyhow::anyhow!("Invalid user_id in MFA session")))?;
+    state.user_service().get(StringUuid::from(user_id)).await
+}
+
+/// Email the user a security alert about their MFA reset. Failures are
+/// logged, never returned: the workflow must not stall on email delivery.
+async fn notify_user<S: HasServices + HasDbPool>(state: &S, user: &User, event: &str) {
+    let notifications =
+        NotificationPreferenceService::from_config(state.db_pool().clone(), state.config());
+    let category = NotificationCategory::SecurityAlerts;
+    if !notifications.allows(user.id, category).await {
+        return;
+    }
+
+    let mut vars = HashMap::new();
+    vars.insert(
+        "user_name".to_string(),
+        user.display_name
+            .clone()
+            .unwrap_or_else(|| "User".to_string()),
+    );
+    vars.insert("event_type".to_string(), event.to_string());
+    vars.insert("device_info".to_string(), "-".to_string());
+    vars.insert("location".to_string(), "-".to_string());
+    vars.insert(
+        "timestamp".to_string(),
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    );
+    if let Err(e) = notifications
+        .send_notification(
+            state.email_service(),
+            user.id,
+            &user.email,
+            category,
+            EmailTemplateType::SecurityAlert,
+            crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+            vars,
+        )
+        .await
+    {
+        tracing::warn!(user_id = %user.id, error = %e, "Failed to send MFA reset notice");
+    }
+}
+
+/// Remove every second factor of the user so they can enroll new ones
+async fn clear_mfa_factors<S: HasServices + HasMfa + HasWebAuthn + HasTrustedDevices>(
+    state: &S,
+    user: &User,
+) -> Result<()> {
+    let user_id = user.id.to_string();
+    state.totp_service().remove_totp(&user_id).await?;
+    state
+        .identity_engine()
+        .credential_store()
+        .remove_totp_credentials(&user.identity_subject)
+        .await?;
+    let credentials = state
+        .webauthn_service()
+        .list_credentials(&user_id, Some(&user.identity_subject))
+        .await?;
+    for credential in credentials {
+        state
+            .webauthn_service()
+            .delete_credential(&user_id, &credential.id, Some(&user.identity_subject))
+            .await?;
+    }
+    state.recovery_code_service().revoke_all(&user_id).await?;
+    state.trusted_device_service().revoke_all(user.id).await?;
+    state
+        .user_service()
+        .set_email_otp_enabled(user.id, false)
+        .await?;
+    state.user_service().set_mfa_enabled(user.id, false).await?;
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/mfa/reset-requests",
+    tag = "Identity",
+    request_body = CreateMfaResetRequestInput,
+    responses(
+        (status = 200, description = "Request opened and queued for tenant admins", body = MfaResetRequest),
+        (status = 401, description = "MFA session expired or invalid"),
+        (status = 403, description = "Not a member of the tenant"),
+        (status = 409, description = "An MFA reset request is already open")
+    )
+)]
+/// Ask a tenant admin to reset the caller's MFA.
+pub async fn create_mfa_reset_request<S: HasServices + HasCache + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Json(input): Json<CreateMfaResetRequestInput>,
+) -> Result<Json<SuccessResponse<MfaResetRequest>>> {
+    input.validate()?;
+    let user = session_user(&state, &input.mfa_session_token).await?;
+    let tenant_id = StringUuid::from(input.tenant_id);
+    let member = state
+        .user_service()
+        .get_user_tenants(user.id)
+        .await?
+        .iter()
+        .any(|membership| membership.tenant_id == tenant_id);
+    if !member {
+        return Err(AppError::Forbidden(
+            "User is not a member of this tenant".to_string(),
+        ));
+    }
+
+    let request = reset_service(&state)
+        .open_request(
+            tenant_id,
+            user.id,
+            input.identity_proof,
+            extract_ip(&headers),
+        )
+        .await?;
+
+    let _ = write_audit_log_with_actor(
+        &state,
+        &headers,
+        Some(*user.id),
+        "mfa_reset.requested",
+        AUDIT_RESOURCE,
+        Some(*user.id),
+        None,
+        Some(serde_json::json!({ "request_id": request.id, "tenant_id": tenant_id })),
+    )
+    .await;
+    notify_user(
+        &state,
+        &user,
+        "An MFA reset was requested for your account. If this was not you, contact your administrator",
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(request)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/mfa/reset-requests/{id}/complete",
+    tag = "Identity",
+    params(("id" = String, Path, description = "MFA reset request ID (UUID)")),
+    request_body = CompleteMfaResetInput,
+    responses(
+        (status = 200, description = "Second factors removed; log in again and enroll new ones"),
+        (status = 401, description = "MFA session expired or invalid"),
+        (status = 404, description = "Request not found for this user"),
+        (status = 409, description = "Request not approved, window closed or already completed")
+    )
+)]
+/// Clear the caller's second factors inside an approved reset window.
+pub async fn complete_mfa_reset<
+    S: HasServices + HasCache + HasDbPool + HasMfa + HasWebAuthn + HasTrustedDevices,
+>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<CompleteMfaResetInput>,
+) -> Result<Json<MessageResponse>> {
+    input.validate()?;
+    let user = session_user(&state, &input.mfa_session_token).await?;
+    let request = reset_service(&state)
+        .complete(StringUuid::from(id), user.id)
+        .await?;
+    clear_mfa_factors(&state, &user).await?;
+
+    let _ = write_audit_log_with_actor(
+        &state,
+        &headers,
+        Some(*user.id),
+        "mfa_reset.completed",
+        AUDIT_RESOURCE,
+        Some(*user.id),
+        None,
+        Some(serde_json::json!({ "request_id": request.id, "tenant_id": request.tenant_id })),
+    )
+    .await;
+    notify_user(
+        &state,
+        &user,
+        "Your multi-factor authentication was reset. Enroll a new factor at your next login",
+    )
+    .await;
+    Ok(Json(MessageResponse::new(
+        "MFA has been reset. Log in again and enroll a new factor.",
+    )))
+}
+
+async fn require_reviewer<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: Uuid,
+) -> Result<()> {
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::MfaResetReview,
+                scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+            },
+        )
+        .await
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListMfaResetRequestsQuery {
+    /// Filter by status (`pending`, `approved`, `rejected`, `completed`)
+    pub status: Option<String>,
+    /// Maximum number of requests to return (1-100, default 50)
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/mfa-reset-requests",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ListMfaResetRequestsQuery
+    ),
+    responses(
+        (status = 200, description = "MFA reset requests, newest first", body = Vec<MfaResetRequest>),
+        (status = 403, description = "Tenant admin required")
+    )
+)]
+pub async fn list_mfa_reset_requests<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<ListMfaResetRequestsQuery>,
+) -> Result<Json<SuccessResponse<Vec<MfaResetRequest>>>> {
+    require_reviewer(&state, &authz, tenant_id).await?;
+    let status = query
+        .status
+        .as_deref()
+        .map(|s| {
+            MfaResetStatus::parse(s)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown MFA reset status '{}'", s)))
+        })
+        .transpose()?;
+
+    let requests = reset_service(&state)
+        .list(
+            StringUuid::from(tenant_id),
+            status,
+            query.limit.unwrap_or(50),
+        )
+        .await?;
+    Ok(Json(SuccessResponse::new(requests)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("id" = String, Path, description = "MFA reset request ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "MFA reset request", body = MfaResetRequest),
+        (status = 404, description = "Request not found")
+    )
+)]
+pub async fn get_mfa_reset_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path((tenant_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<MfaResetRequest>>> {
+    require_reviewer(&state, &authz, tenant_id).await?;
+    let request = reset_service(&state)
+        .get(StringUuid::from(tenant_id), StringUuid::from(id))
+        .await?;
+    Ok(Json(SuccessResponse::new(request)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}/approve",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("id" = String, Path, description = "MFA reset request ID (UUID)")
+    ),
+    request_body = MfaResetDecisionInput,
+    responses(
+        (status = 200, description = "Request approved; the reset window is open", body = MfaResetRequest),
+        (status = 409, description = "Request no longer pending")
+    )
+)]
+pub async fn approve_mfa_reset_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<MfaResetDecisionInput>,
+) -> Result<Json<SuccessResponse<MfaResetRequest>>> {
+    decide(state, authz, headers, tenant_id, id, input, true).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}/reject",
+    tag = "Identity",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("id" = String, Path, description = "MFA reset request ID (UUID)")
+    ),
+    request_body = MfaResetDecisionInput,
+    responses(
+        (status = 200, description = "Request rejected", body = MfaResetRequest),
+        (status = 409, description = "Request no longer pending")
+    )
+)]
+pub async fn reject_mfa_reset_request<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<MfaResetDecisionInput>,
+) -> Result<Json<SuccessResponse<MfaResetRequest>>> {
+    decide(state, authz, headers, tenant_id, id, input, false).await
+}
+
+async fn decide<S: HasServices + HasDbPool>(
+    state: S,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    tenant_id: Uuid,
+    id: Uuid,
+    input: MfaResetDecisionInput,
+    approve: bool,
+) -> Result<Json<SuccessResponse<MfaResetRequest>>> {
+    require_reviewer(&state, &authz, tenant_id).await?;
+    input.validate()?;
+
+    let request = reset_service(&state)
+        .decide(
+            StringUuid::from(tenant_id),
+            StringUuid::from(id),
+            authz.user_id(),
+            approve,
+            input.comment.clone(),
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        if approve {
+            "mfa_reset.approved"
+        } else {
+            "mfa_reset.rejected"
+        },
+        AUDIT_RESOURCE,
+        Some(*request.user_id),
+        None,
+        Some(serde_json::json!({
+            "request_id": request.id,
+            "tenant_id": request.tenant_id,
+            "window_expires_at": request.window_expires_at,
+            "comment": input.comment,
+        })),
+    )
+    .await;
+    if let Ok(user) = state.user_service().get(request.user_id).await {
+        let event = if approve {
+            "Your MFA reset request was approved. Log in within 24 hours to reset your factors"
+        } else {
+            "Your MFA reset request was rejected. Contact your administrator for help"
+        };
+        notify_user(&state, &user, event).await;
+    }
+    Ok(Json(SuccessResponse::new(request)))
+}
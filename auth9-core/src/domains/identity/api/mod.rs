@@ -1,6 +1,10 @@
 //! Identity domain API handlers.
 
+pub mod account_deletion;
+pub mod account_recovery;
+pub mod attribute_release;
 pub mod auth;
+pub mod conditional_access;
 pub mod confirm_link;
 pub mod email_otp;
 pub mod email_verification;
@@ -10,9 +14,15 @@ pub mod enterprise_ldap_broker;
 pub mod enterprise_saml_broker;
 pub mod hosted_login;
 pub mod identity_provider;
+pub mod login_identifier;
 pub mod mfa;
+pub mod mfa_reset;
 pub mod password;
+pub mod password_deny_list;
+pub mod progressive_profiling;
 pub mod required_actions;
+pub mod revocation;
 pub mod session;
 pub mod social_broker;
 pub mod webauthn;
+pub mod workload_identity;
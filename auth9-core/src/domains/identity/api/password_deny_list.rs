@@ -0,0 +1,128 @@
+//! Tenant password deny list API handlers
+
+use crate::error::AppError;
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::password_deny_list::{PasswordDenyListSummary, UpdatePasswordDenyListInput};
+use crate::policy::{enforce, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+fn enforce_tenant_config<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+    action: PolicyAction,
+) -> Result<(), AppError> {
+    enforce(
+        state.config(),
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{id}/password-deny-list",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Password deny list summary", body = PasswordDenyListSummary),
+        (status = 404, description = "Tenant has no password deny list")
+    )
+)]
+/// Get the tenant's password deny list summary (terms are not retained)
+pub async fn get_password_deny_list<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<StringUuid>,
+) -> Result<Json<SuccessResponse<PasswordDenyListSummary>>, AppError> {
+    enforce_tenant_config(&state, &auth, tenant_id, PolicyAction::SystemConfigRead)?;
+
+    let summary = state
+        .password_deny_list_service()
+        .get(tenant_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tenant has no password deny list".to_string()))?;
+    Ok(Json(SuccessResponse::new(summary)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{id}/password-deny-list",
+    tag = "Identity",
+    request_body = UpdatePasswordDenyListInput,
+    responses(
+        (status = 200, description = "Password deny list replaced", body = PasswordDenyListSummary),
+        (status = 422, description = "A term is too short or too long, or there are too many terms")
+    )
+)]
+/// Replace the tenant's password deny list
+pub async fn update_password_deny_list<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+    Json(input): Json<UpdatePasswordDenyListInput>,
+) -> Result<Json<SuccessResponse<PasswordDenyListSummary>>, AppError> {
+    enforce_tenant_config(&state, &auth, tenant_id, PolicyAction::SystemConfigWrite)?;
+
+    let summary = state
+        .password_deny_list_service()
+        .replace(tenant_id, &input.terms)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.password_deny_list.update",
+        "tenant",
+        Some(*tenant_id),
+        None,
+        serde_json::to_value(&summary).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(summary)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{id}/password-deny-list",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Password deny list removed"),
+        (status = 404, description = "Tenant has no password deny list")
+    )
+)]
+/// Remove the tenant's password deny list
+pub async fn delete_password_deny_list<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+) -> Result<Json<MessageResponse>, AppError> {
+    enforce_tenant_config(&state, &auth, tenant_id, PolicyAction::SystemConfigWrite)?;
+
+    state.password_deny_list_service().delete(tenant_id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.password_deny_list.delete",
+        "tenant",
+        Some(*tenant_id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Password deny list removed.")))
+}
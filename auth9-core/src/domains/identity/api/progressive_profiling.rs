@@ -0,0 +1,282 @@
+//! Progressive profiling API handlers.
+//!
+//! Service administrators declare required profile fields per scope; the
+//! hosted form lists and collects missing fields for the signed-in user.
+
+use crate::config::Config;
+use crate::domains::authorization::api::service::require_service_access;
+use crate::domains::identity::service::{AttributeReleaseService, ProgressiveProfilingService};
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::released_claims;
+use crate::models::progressive_profiling::{
+    ProfileFormResponse, ProfileRequirement, SetProfileRequirementsInput, SubmitProfileInput,
+};
+use crate::models::service::Service;
+use crate::models::user::{UpdateUserInput, User};
+use crate::repository::progressive_profiling::ProgressiveProfilingRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+pub(crate) fn progressive_profiling_service<S: HasDbPool>(
+    state: &S,
+) -> ProgressiveProfilingService<ProgressiveProfilingRepositoryImpl> {
+    ProgressiveProfilingService::new(Arc::new(ProgressiveProfilingRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+/// Custom scope claim mappings of a tenant (none for platform services)
+pub(crate) async fn tenant_custom_scope_claims<S: HasServices>(
+    state: &S,
+    tenant_id: Option<StringUuid>,
+) -> Result<BTreeMap<String, Vec<String>>> {
+    match tenant_id {
+        Some(tenant_id) => Ok(state
+            .tenant_service()
+            .get(tenant_id)
+            .await?
+            .settings
+            .custom_scope_claims),
+        None => Ok(Default::default()),
+    }
+}
+
+/// Claims of the user released to `service` by `scope`, honoring the custom
+/// scope claim mappings of the tenant that owns the service and the service's
+/// attribute release policy. Nothing is released without a service.
+pub(crate) async fn scoped_user_claims<S: HasServices + HasDbPool>(
+    state: &S,
+    service: Option<&Service>,
+    user: &User,
+    scope: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let Some(service) = service else {
+        return Ok(Default::default());
+    };
+    let custom = tenant_custom_scope_claims(state, service.tenant_id).await?;
+    let mut claims = progressive_profiling_service(state)
+        .scoped_claims(user, scope, &custom)
+        .await?;
+    // Usernames and phone numbers are login identifiers, not profile attributes
+    let released = released_claims(scope, &custom);
+    if released.contains("preferred_username") || released.contains("phone_number") {
+        let identifiers = state.login_identifier_service().list(user.id).await?;
+        for identifier in identifiers {
+            let claim = identifier.kind.claim_name();
+            if released.contains(claim) {
+                claims.insert(claim.to_string(), identifier.value.into());
+            }
+        }
+    }
+    AttributeReleaseService::from_pool(state.db_pool().clone())
+        .release(service.id, claims)
+        .await
+}
+
+/// Hosted form URL returned with `interaction_required` token errors
+pub(crate) fn profile_interaction_uri(config: &Config, client_id: &str, scope: &str) -> String {
+    let portal = config.portal_url.as_deref().unwrap_or(&config.jwt.issuer);
+    format!(
+        "{}/complete-profile?client_id={}&scope={}",
+        portal.trim_end_matches('/'),
+        urlencoding::encode(client_id),
+        urlencoding::encode(scope)
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/services/{id}/profile-requirements",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Profile fields required by the service", body = Vec<ProfileRequirement>)
+    )
+)]
+pub async fn list_requirements<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<ProfileRequirement>>>> {
+    let service = state.client_service().get(id).await?;
+    require_service_access(
+        state.config(),
+        &auth,
+        service.tenant_id.as_ref().map(|t| t.0),
+    )?;
+
+    let requirements = progressive_profiling_service(&state)
+        .list_requirements(service.id)
+        .await?;
+    Ok(Json(SuccessResponse::new(requirements)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/services/{id}/profile-requirements",
+    tag = "Identity",
+    request_body = SetProfileRequirementsInput,
+    responses(
+        (status = 200, description = "Profile requirements replaced", body = Vec<ProfileRequirement>)
+    )
+)]
+pub async fn set_requirements<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<SetProfileRequirementsInput>,
+) -> Result<Json<SuccessResponse<Vec<ProfileRequirement>>>> {
+    input.validate()?;
+    let service = state.client_service().get(id).await?;
+    require_service_access(
+        state.config(),
+        &auth,
+        service.tenant_id.as_ref().map(|t| t.0),
+    )?;
+
+    let profiling = progressive_profiling_service(&state);
+    let before = profiling.list_requirements(service.id).await?;
+    let after = profiling.set_requirements(service.id, input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "service.profile_requirements.update",
+        "service",
+        Some(id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&after).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(after)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ProfileFormQuery {
+    pub client_id: String,
+    #[serde(default)]
+    pub scope: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/hosted-login/profile",
+    tag = "Identity",
+    params(ProfileFormQuery),
+    responses(
+        (status = 200, description = "Profile fields requested by the application", body = ProfileFormResponse)
+    )
+)]
+/// Fields the application requires for the signed-in user, with current values.
+///
+/// GET /api/v1/hosted-login/profile
+pub async fn get_profile_form<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(query): Query<ProfileFormQuery>,
+) -> Result<Json<SuccessResponse<ProfileFormResponse>>> {
+    let client = state
+        .client_service()
+        .get_client_record(&query.client_id)
+        .await?;
+    let user = state
+        .user_service()
+        .get(StringUuid::from(auth.user_id))
+        .await?;
+
+    let form = progressive_profiling_service(&state)
+        .profile_form(client.service_id, &query.client_id, &query.scope, &user)
+        .await?;
+    Ok(Json(SuccessResponse::new(form)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/hosted-login/profile",
+    tag = "Identity",
+    request_body = SubmitProfileInput,
+    responses(
+        (status = 200, description = "Attributes stored; remaining missing fields returned", body = ProfileFormResponse),
+        (status = 400, description = "Field not requested by the application or invalid value")
+    )
+)]
+/// Store attributes collected by the hosted progressive profiling form.
+///
+/// POST /api/v1/hosted-login/profile
+pub async fn submit_profile<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<SubmitProfileInput>,
+) -> Result<Json<SuccessResponse<ProfileFormResponse>>> {
+    let client = state
+        .client_service()
+        .get_client_record(&input.client_id)
+        .await?;
+    let user_id = StringUuid::from(auth.user_id);
+    let mut user = state.user_service().get(user_id).await?;
+
+    let client_id = input.client_id.clone();
+    let scope = input.scope.clone();
+    let fields: Vec<String> = input.attributes.keys().cloned().collect();
+
+    let profiling = progressive_profiling_service(&state);
+    if let Some(display_name) = profiling.submit(client.service_id, &user, input).await? {
+        user = state
+            .user_service()
+            .update(
+                user_id,
+                UpdateUserInput {
+                    display_name: Some(display_name),
+                    avatar_url: None,
+                },
+                None,
+            )
+            .await?;
+    }
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.profile.progressive_update",
+        "user",
+        Some(auth.user_id),
+        None,
+        Some(serde_json::json!({ "client_id": client_id, "fields": fields })),
+    )
+    .await;
+
+    let form = profiling
+        .profile_form(client.service_id, &client_id, &scope, &user)
+        .await?;
+    Ok(Json(SuccessResponse::new(form)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_interaction_uri_encodes_params() {
+        let mut config = Config::for_tests();
+        config.portal_url = Some("https://portal.example.com/".to_string());
+        assert_eq!(
+            profile_interaction_uri(&config, "my app", "openid billing"),
+            "https://portal.example.com/complete-profile?client_id=my%20app&scope=openid%20billing"
+        );
+    }
+}
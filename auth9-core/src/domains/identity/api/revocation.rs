@@ -0,0 +1,58 @@
+//! Session revocation feed API handlers
+//!
+//! Relying parties that were offline read the revocations they missed here,
+//! resuming from the last sequence they received by webhook or watch stream.
+
+use crate::domains::identity::service::RevocationFeedService;
+use crate::error::Result;
+use crate::http_support::SuccessResponse;
+use crate::middleware::auth::{AuthUser, TokenType};
+use crate::models::common::StringUuid;
+use crate::models::session_revocation::{RevocationFeedPage, RevocationFeedQuery};
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+/// Revoked sessions after a sequence number
+///
+/// Service clients bound to a tenant only see revocations of the tenant's
+/// members; platform services and platform admins see all of them.
+#[utoipa::path(
+    get,
+    path = "/api/v1/revocations",
+    tag = "Identity",
+    params(RevocationFeedQuery),
+    responses(
+        (status = 200, description = "Notices in sequence order", body = RevocationFeedPage),
+        (status = 403, description = "Service client or platform admin required")
+    )
+)]
+pub async fn list_revocations<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(query): Query<RevocationFeedQuery>,
+) -> Result<Json<SuccessResponse<RevocationFeedPage>>> {
+    let tenant_id = if auth.token_type == TokenType::ServiceClient {
+        auth.tenant_id.map(StringUuid::from)
+    } else {
+        enforce_with_state(
+            &state,
+            &auth,
+            &PolicyInput {
+                action: PolicyAction::PlatformAdmin,
+                scope: ResourceScope::Global,
+            },
+        )
+        .await?;
+        None
+    };
+
+    let page =
+        RevocationFeedService::from_pool(state.db_pool().clone(), &state.config().revocation_feed)
+            .page(query.since, tenant_id, query.limit)
+            .await?;
+    Ok(Json(SuccessResponse::new(page)))
+}
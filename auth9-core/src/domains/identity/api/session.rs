@@ -1,19 +1,48 @@
 //! Session management API handlers
 
 use crate::cache::CacheOperations;
+use crate::domains::identity::service::trusted_device::compute_device_fingerprint;
+use crate::domains::platform::service::NotificationPreferenceService;
 use crate::error::AppError;
-use crate::http_support::{MessageResponse, SuccessResponse};
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
 use crate::middleware::auth::AuthUser;
+use crate::middleware::response_masking::{
+    masked_fields_header, masking_rules_for_user, MASKED_FIELDS_HEADER,
+};
 use crate::models::common::StringUuid;
-use crate::models::session::SessionInfo;
+use crate::models::email_template::EmailTemplateType;
+use crate::models::masking::apply_masking;
+use crate::models::notification_preference::NotificationCategory;
+use crate::models::session::{
+    RenameSessionInput, Session, SessionInfo, DEFAULT_SESSION_EXPORT_PAGE,
+};
 use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
-use crate::state::{HasCache, HasServices, HasSessionManagement};
+use crate::state::{
+    HasCache, HasDbPool, HasSecurityAlerts, HasServices, HasSessionManagement, HasSystemSettings,
+    HasTrustedDevices,
+};
 use axum::{
-    extract::{Path, State},
-    http::HeaderMap,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue},
+    response::IntoResponse,
     Json,
 };
-use utoipa::ToSchema;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+/// State needed to alert users about revocations from unrecognized devices
+pub trait DeviceAlertContext:
+    HasSessionManagement + HasServices + HasDbPool + HasSystemSettings + HasTrustedDevices
+{
+}
+
+impl<T> DeviceAlertContext for T where
+    T: HasSessionManagement + HasServices + HasDbPool + HasSystemSettings + HasTrustedDevices
+{
+}
 
 #[utoipa::path(
     get,
@@ -47,7 +76,7 @@ pub async fn list_my_sessions<S: HasSessionManagement>(
     )
 )]
 /// Revoke a specific session
-pub async fn revoke_session<S: HasSessionManagement>(
+pub async fn revoke_session<S: DeviceAlertContext>(
     State(state): State<S>,
     headers: HeaderMap,
     Path(session_id): Path<StringUuid>,
@@ -60,14 +89,58 @@ pub async fn revoke_session<S: HasSessionManagement>(
         ));
     }
 
+    let revoked = state.session_service().find_session(session_id).await?;
     state
         .session_service()
         .revoke_session(session_id, user_id)
         .await?;
 
+    let description = match revoked {
+        Some(session) => format!("Device signed out: {}", session.friendly_name()),
+        None => "Device signed out".to_string(),
+    };
+    spawn_revocation_alert(&state, user_id, current_session_id, description);
+
     Ok(Json(MessageResponse::new("Session revoked successfully.")))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/sessions/{id}",
+    tag = "Identity",
+    request_body = RenameSessionInput,
+    responses(
+        (status = 200, description = "Device renamed", body = SessionInfo),
+        (status = 403, description = "Session belongs to another user"),
+        (status = 404, description = "Session not found")
+    )
+)]
+/// Give one of the current user's devices a friendly name.
+/// An omitted `name` restores the detected device name.
+pub async fn rename_session<S: HasSessionManagement>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Path(session_id): Path<StringUuid>,
+    Json(input): Json<RenameSessionInput>,
+) -> Result<Json<SuccessResponse<SessionInfo>>, AppError> {
+    let (user_id, current_session_id) = extract_session_info(&state, &headers)?;
+    input.validate()?;
+    if input.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::BadRequest(
+            "Device name cannot be blank".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service()
+        .rename_session(session_id, user_id, input.name)
+        .await?;
+
+    let mut info = SessionInfo::from(session);
+    info.is_current = info.id == current_session_id.to_string();
+    Ok(Json(SuccessResponse::new(info)))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/v1/users/me/sessions",
@@ -77,7 +150,7 @@ pub async fn revoke_session<S: HasSessionManagement>(
     )
 )]
 /// Revoke all other sessions (except current)
-pub async fn revoke_other_sessions<S: HasSessionManagement>(
+pub async fn revoke_other_sessions<S: DeviceAlertContext>(
     State(state): State<S>,
     headers: HeaderMap,
 ) -> Result<Json<SuccessResponse<RevokeSessionsResponse>>, AppError> {
@@ -88,6 +161,61 @@ pub async fn revoke_other_sessions<S: HasSessionManagement>(
         .revoke_other_sessions(user_id, current_session_id)
         .await?;
 
+    if count > 0 {
+        spawn_revocation_alert(
+            &state,
+            user_id,
+            current_session_id,
+            format!("{} other device(s) signed out", count),
+        );
+    }
+
+    Ok(Json(SuccessResponse::new(RevokeSessionsResponse {
+        revoked_count: count,
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/sessions/sign-out-everywhere",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "All sessions, including the current one, revoked", body = RevokeSessionsResponse)
+    )
+)]
+/// Sign out on every device, including the current one
+pub async fn sign_out_everywhere<S: DeviceAlertContext + HasCache>(
+    State(state): State<S>,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<RevokeSessionsResponse>>, AppError> {
+    let (user_id, current_session_id) = extract_session_info(&state, &headers)?;
+
+    let revoked = state.session_service().sign_out_everywhere(user_id).await?;
+
+    // Revoked sessions must not keep working until their access tokens expire
+    let blacklist_ttl = state.config().jwt.access_token_ttl_secs.unsigned_abs();
+    let cache = state.cache();
+    let mut sids: Vec<String> = revoked.iter().map(|s| s.id.to_string()).collect();
+    if !sids.contains(&current_session_id.to_string()) {
+        sids.push(current_session_id.to_string());
+    }
+    for sid in &sids {
+        if let Err(e) = cache.add_to_token_blacklist(sid, blacklist_ttl).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to blacklist session token during sign-out everywhere");
+        }
+        if let Err(e) = cache.remove_all_refresh_sessions_for_session(sid).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to clean up refresh sessions during sign-out everywhere");
+        }
+    }
+
+    let count = revoked.len() as u64;
+    spawn_revocation_alert(
+        &state,
+        user_id,
+        current_session_id,
+        "Signed out on all devices".to_string(),
+    );
+
     Ok(Json(SuccessResponse::new(RevokeSessionsResponse {
         revoked_count: count,
     })))
@@ -102,6 +230,9 @@ pub async fn revoke_other_sessions<S: HasSessionManagement>(
     )
 )]
 /// Admin: Force logout a user (revoke all sessions)
+///
+/// Tenant admins may log out members of their own tenant, limited to the
+/// members of their admin units when they administer any.
 pub async fn force_logout_user<S: HasSessionManagement + HasServices + HasCache>(
     State(state): State<S>,
     auth: AuthUser,
@@ -192,12 +323,371 @@ pub async fn list_user_sessions<S: HasSessionManagement + HasServices>(
     Ok(Json(SuccessResponse::new(sessions)))
 }
 
+/// Query of a tenant session export page
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SessionExportQuery {
+    /// Continuation cursor from the `X-Next-Cursor` header of the previous page
+    pub cursor: Option<String>,
+    /// Sessions per page (default 1000, max 10000)
+    pub limit: Option<i64>,
+    /// Skip revoked sessions (default true)
+    #[serde(default = "default_active_only")]
+    pub active_only: bool,
+}
+
+fn default_active_only() -> bool {
+    true
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/sessions/export",
+    tag = "Identity",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)"), SessionExportQuery),
+    responses(
+        (status = 200, description = "One session per line; `X-Next-Cursor` is set while more pages remain", content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid cursor")
+    )
+)]
+/// Export the sessions of a tenant's members as NDJSON
+///
+/// Pages are keyset scans ordered by session id, so every page costs the
+/// same however large the tenant is. Request pages until the response has
+/// no `X-Next-Cursor` header.
+pub async fn export_tenant_sessions<S: HasSessionManagement + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<StringUuid>,
+    Query(query): Query<SessionExportQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::SessionExport,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+    .await?;
+
+    let page = state
+        .session_service()
+        .export_tenant_sessions(
+            tenant_id,
+            query.cursor.as_deref(),
+            query.limit.unwrap_or(DEFAULT_SESSION_EXPORT_PAGE),
+            query.active_only,
+        )
+        .await?;
+
+    // Audit the export once, not once per page
+    if query.cursor.is_none() {
+        let _ = write_audit_log_generic(
+            &state,
+            &headers,
+            "session.export",
+            "tenant",
+            Some(*tenant_id),
+            None,
+            Some(serde_json::json!({ "active_only": query.active_only })),
+        )
+        .await;
+    }
+
+    // The masking middleware leaves NDJSON alone; mask each line here
+    let rules = masking_rules_for_user(&state.config().response_masking, &auth);
+    let mut masked = false;
+    let mut body = Vec::new();
+    for session in &page.sessions {
+        let mut line =
+            serde_json::to_value(session).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+        masked |= apply_masking(&mut line, &rules);
+        serde_json::to_writer(&mut body, &line)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+        body.push(b'\n');
+    }
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson"),
+    );
+    if masked {
+        metrics::counter!("auth9_response_masked_total").increment(1);
+        if let Some(fields) = masked_fields_header(&rules) {
+            response_headers.insert(MASKED_FIELDS_HEADER, fields);
+        }
+    }
+    if let Some(cursor) = page.next_cursor {
+        // The cursor is base64url, always a valid header value
+        if let Ok(value) = HeaderValue::from_str(&cursor) {
+            response_headers.insert("x-next-cursor", value);
+        }
+    }
+    Ok((response_headers, body))
+}
+
 /// Response for session revocation
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, ToSchema)]
 pub struct RevokeSessionsResponse {
     pub revoked_count: u64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SignInReportQuery {
+    pub token: String,
+}
+
+/// Sign-in named by the "this wasn't me" link of a sign-in email
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignInReportResponse {
+    /// Reported session; absent when the link covers every session
+    pub session_id: Option<StringUuid>,
+    pub device: Option<String>,
+    pub location: Option<String>,
+    pub ip_address: Option<String>,
+    pub signed_in_at: Option<DateTime<Utc>>,
+    /// Whether the reported session is signed out
+    pub signed_out: bool,
+}
+
+impl SignInReportResponse {
+    fn new(session: Option<&Session>, signed_out: bool) -> Self {
+        Self {
+            session_id: session.map(|s| s.id),
+            device: session.map(Session::device_summary),
+            location: session.and_then(|s| s.location.clone()),
+            ip_address: session.and_then(|s| s.ip_address.clone()),
+            signed_in_at: session.map(|s| s.created_at),
+            signed_out,
+        }
+    }
+}
+
+/// Verify a "this wasn't me" token and load the session it names (none for
+/// links covering every session, or sessions no longer on record)
+async fn reported_session<S: HasSessionManagement + HasServices + HasDbPool>(
+    state: &S,
+    token: &str,
+) -> Result<(StringUuid, Option<Session>), AppError> {
+    let (user_id, session_id) =
+        NotificationPreferenceService::from_config(state.db_pool().clone(), state.config())
+            .verify_sign_in_report_token(token, Utc::now())?;
+    let session = match session_id {
+        Some(id) => state
+            .session_service()
+            .find_session(id)
+            .await?
+            .filter(|session| session.user_id == user_id),
+        None => None,
+    };
+    Ok((user_id, session))
+}
+
+/// Look up a "this wasn't me" link (public endpoint)
+///
+/// Does not change anything, so link scanners opening the URL are harmless.
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/sign-in-report",
+    tag = "Identity",
+    params(("token" = String, Query, description = "Report token from the sign-in email")),
+    responses(
+        (status = 200, description = "The reported sign-in", body = SignInReportResponse),
+        (status = 400, description = "Invalid or expired token")
+    )
+)]
+pub async fn get_sign_in_report<S: HasSessionManagement + HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(query): Query<SignInReportQuery>,
+) -> Result<Json<SuccessResponse<SignInReportResponse>>, AppError> {
+    let (_, session) = reported_session(&state, &query.token).await?;
+    let signed_out = session.as_ref().is_some_and(|s| s.revoked_at.is_some());
+    Ok(Json(SuccessResponse::new(SignInReportResponse::new(
+        session.as_ref(),
+        signed_out,
+    ))))
+}
+
+/// Report a sign-in as not yours (public endpoint)
+///
+/// Signs the reported session out (every session when the link does not
+/// name one or it is no longer on record) and raises a security alert.
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/sign-in-report",
+    tag = "Identity",
+    params(("token" = String, Query, description = "Report token from the sign-in email")),
+    responses(
+        (status = 200, description = "Session signed out and alert raised", body = SignInReportResponse),
+        (status = 400, description = "Invalid or expired token")
+    )
+)]
+pub async fn report_sign_in<
+    S: HasSessionManagement + HasServices + HasDbPool + HasCache + HasSecurityAlerts,
+>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Query(query): Query<SignInReportQuery>,
+) -> Result<Json<SuccessResponse<SignInReportResponse>>, AppError> {
+    let (user_id, session) = reported_session(&state, &query.token).await?;
+
+    let sids: Vec<String> = match &session {
+        Some(session) => {
+            if session.revoked_at.is_none() {
+                state
+                    .session_service()
+                    .revoke_session(session.id, user_id)
+                    .await?;
+            }
+            vec![session.id.to_string()]
+        }
+        None => state
+            .session_service()
+            .sign_out_everywhere(user_id)
+            .await?
+            .iter()
+            .map(|s| s.id.to_string())
+            .collect(),
+    };
+
+    // The reported sessions must not keep working until their tokens expire
+    let blacklist_ttl = state.config().jwt.access_token_ttl_secs.unsigned_abs();
+    let cache = state.cache();
+    for sid in &sids {
+        if let Err(e) = cache.add_to_token_blacklist(sid, blacklist_ttl).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to blacklist reported session token");
+        }
+        if let Err(e) = cache.remove_all_refresh_sessions_for_session(sid).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to clean up refresh sessions of reported session");
+        }
+    }
+
+    let alert = state
+        .security_detection_service()
+        .report_sign_in(user_id, session.as_ref())
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "session.reported",
+        "user",
+        Some(*user_id),
+        None,
+        Some(serde_json::json!({
+            "alert_id": alert.id,
+            "revoked_sessions": sids,
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(SignInReportResponse::new(
+        session.as_ref(),
+        true,
+    ))))
+}
+
+/// Email the user in the background when devices were signed out from a
+/// session that is not on one of their trusted devices
+fn spawn_revocation_alert<S: DeviceAlertContext>(
+    state: &S,
+    user_id: StringUuid,
+    acting_session_id: StringUuid,
+    description: String,
+) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let acting = state
+            .session_service()
+            .find_session(acting_session_id)
+            .await
+            .ok()
+            .flatten();
+        notify_unrecognized_revocation(&state, user_id, acting, description).await;
+    });
+}
+
+async fn notify_unrecognized_revocation<S: DeviceAlertContext>(
+    state: &S,
+    user_id: StringUuid,
+    acting: Option<Session>,
+    description: String,
+) {
+    let Some(acting) = acting else {
+        return;
+    };
+    if let (Some(user_agent), Some(ip)) = (&acting.user_agent, &acting.ip_address) {
+        let fingerprint = compute_device_fingerprint(user_agent, ip);
+        match state
+            .trusted_device_service()
+            .is_trusted(user_id, &fingerprint)
+            .await
+        {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(user_id = %user_id, error = %e, "Trusted device lookup failed");
+                return;
+            }
+        }
+    }
+
+    let notifications =
+        NotificationPreferenceService::from_config(state.db_pool().clone(), state.config());
+    let category = NotificationCategory::SecurityAlerts;
+    if !notifications.allows(user_id, category).await {
+        return;
+    }
+    let user = match state.user_service().get(user_id).await {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::warn!(user_id = %user_id, error = %e, "Skipping device revocation email");
+            return;
+        }
+    };
+
+    let mut vars = HashMap::new();
+    vars.insert(
+        "user_name".to_string(),
+        user.display_name
+            .clone()
+            .unwrap_or_else(|| "User".to_string()),
+    );
+    vars.insert(
+        "event_type".to_string(),
+        format!("{} from an unrecognized device", description),
+    );
+    vars.insert("device_info".to_string(), acting.friendly_name());
+    vars.insert(
+        "location".to_string(),
+        acting
+            .location
+            .clone()
+            .or_else(|| acting.ip_address.clone())
+            .unwrap_or_else(|| "Unknown".to_string()),
+    );
+    vars.insert(
+        "timestamp".to_string(),
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    );
+
+    if let Err(e) = notifications
+        .send_notification(
+            state.email_service(),
+            user_id,
+            &user.email,
+            category,
+            EmailTemplateType::SecurityAlert,
+            crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+            vars,
+        )
+        .await
+    {
+        tracing::warn!(user_id = %user_id, error = %e, "Failed to send device revocation email");
+    }
+}
+
 /// Extract user ID and current session ID from JWT token
 fn extract_session_info<S: HasSessionManagement>(
     state: &S,
@@ -4,8 +4,10 @@
 //! for social login providers (Google, GitHub, Microsoft, generic OIDC).
 
 use crate::cache::CacheOperations;
+use crate::crypto::FlowKind;
 use crate::domains::identity::api::auth::helpers::{
-    AuthorizationCodeData, LoginChallengeData, AUTH_CODE_TTL_SECS,
+    consume_flow_state, peek_flow_state, AuthorizationCodeData, LoginChallengeData,
+    AUTH_CODE_TTL_SECS,
 };
 use crate::domains::security_observability::service::analytics::FederationEventMetadata;
 use crate::error::{AppError, Result};
@@ -414,22 +416,13 @@ pub async fn authorize<S: HasIdentityProviders + HasServices + HasCache>(
     Path(alias): Path<String>,
     Query(params): Query<SocialAuthorizeQuery>,
 ) -> Result<Response> {
-    // 1. Verify login_challenge exists (peek, do NOT consume)
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(&params.login_challenge)
-        .await?;
-    let challenge_json = challenge_json
-        .ok_or_else(|| AppError::BadRequest("Invalid or expired login challenge".to_string()))?;
-    // Re-store it immediately (peek pattern)
-    state
-        .cache()
-        .store_login_challenge(
-            &params.login_challenge,
-            &challenge_json,
-            super::auth::LOGIN_CHALLENGE_TTL_SECS,
-        )
-        .await?;
+    // 1. Verify login_challenge is valid (peek, do NOT consume)
+    peek_flow_state::<_, LoginChallengeData>(
+        &state,
+        FlowKind::LoginChallenge,
+        &params.login_challenge,
+    )
+    .ok_or_else(|| AppError::BadRequest("Invalid or expired login challenge".to_string()))?;
 
     // 2. Look up provider
     let provider = state
@@ -592,15 +585,15 @@ pub async fn callback<
         .await?;
 
     // 9. Consume login challenge and generate authorization code
-    let challenge_json = state
-        .cache()
-        .consume_login_challenge(&social_state.login_challenge_id)
-        .await?
-        .ok_or_else(|| {
-            AppError::BadRequest("Login challenge expired during social login".to_string())
-        })?;
-    let challenge: LoginChallengeData =
-        serde_json::from_str(&challenge_json).map_err(|e| AppError::Internal(e.into()))?;
+    let challenge: LoginChallengeData = consume_flow_state(
+        &state,
+        FlowKind::LoginChallenge,
+        &social_state.login_challenge_id,
+    )
+    .await?
+    .ok_or_else(|| {
+        AppError::BadRequest("Login challenge expired during social login".to_string())
+    })?;
 
     let auth_code = uuid::Uuid::new_v4().to_string();
     let code_data = AuthorizationCodeData {
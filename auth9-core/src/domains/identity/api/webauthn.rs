@@ -1,9 +1,10 @@
 //! WebAuthn/Passkey API handlers
 
+use crate::domains::identity::api::conditional_access;
 use crate::error::AppError;
 use crate::http_support::{MessageResponse, SuccessResponse};
 use crate::models::webauthn::WebAuthnCredential;
-use crate::state::{HasServices, HasSessionManagement, HasWebAuthn};
+use crate::state::{HasCache, HasServices, HasSessionManagement, HasWebAuthn};
 use axum::{
     extract::{Path, State},
     http::HeaderMap,
@@ -136,7 +137,9 @@ pub struct AuthenticationStartResponse {
 /// Complete passkey authentication
 ///
 /// POST /api/v1/auth/webauthn/authenticate/complete
-pub async fn complete_authentication<S: HasWebAuthn + HasServices + HasSessionManagement>(
+pub async fn complete_authentication<
+    S: HasWebAuthn + HasServices + HasSessionManagement + HasCache,
+>(
     State(state): State<S>,
     headers: HeaderMap,
     Json(body): Json<CompleteAuthenticationRequest>,
@@ -163,6 +166,8 @@ pub async fn complete_authentication<S: HasWebAuthn + HasServices + HasSessionMa
         .session_service()
         .create_session(user.id, None, ip_address, user_agent)
         .await?;
+    // A passkey assertion satisfies conditional access MFA requirements
+    conditional_access::mark_session_mfa(&state, session.id).await;
 
     // Issue identity token
     let jwt_manager = HasServices::jwt_manager(&state);
@@ -0,0 +1,205 @@
+//! Workload identity federation API handlers.
+//!
+//! Tenant administrators define which CI OIDC tokens (issuer, audience,
+//! subject and claims) may be exchanged at the token endpoint for a
+//! short-lived token of one of the tenant's services.
+
+use crate::domains::identity::service::WorkloadIdentityService;
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::workload_identity::{
+    CreateWorkloadIdentityRuleInput, UpdateWorkloadIdentityRuleInput, WorkloadIdentityRule,
+};
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::workload_identity::WorkloadIdentityRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use uuid::Uuid;
+
+fn workload_identity_service<S: HasDbPool>(
+    state: &S,
+) -> WorkloadIdentityService<WorkloadIdentityRepositoryImpl> {
+    WorkloadIdentityService::from_pool(state.db_pool().clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/workload-identity/rules",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Workload identity federation rules", body = Vec<WorkloadIdentityRule>)
+    )
+)]
+pub async fn list_rules<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<WorkloadIdentityRule>>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantRead).await?;
+    let rules = workload_identity_service(&state)
+        .list(StringUuid::from(tenant_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(rules)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/workload-identity/rules",
+    tag = "Identity",
+    request_body = CreateWorkloadIdentityRuleInput,
+    responses(
+        (status = 200, description = "Rule created", body = WorkloadIdentityRule),
+        (status = 400, description = "Invalid rule or rule limit reached"),
+        (status = 403, description = "Service does not belong to the tenant")
+    )
+)]
+pub async fn create_rule<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<CreateWorkloadIdentityRuleInput>,
+) -> Result<Json<SuccessResponse<WorkloadIdentityRule>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let service = state.client_service().get(input.service_id).await?;
+    if service.tenant_id.map(|t| t.0) != Some(tenant_id) {
+        return Err(AppError::Forbidden(
+            "Service does not belong to this tenant".to_string(),
+        ));
+    }
+
+    let created = workload_identity_service(&state)
+        .create(StringUuid::from(tenant_id), input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.workload_identity.create",
+        "workload_identity_rule",
+        Some(*created.id),
+        None,
+        serde_json::to_value(&created).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/workload-identity/rules/{rule_id}",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Workload identity federation rule", body = WorkloadIdentityRule),
+        (status = 404, description = "Rule not found")
+    )
+)]
+pub async fn get_rule<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, rule_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<WorkloadIdentityRule>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantRead).await?;
+    let rule = workload_identity_service(&state)
+        .get(StringUuid::from(tenant_id), StringUuid::from(rule_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(rule)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/workload-identity/rules/{rule_id}",
+    tag = "Identity",
+    request_body = UpdateWorkloadIdentityRuleInput,
+    responses(
+        (status = 200, description = "Rule updated", body = WorkloadIdentityRule),
+        (status = 404, description = "Rule not found")
+    )
+)]
+pub async fn update_rule<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, rule_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateWorkloadIdentityRuleInput>,
+) -> Result<Json<SuccessResponse<WorkloadIdentityRule>>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let service = workload_identity_service(&state);
+    let tenant_id = StringUuid::from(tenant_id);
+    let rule_id = StringUuid::from(rule_id);
+    let before = service.get(tenant_id, rule_id).await?;
+    let after = service.update(tenant_id, rule_id, input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.workload_identity.update",
+        "workload_identity_rule",
+        Some(*rule_id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&after).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(after)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/workload-identity/rules/{rule_id}",
+    tag = "Identity",
+    responses(
+        (status = 200, description = "Rule deleted"),
+        (status = 404, description = "Rule not found")
+    )
+)]
+pub async fn delete_rule<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, rule_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_tenant_access(&state, &auth, tenant_id, PolicyAction::TenantOwner).await?;
+    let service = workload_identity_service(&state);
+    let tenant_id = StringUuid::from(tenant_id);
+    let rule_id = StringUuid::from(rule_id);
+    let removed = service.get(tenant_id, rule_id).await?;
+    service.delete(tenant_id, rule_id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.workload_identity.delete",
+        "workload_identity_rule",
+        Some(*rule_id),
+        serde_json::to_value(&removed).ok(),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Workload identity rule deleted")))
+}
+
+async fn ensure_tenant_access<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: Uuid,
+    action: PolicyAction,
+) -> Result<()> {
+    policy::enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+        },
+    )
+    .await
+}
@@ -1,5 +1,6 @@
 use crate::domains::identity::api as identity_api;
 use crate::domains::identity::context::IdentityContext;
+use crate::state::HasSecurityAlerts;
 use axum::{
     routing::{delete, get, post},
     Router,
@@ -7,7 +8,7 @@ use axum::{
 
 pub fn public_routes<S>() -> Router<S>
 where
-    S: IdentityContext,
+    S: IdentityContext + HasSecurityAlerts,
 {
     Router::new()
         .route(
@@ -32,6 +33,20 @@ where
             "/api/v1/auth/authorize/complete",
             post(identity_api::auth::authorize_complete::<S>),
         )
+        .route(
+            "/api/v1/auth/authorize/consent",
+            get(identity_api::auth::authorize_consent::<S>),
+        )
+        .route(
+            "/api/v1/auth/session/check",
+            get(identity_api::auth::session_check::<S>),
+        )
+        // "This wasn't me" links in sign-in emails
+        .route(
+            "/api/v1/notifications/sign-in-report",
+            get(identity_api::session::get_sign_in_report::<S>)
+                .post(identity_api::session::report_sign_in::<S>),
+        )
         .route(
             "/api/v1/auth/logout",
             get(identity_api::auth::logout_redirect::<S>).post(identity_api::auth::logout::<S>),
@@ -44,6 +59,22 @@ where
             "/api/v1/auth/reset-password",
             post(identity_api::password::reset_password::<S>),
         )
+        .route(
+            "/api/v1/auth/account-recovery/recovery-code",
+            post(identity_api::account_recovery::recover_with_code::<S>),
+        )
+        .route(
+            "/api/v1/auth/account-recovery/secondary-factor",
+            post(identity_api::account_recovery::recover_with_factor::<S>),
+        )
+        .route(
+            "/api/v1/auth/account-recovery/requests",
+            post(identity_api::account_recovery::create_recovery_request::<S>),
+        )
+        .route(
+            "/api/v1/auth/account-recovery/requests/{id}/claim",
+            post(identity_api::account_recovery::claim_recovery_request::<S>),
+        )
         .route(
             "/api/v1/auth/webauthn/authenticate/start",
             post(identity_api::webauthn::start_authentication::<S>),
@@ -86,6 +117,15 @@ where
             "/api/v1/mfa/challenge/recovery-code",
             post(identity_api::mfa::challenge_recovery_code::<S>),
         )
+        // Admin-assisted MFA reset (MFA session token, no JWT required)
+        .route(
+            "/api/v1/mfa/reset-requests",
+            post(identity_api::mfa_reset::create_mfa_reset_request::<S>),
+        )
+        .route(
+            "/api/v1/mfa/reset-requests/{id}/complete",
+            post(identity_api::mfa_reset::complete_mfa_reset::<S>),
+        )
         // Email verification
         .route(
             "/api/v1/hosted-login/send-verification",
@@ -145,6 +185,38 @@ where
     S: IdentityContext,
 {
     Router::new()
+        .route(
+            "/api/v1/admin/account-recovery/requests",
+            get(identity_api::account_recovery::list_recovery_requests::<S>),
+        )
+        .route(
+            "/api/v1/admin/account-recovery/requests/{id}",
+            get(identity_api::account_recovery::get_recovery_request::<S>),
+        )
+        .route(
+            "/api/v1/admin/account-recovery/requests/{id}/approve",
+            post(identity_api::account_recovery::approve_recovery_request::<S>),
+        )
+        .route(
+            "/api/v1/admin/account-recovery/requests/{id}/reject",
+            post(identity_api::account_recovery::reject_recovery_request::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/mfa-reset-requests",
+            get(identity_api::mfa_reset::list_mfa_reset_requests::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}",
+            get(identity_api::mfa_reset::get_mfa_reset_request::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}/approve",
+            post(identity_api::mfa_reset::approve_mfa_reset_request::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/mfa-reset-requests/{id}/reject",
+            post(identity_api::mfa_reset::reject_mfa_reset_request::<S>),
+        )
         .route(
             "/api/v1/auth/tenant-token",
             post(identity_api::auth::tenant_token::<S>),
@@ -161,6 +233,15 @@ where
             "/api/v1/users/me/force-update-password",
             post(identity_api::password::force_change_password::<S>),
         )
+        .route(
+            "/api/v1/users/me/deletion",
+            get(identity_api::account_deletion::get_account_deletion::<S>)
+                .post(identity_api::account_deletion::schedule_account_deletion::<S>),
+        )
+        .route(
+            "/api/v1/users/me/deletion/restore",
+            post(identity_api::account_deletion::restore_account::<S>),
+        )
         .route(
             "/api/v1/users/{id}/password",
             axum::routing::put(identity_api::password::admin_set_password::<S>),
@@ -170,6 +251,21 @@ where
             get(identity_api::password::get_password_policy::<S>)
                 .put(identity_api::password::update_password_policy::<S>),
         )
+        .route(
+            "/api/v1/tenants/{id}/password-deny-list",
+            get(identity_api::password_deny_list::get_password_deny_list::<S>)
+                .put(identity_api::password_deny_list::update_password_deny_list::<S>)
+                .delete(identity_api::password_deny_list::delete_password_deny_list::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users/{user_id}/login-identifiers",
+            get(identity_api::login_identifier::list_login_identifiers::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users/{user_id}/login-identifiers/{kind}",
+            axum::routing::put(identity_api::login_identifier::set_login_identifier::<S>)
+                .delete(identity_api::login_identifier::delete_login_identifier::<S>),
+        )
         .route(
             "/api/v1/users/me/sessions",
             get(identity_api::session::list_my_sessions::<S>)
@@ -177,12 +273,25 @@ where
         )
         .route(
             "/api/v1/users/me/sessions/{id}",
-            delete(identity_api::session::revoke_session::<S>),
+            delete(identity_api::session::revoke_session::<S>)
+                .put(identity_api::session::rename_session::<S>),
+        )
+        .route(
+            "/api/v1/users/me/sessions/sign-out-everywhere",
+            post(identity_api::session::sign_out_everywhere::<S>),
         )
         .route(
             "/api/v1/admin/users/{id}/logout",
             post(identity_api::session::force_logout_user::<S>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/sessions/export",
+            get(identity_api::session::export_tenant_sessions::<S>),
+        )
+        .route(
+            "/api/v1/revocations",
+            get(identity_api::revocation::list_revocations::<S>),
+        )
         .route(
             "/api/v1/users/me/passkeys",
             get(identity_api::webauthn::list_passkeys::<S>),
@@ -241,6 +350,50 @@ where
             "/api/v1/hosted-login/complete-action",
             post(identity_api::required_actions::complete_action::<S>),
         )
+        // Progressive profiling
+        .route(
+            "/api/v1/hosted-login/profile",
+            get(identity_api::progressive_profiling::get_profile_form::<S>)
+                .post(identity_api::progressive_profiling::submit_profile::<S>),
+        )
+        .route(
+            "/api/v1/services/{id}/profile-requirements",
+            get(identity_api::progressive_profiling::list_requirements::<S>)
+                .put(identity_api::progressive_profiling::set_requirements::<S>),
+        )
+        .route(
+            "/api/v1/services/{id}/attribute-release",
+            get(identity_api::attribute_release::get_policy::<S>)
+                .put(identity_api::attribute_release::set_policy::<S>),
+        )
+        // Conditional access
+        .route(
+            "/api/v1/tenants/{tenant_id}/conditional-access/policies",
+            get(identity_api::conditional_access::list_policies::<S>)
+                .post(identity_api::conditional_access::create_policy::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/conditional-access/policies/{policy_id}",
+            get(identity_api::conditional_access::get_policy::<S>)
+                .put(identity_api::conditional_access::update_policy::<S>)
+                .delete(identity_api::conditional_access::delete_policy::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/conditional-access/simulate",
+            post(identity_api::conditional_access::simulate::<S>),
+        )
+        // Workload identity federation
+        .route(
+            "/api/v1/tenants/{tenant_id}/workload-identity/rules",
+            get(identity_api::workload_identity::list_rules::<S>)
+                .post(identity_api::workload_identity::create_rule::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/workload-identity/rules/{rule_id}",
+            get(identity_api::workload_identity::get_rule::<S>)
+                .put(identity_api::workload_identity::update_rule::<S>)
+                .delete(identity_api::workload_identity::delete_rule::<S>),
+        )
         // MFA management (authenticated)
         .route(
             "/api/v1/mfa/status",
@@ -0,0 +1,208 @@
+//! Deferred self-service account deletion
+//!
+//! Deleting an account opens a grace period of
+//! `ACCOUNT_DELETION_GRACE_DAYS` during which the user can sign in and
+//! restore it. A reminder is sent `ACCOUNT_DELETION_REMINDER_DAYS` before the
+//! period ends; once it has ended an `account_deletion` job removes the user
+//! and can no longer be stopped by a restore.
+
+use crate::config::AccountDeletionConfig;
+use crate::error::{AppError, Result};
+use crate::models::account_deletion::{
+    AccountDeletionJobPayload, AccountDeletionNotice, AccountDeletionRequest,
+};
+use crate::models::common::StringUuid;
+use crate::models::job::{CreateJobInput, JobKind};
+use crate::repository::account_deletion::AccountDeletionRepositoryImpl;
+use crate::repository::{AccountDeletionRepository, JobRepository};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+/// Requests handled per scheduler tick
+const SWEEP_BATCH_SIZE: i64 = 100;
+
+pub struct AccountDeletionService<R: AccountDeletionRepository> {
+    repo: Arc<R>,
+    config: AccountDeletionConfig,
+}
+
+impl AccountDeletionService<AccountDeletionRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool, config: &AccountDeletionConfig) -> Self {
+        Self::new(
+            Arc::new(AccountDeletionRepositoryImpl::new(pool)),
+            config.clone(),
+        )
+    }
+}
+
+impl<R: AccountDeletionRepository> AccountDeletionService<R> {
+    pub fn new(repo: Arc<R>, config: AccountDeletionConfig) -> Self {
+        Self { repo, config }
+    }
+
+    pub fn repo(&self) -> &R {
+        &self.repo
+    }
+
+    /// Schedule deletion of the user's account at the end of the grace period
+    pub async fn schedule(
+        &self,
+        user_id: StringUuid,
+        now: DateTime<Utc>,
+    ) -> Result<AccountDeletionRequest> {
+        if self.repo.find(user_id).await?.is_some() {
+            return Err(AppError::Conflict(
+                "Account deletion is already scheduled".to_string(),
+            ));
+        }
+        let request = AccountDeletionRequest {
+            user_id,
+            requested_at: now,
+            purge_after: now + Duration::days(self.config.grace_period_days as i64),
+            reminder_sent_at: None,
+            purge_job_id: None,
+        };
+        self.repo.create(&request).await?;
+        Ok(request)
+    }
+
+    pub async fn status(&self, user_id: StringUuid) -> Result<AccountDeletionRequest> {
+        self.repo
+            .find(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("No account deletion is scheduled".to_string()))
+    }
+
+    /// Cancel a scheduled deletion. Fails once the deletion job was queued.
+    pub async fn restore(&self, user_id: StringUuid) -> Result<AccountDeletionRequest> {
+        let request = self.status(user_id).await?;
+        if !request.is_restorable() {
+            return Err(AppError::Conflict(
+                "The grace period has ended and the account is being deleted".to_string(),
+            ));
+        }
+        self.repo.delete(user_id).await?;
+        Ok(request)
+    }
+
+    /// Users whose grace period ends within the reminder window and who have
+    /// not been reminded yet
+    pub async fn due_reminders(&self, now: DateTime<Utc>) -> Result<Vec<AccountDeletionNotice>> {
+        let purge_before = now + Duration::days(self.config.reminder_days_before as i64);
+        self.repo
+            .list_due_reminders(purge_before, SWEEP_BATCH_SIZE)
+            .await
+    }
+
+    /// Queue an `account_deletion` job for every request whose grace period
+    /// has ended. Returns the number of jobs queued.
+    pub async fn queue_due_purges<J: JobRepository + ?Sized>(
+        &self,
+        jobs: &J,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let due = self.repo.list_due_purges(now, SWEEP_BATCH_SIZE).await?;
+        for request in &due {
+            let payload = AccountDeletionJobPayload {
+                user_id: request.user_id,
+            };
+            let job = jobs
+                .create(&CreateJobInput {
+                    kind: JobKind::AccountDeletion,
+                    tenant_id: None,
+                    payload: serde_json::to_value(&payload)
+                        .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?,
+                    created_by: None,
+                })
+                .await?;
+            self.repo.set_purge_job(request.user_id, job.id).await?;
+        }
+        Ok(due.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::account_deletion::MockAccountDeletionRepository;
+
+    fn request(user_id: StringUuid, purge_job_id: Option<StringUuid>) -> AccountDeletionRequest {
+        let now = Utc::now();
+        AccountDeletionRequest {
+            user_id,
+            requested_at: now,
+            purge_after: now + Duration::days(14),
+            reminder_sent_at: None,
+            purge_job_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schedule_sets_grace_period() {
+        let mut repo = MockAccountDeletionRepository::new();
+        repo.expect_find().returning(|_| Ok(None));
+        repo.expect_create().times(1).returning(|_| Ok(()));
+        let config = AccountDeletionConfig {
+            grace_period_days: 30,
+            ..Default::default()
+        };
+
+        let now = Utc::now();
+        let request = AccountDeletionService::new(Arc::new(repo), config)
+            .schedule(StringUuid::new_v4(), now)
+            .await
+            .unwrap();
+        assert_eq!(request.purge_after, now + Duration::days(30));
+        assert!(request.is_restorable());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_twice_conflicts() {
+        let mut repo = MockAccountDeletionRepository::new();
+        repo.expect_find()
+            .returning(|user_id| Ok(Some(request(user_id, None))));
+
+        let result = AccountDeletionService::new(Arc::new(repo), Default::default())
+            .schedule(StringUuid::new_v4(), Utc::now())
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_restore_removes_request() {
+        let mut repo = MockAccountDeletionRepository::new();
+        repo.expect_find()
+            .returning(|user_id| Ok(Some(request(user_id, None))));
+        repo.expect_delete().times(1).returning(|_| Ok(true));
+
+        AccountDeletionService::new(Arc::new(repo), Default::default())
+            .restore(StringUuid::new_v4())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_after_purge_queued_conflicts() {
+        let mut repo = MockAccountDeletionRepository::new();
+        repo.expect_find()
+            .returning(|user_id| Ok(Some(request(user_id, Some(StringUuid::new_v4())))));
+        repo.expect_delete().never();
+
+        let result = AccountDeletionService::new(Arc::new(repo), Default::default())
+            .restore(StringUuid::new_v4())
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_restore_without_request_not_found() {
+        let mut repo = MockAccountDeletionRepository::new();
+        repo.expect_find().returning(|_| Ok(None));
+
+        let result = AccountDeletionService::new(Arc::new(repo), Default::default())
+            .restore(StringUuid::new_v4())
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,378 @@
+//! Admin-mediated account recovery
+//!
+//! A user who lost both password and email access opens a request and gets a
+//! claim secret. Once two distinct platform admins approve it, the secret can
+//! be exchanged (once) for a password reset token. A single rejection closes
+//! the request. Only the SHA-256 hash of the claim secret is stored.
+
+use crate::error::{AppError, Result};
+use crate::models::account_recovery::{
+    AccountRecoveryApproval, AccountRecoveryRequest, AccountRecoveryRequestCreated,
+    AccountRecoveryRequestDetail, AccountRecoveryStatus, RECOVERY_REQUEST_TTL_HOURS,
+    REQUIRED_APPROVALS,
+};
+use crate::models::common::StringUuid;
+use crate::repository::AccountRecoveryRepository;
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const CLAIM_SECRET_LENGTH: usize = 32;
+const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz23456789";
+
+const DECISION_APPROVE: &str = "approve";
+const DECISION_REJECT: &str = "reject";
+
+pub struct AccountRecoveryService<R: AccountRecoveryRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: AccountRecoveryRepository> AccountRecoveryService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// Random claim secret handed to the requester
+    pub fn generate_claim_secret() -> String {
+        let mut rng = rand::thread_rng();
+        (0..CLAIM_SECRET_LENGTH)
+            .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+            .collect()
+    }
+
+    fn hash_secret(secret: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(secret.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Open a request for `user_id`. Returns `None` when the user already has
+    /// an open request, so repeated submissions cannot flood the admin queue.
+    pub async fn open_request(
+        &self,
+        user_id: StringUuid,
+        reason: Option<String>,
+        requester_ip: Option<String>,
+    ) -> Result<Option<AccountRecoveryRequestCreated>> {
+        if self.repo.find_open_by_user(user_id).await?.is_some() {
+            return Ok(None);
+        }
+
+        let claim_secret = Self::generate_claim_secret();
+        let now = Utc::now();
+        let request = AccountRecoveryRequest {
+            id: StringUuid::new_v4(),
+            user_id,
+            status: AccountRecoveryStatus::Pending.as_str().to_string(),
+            reason,
+            claim_secret_hash: Self::hash_secret(&claim_secret),
+            requester_ip,
+            created_at: now,
+            expires_at: now + Duration::hours(RECOVERY_REQUEST_TTL_HOURS),
+            decided_at: None,
+            claimed_at: None,
+        };
+        self.repo.create(&request).await?;
+
+        Ok(Some(AccountRecoveryRequestCreated {
+            request_id: request.id,
+            claim_secret,
+            expires_at: request.expires_at,
+        }))
+    }
+
+    pub async fn get(&self, id: StringUuid) -> Result<AccountRecoveryRequestDetail> {
+        let request = self.find(id).await?;
+        let approvals = self.repo.list_approvals(id).await?;
+        Ok(AccountRecoveryRequestDetail { request, approvals })
+    }
+
+    pub async fn list(
+        &self,
+        status: Option<AccountRecoveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<AccountRecoveryRequest>> {
+        self.repo
+            .list(status.map(|s| s.as_str().to_string()), limit.clamp(1, 100))
+            .await
+    }
+
+    /// Record an admin's approval or rejection. The request becomes
+    /// `approved` once [`REQUIRED_APPROVALS`] distinct admins approved it and
+    /// `rejected` on the first rejection.
+    pub async fn decide(
+        &self,
+        id: StringUuid,
+        admin_id: StringUuid,
+        approve: bool,
+        comment: Option<String>,
+    ) -> Result<AccountRecoveryRequestDetail> {
+        let request = self.find(id).await?;
+        if request.user_id == admin_id {
+            return Err(AppError::Forbidden(
+                "Admins cannot decide on their own recovery request".to_string(),
+            ));
+        }
+        ensure_pending(&request)?;
+
+        self.repo
+            .add_approval(&AccountRecoveryApproval {
+                id: StringUuid::new_v4(),
+                request_id: id,
+                admin_id,
+                decision: if approve {
+                    DECISION_APPROVE
+                } else {
+                    DECISION_REJECT
+                }
+                .to_string(),
+                comment,
+                created_at: Utc::now(),
+            })
+            .await?;
+
+        if !approve {
+            self.repo
+                .transition(
+                    id,
+                    AccountRecoveryStatus::Pending.as_str(),
+                    AccountRecoveryStatus::Rejected.as_str(),
+                )
+                .await?;
+        } else {
+            let approvals = self.repo.list_approvals(id).await?;
+            let approvers: HashSet<StringUuid> = approvals
+                .iter()
+                .filter(|a| a.decision == DECISION_APPROVE)
+                .map(|a| a.admin_id)
+                .collect();
+            if approvers.len() >= REQUIRED_APPROVALS {
+                self.repo
+                    .transition(
+                        id,
+                        AccountRecoveryStatus::Pending.as_str(),
+                        AccountRecoveryStatus::Approved.as_str(),
+                    )
+                    .await?;
+            }
+        }
+
+        self.get(id).await
+    }
+
+    /// Exchange the claim secret of an approved request. Succeeds once; the
+    /// caller then issues the password reset token.
+    pub async fn claim(
+        &self,
+        id: StringUuid,
+        claim_secret: &str,
+    ) -> Result<AccountRecoveryRequest> {
+        let invalid =
+            || AppError::Unauthorized("Invalid recovery request or claim secret".to_string());
+        let request = self.repo.find_by_id(id).await?.ok_or_else(invalid)?;
+        if request.claim_secret_hash != Self::hash_secret(claim_secret) {
+            return Err(invalid());
+        }
+        if request.is_expired() {
+            return Err(AppError::Conflict(
+                "Recovery request has expired".to_string(),
+            ));
+        }
+        match request.recovery_status() {
+            Some(AccountRecoveryStatus::Approved) => {}
+            Some(AccountRecoveryStatus::Pending) => {
+                return Err(AppError::Conflict(
+                    "Recovery request is still awaiting admin approval".to_string(),
+                ))
+            }
+            _ => {
+                return Err(AppError::Conflict(
+                    "Recovery request can no longer be claimed".to_string(),
+                ))
+            }
+        }
+
+        let claimed = self
+            .repo
+            .transition(
+                id,
+                AccountRecoveryStatus::Approved.as_str(),
+                AccountRecoveryStatus::Claimed.as_str(),
+            )
+            .await?;
+        if !claimed {
+            return Err(AppError::Conflict(
+                "Recovery request can no longer be claimed".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+
+    async fn find(&self, id: StringUuid) -> Result<AccountRecoveryRequest> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Recovery request {} not found", id)))
+    }
+}
+
+fn ensure_pending(request: &AccountRecoveryRequest) -> Result<()> {
+    if request.recovery_status() != Some(AccountRecoveryStatus::Pending) {
+        return Err(AppError::Conflict(
+            "Recovery request is no longer pending".to_string(),
+        ));
+    }
+    if request.is_expired() {
+        return Err(AppError::Conflict(
+            "Recovery request has expired".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::account_recovery::MockAccountRecoveryRepository;
+    use mockall::predicate::*;
+
+    fn request(status: AccountRecoveryStatus, secret: &str) -> AccountRecoveryRequest {
+        AccountRecoveryRequest {
+            id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            status: status.as_str().to_string(),
+            reason: None,
+            claim_secret_hash: AccountRecoveryService::<MockAccountRecoveryRepository>::hash_secret(
+                secret,
+            ),
+            requester_ip: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now() + Duration::hours(1),
+            decided_at: None,
+            claimed_at: None,
+        }
+    }
+
+    fn approval(request_id: StringUuid, admin_id: StringUuid) -> AccountRecoveryApproval {
+        AccountRecoveryApproval {
+            id: StringUuid::new_v4(),
+            request_id,
+            admin_id,
+            decision: DECISION_APPROVE.to_string(),
+            comment: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_request_skips_when_one_is_open() {
+        let existing = request(AccountRecoveryStatus::Pending, "secret");
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_open_by_user()
+            .returning(move |_| Ok(Some(existing.clone())));
+        repo.expect_create().never();
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        let created = service
+            .open_request(StringUuid::new_v4(), None, None)
+            .await
+            .unwrap();
+        assert!(created.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_second_distinct_approval_approves_request() {
+        let pending = request(AccountRecoveryStatus::Pending, "secret");
+        let id = pending.id;
+        let first_admin = StringUuid::new_v4();
+        let second_admin = StringUuid::new_v4();
+
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_add_approval().returning(|_| Ok(()));
+        repo.expect_list_approvals()
+            .returning(move |_| Ok(vec![approval(id, first_admin), approval(id, second_admin)]));
+        repo.expect_transition()
+            .with(eq(id), eq("pending"), eq("approved"))
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        let detail = service.decide(id, second_admin, true, None).await.unwrap();
+        assert_eq!(detail.approvals.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_single_approval_keeps_request_pending() {
+        let pending = request(AccountRecoveryStatus::Pending, "secret");
+        let id = pending.id;
+        let admin = StringUuid::new_v4();
+
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_add_approval().returning(|_| Ok(()));
+        repo.expect_list_approvals()
+            .returning(move |_| Ok(vec![approval(id, admin)]));
+        repo.expect_transition().never();
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        service.decide(id, admin, true, None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_user_cannot_decide_own_request() {
+        let pending = request(AccountRecoveryStatus::Pending, "secret");
+        let (id, user_id) = (pending.id, pending.user_id);
+
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_add_approval().never();
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        let result = service.decide(id, user_id, true, None).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_claim_requires_secret_and_approval() {
+        let pending = request(AccountRecoveryStatus::Pending, "secret");
+        let id = pending.id;
+
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_transition().never();
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        assert!(matches!(
+            service.claim(id, "wrong").await,
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            service.claim(id, "secret").await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_claim_approved_request_once() {
+        let approved = request(AccountRecoveryStatus::Approved, "secret");
+        let id = approved.id;
+
+        let mut repo = MockAccountRecoveryRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(approved.clone())));
+        repo.expect_transition()
+            .with(eq(id), eq("approved"), eq("claimed"))
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+
+        let service = AccountRecoveryService::new(Arc::new(repo));
+        assert!(service.claim(id, "secret").await.is_ok());
+    }
+}
@@ -0,0 +1,153 @@
+//! Attribute release service — per-service allowlist of released user claims
+
+use crate::error::{AppError, Result};
+use crate::models::attribute_release::{AttributeReleasePolicy, SetAttributeReleasePolicyInput};
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::is_standard_claim;
+use crate::repository::attribute_release::AttributeReleaseRepositoryImpl;
+use crate::repository::AttributeReleaseRepository;
+use serde_json::{Map, Value};
+use sqlx::MySqlPool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct AttributeReleaseService<R: AttributeReleaseRepository> {
+    repo: Arc<R>,
+}
+
+impl AttributeReleaseService<AttributeReleaseRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(AttributeReleaseRepositoryImpl::new(pool)))
+    }
+}
+
+impl<R: AttributeReleaseRepository> AttributeReleaseService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// Policy of a service; services without one release nothing
+    pub async fn get(&self, service_id: StringUuid) -> Result<AttributeReleasePolicy> {
+        Ok(self
+            .repo
+            .find_by_service(service_id)
+            .await?
+            .unwrap_or_else(|| AttributeReleasePolicy::deny_all(service_id)))
+    }
+
+    /// Replace the allowlist of a service. Every attribute must be a standard
+    /// OIDC claim or one of the profile attributes the owning tenant's custom
+    /// scopes declare.
+    pub async fn set(
+        &self,
+        service_id: StringUuid,
+        input: SetAttributeReleasePolicyInput,
+        custom: &BTreeMap<String, Vec<String>>,
+    ) -> Result<AttributeReleasePolicy> {
+        input.validate()?;
+        if let Some(unknown) = input.released_attributes.iter().find(|name| {
+            !is_standard_claim(name) && !custom.values().any(|attrs| attrs.contains(name))
+        }) {
+            return Err(AppError::Validation(format!(
+                "Attribute '{}' is neither a standard claim nor declared by a custom scope of the tenant",
+                unknown
+            )));
+        }
+        self.repo
+            .upsert(service_id, &input.released_attributes)
+            .await
+    }
+
+    /// Drop the claims the service's policy does not release
+    pub async fn release(
+        &self,
+        service_id: StringUuid,
+        claims: Map<String, Value>,
+    ) -> Result<Map<String, Value>> {
+        if claims.is_empty() {
+            return Ok(claims);
+        }
+        Ok(self.get(service_id).await?.apply(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::attribute_release::MockAttributeReleaseRepository;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn policy(service_id: StringUuid, attributes: &[&str]) -> AttributeReleasePolicy {
+        AttributeReleasePolicy {
+            service_id,
+            released_attributes: attributes.iter().map(|s| s.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_release_denies_by_default() {
+        let mut repo = MockAttributeReleaseRepository::new();
+        repo.expect_find_by_service().returning(|_| Ok(None));
+        let service = AttributeReleaseService::new(Arc::new(repo));
+
+        let claims = json!({ "email": "a@example.com" })
+            .as_object()
+            .unwrap()
+            .clone();
+        let released = service.release(StringUuid::new_v4(), claims).await.unwrap();
+        assert!(released.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_release_filters_by_allowlist() {
+        let mut repo = MockAttributeReleaseRepository::new();
+        repo.expect_find_by_service()
+            .returning(|id| Ok(Some(policy(id, &["email", "department"]))));
+        let service = AttributeReleaseService::new(Arc::new(repo));
+
+        let claims = json!({ "email": "a@example.com", "name": "A", "department": "ops" })
+            .as_object()
+            .unwrap()
+            .clone();
+        let released = service.release(StringUuid::new_v4(), claims).await.unwrap();
+        assert_eq!(released.len(), 2);
+        assert!(!released.contains_key("name"));
+    }
+
+    #[tokio::test]
+    async fn test_set_rejects_undeclared_attribute() {
+        let mut repo = MockAttributeReleaseRepository::new();
+        repo.expect_upsert().times(1).returning(|id, attrs| {
+            let attrs: Vec<&str> = attrs.iter().map(String::as_str).collect();
+            Ok(policy(id, &attrs))
+        });
+        let service = AttributeReleaseService::new(Arc::new(repo));
+        let custom = BTreeMap::from([(
+            "hr".to_string(),
+            vec!["department".to_string(), "cost_center".to_string()],
+        )]);
+        let input = |names: &[&str]| SetAttributeReleasePolicyInput {
+            released_attributes: names.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let err = service
+            .set(StringUuid::new_v4(), input(&["email", "salary"]), &custom)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        let stored = service
+            .set(
+                StringUuid::new_v4(),
+                input(&["email", "department"]),
+                &custom,
+            )
+            .await
+            .unwrap();
+        assert_eq!(stored.released_attributes, vec!["email", "department"]);
+    }
+}
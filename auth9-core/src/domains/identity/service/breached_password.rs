@@ -43,17 +43,28 @@ impl BreachedPasswordService {
     /// Uses the k-Anonymity model: only a 5-character SHA-1 prefix is sent to HIBP.
     /// On any error (timeout, network, parse), returns not-breached (fail-open).
     pub async fn check_password(&self, password: &str) -> BreachCheckResult {
-        if !self.enabled {
+        let mut hasher = Sha1::new();
+        hasher.update(password.as_bytes());
+        let hash = format!("{:X}", hasher.finalize());
+        self.check_sha1(&hash).await
+    }
+
+    /// Whether checks are performed at all; disabled services report every
+    /// password as not breached.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Check an upper-case hex SHA-1 password digest, e.g. a legacy unsalted
+    /// hash, without knowing the password. Fails open like `check_password`.
+    pub async fn check_sha1(&self, hash: &str) -> BreachCheckResult {
+        if !self.enabled || hash.len() != 40 {
             return BreachCheckResult {
                 is_breached: false,
                 breach_count: 0,
             };
         }
 
-        let mut hasher = Sha1::new();
-        hasher.update(password.as_bytes());
-        let hash = format!("{:X}", hasher.finalize());
-
         let prefix = &hash[..5];
         let suffix = &hash[5..];
 
@@ -154,6 +165,26 @@ mod tests {
         assert_eq!(result.breach_count, 3861493);
     }
 
+    #[tokio::test]
+    async fn test_check_sha1_digest_without_password() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex("/range/5BAA6"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("1E4C9B93F3F0682250B6CF8331B7EE68FD8:3861493\r\n"), // pragma: allowlist secret
+            )
+            .mount(&mock_server)
+            .await;
+
+        let service = service_with_url(&mock_server.uri());
+        let result = service.check_sha1(&sha1_hex("password")).await; // pragma: allowlist secret
+        assert!(result.is_breached);
+        assert_eq!(result.breach_count, 3861493);
+        assert!(!service.check_sha1("not-a-digest").await.is_breached);
+    }
+
     #[tokio::test]
     async fn test_password_not_breached() {
         let mock_server = MockServer::start().await;
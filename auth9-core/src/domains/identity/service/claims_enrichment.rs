@@ -0,0 +1,468 @@
+//! Token claims enrichment plugins
+//!
+//! Plugins compute extra claims from external systems while a tenant access
+//! token is issued. They are compiled into the binary and registered in
+//! [`ClaimsEnricherRegistry::builtin`]; services opt in per plugin with their
+//! own settings, timeout and failure policy. Returned claims go through the
+//! same reserved-key filter and namespace prefix as Action claims.
+
+use crate::error::{AppError, Result};
+use crate::jwt::claims::sanitize_action_claims;
+use crate::models::claims_enrichment::{
+    EnrichmentContext, EnrichmentFailurePolicy, ServiceClaimsEnricher, UpsertClaimsEnricherInput,
+    DEFAULT_ENRICHMENT_TIMEOUT_MS,
+};
+use crate::models::common::{validate_url_no_ssrf, StringUuid};
+use crate::repository::claims_enricher::{ClaimsEnricherRepository, ClaimsEnricherRepositoryImpl};
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::MySqlPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use validator::Validate;
+
+pub type Claims = HashMap<String, Value>;
+
+/// A compiled-in source of extra token claims
+#[async_trait]
+pub trait ClaimsEnricher: Send + Sync {
+    /// Registry name, referenced by service configurations
+    fn name(&self) -> &'static str;
+
+    /// Reject unusable settings when a service enables the plugin
+    fn validate_settings(&self, _settings: &Value) -> Result<()> {
+        Ok(())
+    }
+
+    async fn enrich(&self, context: &EnrichmentContext, settings: &Value) -> Result<Claims>;
+}
+
+/// Adds fixed claims from the service settings: `{"claims": {"plan": "gold"}}`
+pub struct StaticClaimsEnricher;
+
+#[async_trait]
+impl ClaimsEnricher for StaticClaimsEnricher {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    fn validate_settings(&self, settings: &Value) -> Result<()> {
+        match settings.get("claims") {
+            Some(Value::Object(_)) => Ok(()),
+            _ => Err(AppError::Validation(
+                "static enricher requires a 'claims' object".to_string(),
+            )),
+        }
+    }
+
+    async fn enrich(&self, _context: &EnrichmentContext, settings: &Value) -> Result<Claims> {
+        Ok(settings
+            .get("claims")
+            .and_then(Value::as_object)
+            .map(|claims| claims.clone().into_iter().collect())
+            .unwrap_or_default())
+    }
+}
+
+/// POSTs the [`EnrichmentContext`] as JSON to `settings.url` and uses the
+/// JSON object in the response body as claims
+pub struct HttpClaimsEnricher {
+    http_client: reqwest::Client,
+}
+
+impl HttpClaimsEnricher {
+    pub fn new() -> Self {
+        Self {
+            http_client: reqwest::Client::builder()
+                .user_agent("Auth9-Core")
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn url(settings: &Value) -> Option<&str> {
+        settings.get("url").and_then(Value::as_str)
+    }
+}
+
+impl Default for HttpClaimsEnricher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClaimsEnricher for HttpClaimsEnricher {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn validate_settings(&self, settings: &Value) -> Result<()> {
+        let url = Self::url(settings).ok_or_else(|| {
+            AppError::Validation("http enricher requires a 'url' setting".to_string())
+        })?;
+        validate_url_no_ssrf(url).map_err(|e| {
+            AppError::Validation(format!(
+                "Invalid enricher url: {}",
+                e.message.as_deref().unwrap_or(&e.code)
+            ))
+        })
+    }
+
+    async fn enrich(&self, context: &EnrichmentContext, settings: &Value) -> Result<Claims> {
+        let url = Self::url(settings).ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("http enricher has no url configured"))
+        })?;
+        let response = self
+            .http_client
+            .post(url)
+            .json(context)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))?
+            .error_for_status()
+            .map_err(|e| AppError::Internal(e.into()))?;
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))?;
+        match body {
+            Value::Object(claims) => Ok(claims.into_iter().collect()),
+            _ => Err(AppError::Internal(anyhow::anyhow!(
+                "http enricher response is not a JSON object"
+            ))),
+        }
+    }
+}
+
+/// Plugins available to services, keyed by name
+pub struct ClaimsEnricherRegistry {
+    plugins: BTreeMap<&'static str, Arc<dyn ClaimsEnricher>>,
+}
+
+lazy_static::lazy_static! {
+    static ref BUILTIN_REGISTRY: Arc<ClaimsEnricherRegistry> =
+        Arc::new(ClaimsEnricherRegistry::builtin());
+}
+
+impl ClaimsEnricherRegistry {
+    pub fn empty() -> Self {
+        Self {
+            plugins: BTreeMap::new(),
+        }
+    }
+
+    /// Registry of the plugins shipped with auth9-core. Bespoke plugins are
+    /// added here.
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(StaticClaimsEnricher));
+        registry.register(Arc::new(HttpClaimsEnricher::new()));
+        registry
+    }
+
+    /// Process-wide instance of [`Self::builtin`]
+    pub fn shared() -> Arc<Self> {
+        BUILTIN_REGISTRY.clone()
+    }
+
+    pub fn register(&mut self, plugin: Arc<dyn ClaimsEnricher>) {
+        self.plugins.insert(plugin.name(), plugin);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ClaimsEnricher>> {
+        self.plugins.get(name).cloned()
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.plugins.keys().copied().collect()
+    }
+}
+
+/// Merge enrichment and Action claims; Action claims win on conflicts since
+/// they are the tenant's own customization.
+pub fn merge_claims(enriched: Option<Claims>, actions: Option<Claims>) -> Option<Claims> {
+    match (enriched, actions) {
+        (Some(mut enriched), Some(actions)) => {
+            enriched.extend(actions);
+            Some(enriched)
+        }
+        (enriched, actions) => enriched.or(actions),
+    }
+}
+
+pub struct ClaimsEnrichmentService<R: ClaimsEnricherRepository> {
+    repo: Arc<R>,
+    registry: Arc<ClaimsEnricherRegistry>,
+}
+
+impl ClaimsEnrichmentService<ClaimsEnricherRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(
+            Arc::new(ClaimsEnricherRepositoryImpl::new(pool)),
+            ClaimsEnricherRegistry::shared(),
+        )
+    }
+}
+
+impl<R: ClaimsEnricherRepository> ClaimsEnrichmentService<R> {
+    pub fn new(repo: Arc<R>, registry: Arc<ClaimsEnricherRegistry>) -> Self {
+        Self { repo, registry }
+    }
+
+    pub fn available_plugins(&self) -> Vec<&'static str> {
+        self.registry.names()
+    }
+
+    pub async fn list(&self, service_id: StringUuid) -> Result<Vec<ServiceClaimsEnricher>> {
+        self.repo.list_by_service(service_id).await
+    }
+
+    /// Enable a plugin for a service or update its configuration
+    pub async fn upsert(
+        &self,
+        service_id: StringUuid,
+        plugin: &str,
+        input: UpsertClaimsEnricherInput,
+    ) -> Result<ServiceClaimsEnricher> {
+        input.validate()?;
+        let enricher = self.registry.get(plugin).ok_or_else(|| {
+            AppError::Validation(format!(
+                "Unknown claims enrichment plugin '{}' (available: {})",
+                plugin,
+                self.registry.names().join(", ")
+            ))
+        })?;
+        let settings = input
+            .settings
+            .unwrap_or_else(|| Value::Object(Default::default()));
+        enricher.validate_settings(&settings)?;
+
+        self.repo
+            .upsert(
+                service_id,
+                plugin,
+                &settings,
+                input.timeout_ms.unwrap_or(DEFAULT_ENRICHMENT_TIMEOUT_MS),
+                input.failure_policy.unwrap_or_default(),
+                input.enabled.unwrap_or(true),
+            )
+            .await
+    }
+
+    pub async fn delete(&self, service_id: StringUuid, plugin: &str) -> Result<()> {
+        self.repo.delete(service_id, plugin).await
+    }
+
+    /// Run the service's enabled plugins in configuration order.
+    ///
+    /// A failing or slow plugin is skipped unless its failure policy is
+    /// `deny`, in which case token issuance is refused.
+    pub async fn enrich(&self, context: &EnrichmentContext) -> Result<Option<Claims>> {
+        let configs = self.repo.list_by_service(context.service_id).await?;
+        let mut claims = Claims::new();
+
+        for config in configs.into_iter().filter(|c| c.enabled) {
+            let outcome = match self.registry.get(&config.plugin) {
+                Some(plugin) => tokio::time::timeout(
+                    Duration::from_millis(u64::from(config.timeout_ms)),
+                    plugin.enrich(context, &config.settings),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(AppError::Internal(anyhow::anyhow!(
+                        "timed out after {}ms",
+                        config.timeout_ms
+                    )))
+                }),
+                None => Err(AppError::Internal(anyhow::anyhow!(
+                    "plugin is not compiled into this build"
+                ))),
+            };
+
+            let result = if outcome.is_ok() {
+                "success"
+            } else {
+                "failure"
+            };
+            metrics::counter!(
+                "auth9_claims_enrichment_total",
+                "plugin" => config.plugin.clone(),
+                "result" => result
+            )
+            .increment(1);
+
+            match outcome {
+                Ok(extra) => claims.extend(extra),
+                Err(e) => {
+                    tracing::warn!(
+                        plugin = %config.plugin,
+                        service_id = %context.service_id,
+                        policy = %config.failure_policy,
+                        error = %e,
+                        "Claims enrichment failed"
+                    );
+                    if config.failure_policy == EnrichmentFailurePolicy::Deny {
+                        return Err(AppError::Forbidden(format!(
+                            "Token issuance denied: claims enrichment '{}' failed",
+                            config.plugin
+                        )));
+                    }
+                }
+            }
+        }
+
+        Ok(sanitize_action_claims(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::claims_enricher::MockClaimsEnricherRepository;
+    use chrono::Utc;
+    use serde_json::json;
+
+    struct SlowEnricher;
+
+    #[async_trait]
+    impl ClaimsEnricher for SlowEnricher {
+        fn name(&self) -> &'static str {
+            "slow"
+        }
+
+        async fn enrich(&self, _context: &EnrichmentContext, _settings: &Value) -> Result<Claims> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(Claims::new())
+        }
+    }
+
+    fn context() -> EnrichmentContext {
+        EnrichmentContext {
+            user_id: StringUuid::new_v4(),
+            email: "user@example.com".to_string(),
+            tenant_id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            client_id: "app".to_string(),
+            roles: vec![],
+            permissions: vec![],
+        }
+    }
+
+    fn config(
+        plugin: &str,
+        settings: Value,
+        failure_policy: EnrichmentFailurePolicy,
+    ) -> ServiceClaimsEnricher {
+        ServiceClaimsEnricher {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            plugin: plugin.to_string(),
+            settings,
+            timeout_ms: 20,
+            failure_policy,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn service_with(
+        configs: Vec<ServiceClaimsEnricher>,
+    ) -> ClaimsEnrichmentService<MockClaimsEnricherRepository> {
+        let mut mock = MockClaimsEnricherRepository::new();
+        mock.expect_list_by_service()
+            .returning(move |_| Ok(configs.clone()));
+        let mut registry = ClaimsEnricherRegistry::builtin();
+        registry.register(Arc::new(SlowEnricher));
+        ClaimsEnrichmentService::new(Arc::new(mock), Arc::new(registry))
+    }
+
+    #[tokio::test]
+    async fn test_enrich_namespaces_and_filters_reserved_claims() {
+        let service = service_with(vec![config(
+            "static",
+            json!({"claims": {"plan": "gold", "sub": "spoofed"}}),
+            EnrichmentFailurePolicy::Skip,
+        )]);
+
+        let claims = service.enrich(&context()).await.unwrap().unwrap();
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims["https://auth9.dev/plan"], json!("gold"));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_skips_timed_out_plugin() {
+        let service = service_with(vec![
+            config("slow", json!({}), EnrichmentFailurePolicy::Skip),
+            config(
+                "static",
+                json!({"claims": {"plan": "gold"}}),
+                EnrichmentFailurePolicy::Skip,
+            ),
+        ]);
+
+        let claims = service.enrich(&context()).await.unwrap().unwrap();
+        assert!(claims.contains_key("https://auth9.dev/plan"));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_deny_policy_fails_issuance() {
+        let service = service_with(vec![config(
+            "slow",
+            json!({}),
+            EnrichmentFailurePolicy::Deny,
+        )]);
+
+        let result = service.enrich(&context()).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_unknown_plugin_follows_policy() {
+        let service = service_with(vec![config(
+            "removed",
+            json!({}),
+            EnrichmentFailurePolicy::Skip,
+        )]);
+
+        assert!(service.enrich(&context()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_rejects_unknown_plugin_and_bad_settings() {
+        let service = service_with(vec![]);
+        let service_id = StringUuid::new_v4();
+
+        let result = service
+            .upsert(service_id, "ldap", UpsertClaimsEnricherInput::default())
+            .await;
+        assert!(
+            matches!(result, Err(AppError::Validation(msg)) if msg.contains("http, slow, static"))
+        );
+
+        let result = service
+            .upsert(
+                service_id,
+                "http",
+                UpsertClaimsEnricherInput {
+                    settings: Some(json!({"url": "https://169.254.169.254/claims"})),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[test]
+    fn test_merge_claims_prefers_action_claims() {
+        let enriched = Claims::from([("a".to_string(), json!(1)), ("b".to_string(), json!(1))]);
+        let actions = Claims::from([("b".to_string(), json!(2))]);
+
+        let merged = merge_claims(Some(enriched), Some(actions)).unwrap();
+        assert_eq!(merged["a"], json!(1));
+        assert_eq!(merged["b"], json!(2));
+        assert!(merge_claims(None, None).is_none());
+    }
+}
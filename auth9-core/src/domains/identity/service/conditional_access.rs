@@ -0,0 +1,301 @@
+//! Conditional access service — tenant rules evaluated at token issuance
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::conditional_access::{
+    evaluate_policies, ConditionalAccessContext, ConditionalAccessDecision,
+    ConditionalAccessPolicy, CreateConditionalAccessPolicyInput, SimulateConditionalAccessInput,
+    UpdateConditionalAccessPolicyInput, MAX_POLICIES_PER_TENANT,
+};
+use crate::repository::conditional_access::{
+    ConditionalAccessPolicyRecord, ConditionalAccessRepository,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use validator::Validate;
+
+/// Cache key prefix marking sessions that completed a second factor
+pub const SESSION_MFA_KEY_PREFIX: &str = "auth9:session_mfa";
+
+/// Cache key recording that `session_id` was established with MFA
+pub fn session_mfa_key(session_id: &str) -> String {
+    format!("{}:{}", SESSION_MFA_KEY_PREFIX, session_id)
+}
+
+pub struct ConditionalAccessService<R: ConditionalAccessRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: ConditionalAccessRepository> ConditionalAccessService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list(&self, tenant_id: StringUuid) -> Result<Vec<ConditionalAccessPolicy>> {
+        self.repo.list_by_tenant(tenant_id).await
+    }
+
+    pub async fn get(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<ConditionalAccessPolicy> {
+        self.repo.find(tenant_id, id).await?.ok_or_else(|| {
+            AppError::NotFound(format!("Conditional access policy {} not found", id))
+        })
+    }
+
+    pub async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: CreateConditionalAccessPolicyInput,
+    ) -> Result<ConditionalAccessPolicy> {
+        input.validate()?;
+
+        if self.repo.count_by_tenant(tenant_id).await? >= MAX_POLICIES_PER_TENANT as i64 {
+            return Err(AppError::BadRequest(format!(
+                "A tenant can have at most {} conditional access policies",
+                MAX_POLICIES_PER_TENANT
+            )));
+        }
+
+        let record = ConditionalAccessPolicyRecord {
+            name: input.name,
+            description: input.description,
+            enabled: input.enabled,
+            priority: input.priority,
+            conditions: input.conditions,
+            outcome: input.outcome,
+        };
+        self.repo.create(tenant_id, &record).await
+    }
+
+    pub async fn update(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        input: UpdateConditionalAccessPolicyInput,
+    ) -> Result<ConditionalAccessPolicy> {
+        input.validate()?;
+        let existing = self.get(tenant_id, id).await?;
+
+        let record = ConditionalAccessPolicyRecord {
+            name: input.name.unwrap_or(existing.name),
+            description: input.description.or(existing.description),
+            enabled: input.enabled.unwrap_or(existing.enabled),
+            priority: input.priority.unwrap_or(existing.priority),
+            conditions: input.conditions.unwrap_or(existing.conditions),
+            outcome: input.outcome.unwrap_or(existing.outcome),
+        };
+        self.repo.update(tenant_id, id, &record).await
+    }
+
+    pub async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()> {
+        self.repo.delete(tenant_id, id).await
+    }
+
+    /// Evaluate the tenant's enabled policies for a token request
+    pub async fn evaluate(
+        &self,
+        tenant_id: StringUuid,
+        ctx: &ConditionalAccessContext,
+    ) -> Result<ConditionalAccessDecision> {
+        let policies: Vec<_> = self
+            .repo
+            .list_by_tenant(tenant_id)
+            .await?
+            .into_iter()
+            .filter(|p| p.enabled)
+            .collect();
+        Ok(evaluate_policies(&policies, ctx))
+    }
+
+    /// Dry-run the tenant's policies against admin-supplied request attributes
+    pub async fn simulate(
+        &self,
+        tenant_id: StringUuid,
+        input: SimulateConditionalAccessInput,
+    ) -> Result<ConditionalAccessDecision> {
+        input.validate()?;
+
+        let mut policies = self.repo.list_by_tenant(tenant_id).await?;
+        if input.include_disabled {
+            for policy in &mut policies {
+                policy.enabled = true;
+            }
+        }
+
+        let ctx = ConditionalAccessContext {
+            client_id: input.client_id,
+            ip_address: input.ip_address,
+            device_trusted: input.device_trusted,
+            at: input.at.unwrap_or_else(Utc::now),
+        };
+        Ok(evaluate_policies(&policies, &ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::conditional_access::{
+        ConditionalAccessConditions, ConditionalAccessOutcome,
+    };
+    use crate::repository::conditional_access::MockConditionalAccessRepository;
+
+    fn policy(
+        tenant_id: StringUuid,
+        enabled: bool,
+        outcome: ConditionalAccessOutcome,
+    ) -> ConditionalAccessPolicy {
+        ConditionalAccessPolicy {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            name: "policy".to_string(),
+            description: None,
+            enabled,
+            priority: 0,
+            conditions: ConditionalAccessConditions::default(),
+            outcome,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn create_input() -> CreateConditionalAccessPolicyInput {
+        CreateConditionalAccessPolicyInput {
+            name: "Block legacy".to_string(),
+            description: None,
+            enabled: true,
+            priority: 0,
+            conditions: ConditionalAccessConditions::default(),
+            outcome: ConditionalAccessOutcome::Block,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_enforces_tenant_limit() {
+        let mut repo = MockConditionalAccessRepository::new();
+        repo.expect_count_by_tenant()
+            .returning(|_| Ok(MAX_POLICIES_PER_TENANT as i64));
+        repo.expect_create().never();
+
+        let service = ConditionalAccessService::new(Arc::new(repo));
+        let err = service
+            .create(StringUuid::new_v4(), create_input())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_ignores_disabled_policies() {
+        let tenant_id = StringUuid::new_v4();
+        let mut repo = MockConditionalAccessRepository::new();
+        repo.expect_list_by_tenant().returning(move |_| {
+            Ok(vec![policy(
+                tenant_id,
+                false,
+                ConditionalAccessOutcome::Block,
+            )])
+        });
+
+        let service = ConditionalAccessService::new(Arc::new(repo));
+        let decision = service
+            .evaluate(
+                tenant_id,
+                &ConditionalAccessContext {
+                    client_id: "app".to_string(),
+                    ip_address: None,
+                    device_trusted: false,
+                    at: Utc::now(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Allow);
+        assert!(decision.trace.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simulate_can_include_disabled_policies() {
+        let tenant_id = StringUuid::new_v4();
+        let mut repo = MockConditionalAccessRepository::new();
+        repo.expect_list_by_tenant().returning(move |_| {
+            Ok(vec![policy(
+                tenant_id,
+                false,
+                ConditionalAccessOutcome::Block,
+            )])
+        });
+
+        let service = ConditionalAccessService::new(Arc::new(repo));
+        let input = SimulateConditionalAccessInput {
+            client_id: "app".to_string(),
+            ip_address: Some("203.0.113.1".to_string()),
+            device_trusted: false,
+            at: None,
+            include_disabled: false,
+        };
+        let decision = service.simulate(tenant_id, input.clone()).await.unwrap();
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Allow);
+        assert_eq!(decision.trace.len(), 1);
+
+        let decision = service
+            .simulate(
+                tenant_id,
+                SimulateConditionalAccessInput {
+                    include_disabled: true,
+                    ..input
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Block);
+    }
+
+    #[tokio::test]
+    async fn test_update_merges_fields() {
+        let tenant_id = StringUuid::new_v4();
+        let existing = policy(tenant_id, true, ConditionalAccessOutcome::Allow);
+        let existing_id = existing.id;
+        let mut repo = MockConditionalAccessRepository::new();
+        let found = existing.clone();
+        repo.expect_find()
+            .returning(move |_, _| Ok(Some(found.clone())));
+        repo.expect_update()
+            .withf(|_, _, record| {
+                record.outcome == ConditionalAccessOutcome::RequireMfa
+                    && record.name == "policy"
+                    && record.enabled
+            })
+            .returning(move |_, _, record| {
+                let mut updated = existing.clone();
+                updated.outcome = record.outcome;
+                Ok(updated)
+            });
+
+        let service = ConditionalAccessService::new(Arc::new(repo));
+        let updated = service
+            .update(
+                tenant_id,
+                existing_id,
+                UpdateConditionalAccessPolicyInput {
+                    name: None,
+                    description: None,
+                    enabled: None,
+                    priority: None,
+                    conditions: None,
+                    outcome: Some(ConditionalAccessOutcome::RequireMfa),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.outcome, ConditionalAccessOutcome::RequireMfa);
+    }
+
+    #[test]
+    fn test_session_mfa_key() {
+        assert_eq!(session_mfa_key("abc"), "auth9:session_mfa:abc");
+    }
+}
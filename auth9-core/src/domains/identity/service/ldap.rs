@@ -5,7 +5,9 @@ use crate::error::{AppError, Result};
 #[cfg(test)]
 use crate::models::ldap::SearchScope;
 use crate::models::ldap::{escape_ldap_search_filter, LdapConfig, LdapUserProfile};
+use crate::models::ldap_sync::LdapSyncBatch;
 use async_trait::async_trait;
+use ldap3::adapters::{Adapter, EntriesOnly, PagedResults};
 use ldap3::{drive, LdapConnAsync, LdapConnSettings, Scope};
 use std::time::Duration;
 
@@ -29,8 +31,20 @@ pub trait LdapAuthenticator: Send + Sync {
         query: &str,
         limit: u32,
     ) -> Result<Vec<LdapUserProfile>>;
+
+    /// Read directory users for a sync: every user matching the user search
+    /// filter, or only those changed after `cursor` when one is given
+    async fn list_sync_users(
+        &self,
+        config: &LdapConfig,
+        cursor: Option<String>,
+    ) -> Result<LdapSyncBatch>;
 }
 
+/// Page size of sync searches; stays below the Active Directory
+/// `MaxPageSize` default of 1000
+const SYNC_PAGE_SIZE: i32 = 500;
+
 // ── Default Implementation ──
 
 pub struct DefaultLdapAuthenticator;
@@ -78,6 +92,50 @@ impl DefaultLdapAuthenticator {
         config.user_search_filter.replace("{username}", &escaped)
     }
 
+    /// Filter of a sync search: the user search filter matching any username,
+    /// narrowed to entries changed after the cursor. Active Directory cursors
+    /// are USNs, other servers use `modifyTimestamp` in generalized time.
+    fn build_sync_filter(config: &LdapConfig, cursor: Option<&str>) -> String {
+        let base = config.user_search_filter.replace("{username}", "*");
+        match cursor {
+            Some(cursor) if config.is_active_directory => match cursor.parse::<u64>() {
+                Ok(usn) => format!("(&{}(uSNChanged>={}))", base, usn + 1),
+                Err(_) => base,
+            },
+            Some(cursor) => format!(
+                "(&{}(modifyTimestamp>={}))",
+                base,
+                escape_ldap_search_filter(cursor)
+            ),
+            None => base,
+        }
+    }
+
+    /// Highest committed USN of the Active Directory server, read before the
+    /// search so changes made while it runs are picked up next time
+    async fn highest_committed_usn(ldap: &mut ldap3::Ldap) -> Result<Option<String>> {
+        let (results, _) = ldap
+            .search(
+                "",
+                Scope::Base,
+                "(objectClass=*)",
+                vec!["highestCommittedUSN"],
+            )
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("LDAP rootDSE search error: {}", e)))?
+            .success()
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("LDAP rootDSE search failed: {}", e))
+            })?;
+        Ok(results.into_iter().next().and_then(|entry| {
+            ldap3::SearchEntry::construct(entry)
+                .attrs
+                .get("highestCommittedUSN")
+                .and_then(|vals| vals.first())
+                .cloned()
+        }))
+    }
+
     fn build_search_attrs(config: &LdapConfig) -> Vec<&str> {
         let mut attrs = vec![
             "dn",
@@ -275,6 +333,71 @@ impl LdapAuthenticator for DefaultLdapAuthenticator {
 
         Ok(profiles)
     }
+
+    async fn list_sync_users(
+        &self,
+        config: &LdapConfig,
+        cursor: Option<String>,
+    ) -> Result<LdapSyncBatch> {
+        let mut ldap = Self::connect(config).await?;
+        Self::service_bind(&mut ldap, config).await?;
+
+        let mut next_cursor = if config.is_active_directory {
+            Self::highest_committed_usn(&mut ldap).await?
+        } else {
+            None
+        };
+
+        let filter = Self::build_sync_filter(config, cursor.as_deref());
+        let mut attrs = Self::build_search_attrs(config);
+        if !config.is_active_directory {
+            attrs.push("modifyTimestamp");
+        }
+        let scope = config.user_search_scope.to_ldap3_scope();
+
+        let adapters: Vec<Box<dyn Adapter<_, _>>> = vec![
+            Box::new(EntriesOnly::new()),
+            Box::new(PagedResults::new(SYNC_PAGE_SIZE)),
+        ];
+        let mut users = Vec::new();
+        {
+            let mut search = ldap
+                .streaming_search_with(adapters, &config.base_dn, scope, &filter, attrs)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("LDAP search error: {}", e)))?;
+            while let Some(entry) = search
+                .next()
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("LDAP search error: {}", e)))?
+            {
+                let entry = ldap3::SearchEntry::construct(entry);
+                if !config.is_active_directory {
+                    // Generalized time strings of one server compare in time order
+                    let modified = entry
+                        .attrs
+                        .get("modifyTimestamp")
+                        .and_then(|vals| vals.first());
+                    if let Some(modified) = modified {
+                        if next_cursor.as_ref().is_none_or(|c| modified > c) {
+                            next_cursor = Some(modified.clone());
+                        }
+                    }
+                }
+                users.push(Self::extract_profile(config, &entry));
+            }
+            search
+                .finish()
+                .await
+                .success()
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("LDAP search failed: {}", e)))?;
+        }
+        let _ = ldap.unbind().await;
+
+        Ok(LdapSyncBatch {
+            users,
+            cursor: next_cursor.or(cursor),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -334,4 +457,33 @@ mod tests {
             "(uid=\\29\\28cn=\\2a\\29)"
         );
     }
+
+    #[test]
+    fn build_sync_filter_narrows_to_changes_after_cursor() {
+        let mut raw: std::collections::HashMap<String, String> = [
+            ("serverUrl", "ldaps://localhost:636"),
+            ("bindDn", "cn=admin"),
+            ("bindPassword", "pass"), // pragma: allowlist secret
+            ("baseDn", "dc=test"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let config = crate::models::ldap::parse_ldap_config(&raw).unwrap();
+        assert_eq!(
+            DefaultLdapAuthenticator::build_sync_filter(&config, None),
+            "(uid=*)"
+        );
+        assert_eq!(
+            DefaultLdapAuthenticator::build_sync_filter(&config, Some("20260101000000Z")),
+            "(&(uid=*)(modifyTimestamp>=20260101000000Z))"
+        );
+
+        raw.insert("isActiveDirectory".to_string(), "true".to_string());
+        let ad_config = crate::models::ldap::parse_ldap_config(&raw).unwrap();
+        assert_eq!(
+            DefaultLdapAuthenticator::build_sync_filter(&ad_config, Some("12345")),
+            "(&(sAMAccountName=*)(uSNChanged>=12346))"
+        );
+    }
 }
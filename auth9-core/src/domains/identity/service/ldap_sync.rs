@@ -0,0 +1,807 @@
+//! LDAP/Active Directory directory sync
+//!
+//! Imports the users of an LDAP connector into its tenant and keeps the roles
+//! granted through the connector's group-role mappings in line with their
+//! directory groups. Roles that no mapping of the connector grants are never
+//! touched. Synced users are linked to their DN like users created at LDAP
+//! login, so either path finds the same account.
+
+use crate::domains::identity::service::ldap::LdapAuthenticator;
+use crate::error::{AppError, Result};
+use crate::models::common::{StringUuid, TenantId, UserId};
+use crate::models::ldap::{parse_ldap_config, LdapGroupRoleMapping, LdapUserProfile};
+use crate::models::ldap_sync::{
+    LdapSyncAction, LdapSyncChange, LdapSyncConflictPolicy, LdapSyncConnector, LdapSyncMode,
+    LdapSyncRun, LdapSyncSettings, LdapSyncStatus, TriggerLdapSyncInput,
+};
+use crate::models::linked_identity::CreateLinkedIdentityInput;
+use crate::models::rbac::AssignRolesInput;
+use crate::models::user::{AddUserToTenantInput, CreateUserInput, UpdateUserInput, User};
+use crate::repository::{
+    LdapGroupRoleMappingRepository, LdapSyncRunRepository, LinkedIdentityRepository,
+    RbacRepository, UserRepository,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub const TRIGGER_MANUAL: &str = "manual";
+pub const TRIGGER_SCHEDULED: &str = "scheduled";
+
+pub struct LdapSyncService<R, M>
+where
+    R: LdapSyncRunRepository,
+    M: LdapGroupRoleMappingRepository,
+{
+    run_repo: Arc<R>,
+    mapping_repo: Arc<M>,
+    user_repo: Arc<dyn UserRepository>,
+    linked_identity_repo: Arc<dyn LinkedIdentityRepository>,
+    rbac_repo: Arc<dyn RbacRepository>,
+    stale_after_secs: i64,
+}
+
+/// Settings shared by every directory user of one run
+struct SyncContext<'a> {
+    connector: &'a LdapSyncConnector,
+    policy: LdapSyncConflictPolicy,
+    mappings: &'a [LdapGroupRoleMapping],
+    dry_run: bool,
+}
+
+impl<R, M> LdapSyncService<R, M>
+where
+    R: LdapSyncRunRepository,
+    M: LdapGroupRoleMappingRepository,
+{
+    pub fn new(
+        run_repo: Arc<R>,
+        mapping_repo: Arc<M>,
+        user_repo: Arc<dyn UserRepository>,
+        linked_identity_repo: Arc<dyn LinkedIdentityRepository>,
+        rbac_repo: Arc<dyn RbacRepository>,
+        stale_after_secs: u64,
+    ) -> Self {
+        Self {
+            run_repo,
+            mapping_repo,
+            user_repo,
+            linked_identity_repo,
+            rbac_repo,
+            stale_after_secs: stale_after_secs as i64,
+        }
+    }
+
+    pub async fn list_runs(
+        &self,
+        connector_id: StringUuid,
+        limit: i64,
+    ) -> Result<Vec<LdapSyncRun>> {
+        self.run_repo.list_by_connector(connector_id, limit).await
+    }
+
+    pub async fn get_run(&self, connector_id: StringUuid, id: StringUuid) -> Result<LdapSyncRun> {
+        self.run_repo
+            .find_by_id(connector_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("LDAP sync run not found".to_string()))
+    }
+
+    /// Sync a connector and record the run. Incremental unless `input.full`
+    /// is set or no successful run left a cursor. A failed sync is returned
+    /// as a run with status `failed`; the cursor only advances on success.
+    pub async fn run(
+        &self,
+        ldap: &dyn LdapAuthenticator,
+        connector: &LdapSyncConnector,
+        triggered_by: &str,
+        input: &TriggerLdapSyncInput,
+    ) -> Result<LdapSyncRun> {
+        let settings = LdapSyncSettings::from_connector_config(&connector.config);
+        let ldap_config = parse_ldap_config(&connector.config)?;
+
+        let cursor = if input.full {
+            None
+        } else {
+            self.run_repo.last_cursor(connector.id).await?
+        };
+        let mode = if cursor.is_some() {
+            LdapSyncMode::Incremental
+        } else {
+            LdapSyncMode::Full
+        };
+
+        let mut run = LdapSyncRun::start(
+            connector.tenant_id,
+            connector.id,
+            triggered_by,
+            mode,
+            input.dry_run,
+            settings.conflict_policy,
+            cursor.clone(),
+        );
+        if !self.run_repo.start(&run, self.stale_after_secs).await? {
+            return Err(AppError::Conflict(
+                "A sync of this LDAP connector is already running".to_string(),
+            ));
+        }
+
+        let outcome = async {
+            let batch = ldap.list_sync_users(&ldap_config, cursor).await?;
+            let mappings = self.mapping_repo.list_by_connector(connector.id).await?;
+            let ctx = SyncContext {
+                connector,
+                policy: settings.conflict_policy,
+                mappings: &mappings,
+                dry_run: input.dry_run,
+            };
+            for profile in &batch.users {
+                run.users_seen += 1;
+                self.sync_user(&ctx, profile, &mut run).await?;
+            }
+            Ok::<_, AppError>(batch.cursor)
+        }
+        .await;
+
+        match outcome {
+            Ok(cursor_after) => {
+                run.status = LdapSyncStatus::Succeeded.as_str().to_string();
+                run.cursor_after = cursor_after;
+            }
+            Err(e) => {
+                tracing::warn!(connector_id = %connector.id, error = %e, "LDAP sync failed");
+                run.status = LdapSyncStatus::Failed.as_str().to_string();
+                run.error = Some(e.to_string());
+            }
+        }
+        run.finished_at = Some(Utc::now());
+        self.run_repo.finish(&run).await?;
+
+        metrics::counter!(
+            "auth9_ldap_sync_runs_total",
+            "trigger" => triggered_by.to_string(),
+            "status" => run.status.clone()
+        )
+        .increment(1);
+        tracing::info!(
+            connector_id = %connector.id,
+            mode = %run.mode,
+            dry_run = run.dry_run,
+            users_seen = run.users_seen,
+            users_created = run.users_created,
+            conflicts = run.conflicts,
+            "LDAP sync finished"
+        );
+
+        Ok(run)
+    }
+
+    /// Run a scheduled sync of every connector with sync enabled whose last
+    /// run started at least its interval ago. Returns the number of runs.
+    pub async fn sync_due_connectors(
+        &self,
+        ldap: &dyn LdapAuthenticator,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let mut runs = 0;
+        for connector in self.run_repo.list_ldap_connectors().await? {
+            let settings = LdapSyncSettings::from_connector_config(&connector.config);
+            if !settings.enabled {
+                continue;
+            }
+            let interval = Duration::minutes(settings.interval_minutes as i64);
+            if let Some(last) = self.run_repo.last_started_at(connector.id).await? {
+                if now - last < interval {
+                    continue;
+                }
+            }
+            match self
+                .run(
+                    ldap,
+                    &connector,
+                    TRIGGER_SCHEDULED,
+                    &TriggerLdapSyncInput::default(),
+                )
+                .await
+            {
+                Ok(_) => runs += 1,
+                Err(AppError::Conflict(_)) => {}
+                Err(e) => {
+                    tracing::warn!(
+                        connector_id = %connector.id,
+                        error = %e,
+                        "Scheduled LDAP sync failed"
+                    );
+                }
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Reconcile one directory user: find, link or create the Auth9 user, then
+    /// align its mapped roles. In a dry run the changes are only recorded.
+    async fn sync_user(
+        &self,
+        ctx: &SyncContext<'_>,
+        profile: &LdapUserProfile,
+        run: &mut LdapSyncRun,
+    ) -> Result<()> {
+        let change = |action: LdapSyncAction,
+                      user_id: Option<StringUuid>,
+                      role_id: Option<StringUuid>,
+                      detail: Option<String>| LdapSyncChange {
+            action,
+            dn: profile.dn.clone(),
+            email: profile.email.clone(),
+            user_id,
+            role_id,
+            detail,
+        };
+
+        let linked = self
+            .linked_identity_repo
+            .find_by_provider(&ctx.connector.alias, &profile.dn)
+            .await?;
+        let linked_user = match linked {
+            Some(link) => self.user_repo.find_by_id(link.user_id).await?,
+            None => None,
+        };
+
+        let user: Option<User> = if let Some(user) = linked_user {
+            if display_name_changed(&user, profile) {
+                run.record(change(
+                    LdapSyncAction::UpdateUser,
+                    Some(user.id),
+                    None,
+                    profile.display_name.clone(),
+                ));
+                if !ctx.dry_run {
+                    self.update_profile(&user, profile).await?;
+                }
+            }
+            Some(user)
+        } else {
+            let Some(email) = profile.email.as_deref().filter(|e| !e.is_empty()) else {
+                run.record(change(
+                    LdapSyncAction::Conflict,
+                    None,
+                    None,
+                    Some("Directory entry has no email".to_string()),
+                ));
+                return Ok(());
+            };
+
+            match self.user_repo.find_by_email(email).await? {
+                Some(existing) => {
+                    if ctx.policy == LdapSyncConflictPolicy::Skip {
+                        run.record(change(
+                            LdapSyncAction::Conflict,
+                            Some(existing.id),
+                            None,
+                            Some(
+                                "Email belongs to a user not linked to this connector".to_string(),
+                            ),
+                        ));
+                        return Ok(());
+                    }
+                    run.record(change(
+                        LdapSyncAction::LinkUser,
+                        Some(existing.id),
+                        None,
+                        None,
+                    ));
+                    let overwrite = ctx.policy == LdapSyncConflictPolicy::Overwrite
+                        && display_name_changed(&existing, profile);
+                    if overwrite {
+                        run.record(change(
+                            LdapSyncAction::UpdateUser,
+                            Some(existing.id),
+                            None,
+                            profile.display_name.clone(),
+                        ));
+                    }
+                    if !ctx.dry_run {
+                        self.link(ctx.connector, existing.id, profile).await?;
+                        if overwrite {
+                            self.update_profile(&existing, profile).await?;
+                        }
+                    }
+                    Some(existing)
+                }
+                None => {
+                    if ctx.dry_run {
+                        run.record(change(LdapSyncAction::CreateUser, None, None, None));
+                        None
+                    } else {
+                        let user = self
+                            .user_repo
+                            .create(
+                                &uuid::Uuid::new_v4().to_string(),
+                                &CreateUserInput {
+                                    email: email.to_string(),
+                                    display_name: profile.display_name.clone(),
+                                    avatar_url: None,
+                                },
+                            )
+                            .await?;
+                        run.record(change(
+                            LdapSyncAction::CreateUser,
+                            Some(user.id),
+                            None,
+                            None,
+                        ));
+                        self.link(ctx.connector, user.id, profile).await?;
+                        Some(user)
+                    }
+                }
+            }
+        };
+
+        // Roles
+        let desired = desired_roles(ctx.mappings, &profile.groups);
+        let managed: HashSet<StringUuid> = ctx.mappings.iter().map(|m| m.role_id).collect();
+        let current: HashSet<StringUuid> = match &user {
+            Some(user) => self
+                .rbac_repo
+                .find_user_role_records_in_tenant(
+                    UserId::from(user.id),
+                    TenantId::from(ctx.connector.tenant_id),
+                    None,
+                )
+                .await?
+                .into_iter()
+                .map(|role| role.id)
+                .filter(|id| managed.contains(id))
+                .collect(),
+            None => HashSet::new(),
+        };
+        let user_id = user.as_ref().map(|u| u.id);
+
+        let to_assign: Vec<StringUuid> = desired.difference(&current).copied().collect();
+        for role_id in &to_assign {
+            run.record(change(
+                LdapSyncAction::AssignRole,
+                user_id,
+                Some(*role_id),
+                None,
+            ));
+        }
+        let to_remove: Vec<StringUuid> = current.difference(&desired).copied().collect();
+        for role_id in &to_remove {
+            run.record(change(
+                LdapSyncAction::RemoveRole,
+                user_id,
+                Some(*role_id),
+                None,
+            ));
+        }
+
+        let Some(user_id) = user_id.filter(|_| !ctx.dry_run) else {
+            return Ok(());
+        };
+        if !to_assign.is_empty() {
+            self.rbac_repo
+                .assign_roles_to_user(
+                    &AssignRolesInput {
+                        user_id: *user_id,
+                        tenant_id: *ctx.connector.tenant_id,
+                        role_ids: to_assign.iter().map(|id| **id).collect(),
+                        service_id: None,
+                    },
+                    None,
+                )
+                .await?;
+        }
+        if !to_remove.is_empty() {
+            if let Some(tenant_user_id) = self
+                .rbac_repo
+                .find_tenant_user_id(
+                    UserId::from(user_id),
+                    TenantId::from(ctx.connector.tenant_id),
+                )
+                .await?
+            {
+                for role_id in to_remove {
+                    self.rbac_repo
+                        .remove_role_from_user(tenant_user_id, role_id)
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Link a user to its directory entry and make it a member of the tenant
+    async fn link(
+        &self,
+        connector: &LdapSyncConnector,
+        user_id: StringUuid,
+        profile: &LdapUserProfile,
+    ) -> Result<()> {
+        self.linked_identity_repo
+            .create(&CreateLinkedIdentityInput {
+                user_id,
+                provider_type: "ldap".to_string(),
+                provider_alias: connector.alias.clone(),
+                external_user_id: profile.dn.clone(),
+                external_email: profile.email.clone(),
+            })
+            .await?;
+        if self
+            .rbac_repo
+            .find_tenant_user_id(UserId::from(user_id), TenantId::from(connector.tenant_id))
+            .await?
+            .is_none()
+        {
+            self.user_repo
+                .add_to_tenant(&AddUserToTenantInput {
+                    user_id: *user_id,
+                    tenant_id: *connector.tenant_id,
+                    role_in_tenant: "member".to_string(),
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn update_profile(&self, user: &User, profile: &LdapUserProfile) -> Result<()> {
+        self.user_repo
+            .update(
+                user.id,
+                &UpdateUserInput {
+                    display_name: profile.display_name.clone(),
+                    avatar_url: None,
+                },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn display_name_changed(user: &User, profile: &LdapUserProfile) -> bool {
+    profile.display_name.is_some() && profile.display_name != user.display_name
+}
+
+/// Roles granted by the mappings of the groups a directory user belongs to.
+/// Group DNs compare case-insensitively, as directory servers do.
+fn desired_roles(mappings: &[LdapGroupRoleMapping], groups: &[String]) -> HashSet<StringUuid> {
+    mappings
+        .iter()
+        .filter(|m| {
+            groups
+                .iter()
+                .any(|g| g.eq_ignore_ascii_case(&m.ldap_group_dn))
+        })
+        .map(|m| m.role_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::identity::service::ldap::MockLdapAuthenticator;
+    use crate::models::ldap_sync::LdapSyncBatch;
+    use crate::models::linked_identity::LinkedIdentity;
+    use crate::models::rbac::Role;
+    use crate::repository::ldap_group_mapping::MockLdapGroupRoleMappingRepository;
+    use crate::repository::ldap_sync_run::MockLdapSyncRunRepository;
+    use crate::repository::linked_identity::MockLinkedIdentityRepository;
+    use crate::repository::rbac::MockRbacRepository;
+    use crate::repository::user::MockUserRepository;
+    use std::collections::HashMap;
+
+    const ENGINEERS: &str = "CN=Engineers,OU=Groups,DC=example,DC=com";
+
+    fn connector() -> LdapSyncConnector {
+        let config: HashMap<String, String> = [
+            ("serverUrl", "ldaps://ad.example.com:636"),
+            ("bindDn", "CN=svc,DC=example,DC=com"),
+            ("bindPassword", "secret"), // pragma: allowlist secret
+            ("baseDn", "DC=example,DC=com"),
+            ("isActiveDirectory", "true"),
+            ("syncEnabled", "true"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        LdapSyncConnector {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            alias: "corp-ad".to_string(),
+            config,
+        }
+    }
+
+    fn mapping(connector: &LdapSyncConnector, group_dn: &str) -> LdapGroupRoleMapping {
+        LdapGroupRoleMapping {
+            id: StringUuid::new_v4(),
+            tenant_id: connector.tenant_id,
+            connector_id: connector.id,
+            ldap_group_dn: group_dn.to_string(),
+            ldap_group_display_name: None,
+            role_id: StringUuid::new_v4(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn profile(email: &str, groups: &[&str]) -> LdapUserProfile {
+        LdapUserProfile {
+            dn: format!("CN={},OU=Users,DC=example,DC=com", email),
+            username: email.to_string(),
+            email: Some(email.to_string()),
+            first_name: None,
+            last_name: None,
+            display_name: Some("Directory Name".to_string()),
+            phone: None,
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    fn run_repo(cursor: Option<String>) -> MockLdapSyncRunRepository {
+        let mut repo = MockLdapSyncRunRepository::new();
+        repo.expect_last_cursor()
+            .returning(move |_| Ok(cursor.clone()));
+        repo.expect_start().returning(|_, _| Ok(true));
+        repo.expect_finish().returning(|_| Ok(()));
+        repo
+    }
+
+    fn ldap_returning(users: Vec<LdapUserProfile>) -> MockLdapAuthenticator {
+        let mut ldap = MockLdapAuthenticator::new();
+        ldap.expect_list_sync_users().returning(move |_, _| {
+            Ok(LdapSyncBatch {
+                users: users.clone(),
+                cursor: Some("20500".to_string()),
+            })
+        });
+        ldap
+    }
+
+    #[test]
+    fn test_desired_roles_match_group_dns_case_insensitively() {
+        let connector = connector();
+        let engineers = mapping(&connector, ENGINEERS);
+        let admins = mapping(&connector, "CN=Admins,OU=Groups,DC=example,DC=com");
+        let roles = desired_roles(
+            &[engineers.clone(), admins],
+            &[
+                ENGINEERS.to_lowercase(),
+                "CN=Other,DC=example,DC=com".to_string(),
+            ],
+        );
+        assert_eq!(roles, HashSet::from([engineers.role_id]));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_plans_changes_without_writing() {
+        let connector = connector();
+        let engineers = mapping(&connector, ENGINEERS);
+        let existing = User {
+            email: "taken@example.com".to_string(),
+            ..User::default()
+        };
+
+        let mut mapping_repo = MockLdapGroupRoleMappingRepository::new();
+        let mappings = vec![engineers.clone()];
+        mapping_repo
+            .expect_list_by_connector()
+            .returning(move |_| Ok(mappings.clone()));
+        let mut linked_repo = MockLinkedIdentityRepository::new();
+        linked_repo
+            .expect_find_by_provider()
+            .returning(|_, _| Ok(None));
+        linked_repo.expect_create().never();
+        let mut user_repo = MockUserRepository::new();
+        let existing_clone = existing.clone();
+        user_repo.expect_find_by_email().returning(move |email| {
+            Ok((email == "taken@example.com").then(|| existing_clone.clone()))
+        });
+        user_repo.expect_create().never();
+        let mut rbac_repo = MockRbacRepository::new();
+        rbac_repo
+            .expect_find_user_role_records_in_tenant()
+            .returning(|_, _, _| Ok(vec![]));
+        rbac_repo.expect_assign_roles_to_user().never();
+
+        let service = LdapSyncService::new(
+            Arc::new(run_repo(None)),
+            Arc::new(mapping_repo),
+            Arc::new(user_repo),
+            Arc::new(linked_repo),
+            Arc::new(rbac_repo),
+            3600,
+        );
+        let ldap = ldap_returning(vec![
+            profile("new@example.com", &[ENGINEERS]),
+            profile("taken@example.com", &[ENGINEERS]),
+        ]);
+
+        let run = service
+            .run(
+                &ldap,
+                &connector,
+                TRIGGER_MANUAL,
+                &TriggerLdapSyncInput {
+                    dry_run: true,
+                    full: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(run.status, "succeeded");
+        assert_eq!(run.mode, "full");
+        assert_eq!(run.users_seen, 2);
+        assert_eq!(run.users_created, 1);
+        // The existing user is skipped under the default policy
+        assert_eq!(run.conflicts, 1);
+        assert_eq!(run.roles_assigned, 1);
+        assert_eq!(run.cursor_after.as_deref(), Some("20500"));
+    }
+
+    #[tokio::test]
+    async fn test_incremental_sync_links_and_removes_unmapped_roles() {
+        let mut connector = connector();
+        connector
+            .config
+            .insert("syncConflictPolicy".to_string(), "link".to_string());
+        let engineers = mapping(&connector, ENGINEERS);
+        let admins = mapping(&connector, "CN=Admins,OU=Groups,DC=example,DC=com");
+        let manual_role = StringUuid::new_v4();
+        let existing = User {
+            email: "jane@example.com".to_string(),
+            ..User::default()
+        };
+        let existing_id = existing.id;
+
+        let mut mapping_repo = MockLdapGroupRoleMappingRepository::new();
+        let mappings = vec![engineers.clone(), admins.clone()];
+        mapping_repo
+            .expect_list_by_connector()
+            .returning(move |_| Ok(mappings.clone()));
+        let mut linked_repo = MockLinkedIdentityRepository::new();
+        linked_repo
+            .expect_find_by_provider()
+            .returning(|_, _| Ok(None));
+        linked_repo
+            .expect_create()
+            .times(1)
+            .returning(|_| Ok(LinkedIdentity::default()));
+        let mut user_repo = MockUserRepository::new();
+        user_repo
+            .expect_find_by_email()
+            .returning(move |_| Ok(Some(existing.clone())));
+        let mut rbac_repo = MockRbacRepository::new();
+        let tenant_user_id = StringUuid::new_v4();
+        rbac_repo
+            .expect_find_tenant_user_id()
+            .returning(move |_, _| Ok(Some(tenant_user_id)));
+        let (admin_role, engineer_role) = (admins.role_id, engineers.role_id);
+        rbac_repo
+            .expect_find_user_role_records_in_tenant()
+            .returning(move |_, _, _| {
+                Ok(vec![
+                    Role {
+                        id: admin_role,
+                        ..Role::default()
+                    },
+                    Role {
+                        id: manual_role,
+                        ..Role::default()
+                    },
+                ])
+            });
+        rbac_repo
+            .expect_assign_roles_to_user()
+            .withf(move |input, _| {
+                input.user_id == *existing_id && input.role_ids == vec![*engineer_role]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        rbac_repo
+            .expect_remove_role_from_user()
+            .withf(move |_, role_id| *role_id == admin_role)
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = LdapSyncService::new(
+            Arc::new(run_repo(Some("20000".to_string()))),
+            Arc::new(mapping_repo),
+            Arc::new(user_repo),
+            Arc::new(linked_repo),
+            Arc::new(rbac_repo),
+            3600,
+        );
+        let mut ldap = MockLdapAuthenticator::new();
+        let users = vec![profile("jane@example.com", &[ENGINEERS])];
+        ldap.expect_list_sync_users()
+            .withf(|_, cursor| cursor.as_deref() == Some("20000"))
+            .returning(move |_, _| {
+                Ok(LdapSyncBatch {
+                    users: users.clone(),
+                    cursor: Some("20500".to_string()),
+                })
+            });
+
+        let run = service
+            .run(
+                &ldap,
+                &connector,
+                TRIGGER_SCHEDULED,
+                &TriggerLdapSyncInput::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(run.status, "succeeded");
+        assert_eq!(run.mode, "incremental");
+        assert_eq!(run.users_linked, 1);
+        assert_eq!(run.roles_assigned, 1);
+        assert_eq!(run.roles_removed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_search_records_failed_run_and_keeps_cursor() {
+        let connector = connector();
+        let mut runs = MockLdapSyncRunRepository::new();
+        runs.expect_last_cursor()
+            .returning(|_| Ok(Some("20000".to_string())));
+        runs.expect_start().returning(|_, _| Ok(true));
+        runs.expect_finish()
+            .withf(|run| run.status == "failed" && run.cursor_after.is_none())
+            .times(1)
+            .returning(|_| Ok(()));
+        let mut ldap = MockLdapAuthenticator::new();
+        ldap.expect_list_sync_users()
+            .returning(|_, _| Err(AppError::BadRequest("connection refused".to_string())));
+
+        let service = LdapSyncService::new(
+            Arc::new(runs),
+            Arc::new(MockLdapGroupRoleMappingRepository::new()),
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockLinkedIdentityRepository::new()),
+            Arc::new(MockRbacRepository::new()),
+            3600,
+        );
+        let run = service
+            .run(
+                &ldap,
+                &connector,
+                TRIGGER_MANUAL,
+                &TriggerLdapSyncInput::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(run.status, "failed");
+        assert!(run.error.unwrap().contains("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_run_is_rejected() {
+        let connector = connector();
+        let mut runs = MockLdapSyncRunRepository::new();
+        runs.expect_last_cursor().returning(|_| Ok(None));
+        runs.expect_start().returning(|_, _| Ok(false));
+        runs.expect_finish().never();
+
+        let service = LdapSyncService::new(
+            Arc::new(runs),
+            Arc::new(MockLdapGroupRoleMappingRepository::new()),
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockLinkedIdentityRepository::new()),
+            Arc::new(MockRbacRepository::new()),
+            3600,
+        );
+        let result = service
+            .run(
+                &MockLdapAuthenticator::new(),
+                &connector,
+                TRIGGER_MANUAL,
+                &TriggerLdapSyncInput::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+}
@@ -0,0 +1,267 @@
+//! Username and phone number login identifiers
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::login_identifier::{
+    LoginIdentifier, LoginIdentifierKind, LoginIdentifierSettings, GLOBAL_SCOPE,
+};
+use crate::repository::login_identifier::LoginIdentifierRepositoryImpl;
+use crate::repository::LoginIdentifierRepository;
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+pub struct LoginIdentifierService {
+    repo: Arc<dyn LoginIdentifierRepository>,
+}
+
+impl LoginIdentifierService {
+    pub fn new(repo: Arc<dyn LoginIdentifierRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(LoginIdentifierRepositoryImpl::new(pool)))
+    }
+
+    pub async fn list(&self, user_id: StringUuid) -> Result<Vec<LoginIdentifier>> {
+        self.repo.list_for_user(user_id).await
+    }
+
+    /// Give a tenant member a username or phone number, replacing the one
+    /// they have unless another tenant assigned it
+    pub async fn assign(
+        &self,
+        tenant_id: StringUuid,
+        settings: Option<&LoginIdentifierSettings>,
+        user_id: StringUuid,
+        kind: LoginIdentifierKind,
+        raw_value: &str,
+    ) -> Result<LoginIdentifier> {
+        let settings = settings.filter(|s| s.allows(kind)).ok_or_else(|| {
+            AppError::BadRequest(format!("Tenant does not allow {} sign-in", kind))
+        })?;
+        let value = kind.normalize(raw_value).map_err(AppError::Validation)?;
+        let current = self.repo.list_for_user(user_id).await?;
+        ensure_managed(&current, tenant_id, kind)?;
+
+        // A global value must be unique everywhere; a tenant value must not
+        // collide with a global one, or sign-in to the tenant is ambiguous
+        let scope = settings.scope_for(tenant_id);
+        let lookup_tenant = (scope != GLOBAL_SCOPE).then_some(tenant_id);
+        let taken = self
+            .repo
+            .find_by_value(kind, &value, lookup_tenant)
+            .await?
+            .iter()
+            .any(|existing| existing.user_id != user_id);
+        if taken {
+            return Err(AppError::Conflict(format!(
+                "This {} is already in use",
+                kind
+            )));
+        }
+
+        let identifier = LoginIdentifier {
+            user_id,
+            kind,
+            value,
+            scope,
+            created_at: Utc::now(),
+        };
+        self.repo.assign(&identifier).await?;
+        Ok(identifier)
+    }
+
+    pub async fn remove(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        kind: LoginIdentifierKind,
+    ) -> Result<()> {
+        let current = self.repo.list_for_user(user_id).await?;
+        if !current.iter().any(|i| i.kind == kind) {
+            return Err(AppError::NotFound(format!("User has no {}", kind)));
+        }
+        ensure_managed(&current, tenant_id, kind)?;
+        self.repo.remove(user_id, kind).await?;
+        Ok(())
+    }
+
+    /// Identifier a user signs in with; `None` when unknown or when it
+    /// matches several users (the tenant is needed to tell them apart)
+    pub async fn resolve(
+        &self,
+        kind: LoginIdentifierKind,
+        value: &str,
+        tenant_id: Option<StringUuid>,
+    ) -> Result<Option<LoginIdentifier>> {
+        let mut matches = self.repo.find_by_value(kind, value, tenant_id).await?;
+        Ok(match matches.len() {
+            1 => matches.pop(),
+            _ => None,
+        })
+    }
+}
+
+/// Tenants cannot change identifiers another tenant assigned
+fn ensure_managed(
+    current: &[LoginIdentifier],
+    tenant_id: StringUuid,
+    kind: LoginIdentifierKind,
+) -> Result<()> {
+    match current.iter().find(|i| i.kind == kind) {
+        Some(existing) if !existing.managed_by(tenant_id) => Err(AppError::Forbidden(format!(
+            "The user's {} is managed by another tenant",
+            kind
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::login_identifier::LoginIdentifierUniqueness;
+    use crate::repository::login_identifier::MockLoginIdentifierRepository;
+    use mockall::predicate::*;
+
+    fn settings(uniqueness: LoginIdentifierUniqueness) -> LoginIdentifierSettings {
+        LoginIdentifierSettings {
+            username: true,
+            phone: false,
+            uniqueness,
+        }
+    }
+
+    fn identifier(user_id: StringUuid, value: &str, scope: &str) -> LoginIdentifier {
+        LoginIdentifier {
+            user_id,
+            kind: LoginIdentifierKind::Username,
+            value: value.to_string(),
+            scope: scope.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assign_tenant_scoped_username() {
+        let (tenant_id, user_id) = (StringUuid::new_v4(), StringUuid::new_v4());
+        let mut repo = MockLoginIdentifierRepository::new();
+        repo.expect_list_for_user().returning(|_| Ok(vec![]));
+        repo.expect_find_by_value()
+            .with(
+                eq(LoginIdentifierKind::Username),
+                eq("jdoe"),
+                eq(Some(tenant_id)),
+            )
+            .returning(|_, _, _| Ok(vec![]));
+        repo.expect_assign()
+            .withf(move |i| i.value == "jdoe" && i.scope == tenant_id.to_string())
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let assigned = LoginIdentifierService::new(Arc::new(repo))
+            .assign(
+                tenant_id,
+                Some(&settings(LoginIdentifierUniqueness::Tenant)),
+                user_id,
+                LoginIdentifierKind::Username,
+                "JDoe",
+            )
+            .await
+            .unwrap();
+        assert_eq!(assigned.user_id, user_id);
+    }
+
+    #[tokio::test]
+    async fn test_assign_rejects_disabled_kind_and_taken_value() {
+        let (tenant_id, user_id) = (StringUuid::new_v4(), StringUuid::new_v4());
+        let mut repo = MockLoginIdentifierRepository::new();
+        repo.expect_list_for_user().returning(|_| Ok(vec![]));
+        repo.expect_find_by_value()
+            .with(
+                eq(LoginIdentifierKind::Username),
+                eq("jdoe"),
+                eq(None::<StringUuid>),
+            )
+            .returning(|_, _, _| Ok(vec![identifier(StringUuid::new_v4(), "jdoe", "*")]));
+        let service = LoginIdentifierService::new(Arc::new(repo));
+
+        let disabled = service
+            .assign(
+                tenant_id,
+                Some(&settings(LoginIdentifierUniqueness::Global)),
+                user_id,
+                LoginIdentifierKind::Phone,
+                "+14155550100",
+            )
+            .await;
+        assert!(matches!(disabled, Err(AppError::BadRequest(_))));
+        let taken = service
+            .assign(
+                tenant_id,
+                Some(&settings(LoginIdentifierUniqueness::Global)),
+                user_id,
+                LoginIdentifierKind::Username,
+                "jdoe",
+            )
+            .await;
+        assert!(matches!(taken, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_other_tenants_identifier_is_protected() {
+        let (tenant_id, user_id) = (StringUuid::new_v4(), StringUuid::new_v4());
+        let other_scope = StringUuid::new_v4().to_string();
+        let mut repo = MockLoginIdentifierRepository::new();
+        repo.expect_list_for_user()
+            .returning(move |id| Ok(vec![identifier(id, "jdoe", &other_scope)]));
+        let result = LoginIdentifierService::new(Arc::new(repo))
+            .remove(tenant_id, user_id, LoginIdentifierKind::Username)
+            .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_requires_single_match() {
+        let user_id = StringUuid::new_v4();
+        let mut repo = MockLoginIdentifierRepository::new();
+        repo.expect_find_by_value()
+            .with(
+                eq(LoginIdentifierKind::Username),
+                eq("jdoe"),
+                eq(None::<StringUuid>),
+            )
+            .returning(move |_, _, _| Ok(vec![identifier(user_id, "jdoe", "*")]));
+        repo.expect_find_by_value()
+            .with(
+                eq(LoginIdentifierKind::Username),
+                eq("shared"),
+                eq(None::<StringUuid>),
+            )
+            .returning(|_, _, _| {
+                Ok(vec![
+                    identifier(StringUuid::new_v4(), "shared", "a"),
+                    identifier(StringUuid::new_v4(), "shared", "b"),
+                ])
+            });
+        let service = LoginIdentifierService::new(Arc::new(repo));
+
+        assert_eq!(
+            service
+                .resolve(LoginIdentifierKind::Username, "jdoe", None)
+                .await
+                .unwrap()
+                .map(|i| i.user_id),
+            Some(user_id)
+        );
+        assert_eq!(
+            service
+                .resolve(LoginIdentifierKind::Username, "shared", None)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
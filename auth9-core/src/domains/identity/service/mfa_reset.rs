@@ -0,0 +1,299 @@
+//! Admin-assisted MFA reset
+//!
+//! A user who lost their second factor opens a request after proving their
+//! password. A tenant admin reviews the identity proof and approves or
+//! rejects it; approval opens a reset window of [`MFA_RESET_WINDOW_HOURS`]
+//! in which the user (after a fresh password login) clears their factors
+//! once. Users cannot review their own requests.
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::mfa_reset::{
+    MfaResetRequest, MfaResetStatus, MFA_RESET_REQUEST_TTL_HOURS, MFA_RESET_WINDOW_HOURS,
+};
+use crate::repository::MfaResetRepository;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+pub struct MfaResetService<R: MfaResetRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: MfaResetRepository> MfaResetService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// Open a request for `user_id` in `tenant_id`. A user has at most one
+    /// open request at a time.
+    pub async fn open_request(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        identity_proof: String,
+        requester_ip: Option<String>,
+    ) -> Result<MfaResetRequest> {
+        if self.repo.find_open_by_user(user_id).await?.is_some() {
+            return Err(AppError::Conflict(
+                "An MFA reset request is already open for this account".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let request = MfaResetRequest {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            user_id,
+            status: MfaResetStatus::Pending.as_str().to_string(),
+            identity_proof,
+            requester_ip,
+            reviewed_by: None,
+            review_comment: None,
+            created_at: now,
+            expires_at: now + Duration::hours(MFA_RESET_REQUEST_TTL_HOURS),
+            reviewed_at: None,
+            window_expires_at: None,
+            completed_at: None,
+        };
+        self.repo.create(&request).await?;
+        Ok(request)
+    }
+
+    pub async fn list(
+        &self,
+        tenant_id: StringUuid,
+        status: Option<MfaResetStatus>,
+        limit: i64,
+    ) -> Result<Vec<MfaResetRequest>> {
+        self.repo
+            .list_by_tenant(
+                tenant_id,
+                status.map(|s| s.as_str().to_string()),
+                limit.clamp(1, 100),
+            )
+            .await
+    }
+
+    /// Request of the tenant; requests of other tenants are not found
+    pub async fn get(&self, tenant_id: StringUuid, id: StringUuid) -> Result<MfaResetRequest> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .filter(|request| request.tenant_id == tenant_id)
+            .ok_or_else(|| AppError::NotFound(format!("MFA reset request {} not found", id)))
+    }
+
+    /// Approve or reject a pending request. Approval opens the reset window.
+    pub async fn decide(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        reviewer_id: StringUuid,
+        approve: bool,
+        comment: Option<String>,
+    ) -> Result<MfaResetRequest> {
+        let request = self.get(tenant_id, id).await?;
+        if request.user_id == reviewer_id {
+            return Err(AppError::Forbidden(
+                "Admins cannot review their own MFA reset request".to_string(),
+            ));
+        }
+        if request.reset_status() != Some(MfaResetStatus::Pending) || request.is_expired() {
+            return Err(AppError::Conflict(
+                "MFA reset request is no longer pending".to_string(),
+            ));
+        }
+
+        let (status, window_expires_at) = if approve {
+            (
+                MfaResetStatus::Approved,
+                Some(Utc::now() + Duration::hours(MFA_RESET_WINDOW_HOURS)),
+            )
+        } else {
+            (MfaResetStatus::Rejected, None)
+        };
+        let decided = self
+            .repo
+            .decide(id, status.as_str(), reviewer_id, comment, window_expires_at)
+            .await?;
+        if !decided {
+            return Err(AppError::Conflict(
+                "MFA reset request is no longer pending".to_string(),
+            ));
+        }
+        self.get(tenant_id, id).await
+    }
+
+    /// Close the reset window of an approved request on behalf of its user.
+    /// Succeeds once; the caller then clears the user's factors.
+    pub async fn complete(&self, id: StringUuid, user_id: StringUuid) -> Result<MfaResetRequest> {
+        let request = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .filter(|request| request.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound(format!("MFA reset request {} not found", id)))?;
+        match request.reset_status() {
+            Some(MfaResetStatus::Approved) if request.window_open() => {}
+            Some(MfaResetStatus::Approved) => {
+                return Err(AppError::Conflict(
+                    "The MFA reset window has closed".to_string(),
+                ))
+            }
+            Some(MfaResetStatus::Pending) => {
+                return Err(AppError::Conflict(
+                    "MFA reset request is still awaiting admin approval".to_string(),
+                ))
+            }
+            _ => {
+                return Err(AppError::Conflict(
+                    "MFA reset request can no longer be completed".to_string(),
+                ))
+            }
+        }
+
+        if !self.repo.complete(id).await? {
+            return Err(AppError::Conflict(
+                "MFA reset request can no longer be completed".to_string(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::mfa_reset::MockMfaResetRepository;
+    use mockall::predicate::*;
+
+    fn request(status: MfaResetStatus) -> MfaResetRequest {
+        MfaResetRequest {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            status: status.as_str().to_string(),
+            identity_proof: "Employee #42".to_string(),
+            requester_ip: None,
+            reviewed_by: None,
+            review_comment: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now() + Duration::hours(1),
+            reviewed_at: None,
+            window_expires_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_request_conflicts_with_open_one() {
+        let existing = request(MfaResetStatus::Pending);
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_open_by_user()
+            .returning(move |_| Ok(Some(existing.clone())));
+        repo.expect_create().never();
+
+        let service = MfaResetService::new(Arc::new(repo));
+        let result = service
+            .open_request(
+                StringUuid::new_v4(),
+                StringUuid::new_v4(),
+                "proof".to_string(),
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_approval_opens_reset_window() {
+        let pending = request(MfaResetStatus::Pending);
+        let (id, tenant_id) = (pending.id, pending.tenant_id);
+        let reviewer = StringUuid::new_v4();
+
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_decide()
+            .withf(move |req_id, status, by, _, window| {
+                *req_id == id && status == "approved" && *by == reviewer && window.is_some()
+            })
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(true));
+
+        let service = MfaResetService::new(Arc::new(repo));
+        assert!(service
+            .decide(tenant_id, id, reviewer, true, None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_user_cannot_review_own_request() {
+        let pending = request(MfaResetStatus::Pending);
+        let (id, tenant_id, user_id) = (pending.id, pending.tenant_id, pending.user_id);
+
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+        repo.expect_decide().never();
+
+        let service = MfaResetService::new(Arc::new(repo));
+        let result = service.decide(tenant_id, id, user_id, true, None).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_other_tenant_cannot_see_request() {
+        let pending = request(MfaResetStatus::Pending);
+        let id = pending.id;
+
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(pending.clone())));
+
+        let service = MfaResetService::new(Arc::new(repo));
+        let result = service.get(StringUuid::new_v4(), id).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_complete_requires_open_window() {
+        let mut approved = request(MfaResetStatus::Approved);
+        approved.window_expires_at = Some(Utc::now() - Duration::minutes(1));
+        let (id, user_id) = (approved.id, approved.user_id);
+
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(approved.clone())));
+        repo.expect_complete().never();
+
+        let service = MfaResetService::new(Arc::new(repo));
+        assert!(matches!(
+            service.complete(id, user_id).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            service.complete(id, StringUuid::new_v4()).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_complete_inside_window_once() {
+        let mut approved = request(MfaResetStatus::Approved);
+        approved.window_expires_at = Some(Utc::now() + Duration::hours(1));
+        let (id, user_id) = (approved.id, approved.user_id);
+
+        let mut repo = MockMfaResetRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(approved.clone())));
+        repo.expect_complete()
+            .with(eq(id))
+            .times(1)
+            .returning(|_| Ok(true));
+
+        let service = MfaResetService::new(Arc::new(repo));
+        assert!(service.complete(id, user_id).await.is_ok());
+    }
+}
@@ -1,26 +1,52 @@
+pub mod account_deletion;
+pub mod account_recovery;
 pub mod adaptive_mfa;
+pub mod attribute_release;
 pub mod breached_password;
+pub mod claims_enrichment;
+pub mod conditional_access;
 pub mod email_verification;
 pub mod identity_provider;
 pub mod ldap;
+pub mod ldap_sync;
+pub mod login_identifier;
+pub mod mfa_reset;
 pub mod otp;
 pub mod password;
+pub mod password_deny_list;
+pub mod progressive_profiling;
 pub mod recovery_code;
 pub mod required_actions;
+pub mod revocation_feed;
 pub mod session;
+pub mod session_activity;
 pub mod totp;
 pub mod trusted_device;
 pub mod webauthn;
+pub mod workload_identity;
 
+pub use account_deletion::AccountDeletionService;
+pub use account_recovery::AccountRecoveryService;
 pub use adaptive_mfa::{AdaptiveMfaEngine, AdaptiveMfaMode, AdaptiveMfaPolicy, MfaDecision};
+pub use attribute_release::AttributeReleaseService;
 pub use breached_password::BreachedPasswordService;
+pub use claims_enrichment::{ClaimsEnricher, ClaimsEnricherRegistry, ClaimsEnrichmentService};
+pub use conditional_access::ConditionalAccessService;
 pub use email_verification::EmailVerificationService;
 pub use identity_provider::IdentityProviderService;
+pub use ldap_sync::LdapSyncService;
+pub use login_identifier::LoginIdentifierService;
+pub use mfa_reset::MfaResetService;
 pub use otp::{OtpChannel, OtpChannelType, OtpManager, OtpRateLimitConfig};
 pub use password::PasswordService;
+pub use password_deny_list::PasswordDenyListService;
+pub use progressive_profiling::ProgressiveProfilingService;
 pub use recovery_code::RecoveryCodeService;
 pub use required_actions::RequiredActionService;
-pub use session::SessionService;
+pub use revocation_feed::RevocationFeedService;
+pub use session::{SessionService, SignInNotifier};
+pub use session_activity::SessionActivityFlusher;
 pub use totp::TotpService;
 pub use trusted_device::TrustedDeviceService;
 pub use webauthn::WebAuthnService;
+pub use workload_identity::WorkloadIdentityService;
@@ -32,7 +32,6 @@ impl<R: SystemSettingsRepository + 'static> OtpChannel for EmailOtpChannel<R> {
         vars.insert("user_name".to_string(), destination.to_string());
         vars.insert("verification_code".to_string(), code.to_string());
         vars.insert("expires_in_minutes".to_string(), ttl_minutes.to_string());
-        vars.insert("app_name".to_string(), "Auth9".to_string());
         vars.insert(
             "year".to_string(),
             chrono::Utc::now().format("%Y").to_string(),
@@ -3,6 +3,7 @@
 use crate::domains::integration::service::ActionEngine;
 use crate::domains::platform::service::{EmailService, IdentitySyncService};
 use crate::error::{AppError, Result};
+use crate::i18n::resolve_locale;
 use crate::identity_engine::IdentityEngine;
 use crate::models::action::{
     ActionContext, ActionContextRequest, ActionContextTenant, ActionContextUser,
@@ -27,7 +28,8 @@ use sha2::Sha256;
 use std::sync::Arc;
 use validator::Validate;
 
-use super::BreachedPasswordService;
+use super::password_deny_list::check_common_password;
+use super::{BreachedPasswordService, PasswordDenyListService};
 
 pub struct PasswordService<
     P: PasswordResetRepository,
@@ -47,6 +49,7 @@ pub struct PasswordService<
     identity_sync: Option<Arc<IdentitySyncService>>,
     hmac_key: String,
     breached_password_service: Option<Arc<BreachedPasswordService>>,
+    password_deny_list: Option<Arc<PasswordDenyListService>>,
 }
 
 impl<P: PasswordResetRepository, U: UserRepository, S: SystemSettingsRepository>
@@ -69,6 +72,7 @@ impl<P: PasswordResetRepository, U: UserRepository, S: SystemSettingsRepository>
             identity_sync: None,
             hmac_key,
             breached_password_service: None,
+            password_deny_list: None,
         }
     }
 }
@@ -99,6 +103,7 @@ impl<
             identity_sync: Some(identity_sync),
             hmac_key,
             breached_password_service: None,
+            password_deny_list: None,
         }
     }
 
@@ -123,6 +128,7 @@ impl<
             identity_sync: Some(identity_sync),
             hmac_key,
             breached_password_service: None,
+            password_deny_list: None,
         }
     }
 
@@ -132,6 +138,12 @@ impl<
         self
     }
 
+    /// Set the tenant password deny list service (builder pattern).
+    pub fn with_password_deny_list(mut self, svc: Arc<PasswordDenyListService>) -> Self {
+        self.password_deny_list = Some(svc);
+        self
+    }
+
     /// Request a password reset email
     pub async fn request_reset(&self, input: ForgotPasswordInput) -> Result<()> {
         input.validate()?;
@@ -145,6 +157,30 @@ impl<
             }
         };
 
+        let token = self.issue_reset_token(user.id).await?;
+
+        // Send the reset email
+        // The token is passed to the email template which builds the reset URL
+        // Errors are logged but NOT propagated to prevent email enumeration
+        if let Err(e) = self
+            .email_service
+            .send_password_reset(
+                &input.email,
+                &token,
+                user.display_name.as_deref(),
+                resolve_locale(user.locale.as_deref(), None, None),
+            )
+            .await
+        {
+            tracing::error!("Failed to send password reset email: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Issue a one-hour password reset token for a user, replacing any
+    /// outstanding one. The caller is responsible for delivering it.
+    pub async fn issue_reset_token(&self, user_id: StringUuid) -> Result<String> {
         // Generate a secure random token
         let token = generate_reset_token();
         let token_hash = hash_token(&token, self.hmac_key.as_bytes())?;
@@ -153,24 +189,13 @@ impl<
         let expires_at = Utc::now() + Duration::hours(1);
         self.password_reset_repo
             .replace_for_user(&CreatePasswordResetTokenInput {
-                user_id: user.id,
+                user_id,
                 token_hash,
                 expires_at,
             })
             .await?;
 
-        // Send the reset email
-        // The token is passed to the email template which builds the reset URL
-        // Errors are logged but NOT propagated to prevent email enumeration
-        if let Err(e) = self
-            .email_service
-            .send_password_reset(&input.email, &token, user.display_name.as_deref())
-            .await
-        {
-            tracing::error!("Failed to send password reset email: {}", e);
-        }
-
-        Ok(())
+        Ok(token)
     }
 
     /// Reset password using a token.
@@ -204,6 +229,8 @@ impl<
         if let Err(errors) = policy.validate_password(&input.new_password) {
             return Err(AppError::Validation(errors.join("; ")));
         }
+        self.check_password_deny_lists(preview_token.user_id, &input.new_password)
+            .await?;
 
         // Check if password has been found in a data breach (before claiming)
         let breach_warning = self
@@ -257,7 +284,11 @@ impl<
         // Send password changed notification (best-effort: password is already changed)
         let _ = self
             .email_service
-            .send_password_changed(&user.email, user.display_name.as_deref())
+            .send_password_changed(
+                &user.email,
+                user.display_name.as_deref(),
+                resolve_locale(user.locale.as_deref(), None, None),
+            )
             .await;
 
         self.execute_post_change_password_actions(&user).await;
@@ -287,6 +318,8 @@ impl<
         if let Err(errors) = policy.validate_password(&input.new_password) {
             return Err(AppError::Validation(errors.join("; ")));
         }
+        self.check_password_deny_lists(user_id, &input.new_password)
+            .await?;
 
         // Check if password has been found in a data breach
         let breach_warning = self
@@ -357,7 +390,11 @@ impl<
         // Send password changed notification (best-effort: password is already changed)
         let _ = self
             .email_service
-            .send_password_changed(&user.email, user.display_name.as_deref())
+            .send_password_changed(
+                &user.email,
+                user.display_name.as_deref(),
+                resolve_locale(user.locale.as_deref(), None, None),
+            )
             .await;
 
         self.execute_post_change_password_actions(&user).await;
@@ -386,6 +423,8 @@ impl<
         if let Err(errors) = policy.validate_password(&input.new_password) {
             return Err(AppError::Validation(errors.join("; ")));
         }
+        self.check_password_deny_lists(user_id, &input.new_password)
+            .await?;
 
         // Check if password has been found in a data breach
         let breach_warning = self
@@ -429,7 +468,11 @@ impl<
         // Send password changed notification (best-effort)
         let _ = self
             .email_service
-            .send_password_changed(&user.email, user.display_name.as_deref())
+            .send_password_changed(
+                &user.email,
+                user.display_name.as_deref(),
+                resolve_locale(user.locale.as_deref(), None, None),
+            )
             .await;
 
         self.execute_post_change_password_actions(&user).await;
@@ -555,6 +598,15 @@ impl<
         Ok(None)
     }
 
+    /// Check the new password against the common passwords and, when
+    /// configured, the deny lists of the user's tenants
+    async fn check_password_deny_lists(&self, user_id: StringUuid, password: &str) -> Result<()> {
+        match self.password_deny_list {
+            Some(ref svc) => svc.check_for_user(user_id, password).await,
+            None => check_common_password(password),
+        }
+    }
+
     /// Check if the new password matches any of the user's recent passwords.
     /// Returns an error if the password was recently used; Ok(()) otherwise.
     async fn check_password_history(
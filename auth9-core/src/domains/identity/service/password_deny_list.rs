@@ -0,0 +1,194 @@
+//! Password deny list management and checks
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::password_deny_list::{
+    is_common_password, PasswordDenyListSummary, TenantPasswordDenyList,
+};
+use crate::repository::password_deny_list::PasswordDenyListRepositoryImpl;
+use crate::repository::PasswordDenyListRepository;
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+const COMMON_PASSWORD_MESSAGE: &str =
+    "This password is too common. Please choose a different password.";
+const DENIED_TERM_MESSAGE: &str =
+    "This password contains a word your organization does not allow. Please choose a different password.";
+
+/// Reject passwords on the built-in common password list
+pub fn check_common_password(password: &str) -> Result<()> {
+    if is_common_password(password) {
+        return Err(AppError::Validation(COMMON_PASSWORD_MESSAGE.to_string()));
+    }
+    Ok(())
+}
+
+pub struct PasswordDenyListService {
+    repo: Arc<dyn PasswordDenyListRepository>,
+}
+
+impl PasswordDenyListService {
+    pub fn new(repo: Arc<dyn PasswordDenyListRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(PasswordDenyListRepositoryImpl::new(pool)))
+    }
+
+    pub async fn get(&self, tenant_id: StringUuid) -> Result<Option<PasswordDenyListSummary>> {
+        Ok(self.repo.find(tenant_id).await?.map(|list| list.summary()))
+    }
+
+    /// Replace the tenant's terms
+    pub async fn replace(
+        &self,
+        tenant_id: StringUuid,
+        terms: &[String],
+    ) -> Result<PasswordDenyListSummary> {
+        let list = TenantPasswordDenyList::build(tenant_id, terms, Utc::now())
+            .map_err(AppError::Validation)?;
+        self.repo.upsert(&list).await?;
+        Ok(list.summary())
+    }
+
+    pub async fn delete(&self, tenant_id: StringUuid) -> Result<()> {
+        if !self.repo.delete(tenant_id).await? {
+            return Err(AppError::NotFound(
+                "Tenant has no password deny list".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check a new password against the common passwords and the deny list
+    /// of the tenant it is set in
+    pub async fn check_for_tenant(
+        &self,
+        tenant_id: Option<StringUuid>,
+        password: &str,
+    ) -> Result<()> {
+        check_common_password(password)?;
+        let Some(tenant_id) = tenant_id else {
+            return Ok(());
+        };
+        if let Some(list) = self.repo.find(tenant_id).await? {
+            if list.matches(password) {
+                return Err(AppError::Validation(DENIED_TERM_MESSAGE.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a user's new password against the common passwords and the deny
+    /// lists of all the user's tenants
+    pub async fn check_for_user(&self, user_id: StringUuid, password: &str) -> Result<()> {
+        check_common_password(password)?;
+        let lists = self.repo.list_for_user(user_id).await?;
+        if lists.iter().any(|list| list.matches(password)) {
+            return Err(AppError::Validation(DENIED_TERM_MESSAGE.to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::password_deny_list::MockPasswordDenyListRepository;
+    use mockall::predicate::*;
+
+    fn acme_list(tenant_id: StringUuid) -> TenantPasswordDenyList {
+        TenantPasswordDenyList::build(tenant_id, &["Acme".to_string()], Utc::now()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_check_for_tenant_merges_common_and_tenant_terms() {
+        let tenant_id = StringUuid::new_v4();
+        let mut repo = MockPasswordDenyListRepository::new();
+        repo.expect_find()
+            .with(eq(tenant_id))
+            .returning(move |id| Ok(Some(acme_list(id))));
+        let service = PasswordDenyListService::new(Arc::new(repo));
+
+        let common = service
+            .check_for_tenant(Some(tenant_id), "Password123!")
+            .await;
+        assert!(matches!(common, Err(AppError::Validation(m)) if m == COMMON_PASSWORD_MESSAGE));
+        let denied = service
+            .check_for_tenant(Some(tenant_id), "Welcome2Acme!!")
+            .await;
+        assert!(matches!(denied, Err(AppError::Validation(m)) if m == DENIED_TERM_MESSAGE));
+        service
+            .check_for_tenant(Some(tenant_id), "Tr0ub4dor&3horse")
+            .await
+            .unwrap();
+        // Without a tenant only the common passwords apply
+        service
+            .check_for_tenant(None, "Welcome2Acme!!")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_for_user_uses_all_tenants() {
+        let user_id = StringUuid::new_v4();
+        let mut repo = MockPasswordDenyListRepository::new();
+        repo.expect_list_for_user()
+            .with(eq(user_id))
+            .returning(|_| {
+                Ok(vec![
+                    TenantPasswordDenyList::build(
+                        StringUuid::new_v4(),
+                        &["Globex".to_string()],
+                        Utc::now(),
+                    )
+                    .unwrap(),
+                    acme_list(StringUuid::new_v4()),
+                ])
+            });
+        let service = PasswordDenyListService::new(Arc::new(repo));
+
+        assert!(service
+            .check_for_user(user_id, "MyAcme#Login9")
+            .await
+            .is_err());
+        assert!(service
+            .check_for_user(user_id, "Gl0bex-Rules-42")
+            .await
+            .is_err());
+        service
+            .check_for_user(user_id, "Tr0ub4dor&3horse")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replace_validates_and_stores() {
+        let tenant_id = StringUuid::new_v4();
+        let mut repo = MockPasswordDenyListRepository::new();
+        repo.expect_upsert()
+            .withf(move |list| list.tenant_id == tenant_id && list.term_count == 2)
+            .times(1)
+            .returning(|_| Ok(()));
+        let service = PasswordDenyListService::new(Arc::new(repo));
+
+        let summary = service
+            .replace(
+                tenant_id,
+                &[
+                    "Acme".to_string(),
+                    "ACME".to_string(),
+                    "Roadrunner".to_string(),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary.term_count, 2);
+        assert!(summary.size_bytes > 0);
+
+        let invalid = service.replace(tenant_id, &["ab".to_string()]).await;
+        assert!(matches!(invalid, Err(AppError::Validation(_))));
+    }
+}
@@ -0,0 +1,472 @@
+//! Progressive profiling service — collect profile fields services require per scope
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::released_claims;
+use crate::models::progressive_profiling::{
+    ProfileFormField, ProfileFormResponse, ProfileRequirement, SetProfileRequirementsInput,
+    SubmitProfileInput, FIELD_DISPLAY_NAME, MAX_ATTRIBUTE_VALUE_LEN,
+};
+use crate::models::user::User;
+use crate::repository::ProgressiveProfilingRepository;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use validator::Validate;
+
+/// Progressive profiling service
+pub struct ProgressiveProfilingService<R: ProgressiveProfilingRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: ProgressiveProfilingRepository> ProgressiveProfilingService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_requirements(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Vec<ProfileRequirement>> {
+        self.repo.list_requirements(service_id).await
+    }
+
+    /// Replace the requirements of a service
+    pub async fn set_requirements(
+        &self,
+        service_id: StringUuid,
+        input: SetProfileRequirementsInput,
+    ) -> Result<Vec<ProfileRequirement>> {
+        input.validate()?;
+
+        let mut seen = HashSet::new();
+        for requirement in &input.requirements {
+            if !seen.insert((requirement.scope.as_str(), requirement.field_name.as_str())) {
+                return Err(AppError::BadRequest(format!(
+                    "Duplicate requirement '{}' for scope '{}'",
+                    requirement.field_name, requirement.scope
+                )));
+            }
+        }
+
+        self.repo
+            .replace_requirements(service_id, &input.requirements)
+            .await
+    }
+
+    /// Requirements that apply to a scope string, one per field name
+    async fn applicable_requirements(
+        &self,
+        service_id: StringUuid,
+        scope: &str,
+    ) -> Result<Vec<ProfileRequirement>> {
+        let mut seen = HashSet::new();
+        Ok(self
+            .repo
+            .list_requirements(service_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.applies_to(scope))
+            .filter(|r| seen.insert(r.field_name.clone()))
+            .collect())
+    }
+
+    async fn current_values(
+        &self,
+        user_id: StringUuid,
+        display_name: Option<&str>,
+    ) -> Result<HashMap<String, String>> {
+        let mut values: HashMap<String, String> = self
+            .repo
+            .get_user_attributes(user_id)
+            .await?
+            .into_iter()
+            .map(|a| (a.attr_key, a.attr_value))
+            .collect();
+        if let Some(name) = display_name {
+            values.insert(FIELD_DISPLAY_NAME.to_string(), name.to_string());
+        }
+        values.retain(|_, v| !v.trim().is_empty());
+        Ok(values)
+    }
+
+    /// Field names the user still has to provide before a token can be issued
+    /// for `scope`. Returns an empty list without touching user attributes when
+    /// the service declares no applicable requirement.
+    pub async fn missing_fields(
+        &self,
+        service_id: StringUuid,
+        scope: &str,
+        user_id: StringUuid,
+        display_name: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let requirements = self.applicable_requirements(service_id, scope).await?;
+        if requirements.is_empty() {
+            return Ok(vec![]);
+        }
+        let values = self.current_values(user_id, display_name).await?;
+        Ok(requirements
+            .into_iter()
+            .filter(|r| !values.contains_key(&r.field_name))
+            .map(|r| r.field_name)
+            .collect())
+    }
+
+    /// Build the hosted form for a client and scope
+    pub async fn profile_form(
+        &self,
+        service_id: StringUuid,
+        client_id: &str,
+        scope: &str,
+        user: &User,
+    ) -> Result<ProfileFormResponse> {
+        let requirements = self.applicable_requirements(service_id, scope).await?;
+        let values = self
+            .current_values(user.id, user.display_name.as_deref())
+            .await?;
+
+        let fields: Vec<ProfileFormField> = requirements
+            .into_iter()
+            .map(|r| ProfileFormField {
+                value: values.get(&r.field_name).cloned(),
+                field_name: r.field_name,
+                label: r.label,
+            })
+            .collect();
+        let missing_fields = fields
+            .iter()
+            .filter(|f| f.value.is_none())
+            .map(|f| f.field_name.clone())
+            .collect();
+
+        Ok(ProfileFormResponse {
+            client_id: client_id.to_string(),
+            scope: scope.to_string(),
+            fields,
+            missing_fields,
+        })
+    }
+
+    /// Store submitted attributes on the user.
+    ///
+    /// Only fields requested for the scope are accepted. Custom attributes are
+    /// persisted here; a submitted `display_name` is returned for the caller to
+    /// write to the user record.
+    pub async fn submit(
+        &self,
+        service_id: StringUuid,
+        user: &User,
+        input: SubmitProfileInput,
+    ) -> Result<Option<String>> {
+        input.validate()?;
+
+        let requested: HashSet<String> = self
+            .applicable_requirements(service_id, &input.scope)
+            .await?
+            .into_iter()
+            .map(|r| r.field_name)
+            .collect();
+
+        let mut display_name = None;
+        let mut attributes = HashMap::new();
+        for (key, value) in input.attributes {
+            if !requested.contains(&key) {
+                return Err(AppError::BadRequest(format!(
+                    "Field '{}' is not requested by this application",
+                    key
+                )));
+            }
+            let value = value.trim();
+            if value.is_empty() || value.len() > MAX_ATTRIBUTE_VALUE_LEN {
+                return Err(AppError::BadRequest(format!(
+                    "Field '{}' must be between 1 and {} characters",
+                    key, MAX_ATTRIBUTE_VALUE_LEN
+                )));
+            }
+            if key == FIELD_DISPLAY_NAME {
+                display_name = Some(value.to_string());
+            } else {
+                attributes.insert(key, value.to_string());
+            }
+        }
+
+        if !attributes.is_empty() {
+            self.repo
+                .upsert_user_attributes(user.id, &attributes)
+                .await?;
+        }
+        Ok(display_name)
+    }
+
+    /// OIDC claims of the user released by `scope`, for ID tokens and
+    /// userinfo. Built-in claims come from the user record, the rest from
+    /// collected attributes of the same name; `custom` maps a tenant's custom
+    /// scopes to the attributes they release.
+    pub async fn scoped_claims(
+        &self,
+        user: &User,
+        scope: &str,
+        custom: &BTreeMap<String, Vec<String>>,
+    ) -> Result<Map<String, Value>> {
+        let released = released_claims(scope, custom);
+        let mut claims = Map::new();
+        let builtin = [
+            ("email", Some(Value::from(user.email.clone()))),
+            ("name", user.display_name.clone().map(Value::from)),
+            ("picture", user.avatar_url.clone().map(Value::from)),
+            ("locale", user.locale.clone().map(Value::from)),
+            ("updated_at", Some(Value::from(user.updated_at.timestamp()))),
+        ];
+        for (name, value) in builtin {
+            if let Some(value) = value.filter(|_| released.contains(name)) {
+                claims.insert(name.to_string(), value);
+            }
+        }
+
+        if released.iter().any(|name| !claims.contains_key(name)) {
+            for attribute in self.repo.get_user_attributes(user.id).await? {
+                if !released.contains(&attribute.attr_key)
+                    || claims.contains_key(&attribute.attr_key)
+                    || attribute.attr_value.trim().is_empty()
+                {
+                    continue;
+                }
+                // The standard address claim is a structured object
+                let value = if attribute.attr_key == "address" {
+                    serde_json::json!({ "formatted": attribute.attr_value })
+                } else {
+                    Value::from(attribute.attr_value)
+                };
+                claims.insert(attribute.attr_key, value);
+            }
+        }
+        Ok(claims)
+    }
+}
+
+#[cfg(test)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::progressive_profiling::{ProfileRequirementInput, UserProfileAttribute};
+    use crate::repository::progressive_profiling::MockProgressiveProfilingRepository;
+    use chrono::Utc;
+
+    fn requirement(service_id: StringUuid, scope: &str, field: &str) -> ProfileRequirement {
+        ProfileRequirement {
+            id: StringUuid::new_v4(),
+            service_id,
+            scope: scope.to_string(),
+            field_name: field.to_string(),
+            label: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn attribute(user_id: StringUuid, key: &str, value: &str) -> UserProfileAttribute {
+        UserProfileAttribute {
+            user_id,
+            attr_key: key.to_string(),
+            attr_value: value.to_string(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_fields_skips_attribute_lookup_without_requirements() {
+        let service_id = StringUuid::new_v4();
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_list_requirements()
+            .returning(move |_| Ok(vec![requirement(service_id, "billing", "company")]));
+        repo.expect_get_user_attributes().never();
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let missing = service
+            .missing_fields(service_id, "openid email", StringUuid::new_v4(), None)
+            .await
+            .unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_fields_checks_display_name_and_attributes() {
+        let service_id = StringUuid::new_v4();
+        let user_id = StringUuid::new_v4();
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_list_requirements().returning(move |_| {
+            Ok(vec![
+                requirement(service_id, "*", "display_name"),
+                requirement(service_id, "billing", "company"),
+                requirement(service_id, "billing", "vat_id"),
+            ])
+        });
+        repo.expect_get_user_attributes()
+            .returning(move |_| Ok(vec![attribute(user_id, "company", "Acme")]));
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let missing = service
+            .missing_fields(service_id, "openid billing", user_id, None)
+            .await
+            .unwrap();
+        assert_eq!(missing, vec!["display_name", "vat_id"]);
+    }
+
+    #[tokio::test]
+    async fn test_profile_form_prefills_known_values() {
+        let service_id = StringUuid::new_v4();
+        let user = User {
+            display_name: Some("Jane".to_string()),
+            ..Default::default()
+        };
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_list_requirements().returning(move |_| {
+            Ok(vec![
+                requirement(service_id, "*", "display_name"),
+                requirement(service_id, "*", "company"),
+            ])
+        });
+        repo.expect_get_user_attributes().returning(|_| Ok(vec![]));
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let form = service
+            .profile_form(service_id, "app", "openid", &user)
+            .await
+            .unwrap();
+        assert_eq!(form.fields.len(), 2);
+        assert_eq!(form.fields[0].value.as_deref(), Some("Jane"));
+        assert_eq!(form.missing_fields, vec!["company"]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_stores_attributes_and_returns_display_name() {
+        let service_id = StringUuid::new_v4();
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_list_requirements().returning(move |_| {
+            Ok(vec![
+                requirement(service_id, "*", "display_name"),
+                requirement(service_id, "*", "company"),
+            ])
+        });
+        repo.expect_upsert_user_attributes()
+            .withf(|_, attrs| attrs.len() == 1 && attrs.get("company").unwrap() == "Acme")
+            .returning(|_, _| Ok(()));
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let input = SubmitProfileInput {
+            client_id: "app".to_string(),
+            scope: "openid".to_string(),
+            attributes: HashMap::from([
+                ("display_name".to_string(), " Jane ".to_string()),
+                ("company".to_string(), "Acme".to_string()),
+            ]),
+        };
+        let display_name = service
+            .submit(service_id, &User::default(), input)
+            .await
+            .unwrap();
+        assert_eq!(display_name.as_deref(), Some("Jane"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_unrequested_field() {
+        let service_id = StringUuid::new_v4();
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_list_requirements()
+            .returning(move |_| Ok(vec![requirement(service_id, "*", "company")]));
+        repo.expect_upsert_user_attributes().never();
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let input = SubmitProfileInput {
+            client_id: "app".to_string(),
+            scope: "openid".to_string(),
+            attributes: HashMap::from([("salary".to_string(), "1".to_string())]),
+        };
+        let result = service.submit(service_id, &User::default(), input).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_requirements_rejects_duplicates() {
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_replace_requirements().never();
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let requirement = ProfileRequirementInput {
+            scope: "*".to_string(),
+            field_name: "company".to_string(),
+            label: None,
+        };
+        let input = SetProfileRequirementsInput {
+            requirements: vec![requirement.clone(), requirement],
+        };
+        let result = service.set_requirements(StringUuid::new_v4(), input).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scoped_claims_without_claim_scopes_skip_attribute_lookup() {
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_get_user_attributes().never();
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let claims = service
+            .scoped_claims(&User::default(), "openid invoices:read", &BTreeMap::new())
+            .await
+            .unwrap();
+        assert!(claims.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scoped_claims_release_builtin_claims_by_scope() {
+        let user = User {
+            email: "jane@example.com".to_string(),
+            display_name: Some("Jane".to_string()),
+            ..Default::default()
+        };
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_get_user_attributes().returning(|_| Ok(vec![]));
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let claims = service
+            .scoped_claims(&user, "openid email", &BTreeMap::new())
+            .await
+            .unwrap();
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims["email"], "jane@example.com");
+
+        let claims = service
+            .scoped_claims(&user, "openid profile", &BTreeMap::new())
+            .await
+            .unwrap();
+        assert_eq!(claims["name"], "Jane");
+        assert!(claims.contains_key("updated_at"));
+        assert!(!claims.contains_key("email"));
+    }
+
+    #[tokio::test]
+    async fn test_scoped_claims_release_attributes_by_scope() {
+        let user = User::default();
+        let user_id = user.id;
+        let mut repo = MockProgressiveProfilingRepository::new();
+        repo.expect_get_user_attributes().returning(move |_| {
+            Ok(vec![
+                attribute(user_id, "phone_number", "+1 555 0100"),
+                attribute(user_id, "address", "1 Main St"),
+                attribute(user_id, "vat_id", "DE1"),
+                attribute(user_id, "company", "Acme"),
+            ])
+        });
+        let custom = BTreeMap::from([("billing:profile".to_string(), vec!["vat_id".to_string()])]);
+
+        let service = ProgressiveProfilingService::new(Arc::new(repo));
+        let claims = service
+            .scoped_claims(&user, "openid phone address billing:profile", &custom)
+            .await
+            .unwrap();
+        assert_eq!(claims["phone_number"], "+1 555 0100");
+        assert_eq!(claims["address"]["formatted"], "1 Main St");
+        assert_eq!(claims["vat_id"], "DE1");
+        assert!(!claims.contains_key("company"));
+        assert!(!claims.contains_key("email"));
+    }
+}
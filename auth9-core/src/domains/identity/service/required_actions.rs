@@ -15,6 +15,10 @@ pub const ACTION_VERIFY_EMAIL: &str = "verify_email";
 pub const ACTION_UPDATE_PASSWORD: &str = "update_password"; // pragma: allowlist secret
 pub const ACTION_COMPLETE_PROFILE: &str = "complete_profile";
 pub const ACTION_CONFIGURE_TOTP: &str = "CONFIGURE_TOTP";
+/// Offered at login while the account is scheduled for deletion
+pub const ACTION_RESTORE_ACCOUNT: &str = "restore_account";
+/// Offered at login while a tenant's MFA enrollment campaign waits on the user
+pub const ACTION_ENROLL_MFA: &str = "enroll_mfa";
 
 /// Response object for a pending action with its redirect URL.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -24,6 +28,28 @@ pub struct PendingActionResponse {
     pub redirect_url: String,
 }
 
+impl PendingActionResponse {
+    /// Restore prompt for an account pending deletion; not stored in the
+    /// identity engine
+    pub fn restore_account() -> Self {
+        Self {
+            id: "account_deletion".to_string(),
+            action_type: ACTION_RESTORE_ACCOUNT.to_string(),
+            redirect_url: RequiredActionService::action_redirect_url(ACTION_RESTORE_ACCOUNT),
+        }
+    }
+
+    /// MFA enrollment reminder of a tenant campaign; deadlines are listed by
+    /// `GET /api/v1/users/me/mfa-enrollment`
+    pub fn enroll_mfa() -> Self {
+        Self {
+            id: "mfa_enrollment".to_string(),
+            action_type: ACTION_ENROLL_MFA.to_string(),
+            redirect_url: RequiredActionService::action_redirect_url(ACTION_ENROLL_MFA),
+        }
+    }
+}
+
 pub struct RequiredActionService {
     identity_engine: Arc<dyn IdentityEngine>,
 }
@@ -166,6 +192,8 @@ impl RequiredActionService {
             ACTION_UPDATE_PASSWORD => "/force-update-password".to_string(),
             ACTION_COMPLETE_PROFILE => "/complete-profile".to_string(),
             ACTION_CONFIGURE_TOTP => "/mfa/setup-totp".to_string(),
+            ACTION_RESTORE_ACCOUNT => "/restore-account".to_string(),
+            ACTION_ENROLL_MFA => "/dashboard/account/mfa".to_string(),
             other => format!("/pending-action?type={}", other),
         }
     }
@@ -495,6 +523,14 @@ mod tests {
             RequiredActionService::action_redirect_url(ACTION_CONFIGURE_TOTP),
             "/mfa/setup-totp"
         );
+        assert_eq!(
+            RequiredActionService::action_redirect_url(ACTION_RESTORE_ACCOUNT),
+            "/restore-account"
+        );
+        assert_eq!(
+            RequiredActionService::action_redirect_url(ACTION_ENROLL_MFA),
+            "/dashboard/account/mfa"
+        );
     }
 
     #[test]
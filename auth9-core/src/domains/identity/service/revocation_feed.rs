@@ -0,0 +1,159 @@
+//! Session revocation feed for relying parties
+//!
+//! Revoked sessions are appended by the domain event subscriber; this
+//! service serves the catch-up reads and purges notices past
+//! `REVOCATION_FEED_RETENTION_DAYS`.
+
+use crate::config::RevocationFeedConfig;
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::session_revocation::{RevocationFeedPage, MAX_REVOCATION_PAGE};
+use crate::repository::session_revocation::SessionRevocationRepositoryImpl;
+use crate::repository::SessionRevocationRepository;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+/// Page size when the caller gives none
+const DEFAULT_PAGE_SIZE: i64 = 100;
+
+pub struct RevocationFeedService<R: SessionRevocationRepository> {
+    repo: Arc<R>,
+    config: RevocationFeedConfig,
+}
+
+impl RevocationFeedService<SessionRevocationRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool, config: &RevocationFeedConfig) -> Self {
+        Self::new(
+            Arc::new(SessionRevocationRepositoryImpl::new(pool)),
+            config.clone(),
+        )
+    }
+}
+
+impl<R: SessionRevocationRepository> RevocationFeedService<R> {
+    pub fn new(repo: Arc<R>, config: RevocationFeedConfig) -> Self {
+        Self { repo, config }
+    }
+
+    /// Notices after `since`, limited to the tenant's members when given
+    pub async fn page(
+        &self,
+        since: i64,
+        tenant_id: Option<StringUuid>,
+        limit: Option<i64>,
+    ) -> Result<RevocationFeedPage> {
+        let since = since.max(0);
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_REVOCATION_PAGE);
+
+        // One extra row tells whether another page follows
+        let mut revocations = self.repo.list_since(since, tenant_id, limit + 1).await?;
+        let has_more = revocations.len() as i64 > limit;
+        revocations.truncate(limit as usize);
+
+        let gap = match self.repo.oldest_sequence().await? {
+            Some(oldest) => since < oldest - 1,
+            None => false,
+        };
+        Ok(RevocationFeedPage {
+            next_since: revocations.last().map_or(since, |n| n.sequence),
+            revocations,
+            has_more,
+            gap,
+        })
+    }
+
+    /// Delete notices older than the retention period
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64> {
+        self.repo
+            .delete_before(now - Duration::days(self.config.retention_days as i64))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session_revocation::{RevocationNotice, SessionRevocationReason};
+    use crate::repository::session_revocation::MockSessionRevocationRepository;
+    use mockall::predicate::*;
+
+    fn notice(sequence: i64) -> RevocationNotice {
+        RevocationNotice {
+            sequence,
+            session_id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            reason: SessionRevocationReason::UserRequest,
+            revoked_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_page_reports_more_and_next_since() {
+        let tenant_id = StringUuid::new_v4();
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_list_since()
+            .with(eq(10), eq(Some(tenant_id)), eq(3))
+            .returning(|_, _, _| Ok(vec![notice(11), notice(12), notice(15)]));
+        repo.expect_oldest_sequence().returning(|| Ok(Some(1)));
+
+        let page = RevocationFeedService::new(Arc::new(repo), Default::default())
+            .page(10, Some(tenant_id), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(page.revocations.len(), 2);
+        assert!(page.has_more);
+        assert_eq!(page.next_since, 12);
+        assert!(!page.gap);
+    }
+
+    #[tokio::test]
+    async fn test_empty_page_keeps_since() {
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_list_since().returning(|_, _, _| Ok(vec![]));
+        repo.expect_oldest_sequence().returning(|| Ok(None));
+
+        let page = RevocationFeedService::new(Arc::new(repo), Default::default())
+            .page(42, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.next_since, 42);
+        assert!(!page.has_more);
+        assert!(!page.gap);
+    }
+
+    #[tokio::test]
+    async fn test_page_flags_purged_notices() {
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_list_since()
+            .returning(|_, _, _| Ok(vec![notice(100)]));
+        repo.expect_oldest_sequence().returning(|| Ok(Some(100)));
+
+        let page = RevocationFeedService::new(Arc::new(repo), Default::default())
+            .page(5, None, None)
+            .await
+            .unwrap();
+        assert!(page.gap);
+    }
+
+    #[tokio::test]
+    async fn test_purge_uses_retention() {
+        let now = Utc::now();
+        let mut repo = MockSessionRevocationRepository::new();
+        repo.expect_delete_before()
+            .withf(move |before| *before == now - Duration::days(7))
+            .returning(|_| Ok(3));
+        let config = RevocationFeedConfig {
+            retention_days: 7,
+            ..Default::default()
+        };
+
+        let purged = RevocationFeedService::new(Arc::new(repo), config)
+            .purge_expired(now)
+            .await
+            .unwrap();
+        assert_eq!(purged, 3);
+    }
+}
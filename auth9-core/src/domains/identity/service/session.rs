@@ -1,24 +1,42 @@
 //! Session management business logic
 
-use crate::domains::integration::service::WebhookEventPublisher;
+use crate::cache::CacheOperations;
+use crate::domains::events::{DomainEvent, EventBus};
+use crate::domains::security_observability::service::GeoIpService;
 use crate::error::{AppError, Result};
 use crate::identity_engine::IdentitySessionStore;
-use crate::models::analytics::WebhookEvent;
 use crate::models::common::StringUuid;
-use crate::models::session::{parse_user_agent, CreateSessionInput, Session, SessionInfo};
+use crate::models::session::{
+    decode_session_cursor, encode_session_cursor, parse_user_agent, CreateSessionInput, Session,
+    SessionCreateOutcome, SessionExportPage, SessionInfo, SessionLimit, SessionLimitAction,
+    SessionLimitPolicy, MAX_SESSION_EXPORT_PAGE,
+};
+use crate::models::session_revocation::SessionRevocationReason;
 use crate::repository::{SessionRepository, UserRepository};
-use chrono::Utc;
+use async_trait::async_trait;
 use std::sync::Arc;
-
-/// Maximum number of concurrent sessions allowed per user.
-/// When this limit is exceeded, the oldest session is automatically revoked.
-const MAX_SESSIONS_PER_USER: i64 = 10;
+use std::time::Duration;
+
+/// How long the per-user session limit lock lives if its holder dies
+const SESSION_LIMIT_LOCK_TTL_MS: u64 = 5_000;
+/// How long a login waits for a parallel login of the same user
+const SESSION_LIMIT_LOCK_WAIT: Duration = Duration::from_secs(2);
+const SESSION_LIMIT_LOCK_RETRY: Duration = Duration::from_millis(50);
+
+/// Told about each session a sign-in creates, e.g. to email the user
+#[async_trait]
+pub trait SignInNotifier: Send + Sync {
+    async fn session_created(&self, session: &Session);
+}
 
 pub struct SessionService<S: SessionRepository, U: UserRepository> {
     session_repo: Arc<S>,
     user_repo: Arc<U>,
     identity_sessions: Arc<dyn IdentitySessionStore>,
-    webhook_publisher: Option<Arc<dyn WebhookEventPublisher>>,
+    event_bus: Option<Arc<EventBus>>,
+    geoip: Option<Arc<GeoIpService>>,
+    cache: Option<Arc<dyn CacheOperations>>,
+    sign_in_notifier: Option<Arc<dyn SignInNotifier>>,
 }
 
 impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
@@ -26,20 +44,42 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
         session_repo: Arc<S>,
         user_repo: Arc<U>,
         identity_sessions: Arc<dyn IdentitySessionStore>,
-        webhook_publisher: Option<Arc<dyn WebhookEventPublisher>>,
+        event_bus: Option<Arc<EventBus>>,
     ) -> Self {
         Self {
             session_repo,
             user_repo,
             identity_sessions,
-            webhook_publisher,
+            event_bus,
+            geoip: None,
+            cache: None,
+            sign_in_notifier: None,
         }
     }
 
+    /// Resolve session locations from the client IP
+    pub fn with_geoip(mut self, geoip: Arc<GeoIpService>) -> Self {
+        self.geoip = Some(geoip);
+        self
+    }
+
+    /// Serialize session creation per user across instances through Redis
+    pub fn with_cache(mut self, cache: Arc<dyn CacheOperations>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Notify about new sessions in the background, without delaying login
+    pub fn with_sign_in_notifier(mut self, notifier: Arc<dyn SignInNotifier>) -> Self {
+        self.sign_in_notifier = Some(notifier);
+        self
+    }
+
     /// Create a new session after login.
     ///
-    /// Enforces session concurrency limit: if the user has MAX_SESSIONS_PER_USER or more
-    /// active sessions, the oldest session is automatically revoked before creating the new one.
+    /// Enforces the user's concurrent session limit (the strictest
+    /// `session_limit` among their tenants). Login flows calling this cannot
+    /// ask the user, so a `prompt` policy evicts the oldest sessions here.
     pub async fn create_session(
         &self,
         user_id: StringUuid,
@@ -47,32 +87,57 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
         ip_address: Option<String>,
         user_agent: Option<String>,
     ) -> Result<Session> {
-        // Enforce session concurrency limit
-        let active_count = self.session_repo.count_active_by_user(user_id).await?;
-        if active_count >= MAX_SESSIONS_PER_USER {
-            // Revoke the oldest session to make room for the new one
-            if let Some(oldest_session) = self
-                .session_repo
-                .find_oldest_active_by_user(user_id)
-                .await?
-            {
-                // Revoke in the identity backend if session ID exists
-                if let Some(provider_session_id) = &oldest_session.provider_session_id {
-                    let _ = self
-                        .identity_sessions
-                        .delete_user_session(provider_session_id)
-                        .await;
-                }
-                // Revoke in database
-                let _ = self.session_repo.revoke(oldest_session.id).await;
+        self.create_limited_session(user_id, provider_session_id, ip_address, user_agent, None)
+            .await
+    }
 
-                tracing::info!(
-                    user_id = %user_id,
-                    session_id = %oldest_session.id,
-                    "Revoked oldest session due to session limit"
-                );
-            }
-        }
+    /// Create a new session in a login flow that can ask the user which
+    /// sessions to end. Under a `prompt` policy at the limit this fails with
+    /// [`AppError::SessionLimitReached`] listing the active sessions; the
+    /// client retries with the chosen ones in `revoke_session_ids`.
+    pub async fn create_session_with_prompt(
+        &self,
+        user_id: StringUuid,
+        provider_session_id: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        revoke_session_ids: Vec<StringUuid>,
+    ) -> Result<Session> {
+        self.create_limited_session(
+            user_id,
+            provider_session_id,
+            ip_address,
+            user_agent,
+            Some(revoke_session_ids),
+        )
+        .await
+    }
+
+    async fn create_limited_session(
+        &self,
+        user_id: StringUuid,
+        provider_session_id: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        prompt_choice: Option<Vec<StringUuid>>,
+    ) -> Result<Session> {
+        let policies = self
+            .session_repo
+            .list_session_limit_policies(user_id)
+            .await?;
+        let policy = SessionLimitPolicy::strictest(&policies);
+        let limit = SessionLimit {
+            max_sessions: policy.max_sessions,
+            evict_oldest: match policy.on_limit {
+                SessionLimitAction::EvictOldest => true,
+                SessionLimitAction::Prompt => prompt_choice.is_none(),
+                SessionLimitAction::DenyNew => false,
+            },
+            revoke_session_ids: match policy.on_limit {
+                SessionLimitAction::Prompt => prompt_choice.unwrap_or_default(),
+                _ => Vec::new(),
+            },
+        };
 
         let (device_type, device_name) = user_agent
             .as_ref()
@@ -84,12 +149,106 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
             provider_session_id,
             device_type,
             device_name,
+            location: ip_address
+                .as_deref()
+                .zip(self.geoip.as_ref())
+                .and_then(|(ip, geoip)| geoip.lookup(ip))
+                .map(|geo| geo.label()),
             ip_address,
-            location: None, // TODO: Implement IP geolocation
             user_agent,
         };
 
-        self.session_repo.create(&input).await
+        let lock_token = self.acquire_session_limit_lock(user_id).await;
+        let outcome = self.session_repo.create_within_limit(&input, &limit).await;
+        if let (Some(cache), Some(token)) = (&self.cache, &lock_token) {
+            if let Err(e) = cache
+                .release_session_limit_lock(&user_id.to_string(), token)
+                .await
+            {
+                tracing::warn!(user_id = %user_id, "Failed to release session limit lock: {}", e);
+            }
+        }
+
+        match outcome? {
+            SessionCreateOutcome::Created { session, revoked } => {
+                for old in &revoked {
+                    // Revoke in the identity backend if session ID exists
+                    if let Some(provider_session_id) = &old.provider_session_id {
+                        let _ = self
+                            .identity_sessions
+                            .delete_user_session(provider_session_id)
+                            .await;
+                    }
+                    tracing::info!(
+                        user_id = %user_id,
+                        session_id = %old.id,
+                        "Revoked session due to session limit"
+                    );
+                }
+                if !revoked.is_empty() {
+                    metrics::counter!("auth9_session_limit_evictions_total")
+                        .increment(revoked.len() as u64);
+                    self.publish_revoked(&revoked, SessionRevocationReason::SessionLimit)
+                        .await;
+                }
+                if let Some(notifier) = &self.sign_in_notifier {
+                    let notifier = notifier.clone();
+                    let created = session.clone();
+                    tokio::spawn(async move {
+                        notifier.session_created(&created).await;
+                    });
+                }
+                Ok(session)
+            }
+            SessionCreateOutcome::LimitReached { active } => {
+                metrics::counter!(
+                    "auth9_session_limit_rejections_total",
+                    "action" => policy.on_limit.as_str()
+                )
+                .increment(1);
+                tracing::info!(
+                    user_id = %user_id,
+                    active = active.len(),
+                    max_sessions = policy.max_sessions,
+                    action = policy.on_limit.as_str(),
+                    "Session limit reached"
+                );
+                Err(AppError::SessionLimitReached {
+                    max_sessions: policy.max_sessions,
+                    prompt: policy.on_limit == SessionLimitAction::Prompt,
+                    sessions: active.into_iter().map(SessionInfo::from).collect(),
+                })
+            }
+        }
+    }
+
+    /// Take the per-user lock so parallel logins on other instances queue
+    /// here instead of on the database row lock. Best effort: the limit is
+    /// enforced by the repository transaction either way.
+    async fn acquire_session_limit_lock(&self, user_id: StringUuid) -> Option<String> {
+        let cache = self.cache.as_ref()?;
+        let key = user_id.to_string();
+        let token = uuid::Uuid::new_v4().to_string();
+        let deadline = tokio::time::Instant::now() + SESSION_LIMIT_LOCK_WAIT;
+        loop {
+            match cache
+                .acquire_session_limit_lock(&key, &token, SESSION_LIMIT_LOCK_TTL_MS)
+                .await
+            {
+                Ok(true) => return Some(token),
+                Ok(false) if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(SESSION_LIMIT_LOCK_RETRY).await;
+                }
+                Ok(false) => {
+                    tracing::warn!(user_id = %user_id, "Timed out waiting for session limit lock");
+                    return None;
+                }
+                Err(e) => {
+                    tracing::warn!(user_id = %user_id, "Session limit lock unavailable: {}", e);
+                    return None;
+                }
+            }
+        }
     }
 
     /// Get sessions for the current user
@@ -142,43 +301,75 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
         // Mark session as revoked in our database
         self.session_repo.revoke(session_id).await?;
 
-        // Trigger session.revoked webhook event
-        if let Some(publisher) = &self.webhook_publisher {
-            if let Err(e) = publisher
-                .trigger_event(WebhookEvent {
-                    event_type: "session.revoked".to_string(),
-                    timestamp: Utc::now(),
-                    data: serde_json::json!({
-                        "session_id": session_id.to_string(),
-                        "user_id": user_id.to_string(),
-                        "device_type": session.device_type,
-                        "device_name": session.device_name,
-                    }),
-                })
-                .await
-            {
-                tracing::warn!("Failed to trigger session.revoked webhook event: {}", e);
-            }
-        }
+        self.publish_revoked(&[session], SessionRevocationReason::UserRequest)
+            .await;
 
         Ok(())
     }
 
+    /// Tell subscribers (webhooks, token blacklist, revocation feed) about
+    /// sessions that were just revoked
+    async fn publish_revoked(&self, sessions: &[Session], reason: SessionRevocationReason) {
+        let Some(bus) = &self.event_bus else {
+            return;
+        };
+        for session in sessions {
+            bus.publish(DomainEvent::SessionRevoked {
+                session_id: session.id,
+                user_id: session.user_id,
+                device_type: session.device_type.clone(),
+                device_name: session.device_name.clone(),
+                reason,
+            })
+            .await;
+        }
+    }
+
+    /// Find a session by ID (including revoked sessions)
+    pub async fn find_session(&self, session_id: StringUuid) -> Result<Option<Session>> {
+        self.session_repo.find_by_id(session_id).await
+    }
+
+    /// Load a session and verify it belongs to `user_id`
+    async fn owned_session(&self, session_id: StringUuid, user_id: StringUuid) -> Result<Session> {
+        let session = self
+            .session_repo
+            .find_by_id(session_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Session not found".to_string()))?;
+        if session.user_id != user_id {
+            return Err(AppError::Forbidden(
+                "Cannot manage another user's session".to_string(),
+            ));
+        }
+        Ok(session)
+    }
+
+    /// Give one of the user's devices a name, or clear it with `None`
+    pub async fn rename_session(
+        &self,
+        session_id: StringUuid,
+        user_id: StringUuid,
+        name: Option<String>,
+    ) -> Result<Session> {
+        self.owned_session(session_id, user_id).await?;
+        let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        self.session_repo.rename(session_id, name).await?;
+        self.owned_session(session_id, user_id).await
+    }
+
     /// Revoke all sessions except the current one
     pub async fn revoke_other_sessions(
         &self,
         user_id: StringUuid,
         current_session_id: StringUuid,
     ) -> Result<u64> {
-        // Get all active sessions
-        let sessions = self.session_repo.list_active_by_user(user_id).await?;
-
-        // Revoke each session in the identity backend (except current)
-        for session in sessions {
-            if session.id == current_session_id {
-                continue;
-            }
+        // Get all active sessions except the current one
+        let mut sessions = self.session_repo.list_active_by_user(user_id).await?;
+        sessions.retain(|session| session.id != current_session_id);
 
+        // Revoke each session in the identity backend
+        for session in &sessions {
             if let Some(provider_session_id) = &session.provider_session_id {
                 let _ = self
                     .identity_sessions
@@ -188,9 +379,32 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
         }
 
         // Revoke in database
-        self.session_repo
+        let revoked = self
+            .session_repo
             .revoke_all_except(user_id, current_session_id)
-            .await
+            .await?;
+        self.publish_revoked(&sessions, SessionRevocationReason::SignOutOthers)
+            .await;
+        Ok(revoked)
+    }
+
+    /// Sign the user out on every device, including the current one.
+    ///
+    /// Returns the sessions that were active so callers can invalidate their tokens.
+    pub async fn sign_out_everywhere(&self, user_id: StringUuid) -> Result<Vec<Session>> {
+        let sessions = self.session_repo.list_active_by_user(user_id).await?;
+        for session in &sessions {
+            if let Some(provider_session_id) = &session.provider_session_id {
+                let _ = self
+                    .identity_sessions
+                    .delete_user_session(provider_session_id)
+                    .await;
+            }
+        }
+        self.session_repo.revoke_all_by_user(user_id).await?;
+        self.publish_revoked(&sessions, SessionRevocationReason::SignOutEverywhere)
+            .await;
+        Ok(sessions)
     }
 
     /// Force logout a user (admin action)
@@ -208,8 +422,17 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
             .logout_user(&user.identity_subject)
             .await;
 
+        // Only looked up when someone listens for the revocations
+        let sessions = match &self.event_bus {
+            Some(_) => self.session_repo.list_active_by_user(user_id).await?,
+            None => Vec::new(),
+        };
+
         // Revoke all sessions in database regardless of identity engine status
-        self.session_repo.revoke_all_by_user(user_id).await
+        let revoked = self.session_repo.revoke_all_by_user(user_id).await?;
+        self.publish_revoked(&sessions, SessionRevocationReason::AdminForceLogout)
+            .await;
+        Ok(revoked)
     }
 
     /// Update session last active time
@@ -233,6 +456,40 @@ impl<S: SessionRepository, U: UserRepository> SessionService<S, U> {
         Ok(session_infos)
     }
 
+    /// One page of the sessions of a tenant's members, resuming after
+    /// `cursor`. Pages are read with a keyset scan, so exporting millions of
+    /// sessions never issues a large OFFSET query or holds more than one
+    /// page in memory.
+    pub async fn export_tenant_sessions(
+        &self,
+        tenant_id: StringUuid,
+        cursor: Option<&str>,
+        limit: i64,
+        active_only: bool,
+    ) -> Result<SessionExportPage> {
+        let after = cursor
+            .map(decode_session_cursor)
+            .transpose()
+            .map_err(AppError::BadRequest)?;
+        let limit = limit.clamp(1, MAX_SESSION_EXPORT_PAGE);
+
+        // One extra row tells whether another page follows
+        let mut sessions = self
+            .session_repo
+            .list_by_tenant_after(tenant_id, after, active_only, limit + 1)
+            .await?;
+        let next_cursor = if sessions.len() as i64 > limit {
+            sessions.truncate(limit as usize);
+            sessions.last().map(|s| encode_session_cursor(s.id))
+        } else {
+            None
+        };
+        Ok(SessionExportPage {
+            sessions,
+            next_cursor,
+        })
+    }
+
     /// Clean up old sessions
     pub async fn cleanup_old_sessions(&self, days: i64) -> Result<u64> {
         self.session_repo.delete_old(days).await
@@ -325,7 +582,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let sessions = service.get_user_sessions(user_id, None).await.unwrap();
@@ -356,7 +613,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let sessions = service
@@ -383,7 +640,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let result = service.revoke_session(session_id, user_id).await;
@@ -411,7 +668,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let result = service.revoke_session(session_id, user_id).await;
@@ -419,6 +676,107 @@ mod tests {
         assert!(matches!(result.unwrap_err(), AppError::Forbidden(_)));
     }
 
+    #[tokio::test]
+    async fn test_rename_session_trims_name() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_mock = MockUserRepository::new();
+        let user_id = StringUuid::new_v4();
+        let session_id = StringUuid::new_v4();
+
+        session_mock.expect_find_by_id().returning(move |_| {
+            Ok(Some(Session {
+                id: session_id,
+                user_id,
+                display_name: Some("Work laptop".to_string()),
+                ..Default::default()
+            }))
+        });
+        session_mock
+            .expect_rename()
+            .with(eq(session_id), eq(Some("Work laptop".to_string())))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(user_mock),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let session = service
+            .rename_session(session_id, user_id, Some("  Work laptop ".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(session.friendly_name(), "Work laptop");
+    }
+
+    #[tokio::test]
+    async fn test_rename_session_wrong_user() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_mock = MockUserRepository::new();
+        let other_user_id = StringUuid::new_v4();
+
+        session_mock.expect_find_by_id().returning(move |id| {
+            Ok(Some(Session {
+                id,
+                user_id: other_user_id,
+                ..Default::default()
+            }))
+        });
+        session_mock.expect_rename().never();
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(user_mock),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let result = service
+            .rename_session(StringUuid::new_v4(), StringUuid::new_v4(), None)
+            .await;
+        assert!(matches!(result.unwrap_err(), AppError::Forbidden(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_out_everywhere_revokes_all() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_mock = MockUserRepository::new();
+        let user_id = StringUuid::new_v4();
+
+        session_mock
+            .expect_list_active_by_user()
+            .with(eq(user_id))
+            .returning(move |_| {
+                Ok(vec![
+                    Session {
+                        user_id,
+                        ..Default::default()
+                    },
+                    Session {
+                        user_id,
+                        ..Default::default()
+                    },
+                ])
+            });
+        session_mock
+            .expect_revoke_all_by_user()
+            .with(eq(user_id))
+            .times(1)
+            .returning(|_| Ok(2));
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(user_mock),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let revoked = service.sign_out_everywhere(user_id).await.unwrap();
+        assert_eq!(revoked.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_update_last_active() {
         let mut session_mock = MockSessionRepository::new();
@@ -434,7 +792,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let result = service.update_last_active(session_id).await;
@@ -455,7 +813,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let count = service.cleanup_old_sessions(30).await.unwrap();
@@ -477,7 +835,7 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let result = service.get_user_sessions_admin(user_id).await;
@@ -524,33 +882,43 @@ mod tests {
             Arc::new(session_mock),
             Arc::new(user_mock),
             create_test_identity_sessions(),
-            None, // webhook_publisher
+            None, // event_bus
         );
 
         let sessions = service.get_user_sessions_admin(user_id).await.unwrap();
         assert_eq!(sessions.len(), 2);
     }
 
+    fn created(input: &CreateSessionInput, revoked: Vec<Session>) -> SessionCreateOutcome {
+        SessionCreateOutcome::Created {
+            session: Session {
+                user_id: input.user_id,
+                device_type: input.device_type.clone(),
+                ..Default::default()
+            },
+            revoked,
+        }
+    }
+
     #[tokio::test]
-    async fn test_create_session_under_limit() {
+    async fn test_create_session_uses_default_limit() {
         let mut session_mock = MockSessionRepository::new();
         let user_mock = MockUserRepository::new();
         let user_id = StringUuid::new_v4();
 
-        // User has only 5 sessions, under the limit of 10
+        // No tenant sets a limit: 10 sessions, evicting the oldest
         session_mock
-            .expect_count_active_by_user()
+            .expect_list_session_limit_policies()
             .with(eq(user_id))
-            .returning(|_| Ok(5));
-
-        // Should create session without revoking anything
-        session_mock.expect_create().returning(|input| {
-            Ok(Session {
-                user_id: input.user_id,
-                device_type: input.device_type.clone(),
-                ..Default::default()
+            .returning(|_| Ok(vec![]));
+        session_mock
+            .expect_create_within_limit()
+            .withf(|_, limit| {
+                limit.max_sessions == 10
+                    && limit.evict_oldest
+                    && limit.revoke_session_ids.is_empty()
             })
-        });
+            .returning(|input, _| Ok(created(input, vec![])));
 
         let service = SessionService::new(
             Arc::new(session_mock),
@@ -565,45 +933,68 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    struct ChannelNotifier(tokio::sync::mpsc::UnboundedSender<StringUuid>);
+
+    #[async_trait]
+    impl SignInNotifier for ChannelNotifier {
+        async fn session_created(&self, session: &Session) {
+            let _ = self.0.send(session.user_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_session_notifies_sign_in() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_id = StringUuid::new_v4();
+        session_mock
+            .expect_list_session_limit_policies()
+            .returning(|_| Ok(vec![]));
+        session_mock
+            .expect_create_within_limit()
+            .returning(|input, _| Ok(created(input, vec![])));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(MockUserRepository::new()),
+            create_test_identity_sessions(),
+            None,
+        )
+        .with_sign_in_notifier(Arc::new(ChannelNotifier(tx)));
+
+        service
+            .create_session(user_id, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await, Some(user_id));
+    }
+
     #[tokio::test]
     async fn test_create_session_at_limit_revokes_oldest() {
         let mut session_mock = MockSessionRepository::new();
         let user_mock = MockUserRepository::new();
         let user_id = StringUuid::new_v4();
-        let oldest_session_id = StringUuid::new_v4();
 
-        // User has 10 sessions, at the limit
         session_mock
-            .expect_count_active_by_user()
-            .with(eq(user_id))
-            .returning(|_| Ok(10));
-
-        // Should find and revoke oldest session
+            .expect_list_session_limit_policies()
+            .returning(|_| {
+                Ok(vec![SessionLimitPolicy {
+                    max_sessions: 2,
+                    on_limit: SessionLimitAction::EvictOldest,
+                }])
+            });
         session_mock
-            .expect_find_oldest_active_by_user()
-            .with(eq(user_id))
-            .returning(move |uid| {
-                Ok(Some(Session {
-                    id: oldest_session_id,
-                    user_id: uid,
+            .expect_create_within_limit()
+            .withf(|_, limit| limit.max_sessions == 2 && limit.evict_oldest)
+            .returning(|input, _| {
+                let oldest = Session {
+                    user_id: input.user_id,
                     provider_session_id: Some("old-kc-session".to_string()),
                     ..Default::default()
-                }))
+                };
+                Ok(created(input, vec![oldest]))
             });
 
-        session_mock
-            .expect_revoke()
-            .with(eq(oldest_session_id))
-            .returning(|_| Ok(()));
-
-        // Then create new session
-        session_mock.expect_create().returning(|input| {
-            Ok(Session {
-                user_id: input.user_id,
-                ..Default::default()
-            })
-        });
-
         let service = SessionService::new(
             Arc::new(session_mock),
             Arc::new(user_mock),
@@ -618,30 +1009,77 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_create_session_over_limit_no_oldest_found() {
+    async fn test_create_session_deny_new_at_limit() {
         let mut session_mock = MockSessionRepository::new();
         let user_mock = MockUserRepository::new();
         let user_id = StringUuid::new_v4();
 
-        // User has 12 sessions, over the limit
         session_mock
-            .expect_count_active_by_user()
-            .with(eq(user_id))
-            .returning(|_| Ok(12));
-
-        // No oldest session found (edge case - shouldn't happen in practice)
+            .expect_list_session_limit_policies()
+            .returning(|_| {
+                Ok(vec![SessionLimitPolicy {
+                    max_sessions: 1,
+                    on_limit: SessionLimitAction::DenyNew,
+                }])
+            });
         session_mock
-            .expect_find_oldest_active_by_user()
-            .with(eq(user_id))
-            .returning(|_| Ok(None));
+            .expect_create_within_limit()
+            .withf(|_, limit| !limit.evict_oldest)
+            .returning(|_, _| {
+                Ok(SessionCreateOutcome::LimitReached {
+                    active: vec![Session::default()],
+                })
+            });
 
-        // Should still create new session
-        session_mock.expect_create().returning(|input| {
-            Ok(Session {
-                user_id: input.user_id,
-                ..Default::default()
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(user_mock),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let result = service.create_session(user_id, None, None, None).await;
+        assert!(matches!(
+            result,
+            Err(AppError::SessionLimitReached {
+                max_sessions: 1,
+                prompt: false,
+                ..
             })
-        });
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_session_prompt_policy() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_mock = MockUserRepository::new();
+        let user_id = StringUuid::new_v4();
+        let chosen = StringUuid::new_v4();
+
+        session_mock
+            .expect_list_session_limit_policies()
+            .returning(|_| {
+                Ok(vec![SessionLimitPolicy {
+                    max_sessions: 3,
+                    on_limit: SessionLimitAction::Prompt,
+                }])
+            });
+        // Interactive login: no eviction, only the sessions the user chose
+        session_mock
+            .expect_create_within_limit()
+            .withf(move |_, limit| !limit.evict_oldest && limit.revoke_session_ids == vec![chosen])
+            .times(1)
+            .returning(|_, _| {
+                Ok(SessionCreateOutcome::LimitReached {
+                    active: vec![Session::default(); 3],
+                })
+            });
+        // Unattended login (e.g. social) falls back to evicting the oldest
+        session_mock
+            .expect_create_within_limit()
+            .withf(|_, limit| limit.evict_oldest && limit.revoke_session_ids.is_empty())
+            .times(1)
+            .returning(|input, _| Ok(created(input, vec![])));
 
         let service = SessionService::new(
             Arc::new(session_mock),
@@ -651,12 +1089,136 @@ mod tests {
         );
 
         let result = service
-            .create_session(user_id, Some("kc-123".to_string()), None, None)
+            .create_session_with_prompt(user_id, None, None, None, vec![chosen])
             .await;
-        assert!(result.is_ok());
+        match result {
+            Err(AppError::SessionLimitReached {
+                prompt: true,
+                sessions,
+                ..
+            }) => assert_eq!(sessions.len(), 3),
+            other => panic!(
+                "expected session limit prompt, got {:?}",
+                other.map(|s| s.id)
+            ),
+        }
+
+        assert!(service
+            .create_session(user_id, None, None, None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_session_takes_and_releases_lock() {
+        let mut session_mock = MockSessionRepository::new();
+        let user_mock = MockUserRepository::new();
+        let user_id = StringUuid::new_v4();
+        session_mock
+            .expect_list_session_limit_policies()
+            .returning(|_| Ok(vec![]));
+        session_mock
+            .expect_create_within_limit()
+            .returning(|input, _| Ok(created(input, vec![])));
+
+        let mut cache = crate::cache::MockCacheOperations::new();
+        cache
+            .expect_acquire_session_limit_lock()
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+        cache
+            .expect_release_session_limit_lock()
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(user_mock),
+            create_test_identity_sessions(),
+            None,
+        )
+        .with_cache(Arc::new(cache));
+
+        assert!(service
+            .create_session(user_id, None, None, None)
+            .await
+            .is_ok());
     }
 
     fn create_test_identity_sessions() -> Arc<dyn IdentitySessionStore> {
         Arc::new(Auth9OidcSessionStoreAdapter::new())
     }
+
+    #[tokio::test]
+    async fn test_export_tenant_sessions_returns_cursor_when_more_remain() {
+        let mut session_mock = MockSessionRepository::new();
+        let tenant_id = StringUuid::new_v4();
+        session_mock
+            .expect_list_by_tenant_after()
+            .withf(move |t, after, active_only, limit| {
+                *t == tenant_id && after.is_none() && *active_only && *limit == 3
+            })
+            .returning(|_, _, _, _| Ok(vec![Session::default(); 3]));
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(MockUserRepository::new()),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let page = service
+            .export_tenant_sessions(tenant_id, None, 2, true)
+            .await
+            .unwrap();
+        assert_eq!(page.sessions.len(), 2);
+        assert_eq!(
+            page.next_cursor,
+            Some(encode_session_cursor(page.sessions[1].id))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_tenant_sessions_resumes_after_cursor() {
+        let mut session_mock = MockSessionRepository::new();
+        let last_id = StringUuid::new_v4();
+        session_mock
+            .expect_list_by_tenant_after()
+            .withf(move |_, after, _, _| *after == Some(last_id))
+            .returning(|_, _, _, _| Ok(vec![Session::default()]));
+
+        let service = SessionService::new(
+            Arc::new(session_mock),
+            Arc::new(MockUserRepository::new()),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let page = service
+            .export_tenant_sessions(
+                StringUuid::new_v4(),
+                Some(&encode_session_cursor(last_id)),
+                100,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.sessions.len(), 1);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_export_tenant_sessions_rejects_bad_cursor() {
+        let service = SessionService::new(
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockUserRepository::new()),
+            create_test_identity_sessions(),
+            None,
+        );
+
+        let result = service
+            .export_tenant_sessions(StringUuid::new_v4(), Some("%%%"), 100, true)
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
 }
@@ -0,0 +1,140 @@
+//! Write-behind flushing of session activity
+//!
+//! The auth middleware marks sessions as active in Redis (at most once per
+//! precision window); this flusher moves the buffered markers to
+//! `sessions.last_active_at` in batches.
+
+use crate::cache::CacheOperations;
+use crate::config::SessionActivityConfig;
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::repository::SessionRepository;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+pub struct SessionActivityFlusher<S: SessionRepository> {
+    cache: Arc<dyn CacheOperations>,
+    session_repo: Arc<S>,
+    batch_size: usize,
+}
+
+impl<S: SessionRepository> SessionActivityFlusher<S> {
+    pub fn new(
+        cache: Arc<dyn CacheOperations>,
+        session_repo: Arc<S>,
+        config: &SessionActivityConfig,
+    ) -> Self {
+        Self {
+            cache,
+            session_repo,
+            batch_size: config.flush_batch_size.max(1),
+        }
+    }
+
+    /// Flush all pending activity markers, returning how many were drained.
+    ///
+    /// Markers of a batch that fails to write are dropped; the next request on
+    /// the session records a new one after the precision window.
+    pub async fn flush(&self) -> Result<usize> {
+        let mut drained = 0;
+        loop {
+            let markers = self.cache.drain_session_activity(self.batch_size).await?;
+            if markers.is_empty() {
+                break;
+            }
+            drained += markers.len();
+
+            let updates: Vec<(StringUuid, DateTime<Utc>)> = markers
+                .iter()
+                .filter_map(|(session_id, at)| {
+                    let session_id = StringUuid::parse_str(session_id).ok()?;
+                    let at = DateTime::from_timestamp(*at, 0)?;
+                    Some((session_id, at))
+                })
+                .collect();
+
+            if let Some(oldest) = updates.iter().map(|(_, at)| *at).min() {
+                let lag = (Utc::now() - oldest).num_milliseconds().max(0) as f64 / 1000.0;
+                metrics::histogram!("auth9_session_activity_flush_lag_seconds").record(lag);
+            }
+
+            if let Err(e) = self.session_repo.update_last_active_batch(&updates).await {
+                metrics::counter!("auth9_session_activity_flush_errors_total").increment(1);
+                return Err(e);
+            }
+            metrics::counter!("auth9_session_activity_flushed_total")
+                .increment(updates.len() as u64);
+
+            if markers.len() < self.batch_size {
+                break;
+            }
+        }
+        Ok(drained)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::NoOpCacheManager;
+    use crate::repository::session::MockSessionRepository;
+
+    fn config(batch: usize) -> SessionActivityConfig {
+        SessionActivityConfig {
+            flush_batch_size: batch,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_writes_markers_in_batches() {
+        let cache = Arc::new(NoOpCacheManager::new());
+        let ids: Vec<StringUuid> = (0..3).map(|_| StringUuid::new_v4()).collect();
+        for (i, id) in ids.iter().enumerate() {
+            cache
+                .mark_session_active(&id.to_string(), 1_700_000_000 + i as i64, 60)
+                .await
+                .unwrap();
+        }
+        // Unparseable ids are drained but not written
+        cache
+            .mark_session_active("not-a-session", 1_700_000_000, 60)
+            .await
+            .unwrap();
+
+        let mut repo = MockSessionRepository::new();
+        repo.expect_update_last_active_batch()
+            .times(2)
+            .returning(|updates| Ok(updates.len() as u64));
+
+        let flusher = SessionActivityFlusher::new(cache.clone(), Arc::new(repo), &config(2));
+        assert_eq!(flusher.flush().await.unwrap(), 4);
+        assert!(cache.drain_session_activity(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flush_nothing_pending() {
+        let cache = Arc::new(NoOpCacheManager::new());
+        let mut repo = MockSessionRepository::new();
+        repo.expect_update_last_active_batch().never();
+
+        let flusher = SessionActivityFlusher::new(cache, Arc::new(repo), &config(10));
+        assert_eq!(flusher.flush().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_propagates_db_error() {
+        let cache = Arc::new(NoOpCacheManager::new());
+        cache
+            .mark_session_active(&StringUuid::new_v4().to_string(), 1_700_000_000, 60)
+            .await
+            .unwrap();
+
+        let mut repo = MockSessionRepository::new();
+        repo.expect_update_last_active_batch()
+            .returning(|_| Err(crate::error::AppError::Internal(anyhow::anyhow!("db down"))));
+
+        let flusher = SessionActivityFlusher::new(cache, Arc::new(repo), &config(10));
+        assert!(flusher.flush().await.is_err());
+    }
+}
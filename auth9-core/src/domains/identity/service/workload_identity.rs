@@ -0,0 +1,485 @@
+//! Workload identity federation — trusts CI platform OIDC tokens
+//!
+//! A pipeline presents the OIDC token its platform issued for the job. The
+//! token is verified against the JWKS published by the issuer of a matching
+//! tenant rule (discovered via `/.well-known/openid-configuration`), then its
+//! subject and claims are matched against the rule. Only issuers configured
+//! in a rule are ever contacted.
+
+use crate::error::{AppError, Result};
+use crate::models::common::{validate_url_no_ssrf, StringUuid};
+use crate::models::workload_identity::{
+    CreateWorkloadIdentityRuleInput, UpdateWorkloadIdentityRuleInput, WorkloadIdentityRule,
+    DEFAULT_FEDERATED_TOKEN_TTL_SECS, MAX_RULES_PER_TENANT,
+};
+use crate::repository::workload_identity::{
+    WorkloadIdentityRepository, WorkloadIdentityRepositoryImpl,
+};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use validator::Validate;
+
+/// How long a fetched JWKS is used before it is fetched again
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Minimum interval between refetches triggered by an unknown `kid`
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Signature algorithms accepted on external workload tokens
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+lazy_static::lazy_static! {
+    static ref SHARED_JWKS_PROVIDER: Arc<HttpJwksProvider> = Arc::new(HttpJwksProvider::new());
+}
+
+/// Supplies the signing keys of an OIDC issuer
+#[async_trait]
+pub trait JwksProvider: Send + Sync {
+    /// Keys of `issuer`; `refresh` asks for a fresh copy when a key is missing
+    async fn jwks(&self, issuer: &str, refresh: bool) -> Result<JwkSet>;
+}
+
+/// Fetches JWKS via OIDC discovery and caches them in-process
+pub struct HttpJwksProvider {
+    http_client: reqwest::Client,
+    cache: Mutex<HashMap<String, (JwkSet, Instant)>>,
+}
+
+impl HttpJwksProvider {
+    pub fn new() -> Self {
+        Self {
+            http_client: reqwest::Client::builder()
+                .user_agent("Auth9-Core")
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn get_json(&self, url: &str) -> Result<Value> {
+        validate_url_no_ssrf(url)
+            .map_err(|_| AppError::Internal(anyhow::anyhow!("Refusing to fetch '{}'", url)))?;
+        self.http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))?
+            .error_for_status()
+            .map_err(|e| AppError::Internal(e.into()))?
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(e.into()))
+    }
+
+    async fn fetch(&self, issuer: &str) -> Result<JwkSet> {
+        let discovery = self
+            .get_json(&format!(
+                "{}/.well-known/openid-configuration",
+                issuer.trim_end_matches('/')
+            ))
+            .await?;
+        let jwks_uri = discovery["jwks_uri"].as_str().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Issuer '{}' publishes no jwks_uri", issuer))
+        })?;
+        serde_json::from_value(self.get_json(jwks_uri).await?)
+            .map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+impl Default for HttpJwksProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl JwksProvider for HttpJwksProvider {
+    async fn jwks(&self, issuer: &str, refresh: bool) -> Result<JwkSet> {
+        if let Ok(cache) = self.cache.lock() {
+            if let Some((jwks, fetched_at)) = cache.get(issuer) {
+                let max_age = if refresh {
+                    JWKS_MIN_REFRESH_INTERVAL
+                } else {
+                    JWKS_CACHE_TTL
+                };
+                if fetched_at.elapsed() < max_age {
+                    return Ok(jwks.clone());
+                }
+            }
+        }
+
+        let jwks = self.fetch(issuer).await?;
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(issuer.to_string(), (jwks.clone(), Instant::now()));
+        }
+        Ok(jwks)
+    }
+}
+
+/// A verified workload token and the rule it matched
+#[derive(Debug, Clone)]
+pub struct FederatedWorkload {
+    pub rule: WorkloadIdentityRule,
+    /// `sub` of the external token
+    pub subject: String,
+}
+
+fn rejected(message: impl Into<String>) -> AppError {
+    AppError::Unauthorized(message.into())
+}
+
+/// `iss` of a JWT, read before the signature is checked so the issuer's
+/// keys can be looked up
+fn unverified_issuer(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let claims: Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+    claims["iss"].as_str().map(str::to_string)
+}
+
+fn same_issuer(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+/// `aud` claim as a list (it may be a string or an array)
+fn token_audiences(claims: &Map<String, Value>) -> Vec<&str> {
+    match claims.get("aud") {
+        Some(Value::String(aud)) => vec![aud.as_str()],
+        Some(Value::Array(auds)) => auds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub struct WorkloadIdentityService<R: WorkloadIdentityRepository> {
+    repo: Arc<R>,
+    jwks: Arc<dyn JwksProvider>,
+}
+
+impl WorkloadIdentityService<WorkloadIdentityRepositoryImpl> {
+    /// Service over the process-wide JWKS cache
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self {
+            repo: Arc::new(WorkloadIdentityRepositoryImpl::new(pool)),
+            jwks: SHARED_JWKS_PROVIDER.clone(),
+        }
+    }
+}
+
+impl<R: WorkloadIdentityRepository> WorkloadIdentityService<R> {
+    pub fn new(repo: Arc<R>, jwks: Arc<dyn JwksProvider>) -> Self {
+        Self { repo, jwks }
+    }
+
+    pub async fn list(&self, tenant_id: StringUuid) -> Result<Vec<WorkloadIdentityRule>> {
+        self.repo.list_by_tenant(tenant_id).await
+    }
+
+    pub async fn get(&self, tenant_id: StringUuid, id: StringUuid) -> Result<WorkloadIdentityRule> {
+        self.repo
+            .find(tenant_id, id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Workload identity rule {} not found", id)))
+    }
+
+    /// Create a rule. The caller checks that the service belongs to the tenant.
+    pub async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: CreateWorkloadIdentityRuleInput,
+    ) -> Result<WorkloadIdentityRule> {
+        input.validate()?;
+
+        if self.repo.count_by_tenant(tenant_id).await? >= MAX_RULES_PER_TENANT as i64 {
+            return Err(AppError::BadRequest(format!(
+                "A tenant can have at most {} workload identity rules",
+                MAX_RULES_PER_TENANT
+            )));
+        }
+
+        let now = Utc::now();
+        let rule = WorkloadIdentityRule {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            service_id: StringUuid::from(input.service_id),
+            name: input.name,
+            issuer: input.issuer.trim_end_matches('/').to_string(),
+            audience: input.audience,
+            subject_pattern: input.subject_pattern,
+            claim_conditions: input.claim_conditions,
+            token_ttl_secs: input
+                .token_ttl_secs
+                .unwrap_or(DEFAULT_FEDERATED_TOKEN_TTL_SECS),
+            enabled: input.enabled,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(&rule).await
+    }
+
+    pub async fn update(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        input: UpdateWorkloadIdentityRuleInput,
+    ) -> Result<WorkloadIdentityRule> {
+        input.validate()?;
+        let existing = self.get(tenant_id, id).await?;
+
+        let rule = WorkloadIdentityRule {
+            name: input.name.unwrap_or(existing.name),
+            audience: input.audience.unwrap_or(existing.audience),
+            subject_pattern: input.subject_pattern.unwrap_or(existing.subject_pattern),
+            claim_conditions: input.claim_conditions.unwrap_or(existing.claim_conditions),
+            token_ttl_secs: input.token_ttl_secs.unwrap_or(existing.token_ttl_secs),
+            enabled: input.enabled.unwrap_or(existing.enabled),
+            ..existing
+        };
+        self.repo.update(&rule).await
+    }
+
+    pub async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()> {
+        self.repo.delete(tenant_id, id).await
+    }
+
+    /// Verify an external workload token for a service and return the rule
+    /// it matched. Fails with `Unauthorized` when no enabled rule trusts it.
+    pub async fn authenticate(
+        &self,
+        service_id: StringUuid,
+        subject_token: &str,
+    ) -> Result<FederatedWorkload> {
+        let header = jsonwebtoken::decode_header(subject_token)
+            .map_err(|_| rejected("subject_token is not a signed JWT"))?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(rejected(format!(
+                "subject_token algorithm {:?} is not accepted",
+                header.alg
+            )));
+        }
+        let token_issuer =
+            unverified_issuer(subject_token).ok_or_else(|| rejected("subject_token has no iss"))?;
+
+        let rules: Vec<WorkloadIdentityRule> = self
+            .repo
+            .list_enabled_by_service(service_id)
+            .await?
+            .into_iter()
+            .filter(|rule| same_issuer(&rule.issuer, &token_issuer))
+            .collect();
+        // Keys are fetched from the configured issuer, never from the token
+        let Some(issuer) = rules.first().map(|rule| rule.issuer.clone()) else {
+            return Err(rejected(
+                "No workload identity rule of this client trusts the token issuer",
+            ));
+        };
+
+        let key = self.decoding_key(&issuer, header.kid.as_deref()).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&token_issuer]);
+        let audiences: Vec<&str> = rules.iter().map(|rule| rule.audience.as_str()).collect();
+        validation.set_audience(&audiences);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        validation.leeway = 30;
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(subject_token, &key, &validation)
+            .map_err(|e| rejected(format!("subject_token rejected: {}", e)))?
+            .claims;
+
+        let token_audiences = token_audiences(&claims);
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        rules
+            .into_iter()
+            .find(|rule| {
+                token_audiences.contains(&rule.audience.as_str()) && rule.matches_claims(&claims)
+            })
+            .map(|rule| FederatedWorkload { rule, subject })
+            .ok_or_else(|| rejected("subject_token does not match any workload identity rule"))
+    }
+
+    async fn decoding_key(&self, issuer: &str, kid: Option<&str>) -> Result<DecodingKey> {
+        for refresh in [false, true] {
+            let jwks = self.jwks.jwks(issuer, refresh).await?;
+            let jwk = match kid {
+                Some(kid) => jwks.find(kid),
+                None if jwks.keys.len() == 1 => jwks.keys.first(),
+                None => None,
+            };
+            if let Some(jwk) = jwk {
+                return DecodingKey::from_jwk(jwk)
+                    .map_err(|e| rejected(format!("Unusable signing key: {}", e)));
+            }
+        }
+        Err(rejected(
+            "subject_token signing key is not published by the issuer",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::workload_identity::GITHUB_ACTIONS_ISSUER;
+    use crate::repository::workload_identity::MockWorkloadIdentityRepository;
+    use jsonwebtoken::{EncodingKey, Header};
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+    use rsa::traits::PublicKeyParts;
+    use rsa::RsaPrivateKey;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    struct StaticJwks(JwkSet);
+
+    #[async_trait]
+    impl JwksProvider for StaticJwks {
+        async fn jwks(&self, _issuer: &str, _refresh: bool) -> Result<JwkSet> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn rule(service_id: StringUuid, subject_pattern: &str) -> WorkloadIdentityRule {
+        let now = Utc::now();
+        WorkloadIdentityRule {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            service_id,
+            name: "deploy".to_string(),
+            issuer: GITHUB_ACTIONS_ISSUER.to_string(),
+            audience: "https://auth9.example.com".to_string(),
+            subject_pattern: subject_pattern.to_string(),
+            claim_conditions: BTreeMap::from([(
+                "repository_owner".to_string(),
+                "acme".to_string(),
+            )]),
+            token_ttl_secs: 600,
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_verifies_signature_and_matches_rule() {
+        let private_key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048).unwrap();
+        let pem = private_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let encoding_key = EncodingKey::from_rsa_pem(pem.as_bytes()).unwrap();
+        let jwks: JwkSet = serde_json::from_value(json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": "ci-key",
+                "alg": "RS256",
+                "use": "sig",
+                "n": URL_SAFE_NO_PAD.encode(private_key.n().to_bytes_be()),
+                "e": URL_SAFE_NO_PAD.encode(private_key.e().to_bytes_be()),
+            }]
+        }))
+        .unwrap();
+
+        let service_id = StringUuid::new_v4();
+        let rules = vec![rule(service_id, "repo:acme/deploy:ref:refs/heads/*")];
+        let mut repo = MockWorkloadIdentityRepository::new();
+        repo.expect_list_enabled_by_service()
+            .returning(move |_| Ok(rules.clone()));
+        let service = WorkloadIdentityService::new(Arc::new(repo), Arc::new(StaticJwks(jwks)));
+
+        let sign = |sub: &str, owner: &str, aud: &str| {
+            let mut header = Header::new(Algorithm::RS256);
+            header.kid = Some("ci-key".to_string());
+            let claims = json!({
+                "iss": GITHUB_ACTIONS_ISSUER,
+                "aud": aud,
+                "sub": sub,
+                "repository_owner": owner,
+                "exp": Utc::now().timestamp() + 300,
+            });
+            jsonwebtoken::encode(&header, &claims, &encoding_key).unwrap()
+        };
+
+        let workload = service
+            .authenticate(
+                service_id,
+                &sign(
+                    "repo:acme/deploy:ref:refs/heads/main",
+                    "acme",
+                    "https://auth9.example.com",
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(workload.subject, "repo:acme/deploy:ref:refs/heads/main");
+        assert_eq!(workload.rule.token_ttl_secs, 600);
+
+        for token in [
+            sign(
+                "repo:evil/deploy:ref:refs/heads/main",
+                "acme",
+                "https://auth9.example.com",
+            ),
+            sign(
+                "repo:acme/deploy:ref:refs/heads/main",
+                "evil",
+                "https://auth9.example.com",
+            ),
+            sign(
+                "repo:acme/deploy:ref:refs/heads/main",
+                "acme",
+                "https://other.example.com",
+            ),
+        ] {
+            assert!(matches!(
+                service.authenticate(service_id, &token).await,
+                Err(AppError::Unauthorized(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_untrusted_issuer_and_hmac() {
+        let service_id = StringUuid::new_v4();
+        let rules = vec![rule(service_id, "repo:acme/*")];
+        let mut repo = MockWorkloadIdentityRepository::new();
+        repo.expect_list_enabled_by_service()
+            .returning(move |_| Ok(rules.clone()));
+        let service = WorkloadIdentityService::new(
+            Arc::new(repo),
+            Arc::new(StaticJwks(JwkSet { keys: Vec::new() })),
+        );
+
+        let hmac = jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &json!({"iss": GITHUB_ACTIONS_ISSUER, "sub": "repo:acme/x", "exp": 4102444800i64}),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        assert!(matches!(
+            service.authenticate(service_id, &hmac).await,
+            Err(AppError::Unauthorized(_))
+        ));
+
+        let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"RS256","kid":"k"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(br#"{"iss":"https://evil.example.com"}"#);
+        let forged = format!("{}.{}.c2ln", header, payload);
+        assert!(matches!(
+            service.authenticate(service_id, &forged).await,
+            Err(AppError::Unauthorized(_))
+        ));
+    }
+}
@@ -4,17 +4,27 @@
 //! This enables real-time security monitoring and analytics for authentication events.
 
 use crate::cache::CacheOperations;
+use crate::domains::platform::service::NotificationPreferenceService;
+use crate::domains::security_observability::service::login_forensics::capture_login_failure;
 use crate::error::AppError;
-use crate::models::analytics::{CreateLoginEventInput, LoginEventType};
+use crate::models::analytics::{
+    CreateLoginEventInput, LoginEvent, LoginEventType, SecurityAlert, SecurityAlertType,
+};
 use crate::models::common::StringUuid;
-use crate::state::{HasAnalytics, HasCache, HasSecurityAlerts, HasServices};
+use crate::models::email_template::EmailTemplateType;
+use crate::models::login_forensics::{LoginFailureCapture, LoginFailureStage};
+use crate::models::notification_preference::{NotificationCategory, SignInNotice};
+use crate::models::session::{device_summary, parse_user_agent};
+use crate::state::{
+    HasAnalytics, HasCache, HasDbPool, HasSecurityAlerts, HasServices, HasSystemSettings,
+};
 use axum::{
     body::Bytes,
     extract::State,
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
 use serde::Deserialize;
 use sha2::Sha256;
@@ -394,7 +404,7 @@ pub fn parse_identity_event(body: &[u8]) -> Result<IdentityEvent, AppError> {
 
 /// Process a Keycloak event from webhook or stream.
 pub async fn process_identity_event<
-    S: HasServices + HasAnalytics + HasSecurityAlerts + HasCache,
+    S: HasServices + HasAnalytics + HasSecurityAlerts + HasCache + HasDbPool + HasSystemSettings,
 >(
     state: &S,
     event: IdentityEvent,
@@ -566,6 +576,20 @@ pub async fn process_identity_event<
         country_code: None,
     };
 
+    if let Some(stage) = LoginFailureStage::from_event_type(&login_event_type) {
+        let capture = LoginFailureCapture {
+            identifier: email.clone(),
+            user_id,
+            tenant_id,
+            client_id: event.client_id.clone(),
+            ip_address: input.ip_address.clone(),
+            user_agent: input.user_agent.clone(),
+            failure_reason: input.failure_reason.clone(),
+            ..LoginFailureCapture::new(stage)
+        };
+        capture_login_failure(state, capture).await?;
+    }
+
     let event_id = state.analytics_service().record_login_event(input).await?;
     info!(
         "Recorded login event: id={}, type={}, user_id={:?}",
@@ -634,24 +658,176 @@ pub async fn process_identity_event<
     }
 
     if let Ok(Some(login_event)) = state.analytics_service().get_event(event_id).await {
-        if let Err(err) = state
+        let alerts = match state
             .security_detection_service()
             .analyze_login_event(&login_event)
             .await
         {
-            error!("Security analysis failed for event {}: {}", event_id, err);
+            Ok(alerts) => alerts,
+            Err(err) => {
+                error!("Security analysis failed for event {}: {}", event_id, err);
+                Vec::new()
+            }
+        };
+
+        let is_sign_in = matches!(
+            login_event.event_type,
+            LoginEventType::Success | LoginEventType::Social
+        );
+        if login_event.user_id.is_some() && (is_sign_in || !alerts.is_empty()) {
+            let state = state.clone();
+            tokio::spawn(async move {
+                notify_login_event(&state, &login_event, &alerts).await;
+            });
         }
     }
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+fn describe_alert(alert_type: &SecurityAlertType) -> &'static str {
+    match alert_type {
+        SecurityAlertType::BruteForce | SecurityAlertType::SlowBruteForce => {
+            "Repeated failed sign-in attempts"
+        }
+        SecurityAlertType::PasswordSpray => "Password spray attack",
+        SecurityAlertType::NewDevice => "New sign-in from an unknown device",
+        SecurityAlertType::ImpossibleTravel => "Sign-in from an unusual location",
+        SecurityAlertType::SuspiciousIp => "Sign-in attempt from a suspicious IP address",
+        SecurityAlertType::HighRiskLogin => "High-risk sign-in",
+        SecurityAlertType::UserReportedSignIn => "Sign-in reported as not yours",
+    }
+}
+
+/// Email the user about a sign-in and any security alerts it raised,
+/// honoring their notification preferences
+async fn notify_login_event<S: HasServices + HasDbPool + HasSystemSettings>(
+    state: &S,
+    login_event: &LoginEvent,
+    alerts: &[SecurityAlert],
+) {
+    let Some(user_id) = login_event.user_id else {
+        return;
+    };
+    let notifications =
+        NotificationPreferenceService::from_config(state.db_pool().clone(), state.config());
+
+    let pending: Vec<(NotificationCategory, &'static str, DateTime<Utc>)> = alerts
+        .iter()
+        .filter(|alert| alert.user_id == Some(user_id))
+        .map(|alert| {
+            (
+                NotificationCategory::SecurityAlerts,
+                describe_alert(&alert.alert_type),
+                alert.created_at,
+            )
+        })
+        .collect();
+    // A sign-in that raised an alert is already covered by the alert email
+    if pending.is_empty()
+        && matches!(
+            login_event.event_type,
+            LoginEventType::Success | LoginEventType::Social
+        )
+    {
+        let (device_type, device_name) = login_event
+            .user_agent
+            .as_deref()
+            .map(parse_user_agent)
+            .unwrap_or((None, None));
+        // The event carries the backend's session ID, not an Auth9 session,
+        // so a "this wasn't me" report signs the user out everywhere
+        let notice = SignInNotice {
+            user_id,
+            session_id: None,
+            device: device_summary(device_name.as_deref(), device_type.as_deref()),
+            location: login_event.location.clone(),
+            ip_address: login_event.ip_address.clone(),
+            signed_in_at: login_event.created_at,
+        };
+        if let Err(err) = notifications
+            .send_sign_in_notification(state.email_service(), &notice)
+            .await
+        {
+            warn!("Failed to send sign-in email to {}: {}", user_id, err);
+        }
+        return;
+    }
+
+    let mut user = None;
+    for (category, description, timestamp) in pending {
+        if !notifications.allows(user_id, category).await {
+            continue;
+        }
+        if user.is_none() {
+            match state.user_service().get(user_id).await {
+                Ok(found) => user = Some(found),
+                Err(err) => {
+                    warn!("Skipping notification email for {}: {}", user_id, err);
+                    return;
+                }
+            }
+        }
+        let Some(user) = user.as_ref() else {
+            return;
+        };
+
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            user.display_name
+                .clone()
+                .unwrap_or_else(|| "User".to_string()),
+        );
+        vars.insert("event_type".to_string(), description.to_string());
+        vars.insert(
+            "device_info".to_string(),
+            login_event
+                .user_agent
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+        );
+        vars.insert(
+            "location".to_string(),
+            login_event
+                .location
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+        );
+        vars.insert(
+            "timestamp".to_string(),
+            timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        );
+
+        if let Err(err) = notifications
+            .send_notification(
+                state.email_service(),
+                user_id,
+                &user.email,
+                category,
+                EmailTemplateType::SecurityAlert,
+                crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+                vars,
+            )
+            .await
+        {
+            warn!(
+                "Failed to send {} email to {}: {}",
+                category.as_str(),
+                user_id,
+                err
+            );
+        }
+    }
+}
+
 /// Receive identity events webhook
 ///
 /// POST /api/v1/identity/events
 ///
 /// This endpoint receives identity events (login, logout, register, etc.).
-/// It validates the HMAC signature (if configured), maps the event to our domain model,
+/// It validates the HMAC signature (if configured; over `{timestamp}.{nonce}.{body}`
+/// when replay protection headers are sent), maps the event to our domain model,
 /// records it in the analytics system, and triggers security detection analysis.
 #[utoipa::path(
     post,
@@ -662,7 +838,9 @@ pub async fn process_identity_event<
         (status = 200, description = "Success")
     )
 )]
-pub async fn receive<S: HasServices + HasAnalytics + HasSecurityAlerts + HasCache>(
+pub async fn receive<
+    S: HasServices + HasAnalytics + HasSecurityAlerts + HasCache + HasDbPool + HasSystemSettings,
+>(
     State(state): State<S>,
     headers: HeaderMap,
     body: Bytes,
@@ -684,7 +862,8 @@ pub async fn receive<S: HasServices + HasAnalytics + HasSecurityAlerts + HasCach
             ));
         }
 
-        if !verify_signature(secret, &body, signature) {
+        let payload = crate::middleware::replay::signed_payload(&headers, &body);
+        if !verify_signature(secret, &payload, signature) {
             warn!("Identity webhook signature verification failed");
             return Err(AppError::Unauthorized(
                 "Invalid webhook signature".to_string(),
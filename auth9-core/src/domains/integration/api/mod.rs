@@ -3,3 +3,4 @@
 pub mod action;
 pub mod identity_event;
 pub mod webhook;
+pub mod webhook_bulk;
@@ -1,16 +1,24 @@
 //! Webhook API handlers
 
-use crate::domains::integration::service::WebhookTestResult;
+use crate::domains::integration::service::{WebhookPingResult, WebhookTestResult};
 use crate::error::AppError;
-use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::http_support::etag::{if_match_version, with_etag};
+use crate::http_support::{
+    write_audit_log_generic, MessageResponse, PaginatedResponse, PaginationQuery, SuccessResponse,
+};
 use crate::middleware::auth::AuthUser;
 use crate::models::analytics::{CreateWebhookInput, UpdateWebhookInput, Webhook};
 use crate::models::common::StringUuid;
-use crate::policy::{enforce, PolicyAction, PolicyInput, ResourceScope};
+use crate::models::list_query::ListGrammarUnsupported;
+use crate::models::webhook_delivery::{
+    SendSyntheticEventInput, WebhookDelivery, WebhookDeliveryFilter,
+};
+use crate::policy::{enforce, enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
 use crate::state::{HasServices, HasWebhooks};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
+    response::Response,
     Json,
 };
 
@@ -23,12 +31,14 @@ use axum::{
         ("tenant_id" = String, Path, description = "Tenant ID")
     ),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 400, description = "filter, sort or fields passed")
     )
 )]
 pub async fn list_webhooks<S: HasWebhooks + HasServices>(
     State(state): State<S>,
     auth: AuthUser,
+    _: ListGrammarUnsupported,
     Path(tenant_id): Path<StringUuid>,
 ) -> Result<Json<SuccessResponse<Vec<Webhook>>>, AppError> {
     enforce(
@@ -54,14 +64,14 @@ pub async fn list_webhooks<S: HasWebhooks + HasServices>(
         ("webhook_id" = String, Path, description = "Webhook ID")
     ),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success; `ETag` carries the webhook version")
     )
 )]
 pub async fn get_webhook<S: HasWebhooks + HasServices>(
     State(state): State<S>,
     auth: AuthUser,
     Path((tenant_id, webhook_id)): Path<(StringUuid, StringUuid)>,
-) -> Result<Json<SuccessResponse<Webhook>>, AppError> {
+) -> Result<Response, AppError> {
     enforce(
         state.config(),
         &auth,
@@ -74,11 +84,14 @@ pub async fn get_webhook<S: HasWebhooks + HasServices>(
     let webhook = state.webhook_service().get(webhook_id).await?;
 
     // Verify the webhook belongs to the tenant
-    if webhook.tenant_id != tenant_id {
+    if webhook.tenant_id != Some(tenant_id) {
         return Err(AppError::NotFound("Webhook not found".to_string()));
     }
 
-    Ok(Json(SuccessResponse::new(webhook)))
+    Ok(with_etag(
+        webhook.version,
+        Json(SuccessResponse::new(webhook)),
+    ))
 }
 
 /// Create a new webhook
@@ -116,24 +129,31 @@ pub async fn create_webhook<S: HasWebhooks + HasServices>(
 }
 
 /// Update a webhook
+///
+/// With `If-Match` the update only applies if the webhook has not been
+/// changed since it was read.
 #[utoipa::path(
     put,
     path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}",
     tag = "Integration",
     params(
         ("tenant_id" = String, Path, description = "Tenant ID"),
-        ("webhook_id" = String, Path, description = "Webhook ID")
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected webhook version (ETag)")
     ),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
 pub async fn update_webhook<S: HasWebhooks + HasServices>(
     State(state): State<S>,
     auth: AuthUser,
+    headers: HeaderMap,
     Path((tenant_id, webhook_id)): Path<(StringUuid, StringUuid)>,
     Json(input): Json<UpdateWebhookInput>,
-) -> Result<Json<SuccessResponse<Webhook>>, AppError> {
+) -> Result<Response, AppError> {
     enforce(
         state.config(),
         &auth,
@@ -145,12 +165,19 @@ pub async fn update_webhook<S: HasWebhooks + HasServices>(
 
     // Verify the webhook belongs to the tenant
     let existing = state.webhook_service().get(webhook_id).await?;
-    if existing.tenant_id != tenant_id {
+    if existing.tenant_id != Some(tenant_id) {
         return Err(AppError::NotFound("Webhook not found".to_string()));
     }
 
-    let webhook = state.webhook_service().update(webhook_id, input).await?;
-    Ok(Json(SuccessResponse::new(webhook)))
+    let expected_version = if_match_version(&headers)?;
+    let webhook = state
+        .webhook_service()
+        .update(webhook_id, input, expected_version)
+        .await?;
+    Ok(with_etag(
+        webhook.version,
+        Json(SuccessResponse::new(webhook)),
+    ))
 }
 
 /// Delete a webhook
@@ -182,7 +209,7 @@ pub async fn delete_webhook<S: HasWebhooks + HasServices>(
 
     // Verify the webhook belongs to the tenant
     let existing = state.webhook_service().get(webhook_id).await?;
-    if existing.tenant_id != tenant_id {
+    if existing.tenant_id != Some(tenant_id) {
         return Err(AppError::NotFound("Webhook not found".to_string()));
     }
 
@@ -220,7 +247,7 @@ pub async fn regenerate_webhook_secret<S: HasWebhooks + HasServices>(
 
     // Verify the webhook belongs to the tenant
     let existing = state.webhook_service().get(webhook_id).await?;
-    if existing.tenant_id != tenant_id {
+    if existing.tenant_id != Some(tenant_id) {
         return Err(AppError::NotFound("Webhook not found".to_string()));
     }
 
@@ -272,14 +299,565 @@ pub async fn test_webhook<S: HasWebhooks + HasServices>(
 
     // Verify the webhook belongs to the tenant
     let existing = state.webhook_service().get(webhook_id).await?;
-    if existing.tenant_id != tenant_id {
+    if existing.tenant_id != Some(tenant_id) {
+        return Err(AppError::NotFound("Webhook not found".to_string()));
+    }
+
+    let result = state.webhook_service().test(webhook_id).await?;
+    Ok(Json(SuccessResponse::new(result)))
+}
+
+/// Send a signed sample payload to a webhook receiver
+///
+/// The response echoes the exact body, signature and timestamp that were sent
+/// so integrators can check their verification code against them.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/ping",
+    tag = "Integration",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+pub async fn ping_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, webhook_id)): Path<(StringUuid, StringUuid)>,
+) -> Result<Json<SuccessResponse<WebhookPingResult>>, AppError> {
+    enforce(
+        state.config(),
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::WebhookWrite,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )?;
+
+    let existing = state.webhook_service().get(webhook_id).await?;
+    if existing.tenant_id != Some(tenant_id) {
+        return Err(AppError::NotFound("Webhook not found".to_string()));
+    }
+
+    let result = state.webhook_service().ping(webhook_id).await?;
+    Ok(Json(SuccessResponse::new(result)))
+}
+
+/// Enforce `action` on the tenant and check the webhook belongs to it
+async fn authorize_tenant_webhook<S: HasWebhooks + HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+    webhook_id: StringUuid,
+    action: PolicyAction,
+) -> Result<(), AppError> {
+    enforce(
+        state.config(),
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )?;
+
+    let existing = state.webhook_service().get(webhook_id).await?;
+    if existing.tenant_id != Some(tenant_id) {
+        return Err(AppError::NotFound("Webhook not found".to_string()));
+    }
+    Ok(())
+}
+
+/// List recent delivery attempts of a webhook
+///
+/// Each attempt carries its status code, latency and the start of the
+/// receiver's response body. Filter with `success` and `event_type`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries",
+    tag = "Integration",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("success" = Option<bool>, Query, description = "Only successful or failed attempts"),
+        ("event_type" = Option<String>, Query, description = "Only this event type")
+    ),
+    responses(
+        (status = 200, description = "Delivery attempts, newest first")
+    )
+)]
+pub async fn list_deliveries<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, webhook_id)): Path<(StringUuid, StringUuid)>,
+    Query(pagination): Query<PaginationQuery>,
+    Query(filter): Query<WebhookDeliveryFilter>,
+) -> Result<Json<PaginatedResponse<WebhookDelivery>>, AppError> {
+    authorize_tenant_webhook(
+        &state,
+        &auth,
+        tenant_id,
+        webhook_id,
+        PolicyAction::WebhookRead,
+    )
+    .await?;
+
+    let (deliveries, total) = state
+        .webhook_service()
+        .list_deliveries(webhook_id, &filter, pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        deliveries,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+/// Get one delivery attempt, including the payload that was sent
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}",
+    tag = "Integration",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("delivery_id" = String, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Success", body = WebhookDelivery),
+        (status = 404, description = "Delivery not found")
+    )
+)]
+pub async fn get_delivery<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, webhook_id, delivery_id)): Path<(StringUuid, StringUuid, StringUuid)>,
+) -> Result<Json<SuccessResponse<WebhookDelivery>>, AppError> {
+    authorize_tenant_webhook(
+        &state,
+        &auth,
+        tenant_id,
+        webhook_id,
+        PolicyAction::WebhookRead,
+    )
+    .await?;
+
+    let delivery = state
+        .webhook_service()
+        .get_delivery(webhook_id, delivery_id)
+        .await?;
+    Ok(Json(SuccessResponse::new(delivery)))
+}
+
+/// Replay a recorded delivery
+///
+/// The original event is re-sent with a fresh timestamp and signature and
+/// recorded as a new delivery that references the original.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/replay",
+    tag = "Integration",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("delivery_id" = String, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Outcome of the replayed delivery"),
+        (status = 404, description = "Delivery not found")
+    )
+)]
+pub async fn replay_delivery<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, webhook_id, delivery_id)): Path<(StringUuid, StringUuid, StringUuid)>,
+) -> Result<Json<SuccessResponse<WebhookTestResult>>, AppError> {
+    authorize_tenant_webhook(
+        &state,
+        &auth,
+        tenant_id,
+        webhook_id,
+        PolicyAction::WebhookWrite,
+    )
+    .await?;
+
+    let result = state
+        .webhook_service()
+        .replay(webhook_id, delivery_id)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "webhook.delivery_replayed",
+        "webhook",
+        Some(*webhook_id),
+        None,
+        Some(serde_json::json!({
+            "delivery_id": delivery_id.to_string(),
+            "success": result.success,
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(result)))
+}
+
+/// Send a synthetic event of any type to a webhook
+///
+/// The webhook does not need to subscribe to the event type. Omit `data` to
+/// send a sample object.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/send-event",
+    tag = "Integration",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    request_body = SendSyntheticEventInput,
+    responses(
+        (status = 200, description = "Outcome of the delivery"),
+        (status = 422, description = "Invalid event type")
+    )
+)]
+pub async fn send_synthetic_event<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, webhook_id)): Path<(StringUuid, StringUuid)>,
+    Json(input): Json<SendSyntheticEventInput>,
+) -> Result<Json<SuccessResponse<WebhookTestResult>>, AppError> {
+    authorize_tenant_webhook(
+        &state,
+        &auth,
+        tenant_id,
+        webhook_id,
+        PolicyAction::WebhookWrite,
+    )
+    .await?;
+
+    let event_type = input.event_type.clone();
+    let result = state
+        .webhook_service()
+        .send_synthetic(webhook_id, input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "webhook.synthetic_event_sent",
+        "webhook",
+        Some(*webhook_id),
+        None,
+        Some(serde_json::json!({
+            "event_type": event_type,
+            "success": result.success,
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(result)))
+}
+
+/// Enforce `action` on platform webhooks and check the webhook is not tenant-bound
+async fn authorize_platform_webhook<S: HasWebhooks + HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    webhook_id: Option<StringUuid>,
+    action: PolicyAction,
+) -> Result<Option<Webhook>, AppError> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await?;
+
+    let Some(webhook_id) = webhook_id else {
+        return Ok(None);
+    };
+    let existing = state.webhook_service().get(webhook_id).await?;
+    if existing.tenant_id.is_some() {
         return Err(AppError::NotFound("Webhook not found".to_string()));
     }
+    Ok(Some(existing))
+}
+
+/// List platform webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/platform/webhooks",
+    tag = "Integration",
+    responses(
+        (status = 200, description = "Success"),
+        (status = 400, description = "filter, sort or fields passed"),
+        (status = 403, description = "Platform admin required")
+    )
+)]
+pub async fn list_platform_webhooks<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    _: ListGrammarUnsupported,
+) -> Result<Json<SuccessResponse<Vec<Webhook>>>, AppError> {
+    authorize_platform_webhook(&state, &auth, None, PolicyAction::PlatformWebhookRead).await?;
+
+    let webhooks = state.webhook_service().list_platform().await?;
+    Ok(Json(SuccessResponse::new(webhooks)))
+}
+
+/// Get a platform webhook by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/platform/webhooks/{webhook_id}",
+    tag = "Integration",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Success; `ETag` carries the webhook version")
+    )
+)]
+pub async fn get_platform_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(webhook_id): Path<StringUuid>,
+) -> Result<Response, AppError> {
+    let webhook = authorize_platform_webhook(
+        &state,
+        &auth,
+        Some(webhook_id),
+        PolicyAction::PlatformWebhookRead,
+    )
+    .await?
+    .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))?;
+    Ok(with_etag(
+        webhook.version,
+        Json(SuccessResponse::new(webhook)),
+    ))
+}
+
+/// Create a platform webhook
+///
+/// Platform webhooks are not bound to a tenant and may only subscribe to
+/// `tenant.created`, `tenant.deleted` and `security.alert`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/platform/webhooks",
+    tag = "Integration",
+    request_body = CreateWebhookInput,
+    responses(
+        (status = 200, description = "Success"),
+        (status = 403, description = "Platform admin required"),
+        (status = 422, description = "Unsupported event type")
+    )
+)]
+pub async fn create_platform_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CreateWebhookInput>,
+) -> Result<Json<SuccessResponse<Webhook>>, AppError> {
+    authorize_platform_webhook(&state, &auth, None, PolicyAction::PlatformWebhookWrite).await?;
+
+    let webhook = state.webhook_service().create_platform(input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "platform_webhook.create",
+        "webhook",
+        Some(*webhook.id),
+        None,
+        Some(serde_json::json!({
+            "name": webhook.name,
+            "url": webhook.url,
+            "events": webhook.events,
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(webhook)))
+}
+
+/// Update a platform webhook
+#[utoipa::path(
+    put,
+    path = "/api/v1/platform/webhooks/{webhook_id}",
+    tag = "Integration",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("If-Match" = Option<String>, Header, description = "Expected webhook version (ETag)")
+    ),
+    request_body = UpdateWebhookInput,
+    responses(
+        (status = 200, description = "Success"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version"),
+        (status = 422, description = "Unsupported event type")
+    )
+)]
+pub async fn update_platform_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(webhook_id): Path<StringUuid>,
+    Json(input): Json<UpdateWebhookInput>,
+) -> Result<Response, AppError> {
+    authorize_platform_webhook(
+        &state,
+        &auth,
+        Some(webhook_id),
+        PolicyAction::PlatformWebhookWrite,
+    )
+    .await?;
+
+    let expected_version = if_match_version(&headers)?;
+    let webhook = state
+        .webhook_service()
+        .update_platform(webhook_id, input, expected_version)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "platform_webhook.update",
+        "webhook",
+        Some(*webhook_id),
+        None,
+        Some(serde_json::json!({
+            "url": webhook.url,
+            "events": webhook.events,
+            "enabled": webhook.enabled,
+        })),
+    )
+    .await;
+
+    Ok(with_etag(
+        webhook.version,
+        Json(SuccessResponse::new(webhook)),
+    ))
+}
+
+/// Delete a platform webhook
+#[utoipa::path(
+    delete,
+    path = "/api/v1/platform/webhooks/{webhook_id}",
+    tag = "Integration",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+pub async fn delete_platform_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(webhook_id): Path<StringUuid>,
+) -> Result<Json<MessageResponse>, AppError> {
+    authorize_platform_webhook(
+        &state,
+        &auth,
+        Some(webhook_id),
+        PolicyAction::PlatformWebhookWrite,
+    )
+    .await?;
+
+    state.webhook_service().delete(webhook_id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "platform_webhook.delete",
+        "webhook",
+        Some(*webhook_id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Webhook deleted successfully.")))
+}
+
+/// Test a platform webhook by sending a test event
+#[utoipa::path(
+    post,
+    path = "/api/v1/platform/webhooks/{webhook_id}/test",
+    tag = "Integration",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+pub async fn test_platform_webhook<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(webhook_id): Path<StringUuid>,
+) -> Result<Json<SuccessResponse<WebhookTestResult>>, AppError> {
+    authorize_platform_webhook(
+        &state,
+        &auth,
+        Some(webhook_id),
+        PolicyAction::PlatformWebhookWrite,
+    )
+    .await?;
 
     let result = state.webhook_service().test(webhook_id).await?;
     Ok(Json(SuccessResponse::new(result)))
 }
 
+/// List recent delivery attempts of a platform webhook
+#[utoipa::path(
+    get,
+    path = "/api/v1/platform/webhooks/{webhook_id}/deliveries",
+    tag = "Integration",
+    params(
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        ("success" = Option<bool>, Query, description = "Only successful or failed attempts"),
+        ("event_type" = Option<String>, Query, description = "Only this event type")
+    ),
+    responses(
+        (status = 200, description = "Delivery attempts, newest first")
+    )
+)]
+pub async fn list_platform_deliveries<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(webhook_id): Path<StringUuid>,
+    Query(pagination): Query<PaginationQuery>,
+    Query(filter): Query<WebhookDeliveryFilter>,
+) -> Result<Json<PaginatedResponse<WebhookDelivery>>, AppError> {
+    authorize_platform_webhook(
+        &state,
+        &auth,
+        Some(webhook_id),
+        PolicyAction::PlatformWebhookRead,
+    )
+    .await?;
+
+    let (deliveries, total) = state
+        .webhook_service()
+        .list_deliveries(webhook_id, &filter, pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        deliveries,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +869,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: Some("secret123".to_string()),
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
@@ -0,0 +1,340 @@
+//! Bulk webhook subscription API handlers
+//!
+//! Each item is authorized against its own tenant: platform admins can act
+//! on any tenant, tenant admins only on theirs. Failed items are reported in
+//! the result and do not stop the rest of the request.
+
+use crate::error::AppError;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::analytics::{UpdateWebhookInput, Webhook};
+use crate::models::common::StringUuid;
+use crate::models::webhook_bulk::{
+    find_webhook_template, BulkSetWebhooksEnabledInput, BulkWebhookItemResult,
+    BulkWebhookItemStatus, BulkWebhookResult, CopyWebhooksInput, CreateWebhooksFromTemplateInput,
+    WebhookTemplate, WEBHOOK_TEMPLATES,
+};
+use crate::policy::{enforce, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::{HasServices, HasWebhooks};
+use axum::{extract::State, http::HeaderMap, Json};
+use validator::Validate;
+
+/// Check `WebhookWrite` on the tenant and that it accepts changes
+async fn authorize_target<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+) -> Result<(), AppError> {
+    enforce(
+        state.config(),
+        auth,
+        &PolicyInput {
+            action: PolicyAction::WebhookWrite,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )?;
+    state.tenant_service().require_active(tenant_id).await?;
+    Ok(())
+}
+
+fn audit_value(webhook: &Webhook) -> Option<serde_json::Value> {
+    Some(serde_json::json!({
+        "tenant_id": webhook.tenant_id,
+        "name": webhook.name,
+        "url": webhook.url,
+        "events": webhook.events,
+        "enabled": webhook.enabled,
+    }))
+}
+
+/// List the webhook template catalog
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-templates",
+    tag = "Integration",
+    responses(
+        (status = 200, description = "Templates usable with bulk creation", body = Vec<WebhookTemplate>)
+    )
+)]
+pub async fn list_webhook_templates(
+    _auth: AuthUser,
+) -> Json<SuccessResponse<&'static [WebhookTemplate]>> {
+    Json(SuccessResponse::new(WEBHOOK_TEMPLATES))
+}
+
+/// Copy webhooks of a tenant to other tenants
+///
+/// Copies get a fresh secret. Targets that already have a webhook with the
+/// same name and URL are skipped.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/bulk/copy",
+    tag = "Integration",
+    request_body = CopyWebhooksInput,
+    responses(
+        (status = 200, description = "Per-item results", body = BulkWebhookResult)
+    )
+)]
+pub async fn copy_webhooks<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CopyWebhooksInput>,
+) -> Result<Json<SuccessResponse<BulkWebhookResult>>, AppError> {
+    input.validate()?;
+    enforce(
+        state.config(),
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::WebhookRead,
+            scope: ResourceScope::Tenant(input.source_tenant_id),
+        },
+    )?;
+
+    let mut sources = state
+        .webhook_service()
+        .list_by_tenant(input.source_tenant_id)
+        .await?;
+    if let Some(ids) = &input.webhook_ids {
+        if let Some(missing) = ids.iter().find(|id| !sources.iter().any(|w| w.id == **id)) {
+            return Err(AppError::NotFound(format!("Webhook {} not found", missing)));
+        }
+        sources.retain(|w| ids.contains(&w.id));
+    }
+
+    let mut items = Vec::new();
+    for target in &input.target_tenant_ids {
+        let target = *target;
+        if target == input.source_tenant_id {
+            items.push(BulkWebhookItemResult::failed(
+                Some(target),
+                "Target tenant is the source tenant",
+            ));
+            continue;
+        }
+        if let Err(e) = authorize_target(&state, &auth, target).await {
+            items.push(BulkWebhookItemResult::failed(Some(target), e.to_string()));
+            continue;
+        }
+        for source in &sources {
+            let copied = state.webhook_service().copy_to_tenant(source, target).await;
+            let item = match copied {
+                Ok(Some(webhook)) => {
+                    let _ = write_audit_log_generic(
+                        &state,
+                        &headers,
+                        "webhook.bulk_copy",
+                        "webhook",
+                        Some(*webhook.id),
+                        Some(serde_json::json!({ "source_webhook_id": source.id })),
+                        audit_value(&webhook),
+                    )
+                    .await;
+                    BulkWebhookItemResult {
+                        tenant_id: Some(target),
+                        source_webhook_id: Some(source.id),
+                        webhook_id: Some(webhook.id),
+                        status: BulkWebhookItemStatus::Created,
+                        error: None,
+                    }
+                }
+                Ok(None) => BulkWebhookItemResult {
+                    tenant_id: Some(target),
+                    source_webhook_id: Some(source.id),
+                    webhook_id: None,
+                    status: BulkWebhookItemStatus::Skipped,
+                    error: None,
+                },
+                Err(e) => BulkWebhookItemResult {
+                    source_webhook_id: Some(source.id),
+                    ..BulkWebhookItemResult::failed(Some(target), e.to_string())
+                },
+            };
+            items.push(item);
+        }
+    }
+    Ok(Json(SuccessResponse::new(items.into())))
+}
+
+/// Enable or disable webhooks of one or more tenants
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/bulk/enabled",
+    tag = "Integration",
+    request_body = BulkSetWebhooksEnabledInput,
+    responses(
+        (status = 200, description = "Per-item results", body = BulkWebhookResult)
+    )
+)]
+pub async fn set_webhooks_enabled<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<BulkSetWebhooksEnabledInput>,
+) -> Result<Json<SuccessResponse<BulkWebhookResult>>, AppError> {
+    input.validate()?;
+    let action = if input.enabled {
+        "webhook.bulk_enable"
+    } else {
+        "webhook.bulk_disable"
+    };
+
+    let mut items = Vec::new();
+    for webhook_id in &input.webhook_ids {
+        let webhook_id = *webhook_id;
+        let existing = match state.webhook_service().get(webhook_id).await {
+            Ok(webhook) => webhook,
+            Err(AppError::NotFound(_)) => {
+                items.push(BulkWebhookItemResult {
+                    webhook_id: Some(webhook_id),
+                    ..BulkWebhookItemResult::failed(None, "Webhook not found")
+                });
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let authorized = match existing.tenant_id {
+            Some(tenant_id) => authorize_target(&state, &auth, tenant_id).await,
+            // Platform webhooks are managed through the platform webhook API
+            None => Err(AppError::NotFound("Webhook not found".to_string())),
+        };
+        if let Err(e) = authorized {
+            // Do not reveal webhooks of tenants the caller cannot manage
+            let error = match e {
+                AppError::Forbidden(_) | AppError::NotFound(_) => "Webhook not found".to_string(),
+                e => e.to_string(),
+            };
+            items.push(BulkWebhookItemResult {
+                webhook_id: Some(webhook_id),
+                ..BulkWebhookItemResult::failed(None, error)
+            });
+            continue;
+        }
+        let tenant_id = existing.tenant_id;
+        if existing.enabled == input.enabled {
+            items.push(BulkWebhookItemResult {
+                tenant_id,
+                source_webhook_id: None,
+                webhook_id: Some(webhook_id),
+                status: BulkWebhookItemStatus::Skipped,
+                error: None,
+            });
+            continue;
+        }
+
+        let update = UpdateWebhookInput {
+            enabled: Some(input.enabled),
+            ..Default::default()
+        };
+        let item = match state
+            .webhook_service()
+            .update(webhook_id, update, None)
+            .await
+        {
+            Ok(webhook) => {
+                let _ = write_audit_log_generic(
+                    &state,
+                    &headers,
+                    action,
+                    "webhook",
+                    Some(*webhook_id),
+                    audit_value(&existing),
+                    audit_value(&webhook),
+                )
+                .await;
+                BulkWebhookItemResult {
+                    tenant_id,
+                    source_webhook_id: None,
+                    webhook_id: Some(webhook_id),
+                    status: BulkWebhookItemStatus::Updated,
+                    error: None,
+                }
+            }
+            Err(e) => BulkWebhookItemResult {
+                webhook_id: Some(webhook_id),
+                ..BulkWebhookItemResult::failed(tenant_id, e.to_string())
+            },
+        };
+        items.push(item);
+    }
+    Ok(Json(SuccessResponse::new(items.into())))
+}
+
+/// Create a webhook from a catalog template in each tenant
+///
+/// Tenants that already have a webhook with the same name and URL are
+/// skipped. Each created webhook gets its own secret.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/bulk/from-template",
+    tag = "Integration",
+    request_body = CreateWebhooksFromTemplateInput,
+    responses(
+        (status = 200, description = "Per-item results", body = BulkWebhookResult),
+        (status = 400, description = "Unknown template")
+    )
+)]
+pub async fn create_webhooks_from_template<S: HasWebhooks + HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CreateWebhooksFromTemplateInput>,
+) -> Result<Json<SuccessResponse<BulkWebhookResult>>, AppError> {
+    input.validate()?;
+    let template = find_webhook_template(&input.template).ok_or_else(|| {
+        AppError::BadRequest(format!("Unknown webhook template '{}'", input.template))
+    })?;
+
+    let mut items = Vec::new();
+    for tenant_id in &input.tenant_ids {
+        let tenant_id = *tenant_id;
+        if let Err(e) = authorize_target(&state, &auth, tenant_id).await {
+            items.push(BulkWebhookItemResult::failed(
+                Some(tenant_id),
+                e.to_string(),
+            ));
+            continue;
+        }
+        let created = state
+            .webhook_service()
+            .create_from_template(
+                tenant_id,
+                template,
+                input.name.as_deref(),
+                &input.url,
+                input.enabled,
+            )
+            .await;
+        let item = match created {
+            Ok(Some(webhook)) => {
+                let _ = write_audit_log_generic(
+                    &state,
+                    &headers,
+                    "webhook.create_from_template",
+                    "webhook",
+                    Some(*webhook.id),
+                    Some(serde_json::json!({ "template": template.key })),
+                    audit_value(&webhook),
+                )
+                .await;
+                BulkWebhookItemResult {
+                    tenant_id: Some(tenant_id),
+                    source_webhook_id: None,
+                    webhook_id: Some(webhook.id),
+                    status: BulkWebhookItemStatus::Created,
+                    error: None,
+                }
+            }
+            Ok(None) => BulkWebhookItemResult {
+                tenant_id: Some(tenant_id),
+                source_webhook_id: None,
+                webhook_id: None,
+                status: BulkWebhookItemStatus::Skipped,
+                error: None,
+            },
+            Err(e) => BulkWebhookItemResult::failed(Some(tenant_id), e.to_string()),
+        };
+        items.push(item);
+    }
+    Ok(Json(SuccessResponse::new(items.into())))
+}
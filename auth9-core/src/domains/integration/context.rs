@@ -1,11 +1,26 @@
-use crate::state::{HasAnalytics, HasCache, HasSecurityAlerts, HasServices, HasWebhooks};
+use crate::state::{
+    HasAnalytics, HasCache, HasDbPool, HasSecurityAlerts, HasServices, HasSystemSettings,
+    HasWebhooks,
+};
 
 pub trait IntegrationContext:
-    HasServices + HasWebhooks + HasAnalytics + HasSecurityAlerts + HasCache
+    HasServices
+    + HasWebhooks
+    + HasAnalytics
+    + HasSecurityAlerts
+    + HasCache
+    + HasDbPool
+    + HasSystemSettings
 {
 }
 
 impl<T> IntegrationContext for T where
-    T: HasServices + HasWebhooks + HasAnalytics + HasSecurityAlerts + HasCache
+    T: HasServices
+        + HasWebhooks
+        + HasAnalytics
+        + HasSecurityAlerts
+        + HasCache
+        + HasDbPool
+        + HasSystemSettings
 {
 }
@@ -35,10 +35,65 @@ where
             "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/test",
             post(integration_api::webhook::test_webhook::<S>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/ping",
+            post(integration_api::webhook::ping_webhook::<S>),
+        )
         .route(
             "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/regenerate-secret",
             post(integration_api::webhook::regenerate_webhook_secret::<S>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/send-event",
+            post(integration_api::webhook::send_synthetic_event::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries",
+            get(integration_api::webhook::list_deliveries::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}",
+            get(integration_api::webhook::get_delivery::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/replay",
+            post(integration_api::webhook::replay_delivery::<S>),
+        )
+        .route(
+            "/api/v1/webhook-templates",
+            get(integration_api::webhook_bulk::list_webhook_templates),
+        )
+        .route(
+            "/api/v1/webhooks/bulk/copy",
+            post(integration_api::webhook_bulk::copy_webhooks::<S>),
+        )
+        .route(
+            "/api/v1/webhooks/bulk/enabled",
+            post(integration_api::webhook_bulk::set_webhooks_enabled::<S>),
+        )
+        .route(
+            "/api/v1/webhooks/bulk/from-template",
+            post(integration_api::webhook_bulk::create_webhooks_from_template::<S>),
+        )
+        .route(
+            "/api/v1/platform/webhooks",
+            get(integration_api::webhook::list_platform_webhooks::<S>)
+                .post(integration_api::webhook::create_platform_webhook::<S>),
+        )
+        .route(
+            "/api/v1/platform/webhooks/{webhook_id}",
+            get(integration_api::webhook::get_platform_webhook::<S>)
+                .put(integration_api::webhook::update_platform_webhook::<S>)
+                .delete(integration_api::webhook::delete_platform_webhook::<S>),
+        )
+        .route(
+            "/api/v1/platform/webhooks/{webhook_id}/test",
+            post(integration_api::webhook::test_platform_webhook::<S>),
+        )
+        .route(
+            "/api/v1/platform/webhooks/{webhook_id}/deliveries",
+            get(integration_api::webhook::list_platform_deliveries::<S>),
+        )
         .route(
             "/api/v1/services/{service_id}/actions",
             get(integration_api::action::list_actions::<S>)
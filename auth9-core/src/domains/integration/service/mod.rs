@@ -1,7 +1,8 @@
 pub mod action;
 pub mod action_engine;
 pub mod webhook;
+pub mod webhook_filter;
 
 pub use action::ActionService;
 pub use action_engine::ActionEngine;
-pub use webhook::{WebhookEventPublisher, WebhookService, WebhookTestResult};
+pub use webhook::{WebhookEventPublisher, WebhookPingResult, WebhookService, WebhookTestResult};
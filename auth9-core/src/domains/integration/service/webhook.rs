@@ -1,21 +1,26 @@
 //! Webhook service for event notifications
 
+use super::webhook_filter::{evaluate_filter, parse_filter};
 use crate::error::{AppError, Result};
-use crate::models::analytics::{CreateWebhookInput, UpdateWebhookInput, Webhook, WebhookEvent};
+use crate::models::analytics::{
+    CreateWebhookInput, UpdateWebhookInput, Webhook, WebhookEvent, PLATFORM_WEBHOOK_EVENTS,
+};
 use crate::models::common::StringUuid;
-use crate::repository::WebhookRepository;
+use crate::models::webhook_bulk::WebhookTemplate;
+use crate::models::webhook_delivery::{
+    response_snippet, SendSyntheticEventInput, WebhookDelivery, WebhookDeliveryFilter,
+    WebhookDeliverySource,
+};
+use crate::repository::{WebhookDeliveryRepository, WebhookRepository};
+use crate::webhook::{EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
 use async_trait::async_trait;
 use chrono::Utc;
-use hmac::{Hmac, Mac};
 use rand::Rng;
-use sha2::Sha256;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::time::{timeout, Duration};
 use validator::Validate;
 
-type HmacSha256 = Hmac<Sha256>;
-
 /// Minimal HTTP client interface for webhook delivery.
 ///
 /// This exists to keep unit tests hermetic (no TCP listeners required).
@@ -108,6 +113,12 @@ pub trait WebhookEventPublisher: Send + Sync {
     /// This method finds all enabled webhooks subscribed to the event
     /// and sends the payload to each one asynchronously.
     async fn trigger_event(&self, event: WebhookEvent) -> Result<()>;
+
+    /// Trigger only the given tenant's webhooks subscribed to the event.
+    async fn trigger_tenant_event(&self, tenant_id: StringUuid, event: WebhookEvent) -> Result<()>;
+
+    /// Trigger only platform webhooks subscribed to the event.
+    async fn trigger_platform_event(&self, event: WebhookEvent) -> Result<()>;
 }
 
 /// Generate a random webhook secret
@@ -126,6 +137,8 @@ const MAX_RETRY_ATTEMPTS: u32 = 3;
 pub struct WebhookService<W: WebhookRepository> {
     webhook_repo: Arc<W>,
     http_client: Arc<dyn WebhookHttpClient>,
+    /// Delivery log; attempts are not recorded when unset
+    delivery_repo: Option<Arc<dyn WebhookDeliveryRepository>>,
 }
 
 impl<W: WebhookRepository + 'static> WebhookService<W> {
@@ -141,14 +154,22 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
             http_client: Arc::new(ReqwestWebhookHttpClient {
                 client: http_client,
             }),
+            delivery_repo: None,
         }
     }
 
+    /// Record every delivery attempt in the delivery log
+    pub fn with_delivery_repo(mut self, delivery_repo: Arc<dyn WebhookDeliveryRepository>) -> Self {
+        self.delivery_repo = Some(delivery_repo);
+        self
+    }
+
     #[cfg(test)]
     fn new_with_http(webhook_repo: Arc<W>, http_client: Arc<dyn WebhookHttpClient>) -> Self {
         Self {
             webhook_repo,
             http_client,
+            delivery_repo: None,
         }
     }
 
@@ -158,16 +179,18 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
     pub async fn create(
         &self,
         tenant_id: StringUuid,
-        mut input: CreateWebhookInput,
+        input: CreateWebhookInput,
     ) -> Result<Webhook> {
-        input.validate()?;
-
-        // Auto-generate secret if not provided
-        if input.secret.is_none() {
-            input.secret = Some(generate_webhook_secret());
-        }
+        let input = prepare_create_input(input)?;
+        self.webhook_repo.create(Some(tenant_id), &input).await
+    }
 
-        self.webhook_repo.create(tenant_id, &input).await
+    /// Create a platform webhook, not bound to any tenant. It may only
+    /// subscribe to [`PLATFORM_WEBHOOK_EVENTS`].
+    pub async fn create_platform(&self, input: CreateWebhookInput) -> Result<Webhook> {
+        validate_platform_events(&input.events)?;
+        let input = prepare_create_input(input)?;
+        self.webhook_repo.create(None, &input).await
     }
 
     /// Get a webhook by ID
@@ -183,10 +206,41 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
         self.webhook_repo.list_by_tenant(tenant_id).await
     }
 
-    /// Update a webhook
-    pub async fn update(&self, id: StringUuid, input: UpdateWebhookInput) -> Result<Webhook> {
+    /// List platform webhooks
+    pub async fn list_platform(&self) -> Result<Vec<Webhook>> {
+        self.webhook_repo.list_platform().await
+    }
+
+    /// Update a webhook. `expected_version` (from `If-Match`) rejects the
+    /// write if the webhook was changed by someone else in the meantime.
+    pub async fn update(
+        &self,
+        id: StringUuid,
+        input: UpdateWebhookInput,
+        expected_version: Option<i32>,
+    ) -> Result<Webhook> {
         input.validate()?;
-        self.webhook_repo.update(id, &input).await
+        if let Some(filter) = input
+            .filter_expression
+            .as_deref()
+            .filter(|filter| !filter.trim().is_empty())
+        {
+            parse_filter(filter)?;
+        }
+        self.webhook_repo.update(id, &input, expected_version).await
+    }
+
+    /// Update a platform webhook, keeping its subscriptions to platform events
+    pub async fn update_platform(
+        &self,
+        id: StringUuid,
+        input: UpdateWebhookInput,
+        expected_version: Option<i32>,
+    ) -> Result<Webhook> {
+        if let Some(events) = &input.events {
+            validate_platform_events(events)?;
+        }
+        self.update(id, input, expected_version).await
     }
 
     /// Delete a webhook
@@ -194,6 +248,65 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
         self.webhook_repo.delete(id).await
     }
 
+    /// Copy a tenant webhook into another tenant with a fresh secret.
+    /// Returns `None` when the target already has a webhook with the same
+    /// name and URL.
+    pub async fn copy_to_tenant(
+        &self,
+        source: &Webhook,
+        tenant_id: StringUuid,
+    ) -> Result<Option<Webhook>> {
+        if self
+            .has_subscription(tenant_id, &source.name, &source.url)
+            .await?
+        {
+            return Ok(None);
+        }
+        let input = CreateWebhookInput {
+            name: source.name.clone(),
+            url: source.url.clone(),
+            secret: None,
+            events: source.events.clone(),
+            filter_expression: source.filter_expression.clone(),
+            enabled: source.enabled,
+        };
+        self.create(tenant_id, input).await.map(Some)
+    }
+
+    /// Create a webhook from a catalog template. Returns `None` when the
+    /// tenant already has a webhook with the same name and URL.
+    pub async fn create_from_template(
+        &self,
+        tenant_id: StringUuid,
+        template: &WebhookTemplate,
+        name: Option<&str>,
+        url: &str,
+        enabled: bool,
+    ) -> Result<Option<Webhook>> {
+        let name = name.unwrap_or(template.name);
+        if self.has_subscription(tenant_id, name, url).await? {
+            return Ok(None);
+        }
+        let input = CreateWebhookInput {
+            name: name.to_string(),
+            url: url.to_string(),
+            secret: None,
+            events: template.events.iter().map(|e| e.to_string()).collect(),
+            filter_expression: template.filter_expression.map(str::to_string),
+            enabled,
+        };
+        self.create(tenant_id, input).await.map(Some)
+    }
+
+    async fn has_subscription(&self, tenant_id: StringUuid, name: &str, url: &str) -> Result<bool> {
+        Ok(self
+            .webhook_repo
+            .list_by_tenant(tenant_id)
+            .await?
+            .iter()
+            .any(|w| w.name == name && w.url == url))
+    }
+
     /// Test a webhook by sending a test event
     pub async fn test(&self, id: StringUuid) -> Result<WebhookTestResult> {
         let webhook = self.get(id).await?;
@@ -207,26 +320,9 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
             }),
         };
 
-        let start = Instant::now();
-        let result =
-            match deliver_webhook_with_status(self.http_client.as_ref(), &webhook, &test_event)
-                .await
-            {
-                Ok(response) => WebhookTestResult {
-                    success: true,
-                    status_code: Some(response.status_code),
-                    response_body: response.body,
-                    error: None,
-                    response_time_ms: Some(start.elapsed().as_millis() as u64),
-                },
-                Err((status_code, error_msg)) => WebhookTestResult {
-                    success: false,
-                    status_code,
-                    response_body: None,
-                    error: Some(error_msg),
-                    response_time_ms: Some(start.elapsed().as_millis() as u64),
-                },
-            };
+        let result = self
+            .timed_delivery(&webhook, &test_event, WebhookDeliverySource::Test, None)
+            .await;
 
         // Update webhook status (reset failure_count on success, increment on failure)
         let _ = self.webhook_repo.update_triggered(id, result.success).await;
@@ -235,6 +331,152 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
         Ok(result)
     }
 
+    /// Send a signed sample event so integrators can validate their receiver.
+    ///
+    /// Unlike [`Self::test`], a ping does not count toward the webhook's
+    /// failure tally, and the response echoes exactly what was sent.
+    pub async fn ping(&self, id: StringUuid) -> Result<WebhookPingResult> {
+        let webhook = self.get(id).await?;
+
+        let event = WebhookEvent {
+            event_type: "webhook.ping".to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({
+                "webhook_id": id.to_string(),
+                "message": "Sample delivery from Auth9. Verify X-Webhook-Signature over the raw body and reject timestamps outside your tolerance window.",
+                "sample": {
+                    "user_id": "00000000-0000-0000-0000-000000000000",
+                    "email": "user@example.com",
+                },
+            }),
+        };
+        let payload =
+            serde_json::to_string(&event).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+        let signature = webhook
+            .secret
+            .as_deref()
+            .map(|secret| compute_signature(&payload, secret));
+
+        let delivery = self
+            .timed_delivery(&webhook, &event, WebhookDeliverySource::Ping, None)
+            .await;
+        Ok(WebhookPingResult {
+            delivery,
+            payload,
+            signature,
+            timestamp: event.timestamp.to_rfc3339(),
+        })
+    }
+
+    /// List a webhook's delivery attempts, newest first
+    pub async fn list_deliveries(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<WebhookDelivery>, i64)> {
+        let Some(repo) = &self.delivery_repo else {
+            return Ok((Vec::new(), 0));
+        };
+        let offset = (page - 1) * per_page;
+        let deliveries = repo
+            .list_by_webhook(webhook_id, filter, offset, per_page)
+            .await?;
+        let total = repo.count_by_webhook(webhook_id, filter).await?;
+        Ok((deliveries, total))
+    }
+
+    /// Get one delivery attempt of a webhook
+    pub async fn get_delivery(
+        &self,
+        webhook_id: StringUuid,
+        delivery_id: StringUuid,
+    ) -> Result<WebhookDelivery> {
+        let delivery = match &self.delivery_repo {
+            Some(repo) => repo.find_by_id(delivery_id).await?,
+            None => None,
+        };
+        delivery
+            .filter(|d| d.webhook_id == webhook_id)
+            .ok_or_else(|| AppError::NotFound(format!("Delivery {} not found", delivery_id)))
+    }
+
+    /// Send a recorded delivery again.
+    ///
+    /// The original event type and data are re-sent with a fresh timestamp
+    /// and signature, so receivers enforcing a tolerance window accept it.
+    pub async fn replay(
+        &self,
+        webhook_id: StringUuid,
+        delivery_id: StringUuid,
+    ) -> Result<WebhookTestResult> {
+        let webhook = self.get(webhook_id).await?;
+        let original = self.get_delivery(webhook_id, delivery_id).await?;
+        let mut event: WebhookEvent = serde_json::from_str(&original.payload).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Stored webhook payload is invalid: {}", e))
+        })?;
+        event.timestamp = Utc::now();
+
+        Ok(self
+            .timed_delivery(
+                &webhook,
+                &event,
+                WebhookDeliverySource::Replay,
+                Some(original.id),
+            )
+            .await)
+    }
+
+    /// Send an event of any type to one webhook, whether or not it subscribes
+    /// to that type. Like a ping, it does not count toward the failure tally.
+    pub async fn send_synthetic(
+        &self,
+        webhook_id: StringUuid,
+        input: SendSyntheticEventInput,
+    ) -> Result<WebhookTestResult> {
+        input.validate()?;
+        let webhook = self.get(webhook_id).await?;
+
+        let event = WebhookEvent {
+            event_type: input.event_type,
+            timestamp: Utc::now(),
+            data: input.data.unwrap_or_else(|| {
+                serde_json::json!({
+                    "synthetic": true,
+                    "webhook_id": webhook_id.to_string(),
+                })
+            }),
+        };
+
+        Ok(self
+            .timed_delivery(&webhook, &event, WebhookDeliverySource::Synthetic, None)
+            .await)
+    }
+
+    async fn timed_delivery(
+        &self,
+        webhook: &Webhook,
+        event: &WebhookEvent,
+        source: WebhookDeliverySource,
+        replay_of: Option<StringUuid>,
+    ) -> WebhookTestResult {
+        let result = timed_delivery(self.http_client.as_ref(), webhook, event).await;
+        record_delivery(
+            self.delivery_repo.as_deref(),
+            webhook,
+            event,
+            DeliveryAttempt {
+                source,
+                attempt: 1,
+                replay_of,
+            },
+            &result,
+        )
+        .await;
+        result
+    }
+
     /// Regenerate webhook secret
     pub async fn regenerate_secret(&self, id: StringUuid) -> Result<Webhook> {
         let new_secret = generate_webhook_secret();
@@ -245,6 +487,7 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
                     secret: Some(new_secret),
                     ..Default::default()
                 },
+                None,
             )
             .await
     }
@@ -269,6 +512,7 @@ impl<W: WebhookRepository + 'static> WebhookService<W> {
                             enabled: Some(false),
                             ..Default::default()
                         },
+                        None,
                     )
                     .await;
             }
@@ -283,7 +527,44 @@ impl<W: WebhookRepository + 'static> WebhookEventPublisher for WebhookService<W>
             .webhook_repo
             .list_enabled_for_event(&event.event_type)
             .await?;
+        self.dispatch(webhooks, event);
+        Ok(())
+    }
 
+    async fn trigger_tenant_event(&self, tenant_id: StringUuid, event: WebhookEvent) -> Result<()> {
+        let webhooks = self
+            .webhook_repo
+            .list_enabled_for_event(&event.event_type)
+            .await?
+            .into_iter()
+            .filter(|w| w.tenant_id == Some(tenant_id))
+            .collect();
+        self.dispatch(webhooks, event);
+        Ok(())
+    }
+
+    async fn trigger_platform_event(&self, event: WebhookEvent) -> Result<()> {
+        let webhooks = self
+            .webhook_repo
+            .list_enabled_for_event(&event.event_type)
+            .await?
+            .into_iter()
+            .filter(|w| w.tenant_id.is_none())
+            .collect();
+        self.dispatch(webhooks, event);
+        Ok(())
+    }
+}
+
+impl<W: WebhookRepository + 'static> WebhookService<W> {
+    /// Deliver an event to each webhook whose filter matches it in the
+    /// background, with retries
+    fn dispatch(&self, webhooks: Vec<Webhook>, event: WebhookEvent) {
+        let event_value = serde_json::to_value(&event).unwrap_or_default();
+        let webhooks: Vec<Webhook> = webhooks
+            .into_iter()
+            .filter(|webhook| filter_matches(webhook, &event_value))
+            .collect();
         tracing::info!(
             event_type = %event.event_type,
             webhook_count = webhooks.len(),
@@ -293,6 +574,7 @@ impl<W: WebhookRepository + 'static> WebhookEventPublisher for WebhookService<W>
         for webhook in webhooks {
             let http_client = self.http_client.clone();
             let webhook_repo = self.webhook_repo.clone();
+            let delivery_repo = self.delivery_repo.clone();
             let event_clone = event.clone();
             let webhook_clone = webhook.clone();
 
@@ -301,25 +583,35 @@ impl<W: WebhookRepository + 'static> WebhookEventPublisher for WebhookService<W>
 
                 // Retry with exponential backoff
                 for attempt in 0..MAX_RETRY_ATTEMPTS {
-                    match deliver_webhook(http_client.as_ref(), &webhook_clone, &event_clone).await
-                    {
-                        Ok(_) => {
-                            success = true;
-                            break;
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                "Webhook delivery attempt {}/{} failed for {}: {}",
-                                attempt + 1,
-                                MAX_RETRY_ATTEMPTS,
-                                webhook_clone.id,
-                                e
-                            );
-                            if attempt + 1 < MAX_RETRY_ATTEMPTS {
-                                let delay = Duration::from_secs(2u64.pow(attempt));
-                                tokio::time::sleep(delay).await;
-                            }
-                        }
+                    let result =
+                        timed_delivery(http_client.as_ref(), &webhook_clone, &event_clone).await;
+                    record_delivery(
+                        delivery_repo.as_deref(),
+                        &webhook_clone,
+                        &event_clone,
+                        DeliveryAttempt {
+                            source: WebhookDeliverySource::Event,
+                            attempt: attempt as i32 + 1,
+                            replay_of: None,
+                        },
+                        &result,
+                    )
+                    .await;
+                    if result.success {
+                        success = true;
+                        break;
+                    }
+
+                    tracing::warn!(
+                        "Webhook delivery attempt {}/{} failed for {}: {}",
+                        attempt + 1,
+                        MAX_RETRY_ATTEMPTS,
+                        webhook_clone.id,
+                        result.error.as_deref().unwrap_or("unknown error")
+                    );
+                    if attempt + 1 < MAX_RETRY_ATTEMPTS {
+                        let delay = Duration::from_secs(2u64.pow(attempt));
+                        tokio::time::sleep(delay).await;
                     }
                 }
 
@@ -344,6 +636,7 @@ impl<W: WebhookRepository + 'static> WebhookEventPublisher for WebhookService<W>
                                         enabled: Some(false),
                                         ..Default::default()
                                     },
+                                    None,
                                 )
                                 .await;
                         }
@@ -351,9 +644,62 @@ impl<W: WebhookRepository + 'static> WebhookEventPublisher for WebhookService<W>
                 }
             });
         }
+    }
+}
 
-        Ok(())
+/// Validate a new webhook, normalize its filter and generate a secret if
+/// none is provided
+fn prepare_create_input(mut input: CreateWebhookInput) -> Result<CreateWebhookInput> {
+    input.validate()?;
+    input.filter_expression = input
+        .filter_expression
+        .filter(|filter| !filter.trim().is_empty());
+    if let Some(filter) = &input.filter_expression {
+        parse_filter(filter)?;
+    }
+
+    // Auto-generate secret if not provided
+    if input.secret.is_none() {
+        input.secret = Some(generate_webhook_secret());
     }
+    Ok(input)
+}
+
+/// Platform webhooks only receive platform events
+fn validate_platform_events(events: &[String]) -> Result<()> {
+    match events
+        .iter()
+        .find(|e| !PLATFORM_WEBHOOK_EVENTS.contains(&e.as_str()))
+    {
+        Some(event) => Err(AppError::Validation(format!(
+            "Event '{}' is not available to platform webhooks (allowed: {})",
+            event,
+            PLATFORM_WEBHOOK_EVENTS.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Whether a webhook's filter expression lets an event through. Webhooks
+/// without a filter receive every subscribed event; evaluation errors (for
+/// example a field missing from the payload) count as no match.
+fn filter_matches(webhook: &Webhook, event: &serde_json::Value) -> bool {
+    let Some(filter) = webhook.filter_expression.as_deref() else {
+        return true;
+    };
+    let outcome = parse_filter(filter)
+        .map_err(|e| e.to_string())
+        .and_then(|expr| evaluate_filter(&expr, event));
+    let result = match &outcome {
+        Ok(true) => "matched",
+        Ok(false) => "filtered",
+        Err(e) => {
+            tracing::debug!(webhook_id = %webhook.id, error = %e, "Webhook filter evaluation failed");
+            "error"
+        }
+    };
+    metrics::counter!("auth9_webhook_filter_evaluations_total", "result" => result).increment(1);
+    outcome == Ok(true)
 }
 
 /// Result of a webhook test
@@ -366,79 +712,139 @@ pub struct WebhookTestResult {
     pub response_time_ms: Option<u64>,
 }
 
+/// Result of a webhook ping, including exactly what was sent
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WebhookPingResult {
+    #[serde(flatten)]
+    pub delivery: WebhookTestResult,
+    /// Raw request body that was signed and delivered
+    pub payload: String,
+    /// `X-Webhook-Signature` header value (absent when the webhook has no secret)
+    pub signature: Option<String>,
+    /// `X-Webhook-Timestamp` header value
+    pub timestamp: String,
+}
+
 /// Response from a webhook delivery
 struct WebhookResponse {
     status_code: u16,
     body: Option<String>,
 }
 
-/// Deliver a webhook event to a specific webhook endpoint
-async fn deliver_webhook(
+/// Deliver a webhook event once and time it
+async fn timed_delivery(
     client: &dyn WebhookHttpClient,
     webhook: &Webhook,
     event: &WebhookEvent,
-) -> Result<WebhookResponse> {
-    match deliver_webhook_with_status(client, webhook, event).await {
-        Ok(response) => Ok(response),
-        Err((_, msg)) => Err(AppError::Internal(anyhow::anyhow!("{}", msg))),
+) -> WebhookTestResult {
+    let start = Instant::now();
+    let result = deliver_webhook_with_status(client, webhook, event).await;
+    let response_time_ms = Some(start.elapsed().as_millis() as u64);
+    match result {
+        // Consider 2xx status codes as success
+        Ok(response) if (200..=299).contains(&response.status_code) => WebhookTestResult {
+            success: true,
+            status_code: Some(response.status_code),
+            response_body: response.body,
+            error: None,
+            response_time_ms,
+        },
+        Ok(response) => WebhookTestResult {
+            success: false,
+            status_code: Some(response.status_code),
+            error: Some(format!(
+                "Webhook returned error status {}",
+                response.status_code
+            )),
+            response_body: response.body,
+            response_time_ms,
+        },
+        Err(error_msg) => WebhookTestResult {
+            success: false,
+            status_code: None,
+            response_body: None,
+            error: Some(error_msg),
+            response_time_ms,
+        },
+    }
+}
+
+/// How a recorded delivery attempt came about
+struct DeliveryAttempt {
+    source: WebhookDeliverySource,
+    attempt: i32,
+    replay_of: Option<StringUuid>,
+}
+
+/// Append a delivery attempt to the log. Logging failures never affect delivery.
+async fn record_delivery(
+    repo: Option<&dyn WebhookDeliveryRepository>,
+    webhook: &Webhook,
+    event: &WebhookEvent,
+    attempt: DeliveryAttempt,
+    result: &WebhookTestResult,
+) {
+    let Some(repo) = repo else {
+        return;
+    };
+    let delivery = WebhookDelivery {
+        id: StringUuid::new_v4(),
+        webhook_id: webhook.id,
+        tenant_id: webhook.tenant_id,
+        event_type: event.event_type.clone(),
+        payload: serde_json::to_string(event).unwrap_or_default(),
+        source: attempt.source,
+        attempt: attempt.attempt,
+        success: result.success,
+        status_code: result.status_code.map(i32::from),
+        response_time_ms: result.response_time_ms.map(|ms| ms as i64),
+        response_snippet: result.response_body.as_deref().map(response_snippet),
+        error: result.error.as_deref().map(response_snippet),
+        replay_of: attempt.replay_of,
+        created_at: Utc::now(),
+    };
+    if let Err(e) = repo.create(&delivery).await {
+        tracing::warn!(webhook_id = %webhook.id, error = %e, "Failed to record webhook delivery");
     }
 }
 
-/// Deliver a webhook and return status code even on error
+/// Deliver a webhook, returning any HTTP response; errors are transport failures
 async fn deliver_webhook_with_status(
     client: &dyn WebhookHttpClient,
     webhook: &Webhook,
     event: &WebhookEvent,
-) -> std::result::Result<WebhookResponse, (Option<u16>, String)> {
-    let payload = serde_json::to_string(event).map_err(|e| (None, e.to_string()))?;
+) -> std::result::Result<WebhookResponse, String> {
+    let payload = serde_json::to_string(event).map_err(|e| e.to_string())?;
 
     let mut headers = vec![
         ("Content-Type".to_string(), "application/json".to_string()),
-        ("X-Webhook-Event".to_string(), event.event_type.clone()),
-        (
-            "X-Webhook-Timestamp".to_string(),
-            event.timestamp.to_rfc3339(),
-        ),
+        (EVENT_HEADER.to_string(), event.event_type.clone()),
+        (TIMESTAMP_HEADER.to_string(), event.timestamp.to_rfc3339()),
     ];
 
     // Add signature if secret is configured
     if let Some(secret) = &webhook.secret {
-        let signature = compute_signature(&payload, secret).map_err(|e| (None, e.to_string()))?;
-        headers.push(("X-Webhook-Signature".to_string(), signature));
+        let signature = compute_signature(&payload, secret);
+        headers.push((SIGNATURE_HEADER.to_string(), signature));
     }
 
     let (status_code, body) = client
         .post(&webhook.url, headers, payload, Duration::from_secs(15))
-        .await
-        .map_err(|e| (None, e))?;
-
-    // Consider 2xx status codes as success
-    if !(200..=299).contains(&status_code) {
-        return Err((
-            Some(status_code),
-            format!("Webhook returned error status {}: {:?}", status_code, body),
-        ));
-    }
+        .await?;
 
     Ok(WebhookResponse { status_code, body })
 }
 
 /// Compute HMAC-SHA256 signature for webhook payload
-fn compute_signature(payload: &str, secret: &str) -> Result<String> {
-    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-        .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid HMAC key: {}", e)))?;
-
-    mac.update(payload.as_bytes());
-    let result = mac.finalize();
-    let signature = hex::encode(result.into_bytes());
-
-    Ok(format!("sha256={}", signature))
+fn compute_signature(payload: &str, secret: &str) -> String {
+    crate::webhook::sign(payload.as_bytes(), secret)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::repository::webhook::MockWebhookRepository;
+    use crate::repository::webhook_delivery::MockWebhookDeliveryRepository;
     use mockall::predicate::*;
     use std::sync::Mutex;
 
@@ -479,17 +885,17 @@ mod tests {
         let payload = r#"{"event_type":"test","data":{}}"#;
         let secret = "my-secret-key";
 
-        let signature = compute_signature(payload, secret).unwrap();
+        let signature = compute_signature(payload, secret);
 
         // Signature should start with sha256=
         assert!(signature.starts_with("sha256="));
 
         // Same payload and secret should produce same signature
-        let signature2 = compute_signature(payload, secret).unwrap();
+        let signature2 = compute_signature(payload, secret);
         assert_eq!(signature, signature2);
 
         // Different secret should produce different signature
-        let signature3 = compute_signature(payload, "different-secret").unwrap();
+        let signature3 = compute_signature(payload, "different-secret");
         assert_ne!(signature, signature3);
     }
 
@@ -517,12 +923,73 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: None,
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
         let webhook = service.create(tenant_id, input).await.unwrap();
         assert_eq!(webhook.name, "Test Webhook");
-        assert_eq!(webhook.tenant_id, tenant_id);
+        assert_eq!(webhook.tenant_id, Some(tenant_id));
+    }
+
+    #[tokio::test]
+    async fn test_copy_to_tenant_regenerates_secret() {
+        let mut mock = MockWebhookRepository::new();
+        let target = StringUuid::new_v4();
+        mock.expect_list_by_tenant().returning(|_| Ok(vec![]));
+        mock.expect_create()
+            .withf(|_, input| input.secret.as_deref() != Some("whsec_source"))
+            .returning(|tenant_id, input| {
+                Ok(Webhook {
+                    tenant_id,
+                    name: input.name.clone(),
+                    url: input.url.clone(),
+                    events: input.events.clone(),
+                    ..Default::default()
+                })
+            });
+
+        let source = Webhook {
+            name: "CRM sync".to_string(),
+            url: "https://example.com/webhook".to_string(),
+            secret: Some("whsec_source".to_string()),
+            events: vec!["user.created".to_string()],
+            ..Default::default()
+        };
+        let copy = WebhookService::new(Arc::new(mock))
+            .copy_to_tenant(&source, target)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(copy.tenant_id, Some(target));
+        assert_eq!(copy.events, source.events);
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template_skips_existing_subscription() {
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_list_by_tenant().returning(|tenant_id| {
+            Ok(vec![Webhook {
+                tenant_id: Some(tenant_id),
+                name: "Security events".to_string(),
+                url: "https://example.com/webhook".to_string(),
+                ..Default::default()
+            }])
+        });
+        mock.expect_create().never();
+
+        let template = crate::models::webhook_bulk::find_webhook_template("security").unwrap();
+        let result = WebhookService::new(Arc::new(mock))
+            .create_from_template(
+                StringUuid::new_v4(),
+                template,
+                None,
+                "https://example.com/webhook",
+                true,
+            )
+            .await
+            .unwrap();
+        assert!(result.is_none());
     }
 
     #[tokio::test]
@@ -538,6 +1005,7 @@ mod tests {
             url: "not-a-valid-url".to_string(),
             secret: None,
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
@@ -632,7 +1100,7 @@ mod tests {
         let mut mock = MockWebhookRepository::new();
         let webhook_id = StringUuid::new_v4();
 
-        mock.expect_update().returning(|id, input| {
+        mock.expect_update().returning(|id, input, _| {
             Ok(Webhook {
                 id,
                 name: input.name.clone().unwrap_or_default(),
@@ -649,14 +1117,39 @@ mod tests {
             url: Some("https://example.com/updated".to_string()),
             secret: None,
             events: None,
+            filter_expression: None,
             enabled: Some(false),
         };
 
-        let webhook = service.update(webhook_id, input).await.unwrap();
+        let webhook = service.update(webhook_id, input, None).await.unwrap();
         assert_eq!(webhook.name, "Updated Webhook");
         assert!(!webhook.enabled);
     }
 
+    #[tokio::test]
+    async fn test_create_webhook_rejects_invalid_filter() {
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_create().never();
+        let service = WebhookService::new(Arc::new(mock));
+
+        let input = CreateWebhookInput {
+            name: "Filtered".to_string(),
+            url: "https://example.com/webhook".to_string(),
+            secret: None,
+            events: vec!["user.updated".to_string()],
+            filter_expression: Some("user.role == 'admin'".to_string()),
+            enabled: true,
+        };
+
+        let err = service
+            .create(StringUuid::new_v4(), input)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, AppError::BadRequest(msg) if msg.contains("unknown variable 'user'"))
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_webhook() {
         let mut mock = MockWebhookRepository::new();
@@ -688,7 +1181,7 @@ mod tests {
             move |_| {
                 Ok(vec![Webhook {
                     id: webhook_id,
-                    tenant_id,
+                    tenant_id: Some(tenant_id),
                     name: "Test Webhook".to_string(),
                     url: url.clone(),
                     events: vec!["login.success".to_string()],
@@ -727,6 +1220,210 @@ mod tests {
             .any(|(k, v)| k == "X-Webhook-Event" && v == "login.success"));
     }
 
+    #[tokio::test]
+    async fn test_trigger_event_skips_webhooks_whose_filter_does_not_match() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 200,
+            body: None,
+        });
+
+        let admin_webhook_id = StringUuid::new_v4();
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_list_enabled_for_event().returning(move |_| {
+            Ok(vec![
+                Webhook {
+                    id: admin_webhook_id,
+                    url: "https://admins.example.com/hook".to_string(),
+                    events: vec!["user.updated".to_string()],
+                    filter_expression: Some(r#"data.role == "admin""#.to_string()),
+                    ..Default::default()
+                },
+                Webhook {
+                    url: "https://viewers.example.com/hook".to_string(),
+                    events: vec!["user.updated".to_string()],
+                    filter_expression: Some(r#"data.role == "viewer""#.to_string()),
+                    ..Default::default()
+                },
+                Webhook {
+                    url: "https://missing.example.com/hook".to_string(),
+                    events: vec!["user.updated".to_string()],
+                    filter_expression: Some(r#"data.department == "eng""#.to_string()),
+                    ..Default::default()
+                },
+            ])
+        });
+        mock.expect_update_triggered()
+            .with(eq(admin_webhook_id), eq(true))
+            .returning(|_, _| Ok(()))
+            .times(1);
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http);
+        let event = WebhookEvent {
+            event_type: "user.updated".to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({"role": "admin"}),
+        };
+
+        service.trigger_event(event).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let reqs = requests.lock().unwrap();
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].url, "https://admins.example.com/hook");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_tenant_event_skips_other_tenants() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 200,
+            body: None,
+        });
+
+        let tenant_id = StringUuid::new_v4();
+        let own_webhook_id = StringUuid::new_v4();
+
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_list_enabled_for_event().returning(move |_| {
+            Ok(vec![
+                Webhook {
+                    id: own_webhook_id,
+                    tenant_id: Some(tenant_id),
+                    url: "https://own.example.com/hook".to_string(),
+                    events: vec!["job.completed".to_string()],
+                    enabled: true,
+                    ..Default::default()
+                },
+                Webhook {
+                    id: StringUuid::new_v4(),
+                    tenant_id: Some(StringUuid::new_v4()),
+                    url: "https://other.example.com/hook".to_string(),
+                    events: vec!["job.completed".to_string()],
+                    enabled: true,
+                    ..Default::default()
+                },
+            ])
+        });
+        mock.expect_update_triggered()
+            .with(eq(own_webhook_id), eq(true))
+            .returning(|_, _| Ok(()))
+            .times(1);
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http);
+        let event = WebhookEvent {
+            event_type: "job.completed".to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({"job_id": "job-1"}),
+        };
+
+        service
+            .trigger_tenant_event(tenant_id, event)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let reqs = requests.lock().unwrap();
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].url, "https://own.example.com/hook");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_platform_event_skips_tenant_webhooks() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 200,
+            body: None,
+        });
+
+        let platform_webhook_id = StringUuid::new_v4();
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_list_enabled_for_event().returning(move |_| {
+            Ok(vec![
+                Webhook {
+                    id: platform_webhook_id,
+                    tenant_id: None,
+                    url: "https://ops.example.com/hook".to_string(),
+                    events: vec!["tenant.created".to_string()],
+                    ..Default::default()
+                },
+                Webhook {
+                    url: "https://tenant.example.com/hook".to_string(),
+                    events: vec!["tenant.created".to_string()],
+                    ..Default::default()
+                },
+            ])
+        });
+        mock.expect_update_triggered()
+            .with(eq(platform_webhook_id), eq(true))
+            .returning(|_, _| Ok(()))
+            .times(1);
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http);
+        service
+            .trigger_platform_event(WebhookEvent {
+                event_type: "tenant.created".to_string(),
+                timestamp: Utc::now(),
+                data: serde_json::json!({"tenant_id": "t-1"}),
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let reqs = requests.lock().unwrap();
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].url, "https://ops.example.com/hook");
+    }
+
+    #[tokio::test]
+    async fn test_create_platform_webhook_limits_events() {
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_create()
+            .withf(|tenant_id, _| tenant_id.is_none())
+            .times(1)
+            .returning(|tenant_id, input| {
+                Ok(Webhook {
+                    tenant_id,
+                    events: input.events.clone(),
+                    ..Default::default()
+                })
+            });
+        let service = WebhookService::new(Arc::new(mock));
+        let input = |events: &[&str]| CreateWebhookInput {
+            name: "Provisioning".to_string(),
+            url: "https://ops.example.com/hook".to_string(),
+            secret: None,
+            events: events.iter().map(|e| e.to_string()).collect(),
+            filter_expression: None,
+            enabled: true,
+        };
+
+        let webhook = service
+            .create_platform(input(&["tenant.created", "tenant.deleted"]))
+            .await
+            .unwrap();
+        assert!(webhook.tenant_id.is_none());
+
+        let result = service
+            .create_platform(input(&["tenant.created", "user.created"]))
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+        let result = service
+            .update_platform(
+                StringUuid::new_v4(),
+                UpdateWebhookInput {
+                    events: Some(vec!["login.success".to_string()]),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
     #[tokio::test]
     async fn test_trigger_event_no_webhooks() {
         let mut mock = MockWebhookRepository::new();
@@ -763,7 +1460,7 @@ mod tests {
             move |_| {
                 Ok(vec![Webhook {
                     id: webhook_id,
-                    tenant_id,
+                    tenant_id: Some(tenant_id),
                     name: "Signed Webhook".to_string(),
                     url: url.clone(),
                     events: vec!["user.created".to_string()],
@@ -802,7 +1499,7 @@ mod tests {
         assert!(sig.is_some());
 
         // The signature must match the recorded payload and the configured secret.
-        let expected = compute_signature(&req.body, "webhook-secret").unwrap();
+        let expected = compute_signature(&req.body, "webhook-secret");
         assert_eq!(sig.unwrap(), expected);
     }
 
@@ -852,6 +1549,58 @@ mod tests {
             .any(|(k, v)| k == "X-Webhook-Event" && v == "test"));
     }
 
+    #[tokio::test]
+    async fn test_ping_webhook_sends_verifiable_delivery() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 200,
+            body: Some("ok".to_string()),
+        });
+
+        let webhook_id = StringUuid::new_v4();
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_find_by_id().returning(move |id| {
+            Ok(Some(Webhook {
+                id,
+                name: "Receiver".to_string(),
+                url: "https://example.com/webhook".to_string(),
+                enabled: true,
+                secret: Some("ping-secret".to_string()),
+                ..Default::default()
+            }))
+        });
+        // Pings never touch the failure tally
+        mock.expect_update_triggered().never();
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http);
+        let result = service.ping(webhook_id).await.unwrap();
+        assert!(result.delivery.success);
+
+        let reqs = requests.lock().unwrap();
+        assert_eq!(reqs.len(), 1);
+        let req = &reqs[0];
+        assert_eq!(req.body, result.payload);
+        let header = |name: &str| {
+            req.headers
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.clone())
+                .unwrap()
+        };
+        assert_eq!(Some(header(SIGNATURE_HEADER)), result.signature);
+
+        let event = crate::webhook::verify_signature(
+            req.body.as_bytes(),
+            &header(SIGNATURE_HEADER),
+            &header(TIMESTAMP_HEADER),
+            "ping-secret",
+            crate::webhook::DEFAULT_TOLERANCE,
+        )
+        .unwrap();
+        assert_eq!(event.event_type, "webhook.ping");
+    }
+
     #[tokio::test]
     async fn test_test_webhook_failure_http_error() {
         let http = Arc::new(RecordingHttpClient {
@@ -889,6 +1638,10 @@ mod tests {
         assert_eq!(result.status_code, Some(500));
         assert!(result.error.is_some());
         assert!(result.error.unwrap().contains("500"));
+        assert_eq!(
+            result.response_body.as_deref(),
+            Some("Internal Server Error")
+        );
         // response_time_ms should be present
         assert!(result.response_time_ms.is_some());
     }
@@ -925,15 +1678,16 @@ mod tests {
             .returning(|_, _| Ok(()))
             .times(1);
         mock.expect_update()
-            .withf(move |id, input| {
+            .withf(move |id, input, expected_version| {
                 *id == webhook_id
+                    && expected_version.is_none()
                     && input.enabled == Some(false)
                     && input.name.is_none()
                     && input.url.is_none()
                     && input.secret.is_none()
                     && input.events.is_none()
             })
-            .returning(move |_, _| Ok(webhook_after_disable.clone()))
+            .returning(move |_, _, _| Ok(webhook_after_disable.clone()))
             .times(1);
 
         let service = WebhookService::new_with_http(Arc::new(mock), http);
@@ -1020,6 +1774,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: None, // No secret provided
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
@@ -1053,6 +1808,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: Some("user-provided-secret".to_string()),
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
@@ -1062,7 +1818,7 @@ mod tests {
 
     #[test]
     fn test_signature_format() {
-        let signature = compute_signature("test payload", "secret").unwrap();
+        let signature = compute_signature("test payload", "secret");
 
         // Verify format: sha256=<hex>
         assert!(signature.starts_with("sha256="));
@@ -1072,4 +1828,142 @@ mod tests {
         // All characters should be valid hex
         assert!(hex_part.chars().all(|c| c.is_ascii_hexdigit()));
     }
+
+    fn receiver(id: StringUuid) -> Webhook {
+        Webhook {
+            id,
+            name: "Receiver".to_string(),
+            url: "https://example.com/webhook".to_string(),
+            enabled: true,
+            secret: Some("replay-secret".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_resends_event_with_fresh_timestamp() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 200,
+            body: Some("ok".to_string()),
+        });
+
+        let webhook_id = StringUuid::new_v4();
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(receiver(id))));
+        mock.expect_update_triggered().never();
+
+        let original_event = WebhookEvent {
+            event_type: "user.created".to_string(),
+            timestamp: Utc::now() - chrono::Duration::days(2),
+            data: serde_json::json!({ "user_id": "u-1" }),
+        };
+        let original = WebhookDelivery {
+            webhook_id,
+            event_type: original_event.event_type.clone(),
+            payload: serde_json::to_string(&original_event).unwrap(),
+            ..Default::default()
+        };
+        let original_id = original.id;
+
+        let mut deliveries = MockWebhookDeliveryRepository::new();
+        deliveries
+            .expect_find_by_id()
+            .with(eq(original_id))
+            .returning(move |_| Ok(Some(original.clone())));
+        deliveries
+            .expect_create()
+            .withf(move |d| {
+                d.source == WebhookDeliverySource::Replay
+                    && d.replay_of == Some(original_id)
+                    && d.success
+                    && d.response_snippet.as_deref() == Some("ok")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http)
+            .with_delivery_repo(Arc::new(deliveries));
+        let result = service.replay(webhook_id, original_id).await.unwrap();
+        assert!(result.success);
+
+        let reqs = requests.lock().unwrap();
+        let sent: WebhookEvent = serde_json::from_str(&reqs[0].body).unwrap();
+        assert_eq!(sent.event_type, "user.created");
+        assert_eq!(sent.data, original_event.data);
+        assert!(sent.timestamp > original_event.timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejects_delivery_of_other_webhook() {
+        let http = Arc::new(RecordingHttpClient {
+            requests: Arc::new(Mutex::new(Vec::new())),
+            status: 200,
+            body: None,
+        });
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(receiver(id))));
+
+        let mut deliveries = MockWebhookDeliveryRepository::new();
+        deliveries
+            .expect_find_by_id()
+            .returning(|_| Ok(Some(WebhookDelivery::default())));
+        deliveries.expect_create().never();
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http)
+            .with_delivery_repo(Arc::new(deliveries));
+        let result = service
+            .replay(StringUuid::new_v4(), StringUuid::new_v4())
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_send_synthetic_event_records_delivery() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http = Arc::new(RecordingHttpClient {
+            requests: requests.clone(),
+            status: 503,
+            body: Some("maintenance".to_string()),
+        });
+        let mut mock = MockWebhookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(receiver(id))));
+        mock.expect_update_triggered().never();
+
+        let mut deliveries = MockWebhookDeliveryRepository::new();
+        deliveries
+            .expect_create()
+            .withf(|d| {
+                d.source == WebhookDeliverySource::Synthetic
+                    && d.event_type == "tenant.deleted"
+                    && !d.success
+                    && d.status_code == Some(503)
+                    && d.response_snippet.as_deref() == Some("maintenance")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = WebhookService::new_with_http(Arc::new(mock), http)
+            .with_delivery_repo(Arc::new(deliveries));
+        let result = service
+            .send_synthetic(
+                StringUuid::new_v4(),
+                SendSyntheticEventInput {
+                    event_type: "tenant.deleted".to_string(),
+                    data: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        let reqs = requests.lock().unwrap();
+        let sent: WebhookEvent = serde_json::from_str(&reqs[0].body).unwrap();
+        assert_eq!(sent.event_type, "tenant.deleted");
+        assert_eq!(sent.data["synthetic"], true);
+    }
 }
@@ -0,0 +1,630 @@
+//! Webhook event filter expressions
+//!
+//! Recursive descent parser and evaluator for the subset of CEL (Common
+//! Expression Language) used to filter webhook events. Expressions are
+//! evaluated against the delivered event, so `event_type`, `timestamp` and
+//! `data` are in scope:
+//!
+//! ```text
+//! event_type == "role.assigned" && data.role in ["admin", "owner"]
+//! has(data.email) && data.email.endsWith("@example.com")
+//! ```
+//!
+//! Supported: literals (string, int, double, bool, null, list), field and
+//! index access, `!`, `&&`, `||`, comparisons, `in`, `has()`, `size()` and the
+//! string methods `startsWith`, `endsWith` and `contains`. Like CEL, `&&` and
+//! `||` absorb errors when the other side decides the result.
+
+use crate::error::{AppError, Result};
+use serde_json::Value;
+
+/// Maximum filter expression length in bytes
+pub const MAX_FILTER_LENGTH: usize = 1024;
+
+/// Variables in scope of a filter expression
+pub const FILTER_VARIABLES: &[&str] = &["event_type", "timestamp", "data"];
+
+/// Maximum nesting depth of a parsed expression
+const MAX_DEPTH: usize = 32;
+
+/// Parsed filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    Literal(Value),
+    List(Vec<FilterExpr>),
+    Ident(String),
+    Select(Box<FilterExpr>, String),
+    Index(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Compare(Box<FilterExpr>, CompareOp, Box<FilterExpr>),
+    Has(Box<FilterExpr>),
+    Size(Box<FilterExpr>),
+    Method(Box<FilterExpr>, StringMethod, Box<FilterExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    In,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringMethod {
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+/// Parse and validate a filter expression.
+///
+/// Fails for syntax errors and for identifiers that are not in
+/// [`FILTER_VARIABLES`], so typos are caught when the webhook is saved.
+pub fn parse_filter(input: &str) -> Result<FilterExpr> {
+    if input.trim().is_empty() {
+        return Err(invalid("expression is empty"));
+    }
+    if input.len() > MAX_FILTER_LENGTH {
+        return Err(invalid(&format!(
+            "expression is longer than {} bytes",
+            MAX_FILTER_LENGTH
+        )));
+    }
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(token) = tokens.get(parser.pos) {
+        return Err(invalid(&format!("unexpected token '{}'", token)));
+    }
+    Ok(expr)
+}
+
+/// Evaluate a filter against an event. Errors (missing fields, type
+/// mismatches) are returned so callers can count them separately.
+pub fn evaluate_filter(expr: &FilterExpr, event: &Value) -> std::result::Result<bool, String> {
+    match eval(expr, event)? {
+        Value::Bool(matched) => Ok(matched),
+        other => Err(format!("filter evaluated to {} instead of a bool", other)),
+    }
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::BadRequest(format!("Invalid webhook filter: {}", message))
+}
+
+// ==================== Tokenizer ====================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Double(f64),
+    Op(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "{}", s),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Int(i) => write!(f, "{}", i),
+            Token::Double(d) => write!(f, "{}", d),
+            Token::Op(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")", "[", "]", ".", ",",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(invalid("unterminated string literal")),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(&e @ ('\\' | '"' | '\'')) => value.push(e),
+                            _ => return Err(invalid("unsupported escape sequence")),
+                        }
+                    }
+                    Some(&other) => value.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(value));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let token = if text.contains('.') {
+                text.parse().map(Token::Double).ok()
+            } else {
+                text.parse().map(Token::Int).ok()
+            };
+            tokens.push(token.ok_or_else(|| invalid(&format!("invalid number '{}'", text)))?);
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| invalid(&format!("unexpected character '{}'", c)))?;
+            tokens.push(Token::Op(*op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+// ==================== Parser ====================
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<()> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(invalid(&format!("expected '{}'", op)))
+        }
+    }
+
+    fn descend(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(invalid("expression is nested too deeply"));
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<FilterExpr> {
+        self.descend()?;
+        let mut left = self.parse_and()?;
+        while self.eat_op("||") {
+            let right = self.parse_and()?;
+            left = FilterExpr::Or(Box::new(left), Box::new(right));
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<FilterExpr> {
+        let mut left = self.parse_relation()?;
+        while self.eat_op("&&") {
+            let right = self.parse_relation()?;
+            left = FilterExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_relation(&mut self) -> Result<FilterExpr> {
+        let left = self.parse_unary()?;
+        let op = match self.peek() {
+            Some(Token::Op("==")) => CompareOp::Eq,
+            Some(Token::Op("!=")) => CompareOp::Ne,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::Le,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::Ge,
+            Some(Token::Ident(word)) if word == "in" => CompareOp::In,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.parse_unary()?;
+        Ok(FilterExpr::Compare(Box::new(left), op, Box::new(right)))
+    }
+
+    fn parse_unary(&mut self) -> Result<FilterExpr> {
+        if self.eat_op("!") {
+            self.descend()?;
+            let inner = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(FilterExpr::Not(Box::new(inner)));
+        }
+        self.parse_member()
+    }
+
+    fn parse_member(&mut self) -> Result<FilterExpr> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            if self.eat_op(".") {
+                let Some(Token::Ident(name)) = self.peek().cloned() else {
+                    return Err(invalid("expected a field name after '.'"));
+                };
+                self.pos += 1;
+                if self.eat_op("(") {
+                    expr = self.parse_method(expr, &name)?;
+                } else {
+                    expr = FilterExpr::Select(Box::new(expr), name);
+                }
+            } else if self.eat_op("[") {
+                let index = self.parse_or()?;
+                self.expect_op("]")?;
+                expr = FilterExpr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_method(&mut self, target: FilterExpr, name: &str) -> Result<FilterExpr> {
+        if name == "size" {
+            self.expect_op(")")?;
+            return Ok(FilterExpr::Size(Box::new(target)));
+        }
+        let method = match name {
+            "startsWith" => StringMethod::StartsWith,
+            "endsWith" => StringMethod::EndsWith,
+            "contains" => StringMethod::Contains,
+            other => return Err(invalid(&format!("unknown method '{}'", other))),
+        };
+        let arg = self.parse_or()?;
+        self.expect_op(")")?;
+        Ok(FilterExpr::Method(Box::new(target), method, Box::new(arg)))
+    }
+
+    fn parse_primary(&mut self) -> Result<FilterExpr> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| invalid("unexpected end of expression"))?;
+        self.pos += 1;
+        match token {
+            Token::Str(s) => Ok(FilterExpr::Literal(Value::String(s))),
+            Token::Int(i) => Ok(FilterExpr::Literal(Value::from(i))),
+            Token::Double(d) => Ok(FilterExpr::Literal(Value::from(d))),
+            Token::Op("(") => {
+                let expr = self.parse_or()?;
+                self.expect_op(")")?;
+                Ok(expr)
+            }
+            Token::Op("[") => {
+                let mut items = Vec::new();
+                if !self.eat_op("]") {
+                    loop {
+                        items.push(self.parse_or()?);
+                        if self.eat_op("]") {
+                            break;
+                        }
+                        self.expect_op(",")?;
+                    }
+                }
+                Ok(FilterExpr::List(items))
+            }
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(FilterExpr::Literal(Value::Bool(true))),
+                "false" => Ok(FilterExpr::Literal(Value::Bool(false))),
+                "null" => Ok(FilterExpr::Literal(Value::Null)),
+                "has" => {
+                    self.expect_op("(")?;
+                    let arg = self.parse_or()?;
+                    self.expect_op(")")?;
+                    if !matches!(arg, FilterExpr::Select(..)) {
+                        return Err(invalid("has() expects a field selection like data.role"));
+                    }
+                    Ok(FilterExpr::Has(Box::new(arg)))
+                }
+                "size" if self.eat_op("(") => {
+                    let arg = self.parse_or()?;
+                    self.expect_op(")")?;
+                    Ok(FilterExpr::Size(Box::new(arg)))
+                }
+                _ if FILTER_VARIABLES.contains(&name.as_str()) => Ok(FilterExpr::Ident(name)),
+                _ => Err(invalid(&format!(
+                    "unknown variable '{}' (available: {})",
+                    name,
+                    FILTER_VARIABLES.join(", ")
+                ))),
+            },
+            other => Err(invalid(&format!("unexpected token '{}'", other))),
+        }
+    }
+}
+
+// ==================== Evaluator ====================
+
+type EvalResult = std::result::Result<Value, String>;
+
+fn eval(expr: &FilterExpr, event: &Value) -> EvalResult {
+    match expr {
+        FilterExpr::Literal(value) => Ok(value.clone()),
+        FilterExpr::List(items) => items
+            .iter()
+            .map(|item| eval(item, event))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map(Value::Array),
+        FilterExpr::Ident(name) => event
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("no such key: {}", name)),
+        FilterExpr::Select(target, field) => match eval(target, event)? {
+            Value::Object(map) => map
+                .get(field)
+                .cloned()
+                .ok_or_else(|| format!("no such key: {}", field)),
+            other => Err(format!(
+                "cannot select '{}' from {}",
+                field,
+                type_name(&other)
+            )),
+        },
+        FilterExpr::Index(target, index) => {
+            let target = eval(target, event)?;
+            let index = eval(index, event)?;
+            match (&target, &index) {
+                (Value::Array(items), Value::Number(n)) => n
+                    .as_u64()
+                    .and_then(|i| items.get(i as usize))
+                    .cloned()
+                    .ok_or_else(|| format!("index {} out of range", n)),
+                (Value::Object(map), Value::String(key)) => map
+                    .get(key)
+                    .cloned()
+                    .ok_or_else(|| format!("no such key: {}", key)),
+                _ => Err(format!(
+                    "cannot index {} with {}",
+                    type_name(&target),
+                    type_name(&index)
+                )),
+            }
+        }
+        FilterExpr::Not(inner) => Ok(Value::Bool(!as_bool(eval(inner, event)?)?)),
+        FilterExpr::And(left, right) => logical(left, right, event, false),
+        FilterExpr::Or(left, right) => logical(left, right, event, true),
+        FilterExpr::Compare(left, op, right) => {
+            let left = eval(left, event)?;
+            let right = eval(right, event)?;
+            compare(&left, *op, &right).map(Value::Bool)
+        }
+        FilterExpr::Has(selection) => {
+            let FilterExpr::Select(target, field) = selection.as_ref() else {
+                return Err("has() expects a field selection".to_string());
+            };
+            match eval(target, event)? {
+                Value::Object(map) => Ok(Value::Bool(map.contains_key(field))),
+                other => Err(format!(
+                    "cannot select '{}' from {}",
+                    field,
+                    type_name(&other)
+                )),
+            }
+        }
+        FilterExpr::Size(target) => match eval(target, event)? {
+            Value::String(s) => Ok(Value::from(s.chars().count() as u64)),
+            Value::Array(items) => Ok(Value::from(items.len() as u64)),
+            Value::Object(map) => Ok(Value::from(map.len() as u64)),
+            other => Err(format!("size() is not defined for {}", type_name(&other))),
+        },
+        FilterExpr::Method(target, method, arg) => {
+            let (Value::String(target), Value::String(arg)) =
+                (eval(target, event)?, eval(arg, event)?)
+            else {
+                return Err("string methods require string operands".to_string());
+            };
+            Ok(Value::Bool(match method {
+                StringMethod::StartsWith => target.starts_with(&arg),
+                StringMethod::EndsWith => target.ends_with(&arg),
+                StringMethod::Contains => target.contains(&arg),
+            }))
+        }
+    }
+}
+
+/// `&&` (`short_circuit = false`) and `||` (`short_circuit = true`): a side
+/// evaluating to `short_circuit` decides the result even if the other errs
+fn logical(
+    left: &FilterExpr,
+    right: &FilterExpr,
+    event: &Value,
+    short_circuit: bool,
+) -> EvalResult {
+    let left = eval(left, event).and_then(as_bool);
+    if left == Ok(short_circuit) {
+        return Ok(Value::Bool(short_circuit));
+    }
+    let right = eval(right, event).and_then(as_bool);
+    if right == Ok(short_circuit) {
+        return Ok(Value::Bool(short_circuit));
+    }
+    left?;
+    right?;
+    Ok(Value::Bool(!short_circuit))
+}
+
+fn as_bool(value: Value) -> std::result::Result<bool, String> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => Err(format!("expected bool, got {}", type_name(&other))),
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> std::result::Result<bool, String> {
+    use std::cmp::Ordering;
+
+    let ordering = || -> std::result::Result<Ordering, String> {
+        match (left, right) {
+            (Value::Number(a), Value::Number(b)) => a
+                .as_f64()
+                .zip(b.as_f64())
+                .and_then(|(a, b)| a.partial_cmp(&b))
+                .ok_or_else(|| "numbers are not comparable".to_string()),
+            (Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+            _ => Err(format!(
+                "cannot compare {} with {}",
+                type_name(left),
+                type_name(right)
+            )),
+        }
+    };
+
+    match op {
+        CompareOp::Eq => Ok(values_equal(left, right)),
+        CompareOp::Ne => Ok(!values_equal(left, right)),
+        CompareOp::Lt => Ok(ordering()? == Ordering::Less),
+        CompareOp::Le => Ok(ordering()? != Ordering::Greater),
+        CompareOp::Gt => Ok(ordering()? == Ordering::Greater),
+        CompareOp::Ge => Ok(ordering()? != Ordering::Less),
+        CompareOp::In => match right {
+            Value::Array(items) => Ok(items.iter().any(|item| values_equal(left, item))),
+            Value::Object(map) => match left {
+                Value::String(key) => Ok(map.contains_key(key)),
+                _ => Err("map membership requires a string key".to_string()),
+            },
+            other => Err(format!("'in' is not defined for {}", type_name(other))),
+        },
+    }
+}
+
+/// Equality with ints and doubles compared by value
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "list",
+        Value::Object(_) => "map",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event() -> Value {
+        json!({
+            "event_type": "role.assigned",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "data": {
+                "role": "admin",
+                "email": "alice@example.com",
+                "attempts": 3,
+                "groups": ["eng", "ops"]
+            }
+        })
+    }
+
+    fn matches(filter: &str) -> std::result::Result<bool, String> {
+        evaluate_filter(&parse_filter(filter).unwrap(), &event())
+    }
+
+    #[test]
+    fn test_equality_and_logic() {
+        assert_eq!(
+            matches(r#"event_type == "role.assigned" && data.role == "admin""#),
+            Ok(true)
+        );
+        assert_eq!(matches(r#"data.role != 'admin' || false"#), Ok(false));
+        assert_eq!(matches(r#"!(data.role == "viewer")"#), Ok(true));
+    }
+
+    #[test]
+    fn test_comparisons_and_membership() {
+        assert_eq!(matches("data.attempts >= 3"), Ok(true));
+        assert_eq!(matches("data.attempts < 2.5"), Ok(false));
+        assert_eq!(matches(r#"data.role in ["admin", "owner"]"#), Ok(true));
+        assert_eq!(matches(r#""ops" in data.groups"#), Ok(true));
+        assert_eq!(matches(r#""role" in data"#), Ok(true));
+        assert_eq!(matches("data.groups[1] == 'ops'"), Ok(true));
+    }
+
+    #[test]
+    fn test_functions_and_methods() {
+        assert_eq!(matches("has(data.email)"), Ok(true));
+        assert_eq!(matches("has(data.phone)"), Ok(false));
+        assert_eq!(matches("size(data.groups) == 2"), Ok(true));
+        assert_eq!(matches("data.role.size() == 5"), Ok(true));
+        assert_eq!(
+            matches(r#"data.email.endsWith("@example.com") && event_type.startsWith("role.")"#),
+            Ok(true)
+        );
+        assert_eq!(matches(r#"data.email.contains("bob")"#), Ok(false));
+    }
+
+    #[test]
+    fn test_missing_field_is_an_error_unless_absorbed() {
+        assert!(matches(r#"data.phone == "1""#).is_err());
+        assert_eq!(matches(r#"false && data.phone == "1""#), Ok(false));
+        assert_eq!(matches(r#"data.phone == "1" || true"#), Ok(true));
+        assert!(matches(r#"data.role"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_expressions() {
+        for filter in [
+            "",
+            "data.role ==",
+            "user.role == 'admin'",
+            "data.role == 'admin",
+            "data.role.matches('a.*')",
+            "has(data)",
+            "data.role = 'admin'",
+            "(data.role == 'admin'",
+        ] {
+            assert!(parse_filter(filter).is_err(), "{} should not parse", filter);
+        }
+        assert!(parse_filter(&"!".repeat(64)).is_err());
+        assert!(
+            parse_filter(&format!("data.role == '{}'", "a".repeat(MAX_FILTER_LENGTH))).is_err()
+        );
+    }
+}
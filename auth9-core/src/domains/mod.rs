@@ -1,6 +1,7 @@
 //! Bounded-context modules grouping API, service, and route layers.
 
 pub mod authorization;
+pub mod events;
 pub mod identity;
 pub mod integration;
 pub mod platform;
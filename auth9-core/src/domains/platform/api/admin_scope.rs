@@ -0,0 +1,111 @@
+//! Platform admin scope management APIs
+
+use crate::domains::platform::service::admin_scope::AdminScopeService;
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::{AuthUser, TokenType};
+use crate::models::admin_scope::{AdminScope, AdminScopesResponse, SetAdminScopesInput};
+use crate::models::common::StringUuid;
+use crate::repository::admin_scope::AdminScopeRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn admin_scope_service<S: HasDbPool>(state: &S) -> AdminScopeService<AdminScopeRepositoryImpl> {
+    AdminScopeService::new(Arc::new(AdminScopeRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+}
+
+fn is_root_admin<S: HasServices>(state: &S, auth: &AuthUser) -> bool {
+    auth.token_type == TokenType::Identity && state.config().is_platform_admin_email(&auth.email)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/scopes",
+    tag = "Platform",
+    params(("id" = String, Path, description = "User ID (UUID)")),
+    responses(
+        (status = 200, description = "Admin scopes granted to the user", body = AdminScopesResponse),
+        (status = 403, description = "Forbidden")
+    )
+)]
+pub async fn get_admin_scopes<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<AdminScopesResponse>>> {
+    // Admins may always see their own scopes
+    if auth.user_id != id && !is_root_admin(&state, &auth) {
+        return Err(AppError::Forbidden(
+            "Root platform admin required".to_string(),
+        ));
+    }
+
+    let user_id = StringUuid::from(id);
+    let scopes = if auth.user_id == id && is_root_admin(&state, &auth) {
+        AdminScope::ALL.to_vec()
+    } else {
+        admin_scope_service(&state).scopes_for(user_id).await?
+    };
+    Ok(Json(SuccessResponse::new(AdminScopesResponse {
+        user_id,
+        scopes,
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{id}/scopes",
+    tag = "Platform",
+    params(("id" = String, Path, description = "User ID (UUID)")),
+    request_body = SetAdminScopesInput,
+    responses(
+        (status = 200, description = "Scopes replaced", body = AdminScopesResponse),
+        (status = 403, description = "Forbidden")
+    )
+)]
+pub async fn set_admin_scopes<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<SetAdminScopesInput>,
+) -> Result<Json<SuccessResponse<AdminScopesResponse>>> {
+    if !is_root_admin(&state, &auth) {
+        return Err(AppError::Forbidden(
+            "Only platform admins listed in PLATFORM_ADMIN_EMAILS can grant admin scopes"
+                .to_string(),
+        ));
+    }
+
+    let user_id = StringUuid::from(id);
+    state.user_service().get(user_id).await?;
+
+    let service = admin_scope_service(&state);
+    let before = service.scopes_for(user_id).await?;
+    let scopes = service
+        .set_scopes(user_id, input.scopes, StringUuid::from(auth.user_id))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "admin_scope.update",
+        "user",
+        Some(id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&scopes).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(AdminScopesResponse {
+        user_id,
+        scopes,
+    })))
+}
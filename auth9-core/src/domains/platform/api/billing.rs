@@ -0,0 +1,104 @@
+//! Billing event ledger API handlers
+
+use crate::domains::platform::service::BillingService;
+use crate::error::Result;
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, PaginatedResponse, PaginationQuery,
+    SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::billing::{BillingEvent, BillingEventFilter, NewBillingEvent};
+use crate::models::common::StringUuid;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+fn billing_service<S: HasDbPool>(state: &S) -> BillingService {
+    BillingService::from_pool(state.db_pool().clone())
+}
+
+/// Record a billing event when a billing sink is configured.
+///
+/// Best effort like audit logging: a ledger failure never fails the request
+/// that produced the event.
+pub(crate) async fn record_billing_event<S: HasServices + HasDbPool>(
+    state: &S,
+    event: NewBillingEvent,
+) {
+    if !state.config().billing.is_enabled() {
+        return;
+    }
+    if let Err(e) = billing_service(state).record(&event).await {
+        tracing::warn!(
+            error = %e,
+            idempotency_key = %event.idempotency_key,
+            "Failed to record billing event"
+        );
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/billing/events",
+    tag = "Platform",
+    params(BillingEventFilter),
+    responses(
+        (status = 200, description = "Billing ledger entries, newest first")
+    )
+)]
+pub async fn list_billing_events<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(pagination): Query<PaginationQuery>,
+    Query(filter): Query<BillingEventFilter>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let (items, total) = billing_service(&state)
+        .list(&filter, pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/billing/events/{id}/redeliver",
+    tag = "Platform",
+    params(("id" = String, Path, description = "Billing event ID (UUID)")),
+    responses(
+        (status = 200, description = "Event queued for delivery again", body = BillingEvent),
+        (status = 404, description = "Billing event not found"),
+        (status = 409, description = "Event has not failed")
+    )
+)]
+pub async fn redeliver_billing_event<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let event = billing_service(&state)
+        .redeliver(StringUuid::from(id))
+        .await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "system.billing.event.redeliver",
+        "billing_event",
+        Some(id),
+        None,
+        serde_json::to_value(&event).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(event)))
+}
@@ -7,12 +7,14 @@ use crate::http_support::{
 };
 use crate::middleware::auth::AuthUser;
 use crate::models::branding::{
-    PublicBrandingQuery, UpdateBrandingRequest, UpdateServiceBrandingRequest,
+    BrandingContext, BrandingContextQuery, PublicBrandingQuery, UpdateBrandingRequest,
+    UpdateServiceBrandingRequest,
 };
 use crate::models::common::StringUuid;
+use crate::models::custom_domain::CustomDomainTenant;
 use crate::state::{HasBranding, HasServices};
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::HeaderMap,
     response::IntoResponse,
     Json,
@@ -48,6 +50,44 @@ pub async fn get_public_branding<S: HasBranding>(
     Ok(Json(SuccessResponse::new(config)))
 }
 
+/// Get the resolved branding for a tenant (public endpoint)
+///
+/// Hosted pages render from this: the tenant's name, logo, color and support
+/// links layered over the platform branding. The tenant is taken from
+/// `tenant_id` or `tenant_slug`, else from the custom domain the request was
+/// made on; without one the platform branding is returned.
+///
+/// GET /api/v1/public/branding/context?tenant_slug=acme
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/branding/context",
+    tag = "Platform",
+    params(
+        ("tenant_id" = Option<String>, Query, description = "Tenant ID"),
+        ("tenant_slug" = Option<String>, Query, description = "Tenant slug")
+    ),
+    responses(
+        (status = 200, description = "Resolved branding", body = BrandingContext),
+        (status = 404, description = "Tenant slug not found")
+    )
+)]
+pub async fn get_public_branding_context<S: HasBranding>(
+    State(state): State<S>,
+    custom_domain: Option<Extension<CustomDomainTenant>>,
+    Query(query): Query<BrandingContextQuery>,
+) -> Result<impl IntoResponse> {
+    let service = state.branding_service();
+    let context = match (query.tenant_id, &query.tenant_slug, custom_domain) {
+        (Some(tenant_id), _, _) => service.resolve_context(Some(tenant_id)).await?,
+        (None, Some(slug), _) => service.resolve_context_by_slug(slug).await?,
+        (None, None, Some(Extension(resolved))) => {
+            service.resolve_context(Some(resolved.tenant_id)).await?
+        }
+        (None, None, None) => service.resolve_context(None).await?,
+    };
+    Ok(Json(SuccessResponse::new(context)))
+}
+
 /// Get branding configuration (authenticated endpoint)
 ///
 /// GET /api/v1/system/branding
@@ -251,6 +291,8 @@ mod tests {
             favicon_url: Some("https://example.com/favicon.ico".to_string()),
             allow_registration: false,
             email_otp_enabled: false,
+            support_email: None,
+            support_url: None,
         };
 
         let response = SuccessResponse::new(config);
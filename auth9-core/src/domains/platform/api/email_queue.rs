@@ -0,0 +1,218 @@
+//! Email queue, suppression list and provider feedback API handlers
+
+use crate::domains::platform::service::EmailQueueService;
+use crate::error::{AppError, Result};
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, MessageResponse, PaginatedResponse,
+    PaginationQuery, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::email_queue::{
+    parse_email_feedback, CreateEmailSuppressionInput, EmailQueueStats, EmailSuppression,
+};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+fn email_queue_service<S: HasDbPool>(state: &S) -> EmailQueueService {
+    EmailQueueService::from_pool(state.db_pool().clone())
+}
+
+/// Verify a `sha256=<hex>` HMAC-SHA256 signature of the request body
+fn verify_feedback_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let hex_signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(hex_signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/email/queue",
+    tag = "Platform",
+    responses(
+        (status = 200, description = "Queued emails per status", body = EmailQueueStats)
+    )
+)]
+pub async fn get_queue_stats<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let stats = email_queue_service(&state).stats().await?;
+    Ok(Json(SuccessResponse::new(stats)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/email/suppressions",
+    tag = "Platform",
+    responses(
+        (status = 200, description = "Suppressed email addresses, newest first")
+    )
+)]
+pub async fn list_suppressions<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let (items, total) = email_queue_service(&state)
+        .list_suppressions(pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/email/suppressions",
+    tag = "Platform",
+    request_body = CreateEmailSuppressionInput,
+    responses(
+        (status = 201, description = "Address suppressed", body = EmailSuppression)
+    )
+)]
+pub async fn create_suppression<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CreateEmailSuppressionInput>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let suppression = email_queue_service(&state).suppress(input).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "system.email.suppression.create",
+        "email_suppression",
+        None,
+        None,
+        serde_json::to_value(&suppression).ok(),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(suppression))))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/system/email/suppressions/{email}",
+    tag = "Platform",
+    params(("email" = String, Path, description = "Suppressed email address")),
+    responses(
+        (status = 200, description = "Address removed from the suppression list"),
+        (status = 404, description = "Address is not suppressed")
+    )
+)]
+pub async fn delete_suppression<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    email_queue_service(&state).unsuppress(&email).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "system.email.suppression.delete",
+        "email_suppression",
+        None,
+        Some(serde_json::json!({ "email": email })),
+        None,
+    )
+    .await;
+    Ok(Json(MessageResponse::new(
+        "Address removed from suppression list",
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/feedback",
+    tag = "Platform",
+    responses(
+        (status = 200, description = "Feedback recorded"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 404, description = "Feedback ingestion is not configured")
+    )
+)]
+/// Receive bounce/complaint notifications from the email provider.
+///
+/// Requests must carry an `x-webhook-signature: sha256=<hex>` HMAC of the body
+/// made with `EMAIL_FEEDBACK_WEBHOOK_SECRET`. Senders using replay protection
+/// sign `{timestamp}.{nonce}.{body}` instead. Hard bounces and complaints add
+/// the recipients to the suppression list.
+pub async fn receive_feedback<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse> {
+    let Some(secret) = state
+        .config()
+        .email_queue
+        .feedback_webhook_secret
+        .as_deref()
+    else {
+        return Err(AppError::NotFound(
+            "Email feedback ingestion is not configured".to_string(),
+        ));
+    };
+    let signature = headers
+        .get("x-webhook-signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let signed = crate::middleware::replay::signed_payload(&headers, &body);
+    if signature.is_empty() || !verify_feedback_signature(secret, &signed, signature) {
+        return Err(AppError::Unauthorized(
+            "Invalid webhook signature".to_string(),
+        ));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Invalid feedback payload: {}", e)))?;
+    let Some(feedback) = parse_email_feedback(&payload) else {
+        // Delivery receipts and other notification types are acknowledged and ignored
+        return Ok(Json(MessageResponse::new("Notification ignored")));
+    };
+
+    let suppressed = email_queue_service(&state)
+        .ingest_feedback(&feedback)
+        .await?;
+    tracing::info!(suppressed, kind = ?feedback.kind, "Email feedback recorded");
+    Ok(Json(MessageResponse::new("Feedback recorded")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_feedback_signature() {
+        let secret = "feedback-secret"; // pragma: allowlist secret
+        let body = br#"{"type":"complaint","recipients":["a@example.com"]}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert!(verify_feedback_signature(secret, body, &signature));
+        assert!(!verify_feedback_signature("other", body, &signature));
+        assert!(!verify_feedback_signature(secret, body, "sha256=zz"));
+    }
+}
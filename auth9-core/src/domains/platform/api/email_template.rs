@@ -4,6 +4,7 @@ use crate::error::{AppError, Result};
 use crate::http_support::{
     require_platform_admin_with_db, write_audit_log_generic, SuccessResponse,
 };
+use crate::i18n::Locale;
 use crate::middleware::auth::AuthUser;
 use crate::models::email::{EmailAddress, EmailMessage};
 use crate::models::email_template::{
@@ -11,14 +12,14 @@ use crate::models::email_template::{
 };
 use crate::state::{HasEmailTemplates, HasServices, HasSystemSettings};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 /// Response for list templates
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -26,6 +27,13 @@ pub struct ListTemplatesResponse {
     pub data: Vec<EmailTemplateWithContent>,
 }
 
+/// Query selecting the locale of a template
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct TemplateLocaleQuery {
+    /// Template locale (`en-US`, `zh-CN`, `ja`); defaults to `en-US`
+    pub locale: Option<Locale>,
+}
+
 /// Request body for updating a template
 #[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct UpdateTemplateRequest {
@@ -109,7 +117,8 @@ pub async fn list_templates<S: HasEmailTemplates + HasServices>(
     path = "/api/v1/system/email-templates/{type}",
     tag = "Platform",
     params(
-        ("type" = String, Path, description = "Template type")
+        ("type" = String, Path, description = "Template type"),
+        TemplateLocaleQuery
     ),
     responses(
         (status = 200, description = "Success")
@@ -119,12 +128,13 @@ pub async fn get_template<S: HasEmailTemplates + HasServices>(
     State(state): State<S>,
     auth: AuthUser,
     Path(template_type): Path<String>,
+    Query(query): Query<TemplateLocaleQuery>,
 ) -> Result<impl IntoResponse> {
     require_platform_admin_with_db(&state, &auth).await?;
     let template_type = parse_template_type(&template_type)?;
     let template = state
         .email_template_service()
-        .get_template(template_type)
+        .get_localized_template(template_type, query.locale.unwrap_or_default())
         .await?;
     Ok(Json(SuccessResponse::new(template)))
 }
@@ -137,7 +147,8 @@ pub async fn get_template<S: HasEmailTemplates + HasServices>(
     path = "/api/v1/system/email-templates/{type}",
     tag = "Platform",
     params(
-        ("type" = String, Path, description = "Template type")
+        ("type" = String, Path, description = "Template type"),
+        TemplateLocaleQuery
     ),
     request_body = UpdateTemplateRequest,
     responses(
@@ -149,6 +160,7 @@ pub async fn update_template<S: HasEmailTemplates + HasServices>(
     auth: AuthUser,
     headers: HeaderMap,
     Path(template_type): Path<String>,
+    Query(query): Query<TemplateLocaleQuery>,
     Json(request): Json<UpdateTemplateRequest>,
 ) -> Result<impl IntoResponse> {
     require_platform_admin_with_db(&state, &auth).await?;
@@ -156,7 +168,7 @@ pub async fn update_template<S: HasEmailTemplates + HasServices>(
     let content: EmailTemplateContent = request.into();
     let template = state
         .email_template_service()
-        .update_template(template_type, content)
+        .update_localized_template(template_type, query.locale.unwrap_or_default(), content)
         .await?;
 
     let _ = write_audit_log_generic(
@@ -181,7 +193,8 @@ pub async fn update_template<S: HasEmailTemplates + HasServices>(
     path = "/api/v1/system/email-templates/{type}",
     tag = "Platform",
     params(
-        ("type" = String, Path, description = "Template type")
+        ("type" = String, Path, description = "Template type"),
+        TemplateLocaleQuery
     ),
     responses(
         (status = 200, description = "Success")
@@ -191,12 +204,13 @@ pub async fn reset_template<S: HasEmailTemplates + HasServices>(
     State(state): State<S>,
     auth: AuthUser,
     Path(template_type): Path<String>,
+    Query(query): Query<TemplateLocaleQuery>,
 ) -> Result<impl IntoResponse> {
     require_platform_admin_with_db(&state, &auth).await?;
     let template_type = parse_template_type(&template_type)?;
     let template = state
         .email_template_service()
-        .reset_template(template_type)
+        .reset_localized_template(template_type, query.locale.unwrap_or_default())
         .await?;
     Ok(Json(SuccessResponse::new(template)))
 }
@@ -277,7 +291,7 @@ pub async fn send_test_email<S: HasEmailTemplates + HasSystemSettings + HasServi
     .with_text_body(&rendered.text_body);
 
     // Send the email using the email service
-    match state.email_service().send(&message, None).await {
+    match state.email_service().send_now(&message, None).await {
         Ok(result) => Ok(Json(SendTestEmailResponse {
             success: true,
             message: "Test email sent successfully".to_string(),
@@ -331,6 +345,14 @@ mod tests {
             parse_template_type("security_alert").unwrap(),
             EmailTemplateType::SecurityAlert
         );
+        assert_eq!(
+            parse_template_type("notification_digest").unwrap(),
+            EmailTemplateType::NotificationDigest
+        );
+        assert_eq!(
+            parse_template_type("security_alert_digest").unwrap(),
+            EmailTemplateType::SecurityAlertDigest
+        );
     }
 
     #[test]
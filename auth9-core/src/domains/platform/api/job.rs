@@ -0,0 +1,1378 @@
+//! Async job APIs and the executor that runs queued jobs against app state.
+
+use crate::crypto::TenantKeyring;
+use crate::domains::identity::service::required_actions::ACTION_UPDATE_PASSWORD;
+use crate::domains::identity::service::BreachedPasswordService;
+use crate::domains::integration::service::WebhookEventPublisher;
+use crate::domains::platform::service::job::{JobExecutor, JobOutcome, JobProgress, JobService};
+use crate::domains::platform::service::NotificationPreferenceService;
+use crate::domains::tenant_access::service::sagas::{run_user_create, UserCreateContext};
+use crate::domains::tenant_access::service::{InactivityService, TenantKeyService};
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::migration::backfill::{self, BackfillJobPayload};
+use crate::models::account_deletion::AccountDeletionJobPayload;
+use crate::models::analytics::WebhookEvent;
+use crate::models::audit_search::{export_row, MAX_EXPORT_ROWS};
+use crate::models::common::{StringUuid, TenantId, UserId};
+use crate::models::email_template::EmailTemplateType;
+use crate::models::inactivity::{InactiveMember, InactivityPolicy, InactivityStep};
+use crate::models::job::{
+    CreateJobInput, ForcePasswordResetInput, Job, JobKind, JobResponse, UserImportInput,
+    UserImportRow,
+};
+use crate::models::notification_preference::NotificationCategory;
+use crate::models::password::ForgotPasswordInput;
+use crate::models::password_breach::{
+    offline_checkable_sha1, BreachCampaignAction, BreachCampaignInput, BreachCampaignReport,
+    PasswordBreachCheck, PasswordBreachStatus,
+};
+use crate::models::tenant::Tenant;
+use crate::models::tenant_key::{KeyRotationJobPayload, ReencryptOutcome};
+use crate::models::user::{AddUserToTenantInput, CreateUserInput, User, UserCohort};
+use crate::policy::{AuthzContext, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::account_deletion::AccountDeletionRepositoryImpl;
+use crate::repository::audit::{AuditLogQuery, CreateAuditLogInput};
+use crate::repository::inactivity::{InactivityRepository, InactivityRepositoryImpl};
+use crate::repository::job::JobRepositoryImpl;
+use crate::repository::password_breach_check::PasswordBreachCheckRepositoryImpl;
+use crate::repository::tenant_key::TenantKeyRepositoryImpl;
+use crate::repository::AccountDeletionRepository;
+use crate::repository::AuditRepository;
+use crate::repository::PasswordBreachCheckRepository;
+use crate::state::{
+    HasDbPool, HasPasswordManagement, HasRequiredActions, HasServices, HasSessionManagement,
+    HasSystemSettings, HasWebhooks,
+};
+use async_trait::async_trait;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Items processed between progress saves and cancellation checks
+const CHECKPOINT_EVERY: usize = 50;
+
+/// Page size used when exporting tenant users and audit logs
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+pub(crate) fn job_service<S: HasDbPool>(state: &S) -> JobService<JobRepositoryImpl> {
+    JobService::new(Arc::new(JobRepositoryImpl::new(state.db_pool().clone())))
+}
+
+/// Platform admins, the job's creator and user managers of the job's tenant
+/// may see a job. Everyone else gets 404 so job IDs do not leak.
+async fn ensure_job_access<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    job: &Job,
+) -> Result<()> {
+    if job.created_by == Some(authz.user_id()) || authz.is_platform_admin(state).await {
+        return Ok(());
+    }
+    if let Some(tenant_id) = job.tenant_id {
+        let input = PolicyInput {
+            action: PolicyAction::UserTenantRead,
+            scope: ResourceScope::Tenant(tenant_id),
+        };
+        if authz.enforce(state, &input).await.is_ok() {
+            return Ok(());
+        }
+    }
+    Err(AppError::NotFound(format!("Job {} not found", job.id)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    tag = "Platform",
+    params(("id" = String, Path, description = "Job ID (UUID)")),
+    responses(
+        (status = 200, description = "Job status, progress and partial results", body = JobResponse),
+        (status = 404, description = "Job not found")
+    )
+)]
+pub async fn get_job<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<JobResponse>>> {
+    let job = job_service(&state).get(StringUuid::from(id)).await?;
+    ensure_job_access(&state, &authz, &job).await?;
+    Ok(Json(SuccessResponse::new(JobResponse::from(job))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs/{id}/cancel",
+    tag = "Platform",
+    params(("id" = String, Path, description = "Job ID (UUID)")),
+    responses(
+        (status = 200, description = "Cancelled, or cancellation requested for a running job", body = JobResponse),
+        (status = 404, description = "Job not found"),
+        (status = 409, description = "Job already finished")
+    )
+)]
+pub async fn cancel_job<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<JobResponse>>> {
+    let service = job_service(&state);
+    let job = service.get(StringUuid::from(id)).await?;
+    ensure_job_access(&state, &authz, &job).await?;
+
+    let job = service.cancel(job.id).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "job.cancel",
+        "job",
+        Some(*job.id),
+        None,
+        serde_json::to_value(&job).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(JobResponse::from(job))))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListJobsQuery {
+    /// Maximum number of jobs to return (1-100, default 20)
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/jobs",
+    tag = "Platform",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ListJobsQuery
+    ),
+    responses(
+        (status = 200, description = "Most recent jobs for the tenant", body = Vec<JobResponse>)
+    )
+)]
+pub async fn list_tenant_jobs<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<ListJobsQuery>,
+) -> Result<Json<SuccessResponse<Vec<JobResponse>>>> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::UserTenantRead,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+
+    let jobs = job_service(&state)
+        .list_by_tenant(tenant_id, query.limit.unwrap_or(20))
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        jobs.into_iter().map(JobResponse::from).collect(),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/users/import",
+    tag = "Platform",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    request_body = UserImportInput,
+    responses(
+        (status = 202, description = "Import job queued", body = JobResponse)
+    )
+)]
+pub async fn import_users<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<UserImportInput>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+    input.validate()?;
+    state.tenant_service().require_active(tenant_id).await?;
+
+    let job = job_service(&state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::UserImport,
+            tenant_id: Some(tenant_id),
+            payload: serde_json::to_value(&input)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?,
+            created_by: Some(authz.user_id()),
+        })
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.import",
+        "job",
+        Some(*job.id),
+        None,
+        Some(serde_json::json!({ "tenant_id": tenant_id, "rows": input.users.len() })),
+    )
+    .await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/users/export",
+    tag = "Platform",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    responses(
+        (status = 202, description = "Export job queued; exported users appear in the job results", body = JobResponse)
+    )
+)]
+pub async fn export_users<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::UserTenantRead,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+
+    let job = job_service(&state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::UserExport,
+            tenant_id: Some(tenant_id),
+            payload: serde_json::json!({}),
+            created_by: Some(authz.user_id()),
+        })
+        .await?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/users:force-password-reset",
+    tag = "Platform",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    request_body = ForcePasswordResetInput,
+    responses(
+        (status = 202, description = "Reset job queued; flagged users appear in the job results", body = JobResponse)
+    )
+)]
+pub async fn force_password_reset<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<ForcePasswordResetInput>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+    if matches!(&input.cohort, UserCohort::Role { role } if role.trim().is_empty()) {
+        return Err(AppError::Validation(
+            "Cohort role must not be empty".to_string(),
+        ));
+    }
+    state.tenant_service().require_active(tenant_id).await?;
+
+    let payload =
+        serde_json::to_value(&input).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+    let job = job_service(&state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::ForcePasswordReset,
+            tenant_id: Some(tenant_id),
+            payload: payload.clone(),
+            created_by: Some(authz.user_id()),
+        })
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.force_password_reset",
+        "job",
+        Some(*job.id),
+        None,
+        Some(serde_json::json!({ "tenant_id": tenant_id, "request": payload })),
+    )
+    .await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/users:breach-campaign",
+    tag = "Platform",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    request_body = BreachCampaignInput,
+    responses(
+        (status = 202, description = "Campaign job queued; checked users appear in the job results", body = JobResponse),
+        (status = 422, description = "Breached password detection is disabled")
+    )
+)]
+pub async fn start_breach_campaign<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<BreachCampaignInput>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+    if matches!(&input.cohort, UserCohort::Role { role } if role.trim().is_empty()) {
+        return Err(AppError::Validation(
+            "Cohort role must not be empty".to_string(),
+        ));
+    }
+    if !state
+        .breached_password_service()
+        .is_some_and(|svc| svc.is_enabled())
+    {
+        return Err(AppError::Validation(
+            "Breached password detection is disabled".to_string(),
+        ));
+    }
+    state.tenant_service().require_active(tenant_id).await?;
+
+    let payload =
+        serde_json::to_value(&input).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+    let job = job_service(&state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::BreachCampaign,
+            tenant_id: Some(tenant_id),
+            payload: payload.clone(),
+            created_by: Some(authz.user_id()),
+        })
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.breach_campaign",
+        "job",
+        Some(*job.id),
+        None,
+        Some(serde_json::json!({ "tenant_id": tenant_id, "request": payload })),
+    )
+    .await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/breach-campaigns/{job_id}/report",
+    tag = "Platform",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("job_id" = String, Path, description = "Campaign job ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Outcome counts and affected accounts, including results of login-time checks", body = BreachCampaignReport),
+        (status = 404, description = "Campaign not found")
+    )
+)]
+pub async fn breach_campaign_report<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path((tenant_id, job_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<BreachCampaignReport>>> {
+    let tenant_id = StringUuid::from(tenant_id);
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(tenant_id),
+            },
+        )
+        .await?;
+
+    let job = job_service(&state).get(StringUuid::from(job_id)).await?;
+    if job.tenant_id != Some(tenant_id) || job.job_kind() != Some(JobKind::BreachCampaign) {
+        return Err(AppError::NotFound(format!(
+            "Breach campaign {} not found",
+            job_id
+        )));
+    }
+    let checks = PasswordBreachCheckRepositoryImpl::new(state.db_pool().clone())
+        .list_by_job(job.id)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        BreachCampaignReport::from_checks(job.id, checks),
+    )))
+}
+
+/// Runs queued jobs with the application's services
+pub struct StateJobExecutor<S> {
+    state: S,
+}
+
+impl<S: HasServices> StateJobExecutor<S> {
+    pub fn new(state: S) -> Self {
+        Self { state }
+    }
+
+    async fn import_users(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        let input: UserImportInput = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid import payload: {}", e)))?;
+        progress.set_total(input.users.len());
+
+        // Rows before `processed` were handled by an earlier attempt of this job
+        let already_processed = progress.processed().max(0) as usize;
+        for (index, row) in input.users.iter().enumerate().skip(already_processed) {
+            match self.import_user(tenant_id, row).await {
+                Ok(status) => progress.record_success(Some(serde_json::json!({
+                    "row": index,
+                    "email": row.email,
+                    "status": status,
+                }))),
+                Err(e) => progress.record_failure(serde_json::json!({
+                    "row": index,
+                    "email": row.email,
+                    "status": "failed",
+                    "error": e.to_string(),
+                })),
+            }
+            if (index + 1) % CHECKPOINT_EVERY == 0 && progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+        }
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn import_user(
+        &self,
+        tenant_id: StringUuid,
+        row: &UserImportRow,
+    ) -> Result<&'static str> {
+        let role = row.role_in_tenant.as_deref().unwrap_or("member");
+        if role != "member" && role != "admin" {
+            return Err(AppError::Validation(format!(
+                "Unsupported role_in_tenant '{}'",
+                role
+            )));
+        }
+
+        let (user, created) = match self.state.user_service().get_by_email(&row.email).await {
+            Ok(user) => (user, false),
+            Err(AppError::NotFound(_)) => (self.create_user(row).await?, true),
+            Err(e) => return Err(e),
+        };
+
+        let added = self
+            .state
+            .user_service()
+            .add_to_tenant(AddUserToTenantInput {
+                user_id: *user.id,
+                tenant_id: *tenant_id,
+                role_in_tenant: role.to_string(),
+            })
+            .await;
+        match added {
+            Ok(_) if created => Ok("created"),
+            Ok(_) => Ok("added"),
+            Err(AppError::Conflict(_)) => Ok("already_member"),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn create_user(&self, row: &UserImportRow) -> Result<crate::models::user::User> {
+        let input = CreateUserInput {
+            email: row.email.clone(),
+            display_name: row.display_name.clone(),
+            avatar_url: None,
+        };
+        input.validate()?;
+
+        run_user_create(&self.state, UserCreateContext::new(input, None, None), None).await
+    }
+
+    async fn export_users(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        let mut page = 1;
+        loop {
+            let (users, total) = self
+                .state
+                .user_service()
+                .list_tenant_users(tenant_id, page, EXPORT_PAGE_SIZE)
+                .await?;
+            progress.set_total(total.max(0) as usize);
+            if users.is_empty() {
+                break;
+            }
+            for user in &users {
+                progress.record_success(Some(serde_json::json!({
+                    "id": user.id,
+                    "email": user.email,
+                    "display_name": user.display_name,
+                    "created_at": user.created_at,
+                })));
+            }
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if (users.len() as i64) < EXPORT_PAGE_SIZE {
+                break;
+            }
+            page += 1;
+        }
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn delete_tenant(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        progress.set_total(1);
+        // Tenant deletion cascades in a single transaction, so it cannot be
+        // cancelled part-way through.
+        if progress.checkpoint().await? {
+            return Ok(JobOutcome::Cancelled);
+        }
+        self.state.tenant_service().delete(tenant_id).await?;
+        progress.record_success(Some(serde_json::json!({ "tenant_id": tenant_id })));
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn export_audit_logs(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let filter: AuditLogQuery = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid export payload: {}", e)))?;
+        let total = self.state.audit_repo().count(&filter).await?;
+        progress.set_total((total.max(0) as usize).min(MAX_EXPORT_ROWS));
+
+        // Rows before `processed` were exported by an earlier attempt of this job
+        let mut offset = progress.processed().max(0) as usize;
+        while offset < MAX_EXPORT_ROWS {
+            let page = AuditLogQuery {
+                offset: Some(offset as i64),
+                limit: Some(EXPORT_PAGE_SIZE),
+                page: None,
+                ..filter.clone()
+            };
+            let logs = self.state.audit_repo().find_with_actor(&page).await?;
+            for log in logs.iter().take(MAX_EXPORT_ROWS - offset) {
+                progress.record_success(Some(export_row(log)));
+            }
+            offset += logs.len();
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if (logs.len() as i64) < EXPORT_PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(JobOutcome::Completed)
+    }
+}
+
+impl<S> StateJobExecutor<S>
+where
+    S: HasServices
+        + HasRequiredActions
+        + HasSessionManagement
+        + HasPasswordManagement
+        + HasDbPool
+        + HasSystemSettings,
+{
+    /// Flag a cohort of tenant members to change their password at next
+    /// login, one page per checkpoint
+    async fn force_password_reset(
+        &self,
+        job: &Job,
+        progress: &mut JobProgress,
+    ) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        let input: ForcePasswordResetInput = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid reset payload: {}", e)))?;
+
+        // Users before `processed` were handled by an earlier attempt of this
+        // job; flagging does not change cohort membership, so offsets are stable
+        let mut offset = progress.processed().max(0) as i64;
+        loop {
+            let (users, total) = self
+                .state
+                .user_service()
+                .list_tenant_users_in_cohort(tenant_id, &input.cohort, offset, EXPORT_PAGE_SIZE)
+                .await?;
+            progress.set_total(total.max(0) as usize);
+            for user in &users {
+                let reset = self.reset_user_password(
+                    job,
+                    user,
+                    "admin_forced",
+                    input.revoke_sessions,
+                    input.notify,
+                );
+                match reset.await {
+                    Ok(status) => progress.record_success(Some(serde_json::json!({
+                        "user_id": user.id,
+                        "email": user.email,
+                        "status": status,
+                    }))),
+                    Err(e) => progress.record_failure(serde_json::json!({
+                        "user_id": user.id,
+                        "email": user.email,
+                        "status": "failed",
+                        "error": e.to_string(),
+                    })),
+                }
+            }
+            offset += users.len() as i64;
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if (users.len() as i64) < EXPORT_PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn reset_user_password(
+        &self,
+        job: &Job,
+        user: &User,
+        reason: &str,
+        revoke_sessions: bool,
+        notify: bool,
+    ) -> Result<&'static str> {
+        let actions = self.state.required_actions_service();
+        let already_flagged = actions
+            .get_pending_actions(&user.identity_subject)
+            .await?
+            .iter()
+            .any(|a| a.action_type == ACTION_UPDATE_PASSWORD);
+        if !already_flagged {
+            actions
+                .create_action(
+                    &user.identity_subject,
+                    ACTION_UPDATE_PASSWORD,
+                    Some(serde_json::json!({ "reason": reason, "job_id": job.id })),
+                )
+                .await?;
+        }
+
+        if revoke_sessions {
+            self.state
+                .session_service()
+                .force_logout_user(user.id)
+                .await?;
+        }
+        if notify {
+            // Issues a fresh reset token and emails the link; delivery
+            // failures are logged by the password service
+            self.state
+                .password_service()
+                .request_reset(ForgotPasswordInput {
+                    email: user.email.clone(),
+                })
+                .await?;
+        }
+
+        Ok(if already_flagged {
+            "already_flagged"
+        } else {
+            "flagged"
+        })
+    }
+}
+
+impl<S> StateJobExecutor<S>
+where
+    S: HasServices
+        + HasRequiredActions
+        + HasSessionManagement
+        + HasPasswordManagement
+        + HasDbPool
+        + HasSystemSettings,
+{
+    /// Check a cohort of tenant members against the breached password corpus,
+    /// one page per checkpoint. Unsalted SHA-1 hashes are checked right away;
+    /// other accounts are left pending until their next password login.
+    async fn breach_campaign(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        let input: BreachCampaignInput = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid campaign payload: {}", e)))?;
+        let breach_service = self
+            .state
+            .breached_password_service()
+            .filter(|svc| svc.is_enabled())
+            .ok_or_else(|| {
+                AppError::BadRequest("Breached password detection is disabled".to_string())
+            })?;
+        let min_breach_count = self
+            .state
+            .tenant_service()
+            .get(tenant_id)
+            .await?
+            .password_policy
+            .unwrap_or_default()
+            .min_breach_count;
+        let checks = PasswordBreachCheckRepositoryImpl::new(self.state.db_pool().clone());
+
+        // Checks are upserted per (job, user), so resuming from `processed`
+        // never duplicates an account
+        let mut offset = progress.processed().max(0) as i64;
+        loop {
+            let (users, total) = self
+                .state
+                .user_service()
+                .list_tenant_users_in_cohort(tenant_id, &input.cohort, offset, EXPORT_PAGE_SIZE)
+                .await?;
+            progress.set_total(total.max(0) as usize);
+            for user in &users {
+                let checked = self.check_user_breach(
+                    job,
+                    user,
+                    &input,
+                    breach_service,
+                    min_breach_count,
+                    &checks,
+                );
+                match checked.await {
+                    Ok(status) => progress.record_success(Some(serde_json::json!({
+                        "user_id": user.id,
+                        "email": user.email,
+                        "status": status,
+                    }))),
+                    Err(e) => progress.record_failure(serde_json::json!({
+                        "user_id": user.id,
+                        "email": user.email,
+                        "status": "failed",
+                        "error": e.to_string(),
+                    })),
+                }
+            }
+            offset += users.len() as i64;
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if (users.len() as i64) < EXPORT_PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn check_user_breach(
+        &self,
+        job: &Job,
+        user: &User,
+        input: &BreachCampaignInput,
+        breach_service: &BreachedPasswordService,
+        min_breach_count: u64,
+        checks: &PasswordBreachCheckRepositoryImpl,
+    ) -> Result<&'static str> {
+        let tenant_id = require_job_tenant(job)?;
+        let Some(stored_hash) = self
+            .state
+            .identity_engine()
+            .user_store()
+            .get_user_password_hash(&user.identity_subject)
+            .await?
+        else {
+            return Ok("no_password");
+        };
+
+        let (status, breach_count) = match offline_checkable_sha1(&stored_hash) {
+            Some(digest) => {
+                let result = breach_service.check_sha1(&digest).await;
+                if result.is_breached && result.breach_count >= min_breach_count {
+                    (PasswordBreachStatus::Breached, result.breach_count)
+                } else {
+                    (PasswordBreachStatus::Clean, result.breach_count)
+                }
+            }
+            None if input.check_on_next_login => (PasswordBreachStatus::Pending, 0),
+            None => return Ok("not_checkable"),
+        };
+        let mut check = PasswordBreachCheck::new(
+            job.id,
+            tenant_id,
+            user.id,
+            &user.email,
+            status,
+            breach_count,
+        );
+
+        if status == PasswordBreachStatus::Breached {
+            let acted = match input.action {
+                BreachCampaignAction::ReportOnly => false,
+                BreachCampaignAction::Notify => self.send_breach_notice(user).await?,
+                BreachCampaignAction::ForceReset => {
+                    self.reset_user_password(
+                        job,
+                        user,
+                        "breached_password",
+                        input.revoke_sessions,
+                        true,
+                    )
+                    .await?;
+                    true
+                }
+            };
+            if acted {
+                check.action_taken = Some(input.action.as_str().to_string());
+            }
+        }
+        checks.upsert(&check).await?;
+
+        metrics::counter!(
+            "auth9_password_breach_checks_total",
+            "source" => check.source.clone(),
+            "status" => status.as_str()
+        )
+        .increment(1);
+        Ok(status.as_str())
+    }
+
+    /// Email a security alert about the breached password. Returns false when
+    /// the user has opted out of security alert emails.
+    async fn send_breach_notice(&self, user: &User) -> Result<bool> {
+        let notifications = NotificationPreferenceService::from_config(
+            self.state.db_pool().clone(),
+            self.state.config(),
+        );
+        let category = NotificationCategory::SecurityAlerts;
+        if !notifications.allows(user.id, category).await {
+            return Ok(false);
+        }
+
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            user.display_name
+                .clone()
+                .unwrap_or_else(|| "User".to_string()),
+        );
+        vars.insert(
+            "event_type".to_string(),
+            "Your password was found in a known data breach. Please change it now".to_string(),
+        );
+        vars.insert("device_info".to_string(), "-".to_string());
+        vars.insert("location".to_string(), "-".to_string());
+        vars.insert(
+            "timestamp".to_string(),
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        );
+        notifications
+            .send_notification(
+                self.state.email_service(),
+                user.id,
+                &user.email,
+                category,
+                EmailTemplateType::SecurityAlert,
+                crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+                vars,
+            )
+            .await?;
+        Ok(true)
+    }
+}
+
+impl<S: HasDbPool> StateJobExecutor<S> {
+    /// Apply a registered schema backfill one batch per checkpoint until no
+    /// row is left to update
+    async fn schema_backfill(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let input: BackfillJobPayload = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid backfill payload: {}", e)))?;
+        let backfill = backfill::find(&input.backfill).ok_or_else(|| {
+            AppError::BadRequest(format!("Unknown backfill '{}'", input.backfill))
+        })?;
+        let pool = self.state.db_pool();
+
+        let remaining: i64 = sqlx::query_scalar(&backfill.remaining_sql())
+            .fetch_one(pool)
+            .await?;
+        progress.set_total(progress.processed().max(0) as usize + remaining.max(0) as usize);
+        let batch_sql = backfill.batch_sql();
+        loop {
+            let updated = sqlx::query(&batch_sql)
+                .bind(input.batch_size.max(1) as i64)
+                .execute(pool)
+                .await?
+                .rows_affected();
+            if updated == 0 {
+                break;
+            }
+            progress.record_batch(updated as usize);
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if input.pause_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(input.pause_ms)).await;
+            }
+        }
+        tracing::info!(
+            job_id = %job.id,
+            backfill = backfill.name,
+            rows = progress.processed(),
+            "Schema backfill finished"
+        );
+        Ok(JobOutcome::Completed)
+    }
+}
+
+impl<S: HasServices + HasDbPool> StateJobExecutor<S> {
+    /// Re-encrypt stored secrets with the current keys one batch per
+    /// checkpoint. The last secret of each batch is saved as the resume
+    /// point, so a requeued job continues after it.
+    async fn key_rotation(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let input: KeyRotationJobPayload = serde_json::from_value(job.payload.clone())
+            .map_err(|e| AppError::BadRequest(format!("Invalid key rotation payload: {}", e)))?;
+        let service = TenantKeyService::new(
+            Arc::new(TenantKeyRepositoryImpl::new(self.state.db_pool().clone())),
+            TenantKeyring::from_config(&self.state.config().settings_encryption),
+        );
+
+        let mut after = progress
+            .resume_after()
+            .map(|id| {
+                id.parse::<Uuid>().map(StringUuid::from).map_err(|e| {
+                    AppError::BadRequest(format!("Invalid key rotation resume point: {}", e))
+                })
+            })
+            .transpose()?;
+        let pending = service.count_pending(job.tenant_id, after).await?;
+        progress.set_total(progress.processed().max(0) as usize + pending as usize);
+        loop {
+            let outcomes = service
+                .reencrypt_batch(job.tenant_id, after, input.batch_size)
+                .await?;
+            let Some((last, _)) = outcomes.last() else {
+                break;
+            };
+            after = Some(*last);
+            let mut done = 0;
+            for (id, outcome) in &outcomes {
+                if *outcome == ReencryptOutcome::Failed {
+                    progress.record_failure(serde_json::json!({
+                        "webhook_id": id,
+                        "status": "failed",
+                        "error": "Cannot decrypt webhook secret",
+                    }));
+                } else {
+                    done += 1;
+                }
+            }
+            progress.record_batch(done);
+            progress.set_resume_after(last.to_string());
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            if input.pause_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(input.pause_ms)).await;
+            }
+        }
+        tracing::info!(
+            job_id = %job.id,
+            tenant_id = ?job.tenant_id,
+            secrets = progress.processed(),
+            failed = progress.failed(),
+            "Key rotation finished"
+        );
+        Ok(JobOutcome::Completed)
+    }
+}
+
+impl<S> StateJobExecutor<S>
+where
+    S: HasServices + HasSessionManagement + HasDbPool + HasSystemSettings + HasWebhooks,
+{
+    /// Apply every step of the tenant's inactivity policy that is due, one
+    /// page of inactive members per checkpoint. Applied steps are recorded,
+    /// so a resumed sweep skips them.
+    async fn inactivity_sweep(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let tenant_id = require_job_tenant(job)?;
+        let tenant = self.state.tenant_service().get(tenant_id).await?;
+        let Some(policy) = tenant
+            .settings
+            .inactivity_policy
+            .clone()
+            .filter(|p| p.enabled)
+        else {
+            tracing::info!(job_id = %job.id, "Inactivity policy disabled since the sweep was queued");
+            return Ok(JobOutcome::Completed);
+        };
+        let service = InactivityService::new(Arc::new(InactivityRepositoryImpl::new(
+            self.state.db_pool().clone(),
+        )));
+
+        let now = Utc::now();
+        let mut after = None;
+        loop {
+            let page = service.due_members(tenant_id, &policy, now, after).await?;
+            progress.set_total(progress.processed().max(0) as usize + page.due.len());
+            for (member, step) in &page.due {
+                let applied = self.apply_inactivity_step(
+                    job,
+                    &tenant,
+                    &policy,
+                    member,
+                    *step,
+                    service.repo(),
+                );
+                match applied.await {
+                    Ok(status) => progress.record_success(Some(serde_json::json!({
+                        "user_id": member.user_id,
+                        "email": member.email,
+                        "step": step.as_str(),
+                        "status": status,
+                    }))),
+                    Err(e) => progress.record_failure(serde_json::json!({
+                        "user_id": member.user_id,
+                        "email": member.email,
+                        "step": step.as_str(),
+                        "status": "failed",
+                        "error": e.to_string(),
+                    })),
+                }
+            }
+            if progress.checkpoint().await? {
+                return Ok(JobOutcome::Cancelled);
+            }
+            match page.next_after {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        Ok(JobOutcome::Completed)
+    }
+
+    async fn apply_inactivity_step(
+        &self,
+        job: &Job,
+        tenant: &Tenant,
+        policy: &InactivityPolicy,
+        member: &InactiveMember,
+        step: InactivityStep,
+        repo: &InactivityRepositoryImpl,
+    ) -> Result<&'static str> {
+        let user = self.state.user_service().get(member.user_id).await?;
+        if self.state.config().is_platform_admin_email(&user.email) {
+            return Ok("skipped_platform_admin");
+        }
+        let now = Utc::now();
+
+        let status = match step {
+            InactivityStep::Notify => {
+                let sent = self.send_inactivity_notice(&user, tenant, policy).await?;
+                repo.mark_notified(tenant.id, user.id, now).await?;
+                if sent {
+                    "notified"
+                } else {
+                    "notice_opted_out"
+                }
+            }
+            InactivityStep::Disable if member.shared_account => {
+                repo.mark_disabled(tenant.id, user.id, now).await?;
+                "kept_shared"
+            }
+            InactivityStep::Disable => {
+                let locked_until = chrono::DateTime::parse_from_rfc3339("2037-12-31T23:59:59Z")
+                    .unwrap()
+                    .with_timezone(&Utc);
+                self.state
+                    .user_service()
+                    .update_locked_until(user.id, Some(locked_until))
+                    .await?;
+                self.state
+                    .session_service()
+                    .force_logout_user(user.id)
+                    .await?;
+                repo.mark_disabled(tenant.id, user.id, now).await?;
+                "disabled"
+            }
+            InactivityStep::Delete if member.shared_account => {
+                self.state
+                    .user_service()
+                    .remove_from_tenant(UserId::from(user.id), TenantId::from(tenant.id))
+                    .await?;
+                repo.clear(tenant.id, user.id).await?;
+                "removed_from_tenant"
+            }
+            InactivityStep::Delete => {
+                if let Err(err) = self
+                    .state
+                    .identity_engine()
+                    .user_store()
+                    .delete_user(&user.identity_subject)
+                    .await
+                {
+                    if !matches!(err, AppError::NotFound(_)) {
+                        return Err(err);
+                    }
+                }
+                self.state.user_service().delete(user.id).await?;
+                repo.clear(tenant.id, user.id).await?;
+                "deleted"
+            }
+        };
+
+        let details = serde_json::json!({
+            "tenant_id": tenant.id.to_string(),
+            "user_id": user.id.to_string(),
+            "email": user.email,
+            "step": step.as_str(),
+            "status": status,
+            "last_login_at": member.last_login_at,
+            "job_id": job.id.to_string(),
+        });
+        let _ = self
+            .state
+            .audit_repo()
+            .create(&CreateAuditLogInput {
+                actor_id: None,
+                action: format!("inactivity.{}", step.as_str()),
+                resource_type: "user".to_string(),
+                resource_id: Some(*user.id),
+                old_value: None,
+                new_value: Some(details.clone()),
+                ip_address: None,
+            })
+            .await;
+        let event = WebhookEvent {
+            event_type: step.event_type().to_string(),
+            timestamp: now,
+            data: details,
+        };
+        if let Err(e) = self
+            .state
+            .webhook_service()
+            .trigger_tenant_event(tenant.id, event)
+            .await
+        {
+            tracing::warn!(job_id = %job.id, "Failed to trigger {} webhook: {}", step.event_type(), e);
+        }
+        metrics::counter!("auth9_inactivity_steps_total", "step" => step.as_str(), "status" => status)
+            .increment(1);
+        Ok(status)
+    }
+
+    /// Warn the member that the account will be locked unless they sign in.
+    /// Returns false when the user has opted out of security alert emails.
+    async fn send_inactivity_notice(
+        &self,
+        user: &User,
+        tenant: &Tenant,
+        policy: &InactivityPolicy,
+    ) -> Result<bool> {
+        let notifications = NotificationPreferenceService::from_config(
+            self.state.db_pool().clone(),
+            self.state.config(),
+        );
+        let category = NotificationCategory::SecurityAlerts;
+        if !notifications.allows(user.id, category).await {
+            return Ok(false);
+        }
+
+        let lock_date = Utc::now() + chrono::Duration::days(policy.disable_after_days as i64);
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            user.display_name
+                .clone()
+                .unwrap_or_else(|| "User".to_string()),
+        );
+        vars.insert(
+            "event_type".to_string(),
+            format!(
+                "Your {} account has not been used for {} days and will be locked on {}. Sign in to keep it",
+                tenant.name,
+                policy.inactive_days,
+                lock_date.format("%Y-%m-%d")
+            ),
+        );
+        vars.insert("device_info".to_string(), "-".to_string());
+        vars.insert("location".to_string(), "-".to_string());
+        vars.insert(
+            "timestamp".to_string(),
+            Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        );
+        notifications
+            .send_notification(
+                self.state.email_service(),
+                user.id,
+                &user.email,
+                category,
+                EmailTemplateType::SecurityAlert,
+                crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+                vars,
+            )
+            .await?;
+        Ok(true)
+    }
+}
+
+impl<S> StateJobExecutor<S>
+where
+    S: HasServices + HasDbPool + HasSystemSettings,
+{
+    /// Remove a self-deleted account whose grace period has ended: the
+    /// identity backend user first, then the local user with its linked
+    /// identities, sessions and the deletion request.
+    async fn delete_account(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        let input: AccountDeletionJobPayload = serde_json::from_value(job.payload.clone())
+            .map_err(|e| {
+                AppError::BadRequest(format!("Invalid account deletion payload: {}", e))
+            })?;
+        progress.set_total(1);
+        let requests = AccountDeletionRepositoryImpl::new(self.state.db_pool().clone());
+        if requests.find(input.user_id).await?.is_none() {
+            progress.record_success(Some(serde_json::json!({
+                "user_id": input.user_id,
+                "status": "skipped",
+            })));
+            return Ok(JobOutcome::Completed);
+        }
+        let user = match self.state.user_service().get(input.user_id).await {
+            Ok(user) => user,
+            Err(AppError::NotFound(_)) => {
+                requests.delete(input.user_id).await?;
+                progress.record_success(Some(serde_json::json!({
+                    "user_id": input.user_id,
+                    "status": "skipped",
+                })));
+                return Ok(JobOutcome::Completed);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Err(err) = self
+            .state
+            .identity_engine()
+            .user_store()
+            .delete_user(&user.identity_subject)
+            .await
+        {
+            if !matches!(err, AppError::NotFound(_)) {
+                return Err(err);
+            }
+        }
+        self.state.user_service().delete(user.id).await?;
+
+        if let Err(e) = self
+            .state
+            .email_service()
+            .send_account_deletion_notice(
+                &user.email,
+                user.display_name.as_deref(),
+                crate::i18n::resolve_locale(user.locale.as_deref(), None, None),
+                "Your account has been deleted",
+            )
+            .await
+        {
+            tracing::warn!(job_id = %job.id, "Failed to send account deletion notice: {}", e);
+        }
+        let _ = self
+            .state
+            .audit_repo()
+            .create(&CreateAuditLogInput {
+                actor_id: None,
+                action: "user.deletion.complete".to_string(),
+                resource_type: "user".to_string(),
+                resource_id: Some(*user.id),
+                old_value: Some(serde_json::json!({ "email": user.email })),
+                new_value: Some(serde_json::json!({ "job_id": job.id.to_string() })),
+                ip_address: None,
+            })
+            .await;
+        progress.record_success(Some(serde_json::json!({
+            "user_id": user.id,
+            "status": "deleted",
+        })));
+        Ok(JobOutcome::Completed)
+    }
+}
+
+fn require_job_tenant(job: &Job) -> Result<StringUuid> {
+    job.tenant_id
+        .ok_or_else(|| AppError::BadRequest(format!("Job {} has no tenant", job.id)))
+}
+
+#[async_trait]
+impl<S> JobExecutor for StateJobExecutor<S>
+where
+    S: HasServices
+        + HasRequiredActions
+        + HasSessionManagement
+        + HasPasswordManagement
+        + HasDbPool
+        + HasSystemSettings
+        + HasWebhooks,
+{
+    async fn execute(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+        match job.job_kind() {
+            Some(JobKind::UserImport) => self.import_users(job, progress).await,
+            Some(JobKind::UserExport) => self.export_users(job, progress).await,
+            Some(JobKind::TenantDelete) => self.delete_tenant(job, progress).await,
+            Some(JobKind::AuditExport) => self.export_audit_logs(job, progress).await,
+            Some(JobKind::ForcePasswordReset) => self.force_password_reset(job, progress).await,
+            Some(JobKind::BreachCampaign) => self.breach_campaign(job, progress).await,
+            Some(JobKind::SchemaBackfill) => self.schema_backfill(job, progress).await,
+            Some(JobKind::InactivitySweep) => self.inactivity_sweep(job, progress).await,
+            Some(JobKind::AccountDeletion) => self.delete_account(job, progress).await,
+            Some(JobKind::KeyRotation) => self.key_rotation(job, progress).await,
+            None => Err(AppError::BadRequest(format!(
+                "Unknown job kind '{}'",
+                job.kind
+            ))),
+        }
+    }
+}
@@ -1,5 +1,11 @@
 //! Platform domain API facade.
 
+pub mod admin_scope;
+pub mod billing;
 pub mod branding;
+pub mod email_queue;
 pub mod email_template;
+pub mod job;
+pub mod rate_limit;
+pub mod saga;
 pub mod system_settings;
@@ -0,0 +1,46 @@
+//! Rate limit introspection API handler
+
+use crate::error::{AppError, Result};
+use crate::http_support::SuccessResponse;
+use crate::middleware::rate_limit::{RateLimitCaller, RateLimitError};
+use crate::models::rate_limit::RateLimitStatus;
+use axum::{response::IntoResponse, Extension, Json};
+
+/// Get the caller's rate limit buckets and remaining quota
+///
+/// The caller is identified the same way the rate limiter counts it: by the
+/// user or tenant client of a valid bearer token, else by client IP.
+///
+/// GET /api/v1/rate-limits/self
+#[utoipa::path(
+    get,
+    path = "/api/v1/rate-limits/self",
+    tag = "Platform",
+    responses(
+        (status = 200, description = "Caller's rate limit status", body = RateLimitStatus)
+    )
+)]
+pub async fn get_own_rate_limits(
+    caller: Option<Extension<RateLimitCaller>>,
+) -> Result<impl IntoResponse> {
+    let status = match caller {
+        Some(Extension(caller)) => caller.status().await.map_err(|e| match e {
+            RateLimitError::NotConfigured => {
+                AppError::Internal(anyhow::anyhow!("Rate limiting is not configured"))
+            }
+            RateLimitError::RedisError(msg) => AppError::Internal(anyhow::anyhow!(
+                "Failed to inspect rate limit buckets: {}",
+                msg
+            )),
+        })?,
+        // Router built without the rate limit layer
+        None => RateLimitStatus {
+            enabled: false,
+            subject: "ip".to_string(),
+            default_limit: 0,
+            default_window_secs: 0,
+            buckets: Vec::new(),
+        },
+    };
+    Ok(Json(SuccessResponse::new(status)))
+}
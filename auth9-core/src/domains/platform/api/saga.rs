@@ -0,0 +1,214 @@
+//! Saga operator API handlers: inspect sagas and resume or compensate the
+//! stuck ones
+
+use crate::domains::authorization::service::sagas::{service_delete_saga, SERVICE_DELETE};
+use crate::domains::tenant_access::service::sagas::{
+    tenant_provision_saga, user_create_saga, TENANT_PROVISION, USER_CREATE,
+};
+use crate::error::{AppError, Result};
+use crate::http_support::{
+    require_platform_admin_with_db, write_audit_log_generic, PaginatedResponse, PaginationQuery,
+    SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::saga::{SagaFilter, SagaRecord, SagaStatus};
+use crate::repository::saga::SagaRepositoryImpl;
+use crate::repository::SagaRepository;
+use crate::saga::SagaRunner;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn saga_repo<S: HasDbPool>(state: &S) -> Arc<SagaRepositoryImpl> {
+    Arc::new(SagaRepositoryImpl::new(state.db_pool().clone()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/sagas",
+    tag = "Platform",
+    params(SagaFilter),
+    responses(
+        (status = 200, description = "Sagas, most recently updated first")
+    )
+)]
+pub async fn list_sagas<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(pagination): Query<PaginationQuery>,
+    Query(filter): Query<SagaFilter>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    if let Some(status) = &filter.status {
+        if SagaStatus::parse(status).is_none() {
+            return Err(AppError::BadRequest(format!(
+                "Unknown saga status '{}'",
+                status
+            )));
+        }
+    }
+    let repo = saga_repo(&state);
+    let offset = (pagination.page - 1) * pagination.per_page;
+    let items = repo.list(&filter, offset, pagination.per_page).await?;
+    let total = repo.count(&filter).await?;
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/system/sagas/{id}",
+    tag = "Platform",
+    params(("id" = Uuid, Path, description = "Saga ID")),
+    responses(
+        (status = 200, description = "Saga with its step states and context", body = SagaRecord),
+        (status = 404, description = "Saga not found")
+    )
+)]
+pub async fn get_saga<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let record = find_saga(&state, id).await?;
+    Ok(Json(SuccessResponse::new(record)))
+}
+
+/// Resume a stuck saga
+///
+/// Retries the failed step and continues in the direction the saga was
+/// going: forward through the remaining steps, or backward through the
+/// remaining compensations. The saga is `stuck` again if it fails again.
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/sagas/{id}/resume",
+    tag = "Platform",
+    params(("id" = Uuid, Path, description = "Saga ID")),
+    responses(
+        (status = 200, description = "Saga state after resuming", body = SagaRecord),
+        (status = 404, description = "Saga not found"),
+        (status = 409, description = "Saga is not stuck")
+    )
+)]
+pub async fn resume_saga<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let record = find_saga(&state, id).await?;
+    let record = recover(&state, record, false).await?;
+    audit_recovery(&state, &headers, "system.saga.resume", &record).await;
+    Ok(Json(SuccessResponse::new(record)))
+}
+
+/// Compensate a stuck saga
+///
+/// Undoes the saga's completed steps instead of finishing it. Not available
+/// for sagas that cannot be undone, such as deletions.
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/sagas/{id}/compensate",
+    tag = "Platform",
+    params(("id" = Uuid, Path, description = "Saga ID")),
+    responses(
+        (status = 200, description = "Saga state after compensating", body = SagaRecord),
+        (status = 400, description = "Saga cannot be compensated"),
+        (status = 404, description = "Saga not found"),
+        (status = 409, description = "Saga is not stuck")
+    )
+)]
+pub async fn compensate_saga<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_platform_admin_with_db(&state, &auth).await?;
+    let record = find_saga(&state, id).await?;
+    let record = recover(&state, record, true).await?;
+    audit_recovery(&state, &headers, "system.saga.compensate", &record).await;
+    Ok(Json(SuccessResponse::new(record)))
+}
+
+async fn find_saga<S: HasDbPool>(state: &S, id: Uuid) -> Result<SagaRecord> {
+    saga_repo(state)
+        .find_by_id(StringUuid::from(id))
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Saga {} not found", id)))
+}
+
+/// Resume or compensate a saga with the definition of its kind
+async fn recover<S: HasServices + HasDbPool>(
+    state: &S,
+    record: SagaRecord,
+    compensate: bool,
+) -> Result<SagaRecord> {
+    let store: Arc<dyn SagaRepository> = saga_repo(state);
+    let runner = SagaRunner::new(Some(store));
+    match record.kind.as_str() {
+        USER_CREATE => {
+            let saga = user_create_saga(state);
+            if compensate {
+                runner.abort(&saga, record).await
+            } else {
+                runner.resume(&saga, record).await
+            }
+        }
+        TENANT_PROVISION => {
+            let saga = tenant_provision_saga(state);
+            if compensate {
+                runner.abort(&saga, record).await
+            } else {
+                runner.resume(&saga, record).await
+            }
+        }
+        SERVICE_DELETE => {
+            let saga = service_delete_saga(state);
+            if compensate {
+                runner.abort(&saga, record).await
+            } else {
+                runner.resume(&saga, record).await
+            }
+        }
+        kind => Err(AppError::BadRequest(format!(
+            "Unknown saga kind '{}'",
+            kind
+        ))),
+    }
+}
+
+async fn audit_recovery<S: HasServices>(
+    state: &S,
+    headers: &HeaderMap,
+    action: &str,
+    record: &SagaRecord,
+) {
+    let _ = write_audit_log_generic(
+        state,
+        headers,
+        action,
+        "saga",
+        Some(*record.id),
+        None,
+        Some(serde_json::json!({
+            "kind": record.kind,
+            "status": record.status,
+            "error": record.error,
+        })),
+    )
+    .await;
+}
@@ -1,11 +1,11 @@
-use crate::state::{HasBranding, HasEmailTemplates, HasServices, HasSystemSettings};
+use crate::state::{HasBranding, HasDbPool, HasEmailTemplates, HasServices, HasSystemSettings};
 
 pub trait PlatformContext:
-    HasServices + HasSystemSettings + HasEmailTemplates + HasBranding
+    HasServices + HasSystemSettings + HasEmailTemplates + HasBranding + HasDbPool
 {
 }
 
 impl<T> PlatformContext for T where
-    T: HasServices + HasSystemSettings + HasEmailTemplates + HasBranding
+    T: HasServices + HasSystemSettings + HasEmailTemplates + HasBranding + HasDbPool
 {
 }
@@ -1,7 +1,7 @@
 use crate::domains::platform::api as platform_api;
 use crate::domains::platform::context::PlatformContext;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 
@@ -9,10 +9,23 @@ pub fn public_routes<S>() -> Router<S>
 where
     S: PlatformContext,
 {
-    Router::new().route(
-        "/api/v1/public/branding",
-        get(platform_api::branding::get_public_branding::<S>),
-    )
+    Router::new()
+        .route(
+            "/api/v1/public/branding",
+            get(platform_api::branding::get_public_branding::<S>),
+        )
+        .route(
+            "/api/v1/public/branding/context",
+            get(platform_api::branding::get_public_branding_context::<S>),
+        )
+        .route(
+            "/api/v1/rate-limits/self",
+            get(platform_api::rate_limit::get_own_rate_limits),
+        )
+        .route(
+            "/api/v1/email/feedback",
+            post(platform_api::email_queue::receive_feedback::<S>),
+        )
 }
 
 pub fn protected_routes<S>() -> Router<S>
@@ -33,6 +46,43 @@ where
             "/api/v1/system/email/send-test",
             post(platform_api::system_settings::send_test_email::<S>),
         )
+        .route(
+            "/api/v1/system/email/queue",
+            get(platform_api::email_queue::get_queue_stats::<S>),
+        )
+        .route(
+            "/api/v1/system/email/suppressions",
+            get(platform_api::email_queue::list_suppressions::<S>)
+                .post(platform_api::email_queue::create_suppression::<S>),
+        )
+        .route(
+            "/api/v1/system/email/suppressions/{email}",
+            delete(platform_api::email_queue::delete_suppression::<S>),
+        )
+        .route(
+            "/api/v1/system/sagas",
+            get(platform_api::saga::list_sagas::<S>),
+        )
+        .route(
+            "/api/v1/system/sagas/{id}",
+            get(platform_api::saga::get_saga::<S>),
+        )
+        .route(
+            "/api/v1/system/sagas/{id}/resume",
+            post(platform_api::saga::resume_saga::<S>),
+        )
+        .route(
+            "/api/v1/system/sagas/{id}/compensate",
+            post(platform_api::saga::compensate_saga::<S>),
+        )
+        .route(
+            "/api/v1/system/billing/events",
+            get(platform_api::billing::list_billing_events::<S>),
+        )
+        .route(
+            "/api/v1/system/billing/events/{id}/redeliver",
+            post(platform_api::billing::redeliver_billing_event::<S>),
+        )
         .route(
             "/api/v1/system/security/malicious-ip-blacklist",
             get(platform_api::system_settings::get_malicious_ip_blacklist::<S>)
@@ -67,4 +117,38 @@ where
                 .put(platform_api::branding::update_service_branding::<S>)
                 .delete(platform_api::branding::delete_service_branding::<S>),
         )
+        .route(
+            "/api/v1/admin/users/{id}/scopes",
+            get(platform_api::admin_scope::get_admin_scopes::<S>)
+                .put(platform_api::admin_scope::set_admin_scopes::<S>),
+        )
+        .route("/api/v1/jobs/{id}", get(platform_api::job::get_job::<S>))
+        .route(
+            "/api/v1/jobs/{id}/cancel",
+            post(platform_api::job::cancel_job::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/jobs",
+            get(platform_api::job::list_tenant_jobs::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users/import",
+            post(platform_api::job::import_users::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users/export",
+            post(platform_api::job::export_users::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users:force-password-reset",
+            post(platform_api::job::force_password_reset::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/users:breach-campaign",
+            post(platform_api::job::start_breach_campaign::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/breach-campaigns/{job_id}/report",
+            get(platform_api::job::breach_campaign_report::<S>),
+        )
 }
@@ -0,0 +1,143 @@
+//! Scoped platform admin permissions
+//!
+//! Platform admins listed in `PLATFORM_ADMIN_EMAILS` are root admins: they hold
+//! every scope and are the only ones who can grant scopes. Other platform
+//! admins (admins of the `auth9-platform` tenant) hold only the scopes granted
+//! to them once `PLATFORM_ADMIN_SCOPES_ENFORCED` is enabled.
+
+use crate::error::{AppError, Result};
+use crate::models::admin_scope::AdminScope;
+use crate::models::common::StringUuid;
+use crate::repository::AdminScopeRepository;
+use std::sync::Arc;
+
+pub struct AdminScopeService<R: AdminScopeRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: AdminScopeRepository> AdminScopeService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// Scopes granted to a platform admin (unknown stored values are ignored)
+    pub async fn scopes_for(&self, user_id: StringUuid) -> Result<Vec<AdminScope>> {
+        let grants = self.repo.list_by_user(user_id).await?;
+        Ok(grants
+            .iter()
+            .filter_map(|g| AdminScope::parse(&g.scope))
+            .collect())
+    }
+
+    pub async fn set_scopes(
+        &self,
+        user_id: StringUuid,
+        mut scopes: Vec<AdminScope>,
+        granted_by: StringUuid,
+    ) -> Result<Vec<AdminScope>> {
+        scopes.sort_by_key(|s| s.as_str());
+        scopes.dedup();
+        self.repo
+            .replace_for_user(user_id, &scopes, Some(granted_by))
+            .await?;
+        Ok(scopes)
+    }
+}
+
+/// Decide whether a platform admin may use `required`.
+///
+/// `granted` is only consulted for non-root admins while enforcement is on.
+pub fn check_admin_scope(
+    required: AdminScope,
+    is_root: bool,
+    enforced: bool,
+    granted: &[AdminScope],
+) -> Result<()> {
+    if is_root || !enforced || granted.contains(&required) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "Admin scope '{}' required",
+            required.as_str()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::admin_scope::AdminScopeGrant;
+    use crate::repository::admin_scope::MockAdminScopeRepository;
+    use mockall::predicate::*;
+
+    fn grant(user_id: StringUuid, scope: &str) -> AdminScopeGrant {
+        AdminScopeGrant {
+            id: StringUuid::new_v4(),
+            user_id,
+            scope: scope.to_string(),
+            granted_by: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_check_admin_scope() {
+        let read_only = [AdminScope::TenantsRead];
+
+        assert!(check_admin_scope(AdminScope::TenantsRead, false, true, &read_only).is_ok());
+        let err = check_admin_scope(AdminScope::TenantsWrite, false, true, &read_only).unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(msg) if msg.contains("tenants:write")));
+
+        // Root admins and unenforced deployments keep full access
+        assert!(check_admin_scope(AdminScope::SettingsWrite, true, true, &[]).is_ok());
+        assert!(check_admin_scope(AdminScope::SettingsWrite, false, false, &[]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scopes_for_ignores_unknown_values() {
+        let user_id = StringUuid::new_v4();
+        let mut mock = MockAdminScopeRepository::new();
+        mock.expect_list_by_user()
+            .with(eq(user_id))
+            .returning(move |_| {
+                Ok(vec![
+                    grant(user_id, "tenants:read"),
+                    grant(user_id, "billing:write"),
+                ])
+            });
+
+        let service = AdminScopeService::new(Arc::new(mock));
+        let scopes = service.scopes_for(user_id).await.unwrap();
+        assert_eq!(scopes, vec![AdminScope::TenantsRead]);
+    }
+
+    #[tokio::test]
+    async fn test_set_scopes_dedupes() {
+        let user_id = StringUuid::new_v4();
+        let granted_by = StringUuid::new_v4();
+        let mut mock = MockAdminScopeRepository::new();
+        mock.expect_replace_for_user()
+            .withf(move |u, scopes, by| {
+                *u == user_id
+                    && scopes == [AdminScope::SettingsWrite, AdminScope::TenantsRead]
+                    && *by == Some(granted_by)
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = AdminScopeService::new(Arc::new(mock));
+        let scopes = service
+            .set_scopes(
+                user_id,
+                vec![
+                    AdminScope::TenantsRead,
+                    AdminScope::SettingsWrite,
+                    AdminScope::TenantsRead,
+                ],
+                granted_by,
+            )
+            .await
+            .unwrap();
+        assert_eq!(scopes.len(), 2);
+    }
+}
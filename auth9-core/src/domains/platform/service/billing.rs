@@ -0,0 +1,453 @@
+//! Billing integration hooks: idempotent event ledger, MAU threshold
+//! detection and delivery to the billing sink
+
+use crate::config::{BillingConfig, BillingSinkKind};
+use crate::error::Result;
+use crate::models::billing::{
+    billing_period, billing_period_start, crossed_thresholds, sink_payload, BillingEvent,
+    BillingEventFilter, NewBillingEvent,
+};
+use crate::models::common::StringUuid;
+use crate::models::email_queue::retry_delay_secs;
+use crate::repository::billing::BillingEventRepositoryImpl;
+use crate::repository::BillingEventRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Claimed events not reported back within this window are requeued
+const STALE_CLAIM_SECS: i64 = 600;
+
+/// First retry delay; doubled per attempt up to [`RETRY_MAX_SECS`]
+const RETRY_BASE_SECS: u64 = 60;
+const RETRY_MAX_SECS: u64 = 6 * 3600;
+
+/// Ledger recording and administration
+pub struct BillingService {
+    repo: Arc<dyn BillingEventRepository>,
+}
+
+impl BillingService {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(BillingEventRepositoryImpl::new(pool)))
+    }
+
+    pub fn new(repo: Arc<dyn BillingEventRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Add an event to the ledger. Returns false when its idempotency key was
+    /// already recorded.
+    pub async fn record(&self, event: &NewBillingEvent) -> Result<bool> {
+        let inserted = self.repo.record(event).await?;
+        if inserted {
+            metrics::counter!("auth9_billing_events_recorded_total", "type" => event.event_type.as_str())
+                .increment(1);
+        }
+        Ok(inserted)
+    }
+
+    pub async fn list(
+        &self,
+        filter: &BillingEventFilter,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<BillingEvent>, i64)> {
+        let offset = (page - 1) * per_page;
+        let items = self.repo.list(filter, offset, per_page).await?;
+        let total = self.repo.count(filter).await?;
+        Ok((items, total))
+    }
+
+    pub async fn redeliver(&self, id: StringUuid) -> Result<BillingEvent> {
+        self.repo.redeliver(id).await
+    }
+
+    /// Count this period's monthly active users and record an event for
+    /// every threshold a tenant has reached. Returns the number of new events.
+    pub async fn check_mau_thresholds(
+        &self,
+        thresholds: &[u64],
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        if thresholds.is_empty() {
+            return Ok(0);
+        }
+        let period = billing_period(now);
+        let usage = self
+            .repo
+            .monthly_active_users(billing_period_start(now))
+            .await?;
+
+        let mut recorded = 0;
+        for (tenant_id, mau) in usage {
+            for threshold in crossed_thresholds(thresholds, mau) {
+                let event =
+                    NewBillingEvent::mau_threshold_crossed(tenant_id, &period, threshold, mau, now);
+                if self.record(&event).await? {
+                    recorded += 1;
+                }
+            }
+        }
+        Ok(recorded)
+    }
+}
+
+/// Destination billing events are delivered to
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BillingSink: Send + Sync {
+    async fn deliver(&self, event: &BillingEvent) -> std::result::Result<(), String>;
+}
+
+/// Posts events to the configured sink URL
+pub struct HttpBillingSink {
+    client: reqwest::Client,
+    url: String,
+    kind: BillingSinkKind,
+    signing_secret: Option<String>,
+}
+
+impl HttpBillingSink {
+    /// `None` when no sink URL is configured
+    pub fn from_config(config: &BillingConfig) -> Option<Self> {
+        let url = config.sink_url.clone()?;
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+        Some(Self {
+            client,
+            url,
+            kind: config.sink_kind,
+            signing_secret: config.signing_secret.clone(),
+        })
+    }
+}
+
+/// `Stripe-Signature` header value: `t=<unix>,v1=<hex HMAC-SHA256 of "<t>.<body>">`
+fn stripe_signature(body: &[u8], secret: &str, timestamp: i64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+#[async_trait]
+impl BillingSink for HttpBillingSink {
+    async fn deliver(&self, event: &BillingEvent) -> std::result::Result<(), String> {
+        let body =
+            serde_json::to_vec(&sink_payload(self.kind, event)).map_err(|e| e.to_string())?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("Idempotency-Key", &event.idempotency_key);
+        if let Some(secret) = &self.signing_secret {
+            request = match self.kind {
+                BillingSinkKind::Http => request.header(
+                    crate::webhook::SIGNATURE_HEADER,
+                    crate::webhook::sign(&body, secret),
+                ),
+                BillingSinkKind::StripeRelay => request.header(
+                    "Stripe-Signature",
+                    stripe_signature(&body, secret, Utc::now().timestamp()),
+                ),
+            };
+        }
+
+        let response = request.body(body).send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        // 409 means the sink already has this idempotency key
+        if status.is_success() || status == reqwest::StatusCode::CONFLICT {
+            Ok(())
+        } else {
+            Err(format!("Billing sink responded with {}", status))
+        }
+    }
+}
+
+/// Outcome counts of one dispatcher run
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BillingDispatchRun {
+    pub delivered: usize,
+    pub retried: usize,
+    pub failed: usize,
+}
+
+impl BillingDispatchRun {
+    pub fn total(&self) -> usize {
+        self.delivered + self.retried + self.failed
+    }
+}
+
+/// Periodically recounts MAU and delivers pending ledger entries
+pub struct BillingDispatcher {
+    service: BillingService,
+    repo: Arc<dyn BillingEventRepository>,
+    sink: Arc<dyn BillingSink>,
+    config: BillingConfig,
+}
+
+impl BillingDispatcher {
+    pub fn new(
+        repo: Arc<dyn BillingEventRepository>,
+        sink: Arc<dyn BillingSink>,
+        config: BillingConfig,
+    ) -> Self {
+        Self {
+            service: BillingService::new(repo.clone()),
+            repo,
+            sink,
+            config,
+        }
+    }
+
+    /// Run until the task is dropped
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
+            let mau_interval = Duration::from_secs(self.config.mau_check_interval_secs);
+            let mut next_mau_check = Instant::now();
+            loop {
+                if Instant::now() >= next_mau_check {
+                    match self
+                        .service
+                        .check_mau_thresholds(&self.config.mau_thresholds, Utc::now())
+                        .await
+                    {
+                        Ok(0) => {}
+                        Ok(n) => tracing::info!(count = n, "Recorded MAU threshold billing events"),
+                        Err(e) => tracing::warn!(error = %e, "MAU threshold check failed"),
+                    }
+                    next_mau_check = Instant::now() + mau_interval;
+                }
+
+                match self.run_once().await {
+                    // A full batch means more events are likely due
+                    Ok(run) if run.total() >= self.config.batch_size => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(error = %e, "Billing event dispatch failed"),
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+    }
+
+    /// Claim a batch of due events and deliver them to the sink
+    pub async fn run_once(&self) -> Result<BillingDispatchRun> {
+        let stale_before = Utc::now() - chrono::Duration::seconds(STALE_CLAIM_SECS);
+        match self.repo.requeue_stale(stale_before).await {
+            Ok(0) => {}
+            Ok(n) => tracing::warn!(count = n, "Requeued stale billing event claims"),
+            Err(e) => tracing::warn!(error = %e, "Failed to requeue stale billing event claims"),
+        }
+
+        let claim_id = format!("billing:{}", uuid::Uuid::new_v4());
+        let events = self
+            .repo
+            .claim_due(&claim_id, self.config.batch_size)
+            .await?;
+
+        let mut run = BillingDispatchRun::default();
+        for event in &events {
+            match self.sink.deliver(event).await {
+                Ok(()) => {
+                    self.repo.mark_delivered(event.id).await?;
+                    metrics::counter!("auth9_billing_events_delivered_total").increment(1);
+                    run.delivered += 1;
+                }
+                Err(error) => {
+                    let attempts = event.attempts.max(0) as u32 + 1;
+                    if attempts >= self.config.max_attempts {
+                        tracing::error!(event_id = %event.id, attempts, error, "Giving up on billing event");
+                        self.repo.mark_failed(event.id, &error).await?;
+                        metrics::counter!("auth9_billing_events_failed_total").increment(1);
+                        run.failed += 1;
+                    } else {
+                        let delay = retry_delay_secs(attempts, RETRY_BASE_SECS, RETRY_MAX_SECS);
+                        tracing::warn!(event_id = %event.id, attempts, delay, error, "Billing event delivery failed");
+                        self.repo
+                            .mark_retry(
+                                event.id,
+                                &error,
+                                Utc::now() + chrono::Duration::seconds(delay as i64),
+                            )
+                            .await?;
+                        run.retried += 1;
+                    }
+                }
+            }
+        }
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::billing::MockBillingEventRepository;
+
+    fn pending(event: NewBillingEvent, attempts: i32) -> BillingEvent {
+        BillingEvent {
+            id: StringUuid::new_v4(),
+            idempotency_key: event.idempotency_key,
+            tenant_id: event.tenant_id,
+            event_type: event.event_type.as_str().to_string(),
+            data: event.data,
+            occurred_at: event.occurred_at,
+            status: "sending".to_string(),
+            attempts,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        }
+    }
+
+    fn tenant_created(attempts: i32) -> BillingEvent {
+        pending(
+            NewBillingEvent::tenant_created(StringUuid::new_v4(), "Acme", "acme", Utc::now()),
+            attempts,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_record_reports_duplicates() {
+        let mut repo = MockBillingEventRepository::new();
+        let mut seq = mockall::Sequence::new();
+        repo.expect_record()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(true));
+        repo.expect_record()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(false));
+        let service = BillingService::new(Arc::new(repo));
+
+        let event =
+            NewBillingEvent::tenant_created(StringUuid::new_v4(), "Acme", "acme", Utc::now());
+        assert!(service.record(&event).await.unwrap());
+        assert!(!service.record(&event).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_check_mau_thresholds_records_each_crossed_threshold() {
+        let tenant_a = StringUuid::new_v4();
+        let tenant_b = StringUuid::new_v4();
+        let mut repo = MockBillingEventRepository::new();
+        repo.expect_monthly_active_users()
+            .returning(move |_| Ok(vec![(tenant_a, 12_000), (tenant_b, 10)]));
+        repo.expect_record()
+            .withf(move |e| e.tenant_id == tenant_a)
+            .times(2)
+            .returning(|_| Ok(true));
+        let service = BillingService::new(Arc::new(repo));
+
+        let recorded = service
+            .check_mau_thresholds(&[1_000, 10_000, 100_000], Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(recorded, 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_mau_thresholds_without_thresholds_skips_count() {
+        let mut repo = MockBillingEventRepository::new();
+        repo.expect_monthly_active_users().never();
+        let service = BillingService::new(Arc::new(repo));
+
+        assert_eq!(
+            service.check_mau_thresholds(&[], Utc::now()).await.unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_once_delivers_and_retries() {
+        let ok = tenant_created(0);
+        let failing = tenant_created(0);
+        let ok_id = ok.id;
+        let failing_id = failing.id;
+
+        let mut repo = MockBillingEventRepository::new();
+        repo.expect_requeue_stale().returning(|_| Ok(0));
+        repo.expect_claim_due()
+            .returning(move |_, _| Ok(vec![ok.clone(), failing.clone()]));
+        repo.expect_mark_delivered()
+            .withf(move |id| *id == ok_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        repo.expect_mark_retry()
+            .withf(move |id, error, _| *id == failing_id && error.contains("503"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let mut sink = MockBillingSink::new();
+        sink.expect_deliver().returning(move |event| {
+            if event.id == ok_id {
+                Ok(())
+            } else {
+                Err("Billing sink responded with 503".to_string())
+            }
+        });
+
+        let dispatcher =
+            BillingDispatcher::new(Arc::new(repo), Arc::new(sink), BillingConfig::default());
+        let run = dispatcher.run_once().await.unwrap();
+        assert_eq!(
+            run,
+            BillingDispatchRun {
+                delivered: 1,
+                retried: 1,
+                failed: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_once_gives_up_after_max_attempts() {
+        let event = tenant_created(2);
+        let mut repo = MockBillingEventRepository::new();
+        repo.expect_requeue_stale().returning(|_| Ok(0));
+        repo.expect_claim_due()
+            .returning(move |_, _| Ok(vec![event.clone()]));
+        repo.expect_mark_failed().times(1).returning(|_, _| Ok(()));
+        repo.expect_mark_retry().never();
+
+        let mut sink = MockBillingSink::new();
+        sink.expect_deliver()
+            .returning(|_| Err("connection refused".to_string()));
+
+        let dispatcher = BillingDispatcher::new(
+            Arc::new(repo),
+            Arc::new(sink),
+            BillingConfig {
+                max_attempts: 3,
+                ..Default::default()
+            },
+        );
+        assert_eq!(dispatcher.run_once().await.unwrap().failed, 1);
+    }
+
+    #[test]
+    fn test_stripe_signature_format() {
+        let signature = stripe_signature(b"{}", "whsec_test", 1_700_000_000);
+        assert!(signature.starts_with("t=1700000000,v1="));
+        assert_eq!(signature.len(), "t=1700000000,v1=".len() + 64);
+    }
+}
@@ -2,15 +2,37 @@
 
 use crate::domains::platform::service::IdentitySyncService;
 use crate::error::{AppError, Result};
-use crate::models::branding::{BrandingConfig, ServiceBranding};
+use crate::models::branding::{BrandingConfig, BrandingContext, ServiceBranding};
+use crate::models::common::StringUuid;
 use crate::models::system_settings::{SettingCategory, SystemSettingRow, UpsertSystemSettingInput};
-use crate::repository::{ServiceBrandingRepository, ServiceRepository, SystemSettingsRepository};
-use std::sync::Arc;
+use crate::repository::{
+    ServiceBrandingRepository, ServiceRepository, SystemSettingsRepository, TenantRepository,
+};
+use async_trait::async_trait;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use validator::Validate;
 
 /// Setting key for branding configuration
 const BRANDING_CONFIG_KEY: &str = "config";
 
+/// Resolved contexts kept per tenant (None = platform defaults)
+const CONTEXT_CACHE_CAPACITY: usize = 1024;
+const CONTEXT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+type ContextCache = LruCache<Option<StringUuid>, (BrandingContext, Instant)>;
+
+/// Source of tenant branding for rendering paths that only need the
+/// resolved context (e.g. email)
+#[async_trait]
+pub trait BrandingResolver: Send + Sync {
+    /// Branding for `tenant_id`, or the platform's for `None`. Never fails:
+    /// lookup errors fall back to the platform defaults.
+    async fn branding_context(&self, tenant_id: Option<StringUuid>) -> BrandingContext;
+}
+
 /// Service for managing branding configuration
 pub struct BrandingService<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> {
     repo: Arc<R>,
@@ -21,6 +43,9 @@ pub struct BrandingService<R: SystemSettingsRepository, SBR: ServiceBrandingRepo
     allowed_domains: Vec<String>,
     /// Service repository for resolving client_id -> service_id
     service_repo: Option<Arc<dyn ServiceRepository>>,
+    /// Tenant repository for layering tenant branding over the platform's
+    tenant_repo: Option<Arc<dyn TenantRepository>>,
+    context_cache: Mutex<ContextCache>,
 }
 
 impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingService<R, SBR> {
@@ -32,6 +57,10 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
             sync_service: None,
             allowed_domains: vec![],
             service_repo: None,
+            tenant_repo: None,
+            context_cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(CONTEXT_CACHE_CAPACITY).expect("non-zero capacity"),
+            )),
         }
     }
 
@@ -47,6 +76,10 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
             sync_service: Some(sync_service),
             allowed_domains: vec![],
             service_repo: None,
+            tenant_repo: None,
+            context_cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(CONTEXT_CACHE_CAPACITY).expect("non-zero capacity"),
+            )),
         }
     }
 
@@ -62,6 +95,12 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
         self
     }
 
+    /// Set tenant repository for tenant branding resolution
+    pub fn with_tenant_repo(mut self, tenant_repo: Arc<dyn TenantRepository>) -> Self {
+        self.tenant_repo = Some(tenant_repo);
+        self
+    }
+
     /// Get system-level branding configuration (default)
     ///
     /// Returns the stored configuration or default values if not configured
@@ -92,6 +131,7 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
         };
 
         self.repo.upsert(&input).await?;
+        self.invalidate_contexts();
 
         if let Some(sync) = &self.sync_service {
             sync.sync_branding_config(&config).await;
@@ -143,6 +183,57 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
         }
     }
 
+    /// Branding context for a tenant (platform defaults for `None`).
+    ///
+    /// Contexts are cached for a minute, so tenant branding changes show up
+    /// in emails and hosted pages shortly after they are saved.
+    pub async fn resolve_context(&self, tenant_id: Option<StringUuid>) -> Result<BrandingContext> {
+        if let Ok(mut cache) = self.context_cache.lock() {
+            if let Some((context, cached_at)) = cache.get(&tenant_id) {
+                if cached_at.elapsed() < CONTEXT_CACHE_TTL {
+                    return Ok(context.clone());
+                }
+            }
+        }
+
+        let platform = self.get_branding().await?;
+        let tenant = match (tenant_id, &self.tenant_repo) {
+            (Some(id), Some(repo)) => repo.find_by_id(id).await?,
+            _ => None,
+        };
+        let context = BrandingContext::resolve(&platform, tenant.as_ref());
+
+        if let Ok(mut cache) = self.context_cache.lock() {
+            cache.put(tenant_id, (context.clone(), Instant::now()));
+        }
+        Ok(context)
+    }
+
+    /// Branding context for the tenant with `slug`
+    pub async fn resolve_context_by_slug(&self, slug: &str) -> Result<BrandingContext> {
+        let Some(repo) = &self.tenant_repo else {
+            return self.resolve_context(None).await;
+        };
+        let tenant = repo
+            .find_by_slug(slug)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Tenant '{}' not found", slug)))?;
+        self.resolve_context(Some(tenant.id)).await
+    }
+
+    /// Drop cached contexts for a tenant, e.g. after its settings change
+    pub fn invalidate_context(&self, tenant_id: StringUuid) {
+        if let Ok(mut cache) = self.context_cache.lock() {
+            cache.pop(&Some(tenant_id));
+        }
+    }
+
+    fn invalidate_contexts(&self) {
+        if let Ok(mut cache) = self.context_cache.lock() {
+            cache.clear();
+        }
+    }
+
     /// Update branding for a specific service
     pub async fn update_service_branding(
         &self,
@@ -208,6 +299,27 @@ impl<R: SystemSettingsRepository, SBR: ServiceBrandingRepository> BrandingServic
     }
 }
 
+#[async_trait]
+impl<R, SBR> BrandingResolver for BrandingService<R, SBR>
+where
+    R: SystemSettingsRepository + 'static,
+    SBR: ServiceBrandingRepository + 'static,
+{
+    async fn branding_context(&self, tenant_id: Option<StringUuid>) -> BrandingContext {
+        match self.resolve_context(tenant_id).await {
+            Ok(context) => context,
+            Err(e) => {
+                tracing::warn!(
+                    tenant_id = ?tenant_id,
+                    error = %e,
+                    "Failed to resolve branding, using platform defaults"
+                );
+                BrandingContext::default()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,6 +417,8 @@ mod tests {
             favicon_url: None,
             allow_registration: false,
             email_otp_enabled: false,
+            support_email: None,
+            support_url: None,
         };
 
         let result = service.update_branding(config.clone()).await;
@@ -405,4 +519,58 @@ mod tests {
         };
         assert!(service.validate_branding(&config).is_ok());
     }
+
+    #[tokio::test]
+    async fn test_resolve_context_layers_tenant_and_caches() {
+        use crate::models::tenant::Tenant;
+        use crate::repository::tenant::MockTenantRepository;
+
+        let (mut mock_sys, mock_sb) = create_service();
+        mock_sys
+            .expect_get()
+            .with(eq("branding"), eq("config"))
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let tenant = Tenant {
+            name: "Acme".to_string(),
+            ..Default::default()
+        };
+        let tenant_id = tenant.id;
+        let mut mock_tenant = MockTenantRepository::new();
+        mock_tenant
+            .expect_find_by_id()
+            .with(eq(tenant_id))
+            .times(1)
+            .returning(move |_| Ok(Some(tenant.clone())));
+
+        let service = BrandingService::new(Arc::new(mock_sys), Arc::new(mock_sb))
+            .with_tenant_repo(Arc::new(mock_tenant));
+
+        let context = service.resolve_context(Some(tenant_id)).await.unwrap();
+        assert_eq!(context.name, "Acme");
+        assert_eq!(context.tenant_id, Some(tenant_id));
+
+        // Served from the cache: the mocks only allow one lookup each
+        let cached = service.resolve_context(Some(tenant_id)).await.unwrap();
+        assert_eq!(cached, context);
+    }
+
+    #[tokio::test]
+    async fn test_branding_context_falls_back_on_error() {
+        use crate::repository::tenant::MockTenantRepository;
+
+        let (mut mock_sys, mock_sb) = create_service();
+        mock_sys.expect_get().returning(|_, _| Ok(None));
+        let mut mock_tenant = MockTenantRepository::new();
+        mock_tenant
+            .expect_find_by_id()
+            .returning(|_| Err(AppError::Internal(anyhow::anyhow!("database down"))));
+
+        let service = BrandingService::new(Arc::new(mock_sys), Arc::new(mock_sb))
+            .with_tenant_repo(Arc::new(mock_tenant));
+
+        let context = service.branding_context(Some(StringUuid::new_v4())).await;
+        assert_eq!(context, BrandingContext::default());
+    }
 }
@@ -1,19 +1,33 @@
 //! Email service for sending emails through configured providers
 
+use crate::config::FailurePolicy;
+use crate::domains::platform::service::branding::BrandingResolver;
 use crate::domains::platform::service::{EmailTemplateService, SystemSettingsService};
 use crate::email::{
     EmailProvider, EmailProviderError, RenderedEmail, SesEmailProvider, SmtpEmailProvider,
     TemplateEngine,
 };
 use crate::error::{AppError, Result};
+use crate::i18n::{Locale, DEFAULT_LOCALE};
+use crate::models::branding::BrandingContext;
+use crate::models::common::StringUuid;
 use crate::models::email::{
     EmailAddress, EmailMessage, EmailProviderConfig, EmailSendResult, TenantEmailSettings,
 };
+use crate::models::email_queue::{normalize_email, QueuedEmailPayload};
 use crate::models::email_template::EmailTemplateType;
-use crate::repository::SystemSettingsRepository;
+use crate::repository::{EmailQueueRepository, SystemSettingsRepository};
+use crate::telemetry::metrics::record_degraded_operation;
 use async_trait::async_trait;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of emails held for deferred delivery
+const MAX_DEFERRED_EMAILS: usize = 1000;
+
+/// Delivery attempts for a deferred email before it is dropped
+const MAX_DEFERRED_ATTEMPTS: u32 = 5;
 
 /// Factory for building an [`EmailProvider`] from configuration.
 ///
@@ -61,13 +75,27 @@ impl EmailProviderFactory for DefaultEmailProviderFactory {
     }
 }
 
+/// Email held for retry after the provider was unreachable
+struct DeferredEmail {
+    message: EmailMessage,
+    tenant_settings: Option<TenantEmailSettings>,
+    attempts: u32,
+}
+
 /// Service for sending emails
 ///
-/// Handles provider selection with tenant override support.
+/// Handles provider selection with tenant override support. With a persistent
+/// queue attached, emails are queued for the background worker instead of
+/// being sent inline, and suppressed recipients are skipped.
 pub struct EmailService<R: SystemSettingsRepository> {
     settings_service: Arc<SystemSettingsService<R>>,
     provider_factory: Arc<dyn EmailProviderFactory>,
     template_service: Option<Arc<EmailTemplateService<R>>>,
+    /// Behavior when the provider is unreachable (default: fail-closed)
+    failure_policy: FailurePolicy,
+    deferred: Mutex<VecDeque<DeferredEmail>>,
+    queue: Option<Arc<dyn EmailQueueRepository>>,
+    branding: Option<Arc<dyn BrandingResolver>>,
 }
 
 impl<R: SystemSettingsRepository> EmailService<R> {
@@ -76,6 +104,10 @@ impl<R: SystemSettingsRepository> EmailService<R> {
             settings_service,
             provider_factory: Arc::new(DefaultEmailProviderFactory),
             template_service: None,
+            failure_policy: FailurePolicy::FailClosed,
+            deferred: Mutex::new(VecDeque::new()),
+            queue: None,
+            branding: None,
         }
     }
 
@@ -85,8 +117,28 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         self
     }
 
+    /// Set the behavior when the email provider is unreachable.
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Queue emails in the database for the background worker and honor the
+    /// suppression list.
+    pub fn with_queue(mut self, queue: Arc<dyn EmailQueueRepository>) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Render templates with tenant branding (name, logo, color, support
+    /// links) instead of the built-in defaults.
+    pub fn with_branding(mut self, branding: Arc<dyn BrandingResolver>) -> Self {
+        self.branding = Some(branding);
+        self
+    }
+
     #[cfg(test)]
-    fn new_with_factory(
+    pub(crate) fn new_with_factory(
         settings_service: Arc<SystemSettingsService<R>>,
         provider_factory: Arc<dyn EmailProviderFactory>,
     ) -> Self {
@@ -94,6 +146,10 @@ impl<R: SystemSettingsRepository> EmailService<R> {
             settings_service,
             provider_factory,
             template_service: None,
+            failure_policy: FailurePolicy::FailClosed,
+            deferred: Mutex::new(VecDeque::new()),
+            queue: None,
+            branding: None,
         }
     }
 
@@ -102,16 +158,47 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         &self,
         template_type: EmailTemplateType,
         variables: &HashMap<String, String>,
+    ) -> Result<RenderedEmail> {
+        self.resolve_and_render_localized(template_type, DEFAULT_LOCALE, variables)
+            .await
+    }
+
+    /// Resolve template content for `locale` and render with variables,
+    /// using the platform branding.
+    pub async fn resolve_and_render_localized(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
+        variables: &HashMap<String, String>,
+    ) -> Result<RenderedEmail> {
+        self.resolve_and_render_for_tenant(template_type, locale, None, variables)
+            .await
+    }
+
+    /// Resolve template content for `locale` and render with `tenant_id`'s
+    /// branding. Explicit variables take precedence over branding ones.
+    pub async fn resolve_and_render_for_tenant(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
+        tenant_id: Option<StringUuid>,
+        variables: &HashMap<String, String>,
     ) -> Result<RenderedEmail> {
         use crate::email::templates::EmailTemplate;
 
         let content = if let Some(ts) = &self.template_service {
-            ts.get_content(template_type).await?
+            ts.get_content_for_locale(template_type, locale).await?
         } else {
-            EmailTemplate::default_content(template_type)
+            EmailTemplate::localized_content(template_type, locale)
+        };
+
+        let branding = match &self.branding {
+            Some(resolver) => resolver.branding_context(tenant_id).await,
+            None => BrandingContext::default(),
         };
 
         let mut engine = TemplateEngine::new();
+        engine.set_all(branding.template_variables());
         for (key, value) in variables {
             engine.set(key, value);
         }
@@ -123,31 +210,98 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         })
     }
 
-    /// Send an email using the configured provider
+    /// Send an email
     ///
-    /// Uses tenant settings if provided, otherwise falls back to system settings.
+    /// Suppressed recipients are dropped. With a queue attached the email is
+    /// queued for background delivery; emails using a tenant provider override
+    /// are sent inline because the override's credentials are not persisted.
+    /// If queueing fails the email is sent inline as well.
     pub async fn send(
         &self,
         message: &EmailMessage,
         tenant_settings: Option<&TenantEmailSettings>,
     ) -> Result<EmailSendResult> {
-        // Get the effective email configuration
-        let config = self.get_effective_config(tenant_settings).await?;
+        let Some(message) = self.without_suppressed(message).await else {
+            metrics::counter!("auth9_email_suppressed_total").increment(1);
+            return Ok(EmailSendResult::failure("all recipients are suppressed"));
+        };
 
-        if !config.is_configured() {
-            return Err(AppError::BadRequest(
-                "Email provider not configured".to_string(),
-            ));
+        let provider_override = tenant_settings.is_some_and(|s| s.provider.is_some());
+        if let (Some(queue), false) = (&self.queue, provider_override) {
+            let recipients: Vec<String> = message
+                .to
+                .iter()
+                .map(|a| normalize_email(&a.email))
+                .collect();
+            match queue
+                .enqueue(&recipients, &QueuedEmailPayload::from(message.as_ref()))
+                .await
+            {
+                Ok(_) => {
+                    metrics::counter!("auth9_email_queue_enqueued_total").increment(1);
+                    return Ok(EmailSendResult::accepted());
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to queue email, sending inline");
+                }
+            }
         }
 
-        // Create the appropriate provider
-        let provider = self.create_provider(&config).await?;
+        self.send_now(&message, tenant_settings).await
+    }
 
-        // Send the email
-        provider
-            .send(message)
-            .await
-            .map_err(|e| AppError::BadRequest(format!("Email send failed: {}", e)))
+    /// Send an email inline using the configured provider
+    ///
+    /// Uses tenant settings if provided, otherwise falls back to system settings.
+    /// If the provider is unreachable, the configured failure policy decides whether
+    /// the error is returned, swallowed, or the email is queued for retry.
+    pub async fn send_now(
+        &self,
+        message: &EmailMessage,
+        tenant_settings: Option<&TenantEmailSettings>,
+    ) -> Result<EmailSendResult> {
+        match self.deliver(message, tenant_settings).await {
+            Err(e) if is_provider_unavailable(&e) => {
+                self.handle_unavailable(message, tenant_settings, e)
+            }
+            result => result.map_err(AppError::from),
+        }
+    }
+
+    /// Retry emails deferred while the provider was unreachable.
+    ///
+    /// Returns the number delivered. Emails still failing are re-queued until
+    /// they exhaust their attempts.
+    pub async fn retry_deferred(&self) -> usize {
+        let batch: Vec<DeferredEmail> = self.deferred.lock().unwrap().drain(..).collect();
+        let mut delivered = 0;
+        for mut item in batch {
+            match self
+                .deliver(&item.message, item.tenant_settings.as_ref())
+                .await
+            {
+                Ok(_) => delivered += 1,
+                Err(e) => {
+                    item.attempts += 1;
+                    if item.attempts >= MAX_DEFERRED_ATTEMPTS {
+                        tracing::error!(
+                            error = %e,
+                            attempts = item.attempts,
+                            "Dropping deferred email after repeated delivery failures"
+                        );
+                        record_degraded_operation("email", "send_dropped", FailurePolicy::Queue);
+                    } else {
+                        self.deferred.lock().unwrap().push_back(item);
+                    }
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Number of emails waiting for deferred delivery
+    pub fn deferred_len(&self) -> usize {
+        self.deferred.lock().unwrap().len()
     }
 
     /// Send an email using a specific from address override
@@ -202,6 +356,7 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         to_email: &str,
         reset_token: &str,
         user_name: Option<&str>,
+        locale: Locale,
     ) -> Result<EmailSendResult> {
         let display_name = user_name.unwrap_or("User");
         let reset_url = format!(
@@ -215,14 +370,13 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         vars.insert("user_name".to_string(), display_name.to_string());
         vars.insert("reset_link".to_string(), reset_url);
         vars.insert("expires_in_minutes".to_string(), "60".to_string());
-        vars.insert("app_name".to_string(), "Auth9".to_string());
         vars.insert(
             "year".to_string(),
             chrono::Utc::now().format("%Y").to_string(),
         );
 
         let rendered = self
-            .resolve_and_render(EmailTemplateType::PasswordReset, &vars)
+            .resolve_and_render_localized(EmailTemplateType::PasswordReset, locale, &vars)
             .await?;
 
         self.send_with_from(
@@ -240,6 +394,7 @@ impl<R: SystemSettingsRepository> EmailService<R> {
         &self,
         to_email: &str,
         user_name: Option<&str>,
+        locale: Locale,
     ) -> Result<EmailSendResult> {
         let display_name = user_name.unwrap_or("User");
         let now = chrono::Utc::now();
@@ -250,11 +405,48 @@ impl<R: SystemSettingsRepository> EmailService<R> {
             "changed_at".to_string(),
             now.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
         );
-        vars.insert("app_name".to_string(), "Auth9".to_string());
         vars.insert("year".to_string(), now.format("%Y").to_string());
 
         let rendered = self
-            .resolve_and_render(EmailTemplateType::PasswordChanged, &vars)
+            .resolve_and_render_localized(EmailTemplateType::PasswordChanged, locale, &vars)
+            .await?;
+
+        self.send_with_from(
+            EmailAddress::new(to_email),
+            &rendered.subject,
+            &rendered.html_body,
+            Some(&rendered.text_body),
+            None,
+        )
+        .await
+    }
+
+    /// Send an account deletion notice (scheduled, reminder, restored or
+    /// deleted). These are transactional and ignore notification preferences.
+    pub async fn send_account_deletion_notice(
+        &self,
+        to_email: &str,
+        user_name: Option<&str>,
+        locale: Locale,
+        message: &str,
+    ) -> Result<EmailSendResult> {
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            user_name.unwrap_or("User").to_string(),
+        );
+        vars.insert("event_type".to_string(), message.to_string());
+        vars.insert("device_info".to_string(), "-".to_string());
+        vars.insert("location".to_string(), "-".to_string());
+        vars.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now()
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        );
+
+        let rendered = self
+            .resolve_and_render_localized(EmailTemplateType::SecurityAlert, locale, &vars)
             .await?;
 
         self.send_with_from(
@@ -302,20 +494,114 @@ impl<R: SystemSettingsRepository> EmailService<R> {
             chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         );
 
-        self.send_with_from(
-            EmailAddress::new(to_email),
-            "Auth9 Test Email",
-            &html_body,
-            Some(&text_body),
-            tenant_settings,
-        )
-        .await
+        // Test emails report delivery failures directly instead of applying
+        // the failure policy, so admins see whether the configuration works.
+        let message =
+            EmailMessage::new(EmailAddress::new(to_email), "Auth9 Test Email", &html_body)
+                .with_text_body(text_body);
+        self.deliver(&message, tenant_settings)
+            .await
+            .map_err(AppError::from)
+    }
+
+    /// The system provider used for queued emails, with its type
+    /// (`smtp`, `ses`, `oracle`)
+    pub async fn queue_provider(&self) -> Result<(&'static str, Box<dyn EmailProvider>)> {
+        let config = self.get_effective_config(None).await?;
+        if !config.is_configured() {
+            return Err(AppError::BadRequest(
+                "Email provider not configured".to_string(),
+            ));
+        }
+        let provider = self.create_provider(&config).await?;
+        Ok((config.provider_type(), provider))
     }
 
     // ========================================================================
     // Private helpers
     // ========================================================================
 
+    /// Drop suppressed recipients. Returns None if none are left. Lookup
+    /// failures are logged and the message is sent unchanged.
+    async fn without_suppressed<'a>(
+        &self,
+        message: &'a EmailMessage,
+    ) -> Option<Cow<'a, EmailMessage>> {
+        let Some(queue) = &self.queue else {
+            return Some(Cow::Borrowed(message));
+        };
+        let recipients: Vec<String> = message
+            .to
+            .iter()
+            .map(|a| normalize_email(&a.email))
+            .collect();
+        let suppressed = match queue.find_suppressed(&recipients).await {
+            Ok(suppressed) if suppressed.is_empty() => return Some(Cow::Borrowed(message)),
+            Ok(suppressed) => suppressed,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to check email suppression list");
+                return Some(Cow::Borrowed(message));
+            }
+        };
+
+        let mut filtered = message.clone();
+        filtered
+            .to
+            .retain(|a| !suppressed.contains(&normalize_email(&a.email)));
+        (!filtered.to.is_empty()).then_some(Cow::Owned(filtered))
+    }
+
+    /// Send through the effective provider. Configuration problems surface as
+    /// `AppError`s; provider failures as `EmailProviderError`s.
+    async fn deliver(
+        &self,
+        message: &EmailMessage,
+        tenant_settings: Option<&TenantEmailSettings>,
+    ) -> std::result::Result<EmailSendResult, DeliveryError> {
+        // Get the effective email configuration
+        let config = self.get_effective_config(tenant_settings).await?;
+
+        if !config.is_configured() {
+            return Err(AppError::BadRequest("Email provider not configured".to_string()).into());
+        }
+
+        // Create the appropriate provider
+        let provider = self.create_provider(&config).await?;
+
+        // Send the email
+        Ok(provider.send(message).await?)
+    }
+
+    fn handle_unavailable(
+        &self,
+        message: &EmailMessage,
+        tenant_settings: Option<&TenantEmailSettings>,
+        error: DeliveryError,
+    ) -> Result<EmailSendResult> {
+        record_degraded_operation("email", "send", self.failure_policy);
+        match self.failure_policy {
+            FailurePolicy::FailClosed => Err(error.into()),
+            FailurePolicy::FailOpen => {
+                tracing::warn!(error = %error, "Email provider unavailable, dropping email (fail-open)");
+                Ok(EmailSendResult::failure(error.to_string()))
+            }
+            FailurePolicy::Queue => {
+                tracing::warn!(error = %error, "Email provider unavailable, queueing email for retry");
+                let mut deferred = self.deferred.lock().unwrap();
+                if deferred.len() >= MAX_DEFERRED_EMAILS {
+                    deferred.pop_front();
+                    record_degraded_operation("email", "send_dropped", FailurePolicy::Queue);
+                }
+                deferred.push_back(DeferredEmail {
+                    message: message.clone(),
+                    tenant_settings: tenant_settings.cloned(),
+                    attempts: 1,
+                });
+                Ok(EmailSendResult::queued())
+            }
+        }
+    }
+
     async fn get_effective_config(
         &self,
         tenant_settings: Option<&TenantEmailSettings>,
@@ -339,11 +625,41 @@ impl<R: SystemSettingsRepository> EmailService<R> {
     }
 }
 
+/// Failure while delivering through a provider
+#[derive(Debug, thiserror::Error)]
+enum DeliveryError {
+    #[error("{0}")]
+    App(#[from] AppError),
+    #[error("{0}")]
+    Provider(#[from] EmailProviderError),
+}
+
+impl From<DeliveryError> for AppError {
+    fn from(error: DeliveryError) -> Self {
+        match error {
+            DeliveryError::App(e) => e,
+            DeliveryError::Provider(e) => AppError::BadRequest(format!("Email send failed: {}", e)),
+        }
+    }
+}
+
+/// Whether a delivery failure means the provider is unreachable (vs. a rejected message)
+fn is_provider_unavailable(error: &DeliveryError) -> bool {
+    matches!(
+        error,
+        DeliveryError::Provider(
+            EmailProviderError::ConnectionError(_) | EmailProviderError::RateLimited
+        )
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::common::StringUuid;
     use crate::models::email::SmtpConfig;
     use crate::models::system_settings::SystemSettingRow;
+    use crate::repository::email_queue::MockEmailQueueRepository;
     use crate::repository::system_settings::MockSystemSettingsRepository;
     use mockall::predicate::*;
 
@@ -682,6 +998,97 @@ mod tests {
         mock
     }
 
+    struct UnreachableProvider;
+
+    #[async_trait]
+    impl EmailProvider for UnreachableProvider {
+        async fn send(
+            &self,
+            _msg: &EmailMessage,
+        ) -> std::result::Result<EmailSendResult, EmailProviderError> {
+            Err(EmailProviderError::ConnectionError(
+                "connection refused".to_string(),
+            ))
+        }
+        async fn test_connection(&self) -> std::result::Result<(), EmailProviderError> {
+            Ok(())
+        }
+        fn provider_name(&self) -> &'static str {
+            "unreachable"
+        }
+    }
+
+    fn unreachable_email_service(
+        policy: FailurePolicy,
+    ) -> EmailService<MockSystemSettingsRepository> {
+        let mock = smtp_system_settings_mock();
+        let settings_service = Arc::new(SystemSettingsService::new(Arc::new(mock), None));
+        let mut factory = MockEmailProviderFactory::new();
+        factory
+            .expect_create()
+            .returning(|_| Ok(Box::new(UnreachableProvider)));
+        EmailService::new_with_factory(settings_service, Arc::new(factory))
+            .with_failure_policy(policy)
+    }
+
+    fn test_message() -> EmailMessage {
+        EmailMessage::new(
+            EmailAddress::new("user@example.com"),
+            "Hello",
+            "<p>World</p>",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_send_provider_unavailable_fail_closed() {
+        let email_service = unreachable_email_service(FailurePolicy::FailClosed);
+        let result = email_service.send(&test_message(), None).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        assert_eq!(email_service.deferred_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_provider_unavailable_fail_open() {
+        let email_service = unreachable_email_service(FailurePolicy::FailOpen);
+        let result = email_service.send(&test_message(), None).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(email_service.deferred_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_provider_unavailable_queues_and_retries() {
+        let email_service = unreachable_email_service(FailurePolicy::Queue);
+        let result = email_service.send(&test_message(), None).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(email_service.deferred_len(), 1);
+
+        // Still unreachable: re-queued until attempts are exhausted
+        for _ in 1..MAX_DEFERRED_ATTEMPTS - 1 {
+            assert_eq!(email_service.retry_deferred().await, 0);
+            assert_eq!(email_service.deferred_len(), 1);
+        }
+        assert_eq!(email_service.retry_deferred().await, 0);
+        assert_eq!(email_service.deferred_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_rejected_message_not_queued() {
+        let mock = smtp_system_settings_mock();
+        let settings_service = Arc::new(SystemSettingsService::new(Arc::new(mock), None));
+        let mut factory = MockEmailProviderFactory::new();
+        factory.expect_create().returning(|_| {
+            Ok(Box::new(FailingProvider(EmailProviderError::SendFailed(
+                "rejected".to_string(),
+            ))))
+        });
+        let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory))
+            .with_failure_policy(FailurePolicy::Queue);
+
+        let result = email_service.send(&test_message(), None).await;
+        assert!(result.is_err());
+        assert_eq!(email_service.deferred_len(), 0);
+    }
+
     #[tokio::test]
     async fn test_send_success() {
         let mock = smtp_system_settings_mock();
@@ -768,7 +1175,12 @@ mod tests {
         let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory));
 
         let result = email_service
-            .send_password_reset("user@example.com", "reset-token-123", Some("Alice"))
+            .send_password_reset(
+                "user@example.com",
+                "reset-token-123",
+                Some("Alice"),
+                DEFAULT_LOCALE,
+            )
             .await;
         assert!(result.is_ok());
     }
@@ -781,7 +1193,7 @@ mod tests {
         let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory));
 
         let result = email_service
-            .send_password_reset("user@example.com", "token-abc", None)
+            .send_password_reset("user@example.com", "token-abc", None, Locale::Ja)
             .await;
         assert!(result.is_ok());
     }
@@ -794,7 +1206,7 @@ mod tests {
         let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory));
 
         let result = email_service
-            .send_password_changed("user@example.com", Some("Bob"))
+            .send_password_changed("user@example.com", Some("Bob"), DEFAULT_LOCALE)
             .await;
         assert!(result.is_ok());
     }
@@ -807,7 +1219,7 @@ mod tests {
         let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory));
 
         let result = email_service
-            .send_password_changed("user@example.com", None)
+            .send_password_changed("user@example.com", None, Locale::ZhCn)
             .await;
         assert!(result.is_ok());
     }
@@ -934,4 +1346,119 @@ mod tests {
         let result = email_service.test_connection(None).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_send_with_queue_enqueues_instead_of_sending() {
+        let settings_service = Arc::new(SystemSettingsService::new(
+            Arc::new(MockSystemSettingsRepository::new()),
+            None,
+        ));
+        let mut factory = MockEmailProviderFactory::new();
+        factory.expect_create().never();
+
+        let mut queue = MockEmailQueueRepository::new();
+        queue.expect_find_suppressed().returning(|_| Ok(vec![]));
+        queue
+            .expect_enqueue()
+            .withf(|recipients, payload| {
+                recipients.len() == 1
+                    && recipients[0] == "user@example.com"
+                    && payload.subject == "Hello"
+            })
+            .times(1)
+            .returning(|_, _| Ok(StringUuid::new_v4()));
+
+        let email_service = EmailService::new_with_factory(settings_service, Arc::new(factory))
+            .with_queue(Arc::new(queue));
+        let result = email_service.send(&test_message(), None).await.unwrap();
+        assert!(result.success);
+    }
+
+    #[tokio::test]
+    async fn test_send_skips_suppressed_recipients() {
+        let settings_service = Arc::new(SystemSettingsService::new(
+            Arc::new(MockSystemSettingsRepository::new()),
+            None,
+        ));
+        let mut queue = MockEmailQueueRepository::new();
+        queue
+            .expect_find_suppressed()
+            .returning(|_| Ok(vec!["user@example.com".to_string()]));
+        queue.expect_enqueue().never();
+
+        let email_service = EmailService::new_with_factory(
+            settings_service,
+            Arc::new(MockEmailProviderFactory::new()),
+        )
+        .with_queue(Arc::new(queue));
+        let result = email_service.send(&test_message(), None).await.unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_send_falls_back_inline_when_enqueue_fails() {
+        let mock = smtp_system_settings_mock();
+        let settings_service = Arc::new(SystemSettingsService::new(Arc::new(mock), None));
+        let mut queue = MockEmailQueueRepository::new();
+        queue.expect_find_suppressed().returning(|_| Ok(vec![]));
+        queue
+            .expect_enqueue()
+            .returning(|_, _| Err(AppError::Internal(anyhow::anyhow!("db down"))));
+
+        let email_service =
+            EmailService::new_with_factory(settings_service, Arc::new(create_stub_factory()))
+                .with_queue(Arc::new(queue));
+        let result = email_service.send(&test_message(), None).await.unwrap();
+        assert_eq!(result.message_id.as_deref(), Some("msg-1"));
+    }
+
+    struct FixedBranding(BrandingContext);
+
+    #[async_trait]
+    impl BrandingResolver for FixedBranding {
+        async fn branding_context(&self, tenant_id: Option<StringUuid>) -> BrandingContext {
+            BrandingContext {
+                tenant_id,
+                ..self.0.clone()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_for_tenant_applies_branding() {
+        let settings_service = Arc::new(SystemSettingsService::new(
+            Arc::new(MockSystemSettingsRepository::new()),
+            None,
+        ));
+        let email_service = EmailService::new(settings_service).with_branding(Arc::new(
+            FixedBranding(BrandingContext {
+                name: "Acme".to_string(),
+                primary_color: "#FF5733".to_string(),
+                ..Default::default()
+            }),
+        ));
+
+        let mut vars = HashMap::new();
+        vars.insert("user_name".to_string(), "Jane".to_string());
+        let rendered = email_service
+            .resolve_and_render_for_tenant(
+                EmailTemplateType::PasswordChanged,
+                DEFAULT_LOCALE,
+                Some(StringUuid::new_v4()),
+                &vars,
+            )
+            .await
+            .unwrap();
+        assert!(rendered.html_body.contains("#FF5733"));
+        assert!(rendered.text_body.contains("Acme"));
+        assert!(!rendered.html_body.contains("{{brand_primary_color}}"));
+
+        // Explicit variables win over branding
+        vars.insert("app_name".to_string(), "Override".to_string());
+        let rendered = email_service
+            .resolve_and_render(EmailTemplateType::PasswordChanged, &vars)
+            .await
+            .unwrap();
+        assert!(rendered.text_body.contains("Override"));
+    }
 }
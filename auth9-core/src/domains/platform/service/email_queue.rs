@@ -0,0 +1,524 @@
+//! Persistent email queue: background delivery, suppression list and
+//! bounce/complaint ingestion
+
+use crate::config::EmailQueueConfig;
+use crate::domains::platform::service::EmailService;
+use crate::error::Result;
+use crate::models::email_queue::{
+    normalize_email, retry_delay_secs, CreateEmailSuppressionInput, EmailFeedback, EmailQueueStats,
+    EmailSuppression, QueuedEmail, SuppressionReason,
+};
+use crate::repository::email_queue::EmailQueueRepositoryImpl;
+use crate::repository::{EmailQueueRepository, SystemSettingsRepository};
+use chrono::Utc;
+use sqlx::MySqlPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use validator::Validate;
+
+/// Claimed emails not reported back within this window are requeued
+const STALE_CLAIM_SECS: i64 = 600;
+
+/// Suppression list and queue status management
+pub struct EmailQueueService {
+    repo: Arc<dyn EmailQueueRepository>,
+}
+
+impl EmailQueueService {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(EmailQueueRepositoryImpl::new(pool)))
+    }
+
+    pub fn new(repo: Arc<dyn EmailQueueRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn stats(&self) -> Result<EmailQueueStats> {
+        self.repo.stats().await
+    }
+
+    pub async fn list_suppressions(
+        &self,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<EmailSuppression>, i64)> {
+        let offset = (page - 1) * per_page;
+        let items = self.repo.list_suppressions(offset, per_page).await?;
+        let total = self.repo.count_suppressions().await?;
+        Ok((items, total))
+    }
+
+    pub async fn suppress(&self, input: CreateEmailSuppressionInput) -> Result<EmailSuppression> {
+        input.validate()?;
+        self.repo
+            .add_suppression(
+                &normalize_email(&input.email),
+                SuppressionReason::Manual,
+                input.detail,
+            )
+            .await
+    }
+
+    pub async fn unsuppress(&self, email: &str) -> Result<()> {
+        self.repo.remove_suppression(&normalize_email(email)).await
+    }
+
+    /// Record a provider bounce/complaint. Returns the number of addresses
+    /// suppressed (transient bounces suppress nothing).
+    pub async fn ingest_feedback(&self, feedback: &EmailFeedback) -> Result<usize> {
+        let kind = match feedback.suppression_reason() {
+            Some(reason) => reason.as_str(),
+            None => "transient_bounce",
+        };
+        metrics::counter!("auth9_email_feedback_total", "kind" => kind).increment(1);
+
+        let Some(reason) = feedback.suppression_reason() else {
+            return Ok(0);
+        };
+        for email in &feedback.recipients {
+            self.repo
+                .add_suppression(email, reason, feedback.detail.clone())
+                .await?;
+        }
+        Ok(feedback.recipients.len())
+    }
+}
+
+/// Paces sends per provider type. Limits apply per replica.
+#[derive(Default)]
+pub struct ProviderRateLimiter {
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl ProviderRateLimiter {
+    /// Reserve the next send slot for `provider` and return how long to wait for it
+    fn reserve(&self, provider: &str, rate_per_sec: u32, now: Instant) -> Duration {
+        let interval = Duration::from_secs(1) / rate_per_sec.max(1);
+        let mut slots = self.next_slot.lock().unwrap();
+        let slot = slots.entry(provider.to_string()).or_insert(now);
+        let start = (*slot).max(now);
+        *slot = start + interval;
+        start - now
+    }
+
+    pub async fn acquire(&self, provider: &str, rate_per_sec: u32) {
+        let wait = self.reserve(provider, rate_per_sec, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Outcome counts of one worker run
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EmailQueueRun {
+    pub sent: usize,
+    pub retried: usize,
+    pub failed: usize,
+    pub suppressed: usize,
+}
+
+impl EmailQueueRun {
+    pub fn total(&self) -> usize {
+        self.sent + self.retried + self.failed + self.suppressed
+    }
+}
+
+/// Delivers queued emails through the system provider
+pub struct EmailQueueWorker<R: SystemSettingsRepository> {
+    email_service: Arc<EmailService<R>>,
+    repo: Arc<dyn EmailQueueRepository>,
+    config: EmailQueueConfig,
+    limiter: ProviderRateLimiter,
+}
+
+impl<R: SystemSettingsRepository + 'static> EmailQueueWorker<R> {
+    pub fn new(
+        email_service: Arc<EmailService<R>>,
+        repo: Arc<dyn EmailQueueRepository>,
+        config: EmailQueueConfig,
+    ) -> Self {
+        Self {
+            email_service,
+            repo,
+            config,
+            limiter: ProviderRateLimiter::default(),
+        }
+    }
+
+    /// Poll the queue until the task is dropped
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
+            loop {
+                match self.run_once().await {
+                    // A full batch means more emails are likely due
+                    Ok(run) if run.total() >= self.config.batch_size => continue,
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(error = %e, "Email queue poll failed"),
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        });
+    }
+
+    /// Claim a batch of due emails and attempt to deliver them
+    pub async fn run_once(&self) -> Result<EmailQueueRun> {
+        let stale_before = Utc::now() - chrono::Duration::seconds(STALE_CLAIM_SECS);
+        match self.repo.requeue_stale(stale_before).await {
+            Ok(0) => {}
+            Ok(n) => tracing::warn!(count = n, "Requeued stale email claims"),
+            Err(e) => tracing::warn!(error = %e, "Failed to requeue stale email claims"),
+        }
+
+        let claim_id = format!("email:{}", uuid::Uuid::new_v4());
+        let emails = self
+            .repo
+            .claim_due(&claim_id, self.config.batch_size)
+            .await?;
+        let mut run = EmailQueueRun::default();
+        if emails.is_empty() {
+            return Ok(run);
+        }
+
+        let (provider_type, provider) = match self.email_service.queue_provider().await {
+            Ok(provider) => provider,
+            Err(e) => {
+                for email in &emails {
+                    self.record_failure(email, None, &e.to_string(), &mut run)
+                        .await?;
+                }
+                return Ok(run);
+            }
+        };
+
+        let recipients: Vec<String> = emails
+            .iter()
+            .flat_map(|e| e.recipients.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let suppressed: HashSet<String> = self
+            .repo
+            .find_suppressed(&recipients)
+            .await?
+            .into_iter()
+            .collect();
+
+        for email in &emails {
+            let message = email
+                .payload
+                .to_message(|addr| !suppressed.contains(&normalize_email(addr)));
+            if message.to.is_empty() {
+                self.repo.mark_suppressed(email.id).await?;
+                run.suppressed += 1;
+                continue;
+            }
+
+            self.limiter
+                .acquire(provider_type, self.config.rate_per_sec(provider_type))
+                .await;
+            let result = provider.send(&message).await;
+            match result {
+                Ok(sent) if sent.success => {
+                    self.repo
+                        .mark_sent(email.id, provider_type, sent.message_id)
+                        .await?;
+                    metrics::counter!("auth9_email_queue_sent_total", "provider" => provider_type)
+                        .increment(1);
+                    metrics::histogram!("auth9_email_queue_delivery_lag_seconds").record(
+                        (Utc::now() - email.created_at).num_milliseconds().max(0) as f64 / 1000.0,
+                    );
+                    run.sent += 1;
+                }
+                Ok(sent) => {
+                    let error = sent.error.unwrap_or_else(|| "send failed".to_string());
+                    self.record_failure(email, Some(provider_type), &error, &mut run)
+                        .await?;
+                }
+                Err(e) => {
+                    self.record_failure(email, Some(provider_type), &e.to_string(), &mut run)
+                        .await?;
+                }
+            }
+        }
+        Ok(run)
+    }
+
+    /// Schedule a retry with exponential backoff, or give up after the last attempt
+    async fn record_failure(
+        &self,
+        email: &QueuedEmail,
+        provider: Option<&str>,
+        error: &str,
+        run: &mut EmailQueueRun,
+    ) -> Result<()> {
+        let attempts = email.attempts.max(0) as u32 + 1;
+        let provider = provider.map(str::to_string);
+        if attempts >= self.config.max_attempts {
+            tracing::error!(email_id = %email.id, attempts, error, "Giving up on queued email");
+            self.repo.mark_failed(email.id, provider, error).await?;
+            metrics::counter!("auth9_email_queue_failed_total").increment(1);
+            run.failed += 1;
+        } else {
+            let delay = retry_delay_secs(
+                attempts,
+                self.config.retry_base_secs,
+                self.config.retry_max_secs,
+            );
+            tracing::warn!(email_id = %email.id, attempts, delay, error, "Queued email delivery failed");
+            self.repo
+                .mark_retry(
+                    email.id,
+                    provider,
+                    error,
+                    Utc::now() + chrono::Duration::seconds(delay as i64),
+                )
+                .await?;
+            metrics::counter!("auth9_email_queue_retried_total").increment(1);
+            run.retried += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::platform::service::email::MockEmailProviderFactory;
+    use crate::domains::platform::service::SystemSettingsService;
+    use crate::email::{EmailProvider, EmailProviderError};
+    use crate::error::AppError;
+    use crate::models::common::StringUuid;
+    use crate::models::email::{EmailAddress, EmailMessage, EmailSendResult};
+    use crate::models::email_queue::{EmailFeedbackKind, QueuedEmailPayload};
+    use crate::models::system_settings::SystemSettingRow;
+    use crate::repository::email_queue::MockEmailQueueRepository;
+    use crate::repository::system_settings::MockSystemSettingsRepository;
+    use async_trait::async_trait;
+    use mockall::predicate::*;
+
+    struct ScriptedProvider {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl EmailProvider for ScriptedProvider {
+        async fn send(
+            &self,
+            _message: &EmailMessage,
+        ) -> std::result::Result<EmailSendResult, EmailProviderError> {
+            if self.fail {
+                Err(EmailProviderError::ConnectionError("refused".to_string()))
+            } else {
+                Ok(EmailSendResult::success(Some("msg-1".to_string())))
+            }
+        }
+        async fn test_connection(&self) -> std::result::Result<(), EmailProviderError> {
+            Ok(())
+        }
+        fn provider_name(&self) -> &'static str {
+            "scripted"
+        }
+    }
+
+    fn email_service(fail: bool) -> Arc<EmailService<MockSystemSettingsRepository>> {
+        let mut settings = MockSystemSettingsRepository::new();
+        settings
+            .expect_get()
+            .with(eq("email"), eq("provider"))
+            .returning(|_, _| {
+                Ok(Some(SystemSettingRow {
+                    id: 1,
+                    category: "email".to_string(),
+                    setting_key: "provider".to_string(),
+                    value: serde_json::json!({
+                        "type": "smtp",
+                        "host": "smtp.example.com",
+                        "port": 587,
+                        "from_email": "no-reply@example.com"
+                    }),
+                    encrypted: false,
+                    description: None,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                }))
+            });
+        let mut factory = MockEmailProviderFactory::new();
+        factory
+            .expect_create()
+            .returning(move |_| Ok(Box::new(ScriptedProvider { fail })));
+        let settings_service = Arc::new(SystemSettingsService::new(Arc::new(settings), None));
+        Arc::new(EmailService::new_with_factory(
+            settings_service,
+            Arc::new(factory),
+        ))
+    }
+
+    fn queued(to: &str, attempts: i32) -> QueuedEmail {
+        let message = EmailMessage::new(EmailAddress::new(to), "Hi", "<p>Hi</p>");
+        QueuedEmail {
+            id: StringUuid::new_v4(),
+            recipients: vec![to.to_string()],
+            payload: QueuedEmailPayload::from(&message),
+            status: "sending".to_string(),
+            attempts,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            provider: None,
+            provider_message_id: None,
+            created_at: Utc::now(),
+            sent_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn worker(
+        fail: bool,
+        repo: MockEmailQueueRepository,
+    ) -> EmailQueueWorker<MockSystemSettingsRepository> {
+        EmailQueueWorker::new(
+            email_service(fail),
+            Arc::new(repo),
+            EmailQueueConfig {
+                max_attempts: 3,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_once_sends_and_skips_suppressed() {
+        let mut repo = MockEmailQueueRepository::new();
+        repo.expect_requeue_stale().returning(|_| Ok(0));
+        repo.expect_claim_due().returning(|_, _| {
+            Ok(vec![
+                queued("ok@example.com", 0),
+                queued("gone@example.com", 0),
+            ])
+        });
+        repo.expect_find_suppressed()
+            .returning(|_| Ok(vec!["gone@example.com".to_string()]));
+        repo.expect_mark_sent()
+            .with(always(), eq("smtp"), eq(Some("msg-1".to_string())))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        repo.expect_mark_suppressed().times(1).returning(|_| Ok(()));
+
+        let run = worker(false, repo).run_once().await.unwrap();
+        assert_eq!(
+            run,
+            EmailQueueRun {
+                sent: 1,
+                suppressed: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_once_retries_then_gives_up() {
+        let mut repo = MockEmailQueueRepository::new();
+        repo.expect_requeue_stale().returning(|_| Ok(0));
+        repo.expect_claim_due().returning(|_, _| {
+            Ok(vec![
+                queued("first@example.com", 0),
+                queued("last@example.com", 2),
+            ])
+        });
+        repo.expect_find_suppressed().returning(|_| Ok(vec![]));
+        repo.expect_mark_retry()
+            .times(1)
+            .returning(|_, provider, _, next_attempt_at| {
+                assert_eq!(provider.as_deref(), Some("smtp"));
+                assert!(next_attempt_at > Utc::now() + chrono::Duration::seconds(20));
+                Ok(())
+            });
+        repo.expect_mark_failed()
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let run = worker(true, repo).run_once().await.unwrap();
+        assert_eq!(run.retried, 1);
+        assert_eq!(run.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_once_empty_queue() {
+        let mut repo = MockEmailQueueRepository::new();
+        repo.expect_requeue_stale().returning(|_| Ok(0));
+        repo.expect_claim_due().returning(|_, _| Ok(vec![]));
+
+        let run = worker(false, repo).run_once().await.unwrap();
+        assert_eq!(run, EmailQueueRun::default());
+    }
+
+    #[test]
+    fn test_rate_limiter_spaces_sends_per_provider() {
+        let limiter = ProviderRateLimiter::default();
+        let now = Instant::now();
+        assert_eq!(limiter.reserve("smtp", 10, now), Duration::ZERO);
+        assert_eq!(limiter.reserve("smtp", 10, now), Duration::from_millis(100));
+        assert_eq!(limiter.reserve("smtp", 10, now), Duration::from_millis(200));
+        // Other providers have their own budget
+        assert_eq!(limiter.reserve("ses", 10, now), Duration::ZERO);
+        // Idle time does not accumulate a burst
+        let later = now + Duration::from_secs(5);
+        assert_eq!(limiter.reserve("smtp", 10, later), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("smtp", 10, later),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ingest_feedback_suppresses_hard_bounces_only() {
+        let mut repo = MockEmailQueueRepository::new();
+        repo.expect_add_suppression()
+            .with(
+                eq("gone@example.com"),
+                eq(SuppressionReason::Bounce),
+                always(),
+            )
+            .times(1)
+            .returning(|email, reason, detail| {
+                Ok(EmailSuppression {
+                    email: email.to_string(),
+                    reason: reason.as_str().to_string(),
+                    detail,
+                    created_at: Utc::now(),
+                })
+            });
+        let service = EmailQueueService::new(Arc::new(repo));
+
+        let hard = EmailFeedback {
+            kind: EmailFeedbackKind::Bounce { permanent: true },
+            recipients: vec!["gone@example.com".to_string()],
+            detail: None,
+        };
+        assert_eq!(service.ingest_feedback(&hard).await.unwrap(), 1);
+
+        let soft = EmailFeedback {
+            kind: EmailFeedbackKind::Bounce { permanent: false },
+            ..hard
+        };
+        assert_eq!(service.ingest_feedback(&soft).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_suppress_rejects_invalid_email() {
+        let mut repo = MockEmailQueueRepository::new();
+        repo.expect_add_suppression().never();
+        let service = EmailQueueService::new(Arc::new(repo));
+
+        let result = service
+            .suppress(CreateEmailSuppressionInput {
+                email: "not-an-email".to_string(),
+                detail: None,
+            })
+            .await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+}
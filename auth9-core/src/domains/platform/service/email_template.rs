@@ -1,9 +1,11 @@
 //! Email template service
 //!
 //! Manages customizable email templates stored in system_settings.
+//! Per-locale customizations are stored under `<type>.<locale>` keys.
 
 use crate::email::templates::{EmailTemplate, TemplateEngine};
 use crate::error::{AppError, Result};
+use crate::i18n::{Locale, DEFAULT_LOCALE};
 use crate::models::email_template::{
     EmailTemplateContent, EmailTemplateMetadata, EmailTemplateType, EmailTemplateWithContent,
     RenderedEmailPreview,
@@ -53,13 +55,26 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
     pub async fn get_template(
         &self,
         template_type: EmailTemplateType,
+    ) -> Result<EmailTemplateWithContent> {
+        self.get_localized_template(template_type, DEFAULT_LOCALE)
+            .await
+    }
+
+    /// Get a template for a locale (returns the built-in translation if not customized)
+    pub async fn get_localized_template(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
     ) -> Result<EmailTemplateWithContent> {
         let custom = self
             .repo
-            .get(EMAIL_TEMPLATES_CATEGORY, template_type.as_str())
+            .get(
+                EMAIL_TEMPLATES_CATEGORY,
+                &setting_key(template_type, locale),
+            )
             .await?;
 
-        Ok(self.build_template_with_content(template_type, custom.as_ref()))
+        Ok(self.build_localized_template(template_type, locale, custom.as_ref()))
     }
 
     /// Update a template with custom content
@@ -67,6 +82,17 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
         &self,
         template_type: EmailTemplateType,
         content: EmailTemplateContent,
+    ) -> Result<EmailTemplateWithContent> {
+        self.update_localized_template(template_type, DEFAULT_LOCALE, content)
+            .await
+    }
+
+    /// Update a template with custom content for a locale
+    pub async fn update_localized_template(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
+        content: EmailTemplateContent,
     ) -> Result<EmailTemplateWithContent> {
         // Validate content
         self.validate_content(&content)?;
@@ -76,34 +102,53 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
             AppError::Internal(anyhow::anyhow!("Failed to serialize content: {}", e))
         })?;
 
+        let description = if locale == DEFAULT_LOCALE {
+            format!("Custom {} email template", template_type.display_name())
+        } else {
+            format!(
+                "Custom {} email template ({})",
+                template_type.display_name(),
+                locale
+            )
+        };
         let input = UpsertSystemSettingInput {
             category: EMAIL_TEMPLATES_CATEGORY.to_string(),
-            setting_key: template_type.as_str().to_string(),
+            setting_key: setting_key(template_type, locale),
             value,
             encrypted: false,
-            description: Some(format!(
-                "Custom {} email template",
-                template_type.display_name()
-            )),
+            description: Some(description),
         };
 
         let row = self.repo.upsert(&input).await?;
 
-        Ok(self.build_template_with_content(template_type, Some(&row)))
+        Ok(self.build_localized_template(template_type, locale, Some(&row)))
     }
 
     /// Reset a template to default (delete custom version)
     pub async fn reset_template(
         &self,
         template_type: EmailTemplateType,
+    ) -> Result<EmailTemplateWithContent> {
+        self.reset_localized_template(template_type, DEFAULT_LOCALE)
+            .await
+    }
+
+    /// Reset a locale's template to the built-in translation
+    pub async fn reset_localized_template(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
     ) -> Result<EmailTemplateWithContent> {
         // Delete custom template from database
         self.repo
-            .delete(EMAIL_TEMPLATES_CATEGORY, template_type.as_str())
+            .delete(
+                EMAIL_TEMPLATES_CATEGORY,
+                &setting_key(template_type, locale),
+            )
             .await?;
 
         // Return default template
-        Ok(self.build_template_with_content(template_type, None))
+        Ok(self.build_localized_template(template_type, locale, None))
     }
 
     /// Preview a template with sample data
@@ -161,6 +206,32 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
         &self,
         template_type: EmailTemplateType,
     ) -> Result<EmailTemplateContent> {
+        self.get_content_for_locale(template_type, DEFAULT_LOCALE)
+            .await
+    }
+
+    /// Get the content for a template in a locale.
+    ///
+    /// Lookup order: custom template for the locale, custom default-locale
+    /// template (keeps operator branding), built-in translation.
+    pub async fn get_content_for_locale(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
+    ) -> Result<EmailTemplateContent> {
+        if locale != DEFAULT_LOCALE {
+            if let Some(row) = self
+                .repo
+                .get(
+                    EMAIL_TEMPLATES_CATEGORY,
+                    &setting_key(template_type, locale),
+                )
+                .await?
+            {
+                return self.parse_content(&row.value);
+            }
+        }
+
         let custom = self
             .repo
             .get(EMAIL_TEMPLATES_CATEGORY, template_type.as_str())
@@ -168,7 +239,7 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
 
         match custom {
             Some(row) => self.parse_content(&row.value),
-            None => Ok(EmailTemplate::default_content(template_type)),
+            None => Ok(EmailTemplate::localized_content(template_type, locale)),
         }
     }
 
@@ -180,6 +251,15 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
         &self,
         template_type: EmailTemplateType,
         custom: Option<&SystemSettingRow>,
+    ) -> EmailTemplateWithContent {
+        self.build_localized_template(template_type, DEFAULT_LOCALE, custom)
+    }
+
+    fn build_localized_template(
+        &self,
+        template_type: EmailTemplateType,
+        locale: Locale,
+        custom: Option<&SystemSettingRow>,
     ) -> EmailTemplateWithContent {
         let metadata = EmailTemplateMetadata::from_type(template_type);
 
@@ -188,7 +268,7 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
                 // Try to parse custom content, fall back to default on error
                 let content = self
                     .parse_content(&row.value)
-                    .unwrap_or_else(|_| EmailTemplate::default_content(template_type));
+                    .unwrap_or_else(|_| EmailTemplate::localized_content(template_type, locale));
 
                 EmailTemplateWithContent {
                     metadata,
@@ -201,7 +281,7 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
                 // Return default template
                 EmailTemplateWithContent {
                     metadata,
-                    content: EmailTemplate::default_content(template_type),
+                    content: EmailTemplate::localized_content(template_type, locale),
                     is_customized: false,
                     updated_at: None,
                 }
@@ -249,6 +329,15 @@ impl<R: SystemSettingsRepository> EmailTemplateService<R> {
     }
 }
 
+/// system_settings key of a template's customization for `locale`
+fn setting_key(template_type: EmailTemplateType, locale: Locale) -> String {
+    if locale == DEFAULT_LOCALE {
+        template_type.as_str().to_string()
+    } else {
+        format!("{}.{}", template_type.as_str(), locale)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,7 +355,7 @@ mod tests {
         let service = EmailTemplateService::new(Arc::new(mock));
         let templates = service.list_templates().await.unwrap();
 
-        assert_eq!(templates.len(), 7);
+        assert_eq!(templates.len(), 9);
         assert!(templates.iter().all(|t| !t.is_customized));
     }
 
@@ -503,6 +592,80 @@ mod tests {
         assert_eq!(content.subject, "Custom");
     }
 
+    #[tokio::test]
+    async fn test_get_content_for_locale_prefers_localized_custom() {
+        let mut mock = MockSystemSettingsRepository::new();
+
+        mock.expect_get()
+            .with(eq(EMAIL_TEMPLATES_CATEGORY), eq("invitation.ja"))
+            .returning(|_, _| {
+                Ok(Some(SystemSettingRow {
+                    id: 2,
+                    category: EMAIL_TEMPLATES_CATEGORY.to_string(),
+                    setting_key: "invitation.ja".to_string(),
+                    value: serde_json::json!({
+                        "subject": "招待",
+                        "html_body": "<p>招待</p>",
+                        "text_body": "招待"
+                    }),
+                    encrypted: false,
+                    description: None,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                }))
+            });
+
+        let service = EmailTemplateService::new(Arc::new(mock));
+        let content = service
+            .get_content_for_locale(EmailTemplateType::Invitation, Locale::Ja)
+            .await
+            .unwrap();
+
+        assert_eq!(content.subject, "招待");
+    }
+
+    #[tokio::test]
+    async fn test_get_content_for_locale_falls_back_to_builtin_translation() {
+        let mut mock = MockSystemSettingsRepository::new();
+
+        mock.expect_get()
+            .with(eq(EMAIL_TEMPLATES_CATEGORY), eq("invitation.zh-CN"))
+            .returning(|_, _| Ok(None));
+        mock.expect_get()
+            .with(eq(EMAIL_TEMPLATES_CATEGORY), eq("invitation"))
+            .returning(|_, _| Ok(None));
+
+        let service = EmailTemplateService::new(Arc::new(mock));
+        let content = service
+            .get_content_for_locale(EmailTemplateType::Invitation, Locale::ZhCn)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            content.subject,
+            EmailTemplate::localized_content(EmailTemplateType::Invitation, Locale::ZhCn).subject
+        );
+        assert!(content.html_body.contains("lang=\"zh-CN\""));
+    }
+
+    #[tokio::test]
+    async fn test_reset_localized_template_deletes_locale_key() {
+        let mut mock = MockSystemSettingsRepository::new();
+
+        mock.expect_delete()
+            .with(eq(EMAIL_TEMPLATES_CATEGORY), eq("welcome.ja"))
+            .returning(|_, _| Ok(()));
+
+        let service = EmailTemplateService::new(Arc::new(mock));
+        let template = service
+            .reset_localized_template(EmailTemplateType::Welcome, Locale::Ja)
+            .await
+            .unwrap();
+
+        assert!(!template.is_customized);
+        assert!(template.content.html_body.contains("lang=\"ja\""));
+    }
+
     #[test]
     fn test_render_template_with_variables_custom_overrides() {
         let mock = MockSystemSettingsRepository::new();
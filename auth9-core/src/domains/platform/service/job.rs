@@ -0,0 +1,610 @@
+//! Async job framework
+//!
+//! `JobService` queues jobs and serves their status. `JobWorkerPool` runs
+//! them: workers on every replica poll the `jobs` table and claim work
+//! atomically, so each job runs once no matter which replica enqueued it.
+//! Executors report progress and per-item results through `JobProgress` and
+//! check it between batches for cancellation.
+
+use crate::config::JobWorkerConfig;
+use crate::domains::integration::service::WebhookEventPublisher;
+use crate::error::{AppError, Result};
+use crate::models::analytics::WebhookEvent;
+use crate::models::common::StringUuid;
+use crate::models::job::{CreateJobInput, Job, JobStatus};
+use crate::repository::JobRepository;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Per-item results kept on a job; later items are counted but not stored
+pub const MAX_JOB_RESULTS: usize = 10_000;
+
+/// Webhook event emitted when a job reaches a terminal status
+pub const JOB_COMPLETED_EVENT: &str = "job.completed";
+
+pub struct JobService<R: JobRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: JobRepository + 'static> JobService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn enqueue(&self, input: CreateJobInput) -> Result<Job> {
+        let job = self.repo.create(&input).await?;
+        tracing::info!(job_id = %job.id, kind = %job.kind, "Job queued");
+        Ok(job)
+    }
+
+    pub async fn get(&self, id: StringUuid) -> Result<Job> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Job {} not found", id)))
+    }
+
+    pub async fn list_by_tenant(&self, tenant_id: StringUuid, limit: i64) -> Result<Vec<Job>> {
+        self.repo
+            .list_by_tenant(tenant_id, limit.clamp(1, 100))
+            .await
+    }
+
+    /// Cancel a job. Queued jobs are cancelled immediately; running jobs stop
+    /// at the executor's next cancellation check.
+    pub async fn cancel(&self, id: StringUuid) -> Result<Job> {
+        let job = self.get(id).await?;
+        if job.is_terminal() {
+            return Err(AppError::Conflict(format!(
+                "Job {} has already finished",
+                id
+            )));
+        }
+        if !self.repo.cancel_queued(id).await? {
+            self.repo.request_cancel(id).await?;
+        }
+        self.get(id).await
+    }
+}
+
+/// Progress handle passed to job executors
+pub struct JobProgress {
+    repo: Arc<dyn JobRepository>,
+    job_id: StringUuid,
+    total: i32,
+    processed: i32,
+    failed: i32,
+    results: Vec<serde_json::Value>,
+    resume_after: Option<String>,
+}
+
+impl JobProgress {
+    fn new(repo: Arc<dyn JobRepository>, job: &Job) -> Self {
+        Self {
+            repo,
+            job_id: job.id,
+            total: job.total_items,
+            processed: job.processed_items,
+            failed: job.failed_items,
+            results: job.results.clone(),
+            resume_after: job.resume_after.clone(),
+        }
+    }
+
+    /// Items already processed by an earlier attempt (for resuming requeued jobs)
+    pub fn processed(&self) -> i32 {
+        self.processed
+    }
+
+    pub fn failed(&self) -> i32 {
+        self.failed
+    }
+
+    /// Resume point saved by an earlier attempt (see [`Self::set_resume_after`])
+    pub fn resume_after(&self) -> Option<&str> {
+        self.resume_after.as_deref()
+    }
+
+    /// Record where a batched executor should continue if the job is
+    /// requeued; saved with the next flush
+    pub fn set_resume_after(&mut self, resume_after: impl Into<String>) {
+        self.resume_after = Some(resume_after.into());
+    }
+
+    pub fn set_total(&mut self, total: usize) {
+        self.total = i32::try_from(total).unwrap_or(i32::MAX);
+    }
+
+    pub fn record_success(&mut self, result: Option<serde_json::Value>) {
+        self.processed += 1;
+        self.push_result(result);
+    }
+
+    /// Count `count` items processed in one batch, without per-item results
+    pub fn record_batch(&mut self, count: usize) {
+        self.processed = self
+            .processed
+            .saturating_add(i32::try_from(count).unwrap_or(i32::MAX));
+    }
+
+    pub fn record_failure(&mut self, result: serde_json::Value) {
+        self.processed += 1;
+        self.failed += 1;
+        self.push_result(Some(result));
+    }
+
+    fn push_result(&mut self, result: Option<serde_json::Value>) {
+        if let Some(result) = result {
+            if self.results.len() < MAX_JOB_RESULTS {
+                self.results.push(result);
+            }
+        }
+    }
+
+    /// Persist progress so far; also keeps the job from being considered stale
+    pub async fn flush(&self) -> Result<()> {
+        self.repo
+            .update_progress(
+                self.job_id,
+                self.total,
+                self.processed,
+                self.failed,
+                &self.results,
+                self.resume_after.clone(),
+            )
+            .await
+    }
+
+    /// Persist progress and report whether cancellation was requested
+    pub async fn checkpoint(&self) -> Result<bool> {
+        self.flush().await?;
+        self.repo.is_cancel_requested(self.job_id).await
+    }
+}
+
+/// How an executor run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobOutcome {
+    Completed,
+    Cancelled,
+}
+
+/// Runs jobs of every kind
+#[async_trait]
+pub trait JobExecutor: Send + Sync {
+    async fn execute(&self, job: &Job, progress: &mut JobProgress) -> Result<JobOutcome>;
+}
+
+/// Background workers that claim and execute queued jobs
+pub struct JobWorkerPool<R: JobRepository> {
+    service: Arc<JobService<R>>,
+    executor: Arc<dyn JobExecutor>,
+    publisher: Option<Arc<dyn WebhookEventPublisher>>,
+    config: JobWorkerConfig,
+}
+
+impl<R: JobRepository + 'static> JobWorkerPool<R> {
+    pub fn new(
+        service: Arc<JobService<R>>,
+        executor: Arc<dyn JobExecutor>,
+        config: JobWorkerConfig,
+    ) -> Self {
+        Self {
+            service,
+            executor,
+            publisher: None,
+            config,
+        }
+    }
+
+    /// Send `job.completed` webhooks to the job's tenant
+    pub fn with_webhook_publisher(mut self, publisher: Arc<dyn WebhookEventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Start the configured number of workers
+    pub fn spawn(self) {
+        let pool = Arc::new(self);
+        for worker in 0..pool.config.workers {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                let poll_interval = Duration::from_millis(pool.config.poll_interval_ms);
+                loop {
+                    if worker == 0 {
+                        pool.requeue_stale().await;
+                    }
+                    let claim_id = format!("{}:{}", worker, uuid::Uuid::new_v4());
+                    match pool.run_next(&claim_id).await {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => {}
+                        Err(e) => tracing::warn!(worker, error = %e, "Job worker poll failed"),
+                    }
+                    tokio::time::sleep(poll_interval).await;
+                }
+            });
+        }
+    }
+
+    async fn requeue_stale(&self) {
+        let stale_before =
+            Utc::now() - chrono::Duration::seconds(self.config.stale_after_secs as i64);
+        match self.service.repo.requeue_stale(stale_before).await {
+            Ok(0) => {}
+            Ok(n) => tracing::warn!(count = n, "Requeued stale jobs"),
+            Err(e) => tracing::warn!(error = %e, "Failed to requeue stale jobs"),
+        }
+    }
+
+    /// Claim the oldest queued job and run it to completion
+    pub async fn run_next(&self, claim_id: &str) -> Result<Option<Job>> {
+        let Some(job) = self.service.repo.claim_next(claim_id).await? else {
+            return Ok(None);
+        };
+        let started = Instant::now();
+        tracing::info!(job_id = %job.id, kind = %job.kind, "Job started");
+
+        let repo: Arc<dyn JobRepository> = self.service.repo.clone();
+        let mut progress = JobProgress::new(repo, &job);
+        let (status, error) = match self.executor.execute(&job, &mut progress).await {
+            Ok(JobOutcome::Completed) => (JobStatus::Succeeded, None),
+            Ok(JobOutcome::Cancelled) => (JobStatus::Cancelled, None),
+            Err(e) => (JobStatus::Failed, Some(e.to_string())),
+        };
+
+        if let Err(e) = progress.flush().await {
+            tracing::warn!(job_id = %job.id, error = %e, "Failed to save final job progress");
+        }
+        self.service.repo.finish(job.id, status, error).await?;
+
+        metrics::counter!(
+            "auth9_jobs_finished_total",
+            "kind" => job.kind.clone(),
+            "status" => status.as_str()
+        )
+        .increment(1);
+        metrics::histogram!("auth9_job_duration_seconds", "kind" => job.kind.clone())
+            .record(started.elapsed().as_secs_f64());
+        tracing::info!(job_id = %job.id, status = status.as_str(), "Job finished");
+
+        let finished = self.service.get(job.id).await?;
+        self.notify(&finished).await;
+        Ok(Some(finished))
+    }
+
+    async fn notify(&self, job: &Job) {
+        let (Some(publisher), Some(tenant_id)) = (&self.publisher, job.tenant_id) else {
+            return;
+        };
+        let event = WebhookEvent {
+            event_type: JOB_COMPLETED_EVENT.to_string(),
+            timestamp: Utc::now(),
+            data: serde_json::json!({
+                "job_id": job.id.to_string(),
+                "tenant_id": tenant_id.to_string(),
+                "kind": job.kind,
+                "status": job.status,
+                "total_items": job.total_items,
+                "processed_items": job.processed_items,
+                "failed_items": job.failed_items,
+                "error": job.error,
+            }),
+        };
+        if let Err(e) = publisher.trigger_tenant_event(tenant_id, event).await {
+            tracing::warn!(job_id = %job.id, "Failed to trigger job.completed webhook: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::integration::service::webhook::MockWebhookEventPublisher;
+    use crate::models::job::JobKind;
+    use crate::repository::job::MockJobRepository;
+    use mockall::predicate::*;
+    use std::sync::Mutex;
+
+    fn job(status: JobStatus) -> Job {
+        Job {
+            id: StringUuid::new_v4(),
+            tenant_id: Some(StringUuid::new_v4()),
+            kind: JobKind::UserExport.as_str().to_string(),
+            status: status.as_str().to_string(),
+            payload: serde_json::json!({}),
+            total_items: 0,
+            processed_items: 0,
+            failed_items: 0,
+            results: vec![],
+            error: None,
+            cancel_requested: false,
+            claim_id: None,
+            resume_after: None,
+            created_by: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Processes three items, checking for cancellation after each
+    struct CountingExecutor;
+
+    #[async_trait]
+    impl JobExecutor for CountingExecutor {
+        async fn execute(&self, _job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+            progress.set_total(3);
+            for i in 0..3 {
+                if i == 1 {
+                    progress.record_failure(serde_json::json!({ "item": i, "error": "bad" }));
+                } else {
+                    progress.record_success(Some(serde_json::json!({ "item": i })));
+                }
+                if progress.checkpoint().await? {
+                    return Ok(JobOutcome::Cancelled);
+                }
+            }
+            Ok(JobOutcome::Completed)
+        }
+    }
+
+    struct FailingExecutor;
+
+    #[async_trait]
+    impl JobExecutor for FailingExecutor {
+        async fn execute(&self, _job: &Job, _progress: &mut JobProgress) -> Result<JobOutcome> {
+            Err(AppError::Internal(anyhow::anyhow!("boom")))
+        }
+    }
+
+    /// Continues a keyset scan from the saved resume point
+    struct ResumingExecutor;
+
+    #[async_trait]
+    impl JobExecutor for ResumingExecutor {
+        async fn execute(&self, _job: &Job, progress: &mut JobProgress) -> Result<JobOutcome> {
+            assert_eq!(progress.resume_after(), Some("row-10"));
+            progress.record_batch(5);
+            progress.set_resume_after("row-15");
+            progress.checkpoint().await?;
+            Ok(JobOutcome::Completed)
+        }
+    }
+
+    fn config() -> JobWorkerConfig {
+        JobWorkerConfig::default()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_job() {
+        let queued = job(JobStatus::Queued);
+        let id = queued.id;
+        let mut cancelled = queued.clone();
+        cancelled.status = JobStatus::Cancelled.as_str().to_string();
+
+        let mut mock = MockJobRepository::new();
+        let mut seq = mockall::Sequence::new();
+        mock.expect_find_by_id()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(Some(queued.clone())));
+        mock.expect_cancel_queued()
+            .with(eq(id))
+            .times(1)
+            .returning(|_| Ok(true));
+        mock.expect_request_cancel().never();
+        mock.expect_find_by_id()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(Some(cancelled.clone())));
+
+        let service = JobService::new(Arc::new(mock));
+        let job = service.cancel(id).await.unwrap();
+        assert_eq!(job.status, "cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_job_sets_flag() {
+        let running = job(JobStatus::Running);
+        let id = running.id;
+
+        let mut mock = MockJobRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(running.clone())));
+        mock.expect_cancel_queued().returning(|_| Ok(false));
+        mock.expect_request_cancel()
+            .with(eq(id))
+            .times(1)
+            .returning(|_| Ok(true));
+
+        let service = JobService::new(Arc::new(mock));
+        assert!(service.cancel(id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finished_job_conflict() {
+        let done = job(JobStatus::Succeeded);
+        let id = done.id;
+
+        let mut mock = MockJobRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(done.clone())));
+        mock.expect_cancel_queued().never();
+
+        let service = JobService::new(Arc::new(mock));
+        let err = service.cancel(id).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_job_not_found() {
+        let mut mock = MockJobRepository::new();
+        mock.expect_find_by_id().returning(|_| Ok(None));
+
+        let service = JobService::new(Arc::new(mock));
+        let err = service.get(StringUuid::new_v4()).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_run_next_idle_when_queue_empty() {
+        let mut mock = MockJobRepository::new();
+        mock.expect_claim_next().returning(|_| Ok(None));
+
+        let pool = JobWorkerPool::new(
+            Arc::new(JobService::new(Arc::new(mock))),
+            Arc::new(CountingExecutor),
+            config(),
+        );
+        assert!(pool.run_next("w").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_next_records_progress_and_notifies_tenant() {
+        let claimed = job(JobStatus::Running);
+        let id = claimed.id;
+        let tenant_id = claimed.tenant_id.unwrap();
+        let mut finished = claimed.clone();
+        finished.status = JobStatus::Succeeded.as_str().to_string();
+        finished.total_items = 3;
+        finished.processed_items = 3;
+        finished.failed_items = 1;
+
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let mut mock = MockJobRepository::new();
+        mock.expect_claim_next()
+            .returning(move |_| Ok(Some(claimed.clone())));
+        let saved_clone = saved.clone();
+        mock.expect_update_progress()
+            .returning(move |_, total, processed, failed, results, _| {
+                saved_clone
+                    .lock()
+                    .unwrap()
+                    .push((total, processed, failed, results.len()));
+                Ok(())
+            });
+        mock.expect_is_cancel_requested().returning(|_| Ok(false));
+        mock.expect_finish()
+            .with(eq(id), eq(JobStatus::Succeeded), eq(None::<String>))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(finished.clone())));
+
+        let mut publisher = MockWebhookEventPublisher::new();
+        publisher
+            .expect_trigger_tenant_event()
+            .withf(move |t, e| {
+                *t == tenant_id
+                    && e.event_type == JOB_COMPLETED_EVENT
+                    && e.data["status"] == "succeeded"
+                    && e.data["failed_items"] == 1
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let pool = JobWorkerPool::new(
+            Arc::new(JobService::new(Arc::new(mock))),
+            Arc::new(CountingExecutor),
+            config(),
+        )
+        .with_webhook_publisher(Arc::new(publisher));
+
+        let job = pool.run_next("w").await.unwrap().unwrap();
+        assert_eq!(job.progress_percent(), 100);
+
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved.first(), Some(&(3, 1, 0, 1)));
+        assert_eq!(saved.last(), Some(&(3, 3, 1, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_run_next_stops_on_cancellation() {
+        let claimed = job(JobStatus::Running);
+        let mut cancelled = claimed.clone();
+        cancelled.status = JobStatus::Cancelled.as_str().to_string();
+
+        let mut mock = MockJobRepository::new();
+        mock.expect_claim_next()
+            .returning(move |_| Ok(Some(claimed.clone())));
+        mock.expect_update_progress()
+            .returning(|_, _, _, _, _, _| Ok(()));
+        mock.expect_is_cancel_requested()
+            .times(1)
+            .returning(|_| Ok(true));
+        mock.expect_finish()
+            .with(always(), eq(JobStatus::Cancelled), eq(None::<String>))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(cancelled.clone())));
+
+        let pool = JobWorkerPool::new(
+            Arc::new(JobService::new(Arc::new(mock))),
+            Arc::new(CountingExecutor),
+            config(),
+        );
+        let job = pool.run_next("w").await.unwrap().unwrap();
+        assert_eq!(job.status, "cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_run_next_marks_executor_error_failed() {
+        let claimed = job(JobStatus::Running);
+        let mut failed = claimed.clone();
+        failed.status = JobStatus::Failed.as_str().to_string();
+
+        let mut mock = MockJobRepository::new();
+        mock.expect_claim_next()
+            .returning(move |_| Ok(Some(claimed.clone())));
+        mock.expect_update_progress()
+            .returning(|_, _, _, _, _, _| Ok(()));
+        mock.expect_finish()
+            .withf(|_, status, error| {
+                *status == JobStatus::Failed && error.as_deref().is_some_and(|e| e.contains("boom"))
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(failed.clone())));
+
+        let pool = JobWorkerPool::new(
+            Arc::new(JobService::new(Arc::new(mock))),
+            Arc::new(FailingExecutor),
+            config(),
+        );
+        let job = pool.run_next("w").await.unwrap().unwrap();
+        assert_eq!(job.status, "failed");
+    }
+
+    #[tokio::test]
+    async fn test_run_next_saves_resume_point() {
+        let mut claimed = job(JobStatus::Running);
+        claimed.processed_items = 10;
+        claimed.resume_after = Some("row-10".to_string());
+        let finished = claimed.clone();
+
+        let mut mock = MockJobRepository::new();
+        mock.expect_claim_next()
+            .returning(move |_| Ok(Some(claimed.clone())));
+        mock.expect_update_progress()
+            .withf(|_, _, processed, _, _, resume_after| {
+                *processed == 15 && resume_after.as_deref() == Some("row-15")
+            })
+            .returning(|_, _, _, _, _, _| Ok(()));
+        mock.expect_is_cancel_requested().returning(|_| Ok(false));
+        mock.expect_finish().returning(|_, _, _| Ok(()));
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(finished.clone())));
+
+        let pool = JobWorkerPool::new(
+            Arc::new(JobService::new(Arc::new(mock))),
+            Arc::new(ResumingExecutor),
+            config(),
+        );
+        assert!(pool.run_next("w").await.unwrap().is_some());
+    }
+}
@@ -1,11 +1,21 @@
+pub mod admin_scope;
+pub mod billing;
 pub mod branding;
 pub mod email;
+pub mod email_queue;
 pub mod email_template;
 pub mod identity_sync;
+pub mod job;
+pub mod notification_preference;
 pub mod system_settings;
 
+pub use admin_scope::AdminScopeService;
+pub use billing::{BillingDispatcher, BillingService, HttpBillingSink};
 pub use branding::BrandingService;
 pub use email::EmailService;
+pub use email_queue::{EmailQueueService, EmailQueueWorker};
 pub use email_template::EmailTemplateService;
 pub use identity_sync::IdentitySyncService;
+pub use job::{JobExecutor, JobOutcome, JobProgress, JobService, JobWorkerPool};
+pub use notification_preference::{NotificationPreferenceService, SignInEmailNotifier};
 pub use system_settings::SystemSettingsService;
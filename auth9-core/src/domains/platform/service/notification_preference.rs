@@ -0,0 +1,634 @@
+//! User notification preferences, unsubscribe links, sign-in emails and
+//! activity digests
+//!
+//! Unsubscribe links carry a stateless token: the user ID and category,
+//! signed with HMAC-SHA256. They stay valid until the signing secret changes,
+//! so links in old emails keep working. The "this wasn't me" link of sign-in
+//! emails is signed the same way but expires after
+//! [`SIGN_IN_REPORT_TTL_DAYS`].
+
+use crate::config::Config;
+use crate::domains::identity::service::SignInNotifier;
+use crate::domains::platform::service::EmailService;
+use crate::error::{AppError, Result};
+use crate::i18n::{self, Locale};
+use crate::models::common::StringUuid;
+use crate::models::email::{EmailAddress, EmailMessage};
+use crate::models::email_template::EmailTemplateType;
+use crate::models::notification_preference::{
+    DigestFrequency, DigestRecipient, NotificationCategory, NotificationPreferences, SignInNotice,
+    UpdateNotificationPreferencesInput,
+};
+use crate::models::session::Session;
+use crate::repository::notification_preference::NotificationPreferenceRepositoryImpl;
+use crate::repository::{NotificationPreferenceRepository, SystemSettingsRepository};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Domain separator so unsubscribe tokens can't be confused with other
+/// values signed by the same secret
+const UNSUBSCRIBE_TOKEN_CONTEXT: &[u8] = b"auth9-notification-unsubscribe:";
+const SIGN_IN_REPORT_TOKEN_CONTEXT: &[u8] = b"auth9-sign-in-report:";
+
+/// How long the "this wasn't me" link of a sign-in email stays valid
+pub const SIGN_IN_REPORT_TTL_DAYS: i64 = 7;
+
+/// Maximum digests sent per maintenance run
+pub const DIGEST_BATCH_SIZE: i64 = 100;
+
+pub struct NotificationPreferenceService<R: NotificationPreferenceRepository> {
+    repo: Arc<R>,
+    signing_secret: String,
+    base_url: String,
+}
+
+impl NotificationPreferenceService<NotificationPreferenceRepositoryImpl> {
+    /// Service signing unsubscribe links with the JWT secret, pointing at
+    /// the public core URL (the issuer when unset)
+    pub fn from_config(pool: MySqlPool, config: &Config) -> Self {
+        Self::new(
+            Arc::new(NotificationPreferenceRepositoryImpl::new(pool)),
+            config.jwt.secret.clone(),
+            config
+                .core_public_url
+                .as_deref()
+                .unwrap_or(&config.jwt.issuer),
+        )
+    }
+}
+
+impl<R: NotificationPreferenceRepository> NotificationPreferenceService<R> {
+    /// `base_url` is the public URL of auth9-core, used for unsubscribe links
+    pub fn new(repo: Arc<R>, signing_secret: impl Into<String>, base_url: &str) -> Self {
+        Self {
+            repo,
+            signing_secret: signing_secret.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Stored preferences, or the defaults for users who never changed them
+    pub async fn get(&self, user_id: StringUuid) -> Result<NotificationPreferences> {
+        Ok(self
+            .repo
+            .find(user_id)
+            .await?
+            .unwrap_or_else(|| NotificationPreferences::defaults(user_id)))
+    }
+
+    pub async fn update(
+        &self,
+        user_id: StringUuid,
+        input: UpdateNotificationPreferencesInput,
+    ) -> Result<NotificationPreferences> {
+        let mut prefs = self.get(user_id).await?;
+        prefs.apply(input);
+        prefs.updated_at = Utc::now();
+        self.repo.upsert(&prefs).await?;
+        Ok(prefs)
+    }
+
+    /// Whether the user wants emails of this category.
+    ///
+    /// Security alerts fail open when preferences can't be loaded; opt-in
+    /// categories fail closed.
+    pub async fn allows(&self, user_id: StringUuid, category: NotificationCategory) -> bool {
+        match self.repo.find(user_id).await {
+            Ok(prefs) => prefs
+                .unwrap_or_else(|| NotificationPreferences::defaults(user_id))
+                .allows(category),
+            Err(e) => {
+                tracing::warn!(
+                    user_id = %user_id,
+                    error = %e,
+                    "Failed to load notification preferences"
+                );
+                category == NotificationCategory::SecurityAlerts
+            }
+        }
+    }
+
+    pub fn unsubscribe_token(&self, user_id: StringUuid, category: NotificationCategory) -> String {
+        let payload = format!("{}:{}", user_id, category.as_str());
+        let signature = self.sign(UNSUBSCRIBE_TOKEN_CONTEXT, payload.as_bytes());
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    pub fn unsubscribe_url(&self, user_id: StringUuid, category: NotificationCategory) -> String {
+        format!(
+            "{}/api/v1/notifications/unsubscribe?token={}",
+            self.base_url,
+            self.unsubscribe_token(user_id, category)
+        )
+    }
+
+    /// Check an unsubscribe token and return the user and category it targets
+    pub fn verify_unsubscribe_token(
+        &self,
+        token: &str,
+    ) -> Result<(StringUuid, NotificationCategory)> {
+        let invalid = || AppError::BadRequest("Invalid unsubscribe token".to_string());
+
+        let (payload_b64, signature_b64) = token.split_once('.').ok_or_else(invalid)?;
+        let payload = URL_SAFE_NO_PAD.decode(payload_b64).map_err(|_| invalid())?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature_b64)
+            .map_err(|_| invalid())?;
+
+        let mut mac = self.mac(UNSUBSCRIBE_TOKEN_CONTEXT);
+        mac.update(&payload);
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let payload = String::from_utf8(payload).map_err(|_| invalid())?;
+        let (user_id, category) = payload.split_once(':').ok_or_else(invalid)?;
+        let user_id = user_id.parse::<StringUuid>().map_err(|_| invalid())?;
+        let category = category
+            .parse::<NotificationCategory>()
+            .map_err(|_| invalid())?;
+        Ok((user_id, category))
+    }
+
+    /// Turn off the category named by an unsubscribe token
+    pub async fn unsubscribe(
+        &self,
+        token: &str,
+    ) -> Result<(NotificationCategory, NotificationPreferences)> {
+        let (user_id, category) = self.verify_unsubscribe_token(token)?;
+        let mut prefs = self.get(user_id).await?;
+        prefs.disable(category);
+        prefs.updated_at = Utc::now();
+        self.repo.upsert(&prefs).await?;
+        Ok((category, prefs))
+    }
+
+    /// Token of the "this wasn't me" link for a sign-in. Without a session
+    /// the report signs the user out everywhere.
+    pub fn sign_in_report_token(
+        &self,
+        user_id: StringUuid,
+        session_id: Option<StringUuid>,
+        expires_at: DateTime<Utc>,
+    ) -> String {
+        let payload = format!(
+            "{}:{}:{}",
+            user_id,
+            session_id.map(|id| id.to_string()).unwrap_or_default(),
+            expires_at.timestamp()
+        );
+        let signature = self.sign(SIGN_IN_REPORT_TOKEN_CONTEXT, payload.as_bytes());
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    pub fn sign_in_report_url(
+        &self,
+        user_id: StringUuid,
+        session_id: Option<StringUuid>,
+        expires_at: DateTime<Utc>,
+    ) -> String {
+        format!(
+            "{}/api/v1/notifications/sign-in-report?token={}",
+            self.base_url,
+            self.sign_in_report_token(user_id, session_id, expires_at)
+        )
+    }
+
+    /// Check a "this wasn't me" token and return the user and session it
+    /// reports
+    pub fn verify_sign_in_report_token(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(StringUuid, Option<StringUuid>)> {
+        let invalid = || AppError::BadRequest("Invalid or expired sign-in report link".to_string());
+
+        let (payload_b64, signature_b64) = token.split_once('.').ok_or_else(invalid)?;
+        let payload = URL_SAFE_NO_PAD.decode(payload_b64).map_err(|_| invalid())?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature_b64)
+            .map_err(|_| invalid())?;
+
+        let mut mac = self.mac(SIGN_IN_REPORT_TOKEN_CONTEXT);
+        mac.update(&payload);
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let payload = String::from_utf8(payload).map_err(|_| invalid())?;
+        let mut parts = payload.splitn(3, ':');
+        let (Some(user_id), Some(session_id), Some(expires_at)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let expires_at: i64 = expires_at.parse().map_err(|_| invalid())?;
+        if now.timestamp() >= expires_at {
+            return Err(invalid());
+        }
+        let user_id = user_id.parse::<StringUuid>().map_err(|_| invalid())?;
+        let session_id = match session_id {
+            "" => None,
+            id => Some(id.parse::<StringUuid>().map_err(|_| invalid())?),
+        };
+        Ok((user_id, session_id))
+    }
+
+    /// Email the user about a new sign-in, unless they or all their tenants
+    /// turned sign-in notifications off. Returns whether an email was sent.
+    pub async fn send_sign_in_notification<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        notice: &SignInNotice,
+    ) -> Result<bool> {
+        let Some(recipient) = self.repo.find_sign_in_recipient(notice.user_id).await? else {
+            return Ok(false);
+        };
+        if !recipient.wants_sign_in_email() {
+            return Ok(false);
+        }
+
+        let unknown = || "Unknown".to_string();
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            recipient
+                .display_name
+                .clone()
+                .unwrap_or_else(|| "User".to_string()),
+        );
+        vars.insert("device_info".to_string(), notice.device.clone());
+        vars.insert(
+            "location".to_string(),
+            notice.location.clone().unwrap_or_else(unknown),
+        );
+        vars.insert(
+            "ip_address".to_string(),
+            notice.ip_address.clone().unwrap_or_else(unknown),
+        );
+        vars.insert(
+            "timestamp".to_string(),
+            notice
+                .signed_in_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        );
+        vars.insert(
+            "report_link".to_string(),
+            self.sign_in_report_url(
+                notice.user_id,
+                notice.session_id,
+                Utc::now() + Duration::days(SIGN_IN_REPORT_TTL_DAYS),
+            ),
+        );
+
+        self.send_notification(
+            email_service,
+            notice.user_id,
+            &recipient.email,
+            NotificationCategory::LoginNotifications,
+            EmailTemplateType::LoginNotification,
+            i18n::resolve_locale(recipient.locale.as_deref(), None, None),
+            vars,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Send a rendered template to a user, with the unsubscribe link for
+    /// `category` in the body and in the `List-Unsubscribe` header
+    pub async fn send_notification<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        user_id: StringUuid,
+        to: &str,
+        category: NotificationCategory,
+        template_type: EmailTemplateType,
+        locale: Locale,
+        mut variables: HashMap<String, String>,
+    ) -> Result<()> {
+        let unsubscribe_url = self.unsubscribe_url(user_id, category);
+        variables.insert("unsubscribe_link".to_string(), unsubscribe_url.clone());
+        variables
+            .entry("year".to_string())
+            .or_insert_with(|| Utc::now().format("%Y").to_string());
+
+        let rendered = email_service
+            .resolve_and_render_localized(template_type, locale, &variables)
+            .await?;
+        let message =
+            EmailMessage::new(EmailAddress::new(to), rendered.subject, rendered.html_body)
+                .with_text_body(rendered.text_body)
+                .with_list_unsubscribe(unsubscribe_url);
+        email_service.send(&message, None).await?;
+        Ok(())
+    }
+
+    /// Send activity digests to users whose digest period has elapsed.
+    ///
+    /// Returns the number of digests sent. A failed send leaves the user due,
+    /// so they are retried on the next run.
+    pub async fn send_due_digests<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let recipients = self
+            .repo
+            .list_due_for_digest(now, DIGEST_BATCH_SIZE)
+            .await?;
+
+        let mut sent = 0;
+        for recipient in recipients {
+            match self.send_digest(email_service, &recipient, now).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        user_id = %recipient.user_id,
+                        error = %e,
+                        "Failed to send activity digest"
+                    );
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn send_digest<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        recipient: &DigestRecipient,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let Some(period) = recipient.digest_frequency.period() else {
+            return Ok(());
+        };
+        let since = recipient.last_digest_sent_at.unwrap_or(now - period);
+        let activity = self.repo.activity_since(recipient.user_id, since).await?;
+
+        let locale = i18n::resolve_locale(recipient.locale.as_deref(), None, None);
+        let period_name = match recipient.digest_frequency {
+            DigestFrequency::Daily => "day",
+            _ => "week",
+        };
+        let period_name = i18n::lookup(
+            locale,
+            &format!("email-notification-digest-period-{}", period_name),
+        )
+        .unwrap_or(period_name);
+        let mut vars = HashMap::new();
+        vars.insert(
+            "user_name".to_string(),
+            recipient
+                .display_name
+                .clone()
+                .unwrap_or_else(|| "User".to_string()),
+        );
+        vars.insert("period".to_string(), period_name.to_string());
+        vars.insert(
+            "successful_logins".to_string(),
+            activity.successful_logins.to_string(),
+        );
+        vars.insert(
+            "failed_logins".to_string(),
+            activity.failed_logins.to_string(),
+        );
+        vars.insert(
+            "security_alerts".to_string(),
+            activity.security_alerts.to_string(),
+        );
+
+        self.send_notification(
+            email_service,
+            recipient.user_id,
+            &recipient.email,
+            NotificationCategory::Digest,
+            EmailTemplateType::NotificationDigest,
+            locale,
+            vars,
+        )
+        .await?;
+        self.repo.mark_digest_sent(recipient.user_id, now).await
+    }
+
+    fn mac(&self, context: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.signing_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(context);
+        mac
+    }
+
+    fn sign(&self, context: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac(context);
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+/// Emails users about sessions created by their sign-ins
+pub struct SignInEmailNotifier<S: SystemSettingsRepository> {
+    notifications: NotificationPreferenceService<NotificationPreferenceRepositoryImpl>,
+    email_service: Arc<EmailService<S>>,
+}
+
+impl<S: SystemSettingsRepository> SignInEmailNotifier<S> {
+    pub fn new(
+        notifications: NotificationPreferenceService<NotificationPreferenceRepositoryImpl>,
+        email_service: Arc<EmailService<S>>,
+    ) -> Self {
+        Self {
+            notifications,
+            email_service,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: SystemSettingsRepository> SignInNotifier for SignInEmailNotifier<S> {
+    async fn session_created(&self, session: &Session) {
+        if let Err(e) = self
+            .notifications
+            .send_sign_in_notification(&self.email_service, &SignInNotice::from(session))
+            .await
+        {
+            tracing::warn!(
+                user_id = %session.user_id,
+                error = %e,
+                "Failed to send sign-in notification"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::notification_preference::MockNotificationPreferenceRepository;
+
+    fn service(
+        repo: MockNotificationPreferenceRepository,
+    ) -> NotificationPreferenceService<MockNotificationPreferenceRepository> {
+        NotificationPreferenceService::new(
+            Arc::new(repo),
+            "test-secret",
+            "https://auth9.example.com/",
+        )
+    }
+
+    #[test]
+    fn test_unsubscribe_token_round_trip() {
+        let svc = service(MockNotificationPreferenceRepository::new());
+        let user_id = StringUuid::new_v4();
+
+        let token = svc.unsubscribe_token(user_id, NotificationCategory::SecurityAlerts);
+        let (parsed_user, category) = svc.verify_unsubscribe_token(&token).unwrap();
+        assert_eq!(parsed_user, user_id);
+        assert_eq!(category, NotificationCategory::SecurityAlerts);
+
+        let url = svc.unsubscribe_url(user_id, NotificationCategory::Digest);
+        assert!(
+            url.starts_with("https://auth9.example.com/api/v1/notifications/unsubscribe?token=")
+        );
+    }
+
+    #[test]
+    fn test_unsubscribe_token_rejects_tampering() {
+        let svc = service(MockNotificationPreferenceRepository::new());
+        let user_id = StringUuid::new_v4();
+        let token = svc.unsubscribe_token(user_id, NotificationCategory::Digest);
+        let (_, signature) = token.split_once('.').unwrap();
+
+        // Same signature, different category
+        let forged_payload = URL_SAFE_NO_PAD.encode(format!("{}:security_alerts", user_id));
+        let forged = format!("{}.{}", forged_payload, signature);
+        assert!(svc.verify_unsubscribe_token(&forged).is_err());
+
+        // Signed with another secret
+        let other = NotificationPreferenceService::new(
+            Arc::new(MockNotificationPreferenceRepository::new()),
+            "other-secret",
+            "https://auth9.example.com",
+        );
+        assert!(other.verify_unsubscribe_token(&token).is_err());
+
+        assert!(svc.verify_unsubscribe_token("garbage").is_err());
+    }
+
+    #[test]
+    fn test_sign_in_report_token_round_trip_and_expiry() {
+        let svc = service(MockNotificationPreferenceRepository::new());
+        let user_id = StringUuid::new_v4();
+        let session_id = StringUuid::new_v4();
+        let now = Utc::now();
+        let expires_at = now + Duration::days(SIGN_IN_REPORT_TTL_DAYS);
+
+        let token = svc.sign_in_report_token(user_id, Some(session_id), expires_at);
+        assert_eq!(
+            svc.verify_sign_in_report_token(&token, now).unwrap(),
+            (user_id, Some(session_id))
+        );
+        assert!(svc
+            .verify_sign_in_report_token(&token, expires_at + Duration::seconds(1))
+            .is_err());
+
+        let token = svc.sign_in_report_token(user_id, None, expires_at);
+        assert_eq!(
+            svc.verify_sign_in_report_token(&token, now).unwrap(),
+            (user_id, None)
+        );
+    }
+
+    #[test]
+    fn test_token_kinds_are_not_interchangeable() {
+        let svc = service(MockNotificationPreferenceRepository::new());
+        let user_id = StringUuid::new_v4();
+
+        let unsubscribe = svc.unsubscribe_token(user_id, NotificationCategory::Digest);
+        assert!(svc
+            .verify_sign_in_report_token(&unsubscribe, Utc::now())
+            .is_err());
+
+        let report = svc.sign_in_report_token(user_id, None, Utc::now() + Duration::days(1));
+        assert!(svc.verify_unsubscribe_token(&report).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_in_notification_skipped_when_tenants_opted_out() {
+        use crate::domains::platform::service::SystemSettingsService;
+        use crate::models::notification_preference::SignInRecipient;
+        use crate::repository::system_settings::MockSystemSettingsRepository;
+
+        let mut repo = MockNotificationPreferenceRepository::new();
+        repo.expect_find_sign_in_recipient().returning(|user_id| {
+            Ok(Some(SignInRecipient {
+                user_id,
+                email: "user@example.com".to_string(),
+                display_name: None,
+                locale: None,
+                login_notifications: None,
+                tenant_count: 1,
+                tenants_opted_out: 1,
+            }))
+        });
+        let svc = service(repo);
+        let email_service = EmailService::new(Arc::new(SystemSettingsService::new(
+            Arc::new(MockSystemSettingsRepository::new()),
+            None,
+        )));
+
+        let sent = svc
+            .send_sign_in_notification(&email_service, &SignInNotice::from(&Session::default()))
+            .await
+            .unwrap();
+        assert!(!sent);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_disables_category() {
+        let user_id = StringUuid::new_v4();
+        let mut repo = MockNotificationPreferenceRepository::new();
+        repo.expect_find().returning(|_| Ok(None));
+        repo.expect_upsert()
+            .withf(|prefs| !prefs.security_alerts)
+            .times(1)
+            .returning(|_| Ok(()));
+        let svc = service(repo);
+
+        let token = svc.unsubscribe_token(user_id, NotificationCategory::SecurityAlerts);
+        let (category, prefs) = svc.unsubscribe(&token).await.unwrap();
+        assert_eq!(category, NotificationCategory::SecurityAlerts);
+        assert_eq!(prefs.user_id, user_id);
+        assert!(!prefs.security_alerts);
+    }
+
+    #[tokio::test]
+    async fn test_allows_fails_open_for_alerts_only() {
+        let mut repo = MockNotificationPreferenceRepository::new();
+        repo.expect_find()
+            .returning(|_| Err(AppError::Internal(anyhow::anyhow!("db down"))));
+        let svc = service(repo);
+        let user_id = StringUuid::new_v4();
+
+        assert!(
+            svc.allows(user_id, NotificationCategory::SecurityAlerts)
+                .await
+        );
+        assert!(
+            !svc.allows(user_id, NotificationCategory::LoginNotifications)
+                .await
+        );
+        assert!(!svc.allows(user_id, NotificationCategory::Digest).await);
+    }
+}
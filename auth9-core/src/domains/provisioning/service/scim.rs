@@ -2,7 +2,7 @@
 
 use crate::error::{AppError, Result};
 use crate::identity_engine::{IdentityEngine, IdentityUserCreateInput};
-use crate::models::common::StringUuid;
+use crate::models::common::{StringUuid, TenantId, UserId};
 use crate::models::rbac::AssignRolesInput;
 use crate::models::scim::*;
 use crate::models::user::{AddUserToTenantInput, CreateUserInput, UpdateUserInput, User};
@@ -252,7 +252,7 @@ where
             display_name: fields.display_name.clone(),
             avatar_url: fields.avatar_url.clone(),
         };
-        self.user_repo.update(user_id, &update_input).await?;
+        self.user_repo.update(user_id, &update_input, None).await?;
 
         // Update SCIM fields
         if fields.external_id.is_some() {
@@ -330,14 +330,14 @@ where
                                     display_name: None,
                                     avatar_url: None,
                                 };
-                                self.user_repo.update(user_id, &input).await?;
+                                self.user_repo.update(user_id, &input, None).await?;
                             }
                             "photos" => {
                                 let input = UpdateUserInput {
                                     display_name: None,
                                     avatar_url: None,
                                 };
-                                self.user_repo.update(user_id, &input).await?;
+                                self.user_repo.update(user_id, &input, None).await?;
                             }
                             _ => {}
                         }
@@ -818,7 +818,7 @@ where
             .map_err(|_| AppError::BadRequest(format!("Invalid member ID: {}", member_user_id)))?;
 
         let tenant_user_id = rbac
-            .find_tenant_user_id(user_id, tenant_id)
+            .find_tenant_user_id(UserId::from(user_id), TenantId::from(tenant_id))
             .await?
             .ok_or_else(|| {
                 AppError::NotFound(format!("User {} not found in tenant", member_user_id))
@@ -850,7 +850,7 @@ where
         // Query user_tenant_roles directly by role_id (works for both
         // real RBAC roles and SCIM placeholder role_ids)
         let user_ids = match rbac
-            .find_user_ids_by_role_in_tenant(tenant_id, role_id)
+            .find_user_ids_by_role_in_tenant(TenantId::from(tenant_id), role_id)
             .await
         {
             Ok(ids) => ids,
@@ -1050,7 +1050,7 @@ where
                 display_name: fields.display_name.clone(),
                 avatar_url: fields.avatar_url.clone(),
             };
-            self.user_repo.update(user_id, &input).await?;
+            self.user_repo.update(user_id, &input, None).await?;
         }
 
         // Update SCIM external ID
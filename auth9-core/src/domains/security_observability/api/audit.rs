@@ -1,41 +1,65 @@
 //! Audit log API handlers
 
+use crate::domains::platform::api::job::job_service;
+use crate::domains::security_observability::service::AuditIntegrityService;
 use crate::error::{AppError, Result};
-use crate::http_support::PaginatedResponse;
+use crate::http_support::{write_audit_log_generic, PaginatedResponse, SuccessResponse};
 use crate::middleware::auth::AuthUser;
+use crate::models::audit_integrity::{AuditIntegrityReport, AuditVerifyParams};
+use crate::models::audit_search::{
+    histogram_range, render_csv, AuditHistogram, AuditHistogramParams,
+};
+use crate::models::audit_state::{
+    reconstruct_state, AuditStateResource, AuditStateSnapshot, MAX_STATE_HISTORY,
+};
+use crate::models::common::StringUuid;
+use crate::models::job::{CreateJobInput, JobKind, JobResponse, JobStatus};
+use crate::models::list_query::ListGrammarUnsupported;
 use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
 use crate::repository::audit::AuditLogQuery;
 use crate::repository::AuditRepository;
-use crate::state::HasServices;
+use crate::state::{HasDbPool, HasServices};
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
 
-/// List audit logs with actor information (email, display_name)
+async fn require_audit_read<S: HasServices>(state: &S, auth: &AuthUser) -> Result<()> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::AuditRead,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await
+}
+
+/// List audit logs with actor information (email, display_name).
+/// `q` runs a full-text search over action, resource and payload JSON.
 #[utoipa::path(
     get,
     path = "/api/v1/audit-logs",
     tag = "Security & Observability",
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 400, description = "filter, sort or fields passed")
     )
 )]
 pub async fn list<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
+    _: ListGrammarUnsupported,
     Query(query): Query<AuditLogQuery>,
 ) -> Result<impl IntoResponse> {
-    enforce_with_state(
-        &state,
-        &auth,
-        &PolicyInput {
-            action: PolicyAction::AuditRead,
-            scope: ResourceScope::Global,
-        },
-    )
-    .await?;
+    require_audit_read(&state, &auth).await?;
 
     // Validate per_page and page values
     if let Some(limit) = query.limit {
@@ -92,6 +116,232 @@ pub async fn list<S: HasServices>(
     )))
 }
 
+/// Count matching audit entries per time bucket, for histogram charts.
+/// Accepts the same filters as the list endpoint, including `q`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/histogram",
+    tag = "Security & Observability",
+    params(AuditHistogramParams),
+    responses(
+        (status = 200, description = "Entry counts per bucket", body = AuditHistogram),
+        (status = 400, description = "Invalid range or too many buckets")
+    )
+)]
+pub async fn histogram<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(query): Query<AuditLogQuery>,
+    Query(params): Query<AuditHistogramParams>,
+) -> Result<Json<SuccessResponse<AuditHistogram>>> {
+    require_audit_read(&state, &auth).await?;
+
+    let interval = params.interval.unwrap_or_default();
+    let query = histogram_range(&query, interval, Utc::now()).map_err(AppError::BadRequest)?;
+    let buckets = state.audit_repo().histogram(&query, interval).await?;
+
+    Ok(Json(SuccessResponse::new(AuditHistogram {
+        interval,
+        from_date: query.from_date.unwrap_or_default(),
+        to_date: query.to_date.unwrap_or_default(),
+        buckets,
+    })))
+}
+
+/// Export audit entries matching the list filters to CSV.
+/// Runs as an async job; poll `GET /api/v1/jobs/{id}` and download the file
+/// once it has succeeded. Exports are capped at 10,000 rows.
+#[utoipa::path(
+    post,
+    path = "/api/v1/audit-logs/exports",
+    tag = "Security & Observability",
+    responses(
+        (status = 202, description = "Export job queued", body = JobResponse)
+    )
+)]
+pub async fn export<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    // Pin the upper bound so rows written during the export do not shift pages
+    let filter = AuditLogQuery {
+        to_date: Some(query.to_date.unwrap_or_else(Utc::now)),
+        offset: None,
+        limit: None,
+        page: None,
+        ..query
+    };
+    let payload =
+        serde_json::to_value(&filter).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+    let job = job_service(&state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::AuditExport,
+            tenant_id: None,
+            payload: payload.clone(),
+            created_by: Some(StringUuid::from(auth.user_id)),
+        })
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "audit.export",
+        "job",
+        Some(*job.id),
+        None,
+        Some(payload),
+    )
+    .await;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+/// Download the CSV produced by a finished audit export job
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/exports/{id}/download",
+    tag = "Security & Observability",
+    params(("id" = String, Path, description = "Export job ID (UUID)")),
+    responses(
+        (status = 200, description = "CSV file", content_type = "text/csv"),
+        (status = 404, description = "Export not found"),
+        (status = 409, description = "Export has not succeeded")
+    )
+)]
+pub async fn download_export<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    let job = job_service(&state).get(StringUuid::from(id)).await?;
+    if job.job_kind() != Some(JobKind::AuditExport) {
+        return Err(AppError::NotFound(format!("Audit export {} not found", id)));
+    }
+    if job.job_status() != Some(JobStatus::Succeeded) {
+        return Err(AppError::Conflict(format!(
+            "Audit export {} is {}",
+            id, job.status
+        )));
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"audit-logs-{}.csv\"", id),
+            ),
+        ],
+        render_csv(&job.results),
+    ))
+}
+
+/// Verify the audit log hash chain and signed checkpoints, reporting
+/// edited, deleted or reordered entries and truncation of the newest ones
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/verify",
+    tag = "Security & Observability",
+    params(AuditVerifyParams),
+    responses(
+        (status = 200, description = "Verification report", body = AuditIntegrityReport),
+        (status = 400, description = "Invalid range")
+    )
+)]
+pub async fn verify<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(params): Query<AuditVerifyParams>,
+) -> Result<Json<SuccessResponse<AuditIntegrityReport>>> {
+    require_audit_read(&state, &auth).await?;
+    if let (Some(from), Some(to)) = (params.from_seq, params.to_seq) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "from_seq must not be greater than to_seq".to_string(),
+            ));
+        }
+    }
+
+    let report =
+        AuditIntegrityService::from_pool(state.db_pool().clone(), &state.config().audit_integrity)
+            .verify(params.from_seq, params.to_seq)
+            .await?;
+    Ok(Json(SuccessResponse::new(report)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditStateQuery {
+    /// Resource as `<type>:<id>`, where type is user, tenant or role
+    pub resource: String,
+    /// Point in time (RFC 3339); defaults to now
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Reconstruct a resource's state at a point in time from the audit log
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/state",
+    tag = "Security & Observability",
+    params(AuditStateQuery),
+    responses(
+        (status = 200, description = "Reconstructed state and coverage gaps", body = AuditStateSnapshot),
+        (status = 400, description = "Invalid resource or timestamp")
+    )
+)]
+pub async fn get_state<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(query): Query<AuditStateQuery>,
+) -> Result<Json<SuccessResponse<AuditStateSnapshot>>> {
+    require_audit_read(&state, &auth).await?;
+
+    let (resource_type, resource_id) = parse_state_resource(&query.resource)?;
+    let at = query.at.unwrap_or_else(Utc::now);
+
+    // Load one extra entry so a cut-off history can be told apart
+    let mut history = state
+        .audit_repo()
+        .find_resource_history(
+            resource_type.as_str(),
+            &resource_id.to_string(),
+            at,
+            MAX_STATE_HISTORY + 1,
+        )
+        .await?;
+    let truncated = history.len() as i64 > MAX_STATE_HISTORY;
+    if truncated {
+        history.remove(0);
+    }
+
+    Ok(Json(SuccessResponse::new(reconstruct_state(
+        resource_type,
+        &resource_id.to_string(),
+        at,
+        &history,
+        truncated,
+    ))))
+}
+
+fn parse_state_resource(resource: &str) -> Result<(AuditStateResource, Uuid)> {
+    let (resource_type, resource_id) = resource.split_once(':').ok_or_else(|| {
+        AppError::BadRequest("resource must be formatted as <type>:<id>".to_string())
+    })?;
+    let resource_type = resource_type
+        .parse::<AuditStateResource>()
+        .map_err(AppError::BadRequest)?;
+    let resource_id = Uuid::parse_str(resource_id)
+        .map_err(|_| AppError::BadRequest("resource id must be a UUID".to_string()))?;
+    Ok((resource_type, resource_id))
+}
+
 /// Calculate pagination page from offset and limit
 fn calculate_page(offset: Option<i64>, limit: Option<i64>) -> i64 {
     let offset = offset.unwrap_or(0);
@@ -104,6 +354,18 @@ mod tests {
     use super::*;
     use crate::repository::audit::AuditLogQuery;
 
+    #[test]
+    fn test_parse_state_resource() {
+        let id = Uuid::new_v4();
+        let (resource_type, resource_id) = parse_state_resource(&format!("tenant:{}", id)).unwrap();
+        assert_eq!(resource_type, AuditStateResource::Tenant);
+        assert_eq!(resource_id, id);
+
+        assert!(parse_state_resource("tenant").is_err());
+        assert!(parse_state_resource("tenant:not-a-uuid").is_err());
+        assert!(parse_state_resource(&format!("webhook:{}", id)).is_err());
+    }
+
     #[test]
     fn test_calculate_page_defaults() {
         assert_eq!(calculate_page(None, None), 1);
@@ -0,0 +1,260 @@
+//! Audit share link API handlers
+//!
+//! Administrators create read-only, expiring links to a filtered audit log
+//! view; recipients open them through the public endpoints below, which are
+//! authorized by the link's token alone.
+
+use crate::domains::security_observability::service::AuditShareService;
+use crate::error::Result;
+use crate::http_support::{
+    extract_ip, write_audit_log_generic, PaginatedResponse, PaginationQuery, SuccessResponse,
+};
+use crate::middleware::auth::AuthUser;
+use crate::models::audit_share::{
+    AuditShareLinkResponse, AuditSharePreview, CreateAuditShareLinkInput, CreatedAuditShareLink,
+};
+use crate::models::common::StringUuid;
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+fn share_service<S: HasServices + HasDbPool>(state: &S) -> AuditShareService {
+    AuditShareService::from_config(state.db_pool().clone(), state.config())
+}
+
+async fn require_audit_read<S: HasServices>(state: &S, auth: &AuthUser) -> Result<()> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::AuditRead,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await
+}
+
+/// Create a read-only, expiring share link to a filtered audit log view
+#[utoipa::path(
+    post,
+    path = "/api/v1/audit-logs/shares",
+    tag = "Security & Observability",
+    request_body = CreateAuditShareLinkInput,
+    responses(
+        (status = 201, description = "Link created; the token is only returned once", body = CreatedAuditShareLink),
+        (status = 422, description = "Invalid filter or expiry")
+    )
+)]
+pub async fn create<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<CreateAuditShareLinkInput>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    let service = share_service(&state);
+    let (link, token) = service
+        .create(StringUuid::from(auth.user_id), input)
+        .await?;
+    let response = AuditShareLinkResponse::from(link);
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "audit_share.created",
+        "audit_share",
+        Some(*response.id),
+        None,
+        serde_json::to_value(&response).ok(),
+    )
+    .await;
+
+    let url = service.share_url(&token);
+    Ok((
+        StatusCode::CREATED,
+        Json(SuccessResponse::new(CreatedAuditShareLink {
+            link: response,
+            token,
+            url,
+        })),
+    ))
+}
+
+/// List audit share links
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/shares",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "Share links, newest first")
+    )
+)]
+pub async fn list<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    let (links, total) = share_service(&state)
+        .list(pagination.page, pagination.per_page)
+        .await?;
+    let items: Vec<AuditShareLinkResponse> = links.into_iter().map(Into::into).collect();
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+/// Revoke an audit share link
+#[utoipa::path(
+    post,
+    path = "/api/v1/audit-logs/shares/{id}/revoke",
+    tag = "Security & Observability",
+    params(("id" = String, Path, description = "Share link ID (UUID)")),
+    responses(
+        (status = 200, description = "Link revoked", body = AuditShareLinkResponse),
+        (status = 404, description = "Share link not found")
+    )
+)]
+pub async fn revoke<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    let link = share_service(&state).revoke(StringUuid::from(id)).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "audit_share.revoked",
+        "audit_share",
+        Some(id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(AuditShareLinkResponse::from(
+        link,
+    ))))
+}
+
+/// Views of the audit log through a share link
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs/shares/{id}/accesses",
+    tag = "Security & Observability",
+    params(("id" = String, Path, description = "Share link ID (UUID)")),
+    responses(
+        (status = 200, description = "Accesses through the link, newest first"),
+        (status = 404, description = "Share link not found")
+    )
+)]
+pub async fn list_accesses<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    require_audit_read(&state, &auth).await?;
+
+    let service = share_service(&state);
+    let link = service.get(StringUuid::from(id)).await?;
+    let (accesses, total) = service
+        .list_accesses(link.id, pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        accesses,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareTokenQuery {
+    pub token: String,
+}
+
+/// Describe the audit log view behind a share link (public endpoint)
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/audit-shares",
+    tag = "Security & Observability",
+    params(("token" = String, Query, description = "Token from the share link")),
+    responses(
+        (status = 200, description = "Shared view", body = AuditSharePreview),
+        (status = 403, description = "Link expired or revoked"),
+        (status = 404, description = "Invalid link")
+    )
+)]
+pub async fn preview<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(params): Query<ShareTokenQuery>,
+) -> Result<impl IntoResponse> {
+    let link = share_service(&state)
+        .get_active_by_token(&params.token)
+        .await?;
+    Ok(Json(SuccessResponse::new(AuditSharePreview {
+        name: link.name,
+        filter: link.filter,
+        expires_at: link.expires_at,
+    })))
+}
+
+/// Read the audit entries behind a share link (public endpoint)
+///
+/// Every request is recorded against the link.
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/audit-shares/logs",
+    tag = "Security & Observability",
+    params(("token" = String, Query, description = "Token from the share link")),
+    responses(
+        (status = 200, description = "Audit entries within the shared view, newest first"),
+        (status = 403, description = "Link expired or revoked"),
+        (status = 404, description = "Invalid link")
+    )
+)]
+pub async fn logs<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Query(params): Query<ShareTokenQuery>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    let service = share_service(&state);
+    let link = service.get_active_by_token(&params.token).await?;
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    service
+        .record_access(&link, extract_ip(&headers), user_agent)
+        .await?;
+
+    let offset = (pagination.page - 1) * pagination.per_page;
+    let query = link.filter.to_query(offset, pagination.per_page);
+    let entries = state.audit_repo().find_with_actor(&query).await?;
+    let total = state.audit_repo().count(&query).await?;
+    Ok(Json(PaginatedResponse::new(
+        entries,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
@@ -11,6 +11,8 @@ pub struct HealthResponse {
 }
 
 /// Health check endpoint
+///
+/// Also served as `/health/live` for liveness probes.
 #[utoipa::path(
     get,
     path = "/health",
@@ -26,6 +28,8 @@ pub async fn health() -> impl IntoResponse {
 }
 
 /// Readiness check endpoint
+///
+/// Also served as `/health/ready` for readiness probes.
 #[utoipa::path(
     get,
     path = "/ready",
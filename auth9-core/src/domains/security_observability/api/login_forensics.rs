@@ -0,0 +1,69 @@
+//! Failed sign-in forensics API handlers (platform admin)
+//!
+//! Every search is written to the audit log together with the reason given
+//! by the responder.
+
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::login_forensics::{LoginFailureQuery, LoginFailureRecord};
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
+use validator::Validate;
+
+/// Search failed sign-in captures by IP, user or sign-in identifier
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/login-failures",
+    tag = "Security & Observability",
+    params(LoginFailureQuery),
+    responses(
+        (status = 200, description = "Matching captures, newest first", body = Vec<LoginFailureRecord>),
+        (status = 400, description = "No ip, user_id or identifier given"),
+        (status = 403, description = "Platform admin required")
+    )
+)]
+pub async fn search_login_failures<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Query(query): Query<LoginFailureQuery>,
+) -> Result<Json<SuccessResponse<Vec<LoginFailureRecord>>>> {
+    enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await?;
+    query.validate()?;
+
+    let records = state.login_forensics_service().search(&query).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "security.login_failures.search",
+        "login_failure_forensics",
+        None,
+        None,
+        Some(serde_json::json!({
+            "ip": query.ip,
+            "user_id": query.user_id,
+            "identifier_given": query.identifier.is_some(),
+            "since": query.since,
+            "reason": query.reason,
+            "results": records.len(),
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(records)))
+}
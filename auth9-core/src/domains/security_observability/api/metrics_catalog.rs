@@ -0,0 +1,35 @@
+//! Metrics catalog API handler (platform admin)
+
+use crate::error::Result;
+use crate::http_support::SuccessResponse;
+use crate::middleware::auth::AuthUser;
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use crate::telemetry::metrics::MetricsCatalog;
+use axum::{extract::State, Json};
+
+/// Machine-readable catalog of the metrics this service emits, with the RED
+/// signals used to generate dashboards
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/metrics/catalog",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "Metrics catalog", body = MetricsCatalog)
+    )
+)]
+pub async fn metrics_catalog<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<Json<SuccessResponse<MetricsCatalog>>> {
+    enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await?;
+    Ok(Json(SuccessResponse::new(MetricsCatalog::current())))
+}
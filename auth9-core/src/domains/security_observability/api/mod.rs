@@ -2,7 +2,14 @@
 
 pub mod analytics;
 pub mod audit;
+pub mod audit_share;
 pub mod captcha;
 pub mod health;
+pub mod login_forensics;
+pub mod metrics_catalog;
 pub mod risk;
 pub mod security_alert;
+pub mod security_digest;
+pub mod sql_stats;
+pub mod synthetic;
+pub mod telemetry;
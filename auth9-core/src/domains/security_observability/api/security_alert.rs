@@ -69,7 +69,8 @@ pub struct AlertsQuery {
     pub per_page: Option<i64>,
     /// Filter by resolved status: true=resolved only, false=unresolved only
     pub resolved: Option<bool>,
-    /// If true, only return unresolved alerts (legacy, prefer `resolved`)
+    /// If true, only return unresolved alerts (deprecated, see
+    /// `http_support::deprecation::API_DEPRECATIONS`; use `resolved`)
     pub unresolved_only: Option<bool>,
     /// Filter by severity: low, medium, high, critical
     pub severity: Option<AlertSeverity>,
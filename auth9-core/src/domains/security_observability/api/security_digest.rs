@@ -0,0 +1,199 @@
+//! One-click actions of security alert digests (public endpoints authorized
+//! by the signed token of the link)
+
+use crate::domains::identity::service::required_actions::ACTION_UPDATE_PASSWORD;
+use crate::domains::security_observability::service::SecurityDigestService;
+use crate::error::AppError;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::models::common::StringUuid;
+use crate::models::password::ForgotPasswordInput;
+use crate::models::security_digest::{DigestAction, DigestActionClaims};
+use crate::models::user::User;
+use crate::state::{
+    HasCache, HasDbPool, HasPasswordManagement, HasRequiredActions, HasServices,
+    HasSessionManagement,
+};
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize)]
+pub struct DigestActionQuery {
+    pub token: String,
+}
+
+/// Action named by a digest link
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestActionResponse {
+    pub tenant_id: StringUuid,
+    pub user_id: StringUuid,
+    pub email: String,
+    pub action: DigestAction,
+    /// When the link stops working
+    pub expires_at: DateTime<Utc>,
+    /// Whether the action was carried out by this request
+    pub performed: bool,
+    /// Sessions signed out (`revoke_sessions` only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_sessions: Option<u64>,
+}
+
+impl DigestActionResponse {
+    fn new(claims: DigestActionClaims, user: &User) -> Self {
+        Self {
+            tenant_id: claims.tenant_id,
+            user_id: claims.user_id,
+            email: user.email.clone(),
+            action: claims.action,
+            expires_at: claims.expires_at,
+            performed: false,
+            revoked_sessions: None,
+        }
+    }
+}
+
+/// Verify a digest action token and check it may still be used
+async fn authorized_action<S: HasServices + HasDbPool>(
+    state: &S,
+    token: &str,
+) -> Result<(DigestActionClaims, User), AppError> {
+    let service = SecurityDigestService::from_config(state.db_pool().clone(), state.config());
+    let claims = service.verify_action_token(token, Utc::now())?;
+    let tenant = state.tenant_service().get(claims.tenant_id).await?;
+    service.authorize_action(&claims, &tenant.settings).await?;
+    let user = state.user_service().get(claims.user_id).await?;
+    Ok((claims, user))
+}
+
+/// Look up a digest action link (public endpoint)
+///
+/// Does not change anything, so link scanners opening the URL are harmless.
+#[utoipa::path(
+    get,
+    path = "/api/v1/security-digest/actions",
+    tag = "Security & Observability",
+    params(("token" = String, Query, description = "Action token from the security alert digest")),
+    responses(
+        (status = 200, description = "The action and the user it applies to", body = DigestActionResponse),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 403, description = "The recipient no longer receives the digest or the user left the tenant")
+    )
+)]
+pub async fn get_action<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(query): Query<DigestActionQuery>,
+) -> Result<Json<SuccessResponse<DigestActionResponse>>, AppError> {
+    let (claims, user) = authorized_action(&state, &query.token).await?;
+    Ok(Json(SuccessResponse::new(DigestActionResponse::new(
+        claims, &user,
+    ))))
+}
+
+/// Carry out a digest action (public endpoint)
+///
+/// `force_reset` requires a new password at the user's next sign-in, signs
+/// them out and emails them a reset link; `revoke_sessions` signs them out
+/// of every session.
+#[utoipa::path(
+    post,
+    path = "/api/v1/security-digest/actions",
+    tag = "Security & Observability",
+    params(("token" = String, Query, description = "Action token from the security alert digest")),
+    responses(
+        (status = 200, description = "Action carried out", body = DigestActionResponse),
+        (status = 400, description = "Invalid or expired token"),
+        (status = 403, description = "The recipient no longer receives the digest or the user left the tenant")
+    )
+)]
+pub async fn perform_action<
+    S: HasServices
+        + HasDbPool
+        + HasSessionManagement
+        + HasRequiredActions
+        + HasPasswordManagement
+        + HasCache,
+>(
+    State(state): State<S>,
+    headers: HeaderMap,
+    Query(query): Query<DigestActionQuery>,
+) -> Result<Json<SuccessResponse<DigestActionResponse>>, AppError> {
+    let (claims, user) = authorized_action(&state, &query.token).await?;
+
+    let mut already_flagged = false;
+    if claims.action == DigestAction::ForceReset {
+        let actions = state.required_actions_service();
+        already_flagged = actions
+            .get_pending_actions(&user.identity_subject)
+            .await?
+            .iter()
+            .any(|a| a.action_type == ACTION_UPDATE_PASSWORD);
+        if !already_flagged {
+            actions
+                .create_action(
+                    &user.identity_subject,
+                    ACTION_UPDATE_PASSWORD,
+                    Some(serde_json::json!({ "reason": "security_digest" })),
+                )
+                .await?;
+        }
+    }
+
+    // Both actions sign the user out; their tokens must not keep working
+    // until they expire
+    let active_sessions = state
+        .session_service()
+        .get_user_sessions_admin(user.id)
+        .await
+        .unwrap_or_default();
+    let revoked = state.session_service().force_logout_user(user.id).await?;
+    let blacklist_ttl = state.config().jwt.access_token_ttl_secs.unsigned_abs();
+    let cache = state.cache();
+    for session in &active_sessions {
+        let sid = &session.id;
+        if let Err(e) = cache.add_to_token_blacklist(sid, blacklist_ttl).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to blacklist session token of digest action");
+        }
+        if let Err(e) = cache.remove_all_refresh_sessions_for_session(sid).await {
+            tracing::warn!(session_id = %sid, error = %e, "Failed to clean up refresh sessions of digest action");
+        }
+    }
+
+    if claims.action == DigestAction::ForceReset {
+        // Issues a fresh reset token and emails the link; delivery failures
+        // are logged by the password service
+        state
+            .password_service()
+            .request_reset(ForgotPasswordInput {
+                email: user.email.clone(),
+            })
+            .await?;
+    }
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        &format!("security_digest.{}", claims.action.as_str()),
+        "user",
+        Some(*user.id),
+        None,
+        Some(serde_json::json!({
+            "tenant_id": claims.tenant_id,
+            "recipient": claims.recipient,
+            "revoked_sessions": revoked,
+            "already_flagged": already_flagged,
+        })),
+    )
+    .await;
+
+    let mut response = DigestActionResponse::new(claims, &user);
+    response.performed = true;
+    if response.action == DigestAction::RevokeSessions {
+        response.revoked_sessions = Some(revoked);
+    }
+    Ok(Json(SuccessResponse::new(response)))
+}
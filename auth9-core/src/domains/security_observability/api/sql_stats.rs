@@ -0,0 +1,70 @@
+//! SQL query statistics API handlers (platform admin)
+
+use crate::error::Result;
+use crate::http_support::{MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use crate::telemetry::sql::{global_stats, SlowQueryReport};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SlowQueryParams {
+    /// Number of top fingerprints to return (default 20, max 100)
+    pub limit: Option<usize>,
+}
+
+/// Slow query log and the most expensive statement fingerprints on this replica
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/sql/slow-queries",
+    tag = "Security & Observability",
+    params(SlowQueryParams),
+    responses(
+        (status = 200, description = "Slow query report", body = SlowQueryReport)
+    )
+)]
+pub async fn slow_queries<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Query(params): Query<SlowQueryParams>,
+) -> Result<Json<SuccessResponse<SlowQueryReport>>> {
+    require_platform_admin(&state, &auth).await?;
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    Ok(Json(SuccessResponse::new(global_stats().report(limit))))
+}
+
+/// Reset SQL statistics and the slow query log on this replica
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/sql/slow-queries",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "Statistics cleared", body = MessageResponse)
+    )
+)]
+pub async fn reset_slow_queries<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<Json<MessageResponse>> {
+    require_platform_admin(&state, &auth).await?;
+    global_stats().reset();
+    Ok(Json(MessageResponse::new("SQL statistics cleared")))
+}
+
+async fn require_platform_admin<S: HasServices>(state: &S, auth: &AuthUser) -> Result<()> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await
+}
@@ -0,0 +1,51 @@
+//! Synthetic monitoring API handlers (platform admin)
+
+use crate::domains::security_observability::service::synthetic::{
+    run_login_check, SyntheticCheckReport,
+};
+use crate::error::Result;
+use crate::http_support::SuccessResponse;
+use crate::middleware::auth::AuthUser;
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use axum::{extract::State, http::StatusCode, Json};
+
+/// Run the login flow for the synthetic user and report per-stage latencies
+///
+/// Answers 503 when any stage fails, so uptime monitors can alert on the
+/// status code alone.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/synthetic/login-check",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "All stages passed", body = SyntheticCheckReport),
+        (status = 404, description = "Synthetic check not configured"),
+        (status = 503, description = "A stage failed", body = SyntheticCheckReport)
+    )
+)]
+pub async fn login_check<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<(StatusCode, Json<SuccessResponse<SyntheticCheckReport>>)> {
+    enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await?;
+
+    let report = run_login_check(&state, &state.config().synthetic_check).await?;
+    if !report.ok {
+        tracing::warn!(stages = ?report.stages, "Synthetic login check failed");
+    }
+    let status = if report.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Ok((status, Json(SuccessResponse::new(report))))
+}
@@ -0,0 +1,120 @@
+//! Runtime telemetry API handlers (platform admin)
+//!
+//! Overrides apply to the replica that serves the request and revert on
+//! their own once the TTL elapses.
+
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::policy::{enforce_with_state, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use crate::telemetry::runtime::{
+    runtime, TelemetryOverrideInput, TelemetryStatus, DEFAULT_OVERRIDE_TTL_SECS,
+};
+use axum::{extract::State, http::HeaderMap, Json};
+use chrono::Utc;
+use std::time::Duration;
+
+/// Current log filter and trace sampling ratio of this replica
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/telemetry",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "Telemetry settings", body = TelemetryStatus)
+    )
+)]
+pub async fn get_telemetry<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<Json<SuccessResponse<TelemetryStatus>>> {
+    require_platform_admin(&state, &auth).await?;
+    Ok(Json(SuccessResponse::new(runtime().status(Utc::now()))))
+}
+
+/// Temporarily change the log filter and/or trace sampling ratio of this
+/// replica. The startup configuration is restored after `ttl_secs`.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/telemetry",
+    tag = "Security & Observability",
+    request_body = TelemetryOverrideInput,
+    responses(
+        (status = 200, description = "Override applied", body = TelemetryStatus),
+        (status = 422, description = "Invalid filter directives, ratio or TTL")
+    )
+)]
+pub async fn set_telemetry<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<TelemetryOverrideInput>,
+) -> Result<Json<SuccessResponse<TelemetryStatus>>> {
+    require_platform_admin(&state, &auth).await?;
+
+    let before = runtime().status(Utc::now());
+    let ttl_secs = input.ttl_secs.unwrap_or(DEFAULT_OVERRIDE_TTL_SECS);
+    let (status, generation) = runtime().apply(input, Utc::now())?;
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(ttl_secs)).await;
+        runtime().revert_if_current(generation);
+    });
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "telemetry.override",
+        "telemetry",
+        None,
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&status).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(status)))
+}
+
+/// Drop the active override and restore the startup configuration
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/telemetry",
+    tag = "Security & Observability",
+    responses(
+        (status = 200, description = "Defaults restored", body = TelemetryStatus)
+    )
+)]
+pub async fn reset_telemetry<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+) -> Result<Json<SuccessResponse<TelemetryStatus>>> {
+    require_platform_admin(&state, &auth).await?;
+
+    runtime().revert();
+    let status = runtime().status(Utc::now());
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "telemetry.reset",
+        "telemetry",
+        None,
+        None,
+        serde_json::to_value(&status).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(status)))
+}
+
+async fn require_platform_admin<S: HasServices>(state: &S, auth: &AuthUser) -> Result<()> {
+    enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await
+}
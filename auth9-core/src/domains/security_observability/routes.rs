@@ -1,7 +1,10 @@
 use crate::domains::security_observability::api as secobs_api;
 use crate::domains::security_observability::api::risk::HasRiskPolicy;
 use crate::domains::security_observability::context::SecurityObservabilityContext;
-use crate::state::HasServices;
+use crate::state::{
+    HasCache, HasDbPool, HasPasswordManagement, HasRequiredActions, HasServices,
+    HasSessionManagement,
+};
 use axum::{
     routing::{get, post},
     Router,
@@ -9,23 +12,77 @@ use axum::{
 
 pub fn public_routes<S>() -> Router<S>
 where
-    S: HasServices,
+    S: HasServices
+        + HasDbPool
+        + HasSessionManagement
+        + HasRequiredActions
+        + HasPasswordManagement
+        + HasCache,
 {
     Router::new()
         .route("/health", get(secobs_api::health::health))
         .route("/ready", get(secobs_api::health::ready::<S>))
+        .route("/health/live", get(secobs_api::health::health))
+        .route("/health/ready", get(secobs_api::health::ready::<S>))
         .route(
             "/api/v1/public/captcha-config",
             get(secobs_api::captcha::get_captcha_config::<S>),
         )
+        // Read-only audit log views shared with external auditors
+        .route(
+            "/api/v1/public/audit-shares",
+            get(secobs_api::audit_share::preview::<S>),
+        )
+        .route(
+            "/api/v1/public/audit-shares/logs",
+            get(secobs_api::audit_share::logs::<S>),
+        )
+        // One-click actions in security alert digests
+        .route(
+            "/api/v1/security-digest/actions",
+            get(secobs_api::security_digest::get_action::<S>)
+                .post(secobs_api::security_digest::perform_action::<S>),
+        )
 }
 
 pub fn protected_routes<S>() -> Router<S>
 where
-    S: SecurityObservabilityContext + HasRiskPolicy,
+    S: SecurityObservabilityContext + HasRiskPolicy + HasDbPool,
 {
     Router::new()
         .route("/api/v1/audit-logs", get(secobs_api::audit::list::<S>))
+        .route(
+            "/api/v1/audit-logs/histogram",
+            get(secobs_api::audit::histogram::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/verify",
+            get(secobs_api::audit::verify::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/exports",
+            post(secobs_api::audit::export::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/exports/{id}/download",
+            get(secobs_api::audit::download_export::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/shares",
+            get(secobs_api::audit_share::list::<S>).post(secobs_api::audit_share::create::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/shares/{id}/revoke",
+            post(secobs_api::audit_share::revoke::<S>),
+        )
+        .route(
+            "/api/v1/audit-logs/shares/{id}/accesses",
+            get(secobs_api::audit_share::list_accesses::<S>),
+        )
+        .route(
+            "/api/v1/audit/state",
+            get(secobs_api::audit::get_state::<S>),
+        )
         .route(
             "/api/v1/analytics/login-stats",
             get(secobs_api::analytics::get_stats::<S>),
@@ -46,9 +103,32 @@ where
             "/api/v1/security/alerts/{id}/resolve",
             post(secobs_api::security_alert::resolve_alert::<S>),
         )
+        .route(
+            "/api/v1/security/login-failures",
+            get(secobs_api::login_forensics::search_login_failures::<S>),
+        )
         .route(
             "/api/v1/security/risk-policy",
             get(secobs_api::risk::get_risk_policy::<S>)
                 .put(secobs_api::risk::update_risk_policy::<S>),
         )
+        .route(
+            "/api/v1/admin/sql/slow-queries",
+            get(secobs_api::sql_stats::slow_queries::<S>)
+                .delete(secobs_api::sql_stats::reset_slow_queries::<S>),
+        )
+        .route(
+            "/api/v1/admin/telemetry",
+            get(secobs_api::telemetry::get_telemetry::<S>)
+                .put(secobs_api::telemetry::set_telemetry::<S>)
+                .delete(secobs_api::telemetry::reset_telemetry::<S>),
+        )
+        .route(
+            "/api/v1/admin/metrics/catalog",
+            get(secobs_api::metrics_catalog::metrics_catalog::<S>),
+        )
+        .route(
+            "/api/v1/admin/synthetic/login-check",
+            get(secobs_api::synthetic::login_check::<S>),
+        )
 }
@@ -383,6 +383,12 @@ impl<R: LoginEventRepository> AnalyticsService<R> {
         Ok((events, total))
     }
 
+    /// Risk score recorded on the user's most recent login, if it was scored
+    pub async fn latest_risk_score(&self, user_id: StringUuid) -> Result<Option<u8>> {
+        let events = self.login_event_repo.list_by_user(user_id, 0, 1).await?;
+        Ok(events.first().and_then(|e| e.risk_score))
+    }
+
     /// List login events filtered by email address
     pub async fn list_events_by_email(
         &self,
@@ -667,6 +673,21 @@ mod tests {
         assert_eq!(total, 5);
     }
 
+    #[tokio::test]
+    async fn test_latest_risk_score_empty_history() {
+        let mut mock = MockLoginEventRepository::new();
+        mock.expect_list_by_user()
+            .withf(|_, offset, limit| *offset == 0 && *limit == 1)
+            .returning(|_, _, _| Ok(vec![]));
+
+        let service = AnalyticsService::new(Arc::new(mock));
+        let score = service
+            .latest_risk_score(StringUuid::new_v4())
+            .await
+            .unwrap();
+        assert_eq!(score, None);
+    }
+
     #[tokio::test]
     async fn test_list_tenant_events() {
         let mut mock = MockLoginEventRepository::new();
@@ -0,0 +1,193 @@
+//! Audit log integrity service — chain verification and signed checkpoints
+
+use crate::config::AuditIntegrityConfig;
+use crate::error::Result;
+use crate::models::audit_integrity::{
+    checkpoint_signature, AuditCheckpoint, AuditIntegrityReport, ChainVerifier, VERIFY_BATCH_SIZE,
+};
+use crate::repository::audit_integrity::AuditIntegrityRepositoryImpl;
+use crate::repository::AuditIntegrityRepository;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+pub struct AuditIntegrityService<R: AuditIntegrityRepository> {
+    repo: Arc<R>,
+    checkpoint_key: Option<String>,
+}
+
+impl AuditIntegrityService<AuditIntegrityRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool, config: &AuditIntegrityConfig) -> Self {
+        Self::new(
+            Arc::new(AuditIntegrityRepositoryImpl::new(pool)),
+            config.checkpoint_key.clone(),
+        )
+    }
+}
+
+impl<R: AuditIntegrityRepository> AuditIntegrityService<R> {
+    pub fn new(repo: Arc<R>, checkpoint_key: Option<String>) -> Self {
+        Self {
+            repo,
+            checkpoint_key,
+        }
+    }
+
+    /// Walk the chain between `from_seq` and `to_seq` (default: all retained
+    /// entries) and report edited, missing or reordered entries and
+    /// checkpoints the chain no longer matches.
+    pub async fn verify(
+        &self,
+        from_seq: Option<i64>,
+        to_seq: Option<i64>,
+    ) -> Result<AuditIntegrityReport> {
+        let from_seq = from_seq.unwrap_or(1).max(1);
+        let checkpoints = self.repo.list_checkpoints(from_seq, to_seq).await?;
+        let mut verifier = ChainVerifier::new(checkpoints, self.checkpoint_key.as_deref());
+
+        let mut next_seq = from_seq;
+        loop {
+            let batch = self
+                .repo
+                .list_chain(next_seq, to_seq, VERIFY_BATCH_SIZE)
+                .await?;
+            for entry in &batch {
+                verifier.push(entry);
+            }
+            match batch.last() {
+                Some(last) if batch.len() as i64 == VERIFY_BATCH_SIZE => {
+                    next_seq = last.chain_seq + 1;
+                }
+                _ => break,
+            }
+        }
+
+        // Truncation of the newest entries only shows against the head
+        let head_seq = match to_seq {
+            Some(_) => None,
+            None => Some(self.repo.chain_head().await?.0),
+        };
+        let report = verifier.finish(head_seq);
+
+        metrics::counter!(
+            "auth9_audit_integrity_verifications_total",
+            "result" => if report.valid { "valid" } else { "tampered" }
+        )
+        .increment(1);
+        if !report.valid {
+            tracing::error!(
+                issues = report.issue_count,
+                first_issue = ?report.issues.first(),
+                "Audit log integrity verification found tampering"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Record a signed checkpoint of the chain head. Does nothing without a
+    /// checkpoint key or when the head has not moved since the last one.
+    pub async fn record_checkpoint(&self, now: DateTime<Utc>) -> Result<Option<AuditCheckpoint>> {
+        let Some(key) = self.checkpoint_key.as_deref() else {
+            return Ok(None);
+        };
+        let (chain_seq, row_hash) = self.repo.chain_head().await?;
+        if chain_seq == 0 {
+            return Ok(None);
+        }
+        if let Some(latest) = self.repo.latest_checkpoint().await? {
+            if latest.chain_seq >= chain_seq {
+                return Ok(None);
+            }
+        }
+
+        let created_at = DateTime::from_timestamp(now.timestamp(), 0).unwrap_or_default();
+        let signature = checkpoint_signature(key, chain_seq, &row_hash, created_at);
+        let checkpoint = self
+            .repo
+            .create_checkpoint(chain_seq, &row_hash, &signature, created_at)
+            .await?;
+        Ok(Some(checkpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::audit_integrity::{entry_hash, ChainEntry, GENESIS_HASH};
+    use crate::repository::audit_integrity::MockAuditIntegrityRepository;
+
+    fn entry(seq: i64, prev_hash: &str) -> ChainEntry {
+        let mut entry = ChainEntry {
+            id: seq,
+            chain_seq: seq,
+            actor_id: None,
+            actor_digest: None,
+            action: "tenant.update".to_string(),
+            resource_type: "tenant".to_string(),
+            resource_id: None,
+            old_value: None,
+            new_value: None,
+            ip_address: None,
+            prev_hash: prev_hash.to_string(),
+            row_hash: String::new(),
+            created_at: DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+        };
+        entry.row_hash = entry_hash(prev_hash, &entry.fields());
+        entry
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_truncated_head() {
+        let first = entry(1, GENESIS_HASH);
+        let second = entry(2, &first.row_hash);
+        let mut repo = MockAuditIntegrityRepository::new();
+        repo.expect_list_checkpoints().returning(|_, _| Ok(vec![]));
+        repo.expect_list_chain()
+            .returning(move |_, _, _| Ok(vec![first.clone(), second.clone()]));
+        repo.expect_chain_head()
+            .returning(|| Ok((3, "deleted".to_string())));
+
+        let service = AuditIntegrityService::new(Arc::new(repo), None);
+        let report = service.verify(None, None).await.unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.entries_checked, 2);
+        assert_eq!(report.issue_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_record_checkpoint_signs_new_head_only() {
+        let mut repo = MockAuditIntegrityRepository::new();
+        repo.expect_chain_head()
+            .returning(|| Ok((7, "abc".to_string())));
+        repo.expect_latest_checkpoint()
+            .times(1)
+            .returning(|| Ok(None));
+        repo.expect_create_checkpoint()
+            .times(1)
+            .returning(|seq, hash, signature, created_at| {
+                Ok(AuditCheckpoint {
+                    id: 1,
+                    chain_seq: seq,
+                    row_hash: hash.to_string(),
+                    signature: signature.to_string(),
+                    created_at,
+                })
+            });
+        let service = AuditIntegrityService::new(Arc::new(repo), Some("key".to_string()));
+        let checkpoint = service
+            .record_checkpoint(Utc::now())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(checkpoint.chain_seq, 7);
+        assert!(checkpoint.signature_valid("key"));
+
+        let unsigned =
+            AuditIntegrityService::new(Arc::new(MockAuditIntegrityRepository::new()), None);
+        assert!(unsigned
+            .record_checkpoint(Utc::now())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
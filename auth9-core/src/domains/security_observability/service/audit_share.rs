@@ -0,0 +1,277 @@
+//! Audit share links: creation, revocation and access logging
+
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::models::audit_share::{
+    AuditShareAccess, AuditShareLink, AuditShareStatus, CreateAuditShareLinkInput,
+    DEFAULT_SHARE_EXPIRES_IN_HOURS,
+};
+use crate::models::common::StringUuid;
+use crate::repository::audit_share::AuditShareRepositoryImpl;
+use crate::repository::AuditShareRepository;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct AuditShareService {
+    repo: Arc<dyn AuditShareRepository>,
+    base_url: String,
+}
+
+impl AuditShareService {
+    /// Service building share URLs from the public core URL (the issuer
+    /// when unset)
+    pub fn from_config(pool: MySqlPool, config: &Config) -> Self {
+        Self::new(
+            Arc::new(AuditShareRepositoryImpl::new(pool)),
+            config
+                .core_public_url
+                .as_deref()
+                .unwrap_or(&config.jwt.issuer),
+        )
+    }
+
+    pub fn new(repo: Arc<dyn AuditShareRepository>, base_url: &str) -> Self {
+        Self {
+            repo,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Create a link. Returns it with the raw token, which is not stored.
+    pub async fn create(
+        &self,
+        created_by: StringUuid,
+        input: CreateAuditShareLinkInput,
+    ) -> Result<(AuditShareLink, String)> {
+        input.validate()?;
+
+        let bytes: [u8; 32] = rand::thread_rng().gen();
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+        let now = Utc::now();
+        let link = AuditShareLink {
+            id: StringUuid::new_v4(),
+            name: input
+                .name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty()),
+            filter: input.filter,
+            created_by,
+            token_hash: hash_token(&token),
+            access_count: 0,
+            last_accessed_at: None,
+            expires_at: now
+                + Duration::hours(
+                    input
+                        .expires_in_hours
+                        .unwrap_or(DEFAULT_SHARE_EXPIRES_IN_HOURS),
+                ),
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(&link).await?;
+        Ok((link, token))
+    }
+
+    /// Where a recipient opens the shared view
+    pub fn share_url(&self, token: &str) -> String {
+        format!(
+            "{}/api/v1/public/audit-shares/logs?token={}",
+            self.base_url, token
+        )
+    }
+
+    pub async fn get(&self, id: StringUuid) -> Result<AuditShareLink> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Audit share link {} not found", id)))
+    }
+
+    /// Look up an active link by its raw token
+    pub async fn get_active_by_token(&self, token: &str) -> Result<AuditShareLink> {
+        let link = self
+            .repo
+            .find_by_token_hash(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Invalid audit share link".to_string()))?;
+        match link.status() {
+            AuditShareStatus::Active => Ok(link),
+            AuditShareStatus::Expired => Err(AppError::Forbidden(
+                "Audit share link has expired".to_string(),
+            )),
+            AuditShareStatus::Revoked => Err(AppError::Forbidden(
+                "Audit share link has been revoked".to_string(),
+            )),
+        }
+    }
+
+    pub async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<AuditShareLink>, i64)> {
+        let offset = (page - 1) * per_page;
+        let links = self.repo.list(offset, per_page).await?;
+        let total = self.repo.count().await?;
+        Ok((links, total))
+    }
+
+    pub async fn revoke(&self, id: StringUuid) -> Result<AuditShareLink> {
+        let link = self.get(id).await?;
+        if link.revoked_at.is_some() {
+            return Err(AppError::BadRequest(
+                "Audit share link is already revoked".to_string(),
+            ));
+        }
+        self.repo.revoke(id).await?;
+        self.get(id).await
+    }
+
+    pub async fn record_access(
+        &self,
+        link: &AuditShareLink,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<()> {
+        self.repo
+            .record_access(&AuditShareAccess {
+                id: StringUuid::new_v4(),
+                link_id: link.id,
+                ip_address,
+                user_agent: user_agent.map(|ua| ua.chars().take(512).collect()),
+                created_at: Utc::now(),
+            })
+            .await
+    }
+
+    pub async fn list_accesses(
+        &self,
+        link_id: StringUuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<AuditShareAccess>, i64)> {
+        let offset = (page - 1) * per_page;
+        let accesses = self.repo.list_accesses(link_id, offset, per_page).await?;
+        let total = self.repo.count_accesses(link_id).await?;
+        Ok((accesses, total))
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::audit_share::AuditShareFilter;
+    use crate::repository::audit_share::MockAuditShareRepository;
+    use mockall::predicate::*;
+
+    fn input() -> CreateAuditShareLinkInput {
+        let now = Utc::now();
+        CreateAuditShareLinkInput {
+            name: Some("  Q3 review ".to_string()),
+            filter: AuditShareFilter {
+                from_date: now - Duration::days(30),
+                to_date: now,
+                actor_id: Some(uuid::Uuid::new_v4()),
+                resource_type: None,
+                resource_id: None,
+                action: None,
+                q: None,
+            },
+            expires_in_hours: None,
+        }
+    }
+
+    fn service(repo: MockAuditShareRepository) -> AuditShareService {
+        AuditShareService::new(Arc::new(repo), "https://auth9.example.com/")
+    }
+
+    #[tokio::test]
+    async fn test_create_stores_hash_of_returned_token() {
+        let mut repo = MockAuditShareRepository::new();
+        repo.expect_create().returning(|_| Ok(()));
+        let service = service(repo);
+
+        let (link, token) = service.create(StringUuid::new_v4(), input()).await.unwrap();
+        assert_eq!(link.token_hash, hash_token(&token));
+        assert_eq!(link.name.as_deref(), Some("Q3 review"));
+        assert!(link.expires_at > Utc::now() + Duration::hours(71));
+        assert_eq!(
+            service.share_url(&token),
+            format!(
+                "https://auth9.example.com/api/v1/public/audit-shares/logs?token={}",
+                token
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_unbounded_window() {
+        let service = service(MockAuditShareRepository::new());
+        let mut input = input();
+        input.filter.from_date = input.filter.to_date;
+
+        let result = service.create(StringUuid::new_v4(), input).await;
+        assert!(matches!(result, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_active_by_token_rejects_revoked_and_expired() {
+        let mut repo = MockAuditShareRepository::new();
+        repo.expect_find_by_token_hash()
+            .with(eq(hash_token("active")))
+            .returning(|_| Ok(Some(AuditShareLink::default())));
+        repo.expect_find_by_token_hash()
+            .with(eq(hash_token("revoked")))
+            .returning(|_| {
+                Ok(Some(AuditShareLink {
+                    revoked_at: Some(Utc::now()),
+                    ..Default::default()
+                }))
+            });
+        repo.expect_find_by_token_hash()
+            .with(eq(hash_token("expired")))
+            .returning(|_| {
+                Ok(Some(AuditShareLink {
+                    expires_at: Utc::now() - Duration::minutes(1),
+                    ..Default::default()
+                }))
+            });
+        repo.expect_find_by_token_hash().returning(|_| Ok(None));
+        let service = service(repo);
+
+        assert!(service.get_active_by_token("active").await.is_ok());
+        assert!(matches!(
+            service.get_active_by_token("revoked").await,
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            service.get_active_by_token("expired").await,
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            service.get_active_by_token("unknown").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_twice_fails() {
+        let mut repo = MockAuditShareRepository::new();
+        repo.expect_find_by_id().returning(|_| {
+            Ok(Some(AuditShareLink {
+                revoked_at: Some(Utc::now()),
+                ..Default::default()
+            }))
+        });
+        let service = service(repo);
+
+        let result = service.revoke(StringUuid::new_v4()).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
@@ -18,6 +18,16 @@ pub struct GeoLocation {
     pub accuracy_radius_km: u16,
 }
 
+impl GeoLocation {
+    /// Human-readable place, e.g. "Berlin, Germany"
+    pub fn label(&self) -> String {
+        match &self.city {
+            Some(city) => format!("{}, {}", city, self.country_name),
+            None => self.country_name.clone(),
+        }
+    }
+}
+
 /// GeoIP lookup service backed by MaxMind GeoLite2 `.mmdb` database
 pub struct GeoIpService {
     reader: Arc<maxminddb::Reader<Vec<u8>>>,
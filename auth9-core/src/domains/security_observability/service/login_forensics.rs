@@ -0,0 +1,294 @@
+//! Forensic capture of failed sign-ins
+//!
+//! When `LOGIN_FORENSICS_ENABLED` is set, failed sign-ins are written to a
+//! dedicated store next to the regular login events. Captures keep the
+//! context incident responders need (client, IP, user agent, failure stage)
+//! but never the raw identifier, and are purged after
+//! `LOGIN_FORENSICS_RETENTION_DAYS`.
+
+use crate::config::LoginForensicsConfig;
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::login_forensics::{
+    hash_login_identifier, LoginFailureCapture, LoginFailureQuery, LoginFailureRecord,
+};
+use crate::models::masking::mask_ip;
+use crate::repository::login_forensics::{LoginFailureFilter, LoginForensicsRepositoryImpl};
+use crate::repository::LoginForensicsRepository;
+use crate::state::HasServices;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+/// Captures returned by a search when no limit is given
+const DEFAULT_SEARCH_LIMIT: i64 = 100;
+const MAX_USER_AGENT_CHARS: usize = 512;
+const MAX_FIELD_CHARS: usize = 255;
+
+pub struct LoginForensicsService<R: LoginForensicsRepository> {
+    repo: Arc<R>,
+    config: LoginForensicsConfig,
+}
+
+impl LoginForensicsService<LoginForensicsRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool, config: &LoginForensicsConfig) -> Self {
+        Self::new(
+            Arc::new(LoginForensicsRepositoryImpl::new(pool)),
+            config.clone(),
+        )
+    }
+}
+
+impl<R: LoginForensicsRepository> LoginForensicsService<R> {
+    pub fn new(repo: Arc<R>, config: LoginForensicsConfig) -> Self {
+        Self { repo, config }
+    }
+
+    /// Store a failed sign-in. Returns `None` when capture is disabled.
+    pub async fn capture(
+        &self,
+        capture: LoginFailureCapture,
+        now: DateTime<Utc>,
+    ) -> Result<Option<LoginFailureRecord>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let record = LoginFailureRecord {
+            id: StringUuid::new_v4(),
+            user_id: capture.user_id,
+            identifier_hash: capture
+                .identifier
+                .as_deref()
+                .filter(|i| !i.trim().is_empty())
+                .map(hash_login_identifier),
+            tenant_id: capture.tenant_id,
+            client_id: capture.client_id.map(|c| truncate(c, MAX_FIELD_CHARS)),
+            ip_address: capture.ip_address.map(|ip| self.store_ip(&ip)),
+            user_agent: capture
+                .user_agent
+                .filter(|_| self.config.capture_user_agent)
+                .map(|ua| truncate(ua, MAX_USER_AGENT_CHARS)),
+            failure_stage: capture.stage,
+            failure_reason: capture.failure_reason.map(|r| truncate(r, MAX_FIELD_CHARS)),
+            created_at: now,
+        };
+        self.repo.create(&record).await?;
+        Ok(Some(record))
+    }
+
+    /// Search captures by IP, user or identifier. At least one of them is
+    /// required so responders cannot page through the whole store.
+    pub async fn search(&self, query: &LoginFailureQuery) -> Result<Vec<LoginFailureRecord>> {
+        let filter = LoginFailureFilter {
+            ip_address: query
+                .ip
+                .as_deref()
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty())
+                .map(|ip| self.store_ip(ip)),
+            user_id: query.user_id,
+            identifier_hash: query
+                .identifier
+                .as_deref()
+                .filter(|i| !i.trim().is_empty())
+                .map(hash_login_identifier),
+            since: query.since,
+        };
+        if filter.ip_address.is_none()
+            && filter.user_id.is_none()
+            && filter.identifier_hash.is_none()
+        {
+            return Err(AppError::BadRequest(
+                "Provide at least one of ip, user_id or identifier".to_string(),
+            ));
+        }
+        self.repo
+            .search(&filter, query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .await
+    }
+
+    /// Delete captures older than the retention period
+    pub async fn purge_expired(&self, now: DateTime<Utc>) -> Result<u64> {
+        self.repo
+            .delete_before(now - Duration::days(self.config.retention_days as i64))
+            .await
+    }
+
+    fn store_ip(&self, ip: &str) -> String {
+        if self.config.mask_ip {
+            mask_ip(ip)
+        } else {
+            ip.trim().to_string()
+        }
+    }
+}
+
+fn truncate(value: String, max_chars: usize) -> String {
+    if value.chars().count() > max_chars {
+        value.chars().take(max_chars).collect()
+    } else {
+        value
+    }
+}
+
+/// Capture a failed sign-in if forensic capture is enabled. A capture that
+/// cannot be stored fails the request rather than going missing.
+pub async fn capture_login_failure<S: HasServices>(
+    state: &S,
+    capture: LoginFailureCapture,
+) -> Result<()> {
+    let stage = capture.stage;
+    if let Err(e) = state
+        .login_forensics_service()
+        .capture(capture, Utc::now())
+        .await
+    {
+        tracing::error!(stage = %stage, error = %e, "Failed to capture failed sign-in");
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::login_forensics::LoginFailureStage;
+    use crate::repository::login_forensics::MockLoginForensicsRepository;
+
+    fn enabled() -> LoginForensicsConfig {
+        LoginForensicsConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn query() -> LoginFailureQuery {
+        LoginFailureQuery {
+            ip: None,
+            user_id: None,
+            identifier: None,
+            since: None,
+            limit: None,
+            reason: "incident 42".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_capture_disabled_stores_nothing() {
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_create().never();
+
+        let captured = LoginForensicsService::new(Arc::new(repo), Default::default())
+            .capture(
+                LoginFailureCapture::new(LoginFailureStage::Password),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+        assert!(captured.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_capture_hashes_identifier_and_applies_privacy_settings() {
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_create().times(1).returning(|_| Ok(()));
+        let config = LoginForensicsConfig {
+            mask_ip: true,
+            capture_user_agent: false,
+            ..enabled()
+        };
+
+        let capture = LoginFailureCapture {
+            identifier: Some("Alice@Example.com".to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("curl/8.0".to_string()),
+            client_id: Some("portal".to_string()),
+            ..LoginFailureCapture::new(LoginFailureStage::UnknownUser)
+        };
+        let record = LoginForensicsService::new(Arc::new(repo), config)
+            .capture(capture, Utc::now())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            record.identifier_hash.as_deref(),
+            Some(hash_login_identifier("alice@example.com").as_str())
+        );
+        assert_eq!(record.ip_address.as_deref(), Some("203.0.113.*"));
+        assert!(record.user_agent.is_none());
+        assert_eq!(record.client_id.as_deref(), Some("portal"));
+    }
+
+    #[tokio::test]
+    async fn test_capture_truncates_user_agent() {
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_create().returning(|_| Ok(()));
+
+        let capture = LoginFailureCapture {
+            user_agent: Some("x".repeat(2000)),
+            ..LoginFailureCapture::new(LoginFailureStage::Mfa)
+        };
+        let record = LoginForensicsService::new(Arc::new(repo), enabled())
+            .capture(capture, Utc::now())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.user_agent.unwrap().len(), MAX_USER_AGENT_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_search_requires_a_subject() {
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_search().never();
+
+        let result = LoginForensicsService::new(Arc::new(repo), enabled())
+            .search(&query())
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_masked_ip_and_identifier_hash() {
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_search()
+            .withf(|filter, limit| {
+                filter.ip_address.as_deref() == Some("203.0.113.*")
+                    && filter.identifier_hash == Some(hash_login_identifier("bob@example.com"))
+                    && *limit == DEFAULT_SEARCH_LIMIT
+            })
+            .returning(|_, _| Ok(vec![]));
+        let config = LoginForensicsConfig {
+            mask_ip: true,
+            ..enabled()
+        };
+
+        let query = LoginFailureQuery {
+            ip: Some("203.0.113.99".to_string()),
+            identifier: Some("bob@example.com".to_string()),
+            ..query()
+        };
+        LoginForensicsService::new(Arc::new(repo), config)
+            .search(&query)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_purge_uses_retention() {
+        let now = Utc::now();
+        let mut repo = MockLoginForensicsRepository::new();
+        repo.expect_delete_before()
+            .withf(move |before| *before == now - Duration::days(3))
+            .returning(|_| Ok(5));
+        let config = LoginForensicsConfig {
+            retention_days: 3,
+            ..enabled()
+        };
+
+        let purged = LoginForensicsService::new(Arc::new(repo), config)
+            .purge_expired(now)
+            .await
+            .unwrap();
+        assert_eq!(purged, 5);
+    }
+}
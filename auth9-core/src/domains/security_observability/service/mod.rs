@@ -1,17 +1,26 @@
 pub mod analytics;
+pub mod audit_integrity;
+pub mod audit_share;
 pub mod captcha;
 pub mod geo;
+pub mod login_forensics;
 pub mod risk_engine;
 pub mod risk_response;
 pub mod security_detection;
+pub mod security_digest;
+pub mod synthetic;
 pub mod user_profile;
 
 pub use analytics::AnalyticsService;
+pub use audit_integrity::AuditIntegrityService;
+pub use audit_share::AuditShareService;
 pub use captcha::{
     CaptchaMode, CaptchaProvider, CaptchaProviderType, CaptchaVerification, NoOpCaptchaProvider,
 };
 pub use geo::{haversine_distance_km, GeoIpService, GeoLocation};
+pub use login_forensics::LoginForensicsService;
 pub use risk_engine::{RiskAction, RiskAssessment, RiskEngine, RiskFactor, RiskLevel};
 pub use risk_response::RiskResponseService;
 pub use security_detection::{SecurityDetectionConfig, SecurityDetectionService};
+pub use security_digest::SecurityDigestService;
 pub use user_profile::UserLoginProfileService;
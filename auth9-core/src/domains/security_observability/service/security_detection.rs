@@ -7,6 +7,7 @@ use crate::models::analytics::{
     SecurityAlertType, WebhookEvent,
 };
 use crate::models::common::StringUuid;
+use crate::models::session::Session;
 use crate::repository::WebhookRepository;
 use crate::repository::{
     LoginEventRepository, MaliciousIpBlacklistRepository, SecurityAlertRepository,
@@ -666,6 +667,47 @@ impl<
             })
     }
 
+    /// Raise an alert for a sign-in the user reported as not theirs (the
+    /// "this wasn't me" link of sign-in emails) and notify webhooks
+    pub async fn report_sign_in(
+        &self,
+        user_id: StringUuid,
+        session: Option<&Session>,
+    ) -> Result<SecurityAlert> {
+        let input = CreateSecurityAlertInput {
+            user_id: Some(user_id),
+            tenant_id: None,
+            alert_type: SecurityAlertType::UserReportedSignIn,
+            severity: AlertSeverity::High,
+            details: Some(serde_json::json!({
+                "session_id": session.map(|s| s.id.to_string()),
+                "device_name": session.and_then(|s| s.device_name.clone()),
+                "device_type": session.and_then(|s| s.device_type.clone()),
+                "ip_address": session.and_then(|s| s.ip_address.clone()),
+                "location": session.and_then(|s| s.location.clone()),
+                "signed_in_at": session.map(|s| s.created_at),
+            })),
+        };
+        let alert = self.security_alert_repo.create(&input).await?;
+        metrics::counter!("auth9_security_alerts_total", "type" => "user_reported_sign_in", "severity" => "high").increment(1);
+
+        let _ = self
+            .webhook_service
+            .trigger_event(WebhookEvent {
+                event_type: "security.alert".to_string(),
+                timestamp: Utc::now(),
+                data: serde_json::json!({
+                    "alert_id": alert.id.to_string(),
+                    "alert_type": alert.alert_type,
+                    "severity": alert.severity,
+                    "user_id": alert.user_id.map(|id| id.to_string()),
+                    "details": alert.details,
+                }),
+            })
+            .await;
+        Ok(alert)
+    }
+
     /// Clean up old resolved alerts
     pub async fn cleanup_old_alerts(&self, days: i64) -> Result<u64> {
         self.security_alert_repo.delete_old(days).await
@@ -857,6 +899,56 @@ mod tests {
         assert_eq!(alerts[0].severity, AlertSeverity::Critical);
     }
 
+    #[tokio::test]
+    async fn test_report_sign_in_creates_high_alert() {
+        let mut alert_mock = MockSecurityAlertRepository::new();
+        let mut webhook_mock = MockWebhookRepository::new();
+        let user_id = StringUuid::new_v4();
+        let session = Session {
+            user_id,
+            ip_address: Some("198.51.100.4".to_string()),
+            ..Default::default()
+        };
+        let session_id = session.id.to_string();
+
+        alert_mock.expect_create().returning(move |input| {
+            assert_eq!(input.alert_type, SecurityAlertType::UserReportedSignIn);
+            assert_eq!(input.severity, AlertSeverity::High);
+            assert_eq!(
+                input
+                    .details
+                    .as_ref()
+                    .and_then(|d| d.get("session_id"))
+                    .and_then(|v| v.as_str()),
+                Some(session_id.as_str())
+            );
+            Ok(SecurityAlert {
+                user_id: input.user_id,
+                alert_type: input.alert_type.clone(),
+                severity: input.severity.clone(),
+                details: input.details.clone(),
+                ..Default::default()
+            })
+        });
+        webhook_mock
+            .expect_list_enabled_for_event()
+            .returning(|_| Ok(vec![]));
+
+        let service = SecurityDetectionService::new(
+            Arc::new(MockLoginEventRepository::new()),
+            Arc::new(alert_mock),
+            Arc::new(WebhookService::new(Arc::new(webhook_mock))),
+            SecurityDetectionConfig::default(),
+        );
+
+        let alert = service
+            .report_sign_in(user_id, Some(&session))
+            .await
+            .unwrap();
+        assert_eq!(alert.user_id, Some(user_id));
+        assert_eq!(alert.alert_type, SecurityAlertType::UserReportedSignIn);
+    }
+
     #[tokio::test]
     async fn test_analyze_login_event_tenant_blacklisted_ip_creates_critical_alert() {
         let mut login_mock = MockLoginEventRepository::new();
@@ -0,0 +1,614 @@
+//! Security alert digests for tenant admins and their action links
+//!
+//! Action links carry a stateless token: tenant, user, action, recipient and
+//! expiry, signed with HMAC-SHA256. They expire after
+//! [`DIGEST_ACTION_TTL_HOURS`] and stop working as soon as the recipient
+//! leaves the distribution list, the user leaves the tenant or the digest is
+//! turned off.
+
+use crate::config::Config;
+use crate::domains::platform::service::EmailService;
+use crate::error::{AppError, Result};
+use crate::i18n::{self, Locale};
+use crate::models::common::StringUuid;
+use crate::models::email::{EmailAddress, EmailMessage};
+use crate::models::email_template::EmailTemplateType;
+use crate::models::notification_preference::DigestFrequency;
+use crate::models::security_digest::{
+    DigestAction, DigestActionClaims, DigestAlert, DigestTenant, SecurityDigestPolicy,
+    DIGEST_ACTION_TTL_HOURS, MAX_DIGEST_ALERTS,
+};
+use crate::models::tenant::TenantSettings;
+use crate::repository::security_digest::SecurityDigestRepositoryImpl;
+use crate::repository::{SecurityDigestRepository, SystemSettingsRepository};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Domain separator so action tokens can't be confused with other values
+/// signed by the same secret
+const ACTION_TOKEN_CONTEXT: &[u8] = b"auth9-security-digest-action:";
+
+pub struct SecurityDigestService<R: SecurityDigestRepository> {
+    repo: Arc<R>,
+    signing_secret: String,
+    base_url: String,
+    portal_url: Option<String>,
+}
+
+impl SecurityDigestService<SecurityDigestRepositoryImpl> {
+    /// Service signing action links with the JWT secret, pointing at the
+    /// public core URL (the issuer when unset) and the portal
+    pub fn from_config(pool: MySqlPool, config: &Config) -> Self {
+        Self::new(
+            Arc::new(SecurityDigestRepositoryImpl::new(pool)),
+            config.jwt.secret.clone(),
+            config
+                .core_public_url
+                .as_deref()
+                .unwrap_or(&config.jwt.issuer),
+            config.portal_url.as_deref(),
+        )
+    }
+}
+
+impl<R: SecurityDigestRepository> SecurityDigestService<R> {
+    /// `base_url` is the public URL of auth9-core, used for action links;
+    /// without `portal_url` digests carry no links to the affected users
+    pub fn new(
+        repo: Arc<R>,
+        signing_secret: impl Into<String>,
+        base_url: &str,
+        portal_url: Option<&str>,
+    ) -> Self {
+        Self {
+            repo,
+            signing_secret: signing_secret.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            portal_url: portal_url.map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+
+    pub fn action_token(&self, claims: &DigestActionClaims) -> String {
+        let payload = format!(
+            "{}:{}:{}:{}:{}",
+            claims.tenant_id,
+            claims.user_id,
+            claims.action.as_str(),
+            claims.expires_at.timestamp(),
+            claims.recipient
+        );
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+        )
+    }
+
+    pub fn action_url(&self, claims: &DigestActionClaims) -> String {
+        format!(
+            "{}/api/v1/security-digest/actions?token={}",
+            self.base_url,
+            self.action_token(claims)
+        )
+    }
+
+    /// Check the signature and expiry of an action token
+    pub fn verify_action_token(
+        &self,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<DigestActionClaims> {
+        let invalid = || AppError::BadRequest("Invalid or expired digest action link".to_string());
+
+        let (payload_b64, signature_b64) = token.split_once('.').ok_or_else(invalid)?;
+        let payload = URL_SAFE_NO_PAD.decode(payload_b64).map_err(|_| invalid())?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature_b64)
+            .map_err(|_| invalid())?;
+
+        let mut mac = self.mac();
+        mac.update(&payload);
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let payload = String::from_utf8(payload).map_err(|_| invalid())?;
+        let parts: Vec<&str> = payload.splitn(5, ':').collect();
+        let [tenant_id, user_id, action, expires_at, recipient] = parts[..] else {
+            return Err(invalid());
+        };
+        let expires_at = expires_at
+            .parse::<i64>()
+            .ok()
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+            .ok_or_else(invalid)?;
+        if now >= expires_at {
+            return Err(invalid());
+        }
+        Ok(DigestActionClaims {
+            tenant_id: tenant_id.parse().map_err(|_| invalid())?,
+            user_id: user_id.parse().map_err(|_| invalid())?,
+            action: action.parse().map_err(|_| invalid())?,
+            recipient: recipient.to_string(),
+            expires_at,
+        })
+    }
+
+    /// Check that a verified action link may still be used: the tenant's
+    /// digest is on, the recipient still receives it and the user is still
+    /// a member of the tenant
+    pub async fn authorize_action(
+        &self,
+        claims: &DigestActionClaims,
+        settings: &TenantSettings,
+    ) -> Result<()> {
+        let revoked =
+            || AppError::Forbidden("This digest action link is no longer valid".to_string());
+        let policy = settings
+            .security_digest
+            .as_ref()
+            .filter(|policy| policy.enabled)
+            .ok_or_else(revoked)?;
+        if !self
+            .recipients(claims.tenant_id, policy)
+            .await?
+            .iter()
+            .any(|r| r == &claims.recipient)
+        {
+            return Err(revoked());
+        }
+        if !self
+            .repo
+            .is_member(claims.tenant_id, claims.user_id)
+            .await?
+        {
+            return Err(revoked());
+        }
+        Ok(())
+    }
+
+    /// Send digests to tenants whose digest period has elapsed.
+    ///
+    /// Returns the number of emails sent. Periods without matching alerts
+    /// send nothing. A tenant whose digest fails for every recipient stays
+    /// due and is retried on the next run.
+    pub async fn send_due_digests<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let mut sent = 0;
+        for tenant in self.repo.tenants_with_enabled_digest().await? {
+            let Some(policy) = tenant.policy() else {
+                continue;
+            };
+            let Some(period) = policy.frequency.period() else {
+                continue;
+            };
+            if tenant.last_sent_at.is_some_and(|at| at + period > now) {
+                continue;
+            }
+            match self
+                .send_digest(
+                    email_service,
+                    &tenant,
+                    policy,
+                    tenant.last_sent_at.unwrap_or(now - period),
+                    now,
+                )
+                .await
+            {
+                Ok(count) => sent += count,
+                Err(e) => {
+                    tracing::warn!(
+                        tenant_id = %tenant.tenant_id,
+                        error = %e,
+                        "Failed to send security alert digest"
+                    );
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn send_digest<S: SystemSettingsRepository>(
+        &self,
+        email_service: &EmailService<S>,
+        tenant: &DigestTenant,
+        policy: &SecurityDigestPolicy,
+        since: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let mut alerts = self
+            .repo
+            .alerts_since(
+                tenant.tenant_id,
+                since,
+                policy.min_severity.and_above(),
+                MAX_DIGEST_ALERTS + 1,
+            )
+            .await?;
+        let recipients = self.recipients(tenant.tenant_id, policy).await?;
+        if alerts.is_empty() || recipients.is_empty() {
+            self.repo.mark_sent(tenant.tenant_id, now).await?;
+            return Ok(0);
+        }
+        let truncated = alerts.len() as i64 > MAX_DIGEST_ALERTS;
+        alerts.truncate(MAX_DIGEST_ALERTS as usize);
+
+        let locale = i18n::resolve_locale(None, None, tenant.settings.default_locale.as_deref());
+        let period_name = match policy.frequency {
+            DigestFrequency::Daily => "day",
+            _ => "week",
+        };
+        let period_name = i18n::lookup(
+            locale,
+            &format!("email-notification-digest-period-{}", period_name),
+        )
+        .unwrap_or(period_name);
+        let affected_users = alerts
+            .iter()
+            .filter_map(|alert| alert.user_id)
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+
+        let mut sent = 0;
+        for recipient in &recipients {
+            let mut vars = HashMap::new();
+            vars.insert("tenant_name".to_string(), tenant.name.clone());
+            vars.insert("period".to_string(), period_name.to_string());
+            vars.insert("alert_count".to_string(), alerts.len().to_string());
+            vars.insert("affected_users".to_string(), affected_users.to_string());
+            vars.insert("min_severity".to_string(), policy.min_severity.to_string());
+            vars.insert(
+                "alert_summary".to_string(),
+                self.alert_summary(tenant.tenant_id, &alerts, truncated, recipient, locale, now),
+            );
+            vars.insert("year".to_string(), now.format("%Y").to_string());
+
+            let result = async {
+                let rendered = email_service
+                    .resolve_and_render_for_tenant(
+                        EmailTemplateType::SecurityAlertDigest,
+                        locale,
+                        Some(tenant.tenant_id),
+                        &vars,
+                    )
+                    .await?;
+                let message = EmailMessage::new(
+                    EmailAddress::new(recipient),
+                    rendered.subject,
+                    rendered.html_body,
+                )
+                .with_text_body(rendered.text_body);
+                email_service.send(&message, None).await
+            }
+            .await;
+            match result {
+                Ok(_) => sent += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        tenant_id = %tenant.tenant_id,
+                        error = %e,
+                        "Failed to send security alert digest to a recipient"
+                    );
+                }
+            }
+        }
+
+        if sent > 0 {
+            self.repo.mark_sent(tenant.tenant_id, now).await?;
+        }
+        Ok(sent)
+    }
+
+    /// Distribution list plus (when enabled) owners and admins, deduplicated
+    /// and lowercased
+    async fn recipients(
+        &self,
+        tenant_id: StringUuid,
+        policy: &SecurityDigestPolicy,
+    ) -> Result<Vec<String>> {
+        let mut recipients: Vec<String> = policy
+            .recipients
+            .iter()
+            .map(|email| email.to_lowercase())
+            .collect();
+        if policy.include_tenant_admins {
+            recipients.extend(
+                self.repo
+                    .admin_emails(tenant_id)
+                    .await?
+                    .into_iter()
+                    .map(|email| email.to_lowercase()),
+            );
+        }
+        recipients.sort();
+        recipients.dedup();
+        Ok(recipients)
+    }
+
+    /// Plain-text list of the alerts grouped by affected user, each user
+    /// with a portal link and action links signed for `recipient`
+    fn alert_summary(
+        &self,
+        tenant_id: StringUuid,
+        alerts: &[DigestAlert],
+        truncated: bool,
+        recipient: &str,
+        locale: Locale,
+        now: DateTime<Utc>,
+    ) -> String {
+        let label = |key: &str, default: &'static str| {
+            i18n::lookup(
+                locale,
+                &format!("email-security-alert-digest-label-{}", key),
+            )
+            .unwrap_or(default)
+        };
+
+        // Users in order of their newest alert; tenant-wide alerts last
+        let mut groups: Vec<(Option<StringUuid>, Vec<&DigestAlert>)> = Vec::new();
+        for alert in alerts {
+            match groups
+                .iter_mut()
+                .find(|(user_id, _)| *user_id == alert.user_id)
+            {
+                Some((_, group)) => group.push(alert),
+                None => groups.push((alert.user_id, vec![alert])),
+            }
+        }
+        groups.sort_by_key(|(user_id, _)| user_id.is_none());
+
+        let expires_at = now + Duration::hours(DIGEST_ACTION_TTL_HOURS);
+        let mut summary = String::new();
+        for (user_id, group) in groups {
+            let email = group[0].user_email.as_deref();
+            let heading = match (user_id, email) {
+                (Some(_), Some(email)) => email.to_string(),
+                (Some(user_id), None) => user_id.to_string(),
+                (None, _) => label("tenant-wide", "Tenant-wide").to_string(),
+            };
+            summary.push_str(&format!("{} ({})\n", heading, group.len()));
+            for alert in &group {
+                summary.push_str(&format!(
+                    "  {}  {}  {}\n",
+                    alert.created_at.format("%Y-%m-%d %H:%M UTC"),
+                    alert.alert_type,
+                    alert.severity
+                ));
+            }
+            // Deleted users have no profile and nothing to act on
+            if let (Some(user_id), Some(email)) = (user_id, email) {
+                if let Some(portal_url) = &self.portal_url {
+                    summary.push_str(&format!(
+                        "  {}: {}/dashboard/users?search={}\n",
+                        label("profile", "Profile"),
+                        portal_url,
+                        urlencoding::encode(email)
+                    ));
+                }
+                for (action, key, default) in [
+                    (
+                        DigestAction::ForceReset,
+                        "force-reset",
+                        "Force password reset",
+                    ),
+                    (
+                        DigestAction::RevokeSessions,
+                        "revoke-sessions",
+                        "Sign out everywhere",
+                    ),
+                ] {
+                    let url = self.action_url(&DigestActionClaims {
+                        tenant_id,
+                        user_id,
+                        action,
+                        recipient: recipient.to_string(),
+                        expires_at,
+                    });
+                    summary.push_str(&format!("  {}: {}\n", label(key, default), url));
+                }
+            }
+            summary.push('\n');
+        }
+        if truncated {
+            summary.push_str(label(
+                "truncated",
+                "More alerts were raised than fit in this email; see the security alerts page for all of them.",
+            ));
+            summary.push('\n');
+        }
+        summary.trim_end().to_string()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(self.signing_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(ACTION_TOKEN_CONTEXT);
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::analytics::{AlertSeverity, SecurityAlertType};
+    use crate::repository::security_digest::MockSecurityDigestRepository;
+
+    fn service(
+        repo: MockSecurityDigestRepository,
+    ) -> SecurityDigestService<MockSecurityDigestRepository> {
+        SecurityDigestService::new(
+            Arc::new(repo),
+            "digest-test-secret",
+            "https://auth9.example.com/",
+            Some("https://portal.example.com"),
+        )
+    }
+
+    fn claims(action: DigestAction) -> DigestActionClaims {
+        DigestActionClaims {
+            tenant_id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            action,
+            recipient: "soc@example.com".to_string(),
+            expires_at: Utc.timestamp_opt(Utc::now().timestamp() + 3600, 0).unwrap(),
+        }
+    }
+
+    fn settings(recipients: &[&str], include_tenant_admins: bool) -> TenantSettings {
+        TenantSettings {
+            security_digest: Some(
+                serde_json::from_value(serde_json::json!({
+                    "enabled": true,
+                    "frequency": "daily",
+                    "recipients": recipients,
+                    "include_tenant_admins": include_tenant_admins,
+                }))
+                .unwrap(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn alert(user_id: Option<StringUuid>, email: Option<&str>) -> DigestAlert {
+        DigestAlert {
+            id: StringUuid::new_v4(),
+            user_id,
+            user_email: email.map(str::to_string),
+            alert_type: SecurityAlertType::ImpossibleTravel,
+            severity: AlertSeverity::High,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_action_token_round_trip_and_expiry() {
+        let service = service(MockSecurityDigestRepository::new());
+        let claims = claims(DigestAction::ForceReset);
+        let token = service.action_token(&claims);
+
+        assert_eq!(
+            service.verify_action_token(&token, Utc::now()).unwrap(),
+            claims
+        );
+        assert!(service
+            .verify_action_token(&token, claims.expires_at)
+            .is_err());
+    }
+
+    #[test]
+    fn test_action_token_rejects_tampering_and_other_secrets() {
+        let service = service(MockSecurityDigestRepository::new());
+        let token = service.action_token(&claims(DigestAction::RevokeSessions));
+        let (payload, signature) = token.split_once('.').unwrap();
+
+        let forged_payload = URL_SAFE_NO_PAD.encode(
+            String::from_utf8(URL_SAFE_NO_PAD.decode(payload).unwrap())
+                .unwrap()
+                .replace("revoke_sessions", "force_reset"),
+        );
+        let forged = format!("{}.{}", forged_payload, signature);
+        assert!(service.verify_action_token(&forged, Utc::now()).is_err());
+
+        let other = SecurityDigestService::new(
+            Arc::new(MockSecurityDigestRepository::new()),
+            "other-secret",
+            "https://auth9.example.com",
+            None,
+        );
+        assert!(other.verify_action_token(&token, Utc::now()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_action_requires_recipient_and_membership() {
+        let mut repo = MockSecurityDigestRepository::new();
+        repo.expect_admin_emails()
+            .returning(|_| Ok(vec!["Owner@example.com".to_string()]));
+        repo.expect_is_member().returning(|_, _| Ok(true));
+        let service = service(repo);
+
+        let mut listed = claims(DigestAction::ForceReset);
+        assert!(service
+            .authorize_action(&listed, &settings(&["SOC@example.com"], false))
+            .await
+            .is_ok());
+
+        // Admins only receive the digest while included
+        listed.recipient = "owner@example.com".to_string();
+        assert!(service
+            .authorize_action(&listed, &settings(&[], true))
+            .await
+            .is_ok());
+        assert!(service
+            .authorize_action(&listed, &settings(&[], false))
+            .await
+            .is_err());
+        assert!(service
+            .authorize_action(&listed, &TenantSettings::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_action_rejects_former_members() {
+        let mut repo = MockSecurityDigestRepository::new();
+        repo.expect_is_member().returning(|_, _| Ok(false));
+        let service = service(repo);
+
+        let result = service
+            .authorize_action(
+                &claims(DigestAction::RevokeSessions),
+                &settings(&["soc@example.com"], false),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[test]
+    fn test_alert_summary_groups_by_user_with_links() {
+        let service = service(MockSecurityDigestRepository::new());
+        let tenant_id = StringUuid::new_v4();
+        let user_id = StringUuid::new_v4();
+        let alerts = vec![
+            alert(None, None),
+            alert(Some(user_id), Some("jane@example.com")),
+            alert(Some(user_id), Some("jane@example.com")),
+        ];
+
+        let summary = service.alert_summary(
+            tenant_id,
+            &alerts,
+            false,
+            "soc@example.com",
+            Locale::EnUs,
+            Utc::now(),
+        );
+        assert!(summary.starts_with("jane@example.com (2)"));
+        assert!(summary.contains("impossible_travel  high"));
+        assert!(summary.contains(
+            "Profile: https://portal.example.com/dashboard/users?search=jane%40example.com"
+        ));
+        assert!(summary.contains("Tenant-wide (1)"));
+
+        let tokens: Vec<&str> = summary
+            .lines()
+            .filter_map(|line| line.split_once("/api/v1/security-digest/actions?token="))
+            .map(|(_, token)| token)
+            .collect();
+        assert_eq!(tokens.len(), 2);
+        let claims = service.verify_action_token(tokens[1], Utc::now()).unwrap();
+        assert_eq!(claims.tenant_id, tenant_id);
+        assert_eq!(claims.user_id, user_id);
+        assert_eq!(claims.action, DigestAction::RevokeSessions);
+        assert_eq!(claims.recipient, "soc@example.com");
+    }
+}
@@ -0,0 +1,367 @@
+//! Tenant admin unit APIs (delegated user administration boundaries)
+
+use crate::error::Result;
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::models::admin_unit::{
+    AdminUnit, AdminUnitResponse, AdminUnitUsersInput, CreateAdminUnitInput, UpdateAdminUnitInput,
+};
+use crate::models::common::StringUuid;
+use crate::policy::{AuthzContext, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::HasServices;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+/// Tenant admins may view admin units
+async fn ensure_can_read<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: Uuid,
+) -> Result<()> {
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::UserTenantRead,
+                scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+            },
+        )
+        .await
+}
+
+/// Only tenant owners define admin units, so delegated admins cannot widen
+/// their own boundaries
+async fn ensure_can_write<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: Uuid,
+) -> Result<()> {
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+            },
+        )
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/admin-units",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Admin units of the tenant", body = Vec<AdminUnit>)
+    )
+)]
+pub async fn list<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<AdminUnit>>>> {
+    ensure_can_read(&state, &authz, tenant_id).await?;
+    let units = state
+        .admin_unit_service()
+        .list(StringUuid::from(tenant_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(units)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/admin-units",
+    tag = "Tenant Access",
+    request_body = CreateAdminUnitInput,
+    responses(
+        (status = 201, description = "Admin unit created", body = AdminUnit),
+        (status = 409, description = "An admin unit with this name exists")
+    )
+)]
+pub async fn create<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<CreateAdminUnitInput>,
+) -> Result<impl IntoResponse> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    let unit = state
+        .admin_unit_service()
+        .create(StringUuid::from(tenant_id), input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.create",
+        "admin_unit",
+        Some(*unit.id),
+        None,
+        serde_json::to_value(&unit).ok(),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(unit))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Admin unit with its admins and members", body = AdminUnitResponse),
+        (status = 404, description = "Admin unit not found")
+    )
+)]
+pub async fn get<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path((tenant_id, unit_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<AdminUnitResponse>>> {
+    ensure_can_read(&state, &authz, tenant_id).await?;
+    let unit = state
+        .admin_unit_service()
+        .get(StringUuid::from(tenant_id), StringUuid::from(unit_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(unit)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}",
+    tag = "Tenant Access",
+    request_body = UpdateAdminUnitInput,
+    responses(
+        (status = 200, description = "Admin unit updated", body = AdminUnit),
+        (status = 409, description = "An admin unit with this name exists")
+    )
+)]
+pub async fn update<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UpdateAdminUnitInput>,
+) -> Result<Json<SuccessResponse<AdminUnit>>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    let unit = state
+        .admin_unit_service()
+        .update(
+            StringUuid::from(tenant_id),
+            StringUuid::from(unit_id),
+            input,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.update",
+        "admin_unit",
+        Some(unit_id),
+        None,
+        serde_json::to_value(&unit).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(unit)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Admin unit deleted; its admins manage no unit of it anymore")
+    )
+)]
+pub async fn delete<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    let unit = state
+        .admin_unit_service()
+        .delete(StringUuid::from(tenant_id), StringUuid::from(unit_id))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.delete",
+        "admin_unit",
+        Some(unit_id),
+        serde_json::to_value(&unit).ok(),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Admin unit deleted")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/members",
+    tag = "Tenant Access",
+    request_body = AdminUnitUsersInput,
+    responses(
+        (status = 200, description = "Members added", body = AdminUnitResponse),
+        (status = 400, description = "A user is not a member of the tenant")
+    )
+)]
+pub async fn add_members<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<AdminUnitUsersInput>,
+) -> Result<Json<SuccessResponse<AdminUnitResponse>>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    let added = serde_json::json!({ "member_ids": input.user_ids });
+    let unit = state
+        .admin_unit_service()
+        .add_members(
+            StringUuid::from(tenant_id),
+            StringUuid::from(unit_id),
+            input,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.members.add",
+        "admin_unit",
+        Some(unit_id),
+        None,
+        Some(added),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(unit)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/members/{user_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Member removed")
+    )
+)]
+pub async fn remove_member<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id, user_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    state
+        .admin_unit_service()
+        .remove_member(
+            StringUuid::from(tenant_id),
+            StringUuid::from(unit_id),
+            StringUuid::from(user_id),
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.members.remove",
+        "admin_unit",
+        Some(unit_id),
+        Some(serde_json::json!({ "member_id": user_id })),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Member removed from admin unit")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/admins",
+    tag = "Tenant Access",
+    request_body = AdminUnitUsersInput,
+    responses(
+        (status = 200, description = "Delegated admins added", body = AdminUnitResponse),
+        (status = 400, description = "A user is not a member of the tenant")
+    )
+)]
+pub async fn add_admins<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<AdminUnitUsersInput>,
+) -> Result<Json<SuccessResponse<AdminUnitResponse>>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    let added = serde_json::json!({ "admin_ids": input.user_ids });
+    let unit = state
+        .admin_unit_service()
+        .add_admins(
+            StringUuid::from(tenant_id),
+            StringUuid::from(unit_id),
+            input,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.admins.add",
+        "admin_unit",
+        Some(unit_id),
+        None,
+        Some(added),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(unit)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/admins/{user_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Delegated admin removed")
+    )
+)]
+pub async fn remove_admin<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path((tenant_id, unit_id, user_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_can_write(&state, &authz, tenant_id).await?;
+    state
+        .admin_unit_service()
+        .remove_admin(
+            StringUuid::from(tenant_id),
+            StringUuid::from(unit_id),
+            StringUuid::from(user_id),
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.admin_unit.admins.remove",
+        "admin_unit",
+        Some(unit_id),
+        Some(serde_json::json!({ "admin_id": user_id })),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Admin removed from admin unit")))
+}
@@ -0,0 +1,306 @@
+//! Tenant custom domain APIs (hosted login pages on `auth.customer.com`).
+
+use crate::crypto::EncryptionKey;
+use crate::domains::tenant_access::service::custom_domain::{CustomDomainService, DohTxtResolver};
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::custom_domain::{
+    CreateCustomDomainInput, CustomDomainResponse, CustomDomainTenant, HostedDomainInfo,
+    TlsAskResponse, UploadCertificateInput,
+};
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::custom_domain::CustomDomainRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Extension, Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+fn custom_domain_service<S: HasServices + HasDbPool>(
+    state: &S,
+) -> CustomDomainService<CustomDomainRepositoryImpl> {
+    let config = &state.config().custom_domain;
+    CustomDomainService::new(
+        Arc::new(CustomDomainRepositoryImpl::new(state.db_pool().clone())),
+        Arc::new(DohTxtResolver::new(config)),
+        EncryptionKey::from_config(&state.config().settings_encryption).ok(),
+        config.cname_target.clone(),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Custom domains with DNS setup records", body = Vec<CustomDomainResponse>)
+    )
+)]
+pub async fn list<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<CustomDomainResponse>>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let domains = custom_domain_service(&state)
+        .list(StringUuid::from(tenant_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(domains)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains",
+    tag = "Tenant Access",
+    request_body = CreateCustomDomainInput,
+    responses(
+        (status = 200, description = "Custom domain registered (pending verification)", body = CustomDomainResponse),
+        (status = 409, description = "Domain already registered")
+    )
+)]
+pub async fn create<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<CreateCustomDomainInput>,
+) -> Result<Json<SuccessResponse<CustomDomainResponse>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let created = custom_domain_service(&state)
+        .create(StringUuid::from(tenant_id), input)
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.custom_domain.create",
+        "custom_domain",
+        Some(*created.domain.id),
+        None,
+        serde_json::to_value(&created.domain).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(created)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}/verify",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Verification attempted; see `status`", body = CustomDomainResponse)
+    )
+)]
+pub async fn verify<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, domain_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<CustomDomainResponse>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let verified = custom_domain_service(&state)
+        .verify(StringUuid::from(tenant_id), StringUuid::from(domain_id))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.custom_domain.verify",
+        "custom_domain",
+        Some(domain_id),
+        None,
+        Some(serde_json::json!({ "status": verified.domain.status })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(verified)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}/certificate",
+    tag = "Tenant Access",
+    request_body = UploadCertificateInput,
+    responses(
+        (status = 200, description = "Certificate stored", body = CustomDomainResponse)
+    )
+)]
+pub async fn upload_certificate<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, domain_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<UploadCertificateInput>,
+) -> Result<Json<SuccessResponse<CustomDomainResponse>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let updated = custom_domain_service(&state)
+        .upload_certificate(
+            StringUuid::from(tenant_id),
+            StringUuid::from(domain_id),
+            input,
+        )
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.custom_domain.certificate.upload",
+        "custom_domain",
+        Some(domain_id),
+        None,
+        Some(serde_json::json!({
+            "certificate_expires_at": updated.domain.certificate_expires_at,
+        })),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}/certificate",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Uploaded certificate removed; ACME issuance resumes", body = CustomDomainResponse)
+    )
+)]
+pub async fn delete_certificate<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, domain_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<CustomDomainResponse>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let updated = custom_domain_service(&state)
+        .use_acme(StringUuid::from(tenant_id), StringUuid::from(domain_id))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.custom_domain.certificate.delete",
+        "custom_domain",
+        Some(domain_id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Custom domain removed")
+    )
+)]
+pub async fn delete<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, domain_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let removed = custom_domain_service(&state)
+        .delete(StringUuid::from(tenant_id), StringUuid::from(domain_id))
+        .await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.custom_domain.delete",
+        "custom_domain",
+        Some(domain_id),
+        serde_json::to_value(&removed).ok(),
+        None,
+    )
+    .await;
+
+    Ok(Json(MessageResponse::new("Custom domain deleted")))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TlsAskQuery {
+    pub domain: String,
+}
+
+/// On-demand TLS permission check for the edge proxy (e.g. Caddy `on_demand_tls.ask`).
+///
+/// Returns 200 only for verified custom domains, so ACME certificates are never
+/// requested for hostnames Auth9 does not serve.
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/custom-domains/tls-ask",
+    tag = "Tenant Access",
+    params(TlsAskQuery),
+    responses(
+        (status = 200, description = "Domain is verified; certificate may be issued", body = TlsAskResponse),
+        (status = 404, description = "Unknown or unverified domain")
+    )
+)]
+pub async fn tls_ask<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(query): Query<TlsAskQuery>,
+) -> Result<Json<TlsAskResponse>> {
+    let answer = custom_domain_service(&state).tls_ask(&query.domain).await?;
+    Ok(Json(answer))
+}
+
+/// Resolve the tenant for the custom domain the request was made on.
+///
+/// Hosted login pages call this to discover which tenant they are serving.
+#[utoipa::path(
+    get,
+    path = "/api/v1/public/custom-domains/current",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Tenant served on this host", body = HostedDomainInfo),
+        (status = 404, description = "Request host is not a verified custom domain")
+    )
+)]
+pub async fn current<S: HasServices>(
+    State(state): State<S>,
+    custom_domain: Option<Extension<CustomDomainTenant>>,
+) -> Result<Json<SuccessResponse<HostedDomainInfo>>> {
+    let Some(Extension(resolved)) = custom_domain else {
+        return Err(AppError::NotFound(
+            "Request host is not a custom domain".to_string(),
+        ));
+    };
+    let tenant = state.tenant_service().get(resolved.tenant_id).await?;
+    Ok(Json(SuccessResponse::new(HostedDomainInfo {
+        domain: resolved.domain,
+        tenant_id: tenant.id,
+        tenant_slug: tenant.slug,
+        tenant_name: tenant.name,
+        logo_url: tenant.logo_url,
+    })))
+}
+
+async fn ensure_tenant_access<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: Uuid,
+) -> Result<()> {
+    policy::enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::TenantWrite,
+            scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+        },
+    )
+    .await
+}
@@ -0,0 +1,58 @@
+//! Tenant inactivity policy APIs
+
+use crate::domains::tenant_access::service::InactivityService;
+use crate::error::{AppError, Result};
+use crate::http_support::SuccessResponse;
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::inactivity::InactivityPreview;
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::inactivity::InactivityRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Preview the tenant's inactivity policy
+///
+/// Lists the inactive members the configured policy (enabled or not) would
+/// warn, lock or delete, and when.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/inactivity-policy/preview",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Inactive members and their next lifecycle step", body = InactivityPreview),
+        (status = 404, description = "Tenant has no inactivity policy")
+    )
+)]
+pub async fn preview<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<InactivityPreview>>> {
+    let tenant_id = StringUuid::from(tenant_id);
+    policy::enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::UserTenantRead,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+    .await?;
+
+    let tenant = state.tenant_service().get(tenant_id).await?;
+    let policy = tenant.settings.inactivity_policy.ok_or_else(|| {
+        AppError::NotFound("Tenant has no inactivity policy configured".to_string())
+    })?;
+    let preview = InactivityService::new(Arc::new(InactivityRepositoryImpl::new(
+        state.db_pool().clone(),
+    )))
+    .preview(tenant_id, policy, chrono::Utc::now())
+    .await?;
+    Ok(Json(SuccessResponse::new(preview)))
+}
@@ -55,6 +55,37 @@ fn default_per_page() -> i64 {
     20
 }
 
+/// Check that all role IDs exist and belong to services within the target tenant
+pub(crate) async fn validate_tenant_role_ids<S: HasInvitations>(
+    state: &S,
+    tenant_id: StringUuid,
+    role_ids: &[StringUuid],
+) -> Result<()> {
+    for role_id in role_ids {
+        let role = state
+            .rbac_service()
+            .get_role(*role_id)
+            .await
+            .map_err(|_| AppError::BadRequest(format!("Role '{}' does not exist", role_id)))?;
+        let service = state
+            .client_service()
+            .get(*role.service_id)
+            .await
+            .map_err(|_| {
+                AppError::BadRequest(format!("Service for role '{}' does not exist", role_id))
+            })?;
+        if let Some(ref svc_tenant_id) = service.tenant_id {
+            if *svc_tenant_id != tenant_id {
+                return Err(AppError::BadRequest(format!(
+                    "Role '{}' belongs to a service in a different tenant",
+                    role_id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// List invitations for a tenant
 #[utoipa::path(
     get,
@@ -135,29 +166,7 @@ pub async fn create<S: HasInvitations>(
     // TODO: Get inviter name from user service
     let inviter_name = "Admin"; // Placeholder
 
-    // Validate that all role_ids exist and belong to services within the target tenant
-    for role_id in &input.role_ids {
-        let role = state
-            .rbac_service()
-            .get_role(*role_id)
-            .await
-            .map_err(|_| AppError::BadRequest(format!("Role '{}' does not exist", role_id)))?;
-        let service = state
-            .client_service()
-            .get(*role.service_id)
-            .await
-            .map_err(|_| {
-                AppError::BadRequest(format!("Service for role '{}' does not exist", role_id))
-            })?;
-        if let Some(ref svc_tenant_id) = service.tenant_id {
-            if *svc_tenant_id != tenant_id {
-                return Err(AppError::BadRequest(format!(
-                    "Role '{}' belongs to a service in a different tenant",
-                    role_id
-                )));
-            }
-        }
-    }
+    validate_tenant_role_ids(&state, tenant_id, &input.role_ids).await?;
 
     // Prevent inviting users who are already members of the tenant
     match state.user_service().get_by_email(&input.email).await {
@@ -0,0 +1,356 @@
+//! Shareable invitation link API handlers
+
+use super::invitation::validate_tenant_role_ids;
+use crate::domains::tenant_access::service::InvitationLinkService;
+use crate::error::{AppError, Result};
+use crate::http_support::{
+    write_audit_log_generic, PaginatedResponse, PaginationQuery, SuccessResponse,
+};
+use crate::middleware::auth::{AuthUser, TokenType};
+use crate::models::common::StringUuid;
+use crate::models::invitation_link::{
+    CreateInvitationLinkInput, CreatedInvitationLink, InvitationLinkResponse, InvitationLinkStatus,
+    JoinInvitationLinkInput,
+};
+use crate::models::rbac::AssignRolesInput;
+use crate::models::user::AddUserToTenantInput;
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::state::{HasDbPool, HasInvitations};
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+fn link_service<S: HasDbPool>(state: &S) -> InvitationLinkService {
+    InvitationLinkService::from_pool(state.db_pool().clone())
+}
+
+async fn enforce_tenant<S: HasInvitations>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: StringUuid,
+    action: PolicyAction,
+) -> Result<()> {
+    policy::enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+    .await
+}
+
+/// Create a shareable invitation link
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/invitation-links",
+    tag = "Tenant Access",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    request_body = CreateInvitationLinkInput,
+    responses(
+        (status = 201, description = "Link created; the token is only returned once", body = CreatedInvitationLink)
+    )
+)]
+pub async fn create<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<CreateInvitationLinkInput>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    enforce_tenant(&state, &auth, tenant_id, PolicyAction::InvitationWrite).await?;
+    state.tenant_service().require_active(tenant_id).await?;
+    validate_tenant_role_ids(&state, tenant_id, &input.role_ids).await?;
+
+    let (link, token) = link_service(&state)
+        .create(tenant_id, StringUuid::from(auth.user_id), input)
+        .await?;
+    let response = InvitationLinkResponse::from(link);
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "invitation_link.created",
+        "invitation_link",
+        Some(*response.id),
+        None,
+        serde_json::to_value(&response).ok(),
+    )
+    .await;
+
+    let url = format!(
+        "{}/invite/join?token={}",
+        state
+            .invitation_service()
+            .app_base_url()
+            .trim_end_matches('/'),
+        token
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(SuccessResponse::new(CreatedInvitationLink {
+            link: response,
+            token,
+            url,
+        })),
+    ))
+}
+
+/// List a tenant's invitation links
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/invitation-links",
+    tag = "Tenant Access",
+    params(("tenant_id" = String, Path, description = "Tenant ID (UUID)")),
+    responses(
+        (status = 200, description = "Invitation links, newest first")
+    )
+)]
+pub async fn list<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    enforce_tenant(&state, &auth, tenant_id, PolicyAction::InvitationRead).await?;
+
+    let (links, total) = link_service(&state)
+        .list_by_tenant(tenant_id, pagination.page, pagination.per_page)
+        .await?;
+    let items: Vec<InvitationLinkResponse> = links.into_iter().map(Into::into).collect();
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+/// Revoke an invitation link
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/invitation-links/{id}/revoke",
+    tag = "Tenant Access",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("id" = String, Path, description = "Invitation link ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Link revoked", body = InvitationLinkResponse),
+        (status = 404, description = "Invitation link not found")
+    )
+)]
+pub async fn revoke<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    enforce_tenant(&state, &auth, tenant_id, PolicyAction::InvitationWrite).await?;
+
+    let service = link_service(&state);
+    let link = service.get(StringUuid::from(id)).await?;
+    if link.tenant_id != tenant_id {
+        return Err(AppError::NotFound(format!(
+            "Invitation link {} not found",
+            id
+        )));
+    }
+    let link = service.revoke(link.id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "invitation_link.revoked",
+        "invitation_link",
+        Some(id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(InvitationLinkResponse::from(
+        link,
+    ))))
+}
+
+/// Users who joined through an invitation link
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/invitation-links/{id}/redemptions",
+    tag = "Tenant Access",
+    params(
+        ("tenant_id" = String, Path, description = "Tenant ID (UUID)"),
+        ("id" = String, Path, description = "Invitation link ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Joins through the link, newest first")
+    )
+)]
+pub async fn list_redemptions<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path((tenant_id, id)): Path<(Uuid, Uuid)>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    let tenant_id = StringUuid::from(tenant_id);
+    enforce_tenant(&state, &auth, tenant_id, PolicyAction::InvitationRead).await?;
+
+    let service = link_service(&state);
+    let link = service.get(StringUuid::from(id)).await?;
+    if link.tenant_id != tenant_id {
+        return Err(AppError::NotFound(format!(
+            "Invitation link {} not found",
+            id
+        )));
+    }
+    let (items, total) = service
+        .list_redemptions(link.id, pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        items,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateLinkQuery {
+    pub token: String,
+}
+
+/// Public summary of an invitation link
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvitationLinkPreview {
+    pub status: InvitationLinkStatus,
+    pub tenant_id: StringUuid,
+    pub tenant_name: String,
+    pub allowed_email_domains: Vec<String>,
+}
+
+/// Check an invitation link before joining (public endpoint)
+#[utoipa::path(
+    get,
+    path = "/api/v1/invitation-links/validate",
+    tag = "Tenant Access",
+    params(("token" = String, Query, description = "Invitation link token")),
+    responses(
+        (status = 200, description = "Link status", body = InvitationLinkPreview),
+        (status = 404, description = "Unknown link")
+    )
+)]
+pub async fn validate_token<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    Query(params): Query<ValidateLinkQuery>,
+) -> Result<impl IntoResponse> {
+    if params.token.is_empty() {
+        return Err(AppError::Validation("Token is required".to_string()));
+    }
+    let link = link_service(&state).get_by_token(&params.token).await?;
+    let tenant = state.tenant_service().get(link.tenant_id).await?;
+
+    Ok(Json(SuccessResponse::new(InvitationLinkPreview {
+        status: link.status(),
+        tenant_id: link.tenant_id,
+        tenant_name: tenant.name,
+        allowed_email_domains: link.allowed_email_domains,
+    })))
+}
+
+/// Join a tenant through an invitation link as the signed-in user
+#[utoipa::path(
+    post,
+    path = "/api/v1/invitation-links/join",
+    tag = "Tenant Access",
+    request_body = JoinInvitationLinkInput,
+    responses(
+        (status = 200, description = "Joined the tenant"),
+        (status = 400, description = "Link expired, used up or revoked"),
+        (status = 403, description = "Email domain not allowed"),
+        (status = 409, description = "Already a member")
+    )
+)]
+pub async fn join<S: HasInvitations + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<JoinInvitationLinkInput>,
+) -> Result<impl IntoResponse> {
+    if auth.token_type == TokenType::ServiceClient {
+        return Err(AppError::Forbidden(
+            "Service clients cannot join tenants".to_string(),
+        ));
+    }
+    if input.token.is_empty() {
+        return Err(AppError::Validation("Token is required".to_string()));
+    }
+
+    let service = link_service(&state);
+    let link = service.get_by_token(&input.token).await?;
+    state
+        .tenant_service()
+        .require_active(link.tenant_id)
+        .await?;
+
+    let user = state
+        .user_service()
+        .get(StringUuid::from(auth.user_id))
+        .await?;
+    InvitationLinkService::check_joinable(&link, &user.email)?;
+
+    let tenant_users = state.user_service().get_user_tenants(user.id).await?;
+    if tenant_users.iter().any(|tu| tu.tenant_id == link.tenant_id) {
+        return Err(AppError::Conflict(
+            "You are already a member of this tenant".to_string(),
+        ));
+    }
+
+    let redemption = service.redeem(&link, user.id, &user.email).await?;
+
+    state
+        .user_service()
+        .add_to_tenant(AddUserToTenantInput {
+            user_id: *user.id,
+            tenant_id: *link.tenant_id,
+            role_in_tenant: "member".to_string(),
+        })
+        .await?;
+    if !link.role_ids.is_empty() {
+        state
+            .rbac_service()
+            .assign_roles(
+                AssignRolesInput {
+                    user_id: *user.id,
+                    tenant_id: *link.tenant_id,
+                    role_ids: link.role_ids.iter().map(|id| **id).collect(),
+                    service_id: None,
+                },
+                Some(link.created_by),
+            )
+            .await?;
+    }
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "invitation_link.joined",
+        "invitation_link",
+        Some(*link.id),
+        None,
+        serde_json::to_value(&redemption).ok(),
+    )
+    .await;
+
+    Ok(Json(SuccessResponse::new(redemption)))
+}
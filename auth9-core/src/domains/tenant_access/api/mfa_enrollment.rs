@@ -0,0 +1,83 @@
+//! MFA enrollment campaign APIs
+
+use crate::domains::tenant_access::service::mfa_enrollment::{
+    enrollment_requirements, MfaEnrollmentService,
+};
+use crate::error::{AppError, Result};
+use crate::http_support::SuccessResponse;
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::mfa_enrollment::{MfaCampaignProgress, MfaEnrollmentRequirement};
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::mfa_enrollment::MfaEnrollmentRepositoryImpl;
+use crate::repository::MfaEnrollmentRepository;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+/// Progress of the tenant's MFA enrollment campaign
+///
+/// Counts enrolled, not yet enrolled and exempt members and lists the
+/// members still to enroll.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/mfa-enrollment-campaign/progress",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Campaign progress", body = MfaCampaignProgress),
+        (status = 404, description = "Tenant has no MFA enrollment campaign")
+    )
+)]
+pub async fn progress<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<MfaCampaignProgress>>> {
+    let tenant_id = StringUuid::from(tenant_id);
+    policy::enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::UserTenantRead,
+            scope: ResourceScope::Tenant(tenant_id),
+        },
+    )
+    .await?;
+
+    let tenant = state.tenant_service().get(tenant_id).await?;
+    let campaign = tenant.settings.mfa_enrollment_campaign.ok_or_else(|| {
+        AppError::NotFound("Tenant has no MFA enrollment campaign configured".to_string())
+    })?;
+    let progress = MfaEnrollmentService::from_pool(state.db_pool().clone())
+        .progress(tenant_id, campaign, chrono::Utc::now())
+        .await?;
+    Ok(Json(SuccessResponse::new(progress)))
+}
+
+/// MFA enrollment required by the caller's tenants
+///
+/// One entry per tenant with an enabled campaign, with the deadline and
+/// whether the caller still has to enroll.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/mfa-enrollment",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Campaign requirements", body = Vec<MfaEnrollmentRequirement>)
+    )
+)]
+pub async fn my_requirements<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+) -> Result<Json<SuccessResponse<Vec<MfaEnrollmentRequirement>>>> {
+    let user_id = StringUuid::from(auth.user_id);
+    let enrolled = MfaEnrollmentRepositoryImpl::new(state.db_pool().clone())
+        .is_enrolled(user_id)
+        .await?;
+    let requirements =
+        enrollment_requirements(&state, user_id, enrolled, chrono::Utc::now()).await?;
+    Ok(Json(SuccessResponse::new(requirements)))
+}
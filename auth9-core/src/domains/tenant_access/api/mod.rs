@@ -1,9 +1,17 @@
 //! Tenant access domain API facade.
 
+pub mod admin_unit;
+pub mod custom_domain;
+pub mod inactivity;
 pub mod invitation;
+pub mod invitation_link;
+pub mod mfa_enrollment;
+pub mod notification_preference;
 pub mod organization;
 pub mod saml_application;
 pub mod tenant;
+pub mod tenant_key;
 pub mod tenant_ldap_group_mappings;
+pub mod tenant_ldap_sync;
 pub mod tenant_sso;
 pub mod user;
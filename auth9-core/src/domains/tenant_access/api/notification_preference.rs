@@ -0,0 +1,177 @@
+//! User notification preference API handlers
+
+use super::user::{
+    ensure_user_in_admin_units, ensure_user_in_caller_tenant, require_user_management_permission,
+};
+use crate::domains::platform::service::NotificationPreferenceService;
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::models::common::StringUuid;
+use crate::models::notification_preference::{
+    NotificationCategory, NotificationPreferences, UpdateNotificationPreferencesInput,
+};
+use crate::policy::{AuthzContext, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::notification_preference::NotificationPreferenceRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+fn notification_preference_service<S: HasServices + HasDbPool>(
+    state: &S,
+) -> NotificationPreferenceService<NotificationPreferenceRepositoryImpl> {
+    NotificationPreferenceService::from_config(state.db_pool().clone(), state.config())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
+
+/// Result of an unsubscribe link lookup or confirmation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UnsubscribeResponse {
+    pub category: NotificationCategory,
+    pub subscribed: bool,
+}
+
+/// Get a user's notification preferences
+/// Users can read their own preferences; admins with user:read can read others'
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/notification-preferences",
+    tag = "Tenant Access",
+    params(("id" = String, Path, description = "User ID (UUID)")),
+    responses(
+        (status = 200, description = "Notification preferences", body = NotificationPreferences),
+        (status = 403, description = "Forbidden")
+    )
+)]
+pub async fn get_notification_preferences<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<NotificationPreferences>>> {
+    if authz.auth().user_id != id {
+        authz
+            .enforce(
+                &state,
+                &PolicyInput {
+                    action: PolicyAction::UserReadOther,
+                    scope: ResourceScope::User(StringUuid::from(id)),
+                },
+            )
+            .await?;
+        ensure_user_in_caller_tenant(&state, &authz, id).await?;
+    }
+
+    let user_id = StringUuid::from(id);
+    state.user_service().get(user_id).await?;
+    let prefs = notification_preference_service(&state).get(user_id).await?;
+    Ok(Json(SuccessResponse::new(prefs)))
+}
+
+/// Update a user's notification preferences
+/// Users can update their own preferences; updating others' requires user management
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{id}/notification-preferences",
+    tag = "Tenant Access",
+    params(("id" = String, Path, description = "User ID (UUID)")),
+    request_body = UpdateNotificationPreferencesInput,
+    responses(
+        (status = 200, description = "Updated notification preferences", body = NotificationPreferences),
+        (status = 403, description = "Forbidden")
+    )
+)]
+pub async fn update_notification_preferences<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<UpdateNotificationPreferencesInput>,
+) -> Result<Json<SuccessResponse<NotificationPreferences>>> {
+    if authz.auth().user_id != id {
+        require_user_management_permission(state.config(), authz.auth())?;
+        ensure_user_in_caller_tenant(&state, &authz, id).await?;
+        ensure_user_in_admin_units(&state, &authz, id).await?;
+    }
+
+    let user_id = StringUuid::from(id);
+    state.user_service().get(user_id).await?;
+    let service = notification_preference_service(&state);
+    let before = service.get(user_id).await?;
+    let prefs = service.update(user_id, input).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.notification_preferences.update",
+        "user",
+        Some(id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&prefs).ok(),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(prefs)))
+}
+
+/// Look up an unsubscribe link (public endpoint)
+///
+/// Does not change anything, so link scanners opening the URL are harmless.
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/unsubscribe",
+    tag = "Tenant Access",
+    params(("token" = String, Query, description = "Unsubscribe token from the email")),
+    responses(
+        (status = 200, description = "Category and current subscription state", body = UnsubscribeResponse),
+        (status = 400, description = "Invalid token")
+    )
+)]
+pub async fn get_unsubscribe<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Json<SuccessResponse<UnsubscribeResponse>>> {
+    let service = notification_preference_service(&state);
+    let (user_id, category) = service.verify_unsubscribe_token(&query.token)?;
+    let prefs = service.get(user_id).await?;
+    Ok(Json(SuccessResponse::new(UnsubscribeResponse {
+        category,
+        subscribed: prefs.allows(category),
+    })))
+}
+
+/// Unsubscribe from a notification category (public endpoint)
+///
+/// Also the target of one-click `List-Unsubscribe-Post` requests (RFC 8058),
+/// so the request body is ignored.
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/unsubscribe",
+    tag = "Tenant Access",
+    params(("token" = String, Query, description = "Unsubscribe token from the email")),
+    responses(
+        (status = 200, description = "Unsubscribed", body = UnsubscribeResponse),
+        (status = 400, description = "Invalid token")
+    )
+)]
+pub async fn unsubscribe<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Json<SuccessResponse<UnsubscribeResponse>>> {
+    if query.token.is_empty() {
+        return Err(AppError::Validation("Token is required".to_string()));
+    }
+    let (category, prefs) = notification_preference_service(&state)
+        .unsubscribe(&query.token)
+        .await?;
+    Ok(Json(SuccessResponse::new(UnsubscribeResponse {
+        category,
+        subscribed: prefs.allows(category),
+    })))
+}
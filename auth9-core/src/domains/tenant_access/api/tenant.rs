@@ -1,30 +1,47 @@
 //! Tenant API handlers
 
+use crate::domains::authorization::api::service::expand_service;
+use crate::domains::platform::api::billing::record_billing_event;
+use crate::domains::platform::api::job::job_service;
+use crate::domains::tenant_access::service::sagas::run_tenant_provision;
 use crate::error::{AppError, Result};
+use crate::http_support::deprecation::tenant_slug_redirect_headers;
+use crate::http_support::etag::{if_match_version, with_etag};
 use crate::http_support::{
-    deserialize_page, deserialize_per_page, extract_actor_id_generic, extract_ip,
-    require_platform_admin_identity, write_audit_log_generic, MessageResponse, PaginatedResponse,
-    SuccessResponse,
+    extract_actor_id_generic, extract_ip, require_platform_admin_identity, write_audit_log_generic,
+    MessageResponse, PaginatedResponse, SuccessResponse,
 };
-use crate::middleware::auth::AuthUser;
+use crate::middleware::auth::{AuthUser, TokenType};
+use crate::models::billing::NewBillingEvent;
 use crate::models::common::StringUuid;
+use crate::models::effective_settings::{EffectiveSettings, EffectiveSettingsQuery};
+use crate::models::expand::{
+    expanded, expanded_list, expansion_forbidden, ExpandParams, ExpandQuery, MAX_EXPANDED_ITEMS,
+};
+use crate::models::job::{CreateJobInput, JobKind, JobResponse};
+use crate::models::list_query::{ListQuery, ListQueryParams};
 use crate::models::system_settings::{
     TenantMaliciousIpBlacklistEntry, UpdateTenantMaliciousIpBlacklistRequest,
 };
-use crate::models::tenant::{CreateTenantInput, UpdateTenantInput};
-use crate::models::user::AddUserToTenantInput;
+use crate::models::tenant::{
+    CreateTenantInput, RenameTenantInput, Tenant, TenantRenameResult, TenantSettings,
+    UpdateTenantInput,
+};
+use crate::models::user::UserCohort;
 use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope, TenantListMode};
 use crate::repository::audit::CreateAuditLogInput;
+use crate::repository::tenant::TENANT_LIST_SPEC;
 use crate::repository::AuditRepository;
-use crate::state::{HasServices, HasSystemSettings};
+use crate::state::{HasBranding, HasDbPool, HasServices, HasSystemSettings};
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::Deserialize;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
 /// Check if user has access to a specific tenant, logging access_denied events to audit log
@@ -66,28 +83,6 @@ async fn check_tenant_access<S: HasServices>(
     result
 }
 
-/// Query parameters for tenant list endpoint with search
-#[derive(Debug, Deserialize)]
-pub struct TenantListQuery {
-    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
-    pub page: i64,
-    #[serde(
-        default = "default_per_page",
-        deserialize_with = "deserialize_per_page",
-        alias = "limit"
-    )]
-    pub per_page: i64,
-    pub search: Option<String>,
-}
-
-fn default_page() -> i64 {
-    1
-}
-
-fn default_per_page() -> i64 {
-    20
-}
-
 /// List tenants
 /// - Platform admin (any token type with platform admin email or platform tenant admin): can list all tenants
 /// - Non-admin Identity token: can see tenants they belong to
@@ -96,30 +91,23 @@ fn default_per_page() -> i64 {
     get,
     path = "/api/v1/tenants",
     tag = "Tenant Access",
+    params(ListQueryParams),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 400, description = "Filter or sort field not allowed")
     )
 )]
 pub async fn list<S: HasServices>(
     State(state): State<S>,
     auth: AuthUser,
-    Query(query): Query<TenantListQuery>,
+    query: ListQuery,
 ) -> Result<impl IntoResponse> {
+    TENANT_LIST_SPEC.validate(&query)?;
     match policy::resolve_tenant_list_mode_with_state(&state, &auth).await? {
         TenantListMode::AllTenants => {
-            let (tenants, total) = if let Some(ref search) = query.search {
-                state
-                    .tenant_service()
-                    .search(search, query.page, query.per_page)
-                    .await?
-            } else {
-                state
-                    .tenant_service()
-                    .list(query.page, query.per_page)
-                    .await?
-            };
+            let (tenants, total) = state.tenant_service().list_filtered(&query).await?;
             Ok(Json(PaginatedResponse::new(
-                tenants,
+                query.select_fields(tenants)?,
                 query.page,
                 query.per_page,
                 total,
@@ -138,14 +126,13 @@ pub async fn list<S: HasServices>(
             }
             let total = tenants.len() as i64;
             // Apply pagination
-            let start = ((query.page - 1) * query.per_page) as usize;
             let paged: Vec<_> = tenants
                 .into_iter()
-                .skip(start)
+                .skip(query.offset() as usize)
                 .take(query.per_page as usize)
                 .collect();
             Ok(Json(PaginatedResponse::new(
-                paged,
+                query.select_fields(paged)?,
                 query.page,
                 query.per_page,
                 total,
@@ -153,9 +140,95 @@ pub async fn list<S: HasServices>(
         }
         TenantListMode::TokenTenant(tenant_id) => {
             let tenant = state.tenant_service().get(tenant_id).await?;
-            Ok(Json(PaginatedResponse::new(vec![tenant], 1, 1, 1)))
+            Ok(Json(PaginatedResponse::new(
+                query.select_fields(vec![tenant])?,
+                1,
+                1,
+                1,
+            )))
+        }
+    }
+}
+
+/// Paths `GET /api/v1/tenants/{id}` can expand
+const TENANT_EXPANSIONS: &[&str] = &[
+    "admin_users",
+    "services",
+    "services.clients",
+    "services.permissions",
+    "services.roles",
+];
+
+/// Tenant roles listed by the `admin_users` expansion
+const ADMIN_ROLES_IN_TENANT: &[&str] = &["owner", "admin"];
+
+/// Inline the expansions requested for a tenant, each authorized like the
+/// endpoint listing the same objects
+async fn expand_tenant<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant: &Tenant,
+    expand: &ExpandQuery,
+) -> Result<serde_json::Value> {
+    let mut expansions = Vec::new();
+
+    if expand.contains("services") {
+        policy::enforce_with_state(
+            state,
+            auth,
+            &PolicyInput {
+                action: PolicyAction::ServiceList,
+                scope: ResourceScope::Tenant(tenant.id),
+            },
+        )
+        .await
+        .map_err(|e| expansion_forbidden("services", e))?;
+        let (services, total) = state
+            .client_service()
+            .list(Some(tenant.id.0), 1, MAX_EXPANDED_ITEMS)
+            .await?;
+        let nested = expand.nested("services");
+        let mut values = Vec::with_capacity(services.len());
+        for service in &services {
+            values.push(expand_service(state, auth, service, &nested, Some("services")).await?);
         }
+        expansions.push(("services", expanded_list(&values, total as usize)?));
     }
+
+    if expand.contains("admin_users") {
+        // Tenant read access is already checked; like `GET /tenants/{id}/users`
+        // the members are not listed for Identity tokens
+        if auth.token_type == TokenType::Identity {
+            return Err(expansion_forbidden(
+                "admin_users",
+                AppError::Forbidden(
+                    "Identity token is only allowed for tenant selection and exchange".to_string(),
+                ),
+            ));
+        }
+        let mut users = Vec::new();
+        let mut total = 0;
+        for role in ADMIN_ROLES_IN_TENANT {
+            let cohort = UserCohort::Role {
+                role: role.to_string(),
+            };
+            let remaining = MAX_EXPANDED_ITEMS - users.len() as i64;
+            let (page, count) = state
+                .user_service()
+                .list_tenant_users_in_cohort(tenant.id, &cohort, 0, remaining)
+                .await?;
+            total += count as usize;
+            for user in &page {
+                users.push(expanded(
+                    user,
+                    [("role_in_tenant", serde_json::json!(role))],
+                )?);
+            }
+        }
+        expansions.push(("admin_users", expanded_list(&users, total)?));
+    }
+
+    expanded(tenant, expansions)
 }
 
 /// Get tenant by ID
@@ -164,8 +237,11 @@ pub async fn list<S: HasServices>(
     get,
     path = "/api/v1/tenants/{id}",
     tag = "Tenant Access",
+    params(ExpandParams),
     responses(
-        (status = 200, description = "Success"),
+        (status = 200, description = "Success; `ETag` carries the tenant version. `expand[]` inlines admin_users and services (with services.clients, services.permissions, services.roles)"),
+        (status = 400, description = "Unknown or too deep expansion"),
+        (status = 403, description = "Not allowed to read the tenant or an expansion"),
         (status = 404, description = "Not found")
     )
 )]
@@ -174,12 +250,21 @@ pub async fn get<S: HasServices>(
     auth: AuthUser,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
+    expand: ExpandQuery,
 ) -> Result<impl IntoResponse> {
+    expand.allow(TENANT_EXPANSIONS)?;
     // Check tenant access before returning data
     check_tenant_access(&state, &headers, &auth, id).await?;
 
     let tenant = state.tenant_service().get(StringUuid::from(id)).await?;
-    Ok(Json(SuccessResponse::new(tenant)))
+    if expand.is_empty() {
+        return Ok(with_etag(
+            tenant.version,
+            Json(SuccessResponse::new(tenant)),
+        ));
+    }
+    let value = expand_tenant(&state, &auth, &tenant, &expand).await?;
+    Ok(with_etag(tenant.version, Json(SuccessResponse::new(value))))
 }
 
 /// Create tenant
@@ -190,27 +275,24 @@ pub async fn get<S: HasServices>(
     tag = "Tenant Access",
     responses(
         (status = 201, description = "Created"),
-        (status = 403, description = "Forbidden")
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Slug already taken or reserved"),
+        (status = 422, description = "Invalid name or slug")
     )
 )]
-pub async fn create<S: HasServices>(
+pub async fn create<S: HasServices + HasDbPool>(
     State(state): State<S>,
     auth: AuthUser,
     headers: HeaderMap,
-    Json(input): Json<CreateTenantInput>,
+    Json(mut input): Json<CreateTenantInput>,
 ) -> Result<impl IntoResponse> {
     // Only platform admins can create tenants
     require_platform_admin_identity(&state, &auth).await?;
 
-    let tenant = state.tenant_service().create(input).await?;
+    prepare_create_input(&state, &mut input)?;
 
-    // Add the creator as owner of the new tenant
-    let add_input = AddUserToTenantInput {
-        user_id: auth.user_id,
-        tenant_id: tenant.id.into(),
-        role_in_tenant: "owner".to_string(),
-    };
-    state.user_service().add_to_tenant(add_input).await?;
+    // Create the tenant with the creator as its owner
+    let tenant = run_tenant_provision(&state, input, Some(auth.user_id), None).await?;
 
     let _ = write_audit_log_generic(
         &state,
@@ -222,27 +304,38 @@ pub async fn create<S: HasServices>(
         serde_json::to_value(&tenant).ok(),
     )
     .await;
+    record_billing_event(
+        &state,
+        NewBillingEvent::tenant_created(tenant.id, &tenant.name, &tenant.slug, tenant.created_at),
+    )
+    .await;
     Ok((StatusCode::CREATED, Json(SuccessResponse::new(tenant))))
 }
 
 /// Update tenant
-/// Requires tenant admin/owner role or platform admin
+/// Requires tenant admin/owner role or platform admin.
+/// Send the tenant's `ETag` as `If-Match` to reject the update if the tenant
+/// was changed by someone else in the meantime.
 #[utoipa::path(
     put,
     path = "/api/v1/tenants/{id}",
     tag = "Tenant Access",
+    params(("If-Match" = Option<String>, Header, description = "Expected tenant version (ETag)")),
     responses(
         (status = 200, description = "Success"),
+        (status = 400, description = "Malformed If-Match header"),
         (status = 403, description = "Forbidden"),
-        (status = 404, description = "Not found")
+        (status = 404, description = "Not found"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
-pub async fn update<S: HasServices>(
+pub async fn update<S: HasServices + HasBranding>(
     State(state): State<S>,
     auth: AuthUser,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
-    Json(input): Json<UpdateTenantInput>,
+    Json(mut input): Json<UpdateTenantInput>,
 ) -> Result<impl IntoResponse> {
     // Require admin/owner role for tenant updates (not just membership)
     policy::enforce_with_state(
@@ -255,9 +348,31 @@ pub async fn update<S: HasServices>(
     )
     .await?;
 
+    let expected_version = if_match_version(&headers)?;
     let id = StringUuid::from(id);
     let before = state.tenant_service().get(id).await?;
-    let tenant = state.tenant_service().update(id, input).await?;
+
+    if let Some(settings) = input.settings.as_mut() {
+        validate_token_ttls(&state, settings)?;
+        // Cardinality overrides are a platform decision: tenant owners cannot
+        // change them, and settings they send without them keep the stored ones
+        let stored = before.settings.cardinality_limits;
+        if settings.cardinality_limits != stored
+            && !policy::is_platform_admin_with_db(&state, &auth).await
+        {
+            if settings.cardinality_limits.is_some() {
+                return Err(AppError::Forbidden(
+                    "Only platform admins can change cardinality limits".to_string(),
+                ));
+            }
+            settings.cardinality_limits = stored;
+        }
+    }
+    let tenant = state
+        .tenant_service()
+        .update(id, input, expected_version)
+        .await?;
+    state.branding_service().invalidate_context(id);
 
     let old_value = serde_json::to_value(&before).unwrap_or_else(|e| {
         tracing::warn!(tenant_id = %id, error = %e, "Failed to serialize old tenant value for audit log");
@@ -278,7 +393,194 @@ pub async fn update<S: HasServices>(
         Some(new_value),
     )
     .await;
-    Ok(Json(SuccessResponse::new(tenant)))
+    Ok(with_etag(
+        tenant.version,
+        Json(SuccessResponse::new(tenant)),
+    ))
+}
+
+/// Get tenant by slug
+/// A slug retired by a rename still resolves during its grace period; the
+/// response then carries `Deprecation`, `Sunset` and a `Link` to the current slug.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/by-slug/{slug}",
+    tag = "Tenant Access",
+    params(("slug" = String, Path, description = "Current or recently retired tenant slug")),
+    responses(
+        (status = 200, description = "Success"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub async fn get_by_slug<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> Result<Response> {
+    let (tenant, redirect) = state.tenant_service().resolve_slug(&slug).await?;
+    check_tenant_access(&state, &headers, &auth, tenant.id.0).await?;
+
+    let deprecation = redirect
+        .map(|redirect| tenant_slug_redirect_headers(&redirect, &tenant.slug))
+        .unwrap_or_default();
+    Ok((
+        deprecation,
+        with_etag(tenant.version, Json(SuccessResponse::new(tenant))),
+    )
+        .into_response())
+}
+
+/// Effective settings of a tenant
+/// Resolves each setting over the platform configuration, the tenant's
+/// settings and optionally a service's overrides, with the scope it came from.
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{id}/effective-settings",
+    tag = "Tenant Access",
+    params(EffectiveSettingsQuery),
+    responses(
+        (status = 200, description = "Effective settings with their source", body = EffectiveSettings),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Tenant or service not found")
+    )
+)]
+pub async fn effective_settings<S: HasServices + HasBranding>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Query(query): Query<EffectiveSettingsQuery>,
+) -> Result<Json<SuccessResponse<EffectiveSettings>>> {
+    check_tenant_access(&state, &headers, &auth, id).await?;
+
+    let tenant = state.tenant_service().get(StringUuid::from(id)).await?;
+    let branding = state.branding_service();
+    let platform_branding = branding.get_branding().await?;
+    let service_branding = match query.service_id {
+        Some(service_id) => {
+            let service = state.client_service().get(service_id.0).await?;
+            if service.tenant_id != Some(tenant.id) {
+                return Err(AppError::NotFound(format!(
+                    "Service {} not found",
+                    service_id
+                )));
+            }
+            let login_branding = match branding.get_service_branding_only(service_id).await {
+                Ok(config) => Some(config),
+                Err(AppError::NotFound(_)) => None,
+                Err(e) => return Err(e),
+            };
+            Some((service_id, login_branding))
+        }
+        None => None,
+    };
+
+    let settings = EffectiveSettings::resolve(
+        state.config(),
+        &platform_branding,
+        &tenant,
+        service_branding
+            .as_ref()
+            .map(|(id, config)| (*id, config.as_ref())),
+    );
+    Ok(Json(SuccessResponse::new(settings)))
+}
+
+/// Rename tenant
+/// Changes the display name and/or slug. The old slug keeps resolving for 90
+/// days and cannot be claimed by another tenant until then; slug-derived
+/// enterprise SSO provider aliases follow the new slug.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{id}/rename",
+    tag = "Tenant Access",
+    request_body = RenameTenantInput,
+    responses(
+        (status = 200, description = "Renamed", body = TenantRenameResult),
+        (status = 400, description = "The platform tenant slug cannot change"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Slug already taken or reserved")
+    )
+)]
+pub async fn rename<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(input): Json<RenameTenantInput>,
+) -> Result<impl IntoResponse> {
+    policy::enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::TenantOwner,
+            scope: ResourceScope::Tenant(StringUuid::from(id)),
+        },
+    )
+    .await?;
+
+    let id = StringUuid::from(id);
+    let before = state.tenant_service().get(id).await?;
+    let result = state.tenant_service().rename(id, input).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.rename",
+        "tenant",
+        Some(*id),
+        Some(serde_json::json!({ "name": before.name, "slug": before.slug })),
+        Some(serde_json::json!({
+            "name": result.tenant.name,
+            "slug": result.tenant.slug,
+            "slug_redirect_expires_at": result.slug_redirect.as_ref().map(|r| r.expires_at),
+        })),
+    )
+    .await;
+    Ok(Json(SuccessResponse::new(result)))
+}
+
+/// Checks of a new tenant that need platform configuration, shared by the
+/// REST and gRPC provisioning paths
+pub(crate) fn prepare_create_input<S: HasServices>(
+    state: &S,
+    input: &mut CreateTenantInput,
+) -> Result<()> {
+    // Pinning to the home region is the same as not pinning
+    if let Some(region) = input.data_region.take() {
+        let residency = &state.config().data_residency;
+        if !residency.is_known_region(&region) {
+            return Err(AppError::UnknownDataRegion(region));
+        }
+        if region != residency.home_region {
+            input.data_region = Some(region);
+        }
+    }
+
+    if let Some(settings) = &input.settings {
+        validate_token_ttls(state, settings)?;
+    }
+    Ok(())
+}
+
+/// Token lifetime overrides must stay within the platform maxima
+fn validate_token_ttls<S: HasServices>(state: &S, settings: &TenantSettings) -> Result<()> {
+    let jwt = &state.config().jwt;
+    settings
+        .token_ttls
+        .validate_bounds(
+            jwt.max_access_token_ttl_secs,
+            jwt.max_refresh_token_ttl_secs,
+        )
+        .map_err(AppError::Validation)
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct DeleteTenantQuery {
+    /// Queue the deletion as a background job and return 202 with the job
+    #[serde(default, rename = "async")]
+    pub run_async: bool,
 }
 
 /// Delete tenant
@@ -288,17 +590,20 @@ pub async fn update<S: HasServices>(
     delete,
     path = "/api/v1/tenants/{id}",
     tag = "Tenant Access",
+    params(DeleteTenantQuery),
     responses(
         (status = 200, description = "Deleted"),
+        (status = 202, description = "Deletion queued as a job", body = JobResponse),
         (status = 403, description = "Forbidden")
     )
 )]
-pub async fn delete<S: HasServices>(
+pub async fn delete<S: HasServices + HasDbPool>(
     State(state): State<S>,
     auth: AuthUser,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
-) -> Result<impl IntoResponse> {
+    Query(query): Query<DeleteTenantQuery>,
+) -> Result<Response> {
     // Only platform admins can delete tenants
     require_platform_admin_identity(&state, &auth).await?;
 
@@ -317,6 +622,32 @@ pub async fn delete<S: HasServices>(
     let id = StringUuid::from(id);
     let before = state.tenant_service().get(id).await?;
 
+    if query.run_async {
+        let job = job_service(&state)
+            .enqueue(CreateJobInput {
+                kind: JobKind::TenantDelete,
+                tenant_id: Some(id),
+                payload: serde_json::json!({}),
+                created_by: Some(StringUuid::from(auth.user_id)),
+            })
+            .await?;
+        let _ = write_audit_log_generic(
+            &state,
+            &headers,
+            "tenant.delete_queued",
+            "tenant",
+            Some(*id),
+            serde_json::to_value(&before).ok(),
+            Some(serde_json::json!({ "job_id": job.id })),
+        )
+        .await;
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(SuccessResponse::new(JobResponse::from(job))),
+        )
+            .into_response());
+    }
+
     // Perform physical delete with cascade cleanup
     state.tenant_service().delete(id).await?;
 
@@ -330,7 +661,7 @@ pub async fn delete<S: HasServices>(
         None,
     )
     .await;
-    Ok(Json(MessageResponse::new("Tenant deleted successfully")))
+    Ok(Json(MessageResponse::new("Tenant deleted successfully")).into_response())
 }
 
 #[utoipa::path(
@@ -0,0 +1,203 @@
+//! Tenant data key APIs: status and rotation of the key sealing the tenant's
+//! webhook secrets. Re-encryption runs as a `key_rotation` job whose
+//! progress is served by the jobs API.
+
+use crate::crypto::TenantKeyring;
+use crate::domains::platform::api::job::job_service;
+use crate::domains::tenant_access::service::TenantKeyService;
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::job::{CreateJobInput, Job, JobKind, JobResponse};
+use crate::models::tenant_key::{KeyRotationJobPayload, TenantKeyStatus};
+use crate::policy::{self, PolicyAction, PolicyInput, ResourceScope};
+use crate::repository::tenant_key::TenantKeyRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Recent tenant jobs searched for a rotation still in progress
+const RECENT_JOBS_CHECKED: i64 = 100;
+
+fn tenant_key_service<S: HasServices + HasDbPool>(
+    state: &S,
+) -> TenantKeyService<TenantKeyRepositoryImpl> {
+    TenantKeyService::new(
+        Arc::new(TenantKeyRepositoryImpl::new(state.db_pool().clone())),
+        TenantKeyring::from_config(&state.config().settings_encryption),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/encryption-key",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Current key version and the tenant's secrets by key", body = TenantKeyStatus)
+    )
+)]
+pub async fn status<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<TenantKeyStatus>>> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let status = tenant_key_service(&state)
+        .status(StringUuid::from(tenant_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(status)))
+}
+
+/// Rotate the tenant's data key
+///
+/// Starts a new key version and queues a `key_rotation` job that
+/// re-encrypts the tenant's webhook secrets with it in batches. Secrets
+/// sealed with the old version stay readable until then, and the secrets
+/// themselves do not change, so receivers need no update.
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/encryption-key/rotate",
+    tag = "Tenant Access",
+    responses(
+        (status = 202, description = "Key rotated; re-encryption job queued", body = JobResponse),
+        (status = 400, description = "No master key configured"),
+        (status = 409, description = "A key rotation job of the tenant is still running")
+    )
+)]
+pub async fn rotate<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<impl IntoResponse> {
+    ensure_tenant_access(&state, &auth, tenant_id).await?;
+    let tenant_id = StringUuid::from(tenant_id);
+    let jobs = job_service(&state);
+    if jobs
+        .list_by_tenant(tenant_id, RECENT_JOBS_CHECKED)
+        .await?
+        .iter()
+        .any(|job| job.job_kind() == Some(JobKind::KeyRotation) && !job.is_terminal())
+    {
+        return Err(AppError::Conflict(
+            "A key rotation of this tenant is still running".to_string(),
+        ));
+    }
+
+    let key = tenant_key_service(&state).start_rotation(tenant_id).await?;
+    let job = enqueue_key_rotation(&state, Some(tenant_id), auth.user_id).await?;
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "tenant.encryption_key.rotate",
+        "tenant",
+        Some(*tenant_id),
+        None,
+        Some(serde_json::json!({ "key_version": key.key_version, "job_id": job.id })),
+    )
+    .await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+/// Re-encrypt all stored secrets
+///
+/// Queues a `key_rotation` job over every tenant's and the platform's
+/// webhook secrets: after a master key change (with the old key in
+/// `SETTINGS_ENCRYPTION_KEY_PREVIOUS`), or to encrypt secrets stored before
+/// encryption was enabled.
+#[utoipa::path(
+    post,
+    path = "/api/v1/system/encryption-key/reencrypt",
+    tag = "Tenant Access",
+    responses(
+        (status = 202, description = "Re-encryption job queued", body = JobResponse),
+        (status = 400, description = "No master key configured"),
+        (status = 403, description = "Platform admin required")
+    )
+)]
+pub async fn reencrypt_all<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse> {
+    policy::enforce_with_state(
+        &state,
+        &auth,
+        &PolicyInput {
+            action: PolicyAction::PlatformAdmin,
+            scope: ResourceScope::Global,
+        },
+    )
+    .await?;
+    if TenantKeyring::from_config(&state.config().settings_encryption).is_none() {
+        return Err(AppError::BadRequest(
+            "Secret encryption requires SETTINGS_ENCRYPTION_KEY to be configured".to_string(),
+        ));
+    }
+
+    let job = enqueue_key_rotation(&state, None, auth.user_id).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "system.encryption_key.reencrypt",
+        "job",
+        Some(*job.id),
+        None,
+        None,
+    )
+    .await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(SuccessResponse::new(JobResponse::from(job))),
+    ))
+}
+
+async fn enqueue_key_rotation<S: HasServices + HasDbPool>(
+    state: &S,
+    tenant_id: Option<StringUuid>,
+    created_by: Uuid,
+) -> Result<Job> {
+    let config = &state.config().jobs;
+    let payload = KeyRotationJobPayload {
+        batch_size: config.key_rotation_batch_size,
+        pause_ms: config.key_rotation_pause_ms,
+    };
+    job_service(state)
+        .enqueue(CreateJobInput {
+            kind: JobKind::KeyRotation,
+            tenant_id,
+            payload: serde_json::to_value(&payload)
+                .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?,
+            created_by: Some(StringUuid::from(created_by)),
+        })
+        .await
+}
+
+async fn ensure_tenant_access<S: HasServices>(
+    state: &S,
+    auth: &AuthUser,
+    tenant_id: Uuid,
+) -> Result<()> {
+    policy::enforce_with_state(
+        state,
+        auth,
+        &PolicyInput {
+            action: PolicyAction::TenantWrite,
+            scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+        },
+    )
+    .await
+}
@@ -196,7 +196,7 @@ pub struct LdapSearchUserResult {
     pub groups: Vec<String>,
 }
 
-async fn ensure_tenant_access<S: HasServices>(
+pub(super) async fn ensure_tenant_access<S: HasServices>(
     state: &S,
     _headers: &HeaderMap,
     auth: &AuthUser,
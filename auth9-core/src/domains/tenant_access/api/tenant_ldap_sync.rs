@@ -0,0 +1,177 @@
+//! LDAP directory sync APIs: run a sync (or a dry-run preview) of an LDAP
+//! connector and browse its sync history.
+
+use crate::domains::identity::service::ldap_sync::TRIGGER_MANUAL;
+use crate::domains::identity::service::LdapSyncService;
+use crate::error::{AppError, Result};
+use crate::http_support::{write_audit_log_generic, SuccessResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::common::StringUuid;
+use crate::models::ldap_sync::{LdapSyncConnector, LdapSyncRun, TriggerLdapSyncInput};
+use crate::repository::ldap_group_mapping::LdapGroupRoleMappingRepositoryImpl;
+use crate::repository::ldap_sync_run::LdapSyncRunRepositoryImpl;
+use crate::repository::linked_identity::LinkedIdentityRepositoryImpl;
+use crate::repository::rbac::RbacRepositoryImpl;
+use crate::repository::user::UserRepositoryImpl;
+use crate::state::{HasDbPool, HasLdapAuth, HasServices};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+type SyncService = LdapSyncService<LdapSyncRunRepositoryImpl, LdapGroupRoleMappingRepositoryImpl>;
+
+fn sync_service(pool: &sqlx::MySqlPool, stale_after_secs: u64) -> SyncService {
+    LdapSyncService::new(
+        Arc::new(LdapSyncRunRepositoryImpl::new(pool.clone())),
+        Arc::new(LdapGroupRoleMappingRepositoryImpl::new(pool.clone())),
+        Arc::new(UserRepositoryImpl::new(pool.clone())),
+        Arc::new(LinkedIdentityRepositoryImpl::new(pool.clone())),
+        Arc::new(RbacRepositoryImpl::new(pool.clone())),
+        stale_after_secs,
+    )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SyncRunListQuery {
+    /// Most recent runs to return (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+/// Load an LDAP connector of the tenant for syncing
+async fn load_sync_connector<S: HasDbPool>(
+    state: &S,
+    tenant_id: Uuid,
+    connector_id: Uuid,
+) -> Result<LdapSyncConnector> {
+    let connector = super::tenant_sso::get_connector_by_id(
+        state.db_pool(),
+        tenant_id,
+        StringUuid::from(connector_id),
+    )
+    .await?;
+    if connector.provider_type != "ldap" {
+        return Err(AppError::Validation(
+            "Directory sync is only supported for LDAP connectors".to_string(),
+        ));
+    }
+    Ok(LdapSyncConnector {
+        id: connector.id,
+        tenant_id: connector.tenant_id,
+        alias: connector.alias,
+        config: connector.config,
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync",
+    tag = "Tenant Access",
+    request_body = TriggerLdapSyncInput,
+    responses(
+        (status = 200, description = "Sync run record", body = LdapSyncRun),
+        (status = 409, description = "A sync of the connector is already running")
+    )
+)]
+pub async fn trigger_sync<S: HasServices + HasDbPool + HasLdapAuth>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, connector_id)): Path<(Uuid, Uuid)>,
+    Json(input): Json<TriggerLdapSyncInput>,
+) -> Result<Json<SuccessResponse<LdapSyncRun>>> {
+    super::tenant_ldap_group_mappings::ensure_tenant_access(&state, &headers, &auth, tenant_id)
+        .await?;
+    let connector = load_sync_connector(&state, tenant_id, connector_id).await?;
+
+    let run = sync_service(state.db_pool(), state.config().ldap_sync.stale_after_secs)
+        .run(
+            state.ldap_authenticator(),
+            &connector,
+            TRIGGER_MANUAL,
+            &input,
+        )
+        .await?;
+
+    if !run.dry_run {
+        let _ = write_audit_log_generic(
+            &state,
+            &headers,
+            "tenant.sso.connector.ldap_sync",
+            "enterprise_sso_connector",
+            Some(connector_id),
+            None,
+            Some(serde_json::json!({
+                "run_id": run.id,
+                "mode": run.mode,
+                "status": run.status,
+                "users_created": run.users_created,
+                "users_updated": run.users_updated,
+                "users_linked": run.users_linked,
+                "conflicts": run.conflicts,
+                "roles_assigned": run.roles_assigned,
+                "roles_removed": run.roles_removed,
+            })),
+        )
+        .await;
+    }
+
+    Ok(Json(SuccessResponse::new(run)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync/runs",
+    tag = "Tenant Access",
+    params(SyncRunListQuery),
+    responses(
+        (status = 200, description = "Sync runs, most recent first", body = Vec<LdapSyncRun>)
+    )
+)]
+pub async fn list_sync_runs<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, connector_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<SyncRunListQuery>,
+) -> Result<Json<SuccessResponse<Vec<LdapSyncRun>>>> {
+    super::tenant_ldap_group_mappings::ensure_tenant_access(&state, &headers, &auth, tenant_id)
+        .await?;
+    let connector = load_sync_connector(&state, tenant_id, connector_id).await?;
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let runs = sync_service(state.db_pool(), state.config().ldap_sync.stale_after_secs)
+        .list_runs(connector.id, limit)
+        .await?;
+    Ok(Json(SuccessResponse::new(runs)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync/runs/{run_id}",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Sync run with its changes", body = LdapSyncRun),
+        (status = 404, description = "Sync run not found")
+    )
+)]
+pub async fn get_sync_run<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Path((tenant_id, connector_id, run_id)): Path<(Uuid, Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<LdapSyncRun>>> {
+    super::tenant_ldap_group_mappings::ensure_tenant_access(&state, &headers, &auth, tenant_id)
+        .await?;
+    let connector = load_sync_connector(&state, tenant_id, connector_id).await?;
+
+    let run = sync_service(state.db_pool(), state.config().ldap_sync.stale_after_secs)
+        .get_run(connector.id, StringUuid::from(run_id))
+        .await?;
+    Ok(Json(SuccessResponse::new(run)))
+}
@@ -1,8 +1,10 @@
 //! Tenant-scoped enterprise SSO connector APIs.
 
+use crate::domains::platform::api::billing::record_billing_event;
 use crate::error::{AppError, Result};
 use crate::http_support::{write_audit_log_generic, MessageResponse, SuccessResponse};
 use crate::middleware::auth::AuthUser;
+use crate::models::billing::NewBillingEvent;
 use crate::models::common::StringUuid;
 use crate::models::enterprise_sso::{
     CreateEnterpriseSsoConnectorInput, EnterpriseSsoConnector, UpdateEnterpriseSsoConnectorInput,
@@ -46,6 +48,23 @@ pub async fn list_connectors<S: HasServices + HasDbPool>(
     Ok(Json(SuccessResponse::new(connectors)))
 }
 
+/// Enabling SSO is a billable tenant lifecycle event (recorded once per connector)
+async fn record_sso_enabled<S: HasServices + HasDbPool>(
+    state: &S,
+    connector: &EnterpriseSsoConnector,
+) {
+    record_billing_event(
+        state,
+        NewBillingEvent::sso_enabled(
+            connector.tenant_id,
+            &connector.id.to_string(),
+            &connector.provider_type,
+            chrono::Utc::now(),
+        ),
+    )
+    .await;
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/tenants/{tenant_id}/sso/connectors",
@@ -107,6 +126,9 @@ pub async fn create_connector<S: HasServices + HasDbPool>(
         serde_json::to_value(&created).ok(),
     )
     .await;
+    if created.enabled {
+        record_sso_enabled(&state, &created).await;
+    }
 
     Ok(Json(SuccessResponse::new(created)))
 }
@@ -204,6 +226,9 @@ pub async fn update_connector<S: HasServices + HasDbPool>(
         serde_json::to_value(&after).ok(),
     )
     .await;
+    if !before.enabled && after.enabled {
+        record_sso_enabled(&state, &after).await;
+    }
 
     Ok(Json(SuccessResponse::new(after)))
 }
@@ -233,6 +258,10 @@ pub async fn delete_connector<S: HasServices + HasDbPool>(
         .bind(connector_id.to_string())
         .execute(tx.as_mut())
         .await?;
+    sqlx::query("DELETE FROM ldap_sync_runs WHERE connector_id = ?")
+        .bind(connector_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
     sqlx::query("DELETE FROM scim_group_role_mappings WHERE connector_id = ?")
         .bind(connector_id.to_string())
         .execute(tx.as_mut())
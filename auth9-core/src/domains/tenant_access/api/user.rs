@@ -1,17 +1,22 @@
 //! User API handlers
 
 use crate::config::Config;
+use crate::domains::tenant_access::service::sagas::{run_user_create, UserCreateContext};
 use crate::error::{AppError, Result};
+use crate::http_support::etag::{if_match_version, with_etag};
 use crate::http_support::{
     write_audit_log_generic, MessageResponse, PaginatedResponse, PaginationQuery, SuccessResponse,
 };
-use crate::identity_engine::{IdentityUserCreateInput, IdentityUserUpdateInput};
+use crate::identity_engine::IdentityUserUpdateInput;
 use crate::middleware::auth::{AuthUser, TokenType};
-use crate::models::common::StringUuid;
-use crate::models::user::{AddUserToTenantInput, CreateUserInput, UpdateUserInput, User};
+use crate::models::common::{StringUuid, TenantId, UserId};
+use crate::models::list_query::ListGrammarUnsupported;
+use crate::models::user::{
+    AddUserToTenantInput, CreateUserInput, UpdateUserInput, UpdateUserLocaleInput, User,
+};
 use crate::policy::{
-    enforce, enforce_with_state, is_platform_admin_with_db, PolicyAction, PolicyInput,
-    ResourceScope,
+    enforce, enforce_admin_unit_boundary, enforce_admin_unit_create_boundary, enforce_with_state,
+    AuthzContext, PolicyAction, PolicyInput, ResourceScope,
 };
 use crate::state::{HasBranding, HasRequiredActions, HasServices};
 use axum::{
@@ -71,46 +76,47 @@ fn default_per_page() -> i64 {
 /// Requires the user to be an owner of the target tenant or a platform admin
 async fn require_tenant_owner<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    authz: &AuthzContext,
     target_tenant_id: Uuid,
 ) -> Result<()> {
-    enforce_with_state(
-        state,
-        auth,
-        &PolicyInput {
-            action: PolicyAction::TenantOwner,
-            scope: ResourceScope::Tenant(StringUuid::from(target_tenant_id)),
-        },
-    )
-    .await
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::TenantOwner,
+                scope: ResourceScope::Tenant(StringUuid::from(target_tenant_id)),
+            },
+        )
+        .await
 }
 
 /// Check if user is actually an owner of the target tenant (no platform admin bypass).
 /// Used for ownership transfer operations where only the current owner should be allowed.
 async fn require_actual_tenant_owner<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    authz: &AuthzContext,
     target_tenant_id: Uuid,
 ) -> Result<()> {
-    enforce_with_state(
-        state,
-        auth,
-        &PolicyInput {
-            action: PolicyAction::TenantActualOwner,
-            scope: ResourceScope::Tenant(StringUuid::from(target_tenant_id)),
-        },
-    )
-    .await
+    authz
+        .enforce(
+            state,
+            &PolicyInput {
+                action: PolicyAction::TenantActualOwner,
+                scope: ResourceScope::Tenant(StringUuid::from(target_tenant_id)),
+            },
+        )
+        .await
 }
 
 /// Verify target user belongs to the caller's tenant (for TenantAccess tokens).
 /// Platform admins bypass this check. Identity tokens are handled by the policy layer.
-async fn ensure_user_in_caller_tenant<S: HasServices>(
+pub(super) async fn ensure_user_in_caller_tenant<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    authz: &AuthzContext,
     target_user_id: Uuid,
 ) -> Result<()> {
     // Only enforce for TenantAccess tokens (Identity tokens have separate handling in policy)
+    let auth = authz.auth();
     let tenant_id = match auth.token_type {
         TokenType::TenantAccess => match auth.tenant_id {
             Some(tid) => tid,
@@ -120,7 +126,7 @@ async fn ensure_user_in_caller_tenant<S: HasServices>(
     };
 
     // Platform admins can access any user
-    if is_platform_admin_with_db(state, auth).await {
+    if authz.is_platform_admin(state).await {
         return Ok(());
     }
 
@@ -139,9 +145,31 @@ async fn ensure_user_in_caller_tenant<S: HasServices>(
     Ok(())
 }
 
+/// Keep admins scoped to admin units within their units' members (for
+/// TenantAccess tokens; other callers need platform admin to manage users).
+pub(super) async fn ensure_user_in_admin_units<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    target_user_id: Uuid,
+) -> Result<()> {
+    let auth = authz.auth();
+    match (&auth.token_type, auth.tenant_id) {
+        (TokenType::TenantAccess, Some(tenant_id)) => {
+            enforce_admin_unit_boundary(
+                state,
+                authz,
+                StringUuid::from(tenant_id),
+                StringUuid::from(target_user_id),
+            )
+            .await
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Check if user can manage users within a tenant
 /// Platform admin can always manage, tenant admin with appropriate role can manage their tenant
-fn require_user_management_permission(config: &Config, auth: &AuthUser) -> Result<()> {
+pub(super) fn require_user_management_permission(config: &Config, auth: &AuthUser) -> Result<()> {
     enforce(
         config,
         auth,
@@ -161,16 +189,19 @@ fn require_user_management_permission(config: &Config, auth: &AuthUser) -> Resul
     path = "/api/v1/users",
     tag = "Tenant Access",
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 400, description = "filter, sort or fields passed")
     )
 )]
 pub async fn list<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
+    _: ListGrammarUnsupported,
     Query(query): Query<UserListQuery>,
 ) -> Result<impl IntoResponse> {
+    let auth = authz.auth();
     // Platform admin check applies to both Identity and TenantAccess tokens
-    if is_platform_admin_with_db(&state, &auth).await {
+    if authz.is_platform_admin(&state).await {
         // Determine effective tenant scope: explicit query param > token's tenant_id (for TenantAccess)
         let effective_tenant_id = query.tenant_id.or(auth.tenant_id);
 
@@ -237,15 +268,15 @@ pub async fn list<S: HasServices>(
     let tenant_id = auth
         .tenant_id
         .ok_or_else(|| AppError::Forbidden("No tenant context in token".to_string()))?;
-    enforce_with_state(
-        &state,
-        &auth,
-        &PolicyInput {
-            action: PolicyAction::UserTenantRead,
-            scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
-        },
-    )
-    .await?;
+    authz
+        .enforce(
+            &state,
+            &PolicyInput {
+                action: PolicyAction::UserTenantRead,
+                scope: ResourceScope::Tenant(StringUuid::from(tenant_id)),
+            },
+        )
+        .await?;
     let (users, total) = if let Some(ref search) = query.search {
         if !search.is_empty() {
             state
@@ -289,37 +320,40 @@ pub async fn list<S: HasServices>(
 )]
 pub async fn get<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     // Authorization check: users can only read their own profile
     // unless they have admin permissions
-    if auth.user_id != id {
-        enforce_with_state(
-            &state,
-            &auth,
-            &PolicyInput {
-                action: PolicyAction::UserReadOther,
-                scope: ResourceScope::User(StringUuid::from(id)),
-            },
-        )
-        .await?;
+    if authz.auth().user_id != id {
+        authz
+            .enforce(
+                &state,
+                &PolicyInput {
+                    action: PolicyAction::UserReadOther,
+                    scope: ResourceScope::User(StringUuid::from(id)),
+                },
+            )
+            .await?;
         // Cross-tenant IDOR prevention: verify target user is in caller's tenant
-        ensure_user_in_caller_tenant(&state, &auth, id).await?;
+        ensure_user_in_caller_tenant(&state, &authz, id).await?;
     }
 
     let id = StringUuid::from(id);
     let user = state.user_service().get(id).await?;
-    Ok(Json(SuccessResponse::new(user)))
+    Ok(with_etag(user.version, Json(SuccessResponse::new(user))))
 }
 
 /// Create user input (includes optional password for identity engine)
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateUserRequest {
     #[serde(flatten)]
     pub user: CreateUserInput,
     pub password: Option<String>,
     pub tenant_id: Option<Uuid>,
+    /// Admin unit of the tenant to add the new member to; required for
+    /// admins scoped to admin units
+    pub admin_unit_id: Option<Uuid>,
 }
 
 /// Create user
@@ -398,11 +432,73 @@ pub async fn create<S: HasServices + HasBranding>(
         }
     }
 
-    // Block user creation on non-active tenants (explicit tenant_id or token's tenant)
+    // Create in the explicit tenant_id, or else in the token's tenant
     let effective_tenant_id = input
         .tenant_id
         .or_else(|| auth_user.as_ref().and_then(|a| a.tenant_id));
-    if let Some(tenant_id) = effective_tenant_id {
+    let admin_unit_id = input.admin_unit_id.map(StringUuid::from);
+    match (&auth_user, effective_tenant_id) {
+        (Some(auth), Some(tenant_id)) => {
+            enforce_admin_unit_create_boundary(
+                &state,
+                &AuthzContext::new(auth.clone()),
+                StringUuid::from(tenant_id),
+                admin_unit_id,
+            )
+            .await?
+        }
+        _ if admin_unit_id.is_some() => {
+            return Err(AppError::BadRequest(
+                "admin_unit_id requires an authenticated request for a tenant".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    let (user, breach_warning) = provision_user(
+        &state,
+        input.user,
+        input.password,
+        effective_tenant_id,
+        None,
+    )
+    .await?;
+    if let Some(unit_id) = admin_unit_id {
+        state
+            .admin_unit_service()
+            .add_created_member(unit_id, user.id)
+            .await?;
+    }
+
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.create",
+        "user",
+        Some(*user.id),
+        None,
+        serde_json::to_value(&user).ok(),
+    )
+    .await;
+    Ok((
+        StatusCode::CREATED,
+        Json(SuccessResponse::new(user).with_password_warning(breach_warning)),
+    ))
+}
+
+/// Create a user in the identity engine and Auth9, optionally as a member of
+/// `tenant_id`, applying the tenant's password policy. Returns the user and a
+/// breached-password warning. Shared by the REST and gRPC provisioning paths;
+/// callers authorize the request first. The identity engine and database
+/// writes run as a `user_create` saga, so a failure part-way is undone.
+pub(crate) async fn provision_user<S: HasServices>(
+    state: &S,
+    input: CreateUserInput,
+    password: Option<String>,
+    tenant_id: Option<Uuid>,
+    idempotency_key: Option<&str>,
+) -> Result<(User, Option<String>)> {
+    // Block user creation on non-active tenants
+    if let Some(tenant_id) = tenant_id {
         state
             .tenant_service()
             .require_active(StringUuid::from(tenant_id))
@@ -410,13 +506,13 @@ pub async fn create<S: HasServices + HasBranding>(
     }
 
     // Validate input before calling identity engine (catches invalid emails early)
-    input.user.validate()?;
+    input.validate()?;
 
     // Validate password against tenant password policy if provided
     let mut breach_warning: Option<String> = None;
-    if let Some(ref password) = input.password {
-        // Determine tenant for policy: explicit tenant_id > caller's token tenant > default
-        let policy = if let Some(tenant_id) = effective_tenant_id {
+    if let Some(ref password) = password {
+        // Tenant password policy when created in a tenant, default otherwise
+        let policy = if let Some(tenant_id) = tenant_id {
             let tenant = state
                 .tenant_service()
                 .get(StringUuid::from(tenant_id))
@@ -429,6 +525,12 @@ pub async fn create<S: HasServices + HasBranding>(
             return Err(AppError::Validation(errors.join("; ")));
         }
 
+        // Common passwords and the tenant's deny list
+        state
+            .password_deny_list_service()
+            .check_for_tenant(tenant_id.map(StringUuid::from), password)
+            .await?;
+
         // Check if password has been found in a data breach (respects tenant policy)
         if policy.breach_check_mode != "disabled" {
             if let Some(breach_svc) = state.breached_password_service() {
@@ -445,90 +547,14 @@ pub async fn create<S: HasServices + HasBranding>(
         }
     }
 
-    // Extract password before moving input
-    let password_for_credential = input.password.clone();
-
-    let identity_subject = state
-        .identity_engine()
-        .user_store()
-        .create_user(&IdentityUserCreateInput {
-            username: input.user.email.clone(),
-            email: input.user.email.clone(),
-            first_name: input.user.display_name.clone(),
-            last_name: None,
-            enabled: true,
-            email_verified: false,
-            credentials: None, // Credentials stored after user record creation
-        })
-        .await?;
-
-    let user = match state
-        .user_service()
-        .create(&identity_subject, input.user)
-        .await
-    {
-        Ok(user) => user,
-        Err(e) => {
-            tracing::warn!(
-                identity_subject = %identity_subject,
-                error = %e,
-                "DB user creation failed, compensating by deleting identity engine user"
-            );
-            if let Err(cleanup_err) = state
-                .identity_engine()
-                .user_store()
-                .delete_user(&identity_subject)
-                .await
-            {
-                tracing::error!(
-                    identity_subject = %identity_subject,
-                    error = %cleanup_err,
-                    "Failed to delete orphaned identity engine user during compensation"
-                );
-            }
-            return Err(e);
-        }
-    };
-
-    // Store password credential AFTER user record exists in DB,
-    // so resolve_user_id can map identity_subject → users.id correctly
-    if let Some(ref password) = password_for_credential {
-        state
-            .identity_engine()
-            .user_store()
-            .set_user_password(&identity_subject, password, false)
-            .await?;
-        // Update password_changed_at timestamp
-        state
-            .user_service()
-            .update_password_changed_at(user.id)
-            .await?;
-    }
-
-    // Auto-add user to the tenant if created in a tenant context
-    if let Some(tenant_id) = effective_tenant_id {
-        let add_input = AddUserToTenantInput {
-            user_id: *user.id,
-            tenant_id,
-            role_in_tenant: "member".to_string(),
-        };
-        state.user_service().add_to_tenant(add_input).await?;
-    }
-
-    let _ = write_audit_log_generic(
-        &state,
-        &headers,
-        "user.create",
-        "user",
-        Some(*user.id),
-        None,
-        serde_json::to_value(&user).ok(),
+    let user = run_user_create(
+        state,
+        UserCreateContext::new(input, password, tenant_id),
+        idempotency_key,
     )
-    .await;
-    Ok((
-        StatusCode::CREATED,
-        Json(SuccessResponse::new(user).with_password_warning(breach_warning)),
-    ))
+    .await?;
+
+    Ok((user, breach_warning))
 }
 
 /// Get current user's own profile
@@ -546,7 +572,7 @@ pub async fn get_me<S: HasServices>(
 ) -> Result<impl IntoResponse> {
     let id = StringUuid::from(auth.user_id);
     let user = state.user_service().get(id).await?;
-    Ok(Json(SuccessResponse::new(user)))
+    Ok(with_etag(user.version, Json(SuccessResponse::new(user))))
 }
 
 /// Update current user's own profile (display_name, avatar_url)
@@ -554,8 +580,11 @@ pub async fn get_me<S: HasServices>(
     put,
     path = "/api/v1/users/me",
     tag = "Tenant Access",
+    params(("If-Match" = Option<String>, Header, description = "Expected user version (ETag)")),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
 pub async fn update_me<S: HasServices>(
@@ -568,7 +597,10 @@ pub async fn update_me<S: HasServices>(
     input
         .validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
+    let expected_version = if_match_version(&headers)?;
     let before = state.user_service().get(id).await?;
+    // Fail fast before pushing the change to the identity engine
+    AppError::check_version("user", expected_version, before.version)?;
     if input.display_name.is_some() {
         let update = IdentityUserUpdateInput {
             username: None,
@@ -585,7 +617,10 @@ pub async fn update_me<S: HasServices>(
             .update_user(&before.identity_subject, &update)
             .await?;
     }
-    let user = state.user_service().update(id, input).await?;
+    let user = state
+        .user_service()
+        .update(id, input, expected_version)
+        .await?;
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -596,6 +631,38 @@ pub async fn update_me<S: HasServices>(
         serde_json::to_value(&user).ok(),
     )
     .await;
+    Ok(with_etag(user.version, Json(SuccessResponse::new(user))))
+}
+
+/// Set current user's preferred locale for emails and messages
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me/locale",
+    tag = "Tenant Access",
+    request_body = UpdateUserLocaleInput,
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+pub async fn update_my_locale<S: HasServices>(
+    State(state): State<S>,
+    auth: AuthUser,
+    headers: HeaderMap,
+    Json(input): Json<UpdateUserLocaleInput>,
+) -> Result<impl IntoResponse> {
+    let id = StringUuid::from(auth.user_id);
+    let before = state.user_service().get(id).await?;
+    let user = state.user_service().update_locale(id, input.locale).await?;
+    let _ = write_audit_log_generic(
+        &state,
+        &headers,
+        "user.locale.update",
+        "user",
+        Some(*user.id),
+        serde_json::to_value(&before).ok(),
+        serde_json::to_value(&user).ok(),
+    )
+    .await;
     Ok(Json(SuccessResponse::new(user)))
 }
 
@@ -606,23 +673,27 @@ pub async fn update_me<S: HasServices>(
     put,
     path = "/api/v1/users/{id}",
     tag = "Tenant Access",
+    params(("If-Match" = Option<String>, Header, description = "Expected user version (ETag)")),
     responses(
-        (status = 200, description = "Success")
+        (status = 200, description = "Success"),
+        (status = 409, description = "Modified concurrently during the update"),
+        (status = 412, description = "If-Match does not match the current version")
     )
 )]
 pub async fn update<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(input): Json<UpdateUserInput>,
 ) -> Result<impl IntoResponse> {
     // Self-update: allow users to update their own profile
     // Admin update: require platform admin or tenant admin
-    if auth.user_id != id {
-        require_user_management_permission(state.config(), &auth)?;
+    if authz.auth().user_id != id {
+        require_user_management_permission(state.config(), authz.auth())?;
         // Cross-tenant IDOR prevention: verify target user is in caller's tenant
-        ensure_user_in_caller_tenant(&state, &auth, id).await?;
+        ensure_user_in_caller_tenant(&state, &authz, id).await?;
+        ensure_user_in_admin_units(&state, &authz, id).await?;
     }
 
     let id = StringUuid::from(id);
@@ -630,7 +701,10 @@ pub async fn update<S: HasServices>(
     input
         .validate()
         .map_err(|e| AppError::Validation(e.to_string()))?;
+    let expected_version = if_match_version(&headers)?;
     let before = state.user_service().get(id).await?;
+    // Fail fast before pushing the change to the identity engine
+    AppError::check_version("user", expected_version, before.version)?;
     if input.display_name.is_some() {
         let update = IdentityUserUpdateInput {
             username: None,
@@ -647,7 +721,10 @@ pub async fn update<S: HasServices>(
             .update_user(&before.identity_subject, &update)
             .await?;
     }
-    let user = state.user_service().update(id, input).await?;
+    let user = state
+        .user_service()
+        .update(id, input, expected_version)
+        .await?;
     let _ = write_audit_log_generic(
         &state,
         &headers,
@@ -658,7 +735,7 @@ pub async fn update<S: HasServices>(
         serde_json::to_value(&user).ok(),
     )
     .await;
-    Ok(Json(SuccessResponse::new(user)))
+    Ok(with_etag(user.version, Json(SuccessResponse::new(user))))
 }
 
 /// Delete user
@@ -673,14 +750,15 @@ pub async fn update<S: HasServices>(
 )]
 pub async fn delete<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse> {
     // Check authorization: require platform admin or tenant admin
-    require_user_management_permission(state.config(), &auth)?;
+    require_user_management_permission(state.config(), authz.auth())?;
     // Cross-tenant IDOR prevention: verify target user is in caller's tenant
-    ensure_user_in_caller_tenant(&state, &auth, id).await?;
+    ensure_user_in_caller_tenant(&state, &authz, id).await?;
+    ensure_user_in_admin_units(&state, &authz, id).await?;
 
     let id = StringUuid::from(id);
     let before = state.user_service().get(id).await?;
@@ -719,7 +797,7 @@ pub async fn delete<S: HasServices>(
 const VALID_TENANT_ROLES: &[&str] = &["owner", "admin", "member"];
 
 /// Validate that role_in_tenant is one of the allowed values
-fn validate_role_in_tenant(role: &str) -> Result<()> {
+pub(crate) fn validate_role_in_tenant(role: &str) -> Result<()> {
     if VALID_TENANT_ROLES.contains(&role) {
         Ok(())
     } else {
@@ -750,7 +828,7 @@ pub struct AddToTenantRequest {
 )]
 pub async fn add_to_tenant<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Path(user_id): Path<Uuid>,
     Json(input): Json<AddToTenantRequest>,
@@ -759,7 +837,7 @@ pub async fn add_to_tenant<S: HasServices>(
     validate_role_in_tenant(&input.role_in_tenant)?;
 
     // Check authorization: require owner of the target tenant
-    require_tenant_owner(&state, &auth, input.tenant_id).await?;
+    require_tenant_owner(&state, &authz, input.tenant_id).await?;
 
     // Block write operations on non-active tenants
     state
@@ -808,16 +886,16 @@ pub struct UpdateRoleInTenantRequest {
 )]
 pub async fn update_role_in_tenant<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
-    Path((user_id, tenant_id)): Path<(Uuid, Uuid)>,
+    Path((user_id, tenant_id)): Path<(UserId, TenantId)>,
     Json(input): Json<UpdateRoleInTenantRequest>,
 ) -> Result<impl IntoResponse> {
     // Validate role_in_tenant enum
     validate_role_in_tenant(&input.role_in_tenant)?;
 
     // Prevent self-role-modification: users cannot change their own role
-    if user_id == auth.user_id {
+    if *user_id == authz.auth().user_id {
         return Err(AppError::Forbidden(
             "Cannot modify your own role in a tenant".to_string(),
         ));
@@ -826,14 +904,12 @@ pub async fn update_role_in_tenant<S: HasServices>(
     // Ownership transfer requires the caller to actually be the tenant owner
     // (platform admin bypass is NOT allowed for ownership changes)
     if input.role_in_tenant == "owner" {
-        require_actual_tenant_owner(&state, &auth, tenant_id).await?;
+        require_actual_tenant_owner(&state, &authz, *tenant_id).await?;
     } else {
         // Check authorization: require owner of the target tenant
-        require_tenant_owner(&state, &auth, tenant_id).await?;
+        require_tenant_owner(&state, &authz, *tenant_id).await?;
     };
 
-    let user_id = StringUuid::from(user_id);
-    let tenant_id = StringUuid::from(tenant_id);
     let tenant_user = state
         .user_service()
         .update_role_in_tenant(user_id, tenant_id, input.role_in_tenant)
@@ -863,15 +939,13 @@ pub async fn update_role_in_tenant<S: HasServices>(
 )]
 pub async fn remove_from_tenant<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
-    Path((user_id, tenant_id)): Path<(Uuid, Uuid)>,
+    Path((user_id, tenant_id)): Path<(UserId, TenantId)>,
 ) -> Result<impl IntoResponse> {
     // Check authorization: require owner of the target tenant
-    require_tenant_owner(&state, &auth, tenant_id).await?;
+    require_tenant_owner(&state, &authz, *tenant_id).await?;
 
-    let user_id = StringUuid::from(user_id);
-    let tenant_id = StringUuid::from(tenant_id);
     state
         .user_service()
         .remove_from_tenant(user_id, tenant_id)
@@ -911,6 +985,49 @@ pub async fn get_tenants<S: HasServices>(
     Ok(Json(SuccessResponse::new(tenants)))
 }
 
+/// Get a user's activity timeline
+/// Merges login events, audit entries where the user is actor or subject,
+/// session changes and role grants into one feed, newest first.
+/// Same access rules as reading the user.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/timeline",
+    tag = "Tenant Access",
+    responses(
+        (status = 200, description = "Success")
+    )
+)]
+pub async fn timeline<S: HasServices>(
+    State(state): State<S>,
+    authz: AuthzContext,
+    Path(id): Path<Uuid>,
+    Query(pagination): Query<PaginationQuery>,
+) -> Result<impl IntoResponse> {
+    if authz.auth().user_id != id {
+        authz
+            .enforce(
+                &state,
+                &PolicyInput {
+                    action: PolicyAction::UserReadOther,
+                    scope: ResourceScope::User(StringUuid::from(id)),
+                },
+            )
+            .await?;
+        ensure_user_in_caller_tenant(&state, &authz, id).await?;
+    }
+
+    let (entries, total) = state
+        .user_service()
+        .timeline(StringUuid::from(id), pagination.page, pagination.per_page)
+        .await?;
+    Ok(Json(PaginatedResponse::new(
+        entries,
+        pagination.page,
+        pagination.per_page,
+        total,
+    )))
+}
+
 /// Enable MFA for a user
 /// Requires platform admin or tenant admin
 #[utoipa::path(
@@ -923,13 +1040,15 @@ pub async fn get_tenants<S: HasServices>(
 )]
 pub async fn enable_mfa<S: HasServices + HasRequiredActions>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(input): Json<EnableMfaInput>,
 ) -> Result<impl IntoResponse> {
+    let auth = authz.auth();
     // Check authorization: require platform admin or tenant admin
-    require_user_management_permission(state.config(), &auth)?;
+    require_user_management_permission(state.config(), auth)?;
+    ensure_user_in_admin_units(&state, &authz, id).await?;
 
     input
         .validate()
@@ -1022,13 +1141,15 @@ pub struct DisableMfaInput {
 )]
 pub async fn disable_mfa<S: HasServices>(
     State(state): State<S>,
-    auth: AuthUser,
+    authz: AuthzContext,
     headers: HeaderMap,
     Path(id): Path<Uuid>,
     Json(input): Json<DisableMfaInput>,
 ) -> Result<impl IntoResponse> {
+    let auth = authz.auth();
     // Check authorization: require platform admin or tenant admin
-    require_user_management_permission(state.config(), &auth)?;
+    require_user_management_permission(state.config(), auth)?;
+    ensure_user_in_admin_units(&state, &authz, id).await?;
 
     input
         .validate()
@@ -18,7 +18,20 @@ where
             "/api/v1/invitations/accept",
             post(tenant_access_api::invitation::accept::<S>),
         )
+        .route(
+            "/api/v1/invitation-links/validate",
+            get(tenant_access_api::invitation_link::validate_token::<S>),
+        )
         .route("/api/v1/users", post(tenant_access_api::user::create::<S>))
+        // Custom domain endpoints for the edge proxy and hosted login pages
+        .route(
+            "/api/v1/public/custom-domains/tls-ask",
+            get(tenant_access_api::custom_domain::tls_ask::<S>),
+        )
+        .route(
+            "/api/v1/public/custom-domains/current",
+            get(tenant_access_api::custom_domain::current::<S>),
+        )
         // SAML Application public endpoints (metadata + certificate for external SPs)
         .route(
             "/api/v1/tenants/{tenant_id}/saml-apps/{app_id}/metadata",
@@ -28,6 +41,12 @@ where
             "/api/v1/tenants/{tenant_id}/saml-apps/{app_id}/certificate",
             get(tenant_access_api::saml_application::get_certificate::<S>),
         )
+        // Unsubscribe links in notification emails
+        .route(
+            "/api/v1/notifications/unsubscribe",
+            get(tenant_access_api::notification_preference::get_unsubscribe::<S>)
+                .post(tenant_access_api::notification_preference::unsubscribe::<S>),
+        )
 }
 
 pub fn protected_routes<S>() -> Router<S>
@@ -44,6 +63,10 @@ where
             "/api/v1/users/me/tenants",
             get(tenant_access_api::organization::get_my_tenants::<S>),
         )
+        .route(
+            "/api/v1/users/me/mfa-enrollment",
+            get(tenant_access_api::mfa_enrollment::my_requirements::<S>),
+        )
         .route(
             "/api/v1/tenants",
             get(tenant_access_api::tenant::list::<S>).post(tenant_access_api::tenant::create::<S>),
@@ -54,6 +77,53 @@ where
                 .put(tenant_access_api::tenant::update::<S>)
                 .delete(tenant_access_api::tenant::delete::<S>),
         )
+        .route(
+            "/api/v1/tenants/by-slug/{slug}",
+            get(tenant_access_api::tenant::get_by_slug::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{id}/rename",
+            post(tenant_access_api::tenant::rename::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{id}/effective-settings",
+            get(tenant_access_api::tenant::effective_settings::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units",
+            get(tenant_access_api::admin_unit::list::<S>)
+                .post(tenant_access_api::admin_unit::create::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}",
+            get(tenant_access_api::admin_unit::get::<S>)
+                .put(tenant_access_api::admin_unit::update::<S>)
+                .delete(tenant_access_api::admin_unit::delete::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/members",
+            post(tenant_access_api::admin_unit::add_members::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/members/{user_id}",
+            delete(tenant_access_api::admin_unit::remove_member::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/admins",
+            post(tenant_access_api::admin_unit::add_admins::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/admin-units/{unit_id}/admins/{user_id}",
+            delete(tenant_access_api::admin_unit::remove_admin::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/inactivity-policy/preview",
+            get(tenant_access_api::inactivity::preview::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/mfa-enrollment-campaign/progress",
+            get(tenant_access_api::mfa_enrollment::progress::<S>),
+        )
         .route(
             "/api/v1/tenants/{tenant_id}/security/malicious-ip-blacklist",
             get(tenant_access_api::tenant::get_tenant_malicious_ip_blacklist::<S>)
@@ -73,6 +143,38 @@ where
             "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/test",
             post(tenant_access_api::tenant_sso::test_connector::<S>),
         )
+        // Custom domains for hosted login pages
+        .route(
+            "/api/v1/tenants/{tenant_id}/custom-domains",
+            get(tenant_access_api::custom_domain::list::<S>)
+                .post(tenant_access_api::custom_domain::create::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}",
+            delete(tenant_access_api::custom_domain::delete::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}/verify",
+            post(tenant_access_api::custom_domain::verify::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/custom-domains/{domain_id}/certificate",
+            axum::routing::put(tenant_access_api::custom_domain::upload_certificate::<S>)
+                .delete(tenant_access_api::custom_domain::delete_certificate::<S>),
+        )
+        // Data key sealing the tenant's webhook secrets
+        .route(
+            "/api/v1/tenants/{tenant_id}/encryption-key",
+            get(tenant_access_api::tenant_key::status::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/encryption-key/rotate",
+            post(tenant_access_api::tenant_key::rotate::<S>),
+        )
+        .route(
+            "/api/v1/system/encryption-key/reencrypt",
+            post(tenant_access_api::tenant_key::reencrypt_all::<S>),
+        )
         // LDAP group-role mappings
         .route(
             "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-group-mappings",
@@ -87,10 +189,27 @@ where
             "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-search-users",
             post(tenant_access_api::tenant_ldap_group_mappings::search_ldap_users::<S>),
         )
+        // LDAP directory sync
+        .route(
+            "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync",
+            post(tenant_access_api::tenant_ldap_sync::trigger_sync::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync/runs",
+            get(tenant_access_api::tenant_ldap_sync::list_sync_runs::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/sso/connectors/{connector_id}/ldap-sync/runs/{run_id}",
+            get(tenant_access_api::tenant_ldap_sync::get_sync_run::<S>),
+        )
         .route(
             "/api/v1/users/me",
             get(tenant_access_api::user::get_me::<S>).put(tenant_access_api::user::update_me::<S>),
         )
+        .route(
+            "/api/v1/users/me/locale",
+            axum::routing::put(tenant_access_api::user::update_my_locale::<S>),
+        )
         .route(
             "/api/v1/users/{id}",
             get(tenant_access_api::user::get::<S>)
@@ -102,11 +221,22 @@ where
             post(tenant_access_api::user::enable_mfa::<S>)
                 .delete(tenant_access_api::user::disable_mfa::<S>),
         )
+        .route(
+            "/api/v1/users/{id}/notification-preferences",
+            get(tenant_access_api::notification_preference::get_notification_preferences::<S>)
+                .put(
+                    tenant_access_api::notification_preference::update_notification_preferences::<S>,
+                ),
+        )
         .route(
             "/api/v1/users/{id}/tenants",
             get(tenant_access_api::user::get_tenants::<S>)
                 .post(tenant_access_api::user::add_to_tenant::<S>),
         )
+        .route(
+            "/api/v1/users/{id}/timeline",
+            get(tenant_access_api::user::timeline::<S>),
+        )
         .route(
             "/api/v1/users/{user_id}/tenants/{tenant_id}",
             delete(tenant_access_api::user::remove_from_tenant::<S>)
@@ -134,6 +264,24 @@ where
             "/api/v1/invitations/{id}/resend",
             post(tenant_access_api::invitation::resend::<S>),
         )
+        // Shareable invitation links
+        .route(
+            "/api/v1/tenants/{tenant_id}/invitation-links",
+            get(tenant_access_api::invitation_link::list::<S>)
+                .post(tenant_access_api::invitation_link::create::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/invitation-links/{id}/revoke",
+            post(tenant_access_api::invitation_link::revoke::<S>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/invitation-links/{id}/redemptions",
+            get(tenant_access_api::invitation_link::list_redemptions::<S>),
+        )
+        .route(
+            "/api/v1/invitation-links/join",
+            post(tenant_access_api::invitation_link::join::<S>),
+        )
         // SAML Application CRUD (protected)
         .route(
             "/api/v1/tenants/{tenant_id}/saml-apps",
@@ -0,0 +1,351 @@
+//! Admin unit service: unit management and delegated admin boundaries
+
+use crate::error::{AppError, Result};
+use crate::models::admin_unit::{
+    AdminUnit, AdminUnitResponse, AdminUnitUsersInput, CreateAdminUnitInput, UpdateAdminUnitInput,
+};
+use crate::models::common::StringUuid;
+use crate::repository::AdminUnitRepository;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct AdminUnitService<R: AdminUnitRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: AdminUnitRepository> AdminUnitService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list(&self, tenant_id: StringUuid) -> Result<Vec<AdminUnit>> {
+        self.repo.list_by_tenant(tenant_id).await
+    }
+
+    pub async fn get(&self, tenant_id: StringUuid, id: StringUuid) -> Result<AdminUnitResponse> {
+        let unit = self.find(tenant_id, id).await?;
+        Ok(AdminUnitResponse {
+            admin_ids: self.repo.list_admins(id).await?,
+            member_ids: self.repo.list_members(id).await?,
+            unit,
+        })
+    }
+
+    pub async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: CreateAdminUnitInput,
+    ) -> Result<AdminUnit> {
+        input.validate()?;
+        self.ensure_name_available(tenant_id, &input.name, None)
+            .await?;
+        self.repo.create(tenant_id, &input).await
+    }
+
+    pub async fn update(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        input: UpdateAdminUnitInput,
+    ) -> Result<AdminUnit> {
+        input.validate()?;
+        self.find(tenant_id, id).await?;
+        if let Some(name) = &input.name {
+            self.ensure_name_available(tenant_id, name, Some(id))
+                .await?;
+        }
+        self.repo.update(id, &input).await
+    }
+
+    pub async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<AdminUnit> {
+        let unit = self.find(tenant_id, id).await?;
+        self.repo.delete(id).await?;
+        Ok(unit)
+    }
+
+    pub async fn add_members(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        input: AdminUnitUsersInput,
+    ) -> Result<AdminUnitResponse> {
+        self.find(tenant_id, id).await?;
+        for user_id in self.tenant_members(tenant_id, input).await? {
+            self.repo.add_member(id, user_id).await?;
+        }
+        self.get(tenant_id, id).await
+    }
+
+    pub async fn remove_member(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<()> {
+        self.find(tenant_id, id).await?;
+        self.repo.remove_member(id, user_id).await
+    }
+
+    pub async fn add_admins(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        input: AdminUnitUsersInput,
+    ) -> Result<AdminUnitResponse> {
+        self.find(tenant_id, id).await?;
+        for user_id in self.tenant_members(tenant_id, input).await? {
+            self.repo.add_admin(id, user_id).await?;
+        }
+        self.get(tenant_id, id).await
+    }
+
+    pub async fn remove_admin(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<()> {
+        self.find(tenant_id, id).await?;
+        self.repo.remove_admin(id, user_id).await
+    }
+
+    /// Check that `admin_id` may manage `user_id` in the tenant: admins of
+    /// no unit manage the whole tenant, admins of units only their members.
+    /// Callers exempt tenant owners and platform admins beforehand.
+    pub async fn check_boundary(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<()> {
+        if self
+            .repo
+            .administered_unit_ids(tenant_id, admin_id)
+            .await?
+            .is_empty()
+            || self
+                .repo
+                .manages_member(tenant_id, admin_id, user_id)
+                .await?
+        {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(
+            "User is outside the admin units you manage".to_string(),
+        ))
+    }
+
+    /// Check that a delegated admin may create users in `unit_id`: admins of
+    /// no unit may create users anywhere, admins of units only in their own.
+    pub async fn check_create_boundary(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+        unit_id: Option<StringUuid>,
+    ) -> Result<()> {
+        let units = self.repo.administered_unit_ids(tenant_id, admin_id).await?;
+        match unit_id {
+            None if units.is_empty() => Ok(()),
+            None => Err(AppError::Forbidden(
+                "admin_unit_id is required: you can only create users in the admin units you manage"
+                    .to_string(),
+            )),
+            Some(unit_id) if units.is_empty() || units.contains(&unit_id) => {
+                self.find(tenant_id, unit_id).await.map(|_| ())
+            }
+            Some(_) => Err(AppError::Forbidden(
+                "You can only create users in the admin units you manage".to_string(),
+            )),
+        }
+    }
+
+    /// Add a newly created tenant member to a unit
+    pub async fn add_created_member(&self, unit_id: StringUuid, user_id: StringUuid) -> Result<()> {
+        self.repo.add_member(unit_id, user_id).await
+    }
+
+    async fn find(&self, tenant_id: StringUuid, id: StringUuid) -> Result<AdminUnit> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .filter(|unit| unit.tenant_id == tenant_id)
+            .ok_or_else(|| AppError::NotFound(format!("Admin unit {} not found", id)))
+    }
+
+    async fn ensure_name_available(
+        &self,
+        tenant_id: StringUuid,
+        name: &str,
+        except: Option<StringUuid>,
+    ) -> Result<()> {
+        match self.repo.find_by_name(tenant_id, name).await? {
+            Some(existing) if Some(existing.id) != except => Err(AppError::Conflict(format!(
+                "Admin unit '{}' already exists",
+                name
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn tenant_members(
+        &self,
+        tenant_id: StringUuid,
+        input: AdminUnitUsersInput,
+    ) -> Result<Vec<StringUuid>> {
+        input.validate()?;
+        for user_id in &input.user_ids {
+            if !self.repo.is_tenant_member(tenant_id, *user_id).await? {
+                return Err(AppError::BadRequest(format!(
+                    "User {} is not a member of this tenant",
+                    user_id
+                )));
+            }
+        }
+        Ok(input.user_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::admin_unit::MockAdminUnitRepository;
+    use chrono::Utc;
+
+    fn unit(tenant_id: StringUuid) -> AdminUnit {
+        AdminUnit {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            name: "Engineering".to_string(),
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unscoped_admin_manages_whole_tenant() {
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_administered_unit_ids()
+            .returning(|_, _| Ok(vec![]));
+        repo.expect_manages_member().never();
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let result = service
+            .check_boundary(
+                StringUuid::new_v4(),
+                StringUuid::new_v4(),
+                StringUuid::new_v4(),
+            )
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_scoped_admin_limited_to_unit_members() {
+        let member = StringUuid::new_v4();
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_administered_unit_ids()
+            .returning(|_, _| Ok(vec![StringUuid::new_v4()]));
+        repo.expect_manages_member()
+            .returning(move |_, _, user_id| Ok(user_id == member));
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let (tenant_id, admin_id) = (StringUuid::new_v4(), StringUuid::new_v4());
+        assert!(service
+            .check_boundary(tenant_id, admin_id, member)
+            .await
+            .is_ok());
+        assert!(matches!(
+            service
+                .check_boundary(tenant_id, admin_id, StringUuid::new_v4())
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_scoped_admin_creates_users_only_in_own_units() {
+        let tenant_id = StringUuid::new_v4();
+        let own = unit(tenant_id);
+        let own_id = own.id;
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_administered_unit_ids()
+            .returning(move |_, _| Ok(vec![own_id]));
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(own.clone())));
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let admin_id = StringUuid::new_v4();
+        assert!(service
+            .check_create_boundary(tenant_id, admin_id, Some(own_id))
+            .await
+            .is_ok());
+        assert!(service
+            .check_create_boundary(tenant_id, admin_id, None)
+            .await
+            .is_err());
+        assert!(service
+            .check_create_boundary(tenant_id, admin_id, Some(StringUuid::new_v4()))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_add_members_rejects_non_members() {
+        let tenant_id = StringUuid::new_v4();
+        let existing = unit(tenant_id);
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(existing.clone())));
+        repo.expect_is_tenant_member().returning(|_, _| Ok(false));
+        repo.expect_add_member().never();
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let result = service
+            .add_members(
+                tenant_id,
+                StringUuid::new_v4(),
+                AdminUnitUsersInput {
+                    user_ids: vec![StringUuid::new_v4()],
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unit_of_other_tenant_not_found() {
+        let other = unit(StringUuid::new_v4());
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(other.clone())));
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let result = service
+            .get(StringUuid::new_v4(), StringUuid::new_v4())
+            .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_duplicate_name() {
+        let tenant_id = StringUuid::new_v4();
+        let existing = unit(tenant_id);
+        let mut repo = MockAdminUnitRepository::new();
+        repo.expect_find_by_name()
+            .returning(move |_, _| Ok(Some(existing.clone())));
+        repo.expect_create().never();
+
+        let service = AdminUnitService::new(Arc::new(repo));
+        let result = service
+            .create(
+                tenant_id,
+                CreateAdminUnitInput {
+                    name: "Engineering".to_string(),
+                    description: None,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+}
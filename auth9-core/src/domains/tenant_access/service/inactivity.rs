@@ -0,0 +1,306 @@
+//! Inactive member lifecycle: previews and sweep scheduling
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::inactivity::{
+    InactiveMember, InactivityPolicy, InactivityPreview, InactivityPreviewEntry, InactivityStep,
+    InactivitySummary, MAX_PREVIEW_ENTRIES,
+};
+use crate::models::job::{CreateJobInput, JobKind};
+use crate::repository::{InactivityRepository, JobRepository};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+/// Members scanned per query
+pub const SCAN_PAGE_SIZE: i64 = 200;
+
+/// Minimum time between two sweeps of a tenant
+pub const SWEEP_INTERVAL_HOURS: i64 = 24;
+
+/// Recent jobs of a tenant checked for an earlier sweep
+const RECENT_JOBS_CHECKED: i64 = 50;
+
+/// Scanned page of inactive members with the steps due for them
+pub struct DueMembers {
+    pub due: Vec<(InactiveMember, InactivityStep)>,
+    /// Cursor for the next page; `None` after the last page
+    pub next_after: Option<StringUuid>,
+}
+
+pub struct InactivityService<R: InactivityRepository> {
+    repo: Arc<R>,
+}
+
+impl<R: InactivityRepository> InactivityService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub fn repo(&self) -> &R {
+        &self.repo
+    }
+
+    /// Non-exempt inactive members after `after`, with the steps due now
+    pub async fn due_members(
+        &self,
+        tenant_id: StringUuid,
+        policy: &InactivityPolicy,
+        now: DateTime<Utc>,
+        after: Option<StringUuid>,
+    ) -> Result<DueMembers> {
+        let members = self
+            .repo
+            .find_inactive_members(
+                tenant_id,
+                policy.inactive_before(now),
+                after,
+                SCAN_PAGE_SIZE,
+            )
+            .await?;
+        let next_after = if (members.len() as i64) < SCAN_PAGE_SIZE {
+            None
+        } else {
+            members.last().map(|m| m.user_id)
+        };
+        let due = members
+            .into_iter()
+            .filter(|member| !policy.is_exempt(member))
+            .filter_map(|member| match policy.next_step(&member) {
+                Some((step, due_at)) if due_at <= now => Some((member, step)),
+                _ => None,
+            })
+            .collect();
+        Ok(DueMembers { due, next_after })
+    }
+
+    /// Who `policy` affects at the next sweep and later
+    pub async fn preview(
+        &self,
+        tenant_id: StringUuid,
+        policy: InactivityPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<InactivityPreview> {
+        let mut summary = InactivitySummary::default();
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        let mut after = None;
+        loop {
+            let members = self
+                .repo
+                .find_inactive_members(
+                    tenant_id,
+                    policy.inactive_before(now),
+                    after,
+                    SCAN_PAGE_SIZE,
+                )
+                .await?;
+            let last_page = (members.len() as i64) < SCAN_PAGE_SIZE;
+            after = members.last().map(|m| m.user_id);
+
+            for member in members.into_iter().filter(|m| !policy.is_exempt(m)) {
+                let next = policy.next_step(&member);
+                match next {
+                    Some((_, due_at)) if due_at > now => summary.waiting += 1,
+                    Some((InactivityStep::Notify, _)) => summary.notify += 1,
+                    Some((InactivityStep::Disable, _)) => summary.disable += 1,
+                    Some((InactivityStep::Delete, _)) => summary.delete += 1,
+                    None => {}
+                }
+                if entries.len() < MAX_PREVIEW_ENTRIES {
+                    entries.push(InactivityPreviewEntry {
+                        member,
+                        next_step: next.map(|(step, _)| step),
+                        due_at: next.map(|(_, due_at)| due_at),
+                    });
+                } else {
+                    truncated = true;
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(InactivityPreview {
+            policy,
+            as_of: now,
+            summary,
+            members: entries,
+            truncated,
+        })
+    }
+
+    /// Queue a sweep job for every tenant with an enabled policy that has no
+    /// sweep pending and none in the last [`SWEEP_INTERVAL_HOURS`]. Returns
+    /// the number of jobs queued.
+    pub async fn schedule_sweeps<J: JobRepository>(
+        &self,
+        jobs: &J,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let recent_after = now - Duration::hours(SWEEP_INTERVAL_HOURS);
+        let mut queued = 0;
+        for tenant_id in self.repo.tenants_with_enabled_policy().await? {
+            let recent = jobs.list_by_tenant(tenant_id, RECENT_JOBS_CHECKED).await?;
+            let swept = recent.iter().any(|job| {
+                job.job_kind() == Some(JobKind::InactivitySweep)
+                    && (!job.is_terminal() || job.created_at > recent_after)
+            });
+            if swept {
+                continue;
+            }
+            jobs.create(&CreateJobInput {
+                kind: JobKind::InactivitySweep,
+                tenant_id: Some(tenant_id),
+                payload: serde_json::json!({}),
+                created_by: None,
+            })
+            .await?;
+            queued += 1;
+        }
+        Ok(queued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::job::{Job, JobStatus};
+    use crate::repository::inactivity::MockInactivityRepository;
+    use crate::repository::job::MockJobRepository;
+
+    fn policy() -> InactivityPolicy {
+        serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "inactive_days": 90,
+            "delete_after_days": 30,
+        }))
+        .unwrap()
+    }
+
+    fn member(role: &str, notified_days_ago: Option<i64>) -> InactiveMember {
+        let now = Utc::now();
+        InactiveMember {
+            user_id: StringUuid::new_v4(),
+            email: "idle@example.com".to_string(),
+            role_in_tenant: role.to_string(),
+            joined_at: now - Duration::days(365),
+            last_login_at: Some(now - Duration::days(120)),
+            notified_at: notified_days_ago.map(|days| now - Duration::days(days)),
+            disabled_at: None,
+            shared_account: false,
+            locked: false,
+        }
+    }
+
+    fn sweep_job(tenant_id: StringUuid, status: JobStatus, age_hours: i64) -> Job {
+        let created_at = Utc::now() - Duration::hours(age_hours);
+        Job {
+            id: StringUuid::new_v4(),
+            tenant_id: Some(tenant_id),
+            kind: JobKind::InactivitySweep.as_str().to_string(),
+            status: status.as_str().to_string(),
+            payload: serde_json::json!({}),
+            total_items: 0,
+            processed_items: 0,
+            failed_items: 0,
+            results: vec![],
+            error: None,
+            cancel_requested: false,
+            claim_id: None,
+            resume_after: None,
+            created_by: None,
+            created_at,
+            started_at: None,
+            finished_at: None,
+            updated_at: created_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_due_members_skips_exempt_and_waiting() {
+        let due = member("member", None);
+        let due_id = due.user_id;
+        let members = vec![
+            due,
+            member("owner", None),
+            // Warned 3 days ago; locked only after 14 days
+            member("member", Some(3)),
+        ];
+        let mut repo = MockInactivityRepository::new();
+        repo.expect_find_inactive_members()
+            .returning(move |_, _, _, _| Ok(members.clone()));
+
+        let service = InactivityService::new(Arc::new(repo));
+        let page = service
+            .due_members(StringUuid::new_v4(), &policy(), Utc::now(), None)
+            .await
+            .unwrap();
+
+        assert_eq!(page.due.len(), 1);
+        assert_eq!(page.due[0].0.user_id, due_id);
+        assert_eq!(page.due[0].1, InactivityStep::Notify);
+        assert!(page.next_after.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preview_summarizes_steps() {
+        let members = vec![
+            member("member", None),
+            member("member", Some(20)),
+            member("member", Some(3)),
+            member("owner", None),
+        ];
+        let mut repo = MockInactivityRepository::new();
+        repo.expect_find_inactive_members()
+            .returning(move |_, _, _, _| Ok(members.clone()));
+
+        let service = InactivityService::new(Arc::new(repo));
+        let preview = service
+            .preview(StringUuid::new_v4(), policy(), Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            preview.summary,
+            InactivitySummary {
+                notify: 1,
+                disable: 1,
+                delete: 0,
+                waiting: 1,
+            }
+        );
+        assert_eq!(preview.members.len(), 3);
+        assert!(!preview.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_sweeps_once_per_interval() {
+        let fresh = StringUuid::new_v4();
+        let swept_recently = StringUuid::new_v4();
+        let pending = StringUuid::new_v4();
+        let mut repo = MockInactivityRepository::new();
+        repo.expect_tenants_with_enabled_policy()
+            .returning(move || Ok(vec![fresh, swept_recently, pending]));
+
+        let mut jobs = MockJobRepository::new();
+        jobs.expect_list_by_tenant().returning(move |tenant_id, _| {
+            Ok(if tenant_id == swept_recently {
+                vec![sweep_job(tenant_id, JobStatus::Succeeded, 2)]
+            } else if tenant_id == pending {
+                vec![sweep_job(tenant_id, JobStatus::Queued, 48)]
+            } else {
+                vec![sweep_job(tenant_id, JobStatus::Succeeded, 30)]
+            })
+        });
+        jobs.expect_create()
+            .withf(move |input| {
+                input.kind == JobKind::InactivitySweep && input.tenant_id == Some(fresh)
+            })
+            .times(1)
+            .returning(|input| Ok(sweep_job(input.tenant_id.unwrap(), JobStatus::Queued, 0)));
+
+        let service = InactivityService::new(Arc::new(repo));
+        assert_eq!(service.schedule_sweeps(&jobs, Utc::now()).await.unwrap(), 1);
+    }
+}
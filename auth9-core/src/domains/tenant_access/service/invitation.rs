@@ -2,6 +2,7 @@
 
 use crate::domains::platform::service::EmailService;
 use crate::error::{AppError, Result};
+use crate::i18n::resolve_locale;
 use crate::models::common::StringUuid;
 use crate::models::email::EmailAddress;
 use crate::models::email_template::EmailTemplateType;
@@ -110,11 +111,15 @@ where
             "year".to_string(),
             chrono::Utc::now().format("%Y").to_string(),
         );
-        vars.insert("app_name".to_string(), "Auth9".to_string());
 
         if let Ok(rendered) = self
             .email_service
-            .resolve_and_render(EmailTemplateType::Invitation, &vars)
+            .resolve_and_render_for_tenant(
+                EmailTemplateType::Invitation,
+                resolve_locale(None, None, tenant.settings.default_locale.as_deref()),
+                Some(tenant.id),
+                &vars,
+            )
             .await
         {
             let _ = self
@@ -136,6 +141,11 @@ where
         Ok(invitation)
     }
 
+    /// Base URL that invitation links point to
+    pub fn app_base_url(&self) -> &str {
+        &self.app_base_url
+    }
+
     /// Get an invitation by ID
     ///
     /// Dynamically updates expired pending invitations to show "expired" status.
@@ -290,11 +300,15 @@ where
             "year".to_string(),
             chrono::Utc::now().format("%Y").to_string(),
         );
-        vars.insert("app_name".to_string(), "Auth9".to_string());
 
         let rendered = self
             .email_service
-            .resolve_and_render(EmailTemplateType::Invitation, &vars)
+            .resolve_and_render_for_tenant(
+                EmailTemplateType::Invitation,
+                resolve_locale(None, None, tenant.settings.default_locale.as_deref()),
+                Some(tenant.id),
+                &vars,
+            )
             .await?;
 
         self.email_service
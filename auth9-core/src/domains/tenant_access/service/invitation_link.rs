@@ -0,0 +1,295 @@
+//! Shareable invitation links: creation, revocation and join bookkeeping
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::invitation_link::{
+    normalize_email_domains, CreateInvitationLinkInput, InvitationLink, InvitationLinkRedemption,
+    InvitationLinkStatus, DEFAULT_LINK_EXPIRES_IN_HOURS,
+};
+use crate::repository::invitation_link::InvitationLinkRepositoryImpl;
+use crate::repository::InvitationLinkRepository;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use validator::Validate;
+
+pub struct InvitationLinkService {
+    repo: Arc<dyn InvitationLinkRepository>,
+}
+
+impl InvitationLinkService {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(InvitationLinkRepositoryImpl::new(pool)))
+    }
+
+    pub fn new(repo: Arc<dyn InvitationLinkRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// Create a link. Returns it with the raw token, which is not stored.
+    pub async fn create(
+        &self,
+        tenant_id: StringUuid,
+        created_by: StringUuid,
+        input: CreateInvitationLinkInput,
+    ) -> Result<(InvitationLink, String)> {
+        input.validate()?;
+
+        let bytes: [u8; 32] = rand::thread_rng().gen();
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+        let now = Utc::now();
+        let link = InvitationLink {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            name: input
+                .name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty()),
+            role_ids: input.role_ids,
+            allowed_email_domains: normalize_email_domains(&input.allowed_email_domains),
+            max_uses: input.max_uses,
+            use_count: 0,
+            created_by,
+            token_hash: hash_token(&token),
+            expires_at: now
+                + Duration::hours(
+                    input
+                        .expires_in_hours
+                        .unwrap_or(DEFAULT_LINK_EXPIRES_IN_HOURS),
+                ),
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(&link).await?;
+        Ok((link, token))
+    }
+
+    pub async fn get(&self, id: StringUuid) -> Result<InvitationLink> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Invitation link {} not found", id)))
+    }
+
+    /// Look up a link by its raw token
+    pub async fn get_by_token(&self, token: &str) -> Result<InvitationLink> {
+        self.repo
+            .find_by_token_hash(&hash_token(token))
+            .await?
+            .ok_or_else(|| AppError::NotFound("Invalid invitation link".to_string()))
+    }
+
+    pub async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<InvitationLink>, i64)> {
+        let offset = (page - 1) * per_page;
+        let links = self
+            .repo
+            .list_by_tenant(tenant_id, offset, per_page)
+            .await?;
+        let total = self.repo.count_by_tenant(tenant_id).await?;
+        Ok((links, total))
+    }
+
+    pub async fn revoke(&self, id: StringUuid) -> Result<InvitationLink> {
+        let link = self.get(id).await?;
+        if link.revoked_at.is_some() {
+            return Err(AppError::BadRequest(
+                "Invitation link is already revoked".to_string(),
+            ));
+        }
+        self.repo.revoke(id).await?;
+        self.get(id).await
+    }
+
+    /// Check that `email` may join through `link` right now
+    pub fn check_joinable(link: &InvitationLink, email: &str) -> Result<()> {
+        match link.status() {
+            InvitationLinkStatus::Active => {}
+            status => {
+                return Err(AppError::BadRequest(format!(
+                    "Invitation link is no longer valid (status: {})",
+                    status
+                )))
+            }
+        }
+        if !link.allows_email(email) {
+            return Err(AppError::Forbidden(
+                "Your email domain is not allowed to join through this link".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Take one use of the link for `user_id` and record the join.
+    ///
+    /// Usage limits hold under concurrent joins; a user joining twice through
+    /// the same link is rejected without consuming a use.
+    pub async fn redeem(
+        &self,
+        link: &InvitationLink,
+        user_id: StringUuid,
+        email: &str,
+    ) -> Result<InvitationLinkRedemption> {
+        Self::check_joinable(link, email)?;
+
+        if !self.repo.claim_use(link.id).await? {
+            return Err(AppError::BadRequest(
+                "Invitation link is no longer valid".to_string(),
+            ));
+        }
+
+        let redemption = InvitationLinkRedemption {
+            id: StringUuid::new_v4(),
+            link_id: link.id,
+            tenant_id: link.tenant_id,
+            user_id,
+            email: email.to_string(),
+            created_at: Utc::now(),
+        };
+        match self.repo.record_redemption(&redemption).await {
+            Ok(true) => Ok(redemption),
+            Ok(false) => {
+                self.repo.release_use(link.id).await?;
+                Err(AppError::Conflict(
+                    "You already joined through this invitation link".to_string(),
+                ))
+            }
+            Err(e) => {
+                let _ = self.repo.release_use(link.id).await;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn list_redemptions(
+        &self,
+        link_id: StringUuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<InvitationLinkRedemption>, i64)> {
+        let offset = (page - 1) * per_page;
+        let redemptions = self
+            .repo
+            .list_redemptions(link_id, offset, per_page)
+            .await?;
+        let total = self.repo.count_redemptions(link_id).await?;
+        Ok((redemptions, total))
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::invitation_link::MockInvitationLinkRepository;
+    use mockall::predicate::*;
+
+    fn input() -> CreateInvitationLinkInput {
+        CreateInvitationLinkInput {
+            name: Some("  Spring hiring ".to_string()),
+            role_ids: vec![StringUuid::new_v4()],
+            allowed_email_domains: vec!["Example.com".to_string()],
+            max_uses: Some(5),
+            expires_in_hours: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_stores_hash_of_returned_token() {
+        let mut repo = MockInvitationLinkRepository::new();
+        repo.expect_create().returning(|_| Ok(()));
+        let service = InvitationLinkService::new(Arc::new(repo));
+
+        let (link, token) = service
+            .create(StringUuid::new_v4(), StringUuid::new_v4(), input())
+            .await
+            .unwrap();
+        assert_eq!(link.token_hash, hash_token(&token));
+        assert_eq!(link.name.as_deref(), Some("Spring hiring"));
+        assert_eq!(link.allowed_email_domains, vec!["example.com".to_string()]);
+        assert!(link.expires_at > Utc::now() + Duration::hours(167));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_token_looks_up_hash() {
+        let mut repo = MockInvitationLinkRepository::new();
+        repo.expect_find_by_token_hash()
+            .with(eq(hash_token("abc")))
+            .returning(|_| Ok(Some(InvitationLink::default())));
+        repo.expect_find_by_token_hash().returning(|_| Ok(None));
+        let service = InvitationLinkService::new(Arc::new(repo));
+
+        assert!(service.get_by_token("abc").await.is_ok());
+        assert!(matches!(
+            service.get_by_token("other").await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_rejects_disallowed_domain_without_claiming() {
+        let repo = MockInvitationLinkRepository::new();
+        let service = InvitationLinkService::new(Arc::new(repo));
+        let link = InvitationLink {
+            allowed_email_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+
+        let result = service
+            .redeem(&link, StringUuid::new_v4(), "eve@evil.io")
+            .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_fails_when_no_use_left() {
+        let mut repo = MockInvitationLinkRepository::new();
+        repo.expect_claim_use().returning(|_| Ok(false));
+        let service = InvitationLinkService::new(Arc::new(repo));
+
+        let result = service
+            .redeem(&InvitationLink::default(), StringUuid::new_v4(), "a@b.io")
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_twice_releases_use() {
+        let mut repo = MockInvitationLinkRepository::new();
+        repo.expect_claim_use().returning(|_| Ok(true));
+        repo.expect_record_redemption().returning(|_| Ok(false));
+        repo.expect_release_use().times(1).returning(|_| Ok(()));
+        let service = InvitationLinkService::new(Arc::new(repo));
+
+        let result = service
+            .redeem(&InvitationLink::default(), StringUuid::new_v4(), "a@b.io")
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redeem_records_attribution() {
+        let mut repo = MockInvitationLinkRepository::new();
+        repo.expect_claim_use().returning(|_| Ok(true));
+        repo.expect_record_redemption().returning(|_| Ok(true));
+        let service = InvitationLinkService::new(Arc::new(repo));
+        let link = InvitationLink::default();
+        let user_id = StringUuid::new_v4();
+
+        let redemption = service.redeem(&link, user_id, "a@b.io").await.unwrap();
+        assert_eq!(redemption.link_id, link.id);
+        assert_eq!(redemption.tenant_id, link.tenant_id);
+        assert_eq!(redemption.user_id, user_id);
+    }
+}
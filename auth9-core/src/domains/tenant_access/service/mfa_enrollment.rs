@@ -0,0 +1,270 @@
+//! MFA enrollment campaigns: progress, reminders and enforcement
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::mfa_enrollment::{
+    MfaCampaignProgress, MfaCampaignSummary, MfaEnrollmentCampaign, MfaEnrollmentRequirement,
+    MfaEnrollmentStatus, MAX_PROGRESS_ENTRIES,
+};
+use crate::repository::mfa_enrollment::MfaEnrollmentRepositoryImpl;
+use crate::repository::MfaEnrollmentRepository;
+use crate::state::HasServices;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+use std::sync::Arc;
+
+/// Members scanned per query
+const SCAN_PAGE_SIZE: i64 = 200;
+
+pub struct MfaEnrollmentService<R: MfaEnrollmentRepository> {
+    repo: Arc<R>,
+}
+
+impl MfaEnrollmentService<MfaEnrollmentRepositoryImpl> {
+    pub fn from_pool(pool: MySqlPool) -> Self {
+        Self::new(Arc::new(MfaEnrollmentRepositoryImpl::new(pool)))
+    }
+}
+
+impl<R: MfaEnrollmentRepository> MfaEnrollmentService<R> {
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    /// How far the tenant's members are with enrolling
+    pub async fn progress(
+        &self,
+        tenant_id: StringUuid,
+        campaign: MfaEnrollmentCampaign,
+        now: DateTime<Utc>,
+    ) -> Result<MfaCampaignProgress> {
+        let mut summary = MfaCampaignSummary::default();
+        let mut not_enrolled = Vec::new();
+        let mut truncated = false;
+        let mut after = None;
+        loop {
+            let members = self
+                .repo
+                .list_members(tenant_id, after, SCAN_PAGE_SIZE)
+                .await?;
+            let last_page = (members.len() as i64) < SCAN_PAGE_SIZE;
+            after = members.last().map(|m| m.user_id);
+
+            for member in members {
+                summary.total += 1;
+                match campaign.status(
+                    member.user_id,
+                    &member.role_in_tenant,
+                    member.mfa_enrolled,
+                    now,
+                ) {
+                    MfaEnrollmentStatus::Exempt => summary.exempt += 1,
+                    MfaEnrollmentStatus::Enrolled => summary.enrolled += 1,
+                    MfaEnrollmentStatus::Pending | MfaEnrollmentStatus::Overdue => {
+                        summary.not_enrolled += 1;
+                        if not_enrolled.len() < MAX_PROGRESS_ENTRIES {
+                            not_enrolled.push(member);
+                        } else {
+                            truncated = true;
+                        }
+                    }
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(MfaCampaignProgress {
+            deadline_passed: now >= campaign.deadline,
+            completion_percent: summary.completion_percent(),
+            campaign,
+            as_of: now,
+            summary,
+            not_enrolled,
+            truncated,
+        })
+    }
+
+    /// Refuse tenant tokens to members past the deadline of an enabled
+    /// campaign without MFA
+    pub async fn ensure_token_allowed(
+        &self,
+        tenant_id: StringUuid,
+        campaign: Option<&MfaEnrollmentCampaign>,
+        user_id: StringUuid,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let Some(campaign) = campaign.filter(|c| c.enabled && now >= c.deadline) else {
+            return Ok(());
+        };
+        let Some(member) = self.repo.find_member(tenant_id, user_id).await? else {
+            return Ok(());
+        };
+        if campaign.status(user_id, &member.role_in_tenant, member.mfa_enrolled, now)
+            == MfaEnrollmentStatus::Overdue
+        {
+            return Err(AppError::Forbidden(
+                "MFA enrollment is required by this tenant. Enroll an authenticator app or passkey to continue."
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Enabled campaigns of the user's tenants and where the user stands in each
+pub async fn enrollment_requirements<S: HasServices>(
+    state: &S,
+    user_id: StringUuid,
+    mfa_enrolled: bool,
+    now: DateTime<Utc>,
+) -> Result<Vec<MfaEnrollmentRequirement>> {
+    let mut requirements = Vec::new();
+    for membership in state.user_service().get_user_tenants(user_id).await? {
+        let tenant = state.tenant_service().get(membership.tenant_id).await?;
+        let Some(campaign) = tenant
+            .settings
+            .mfa_enrollment_campaign
+            .filter(|c| c.enabled)
+        else {
+            continue;
+        };
+        let status = campaign.status(user_id, &membership.role_in_tenant, mfa_enrolled, now);
+        requirements.push(MfaEnrollmentRequirement::new(
+            tenant.id,
+            tenant.name,
+            campaign.deadline,
+            status,
+            now,
+        ));
+    }
+    Ok(requirements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::mfa_enrollment::MfaCampaignMember;
+    use crate::repository::mfa_enrollment::MockMfaEnrollmentRepository;
+    use chrono::Duration;
+    use mockall::predicate::*;
+
+    fn campaign(deadline: DateTime<Utc>) -> MfaEnrollmentCampaign {
+        MfaEnrollmentCampaign {
+            enabled: true,
+            deadline,
+            exempt_user_ids: vec![],
+            exempt_roles: vec!["service".to_string()],
+        }
+    }
+
+    fn member(role: &str, mfa_enrolled: bool) -> MfaCampaignMember {
+        MfaCampaignMember {
+            user_id: StringUuid::new_v4(),
+            email: "member@example.com".to_string(),
+            role_in_tenant: role.to_string(),
+            mfa_enrolled,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_counts_members() {
+        let tenant_id = StringUuid::new_v4();
+        let pending = member("member", false);
+        let members = vec![
+            member("member", true),
+            member("admin", true),
+            pending.clone(),
+            member("service", false),
+        ];
+        let mut repo = MockMfaEnrollmentRepository::new();
+        repo.expect_list_members()
+            .with(eq(tenant_id), eq(None), eq(SCAN_PAGE_SIZE))
+            .returning(move |_, _, _| Ok(members.clone()));
+
+        let now = Utc::now();
+        let progress = MfaEnrollmentService::new(Arc::new(repo))
+            .progress(tenant_id, campaign(now + Duration::days(7)), now)
+            .await
+            .unwrap();
+        assert_eq!(
+            progress.summary,
+            MfaCampaignSummary {
+                total: 4,
+                enrolled: 2,
+                not_enrolled: 1,
+                exempt: 1,
+            }
+        );
+        assert!((progress.completion_percent - 66.666).abs() < 0.01);
+        assert!(!progress.deadline_passed);
+        assert_eq!(progress.not_enrolled, vec![pending]);
+        assert!(!progress.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_overdue_member_is_refused() {
+        let tenant_id = StringUuid::new_v4();
+        let overdue = member("member", false);
+        let user_id = overdue.user_id;
+        let mut repo = MockMfaEnrollmentRepository::new();
+        repo.expect_find_member()
+            .with(eq(tenant_id), eq(user_id))
+            .returning(move |_, _| Ok(Some(overdue.clone())));
+
+        let now = Utc::now();
+        let result = MfaEnrollmentService::new(Arc::new(repo))
+            .ensure_token_allowed(
+                tenant_id,
+                Some(&campaign(now - Duration::days(1))),
+                user_id,
+                now,
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_enrolled_or_before_deadline_is_allowed() {
+        let tenant_id = StringUuid::new_v4();
+        let enrolled = member("member", true);
+        let user_id = enrolled.user_id;
+        let mut repo = MockMfaEnrollmentRepository::new();
+        repo.expect_find_member()
+            .times(1)
+            .returning(move |_, _| Ok(Some(enrolled.clone())));
+        let service = MfaEnrollmentService::new(Arc::new(repo));
+
+        let now = Utc::now();
+        service
+            .ensure_token_allowed(
+                tenant_id,
+                Some(&campaign(now - Duration::days(1))),
+                user_id,
+                now,
+            )
+            .await
+            .unwrap();
+        // No lookup before the deadline, for disabled campaigns or without one
+        service
+            .ensure_token_allowed(
+                tenant_id,
+                Some(&campaign(now + Duration::days(1))),
+                user_id,
+                now,
+            )
+            .await
+            .unwrap();
+        let mut disabled = campaign(now - Duration::days(1));
+        disabled.enabled = false;
+        service
+            .ensure_token_allowed(tenant_id, Some(&disabled), user_id, now)
+            .await
+            .unwrap();
+        service
+            .ensure_token_allowed(tenant_id, None, user_id, now)
+            .await
+            .unwrap();
+    }
+}
@@ -1,9 +1,22 @@
+pub mod admin_unit;
+pub mod custom_domain;
+pub mod inactivity;
 pub mod invitation;
+pub mod invitation_link;
+pub mod mfa_enrollment;
+pub mod sagas;
 pub mod saml_application;
 pub mod tenant;
+pub mod tenant_key;
 pub mod user;
 
+pub use admin_unit::AdminUnitService;
+pub use custom_domain::CustomDomainService;
+pub use inactivity::InactivityService;
 pub use invitation::InvitationService;
+pub use invitation_link::InvitationLinkService;
+pub use mfa_enrollment::MfaEnrollmentService;
 pub use saml_application::SamlApplicationService;
 pub use tenant::{TenantRepositoryBundle, TenantService};
+pub use tenant_key::TenantKeyService;
 pub use user::{UserRepositoryBundle, UserService};
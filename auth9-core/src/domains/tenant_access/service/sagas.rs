@@ -0,0 +1,268 @@
+//! Sagas for user creation and tenant provisioning
+//!
+//! Both span the identity engine and the database; see [`crate::saga`].
+
+use crate::error::{AppError, Result};
+use crate::identity_engine::IdentityUserCreateInput;
+use crate::models::tenant::{CreateTenantInput, Tenant};
+use crate::models::user::{AddUserToTenantInput, CreateUserInput, User};
+use crate::saga::{Recovery, Saga, SagaRunner};
+use crate::state::HasServices;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const USER_CREATE: &str = "user_create";
+pub const TENANT_PROVISION: &str = "tenant_provision";
+
+/// State of a user creation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserCreateContext {
+    pub input: CreateUserInput,
+    /// Tenant the user joins as a member
+    pub tenant_id: Option<Uuid>,
+    /// Never persisted; a resumed saga cannot set it
+    #[serde(skip)]
+    pub password: Option<String>,
+    pub has_password: bool,
+    pub identity_subject: Option<String>,
+    pub user: Option<User>,
+}
+
+impl UserCreateContext {
+    pub fn new(input: CreateUserInput, password: Option<String>, tenant_id: Option<Uuid>) -> Self {
+        Self {
+            input,
+            tenant_id,
+            has_password: password.is_some(),
+            password,
+            identity_subject: None,
+            user: None,
+        }
+    }
+}
+
+/// State of a tenant provisioning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantProvisionContext {
+    pub input: CreateTenantInput,
+    /// User added as the tenant's owner
+    pub owner_id: Option<Uuid>,
+    pub tenant: Option<Tenant>,
+}
+
+/// Identity engine user, then the user record, password and tenant
+/// membership. A failure undoes the user record and the identity engine
+/// user.
+pub fn user_create_saga<S: HasServices>(state: &S) -> Saga<UserCreateContext> {
+    let (create_identity, delete_identity) = (state.clone(), state.clone());
+    let (create_user, delete_user) = (state.clone(), state.clone());
+    let (set_password, add_to_tenant) = (state.clone(), state.clone());
+
+    Saga::new(USER_CREATE, Recovery::Compensate)
+        .step("create_identity_user", move |mut ctx: UserCreateContext| {
+            let state = create_identity.clone();
+            async move {
+                if ctx.identity_subject.is_none() {
+                    let subject = state
+                        .identity_engine()
+                        .user_store()
+                        .create_user(&IdentityUserCreateInput {
+                            username: ctx.input.email.clone(),
+                            email: ctx.input.email.clone(),
+                            first_name: ctx.input.display_name.clone(),
+                            last_name: None,
+                            enabled: true,
+                            email_verified: false,
+                            credentials: None, // Set after the user record exists
+                        })
+                        .await?;
+                    ctx.identity_subject = Some(subject);
+                }
+                Ok(ctx)
+            }
+        })
+        .compensate_with(move |ctx: UserCreateContext| {
+            let state = delete_identity.clone();
+            async move {
+                let Some(subject) = ctx.identity_subject else {
+                    return Ok(());
+                };
+                match state
+                    .identity_engine()
+                    .user_store()
+                    .delete_user(&subject)
+                    .await
+                {
+                    Ok(()) | Err(AppError::NotFound(_)) => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        .step("create_user", move |mut ctx: UserCreateContext| {
+            let state = create_user.clone();
+            async move {
+                if ctx.user.is_none() {
+                    let subject = identity_subject(&ctx)?;
+                    let user = state
+                        .user_service()
+                        .create(&subject, ctx.input.clone())
+                        .await?;
+                    ctx.user = Some(user);
+                }
+                Ok(ctx)
+            }
+        })
+        .compensate_with(move |ctx: UserCreateContext| {
+            let state = delete_user.clone();
+            async move {
+                let Some(user) = ctx.user else {
+                    return Ok(());
+                };
+                match state.user_service().delete(user.id).await {
+                    Ok(()) | Err(AppError::NotFound(_)) => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        // After the user record exists, so the identity engine can map the
+        // subject to users.id
+        .step("set_password", move |ctx: UserCreateContext| {
+            let state = set_password.clone();
+            async move {
+                if !ctx.has_password {
+                    return Ok(ctx);
+                }
+                let password = ctx.password.as_deref().ok_or_else(|| {
+                    AppError::BadRequest(
+                        "The password is not stored with the saga; compensate it and create the user again"
+                            .to_string(),
+                    )
+                })?;
+                let user = created_user(&ctx)?;
+                state
+                    .identity_engine()
+                    .user_store()
+                    .set_user_password(&user.identity_subject, password, false)
+                    .await?;
+                state
+                    .user_service()
+                    .update_password_changed_at(user.id)
+                    .await?;
+                Ok(ctx)
+            }
+        })
+        .step("add_to_tenant", move |ctx: UserCreateContext| {
+            let state = add_to_tenant.clone();
+            async move {
+                if let Some(tenant_id) = ctx.tenant_id {
+                    let user = created_user(&ctx)?;
+                    let added = state
+                        .user_service()
+                        .add_to_tenant(AddUserToTenantInput {
+                            user_id: *user.id,
+                            tenant_id,
+                            role_in_tenant: "member".to_string(),
+                        })
+                        .await;
+                    match added {
+                        Ok(_) | Err(AppError::Conflict(_)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(ctx)
+            }
+        })
+}
+
+/// Tenant, then its owner's membership. A failure deletes the tenant.
+pub fn tenant_provision_saga<S: HasServices>(state: &S) -> Saga<TenantProvisionContext> {
+    let (create_tenant, delete_tenant, add_owner) = (state.clone(), state.clone(), state.clone());
+
+    Saga::new(TENANT_PROVISION, Recovery::Compensate)
+        .step("create_tenant", move |mut ctx: TenantProvisionContext| {
+            let state = create_tenant.clone();
+            async move {
+                if ctx.tenant.is_none() {
+                    ctx.tenant = Some(state.tenant_service().create(ctx.input.clone()).await?);
+                }
+                Ok(ctx)
+            }
+        })
+        .compensate_with(move |ctx: TenantProvisionContext| {
+            let state = delete_tenant.clone();
+            async move {
+                let Some(tenant) = ctx.tenant else {
+                    return Ok(());
+                };
+                match state.tenant_service().delete(tenant.id).await {
+                    Ok(()) | Err(AppError::NotFound(_)) => Ok(()),
+                    Err(e) => Err(e),
+                }
+            }
+        })
+        .step("add_owner", move |ctx: TenantProvisionContext| {
+            let state = add_owner.clone();
+            async move {
+                if let Some(owner_id) = ctx.owner_id {
+                    let tenant = ctx.tenant.as_ref().ok_or_else(|| missing("tenant"))?;
+                    let added = state
+                        .user_service()
+                        .add_to_tenant(AddUserToTenantInput {
+                            user_id: owner_id,
+                            tenant_id: *tenant.id,
+                            role_in_tenant: "owner".to_string(),
+                        })
+                        .await;
+                    match added {
+                        Ok(_) | Err(AppError::Conflict(_)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(ctx)
+            }
+        })
+}
+
+/// Create a user in the identity engine and the database
+pub async fn run_user_create<S: HasServices>(
+    state: &S,
+    ctx: UserCreateContext,
+    idempotency_key: Option<&str>,
+) -> Result<User> {
+    let ctx = SagaRunner::new(state.saga_store())
+        .run(&user_create_saga(state), idempotency_key, ctx)
+        .await?;
+    ctx.user.ok_or_else(|| missing("user"))
+}
+
+/// Create a tenant and add its owner
+pub async fn run_tenant_provision<S: HasServices>(
+    state: &S,
+    input: CreateTenantInput,
+    owner_id: Option<Uuid>,
+    idempotency_key: Option<&str>,
+) -> Result<Tenant> {
+    let ctx = TenantProvisionContext {
+        input,
+        owner_id,
+        tenant: None,
+    };
+    let ctx = SagaRunner::new(state.saga_store())
+        .run(&tenant_provision_saga(state), idempotency_key, ctx)
+        .await?;
+    ctx.tenant.ok_or_else(|| missing("tenant"))
+}
+
+fn identity_subject(ctx: &UserCreateContext) -> Result<String> {
+    ctx.identity_subject
+        .clone()
+        .ok_or_else(|| missing("identity_subject"))
+}
+
+fn created_user(ctx: &UserCreateContext) -> Result<&User> {
+    ctx.user.as_ref().ok_or_else(|| missing("user"))
+}
+
+fn missing(field: &str) -> AppError {
+    AppError::Internal(anyhow::anyhow!("Saga context is missing '{}'", field))
+}
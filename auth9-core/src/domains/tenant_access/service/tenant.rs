@@ -1,22 +1,32 @@
 //! Tenant business logic
 
 use crate::cache::CacheManager;
+use crate::domains::integration::service::WebhookEventPublisher;
 use crate::error::{AppError, Result};
+use crate::models::analytics::WebhookEvent;
 use crate::models::common::StringUuid;
+use crate::models::list_query::ListQuery;
 use crate::models::tenant::{
-    CreateOrganizationInput, CreateTenantInput, Tenant, TenantStatus, UpdateTenantInput,
+    slugify, CreateOrganizationInput, CreateTenantInput, RenameTenantInput, Tenant,
+    TenantRenameResult, TenantSlugRedirect, TenantStatus, UpdateTenantInput,
+    SLUG_REDIRECT_GRACE_DAYS,
 };
+use crate::policy::PLATFORM_TENANT_SLUG;
 use crate::repository::{
     ActionRepository, InvitationRepository, LoginEventRepository, RbacRepository,
     SecurityAlertRepository, ServiceRepository, TenantRepository, UserRepository,
     WebhookRepository,
 };
+use rand::Rng;
 use sqlx::MySqlPool;
 use std::sync::Arc;
 use tracing::warn;
 use uuid::Uuid;
 use validator::Validate;
 
+/// Suffixed candidates tried after a generated slug collides
+const SLUG_GENERATION_ATTEMPTS: usize = 5;
+
 /// Repository bundle for TenantService
 pub struct TenantRepositoryBundle<
     R: TenantRepository,
@@ -102,6 +112,7 @@ pub struct TenantService<
     /// Database pool for transactional cascade deletes.
     /// When available, delete operations are wrapped in a transaction.
     pool: Option<MySqlPool>,
+    webhook_publisher: Option<Arc<dyn WebhookEventPublisher>>,
 }
 
 impl<
@@ -133,6 +144,7 @@ impl<
             action_repo: repos.action,
             cache_manager,
             pool: None,
+            webhook_publisher: None,
         }
     }
 
@@ -142,24 +154,49 @@ impl<
         self
     }
 
-    pub async fn create(&self, input: CreateTenantInput) -> Result<Tenant> {
-        // Validate input
-        input.validate()?;
+    /// Send `tenant.created` / `tenant.deleted` to platform webhooks
+    pub fn with_webhook_publisher(mut self, publisher: Arc<dyn WebhookEventPublisher>) -> Self {
+        self.webhook_publisher = Some(publisher);
+        self
+    }
 
-        // Check for duplicate slug
-        if self.repo.find_by_slug(&input.slug).await?.is_some() {
-            return Err(AppError::Conflict(format!(
-                "Tenant with slug '{}' already exists",
-                input.slug
-            )));
+    async fn publish_tenant_event(&self, event_type: &str, tenant: &Tenant) {
+        let Some(publisher) = &self.webhook_publisher else {
+            return;
+        };
+        let event = WebhookEvent {
+            event_type: event_type.to_string(),
+            timestamp: chrono::Utc::now(),
+            data: serde_json::json!({
+                "tenant_id": tenant.id.to_string(),
+                "name": tenant.name,
+                "slug": tenant.slug,
+                "status": tenant.status,
+            }),
+        };
+        if let Err(e) = publisher.trigger_platform_event(event).await {
+            warn!(tenant_id = %tenant.id, "Failed to trigger {} webhook: {}", event_type, e);
         }
+    }
 
-        let tenant = self.repo.create(&input).await?;
+    /// Create a tenant. Without a slug one is derived from the name and made
+    /// unique by suffixing; an explicit slug that is taken is a conflict.
+    pub async fn create(&self, mut input: CreateTenantInput) -> Result<Tenant> {
+        let generated = input.slug.is_empty();
+        if generated {
+            input.slug = slugify(&input.name);
+        }
+        input.validate()?;
+
+        let tenant = self
+            .insert_with_unique_slug(input, generated, "Tenant")
+            .await?;
         if let Some(cache) = &self.cache_manager {
             let _ = cache
                 .set_tenant_config(Uuid::from(tenant.id), &tenant)
                 .await;
         }
+        self.publish_tenant_event("tenant.created", &tenant).await;
         Ok(tenant)
     }
 
@@ -167,18 +204,14 @@ impl<
     /// Returns the created tenant and whether it's active or pending.
     pub async fn create_organization(
         &self,
-        input: CreateOrganizationInput,
+        mut input: CreateOrganizationInput,
         creator_email: &str,
     ) -> Result<Tenant> {
-        input.validate()?;
-
-        // Check for duplicate slug
-        if self.repo.find_by_slug(&input.slug).await?.is_some() {
-            return Err(AppError::Conflict(format!(
-                "Organization with slug '{}' already exists",
-                input.slug
-            )));
+        let generated = input.slug.is_empty();
+        if generated {
+            input.slug = slugify(&input.name);
         }
+        input.validate()?;
 
         // Determine status based on email domain match
         let email_domain = creator_email
@@ -197,9 +230,12 @@ impl<
             domain: Some(input.domain),
             logo_url: input.logo_url,
             settings: None,
+            data_region: None,
         };
 
-        let mut tenant = self.repo.create(&create_input).await?;
+        let mut tenant = self
+            .insert_with_unique_slug(create_input, generated, "Organization")
+            .await?;
 
         // If status should be Pending, update it (repo.create defaults to Active)
         if status == TenantStatus::Pending {
@@ -209,7 +245,7 @@ impl<
                 settings: None,
                 status: Some(TenantStatus::Pending),
             };
-            tenant = self.repo.update(tenant.id, &update).await?;
+            tenant = self.repo.update(tenant.id, &update, None).await?;
         }
 
         if let Some(cache) = &self.cache_manager {
@@ -217,10 +253,52 @@ impl<
                 .set_tenant_config(Uuid::from(tenant.id), &tenant)
                 .await;
         }
+        self.publish_tenant_event("tenant.created", &tenant).await;
 
         Ok(tenant)
     }
 
+    /// Insert the tenant, relying on the unique index rather than the
+    /// pre-check alone so concurrent creates cannot both claim a slug. A
+    /// generated slug that collides is retried with a random suffix.
+    async fn insert_with_unique_slug(
+        &self,
+        mut input: CreateTenantInput,
+        generated: bool,
+        kind: &str,
+    ) -> Result<Tenant> {
+        let base = input.slug.clone();
+        for attempt in 0..=SLUG_GENERATION_ATTEMPTS {
+            if attempt > 0 {
+                input.slug = format!("{}-{}", base, random_slug_suffix());
+            }
+            let taken = self.slug_taken(&input.slug).await?;
+            if !taken {
+                match self.repo.create(&input).await {
+                    Ok(tenant) => return Ok(tenant),
+                    Err(e) if !is_duplicate_key(&e) => return Err(e),
+                    Err(_) => {}
+                }
+            }
+            if !generated {
+                return Err(AppError::Conflict(format!(
+                    "{} with slug '{}' already exists",
+                    kind, input.slug
+                )));
+            }
+        }
+        Err(AppError::Conflict(format!(
+            "Could not generate a unique slug for '{}'",
+            input.name
+        )))
+    }
+
+    /// A slug is taken while a tenant uses it or a rename still redirects from it
+    async fn slug_taken(&self, slug: &str) -> Result<bool> {
+        Ok(self.repo.find_by_slug(slug).await?.is_some()
+            || self.repo.find_slug_redirect(slug).await?.is_some())
+    }
+
     pub async fn get(&self, id: StringUuid) -> Result<Tenant> {
         if let Some(cache) = &self.cache_manager {
             if let Ok(Some(tenant)) = cache.get_tenant_config(Uuid::from(id)).await {
@@ -253,18 +331,35 @@ impl<
         Ok(())
     }
 
+    /// Look up a tenant by slug, following the redirect of a renamed tenant
     pub async fn get_by_slug(&self, slug: &str) -> Result<Tenant> {
-        let tenant = self
-            .repo
-            .find_by_slug(slug)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Tenant '{}' not found", slug)))?;
+        Ok(self.resolve_slug(slug).await?.0)
+    }
+
+    /// Look up a tenant by slug. The redirect is returned when `slug` is a
+    /// retired slug, so callers can tell clients to switch to the new one.
+    pub async fn resolve_slug(&self, slug: &str) -> Result<(Tenant, Option<TenantSlugRedirect>)> {
+        let (tenant, redirect) = match self.repo.find_by_slug(slug).await? {
+            Some(tenant) => (tenant, None),
+            None => {
+                let redirect =
+                    self.repo.find_slug_redirect(slug).await?.ok_or_else(|| {
+                        AppError::NotFound(format!("Tenant '{}' not found", slug))
+                    })?;
+                let tenant = self
+                    .repo
+                    .find_by_id(redirect.tenant_id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Tenant '{}' not found", slug)))?;
+                (tenant, Some(redirect))
+            }
+        };
         if let Some(cache) = &self.cache_manager {
             let _ = cache
                 .set_tenant_config(Uuid::from(tenant.id), &tenant)
                 .await;
         }
-        Ok(tenant)
+        Ok((tenant, redirect))
     }
 
     pub async fn list(&self, page: i64, per_page: i64) -> Result<(Vec<Tenant>, i64)> {
@@ -286,17 +381,73 @@ impl<
         Ok((tenants, total))
     }
 
-    pub async fn update(&self, id: StringUuid, input: UpdateTenantInput) -> Result<Tenant> {
+    /// List tenants with the standard filter / sort grammar
+    pub async fn list_filtered(&self, query: &ListQuery) -> Result<(Vec<Tenant>, i64)> {
+        self.repo.list_filtered(query).await
+    }
+
+    /// Update a tenant. `expected_version` (from `If-Match`) turns the write
+    /// into a conditional update that fails with a version conflict if the
+    /// tenant changed since the caller read it.
+    pub async fn update(
+        &self,
+        id: StringUuid,
+        input: UpdateTenantInput,
+        expected_version: Option<i32>,
+    ) -> Result<Tenant> {
         input.validate()?;
 
         // Verify tenant exists
         let _ = self.get(id).await?;
 
-        let tenant = self.repo.update(id, &input).await?;
+        let result = self.repo.update(id, &input, expected_version).await;
+        // Invalidate on conflicts too so the client's reload sees the current version
         if let Some(cache) = &self.cache_manager {
             let _ = cache.invalidate_tenant_config(Uuid::from(id)).await;
         }
-        Ok(tenant)
+        result
+    }
+
+    /// Rename a tenant. A new slug takes effect immediately; the old one keeps
+    /// resolving for [`SLUG_REDIRECT_GRACE_DAYS`] and stays reserved for this
+    /// tenant until then.
+    pub async fn rename(
+        &self,
+        id: StringUuid,
+        input: RenameTenantInput,
+    ) -> Result<TenantRenameResult> {
+        input.validate()?;
+        if input.name.is_none() && input.slug.is_none() {
+            return Err(AppError::Validation("Set name and/or slug".to_string()));
+        }
+
+        let existing = self.get(id).await?;
+        let slug_changes = input.slug.as_ref().is_some_and(|s| *s != existing.slug);
+        if slug_changes && existing.slug == PLATFORM_TENANT_SLUG {
+            return Err(AppError::BadRequest(
+                "The platform tenant slug cannot be changed".to_string(),
+            ));
+        }
+
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(SLUG_REDIRECT_GRACE_DAYS);
+        let result = self.repo.rename(id, &input, expires_at).await;
+        if let Some(cache) = &self.cache_manager {
+            let _ = cache.invalidate_tenant_config(Uuid::from(id)).await;
+        }
+        let (tenant, slug_redirect) = result.map_err(|e| {
+            if is_duplicate_key(&e) {
+                AppError::Conflict(format!(
+                    "Tenant with slug '{}' already exists",
+                    input.slug.as_deref().unwrap_or_default()
+                ))
+            } else {
+                e
+            }
+        })?;
+        Ok(TenantRenameResult {
+            tenant,
+            slug_redirect,
+        })
     }
 
     /// Delete a tenant with cascade delete of all related data.
@@ -305,7 +456,7 @@ impl<
     /// transaction. Cache invalidation happens after commit.
     pub async fn delete(&self, id: StringUuid) -> Result<()> {
         // Verify tenant exists
-        let _ = self.get(id).await?;
+        let tenant = self.get(id).await?;
 
         // Collect service IDs for cache invalidation after commit
         let services = self.service_repo.list_by_tenant(Uuid::from(id)).await?;
@@ -372,6 +523,49 @@ impl<
                     .await
                     .map_err(AppError::Database)?;
 
+                sqlx::query("DELETE FROM service_permission_namespaces WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                sqlx::query("DELETE FROM service_claims_enrichers WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                sqlx::query("DELETE FROM service_external_authorizers WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                sqlx::query("DELETE FROM service_attribute_release_policies WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                sqlx::query("DELETE FROM service_token_validation_profiles WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                sqlx::query("DELETE FROM service_scopes WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
+                // Delete progressive profiling requirements for this service
+                sqlx::query("DELETE FROM service_profile_requirements WHERE service_id = ?")
+                    .bind(&svc_id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+
                 // Delete the service itself
                 sqlx::query("DELETE FROM services WHERE id = ?")
                     .bind(&svc_id_str)
@@ -440,7 +634,88 @@ impl<
                 .await
                 .map_err(AppError::Database)?;
 
-            // 9. Delete the tenant itself
+            // 9. Delete custom domains (stops Host-header routing to this tenant)
+            sqlx::query("DELETE FROM tenant_custom_domains WHERE tenant_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 10. Delete dynamic client registration state
+            for table in [
+                "client_registrations",
+                "client_registration_initial_tokens",
+                "client_registration_policies",
+            ] {
+                sqlx::query(&format!("DELETE FROM {} WHERE tenant_id = ?", table))
+                    .bind(&id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+            }
+
+            // 11. Delete conditional access policies
+            sqlx::query("DELETE FROM conditional_access_policies WHERE tenant_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 12. Delete workload identity federation rules
+            sqlx::query("DELETE FROM workload_identity_rules WHERE tenant_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 13. Release retired slugs
+            sqlx::query("DELETE FROM tenant_slug_redirects WHERE tenant_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 14. Delete inactivity policy progress, digest state, the data
+            // key version and the password deny list
+            for table in [
+                "tenant_member_inactivity",
+                "tenant_security_digests",
+                "tenant_encryption_keys",
+                "tenant_password_deny_lists",
+            ] {
+                sqlx::query(&format!("DELETE FROM {} WHERE tenant_id = ?", table))
+                    .bind(&id_str)
+                    .execute(tx.as_mut())
+                    .await
+                    .map_err(AppError::Database)?;
+            }
+
+            // 15. Delete admin units with their members and admins
+            sqlx::query(
+                "DELETE m FROM admin_unit_members m \
+                 INNER JOIN admin_units u ON m.admin_unit_id = u.id \
+                 WHERE u.tenant_id = ?",
+            )
+            .bind(&id_str)
+            .execute(tx.as_mut())
+            .await
+            .map_err(AppError::Database)?;
+            sqlx::query(
+                "DELETE a FROM admin_unit_admins a \
+                 INNER JOIN admin_units u ON a.admin_unit_id = u.id \
+                 WHERE u.tenant_id = ?",
+            )
+            .bind(&id_str)
+            .execute(tx.as_mut())
+            .await
+            .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM admin_units WHERE tenant_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 16. Delete the tenant itself
             sqlx::query("DELETE FROM tenants WHERE id = ?")
                 .bind(&id_str)
                 .execute(tx.as_mut())
@@ -537,6 +812,7 @@ impl<
             }
             let _ = cache.invalidate_tenant_config(Uuid::from(id)).await;
         }
+        self.publish_tenant_event("tenant.deleted", &tenant).await;
 
         Ok(())
     }
@@ -557,7 +833,7 @@ impl<
             settings: None,
             status: Some(TenantStatus::Inactive),
         };
-        let tenant = self.repo.update(id, &input).await?;
+        let tenant = self.repo.update(id, &input, None).await?;
         if let Some(cache) = &self.cache_manager {
             let _ = cache.invalidate_tenant_config(Uuid::from(id)).await;
         }
@@ -565,6 +841,27 @@ impl<
     }
 }
 
+/// Six random lowercase alphanumerics appended to a colliding generated slug
+fn random_slug_suffix() -> String {
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut rng = rand::thread_rng();
+    (0..6)
+        .map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char)
+        .collect()
+}
+
+/// Unique-constraint violation reported by the database
+fn is_duplicate_key(e: &AppError) -> bool {
+    match e {
+        AppError::Database(sqlx::Error::Database(db_err)) => {
+            db_err.code().as_deref() == Some("23000")
+                || db_err.code().as_deref() == Some("1062")
+                || db_err.message().contains("Duplicate entry")
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,6 +950,7 @@ mod tests {
         mock.expect_find_by_slug()
             .with(eq("test-tenant"))
             .returning(|_| Ok(None));
+        mock.expect_find_slug_redirect().returning(|_| Ok(None));
 
         mock.expect_create().returning(|input| {
             Ok(Tenant {
@@ -670,6 +968,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         let result = service.create(input).await;
@@ -680,6 +979,42 @@ mod tests {
         assert_eq!(tenant.slug, "test-tenant");
     }
 
+    #[tokio::test]
+    async fn test_create_tenant_publishes_platform_event() {
+        use crate::domains::integration::service::webhook::MockWebhookEventPublisher;
+
+        let mut mock = MockTenantRepository::new();
+        mock.expect_find_by_slug().returning(|_| Ok(None));
+        mock.expect_find_slug_redirect().returning(|_| Ok(None));
+        mock.expect_create().returning(|input| {
+            Ok(Tenant {
+                name: input.name.clone(),
+                slug: input.slug.clone(),
+                ..Default::default()
+            })
+        });
+
+        let mut publisher = MockWebhookEventPublisher::new();
+        publisher
+            .expect_trigger_platform_event()
+            .withf(|event| {
+                event.event_type == "tenant.created" && event.data["slug"] == "acme-platform"
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = create_test_service(mock).with_webhook_publisher(Arc::new(publisher));
+        let input = CreateTenantInput {
+            name: "Acme".to_string(),
+            slug: "acme-platform".to_string(),
+            domain: None,
+            logo_url: None,
+            settings: None,
+            data_region: None,
+        };
+        service.create(input).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_create_tenant_with_settings() {
         let mut mock = MockTenantRepository::new();
@@ -687,6 +1022,7 @@ mod tests {
         mock.expect_find_by_slug()
             .with(eq("custom-tenant"))
             .returning(|_| Ok(None));
+        mock.expect_find_slug_redirect().returning(|_| Ok(None));
 
         mock.expect_create().returning(|input| {
             Ok(Tenant {
@@ -711,6 +1047,7 @@ mod tests {
             domain: None,
             logo_url: Some("https://example.com/logo.png".to_string()),
             settings: Some(settings),
+            data_region: None,
         };
 
         let result = service.create(input).await;
@@ -733,6 +1070,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         let result = service.create(input).await;
@@ -750,6 +1088,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         let result = service.create(input).await;
@@ -767,6 +1106,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         let result = service.create(input).await;
@@ -838,6 +1178,7 @@ mod tests {
         mock.expect_find_by_slug()
             .with(eq("nonexistent"))
             .returning(|_| Ok(None));
+        mock.expect_find_slug_redirect().returning(|_| Ok(None));
 
         let service = create_test_service(mock);
 
@@ -845,6 +1186,169 @@ mod tests {
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
+    #[tokio::test]
+    async fn test_get_by_slug_follows_rename_redirect() {
+        let tenant = Tenant {
+            slug: "acme-new".to_string(),
+            ..Default::default()
+        };
+        let tenant_id = tenant.id;
+        let tenant_clone = tenant.clone();
+
+        let mut mock = MockTenantRepository::new();
+        mock.expect_find_by_slug().returning(|_| Ok(None));
+        mock.expect_find_slug_redirect()
+            .with(eq("acme"))
+            .returning(move |slug| {
+                Ok(Some(TenantSlugRedirect {
+                    old_slug: slug.to_string(),
+                    tenant_id,
+                    expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                    created_at: chrono::Utc::now(),
+                }))
+            });
+        mock.expect_find_by_id()
+            .with(eq(tenant_id))
+            .returning(move |_| Ok(Some(tenant_clone.clone())));
+
+        let service = create_test_service(mock);
+        let (resolved, redirect) = service.resolve_slug("acme").await.unwrap();
+        assert_eq!(resolved.slug, "acme-new");
+        assert_eq!(redirect.unwrap().old_slug, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_create_tenant_generates_unique_slug() {
+        let mut mock = MockTenantRepository::new();
+        // The derived slug is taken, the suffixed candidate is free
+        mock.expect_find_by_slug()
+            .returning(|slug| Ok((slug == "acme-corp").then(Tenant::default)));
+        mock.expect_find_slug_redirect().returning(|_| Ok(None));
+        mock.expect_create().times(1).returning(|input| {
+            Ok(Tenant {
+                name: input.name.clone(),
+                slug: input.slug.clone(),
+                ..Default::default()
+            })
+        });
+
+        let service = create_test_service(mock);
+        let input = CreateTenantInput {
+            name: "Acme Corp".to_string(),
+            slug: String::new(),
+            domain: None,
+            logo_url: None,
+            settings: None,
+            data_region: None,
+        };
+
+        let tenant = service.create(input).await.unwrap();
+        assert!(tenant.slug.starts_with("acme-corp-"));
+        assert_eq!(tenant.slug.len(), "acme-corp-".len() + 6);
+    }
+
+    #[tokio::test]
+    async fn test_create_tenant_rejects_slug_reserved_by_redirect() {
+        let mut mock = MockTenantRepository::new();
+        mock.expect_find_by_slug().returning(|_| Ok(None));
+        mock.expect_find_slug_redirect().returning(|slug| {
+            Ok(Some(TenantSlugRedirect {
+                old_slug: slug.to_string(),
+                tenant_id: StringUuid::new_v4(),
+                expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                created_at: chrono::Utc::now(),
+            }))
+        });
+        mock.expect_create().never();
+
+        let service = create_test_service(mock);
+        let input = CreateTenantInput {
+            name: "Acme".to_string(),
+            slug: "acme".to_string(),
+            domain: None,
+            logo_url: None,
+            settings: None,
+            data_region: None,
+        };
+
+        let result = service.create(input).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rename_tenant_records_redirect() {
+        let tenant = Tenant {
+            slug: "acme".to_string(),
+            ..Default::default()
+        };
+        let tenant_id = tenant.id;
+        let tenant_clone = tenant.clone();
+
+        let mut mock = MockTenantRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(tenant_clone.clone())));
+        mock.expect_rename()
+            .withf(|_, input, expires_at| {
+                input.slug.as_deref() == Some("acme-labs")
+                    && *expires_at > chrono::Utc::now() + chrono::Duration::days(89)
+            })
+            .returning(|id, input, expires_at| {
+                Ok((
+                    Tenant {
+                        id,
+                        slug: input.slug.clone().unwrap(),
+                        ..Default::default()
+                    },
+                    Some(TenantSlugRedirect {
+                        old_slug: "acme".to_string(),
+                        tenant_id: id,
+                        expires_at,
+                        created_at: chrono::Utc::now(),
+                    }),
+                ))
+            });
+
+        let service = create_test_service(mock);
+        let result = service
+            .rename(
+                tenant_id,
+                RenameTenantInput {
+                    name: None,
+                    slug: Some("acme-labs".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.tenant.slug, "acme-labs");
+        assert_eq!(result.slug_redirect.unwrap().old_slug, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_rename_platform_tenant_slug_rejected() {
+        let tenant = Tenant {
+            slug: PLATFORM_TENANT_SLUG.to_string(),
+            ..Default::default()
+        };
+        let tenant_id = tenant.id;
+
+        let mut mock = MockTenantRepository::new();
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(tenant.clone())));
+        mock.expect_rename().never();
+
+        let service = create_test_service(mock);
+        let result = service
+            .rename(
+                tenant_id,
+                RenameTenantInput {
+                    name: None,
+                    slug: Some("platform".to_string()),
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
     #[tokio::test]
     async fn test_list_tenants() {
         let mut mock = MockTenantRepository::new();
@@ -912,7 +1416,7 @@ mod tests {
             .with(eq(id))
             .returning(move |_| Ok(Some(tenant_clone.clone())));
 
-        mock.expect_update().returning(|_, input| {
+        mock.expect_update().returning(|_, input, _| {
             Ok(Tenant {
                 name: input.name.clone().unwrap_or_default(),
                 ..Default::default()
@@ -928,11 +1432,48 @@ mod tests {
             status: None,
         };
 
-        let result = service.update(id, input).await;
+        let result = service.update(id, input, None).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().name, "New Name");
     }
 
+    #[tokio::test]
+    async fn test_update_tenant_stale_version_precondition_failed() {
+        let mut mock = MockTenantRepository::new();
+        let tenant = Tenant {
+            version: 3,
+            ..Default::default()
+        };
+        let id = tenant.id;
+
+        mock.expect_find_by_id()
+            .returning(move |_| Ok(Some(tenant.clone())));
+        mock.expect_update()
+            .withf(|_, _, expected| *expected == Some(2))
+            .returning(|_, _, expected| {
+                AppError::check_version("tenant", expected, 3)?;
+                unreachable!()
+            });
+
+        let service = create_test_service(mock);
+
+        let input = UpdateTenantInput {
+            name: Some("New Name".to_string()),
+            logo_url: None,
+            settings: None,
+            status: None,
+        };
+
+        let result = service.update(id, input, Some(2)).await;
+        assert!(matches!(
+            result,
+            Err(AppError::PreconditionFailed {
+                current_version: Some(3),
+                ..
+            })
+        ));
+    }
+
     #[tokio::test]
     async fn test_update_tenant_not_found() {
         let mut mock = MockTenantRepository::new();
@@ -951,7 +1492,7 @@ mod tests {
             status: None,
         };
 
-        let result = service.update(id, input).await;
+        let result = service.update(id, input, None).await;
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
@@ -968,7 +1509,7 @@ mod tests {
             status: None,
         };
 
-        let result = service.update(id, input).await;
+        let result = service.update(id, input, None).await;
         assert!(matches!(result, Err(AppError::Validation(_))));
     }
 
@@ -1171,7 +1712,7 @@ mod tests {
             .with(eq(id))
             .returning(move |_| Ok(Some(tenant_clone.clone())));
 
-        mock.expect_update().returning(|_, input| {
+        mock.expect_update().returning(|_, input, _| {
             Ok(Tenant {
                 status: input.status.clone().unwrap_or(TenantStatus::Active),
                 ..Default::default()
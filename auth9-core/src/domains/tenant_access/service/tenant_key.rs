@@ -0,0 +1,377 @@
+//! Per-tenant data key rotation and re-encryption of stored secrets, at once
+//! or in batches for `key_rotation` jobs
+
+use crate::crypto::tenant_keys::{sealed_version, TenantKeyring, INITIAL_KEY_VERSION};
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::tenant_key::{
+    ReencryptOutcome, ReencryptReport, SecretKeyCounts, StoredSecret, TenantKeyStatus,
+    TenantKeyVersion,
+};
+use crate::repository::TenantKeyRepository;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub struct TenantKeyService<R: TenantKeyRepository> {
+    repo: Arc<R>,
+    keyring: Option<TenantKeyring>,
+}
+
+impl<R: TenantKeyRepository> TenantKeyService<R> {
+    pub fn new(repo: Arc<R>, keyring: Option<TenantKeyring>) -> Self {
+        Self { repo, keyring }
+    }
+
+    pub async fn status(&self, tenant_id: StringUuid) -> Result<TenantKeyStatus> {
+        let key = self.repo.find(tenant_id).await?;
+        let key_version = key.as_ref().map_or(INITIAL_KEY_VERSION, |k| k.key_version);
+
+        let mut secrets = SecretKeyCounts::default();
+        for stored in self.repo.stored_secrets(Some(tenant_id)).await? {
+            secrets.total += 1;
+            match sealed_version(&stored.secret) {
+                Some(version) if version == key_version => secrets.current += 1,
+                Some(_) => secrets.outdated += 1,
+                None => secrets.plaintext += 1,
+            }
+        }
+
+        Ok(TenantKeyStatus {
+            tenant_id,
+            key_version,
+            rotated_at: key.and_then(|k| k.rotated_at),
+            encryption_enabled: self.keyring.is_some(),
+            secrets,
+        })
+    }
+
+    /// Switch a tenant to a new data key and re-encrypt its secrets
+    pub async fn rotate(&self, tenant_id: StringUuid) -> Result<ReencryptReport> {
+        self.start_rotation(tenant_id).await?;
+        self.reencrypt(Some(tenant_id)).await
+    }
+
+    /// Switch a tenant to a new data key without re-encrypting anything.
+    /// New secrets are sealed with the new version right away and existing
+    /// ones stay readable, so re-encryption can follow in batches.
+    pub async fn start_rotation(&self, tenant_id: StringUuid) -> Result<TenantKeyVersion> {
+        self.keyring()?;
+        self.repo.bump_version(tenant_id).await
+    }
+
+    /// Re-encrypt the secrets of a tenant (or all secrets with `None`) with
+    /// the current master key and key versions. Also encrypts secrets stored
+    /// in plaintext and, after a master key change, secrets sealed with the
+    /// previous master key.
+    pub async fn reencrypt(&self, tenant_id: Option<StringUuid>) -> Result<ReencryptReport> {
+        let keyring = self.keyring()?;
+        let mut report = ReencryptReport {
+            tenant_id,
+            ..Default::default()
+        };
+        let mut versions = HashMap::new();
+
+        for stored in self.repo.stored_secrets(tenant_id).await? {
+            match self
+                .reencrypt_secret(keyring, &stored, &mut versions)
+                .await?
+            {
+                ReencryptOutcome::Reencrypted => report.reencrypted += 1,
+                ReencryptOutcome::Skipped => report.skipped += 1,
+                ReencryptOutcome::Failed => report.failed += 1,
+            }
+        }
+
+        report.key_version = tenant_id.and_then(|id| versions.get(&Some(id)).copied());
+        Ok(report)
+    }
+
+    /// Secrets a batched re-encryption still has to go through after `after`
+    pub async fn count_pending(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+    ) -> Result<u64> {
+        self.repo.count_secrets_after(tenant_id, after).await
+    }
+
+    /// Re-encrypt the next `limit` secrets after `after` in ID order, like
+    /// [`Self::reencrypt`]. An empty result means no secret is left.
+    pub async fn reencrypt_batch(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+        limit: u32,
+    ) -> Result<Vec<(StringUuid, ReencryptOutcome)>> {
+        let keyring = self.keyring()?;
+        let mut versions = HashMap::new();
+        let mut outcomes = Vec::new();
+        for stored in self
+            .repo
+            .stored_secrets_after(tenant_id, after, limit.max(1))
+            .await?
+        {
+            let outcome = self
+                .reencrypt_secret(keyring, &stored, &mut versions)
+                .await?;
+            outcomes.push((stored.id, outcome));
+        }
+        Ok(outcomes)
+    }
+
+    /// Seal one stored secret with its owner's current key; `versions`
+    /// caches key versions by owner
+    async fn reencrypt_secret(
+        &self,
+        keyring: &TenantKeyring,
+        stored: &StoredSecret,
+        versions: &mut HashMap<Option<StringUuid>, u32>,
+    ) -> Result<ReencryptOutcome> {
+        let owner = stored.tenant_id;
+        let plaintext = match keyring.open(owner.map(Into::into), &stored.secret) {
+            Ok(plaintext) => plaintext,
+            Err(e) => {
+                tracing::warn!(webhook_id = %stored.id, error = %e, "Cannot decrypt webhook secret");
+                return Ok(ReencryptOutcome::Failed);
+            }
+        };
+        let version = match versions.get(&owner) {
+            Some(version) => *version,
+            None => {
+                let version = match owner {
+                    Some(owner) => self
+                        .repo
+                        .find(owner)
+                        .await?
+                        .map_or(INITIAL_KEY_VERSION, |k| k.key_version),
+                    None => INITIAL_KEY_VERSION,
+                };
+                versions.insert(owner, version);
+                version
+            }
+        };
+        let sealed = keyring
+            .seal(owner.map(Into::into), version, &plaintext)
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to encrypt webhook secret: {}", e))
+            })?;
+        if self
+            .repo
+            .replace_secret(stored.id, &stored.secret, &sealed)
+            .await?
+        {
+            Ok(ReencryptOutcome::Reencrypted)
+        } else {
+            Ok(ReencryptOutcome::Skipped)
+        }
+    }
+
+    fn keyring(&self) -> Result<&TenantKeyring> {
+        self.keyring.as_ref().ok_or_else(|| {
+            AppError::BadRequest(
+                "Secret encryption requires SETTINGS_ENCRYPTION_KEY to be configured".to_string(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::EncryptionKey;
+    use crate::repository::tenant_key::MockTenantKeyRepository;
+    use mockall::predicate::*;
+
+    fn keyring(byte: u8) -> TenantKeyring {
+        TenantKeyring::new(EncryptionKey::new([byte; 32]))
+    }
+
+    fn key_version(tenant_id: StringUuid, key_version: u32) -> TenantKeyVersion {
+        TenantKeyVersion {
+            tenant_id,
+            key_version,
+            rotated_at: Some(chrono::Utc::now()),
+        }
+    }
+
+    fn stored(tenant_id: Option<StringUuid>, secret: String) -> StoredSecret {
+        StoredSecret {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            secret,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_counts_secrets_by_key() {
+        let tenant_id = StringUuid::new_v4();
+        let keyring = keyring(1);
+        let secrets = vec![
+            stored(
+                Some(tenant_id),
+                keyring.seal(Some(*tenant_id), 2, "a").unwrap(),
+            ),
+            stored(
+                Some(tenant_id),
+                keyring.seal(Some(*tenant_id), 1, "b").unwrap(),
+            ),
+            stored(Some(tenant_id), "whsec_legacy".to_string()),
+        ];
+
+        let mut repo = MockTenantKeyRepository::new();
+        repo.expect_find()
+            .returning(move |id| Ok(Some(key_version(id, 2))));
+        repo.expect_stored_secrets()
+            .with(eq(Some(tenant_id)))
+            .returning(move |_| Ok(secrets.clone()));
+
+        let status = TenantKeyService::new(Arc::new(repo), Some(keyring))
+            .status(tenant_id)
+            .await
+            .unwrap();
+        assert_eq!(status.key_version, 2);
+        assert!(status.encryption_enabled);
+        assert_eq!(
+            status.secrets,
+            SecretKeyCounts {
+                total: 3,
+                current: 1,
+                outdated: 1,
+                plaintext: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate_reseals_with_new_version() {
+        let tenant_id = StringUuid::new_v4();
+        let keyring = keyring(1);
+        let old = keyring.seal(Some(*tenant_id), 1, "whsec_old").unwrap();
+        let secrets = vec![
+            stored(Some(tenant_id), old),
+            stored(Some(tenant_id), "whsec_plain".to_string()),
+        ];
+
+        let mut repo = MockTenantKeyRepository::new();
+        repo.expect_bump_version()
+            .with(eq(tenant_id))
+            .times(1)
+            .returning(|id| Ok(key_version(id, 2)));
+        repo.expect_find()
+            .returning(|id| Ok(Some(key_version(id, 2))));
+        repo.expect_stored_secrets()
+            .returning(move |_| Ok(secrets.clone()));
+        let opener = keyring.clone();
+        repo.expect_replace_secret()
+            .times(2)
+            .returning(move |_, expected, secret| {
+                assert_eq!(sealed_version(secret), Some(2));
+                let plaintext = opener.open(Some(*tenant_id), secret).unwrap();
+                assert_eq!(opener.open(Some(*tenant_id), expected).unwrap(), plaintext);
+                Ok(true)
+            });
+
+        let report = TenantKeyService::new(Arc::new(repo), Some(keyring))
+            .rotate(tenant_id)
+            .await
+            .unwrap();
+        assert_eq!(report.key_version, Some(2));
+        assert_eq!(report.reencrypted, 2);
+        assert_eq!(report.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_after_master_key_change() {
+        let tenant_id = StringUuid::new_v4();
+        let old_master = keyring(1);
+        let secrets = vec![
+            stored(
+                Some(tenant_id),
+                old_master.seal(Some(*tenant_id), 1, "a").unwrap(),
+            ),
+            stored(None, old_master.seal(None, 1, "b").unwrap()),
+            // Sealed with a master key nobody has any more
+            stored(
+                Some(tenant_id),
+                keyring(9).seal(Some(*tenant_id), 1, "c").unwrap(),
+            ),
+        ];
+
+        let mut repo = MockTenantKeyRepository::new();
+        repo.expect_find().returning(|_| Ok(None));
+        repo.expect_stored_secrets()
+            .with(eq(None))
+            .returning(move |_| Ok(secrets.clone()));
+        let new_master = keyring(2);
+        repo.expect_replace_secret()
+            .times(2)
+            .returning(move |_, _, secret| {
+                assert!([None, Some(*tenant_id)]
+                    .into_iter()
+                    .any(|owner| new_master.open(owner, secret).is_ok()));
+                Ok(true)
+            });
+
+        let keyring = keyring(2).with_previous_master(EncryptionKey::new([1; 32]));
+        let report = TenantKeyService::new(Arc::new(repo), Some(keyring))
+            .reencrypt(None)
+            .await
+            .unwrap();
+        assert_eq!(report.reencrypted, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.key_version, None);
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_batch_continues_after_cursor() {
+        let tenant_id = StringUuid::new_v4();
+        let after = StringUuid::new_v4();
+        let current = keyring(1);
+        let secrets = vec![
+            stored(
+                Some(tenant_id),
+                current.seal(Some(*tenant_id), 1, "a").unwrap(),
+            ),
+            stored(
+                Some(tenant_id),
+                keyring(9).seal(Some(*tenant_id), 1, "b").unwrap(),
+            ),
+        ];
+        let ids: Vec<StringUuid> = secrets.iter().map(|s| s.id).collect();
+
+        let mut repo = MockTenantKeyRepository::new();
+        repo.expect_find()
+            .times(1)
+            .returning(|id| Ok(Some(key_version(id, 2))));
+        repo.expect_stored_secrets_after()
+            .with(eq(Some(tenant_id)), eq(Some(after)), eq(2))
+            .returning(move |_, _, _| Ok(secrets.clone()));
+        repo.expect_replace_secret()
+            .times(1)
+            .returning(|_, _, secret| {
+                assert_eq!(sealed_version(secret), Some(2));
+                Ok(true)
+            });
+
+        let outcomes = TenantKeyService::new(Arc::new(repo), Some(current))
+            .reencrypt_batch(Some(tenant_id), Some(after), 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            outcomes,
+            vec![
+                (ids[0], ReencryptOutcome::Reencrypted),
+                (ids[1], ReencryptOutcome::Failed),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotation_requires_master_key() {
+        let service = TenantKeyService::new(Arc::new(MockTenantKeyRepository::new()), None);
+        let result = service.rotate(StringUuid::new_v4()).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let result = service.start_rotation(StringUuid::new_v4()).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
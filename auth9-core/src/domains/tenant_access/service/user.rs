@@ -1,11 +1,16 @@
 //! User business logic
 
+use crate::config::CardinalityConfig;
+use crate::domains::events::{DomainEvent, EventBus};
 use crate::domains::integration::service::WebhookEventPublisher;
 use crate::error::{AppError, Result};
+use crate::i18n::Locale;
 use crate::models::analytics::WebhookEvent;
-use crate::models::common::StringUuid;
+use crate::models::cardinality::CardinalityCap;
+use crate::models::common::{StringUuid, TenantId, UserId};
 use crate::models::user::{
     AddUserToTenantInput, CreateUserInput, TenantUser, TenantUserWithTenant, UpdateUserInput, User,
+    UserCohort, UserTimelineEntry,
 };
 use crate::repository::{
     AuditRepository, LinkedIdentityRepository, LoginEventRepository, PasswordResetRepository,
@@ -91,9 +96,12 @@ pub struct UserService<
     audit_repo: Arc<A>,
     rbac_repo: Arc<Rbac>,
     webhook_publisher: Option<Arc<dyn WebhookEventPublisher>>,
+    event_bus: Option<Arc<EventBus>>,
     /// Database pool for transactional cascade deletes.
     /// When available, delete operations are wrapped in a transaction.
     pool: Option<MySqlPool>,
+    /// Tenant membership cap (not enforced when unset)
+    cardinality: Option<CardinalityConfig>,
 }
 
 impl<
@@ -122,7 +130,9 @@ impl<
             audit_repo: repos.audit,
             rbac_repo: repos.rbac,
             webhook_publisher,
+            event_bus: None,
             pool: None,
+            cardinality: None,
         }
     }
 
@@ -132,6 +142,18 @@ impl<
         self
     }
 
+    /// Enforce the cap on users per tenant
+    pub fn with_cardinality_limits(mut self, config: CardinalityConfig) -> Self {
+        self.cardinality = Some(config);
+        self
+    }
+
+    /// Publish user lifecycle events to the domain event bus
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
     pub async fn create(&self, identity_subject: &str, input: CreateUserInput) -> Result<User> {
         input.validate()?;
 
@@ -157,22 +179,13 @@ impl<
 
         let user = self.repo.create(identity_subject, &input).await?;
 
-        // Trigger user.created webhook event
-        if let Some(publisher) = &self.webhook_publisher {
-            if let Err(e) = publisher
-                .trigger_event(WebhookEvent {
-                    event_type: "user.created".to_string(),
-                    timestamp: Utc::now(),
-                    data: serde_json::json!({
-                        "user_id": user.id.to_string(),
-                        "email": user.email,
-                        "display_name": user.display_name,
-                    }),
-                })
-                .await
-            {
-                tracing::warn!("Failed to trigger user.created webhook event: {}", e);
-            }
+        if let Some(bus) = &self.event_bus {
+            bus.publish(DomainEvent::UserCreated {
+                user_id: user.id,
+                email: user.email.clone(),
+                display_name: user.display_name.clone(),
+            })
+            .await;
         }
 
         Ok(user)
@@ -213,10 +226,32 @@ impl<
         Ok((users, total))
     }
 
-    pub async fn update(&self, id: StringUuid, input: UpdateUserInput) -> Result<User> {
+    /// Activity timeline of a user (logins, audit entries, sessions and role
+    /// grants), newest first
+    pub async fn timeline(
+        &self,
+        id: StringUuid,
+        page: i64,
+        per_page: i64,
+    ) -> Result<(Vec<UserTimelineEntry>, i64)> {
+        let _ = self.get(id).await?;
+        let offset = (page - 1) * per_page;
+        let entries = self.repo.find_timeline(id, offset, per_page).await?;
+        let total = self.repo.count_timeline(id).await?;
+        Ok((entries, total))
+    }
+
+    /// Update a user's profile. `expected_version` (from `If-Match`) rejects
+    /// the write with a version conflict if the user changed in the meantime.
+    pub async fn update(
+        &self,
+        id: StringUuid,
+        input: UpdateUserInput,
+        expected_version: Option<i32>,
+    ) -> Result<User> {
         input.validate()?;
         let _ = self.get(id).await?;
-        let user = self.repo.update(id, &input).await?;
+        let user = self.repo.update(id, &input, expected_version).await?;
 
         // Trigger user.updated webhook event
         if let Some(publisher) = &self.webhook_publisher {
@@ -248,7 +283,7 @@ impl<
     /// 1. Delete user_tenant_roles for all tenant memberships
     /// 2. Delete tenant_users (user's tenant memberships)
     /// 3. Delete sessions
-    /// 4. Delete password_reset_tokens
+    /// 4. Delete password_reset_tokens and account recovery requests
     /// 5. Delete linked_identities
     /// 6. Nullify user_id in login_events (preserve audit trail)
     /// 7. Nullify user_id in security_alerts (preserve audit trail)
@@ -299,6 +334,30 @@ impl<
                 .execute(tx.as_mut())
                 .await
                 .map_err(AppError::Database)?;
+            sqlx::query(
+                "DELETE ara FROM account_recovery_approvals ara \
+                 INNER JOIN account_recovery_requests arr ON ara.request_id = arr.id \
+                 WHERE arr.user_id = ?",
+            )
+            .bind(&id_str)
+            .execute(tx.as_mut())
+            .await
+            .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM account_recovery_requests WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM mfa_reset_requests WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM password_breach_checks WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
 
             // 5. Delete linked identities
             sqlx::query("DELETE FROM linked_identities WHERE user_id = ?")
@@ -328,7 +387,46 @@ impl<
                 .await
                 .map_err(AppError::Database)?;
 
-            // 9. Delete user record
+            // 9. Delete progressive profiling attributes, notification preferences,
+            //    inactivity progress, deletion requests, admin unit assignments
+            //    and login identifiers
+            sqlx::query("DELETE FROM user_profile_attributes WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM user_notification_preferences WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM tenant_member_inactivity WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM account_deletion_requests WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM admin_unit_members WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM admin_unit_admins WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+            sqlx::query("DELETE FROM user_login_identifiers WHERE user_id = ?")
+                .bind(&id_str)
+                .execute(tx.as_mut())
+                .await
+                .map_err(AppError::Database)?;
+
+            // 10. Delete user record
             sqlx::query("DELETE FROM users WHERE id = ?")
                 .bind(&id_str)
                 .execute(tx.as_mut())
@@ -405,6 +503,21 @@ impl<
 
     pub async fn add_to_tenant(&self, input: AddUserToTenantInput) -> Result<TenantUser> {
         input.validate()?;
+        if let Some(limits) = &self.cardinality {
+            let tenant_id = StringUuid::from(input.tenant_id);
+            let current = self.repo.count_tenant_users(tenant_id).await?.max(0) as u64;
+            let overrides = self
+                .rbac_repo
+                .find_tenant_cardinality_limits(TenantId::from(input.tenant_id))
+                .await?;
+            limits.check(
+                CardinalityCap::UsersPerTenant,
+                overrides.as_ref(),
+                tenant_id,
+                current,
+                current + 1,
+            )?;
+        }
         self.repo.add_to_tenant(&input).await.map_err(|e| {
             if let AppError::Database(ref db_err) = e {
                 let err_str = db_err.to_string().to_lowercase();
@@ -420,8 +533,8 @@ impl<
 
     pub async fn update_role_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
         role: String,
     ) -> Result<TenantUser> {
         if role.is_empty() || role.len() > 50 {
@@ -439,11 +552,7 @@ impl<
     /// Cascade order:
     /// 1. Delete user_tenant_roles for this tenant membership
     /// 2. Delete tenant_users record
-    pub async fn remove_from_tenant(
-        &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-    ) -> Result<()> {
+    pub async fn remove_from_tenant(&self, user_id: UserId, tenant_id: TenantId) -> Result<()> {
         // 1. Find tenant_user_id and delete role assignments
         if let Some(tenant_user_id) = self
             .rbac_repo
@@ -474,6 +583,25 @@ impl<
         Ok((users, total))
     }
 
+    /// One page of the tenant members matching `cohort`, plus the cohort size
+    pub async fn list_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<User>, i64)> {
+        let users = self
+            .repo
+            .find_tenant_users_in_cohort(tenant_id, cohort, offset, limit)
+            .await?;
+        let total = self
+            .repo
+            .count_tenant_users_in_cohort(tenant_id, cohort)
+            .await?;
+        Ok((users, total))
+    }
+
     pub async fn search_tenant_users(
         &self,
         tenant_id: StringUuid,
@@ -513,6 +641,14 @@ impl<
         self.repo.update_locked_until(id, locked_until).await
     }
 
+    /// Set the preferred locale (None to clear) and return the updated user
+    pub async fn update_locale(&self, id: StringUuid, locale: Option<Locale>) -> Result<User> {
+        self.repo
+            .update_locale(id, locale.map(|l| l.as_str().to_string()))
+            .await?;
+        self.get(id).await
+    }
+
     pub async fn update_password_changed_at(&self, id: StringUuid) -> Result<()> {
         self.repo.update_password_changed_at(id).await
     }
@@ -817,6 +953,50 @@ mod tests {
         assert_eq!(total, 21);
     }
 
+    #[tokio::test]
+    async fn test_timeline_pagination() {
+        let mut mock = MockUserRepository::new();
+        let user = User::default();
+        let id = user.id;
+
+        mock.expect_find_by_id()
+            .with(eq(id))
+            .returning(move |_| Ok(Some(user.clone())));
+        mock.expect_find_timeline()
+            .with(eq(id), eq(40), eq(20))
+            .returning(|_, _, _| {
+                Ok(vec![UserTimelineEntry {
+                    source: crate::models::user::UserTimelineSource::Session,
+                    event: "session_created".to_string(),
+                    occurred_at: Utc::now(),
+                    source_id: Uuid::new_v4().to_string(),
+                    actor_id: None,
+                    tenant_id: None,
+                    ip_address: Some("10.0.0.1".to_string()),
+                    details: serde_json::json!({}),
+                }])
+            });
+        mock.expect_count_timeline().returning(|_| Ok(41));
+
+        let service = create_test_service(mock);
+
+        let (entries, total) = service.timeline(id, 3, 20).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].event, "session_created");
+        assert_eq!(total, 41);
+    }
+
+    #[tokio::test]
+    async fn test_timeline_user_not_found() {
+        let mut mock = MockUserRepository::new();
+        mock.expect_find_by_id().returning(|_| Ok(None));
+
+        let service = create_test_service(mock);
+
+        let result = service.timeline(StringUuid::new_v4(), 1, 20).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
     #[tokio::test]
     async fn test_update_user_success() {
         let mut mock = MockUserRepository::new();
@@ -831,7 +1011,7 @@ mod tests {
             .with(eq(id))
             .returning(move |_| Ok(Some(user_clone.clone())));
 
-        mock.expect_update().returning(|_, input| {
+        mock.expect_update().returning(|_, input, _| {
             Ok(User {
                 display_name: input.display_name.clone(),
                 avatar_url: input.avatar_url.clone(),
@@ -846,7 +1026,7 @@ mod tests {
             avatar_url: None,
         };
 
-        let result = service.update(id, input).await;
+        let result = service.update(id, input, None).await;
         assert!(result.is_ok());
         assert_eq!(result.unwrap().display_name, Some("New Name".to_string()));
     }
@@ -867,7 +1047,7 @@ mod tests {
             avatar_url: None,
         };
 
-        let result = service.update(id, input).await;
+        let result = service.update(id, input, None).await;
         assert!(matches!(result, Err(AppError::NotFound(_))));
     }
 
@@ -1215,12 +1395,55 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_add_to_tenant_rejects_full_tenant() {
+        let mut mock_user = MockUserRepository::new();
+        let mut mock_rbac = MockRbacRepository::new();
+
+        mock_user.expect_count_tenant_users().returning(|_| Ok(3));
+        mock_user.expect_add_to_tenant().never();
+        mock_rbac
+            .expect_find_tenant_cardinality_limits()
+            .returning(|_| Ok(None));
+
+        let repos = UserRepositoryBundle::new(
+            Arc::new(mock_user),
+            Arc::new(MockSessionRepository::new()),
+            Arc::new(MockPasswordResetRepository::new()),
+            Arc::new(MockLinkedIdentityRepository::new()),
+            Arc::new(MockLoginEventRepository::new()),
+            Arc::new(MockSecurityAlertRepository::new()),
+            Arc::new(MockAuditRepository::new()),
+            Arc::new(mock_rbac),
+        );
+        let service = UserService::new(repos, None).with_cardinality_limits(CardinalityConfig {
+            max_users_per_tenant: 3,
+            ..Default::default()
+        });
+
+        let input = AddUserToTenantInput {
+            user_id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            role_in_tenant: "member".to_string(),
+        };
+
+        let result = service.add_to_tenant(input).await;
+        assert!(matches!(
+            result,
+            Err(AppError::CardinalityLimitExceeded {
+                cap: CardinalityCap::UsersPerTenant,
+                limit: 3,
+                requested: 4,
+            })
+        ));
+    }
+
     #[tokio::test]
     async fn test_remove_from_tenant_success() {
         let mut mock_user = MockUserRepository::new();
         let mut mock_rbac = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
         let tenant_user_id = StringUuid::new_v4();
 
         // Find tenant_user_id
@@ -1261,8 +1484,8 @@ mod tests {
     async fn test_remove_from_tenant_no_roles() {
         let mut mock_user = MockUserRepository::new();
         let mut mock_rbac = MockRbacRepository::new();
-        let user_id = StringUuid::new_v4();
-        let tenant_id = StringUuid::new_v4();
+        let user_id = UserId::new_v4();
+        let tenant_id = TenantId::new_v4();
 
         // User not found in tenant (no tenant_user record)
         mock_rbac
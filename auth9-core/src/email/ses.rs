@@ -7,7 +7,7 @@ use crate::models::email::{EmailMessage, EmailSendResult, SesConfig};
 use async_trait::async_trait;
 use aws_sdk_sesv2::{
     config::Region,
-    types::{Body, Content, Destination, EmailContent, Message},
+    types::{Body, Content, Destination, EmailContent, Message, MessageHeader},
     Client,
 };
 
@@ -128,7 +128,24 @@ impl EmailProvider for SesEmailProvider {
 
         let body = body_builder.build();
 
-        let ses_message = Message::builder().subject(subject).body(body).build();
+        let mut message_builder = Message::builder().subject(subject).body(body);
+        if let Some(url) = &message.list_unsubscribe {
+            for (name, value) in [
+                ("List-Unsubscribe", format!("<{}>", url)),
+                (
+                    "List-Unsubscribe-Post",
+                    "List-Unsubscribe=One-Click".to_string(),
+                ),
+            ] {
+                let header = MessageHeader::builder()
+                    .name(name)
+                    .value(value)
+                    .build()
+                    .map_err(|e| EmailProviderError::InvalidConfiguration(e.to_string()))?;
+                message_builder = message_builder.headers(header);
+            }
+        }
+        let ses_message = message_builder.build();
 
         let email_content = EmailContent::builder().simple(ses_message).build();
 
@@ -331,6 +348,7 @@ mod tests {
             subject: "Test".to_string(),
             html_body: "<p>Test</p>".to_string(),
             text_body: None,
+            list_unsubscribe: None,
         };
         let result = provider.send(&message).await;
         assert!(result.is_err());
@@ -4,7 +4,10 @@ use super::provider::{EmailProvider, EmailProviderError};
 use crate::models::email::{EmailMessage, EmailSendResult, OracleEmailConfig, SmtpConfig};
 use async_trait::async_trait;
 use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    message::{
+        header::{ContentType, HeaderName, HeaderValue},
+        Mailbox, MultiPart, SinglePart,
+    },
     transport::smtp::authentication::Credentials,
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
@@ -111,6 +114,18 @@ impl EmailProvider for SmtpEmailProvider {
             email_builder = email_builder.to(to);
         }
 
+        if let Some(url) = &message.list_unsubscribe {
+            email_builder = email_builder
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe"),
+                    format!("<{}>", url),
+                ))
+                .raw_header(HeaderValue::new(
+                    HeaderName::new_from_ascii_str("List-Unsubscribe-Post"),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ));
+        }
+
         // Build body (multipart if text body is provided)
         let email = if let Some(text_body) = &message.text_body {
             email_builder
@@ -3,6 +3,7 @@
 //! Provides simple variable substitution for email templates.
 //! Variables are specified using {{variable_name}} syntax.
 
+use crate::i18n::{self, Locale, DEFAULT_LOCALE};
 use crate::models::email_template::{EmailTemplateContent, EmailTemplateType};
 use std::collections::HashMap;
 
@@ -32,6 +33,12 @@ pub enum EmailTemplate {
     PasswordChanged,
     /// Security alert (new login, suspicious activity)
     SecurityAlert,
+    /// Periodic account activity digest
+    NotificationDigest,
+    /// New sign-in notification
+    LoginNotification,
+    /// Tenant security alert digest for admins
+    SecurityAlertDigest,
 }
 
 impl EmailTemplate {
@@ -45,6 +52,11 @@ impl EmailTemplate {
             Self::EmailVerification => "Verify your email address",
             Self::PasswordChanged => "Your password has been changed", // pragma: allowlist secret
             Self::SecurityAlert => "Security Alert: {{event_type}}",
+            Self::NotificationDigest => "Your {{app_name}} account activity this {{period}}",
+            Self::LoginNotification => "New sign-in to your {{app_name}} account",
+            Self::SecurityAlertDigest => {
+                "{{tenant_name}}: {{alert_count}} security alerts this {{period}}"
+            }
         }
     }
 
@@ -58,6 +70,9 @@ impl EmailTemplate {
             Self::EmailVerification => EMAIL_VERIFICATION_TEMPLATE,
             Self::PasswordChanged => PASSWORD_CHANGED_TEMPLATE,
             Self::SecurityAlert => SECURITY_ALERT_TEMPLATE,
+            Self::NotificationDigest => NOTIFICATION_DIGEST_TEMPLATE,
+            Self::LoginNotification => LOGIN_NOTIFICATION_TEMPLATE,
+            Self::SecurityAlertDigest => SECURITY_ALERT_DIGEST_TEMPLATE,
         }
     }
 
@@ -71,6 +86,9 @@ impl EmailTemplate {
             Self::EmailVerification => EMAIL_VERIFICATION_TEMPLATE_TEXT,
             Self::PasswordChanged => PASSWORD_CHANGED_TEMPLATE_TEXT,
             Self::SecurityAlert => SECURITY_ALERT_TEMPLATE_TEXT,
+            Self::NotificationDigest => NOTIFICATION_DIGEST_TEMPLATE_TEXT,
+            Self::LoginNotification => LOGIN_NOTIFICATION_TEMPLATE_TEXT,
+            Self::SecurityAlertDigest => SECURITY_ALERT_DIGEST_TEMPLATE_TEXT,
         }
     }
 
@@ -84,6 +102,9 @@ impl EmailTemplate {
             EmailTemplateType::EmailVerification => Self::EmailVerification,
             EmailTemplateType::PasswordChanged => Self::PasswordChanged,
             EmailTemplateType::SecurityAlert => Self::SecurityAlert,
+            EmailTemplateType::NotificationDigest => Self::NotificationDigest,
+            EmailTemplateType::LoginNotification => Self::LoginNotification,
+            EmailTemplateType::SecurityAlertDigest => Self::SecurityAlertDigest,
         }
     }
 
@@ -96,6 +117,99 @@ impl EmailTemplate {
             text_body: template.text_body().to_string(),
         }
     }
+
+    /// Get built-in content for a template type in `locale`.
+    ///
+    /// The default locale uses the hand-written templates above; other locales
+    /// are assembled from the `email-<type>-*` catalog messages, falling back to
+    /// the default content when the catalog has no translation.
+    pub fn localized_content(
+        template_type: EmailTemplateType,
+        locale: Locale,
+    ) -> EmailTemplateContent {
+        if locale == DEFAULT_LOCALE {
+            return Self::default_content(template_type);
+        }
+
+        let prefix = format!("email-{}", template_type.as_str().replace('_', "-"));
+        let message = |part: &str| {
+            i18n::lookup(locale, &format!("{}-{}", prefix, part)).map(catalog_to_template)
+        };
+
+        let Some(subject) = message("subject") else {
+            return Self::default_content(template_type);
+        };
+        let heading = message("heading").unwrap_or_else(|| subject.clone());
+        let body = message("body").unwrap_or_default();
+        let action = message("action").zip(Self::from_template_type(template_type).action_link());
+        let footer = message("footer");
+        let unsubscribe = message("unsubscribe");
+
+        let mut html = String::new();
+        let mut text = format!("{}\n\n", heading);
+        for line in body.lines() {
+            html.push_str(&format!("            <p class=\"body\">{}</p>\n", line));
+            text.push_str(&format!("{}\n\n", line));
+        }
+        if let Some((label, link)) = &action {
+            html.push_str(&format!(
+                concat!(
+                    "            <p style=\"text-align: center; margin: 30px 0;\">\n",
+                    "                <a href=\"{{{{{link}}}}}\" class=\"button\">{label}</a>\n",
+                    "            </p>\n",
+                    "            <p style=\"font-size: 14px; color: #666;\">\n",
+                    "                <a href=\"{{{{{link}}}}}\" class=\"link\">{{{{{link}}}}}</a>\n",
+                    "            </p>\n",
+                ),
+                link = link,
+                label = label,
+            ));
+            text.push_str(&format!("{}:\n{{{{{}}}}}\n\n", label, link));
+        }
+
+        let mut footer_html = String::new();
+        if let Some(footer) = &footer {
+            for line in footer.lines() {
+                footer_html.push_str(&format!("            <p>{}</p>\n", line));
+                text.push_str(&format!("{}\n", line));
+            }
+        }
+        if let Some(label) = &unsubscribe {
+            footer_html.push_str(&format!(
+                "            <p><a href=\"{{{{unsubscribe_link}}}}\" style=\"color: #666;\">{}</a></p>\n",
+                label
+            ));
+            text.push_str(&format!("{}: {{{{unsubscribe_link}}}}\n", label));
+        }
+        text.push_str("\n(c) {{year}} {{app_name}}");
+
+        EmailTemplateContent {
+            subject,
+            html_body: LOCALIZED_TEMPLATE
+                .replace("[[lang]]", locale.as_str())
+                .replace("[[title]]", &heading)
+                .replace("[[content]]", html.trim_end())
+                .replace("[[footer]]", footer_html.trim_end()),
+            text_body: text,
+        }
+    }
+
+    /// Variable holding the call-to-action URL, for templates that have one
+    fn action_link(&self) -> Option<&'static str> {
+        match self {
+            Self::Invitation => Some("invite_link"),
+            Self::PasswordReset => Some("reset_link"),
+            Self::Welcome => Some("login_url"),
+            Self::EmailVerification => Some("verification_link"),
+            Self::LoginNotification => Some("report_link"),
+            _ => None,
+        }
+    }
+}
+
+/// Convert catalog placeables (`{ $name }`) into template variables (`{{name}}`)
+fn catalog_to_template(pattern: &str) -> String {
+    i18n::catalog::map_placeables(pattern, |name| Some(format!("{{{{{}}}}}", name)))
 }
 
 /// Template rendering engine with variable substitution
@@ -175,6 +289,42 @@ pub struct RenderedEmail {
 // Email Templates
 // ============================================================================
 
+/// Layout for catalog-driven (non-default locale) templates
+const LOCALIZED_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="[[lang]]">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>[[title]]</title>
+    <style>
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, 'PingFang SC', 'Hiragino Sans', 'Noto Sans CJK', sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
+        .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .header { text-align: center; margin-bottom: 30px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
+        .content { margin-bottom: 30px; }
+        .button { display: inline-block; background-color: {{brand_primary_color}}; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button:hover { background-color: #1d4ed8; }
+        .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
+        .link { color: {{brand_primary_color}}; word-break: break-all; }
+        .body { white-space: pre-line; word-break: break-word; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>[[title]]</h1>
+        </div>
+        <div class="content">
+[[content]]
+        </div>
+        <div class="footer">
+[[footer]]
+            <p>&copy; {{year}} {{app_name}}</p>
+        </div>
+    </div>
+</body>
+</html>"#;
+
 const INVITATION_TEMPLATE: &str = r#"<!DOCTYPE html>
 <html>
 <head>
@@ -185,12 +335,12 @@ const INVITATION_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
-        .button { display: inline-block; background-color: #2563eb; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button { display: inline-block; background-color: {{brand_primary_color}}; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
         .button:hover { background-color: #1d4ed8; }
         .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
-        .link { color: #2563eb; word-break: break-all; }
+        .link { color: {{brand_primary_color}}; word-break: break-all; }
     </style>
 </head>
 <body>
@@ -247,12 +397,12 @@ const PASSWORD_RESET_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
-        .button { display: inline-block; background-color: #2563eb; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button { display: inline-block; background-color: {{brand_primary_color}}; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
         .button:hover { background-color: #1d4ed8; }
         .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
-        .link { color: #2563eb; word-break: break-all; }
+        .link { color: {{brand_primary_color}}; word-break: break-all; }
         .warning { background-color: #fef3c7; border: 1px solid #f59e0b; padding: 12px; border-radius: 6px; margin: 20px 0; }
     </style>
 </head>
@@ -315,7 +465,7 @@ const EMAIL_MFA_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
         .code-box { background-color: #f3f4f6; border-radius: 8px; padding: 20px; text-align: center; margin: 30px 0; }
         .code { font-size: 32px; font-weight: bold; letter-spacing: 8px; color: #1f2937; font-family: monospace; }
@@ -379,9 +529,9 @@ const WELCOME_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
-        .button { display: inline-block; background-color: #2563eb; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button { display: inline-block; background-color: {{brand_primary_color}}; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
         .button:hover { background-color: #1d4ed8; }
         .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
         .features { background-color: #f9fafb; border-radius: 8px; padding: 20px; margin: 20px 0; }
@@ -448,12 +598,12 @@ const EMAIL_VERIFICATION_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
-        .button { display: inline-block; background-color: #2563eb; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button { display: inline-block; background-color: {{brand_primary_color}}; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
         .button:hover { background-color: #1d4ed8; }
         .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
-        .link { color: #2563eb; word-break: break-all; }
+        .link { color: {{brand_primary_color}}; word-break: break-all; }
     </style>
 </head>
 <body>
@@ -511,7 +661,7 @@ const PASSWORD_CHANGED_TEMPLATE: &str = r#"<!DOCTYPE html>
         body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
         .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
         .header { text-align: center; margin-bottom: 30px; }
-        .header h1 { color: #2563eb; margin: 0; font-size: 24px; }
+        .header h1 { color: {{brand_primary_color}}; margin: 0; font-size: 24px; }
         .content { margin-bottom: 30px; }
         .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
         .info-box { background-color: #f3f4f6; border-radius: 8px; padding: 16px; margin: 20px 0; }
@@ -565,6 +715,63 @@ This is an automated security notification.
 
 (c) {{year}} {{app_name}}"#;
 
+const NOTIFICATION_DIGEST_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Account Activity</title>
+    <style>
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
+        .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .header { text-align: center; margin-bottom: 30px; }
+        .header h1 { color: #1a1a1a; margin: 0; font-size: 24px; }
+        .content { margin-bottom: 30px; }
+        .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
+        .info-box { background-color: #f3f4f6; border-radius: 8px; padding: 16px; margin: 20px 0; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Account Activity</h1>
+        </div>
+        <div class="content">
+            <p>Hi {{user_name}},</p>
+            <p>Here is a summary of your account activity this {{period}}:</p>
+            <div class="info-box">
+                <p style="margin: 0; font-size: 14px;">
+                    <span style="color: #6b7280;">Successful sign-ins:</span> {{successful_logins}}<br>
+                    <span style="color: #6b7280;">Failed sign-in attempts:</span> {{failed_logins}}<br>
+                    <span style="color: #6b7280;">Security alerts:</span> {{security_alerts}}
+                </p>
+            </div>
+            <p>If you don't recognize some of this activity, we recommend changing your password and reviewing your active sessions.</p>
+        </div>
+        <div class="footer">
+            <p><a href="{{unsubscribe_link}}" style="color: #666;">Stop receiving activity digests</a></p>
+            <p>&copy; {{year}} {{app_name}}</p>
+        </div>
+    </div>
+</body>
+</html>"#;
+
+const NOTIFICATION_DIGEST_TEMPLATE_TEXT: &str = r#"Account Activity
+
+Hi {{user_name}},
+
+Here is a summary of your account activity this {{period}}:
+
+- Successful sign-ins: {{successful_logins}}
+- Failed sign-in attempts: {{failed_logins}}
+- Security alerts: {{security_alerts}}
+
+If you don't recognize some of this activity, we recommend changing your password and reviewing your active sessions.
+
+Stop receiving activity digests: {{unsubscribe_link}}
+
+(c) {{year}} {{app_name}}"#;
+
 // ============================================================================
 // Security Alert Template
 // ============================================================================
@@ -614,6 +821,7 @@ const SECURITY_ALERT_TEMPLATE: &str = r#"<!DOCTYPE html>
         </div>
         <div class="footer">
             <p>This is an automated security notification.</p>
+            <p><a href="{{unsubscribe_link}}" style="color: #666;">Unsubscribe from these emails</a></p>
             <p>&copy; {{year}} {{app_name}}</p>
         </div>
     </div>
@@ -641,6 +849,135 @@ IF THIS WASN'T YOU:
 We recommend changing your password immediately and reviewing your account activity.
 
 This is an automated security notification.
+Unsubscribe from these emails: {{unsubscribe_link}}
+
+(c) {{year}} {{app_name}}"#;
+
+// ============================================================================
+// Login Notification Template
+// ============================================================================
+
+const LOGIN_NOTIFICATION_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>New Sign-in</title>
+    <style>
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
+        .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .header { text-align: center; margin-bottom: 30px; }
+        .header h1 { color: #1a1a1a; margin: 0; font-size: 24px; }
+        .content { margin-bottom: 30px; }
+        .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
+        .info-box { background-color: #f3f4f6; border-radius: 8px; padding: 16px; margin: 20px 0; }
+        .button { display: inline-block; background-color: #dc2626; color: #ffffff; padding: 14px 28px; text-decoration: none; border-radius: 6px; font-weight: 600; }
+        .button:hover { background-color: #b91c1c; }
+        .link { color: #dc2626; word-break: break-all; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>New Sign-in</h1>
+        </div>
+        <div class="content">
+            <p>Hi {{user_name}},</p>
+            <p>Your account was just signed in to:</p>
+            <div class="info-box">
+                <p style="margin: 0; font-size: 14px;">
+                    <span style="color: #6b7280;">Device:</span> {{device_info}}<br>
+                    <span style="color: #6b7280;">Location:</span> {{location}}<br>
+                    <span style="color: #6b7280;">IP address:</span> {{ip_address}}<br>
+                    <span style="color: #6b7280;">Time:</span> {{timestamp}}
+                </p>
+            </div>
+            <p><strong>If this was you:</strong> You can safely ignore this email.</p>
+            <p><strong>If this wasn't you:</strong> sign this session out and let us know, then change your password.</p>
+            <p style="text-align: center; margin: 30px 0;">
+                <a href="{{report_link}}" class="button">This wasn't me</a>
+            </p>
+            <p style="font-size: 14px; color: #666;">
+                <a href="{{report_link}}" class="link">{{report_link}}</a>
+            </p>
+        </div>
+        <div class="footer">
+            <p><a href="{{unsubscribe_link}}" style="color: #666;">Stop receiving sign-in notifications</a></p>
+            <p>&copy; {{year}} {{app_name}}</p>
+        </div>
+    </div>
+</body>
+</html>"#;
+
+const LOGIN_NOTIFICATION_TEMPLATE_TEXT: &str = r#"New Sign-in
+
+Hi {{user_name}},
+
+Your account was just signed in to:
+
+- Device: {{device_info}}
+- Location: {{location}}
+- IP address: {{ip_address}}
+- Time: {{timestamp}}
+
+IF THIS WAS YOU:
+You can safely ignore this email.
+
+IF THIS WASN'T YOU:
+Sign this session out and let us know, then change your password:
+{{report_link}}
+
+Stop receiving sign-in notifications: {{unsubscribe_link}}
+
+(c) {{year}} {{app_name}}"#;
+
+// ============================================================================
+// Security Alert Digest Template
+// ============================================================================
+
+const SECURITY_ALERT_DIGEST_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Security Alert Digest</title>
+    <style>
+        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, Helvetica, Arial, sans-serif; line-height: 1.6; color: #333; margin: 0; padding: 0; background-color: #f5f5f5; }
+        .container { max-width: 600px; margin: 40px auto; padding: 40px; background: #ffffff; border-radius: 8px; box-shadow: 0 2px 4px rgba(0,0,0,0.1); }
+        .header { text-align: center; margin-bottom: 30px; }
+        .header h1 { color: #dc2626; margin: 0; font-size: 24px; }
+        .content { margin-bottom: 30px; }
+        .footer { text-align: center; font-size: 12px; color: #666; margin-top: 30px; padding-top: 20px; border-top: 1px solid #eee; }
+        .info-box { background-color: #f3f4f6; border-radius: 8px; padding: 16px; margin: 20px 0; font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 13px; white-space: pre-line; word-break: break-all; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="header">
+            <h1>Security Alert Digest</h1>
+        </div>
+        <div class="content">
+            <p>{{alert_count}} security alerts of severity {{min_severity}} or higher were raised in {{tenant_name}} this {{period}}, affecting {{affected_users}} users.</p>
+            <div class="info-box">{{alert_summary}}</div>
+            <p style="font-size: 14px; color: #666;">Action links are valid for 24 hours and only while you receive this digest.</p>
+        </div>
+        <div class="footer">
+            <p>You receive this digest as an admin or on the security distribution list of {{tenant_name}}.</p>
+            <p>&copy; {{year}} {{app_name}}</p>
+        </div>
+    </div>
+</body>
+</html>"#;
+
+const SECURITY_ALERT_DIGEST_TEMPLATE_TEXT: &str = r#"Security Alert Digest
+
+{{alert_count}} security alerts of severity {{min_severity}} or higher were raised in {{tenant_name}} this {{period}}, affecting {{affected_users}} users.
+
+{{alert_summary}}
+
+Action links are valid for 24 hours and only while you receive this digest.
+
+You receive this digest as an admin or on the security distribution list of {{tenant_name}}.
 
 (c) {{year}} {{app_name}}"#;
 
@@ -880,6 +1217,147 @@ mod tests {
         assert!(rendered.html_body.contains("New York, US"));
     }
 
+    #[test]
+    fn test_notification_digest_template() {
+        let mut engine = TemplateEngine::new();
+        engine
+            .set("user_name", "Jane Doe")
+            .set("period", "week")
+            .set("successful_logins", "12")
+            .set("failed_logins", "3")
+            .set("security_alerts", "1")
+            .set(
+                "unsubscribe_link",
+                "https://example.com/unsubscribe?token=t",
+            )
+            .set("year", "2026")
+            .set("app_name", "Auth9");
+
+        let rendered = engine.render_template(EmailTemplate::NotificationDigest);
+
+        assert_eq!(rendered.subject, "Your Auth9 account activity this week");
+        assert!(rendered.text_body.contains("Failed sign-in attempts: 3"));
+        assert!(rendered
+            .html_body
+            .contains("https://example.com/unsubscribe?token=t"));
+    }
+
+    #[test]
+    fn test_login_notification_template() {
+        let mut engine = TemplateEngine::new();
+        engine
+            .set("user_name", "Jane Doe")
+            .set("device_info", "Safari on iPhone (mobile)")
+            .set("location", "Osaka, JP")
+            .set("ip_address", "203.0.113.7")
+            .set("timestamp", "2026-01-31 10:30:00 UTC")
+            .set("report_link", "https://example.com/sign-in-report?token=r")
+            .set(
+                "unsubscribe_link",
+                "https://example.com/unsubscribe?token=t",
+            )
+            .set("year", "2026")
+            .set("app_name", "Auth9");
+
+        let rendered = engine.render_template(EmailTemplate::LoginNotification);
+
+        assert_eq!(rendered.subject, "New sign-in to your Auth9 account");
+        assert!(rendered.html_body.contains("Safari on iPhone (mobile)"));
+        assert!(rendered.text_body.contains("Location: Osaka, JP"));
+        assert!(rendered
+            .html_body
+            .contains("https://example.com/sign-in-report?token=r"));
+    }
+
+    #[test]
+    fn test_security_alert_digest_template() {
+        let mut engine = TemplateEngine::new();
+        engine
+            .set("tenant_name", "Acme")
+            .set("period", "day")
+            .set("alert_count", "2")
+            .set("affected_users", "1")
+            .set("min_severity", "high")
+            .set(
+                "alert_summary",
+                "jane@example.com (2)\n  Sign out everywhere: https://example.com/a?token=t",
+            )
+            .set("year", "2026")
+            .set("app_name", "Auth9");
+
+        let rendered = engine.render_template(EmailTemplate::SecurityAlertDigest);
+
+        assert_eq!(rendered.subject, "Acme: 2 security alerts this day");
+        assert!(rendered
+            .text_body
+            .contains("jane@example.com (2)\n  Sign out everywhere"));
+        assert!(rendered.html_body.contains("https://example.com/a?token=t"));
+    }
+
+    #[test]
+    fn test_localized_content_default_locale_is_default_content() {
+        let localized =
+            EmailTemplate::localized_content(EmailTemplateType::Invitation, DEFAULT_LOCALE);
+        let default = EmailTemplate::default_content(EmailTemplateType::Invitation);
+        assert_eq!(localized.subject, default.subject);
+        assert_eq!(localized.html_body, default.html_body);
+    }
+
+    #[test]
+    fn test_localized_content_renders_translated_template() {
+        let content =
+            EmailTemplate::localized_content(EmailTemplateType::PasswordReset, Locale::Ja);
+        assert!(content.html_body.contains("lang=\"ja\""));
+        assert!(content.html_body.contains("href=\"{{reset_link}}\""));
+        assert!(!content.html_body.contains("[["));
+
+        let mut engine = TemplateEngine::new();
+        engine
+            .set("user_name", "<b>Jane</b>")
+            .set("reset_link", "https://example.com/reset/xyz")
+            .set("expires_in_minutes", "30")
+            .set("year", "2026")
+            .set("app_name", "Auth9");
+        let html = engine.render_html(&content.html_body);
+        let text = engine.render(&content.text_body);
+
+        assert!(html.contains("&lt;b&gt;Jane&lt;/b&gt;"));
+        assert!(html.contains("https://example.com/reset/xyz"));
+        assert!(text.contains("30"));
+        assert!(!text.contains("{{"));
+        assert!(!text.contains("{ $"));
+    }
+
+    #[test]
+    fn test_localized_content_covers_all_templates() {
+        for locale in [Locale::ZhCn, Locale::Ja] {
+            for template_type in EmailTemplateType::all() {
+                let content = EmailTemplate::localized_content(*template_type, locale);
+                let default = EmailTemplate::default_content(*template_type);
+                assert_ne!(
+                    content.subject, default.subject,
+                    "{} has no {} translation",
+                    template_type, locale
+                );
+                assert!(!content.subject.contains("{ $"));
+                for var in template_type.variables() {
+                    if var.name == "app_name" || var.name == "year" {
+                        continue;
+                    }
+                    let placeholder = format!("{{{{{}}}}}", var.name);
+                    assert!(
+                        content.html_body.contains(&placeholder)
+                            || content.subject.contains(&placeholder),
+                        "{} {} template does not use {}",
+                        locale,
+                        template_type,
+                        var.name
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_from_template_type() {
         assert_eq!(
@@ -0,0 +1,162 @@
+//! Dynamic client registration error responses (RFC 7591 Section 3.2.2,
+//! RFC 7592 Section 3).
+//!
+//! Used only by the registration and client configuration endpoints, which
+//! must answer with the `error` + `error_description` JSON shape.
+
+use super::AppError;
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+#[derive(Debug)]
+pub enum ClientRegistrationError {
+    /// The value of one or more redirection URIs is invalid.
+    InvalidRedirectUri(String),
+
+    /// The value of one of the client metadata fields is invalid.
+    InvalidClientMetadata(String),
+
+    /// The software statement is not a valid signed JWT.
+    InvalidSoftwareStatement(String),
+
+    /// The software statement is valid but not issued by a trusted issuer,
+    /// or one is required and missing.
+    UnapprovedSoftwareStatement(String),
+
+    /// The initial or registration access token is missing, invalid,
+    /// expired or revoked.
+    InvalidToken(String),
+
+    /// Registration is disabled for the tenant.
+    AccessDenied(String),
+
+    /// Internal server error.
+    ServerError(String),
+}
+
+#[derive(Serialize)]
+struct ClientRegistrationErrorResponse {
+    error: &'static str,
+    error_description: String,
+}
+
+impl ClientRegistrationError {
+    fn error_code(&self) -> &'static str {
+        match self {
+            Self::InvalidRedirectUri(_) => "invalid_redirect_uri",
+            Self::InvalidClientMetadata(_) => "invalid_client_metadata",
+            Self::InvalidSoftwareStatement(_) => "invalid_software_statement",
+            Self::UnapprovedSoftwareStatement(_) => "unapproved_software_statement",
+            Self::InvalidToken(_) => "invalid_token",
+            Self::AccessDenied(_) => "access_denied",
+            Self::ServerError(_) => "server_error",
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidToken(_) => StatusCode::UNAUTHORIZED,
+            Self::AccessDenied(_) => StatusCode::FORBIDDEN,
+            Self::ServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self {
+            Self::InvalidRedirectUri(d)
+            | Self::InvalidClientMetadata(d)
+            | Self::InvalidSoftwareStatement(d)
+            | Self::UnapprovedSoftwareStatement(d)
+            | Self::InvalidToken(d)
+            | Self::AccessDenied(d)
+            | Self::ServerError(d) => d,
+        }
+    }
+}
+
+impl From<AppError> for ClientRegistrationError {
+    fn from(err: AppError) -> Self {
+        match err {
+            AppError::Validation(msg) | AppError::BadRequest(msg) => {
+                Self::InvalidClientMetadata(msg)
+            }
+            AppError::Unauthorized(msg) => Self::InvalidToken(msg),
+            AppError::Forbidden(msg) => Self::AccessDenied(msg),
+            other => Self::ServerError(other.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ClientRegistrationError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let body = Json(ClientRegistrationErrorResponse {
+            error: self.error_code(),
+            error_description: self.description().to_string(),
+        });
+        if matches!(self, Self::InvalidToken(_)) {
+            // RFC 6750 Section 3
+            return (
+                status,
+                [(header::WWW_AUTHENTICATE, "Bearer error=\"invalid_token\"")],
+                body,
+            )
+                .into_response();
+        }
+        (status, body).into_response()
+    }
+}
+
+impl std::fmt::Display for ClientRegistrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.error_code(), self.description())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(
+            ClientRegistrationError::InvalidRedirectUri("x".into()).status_code(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            ClientRegistrationError::InvalidToken("x".into()).status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            ClientRegistrationError::AccessDenied("x".into()).status_code(),
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[test]
+    fn test_from_app_error() {
+        let err: ClientRegistrationError = AppError::Validation("bad".into()).into();
+        assert_eq!(err.error_code(), "invalid_client_metadata");
+        let err: ClientRegistrationError = AppError::Unauthorized("no".into()).into();
+        assert_eq!(err.error_code(), "invalid_token");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_sets_www_authenticate() {
+        let response = ClientRegistrationError::InvalidToken("expired".into()).into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get(header::WWW_AUTHENTICATE).is_some());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "invalid_token");
+        assert_eq!(json["error_description"], "expired");
+    }
+}
@@ -1,5 +1,6 @@
 //! Unified error handling for Auth9 Core
 
+pub mod client_registration;
 pub mod oauth;
 
 use axum::{
@@ -32,6 +33,55 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// Optimistic concurrency check failed: the resource changed between
+    /// reading and writing it within the update
+    #[error("Version conflict: {resource} was modified (current version {current_version:?})")]
+    VersionConflict {
+        resource: String,
+        current_version: Option<i32>,
+    },
+
+    /// Conditional request failed: the `If-Match` ETag sent by the client no
+    /// longer matches the stored version of the resource
+    #[error("Precondition failed: {resource} is at version {current_version:?}")]
+    PreconditionFailed {
+        resource: String,
+        current_version: Option<i32>,
+    },
+
+    /// Data residency boundary: the tenant's data lives in another region
+    /// than the one the operation targets (or the operation spans regions)
+    #[error(
+        "Cross-region access: {resource} data lives in region '{region}', not '{requested_region}'"
+    )]
+    CrossRegionAccess {
+        resource: String,
+        region: String,
+        requested_region: String,
+    },
+
+    /// Creating a session would exceed the user's concurrent session limit.
+    /// With `prompt` the client may retry, naming sessions to end.
+    #[error("Session limit reached: at most {max_sessions} concurrent sessions")]
+    SessionLimitReached {
+        max_sessions: u32,
+        prompt: bool,
+        sessions: Vec<crate::models::session::SessionInfo>,
+    },
+
+    /// A write would grow a relation (tenant members, a user's roles, a
+    /// role's permissions) past its configured cap
+    #[error("Cardinality limit exceeded: at most {limit} {}", .cap.describe())]
+    CardinalityLimitExceeded {
+        cap: crate::models::cardinality::CardinalityCap,
+        limit: u64,
+        requested: u64,
+    },
+
+    /// No database is configured for a data residency region
+    #[error("Unknown data region: {0}")]
+    UnknownDataRegion(String),
+
     #[error("Validation error: {0}")]
     Validation(String),
 
@@ -57,6 +107,35 @@ pub enum AppError {
     Internal(#[from] anyhow::Error),
 }
 
+/// Client-facing message for [`AppError::VersionConflict`] and
+/// [`AppError::PreconditionFailed`]
+const VERSION_CONFLICT_MESSAGE: &str =
+    "The resource was modified by another request. Reload it and try again.";
+
+/// Client-facing messages for [`AppError::SessionLimitReached`]
+const SESSION_LIMIT_PROMPT_MESSAGE: &str =
+    "You have too many active sessions. Choose sessions to sign out and try again.";
+const SESSION_LIMIT_DENY_MESSAGE: &str =
+    "You have too many active sessions. Sign out of another device and try again.";
+
+/// Client-facing message for [`AppError::CrossRegionAccess`]
+const CROSS_REGION_ACCESS_MESSAGE: &str =
+    "The requested data is stored in another region and cannot be accessed here.";
+
+impl AppError {
+    /// Reject a write when the caller's expected version (from `If-Match`)
+    /// no longer matches the stored one.
+    pub fn check_version(resource: &str, expected: Option<i32>, current: i32) -> Result<()> {
+        match expected {
+            Some(expected) if expected != current => Err(AppError::PreconditionFailed {
+                resource: resource.to_string(),
+                current_version: Some(current),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Error response body
 #[derive(Serialize, ToSchema)]
 pub(crate) struct ErrorResponse {
@@ -74,6 +153,104 @@ impl IntoResponse for AppError {
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, "unauthorized", msg.clone()),
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, "forbidden", msg.clone()),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg.clone()),
+            AppError::VersionConflict {
+                resource,
+                current_version,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: "version_conflict".to_string(),
+                    message: VERSION_CONFLICT_MESSAGE.to_string(),
+                    details: Some(serde_json::json!({
+                        "resource": resource,
+                        "current_version": current_version,
+                    })),
+                });
+                return (StatusCode::CONFLICT, body).into_response();
+            }
+            AppError::PreconditionFailed {
+                resource,
+                current_version,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: "precondition_failed".to_string(),
+                    message: VERSION_CONFLICT_MESSAGE.to_string(),
+                    details: Some(serde_json::json!({
+                        "resource": resource,
+                        "current_version": current_version,
+                    })),
+                });
+                // Current ETag so the client knows which version it is behind
+                let mut response = (StatusCode::PRECONDITION_FAILED, body).into_response();
+                if let Some(version) = current_version {
+                    response.headers_mut().insert(
+                        axum::http::header::ETAG,
+                        crate::http_support::etag::etag_value(*version),
+                    );
+                }
+                return response;
+            }
+            AppError::CrossRegionAccess {
+                resource,
+                region,
+                requested_region,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: "cross_region_access".to_string(),
+                    message: CROSS_REGION_ACCESS_MESSAGE.to_string(),
+                    details: Some(serde_json::json!({
+                        "resource": resource,
+                        "region": region,
+                        "requested_region": requested_region,
+                    })),
+                });
+                return (StatusCode::MISDIRECTED_REQUEST, body).into_response();
+            }
+            AppError::SessionLimitReached {
+                max_sessions,
+                prompt,
+                sessions,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: "session_limit_reached".to_string(),
+                    message: if *prompt {
+                        SESSION_LIMIT_PROMPT_MESSAGE
+                    } else {
+                        SESSION_LIMIT_DENY_MESSAGE
+                    }
+                    .to_string(),
+                    details: Some(serde_json::json!({
+                        "max_sessions": max_sessions,
+                        "action": if *prompt { "prompt" } else { "deny_new" },
+                        "sessions": sessions,
+                    })),
+                });
+                return (StatusCode::CONFLICT, body).into_response();
+            }
+            AppError::CardinalityLimitExceeded {
+                cap,
+                limit,
+                requested,
+            } => {
+                let body = Json(ErrorResponse {
+                    error: "cardinality_limit_exceeded".to_string(),
+                    message: format!(
+                        "This change would exceed the limit of {} {}.",
+                        limit,
+                        cap.describe()
+                    ),
+                    details: Some(serde_json::json!({
+                        "cap": cap,
+                        "limit": limit,
+                        "requested": requested,
+                    })),
+                });
+                return (StatusCode::CONFLICT, body).into_response();
+            }
+            AppError::UnknownDataRegion(region) => (
+                StatusCode::BAD_REQUEST,
+                "unknown_data_region",
+                format!("Unknown data region: {}", region),
+            ),
             AppError::Validation(msg) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, "validation", msg.clone())
             }
@@ -288,6 +465,115 @@ mod tests {
         assert_eq!(err.to_string(), "Conflict: Resource already exists");
     }
 
+    #[tokio::test]
+    async fn test_version_conflict_response() {
+        let response = AppError::VersionConflict {
+            resource: "tenant".to_string(),
+            current_version: Some(4),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "version_conflict");
+        assert_eq!(json["details"]["resource"], "tenant");
+        assert_eq!(json["details"]["current_version"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_precondition_failed_response() {
+        let response = AppError::PreconditionFailed {
+            resource: "webhook".to_string(),
+            current_version: Some(3),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(response.headers().get("etag").unwrap(), "\"3\"");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "precondition_failed");
+        assert_eq!(json["details"]["resource"], "webhook");
+        assert_eq!(json["details"]["current_version"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_cross_region_access_response() {
+        let response = AppError::CrossRegionAccess {
+            resource: "tenant".to_string(),
+            region: "eu-west".to_string(),
+            requested_region: "us-east".to_string(),
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::MISDIRECTED_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "cross_region_access");
+        assert_eq!(json["details"]["region"], "eu-west");
+        assert_eq!(json["details"]["requested_region"], "us-east");
+    }
+
+    #[tokio::test]
+    async fn test_session_limit_reached_response() {
+        let response = AppError::SessionLimitReached {
+            max_sessions: 2,
+            prompt: true,
+            sessions: vec![crate::models::session::Session::default().into()],
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "session_limit_reached");
+        assert_eq!(json["details"]["max_sessions"], 2);
+        assert_eq!(json["details"]["action"], "prompt");
+        assert_eq!(json["details"]["sessions"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cardinality_limit_exceeded_response() {
+        let response = AppError::CardinalityLimitExceeded {
+            cap: crate::models::cardinality::CardinalityCap::RolesPerUser,
+            limit: 100,
+            requested: 101,
+        }
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "cardinality_limit_exceeded");
+        assert_eq!(json["details"]["cap"], "roles_per_user");
+        assert_eq!(json["details"]["limit"], 100);
+        assert_eq!(json["details"]["requested"], 101);
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert!(AppError::check_version("user", None, 3).is_ok());
+        assert!(AppError::check_version("user", Some(3), 3).is_ok());
+        assert!(matches!(
+            AppError::check_version("user", Some(2), 3),
+            Err(AppError::PreconditionFailed {
+                current_version: Some(3),
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_validation_display() {
         let err = AppError::Validation("Email is required".to_string());
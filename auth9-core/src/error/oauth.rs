@@ -36,6 +36,18 @@ pub enum OAuthTokenError {
 
     /// Internal server error (maps cache/DB failures).
     ServerError(String),
+
+    /// The request was denied by tenant policy (e.g. conditional access).
+    AccessDenied(String),
+
+    /// The user must complete an interaction (e.g. progressive profiling)
+    /// before a token can be issued (OIDC Core Section 3.1.2.6).
+    InteractionRequired {
+        description: String,
+        /// Hosted page where the interaction is completed
+        interaction_uri: String,
+        missing_fields: Vec<String>,
+    },
 }
 
 /// RFC 6749 Section 5.2 error response body.
@@ -43,6 +55,10 @@ pub enum OAuthTokenError {
 struct OAuthErrorResponse {
     error: &'static str,
     error_description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interaction_uri: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    missing_fields: Vec<String>,
 }
 
 impl OAuthTokenError {
@@ -55,6 +71,8 @@ impl OAuthTokenError {
             Self::UnsupportedGrantType(_) => "unsupported_grant_type",
             Self::InvalidScope(_) => "invalid_scope",
             Self::ServerError(_) => "server_error",
+            Self::AccessDenied(_) => "access_denied",
+            Self::InteractionRequired { .. } => "interaction_required",
         }
     }
 
@@ -75,7 +93,9 @@ impl OAuthTokenError {
             | Self::UnauthorizedClient(d)
             | Self::UnsupportedGrantType(d)
             | Self::InvalidScope(d)
-            | Self::ServerError(d) => d,
+            | Self::ServerError(d)
+            | Self::AccessDenied(d) => d,
+            Self::InteractionRequired { description, .. } => description,
         }
     }
 }
@@ -83,9 +103,21 @@ impl OAuthTokenError {
 impl IntoResponse for OAuthTokenError {
     fn into_response(self) -> Response {
         let status = self.status_code();
+        let error = self.error_code();
+        let error_description = self.description().to_string();
+        let (interaction_uri, missing_fields) = match self {
+            Self::InteractionRequired {
+                interaction_uri,
+                missing_fields,
+                ..
+            } => (Some(interaction_uri), missing_fields),
+            _ => (None, vec![]),
+        };
         let body = Json(OAuthErrorResponse {
-            error: self.error_code(),
-            error_description: self.description().to_string(),
+            error,
+            error_description,
+            interaction_uri,
+            missing_fields,
         });
         (status, body).into_response()
     }
@@ -127,6 +159,10 @@ mod tests {
             OAuthTokenError::ServerError("x".into()).error_code(),
             "server_error"
         );
+        assert_eq!(
+            OAuthTokenError::AccessDenied("x".into()).error_code(),
+            "access_denied"
+        );
     }
 
     #[test]
@@ -151,6 +187,10 @@ mod tests {
             OAuthTokenError::ServerError("x".into()).status_code(),
             StatusCode::INTERNAL_SERVER_ERROR
         );
+        assert_eq!(
+            OAuthTokenError::AccessDenied("x".into()).status_code(),
+            StatusCode::BAD_REQUEST
+        );
     }
 
     #[tokio::test]
@@ -173,6 +213,31 @@ mod tests {
         // Must NOT have "message" or "details" fields (auth9 format)
         assert!(json.get("message").is_none());
         assert!(json.get("details").is_none());
+        assert!(json.get("interaction_uri").is_none());
+        assert!(json.get("missing_fields").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interaction_required_response() {
+        let err = OAuthTokenError::InteractionRequired {
+            description: "Profile information required".into(),
+            interaction_uri: "https://portal.example.com/complete-profile?client_id=app".into(),
+            missing_fields: vec!["company".into()],
+        };
+        assert_eq!(err.error_code(), "interaction_required");
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(json["error"], "interaction_required");
+        assert_eq!(
+            json["interaction_uri"],
+            "https://portal.example.com/complete-profile?client_id=app"
+        );
+        assert_eq!(json["missing_fields"], serde_json::json!(["company"]));
     }
 
     #[tokio::test]
@@ -0,0 +1,338 @@
+//! Fault injection for resilience testing (`fault-injection` feature)
+//!
+//! Adds latency and/or errors to calls into the identity engine, the
+//! database and Redis so staging resilience tests can exercise timeouts and
+//! fail-open/fail-closed paths. Faults come from two places:
+//!
+//! - **Config**: `FAULT_INJECTION_IDENTITY_ENGINE`, `FAULT_INJECTION_DATABASE`
+//!   and `FAULT_INJECTION_REDIS` apply to every call of the process.
+//! - **Request header**: with `FAULT_INJECTION_HEADERS=true`, an
+//!   `X-Auth9-Fault` header applies faults to the calls made while handling
+//!   that request, overriding the configured fault of the same target.
+//!
+//! A fault spec is a comma-separated list of `latency:<ms>` and `error` or
+//! `error:<rate>` (0.0-1.0), e.g. `latency:250,error:0.1`. The header holds
+//! `;`-separated `<target>=<spec>` pairs, e.g.
+//! `X-Auth9-Fault: database=error; redis=latency:500`.
+//!
+//! Header faults only reach work done in the request's task; background
+//! tasks spawned by the handler see the configured faults only. The feature
+//! is off by default and must never be enabled in production builds.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use sqlx::mysql::MySqlPoolOptions;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Request header carrying per-request faults
+pub const FAULT_HEADER: &str = "x-auth9-fault";
+
+/// Dependency a fault is injected into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultTarget {
+    IdentityEngine,
+    Database,
+    Redis,
+}
+
+impl FaultTarget {
+    pub const ALL: [FaultTarget; 3] = [Self::IdentityEngine, Self::Database, Self::Redis];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IdentityEngine => "identity_engine",
+            Self::Database => "database",
+            Self::Redis => "redis",
+        }
+    }
+
+    fn env_var(&self) -> &'static str {
+        match self {
+            Self::IdentityEngine => "FAULT_INJECTION_IDENTITY_ENGINE",
+            Self::Database => "FAULT_INJECTION_DATABASE",
+            Self::Redis => "FAULT_INJECTION_REDIS",
+        }
+    }
+}
+
+impl std::str::FromStr for FaultTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| format!("Unknown fault target: {}", s))
+    }
+}
+
+/// Latency and error rate injected into one dependency
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Fault {
+    pub latency: Duration,
+    /// Share of calls that fail (0.0-1.0)
+    pub error_rate: f64,
+}
+
+impl std::str::FromStr for Fault {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fault = Fault::default();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = part.split_once(':').unwrap_or((part, ""));
+            match (name.trim(), value.trim()) {
+                ("latency", ms) => {
+                    let ms: u64 = ms
+                        .parse()
+                        .map_err(|_| format!("Invalid fault latency: {}", ms))?;
+                    fault.latency = Duration::from_millis(ms);
+                }
+                ("error", "") => fault.error_rate = 1.0,
+                ("error", rate) => {
+                    fault.error_rate = rate
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|r| (0.0..=1.0).contains(r))
+                        .ok_or_else(|| format!("Invalid fault error rate: {}", rate))?;
+                }
+                _ => return Err(format!("Unknown fault: {}", part)),
+            }
+        }
+        Ok(fault)
+    }
+}
+
+/// Faults per target
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FaultPlan {
+    pub identity_engine: Option<Fault>,
+    pub database: Option<Fault>,
+    pub redis: Option<Fault>,
+}
+
+impl FaultPlan {
+    pub fn get(&self, target: FaultTarget) -> Option<Fault> {
+        match target {
+            FaultTarget::IdentityEngine => self.identity_engine,
+            FaultTarget::Database => self.database,
+            FaultTarget::Redis => self.redis,
+        }
+    }
+
+    fn set(&mut self, target: FaultTarget, fault: Fault) {
+        match target {
+            FaultTarget::IdentityEngine => self.identity_engine = Some(fault),
+            FaultTarget::Database => self.database = Some(fault),
+            FaultTarget::Redis => self.redis = Some(fault),
+        }
+    }
+
+    /// Parse an `X-Auth9-Fault` header value
+    pub fn parse_header(value: &str) -> Result<Self, String> {
+        let mut plan = FaultPlan::default();
+        for pair in value.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (target, spec) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected <target>=<fault>: {}", pair))?;
+            plan.set(target.trim().parse()?, spec.parse()?);
+        }
+        Ok(plan)
+    }
+}
+
+/// Fault injection settings read from the environment
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjectionConfig {
+    /// Faults applied to every call
+    pub faults: FaultPlan,
+    /// Honor the `X-Auth9-Fault` request header
+    pub headers_enabled: bool,
+}
+
+impl FaultInjectionConfig {
+    pub fn from_env() -> Self {
+        let mut faults = FaultPlan::default();
+        for target in FaultTarget::ALL {
+            let Ok(spec) = std::env::var(target.env_var()) else {
+                continue;
+            };
+            match spec.parse() {
+                Ok(fault) => faults.set(target, fault),
+                Err(e) => tracing::warn!(
+                    target = target.as_str(),
+                    error = %e,
+                    "Ignoring invalid fault injection spec"
+                ),
+            }
+        }
+        Self {
+            faults,
+            headers_enabled: std::env::var("FAULT_INJECTION_HEADERS")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+        }
+    }
+}
+
+static CONFIG: LazyLock<FaultInjectionConfig> = LazyLock::new(FaultInjectionConfig::from_env);
+
+tokio::task_local! {
+    static REQUEST_FAULTS: FaultPlan;
+}
+
+/// Error returned by an injected failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Injected {} fault", .0.as_str())]
+pub struct InjectedFault(pub FaultTarget);
+
+impl From<InjectedFault> for std::io::Error {
+    fn from(fault: InjectedFault) -> Self {
+        std::io::Error::new(std::io::ErrorKind::ConnectionAborted, fault.to_string())
+    }
+}
+
+impl From<InjectedFault> for crate::error::AppError {
+    fn from(fault: InjectedFault) -> Self {
+        match fault.0 {
+            FaultTarget::Database => Self::Database(sqlx::Error::Io(fault.into())),
+            FaultTarget::Redis => Self::Redis(redis::RedisError::from(std::io::Error::from(fault))),
+            FaultTarget::IdentityEngine => Self::Internal(anyhow::Error::new(fault)),
+        }
+    }
+}
+
+/// Log the configured faults once at startup
+pub fn log_config() {
+    let config = &*CONFIG;
+    tracing::warn!(
+        faults = ?config.faults,
+        headers_enabled = config.headers_enabled,
+        "Fault injection is compiled in; do not run this build in production"
+    );
+}
+
+fn active_fault(target: FaultTarget) -> Option<Fault> {
+    REQUEST_FAULTS
+        .try_with(|plan| plan.get(target))
+        .ok()
+        .flatten()
+        .or_else(|| CONFIG.faults.get(target))
+}
+
+/// Apply the active fault of `target`: wait out its latency, then fail at
+/// its error rate
+pub async fn inject(target: FaultTarget) -> Result<(), InjectedFault> {
+    let Some(fault) = active_fault(target) else {
+        return Ok(());
+    };
+    if !fault.latency.is_zero() {
+        metrics::counter!("auth9_fault_injected_total", "target" => target.as_str(), "kind" => "latency")
+            .increment(1);
+        tokio::time::sleep(fault.latency).await;
+    }
+    if fault.error_rate > 0.0 && rand::random::<f64>() < fault.error_rate {
+        metrics::counter!("auth9_fault_injected_total", "target" => target.as_str(), "kind" => "error")
+            .increment(1);
+        return Err(InjectedFault(target));
+    }
+    Ok(())
+}
+
+/// Inject database faults when the pool hands out or opens a connection.
+/// Failed checks close the connection and the pool retries, so a failing
+/// database shows up as slow acquires and, at a high error rate, as
+/// `PoolTimedOut` errors, as when the database is unreachable.
+pub fn with_database_faults(options: MySqlPoolOptions) -> MySqlPoolOptions {
+    options
+        .before_acquire(|_, _| {
+            Box::pin(async {
+                inject(FaultTarget::Database)
+                    .await
+                    .map_err(|fault| sqlx::Error::Io(fault.into()))?;
+                Ok(true)
+            })
+        })
+        .after_connect(|_, _| {
+            Box::pin(async {
+                inject(FaultTarget::Database)
+                    .await
+                    .map_err(|fault| sqlx::Error::Io(fault.into()))
+            })
+        })
+}
+
+/// Scope the `X-Auth9-Fault` header's faults to the request. An invalid
+/// header is ignored with a warning rather than failing the request.
+pub async fn fault_injection_middleware(request: Request, next: Next) -> Response {
+    let plan = CONFIG
+        .headers_enabled
+        .then(|| request.headers().get(FAULT_HEADER))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| match FaultPlan::parse_header(value) {
+            Ok(plan) => Some(plan),
+            Err(e) => {
+                tracing::warn!(error = %e, "Ignoring invalid fault injection header");
+                None
+            }
+        });
+    match plan {
+        Some(plan) => REQUEST_FAULTS.scope(plan, next.run(request)).await,
+        None => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fault_spec() {
+        assert_eq!(
+            "latency:250,error:0.1".parse::<Fault>().unwrap(),
+            Fault {
+                latency: Duration::from_millis(250),
+                error_rate: 0.1
+            }
+        );
+        assert_eq!("error".parse::<Fault>().unwrap().error_rate, 1.0);
+        assert!("error:2".parse::<Fault>().is_err());
+        assert!("latency:soon".parse::<Fault>().is_err());
+        assert!("crash".parse::<Fault>().is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let plan = FaultPlan::parse_header("database=error; redis=latency:500").unwrap();
+        assert_eq!(plan.get(FaultTarget::Database).unwrap().error_rate, 1.0);
+        assert_eq!(
+            plan.get(FaultTarget::Redis).unwrap().latency,
+            Duration::from_millis(500)
+        );
+        assert!(plan.get(FaultTarget::IdentityEngine).is_none());
+        assert!(FaultPlan::parse_header("keycloak=error").is_err());
+        assert!(FaultPlan::parse_header("database").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_request_faults_are_scoped() {
+        let plan = FaultPlan {
+            database: Some(Fault {
+                latency: Duration::ZERO,
+                error_rate: 1.0,
+            }),
+            ..Default::default()
+        };
+        REQUEST_FAULTS
+            .scope(plan, async {
+                assert_eq!(
+                    inject(FaultTarget::Database).await,
+                    Err(InjectedFault(FaultTarget::Database))
+                );
+                assert!(inject(FaultTarget::Redis).await.is_ok());
+            })
+            .await;
+        assert!(inject(FaultTarget::Database).await.is_ok());
+    }
+}
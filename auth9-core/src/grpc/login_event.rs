@@ -0,0 +1,331 @@
+//! Login telemetry gRPC service implementation
+//!
+//! Lets edge gateways and mobile BFFs report sign-in outcomes directly into
+//! the login event store. Reports are enriched server-side (device type,
+//! geolocation, tenant defaulting) before they are persisted so that the
+//! analytics and security detection views treat them like native events.
+
+use crate::domains::security_observability::service::GeoIpService;
+use crate::grpc::proto::{
+    login_telemetry_server::LoginTelemetry, LoginEventReport, RecordLoginEventRequest,
+    RecordLoginEventResponse, RecordLoginEventsRequest, RecordLoginEventsResponse,
+};
+use crate::models::analytics::{CreateLoginEventInput, LoginEventType};
+use crate::models::common::StringUuid;
+use crate::models::service::Service;
+use crate::models::session::parse_user_agent;
+use crate::repository::LoginEventRepository;
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+
+/// Metadata header carrying the reporting service's OAuth client_id
+const CLIENT_ID_HEADER: &str = "x-client-id";
+/// Metadata header carrying the reporting service's client secret
+const CLIENT_SECRET_HEADER: &str = "x-client-secret";
+
+/// Maximum number of events accepted in a single batch call
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Trait for verifying service client credentials on telemetry calls.
+/// Follows the same trait-object pattern as `ActionExecutor`.
+#[async_trait::async_trait]
+pub trait ClientCredentialVerifier: Send + Sync {
+    async fn verify_client(&self, client_id: &str, secret: &str) -> crate::error::Result<Service>;
+}
+
+#[async_trait::async_trait]
+impl<R, RR, AR, BR> ClientCredentialVerifier
+    for crate::domains::authorization::service::ClientService<R, RR, AR, BR>
+where
+    R: crate::repository::ServiceRepository + 'static,
+    RR: crate::repository::RbacRepository + 'static,
+    AR: crate::repository::action::ActionRepository + 'static,
+    BR: crate::repository::service_branding::ServiceBrandingRepository + 'static,
+{
+    async fn verify_client(&self, client_id: &str, secret: &str) -> crate::error::Result<Service> {
+        self.verify_secret(client_id, secret).await
+    }
+}
+
+/// Authenticate the calling service from the client credentials in
+/// `x-client-id` / `x-client-secret`. Returns the client_id and its service.
+pub(crate) async fn authenticate_client(
+    verifier: &dyn ClientCredentialVerifier,
+    metadata: &MetadataMap,
+) -> Result<(String, Service), Status> {
+    let header = |name: &str| {
+        metadata
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let (client_id, secret) =
+        match (header(CLIENT_ID_HEADER), header(CLIENT_SECRET_HEADER)) {
+            (Some(id), Some(secret)) => (id, secret),
+            _ => return Err(Status::unauthenticated(
+                "Missing client credentials. Provide 'x-client-id' and 'x-client-secret' headers.",
+            )),
+        };
+
+    let service = verifier
+        .verify_client(&client_id, &secret)
+        .await
+        .map_err(|_| Status::unauthenticated("Invalid client credentials"))?;
+    Ok((client_id, service))
+}
+
+pub struct LoginTelemetryService<L: LoginEventRepository> {
+    login_event_repo: Arc<L>,
+    verifier: Arc<dyn ClientCredentialVerifier>,
+    geoip: Option<Arc<GeoIpService>>,
+}
+
+impl<L: LoginEventRepository> LoginTelemetryService<L> {
+    pub fn new(login_event_repo: Arc<L>, verifier: Arc<dyn ClientCredentialVerifier>) -> Self {
+        Self {
+            login_event_repo,
+            verifier,
+            geoip: None,
+        }
+    }
+
+    pub fn with_geoip(mut self, geoip: Arc<GeoIpService>) -> Self {
+        self.geoip = Some(geoip);
+        self
+    }
+
+    /// Authenticate the reporting service from its client credentials.
+    async fn authenticate(&self, metadata: &MetadataMap) -> Result<Service, Status> {
+        let (_, service) = authenticate_client(self.verifier.as_ref(), metadata).await?;
+        Ok(service)
+    }
+
+    /// Validate a report and enrich it into a storable login event.
+    fn enrich(
+        &self,
+        report: LoginEventReport,
+        reporter: &Service,
+    ) -> Result<CreateLoginEventInput, Status> {
+        let event_type = parse_outcome(&report.outcome)?;
+        let user_id = parse_optional_uuid(&report.user_id, "user_id")?;
+        let session_id = parse_optional_uuid(&report.session_id, "session_id")?;
+
+        // Services bound to a tenant can only report for that tenant.
+        let tenant_id = match (
+            parse_optional_uuid(&report.tenant_id, "tenant_id")?,
+            reporter.tenant_id,
+        ) {
+            (Some(requested), Some(own)) if requested != own => {
+                return Err(Status::permission_denied(
+                    "Service is not allowed to report events for another tenant",
+                ));
+            }
+            (requested, own) => requested.or(own),
+        };
+
+        let ip_address = non_empty(report.ip_address);
+        let user_agent = non_empty(report.user_agent);
+        let device_type = non_empty(report.device_type)
+            .or_else(|| user_agent.as_deref().and_then(|ua| parse_user_agent(ua).0));
+
+        let geo = match (&self.geoip, ip_address.as_deref()) {
+            (Some(geoip), Some(ip)) => geoip.lookup(ip),
+            _ => None,
+        };
+
+        let failure_reason = if is_failure(&event_type) {
+            non_empty(report.failure_reason)
+        } else {
+            None
+        };
+
+        Ok(CreateLoginEventInput {
+            user_id,
+            email: non_empty(report.email),
+            tenant_id,
+            event_type,
+            ip_address,
+            user_agent,
+            device_type,
+            location: geo.as_ref().map(|g| g.label()),
+            session_id,
+            failure_reason,
+            provider_alias: non_empty(report.provider_alias),
+            provider_type: None,
+            latitude: geo.as_ref().map(|g| g.latitude),
+            longitude: geo.as_ref().map(|g| g.longitude),
+            country_code: geo.map(|g| g.country_code),
+        })
+    }
+
+    async fn store(&self, input: &CreateLoginEventInput, latency_ms: u32) -> Result<i64, Status> {
+        let id = self
+            .login_event_repo
+            .create(input)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to record login event: {}", e)))?;
+
+        let outcome = input.event_type.to_string();
+        metrics::counter!("auth9_edge_login_events_total", "outcome" => outcome.clone())
+            .increment(1);
+        if latency_ms > 0 {
+            metrics::histogram!("auth9_edge_login_latency_seconds", "outcome" => outcome)
+                .record(latency_ms as f64 / 1000.0);
+        }
+        Ok(id)
+    }
+}
+
+#[tonic::async_trait]
+impl<L: LoginEventRepository + 'static> LoginTelemetry for LoginTelemetryService<L> {
+    async fn record_login_event(
+        &self,
+        request: Request<RecordLoginEventRequest>,
+    ) -> Result<Response<RecordLoginEventResponse>, Status> {
+        let reporter = self.authenticate(request.metadata()).await?;
+        let report = request
+            .into_inner()
+            .event
+            .ok_or_else(|| Status::invalid_argument("event is required"))?;
+
+        let latency_ms = report.latency_ms;
+        let input = self.enrich(report, &reporter)?;
+        let event_id = self.store(&input, latency_ms).await?;
+
+        Ok(Response::new(RecordLoginEventResponse { event_id }))
+    }
+
+    async fn record_login_events(
+        &self,
+        request: Request<RecordLoginEventsRequest>,
+    ) -> Result<Response<RecordLoginEventsResponse>, Status> {
+        let reporter = self.authenticate(request.metadata()).await?;
+        let reports = request.into_inner().events;
+
+        if reports.is_empty() {
+            return Err(Status::invalid_argument("events must not be empty"));
+        }
+        if reports.len() > MAX_BATCH_SIZE {
+            return Err(Status::invalid_argument(format!(
+                "At most {} events can be recorded per call",
+                MAX_BATCH_SIZE
+            )));
+        }
+
+        // Validate the whole batch before storing anything so a malformed
+        // entry does not leave a partially ingested batch behind.
+        let mut prepared = Vec::with_capacity(reports.len());
+        for (index, report) in reports.into_iter().enumerate() {
+            let latency_ms = report.latency_ms;
+            let input = self.enrich(report, &reporter).map_err(|s| {
+                Status::new(s.code(), format!("events[{}]: {}", index, s.message()))
+            })?;
+            prepared.push((input, latency_ms));
+        }
+
+        let mut event_ids = Vec::with_capacity(prepared.len());
+        for (input, latency_ms) in &prepared {
+            event_ids.push(self.store(input, *latency_ms).await?);
+        }
+
+        Ok(Response::new(RecordLoginEventsResponse { event_ids }))
+    }
+}
+
+/// Map a reported outcome onto a login event type. Link/unlink events are
+/// managed by Auth9 itself and cannot be reported from the edge.
+fn parse_outcome(outcome: &str) -> Result<LoginEventType, Status> {
+    match outcome.parse::<LoginEventType>() {
+        Ok(LoginEventType::IdentityLinked | LoginEventType::IdentityUnlinked) | Err(_) => Err(
+            Status::invalid_argument(format!("Unsupported login outcome '{}'", outcome)),
+        ),
+        Ok(event_type) => Ok(event_type),
+    }
+}
+
+fn is_failure(event_type: &LoginEventType) -> bool {
+    matches!(
+        event_type,
+        LoginEventType::FailedPassword
+            | LoginEventType::FailedMfa
+            | LoginEventType::Locked
+            | LoginEventType::FederationFailed
+    )
+}
+
+pub(crate) fn parse_optional_uuid(value: &str, field: &str) -> Result<Option<StringUuid>, Status> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse::<StringUuid>()
+        .map(Some)
+        .map_err(|_| Status::invalid_argument(format!("{} must be a UUID", field)))
+}
+
+pub(crate) fn non_empty(value: String) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_outcome_accepts_login_outcomes() {
+        assert_eq!(parse_outcome("success").unwrap(), LoginEventType::Success);
+        assert_eq!(
+            parse_outcome("FAILED_MFA").unwrap(),
+            LoginEventType::FailedMfa
+        );
+        assert_eq!(
+            parse_outcome("federation_failed").unwrap(),
+            LoginEventType::FederationFailed
+        );
+    }
+
+    #[test]
+    fn test_parse_outcome_rejects_link_events_and_unknown() {
+        assert!(parse_outcome("identity_linked").is_err());
+        assert!(parse_outcome("identity_unlinked").is_err());
+        assert!(parse_outcome("teleported").is_err());
+        assert!(parse_outcome("").is_err());
+    }
+
+    #[test]
+    fn test_parse_optional_uuid() {
+        assert!(parse_optional_uuid("", "user_id").unwrap().is_none());
+        let id = uuid::Uuid::new_v4();
+        assert_eq!(
+            parse_optional_uuid(&id.to_string(), "user_id").unwrap(),
+            Some(StringUuid::from(id))
+        );
+        let err = parse_optional_uuid("nope", "user_id").unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert!(err.message().contains("user_id"));
+    }
+
+    #[test]
+    fn test_non_empty_trims() {
+        assert_eq!(non_empty("  ".to_string()), None);
+        assert_eq!(
+            non_empty(" 1.2.3.4 ".to_string()),
+            Some("1.2.3.4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_is_failure() {
+        assert!(is_failure(&LoginEventType::FailedPassword));
+        assert!(is_failure(&LoginEventType::Locked));
+        assert!(!is_failure(&LoginEventType::Success));
+        assert!(!is_failure(&LoginEventType::Social));
+    }
+}
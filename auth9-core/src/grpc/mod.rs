@@ -1,9 +1,17 @@
 //! gRPC services
 
 pub mod interceptor;
+pub mod login_event;
+pub mod permission_replication;
+pub mod provisioning;
+pub mod revocation;
 pub mod token_exchange;
 
 pub use interceptor::{ApiKeyAuthenticator, AuthContext, AuthInterceptor, GrpcAuthenticator};
+pub use login_event::LoginTelemetryService;
+pub use permission_replication::PermissionReplicationService;
+pub use provisioning::ProvisioningService;
+pub use revocation::RevocationWatchService;
 pub use token_exchange::TokenExchangeService;
 
 // Include generated protobuf code
@@ -0,0 +1,330 @@
+//! Permission replication gRPC service implementation
+//!
+//! Sidecars download a gzip-compressed snapshot of each tenant's grants and
+//! then receive deltas. Watch streams recompute the watched tenants' grants
+//! from the database on every poll, so any replica serves every change and
+//! nothing has to hook the individual role and permission writes.
+
+use crate::grpc::login_event::{authenticate_client, ClientCredentialVerifier};
+use crate::grpc::proto::{
+    permission_replication_server::PermissionReplication, permission_update,
+    GetPermissionSnapshotRequest, PermissionDelta as ProtoPermissionDelta,
+    PermissionGrant as ProtoPermissionGrant, PermissionGrantKey, PermissionGrantList,
+    PermissionSnapshot, PermissionUpdate, WatchPermissionsRequest,
+};
+use crate::models::common::StringUuid;
+use crate::models::permission_snapshot::{grants_hash, GrantMap, PermissionDelta, PermissionGrant};
+use crate::models::service::Service;
+use crate::repository::PermissionSnapshotRepository;
+use flate2::{write::GzEncoder, Compression};
+use prost::Message;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+/// Updates buffered per watcher before the poller waits on the client
+const WATCH_BUFFER: usize = 16;
+
+pub struct PermissionReplicationService<R: PermissionSnapshotRepository> {
+    repo: Arc<R>,
+    verifier: Arc<dyn ClientCredentialVerifier>,
+    poll_interval: Duration,
+    max_tenants: usize,
+}
+
+/// Grants a watcher last sent for a tenant
+struct Replica {
+    tenant_id: StringUuid,
+    grants: GrantMap,
+    hash: String,
+}
+
+impl<R: PermissionSnapshotRepository> PermissionReplicationService<R> {
+    pub fn new(
+        repo: Arc<R>,
+        verifier: Arc<dyn ClientCredentialVerifier>,
+        poll_interval: Duration,
+        max_tenants: usize,
+    ) -> Self {
+        Self {
+            repo,
+            verifier,
+            poll_interval,
+            max_tenants,
+        }
+    }
+
+    async fn load(repo: &R, tenant_id: StringUuid) -> Result<GrantMap, Status> {
+        repo.load_tenant(tenant_id)
+            .await
+            .map(|data| data.grants())
+            .map_err(|e| {
+                tracing::error!("Failed to load permissions of tenant {}: {}", tenant_id, e);
+                Status::unavailable("Permission data unavailable")
+            })
+    }
+}
+
+#[tonic::async_trait]
+impl<R: PermissionSnapshotRepository + 'static> PermissionReplication
+    for PermissionReplicationService<R>
+{
+    type WatchPermissionsStream = ReceiverStream<Result<PermissionUpdate, Status>>;
+
+    async fn watch_permissions(
+        &self,
+        request: Request<WatchPermissionsRequest>,
+    ) -> Result<Response<Self::WatchPermissionsStream>, Status> {
+        let (client_id, service) =
+            authenticate_client(self.verifier.as_ref(), request.metadata()).await?;
+        let req = request.into_inner();
+
+        let mut tenant_ids = Vec::new();
+        for raw in &req.tenant_ids {
+            let tenant_id = parse_tenant_id(raw)?;
+            authorize(&service, tenant_id)?;
+            if !tenant_ids.contains(&tenant_id) {
+                tenant_ids.push(tenant_id);
+            }
+        }
+        if tenant_ids.is_empty() {
+            // A tenant-bound service watches its own tenant by default
+            let own = service
+                .tenant_id
+                .ok_or_else(|| Status::invalid_argument("tenant_ids is required"))?;
+            tenant_ids.push(own);
+        }
+        if tenant_ids.len() > self.max_tenants {
+            return Err(Status::invalid_argument(format!(
+                "At most {} tenants per watch",
+                self.max_tenants
+            )));
+        }
+
+        // Snapshots are built before the stream opens so that failures
+        // surface as the call's status
+        let mut replicas = Vec::with_capacity(tenant_ids.len());
+        let mut initial = Vec::new();
+        for tenant_id in tenant_ids {
+            let grants = Self::load(&self.repo, tenant_id).await?;
+            let hash = grants_hash(&grants);
+            if req.known_hashes.get(&tenant_id.to_string()) != Some(&hash) {
+                initial.push(snapshot_update(tenant_id, &grants, &hash)?);
+            }
+            replicas.push(Replica {
+                tenant_id,
+                grants,
+                hash,
+            });
+        }
+
+        tracing::info!(
+            client_id = %client_id,
+            tenants = replicas.len(),
+            snapshots = initial.len(),
+            "Permission watch opened"
+        );
+
+        let (tx, rx) = mpsc::channel(WATCH_BUFFER);
+        let repo = self.repo.clone();
+        let poll_interval = self.poll_interval;
+        tokio::spawn(async move {
+            for update in initial {
+                if tx.send(Ok(update)).await.is_err() {
+                    return;
+                }
+            }
+            loop {
+                tokio::time::sleep(poll_interval).await;
+                if tx.is_closed() {
+                    break;
+                }
+                for replica in &mut replicas {
+                    let grants = match Self::load(&repo, replica.tenant_id).await {
+                        Ok(grants) => grants,
+                        Err(status) => {
+                            let _ = tx.send(Err(status)).await;
+                            return;
+                        }
+                    };
+                    let hash = grants_hash(&grants);
+                    if hash == replica.hash {
+                        continue;
+                    }
+                    let delta = PermissionDelta::between(&replica.grants, &grants);
+                    let update = delta_update(replica.tenant_id, &delta, &replica.hash, &hash);
+                    if tx.send(Ok(update)).await.is_err() {
+                        // Client went away
+                        return;
+                    }
+                    replica.grants = grants;
+                    replica.hash = hash;
+                }
+            }
+            tracing::debug!(client_id = %client_id, "Permission watch closed");
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn get_permission_snapshot(
+        &self,
+        request: Request<GetPermissionSnapshotRequest>,
+    ) -> Result<Response<PermissionUpdate>, Status> {
+        let (_, service) = authenticate_client(self.verifier.as_ref(), request.metadata()).await?;
+        let tenant_id = parse_tenant_id(&request.get_ref().tenant_id)?;
+        authorize(&service, tenant_id)?;
+
+        let grants = Self::load(&self.repo, tenant_id).await?;
+        let hash = grants_hash(&grants);
+        Ok(Response::new(snapshot_update(tenant_id, &grants, &hash)?))
+    }
+}
+
+fn parse_tenant_id(raw: &str) -> Result<StringUuid, Status> {
+    raw.trim()
+        .parse::<StringUuid>()
+        .map_err(|_| Status::invalid_argument(format!("Invalid tenant ID '{}'", raw)))
+}
+
+/// Services bound to a tenant can only replicate that tenant
+fn authorize(service: &Service, tenant_id: StringUuid) -> Result<(), Status> {
+    match service.tenant_id {
+        Some(own) if own != tenant_id => Err(Status::permission_denied(
+            "Service is not allowed to replicate another tenant's permissions",
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn to_proto_grant(grant: &PermissionGrant) -> ProtoPermissionGrant {
+    ProtoPermissionGrant {
+        user_id: grant.user_id.to_string(),
+        service_id: grant.service_id.to_string(),
+        roles: grant.roles.clone(),
+        permissions: grant.permissions.clone(),
+    }
+}
+
+fn snapshot_update(
+    tenant_id: StringUuid,
+    grants: &GrantMap,
+    hash: &str,
+) -> Result<PermissionUpdate, Status> {
+    let list = PermissionGrantList {
+        grants: grants.values().map(to_proto_grant).collect(),
+    };
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let grants_gzip = encoder
+        .write_all(&list.encode_to_vec())
+        .and_then(|_| encoder.finish())
+        .map_err(|e| Status::internal(format!("Failed to compress snapshot: {}", e)))?;
+
+    Ok(PermissionUpdate {
+        tenant_id: tenant_id.to_string(),
+        update: Some(permission_update::Update::Snapshot(PermissionSnapshot {
+            grants_gzip,
+            grant_count: list.grants.len() as u32,
+        })),
+        hash: hash.to_string(),
+    })
+}
+
+fn delta_update(
+    tenant_id: StringUuid,
+    delta: &PermissionDelta,
+    base_hash: &str,
+    hash: &str,
+) -> PermissionUpdate {
+    PermissionUpdate {
+        tenant_id: tenant_id.to_string(),
+        update: Some(permission_update::Update::Delta(ProtoPermissionDelta {
+            base_hash: base_hash.to_string(),
+            upserts: delta.upserts.iter().map(to_proto_grant).collect(),
+            removals: delta
+                .removals
+                .iter()
+                .map(|(user_id, service_id)| PermissionGrantKey {
+                    user_id: user_id.to_string(),
+                    service_id: service_id.to_string(),
+                })
+                .collect(),
+        })),
+        hash: hash.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn grants() -> GrantMap {
+        let grant = PermissionGrant {
+            user_id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            roles: vec!["editor".to_string()],
+            permissions: vec!["doc:read".to_string(), "doc:write".to_string()],
+        };
+        [((grant.user_id, grant.service_id), grant)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_decompresses_to_grants() {
+        let grants = grants();
+        let hash = grants_hash(&grants);
+        let update = snapshot_update(StringUuid::new_v4(), &grants, &hash).unwrap();
+        assert_eq!(update.hash, hash);
+
+        let Some(permission_update::Update::Snapshot(snapshot)) = update.update else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(snapshot.grant_count, 1);
+        let mut bytes = Vec::new();
+        GzDecoder::new(snapshot.grants_gzip.as_slice())
+            .read_to_end(&mut bytes)
+            .unwrap();
+        let list = PermissionGrantList::decode(bytes.as_slice()).unwrap();
+        let grant = grants.values().next().unwrap();
+        assert_eq!(list.grants, vec![to_proto_grant(grant)]);
+    }
+
+    #[test]
+    fn test_delta_update_carries_both_hashes() {
+        let old = grants();
+        let new = GrantMap::new();
+        let delta = PermissionDelta::between(&old, &new);
+        let update = delta_update(StringUuid::new_v4(), &delta, "old", "new");
+        assert_eq!(update.hash, "new");
+        let Some(permission_update::Update::Delta(delta)) = update.update else {
+            panic!("expected a delta");
+        };
+        assert_eq!(delta.base_hash, "old");
+        assert!(delta.upserts.is_empty());
+        assert_eq!(delta.removals.len(), 1);
+    }
+
+    #[test]
+    fn test_authorize_tenant_bound_service() {
+        let own = StringUuid::new_v4();
+        let service = Service {
+            tenant_id: Some(own),
+            ..Default::default()
+        };
+        assert!(authorize(&service, own).is_ok());
+        let err = authorize(&service, StringUuid::new_v4()).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let platform = Service {
+            tenant_id: None,
+            ..Default::default()
+        };
+        assert!(authorize(&platform, StringUuid::new_v4()).is_ok());
+    }
+}
@@ -0,0 +1,590 @@
+//! Provisioning gRPC service implementation
+//!
+//! Lets internal platform services create tenants and users and manage
+//! memberships and role assignments without going through REST. Requests run
+//! through the same validation helpers as the REST handlers. Callers are
+//! service clients listed in `GRPC_PROVISIONING_CLIENT_IDS`; a service bound
+//! to a tenant can only provision inside that tenant.
+
+use crate::domains::authorization::api::role::validate_roles_in_tenant;
+use crate::domains::platform::api::billing::record_billing_event;
+use crate::domains::tenant_access::api::tenant::prepare_create_input;
+use crate::domains::tenant_access::api::user::{provision_user, validate_role_in_tenant};
+use crate::domains::tenant_access::service::sagas::run_tenant_provision;
+use crate::error::AppError;
+use crate::grpc::login_event::{
+    authenticate_client, non_empty, parse_optional_uuid, ClientCredentialVerifier,
+};
+use crate::grpc::proto::{
+    provisioning_server::Provisioning, AddUserToTenantRequest, AddUserToTenantResponse,
+    AssignRolesRequest, AssignRolesResponse, CreateTenantRequest, CreateTenantResponse,
+    CreateUserRequest, CreateUserResponse,
+};
+use crate::models::billing::NewBillingEvent;
+use crate::models::common::StringUuid;
+use crate::models::rbac::AssignRolesInput;
+use crate::models::tenant::{CreateTenantInput, TenantSettings};
+use crate::models::user::{AddUserToTenantInput, CreateUserInput};
+use crate::repository::audit::CreateAuditLogInput;
+use crate::repository::idempotency::IdempotencyRecord;
+use crate::repository::{AuditRepository, IdempotencyRepository};
+use crate::state::{HasDbPool, HasServices};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+/// How long a used idempotency key replays its response
+pub const IDEMPOTENCY_KEY_TTL_HOURS: i64 = 24;
+
+/// Longest accepted idempotency key
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// An authenticated, allowlisted provisioning client
+struct Caller {
+    client_id: String,
+    /// Tenant the client's service belongs to (None = platform service)
+    tenant_id: Option<StringUuid>,
+}
+
+impl Caller {
+    /// Tenant to provision in: the requested one, defaulting to the caller's
+    fn tenant(&self, requested: Option<StringUuid>) -> Result<Option<StringUuid>, Status> {
+        match (requested, self.tenant_id) {
+            (Some(requested), Some(own)) if requested != own => Err(Status::permission_denied(
+                "Service is not allowed to provision in another tenant",
+            )),
+            (requested, own) => Ok(requested.or(own)),
+        }
+    }
+
+    /// Saga idempotency key for a call's idempotency key, scoped to the
+    /// client. A retried call continues the saga of the first attempt.
+    fn saga_key(&self, key: &str) -> Option<String> {
+        (!key.is_empty()).then(|| format!("{}:{}", self.client_id, key))
+    }
+}
+
+pub struct ProvisioningService<S: HasServices + HasDbPool, I: IdempotencyRepository> {
+    state: S,
+    verifier: Arc<dyn ClientCredentialVerifier>,
+    idempotency: Arc<I>,
+    allowed_clients: Vec<String>,
+}
+
+impl<S: HasServices + HasDbPool, I: IdempotencyRepository> ProvisioningService<S, I> {
+    pub fn new(state: S, verifier: Arc<dyn ClientCredentialVerifier>, idempotency: Arc<I>) -> Self {
+        let allowed_clients = state.config().grpc_security.provisioning_client_ids.clone();
+        Self {
+            state,
+            verifier,
+            idempotency,
+            allowed_clients,
+        }
+    }
+
+    async fn authorize(&self, metadata: &MetadataMap) -> Result<Caller, Status> {
+        let (client_id, service) = authenticate_client(self.verifier.as_ref(), metadata).await?;
+        if !self.allowed_clients.contains(&client_id) {
+            return Err(Status::permission_denied(
+                "Client is not allowed to use the provisioning API",
+            ));
+        }
+        Ok(Caller {
+            client_id,
+            tenant_id: service.tenant_id,
+        })
+    }
+
+    /// Run `call` at most once per idempotency key: a retry with the same key
+    /// returns the stored response. Failed calls release their key.
+    async fn idempotent<R, F, Fut>(
+        &self,
+        caller: &Caller,
+        method: &'static str,
+        key: &str,
+        request_hash: &str,
+        call: F,
+    ) -> Result<R, Status>
+    where
+        R: prost::Message + Default,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<R, Status>>,
+    {
+        if key.is_empty() {
+            return call().await;
+        }
+        if key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            return Err(Status::invalid_argument(format!(
+                "idempotency_key must be at most {} characters",
+                MAX_IDEMPOTENCY_KEY_LEN
+            )));
+        }
+
+        let expired_before = Utc::now() - Duration::hours(IDEMPOTENCY_KEY_TTL_HOURS);
+        if let Some(record) = self
+            .idempotency
+            .claim(&caller.client_id, method, key, request_hash, expired_before)
+            .await
+            .map_err(to_status)?
+        {
+            metrics::counter!("auth9_grpc_idempotent_replays_total", "method" => method)
+                .increment(1);
+            return replay(record, request_hash);
+        }
+
+        match call().await {
+            Ok(response) => {
+                if let Err(e) = self
+                    .idempotency
+                    .complete(&caller.client_id, method, key, &response.encode_to_vec())
+                    .await
+                {
+                    tracing::warn!(method, error = %e, "Failed to store idempotent response");
+                }
+                Ok(response)
+            }
+            Err(status) => {
+                if let Err(e) = self
+                    .idempotency
+                    .release(&caller.client_id, method, key)
+                    .await
+                {
+                    tracing::warn!(method, error = %e, "Failed to release idempotency key");
+                }
+                Err(status)
+            }
+        }
+    }
+
+    async fn audit(
+        &self,
+        action: &str,
+        resource_type: &str,
+        resource_id: Option<Uuid>,
+        new_value: Option<serde_json::Value>,
+    ) {
+        let _ = self
+            .state
+            .audit_repo()
+            .create(&CreateAuditLogInput {
+                actor_id: None,
+                action: action.to_string(),
+                resource_type: resource_type.to_string(),
+                resource_id,
+                old_value: None,
+                new_value,
+                ip_address: None,
+            })
+            .await;
+    }
+
+    async fn create_tenant_once(
+        &self,
+        request: CreateTenantRequest,
+        saga_key: Option<String>,
+    ) -> Result<CreateTenantResponse, Status> {
+        let owner_id = parse_optional_uuid(&request.owner_user_id, "owner_user_id")?;
+        let settings = match non_empty(request.settings_json) {
+            Some(json) => Some(serde_json::from_str::<TenantSettings>(&json).map_err(|e| {
+                Status::invalid_argument(format!("settings_json is invalid: {}", e))
+            })?),
+            None => None,
+        };
+        let mut input = CreateTenantInput {
+            name: request.name,
+            slug: request.slug.trim().to_string(),
+            domain: non_empty(request.domain),
+            logo_url: non_empty(request.logo_url),
+            settings,
+            data_region: non_empty(request.data_region),
+        };
+        prepare_create_input(&self.state, &mut input).map_err(to_status)?;
+        // Check the owner first so a bad owner does not leave an ownerless tenant
+        if let Some(owner_id) = owner_id {
+            self.state
+                .user_service()
+                .get(owner_id)
+                .await
+                .map_err(to_status)?;
+        }
+
+        let tenant = run_tenant_provision(
+            &self.state,
+            input,
+            owner_id.map(Uuid::from),
+            saga_key.as_deref(),
+        )
+        .await
+        .map_err(to_status)?;
+
+        self.audit(
+            "tenant.create",
+            "tenant",
+            Some(*tenant.id),
+            serde_json::to_value(&tenant).ok(),
+        )
+        .await;
+        record_billing_event(
+            &self.state,
+            NewBillingEvent::tenant_created(
+                tenant.id,
+                &tenant.name,
+                &tenant.slug,
+                tenant.created_at,
+            ),
+        )
+        .await;
+        Ok(CreateTenantResponse {
+            tenant_id: tenant.id.to_string(),
+            slug: tenant.slug,
+            status: tenant.status.to_string(),
+        })
+    }
+
+    async fn create_user_once(
+        &self,
+        caller: &Caller,
+        request: CreateUserRequest,
+        saga_key: Option<String>,
+    ) -> Result<CreateUserResponse, Status> {
+        let tenant_id = caller.tenant(parse_optional_uuid(&request.tenant_id, "tenant_id")?)?;
+        let input = CreateUserInput {
+            email: request.email.trim().to_string(),
+            display_name: non_empty(request.display_name),
+            avatar_url: None,
+        };
+        let password = Some(request.password).filter(|p| !p.is_empty());
+        let (user, password_warning) = provision_user(
+            &self.state,
+            input,
+            password,
+            tenant_id.map(Uuid::from),
+            saga_key.as_deref(),
+        )
+        .await
+        .map_err(to_status)?;
+
+        self.audit(
+            "user.create",
+            "user",
+            Some(*user.id),
+            serde_json::to_value(&user).ok(),
+        )
+        .await;
+        Ok(CreateUserResponse {
+            user_id: user.id.to_string(),
+            email: user.email,
+            password_warning: password_warning.unwrap_or_default(),
+        })
+    }
+
+    async fn add_user_to_tenant_once(
+        &self,
+        caller: &Caller,
+        request: AddUserToTenantRequest,
+    ) -> Result<AddUserToTenantResponse, Status> {
+        let user_id = parse_uuid(&request.user_id, "user_id")?;
+        let tenant_id = parse_uuid(&request.tenant_id, "tenant_id")?;
+        caller.tenant(Some(tenant_id))?;
+        validate_role_in_tenant(&request.role_in_tenant).map_err(to_status)?;
+        self.state
+            .tenant_service()
+            .require_active(tenant_id)
+            .await
+            .map_err(to_status)?;
+
+        let tenant_user = self
+            .state
+            .user_service()
+            .add_to_tenant(AddUserToTenantInput {
+                user_id: *user_id,
+                tenant_id: *tenant_id,
+                role_in_tenant: request.role_in_tenant,
+            })
+            .await
+            .map_err(to_status)?;
+
+        self.audit(
+            "user.add_to_tenant",
+            "tenant_user",
+            Some(*tenant_user.id),
+            serde_json::to_value(&tenant_user).ok(),
+        )
+        .await;
+        Ok(AddUserToTenantResponse {
+            tenant_user_id: tenant_user.id.to_string(),
+        })
+    }
+
+    async fn assign_roles_once(
+        &self,
+        caller: &Caller,
+        request: AssignRolesRequest,
+    ) -> Result<AssignRolesResponse, Status> {
+        let user_id = parse_uuid(&request.user_id, "user_id")?;
+        let tenant_id = parse_uuid(&request.tenant_id, "tenant_id")?;
+        caller.tenant(Some(tenant_id))?;
+        let role_ids = request
+            .role_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| parse_uuid(id, &format!("role_ids[{}]", index)).map(Uuid::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let input = AssignRolesInput {
+            user_id: *user_id,
+            tenant_id: *tenant_id,
+            role_ids,
+            service_id: parse_optional_uuid(&request.service_id, "service_id")?.map(Uuid::from),
+        };
+        validate_roles_in_tenant(&self.state, &input)
+            .await
+            .map_err(to_status)?;
+
+        let assigned = input.role_ids.len() as u32;
+        self.state
+            .rbac_service()
+            .assign_roles(input, None)
+            .await
+            .map_err(to_status)?;
+
+        self.audit(
+            "rbac.assign_roles",
+            "user_roles",
+            Some(*user_id),
+            Some(serde_json::json!({
+                "tenant_id": tenant_id,
+                "role_ids": request.role_ids,
+            })),
+        )
+        .await;
+        Ok(AssignRolesResponse { assigned })
+    }
+}
+
+#[tonic::async_trait]
+impl<S, I> Provisioning for ProvisioningService<S, I>
+where
+    S: HasServices + HasDbPool,
+    I: IdempotencyRepository + 'static,
+{
+    async fn create_tenant(
+        &self,
+        request: Request<CreateTenantRequest>,
+    ) -> Result<Response<CreateTenantResponse>, Status> {
+        let caller = self.authorize(request.metadata()).await?;
+        if caller.tenant_id.is_some() {
+            return Err(Status::permission_denied(
+                "Only platform services can create tenants",
+            ));
+        }
+        let mut request = request.into_inner();
+        let key = std::mem::take(&mut request.idempotency_key);
+        let hash = request_hash(&request);
+        let saga_key = caller.saga_key(&key);
+        let response = self
+            .idempotent(&caller, "CreateTenant", &key, &hash, || {
+                self.create_tenant_once(request, saga_key)
+            })
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn create_user(
+        &self,
+        request: Request<CreateUserRequest>,
+    ) -> Result<Response<CreateUserResponse>, Status> {
+        let caller = self.authorize(request.metadata()).await?;
+        let mut request = request.into_inner();
+        let key = std::mem::take(&mut request.idempotency_key);
+        let hash = request_hash(&request);
+        let saga_key = caller.saga_key(&key);
+        let response = self
+            .idempotent(&caller, "CreateUser", &key, &hash, || {
+                self.create_user_once(&caller, request, saga_key)
+            })
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn add_user_to_tenant(
+        &self,
+        request: Request<AddUserToTenantRequest>,
+    ) -> Result<Response<AddUserToTenantResponse>, Status> {
+        let caller = self.authorize(request.metadata()).await?;
+        let mut request = request.into_inner();
+        let key = std::mem::take(&mut request.idempotency_key);
+        let hash = request_hash(&request);
+        let response = self
+            .idempotent(&caller, "AddUserToTenant", &key, &hash, || {
+                self.add_user_to_tenant_once(&caller, request)
+            })
+            .await?;
+        Ok(Response::new(response))
+    }
+
+    async fn assign_roles(
+        &self,
+        request: Request<AssignRolesRequest>,
+    ) -> Result<Response<AssignRolesResponse>, Status> {
+        let caller = self.authorize(request.metadata()).await?;
+        let mut request = request.into_inner();
+        let key = std::mem::take(&mut request.idempotency_key);
+        let hash = request_hash(&request);
+        let response = self
+            .idempotent(&caller, "AssignRoles", &key, &hash, || {
+                self.assign_roles_once(&caller, request)
+            })
+            .await?;
+        Ok(Response::new(response))
+    }
+}
+
+/// SHA-256 of a request, taken with its idempotency key cleared
+fn request_hash<M: prost::Message>(request: &M) -> String {
+    hex::encode(Sha256::digest(request.encode_to_vec()))
+}
+
+/// Response to a call whose idempotency key was already used
+fn replay<R: prost::Message + Default>(
+    record: IdempotencyRecord,
+    request_hash: &str,
+) -> Result<R, Status> {
+    if record.request_hash != request_hash {
+        return Err(Status::failed_precondition(
+            "idempotency_key was already used for a different request",
+        ));
+    }
+    match record.response {
+        Some(response) => R::decode(response.as_slice())
+            .map_err(|e| Status::internal(format!("Failed to decode stored response: {}", e))),
+        None => Err(Status::aborted(
+            "A request with this idempotency_key is still in progress",
+        )),
+    }
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<StringUuid, Status> {
+    parse_optional_uuid(value, field)?
+        .ok_or_else(|| Status::invalid_argument(format!("{} is required", field)))
+}
+
+/// Map a service error onto the gRPC status the REST API's HTTP status
+/// corresponds to
+fn to_status(error: AppError) -> Status {
+    match error {
+        AppError::NotFound(msg) => Status::not_found(msg),
+        AppError::BadRequest(msg) | AppError::Validation(msg) => Status::invalid_argument(msg),
+        AppError::UnknownDataRegion(region) => {
+            Status::invalid_argument(format!("Unknown data region: {}", region))
+        }
+        AppError::Unauthorized(msg) => Status::unauthenticated(msg),
+        AppError::Forbidden(msg) => Status::permission_denied(msg),
+        AppError::Conflict(msg) => Status::already_exists(msg),
+        AppError::TooManyRequests(msg) => Status::resource_exhausted(msg),
+        error @ AppError::CardinalityLimitExceeded { .. } => {
+            Status::failed_precondition(error.to_string())
+        }
+        AppError::Database(sqlx::Error::Database(db_err))
+            if db_err.code().as_deref() == Some("23000")
+                || db_err.message().contains("Duplicate entry") =>
+        {
+            Status::already_exists("Resource already exists")
+        }
+        error => {
+            tracing::error!(error = %error, "Provisioning call failed");
+            Status::internal("Internal error")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::proto::AddUserToTenantResponse;
+    use tonic::Code;
+
+    fn caller(tenant_id: Option<StringUuid>) -> Caller {
+        Caller {
+            client_id: "platform-svc".to_string(),
+            tenant_id,
+        }
+    }
+
+    #[test]
+    fn test_caller_tenant_scoping() {
+        let own = StringUuid::new_v4();
+        let other = StringUuid::new_v4();
+        assert_eq!(caller(Some(own)).tenant(None).unwrap(), Some(own));
+        assert_eq!(caller(Some(own)).tenant(Some(own)).unwrap(), Some(own));
+        assert_eq!(
+            caller(Some(own)).tenant(Some(other)).unwrap_err().code(),
+            Code::PermissionDenied
+        );
+        assert_eq!(caller(None).tenant(Some(other)).unwrap(), Some(other));
+        assert_eq!(caller(None).tenant(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_request_hash_ignores_idempotency_key_only() {
+        let request = AddUserToTenantRequest {
+            idempotency_key: String::new(),
+            user_id: "u".to_string(),
+            tenant_id: "t".to_string(),
+            role_in_tenant: "member".to_string(),
+        };
+        let other = AddUserToTenantRequest {
+            role_in_tenant: "admin".to_string(),
+            ..request.clone()
+        };
+        assert_eq!(request_hash(&request), request_hash(&request.clone()));
+        assert_ne!(request_hash(&request), request_hash(&other));
+    }
+
+    #[test]
+    fn test_replay_returns_stored_response() {
+        let stored = AddUserToTenantResponse {
+            tenant_user_id: "tu-1".to_string(),
+        };
+        let record = IdempotencyRecord {
+            request_hash: "h".to_string(),
+            response: Some(stored.encode_to_vec()),
+        };
+        let replayed: AddUserToTenantResponse = replay(record, "h").unwrap();
+        assert_eq!(replayed, stored);
+    }
+
+    #[test]
+    fn test_replay_rejects_different_request_and_in_progress() {
+        let record = IdempotencyRecord {
+            request_hash: "h".to_string(),
+            response: None,
+        };
+        let err = replay::<AddUserToTenantResponse>(record.clone(), "other").unwrap_err();
+        assert_eq!(err.code(), Code::FailedPrecondition);
+        let err = replay::<AddUserToTenantResponse>(record, "h").unwrap_err();
+        assert_eq!(err.code(), Code::Aborted);
+    }
+
+    #[test]
+    fn test_to_status_mirrors_http_errors() {
+        assert_eq!(
+            to_status(AppError::Validation("bad email".to_string())).code(),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            to_status(AppError::Conflict("exists".to_string())).code(),
+            Code::AlreadyExists
+        );
+        assert_eq!(
+            to_status(AppError::NotFound("user".to_string())).code(),
+            Code::NotFound
+        );
+        let internal = to_status(AppError::Internal(anyhow::anyhow!("secret detail")));
+        assert_eq!(internal.code(), Code::Internal);
+        assert!(!internal.message().contains("secret"));
+    }
+}
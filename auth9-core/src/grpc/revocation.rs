@@ -0,0 +1,136 @@
+//! Revocation feed gRPC service implementation
+//!
+//! Streams revoked sessions to relying parties. The stream polls the
+//! `session_revocations` table rather than an in-process channel so every
+//! replica serves the same feed, whichever one revoked the session.
+
+use crate::grpc::login_event::{authenticate_client, ClientCredentialVerifier};
+use crate::grpc::proto::{
+    revocation_feed_server::RevocationFeed, RevocationNotice, WatchRevocationsRequest,
+};
+use crate::models::session_revocation::{self, MAX_REVOCATION_PAGE};
+use crate::repository::SessionRevocationRepository;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+/// Notices buffered per watcher before the poller waits on the client
+const WATCH_BUFFER: usize = 64;
+
+pub struct RevocationWatchService<R: SessionRevocationRepository> {
+    repo: Arc<R>,
+    verifier: Arc<dyn ClientCredentialVerifier>,
+    poll_interval: Duration,
+}
+
+impl<R: SessionRevocationRepository> RevocationWatchService<R> {
+    pub fn new(
+        repo: Arc<R>,
+        verifier: Arc<dyn ClientCredentialVerifier>,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            repo,
+            verifier,
+            poll_interval,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<R: SessionRevocationRepository + 'static> RevocationFeed for RevocationWatchService<R> {
+    type WatchRevocationsStream = ReceiverStream<Result<RevocationNotice, Status>>;
+
+    async fn watch_revocations(
+        &self,
+        request: Request<WatchRevocationsRequest>,
+    ) -> Result<Response<Self::WatchRevocationsStream>, Status> {
+        let (client_id, service) =
+            authenticate_client(self.verifier.as_ref(), request.metadata()).await?;
+        let tenant_id = service.tenant_id;
+        let mut cursor = request.into_inner().since_sequence.max(0);
+
+        tracing::info!(
+            client_id = %client_id,
+            since_sequence = cursor,
+            "Revocation watch opened"
+        );
+
+        let (tx, rx) = mpsc::channel(WATCH_BUFFER);
+        let repo = self.repo.clone();
+        let poll_interval = self.poll_interval;
+        tokio::spawn(async move {
+            loop {
+                let notices = match repo
+                    .list_since(cursor, tenant_id, MAX_REVOCATION_PAGE)
+                    .await
+                {
+                    Ok(notices) => notices,
+                    Err(e) => {
+                        tracing::error!("Revocation watch poll failed: {}", e);
+                        let _ = tx
+                            .send(Err(Status::unavailable("Revocation feed unavailable")))
+                            .await;
+                        return;
+                    }
+                };
+
+                if notices.is_empty() {
+                    if tx.is_closed() {
+                        break;
+                    }
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+                for notice in notices {
+                    cursor = notice.sequence;
+                    if tx.send(Ok(to_proto(notice))).await.is_err() {
+                        // Client went away
+                        return;
+                    }
+                }
+            }
+            tracing::debug!(client_id = %client_id, "Revocation watch closed");
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+fn to_proto(notice: session_revocation::RevocationNotice) -> RevocationNotice {
+    RevocationNotice {
+        sequence: notice.sequence,
+        session_id: notice.session_id.to_string(),
+        user_id: notice.user_id.to_string(),
+        reason: notice.reason.as_str().to_string(),
+        revoked_at: notice.revoked_at.timestamp(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::common::StringUuid;
+    use crate::models::session_revocation::SessionRevocationReason;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_to_proto() {
+        let session_id = StringUuid::new_v4();
+        let user_id = StringUuid::new_v4();
+        let notice = to_proto(session_revocation::RevocationNotice {
+            sequence: 7,
+            session_id,
+            user_id,
+            reason: SessionRevocationReason::AdminForceLogout,
+            revoked_at: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+        });
+        assert_eq!(notice.sequence, 7);
+        assert_eq!(notice.session_id, session_id.to_string());
+        assert_eq!(notice.user_id, user_id.to_string());
+        assert_eq!(notice.reason, "admin_force_logout");
+        assert_eq!(notice.revoked_at, 1_700_000_000);
+    }
+}
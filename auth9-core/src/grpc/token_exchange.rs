@@ -1,14 +1,21 @@
 //! Token Exchange gRPC service implementation
 
+use crate::domains::authorization::service::TokenValidationPolicies;
+use crate::domains::identity::service::claims_enrichment::{merge_claims, ClaimsEnrichmentService};
+use crate::domains::tenant_access::service::MfaEnrollmentService;
+use crate::error::AppError;
 use crate::grpc::proto::{
     token_exchange_server::TokenExchange, ExchangeTokenRequest, ExchangeTokenResponse,
     GetUserRolesRequest, GetUserRolesResponse, IntrospectTokenRequest, IntrospectTokenResponse,
     Role as ProtoRole, ValidateTokenRequest, ValidateTokenResponse,
 };
-use crate::jwt::JwtManager;
+use crate::jwt::{peek_token_audience, JwtManager, TenantAccessClaims};
 use crate::models::action::ActionContext;
-use crate::models::common::StringUuid;
+use crate::models::claims_enrichment::EnrichmentContext;
+use crate::models::common::{ServiceId, StringUuid, TenantId, TokenTtlOverrides, UserId};
 use crate::repository::audit::{AuditRepository, CreateAuditLogInput};
+use crate::repository::claims_enricher::ClaimsEnricherRepositoryImpl;
+use crate::repository::mfa_enrollment::MfaEnrollmentRepositoryImpl;
 use crate::repository::{RbacRepository, ServiceRepository, TenantRepository, UserRepository};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -64,6 +71,39 @@ async fn resolve_optional_service_scope<S: ServiceRepository>(
     Ok(Some(service.id))
 }
 
+/// Tenant ID for a slug. Slugs retired by a rename resolve until their
+/// redirect expires; gRPC has no deprecation header, so the use is logged.
+async fn resolve_tenant_slug(
+    tenant_repo: &dyn TenantRepository,
+    slug: &str,
+) -> Result<StringUuid, Status> {
+    let lookup_failed = |e: AppError| Status::internal(format!("Failed to lookup tenant: {}", e));
+    if let Some(tenant) = tenant_repo
+        .find_by_slug(slug)
+        .await
+        .map_err(lookup_failed)?
+    {
+        return Ok(tenant.id);
+    }
+    let redirect = tenant_repo
+        .find_slug_redirect(slug)
+        .await
+        .map_err(lookup_failed)?
+        .ok_or_else(|| {
+            Status::not_found(format!(
+                "Tenant '{}' not found. Provide a valid tenant UUID or slug.",
+                slug
+            ))
+        })?;
+    tracing::warn!(
+        old_slug = %slug,
+        tenant_id = %redirect.tenant_id,
+        expires_at = %redirect.expires_at,
+        "Tenant addressed by a retired slug"
+    );
+    Ok(redirect.tenant_id)
+}
+
 /// In-memory sliding window rate limiter for gRPC token exchange.
 #[derive(Clone)]
 pub struct GrpcRateLimiter {
@@ -128,9 +168,9 @@ impl GrpcRateLimiter {
 pub trait TokenExchangeCache: Send + Sync {
     fn get_user_roles_for_service(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
-        service_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> impl std::future::Future<
         Output = crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>>,
     > + Send;
@@ -138,13 +178,13 @@ pub trait TokenExchangeCache: Send + Sync {
     fn set_user_roles_for_service(
         &self,
         roles: &crate::models::rbac::UserRolesInTenant,
-        service_id: Uuid,
+        service_id: ServiceId,
     ) -> impl std::future::Future<Output = crate::error::Result<()>> + Send;
 
     fn get_user_roles(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> impl std::future::Future<
         Output = crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>>,
     > + Send;
@@ -158,28 +198,33 @@ pub trait TokenExchangeCache: Send + Sync {
 impl TokenExchangeCache for crate::cache::CacheManager {
     async fn get_user_roles_for_service(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
-        service_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>> {
-        crate::cache::CacheManager::get_user_roles_for_service(self, user_id, tenant_id, service_id)
-            .await
+        crate::cache::CacheManager::get_user_roles_for_service(
+            self,
+            user_id.0,
+            tenant_id.0,
+            service_id.0,
+        )
+        .await
     }
 
     async fn set_user_roles_for_service(
         &self,
         roles: &crate::models::rbac::UserRolesInTenant,
-        service_id: Uuid,
+        service_id: ServiceId,
     ) -> crate::error::Result<()> {
-        crate::cache::CacheManager::set_user_roles_for_service(self, roles, service_id).await
+        crate::cache::CacheManager::set_user_roles_for_service(self, roles, service_id.0).await
     }
 
     async fn get_user_roles(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>> {
-        crate::cache::CacheManager::get_user_roles(self, user_id, tenant_id).await
+        crate::cache::CacheManager::get_user_roles(self, user_id.0, tenant_id.0).await
     }
 
     async fn set_user_roles(
@@ -193,9 +238,9 @@ impl TokenExchangeCache for crate::cache::CacheManager {
 impl TokenExchangeCache for crate::cache::NoOpCacheManager {
     async fn get_user_roles_for_service(
         &self,
-        _user_id: Uuid,
-        _tenant_id: Uuid,
-        _service_id: Uuid,
+        _user_id: UserId,
+        _tenant_id: TenantId,
+        _service_id: ServiceId,
     ) -> crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>> {
         Ok(None)
     }
@@ -203,15 +248,15 @@ impl TokenExchangeCache for crate::cache::NoOpCacheManager {
     async fn set_user_roles_for_service(
         &self,
         _roles: &crate::models::rbac::UserRolesInTenant,
-        _service_id: Uuid,
+        _service_id: ServiceId,
     ) -> crate::error::Result<()> {
         Ok(())
     }
 
     async fn get_user_roles(
         &self,
-        _user_id: Uuid,
-        _tenant_id: Uuid,
+        _user_id: UserId,
+        _tenant_id: TenantId,
     ) -> crate::error::Result<Option<crate::models::rbac::UserRolesInTenant>> {
         Ok(None)
     }
@@ -239,7 +284,10 @@ where
     tenant_repo: Option<Arc<dyn TenantRepository>>,
     audit_repo: Option<Arc<dyn AuditRepository>>,
     action_executor: Option<Arc<dyn ActionExecutor>>,
+    claims_enrichment: Option<Arc<ClaimsEnrichmentService<ClaimsEnricherRepositoryImpl>>>,
+    mfa_enrollment: Option<Arc<MfaEnrollmentService<MfaEnrollmentRepositoryImpl>>>,
     rate_limiter: Option<GrpcRateLimiter>,
+    validation_policies: Option<Arc<TokenValidationPolicies>>,
     is_production: bool,
 }
 
@@ -267,7 +315,10 @@ where
             tenant_repo: None,
             audit_repo: None,
             action_executor: None,
+            claims_enrichment: None,
+            mfa_enrollment: None,
             rate_limiter: None,
+            validation_policies: None,
             is_production,
         }
     }
@@ -290,7 +341,10 @@ where
             tenant_repo: Some(tenant_repo),
             audit_repo: None,
             action_executor: None,
+            claims_enrichment: None,
+            mfa_enrollment: None,
             rate_limiter: None,
+            validation_policies: None,
             is_production,
         }
     }
@@ -305,11 +359,54 @@ where
         self
     }
 
+    pub fn with_claims_enrichment(
+        mut self,
+        service: Arc<ClaimsEnrichmentService<ClaimsEnricherRepositoryImpl>>,
+    ) -> Self {
+        self.claims_enrichment = Some(service);
+        self
+    }
+
+    pub fn with_mfa_enrollment(
+        mut self,
+        service: Arc<MfaEnrollmentService<MfaEnrollmentRepositoryImpl>>,
+    ) -> Self {
+        self.mfa_enrollment = Some(service);
+        self
+    }
+
     pub fn with_rate_limiter(mut self, rate_limiter: GrpcRateLimiter) -> Self {
         self.rate_limiter = Some(rate_limiter);
         self
     }
 
+    pub fn with_validation_policies(mut self, policies: Arc<TokenValidationPolicies>) -> Self {
+        self.validation_policies = Some(policies);
+        self
+    }
+
+    /// Verify a tenant access token under the validation profile of the
+    /// service owning its audience (the platform defaults when unconfigured)
+    async fn verify_tenant_access_token(
+        &self,
+        token: &str,
+        audience: Option<&str>,
+    ) -> crate::error::Result<TenantAccessClaims> {
+        let client_id = audience
+            .map(str::to_string)
+            .or_else(|| peek_token_audience(token));
+        match (&self.validation_policies, client_id) {
+            (Some(policies), Some(client_id)) => {
+                let profile = policies.for_client(&client_id).await;
+                self.jwt_manager
+                    .verify_tenant_access_token_with_profile(token, audience, &profile)
+            }
+            _ => self
+                .jwt_manager
+                .verify_tenant_access_token_with_optional_audience(token, audience),
+        }
+    }
+
     async fn write_exchange_audit_log(
         &self,
         actor_id: Option<Uuid>,
@@ -407,17 +504,7 @@ where
             Ok(id) => id,
             Err(_) => {
                 if let Some(ref tenant_repo) = self.tenant_repo {
-                    let tenant = tenant_repo
-                        .find_by_slug(&req.tenant_id)
-                        .await
-                        .map_err(|e| Status::internal(format!("Failed to lookup tenant: {}", e)))?
-                        .ok_or_else(|| {
-                            Status::not_found(format!(
-                                "Tenant '{}' not found. Provide a valid tenant UUID or slug.",
-                                req.tenant_id
-                            ))
-                        })?;
-                    tenant.id
+                    resolve_tenant_slug(tenant_repo.as_ref(), &req.tenant_id).await?
                 } else {
                     return Err(Status::invalid_argument("Invalid tenant ID"));
                 }
@@ -425,6 +512,8 @@ where
         };
 
         // Verify tenant is active before allowing token exchange
+        let mut tenant_token_ttls = TokenTtlOverrides::default();
+        let mut mfa_enrollment_campaign = None;
         if let Some(ref tenant_repo) = self.tenant_repo {
             let tenant = tenant_repo
                 .find_by_id(tenant_id)
@@ -450,6 +539,8 @@ where
                     tenant.status
                 )));
             }
+            tenant_token_ttls = tenant.settings.token_ttls;
+            mfa_enrollment_campaign = tenant.settings.mfa_enrollment_campaign;
         }
 
         let user = self
@@ -462,7 +553,7 @@ where
         // Verify user is a member of the target tenant (security check)
         let _tenant_user_id = match self
             .rbac_repo
-            .find_tenant_user_id(user_id, tenant_id)
+            .find_tenant_user_id(UserId::from(user_id), TenantId::from(tenant_id))
             .await
             .map_err(|e| Status::internal(format!("Failed to check tenant membership: {}", e)))?
         {
@@ -486,6 +577,43 @@ where
             }
         };
 
+        // Members past the deadline of the tenant's MFA enrollment campaign
+        // must enroll before getting tenant tokens
+        if let Some(ref mfa_enrollment) = self.mfa_enrollment {
+            match mfa_enrollment
+                .ensure_token_allowed(
+                    tenant_id,
+                    mfa_enrollment_campaign.as_ref(),
+                    user_id,
+                    chrono::Utc::now(),
+                )
+                .await
+            {
+                Ok(()) => {}
+                Err(AppError::Forbidden(msg)) => {
+                    self.write_exchange_audit_log(
+                        Some(actor_id),
+                        "token_exchange.exchange.failed",
+                        None,
+                        serde_json::json!({
+                            "tenant_id": Uuid::from(tenant_id),
+                            "service_id": req.service_id,
+                            "reason": "mfa_enrollment_required"
+                        }),
+                        ip_address.clone(),
+                    )
+                    .await;
+                    return Err(Status::permission_denied(msg));
+                }
+                Err(e) => {
+                    return Err(Status::internal(format!(
+                        "Failed to check MFA enrollment: {}",
+                        e
+                    )));
+                }
+            }
+        }
+
         // Verify client exists
         let client = self
             .service_repo
@@ -532,17 +660,25 @@ where
         // after the membership re-check below to prevent stale roles in tokens.
         if let Ok(None) | Err(_) = self
             .cache_manager
-            .get_user_roles_for_service(Uuid::from(user_id), Uuid::from(tenant_id), service.id.0)
+            .get_user_roles_for_service(
+                UserId::from(user_id),
+                TenantId::from(tenant_id),
+                ServiceId::from(service.id),
+            )
             .await
         {
             if let Ok(roles) = self
                 .rbac_repo
-                .find_user_roles_in_tenant_for_service(user_id, tenant_id, service.id)
+                .find_user_roles_in_tenant_for_service(
+                    UserId::from(user_id),
+                    TenantId::from(tenant_id),
+                    ServiceId::from(service.id),
+                )
                 .await
             {
                 let _ = self
                     .cache_manager
-                    .set_user_roles_for_service(&roles, service.id.0)
+                    .set_user_roles_for_service(&roles, ServiceId::from(service.id))
                     .await;
             }
         }
@@ -552,7 +688,7 @@ where
         // membership could have been revoked by a concurrent request.
         let _recheck = match self
             .rbac_repo
-            .find_tenant_user_id(user_id, tenant_id)
+            .find_tenant_user_id(UserId::from(user_id), TenantId::from(tenant_id))
             .await
             .map_err(|e| Status::internal(format!("Failed to re-check tenant membership: {}", e)))?
         {
@@ -580,23 +716,49 @@ where
         // the current state of permissions at the moment of issuance.
         let user_roles = self
             .rbac_repo
-            .find_user_roles_in_tenant_for_service(user_id, tenant_id, service.id)
+            .find_user_roles_in_tenant_for_service(
+                UserId::from(user_id),
+                TenantId::from(tenant_id),
+                ServiceId::from(service.id),
+            )
             .await
             .map_err(|e| Status::internal(format!("Failed to re-fetch user roles: {}", e)))?;
 
         // Include tenant-level role (owner/admin/member) from tenant_users
         // so that policy checks can see the user's membership role.
         let mut roles = user_roles.roles;
-        if let Ok(Some(role_in_tenant)) =
-            self.rbac_repo.find_role_in_tenant(user_id, tenant_id).await
+        if let Ok(Some(role_in_tenant)) = self
+            .rbac_repo
+            .find_role_in_tenant(UserId::from(user_id), TenantId::from(tenant_id))
+            .await
         {
             if !roles.iter().any(|r| r == &role_in_tenant) {
                 roles.insert(0, role_in_tenant);
             }
         }
 
+        // Claims from the service's enrichment plugins
+        let enriched_claims = match &self.claims_enrichment {
+            Some(enrichment) => enrichment
+                .enrich(&EnrichmentContext {
+                    user_id,
+                    email: claims.email.clone(),
+                    tenant_id,
+                    service_id: service.id,
+                    client_id: client.client_id.clone(),
+                    roles: roles.clone(),
+                    permissions: user_roles.permissions.clone(),
+                })
+                .await
+                .map_err(|e| match e {
+                    AppError::Forbidden(msg) => Status::permission_denied(msg),
+                    other => Status::internal(format!("Claims enrichment failed: {}", other)),
+                })?,
+            None => None,
+        };
+
         // Execute post-login actions for the target tenant and sanitize claims (FR-006)
-        let custom_claims = if let Some(ref executor) = self.action_executor {
+        let action_claims = if let Some(ref executor) = self.action_executor {
             use crate::jwt::claims::sanitize_action_claims;
             use crate::models::action::{
                 ActionContextRequest, ActionContextTenant, ActionContextUser,
@@ -631,10 +793,15 @@ where
         } else {
             None
         };
+        let custom_claims = merge_claims(enriched_claims, action_claims);
 
-        // Create tenant access token (propagate session_id for blacklist support)
-        let access_token = self
+        // Client lifetime overrides win over the tenant's
+        let jwt_manager = self
             .jwt_manager
+            .with_ttl_overrides(client.token_ttls.or(tenant_token_ttls));
+
+        // Create tenant access token (propagate session_id for blacklist support)
+        let access_token = jwt_manager
             .create_tenant_access_token_with_claims(
                 Uuid::from(user_id),
                 &claims.email,
@@ -647,8 +814,7 @@ where
             )
             .map_err(|e| Status::internal(format!("Failed to create access token: {}", e)))?;
 
-        let refresh_token = self
-            .jwt_manager
+        let refresh_token = jwt_manager
             .create_refresh_token(
                 Uuid::from(user_id),
                 Uuid::from(tenant_id),
@@ -657,7 +823,14 @@ where
             .map_err(|e| Status::internal(format!("Failed to create refresh token: {}", e)))?;
 
         metrics::counter!("auth9_auth_token_exchange_total", "result" => "success").increment(1);
-        metrics::histogram!("auth9_grpc_request_duration_seconds", "service" => "TokenExchange", "method" => "exchange_token").record(start.elapsed().as_secs_f64());
+        crate::telemetry::exemplars::record_latency(
+            "auth9_grpc_request_duration_seconds",
+            &[
+                ("service", "TokenExchange".to_string()),
+                ("method", "exchange_token".to_string()),
+            ],
+            start.elapsed().as_secs_f64(),
+        );
         metrics::counter!("auth9_grpc_requests_total", "service" => "TokenExchange", "method" => "exchange_token", "status" => "ok").increment(1);
         self.write_exchange_audit_log(
             Some(actor_id),
@@ -674,7 +847,7 @@ where
         Ok(Response::new(ExchangeTokenResponse {
             access_token,
             token_type: "Bearer".to_string(),
-            expires_in: self.jwt_manager.access_token_ttl(),
+            expires_in: jwt_manager.access_token_ttl(),
             refresh_token,
         }))
     }
@@ -713,8 +886,8 @@ where
         };
 
         match self
-            .jwt_manager
-            .verify_tenant_access_token_with_optional_audience(&req.access_token, audience)
+            .verify_tenant_access_token(&req.access_token, audience)
+            .await
         {
             Ok(claims) => {
                 metrics::counter!("auth9_auth_token_validation_total", "result" => "valid")
@@ -747,23 +920,13 @@ where
 
         let user_id = req
             .user_id
-            .parse::<StringUuid>()
+            .parse::<UserId>()
             .map_err(|_| Status::invalid_argument("Invalid user ID"))?;
-        let tenant_id = match req.tenant_id.parse::<StringUuid>() {
+        let tenant_id = match req.tenant_id.parse::<TenantId>() {
             Ok(id) => id,
             Err(_) => {
                 if let Some(ref tenant_repo) = self.tenant_repo {
-                    let tenant = tenant_repo
-                        .find_by_slug(&req.tenant_id)
-                        .await
-                        .map_err(|e| Status::internal(format!("Failed to lookup tenant: {}", e)))?
-                        .ok_or_else(|| {
-                            Status::not_found(format!(
-                                "Tenant '{}' not found. Provide a valid tenant UUID or slug.",
-                                req.tenant_id
-                            ))
-                        })?;
-                    tenant.id
+                    TenantId::from(resolve_tenant_slug(tenant_repo.as_ref(), &req.tenant_id).await?)
                 } else {
                     return Err(Status::invalid_argument("Invalid tenant ID"));
                 }
@@ -785,16 +948,14 @@ where
         }
 
         let resolved_service_id =
-            resolve_optional_service_scope(self.service_repo.as_ref(), &req.service_id).await?;
+            resolve_optional_service_scope(self.service_repo.as_ref(), &req.service_id)
+                .await?
+                .map(ServiceId::from);
 
         let (user_roles, role_records) = if let Some(service_id) = resolved_service_id {
             let user_roles = match self
                 .cache_manager
-                .get_user_roles_for_service(
-                    Uuid::from(user_id),
-                    Uuid::from(tenant_id),
-                    service_id.0,
-                )
+                .get_user_roles_for_service(user_id, tenant_id, service_id)
                 .await
             {
                 Ok(Some(roles)) => roles,
@@ -809,7 +970,7 @@ where
 
                     let _ = self
                         .cache_manager
-                        .set_user_roles_for_service(&roles, service_id.0)
+                        .set_user_roles_for_service(&roles, service_id)
                         .await;
                     roles
                 }
@@ -823,11 +984,7 @@ where
 
             (user_roles, role_records)
         } else {
-            let user_roles = match self
-                .cache_manager
-                .get_user_roles(Uuid::from(user_id), Uuid::from(tenant_id))
-                .await
-            {
+            let user_roles = match self.cache_manager.get_user_roles(user_id, tenant_id).await {
                 Ok(Some(roles)) => roles,
                 _ => {
                     let roles = self
@@ -873,10 +1030,7 @@ where
         let req = request.into_inner();
 
         // Try as tenant access token first
-        match self
-            .jwt_manager
-            .verify_tenant_access_token_with_optional_audience(&req.token, None)
-        {
+        match self.verify_tenant_access_token(&req.token, None).await {
             Ok(claims) => Ok(Response::new(IntrospectTokenResponse {
                 active: true,
                 sub: claims.sub,
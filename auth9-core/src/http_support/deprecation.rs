@@ -0,0 +1,362 @@
+//! Registry of deprecated API endpoints and fields.
+//!
+//! Every deprecation is declared once in [`API_DEPRECATIONS`] with the date it
+//! was deprecated and the date it will be removed. The registry drives:
+//! - `Deprecation` (RFC 9745) and `Sunset` (RFC 8594) response headers, added
+//!   by [`crate::middleware::deprecation_middleware`]
+//! - the `deprecated` flags and sunset notes of the OpenAPI document
+//! - the `auth9_deprecated_api_usage_total` metric, which counts consumers per
+//!   client_id so removals can wait until nobody relies on them
+//!
+//! Retired tenant slugs are signalled the same way by
+//! [`tenant_slug_redirect_headers`], with the dates of the rename.
+
+use crate::models::tenant::TenantSlugRedirect;
+use axum::http::{header, HeaderMap, HeaderValue, Method};
+use chrono::{NaiveDate, NaiveTime};
+use metrics::counter;
+use utoipa::openapi::path::{Operation, ParameterBuilder, ParameterIn};
+use utoipa::openapi::schema::Schema;
+use utoipa::openapi::{Deprecated, OpenApi, RefOr};
+
+/// What part of an endpoint is deprecated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeprecatedTarget {
+    /// The whole endpoint
+    Endpoint,
+    /// A query parameter; only requests that send it are affected
+    QueryParam(&'static str),
+    /// A property of a response schema (OpenAPI component name, property)
+    ResponseField(&'static str, &'static str),
+}
+
+/// A deprecated endpoint or field
+#[derive(Debug, Clone, Copy)]
+pub struct ApiDeprecation {
+    /// HTTP method, e.g. `GET`
+    pub method: &'static str,
+    /// Path template as registered in the router, e.g. `/api/v1/users/{id}`
+    pub path: &'static str,
+    pub target: DeprecatedTarget,
+    /// Date the deprecation was announced (`YYYY-MM-DD`)
+    pub since: &'static str,
+    /// Date after which the endpoint or field may be removed (`YYYY-MM-DD`)
+    pub sunset: &'static str,
+    /// What clients should use instead
+    pub replacement: &'static str,
+}
+
+impl ApiDeprecation {
+    /// Name used in metrics and documentation, e.g. `unresolved_only`
+    pub fn name(&self) -> &'static str {
+        match self.target {
+            DeprecatedTarget::Endpoint => self.path,
+            DeprecatedTarget::QueryParam(name) => name,
+            DeprecatedTarget::ResponseField(_, name) => name,
+        }
+    }
+
+    /// Metric label for the kind of deprecation
+    pub fn kind(&self) -> &'static str {
+        match self.target {
+            DeprecatedTarget::Endpoint => "endpoint",
+            DeprecatedTarget::QueryParam(_) => "query_param",
+            DeprecatedTarget::ResponseField(..) => "response_field",
+        }
+    }
+
+    /// Whether a request relies on the deprecated endpoint or field
+    pub fn applies_to(&self, method: &Method, path: &str, query: Option<&str>) -> bool {
+        if self.method != method.as_str() || !path_matches(self.path, path) {
+            return false;
+        }
+        match self.target {
+            DeprecatedTarget::QueryParam(name) => query.is_some_and(|q| {
+                q.split('&')
+                    .any(|pair| pair.split('=').next() == Some(name))
+            }),
+            DeprecatedTarget::Endpoint | DeprecatedTarget::ResponseField(..) => true,
+        }
+    }
+
+    fn note(&self) -> String {
+        format!(
+            "Deprecated since {}, to be removed after {}. Use {} instead.",
+            self.since, self.sunset, self.replacement
+        )
+    }
+}
+
+/// Deprecated endpoints and fields. Entries stay here until their sunset
+/// date has passed and the metrics show no remaining consumers.
+pub const API_DEPRECATIONS: &[ApiDeprecation] = &[ApiDeprecation {
+    method: "GET",
+    path: "/api/v1/security/alerts",
+    target: DeprecatedTarget::QueryParam("unresolved_only"),
+    since: "2026-10-16",
+    sunset: "2027-04-16",
+    replacement: "`resolved=false`",
+}];
+
+/// Deprecations a request relies on
+pub fn matching<'a>(
+    deprecations: &'a [ApiDeprecation],
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+) -> Vec<&'a ApiDeprecation> {
+    deprecations
+        .iter()
+        .filter(|d| d.applies_to(method, path, query))
+        .collect()
+}
+
+/// Match a request path against a router path template with `{param}` segments
+fn path_matches(template: &str, path: &str) -> bool {
+    let mut template_segments = template.trim_end_matches('/').split('/');
+    let mut path_segments = path.trim_end_matches('/').split('/');
+    loop {
+        match (template_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some(t), Some(p)) if t.starts_with('{') && t.ends_with('}') && !p.is_empty() => {}
+            (Some(t), Some(p)) if t == p => {}
+            _ => return false,
+        }
+    }
+}
+
+fn parse_date(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// `Deprecation` header value (RFC 9745): `@<unix seconds>` of the earliest
+/// deprecation date
+pub fn deprecation_header(deprecations: &[&ApiDeprecation]) -> Option<HeaderValue> {
+    let since = deprecations
+        .iter()
+        .filter_map(|d| parse_date(d.since))
+        .min()?;
+    HeaderValue::from_str(&format!("@{}", since.timestamp())).ok()
+}
+
+/// `Sunset` header value (RFC 8594): HTTP-date of the earliest sunset
+pub fn sunset_header(deprecations: &[&ApiDeprecation]) -> Option<HeaderValue> {
+    let sunset = deprecations
+        .iter()
+        .filter_map(|d| parse_date(d.sunset))
+        .min()?;
+    HeaderValue::from_str(&sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string()).ok()
+}
+
+/// Headers for a response to a request that named a tenant by a slug retired
+/// in a rename: deprecated since the rename, sunset when the redirect expires,
+/// and a `Link` to the tenant under its current slug
+pub fn tenant_slug_redirect_headers(
+    redirect: &TenantSlugRedirect,
+    current_slug: &str,
+) -> HeaderMap {
+    counter!("auth9_tenant_slug_redirect_total").increment(1);
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&format!("@{}", redirect.created_at.timestamp())) {
+        headers.insert("Deprecation", value);
+    }
+    if let Ok(value) = HeaderValue::from_str(
+        &redirect
+            .expires_at
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string(),
+    ) {
+        headers.insert("Sunset", value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "</api/v1/tenants/by-slug/{}>; rel=\"successor-version\"",
+        current_slug
+    )) {
+        headers.insert(header::LINK, value);
+    }
+    headers
+}
+
+/// Flag the registered deprecations in the OpenAPI document and append
+/// their sunset notes to the operation descriptions
+pub fn annotate_openapi(doc: &mut OpenApi, deprecations: &[ApiDeprecation]) {
+    for deprecation in deprecations {
+        if let DeprecatedTarget::ResponseField(schema, field) = deprecation.target {
+            annotate_schema_property(doc, schema, field);
+        }
+        let Some(operation) = doc
+            .paths
+            .paths
+            .get_mut(deprecation.path)
+            .and_then(|item| operation_mut(item, deprecation.method))
+        else {
+            continue;
+        };
+        match deprecation.target {
+            DeprecatedTarget::Endpoint => operation.deprecated = Some(Deprecated::True),
+            DeprecatedTarget::QueryParam(name) => {
+                let parameters = operation.parameters.get_or_insert_with(Vec::new);
+                match parameters.iter_mut().find(|p| p.name == name) {
+                    Some(parameter) => parameter.deprecated = Some(Deprecated::True),
+                    None => parameters.push(
+                        ParameterBuilder::new()
+                            .name(name)
+                            .parameter_in(ParameterIn::Query)
+                            .deprecated(Some(Deprecated::True))
+                            .description(Some(deprecation.note()))
+                            .build(),
+                    ),
+                }
+            }
+            DeprecatedTarget::ResponseField(..) => {}
+        }
+        let note = match deprecation.target {
+            DeprecatedTarget::Endpoint => deprecation.note(),
+            _ => format!("`{}`: {}", deprecation.name(), deprecation.note()),
+        };
+        operation.description = Some(match operation.description.take() {
+            Some(description) if !description.is_empty() => {
+                format!("{}\n\n{}", description, note)
+            }
+            _ => note,
+        });
+    }
+}
+
+fn operation_mut<'a>(
+    item: &'a mut utoipa::openapi::PathItem,
+    method: &str,
+) -> Option<&'a mut Operation> {
+    match method {
+        "GET" => item.get.as_mut(),
+        "POST" => item.post.as_mut(),
+        "PUT" => item.put.as_mut(),
+        "PATCH" => item.patch.as_mut(),
+        "DELETE" => item.delete.as_mut(),
+        _ => None,
+    }
+}
+
+fn annotate_schema_property(doc: &mut OpenApi, schema: &str, field: &str) {
+    let Some(RefOr::T(Schema::Object(object))) = doc
+        .components
+        .as_mut()
+        .and_then(|c| c.schemas.get_mut(schema))
+    else {
+        return;
+    };
+    if let Some(RefOr::T(Schema::Object(property))) = object.properties.get_mut(field) {
+        property.deprecated = Some(Deprecated::True);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALERTS: ApiDeprecation = ApiDeprecation {
+        method: "GET",
+        path: "/api/v1/security/alerts",
+        target: DeprecatedTarget::QueryParam("unresolved_only"),
+        since: "2026-01-01",
+        sunset: "2026-07-01",
+        replacement: "`resolved=false`",
+    };
+
+    const OLD_ENDPOINT: ApiDeprecation = ApiDeprecation {
+        method: "GET",
+        path: "/api/v1/tenants/{id}/legacy",
+        target: DeprecatedTarget::Endpoint,
+        since: "2026-02-01",
+        sunset: "2026-06-01",
+        replacement: "`GET /api/v1/tenants/{id}`",
+    };
+
+    #[test]
+    fn test_registry_dates_are_valid() {
+        for deprecation in API_DEPRECATIONS {
+            let since = parse_date(deprecation.since).expect("valid since date");
+            let sunset = parse_date(deprecation.sunset).expect("valid sunset date");
+            assert!(
+                since < sunset,
+                "{} sunsets before it is deprecated",
+                deprecation.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_matching_by_path_template_and_query() {
+        let registry = [ALERTS, OLD_ENDPOINT];
+        let path = "/api/v1/tenants/550e8400-e29b-41d4-a716-446655440000/legacy";
+        assert_eq!(matching(&registry, &Method::GET, path, None).len(), 1);
+        assert!(matching(&registry, &Method::POST, path, None).is_empty());
+        assert!(matching(&registry, &Method::GET, "/api/v1/tenants/x", None).is_empty());
+
+        let alerts = "/api/v1/security/alerts";
+        assert!(matching(&registry, &Method::GET, alerts, None).is_empty());
+        assert!(matching(&registry, &Method::GET, alerts, Some("resolved=false")).is_empty());
+        assert_eq!(
+            matching(
+                &registry,
+                &Method::GET,
+                alerts,
+                Some("page=1&unresolved_only=true")
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_headers_use_earliest_dates() {
+        let matched = [&ALERTS, &OLD_ENDPOINT];
+        assert_eq!(deprecation_header(&matched).unwrap(), "@1767225600");
+        assert_eq!(
+            sunset_header(&matched).unwrap(),
+            "Mon, 01 Jun 2026 00:00:00 GMT"
+        );
+        assert!(deprecation_header(&[]).is_none());
+    }
+
+    #[test]
+    fn test_tenant_slug_redirect_headers() {
+        let redirect = TenantSlugRedirect {
+            old_slug: "acme".to_string(),
+            tenant_id: crate::models::common::StringUuid::new_v4(),
+            expires_at: parse_date("2026-07-01").unwrap(),
+            created_at: parse_date("2026-01-01").unwrap(),
+        };
+        let headers = tenant_slug_redirect_headers(&redirect, "acme-labs");
+        assert_eq!(headers["deprecation"], "@1767225600");
+        assert_eq!(headers["sunset"], "Wed, 01 Jul 2026 00:00:00 GMT");
+        assert_eq!(
+            headers[header::LINK],
+            "</api/v1/tenants/by-slug/acme-labs>; rel=\"successor-version\""
+        );
+    }
+
+    #[test]
+    fn test_annotate_openapi_flags_query_param() {
+        let mut doc = crate::openapi::ApiDoc::build();
+        let operation = doc
+            .paths
+            .paths
+            .get_mut("/api/v1/security/alerts")
+            .and_then(|item| operation_mut(item, "GET"))
+            .expect("alerts operation");
+        let parameter = operation
+            .parameters
+            .as_ref()
+            .and_then(|params| params.iter().find(|p| p.name == "unresolved_only"))
+            .expect("deprecated parameter documented");
+        assert!(matches!(parameter.deprecated, Some(Deprecated::True)));
+        assert!(operation
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .contains("2027-04-16"));
+    }
+}
@@ -0,0 +1,74 @@
+//! ETag / If-Match helpers for optimistic concurrency on versioned resources.
+//!
+//! Versioned aggregates expose their `version` as a strong ETag (`"3"`).
+//! Update handlers read `If-Match` and pass the expected version down to the
+//! repository, which applies the write only if the stored version still
+//! matches.
+
+use crate::error::{AppError, Result};
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    response::{IntoResponse, Response},
+};
+
+/// ETag header value for a resource version
+pub fn etag_value(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).expect("quoted integer is a valid header")
+}
+
+/// Attach the resource version as `ETag` to a response
+pub fn with_etag(version: i32, response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    response
+        .headers_mut()
+        .insert(header::ETAG, etag_value(version));
+    response
+}
+
+/// Expected version from the `If-Match` header.
+///
+/// Returns `None` when the header is absent or `*` (unconditional update).
+/// Weak validators (`W/"3"`) are accepted since versions are exact.
+pub fn if_match_version(headers: &HeaderMap) -> Result<Option<i32>> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+    let invalid = || AppError::BadRequest("Invalid If-Match header".to_string());
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+    let tag = value.strip_prefix("W/").unwrap_or(value);
+    tag.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .and_then(|t| t.parse::<i32>().ok())
+        .map(Some)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(if_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(if_match).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_if_match_version() {
+        assert_eq!(if_match_version(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(if_match_version(&headers("*")).unwrap(), None);
+        assert_eq!(if_match_version(&headers("\"7\"")).unwrap(), Some(7));
+        assert_eq!(if_match_version(&headers("W/\"7\"")).unwrap(), Some(7));
+        assert!(if_match_version(&headers("7")).is_err());
+        assert!(if_match_version(&headers("\"abc\"")).is_err());
+    }
+
+    #[test]
+    fn test_with_etag() {
+        let response = with_etag(5, "ok");
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), "\"5\"");
+    }
+}
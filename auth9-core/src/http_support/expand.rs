@@ -0,0 +1,16 @@
+//! Extractor for `expand[]` response expansions
+
+use crate::error::AppError;
+use crate::models::expand::ExpandQuery;
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+impl<S> FromRequestParts<S> for ExpandQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        ExpandQuery::from_query_string(parts.uri.query().unwrap_or_default())
+    }
+}
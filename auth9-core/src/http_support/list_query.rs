@@ -0,0 +1,27 @@
+//! Extractor for the standard list query grammar
+
+use crate::error::AppError;
+use crate::models::list_query::{ListGrammarUnsupported, ListQuery};
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+impl<S> FromRequestParts<S> for ListQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        ListQuery::from_query_string(parts.uri.query().unwrap_or_default())
+    }
+}
+
+impl<S> FromRequestParts<S> for ListGrammarUnsupported
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        ListGrammarUnsupported::from_query_string(parts.uri.query().unwrap_or_default())
+    }
+}
@@ -1,6 +1,11 @@
 //! Prometheus /metrics endpoint
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use crate::telemetry::exemplars::{render_openmetrics, OPENMETRICS_CONTENT_TYPE};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
 use metrics_exporter_prometheus::PrometheusHandle;
 use std::sync::Arc;
 
@@ -12,7 +17,9 @@ pub struct MetricsState {
     pub required_token: Option<String>,
 }
 
-/// GET /metrics — returns Prometheus text exposition format.
+/// GET /metrics — returns Prometheus text exposition format, or OpenMetrics
+/// with trace exemplars on latency histograms when the scraper asks for
+/// `application/openmetrics-text`.
 ///
 /// When `METRICS_TOKEN` is configured, requests must include a matching
 /// `Authorization: Bearer <token>` header. This prevents information
@@ -20,23 +27,38 @@ pub struct MetricsState {
 pub async fn metrics_handler(
     State(state): State<MetricsState>,
     headers: axum::http::HeaderMap,
-) -> impl IntoResponse {
+) -> Response {
     // Check bearer token if configured
     if let Some(ref expected) = state.required_token {
         let authorized = headers
-            .get(axum::http::header::AUTHORIZATION)
+            .get(header::AUTHORIZATION)
             .and_then(|v| v.to_str().ok())
             .and_then(|v| v.strip_prefix("Bearer "))
             .map(|token| token == expected.as_str())
             .unwrap_or(false);
 
         if !authorized {
-            return (StatusCode::NOT_FOUND, "Not Found".to_string());
+            return (StatusCode::NOT_FOUND, "Not Found".to_string()).into_response();
         }
     }
 
-    match state.handle.as_ref() {
-        Some(h) => (StatusCode::OK, h.render()),
-        None => (StatusCode::NOT_FOUND, "Metrics not enabled".to_string()),
+    let Some(handle) = state.handle.as_ref() else {
+        return (StatusCode::NOT_FOUND, "Metrics not enabled".to_string()).into_response();
+    };
+
+    // Exemplars are only defined in the OpenMetrics format
+    let wants_openmetrics = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("application/openmetrics-text"));
+    if wants_openmetrics {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+            render_openmetrics(&handle.render()),
+        )
+            .into_response()
+    } else {
+        (StatusCode::OK, handle.render()).into_response()
     }
 }
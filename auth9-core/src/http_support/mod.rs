@@ -1,5 +1,9 @@
 //! Shared HTTP support types and helpers.
 
+pub mod deprecation;
+pub mod etag;
+pub mod expand;
+pub mod list_query;
 pub mod metrics;
 
 use crate::error::{AppError, Result};
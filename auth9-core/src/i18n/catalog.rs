@@ -0,0 +1,197 @@
+//! Message catalog parser
+//!
+//! Supports the subset of the Fluent syntax used by the bundled catalogs:
+//!
+//! ```text
+//! # comment
+//! error-invalid-credentials = Invalid email or password.
+//! email-welcome-body =
+//!     Hi { $user_name },
+//!     your account is ready.
+//! ```
+//!
+//! Indented lines continue the previous message and are joined with `\n`.
+//! Placeables reference arguments with `{ $name }`.
+
+use std::collections::HashMap;
+
+/// Messages of a single locale
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Parse catalog source, reporting the first malformed line.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut current: Option<String> = None;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_no = index + 1;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                let key = current
+                    .as_ref()
+                    .ok_or_else(|| format!("line {}: continuation without a message", line_no))?;
+                let value = messages.get_mut(key).expect("current message exists");
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", line_no))?;
+            let key = key.trim();
+            if !is_valid_key(key) {
+                return Err(format!("line {}: invalid message key '{}'", line_no, key));
+            }
+            if messages
+                .insert(key.to_string(), value.trim().to_string())
+                .is_some()
+            {
+                return Err(format!("line {}: duplicate message key '{}'", line_no, key));
+            }
+            current = Some(key.to_string());
+        }
+
+        if let Some((key, _)) = messages.iter().find(|(_, v)| v.is_empty()) {
+            return Err(format!("message '{}' has no value", key));
+        }
+
+        Ok(Self { messages })
+    }
+
+    /// Raw pattern for a message key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+
+    /// All `(key, pattern)` pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.messages.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Substitute `{ $name }` placeables. Placeables without a matching
+/// argument are left untouched so missing arguments stay visible.
+pub fn format_pattern(pattern: &str, args: &[(&str, &str)]) -> String {
+    map_placeables(pattern, |name| {
+        args.iter()
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, value)| value.to_string())
+    })
+}
+
+/// Whether a pattern references any argument
+pub fn has_placeables(pattern: &str) -> bool {
+    let mut found = false;
+    map_placeables(pattern, |_| {
+        found = true;
+        None
+    });
+    found
+}
+
+/// Rewrite every `{ $name }` placeable with `replace(name)`; `None` keeps
+/// the original text.
+pub(crate) fn map_placeables(
+    pattern: &str,
+    mut replace: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let inner = after[..end].trim();
+        let original = &rest[start..start + end + 2];
+        match inner.strip_prefix('$').filter(|name| is_valid_key(name)) {
+            Some(name) => match replace(name) {
+                Some(value) => out.push_str(&value),
+                None => out.push_str(original),
+            },
+            None => out.push_str(original),
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_and_multiline_messages() {
+        let catalog = Catalog::parse(
+            "# comment\nhello = Hello { $name }!\n\nbody =\n    First line\n    Second line\n",
+        )
+        .unwrap();
+        assert_eq!(catalog.get("hello"), Some("Hello { $name }!"));
+        assert_eq!(catalog.get("body"), Some("First line\nSecond line"));
+        assert_eq!(catalog.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_catalogs() {
+        assert!(Catalog::parse("  orphan continuation").is_err());
+        assert!(Catalog::parse("no equals sign").is_err());
+        assert!(Catalog::parse("a = 1\na = 2").is_err());
+        assert!(Catalog::parse("1bad = x").is_err());
+        assert!(Catalog::parse("empty =").is_err());
+    }
+
+    #[test]
+    fn test_format_pattern() {
+        assert_eq!(
+            format_pattern(
+                "Hi { $name }, {$count} new",
+                &[("name", "Ann"), ("count", "3")]
+            ),
+            "Hi Ann, 3 new"
+        );
+        assert_eq!(format_pattern("Hi { $name }", &[]), "Hi { $name }");
+        assert_eq!(
+            format_pattern("braces { not a var }", &[]),
+            "braces { not a var }"
+        );
+        assert_eq!(
+            format_pattern("unclosed { $name", &[("name", "x")]),
+            "unclosed { $name"
+        );
+    }
+
+    #[test]
+    fn test_has_placeables() {
+        assert!(has_placeables("Hello { $name }"));
+        assert!(!has_placeables("Hello world"));
+    }
+}
@@ -0,0 +1,167 @@
+# Auth9 message catalog — Japanese
+
+## Generic errors
+error-invalid-request-body = リクエスト本文が無効です
+error-authentication-required = 認証が必要です
+error-access-denied = アクセスが拒否されました
+error-not-found = 見つかりません
+error-method-not-allowed = 許可されていないメソッドです
+error-resource-conflict = リソースが競合しています
+error-resource-exists = リソースは既に存在します
+error-version-conflict = このリソースは別のリクエストによって変更されました。再読み込みしてからもう一度お試しください。
+error-cross-region-access = 要求されたデータは別のリージョンに保存されているため、ここではアクセスできません。
+error-validation = 検証エラー
+error-too-many-requests = リクエストが多すぎます
+error-unsupported-content-type = サポートされていないコンテンツタイプです
+error-client-error = クライアントエラー
+error-internal = 内部エラーが発生しました
+error-database = データベースエラーが発生しました
+error-cache = キャッシュエラーが発生しました
+error-identity-backend = 認証サービスのエラーです
+
+## Authentication
+error-missing-authorization-header = Authorization ヘッダーがありません
+error-missing-authorization-token = 認可トークンがありません
+error-invalid-authorization-header = Authorization ヘッダーが無効です
+error-invalid-token = トークンが無効か、有効期限が切れています
+error-token-revoked = トークンは失効しています
+error-invalid-credentials = メールアドレスまたはパスワードが正しくありません。
+error-authentication-failed = 認証に失敗しました。
+error-account-locked = ログイン試行の失敗が多すぎるため、アカウントは一時的にロックされています。しばらくしてから再度お試しください。
+error-invalid-email = メールアドレスが無効です。
+error-password-required = パスワードを入力してください。
+error-invalid-login-challenge = ログインチャレンジが無効か、有効期限が切れています
+error-social-login-challenge-expired = ソーシャルログイン中にログインチャレンジの有効期限が切れました
+error-user-not-found = ユーザーが見つかりません
+error-user-not-registered = ユーザーが見つかりません。登録してください。
+error-session-not-found = セッションが見つかりません
+
+## MFA and one-time codes
+error-invalid-totp = TOTP コードが無効です。
+error-invalid-recovery-code = リカバリーコードが無効か、既に使用されています。
+error-daily-otp-limit = 1 日あたりのワンタイムコードの上限に達しました。
+error-passkey-not-found = パスキーが見つかりません。削除された可能性があります。
+
+## Account recovery and verification
+error-invalid-reset-token = リセットトークンが無効か、有効期限が切れています
+error-invalid-verification-token = 確認トークンが無効か、有効期限が切れています。
+error-invitation-expired = 招待の有効期限が切れています
+
+## OAuth token endpoint
+error-invalid-authorization-code = 認可コードが無効か、有効期限が切れています
+error-profile-required = 追加のプロフィール情報が必要です
+error-conditional-access-blocked = 条件付きアクセスポリシーによりアクセスがブロックされました
+error-conditional-access-mfa = テナントのポリシーにより多要素認証が必要です
+
+## Emails
+#
+# Each template is built from `-subject`, `-heading`, `-body` (one paragraph
+# per line) and optional `-action`, `-footer` and `-unsubscribe` messages.
+
+email-invitation-subject = { $tenant_name } への招待
+email-invitation-heading = 招待が届いています
+email-invitation-body =
+    こんにちは。
+    { $inviter_name } さんから { $tenant_name } への招待が届いています。
+    下のボタンをクリックして招待を承諾し、アカウントを作成してください。
+    この招待の有効期限は { $expires_in_hours } 時間です。
+email-invitation-action = 招待を承諾する
+email-invitation-footer = 心当たりのない場合は、このメールを無視してください。
+
+email-password-reset-subject = パスワードの再設定
+email-password-reset-heading = パスワードの再設定
+email-password-reset-body =
+    { $user_name } 様
+    アカウントのパスワード再設定のリクエストを受け付けました。下のボタンから新しいパスワードを設定してください。
+    このリンクの有効期限は { $expires_in_minutes } 分です。
+email-password-reset-action = パスワードを再設定する
+email-password-reset-footer = 再設定をリクエストしていない場合は、このメールを無視してください。パスワードは変更されません。
+
+email-email-mfa-subject = 確認コード: { $verification_code }
+email-email-mfa-heading = 確認コード
+email-email-mfa-body =
+    { $user_name } 様
+    確認コードは { $verification_code } です。
+    このコードの有効期限は { $expires_in_minutes } 分です。
+email-email-mfa-footer = このコードをリクエストしていない場合は、すぐにパスワードを変更してください。
+
+email-welcome-subject = { $tenant_name } へようこそ！
+email-welcome-heading = ようこそ！
+email-welcome-body =
+    { $user_name } 様
+    アカウントが作成され、{ $tenant_name } にアクセスできるようになりました。
+email-welcome-action = ログインする
+email-welcome-footer = ご不明な点がございましたら、管理者にお問い合わせください。
+
+email-email-verification-subject = メールアドレスの確認
+email-email-verification-heading = メールアドレスの確認
+email-email-verification-body =
+    { $user_name } 様
+    下のボタンをクリックしてメールアドレスを確認してください。
+    このリンクの有効期限は { $expires_in_hours } 時間です。
+email-email-verification-action = メールアドレスを確認する
+email-email-verification-footer = アカウントを作成していない場合は、このメールを無視してください。
+
+email-password-changed-subject = パスワードが変更されました
+email-password-changed-heading = パスワードの変更
+email-password-changed-body =
+    { $user_name } 様
+    パスワードは正常に変更されました。
+    日時: { $changed_at }
+    IP アドレス: { $ip_address }
+    この変更に心当たりがない場合、アカウントが不正に利用されている可能性があります。すぐにサポートに連絡し、パスワードを再設定してください。
+email-password-changed-footer = これはセキュリティに関する自動通知です。
+
+email-security-alert-subject = セキュリティ警告: { $event_type }
+email-security-alert-heading = セキュリティ警告
+email-security-alert-body =
+    { $user_name } 様
+    アカウントで通常とは異なるアクティビティが検出されました。
+    イベント: { $event_type }
+    デバイス: { $device_info }
+    場所: { $location }
+    日時: { $timestamp }
+    ご本人による操作の場合は、このメールを無視してください。
+    心当たりがない場合は、すぐにパスワードを変更し、アカウントのアクティビティを確認してください。
+email-security-alert-footer = これはセキュリティに関する自動通知です。
+email-security-alert-unsubscribe = このメールの配信を停止する
+
+email-notification-digest-subject = { $app_name } アカウントのアクティビティ（{ $period }）
+email-notification-digest-heading = アカウントのアクティビティ
+email-notification-digest-body =
+    { $user_name } 様
+    この{ $period }のアカウントのアクティビティの概要です。
+    ログイン成功: { $successful_logins }
+    ログイン失敗: { $failed_logins }
+    セキュリティ警告: { $security_alerts }
+    心当たりのないアクティビティがある場合は、パスワードを変更し、アクティブなセッションを確認してください。
+email-notification-digest-unsubscribe = アクティビティの概要の配信を停止する
+email-notification-digest-period-day = 日
+email-notification-digest-period-week = 週
+
+email-login-notification-subject = { $app_name } アカウントへの新しいログイン
+email-login-notification-heading = 新しいログイン
+email-login-notification-body =
+    { $user_name } 様
+    次の環境からアカウントにログインがありました。
+    デバイス: { $device_info }
+    場所: { $location }
+    IP アドレス: { $ip_address }
+    日時: { $timestamp }
+    ご本人による操作の場合は、このメールを無視してください。
+    心当たりがない場合は、下のボタンからこのセッションをログアウトしてお知らせのうえ、パスワードを変更してください。
+email-login-notification-action = 心当たりがない
+email-login-notification-unsubscribe = ログイン通知の配信を停止する
+
+email-security-alert-digest-subject = { $tenant_name }: この{ $period }のセキュリティアラート { $alert_count } 件
+email-security-alert-digest-heading = セキュリティアラートの概要
+email-security-alert-digest-body =
+    この{ $period }、{ $tenant_name } で重大度 { $min_severity } 以上のセキュリティアラートが { $alert_count } 件発生し、{ $affected_users } 人のユーザーが対象となりました。
+    { $alert_summary }
+    操作リンクの有効期限は 24 時間で、この概要の受信者である間のみ使用できます。
+email-security-alert-digest-footer = 管理者またはセキュリティ通知リストのメンバーとしてこの概要をお送りしています。
+email-security-alert-digest-label-tenant-wide = テナント全体
+email-security-alert-digest-label-profile = ユーザー情報
+email-security-alert-digest-label-force-reset = パスワードを強制リセット
+email-security-alert-digest-label-revoke-sessions = すべてのセッションからログアウト
+email-security-alert-digest-label-truncated = アラートが多すぎるため一部のみ表示しています。すべてのアラートはセキュリティアラートのページで確認してください。
@@ -0,0 +1,167 @@
+# Auth9 message catalog — Simplified Chinese
+
+## Generic errors
+error-invalid-request-body = 请求体无效
+error-authentication-required = 需要身份验证
+error-access-denied = 拒绝访问
+error-not-found = 未找到
+error-method-not-allowed = 不允许的请求方法
+error-resource-conflict = 资源冲突
+error-resource-exists = 资源已存在
+error-version-conflict = 该资源已被其他请求修改，请重新加载后再试。
+error-cross-region-access = 请求的数据存储在其他区域，无法在此访问。
+error-validation = 验证错误
+error-too-many-requests = 请求过于频繁
+error-unsupported-content-type = 不支持的内容类型
+error-client-error = 客户端错误
+error-internal = 发生内部错误
+error-database = 发生数据库错误
+error-cache = 发生缓存错误
+error-identity-backend = 认证服务错误
+
+## Authentication
+error-missing-authorization-header = 缺少 Authorization 请求头
+error-missing-authorization-token = 缺少授权令牌
+error-invalid-authorization-header = Authorization 请求头无效
+error-invalid-token = 令牌无效或已过期
+error-token-revoked = 令牌已被撤销
+error-invalid-credentials = 邮箱或密码错误。
+error-authentication-failed = 身份验证失败。
+error-account-locked = 由于登录失败次数过多，账户已被暂时锁定。请稍后再试。
+error-invalid-email = 邮箱地址无效。
+error-password-required = 请输入密码。
+error-invalid-login-challenge = 登录请求无效或已过期
+error-social-login-challenge-expired = 社交登录过程中登录请求已过期
+error-user-not-found = 用户不存在
+error-user-not-registered = 用户不存在，请先注册。
+error-session-not-found = 会话不存在
+
+## MFA and one-time codes
+error-invalid-totp = TOTP 验证码无效。
+error-invalid-recovery-code = 恢复码无效或已被使用。
+error-daily-otp-limit = 已达到每日验证码发送上限。
+error-passkey-not-found = 未找到通行密钥，它可能已被删除。
+
+## Account recovery and verification
+error-invalid-reset-token = 重置令牌无效或已过期
+error-invalid-verification-token = 验证令牌无效或已过期。
+error-invitation-expired = 邀请已过期
+
+## OAuth token endpoint
+error-invalid-authorization-code = 授权码无效或已过期
+error-profile-required = 需要补充个人资料信息
+error-conditional-access-blocked = 访问已被条件访问策略阻止
+error-conditional-access-mfa = 租户策略要求进行多因素认证
+
+## Emails
+#
+# Each template is built from `-subject`, `-heading`, `-body` (one paragraph
+# per line) and optional `-action`, `-footer` and `-unsubscribe` messages.
+
+email-invitation-subject = 您已受邀加入 { $tenant_name }
+email-invitation-heading = 您收到了一份邀请！
+email-invitation-body =
+    您好，
+    { $inviter_name } 邀请您加入 { $tenant_name }。
+    点击下方按钮接受邀请并创建您的账户。
+    此邀请将在 { $expires_in_hours } 小时后过期。
+email-invitation-action = 接受邀请
+email-invitation-footer = 如果您没有预期收到此邀请，请忽略此邮件。
+
+email-password-reset-subject = 重置您的密码
+email-password-reset-heading = 重置密码
+email-password-reset-body =
+    { $user_name }，您好：
+    我们收到了重置您账户密码的请求。点击下方按钮设置新密码。
+    此链接将在 { $expires_in_minutes } 分钟后过期。
+email-password-reset-action = 重置密码
+email-password-reset-footer = 如果您没有请求重置密码，请忽略此邮件，您的密码不会被更改。
+
+email-email-mfa-subject = 您的验证码：{ $verification_code }
+email-email-mfa-heading = 验证码
+email-email-mfa-body =
+    { $user_name }，您好：
+    您的验证码是：{ $verification_code }
+    此验证码将在 { $expires_in_minutes } 分钟后过期。
+email-email-mfa-footer = 如果您没有请求此验证码，请立即修改密码。
+
+email-welcome-subject = 欢迎加入 { $tenant_name }！
+email-welcome-heading = 欢迎！
+email-welcome-body =
+    { $user_name }，您好：
+    您的账户已创建，现在可以访问 { $tenant_name }。
+email-welcome-action = 立即登录
+email-welcome-footer = 如有任何问题，请联系您的管理员。
+
+email-email-verification-subject = 验证您的邮箱地址
+email-email-verification-heading = 验证邮箱
+email-email-verification-body =
+    { $user_name }，您好：
+    请点击下方按钮验证您的邮箱地址。
+    此链接将在 { $expires_in_hours } 小时后过期。
+email-email-verification-action = 验证邮箱
+email-email-verification-footer = 如果您没有创建账户，请忽略此邮件。
+
+email-password-changed-subject = 您的密码已更改
+email-password-changed-heading = 密码已更改
+email-password-changed-body =
+    { $user_name }，您好：
+    您的密码已成功更改。
+    时间：{ $changed_at }
+    IP 地址：{ $ip_address }
+    如果这不是您本人的操作，您的账户可能已被盗用，请立即联系支持人员并重置密码。
+email-password-changed-footer = 这是一封自动发送的安全通知。
+
+email-security-alert-subject = 安全警报：{ $event_type }
+email-security-alert-heading = 安全警报
+email-security-alert-body =
+    { $user_name }，您好：
+    我们检测到您的账户存在异常活动：
+    事件：{ $event_type }
+    设备：{ $device_info }
+    位置：{ $location }
+    时间：{ $timestamp }
+    如果这是您本人的操作，请忽略此邮件。
+    如果不是您本人的操作，建议您立即修改密码并检查账户活动。
+email-security-alert-footer = 这是一封自动发送的安全通知。
+email-security-alert-unsubscribe = 退订此类邮件
+
+email-notification-digest-subject = 您的 { $app_name } 账户活动摘要（{ $period }）
+email-notification-digest-heading = 账户活动
+email-notification-digest-body =
+    { $user_name }，您好：
+    以下是您本{ $period }的账户活动摘要：
+    成功登录：{ $successful_logins }
+    登录失败：{ $failed_logins }
+    安全警报：{ $security_alerts }
+    如果有您不认识的活动，建议您修改密码并检查当前的活动会话。
+email-notification-digest-unsubscribe = 不再接收活动摘要
+email-notification-digest-period-day = 日
+email-notification-digest-period-week = 周
+
+email-login-notification-subject = 您的 { $app_name } 账户有新的登录
+email-login-notification-heading = 新的登录
+email-login-notification-body =
+    { $user_name }，您好：
+    您的账户刚刚在以下设备上登录：
+    设备：{ $device_info }
+    位置：{ $location }
+    IP 地址：{ $ip_address }
+    时间：{ $timestamp }
+    如果这是您本人的操作，请忽略此邮件。
+    如果不是您本人的操作，请点击下方按钮退出该会话并告知我们，然后修改密码。
+email-login-notification-action = 这不是我
+email-login-notification-unsubscribe = 不再接收登录通知
+
+email-security-alert-digest-subject = { $tenant_name }：本{ $period }共 { $alert_count } 条安全警报
+email-security-alert-digest-heading = 安全警报摘要
+email-security-alert-digest-body =
+    本{ $period }{ $tenant_name } 共产生 { $alert_count } 条 { $min_severity } 及以上级别的安全警报，涉及 { $affected_users } 位用户。
+    { $alert_summary }
+    操作链接 24 小时内有效，且仅在您仍接收此摘要时可用。
+email-security-alert-digest-footer = 您以管理员或安全通知列表成员的身份收到此摘要。
+email-security-alert-digest-label-tenant-wide = 租户级别
+email-security-alert-digest-label-profile = 用户资料
+email-security-alert-digest-label-force-reset = 强制重置密码
+email-security-alert-digest-label-revoke-sessions = 退出所有会话
+email-security-alert-digest-label-truncated = 警报数量超出本邮件可显示的上限，请在安全警报页面查看全部警报。
@@ -0,0 +1,294 @@
+//! Internationalization for emails and user-facing error messages
+//!
+//! Locales are negotiated from the user's stored preference, the request's
+//! `Accept-Language` header and the tenant default, falling back to
+//! [`DEFAULT_LOCALE`]. Translations live in Fluent-style catalogs compiled
+//! into the binary (`src/i18n/locales/*.ftl`); a missing message falls back
+//! to the default locale and then to the message key.
+
+pub mod catalog;
+
+pub use catalog::{format_pattern, Catalog};
+
+use axum::http::{header, HeaderMap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use utoipa::ToSchema;
+
+/// Supported locales (mirrors the portal's locale set)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "ja")]
+    Ja,
+}
+
+/// Locale used when nothing more specific is known
+pub const DEFAULT_LOCALE: Locale = Locale::EnUs;
+
+/// Prefix of catalog keys that translate error messages
+const ERROR_KEY_PREFIX: &str = "error-";
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::EnUs, Locale::ZhCn, Locale::Ja];
+
+    /// Canonical BCP 47 tag
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EnUs => "en-US",
+            Self::ZhCn => "zh-CN",
+            Self::Ja => "ja",
+        }
+    }
+
+    /// Map a language tag onto a supported locale by its primary language
+    /// (`en-GB` → `en-US`, `zh-Hans` → `zh-CN`).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::EnUs),
+            "zh" => Some(Self::ZhCn),
+            "ja" => Some(Self::Ja),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_tag(s).ok_or_else(|| format!("Unsupported locale: {}", s))
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Pick the best supported locale from an `Accept-Language` value,
+/// honoring quality weights (`q=0` excludes a language).
+pub fn negotiate(accept_language: &str) -> Option<Locale> {
+    let mut ranges: Vec<(f32, &str)> = accept_language
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            (!tag.is_empty() && quality > 0.0).then_some((quality, tag))
+        })
+        .collect();
+    // Stable sort keeps header order for equal weights
+    ranges.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    ranges
+        .into_iter()
+        .find_map(|(_, tag)| Locale::from_tag(tag))
+}
+
+/// Locale requested by the client's `Accept-Language` header
+pub fn negotiate_headers(headers: &HeaderMap) -> Option<Locale> {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(negotiate)
+}
+
+/// Resolve a locale through the fallback chain: user preference, request
+/// `Accept-Language`, tenant default, then [`DEFAULT_LOCALE`].
+pub fn resolve_locale(
+    user_preference: Option<&str>,
+    accept_language: Option<&str>,
+    tenant_default: Option<&str>,
+) -> Locale {
+    user_preference
+        .and_then(Locale::from_tag)
+        .or_else(|| accept_language.and_then(negotiate))
+        .or_else(|| tenant_default.and_then(Locale::from_tag))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+fn catalogs() -> &'static HashMap<Locale, Catalog> {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        [
+            (Locale::EnUs, include_str!("locales/en-US.ftl")),
+            (Locale::ZhCn, include_str!("locales/zh-CN.ftl")),
+            (Locale::Ja, include_str!("locales/ja.ftl")),
+        ]
+        .into_iter()
+        .map(|(locale, source)| {
+            let catalog = Catalog::parse(source)
+                .unwrap_or_else(|e| panic!("invalid {} message catalog: {}", locale, e));
+            (locale, catalog)
+        })
+        .collect()
+    })
+}
+
+/// Raw pattern of a message in exactly `locale` (no fallback)
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    catalogs().get(&locale).and_then(|c| c.get(key))
+}
+
+/// Translate a message, falling back to the default locale and then to the key.
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    match lookup(locale, key).or_else(|| lookup(DEFAULT_LOCALE, key)) {
+        Some(pattern) => format_pattern(pattern, args),
+        None => key.to_string(),
+    }
+}
+
+/// English error messages of the default catalog, mapped to their keys
+fn error_keys() -> &'static HashMap<&'static str, &'static str> {
+    static KEYS: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        catalogs()
+            .get(&DEFAULT_LOCALE)
+            .map(|catalog| {
+                catalog
+                    .iter()
+                    .filter(|(key, pattern)| {
+                        key.starts_with(ERROR_KEY_PREFIX) && !catalog::has_placeables(pattern)
+                    })
+                    .map(|(key, pattern)| (pattern, key))
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// Translate an (English) error message produced by the API into `locale`.
+///
+/// Only messages listed in the default catalog are translated; anything else
+/// (including messages with dynamic content) returns `None` and is sent as-is.
+pub fn translate_error_message(locale: Locale, message: &str) -> Option<String> {
+    if locale == DEFAULT_LOCALE {
+        return None;
+    }
+    let key = error_keys().get(message)?;
+    lookup(locale, key).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("en"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("en-GB"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("zh_TW"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("JA-jp"), Some(Locale::Ja));
+        assert_eq!(Locale::from_tag("fr"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_locale_serde_uses_tags() {
+        assert_eq!(serde_json::to_string(&Locale::ZhCn).unwrap(), "\"zh-CN\"");
+        assert_eq!(
+            serde_json::from_str::<Locale>("\"ja\"").unwrap(),
+            Locale::Ja
+        );
+    }
+
+    #[test]
+    fn test_negotiate_honors_quality() {
+        assert_eq!(negotiate("ja,en;q=0.8"), Some(Locale::Ja));
+        assert_eq!(
+            negotiate("fr-FR, en;q=0.5, zh-CN;q=0.9"),
+            Some(Locale::ZhCn)
+        );
+        assert_eq!(negotiate("zh;q=0, en"), Some(Locale::EnUs));
+        assert_eq!(negotiate("fr, de"), None);
+        assert_eq!(negotiate(""), None);
+        assert_eq!(negotiate("*"), None);
+    }
+
+    #[test]
+    fn test_resolve_locale_fallback_chain() {
+        assert_eq!(
+            resolve_locale(Some("ja"), Some("zh-CN"), Some("en")),
+            Locale::Ja
+        );
+        assert_eq!(
+            resolve_locale(Some("xx"), Some("zh-CN"), Some("ja")),
+            Locale::ZhCn
+        );
+        assert_eq!(resolve_locale(None, Some("fr"), Some("ja")), Locale::Ja);
+        assert_eq!(resolve_locale(None, None, None), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn test_catalogs_parse_and_cover_default_keys() {
+        let default = &catalogs()[&DEFAULT_LOCALE];
+        assert!(!default.is_empty());
+        for locale in Locale::ALL {
+            let catalog = &catalogs()[&locale];
+            for (key, _) in default.iter() {
+                assert!(
+                    catalog.get(key).is_some(),
+                    "{} catalog is missing '{}'",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_falls_back_to_default_then_key() {
+        assert_eq!(
+            translate(Locale::Ja, "error-not-found", &[]),
+            lookup(Locale::Ja, "error-not-found").unwrap()
+        );
+        assert_eq!(
+            translate(Locale::Ja, "no-such-message", &[]),
+            "no-such-message"
+        );
+    }
+
+    #[test]
+    fn test_translate_error_message() {
+        assert_eq!(
+            translate_error_message(Locale::Ja, "Invalid email or password."),
+            lookup(Locale::Ja, "error-invalid-credentials").map(str::to_string)
+        );
+        assert_eq!(
+            translate_error_message(Locale::EnUs, "Invalid email or password."),
+            None
+        );
+        assert_eq!(
+            translate_error_message(Locale::ZhCn, "Tenant 42 not found"),
+            None
+        );
+    }
+}
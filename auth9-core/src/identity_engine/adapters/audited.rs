@@ -0,0 +1,765 @@
+//! Audit-mirroring decorator for identity backends.
+//!
+//! Wraps any [`IdentityEngine`] and records every mutating admin operation
+//! (user, client, credential, session, federation and realm changes) in the
+//! audit log as an `identity_engine.<operation>` entry carrying a request
+//! summary and the outcome, so backend-side changes can be correlated with
+//! the Auth9 action that caused them. Reads pass through unrecorded.
+//!
+//! Summaries never include passwords, client secrets or provider config.
+
+use crate::error::Result;
+use crate::identity_engine::{
+    FederationBroker, IdentityActionStore, IdentityClientStore, IdentityCredentialRepresentation,
+    IdentityCredentialStore, IdentityEngine, IdentityEventSource, IdentityProviderRepresentation,
+    IdentitySamlClientRepresentation, IdentitySessionStore, IdentityUserCreateInput,
+    IdentityUserRepresentation, IdentityUserStore, IdentityUserUpdateInput,
+    IdentityVerificationStore, OidcClientRepresentation, RealmSettingsUpdate,
+};
+use crate::repository::audit::CreateAuditLogInput;
+use crate::repository::AuditRepository;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Audit action prefix for mirrored identity backend operations
+pub const IDENTITY_ENGINE_AUDIT_PREFIX: &str = "identity_engine";
+
+#[derive(Clone)]
+struct AuditMirror {
+    repo: Arc<dyn AuditRepository>,
+}
+
+impl AuditMirror {
+    async fn record<T>(
+        &self,
+        operation: &str,
+        resource_type: &str,
+        target: Option<&str>,
+        request: Value,
+        result: &Result<T>,
+    ) {
+        let entry = mirror_entry(operation, resource_type, target, request, result);
+        if let Err(e) = self.repo.create(&entry).await {
+            tracing::warn!(
+                operation,
+                error = %e,
+                "Failed to mirror identity engine operation to audit log"
+            );
+        }
+    }
+}
+
+fn mirror_entry<T>(
+    operation: &str,
+    resource_type: &str,
+    target: Option<&str>,
+    request: Value,
+    result: &Result<T>,
+) -> CreateAuditLogInput {
+    let mut payload = json!({
+        "operation": operation,
+        "target": target,
+        "request": request,
+        "outcome": if result.is_ok() { "success" } else { "failure" },
+    });
+    if let Err(e) = result {
+        payload["error"] = Value::String(e.to_string());
+    }
+    CreateAuditLogInput {
+        actor_id: None,
+        action: format!("{}.{}", IDENTITY_ENGINE_AUDIT_PREFIX, operation),
+        resource_type: resource_type.to_string(),
+        resource_id: target.and_then(|t| uuid::Uuid::parse_str(t).ok()),
+        old_value: None,
+        new_value: Some(payload),
+        ip_address: None,
+    }
+}
+
+fn oidc_client_summary(client: &OidcClientRepresentation) -> Value {
+    json!({
+        "client_id": client.client_id,
+        "enabled": client.enabled,
+        "public_client": client.public_client,
+        "redirect_uris": client.redirect_uris,
+    })
+}
+
+fn saml_client_summary(client: &IdentitySamlClientRepresentation) -> Value {
+    json!({ "client_id": client.client_id, "enabled": client.enabled })
+}
+
+fn provider_summary(provider: &IdentityProviderRepresentation) -> Value {
+    json!({
+        "alias": provider.alias,
+        "provider_id": provider.provider_id,
+        "enabled": provider.enabled,
+    })
+}
+
+/// [`IdentityEngine`] decorator that mirrors admin operations into the audit log
+pub struct AuditedIdentityEngine {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+    user_store: AuditedUserStore,
+    client_store: AuditedClientStore,
+    session_store: AuditedSessionStore,
+    credential_store: AuditedCredentialStore,
+    federation_broker: AuditedFederationBroker,
+}
+
+impl AuditedIdentityEngine {
+    pub fn new(inner: Arc<dyn IdentityEngine>, audit_repo: Arc<dyn AuditRepository>) -> Self {
+        let audit = AuditMirror { repo: audit_repo };
+        Self {
+            user_store: AuditedUserStore {
+                inner: inner.clone(),
+                audit: audit.clone(),
+            },
+            client_store: AuditedClientStore {
+                inner: inner.clone(),
+                audit: audit.clone(),
+            },
+            session_store: AuditedSessionStore {
+                inner: inner.clone(),
+                audit: audit.clone(),
+            },
+            credential_store: AuditedCredentialStore {
+                inner: inner.clone(),
+                audit: audit.clone(),
+            },
+            federation_broker: AuditedFederationBroker {
+                inner: inner.clone(),
+                audit: audit.clone(),
+            },
+            inner,
+            audit,
+        }
+    }
+}
+
+#[async_trait]
+impl IdentityEngine for AuditedIdentityEngine {
+    fn user_store(&self) -> &dyn IdentityUserStore {
+        &self.user_store
+    }
+
+    fn client_store(&self) -> &dyn IdentityClientStore {
+        &self.client_store
+    }
+
+    fn session_store(&self) -> &dyn IdentitySessionStore {
+        &self.session_store
+    }
+
+    fn credential_store(&self) -> &dyn IdentityCredentialStore {
+        &self.credential_store
+    }
+
+    fn federation_broker(&self) -> &dyn FederationBroker {
+        &self.federation_broker
+    }
+
+    fn event_source(&self) -> &dyn IdentityEventSource {
+        self.inner.event_source()
+    }
+
+    fn action_store(&self) -> &dyn IdentityActionStore {
+        self.inner.action_store()
+    }
+
+    fn verification_store(&self) -> &dyn IdentityVerificationStore {
+        self.inner.verification_store()
+    }
+
+    async fn update_realm(&self, settings: &RealmSettingsUpdate) -> Result<()> {
+        let result = self.inner.update_realm(settings).await;
+        let request = json!({
+            "registration_allowed": settings.registration_allowed,
+            "reset_password_allowed": settings.reset_password_allowed,
+            "password_policy": settings.password_policy,
+            "brute_force_protected": settings.brute_force_protected,
+            "smtp_server_changed": settings.smtp_server.is_some(),
+        });
+        self.audit
+            .record("update_realm", "identity_realm", None, request, &result)
+            .await;
+        result
+    }
+}
+
+struct AuditedUserStore {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+}
+
+#[async_trait]
+impl IdentityUserStore for AuditedUserStore {
+    async fn create_user(&self, input: &IdentityUserCreateInput) -> Result<String> {
+        let result = self.inner.user_store().create_user(input).await;
+        let request = json!({
+            "username": input.username,
+            "email": input.email,
+            "enabled": input.enabled,
+            "with_credentials": input.credentials.as_ref().is_some_and(|c| !c.is_empty()),
+        });
+        let target = result.as_ref().ok().map(String::as_str);
+        self.audit
+            .record("create_user", "identity_user", target, request, &result)
+            .await;
+        result
+    }
+
+    async fn get_user(&self, user_id: &str) -> Result<IdentityUserRepresentation> {
+        self.inner.user_store().get_user(user_id).await
+    }
+
+    async fn update_user(&self, user_id: &str, input: &IdentityUserUpdateInput) -> Result<()> {
+        let result = self.inner.user_store().update_user(user_id, input).await;
+        let request = json!({
+            "username": input.username,
+            "email": input.email,
+            "enabled": input.enabled,
+            "email_verified": input.email_verified,
+            "required_actions": input.required_actions,
+        });
+        self.audit
+            .record(
+                "update_user",
+                "identity_user",
+                Some(user_id),
+                request,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<()> {
+        let result = self.inner.user_store().delete_user(user_id).await;
+        self.audit
+            .record(
+                "delete_user",
+                "identity_user",
+                Some(user_id),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn set_user_password(
+        &self,
+        user_id: &str,
+        password: &str,
+        temporary: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .user_store()
+            .set_user_password(user_id, password, temporary)
+            .await;
+        self.audit
+            .record(
+                "set_user_password",
+                "identity_user",
+                Some(user_id),
+                json!({ "temporary": temporary }),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn admin_set_user_password(
+        &self,
+        user_id: &str,
+        password: &str,
+        temporary: bool,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .user_store()
+            .admin_set_user_password(user_id, password, temporary)
+            .await;
+        self.audit
+            .record(
+                "admin_set_user_password",
+                "identity_user",
+                Some(user_id),
+                json!({ "temporary": temporary }),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn validate_user_password(&self, user_id: &str, password: &str) -> Result<bool> {
+        self.inner
+            .user_store()
+            .validate_user_password(user_id, password)
+            .await
+    }
+
+    async fn get_user_password_hash(&self, user_id: &str) -> Result<Option<String>> {
+        self.inner
+            .user_store()
+            .get_user_password_hash(user_id)
+            .await
+    }
+}
+
+struct AuditedClientStore {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+}
+
+#[async_trait]
+impl IdentityClientStore for AuditedClientStore {
+    async fn create_oidc_client(&self, client: &OidcClientRepresentation) -> Result<String> {
+        let result = self.inner.client_store().create_oidc_client(client).await;
+        let target = result.as_ref().ok().map(String::as_str);
+        self.audit
+            .record(
+                "create_oidc_client",
+                "identity_client",
+                target,
+                oidc_client_summary(client),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn get_client_secret(&self, client_uuid: &str) -> Result<String> {
+        self.inner
+            .client_store()
+            .get_client_secret(client_uuid)
+            .await
+    }
+
+    async fn regenerate_client_secret(&self, client_uuid: &str) -> Result<String> {
+        let result = self
+            .inner
+            .client_store()
+            .regenerate_client_secret(client_uuid)
+            .await;
+        self.audit
+            .record(
+                "regenerate_client_secret",
+                "identity_client",
+                Some(client_uuid),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn get_client_uuid_by_client_id(&self, client_id: &str) -> Result<String> {
+        self.inner
+            .client_store()
+            .get_client_uuid_by_client_id(client_id)
+            .await
+    }
+
+    async fn get_client_by_client_id(&self, client_id: &str) -> Result<OidcClientRepresentation> {
+        self.inner
+            .client_store()
+            .get_client_by_client_id(client_id)
+            .await
+    }
+
+    async fn update_oidc_client(
+        &self,
+        client_uuid: &str,
+        client: &OidcClientRepresentation,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .client_store()
+            .update_oidc_client(client_uuid, client)
+            .await;
+        self.audit
+            .record(
+                "update_oidc_client",
+                "identity_client",
+                Some(client_uuid),
+                oidc_client_summary(client),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn delete_oidc_client(&self, client_uuid: &str) -> Result<()> {
+        let result = self
+            .inner
+            .client_store()
+            .delete_oidc_client(client_uuid)
+            .await;
+        self.audit
+            .record(
+                "delete_oidc_client",
+                "identity_client",
+                Some(client_uuid),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn create_saml_client(
+        &self,
+        client: &IdentitySamlClientRepresentation,
+    ) -> Result<String> {
+        let result = self.inner.client_store().create_saml_client(client).await;
+        let target = result.as_ref().ok().map(String::as_str);
+        self.audit
+            .record(
+                "create_saml_client",
+                "identity_client",
+                target,
+                saml_client_summary(client),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn update_saml_client(
+        &self,
+        client_uuid: &str,
+        client: &IdentitySamlClientRepresentation,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .client_store()
+            .update_saml_client(client_uuid, client)
+            .await;
+        self.audit
+            .record(
+                "update_saml_client",
+                "identity_client",
+                Some(client_uuid),
+                saml_client_summary(client),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn delete_saml_client(&self, client_uuid: &str) -> Result<()> {
+        let result = self
+            .inner
+            .client_store()
+            .delete_saml_client(client_uuid)
+            .await;
+        self.audit
+            .record(
+                "delete_saml_client",
+                "identity_client",
+                Some(client_uuid),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn get_saml_idp_descriptor(&self) -> Result<String> {
+        self.inner.client_store().get_saml_idp_descriptor().await
+    }
+
+    async fn get_active_signing_certificate(&self) -> Result<String> {
+        self.inner
+            .client_store()
+            .get_active_signing_certificate()
+            .await
+    }
+
+    fn saml_sso_url(&self) -> String {
+        self.inner.client_store().saml_sso_url()
+    }
+}
+
+struct AuditedSessionStore {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+}
+
+#[async_trait]
+impl IdentitySessionStore for AuditedSessionStore {
+    async fn delete_user_session(&self, session_id: &str) -> Result<()> {
+        let result = self
+            .inner
+            .session_store()
+            .delete_user_session(session_id)
+            .await;
+        self.audit
+            .record(
+                "delete_user_session",
+                "identity_session",
+                Some(session_id),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn logout_user(&self, user_id: &str) -> Result<()> {
+        let result = self.inner.session_store().logout_user(user_id).await;
+        self.audit
+            .record(
+                "logout_user",
+                "identity_user",
+                Some(user_id),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+}
+
+struct AuditedCredentialStore {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+}
+
+#[async_trait]
+impl IdentityCredentialStore for AuditedCredentialStore {
+    async fn list_user_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<IdentityCredentialRepresentation>> {
+        self.inner
+            .credential_store()
+            .list_user_credentials(user_id)
+            .await
+    }
+
+    async fn remove_totp_credentials(&self, user_id: &str) -> Result<()> {
+        let result = self
+            .inner
+            .credential_store()
+            .remove_totp_credentials(user_id)
+            .await;
+        self.audit
+            .record(
+                "remove_totp_credentials",
+                "identity_user",
+                Some(user_id),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn list_webauthn_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<IdentityCredentialRepresentation>> {
+        self.inner
+            .credential_store()
+            .list_webauthn_credentials(user_id)
+            .await
+    }
+
+    async fn delete_user_credential(&self, user_id: &str, credential_id: &str) -> Result<()> {
+        let result = self
+            .inner
+            .credential_store()
+            .delete_user_credential(user_id, credential_id)
+            .await;
+        self.audit
+            .record(
+                "delete_user_credential",
+                "identity_user",
+                Some(user_id),
+                json!({ "credential_id": credential_id }),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn is_password_temporary(&self, user_id: &str) -> Result<bool> {
+        self.inner
+            .credential_store()
+            .is_password_temporary(user_id)
+            .await
+    }
+}
+
+struct AuditedFederationBroker {
+    inner: Arc<dyn IdentityEngine>,
+    audit: AuditMirror,
+}
+
+#[async_trait]
+impl FederationBroker for AuditedFederationBroker {
+    async fn list_identity_providers(&self) -> Result<Vec<IdentityProviderRepresentation>> {
+        self.inner
+            .federation_broker()
+            .list_identity_providers()
+            .await
+    }
+
+    async fn get_identity_provider(&self, alias: &str) -> Result<IdentityProviderRepresentation> {
+        self.inner
+            .federation_broker()
+            .get_identity_provider(alias)
+            .await
+    }
+
+    async fn create_identity_provider(
+        &self,
+        provider: &IdentityProviderRepresentation,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .federation_broker()
+            .create_identity_provider(provider)
+            .await;
+        self.audit
+            .record(
+                "create_identity_provider",
+                "identity_provider",
+                Some(&provider.alias),
+                provider_summary(provider),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn update_identity_provider(
+        &self,
+        alias: &str,
+        provider: &IdentityProviderRepresentation,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .federation_broker()
+            .update_identity_provider(alias, provider)
+            .await;
+        self.audit
+            .record(
+                "update_identity_provider",
+                "identity_provider",
+                Some(alias),
+                provider_summary(provider),
+                &result,
+            )
+            .await;
+        result
+    }
+
+    async fn delete_identity_provider(&self, alias: &str) -> Result<()> {
+        let result = self
+            .inner
+            .federation_broker()
+            .delete_identity_provider(alias)
+            .await;
+        self.audit
+            .record(
+                "delete_identity_provider",
+                "identity_provider",
+                Some(alias),
+                Value::Null,
+                &result,
+            )
+            .await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+
+    #[test]
+    fn test_mirror_entry_success() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let result: Result<()> = Ok(());
+        let entry = mirror_entry(
+            "delete_user",
+            "identity_user",
+            Some(&user_id),
+            Value::Null,
+            &result,
+        );
+
+        assert_eq!(entry.action, "identity_engine.delete_user");
+        assert_eq!(entry.resource_type, "identity_user");
+        assert_eq!(entry.resource_id.map(|id| id.to_string()), Some(user_id));
+        let payload = entry.new_value.unwrap();
+        assert_eq!(payload["outcome"], "success");
+        assert!(payload.get("error").is_none());
+    }
+
+    #[test]
+    fn test_mirror_entry_failure_keeps_non_uuid_target() {
+        let result: Result<()> = Err(AppError::NotFound("provider missing".to_string()));
+        let entry = mirror_entry(
+            "delete_identity_provider",
+            "identity_provider",
+            Some("google"),
+            Value::Null,
+            &result,
+        );
+
+        assert!(entry.resource_id.is_none());
+        let payload = entry.new_value.unwrap();
+        assert_eq!(payload["outcome"], "failure");
+        assert_eq!(payload["target"], "google");
+        assert!(payload["error"]
+            .as_str()
+            .unwrap()
+            .contains("provider missing"));
+    }
+
+    #[test]
+    fn test_oidc_client_summary_omits_secret() {
+        let client = OidcClientRepresentation {
+            id: None,
+            client_id: "portal".to_string(),
+            name: None,
+            enabled: true,
+            public_client: false,
+            redirect_uris: vec!["https://app.example.com/cb".to_string()],
+            web_origins: vec![],
+            secret: Some("super-secret".to_string()),
+            protocol: None,
+            base_url: None,
+            root_url: None,
+            admin_url: None,
+            attributes: None,
+        };
+        let summary = oidc_client_summary(&client).to_string();
+        assert!(summary.contains("portal"));
+        assert!(!summary.contains("super-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_record_swallows_audit_failures() {
+        let mut repo = crate::repository::audit::MockAuditRepository::new();
+        repo.expect_create()
+            .times(1)
+            .returning(|_| Err(AppError::Internal(anyhow::anyhow!("db down"))));
+        let mirror = AuditMirror {
+            repo: Arc::new(repo),
+        };
+        let result: Result<()> = Ok(());
+        mirror
+            .record("logout_user", "identity_user", None, Value::Null, &result)
+            .await;
+    }
+}
@@ -0,0 +1,332 @@
+//! Fault-injecting decorator for identity backends (`fault-injection` feature).
+//!
+//! Wraps any [`IdentityEngine`] and applies the active identity engine fault
+//! (see [`crate::fault_injection`]) before each user, credential, session,
+//! pending action and verification call, i.e. the calls made while signing
+//! users in. Client, federation and realm administration pass through
+//! untouched.
+
+use crate::error::Result;
+use crate::fault_injection::{inject, FaultTarget};
+use crate::identity_engine::{
+    FederationBroker, IdentityActionStore, IdentityClientStore, IdentityCredentialRepresentation,
+    IdentityCredentialStore, IdentityEngine, IdentityEventSource, IdentitySessionStore,
+    IdentityUserCreateInput, IdentityUserRepresentation, IdentityUserStore,
+    IdentityUserUpdateInput, IdentityVerificationStore, PendingActionInfo, RealmSettingsUpdate,
+    VerificationTokenInfo,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// [`IdentityEngine`] decorator that injects configured faults
+pub struct FaultInjectingIdentityEngine {
+    inner: Arc<dyn IdentityEngine>,
+    user_store: FaultInjectingUserStore,
+    session_store: FaultInjectingSessionStore,
+    credential_store: FaultInjectingCredentialStore,
+    action_store: FaultInjectingActionStore,
+    verification_store: FaultInjectingVerificationStore,
+}
+
+impl FaultInjectingIdentityEngine {
+    pub fn new(inner: Arc<dyn IdentityEngine>) -> Self {
+        Self {
+            user_store: FaultInjectingUserStore {
+                inner: inner.clone(),
+            },
+            session_store: FaultInjectingSessionStore {
+                inner: inner.clone(),
+            },
+            credential_store: FaultInjectingCredentialStore {
+                inner: inner.clone(),
+            },
+            action_store: FaultInjectingActionStore {
+                inner: inner.clone(),
+            },
+            verification_store: FaultInjectingVerificationStore {
+                inner: inner.clone(),
+            },
+            inner,
+        }
+    }
+}
+
+async fn fault() -> Result<()> {
+    Ok(inject(FaultTarget::IdentityEngine).await?)
+}
+
+#[async_trait]
+impl IdentityEngine for FaultInjectingIdentityEngine {
+    fn user_store(&self) -> &dyn IdentityUserStore {
+        &self.user_store
+    }
+
+    fn client_store(&self) -> &dyn IdentityClientStore {
+        self.inner.client_store()
+    }
+
+    fn session_store(&self) -> &dyn IdentitySessionStore {
+        &self.session_store
+    }
+
+    fn credential_store(&self) -> &dyn IdentityCredentialStore {
+        &self.credential_store
+    }
+
+    fn federation_broker(&self) -> &dyn FederationBroker {
+        self.inner.federation_broker()
+    }
+
+    fn event_source(&self) -> &dyn IdentityEventSource {
+        self.inner.event_source()
+    }
+
+    fn action_store(&self) -> &dyn IdentityActionStore {
+        &self.action_store
+    }
+
+    fn verification_store(&self) -> &dyn IdentityVerificationStore {
+        &self.verification_store
+    }
+
+    async fn update_realm(&self, settings: &RealmSettingsUpdate) -> Result<()> {
+        self.inner.update_realm(settings).await
+    }
+}
+
+struct FaultInjectingUserStore {
+    inner: Arc<dyn IdentityEngine>,
+}
+
+#[async_trait]
+impl IdentityUserStore for FaultInjectingUserStore {
+    async fn create_user(&self, input: &IdentityUserCreateInput) -> Result<String> {
+        fault().await?;
+        self.inner.user_store().create_user(input).await
+    }
+
+    async fn get_user(&self, user_id: &str) -> Result<IdentityUserRepresentation> {
+        fault().await?;
+        self.inner.user_store().get_user(user_id).await
+    }
+
+    async fn update_user(&self, user_id: &str, input: &IdentityUserUpdateInput) -> Result<()> {
+        fault().await?;
+        self.inner.user_store().update_user(user_id, input).await
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner.user_store().delete_user(user_id).await
+    }
+
+    async fn set_user_password(
+        &self,
+        user_id: &str,
+        password: &str,
+        temporary: bool,
+    ) -> Result<()> {
+        fault().await?;
+        self.inner
+            .user_store()
+            .set_user_password(user_id, password, temporary)
+            .await
+    }
+
+    async fn admin_set_user_password(
+        &self,
+        user_id: &str,
+        password: &str,
+        temporary: bool,
+    ) -> Result<()> {
+        fault().await?;
+        self.inner
+            .user_store()
+            .admin_set_user_password(user_id, password, temporary)
+            .await
+    }
+
+    async fn validate_user_password(&self, user_id: &str, password: &str) -> Result<bool> {
+        fault().await?;
+        self.inner
+            .user_store()
+            .validate_user_password(user_id, password)
+            .await
+    }
+
+    async fn get_user_password_hash(&self, user_id: &str) -> Result<Option<String>> {
+        fault().await?;
+        self.inner
+            .user_store()
+            .get_user_password_hash(user_id)
+            .await
+    }
+}
+
+struct FaultInjectingSessionStore {
+    inner: Arc<dyn IdentityEngine>,
+}
+
+#[async_trait]
+impl IdentitySessionStore for FaultInjectingSessionStore {
+    async fn delete_user_session(&self, session_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner
+            .session_store()
+            .delete_user_session(session_id)
+            .await
+    }
+
+    async fn logout_user(&self, user_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner.session_store().logout_user(user_id).await
+    }
+}
+
+struct FaultInjectingCredentialStore {
+    inner: Arc<dyn IdentityEngine>,
+}
+
+#[async_trait]
+impl IdentityCredentialStore for FaultInjectingCredentialStore {
+    async fn list_user_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<IdentityCredentialRepresentation>> {
+        fault().await?;
+        self.inner
+            .credential_store()
+            .list_user_credentials(user_id)
+            .await
+    }
+
+    async fn remove_totp_credentials(&self, user_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner
+            .credential_store()
+            .remove_totp_credentials(user_id)
+            .await
+    }
+
+    async fn list_webauthn_credentials(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<IdentityCredentialRepresentation>> {
+        fault().await?;
+        self.inner
+            .credential_store()
+            .list_webauthn_credentials(user_id)
+            .await
+    }
+
+    async fn delete_user_credential(&self, user_id: &str, credential_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner
+            .credential_store()
+            .delete_user_credential(user_id, credential_id)
+            .await
+    }
+
+    async fn is_password_temporary(&self, user_id: &str) -> Result<bool> {
+        fault().await?;
+        self.inner
+            .credential_store()
+            .is_password_temporary(user_id)
+            .await
+    }
+}
+
+struct FaultInjectingActionStore {
+    inner: Arc<dyn IdentityEngine>,
+}
+
+#[async_trait]
+impl IdentityActionStore for FaultInjectingActionStore {
+    async fn get_pending_actions(&self, user_id: &str) -> Result<Vec<PendingActionInfo>> {
+        fault().await?;
+        self.inner.action_store().get_pending_actions(user_id).await
+    }
+
+    async fn create_action(
+        &self,
+        user_id: &str,
+        action_type: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<String> {
+        fault().await?;
+        self.inner
+            .action_store()
+            .create_action(user_id, action_type, metadata)
+            .await
+    }
+
+    async fn complete_action(&self, action_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner.action_store().complete_action(action_id).await
+    }
+
+    async fn cancel_action(&self, action_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner.action_store().cancel_action(action_id).await
+    }
+}
+
+struct FaultInjectingVerificationStore {
+    inner: Arc<dyn IdentityEngine>,
+}
+
+#[async_trait]
+impl IdentityVerificationStore for FaultInjectingVerificationStore {
+    async fn get_verification_status(&self, user_id: &str) -> Result<bool> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .get_verification_status(user_id)
+            .await
+    }
+
+    async fn set_email_verified(&self, user_id: &str, verified: bool) -> Result<()> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .set_email_verified(user_id, verified)
+            .await
+    }
+
+    async fn create_verification_token(
+        &self,
+        user_id: &str,
+        token_hash: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<VerificationTokenInfo> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .create_verification_token(user_id, token_hash, expires_at)
+            .await
+    }
+
+    async fn find_valid_token(&self, token_hash: &str) -> Result<Option<VerificationTokenInfo>> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .find_valid_token(token_hash)
+            .await
+    }
+
+    async fn mark_token_used(&self, token_id: &str) -> Result<()> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .mark_token_used(token_id)
+            .await
+    }
+
+    async fn invalidate_user_tokens(&self, user_id: &str) -> Result<u64> {
+        fault().await?;
+        self.inner
+            .verification_store()
+            .invalidate_user_tokens(user_id)
+            .await
+    }
+}
@@ -1 +1,4 @@
+pub mod audited;
 pub mod auth9_oidc;
+#[cfg(feature = "fault-injection")]
+pub mod fault_injection;
@@ -4,10 +4,14 @@ pub mod claims;
 
 use crate::config::JwtConfig;
 use crate::error::{AppError, Result};
+use crate::models::common::{TokenTtlOverrides, MIN_TOKEN_TTL_SECS};
+use crate::models::token_validation::TokenValidationProfile;
+use arc_swap::ArcSwap;
 use base64::Engine;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Identity Token claims (issued after initial authentication)
@@ -30,6 +34,12 @@ pub struct IdentityClaims {
     /// Token type discriminator (prevents token confusion attacks)
     #[serde(default)]
     pub token_type: String,
+    /// Scope granted by an OIDC authorization (absent for other logins)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Client the OIDC authorization was granted to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp: Option<String>,
     /// Custom claims (from Actions)
     #[serde(flatten)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -103,11 +113,6 @@ pub struct ServiceClientClaims {
 pub struct IdTokenClaims {
     /// Subject (user ID)
     pub sub: String,
-    /// Email
-    pub email: String,
-    /// Display name
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
     /// Nonce (from authorize request, for replay protection)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nonce: Option<String>,
@@ -124,6 +129,9 @@ pub struct IdTokenClaims {
     /// Token type discriminator
     #[serde(default)]
     pub token_type: String,
+    /// User claims released by the granted scopes (`email`, `name`, ...)
+    #[serde(flatten)]
+    pub user_claims: serde_json::Map<String, serde_json::Value>,
     /// Issued at (Unix timestamp)
     pub iat: i64,
     /// Expiration (Unix timestamp)
@@ -144,6 +152,9 @@ pub struct OidcRefreshClaims {
     /// JWT ID (unique identifier for one-time-use enforcement)
     #[serde(default)]
     pub jti: String,
+    /// Scope granted by the original authorization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
     /// Token type discriminator
     #[serde(default)]
     pub token_type: String,
@@ -166,56 +177,257 @@ pub struct RefreshClaims {
     pub exp: i64,
 }
 
-/// JWT token manager
+/// Kind of token named by its `token_type` claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Service,
+    Identity,
+    TenantAccess,
+}
+
+impl TokenKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Service => "service_client",
+            Self::Identity => "identity",
+            Self::TenantAccess => "tenant_access",
+        }
+    }
+}
+
+/// Only the `token_type` claim, borrowed from the payload.
+/// A type containing escapes fails to borrow and falls back to `None`.
+#[derive(Deserialize)]
+struct TokenTypeProbe<'a> {
+    #[serde(default)]
+    token_type: Option<&'a str>,
+}
+
+/// Claims needed to authenticate a request.
+///
+/// Unlike the full claim structs this has no flattened extra-claims map, so
+/// decoding skips unknown claims instead of buffering them as JSON values.
+#[derive(Deserialize)]
+struct BearerClaims {
+    sub: String,
+    #[serde(default)]
+    sid: Option<String>,
+    aud: String,
+    #[serde(default)]
+    token_type: String,
+}
+
+/// A verified bearer token reduced to what request authentication needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BearerSession {
+    pub kind: TokenKind,
+    pub sub: String,
+    pub sid: Option<String>,
+    pub aud: String,
+}
+
+/// A verified bearer token with its full claims
+#[derive(Debug, Clone)]
+pub enum VerifiedToken {
+    Service(ServiceClientClaims),
+    Identity(IdentityClaims),
+    TenantAccess(TenantAccessClaims),
+}
+
+/// Read the unverified `token_type` claim to pick the verifier for a token.
+///
+/// This never authenticates anything: the chosen verifier still checks the
+/// signature, issuer, audience and type. It only spares verifying the same
+/// token against every token kind in turn.
+pub fn peek_token_kind(token: &str) -> Option<TokenKind> {
+    let bytes = peek_payload(token)?;
+    let probe: TokenTypeProbe = serde_json::from_slice(&bytes).ok()?;
+    match probe.token_type? {
+        "service" => Some(TokenKind::Service),
+        "identity" => Some(TokenKind::Identity),
+        "access" => Some(TokenKind::TenantAccess),
+        _ => None,
+    }
+}
+
+/// Read the unverified `aud` claim of a token.
+///
+/// Like [`peek_token_kind`], this only selects how the token is verified
+/// (e.g. the validation profile of the audience's service).
+pub fn peek_token_audience(token: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct AudienceProbe {
+        #[serde(default)]
+        aud: Option<String>,
+    }
+
+    let bytes = peek_payload(token)?;
+    serde_json::from_slice::<AudienceProbe>(&bytes).ok()?.aud
+}
+
+fn peek_payload(token: &str) -> Option<Vec<u8>> {
+    let payload = token.split('.').nth(1)?;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+}
+
+/// Parsed signing keys with validation rules prebuilt for each token kind.
+///
+/// Built once per key set and shared through an `ArcSwap`, so verifying a
+/// token neither parses keys, allocates validation rules nor takes a lock.
 #[derive(Clone)]
-pub struct JwtManager {
-    config: JwtConfig,
+struct KeyMaterial {
     encoding_key: EncodingKey,
     decoding_key: DecodingKey,
     algorithm: Algorithm,
     public_key_pem: Option<String>,
     previous_public_key_pem: Option<String>,
+    /// Issuer only; audience is set per call
+    issuer_validation: Validation,
+    service_validation: Validation,
+    identity_validation: Validation,
+    /// Issuer checked, audience left to the caller
+    any_audience_validation: Validation,
+    /// Keys replaced by the last rotation
+    retired: Option<RetiredKeys>,
 }
 
-impl JwtManager {
-    pub fn new(config: JwtConfig) -> Self {
+/// Keys replaced by a rotation. They no longer sign anything but still
+/// verify tokens until `until`, so tokens issued just before a rotation
+/// stay valid for their lifetime.
+#[derive(Clone)]
+struct RetiredKeys {
+    keys: Arc<KeyMaterial>,
+    until: DateTime<Utc>,
+}
+
+impl KeyMaterial {
+    fn load(config: &JwtConfig) -> Result<Self> {
         let algorithm = if config.private_key_pem.is_some() {
             Algorithm::RS256
         } else {
             Algorithm::HS256
         };
-        let public_key_pem = config.public_key_pem.clone();
-        let previous_public_key_pem = config.previous_public_key_pem.clone();
         let encoding_key = match config.private_key_pem.as_ref() {
-            Some(private_key) => EncodingKey::from_rsa_pem(private_key.as_bytes())
-                .expect("Failed to load JWT private key"),
+            Some(private_key) => {
+                EncodingKey::from_rsa_pem(private_key.as_bytes()).map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Invalid JWT private key: {}", e))
+                })?
+            }
             None => EncodingKey::from_secret(config.secret.as_bytes()),
         };
-        let decoding_key = match config.public_key_pem.as_ref() {
-            Some(public_key) => DecodingKey::from_rsa_pem(public_key.as_bytes())
-                .expect("Failed to load JWT public key"),
-            None => match config.private_key_pem.as_ref() {
-                Some(private_key) => DecodingKey::from_rsa_pem(private_key.as_bytes())
-                    .expect("Failed to load JWT private key"),
-                None => DecodingKey::from_secret(config.secret.as_bytes()),
-            },
+        let decoding_key = match config
+            .public_key_pem
+            .as_ref()
+            .or(config.private_key_pem.as_ref())
+        {
+            Some(pem) => DecodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Invalid JWT public key: {}", e))
+            })?,
+            None => DecodingKey::from_secret(config.secret.as_bytes()),
         };
-        Self {
-            config,
+
+        let issuer_validation = strict_validation(algorithm, &config.issuer);
+        let mut service_validation = issuer_validation.clone();
+        service_validation.set_audience(&["auth9-service"]);
+        let mut identity_validation = issuer_validation.clone();
+        identity_validation.set_audience(&["auth9"]);
+        let mut any_audience_validation = issuer_validation.clone();
+        any_audience_validation.validate_aud = false;
+
+        Ok(Self {
             encoding_key,
             decoding_key,
             algorithm,
-            public_key_pem,
-            previous_public_key_pem,
+            public_key_pem: config.public_key_pem.clone(),
+            previous_public_key_pem: config.previous_public_key_pem.clone(),
+            issuer_validation,
+            service_validation,
+            identity_validation,
+            any_audience_validation,
+            retired: None,
+        })
+    }
+
+    fn header(&self) -> Header {
+        let mut header = Header::new(self.algorithm);
+        header.kid = Some("auth9-current".to_string());
+        header
+    }
+}
+
+/// Create a Validation with a strict leeway (5 seconds) instead of the default 60 seconds.
+/// This ensures tokens expire promptly while still tolerating minor clock skew.
+fn strict_validation(algorithm: Algorithm, issuer: &str) -> Validation {
+    let mut v = Validation::new(algorithm);
+    v.leeway = 5;
+    v.set_issuer(&[issuer]);
+    v
+}
+
+/// JWT token manager
+///
+/// Cheap to clone: clones share the configuration and key material, and
+/// [`JwtManager::rotate_keys`] on any clone is seen by all of them.
+#[derive(Clone)]
+pub struct JwtManager {
+    config: Arc<JwtConfig>,
+    keys: Arc<ArcSwap<KeyMaterial>>,
+}
+
+impl JwtManager {
+    pub fn new(config: JwtConfig) -> Self {
+        let keys = KeyMaterial::load(&config).expect("Failed to load JWT keys");
+        Self {
+            config: Arc::new(config),
+            keys: Arc::new(ArcSwap::from_pointee(keys)),
         }
     }
 
-    /// Create a Validation with a strict leeway (5 seconds) instead of the default 60 seconds.
-    /// This ensures tokens expire promptly while still tolerating minor clock skew.
-    fn strict_validation(&self) -> Validation {
-        let mut v = Validation::new(self.algorithm);
-        v.leeway = 5;
-        v
+    /// Swap in new signing keys (and previous public key) from `config`.
+    ///
+    /// Tokens issued after the swap are signed with the new key; the issuer
+    /// and TTLs of the running configuration are kept. The replaced key
+    /// keeps verifying tokens for the longest token lifetime (the refresh
+    /// token TTL) and is published as the previous key meanwhile. Invalid
+    /// keys leave the current ones in place.
+    pub fn rotate_keys(&self, config: &JwtConfig) -> Result<()> {
+        let mut keys = KeyMaterial::load(&JwtConfig {
+            issuer: self.config.issuer.clone(),
+            ..config.clone()
+        })?;
+        let mut replaced = KeyMaterial::clone(&self.keys.load());
+        replaced.retired = None;
+        let grace = self
+            .config
+            .access_token_ttl_secs
+            .max(self.config.refresh_token_ttl_secs);
+        keys.retired = Some(RetiredKeys {
+            keys: Arc::new(replaced),
+            until: Utc::now() + Duration::seconds(grace),
+        });
+        self.keys.store(Arc::new(keys));
+        Ok(())
+    }
+
+    /// Run `decode` with the current keys and, if that fails, with the keys
+    /// retired by the last rotation while they are still accepted. The
+    /// current keys' error is returned when both fail.
+    fn decode_with_keys<T>(&self, decode: impl Fn(&KeyMaterial) -> Result<T>) -> Result<T> {
+        let keys = self.keys.load();
+        match decode(&keys) {
+            Err(e) => match keys.retired.as_ref().filter(|r| Utc::now() < r.until) {
+                Some(retired) => decode(&retired.keys).map_err(|_| e),
+                None => Err(e),
+            },
+            decoded => decoded,
+        }
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let keys = self.keys.load();
+        encode(&keys.header(), claims, &keys.encoding_key).map_err(|e| AppError::Internal(e.into()))
     }
 
     /// Create an identity token
@@ -262,6 +474,23 @@ impl JwtManager {
         self.create_identity_token_full(user_id, email, name, session_id, None)
     }
 
+    /// Create the access token of an OIDC authorization code or refresh
+    /// grant, recording the granted scope and the client it was granted to
+    pub fn create_oidc_access_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        name: Option<&str>,
+        session_id: Uuid,
+        client_id: &str,
+        scope: &str,
+    ) -> Result<String> {
+        let mut claims = self.identity_claims(user_id, email, name, Some(session_id), None);
+        claims.scope = Some(scope.to_string());
+        claims.azp = Some(client_id.to_string());
+        self.sign(&claims)
+    }
+
     /// Create an identity token with all options
     fn create_identity_token_full(
         &self,
@@ -271,10 +500,22 @@ impl JwtManager {
         session_id: Option<Uuid>,
         custom_claims: Option<std::collections::HashMap<String, serde_json::Value>>,
     ) -> Result<String> {
+        let claims = self.identity_claims(user_id, email, name, session_id, custom_claims);
+        self.sign(&claims)
+    }
+
+    fn identity_claims(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        name: Option<&str>,
+        session_id: Option<Uuid>,
+        custom_claims: Option<std::collections::HashMap<String, serde_json::Value>>,
+    ) -> IdentityClaims {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.config.access_token_ttl_secs);
 
-        let claims = IdentityClaims {
+        IdentityClaims {
             sub: user_id.to_string(),
             sid: session_id.map(|id| id.to_string()),
             email: email.to_string(),
@@ -282,13 +523,12 @@ impl JwtManager {
             iss: self.config.issuer.clone(),
             aud: "auth9".to_string(),
             token_type: "identity".to_string(),
+            scope: None,
+            azp: None,
             extra: custom_claims,
             iat: now.timestamp(),
             exp: exp.timestamp(),
-        };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        }
     }
 
     /// Create a tenant access token
@@ -365,9 +605,7 @@ impl JwtManager {
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        self.sign(&claims)
     }
 
     pub fn create_refresh_token(
@@ -388,9 +626,7 @@ impl JwtManager {
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        self.sign(&claims)
     }
 
     /// Create a service client token (for client_credentials grant)
@@ -399,9 +635,26 @@ impl JwtManager {
         service_id: Uuid,
         email: &str,
         tenant_id: Option<Uuid>,
+    ) -> Result<String> {
+        self.create_service_client_token_with_ttl(
+            service_id,
+            email,
+            tenant_id,
+            self.config.access_token_ttl_secs,
+        )
+    }
+
+    /// Create a service client token with an explicit lifetime (for
+    /// federated workload tokens, which are shorter-lived)
+    pub fn create_service_client_token_with_ttl(
+        &self,
+        service_id: Uuid,
+        email: &str,
+        tenant_id: Option<Uuid>,
+        ttl_secs: i64,
     ) -> Result<String> {
         let now = Utc::now();
-        let exp = now + Duration::seconds(self.config.access_token_ttl_secs);
+        let exp = now + Duration::seconds(ttl_secs);
 
         let claims = ServiceClientClaims {
             sub: service_id.to_string(),
@@ -413,18 +666,15 @@ impl JwtManager {
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        self.sign(&claims)
     }
 
-    /// Create an OIDC ID Token (per OIDC Core spec)
-    #[allow(clippy::too_many_arguments)]
+    /// Create an OIDC ID Token (per OIDC Core spec). `user_claims` are the
+    /// claims released by the granted scopes.
     pub fn create_id_token(
         &self,
         user_id: Uuid,
-        email: &str,
-        name: Option<&str>,
+        user_claims: serde_json::Map<String, serde_json::Value>,
         nonce: Option<&str>,
         client_id: &str,
         session_id: Option<Uuid>,
@@ -440,20 +690,17 @@ impl JwtManager {
 
         let claims = IdTokenClaims {
             sub: user_id.to_string(),
-            email: email.to_string(),
-            name: name.map(String::from),
             nonce: nonce.map(String::from),
             at_hash: Some(at_hash),
             iss: self.config.issuer.clone(),
             aud: client_id.to_string(),
             sid: session_id.map(|id| id.to_string()),
             token_type: "id_token".to_string(),
+            user_claims,
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        self.sign(&claims)
     }
 
     /// Create an OIDC refresh token (session-bound, for OIDC authorization code flow)
@@ -462,6 +709,7 @@ impl JwtManager {
         user_id: Uuid,
         client_id: &str,
         session_id: Uuid,
+        scope: &str,
     ) -> Result<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.config.refresh_token_ttl_secs);
@@ -472,13 +720,12 @@ impl JwtManager {
             iss: self.config.issuer.clone(),
             aud: client_id.to_string(),
             jti: Uuid::new_v4().to_string(),
+            scope: Some(scope.to_string()),
             token_type: "oidc_refresh".to_string(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
         };
-        let mut header = Header::new(self.algorithm);
-        header.kid = Some("auth9-current".to_string());
-        encode(&header, &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.into()))
+        self.sign(&claims)
     }
 
     /// Verify and decode an OIDC refresh token
@@ -487,11 +734,15 @@ impl JwtManager {
         token: &str,
         expected_audience: &str,
     ) -> Result<OidcRefreshClaims> {
-        let mut validation = self.strict_validation();
-        validation.set_audience(&[expected_audience]);
-        validation.set_issuer(&[&self.config.issuer]);
-
-        let token_data = decode::<OidcRefreshClaims>(token, &self.decoding_key, &validation)?;
+        let token_data = self.decode_with_keys(|keys| {
+            let mut validation = keys.issuer_validation.clone();
+            validation.set_audience(&[expected_audience]);
+            Ok(decode::<OidcRefreshClaims>(
+                token,
+                &keys.decoding_key,
+                &validation,
+            )?)
+        })?;
 
         if token_data.claims.token_type != "oidc_refresh" {
             return Err(AppError::Unauthorized(
@@ -502,23 +753,49 @@ impl JwtManager {
         Ok(token_data.claims)
     }
 
+    /// Verify an ID token previously issued to `client_id`, passed back as
+    /// `id_token_hint`. Expired tokens are accepted (OIDC Core 3.1.2.1): the
+    /// hint only identifies the session, which is checked separately.
+    pub fn verify_id_token_hint(&self, token: &str, client_id: &str) -> Result<IdTokenClaims> {
+        let token_data = self.decode_with_keys(|keys| {
+            let mut validation = keys.issuer_validation.clone();
+            validation.set_audience(&[client_id]);
+            validation.validate_exp = false;
+            Ok(decode::<IdTokenClaims>(
+                token,
+                &keys.decoding_key,
+                &validation,
+            )?)
+        })?;
+
+        if token_data.claims.token_type != "id_token" {
+            return Err(AppError::Unauthorized("Not an ID token".to_string()));
+        }
+
+        Ok(token_data.claims)
+    }
+
     /// Verify and decode a service client token
     pub fn verify_service_client_token(&self, token: &str) -> Result<ServiceClientClaims> {
-        let mut validation = self.strict_validation();
-        validation.set_audience(&["auth9-service"]);
-        validation.set_issuer(&[&self.config.issuer]);
-
-        let token_data = decode::<ServiceClientClaims>(token, &self.decoding_key, &validation)?;
+        let token_data = self.decode_with_keys(|keys| {
+            Ok(decode::<ServiceClientClaims>(
+                token,
+                &keys.decoding_key,
+                &keys.service_validation,
+            )?)
+        })?;
         Ok(token_data.claims)
     }
 
     /// Verify and decode an identity token
     pub fn verify_identity_token(&self, token: &str) -> Result<IdentityClaims> {
-        let mut validation = self.strict_validation();
-        validation.set_audience(&["auth9"]);
-        validation.set_issuer(&[&self.config.issuer]);
-
-        let token_data = decode::<IdentityClaims>(token, &self.decoding_key, &validation)?;
+        let token_data = self.decode_with_keys(|keys| {
+            Ok(decode::<IdentityClaims>(
+                token,
+                &keys.decoding_key,
+                &keys.identity_validation,
+            )?)
+        })?;
 
         // Prevent token confusion attacks: only accept tokens with token_type "identity"
         if token_data.claims.token_type != "identity" {
@@ -537,19 +814,60 @@ impl JwtManager {
         token: &str,
         expected_audience: Option<&str>,
     ) -> Result<TenantAccessClaims> {
-        let mut validation = self.strict_validation();
-        validation.set_issuer(&[&self.config.issuer]);
+        let Some(aud) = expected_audience else {
+            return self.verify_tenant_access_token_any_audience(token);
+        };
 
-        if let Some(aud) = expected_audience {
+        let token_data = self.decode_with_keys(|keys| {
+            let mut validation = keys.issuer_validation.clone();
             validation.set_audience(&[aud]);
-        } else {
-            validation.validate_aud = false;
-        }
-
-        let token_data = decode::<TenantAccessClaims>(token, &self.decoding_key, &validation)?;
+            Ok(decode::<TenantAccessClaims>(
+                token,
+                &keys.decoding_key,
+                &validation,
+            )?)
+        })?;
         Ok(token_data.claims)
     }
 
+    /// Verify and decode a tenant access token under a service's validation
+    /// profile. The profile's clock skew replaces the default leeway, and its
+    /// algorithm, required claim and maximum age rules are enforced.
+    pub fn verify_tenant_access_token_with_profile(
+        &self,
+        token: &str,
+        expected_audience: Option<&str>,
+        profile: &TokenValidationProfile,
+    ) -> Result<TenantAccessClaims> {
+        let claims = self.decode_with_keys(|keys| {
+            let algorithm = match keys.algorithm {
+                Algorithm::RS256 => "RS256",
+                _ => "HS256",
+            };
+            if !profile.accepts_algorithm(algorithm) {
+                return Err(AppError::Unauthorized(
+                    "Token signing algorithm is not accepted".to_string(),
+                ));
+            }
+
+            let mut validation = match expected_audience {
+                Some(aud) => {
+                    let mut validation = keys.issuer_validation.clone();
+                    validation.set_audience(&[aud]);
+                    validation
+                }
+                None => keys.any_audience_validation.clone(),
+            };
+            validation.leeway = profile.clock_skew_secs;
+            Ok(decode::<serde_json::Value>(token, &keys.decoding_key, &validation)?.claims)
+        })?;
+        profile
+            .check_claims(&claims, Utc::now().timestamp())
+            .map_err(AppError::Unauthorized)?;
+        serde_json::from_value(claims)
+            .map_err(|e| AppError::Unauthorized(format!("Invalid token claims: {}", e)))
+    }
+
     /// Verify and decode a tenant access token
     #[deprecated(note = "Use verify_tenant_access_token_strict for REST-side validation")]
     pub fn verify_tenant_access_token(
@@ -575,13 +893,15 @@ impl JwtManager {
             ));
         }
 
-        let mut validation = self.strict_validation();
-        validation.set_issuer(&[&self.config.issuer]);
-
-        let aud_refs: Vec<&str> = expected_audiences.iter().map(|s| s.as_str()).collect();
-        validation.set_audience(&aud_refs);
-
-        let token_data = decode::<TenantAccessClaims>(token, &self.decoding_key, &validation)?;
+        let token_data = self.decode_with_keys(|keys| {
+            let mut validation = keys.issuer_validation.clone();
+            validation.set_audience(expected_audiences);
+            Ok(decode::<TenantAccessClaims>(
+                token,
+                &keys.decoding_key,
+                &validation,
+            )?)
+        })?;
         Ok(token_data.claims)
     }
 
@@ -594,29 +914,159 @@ impl JwtManager {
         &self,
         token: &str,
     ) -> Result<TenantAccessClaims> {
-        let mut validation = self.strict_validation();
-        validation.set_issuer(&[&self.config.issuer]);
-        validation.validate_aud = false;
-
-        let token_data = decode::<TenantAccessClaims>(token, &self.decoding_key, &validation)?;
+        let token_data = self.decode_with_keys(|keys| {
+            Ok(decode::<TenantAccessClaims>(
+                token,
+                &keys.decoding_key,
+                &keys.any_audience_validation,
+            )?)
+        })?;
         Ok(token_data.claims)
     }
 
+    /// Verify a bearer token of any kind accepted on the API.
+    ///
+    /// Accepts the same tokens as trying the service client, identity and
+    /// tenant access verifiers in that order, but checks the signature once:
+    /// the token's `token_type` picks the verifier. Tenant access audiences
+    /// are not checked here.
+    pub fn verify_bearer_token(&self, token: &str) -> Result<VerifiedToken> {
+        match peek_token_kind(token) {
+            Some(TokenKind::Service) => self
+                .verify_service_client_token(token)
+                .map(VerifiedToken::Service),
+            Some(TokenKind::Identity) => self
+                .verify_identity_token(token)
+                .map(VerifiedToken::Identity),
+            Some(TokenKind::TenantAccess) => self
+                .verify_tenant_access_token_any_audience(token)
+                .map(VerifiedToken::TenantAccess),
+            // Tokens without a known type (issued before `token_type` existed)
+            None => self
+                .verify_service_client_token(token)
+                .map(VerifiedToken::Service)
+                .or_else(|_| {
+                    self.verify_tenant_access_token_any_audience(token)
+                        .map(VerifiedToken::TenantAccess)
+                }),
+        }
+    }
+
+    /// Like [`JwtManager::verify_bearer_token`], but decodes only the claims
+    /// request authentication needs.
+    pub fn verify_bearer_session(&self, token: &str) -> Result<BearerSession> {
+        let Some(kind) = peek_token_kind(token) else {
+            return self.verify_bearer_token(token).map(BearerSession::from);
+        };
+
+        let expected_type = match kind {
+            TokenKind::Service => "service",
+            TokenKind::Identity => "identity",
+            TokenKind::TenantAccess => "access",
+        };
+        let claims = self.decode_with_keys(|keys| {
+            let validation = match kind {
+                TokenKind::Service => &keys.service_validation,
+                TokenKind::Identity => &keys.identity_validation,
+                TokenKind::TenantAccess => &keys.any_audience_validation,
+            };
+            Ok(decode::<BearerClaims>(token, &keys.decoding_key, validation)?.claims)
+        })?;
+        if claims.token_type != expected_type {
+            return Err(AppError::Unauthorized("Unexpected token type".to_string()));
+        }
+
+        Ok(BearerSession {
+            kind,
+            sub: claims.sub,
+            sid: claims.sid,
+            aud: claims.aud,
+        })
+    }
+
     /// Get token expiration TTL in seconds
     pub fn access_token_ttl(&self) -> i64 {
         self.config.access_token_ttl_secs
     }
 
+    pub fn refresh_token_ttl(&self) -> i64 {
+        self.config.refresh_token_ttl_secs
+    }
+
+    /// Manager issuing tokens with tenant/client lifetime overrides applied.
+    ///
+    /// Overrides are clamped to the platform maxima and to
+    /// [`MIN_TOKEN_TTL_SECS`]; key material is shared with `self`.
+    pub fn with_ttl_overrides(&self, overrides: TokenTtlOverrides) -> Self {
+        if overrides.is_empty() {
+            return self.clone();
+        }
+        let clamp = |value: Option<i64>, default: i64, max: i64| {
+            value.map_or(default, |v| {
+                v.clamp(MIN_TOKEN_TTL_SECS, max.max(MIN_TOKEN_TTL_SECS))
+            })
+        };
+        let config = JwtConfig {
+            access_token_ttl_secs: clamp(
+                overrides.access_token_ttl_secs,
+                self.config.access_token_ttl_secs,
+                self.config.max_access_token_ttl_secs,
+            ),
+            refresh_token_ttl_secs: clamp(
+                overrides.refresh_token_ttl_secs,
+                self.config.refresh_token_ttl_secs,
+                self.config.max_refresh_token_ttl_secs,
+            ),
+            ..(*self.config).clone()
+        };
+        Self {
+            config: Arc::new(config),
+            keys: self.keys.clone(),
+        }
+    }
+
     pub fn uses_rsa(&self) -> bool {
-        self.algorithm == Algorithm::RS256
+        self.keys.load().algorithm == Algorithm::RS256
+    }
+
+    pub fn public_key_pem(&self) -> Option<String> {
+        self.keys.load().public_key_pem.clone()
     }
 
-    pub fn public_key_pem(&self) -> Option<&str> {
-        self.public_key_pem.as_deref()
+    /// Public key of the keys retired by the last rotation while they are
+    /// still accepted, otherwise the configured previous public key
+    pub fn previous_public_key_pem(&self) -> Option<String> {
+        let keys = self.keys.load();
+        keys.retired
+            .as_ref()
+            .filter(|r| Utc::now() < r.until)
+            .and_then(|r| r.keys.public_key_pem.clone())
+            .or_else(|| keys.previous_public_key_pem.clone())
     }
+}
 
-    pub fn previous_public_key_pem(&self) -> Option<&str> {
-        self.previous_public_key_pem.as_deref()
+impl From<VerifiedToken> for BearerSession {
+    fn from(token: VerifiedToken) -> Self {
+        match token {
+            VerifiedToken::Service(claims) => Self {
+                kind: TokenKind::Service,
+                sub: claims.sub,
+                sid: None,
+                aud: claims.aud,
+            },
+            VerifiedToken::Identity(claims) => Self {
+                kind: TokenKind::Identity,
+                sub: claims.sub,
+                sid: claims.sid,
+                aud: claims.aud,
+            },
+            VerifiedToken::TenantAccess(claims) => Self {
+                kind: TokenKind::TenantAccess,
+                sub: claims.sub,
+                sid: claims.sid,
+                aud: claims.aud,
+            },
+        }
     }
 }
 
@@ -633,6 +1083,8 @@ mod tests {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         }
     }
 
@@ -653,6 +1105,28 @@ mod tests {
         assert_eq!(claims.aud, "auth9");
     }
 
+    #[test]
+    fn test_oidc_access_token_carries_scope_and_client() {
+        let manager = JwtManager::new(test_config());
+        let session_id = Uuid::new_v4();
+
+        let token = manager
+            .create_oidc_access_token(
+                Uuid::new_v4(),
+                "test@example.com",
+                None,
+                session_id,
+                "my-client",
+                "openid email",
+            )
+            .unwrap();
+
+        let claims = manager.verify_identity_token(&token).unwrap();
+        assert_eq!(claims.scope.as_deref(), Some("openid email"));
+        assert_eq!(claims.azp.as_deref(), Some("my-client"));
+        assert_eq!(claims.sid, Some(session_id.to_string()));
+    }
+
     #[test]
     fn test_create_and_verify_tenant_access_token() {
         let manager = JwtManager::new(test_config());
@@ -819,6 +1293,8 @@ mod tests {
             iat: 1000000,
             exp: 1003600,
             extra: None,
+            scope: None,
+            azp: None,
         };
 
         let json = serde_json::to_string(&claims).unwrap();
@@ -842,6 +1318,8 @@ mod tests {
             iat: 1000000,
             exp: 1003600,
             extra: None,
+            scope: None,
+            azp: None,
         };
 
         let json = serde_json::to_string(&claims).unwrap();
@@ -1020,12 +1498,43 @@ mod tests {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         };
 
         let manager = JwtManager::new(config);
         assert_eq!(manager.access_token_ttl(), 1800);
     }
 
+    #[test]
+    fn test_ttl_overrides_are_clamped_to_platform_maxima() {
+        let manager = JwtManager::new(test_config());
+
+        let scoped = manager.with_ttl_overrides(TokenTtlOverrides {
+            access_token_ttl_secs: Some(300),
+            refresh_token_ttl_secs: Some(10_000_000),
+        });
+        assert_eq!(scoped.access_token_ttl(), 300);
+        assert_eq!(scoped.refresh_token_ttl(), 7_776_000);
+        // The base manager keeps the platform defaults
+        assert_eq!(manager.access_token_ttl(), 3600);
+
+        let token = scoped
+            .create_service_client_token(Uuid::new_v4(), "svc@auth9.local", None)
+            .unwrap();
+        let claims = scoped.verify_service_client_token(&token).unwrap();
+        assert_eq!(claims.exp - claims.iat, 300);
+        // Tokens from the scoped manager verify with the shared keys
+        assert!(manager.verify_service_client_token(&token).is_ok());
+
+        let floor = manager.with_ttl_overrides(TokenTtlOverrides {
+            access_token_ttl_secs: Some(1),
+            refresh_token_ttl_secs: None,
+        });
+        assert_eq!(floor.access_token_ttl(), MIN_TOKEN_TTL_SECS);
+        assert_eq!(floor.refresh_token_ttl(), 604800);
+    }
+
     // ==================== ID Token Tests ====================
 
     #[test]
@@ -1038,8 +1547,10 @@ mod tests {
         let token = manager
             .create_id_token(
                 user_id,
-                "test@example.com",
-                Some("Test User"),
+                serde_json::json!({ "email": "test@example.com", "name": "Test User" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
                 Some("nonce-123"),
                 "my-client",
                 Some(session_id),
@@ -1059,8 +1570,8 @@ mod tests {
         .unwrap();
 
         assert_eq!(token_data.claims.sub, user_id.to_string());
-        assert_eq!(token_data.claims.email, "test@example.com");
-        assert_eq!(token_data.claims.name, Some("Test User".to_string()));
+        assert_eq!(token_data.claims.user_claims["email"], "test@example.com");
+        assert_eq!(token_data.claims.user_claims["name"], "Test User");
         assert_eq!(token_data.claims.nonce, Some("nonce-123".to_string()));
         assert_eq!(token_data.claims.aud, "my-client");
         assert_eq!(token_data.claims.token_type, "id_token");
@@ -1069,15 +1580,55 @@ mod tests {
     }
 
     #[test]
-    fn test_create_id_token_without_nonce() {
+    fn test_verify_id_token_hint() {
         let manager = JwtManager::new(test_config());
         let user_id = Uuid::new_v4();
-
+        let session_id = Uuid::new_v4();
         let token = manager
             .create_id_token(
+                user_id,
+                serde_json::Map::new(),
+                None,
+                "my-client",
+                Some(session_id),
+                "access-token",
+            )
+            .unwrap();
+
+        let claims = manager.verify_id_token_hint(&token, "my-client").unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.sid, Some(session_id.to_string()));
+
+        // Issued to another client
+        assert!(manager
+            .verify_id_token_hint(&token, "other-client")
+            .is_err());
+
+        // Access tokens are not ID tokens
+        let access_token = manager
+            .create_oidc_access_token(
                 user_id,
                 "test@example.com",
                 None,
+                session_id,
+                "my-client",
+                "openid",
+            )
+            .unwrap();
+        assert!(manager
+            .verify_id_token_hint(&access_token, "my-client")
+            .is_err());
+    }
+
+    #[test]
+    fn test_create_id_token_without_nonce() {
+        let manager = JwtManager::new(test_config());
+        let user_id = Uuid::new_v4();
+
+        let token = manager
+            .create_id_token(
+                user_id,
+                serde_json::Map::new(),
                 None,
                 "client-id",
                 None,
@@ -1096,7 +1647,7 @@ mod tests {
         .unwrap();
 
         assert!(token_data.claims.nonce.is_none());
-        assert!(token_data.claims.name.is_none());
+        assert!(token_data.claims.user_claims.is_empty());
         assert!(token_data.claims.sid.is_none());
     }
 
@@ -1109,8 +1660,7 @@ mod tests {
         let token = manager
             .create_id_token(
                 Uuid::new_v4(),
-                "test@example.com",
-                None,
+                serde_json::Map::new(),
                 None,
                 "client",
                 None,
@@ -1143,7 +1693,7 @@ mod tests {
         let session_id = Uuid::new_v4();
 
         let token = manager
-            .create_oidc_refresh_token(user_id, "my-client", session_id)
+            .create_oidc_refresh_token(user_id, "my-client", session_id, "openid email")
             .unwrap();
 
         let claims = manager
@@ -1154,13 +1704,14 @@ mod tests {
         assert_eq!(claims.sid, session_id.to_string());
         assert_eq!(claims.aud, "my-client");
         assert_eq!(claims.token_type, "oidc_refresh");
+        assert_eq!(claims.scope.as_deref(), Some("openid email"));
     }
 
     #[test]
     fn test_oidc_refresh_token_wrong_audience() {
         let manager = JwtManager::new(test_config());
         let token = manager
-            .create_oidc_refresh_token(Uuid::new_v4(), "my-client", Uuid::new_v4())
+            .create_oidc_refresh_token(Uuid::new_v4(), "my-client", Uuid::new_v4(), "openid")
             .unwrap();
 
         let result = manager.verify_oidc_refresh_token(&token, "wrong-client");
@@ -1184,14 +1735,16 @@ mod tests {
     fn test_id_token_claims_serialization() {
         let claims = IdTokenClaims {
             sub: "user-123".to_string(),
-            email: "test@example.com".to_string(),
-            name: Some("Test".to_string()),
             nonce: Some("nonce-456".to_string()),
             at_hash: Some("hash-value".to_string()),
             iss: "https://auth9.test".to_string(),
             aud: "my-client".to_string(),
             sid: Some("session-789".to_string()),
             token_type: "id_token".to_string(),
+            user_claims: serde_json::json!({ "email": "test@example.com" })
+                .as_object()
+                .unwrap()
+                .clone(),
             iat: 1000000,
             exp: 1003600,
         };
@@ -1200,9 +1753,11 @@ mod tests {
         assert!(json.contains("\"nonce\":\"nonce-456\""));
         assert!(json.contains("\"at_hash\":\"hash-value\""));
         assert!(json.contains("\"token_type\":\"id_token\""));
+        assert!(json.contains("\"email\":\"test@example.com\""));
 
         let decoded: IdTokenClaims = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded.nonce, Some("nonce-456".to_string()));
+        assert_eq!(decoded.user_claims.len(), 1);
     }
 
     #[test]
@@ -1213,6 +1768,7 @@ mod tests {
             iss: "https://auth9.test".to_string(),
             aud: "my-client".to_string(),
             jti: "test-jti-789".to_string(),
+            scope: None,
             token_type: "oidc_refresh".to_string(),
             iat: 1000000,
             exp: 1604800,
@@ -1222,4 +1778,273 @@ mod tests {
         assert!(json.contains("\"sid\":\"session-456\""));
         assert!(json.contains("\"token_type\":\"oidc_refresh\""));
     }
+
+    #[test]
+    fn test_peek_token_kind() {
+        let manager = JwtManager::new(test_config());
+        let identity = manager
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+        let access = manager
+            .create_tenant_access_token(
+                Uuid::new_v4(),
+                "a@b.c",
+                Uuid::new_v4(),
+                "app",
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let service = manager
+            .create_service_client_token(Uuid::new_v4(), "svc@example.com", None)
+            .unwrap();
+        let refresh = manager
+            .create_refresh_token(Uuid::new_v4(), Uuid::new_v4(), "app")
+            .unwrap();
+
+        assert_eq!(peek_token_kind(&identity), Some(TokenKind::Identity));
+        assert_eq!(peek_token_kind(&access), Some(TokenKind::TenantAccess));
+        assert_eq!(peek_token_kind(&service), Some(TokenKind::Service));
+        assert_eq!(peek_token_kind(&refresh), None);
+        assert_eq!(peek_token_kind("not-a-token"), None);
+        assert_eq!(peek_token_kind("a.!!!.c"), None);
+    }
+
+    #[test]
+    fn test_verify_bearer_token_dispatches_by_type() {
+        let manager = JwtManager::new(test_config());
+        let user_id = Uuid::new_v4();
+        let tenant_id = Uuid::new_v4();
+
+        let identity = manager
+            .create_identity_token_with_session(user_id, "u@example.com", None, Some(user_id))
+            .unwrap();
+        assert!(matches!(
+            manager.verify_bearer_token(&identity).unwrap(),
+            VerifiedToken::Identity(c) if c.sub == user_id.to_string()
+        ));
+
+        let access = manager
+            .create_tenant_access_token(user_id, "u@example.com", tenant_id, "app", vec![], vec![])
+            .unwrap();
+        assert!(matches!(
+            manager.verify_bearer_token(&access).unwrap(),
+            VerifiedToken::TenantAccess(c) if c.tenant_id == tenant_id.to_string()
+        ));
+
+        let service = manager
+            .create_service_client_token(user_id, "svc@example.com", Some(tenant_id))
+            .unwrap();
+        assert!(matches!(
+            manager.verify_bearer_token(&service).unwrap(),
+            VerifiedToken::Service(_)
+        ));
+
+        let refresh = manager
+            .create_refresh_token(user_id, tenant_id, "app")
+            .unwrap();
+        assert!(manager.verify_bearer_token(&refresh).is_err());
+    }
+
+    #[test]
+    fn test_verify_bearer_session_matches_full_verification() {
+        let manager = JwtManager::new(test_config());
+        let user_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+
+        let tokens = [
+            manager
+                .create_identity_token_with_session(
+                    user_id,
+                    "u@example.com",
+                    None,
+                    Some(session_id),
+                )
+                .unwrap(),
+            manager
+                .create_tenant_access_token_with_session(
+                    user_id,
+                    "u@example.com",
+                    Uuid::new_v4(),
+                    "app",
+                    vec!["admin".to_string()],
+                    vec![],
+                    Some(session_id.to_string()),
+                )
+                .unwrap(),
+            manager
+                .create_service_client_token(user_id, "svc@example.com", None)
+                .unwrap(),
+        ];
+        for token in &tokens {
+            let full = BearerSession::from(manager.verify_bearer_token(token).unwrap());
+            assert_eq!(manager.verify_bearer_session(token).unwrap(), full);
+        }
+    }
+
+    #[test]
+    fn test_verify_bearer_session_rejects_forged_type() {
+        // An identity token re-signed with another secret must not pass just
+        // because its type picked the verifier
+        let other = JwtManager::new(JwtConfig {
+            secret: "another-secret-key-for-testing-only".to_string(),
+            ..test_config()
+        });
+        let manager = JwtManager::new(test_config());
+        let token = other
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+        assert!(manager.verify_bearer_session(&token).is_err());
+        assert!(manager.verify_bearer_token(&token).is_err());
+    }
+
+    #[test]
+    fn test_verify_with_profile_applies_claim_and_algorithm_rules() {
+        let manager = JwtManager::new(test_config());
+        let user_id = Uuid::new_v4();
+        let without_sid = manager
+            .create_tenant_access_token(
+                user_id,
+                "u@example.com",
+                Uuid::new_v4(),
+                "app",
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let with_sid = manager
+            .create_tenant_access_token_with_session(
+                user_id,
+                "u@example.com",
+                Uuid::new_v4(),
+                "app",
+                vec![],
+                vec![],
+                Some("session-1".to_string()),
+            )
+            .unwrap();
+        assert_eq!(peek_token_audience(&with_sid).as_deref(), Some("app"));
+
+        let requires_sid = TokenValidationProfile {
+            required_claims: vec!["sid".to_string()],
+            ..Default::default()
+        };
+        assert!(manager
+            .verify_tenant_access_token_with_profile(&without_sid, None, &requires_sid)
+            .is_err());
+        let claims = manager
+            .verify_tenant_access_token_with_profile(&with_sid, Some("app"), &requires_sid)
+            .unwrap();
+        assert_eq!(claims.sid.as_deref(), Some("session-1"));
+        assert!(manager
+            .verify_tenant_access_token_with_profile(&with_sid, Some("other"), &requires_sid)
+            .is_err());
+
+        let rsa_only = TokenValidationProfile {
+            allowed_algorithms: vec!["RS256".to_string()],
+            ..Default::default()
+        };
+        assert!(manager
+            .verify_tenant_access_token_with_profile(&with_sid, None, &rsa_only)
+            .is_err());
+    }
+
+    #[test]
+    fn test_verify_with_profile_uses_profile_clock_skew() {
+        // Expired 30 seconds ago: outside the default 5s leeway
+        let manager = JwtManager::new(JwtConfig {
+            access_token_ttl_secs: -30,
+            ..test_config()
+        });
+        let token = manager
+            .create_tenant_access_token(
+                Uuid::new_v4(),
+                "u@example.com",
+                Uuid::new_v4(),
+                "app",
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        assert!(manager
+            .verify_tenant_access_token_with_profile(
+                &token,
+                None,
+                &TokenValidationProfile::default()
+            )
+            .is_err());
+        let lenient = TokenValidationProfile {
+            clock_skew_secs: 60,
+            ..Default::default()
+        };
+        assert!(manager
+            .verify_tenant_access_token_with_profile(&token, None, &lenient)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rotate_keys_is_seen_by_clones() {
+        let manager = JwtManager::new(test_config());
+        let clone = manager.clone();
+        let before = manager
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+
+        manager
+            .rotate_keys(&JwtConfig {
+                secret: "rotated-secret-key-for-testing-only".to_string(),
+                issuer: "https://ignored.example".to_string(),
+                ..test_config()
+            })
+            .unwrap();
+
+        // The replaced key still verifies tokens it signed
+        assert!(clone.verify_identity_token(&before).is_ok());
+        let after = manager
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+        let claims = clone.verify_identity_token(&after).unwrap();
+        // The issuer is not part of the key material
+        assert_eq!(claims.iss, "https://auth9.test");
+    }
+
+    #[test]
+    fn test_rotated_out_key_expires_after_grace_period() {
+        let manager = JwtManager::new(test_config());
+        let before = manager
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+        manager
+            .rotate_keys(&JwtConfig {
+                secret: "rotated-secret-key-for-testing-only".to_string(),
+                ..test_config()
+            })
+            .unwrap();
+        assert!(manager.verify_bearer_session(&before).is_ok());
+
+        // Once the grace period has passed only the new key verifies
+        let mut keys = KeyMaterial::clone(&manager.keys.load());
+        if let Some(retired) = keys.retired.as_mut() {
+            retired.until = Utc::now() - Duration::seconds(1);
+        }
+        manager.keys.store(Arc::new(keys));
+        assert!(manager.verify_identity_token(&before).is_err());
+        assert!(manager.verify_bearer_session(&before).is_err());
+    }
+
+    #[test]
+    fn test_rotate_keys_rejects_invalid_key() {
+        let manager = JwtManager::new(test_config());
+        let result = manager.rotate_keys(&JwtConfig {
+            private_key_pem: Some("not a pem".to_string()),
+            ..test_config()
+        });
+        assert!(result.is_err());
+        assert!(!manager.uses_rsa());
+
+        let token = manager
+            .create_identity_token(Uuid::new_v4(), "test@example.com", None)
+            .unwrap();
+        assert!(manager.verify_identity_token(&token).is_ok());
+    }
 }
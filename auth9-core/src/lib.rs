@@ -4,13 +4,18 @@
 //! including REST API, gRPC services, and identity engine integration.
 
 pub mod cache;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod config;
 pub mod crypto;
 pub mod domains;
 pub mod email;
 pub mod error;
+#[cfg(feature = "fault-injection")]
+pub mod fault_injection;
 pub mod grpc;
 pub mod http_support;
+pub mod i18n;
 pub mod identity_engine;
 pub mod jwt;
 pub mod middleware;
@@ -19,9 +24,14 @@ pub mod models;
 pub mod openapi;
 pub mod policy;
 pub mod repository;
+pub mod saga;
+pub mod schema_export;
 pub mod server;
 pub mod state;
 pub mod telemetry;
+#[cfg(feature = "test-harness")]
+pub mod test_harness;
+pub mod webhook;
 
 // Legacy public alias kept to avoid breaking downstream imports abruptly.
 pub use models as domain;
@@ -3,14 +3,30 @@
 //! Commands:
 //!   serve   - Start the API server (default)
 //!   init    - Run migrations and seed default data
-//!   migrate - Run database migrations only
+//!   migrate - Run database migrations only (`--safety-report` checks them instead)
 //!   seed    - Seed default data only
 //!   reset   - Reset database (drop all tables)
 //!   openapi - Export OpenAPI spec to stdout (JSON)
+//!   schema  - Export versioned protobuf/OpenAPI artifacts and check compatibility
+//!   move-tenant-region - Move a tenant's data to another data residency region
+//!   verify-audit-log - Verify the audit log hash chain and signed checkpoints
+//!   backup  - Write an encrypted, consistent snapshot of the database
+//!   restore - Restore a backup, fully or for a single tenant
+//!   reencrypt-secrets - Re-encrypt stored secrets with the tenants' current data keys
+//!   doctor  - Check referential integrity and optionally repair safe findings
 
 use anyhow::Result;
-use auth9_core::{config::Config, migration, server, telemetry};
+use auth9_core::{
+    config::{secrets, Config},
+    crypto::TenantKeyring,
+    domains::security_observability::service::AuditIntegrityService,
+    domains::tenant_access::service::TenantKeyService,
+    migration,
+    repository::tenant_key::TenantKeyRepositoryImpl,
+    schema_export, server, telemetry,
+};
 use clap::{Parser, Subcommand};
+use std::sync::Arc;
 use tracing::{info, warn};
 
 #[derive(Parser)]
@@ -28,20 +44,133 @@ enum Commands {
     /// Run migrations and seed default data (migrate + seed)
     Init,
     /// Run database migrations only
-    Migrate,
+    Migrate {
+        /// Check pending migrations for locking or non-backward-compatible
+        /// changes instead of applying them; exits non-zero on dangerous ones
+        #[arg(long)]
+        safety_report: bool,
+    },
     /// Seed default data only
     Seed,
     /// Reset database (drop all tables)
     Reset,
     /// Export OpenAPI spec to stdout (JSON)
     Openapi,
+    /// Versioned protobuf/OpenAPI schema artifacts
+    Schema {
+        #[command(subcommand)]
+        action: SchemaCommands,
+    },
+    /// Move a tenant's data to another data residency region
+    MoveTenantRegion {
+        /// Tenant ID
+        #[arg(long)]
+        tenant_id: uuid::Uuid,
+        /// Target region (the home region un-pins the tenant)
+        #[arg(long)]
+        to: String,
+        /// Only print the plan
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete the tenant's rows from the source region afterwards
+        #[arg(long)]
+        purge_source: bool,
+    },
+    /// Verify the audit log hash chain and signed checkpoints; exits non-zero
+    /// when tampering or missing entries are found
+    VerifyAuditLog {
+        /// First chain position to verify (default: oldest retained entry)
+        #[arg(long)]
+        from_seq: Option<i64>,
+        /// Last chain position to verify (default: chain head)
+        #[arg(long)]
+        to_seq: Option<i64>,
+    },
+    /// Write an encrypted, consistent snapshot of the database
+    /// (key from BACKUP_ENCRYPTION_KEY)
+    Backup {
+        /// Backup file to write
+        #[arg(long)]
+        out: std::path::PathBuf,
+        /// Data residency region to back up (default: home region)
+        #[arg(long)]
+        region: Option<String>,
+        /// Table to leave out; may be repeated
+        #[arg(long)]
+        exclude_table: Vec<String>,
+    },
+    /// Restore a backup into a database at the same schema version
+    Restore {
+        /// Backup file to read
+        #[arg(long)]
+        file: std::path::PathBuf,
+        /// Data residency region to restore into (default: home region)
+        #[arg(long)]
+        region: Option<String>,
+        /// Only restore this tenant's data
+        #[arg(long)]
+        tenant_id: Option<uuid::Uuid>,
+        /// Only check the backup and print what would be restored
+        #[arg(long)]
+        dry_run: bool,
+        /// Allow a full restore, which replaces every table in the backup
+        #[arg(long)]
+        force: bool,
+    },
+    /// Re-encrypt stored webhook secrets with the tenants' current data keys,
+    /// e.g. after enabling SETTINGS_ENCRYPTION_KEY or changing it (old key in
+    /// SETTINGS_ENCRYPTION_KEY_PREVIOUS)
+    ReencryptSecrets {
+        /// Only this tenant's secrets (default: all secrets)
+        #[arg(long)]
+        tenant_id: Option<uuid::Uuid>,
+        /// Rotate the tenant's data key first (requires --tenant-id)
+        #[arg(long, requires = "tenant_id")]
+        rotate: bool,
+    },
+    /// Check referential integrity (orphaned memberships, role assignments to
+    /// deleted roles, clients without services); exits non-zero when errors
+    /// remain
+    Doctor {
+        /// Repair findings that are safe to repair (rows referencing deleted
+        /// parents)
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SchemaCommands {
+    /// Write descriptors, proto source and OpenAPI with a checksum manifest
+    Export {
+        /// Directory holding one `v<version>` folder per exported version
+        #[arg(long, default_value = "schemas")]
+        out_dir: std::path::PathBuf,
+        /// Replace an existing export of the same version
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Fail if the current schema breaks previously exported versions
+    Check {
+        #[arg(long, default_value = "schemas")]
+        out_dir: std::path::PathBuf,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load configuration first (telemetry init needs config)
     dotenvy::dotenv().ok();
-    let config = Config::from_env()?;
+    // Secrets from Vault / AWS Secrets Manager go straight into the
+    // configuration; the environment is never written to
+    let external_secrets = match secrets::provider_from_env()? {
+        Some(provider) => Some((provider.name(), provider.fetch().await?)),
+        None => None,
+    };
+    let config = match &external_secrets {
+        Some((_, values)) => Config::from_env_with_secrets(values)?,
+        None => Config::from_env()?,
+    };
     config.validate_security()?;
     if !config.is_production() && config.jwt_tenant_access_allowed_audiences.is_empty() {
         warn!("Tenant access token audience allowlist is not configured; REST tenant token aud validation is disabled (non-production). Set JWT_TENANT_ACCESS_ALLOWED_AUDIENCES.");
@@ -49,6 +178,12 @@ async fn main() -> Result<()> {
 
     // Initialise telemetry (metrics + tracing + structured logging)
     let prometheus_handle = telemetry::init(&config.telemetry);
+    if let Some((provider, values)) = &external_secrets {
+        info!(provider, secrets = ?values.keys(), "Loaded secrets from external provider");
+    }
+
+    // Scan secrets once logging is up so findings reach the structured log
+    config.enforce_secret_scan()?;
 
     let cli = Cli::parse();
 
@@ -56,12 +191,49 @@ async fn main() -> Result<()> {
         Some(Commands::Init) => {
             info!("Running init (migrate + seed)...");
             migration::run_migrations(&config).await?;
+            migration::run_regional_migrations(&config).await?;
             migration::seed_services(&config).await?;
             info!("Init completed successfully");
         }
-        Some(Commands::Migrate) => {
+        Some(Commands::Migrate {
+            safety_report: true,
+        }) => {
+            let report = migration::safety_report(&config).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            for finding in &report.findings {
+                warn!(
+                    "{} {} [{}{}]: {}",
+                    finding.version,
+                    finding.description,
+                    finding.rule.as_str(),
+                    if finding.acknowledged {
+                        ", acknowledged"
+                    } else {
+                        ""
+                    },
+                    finding.message
+                );
+            }
+            let dangers = report.dangers().count();
+            if dangers > 0 {
+                anyhow::bail!(
+                    "{} dangerous change(s) in pending migrations; fix them or acknowledge \
+                     them with `-- migrate:allow <rule>`",
+                    dangers
+                );
+            }
+            info!(
+                "{} pending migration(s) are safe to deploy",
+                report.migrations_checked
+            );
+            return Ok(());
+        }
+        Some(Commands::Migrate {
+            safety_report: false,
+        }) => {
             info!("Running database migrations...");
             migration::run_migrations(&config).await?;
+            migration::run_regional_migrations(&config).await?;
             info!("Migrations completed successfully");
         }
         Some(Commands::Seed) => {
@@ -76,6 +248,226 @@ async fn main() -> Result<()> {
             println!("{}", json);
             return Ok(());
         }
+        Some(Commands::Schema { action }) => {
+            let artifacts = schema_export::SchemaArtifacts::current()?;
+            match action {
+                SchemaCommands::Export { out_dir, overwrite } => {
+                    let report = schema_export::export_schemas(&artifacts, &out_dir, overwrite)?;
+                    info!(
+                        "Schema v{} {} {} (checked against: {})",
+                        report.version,
+                        if report.written {
+                            "exported to"
+                        } else {
+                            "unchanged in"
+                        },
+                        report.dir.display(),
+                        if report.checked_against.is_empty() {
+                            "none".to_string()
+                        } else {
+                            report.checked_against.join(", ")
+                        }
+                    );
+                }
+                SchemaCommands::Check { out_dir } => {
+                    let (checked, incompatibilities) =
+                        schema_export::check_compatibility(&artifacts, &out_dir)?;
+                    if !incompatibilities.is_empty() {
+                        anyhow::bail!(schema_export::format_incompatibilities(&incompatibilities));
+                    }
+                    info!(
+                        "Schema v{} is compatible with {} exported version(s)",
+                        artifacts.version,
+                        checked.len()
+                    );
+                }
+            }
+            return Ok(());
+        }
+        Some(Commands::MoveTenantRegion {
+            tenant_id,
+            to,
+            dry_run,
+            purge_source,
+        }) => {
+            let report = migration::move_tenant_region(
+                &config,
+                tenant_id.into(),
+                &to,
+                migration::TenantMoveOptions {
+                    dry_run,
+                    purge_source,
+                },
+            )
+            .await?;
+            for table in &report.tables {
+                info!("  {}: {} rows", table.table, table.rows);
+            }
+            info!(
+                "{} tenant {} from '{}' to '{}' ({} rows{})",
+                if report.dry_run {
+                    "Would move"
+                } else {
+                    "Moved"
+                },
+                report.tenant_id,
+                report.from_region,
+                report.to_region,
+                report.total_rows(),
+                if report.purged { ", source purged" } else { "" }
+            );
+        }
+        Some(Commands::VerifyAuditLog { from_seq, to_seq }) => {
+            let pool = sqlx::mysql::MySqlPoolOptions::new()
+                .max_connections(2)
+                .connect(&config.database.url)
+                .await?;
+            let report = AuditIntegrityService::from_pool(pool, &config.audit_integrity)
+                .verify(from_seq, to_seq)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.valid {
+                anyhow::bail!(
+                    "Audit log integrity check failed: {} issue(s)",
+                    report.issue_count
+                );
+            }
+            info!(
+                "Audit log chain intact ({} entries, {} checkpoints{})",
+                report.entries_checked,
+                report.checkpoints_checked,
+                if report.signatures_verified {
+                    ""
+                } else {
+                    ", signatures not verified"
+                }
+            );
+            return Ok(());
+        }
+        Some(Commands::Backup {
+            out,
+            region,
+            exclude_table,
+        }) => {
+            let manifest = migration::backup(
+                &config,
+                &out,
+                &migration::BackupOptions {
+                    region,
+                    exclude_tables: exclude_table,
+                },
+            )
+            .await?;
+            info!(
+                "Backed up region '{}' to {} ({} tables, {} rows, schema version {:?})",
+                manifest.region,
+                out.display(),
+                manifest.tables.len(),
+                manifest.total_rows(),
+                manifest.schema_version
+            );
+            return Ok(());
+        }
+        Some(Commands::Restore {
+            file,
+            region,
+            tenant_id,
+            dry_run,
+            force,
+        }) => {
+            let report = migration::restore(
+                &config,
+                &file,
+                &migration::RestoreOptions {
+                    region,
+                    tenant_id: tenant_id.map(Into::into),
+                    dry_run,
+                    force,
+                },
+            )
+            .await?;
+            for table in &report.tables {
+                info!("  {}: {} rows", table.table, table.rows);
+            }
+            info!(
+                "{} {} from backup taken {} ({} rows)",
+                if report.dry_run {
+                    "Would restore"
+                } else {
+                    "Restored"
+                },
+                match report.tenant_id {
+                    Some(tenant_id) => format!("tenant {}", tenant_id),
+                    None => "all tables".to_string(),
+                },
+                report.manifest.created_at,
+                report.total_rows()
+            );
+            if !report.dry_run {
+                warn!("Flush Redis (or restart auth9-core) so cached data from before the restore is dropped");
+            }
+            return Ok(());
+        }
+        Some(Commands::ReencryptSecrets { tenant_id, rotate }) => {
+            let Some(keyring) = TenantKeyring::from_config(&config.settings_encryption) else {
+                anyhow::bail!("SETTINGS_ENCRYPTION_KEY is not set or invalid");
+            };
+            let pool = sqlx::mysql::MySqlPoolOptions::new()
+                .max_connections(2)
+                .connect(&config.database.url)
+                .await?;
+            let service =
+                TenantKeyService::new(Arc::new(TenantKeyRepositoryImpl::new(pool)), Some(keyring));
+            let report = match tenant_id {
+                Some(tenant_id) if rotate => service.rotate(tenant_id.into()).await?,
+                tenant_id => service.reencrypt(tenant_id.map(Into::into)).await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if report.failed > 0 {
+                anyhow::bail!(
+                    "{} secret(s) could not be decrypted; set SETTINGS_ENCRYPTION_KEY_PREVIOUS to the key they were sealed with",
+                    report.failed
+                );
+            }
+            info!("Re-encrypted {} secret(s)", report.reencrypted);
+            return Ok(());
+        }
+        Some(Commands::Doctor { fix }) => {
+            let report = migration::doctor(&config, migration::DoctorOptions { fix }).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            for finding in &report.findings {
+                warn!(
+                    "{} [{:?}]: {} row(s){} - {}",
+                    finding.check,
+                    finding.severity,
+                    finding.count,
+                    if finding.fixed > 0 {
+                        format!(", {} fixed", finding.fixed)
+                    } else {
+                        String::new()
+                    },
+                    finding.description
+                );
+            }
+            let open_errors = report.open_errors().count();
+            if open_errors > 0 {
+                anyhow::bail!(
+                    "{} integrity error(s) remain{}",
+                    open_errors,
+                    if fix {
+                        ""
+                    } else {
+                        "; re-run with --fix to repair the fixable ones"
+                    }
+                );
+            }
+            info!(
+                "{} integrity check(s) passed with {} finding(s)",
+                report.checks_run,
+                report.findings.len()
+            );
+            return Ok(());
+        }
         Some(Commands::Reset) => {
             info!("Resetting database (dropping all tables)...");
             migration::reset_database(&config).await?;
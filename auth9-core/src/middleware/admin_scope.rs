@@ -0,0 +1,97 @@
+//! Platform admin scope enforcement
+//!
+//! Runs after authentication on protected routes. When a platform admin calls
+//! an endpoint that maps to an [`AdminScope`], the admin must hold that scope.
+//! Callers who are not platform admins pass through unchanged; their access is
+//! decided by the handler's tenant-level policy checks.
+//!
+//! Every write-scope use and every denial is written to the audit log.
+
+use crate::domains::platform::service::admin_scope::{check_admin_scope, AdminScopeService};
+use crate::http_support::write_audit_log_generic;
+use crate::models::admin_scope::AdminScope;
+use crate::policy::AuthzContext;
+use crate::repository::admin_scope::AdminScopeRepositoryImpl;
+use crate::state::{HasDbPool, HasServices};
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+pub async fn admin_scope_middleware<S: HasServices + HasDbPool>(
+    State(state): State<S>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(scope) = AdminScope::required_for(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let (mut parts, body) = request.into_parts();
+    // The context is cached in request extensions, so handlers reuse the
+    // memberships loaded here.
+    let Ok(authz) = AuthzContext::from_request_parts(&mut parts, &state).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    if !authz.is_platform_admin(&state).await {
+        return next.run(Request::from_parts(parts, body)).await;
+    }
+
+    let is_root = state.config().is_platform_admin_email(&authz.auth().email);
+    let enforced = state.config().platform_admin_scopes_enforced;
+    let decision = if is_root || !enforced {
+        check_admin_scope(scope, is_root, enforced, &[])
+    } else {
+        let service = AdminScopeService::new(Arc::new(AdminScopeRepositoryImpl::new(
+            state.db_pool().clone(),
+        )));
+        match service.scopes_for(authz.user_id()).await {
+            Ok(granted) => check_admin_scope(scope, is_root, enforced, &granted),
+            Err(e) => Err(e),
+        }
+    };
+
+    let outcome = if decision.is_ok() {
+        "allowed"
+    } else {
+        "denied"
+    };
+    metrics::counter!(
+        "auth9_admin_scope_checks_total",
+        "scope" => scope.as_str(),
+        "outcome" => outcome
+    )
+    .increment(1);
+
+    if decision.is_err() || scope.is_write() {
+        let action = if decision.is_ok() {
+            "admin_scope.use"
+        } else {
+            "admin_scope.denied"
+        };
+        let _ = write_audit_log_generic(
+            &state,
+            &parts.headers,
+            action,
+            "admin_scope",
+            None,
+            None,
+            Some(serde_json::json!({
+                "scope": scope.as_str(),
+                "method": parts.method.as_str(),
+                "path": parts.uri.path(),
+                "root": is_root,
+            })),
+        )
+        .await;
+    }
+
+    match decision {
+        Ok(()) => next.run(Request::from_parts(parts, body)).await,
+        Err(e) => e.into_response(),
+    }
+}
@@ -12,8 +12,12 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::jwt::{IdentityClaims, ServiceClientClaims, TenantAccessClaims};
+use crate::config::FailurePolicy;
+use crate::jwt::{IdentityClaims, ServiceClientClaims, TenantAccessClaims, VerifiedToken};
+use crate::models::rbac::permission_matches;
+use crate::models::token_validation::TokenValidationProfile;
 use crate::state::HasServices;
+use crate::telemetry::metrics::record_degraded_operation;
 
 /// Authenticated user information extracted from JWT token
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +36,10 @@ pub struct AuthUser {
     pub roles: Vec<String>,
     /// Permissions (only present for TenantAccess tokens)
     pub permissions: Vec<String>,
+    /// Scope granted by an OIDC authorization (only present for Identity
+    /// tokens issued by the OIDC token endpoint)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 /// Type of JWT token used for authentication
@@ -57,9 +65,10 @@ impl AuthUser {
             email: claims.email,
             token_type: TokenType::Identity,
             tenant_id: None,
-            aud: None,
+            aud: claims.azp,
             roles: vec![],
             permissions: vec![],
+            scope: claims.scope,
         })
     }
 
@@ -81,6 +90,7 @@ impl AuthUser {
             aud: Some(claims.aud),
             roles: vec![],
             permissions: vec![],
+            scope: None,
         })
     }
 
@@ -100,12 +110,16 @@ impl AuthUser {
             aud: Some(claims.aud),
             roles: claims.roles,
             permissions: claims.permissions,
+            scope: None,
         })
     }
 
-    /// Check if user has a specific permission
+    /// Check if user has a specific permission (wildcard grants such as
+    /// `billing:invoices:*` match the codes below them)
     pub fn has_permission(&self, permission: &str) -> bool {
-        self.permissions.iter().any(|p| p == permission)
+        self.permissions
+            .iter()
+            .any(|p| permission_matches(p, permission))
     }
 
     /// Check if user has a specific role
@@ -216,38 +230,47 @@ where
         let token = extract_bearer_token(&parts.headers)?;
         let jwt_manager = state.jwt_manager();
 
-        // Try to validate as service client token first (aud: "auth9-service")
-        // This must come before identity token check because both use the same
-        // signing key, and we need to distinguish service tokens from user tokens.
-        if let Ok(claims) = jwt_manager.verify_service_client_token(token) {
-            return AuthUser::from_service_client_claims(claims);
-        }
-
-        // Try to validate as identity token (aud: "auth9")
-        if let Ok(claims) = jwt_manager.verify_identity_token(token) {
-            return AuthUser::from_identity_claims(claims);
-        }
+        // Service client (aud: "auth9-service"), identity (aud: "auth9") or
+        // tenant access token, picked by the token's type. Tenant access
+        // tokens use the validation profile the auth middleware resolved.
+        let verified = match parts.extensions.get::<TokenValidationProfile>() {
+            Some(profile) => jwt_manager
+                .verify_tenant_access_token_with_profile(token, None, profile)
+                .map(VerifiedToken::TenantAccess),
+            None => jwt_manager.verify_bearer_token(token),
+        };
+        let claims = match verified {
+            Ok(VerifiedToken::Service(claims)) => {
+                return AuthUser::from_service_client_claims(claims)
+            }
+            Ok(VerifiedToken::Identity(claims)) => return AuthUser::from_identity_claims(claims),
+            Ok(VerifiedToken::TenantAccess(claims)) => claims,
+            Err(_) => {
+                return Err(AuthError::InvalidToken(
+                    "Token validation failed".to_string(),
+                ))
+            }
+        };
 
-        // Try to validate as tenant access token (audience validated via cache)
-        if let Ok(claims) = jwt_manager.verify_tenant_access_token_any_audience(token) {
-            // Audience validation: fail-closed if cache unavailable or audience invalid
-            match state.maybe_cache() {
-                Some(cache) => match cache.is_valid_audience(&claims.aud).await {
-                    Ok(true) => return AuthUser::from_tenant_access_claims(claims),
-                    Ok(false) => {
-                        return Err(AuthError::InvalidToken(
-                            "Token audience not recognized".to_string(),
-                        ));
+        // Audience validation: cache errors follow the auth failure policy
+        // (fail-closed by default); a missing cache always fails closed.
+        match state.maybe_cache() {
+            Some(cache) => match cache.is_valid_audience(&claims.aud).await {
+                Ok(true) => AuthUser::from_tenant_access_claims(claims),
+                Ok(false) => Err(AuthError::InvalidToken(
+                    "Token audience not recognized".to_string(),
+                )),
+                Err(_) => {
+                    let policy = state.config().dependency_policy.auth;
+                    record_degraded_operation("redis", "audience_validation", policy);
+                    if policy == FailurePolicy::FailOpen {
+                        return AuthUser::from_tenant_access_claims(claims);
                     }
-                    Err(_) => return Err(AuthError::ServiceUnavailable),
-                },
-                None => return Err(AuthError::ServiceUnavailable),
-            }
+                    Err(AuthError::ServiceUnavailable)
+                }
+            },
+            None => Err(AuthError::ServiceUnavailable),
         }
-
-        Err(AuthError::InvalidToken(
-            "Token validation failed".to_string(),
-        ))
     }
 }
 
@@ -295,6 +318,8 @@ mod tests {
             iat: 1000000,
             exp: 1003600,
             extra: None,
+            scope: None,
+            azp: None,
         };
 
         let user = AuthUser::from_identity_claims(claims).unwrap();
@@ -356,6 +381,8 @@ mod tests {
             iat: 1000000,
             exp: 1003600,
             extra: None,
+            scope: None,
+            azp: None,
         };
 
         let result = AuthUser::from_identity_claims(claims);
@@ -372,6 +399,7 @@ mod tests {
             aud: Some("test-client".to_string()),
             roles: vec!["admin".to_string()],
             permissions: vec!["user:read".to_string(), "user:write".to_string()],
+            scope: None,
         };
 
         assert!(user.has_permission("user:read"));
@@ -379,6 +407,24 @@ mod tests {
         assert!(!user.has_permission("user:delete"));
     }
 
+    #[test]
+    fn test_auth_user_has_permission_wildcard() {
+        let user = AuthUser {
+            user_id: Uuid::new_v4(),
+            email: "test@example.com".to_string(),
+            token_type: TokenType::TenantAccess,
+            tenant_id: Some(Uuid::new_v4()),
+            aud: Some("test-client".to_string()),
+            roles: vec![],
+            permissions: vec!["billing:invoices:*".to_string()],
+            scope: None,
+        };
+
+        assert!(user.has_permission("billing:invoices:read"));
+        assert!(user.has_permission("billing:invoices:void"));
+        assert!(!user.has_permission("billing:payments:read"));
+    }
+
     #[test]
     fn test_auth_user_has_role() {
         let user = AuthUser {
@@ -389,6 +435,7 @@ mod tests {
             aud: Some("test-client".to_string()),
             roles: vec!["admin".to_string(), "user".to_string()],
             permissions: vec![],
+            scope: None,
         };
 
         assert!(user.has_role("admin"));
@@ -406,6 +453,7 @@ mod tests {
             aud: Some("test-client".to_string()),
             roles: vec![],
             permissions: vec!["user:read".to_string()],
+            scope: None,
         };
 
         assert!(user.has_any_permission(&["user:read", "user:write"]));
@@ -422,6 +470,7 @@ mod tests {
             aud: Some("test-client".to_string()),
             roles: vec![],
             permissions: vec!["user:read".to_string(), "user:write".to_string()],
+            scope: None,
         };
 
         assert!(user.has_all_permissions(&["user:read", "user:write"]));
@@ -492,6 +541,7 @@ mod tests {
             aud: None,
             roles: vec![],
             permissions: vec![],
+            scope: None,
         };
 
         let cloned = user.clone();
@@ -509,6 +559,7 @@ mod tests {
             aud: None,
             roles: vec![],
             permissions: vec![],
+            scope: None,
         };
 
         let debug_str = format!("{:?}", user);
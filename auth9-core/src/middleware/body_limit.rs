@@ -0,0 +1,175 @@
+//! Per-route request body size limits
+//!
+//! Every request body is capped at `HTTP_BODY_LIMIT_BYTES`, except the
+//! dedicated bulk import endpoints which get `HTTP_BULK_BODY_LIMIT_BYTES`.
+//! `HTTP_ROUTE_BODY_LIMITS` overrides individual routes. The limit applies
+//! to the decoded body, so a small compressed payload cannot expand past it.
+
+use crate::config::ServerConfig;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::Limited;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Bulk import endpoints that accept large payloads
+const BULK_ENDPOINTS: &[&str] = &[
+    "POST:/api/v1/tenants/{tenant_id}/users/import",
+    "POST:/api/v1/scim/v2/Bulk",
+];
+
+#[derive(Clone)]
+pub struct BodyLimitState {
+    default_limit: usize,
+    routes: Arc<HashMap<String, usize>>,
+}
+
+impl BodyLimitState {
+    pub fn from_config(config: &ServerConfig) -> Self {
+        let mut routes: HashMap<String, usize> = BULK_ENDPOINTS
+            .iter()
+            .map(|endpoint| (endpoint.to_string(), config.bulk_body_limit_bytes))
+            .collect();
+        routes.extend(
+            config
+                .route_body_limits
+                .iter()
+                .map(|(route, limit)| (route.clone(), *limit)),
+        );
+        Self {
+            default_limit: config.body_limit_bytes,
+            routes: Arc::new(routes),
+        }
+    }
+
+    /// Limit for `METHOD:/route/{template}`
+    pub fn limit_for(&self, endpoint: &str) -> usize {
+        self.routes
+            .get(endpoint)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Largest limit of any route, the ceiling for body extractors
+    pub fn max_limit(&self) -> usize {
+        self.routes
+            .values()
+            .copied()
+            .fold(self.default_limit, usize::max)
+    }
+}
+
+/// Reject bodies over the route's limit: early from `Content-Length`, and
+/// while streaming for chunked or decompressed bodies.
+pub async fn body_limit_middleware(
+    State(state): State<BodyLimitState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let endpoint = format!(
+        "{}:{}",
+        request.method(),
+        request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|p| p.as_str())
+            .unwrap_or_else(|| request.uri().path())
+    );
+    let limit = state.limit_for(&endpoint);
+
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > limit as u64) {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    }
+
+    let (parts, body) = request.into_parts();
+    let request = Request::from_parts(parts, Body::new(Limited::new(body, limit)));
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app(config: &ServerConfig) -> Router {
+        Router::new()
+            .route("/api/v1/users", post(|body: String| async move { body }))
+            .route(
+                "/api/v1/tenants/{tenant_id}/users/import",
+                post(|body: String| async move { body.len().to_string() }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                BodyLimitState::from_config(config),
+                body_limit_middleware,
+            ))
+    }
+
+    fn config() -> ServerConfig {
+        ServerConfig {
+            body_limit_bytes: 16,
+            bulk_body_limit_bytes: 64,
+            ..ServerConfig::default()
+        }
+    }
+
+    /// Send `body`, declaring its length unless `chunked`
+    async fn send(app: Router, uri: &str, body: &str, chunked: bool) -> StatusCode {
+        let mut request = Request::post(uri);
+        if !chunked {
+            request = request.header(header::CONTENT_LENGTH, body.len());
+        }
+        app.oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_default_limit_rejects_oversized_body() {
+        let big = "x".repeat(32);
+        assert_eq!(
+            send(app(&config()), "/api/v1/users", "small", false).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            send(app(&config()), "/api/v1/users", &big, false).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            send(app(&config()), "/api/v1/users", &big, true).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bulk_import_and_route_overrides() {
+        let uri = format!("/api/v1/tenants/{}/users/import", uuid::Uuid::new_v4());
+        let big = "x".repeat(32);
+        assert_eq!(
+            send(app(&config()), &uri, &big, false).await,
+            StatusCode::OK
+        );
+
+        let mut config = config();
+        config.route_body_limits.insert(
+            "POST:/api/v1/tenants/{tenant_id}/users/import".to_string(),
+            8,
+        );
+        assert_eq!(
+            send(app(&config), &uri, &big, true).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(BodyLimitState::from_config(&config).max_limit(), 16);
+    }
+}
@@ -25,6 +25,7 @@ const DEFAULT_PROTECTED_ENDPOINTS: &[&str] = &[
     "POST:/api/v1/auth/register",
     "POST:/api/v1/auth/forgot-password",
     "POST:/api/v1/hosted-login/start-password-reset",
+    "POST:/api/v1/auth/account-recovery/requests",
     "POST:/api/v1/auth/email-otp/send",
     "POST:/api/v1/auth/sms-otp/send",
 ];
@@ -0,0 +1,92 @@
+//! Deprecation signalling for deprecated API endpoints and fields
+//!
+//! Requests that rely on an entry of the deprecation registry get
+//! `Deprecation` and `Sunset` response headers, and each use is counted per
+//! calling client so the team can tell when a deprecated surface is safe to
+//! remove.
+
+use crate::http_support::deprecation::{
+    deprecation_header, matching, sunset_header, API_DEPRECATIONS,
+};
+use crate::middleware::require_auth::AuthenticatedClient;
+use axum::{body::Body, http::Request, middleware::Next, response::Response};
+use metrics::counter;
+
+/// Client label for requests without an authenticated client
+const ANONYMOUS_CLIENT: &str = "anonymous";
+
+pub async fn deprecation_middleware(request: Request<Body>, next: Next) -> Response {
+    let matched = matching(
+        API_DEPRECATIONS,
+        request.method(),
+        request.uri().path(),
+        request.uri().query(),
+    );
+    let mut response = next.run(request).await;
+    if matched.is_empty() {
+        return response;
+    }
+
+    // The authentication layer runs inside this one, so the caller's
+    // client_id comes back on the response
+    let client_id = response
+        .extensions()
+        .get::<AuthenticatedClient>()
+        .map(|client| client.0.clone())
+        .unwrap_or_else(|| ANONYMOUS_CLIENT.to_string());
+    for deprecation in &matched {
+        counter!(
+            "auth9_deprecated_api_usage_total",
+            "kind" => deprecation.kind(),
+            "name" => deprecation.name(),
+            "client_id" => client_id.clone()
+        )
+        .increment(1);
+    }
+
+    let headers = response.headers_mut();
+    if let Some(value) = deprecation_header(&matched) {
+        headers.insert("Deprecation", value);
+    }
+    if let Some(value) = sunset_header(&matched) {
+        headers.insert("Sunset", value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/api/v1/security/alerts", get(|| async { "[]" }))
+            .layer(axum::middleware::from_fn(deprecation_middleware))
+    }
+
+    async fn call(uri: &str) -> Response {
+        app()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_query_param_gets_headers() {
+        let response = call("/api/v1/security/alerts?unresolved_only=true").await;
+        assert!(response.headers().contains_key("deprecation"));
+        assert_eq!(
+            response.headers().get("sunset").unwrap(),
+            "Fri, 16 Apr 2027 00:00:00 GMT"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_usage_has_no_headers() {
+        let response = call("/api/v1/security/alerts?resolved=false").await;
+        assert!(!response.headers().contains_key("deprecation"));
+        assert!(!response.headers().contains_key("sunset"));
+    }
+}
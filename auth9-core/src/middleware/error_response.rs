@@ -22,7 +22,10 @@ pub async fn normalize_error_response(request: Request<Body>, next: Next) -> Res
     let status = response.status();
 
     // Health/readiness endpoints return their own plain-text responses - skip normalization
-    if uri == "/health" || uri == "/ready" {
+    if matches!(
+        uri.as_str(),
+        "/health" | "/ready" | "/health/live" | "/health/ready"
+    ) {
         return response;
     }
 
@@ -58,6 +61,8 @@ fn generic_error_response(status: StatusCode) -> Response {
         StatusCode::NOT_FOUND => "not_found",
         StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
         StatusCode::CONFLICT => "conflict",
+        StatusCode::PRECONDITION_FAILED => "precondition_failed",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
         StatusCode::UNPROCESSABLE_ENTITY => "validation_error",
         StatusCode::TOO_MANY_REQUESTS => "rate_limited",
         StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
@@ -72,6 +77,8 @@ fn generic_error_response(status: StatusCode) -> Response {
         StatusCode::NOT_FOUND => "Not found",
         StatusCode::METHOD_NOT_ALLOWED => "Method not allowed",
         StatusCode::CONFLICT => "Resource conflict",
+        StatusCode::PRECONDITION_FAILED => "Precondition failed",
+        StatusCode::PAYLOAD_TOO_LARGE => "Request body too large",
         StatusCode::UNPROCESSABLE_ENTITY => "Validation error",
         StatusCode::TOO_MANY_REQUESTS => "Too many requests",
         StatusCode::UNSUPPORTED_MEDIA_TYPE => "Unsupported content type",
@@ -0,0 +1,187 @@
+//! Error message localization middleware
+//!
+//! Negotiates a locale from the request's `Accept-Language` header and
+//! translates the `message` / `error_description` fields of JSON error
+//! responses using the i18n catalogs. Machine-readable fields (`error`,
+//! `code`, `details`) are never touched, and messages without a catalog
+//! entry (e.g. ones carrying identifiers) are passed through in English.
+
+use crate::i18n::{self, Locale, DEFAULT_LOCALE};
+use axum::{
+    body::Body,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+/// Error responses larger than this are passed through untranslated
+const MAX_TRANSLATABLE_BODY: usize = 64 * 1024;
+
+/// JSON fields carrying human-readable error text
+const MESSAGE_FIELDS: [&str; 2] = ["message", "error_description"];
+
+/// Middleware that localizes JSON error messages for the negotiated locale.
+pub async fn localize_error_response(request: Request<Body>, next: Next) -> Response {
+    let locale = i18n::negotiate_headers(request.headers()).unwrap_or(DEFAULT_LOCALE);
+    let response = next.run(request).await;
+
+    let status = response.status();
+    if locale == DEFAULT_LOCALE || (!status.is_client_error() && !status.is_server_error()) {
+        return response;
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_TRANSLATABLE_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            // Body exceeded the limit mid-read; it can no longer be forwarded intact
+            tracing::warn!("Error response too large to localize");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let Some(translated) = translate_body(locale, &bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(locale.as_str()),
+    );
+    Response::from_parts(parts, Body::from(translated))
+}
+
+/// Re-serialized body if any message field was translated
+fn translate_body(locale: Locale, bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut value: serde_json::Value = serde_json::from_slice(bytes).ok()?;
+    let object = value.as_object_mut()?;
+
+    let mut changed = false;
+    for field in MESSAGE_FIELDS {
+        if let Some(serde_json::Value::String(message)) = object.get_mut(field) {
+            if let Some(translated) = i18n::translate_error_message(locale, message) {
+                *message = translated;
+                changed = true;
+            }
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+    serde_json::to_vec(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Json, Router};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    fn app(status: StatusCode, body: serde_json::Value) -> Router {
+        Router::new()
+            .route(
+                "/test",
+                get(move || {
+                    let body = body.clone();
+                    async move { (status, Json(body)) }
+                }),
+            )
+            .layer(axum::middleware::from_fn(localize_error_response))
+    }
+
+    async fn call(app: Router, accept_language: Option<&str>) -> Response {
+        let mut request = Request::builder().uri("/test");
+        if let Some(value) = accept_language {
+            request = request.header(header::ACCEPT_LANGUAGE, value);
+        }
+        app.oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_translates_known_error_message() {
+        let response = call(
+            app(
+                StatusCode::UNAUTHORIZED,
+                json!({"error": "unauthorized", "message": "Invalid email or password."}),
+            ),
+            Some("ja,en;q=0.5"),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers().get(header::CONTENT_LANGUAGE).unwrap(),
+            "ja"
+        );
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "unauthorized");
+        assert_eq!(
+            body["message"],
+            i18n::lookup(Locale::Ja, "error-invalid-credentials").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_translates_oauth_error_description() {
+        let response = call(
+            app(
+                StatusCode::BAD_REQUEST,
+                json!({
+                    "error": "invalid_grant",
+                    "error_description": "Invalid or expired authorization code"
+                }),
+            ),
+            Some("zh-CN"),
+        )
+        .await;
+
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "invalid_grant");
+        assert_eq!(
+            body["error_description"],
+            i18n::lookup(Locale::ZhCn, "error-invalid-authorization-code").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_locale_and_unknown_messages_pass_through() {
+        let body = json!({"error": "unauthorized", "message": "Invalid email or password."});
+        let response = call(app(StatusCode::UNAUTHORIZED, body.clone()), None).await;
+        assert!(response.headers().get(header::CONTENT_LANGUAGE).is_none());
+        assert_eq!(json_body(response).await, body);
+
+        let body = json!({"error": "not_found", "message": "Tenant 42 not found"});
+        let response = call(app(StatusCode::NOT_FOUND, body.clone()), Some("ja")).await;
+        assert!(response.headers().get(header::CONTENT_LANGUAGE).is_none());
+        assert_eq!(json_body(response).await, body);
+    }
+
+    #[tokio::test]
+    async fn test_success_response_untouched() {
+        let body = json!({"message": "Not found"});
+        let response = call(app(StatusCode::OK, body.clone()), Some("ja")).await;
+        assert_eq!(json_body(response).await, body);
+    }
+}
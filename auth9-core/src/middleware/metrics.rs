@@ -3,8 +3,9 @@
 //! Implemented as a Tower Layer/Service to avoid axum's `from_fn` layer count limits.
 //! Combines request ID propagation and metrics recording.
 
+use crate::telemetry::exemplars::record_latency;
 use axum::{body::Body, http::Request, response::Response};
-use metrics::{counter, gauge, histogram};
+use metrics::{counter, gauge};
 use std::{
     future::Future,
     pin::Pin,
@@ -73,8 +74,11 @@ where
 
                 counter!("auth9_http_requests_total", "method" => method.clone(), "path" => path.clone(), "status" => status)
                     .increment(1);
-                histogram!("auth9_http_request_duration_seconds", "method" => method, "path" => path)
-                    .record(duration);
+                record_latency(
+                    "auth9_http_request_duration_seconds",
+                    &[("method", method), ("path", path)],
+                    duration,
+                );
                 gauge!("auth9_http_requests_in_flight").decrement(1.0);
 
                 // Echo request ID in response headers
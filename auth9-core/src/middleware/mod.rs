@@ -5,25 +5,46 @@
 //! - Rate limiting middleware
 //! - Security headers middleware
 //! - Authentication enforcement middleware
+//! - Platform admin scope enforcement
+//! - Custom domain (Host header) tenant routing
+//! - Error message localization (Accept-Language)
+//! - Nonce/timestamp replay protection for signed public endpoints
+//! - Deprecation/Sunset headers for deprecated endpoints and fields
+//! - Per-route request body size limits
+//! - Permission-bound masking of emails and IPs in responses
 
+pub mod admin_scope;
 pub mod auth;
+pub mod body_limit;
 pub mod captcha;
 pub mod client_ip;
+pub mod custom_domain;
+pub mod deprecation;
 pub mod error_response;
+pub mod locale;
 pub mod metrics;
 pub mod path_guard;
 pub mod rate_limit;
+pub mod replay;
 pub mod require_auth;
+pub mod response_masking;
 pub mod scim_auth;
 pub mod security_headers;
 pub mod step_up;
 pub mod trace;
 
+pub use admin_scope::admin_scope_middleware;
 pub use auth::{AuthUser, OptionalAuth, RequireAuth};
+pub use body_limit::{body_limit_middleware, BodyLimitState};
 pub use captcha::{captcha_middleware, CaptchaLayer, CaptchaState};
 pub use client_ip::inject_client_ip;
+pub use custom_domain::{custom_domain_middleware, CustomDomainRoutingState};
+pub use deprecation::deprecation_middleware;
 pub use error_response::normalize_error_response;
+pub use locale::localize_error_response;
 pub use path_guard::path_guard_middleware;
 pub use rate_limit::{RateLimitLayer, RateLimitState};
+pub use replay::{replay_protection_middleware, ReplayProtectionState};
 pub use require_auth::{require_auth_middleware, AuthMiddlewareState};
+pub use response_masking::response_masking_middleware;
 pub use security_headers::security_headers_middleware;
@@ -2,14 +2,21 @@
 //!
 //! Implements sliding window rate limiting with Redis backend.
 //! Supports tenant-level and per-client rate limiting.
+//!
+//! Responses carry the `RateLimit-*` headers of the IETF rate limit headers
+//! draft (plus the legacy `X-RateLimit-*` ones), and the caller's buckets can
+//! be inspected through [`RateLimitCaller`].
 
+use crate::config::FailurePolicy;
 use crate::jwt::JwtManager;
+use crate::models::rate_limit::{RateLimitBucket, RateLimitStatus};
+use crate::telemetry::metrics::record_degraded_operation;
 use axum::{
     body::Body,
     extract::MatchedPath,
     extract::State,
     http::header::AUTHORIZATION,
-    http::{Request, StatusCode},
+    http::{HeaderMap, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
@@ -82,6 +89,16 @@ pub enum RateLimitKey {
 }
 
 impl RateLimitKey {
+    /// What the caller is counted by, as reported to the caller
+    pub fn subject(&self) -> &'static str {
+        match self {
+            RateLimitKey::Tenant { .. } => "tenant",
+            RateLimitKey::TenantClient { .. } => "client",
+            RateLimitKey::Ip { .. } => "ip",
+            RateLimitKey::User { .. } => "user",
+        }
+    }
+
     /// Build the Redis key for this rate limit
     pub fn to_redis_key(&self, endpoint: &str) -> String {
         match self {
@@ -163,6 +180,9 @@ pub struct RateLimitState {
     redis: Option<ConnectionManager>,
     jwt_manager: Option<JwtManager>,
     fallback: InMemoryRateLimiter,
+    /// Behavior when Redis is unavailable: fail-open uses the in-memory fallback,
+    /// fail-closed rejects with 503
+    failure_policy: FailurePolicy,
 }
 
 impl RateLimitState {
@@ -173,6 +193,7 @@ impl RateLimitState {
             redis: Some(redis),
             jwt_manager: Some(jwt_manager),
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         }
     }
 
@@ -186,9 +207,16 @@ impl RateLimitState {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         }
     }
 
+    /// Set the behavior when Redis is unavailable
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
     /// Get a reference to the Redis connection (if available)
     pub fn redis_connection(&self) -> Option<&ConnectionManager> {
         self.redis.as_ref()
@@ -234,6 +262,7 @@ impl RateLimitState {
                 limit: u64::MAX,
                 remaining: u64::MAX,
                 reset_at: 0,
+                window_secs: 0,
             });
         }
 
@@ -287,8 +316,11 @@ impl RateLimitState {
             "#,
         );
 
-        let result: Vec<i64> = tokio::time::timeout(
-            std::time::Duration::from_millis(500),
+        let result: Vec<i64> = tokio::time::timeout(std::time::Duration::from_millis(500), async {
+            #[cfg(feature = "fault-injection")]
+            crate::fault_injection::inject(crate::fault_injection::FaultTarget::Redis)
+                .await
+                .map_err(|fault| redis::RedisError::from(std::io::Error::from(fault)))?;
             script
                 .key(&redis_key)
                 .arg(window_start as i64)
@@ -296,8 +328,9 @@ impl RateLimitState {
                 .arg(&request_id)
                 .arg(max_requests as i64)
                 .arg((window_secs + 1) as i64)
-                .invoke_async(&mut conn),
-        )
+                .invoke_async(&mut conn)
+                .await
+        })
         .await
         .map_err(|_| RateLimitError::RedisError("Redis operation timed out".to_string()))?
         .map_err(|e| RateLimitError::RedisError(e.to_string()))?;
@@ -311,6 +344,7 @@ impl RateLimitState {
                 limit: max_requests,
                 remaining: max_requests.saturating_sub(current_count + 1),
                 reset_at: now + window_secs,
+                window_secs,
             })
         } else {
             let oldest_score = result[2] as u64;
@@ -325,9 +359,142 @@ impl RateLimitState {
                 limit: max_requests,
                 remaining: 0,
                 reset_at: now + retry_after,
+                window_secs,
             })
         }
     }
+
+    /// Report the buckets `key` has requests in, with the quota left in each.
+    ///
+    /// Buckets are found by scanning the caller's own key prefix, so the cost
+    /// grows with the number of endpoints the caller has used, not with the
+    /// total number of callers.
+    pub async fn inspect(
+        &self,
+        key: &RateLimitKey,
+        tenant_id: Option<&str>,
+    ) -> Result<RateLimitStatus, RateLimitError> {
+        let mut status = RateLimitStatus {
+            enabled: self.is_enabled(),
+            subject: key.subject().to_string(),
+            default_limit: self.config.default.requests,
+            default_window_secs: self.config.default.window_secs,
+            buckets: Vec::new(),
+        };
+        if !status.enabled {
+            return Ok(status);
+        }
+        let redis = self.redis.as_ref().ok_or(RateLimitError::NotConfigured)?;
+        let multiplier = tenant_id
+            .map(|t| self.get_tenant_multiplier(t))
+            .unwrap_or(1.0);
+        status.default_limit = (self.config.default.requests as f64 * multiplier) as u64;
+
+        let prefix = key.to_redis_key("");
+        let pattern = format!("{}*", prefix);
+        let mut conn = redis.clone();
+        let scan = async {
+            let mut redis_keys: Vec<String> = Vec::new();
+            let mut cursor: u64 = 0;
+            loop {
+                let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg(&pattern)
+                    .arg("COUNT")
+                    .arg(100)
+                    .query_async(&mut conn)
+                    .await?;
+                redis_keys.extend(batch);
+                cursor = next;
+                if cursor == 0 || redis_keys.len() >= MAX_INSPECTED_BUCKETS {
+                    break;
+                }
+            }
+            redis_keys.truncate(MAX_INSPECTED_BUCKETS);
+            redis_keys.sort();
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            let mut buckets = Vec::with_capacity(redis_keys.len());
+            for redis_key in redis_keys {
+                let Some(endpoint) = redis_key.strip_prefix(&prefix) else {
+                    continue;
+                };
+                // A tenant's own keys share the prefix of its clients' keys
+                if endpoint.starts_with("client:") && matches!(key, RateLimitKey::Tenant { .. }) {
+                    continue;
+                }
+                let rule = self
+                    .config
+                    .endpoints
+                    .get(endpoint)
+                    .unwrap_or(&self.config.default);
+                let window_start = now.saturating_sub(rule.window_secs);
+                let (count, oldest): (u64, Vec<(String, u64)>) = redis::pipe()
+                    .cmd("ZCOUNT")
+                    .arg(&redis_key)
+                    .arg(format!("({}", window_start))
+                    .arg("+inf")
+                    .cmd("ZRANGEBYSCORE")
+                    .arg(&redis_key)
+                    .arg(format!("({}", window_start))
+                    .arg("+inf")
+                    .arg("WITHSCORES")
+                    .arg("LIMIT")
+                    .arg(0)
+                    .arg(1)
+                    .query_async(&mut conn)
+                    .await?;
+                if count == 0 {
+                    continue;
+                }
+                let limit = (rule.requests as f64 * multiplier) as u64;
+                let reset_at = oldest
+                    .first()
+                    .map(|(_, score)| score + rule.window_secs)
+                    .unwrap_or(now + rule.window_secs);
+                buckets.push(RateLimitBucket {
+                    endpoint: endpoint.to_string(),
+                    limit,
+                    remaining: limit.saturating_sub(count),
+                    window_secs: rule.window_secs,
+                    reset_at,
+                });
+            }
+            Ok::<_, redis::RedisError>(buckets)
+        };
+        status.buckets = tokio::time::timeout(std::time::Duration::from_secs(2), scan)
+            .await
+            .map_err(|_| RateLimitError::RedisError("Redis operation timed out".to_string()))?
+            .map_err(|e| RateLimitError::RedisError(e.to_string()))?;
+        Ok(status)
+    }
+}
+
+/// Upper bound on buckets reported by [`RateLimitState::inspect`]
+const MAX_INSPECTED_BUCKETS: usize = 200;
+
+/// The rate limit identity of the current request.
+///
+/// Inserted into request extensions by [`rate_limit_middleware`] so handlers
+/// can report the caller's quota without re-deriving its key.
+#[derive(Clone)]
+pub struct RateLimitCaller {
+    state: RateLimitState,
+    key: RateLimitKey,
+    tenant_id: Option<String>,
+}
+
+impl RateLimitCaller {
+    /// The caller's buckets and remaining quota
+    pub async fn status(&self) -> Result<RateLimitStatus, RateLimitError> {
+        self.state
+            .inspect(&self.key, self.tenant_id.as_deref())
+            .await
+    }
 }
 
 /// Result of rate limit check
@@ -341,6 +508,8 @@ pub struct RateLimitResult {
     pub remaining: u64,
     /// Unix timestamp when the rate limit resets
     pub reset_at: u64,
+    /// Length of the window the limit applies to
+    pub window_secs: u64,
 }
 
 /// Rate limit errors
@@ -462,6 +631,33 @@ impl IntoResponse for RateLimitExceededResponse {
     }
 }
 
+/// Add the `RateLimit-*` headers (IETF draft: limit, remaining, seconds
+/// until reset, and the policy) and their legacy `X-RateLimit-*` forms
+fn insert_rate_limit_headers(headers: &mut HeaderMap, result: &RateLimitResult) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let reset_in = result.reset_at.saturating_sub(now);
+    let values = [
+        ("RateLimit-Limit", result.limit.to_string()),
+        ("RateLimit-Remaining", result.remaining.to_string()),
+        ("RateLimit-Reset", reset_in.to_string()),
+        (
+            "RateLimit-Policy",
+            format!("{};w={}", result.limit, result.window_secs),
+        ),
+        ("X-RateLimit-Limit", result.limit.to_string()),
+        ("X-RateLimit-Remaining", result.remaining.to_string()),
+        ("X-RateLimit-Reset", result.reset_at.to_string()),
+    ];
+    for (name, value) in values {
+        if let Ok(value) = value.parse() {
+            headers.insert(name, value);
+        }
+    }
+}
+
 /// Axum layer for rate limiting
 #[derive(Clone)]
 pub struct RateLimitLayer {
@@ -480,14 +676,9 @@ impl RateLimitLayer {
 /// Returns 429 Too Many Requests if rate limit is exceeded.
 pub async fn rate_limit_middleware(
     State(rate_limit): State<RateLimitState>,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
-    if !rate_limit.is_enabled() {
-        return next.run(request).await;
-    }
-
-    let endpoint = endpoint_key(&request);
     let (key, tenant_id) =
         if let Some((key, tenant_id)) = extract_key_from_verified_token(&rate_limit, &request) {
             (key, tenant_id)
@@ -499,6 +690,17 @@ pub async fn rate_limit_middleware(
                 None,
             )
         };
+    request.extensions_mut().insert(RateLimitCaller {
+        state: rate_limit.clone(),
+        key: key.clone(),
+        tenant_id: tenant_id.clone(),
+    });
+
+    if !rate_limit.is_enabled() {
+        return next.run(request).await;
+    }
+
+    let endpoint = endpoint_key(&request);
 
     match rate_limit
         .check_and_increment(&key, &endpoint, tenant_id.as_deref())
@@ -506,21 +708,7 @@ pub async fn rate_limit_middleware(
     {
         Ok(result) if result.allowed => {
             let mut response = next.run(request).await;
-
-            // Add rate limit headers
-            response.headers_mut().insert(
-                "X-RateLimit-Limit",
-                result.limit.to_string().parse().unwrap(),
-            );
-            response.headers_mut().insert(
-                "X-RateLimit-Remaining",
-                result.remaining.to_string().parse().unwrap(),
-            );
-            response.headers_mut().insert(
-                "X-RateLimit-Reset",
-                result.reset_at.to_string().parse().unwrap(),
-            );
-
+            insert_rate_limit_headers(response.headers_mut(), &result);
             response
         }
         Ok(result) => {
@@ -533,12 +721,41 @@ pub async fn rate_limit_middleware(
                 .as_secs();
             let retry_after = result.reset_at.saturating_sub(now);
 
-            RateLimitExceededResponse {
+            let mut response = RateLimitExceededResponse {
                 error: "Rate limit exceeded".to_string(),
                 code: "RATE_LIMITED".to_string(),
                 retry_after,
             }
-            .into_response()
+            .into_response();
+            insert_rate_limit_headers(response.headers_mut(), &result);
+            response
+        }
+        Err(e) if rate_limit.failure_policy == FailurePolicy::FailClosed => {
+            tracing::error!(
+                endpoint = %endpoint,
+                error = ?e,
+                "Redis unavailable for rate limiting, rejecting request (fail-closed)"
+            );
+            record_degraded_operation("redis", "rate_limit", FailurePolicy::FailClosed);
+            metrics::counter!(
+                "auth9_rate_limit_unavailable_total",
+                "endpoint" => endpoint.clone(),
+                "mode" => "fail_close"
+            )
+            .increment(1);
+            let mut response = Response::new(
+                serde_json::json!({
+                    "error": "service_unavailable",
+                    "message": "Rate limiting service temporarily unavailable"
+                })
+                .to_string()
+                .into(),
+            );
+            *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+            response
+                .headers_mut()
+                .insert("Content-Type", "application/json".parse().unwrap());
+            response
         }
         Err(e) => {
             tracing::warn!(
@@ -546,6 +763,7 @@ pub async fn rate_limit_middleware(
                 error = ?e,
                 "Redis unavailable for rate limiting, using in-memory fallback"
             );
+            record_degraded_operation("redis", "rate_limit", FailurePolicy::FailOpen);
 
             // Fail-open: use in-memory fallback rate limiter for all endpoints
             let redis_key = key.to_redis_key(&endpoint);
@@ -574,12 +792,27 @@ pub async fn rate_limit_middleware(
                     "mode" => "fallback_throttle"
                 )
                 .increment(1);
-                RateLimitExceededResponse {
+                let mut response = RateLimitExceededResponse {
                     error: "Rate limit exceeded".to_string(),
                     code: "RATE_LIMITED".to_string(),
                     retry_after: rule.window_secs,
                 }
-                .into_response()
+                .into_response();
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                insert_rate_limit_headers(
+                    response.headers_mut(),
+                    &RateLimitResult {
+                        allowed: false,
+                        limit: max_requests,
+                        remaining: 0,
+                        reset_at: now + rule.window_secs,
+                        window_secs: rule.window_secs,
+                    },
+                );
+                response
             }
         }
     }
@@ -670,6 +903,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
 
         let _rule = state.get_rule("POST", "/api/v1/auth/token");
@@ -700,6 +934,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
 
         assert_eq!(state.get_tenant_multiplier("premium-tenant"), 2.0);
@@ -731,6 +966,7 @@ mod tests {
             limit: 100,
             remaining: 99,
             reset_at: 1000000,
+            window_secs: 60,
         };
 
         let debug_str = format!("{:?}", result);
@@ -787,6 +1023,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
         assert!(!state.is_enabled());
     }
@@ -802,6 +1039,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
         // enabled=true but no redis => still disabled
         assert!(!state.is_enabled());
@@ -823,6 +1061,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
         // Non-matching endpoint should fall back to default
         let rule = state.get_rule("GET", "/api/v1/unknown");
@@ -851,6 +1090,7 @@ mod tests {
             redis: None,
             jwt_manager: None,
             fallback: InMemoryRateLimiter::new(),
+            failure_policy: FailurePolicy::FailOpen,
         };
         let rule = state.get_rule("POST", "/api/v1/auth/login");
         assert_eq!(rule.requests, 5);
@@ -877,6 +1117,83 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_middleware_exposes_caller() {
+        use axum::{body::Body, http::Request, middleware, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        let state = RateLimitState::noop();
+        let app = Router::new()
+            .route(
+                "/test",
+                get(|Extension(caller): Extension<RateLimitCaller>| async move {
+                    let status = caller.status().await.unwrap();
+                    format!("{}:{}", status.enabled, status.subject)
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit_middleware,
+            ))
+            .with_state(state);
+
+        let req = Request::builder().uri("/test").body(Body::empty()).unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"false:ip");
+    }
+
+    #[test]
+    fn test_insert_rate_limit_headers() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut headers = HeaderMap::new();
+        insert_rate_limit_headers(
+            &mut headers,
+            &RateLimitResult {
+                allowed: true,
+                limit: 100,
+                remaining: 42,
+                reset_at: now + 30,
+                window_secs: 60,
+            },
+        );
+        assert_eq!(headers.get("RateLimit-Limit").unwrap(), "100");
+        assert_eq!(headers.get("RateLimit-Remaining").unwrap(), "42");
+        let reset: u64 = headers
+            .get("RateLimit-Reset")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(reset <= 30 && reset >= 29);
+        assert_eq!(headers.get("RateLimit-Policy").unwrap(), "100;w=60");
+        assert_eq!(headers.get("X-RateLimit-Remaining").unwrap(), "42");
+        assert_eq!(
+            headers.get("X-RateLimit-Reset").unwrap().to_str().unwrap(),
+            (now + 30).to_string()
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_key_subject() {
+        let key = RateLimitKey::TenantClient {
+            tenant_id: "t".to_string(),
+            client_id: "c".to_string(),
+        };
+        assert_eq!(key.subject(), "client");
+        let key = RateLimitKey::Ip {
+            ip: "10.0.0.1".to_string(),
+        };
+        assert_eq!(key.subject(), "ip");
+    }
+
     #[test]
     fn test_rate_limit_error_debug() {
         let err = RateLimitError::NotConfigured;
@@ -0,0 +1,309 @@
+//! Replay protection for signed public endpoints
+//!
+//! Senders of identity events, email feedback and SCIM calls can add
+//! `X-Auth9-Timestamp` (unix seconds) and `X-Auth9-Nonce` headers. Requests
+//! outside the client's clock-skew window or reusing a nonce within it are
+//! rejected; nonces are remembered in Redis for twice the skew. Webhook
+//! senders that use these headers sign `{timestamp}.{nonce}.{body}` instead of
+//! the bare body (see [`signed_payload`]), so the headers cannot be swapped.
+
+use crate::cache::{keys, CacheOperations};
+use crate::config::{FailurePolicy, ReplayProtectionConfig};
+use crate::models::scim::ScimRequestContext;
+use crate::telemetry::metrics::record_degraded_operation;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+pub const TIMESTAMP_HEADER: &str = "x-auth9-timestamp";
+pub const NONCE_HEADER: &str = "x-auth9-nonce";
+
+/// Endpoints covered by replay protection, as (path, client source).
+/// Paths ending in `/` match as a prefix.
+const PROTECTED_ENDPOINTS: &[(&str, &str)] = &[
+    ("/api/v1/identity/events", "identity-events"),
+    ("/api/v1/email/feedback", "email-feedback"),
+    ("/api/v1/scim/v2/", "scim"),
+];
+
+const MIN_NONCE_LEN: usize = 16;
+const MAX_NONCE_LEN: usize = 128;
+
+/// Shared state for the replay protection middleware
+#[derive(Clone)]
+pub struct ReplayProtectionState {
+    config: Arc<ReplayProtectionConfig>,
+    cache: Arc<dyn CacheOperations>,
+    /// Behavior when the nonce cache errors (default: fail-closed)
+    failure_policy: FailurePolicy,
+}
+
+impl ReplayProtectionState {
+    pub fn new(config: ReplayProtectionConfig, cache: Arc<dyn CacheOperations>) -> Self {
+        Self {
+            config: Arc::new(config),
+            cache,
+            failure_policy: FailurePolicy::FailClosed,
+        }
+    }
+
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+}
+
+/// Client source of a protected endpoint, `None` for every other path
+fn endpoint_source(path: &str) -> Option<&'static str> {
+    PROTECTED_ENDPOINTS
+        .iter()
+        .find(|(endpoint, _)| {
+            if endpoint.ends_with('/') {
+                path.starts_with(endpoint)
+            } else {
+                path == *endpoint
+            }
+        })
+        .map(|(_, source)| *source)
+}
+
+/// Replay headers of a request, if both are present
+fn replay_headers(headers: &HeaderMap) -> Option<(&str, &str)> {
+    let timestamp = headers.get(TIMESTAMP_HEADER)?.to_str().ok()?;
+    let nonce = headers.get(NONCE_HEADER)?.to_str().ok()?;
+    Some((timestamp, nonce))
+}
+
+/// Payload covered by a webhook HMAC signature: `{timestamp}.{nonce}.{body}`
+/// when replay headers are present, otherwise the bare body
+pub fn signed_payload<'a>(headers: &HeaderMap, body: &'a [u8]) -> Cow<'a, [u8]> {
+    match replay_headers(headers) {
+        Some((timestamp, nonce)) => {
+            let mut payload = format!("{}.{}.", timestamp, nonce).into_bytes();
+            payload.extend_from_slice(body);
+            Cow::Owned(payload)
+        }
+        None => Cow::Borrowed(body),
+    }
+}
+
+fn is_valid_nonce(nonce: &str) -> bool {
+    (MIN_NONCE_LEN..=MAX_NONCE_LEN).contains(&nonce.len())
+        && nonce
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Check a request timestamp against the accepted skew
+fn check_timestamp(timestamp: &str, now: i64, skew_secs: u64) -> Result<(), &'static str> {
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| "Invalid request timestamp")?;
+    if now.abs_diff(timestamp) > skew_secs {
+        return Err("Request timestamp outside the accepted window");
+    }
+    Ok(())
+}
+
+/// Replay protection middleware
+///
+/// Runs on public routes and on SCIM routes after SCIM authentication, so
+/// SCIM connectors are told apart by their connector ID.
+pub async fn replay_protection_middleware(
+    State(state): State<ReplayProtectionState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !state.config.enabled {
+        return next.run(request).await;
+    }
+    let Some(source) = endpoint_source(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let client = match request.extensions().get::<ScimRequestContext>() {
+        Some(ctx) => format!("scim:{}", ctx.connector_id),
+        None => source.to_string(),
+    };
+
+    let Some((timestamp, nonce)) = replay_headers(request.headers()) else {
+        if state.config.is_required(&client, source) {
+            return unauthorized_response("Missing request timestamp or nonce");
+        }
+        return next.run(request).await;
+    };
+
+    let skew_secs = state.config.clock_skew_for(&client, source);
+    if let Err(message) = check_timestamp(timestamp, chrono::Utc::now().timestamp(), skew_secs) {
+        tracing::warn!(client = %client, "Rejected signed request: {}", message);
+        return unauthorized_response(message);
+    }
+    if !is_valid_nonce(nonce) {
+        return unauthorized_response("Invalid request nonce");
+    }
+
+    // Nonces are remembered for the whole window a timestamp can be accepted in
+    let key = format!("{}:{}:{}", keys::REQUEST_NONCE, client, nonce);
+    match state.cache.set_flag(&key, skew_secs * 2).await {
+        Ok(false) => next.run(request).await,
+        Ok(true) => {
+            tracing::warn!(client = %client, "Rejected replayed request nonce");
+            unauthorized_response("Request nonce has already been used")
+        }
+        Err(e) => {
+            record_degraded_operation("redis", "replay_nonce_check", state.failure_policy);
+            if state.failure_policy == FailurePolicy::FailOpen {
+                tracing::warn!(error = %e, "Replay nonce check failed (Redis error), allowing request (fail-open)");
+                return next.run(request).await;
+            }
+            tracing::error!(error = %e, "Replay nonce check failed (Redis error), rejecting request (fail-closed)");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "error": "service_unavailable",
+                    "message": "Replay protection temporarily unavailable"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn unauthorized_response(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({
+            "error": "unauthorized",
+            "message": message
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_endpoint_source() {
+        assert_eq!(
+            endpoint_source("/api/v1/identity/events"),
+            Some("identity-events")
+        );
+        assert_eq!(endpoint_source("/api/v1/scim/v2/Users/1"), Some("scim"));
+        assert_eq!(endpoint_source("/api/v1/identity/events/x"), None);
+        assert_eq!(endpoint_source("/api/v1/auth/token"), None);
+    }
+
+    #[test]
+    fn test_check_timestamp_window() {
+        assert!(check_timestamp("1000", 1000, 300).is_ok());
+        assert!(check_timestamp("700", 1000, 300).is_ok());
+        assert!(check_timestamp("1300", 1000, 300).is_ok());
+        assert!(check_timestamp("699", 1000, 300).is_err());
+        assert!(check_timestamp("1301", 1000, 300).is_err());
+        assert!(check_timestamp("soon", 1000, 300).is_err());
+    }
+
+    #[test]
+    fn test_is_valid_nonce() {
+        assert!(is_valid_nonce("0f8c2b1e-6d7a-4d4b-9d1e"));
+        assert!(!is_valid_nonce("short"));
+        assert!(!is_valid_nonce("contains spaces here!"));
+        assert!(!is_valid_nonce(&"a".repeat(MAX_NONCE_LEN + 1)));
+    }
+
+    #[test]
+    fn test_signed_payload_binds_replay_headers() {
+        let body = br#"{"type":"LOGIN"}"#;
+        let mut headers = HeaderMap::new();
+        assert_eq!(signed_payload(&headers, body).as_ref(), body);
+
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static("1700000000"));
+        headers.insert(NONCE_HEADER, HeaderValue::from_static("abcdef0123456789"));
+        assert_eq!(
+            signed_payload(&headers, body).as_ref(),
+            br#"1700000000.abcdef0123456789.{"type":"LOGIN"}"#
+        );
+    }
+
+    fn router(config: ReplayProtectionConfig) -> axum::Router {
+        let state =
+            ReplayProtectionState::new(config, Arc::new(crate::cache::NoOpCacheManager::new()));
+        axum::Router::new()
+            .route(
+                "/api/v1/identity/events",
+                axum::routing::post(|| async { "ok" }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state,
+                replay_protection_middleware,
+            ))
+    }
+
+    fn request(timestamp: Option<i64>, nonce: &str) -> Request<Body> {
+        let mut builder = Request::post("/api/v1/identity/events");
+        if let Some(timestamp) = timestamp {
+            builder = builder
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(NONCE_HEADER, nonce);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rejects_replayed_nonce() {
+        use tower::ServiceExt;
+
+        let app = router(ReplayProtectionConfig::default());
+        let now = chrono::Utc::now().timestamp();
+        let first = app
+            .clone()
+            .oneshot(request(Some(now), "nonce-0123456789ab"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+
+        let replay = app
+            .clone()
+            .oneshot(request(Some(now), "nonce-0123456789ab"))
+            .await
+            .unwrap();
+        assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+
+        let stale = app
+            .oneshot(request(Some(now - 3600), "nonce-fresh-0123456"))
+            .await
+            .unwrap();
+        assert_eq!(stale.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_requires_headers_only_for_required_clients() {
+        use tower::ServiceExt;
+
+        let optional = router(ReplayProtectionConfig::default())
+            .oneshot(request(None, ""))
+            .await
+            .unwrap();
+        assert_eq!(optional.status(), StatusCode::OK);
+
+        let required = router(ReplayProtectionConfig {
+            required_clients: vec!["identity-events".to_string()],
+            ..ReplayProtectionConfig::default()
+        })
+        .oneshot(request(None, ""))
+        .await
+        .unwrap();
+        assert_eq!(required.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -15,7 +15,10 @@ use axum::{
 use serde_json::json;
 
 use crate::cache::CacheOperations;
-use crate::jwt::JwtManager;
+use crate::config::FailurePolicy;
+use crate::domains::authorization::service::TokenValidationPolicies;
+use crate::jwt::{peek_token_audience, peek_token_kind, BearerSession, JwtManager, TokenKind};
+use crate::telemetry::metrics::record_degraded_operation;
 use std::sync::Arc;
 
 /// Shared state for authentication middleware
@@ -23,6 +26,12 @@ use std::sync::Arc;
 pub struct AuthMiddlewareState {
     jwt_manager: JwtManager,
     cache: Option<Arc<dyn CacheOperations>>,
+    /// Behavior when Redis-backed auth checks error (default: fail-closed)
+    failure_policy: FailurePolicy,
+    /// Precision of write-behind session activity markers (disabled when unset)
+    session_activity_precision_secs: Option<u64>,
+    /// Per-service validation profiles for tenant access tokens
+    validation_policies: Option<Arc<TokenValidationPolicies>>,
 }
 
 impl AuthMiddlewareState {
@@ -30,6 +39,9 @@ impl AuthMiddlewareState {
         Self {
             jwt_manager,
             cache: None,
+            failure_policy: FailurePolicy::FailClosed,
+            session_activity_precision_secs: None,
+            validation_policies: None,
         }
     }
 
@@ -37,8 +49,28 @@ impl AuthMiddlewareState {
         self.cache = Some(cache);
         self
     }
+
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    pub fn with_session_activity(mut self, precision_secs: u64) -> Self {
+        self.session_activity_precision_secs = Some(precision_secs);
+        self
+    }
+
+    pub fn with_validation_policies(mut self, policies: Arc<TokenValidationPolicies>) -> Self {
+        self.validation_policies = Some(policies);
+        self
+    }
 }
 
+/// OAuth client_id (token audience) of an authenticated request, attached to
+/// the response so outer layers can attribute usage to the calling client
+#[derive(Debug, Clone)]
+pub struct AuthenticatedClient(pub String);
+
 /// Authentication enforcement middleware
 ///
 /// This middleware validates JWT tokens on protected routes.
@@ -50,7 +82,7 @@ impl AuthMiddlewareState {
 /// - Valid JWT signature and claims
 pub async fn require_auth_middleware(
     State(auth_state): State<AuthMiddlewareState>,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
     let request_path = request.uri().path().to_string();
@@ -79,27 +111,46 @@ pub async fn require_auth_middleware(
         }
     };
 
-    // Validate the token (service client, identity, then tenant access token)
+    // Tenant access tokens are validated under the profile of the service
+    // owning their audience; the profile is handed to the `AuthUser` extractor
+    let validation_profile = match (&auth_state.validation_policies, peek_token_kind(token)) {
+        (Some(policies), Some(TokenKind::TenantAccess)) => match peek_token_audience(token) {
+            Some(aud) => Some(policies.for_client(&aud).await),
+            None => None,
+        },
+        _ => None,
+    };
+
+    // Validate the token (service client, identity or tenant access token)
     // Also extract session ID for blacklist check.
-    let mut session_id: Option<String> = None;
-    let token_kind = if let Ok(claims) = auth_state.jwt_manager.verify_service_client_token(token) {
-        session_id = Some(claims.sub.clone());
-        Some("service_client")
-    } else if let Ok(claims) = auth_state.jwt_manager.verify_identity_token(token) {
-        session_id = claims.sid.clone().or_else(|| Some(claims.sub.clone()));
-        Some("identity")
-    } else if let Ok(claims) = auth_state
-        .jwt_manager
-        .verify_tenant_access_token_any_audience(token)
-    {
+    let verified = match &validation_profile {
+        Some(profile) => auth_state
+            .jwt_manager
+            .verify_tenant_access_token_with_profile(token, None, profile)
+            .map(|claims| BearerSession {
+                kind: TokenKind::TenantAccess,
+                sub: claims.sub,
+                sid: claims.sid,
+                aud: claims.aud,
+            }),
+        None => auth_state.jwt_manager.verify_bearer_session(token),
+    };
+    let claims = match verified {
+        Ok(claims) => claims,
+        Err(_) => return unauthorized_response("Invalid or expired token"),
+    };
+    let token_kind = claims.kind;
+    let client_id = claims.aud.clone();
+    let session_id = claims.sid.clone().or_else(|| Some(claims.sub.clone()));
+    // Real session ID (`sid` claim) for activity tracking
+    let activity_session_id = claims.sid.clone();
+
+    if token_kind == TokenKind::TenantAccess {
         // Validate audience dynamically via cache (Redis SET of registered client_ids)
-        // Fail-closed: if cache is unavailable or errors, reject with 503.
+        // On cache errors the auth failure policy applies (fail-closed by default).
         if let Some(ref cache) = auth_state.cache {
             match cache.is_valid_audience(&claims.aud).await {
-                Ok(true) => {
-                    session_id = claims.sid.clone().or_else(|| Some(claims.sub.clone()));
-                    Some("tenant_access")
-                }
+                Ok(true) => {}
                 Ok(false) => {
                     tracing::debug!(
                         aud = %claims.aud,
@@ -108,10 +159,18 @@ pub async fn require_auth_middleware(
                     return unauthorized_response("Token audience is not registered");
                 }
                 Err(e) => {
-                    tracing::error!(error = %e, "Audience validation failed (Redis error), rejecting request (fail-closed)");
-                    return service_unavailable_response(
-                        "Authentication service temporarily unavailable",
+                    record_degraded_operation(
+                        "redis",
+                        "audience_validation",
+                        auth_state.failure_policy,
                     );
+                    if auth_state.failure_policy != FailurePolicy::FailOpen {
+                        tracing::error!(error = %e, "Audience validation failed (Redis error), rejecting request (fail-closed)");
+                        return service_unavailable_response(
+                            "Authentication service temporarily unavailable",
+                        );
+                    }
+                    tracing::warn!(error = %e, "Audience validation failed (Redis error), allowing request (fail-open)");
                 }
             }
         } else {
@@ -121,23 +180,19 @@ pub async fn require_auth_middleware(
             );
             return service_unavailable_response("Authentication service temporarily unavailable");
         }
-    } else {
-        None
-    };
-
-    let Some(token_kind) = token_kind else {
-        return unauthorized_response("Invalid or expired token");
-    };
+    }
 
-    if token_kind == "identity" && !is_identity_token_path_allowed(&request_path, &request_method) {
+    if token_kind == TokenKind::Identity
+        && !is_identity_token_path_allowed(&request_path, &request_method)
+    {
         return forbidden_response(
             "Identity token is only allowed for tenant selection and exchange",
         );
     }
 
     // Check token blacklist (e.g., after logout)
-    // Fail-Closed: if Redis is unavailable, reject the request with 503 to prevent
-    // revoked tokens from being used during cache outages.
+    // With the default fail-closed policy, a Redis outage rejects the request with 503
+    // so revoked tokens cannot be used during cache outages.
     if let (Some(ref cache), Some(ref sid)) = (&auth_state.cache, &session_id) {
         match cache.is_token_blacklisted(sid).await {
             Ok(true) => {
@@ -152,18 +207,49 @@ pub async fn require_auth_middleware(
                     }
                     Ok(false) => {}
                     Err(e) => {
-                        tracing::error!(error = %e, "Token blacklist check failed after retry, rejecting request (fail-closed)");
-                        return service_unavailable_response(
-                            "Authentication service temporarily unavailable",
+                        record_degraded_operation(
+                            "redis",
+                            "token_revocation_check",
+                            auth_state.failure_policy,
                         );
+                        if auth_state.failure_policy != FailurePolicy::FailOpen {
+                            tracing::error!(error = %e, "Token blacklist check failed after retry, rejecting request (fail-closed)");
+                            return service_unavailable_response(
+                                "Authentication service temporarily unavailable",
+                            );
+                        }
+                        tracing::warn!(error = %e, "Token blacklist check failed after retry, allowing request (fail-open)");
                     }
                 }
             }
         }
     }
 
+    // Buffer session activity in Redis; a background task flushes it to the
+    // database so requests don't write `last_active_at` themselves
+    if let (Some(cache), Some(precision_secs), Some(sid)) = (
+        auth_state.cache.clone(),
+        auth_state.session_activity_precision_secs,
+        activity_session_id,
+    ) {
+        tokio::spawn(async move {
+            let now = chrono::Utc::now().timestamp();
+            if let Err(e) = cache.mark_session_active(&sid, now, precision_secs).await {
+                tracing::debug!(error = %e, "Failed to mark session activity");
+            }
+        });
+    }
+
+    if let Some(profile) = validation_profile {
+        request.extensions_mut().insert(profile);
+    }
+
     // Token is valid, proceed with the request
-    next.run(request).await
+    let mut response = next.run(request).await;
+    response
+        .extensions_mut()
+        .insert(AuthenticatedClient(client_id));
+    response
 }
 
 /// Generate a 401 Unauthorized response
@@ -198,6 +284,7 @@ fn is_identity_token_path_allowed(path: &str, method: &Method) -> bool {
         || path.starts_with("/api/v1/users/me/force-update-password")
         || path.starts_with("/api/v1/users/me/sessions")
         || path.starts_with("/api/v1/users/me/passkeys")
+        || path.starts_with("/api/v1/users/me/deletion")
         // Platform admin endpoints use identity tokens (admin email check in handler)
         || path.starts_with("/api/v1/system/")
         || path.starts_with("/api/v1/security/")
@@ -210,11 +297,16 @@ fn is_identity_token_path_allowed(path: &str, method: &Method) -> bool {
             && (*method == Method::POST || *method == Method::DELETE))
         // Invitation management (resend, revoke, get by ID)
         || path.starts_with("/api/v1/invitations")
+        // Joining a tenant through a shareable invitation link
+        || (path == "/api/v1/invitation-links/join" && *method == Method::POST)
         // Required actions (checked immediately after login with identity token)
         || path == "/api/v1/hosted-login/pending-actions"
         || path == "/api/v1/hosted-login/complete-action"
         // MFA management (TOTP enrollment, recovery codes, status)
         || path.starts_with("/api/v1/mfa/")
+        // Job status/cancel: handler allows the creator, platform admins and
+        // user managers of the job's tenant (async tenant delete uses Identity tokens)
+        || path.starts_with("/api/v1/jobs/")
 }
 
 /// Generate a 503 Service Unavailable response
@@ -258,6 +350,8 @@ mod tests {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         };
         JwtManager::new(config)
     }
@@ -405,6 +499,51 @@ mod tests {
         assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
+    #[tokio::test]
+    async fn test_blacklist_redis_error_fail_open_policy_allows() {
+        use crate::cache::MockCacheOperations;
+
+        let jwt_manager = create_test_jwt_manager();
+
+        let user_id = uuid::Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let session_id = uuid::Uuid::parse_str("660e8400-e29b-41d4-a716-446655440001").unwrap();
+        let valid_token = jwt_manager
+            .create_identity_token_with_session(
+                user_id,
+                "test@example.com",
+                Some("Test User"),
+                Some(session_id),
+            )
+            .unwrap();
+
+        let mut mock_cache = MockCacheOperations::new();
+        mock_cache
+            .expect_is_token_blacklisted()
+            .times(2)
+            .returning(|_| Err(anyhow::anyhow!("Redis connection refused").into()));
+
+        let auth_state = AuthMiddlewareState::new(jwt_manager)
+            .with_cache(Arc::new(mock_cache))
+            .with_failure_policy(FailurePolicy::FailOpen);
+
+        let app = Router::new()
+            .route("/api/v1/auth/userinfo", get(protected_handler))
+            .layer(axum::middleware::from_fn_with_state(
+                auth_state,
+                require_auth_middleware,
+            ));
+
+        let request = Request::builder()
+            .uri("/api/v1/auth/userinfo")
+            .header("Authorization", format!("Bearer {}", valid_token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_blacklist_redis_error_retry_succeeds() {
         use crate::cache::MockCacheOperations;
@@ -562,6 +701,10 @@ mod tests {
             "/api/v1/users/me/passkeys",
             &get
         ));
+        assert!(is_identity_token_path_allowed(
+            "/api/v1/users/me/deletion/restore",
+            &Method::POST
+        ));
 
         // Invitation management paths
         assert!(is_identity_token_path_allowed(
@@ -0,0 +1,133 @@
+//! Permission-bound masking of personal data in responses
+//!
+//! Runs after authentication on protected routes. For `GET` requests made
+//! with a tenant access token, every configured [`MaskingRule`] whose
+//! permission the token lacks masks its field in the JSON response body.
+//! Identity tokens (portal and platform admins) and service client tokens are
+//! authorized by other means and see unmasked values, as does a user reading
+//! their own profile under `/api/v1/users/me`.
+//!
+//! Streamed NDJSON responses are passed through unbuffered. Their handlers
+//! mask each record before writing it, using [`masking_rules_for_user`].
+
+use crate::config::ResponseMaskingConfig;
+use crate::jwt::VerifiedToken;
+use crate::middleware::auth::{AuthUser, TokenType};
+use crate::models::masking::{apply_masking, MaskingRule};
+use crate::state::HasServices;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Paths returning the caller's own data, never masked
+const SELF_SERVICE_PREFIX: &str = "/api/v1/users/me";
+
+/// Response header listing the masked fields
+pub const MASKED_FIELDS_HEADER: &str = "x-auth9-masked-fields";
+
+pub async fn response_masking_middleware<S: HasServices>(
+    State(state): State<S>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = &state.config().response_masking;
+    if !config.enabled
+        || request.method() != Method::GET
+        || request.uri().path().starts_with(SELF_SERVICE_PREFIX)
+    {
+        return next.run(request).await;
+    }
+
+    let rules = masking_rules_for(&state, &request, config);
+    if rules.is_empty() {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    // NDJSON is masked per record by its handler
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("json") && !ct.contains("ndjson"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Ok(mut value) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    if !apply_masking(&mut value, &rules) {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    let Ok(masked) = serde_json::to_vec(&value) else {
+        // Never fall back to the unmasked body
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    metrics::counter!("auth9_response_masked_total").increment(1);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    if let Some(fields) = masked_fields_header(&rules) {
+        parts.headers.insert(MASKED_FIELDS_HEADER, fields);
+    }
+    Response::from_parts(parts, Body::from(masked))
+}
+
+/// Rules `auth` does not hold the permission for, or none when masking is
+/// disabled or the token is not a tenant access token
+pub fn masking_rules_for_user<'a>(
+    config: &'a ResponseMaskingConfig,
+    auth: &AuthUser,
+) -> Vec<&'a MaskingRule> {
+    if !config.enabled || auth.token_type != TokenType::TenantAccess {
+        return Vec::new();
+    }
+    config
+        .rules
+        .iter()
+        .filter(|rule| !auth.has_permission(&rule.permission))
+        .collect()
+}
+
+/// Value of [`MASKED_FIELDS_HEADER`] for `rules`
+pub fn masked_fields_header(rules: &[&MaskingRule]) -> Option<HeaderValue> {
+    let fields = rules
+        .iter()
+        .map(|r| r.field.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    HeaderValue::from_str(&fields).ok()
+}
+
+/// Rules the caller's token does not hold the permission for
+fn masking_rules_for<'a, S: HasServices>(
+    state: &S,
+    request: &Request<Body>,
+    config: &'a ResponseMaskingConfig,
+) -> Vec<&'a MaskingRule> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let Some(token) = token else {
+        return Vec::new();
+    };
+    // Authentication already ran; only the token type and permissions matter
+    let Ok(VerifiedToken::TenantAccess(claims)) = state.jwt_manager().verify_bearer_token(token)
+    else {
+        return Vec::new();
+    };
+    let Ok(user) = AuthUser::from_tenant_access_claims(claims) else {
+        return Vec::new();
+    };
+    masking_rules_for_user(config, &user)
+}
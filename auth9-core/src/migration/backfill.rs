@@ -0,0 +1,167 @@
+//! Batched backfills for expand migrations
+//!
+//! Filling a new column of a large table with one `UPDATE` locks every row it
+//! touches until the migration commits and floods replication. Instead the
+//! backfill is registered here and referenced by the migration that adds the
+//! column (`-- migrate:backfill <name>`). Once migrations are applied it is
+//! queued as a `schema_backfill` job, and job workers update one batch at a
+//! time until no row matches `pending`, so the job is safe to resume or rerun.
+
+use super::region::quote_ident;
+use super::safety::MigrationAnnotations;
+use crate::config::MigrationSafetyConfig;
+use crate::models::job::{CreateJobInput, JobKind, JobStatus};
+use crate::repository::job::{JobRepository, JobRepositoryImpl};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::MySqlPool;
+use tracing::{info, warn};
+
+/// A data backfill run in batches by background jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backfill {
+    /// Name referenced by `-- migrate:backfill`
+    pub name: &'static str,
+    /// Table with an `id` primary key
+    pub table: &'static str,
+    /// Assignments applied to each batch, e.g. `locale = 'en'`
+    pub set: &'static str,
+    /// Rows still to backfill. Must stop matching a row once `set` has been
+    /// applied to it, which is what makes batches resumable.
+    pub pending: &'static str,
+}
+
+/// Registered backfills. Entries stay until every environment has finished
+/// them and the contract migration that relies on them has shipped.
+pub const BACKFILLS: &[Backfill] = &[];
+
+pub fn find(name: &str) -> Option<&'static Backfill> {
+    BACKFILLS.iter().find(|backfill| backfill.name == name)
+}
+
+impl Backfill {
+    /// Update the next batch; binds the batch size
+    pub fn batch_sql(&self) -> String {
+        format!(
+            "UPDATE {} SET {} WHERE {} ORDER BY id LIMIT ?",
+            quote_ident(self.table),
+            self.set,
+            self.pending
+        )
+    }
+
+    /// Count rows still to backfill
+    pub fn remaining_sql(&self) -> String {
+        format!(
+            "SELECT COUNT(*) FROM {} WHERE {}",
+            quote_ident(self.table),
+            self.pending
+        )
+    }
+}
+
+/// Payload of a `schema_backfill` job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackfillJobPayload {
+    pub backfill: String,
+    /// Migration that requested the backfill
+    pub migration_version: i64,
+    pub batch_size: u64,
+    pub pause_ms: u64,
+}
+
+/// Queue the backfills requested by the given (just applied) migrations,
+/// skipping ones already queued, running or finished
+pub(super) async fn enqueue_backfills(
+    pool: &MySqlPool,
+    migrator: &Migrator,
+    versions: &[i64],
+    config: &MigrationSafetyConfig,
+) -> Result<usize> {
+    let jobs = JobRepositoryImpl::new(pool.clone());
+    let mut queued = 0;
+    for migration in migrator.iter().filter(|m| versions.contains(&m.version)) {
+        let annotations = MigrationAnnotations::parse(&migration.sql)
+            .with_context(|| format!("Invalid annotation in migration {}", migration.version))?;
+        for name in annotations.backfills {
+            if find(&name).is_none() {
+                warn!(
+                    migration = migration.version,
+                    backfill = %name,
+                    "Migration requests an unregistered backfill"
+                );
+                continue;
+            }
+            let existing: i64 = sqlx::query_scalar(
+                r#"
+                SELECT COUNT(*) FROM jobs
+                WHERE kind = ? AND status IN (?, ?, ?)
+                  AND JSON_UNQUOTE(JSON_EXTRACT(payload, '$.backfill')) = ?
+                "#,
+            )
+            .bind(JobKind::SchemaBackfill.as_str())
+            .bind(JobStatus::Queued.as_str())
+            .bind(JobStatus::Running.as_str())
+            .bind(JobStatus::Succeeded.as_str())
+            .bind(&name)
+            .fetch_one(pool)
+            .await
+            .context("Failed to look up backfill jobs")?;
+            if existing > 0 {
+                continue;
+            }
+            let payload = BackfillJobPayload {
+                backfill: name.clone(),
+                migration_version: migration.version,
+                batch_size: config.backfill_batch_size,
+                pause_ms: config.backfill_pause_ms,
+            };
+            let job = jobs
+                .create(&CreateJobInput {
+                    kind: JobKind::SchemaBackfill,
+                    tenant_id: None,
+                    payload: serde_json::to_value(&payload)?,
+                    created_by: None,
+                })
+                .await
+                .with_context(|| format!("Failed to queue backfill '{}'", name))?;
+            info!(job_id = %job.id, backfill = %name, "Queued schema backfill");
+            queued += 1;
+        }
+    }
+    Ok(queued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALE: Backfill = Backfill {
+        name: "users_locale",
+        table: "users",
+        set: "locale = 'en'",
+        pending: "locale IS NULL",
+    };
+
+    #[test]
+    fn test_batch_sql() {
+        assert_eq!(
+            LOCALE.batch_sql(),
+            "UPDATE `users` SET locale = 'en' WHERE locale IS NULL ORDER BY id LIMIT ?"
+        );
+        assert_eq!(
+            LOCALE.remaining_sql(),
+            "SELECT COUNT(*) FROM `users` WHERE locale IS NULL"
+        );
+    }
+
+    #[test]
+    fn test_registered_backfills_are_unique() {
+        for (i, backfill) in BACKFILLS.iter().enumerate() {
+            assert!(BACKFILLS[..i].iter().all(|b| b.name != backfill.name));
+            assert_eq!(find(backfill.name), Some(backfill));
+        }
+        assert!(find("no_such_backfill").is_none());
+    }
+}
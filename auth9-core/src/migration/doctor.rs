@@ -0,0 +1,343 @@
+//! Referential integrity checks (`auth9-core doctor`)
+//!
+//! The schema has no foreign keys (TiDB), so rows can be left pointing at
+//! deleted parents by an interrupted cascade, a partial restore or manual
+//! repairs after an incident. Each check selects the offending rows of one
+//! relation; checks whose repair cannot lose meaningful data (deleting rows
+//! that reference a parent which no longer exists) can be fixed with
+//! `--fix`, the rest are reported for an operator to decide.
+//!
+//! Checks run in order and each fix is applied before the next check, so
+//! cleaning up orphaned memberships also surfaces their role assignments.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::MySqlPool;
+use tracing::info;
+
+/// Offending rows quoted per finding
+const SAMPLE_SIZE: i64 = 10;
+
+/// How much a finding matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Dead data that nothing reads
+    Warning,
+    /// Data the running service trips over (wrong permissions, unusable
+    /// clients, memberships of deleted tenants)
+    Error,
+}
+
+/// One integrity rule
+struct IntegrityCheck {
+    name: &'static str,
+    severity: Severity,
+    description: &'static str,
+    /// Selects one `id` column identifying every offending row
+    select: &'static str,
+    /// Repairs every offending row; `None` when only an operator can decide
+    fix: Option<&'static str>,
+    /// The parent may live in another data residency region, so a missing
+    /// parent is not proof of an orphan when regions are configured
+    cross_region: bool,
+}
+
+const CHECKS: &[IntegrityCheck] = &[
+    IntegrityCheck {
+        name: "tenant_users_without_tenant",
+        severity: Severity::Error,
+        description: "Tenant memberships of deleted tenants",
+        select: "SELECT tu.id FROM tenant_users tu \
+                 WHERE NOT EXISTS (SELECT 1 FROM tenants t WHERE t.id = tu.tenant_id)",
+        fix: Some(
+            "DELETE FROM tenant_users \
+             WHERE NOT EXISTS (SELECT 1 FROM tenants t WHERE t.id = tenant_users.tenant_id)",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "tenant_users_without_user",
+        severity: Severity::Error,
+        description: "Tenant memberships of deleted users",
+        select: "SELECT tu.id FROM tenant_users tu \
+                 WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = tu.user_id)",
+        fix: Some(
+            "DELETE FROM tenant_users \
+             WHERE NOT EXISTS (SELECT 1 FROM users u WHERE u.id = tenant_users.user_id)",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "role_assignments_without_membership",
+        severity: Severity::Warning,
+        description: "Role assignments of memberships that no longer exist",
+        select: "SELECT utr.id FROM user_tenant_roles utr \
+                 WHERE NOT EXISTS (SELECT 1 FROM tenant_users tu WHERE tu.id = utr.tenant_user_id)",
+        fix: Some(
+            "DELETE FROM user_tenant_roles \
+             WHERE NOT EXISTS \
+             (SELECT 1 FROM tenant_users tu WHERE tu.id = user_tenant_roles.tenant_user_id)",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "role_assignments_to_deleted_roles",
+        severity: Severity::Error,
+        description: "Role assignments referencing deleted roles",
+        select: "SELECT utr.id FROM user_tenant_roles utr \
+                 WHERE NOT EXISTS (SELECT 1 FROM roles r WHERE r.id = utr.role_id)",
+        fix: Some(
+            "DELETE FROM user_tenant_roles \
+             WHERE NOT EXISTS (SELECT 1 FROM roles r WHERE r.id = user_tenant_roles.role_id)",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "role_permissions_dangling",
+        severity: Severity::Warning,
+        description: "Permission grants of deleted roles or deleted permissions",
+        select: "SELECT CONCAT(rp.role_id, ':', rp.permission_id) AS id FROM role_permissions rp \
+                 WHERE NOT EXISTS (SELECT 1 FROM roles r WHERE r.id = rp.role_id) \
+                 OR NOT EXISTS (SELECT 1 FROM permissions p WHERE p.id = rp.permission_id)",
+        fix: Some(
+            "DELETE FROM role_permissions \
+             WHERE NOT EXISTS (SELECT 1 FROM roles r WHERE r.id = role_permissions.role_id) \
+             OR NOT EXISTS \
+             (SELECT 1 FROM permissions p WHERE p.id = role_permissions.permission_id)",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "roles_with_deleted_parent",
+        severity: Severity::Warning,
+        description: "Roles inheriting from a deleted parent role (fix detaches them)",
+        select: "SELECT r.id FROM roles r WHERE r.parent_role_id IS NOT NULL \
+                 AND NOT EXISTS (SELECT 1 FROM roles p WHERE p.id = r.parent_role_id)",
+        fix: Some(
+            "UPDATE roles r LEFT JOIN roles p ON p.id = r.parent_role_id \
+             SET r.parent_role_id = NULL \
+             WHERE r.parent_role_id IS NOT NULL AND p.id IS NULL",
+        ),
+        cross_region: false,
+    },
+    IntegrityCheck {
+        name: "clients_without_service",
+        severity: Severity::Error,
+        description: "OAuth clients whose service was deleted",
+        select: "SELECT c.id FROM clients c \
+                 WHERE NOT EXISTS (SELECT 1 FROM services s WHERE s.id = c.service_id)",
+        fix: None,
+        cross_region: true,
+    },
+    IntegrityCheck {
+        name: "roles_without_service",
+        severity: Severity::Error,
+        description: "Roles whose service was deleted",
+        select: "SELECT r.id FROM roles r \
+                 WHERE NOT EXISTS (SELECT 1 FROM services s WHERE s.id = r.service_id)",
+        fix: None,
+        cross_region: true,
+    },
+];
+
+/// What `doctor` may change
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DoctorOptions {
+    /// Repair the findings of fixable checks
+    pub fix: bool,
+}
+
+/// Rows breaking one integrity rule
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorFinding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub description: String,
+    pub count: i64,
+    /// Up to ten identifiers of offending rows
+    pub sample_ids: Vec<String>,
+    pub fixable: bool,
+    /// Rows repaired by `--fix`
+    pub fixed: u64,
+}
+
+impl DoctorFinding {
+    /// Whether the finding is still present after the run
+    pub fn is_open(&self) -> bool {
+        self.fixed < self.count as u64
+    }
+}
+
+/// Outcome of an integrity check run
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub checks_run: usize,
+    pub fix: bool,
+    pub findings: Vec<DoctorFinding>,
+}
+
+impl DoctorReport {
+    /// Open findings of error severity; the command fails when there are any
+    pub fn open_errors(&self) -> impl Iterator<Item = &DoctorFinding> {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error && f.is_open())
+    }
+}
+
+/// Check referential integrity of the home database, optionally repairing
+/// the safe findings
+pub async fn doctor(config: &Config, options: DoctorOptions) -> Result<DoctorReport> {
+    let pool = MySqlPoolOptions::new()
+        .max_connections(2)
+        .connect(&config.database.url)
+        .await
+        .context("Failed to connect to database")?;
+    let multi_region = !config.data_residency.region_database_urls.is_empty();
+
+    let mut findings = Vec::new();
+    for check in CHECKS {
+        if let Some(finding) = run_check(&pool, check, options, multi_region).await? {
+            findings.push(finding);
+        }
+    }
+    Ok(DoctorReport {
+        checks_run: CHECKS.len(),
+        fix: options.fix,
+        findings,
+    })
+}
+
+async fn run_check(
+    pool: &MySqlPool,
+    check: &IntegrityCheck,
+    options: DoctorOptions,
+    multi_region: bool,
+) -> Result<Option<DoctorFinding>> {
+    let (count,): (i64,) = sqlx::query_as(&count_sql(check.select))
+        .fetch_one(pool)
+        .await
+        .with_context(|| format!("Integrity check '{}' failed", check.name))?;
+    if count == 0 {
+        return Ok(None);
+    }
+    let sample_ids: Vec<(String,)> = sqlx::query_as(&sample_sql(check.select))
+        .bind(SAMPLE_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+    let (severity, description) = if check.cross_region && multi_region {
+        (
+            Severity::Warning,
+            format!(
+                "{} (the service may live in another data region)",
+                check.description
+            ),
+        )
+    } else {
+        (check.severity, check.description.to_string())
+    };
+
+    let mut fixed = 0;
+    if options.fix {
+        if let Some(fix) = check.fix {
+            fixed = sqlx::query(fix).execute(pool).await?.rows_affected();
+            info!(
+                check = check.name,
+                rows = fixed,
+                "Repaired integrity finding"
+            );
+        }
+    }
+
+    Ok(Some(DoctorFinding {
+        check: check.name,
+        severity,
+        description,
+        count,
+        sample_ids: sample_ids.into_iter().map(|(id,)| id).collect(),
+        fixable: check.fix.is_some(),
+        fixed,
+    }))
+}
+
+fn count_sql(select: &str) -> String {
+    format!("SELECT COUNT(*) FROM ({}) AS offending", select)
+}
+
+fn sample_sql(select: &str) -> String {
+    format!(
+        "SELECT CAST(id AS CHAR) FROM ({}) AS offending ORDER BY id LIMIT ?",
+        select
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_check_names_are_unique() {
+        let names: HashSet<_> = CHECKS.iter().map(|c| c.name).collect();
+        assert_eq!(names.len(), CHECKS.len());
+    }
+
+    #[test]
+    fn test_cross_region_checks_are_never_fixed() {
+        // A service in another region must not get its clients deleted
+        for check in CHECKS.iter().filter(|c| c.cross_region) {
+            assert!(check.fix.is_none(), "{} must not be fixable", check.name);
+        }
+    }
+
+    #[test]
+    fn test_memberships_are_cleaned_before_their_role_assignments() {
+        let position = |name: &str| CHECKS.iter().position(|c| c.name == name).unwrap();
+        assert!(
+            position("tenant_users_without_tenant")
+                < position("role_assignments_without_membership")
+        );
+        assert!(
+            position("tenant_users_without_user") < position("role_assignments_without_membership")
+        );
+    }
+
+    #[test]
+    fn test_wrapped_queries() {
+        assert_eq!(
+            count_sql("SELECT c.id FROM clients c"),
+            "SELECT COUNT(*) FROM (SELECT c.id FROM clients c) AS offending"
+        );
+        assert!(sample_sql("SELECT c.id FROM clients c").ends_with("ORDER BY id LIMIT ?"));
+    }
+
+    fn finding(severity: Severity, count: i64, fixed: u64) -> DoctorFinding {
+        DoctorFinding {
+            check: "test",
+            severity,
+            description: String::new(),
+            count,
+            sample_ids: vec![],
+            fixable: fixed > 0,
+            fixed,
+        }
+    }
+
+    #[test]
+    fn test_open_errors_ignore_fixed_and_warnings() {
+        let report = DoctorReport {
+            checks_run: 3,
+            fix: true,
+            findings: vec![
+                finding(Severity::Error, 2, 2),
+                finding(Severity::Warning, 5, 0),
+                finding(Severity::Error, 1, 0),
+            ],
+        };
+        assert_eq!(report.open_errors().count(), 1);
+    }
+}
@@ -4,6 +4,23 @@
 //! - Running database migrations
 //! - Seeding default admin user and tenant data
 //! - Seeding default services in database
+//! - Maintaining monthly partitions on event tables
+//! - Moving tenants between data residency regions
+//! - Encrypted logical backups and full or per-tenant restore
+//! - Zero-downtime safety checks and batched backfills for migrations
+//! - Referential integrity checks and repairs (`doctor`)
+
+pub mod backfill;
+pub mod backup;
+pub mod doctor;
+pub mod partition;
+pub mod region;
+pub mod safety;
+
+pub use backup::{backup, restore, BackupOptions, RestoreOptions};
+pub use doctor::{doctor, DoctorOptions, DoctorReport};
+pub use region::{move_tenant_region, run_regional_migrations, TenantMoveOptions};
+pub use safety::{safety_report, SafetyReport};
 
 use crate::config::Config;
 use anyhow::{Context, Result};
@@ -143,8 +160,7 @@ pub async fn run_migrations(config: &Config) -> Result<()> {
     ensure_database_exists(config).await?;
 
     info!("Connecting to database...");
-    let pool = MySqlPoolOptions::new()
-        .max_connections(1)
+    let pool = safety::migration_pool_options(&config.migration_safety)
         .connect(&config.database.url)
         .await
         .context("Failed to connect to database")?;
@@ -152,6 +168,7 @@ pub async fn run_migrations(config: &Config) -> Result<()> {
     info!("Running database migrations...");
 
     let migrator = sqlx::migrate!("./migrations");
+    let pending = safety::pending_versions(&pool, &migrator).await?;
 
     if let Err(error) = migrator.run(&pool).await {
         match error {
@@ -170,12 +187,30 @@ pub async fn run_migrations(config: &Config) -> Result<()> {
                     .await
                     .context("Failed to continue migrations after SCIM compatibility fallback")?;
             }
+            MigrateError::ExecuteMigration(error, version) if is_lock_wait_timeout(&error) => {
+                return Err(error).context(format!(
+                    "Migration {} waited more than {}s for a table lock and was stopped so it \
+                     does not block traffic; retry once long-running transactions on the \
+                     table have finished",
+                    version, config.migration_safety.lock_timeout_secs
+                ));
+            }
             other => {
                 return Err(other).context("Failed to run migrations");
             }
         }
     }
 
+    let queued =
+        backfill::enqueue_backfills(&pool, &migrator, &pending, &config.migration_safety).await?;
+    if queued > 0 {
+        info!("Queued {} schema backfill job(s)", queued);
+    }
+
+    partition::maintain_partitions(&pool, &config.event_partitions)
+        .await
+        .context("Failed to maintain event table partitions")?;
+
     pool.close().await;
     info!("Database migrations completed");
     Ok(())
@@ -283,6 +318,15 @@ async fn apply_scim_mapping_migration_compat(
     Ok(())
 }
 
+/// `ER_LOCK_WAIT_TIMEOUT`, raised by MySQL and TiDB for both metadata and
+/// row lock waits
+fn is_lock_wait_timeout(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|e| e.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>())
+        .is_some_and(|e| e.number() == 1205)
+}
+
 async fn column_exists(pool: &Pool<MySql>, table: &str, column: &str) -> Result<bool> {
     let exists: Option<i64> = sqlx::query_scalar(
         r#"
@@ -0,0 +1,286 @@
+//! Monthly partition maintenance for high-volume event tables
+//!
+//! `login_events` and `audit_logs` are range-partitioned on
+//! `UNIX_TIMESTAMP(created_at)` with one partition per month (`pYYYYMM`),
+//! plus `p_history` for rows older than the first monthly partition and a
+//! catch-all `p_future`. Maintenance:
+//!
+//! - splits monthly partitions out of `p_future` so that the current month and
+//!   the next `premake_months` months always have their own partition;
+//! - drops partitions that end before the retention horizon, which removes
+//!   old events without a slow `DELETE`.
+
+use crate::config::EventPartitionConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use tracing::{info, warn};
+
+/// Tables partitioned by month on `created_at`
+pub const PARTITIONED_TABLES: &[&str] = &["login_events", "audit_logs"];
+
+const FUTURE_PARTITION: &str = "p_future";
+
+/// A range partition and its exclusive upper bound (`None` for `MAXVALUE`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangePartition {
+    pub name: String,
+    pub less_than: Option<i64>,
+}
+
+/// Partition changes needed for one table
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PartitionPlan {
+    /// Monthly partitions to split out of `p_future`, oldest first
+    pub create: Vec<RangePartition>,
+    /// Partitions entirely older than the retention horizon
+    pub drop: Vec<String>,
+}
+
+impl PartitionPlan {
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.drop.is_empty()
+    }
+}
+
+/// Work out which partitions to create and drop at `now`
+pub fn plan_partitions(
+    existing: &[RangePartition],
+    now: DateTime<Utc>,
+    config: &EventPartitionConfig,
+) -> PartitionPlan {
+    let current_month = month_start(now.date_naive());
+    let mut plan = PartitionPlan::default();
+
+    if config.retention_months > 0 {
+        let horizon = epoch(add_months(current_month, -(config.retention_months as i32)));
+        plan.drop = existing
+            .iter()
+            .filter(|p| p.name != FUTURE_PARTITION)
+            .filter(|p| p.less_than.is_some_and(|bound| bound <= horizon))
+            .map(|p| p.name.clone())
+            .collect();
+    }
+
+    // Without p_future there is nothing to split new partitions from
+    if !existing.iter().any(|p| p.name == FUTURE_PARTITION) {
+        return plan;
+    }
+    let target = epoch(add_months(current_month, config.premake_months as i32 + 1));
+    let mut next = match existing.iter().filter_map(|p| p.less_than).max() {
+        Some(bound) => month_start(
+            DateTime::from_timestamp(bound, 0)
+                .unwrap_or(now)
+                .date_naive(),
+        ),
+        None => current_month,
+    };
+    while epoch(next) < target {
+        let end = add_months(next, 1);
+        plan.create.push(RangePartition {
+            name: format!("p{:04}{:02}", next.year(), next.month()),
+            less_than: Some(epoch(end)),
+        });
+        next = end;
+    }
+    plan
+}
+
+/// Create upcoming monthly partitions and drop expired ones on every
+/// partitioned table. Tables that are not partitioned are skipped.
+pub async fn maintain_partitions(pool: &Pool<MySql>, config: &EventPartitionConfig) -> Result<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+    for table in PARTITIONED_TABLES {
+        let existing = list_partitions(pool, table).await?;
+        if existing.is_empty() {
+            warn!(table = %table, "Table is not partitioned; skipping partition maintenance");
+            continue;
+        }
+
+        let plan = plan_partitions(&existing, Utc::now(), config);
+        if plan.is_empty() {
+            continue;
+        }
+
+        if !plan.create.is_empty() {
+            sqlx::query(&reorganize_future_sql(table, &plan.create))
+                .execute(pool)
+                .await
+                .with_context(|| format!("Failed to create partitions on {}", table))?;
+            info!(
+                table = %table,
+                partitions = ?plan.create.iter().map(|p| &p.name).collect::<Vec<_>>(),
+                "Created event partitions"
+            );
+        }
+
+        for name in &plan.drop {
+            sqlx::query(&format!(
+                "ALTER TABLE `{}` DROP PARTITION `{}`",
+                table, name
+            ))
+            .execute(pool)
+            .await
+            .with_context(|| format!("Failed to drop partition {} on {}", name, table))?;
+            info!(table = %table, partition = %name, "Dropped expired event partition");
+        }
+
+        if *table == "audit_logs" && !plan.drop.is_empty() {
+            // The search index is not partitioned; trim it to what is left
+            let removed = sqlx::query(
+                "DELETE FROM audit_log_search WHERE created_at < (SELECT MIN(created_at) FROM audit_logs)",
+            )
+            .execute(pool)
+            .await
+            .context("Failed to trim audit log search index")?
+            .rows_affected();
+            info!(rows = removed, "Trimmed audit log search index");
+        }
+    }
+    Ok(())
+}
+
+async fn list_partitions(pool: &Pool<MySql>, table: &str) -> Result<Vec<RangePartition>> {
+    let rows: Vec<(Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT partition_name, partition_description
+        FROM information_schema.partitions
+        WHERE table_schema = DATABASE()
+          AND table_name = ?
+          AND partition_name IS NOT NULL
+        ORDER BY partition_ordinal_position
+        "#,
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await
+    .context("Failed to read information_schema.partitions")?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(name, description)| {
+            Some(RangePartition {
+                name: name?,
+                less_than: description.and_then(|d| d.trim().parse().ok()),
+            })
+        })
+        .collect())
+}
+
+fn reorganize_future_sql(table: &str, create: &[RangePartition]) -> String {
+    let mut parts: Vec<String> = create
+        .iter()
+        .filter_map(|p| {
+            p.less_than
+                .map(|bound| format!("PARTITION `{}` VALUES LESS THAN ({})", p.name, bound))
+        })
+        .collect();
+    parts.push(format!(
+        "PARTITION `{}` VALUES LESS THAN MAXVALUE",
+        FUTURE_PARTITION
+    ));
+    format!(
+        "ALTER TABLE `{}` REORGANIZE PARTITION `{}` INTO ({})",
+        table,
+        FUTURE_PARTITION,
+        parts.join(", ")
+    )
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn add_months(month: NaiveDate, delta: i32) -> NaiveDate {
+    let index = month.year() * 12 + month.month0() as i32 + delta;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+        .unwrap_or(month)
+}
+
+fn epoch(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config(retention_months: u32, premake_months: u32) -> EventPartitionConfig {
+        EventPartitionConfig {
+            retention_months,
+            premake_months,
+            ..Default::default()
+        }
+    }
+
+    fn partition(name: &str, ymd: Option<(i32, u32)>) -> RangePartition {
+        RangePartition {
+            name: name.to_string(),
+            less_than: ymd.map(|(y, m)| epoch(NaiveDate::from_ymd_opt(y, m, 1).unwrap())),
+        }
+    }
+
+    #[test]
+    fn test_plan_splits_months_out_of_future() {
+        let existing = vec![
+            partition("p_history", Some((2026, 5))),
+            partition(FUTURE_PARTITION, None),
+        ];
+        let now = Utc.with_ymd_and_hms(2026, 6, 15, 12, 0, 0).unwrap();
+
+        let plan = plan_partitions(&existing, now, &config(0, 2));
+        let names: Vec<_> = plan.create.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["p202605", "p202606", "p202607", "p202608"]);
+        assert_eq!(plan.create[3], partition("p202608", Some((2026, 9))));
+        assert!(plan.drop.is_empty());
+    }
+
+    #[test]
+    fn test_plan_drops_partitions_past_retention() {
+        let existing = vec![
+            partition("p_history", Some((2026, 5))),
+            partition("p202605", Some((2026, 6))),
+            partition("p202606", Some((2026, 7))),
+            partition("p202607", Some((2026, 8))),
+            partition(FUTURE_PARTITION, None),
+        ];
+        let now = Utc.with_ymd_and_hms(2026, 7, 3, 0, 0, 0).unwrap();
+
+        // Keep June and the current month
+        let plan = plan_partitions(&existing, now, &config(1, 0));
+        assert_eq!(plan.drop, vec!["p_history", "p202605"]);
+        assert!(plan.create.is_empty());
+    }
+
+    #[test]
+    fn test_plan_crosses_year_boundary() {
+        let existing = vec![
+            partition("p202611", Some((2026, 12))),
+            partition(FUTURE_PARTITION, None),
+        ];
+        let now = Utc.with_ymd_and_hms(2026, 11, 30, 23, 0, 0).unwrap();
+
+        let plan = plan_partitions(&existing, now, &config(0, 2));
+        let names: Vec<_> = plan.create.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["p202612", "p202701"]);
+    }
+
+    #[test]
+    fn test_reorganize_sql_keeps_future_partition() {
+        let sql = reorganize_future_sql("audit_logs", &[partition("p202605", Some((2026, 6)))]);
+        assert_eq!(
+            sql,
+            format!(
+                "ALTER TABLE `audit_logs` REORGANIZE PARTITION `p_future` INTO \
+                 (PARTITION `p202605` VALUES LESS THAN ({}), \
+                 PARTITION `p_future` VALUES LESS THAN MAXVALUE)",
+                epoch(NaiveDate::from_ymd_opt(2026, 6, 1).unwrap())
+            )
+        );
+    }
+}
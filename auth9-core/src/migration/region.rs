@@ -0,0 +1,396 @@
+//! Moving a tenant's data between data residency regions
+//!
+//! Tenant-scoped tables are discovered from the schema (every table with a
+//! `tenant_id` column except the control-plane tables that stay in the home
+//! region). A move:
+//!
+//! 1. plans the move by counting the tenant's rows per table in the source;
+//! 2. copies the rows into the target region (idempotent `REPLACE`, so an
+//!    interrupted move can simply be re-run);
+//! 3. verifies that the target holds as many rows as the source;
+//! 4. re-pins the tenant to the target region in the home database;
+//! 5. optionally purges the rows from the source region.
+//!
+//! The tenant row itself stays authoritative in the home region and is only
+//! replicated into other regions so their foreign keys resolve.
+
+use crate::config::Config;
+use crate::models::common::StringUuid;
+use crate::repository::tenant::TenantRepositoryImpl;
+use crate::repository::{RegionRouter, TenantRepository};
+use anyhow::{bail, Context, Result};
+use sqlx::mysql::{MySqlConnection, MySqlPoolOptions};
+use sqlx::{Connection, MySqlPool, Row};
+use tracing::info;
+
+/// Tables with a `tenant_id` column that always stay in the home region
+pub const CONTROL_PLANE_TABLES: &[&str] = &["tenant_users"];
+
+/// What to do when moving a tenant
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TenantMoveOptions {
+    /// Only plan the move; nothing is written
+    pub dry_run: bool,
+    /// Delete the tenant's rows from the source region after switching
+    pub purge_source: bool,
+}
+
+/// Rows of one table moved for the tenant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableMove {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// Outcome of a tenant region move
+#[derive(Debug, Clone)]
+pub struct TenantMoveReport {
+    pub tenant_id: StringUuid,
+    pub from_region: String,
+    pub to_region: String,
+    pub tables: Vec<TableMove>,
+    pub dry_run: bool,
+    pub purged: bool,
+}
+
+impl TenantMoveReport {
+    pub fn total_rows(&self) -> i64 {
+        self.tables.iter().map(|t| t.rows).sum()
+    }
+}
+
+/// A column of a tenant-scoped table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ColumnInfo {
+    pub(super) name: String,
+    pub(super) is_json: bool,
+}
+
+/// Whether rows of `table` follow the tenant into its region
+pub fn is_movable_table(table: &str) -> bool {
+    !CONTROL_PLANE_TABLES.contains(&table)
+}
+
+pub(super) fn quote_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+/// Select every column as text (JSON) or raw bytes so rows can be copied
+/// between databases without knowing their types
+fn copy_select_sql(table: &str, columns: &[ColumnInfo], key_column: &str) -> String {
+    let exprs: Vec<String> = columns
+        .iter()
+        .map(|c| {
+            let kind = if c.is_json { "CHAR" } else { "BINARY" };
+            format!("CAST({} AS {})", quote_ident(&c.name), kind)
+        })
+        .collect();
+    format!(
+        "SELECT {} FROM {} WHERE {} = ?",
+        exprs.join(", "),
+        quote_ident(table),
+        quote_ident(key_column)
+    )
+}
+
+fn copy_insert_sql(table: &str, columns: &[ColumnInfo]) -> String {
+    let names: Vec<String> = columns.iter().map(|c| quote_ident(&c.name)).collect();
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    format!(
+        "REPLACE INTO {} ({}) VALUES ({})",
+        quote_ident(table),
+        names.join(", "),
+        placeholders
+    )
+}
+
+/// Tables holding the tenant's rows, in name order
+async fn tenant_scoped_tables(pool: &MySqlPool) -> Result<Vec<String>> {
+    let tables: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT table_name
+        FROM information_schema.columns
+        WHERE table_schema = DATABASE()
+          AND column_name = 'tenant_id'
+        ORDER BY table_name
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to list tenant-scoped tables")?;
+    Ok(tables
+        .into_iter()
+        .map(|(t,)| t)
+        .filter(|t| is_movable_table(t))
+        .collect())
+}
+
+pub(super) async fn table_columns(pool: &MySqlPool, table: &str) -> Result<Vec<ColumnInfo>> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT column_name, data_type
+        FROM information_schema.columns
+        WHERE table_schema = DATABASE()
+          AND table_name = ?
+          AND extra NOT LIKE '%GENERATED%'
+        ORDER BY ordinal_position
+        "#,
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await
+    .with_context(|| format!("Failed to read columns of {}", table))?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, data_type)| ColumnInfo {
+            name,
+            is_json: data_type.eq_ignore_ascii_case("json"),
+        })
+        .collect())
+}
+
+async fn count_rows(pool: &MySqlPool, table: &str, tenant_id: StringUuid) -> Result<i64> {
+    let sql = format!(
+        "SELECT COUNT(*) FROM {} WHERE tenant_id = ?",
+        quote_ident(table)
+    );
+    let (count,): (i64,) = sqlx::query_as(&sql)
+        .bind(tenant_id)
+        .fetch_one(pool)
+        .await
+        .with_context(|| format!("Failed to count {} rows", table))?;
+    Ok(count)
+}
+
+/// Connection with foreign key checks off (rows arrive in table order, and
+/// parents such as roles may live in another table) and UTC timestamps
+async fn raw_connection(pool: &MySqlPool) -> Result<sqlx::pool::PoolConnection<sqlx::MySql>> {
+    let mut conn = pool.acquire().await?;
+    sqlx::query("SET FOREIGN_KEY_CHECKS = 0")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("SET time_zone = '+00:00'")
+        .execute(&mut *conn)
+        .await?;
+    Ok(conn)
+}
+
+/// Copy rows of `table` where `key_column = key` from source to target
+async fn copy_rows(
+    source: &MySqlPool,
+    target: &mut MySqlConnection,
+    table: &str,
+    key_column: &str,
+    key: StringUuid,
+) -> Result<u64> {
+    let columns = table_columns(source, table).await?;
+    let mut source_conn = raw_connection(source).await?;
+    let rows = sqlx::query(&copy_select_sql(table, &columns, key_column))
+        .bind(key)
+        .fetch_all(&mut *source_conn)
+        .await
+        .with_context(|| format!("Failed to read {} rows", table))?;
+
+    let insert_sql = copy_insert_sql(table, &columns);
+    let mut tx = target.begin().await?;
+    for row in &rows {
+        let mut insert = sqlx::query(&insert_sql);
+        for (i, column) in columns.iter().enumerate() {
+            insert = if column.is_json {
+                insert.bind(row.try_get::<Option<String>, _>(i)?)
+            } else {
+                insert.bind(row.try_get::<Option<Vec<u8>>, _>(i)?)
+            };
+        }
+        insert
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Failed to copy {} row", table))?;
+    }
+    tx.commit().await?;
+    Ok(rows.len() as u64)
+}
+
+/// Move a tenant's data into `to_region` and re-pin the tenant there
+pub async fn move_tenant_region(
+    config: &Config,
+    tenant_id: StringUuid,
+    to_region: &str,
+    options: TenantMoveOptions,
+) -> Result<TenantMoveReport> {
+    let residency = &config.data_residency;
+    if !residency.is_known_region(to_region) {
+        bail!(
+            "Unknown data region '{}' (known: {})",
+            to_region,
+            residency.regions().join(", ")
+        );
+    }
+
+    let home_pool = MySqlPoolOptions::new()
+        .max_connections(2)
+        .connect(&config.database.url)
+        .await
+        .context("Failed to connect to home database")?;
+    let router = RegionRouter::from_config(residency, &config.database, home_pool.clone())?;
+    let tenants = TenantRepositoryImpl::new(home_pool.clone());
+
+    let tenant = tenants
+        .find_by_id(tenant_id)
+        .await?
+        .with_context(|| format!("Tenant {} not found", tenant_id))?;
+    let from_region = router.region_of(&tenant).to_string();
+    if from_region == to_region {
+        bail!(
+            "Tenant {} already lives in region '{}'",
+            tenant_id,
+            to_region
+        );
+    }
+    let source = router.pool_for_region(&from_region)?.clone();
+    let target = router.pool_for_region(to_region)?.clone();
+
+    let mut report = TenantMoveReport {
+        tenant_id,
+        from_region: from_region.clone(),
+        to_region: to_region.to_string(),
+        tables: Vec::new(),
+        dry_run: options.dry_run,
+        purged: false,
+    };
+    let tables = tenant_scoped_tables(&source).await?;
+    for table in &tables {
+        let rows = count_rows(&source, table, tenant_id).await?;
+        if rows > 0 {
+            report.tables.push(TableMove {
+                table: table.clone(),
+                rows,
+            });
+        }
+    }
+    info!(
+        tenant_id = %tenant_id,
+        from_region = %from_region,
+        to_region = %to_region,
+        rows = report.total_rows(),
+        "Planned tenant region move"
+    );
+    if options.dry_run {
+        return Ok(report);
+    }
+
+    // Copy, then verify before the tenant is switched over
+    let mut target_conn = raw_connection(&target).await?;
+    if to_region != residency.home_region {
+        copy_rows(&home_pool, &mut target_conn, "tenants", "id", tenant_id).await?;
+    }
+    for table in &report.tables {
+        let copied = copy_rows(
+            &source,
+            &mut target_conn,
+            &table.table,
+            "tenant_id",
+            tenant_id,
+        )
+        .await?;
+        info!(table = %table.table, rows = copied, "Copied tenant rows");
+    }
+    for table in &report.tables {
+        let copied = count_rows(&target, &table.table, tenant_id).await?;
+        if copied != table.rows {
+            bail!(
+                "Verification failed for {}: {} rows in '{}', {} in '{}'",
+                table.table,
+                table.rows,
+                from_region,
+                copied,
+                to_region
+            );
+        }
+    }
+
+    let pinned = (to_region != residency.home_region).then(|| to_region.to_string());
+    tenants.set_data_region(tenant_id, pinned).await?;
+    info!(tenant_id = %tenant_id, to_region = %to_region, "Tenant re-pinned");
+
+    if options.purge_source {
+        let mut source_conn = raw_connection(&source).await?;
+        for table in &report.tables {
+            let sql = format!(
+                "DELETE FROM {} WHERE tenant_id = ?",
+                quote_ident(&table.table)
+            );
+            sqlx::query(&sql)
+                .bind(tenant_id)
+                .execute(&mut *source_conn)
+                .await
+                .with_context(|| format!("Failed to purge {} rows", table.table))?;
+        }
+        if from_region != residency.home_region {
+            sqlx::query("DELETE FROM tenants WHERE id = ?")
+                .bind(tenant_id)
+                .execute(&mut *source_conn)
+                .await
+                .context("Failed to purge replicated tenant row")?;
+        }
+        report.purged = true;
+    }
+
+    Ok(report)
+}
+
+/// Run migrations against every non-home region's database
+pub async fn run_regional_migrations(config: &Config) -> Result<()> {
+    for (region, url) in &config.data_residency.region_database_urls {
+        info!("Running database migrations for region '{}'...", region);
+        let mut regional = config.clone();
+        regional.database.url = url.clone();
+        super::run_migrations(&regional)
+            .await
+            .with_context(|| format!("Failed to migrate region '{}'", region))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<ColumnInfo> {
+        vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                is_json: false,
+            },
+            ColumnInfo {
+                name: "settings".to_string(),
+                is_json: true,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_control_plane_tables_stay_home() {
+        assert!(!is_movable_table("tenant_users"));
+        assert!(is_movable_table("webhooks"));
+        assert!(is_movable_table("login_events"));
+    }
+
+    #[test]
+    fn test_copy_sql_casts_columns() {
+        assert_eq!(
+            copy_select_sql("tenant_sso_connectors", &columns(), "tenant_id"),
+            "SELECT CAST(`id` AS BINARY), CAST(`settings` AS CHAR) \
+             FROM `tenant_sso_connectors` WHERE `tenant_id` = ?"
+        );
+        assert_eq!(
+            copy_insert_sql("tenant_sso_connectors", &columns()),
+            "REPLACE INTO `tenant_sso_connectors` (`id`, `settings`) VALUES (?, ?)"
+        );
+    }
+
+    #[test]
+    fn test_quote_ident_escapes_backticks() {
+        assert_eq!(quote_ident("odd`name"), "`odd``name`");
+    }
+}
@@ -0,0 +1,927 @@
+//! Zero-downtime checks for schema migrations
+//!
+//! Migrations are deployed while the previous release keeps serving, so
+//! every migration belongs to one of two phases, declared in a header
+//! comment:
+//!
+//! - `-- migrate:phase expand` (the default) only adds: new tables, nullable
+//!   or defaulted columns, indexes. Old and new code both work afterwards.
+//! - `-- migrate:phase contract` removes or renames what the current code no
+//!   longer reads. It must ship in a later release than the expand it
+//!   follows.
+//!
+//! Two more directives are understood:
+//!
+//! - `-- migrate:backfill <name>` queues the registered batched backfill
+//!   `<name>` (see [`super::backfill`]) as a background job once the
+//!   migration is applied, instead of an `UPDATE` that locks the table.
+//! - `-- migrate:allow <rule>[,<rule>]` acknowledges findings of the safety
+//!   report that were reviewed and accepted.
+//!
+//! `auth9-core migrate --safety-report` checks the pending migrations
+//! against these rules and live table sizes before a deployment.
+
+use super::backfill;
+use crate::config::{Config, MigrationSafetyConfig};
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::{Executor, MySqlPool, Row};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Longest statement excerpt quoted in a finding
+const MAX_EXCERPT_LEN: usize = 160;
+
+/// Phase of a migration in an expand/contract rollout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationPhase {
+    #[default]
+    Expand,
+    Contract,
+}
+
+/// How a finding affects the deployment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Worth reviewing; does not fail the report
+    Warning,
+    /// Likely to block traffic or break the running release
+    Danger,
+}
+
+/// A zero-downtime rule a migration can break
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyRule {
+    /// Table-rewriting `ALTER` on a large table
+    LockingDdl,
+    /// Index build on a large table without `LOCK=NONE`
+    LongIndexBuild,
+    /// Drop, rename or truncate outside a contract migration
+    DestructiveInExpand,
+    /// New `NOT NULL` column without a default
+    NotNullWithoutDefault,
+    /// `UPDATE`/`DELETE` of a large table inside the migration
+    InlineBackfill,
+    /// Contract migration deployed together with an expand of the same table
+    ContractWithExpand,
+    /// `migrate:backfill` naming no registered backfill
+    UnknownBackfill,
+}
+
+impl SafetyRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LockingDdl => "locking_ddl",
+            Self::LongIndexBuild => "long_index_build",
+            Self::DestructiveInExpand => "destructive_in_expand",
+            Self::NotNullWithoutDefault => "not_null_without_default",
+            Self::InlineBackfill => "inline_backfill",
+            Self::ContractWithExpand => "contract_with_expand",
+            Self::UnknownBackfill => "unknown_backfill",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "locking_ddl" => Some(Self::LockingDdl),
+            "long_index_build" => Some(Self::LongIndexBuild),
+            "destructive_in_expand" => Some(Self::DestructiveInExpand),
+            "not_null_without_default" => Some(Self::NotNullWithoutDefault),
+            "inline_backfill" => Some(Self::InlineBackfill),
+            "contract_with_expand" => Some(Self::ContractWithExpand),
+            "unknown_backfill" => Some(Self::UnknownBackfill),
+            _ => None,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::LongIndexBuild => Severity::Warning,
+            _ => Severity::Danger,
+        }
+    }
+}
+
+/// Directives read from a migration's `-- migrate:` comments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationAnnotations {
+    pub phase: MigrationPhase,
+    pub allow: Vec<SafetyRule>,
+    pub backfills: Vec<String>,
+}
+
+impl MigrationAnnotations {
+    pub fn parse(sql: &str) -> Result<Self> {
+        let mut annotations = Self::default();
+        for line in sql.lines() {
+            let Some(directive) = line.trim().strip_prefix("--") else {
+                continue;
+            };
+            let Some(directive) = directive.trim().strip_prefix("migrate:") else {
+                continue;
+            };
+            let (name, value) = directive
+                .split_once(char::is_whitespace)
+                .map(|(name, value)| (name, value.trim()))
+                .unwrap_or((directive, ""));
+            match name {
+                "phase" => {
+                    annotations.phase = match value {
+                        "expand" => MigrationPhase::Expand,
+                        "contract" => MigrationPhase::Contract,
+                        other => bail!("Unknown migration phase '{}'", other),
+                    }
+                }
+                "allow" => {
+                    for rule in value.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+                        annotations.allow.push(
+                            SafetyRule::parse(rule)
+                                .with_context(|| format!("Unknown safety rule '{}'", rule))?,
+                        );
+                    }
+                }
+                "backfill" => {
+                    if value.is_empty() {
+                        bail!("migrate:backfill needs a backfill name");
+                    }
+                    annotations.backfills.push(value.to_string());
+                }
+                other => bail!("Unknown migration directive 'migrate:{}'", other),
+            }
+        }
+        Ok(annotations)
+    }
+}
+
+/// A migration to check
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationSource<'a> {
+    pub version: i64,
+    pub description: &'a str,
+    pub sql: &'a str,
+}
+
+/// One problem found in a migration
+#[derive(Debug, Clone, Serialize)]
+pub struct SafetyFinding {
+    pub version: i64,
+    pub description: String,
+    pub rule: SafetyRule,
+    pub severity: Severity,
+    pub table: Option<String>,
+    pub statement: String,
+    pub message: String,
+    /// Accepted with `-- migrate:allow`
+    pub acknowledged: bool,
+}
+
+/// Result of checking a set of migrations
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SafetyReport {
+    pub migrations_checked: usize,
+    /// Phase of each checked migration, by version
+    pub phases: Vec<(i64, MigrationPhase)>,
+    pub findings: Vec<SafetyFinding>,
+}
+
+impl SafetyReport {
+    /// Unacknowledged findings that should block the deployment
+    pub fn dangers(&self) -> impl Iterator<Item = &SafetyFinding> {
+        self.findings
+            .iter()
+            .filter(|f| !f.acknowledged && f.severity == Severity::Danger)
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.dangers().next().is_none()
+    }
+}
+
+static ALTER_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^ALTER\s+(?:ONLINE\s+)?TABLE\s+`?(\w+)`?\s*(.*)$").unwrap());
+static CREATE_INDEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)^CREATE\s+(?:UNIQUE\s+|FULLTEXT\s+|SPATIAL\s+)?INDEX\s+`?\w+`?\s+ON\s+`?(\w+)`?",
+    )
+    .unwrap()
+});
+static CREATE_TABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?`?(\w+)`?").unwrap()
+});
+static DROP_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?`?(\w+)`?").unwrap());
+static TRUNCATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^TRUNCATE\s+(?:TABLE\s+)?`?(\w+)`?").unwrap());
+static RENAME_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)^RENAME\s+TABLE\s+`?(\w+)`?").unwrap());
+static UPDATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^UPDATE\s+(?:LOW_PRIORITY\s+|IGNORE\s+)*`?(\w+)`?").unwrap()
+});
+static DELETE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)^DELETE\s+(?:LOW_PRIORITY\s+|QUICK\s+|IGNORE\s+)*FROM\s+`?(\w+)`?").unwrap()
+});
+static TABLE_REWRITE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:MODIFY|CHANGE|CONVERT\s+TO|(?:ADD|DROP)\s+PRIMARY\s+KEY|PARTITION\s+BY|ENGINE|(?:DEFAULT\s+)?(?:CHARACTER\s+SET|CHARSET))\b",
+    )
+    .unwrap()
+});
+static ALGORITHM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bALGORITHM\s*=\s*(\w+)").unwrap());
+static LOCK_NONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bLOCK\s*=\s*NONE\b").unwrap());
+static ADD_INDEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^ADD\s+(?:UNIQUE|FULLTEXT|SPATIAL|INDEX|KEY)\b").unwrap());
+static ADD_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^ADD\s+(?:COLUMN\s+)?`?(\w+)`?\s").unwrap());
+static DROP_CLAUSE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^DROP\s+(?:COLUMN\s+)?`?(\w+)`?").unwrap());
+static CHANGE_CLAUSE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^CHANGE\s+(?:COLUMN\s+)?`?(\w+)`?\s+`?(\w+)`?").unwrap());
+static RENAME_CLAUSE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^RENAME\s+(\w+)").unwrap());
+
+/// Keywords after `ADD` / `DROP` that name something other than a column
+const NON_COLUMN_KEYWORDS: &[&str] = &[
+    "INDEX",
+    "KEY",
+    "UNIQUE",
+    "FULLTEXT",
+    "SPATIAL",
+    "PRIMARY",
+    "FOREIGN",
+    "CONSTRAINT",
+    "CHECK",
+    "PARTITION",
+    "DEFAULT",
+];
+
+fn is_column_name(word: &str) -> bool {
+    !NON_COLUMN_KEYWORDS.contains(&word.to_ascii_uppercase().as_str())
+}
+
+/// Split SQL into statements, dropping comments and collapsing whitespace
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            current.push(c);
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => {
+                quote = Some(c);
+                current.push(c);
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                current.push(' ');
+            }
+            '#' => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                current.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+                current.push(' ');
+            }
+            ';' => statements.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    statements.push(current);
+
+    statements
+        .into_iter()
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Split on commas outside parentheses and quotes
+fn split_clauses(spec: &str) -> Vec<&str> {
+    let mut clauses = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in spec.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                clauses.push(spec[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    clauses.push(spec[start..].trim());
+    clauses.into_iter().filter(|c| !c.is_empty()).collect()
+}
+
+/// `ALGORITHM=INSTANT`, or `ALGORITHM=INPLACE` with `LOCK=NONE`: the server
+/// refuses the change rather than blocking writes while it runs
+fn explicitly_online(spec: &str) -> bool {
+    match ALGORITHM.captures(spec).map(|c| c[1].to_ascii_uppercase()) {
+        Some(algorithm) if algorithm == "INSTANT" => true,
+        Some(algorithm) if algorithm == "INPLACE" => LOCK_NONE.is_match(spec),
+        _ => false,
+    }
+}
+
+fn excerpt(statement: &str) -> String {
+    match statement.char_indices().nth(MAX_EXCERPT_LEN) {
+        Some((end, _)) => format!("{}...", &statement[..end]),
+        None => statement.to_string(),
+    }
+}
+
+/// A rule broken by one statement
+struct StatementIssue {
+    rule: SafetyRule,
+    table: String,
+    message: String,
+}
+
+/// Table a statement changes, if any
+fn touched_table(statement: &str) -> Option<String> {
+    [
+        &*ALTER_TABLE,
+        &*CREATE_INDEX,
+        &*CREATE_TABLE,
+        &*DROP_TABLE,
+        &*TRUNCATE,
+        &*RENAME_TABLE,
+        &*UPDATE,
+        &*DELETE,
+    ]
+    .iter()
+    .find_map(|re| re.captures(statement))
+    .map(|c| c[1].to_ascii_lowercase())
+}
+
+fn check_statement(
+    statement: &str,
+    phase: MigrationPhase,
+    is_large: &dyn Fn(&str) -> bool,
+) -> Vec<StatementIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |rule: SafetyRule, table: &str, message: String| {
+        issues.push(StatementIssue {
+            rule,
+            table: table.to_string(),
+            message,
+        })
+    };
+    let expand = phase == MigrationPhase::Expand;
+
+    if let Some(captures) = ALTER_TABLE.captures(statement) {
+        let table = captures[1].to_ascii_lowercase();
+        let spec = &captures[2];
+        let large = is_large(&table);
+        let online = explicitly_online(spec);
+
+        for clause in split_clauses(spec) {
+            if let Some(c) = DROP_CLAUSE.captures(clause) {
+                if expand && is_column_name(&c[1]) {
+                    issue(
+                        SafetyRule::DestructiveInExpand,
+                        &table,
+                        format!(
+                            "Drops column {}.{} which the running release may still read; \
+                             move it to a contract migration",
+                            table, &c[1]
+                        ),
+                    );
+                }
+            } else if let Some(c) = CHANGE_CLAUSE.captures(clause) {
+                if expand && !c[1].eq_ignore_ascii_case(&c[2]) {
+                    issue(
+                        SafetyRule::DestructiveInExpand,
+                        &table,
+                        format!(
+                            "Renames column {}.{} to {}; add the new column, backfill it, \
+                             and drop the old one in a contract migration",
+                            table, &c[1], &c[2]
+                        ),
+                    );
+                }
+            } else if let Some(c) = RENAME_CLAUSE.captures(clause) {
+                let target = c[1].to_ascii_uppercase();
+                if expand && target != "INDEX" && target != "KEY" {
+                    issue(
+                        SafetyRule::DestructiveInExpand,
+                        &table,
+                        format!(
+                            "Renames {} or one of its columns under the running release; \
+                             move it to a contract migration",
+                            table
+                        ),
+                    );
+                }
+            } else if ADD_INDEX.is_match(clause) {
+                if large && !LOCK_NONE.is_match(spec) {
+                    issue(
+                        SafetyRule::LongIndexBuild,
+                        &table,
+                        format!(
+                            "Builds an index on large table {}; add LOCK=NONE so the \
+                             server refuses instead of blocking writes",
+                            table
+                        ),
+                    );
+                }
+            } else if let Some(c) = ADD_COLUMN.captures(clause) {
+                let upper = clause.to_ascii_uppercase();
+                if is_column_name(&c[1])
+                    && upper.contains("NOT NULL")
+                    && !upper.contains("DEFAULT")
+                    && !upper.contains("AUTO_INCREMENT")
+                    && !upper.contains("GENERATED")
+                {
+                    issue(
+                        SafetyRule::NotNullWithoutDefault,
+                        &table,
+                        format!(
+                            "Adds NOT NULL column {}.{} without a default; inserts from the \
+                             running release will fail",
+                            table, &c[1]
+                        ),
+                    );
+                }
+            }
+            if large && !online && TABLE_REWRITE.is_match(clause) {
+                issue(
+                    SafetyRule::LockingDdl,
+                    &table,
+                    format!(
+                        "Rewrites large table {} while blocking writes; use an expand/contract \
+                         sequence, or ALGORITHM=INSTANT / ALGORITHM=INPLACE, LOCK=NONE where \
+                         the change allows it",
+                        table
+                    ),
+                );
+            }
+        }
+    } else if let Some(c) = CREATE_INDEX.captures(statement) {
+        let table = c[1].to_ascii_lowercase();
+        if is_large(&table) && !LOCK_NONE.is_match(statement) {
+            issue(
+                SafetyRule::LongIndexBuild,
+                &table,
+                format!(
+                    "Builds an index on large table {}; add LOCK=NONE so the server \
+                     refuses instead of blocking writes",
+                    table
+                ),
+            );
+        }
+    } else if let Some(c) = DROP_TABLE
+        .captures(statement)
+        .or_else(|| TRUNCATE.captures(statement))
+        .or_else(|| RENAME_TABLE.captures(statement))
+    {
+        let table = c[1].to_ascii_lowercase();
+        if expand {
+            issue(
+                SafetyRule::DestructiveInExpand,
+                &table,
+                format!(
+                    "Removes or renames table {} under the running release; move it to a \
+                     contract migration",
+                    table
+                ),
+            );
+        }
+    } else if let Some(c) = UPDATE
+        .captures(statement)
+        .or_else(|| DELETE.captures(statement))
+    {
+        let table = c[1].to_ascii_lowercase();
+        if is_large(&table) {
+            issue(
+                SafetyRule::InlineBackfill,
+                &table,
+                format!(
+                    "Rewrites rows of large table {} in one transaction; register a batched \
+                     backfill and reference it with `-- migrate:backfill <name>`",
+                    table
+                ),
+            );
+        }
+    }
+    issues
+}
+
+/// Check migrations that will be deployed together.
+///
+/// `table_rows` holds estimated row counts; tables missing from it (such as
+/// ones created by these migrations) count as empty.
+pub fn analyze(
+    migrations: &[MigrationSource<'_>],
+    table_rows: &HashMap<String, u64>,
+    large_table_rows: u64,
+) -> Result<SafetyReport> {
+    let is_large = |table: &str| {
+        table_rows
+            .get(table)
+            .is_some_and(|&rows| rows >= large_table_rows)
+    };
+    let mut report = SafetyReport {
+        migrations_checked: migrations.len(),
+        ..Default::default()
+    };
+
+    let mut parsed = Vec::with_capacity(migrations.len());
+    for migration in migrations {
+        let annotations = MigrationAnnotations::parse(migration.sql).with_context(|| {
+            format!(
+                "Invalid annotation in migration {} ({})",
+                migration.version, migration.description
+            )
+        })?;
+        let statements = split_statements(migration.sql);
+        let tables: HashSet<String> = statements.iter().filter_map(|s| touched_table(s)).collect();
+        report.phases.push((migration.version, annotations.phase));
+        parsed.push((migration, annotations, statements, tables));
+    }
+
+    let expanded_tables: HashMap<&str, i64> = parsed
+        .iter()
+        .filter(|(_, a, _, _)| a.phase == MigrationPhase::Expand)
+        .flat_map(|(m, _, _, tables)| tables.iter().map(|t| (t.as_str(), m.version)))
+        .collect();
+
+    for (migration, annotations, statements, tables) in &parsed {
+        let mut push =
+            |rule: SafetyRule, table: Option<String>, statement: &str, message: String| {
+                report.findings.push(SafetyFinding {
+                    version: migration.version,
+                    description: migration.description.to_string(),
+                    rule,
+                    severity: rule.severity(),
+                    table,
+                    statement: excerpt(statement),
+                    message,
+                    acknowledged: annotations.allow.contains(&rule),
+                })
+            };
+
+        for statement in statements {
+            for issue in check_statement(statement, annotations.phase, &is_large) {
+                push(issue.rule, Some(issue.table), statement, issue.message);
+            }
+        }
+
+        if annotations.phase == MigrationPhase::Contract {
+            let mut overlapping: Vec<_> = tables
+                .iter()
+                .filter_map(|t| expanded_tables.get(t.as_str()).map(|v| (t, *v)))
+                .collect();
+            overlapping.sort();
+            for (table, expand_version) in overlapping {
+                push(
+                    SafetyRule::ContractWithExpand,
+                    Some(table.clone()),
+                    "",
+                    format!(
+                        "Contracts table {} in the same deployment as expand migration {}; \
+                         release the expand and the code using it first",
+                        table, expand_version
+                    ),
+                );
+            }
+        }
+
+        for name in &annotations.backfills {
+            if backfill::find(name).is_none() {
+                push(
+                    SafetyRule::UnknownBackfill,
+                    None,
+                    "",
+                    format!("No backfill named '{}' is registered", name),
+                );
+            }
+        }
+    }
+
+    report
+        .findings
+        .sort_by_key(|f| (std::cmp::Reverse(f.severity), f.version));
+    Ok(report)
+}
+
+/// Pool for running migrations: one connection whose lock waits are capped,
+/// so DDL stuck behind a long transaction fails instead of stalling traffic
+pub(super) fn migration_pool_options(config: &MigrationSafetyConfig) -> MySqlPoolOptions {
+    let timeout = config.lock_timeout_secs;
+    MySqlPoolOptions::new()
+        .max_connections(1)
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                conn.execute(
+                    format!(
+                        "SET SESSION lock_wait_timeout = {0}, innodb_lock_wait_timeout = {0}",
+                        timeout
+                    )
+                    .as_str(),
+                )
+                .await?;
+                Ok(())
+            })
+        })
+}
+
+/// Versions of embedded migrations not yet applied to the database
+pub(super) async fn pending_versions(pool: &MySqlPool, migrator: &Migrator) -> Result<Vec<i64>> {
+    let has_table: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT 1 FROM information_schema.tables
+        WHERE table_schema = DATABASE() AND table_name = '_sqlx_migrations'
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+    .context("Failed to check for _sqlx_migrations")?;
+    let applied: HashSet<i64> = if has_table.is_some() {
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = TRUE")
+            .fetch_all(pool)
+            .await
+            .context("Failed to read applied migrations")?
+            .into_iter()
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    Ok(migrator
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration() && !applied.contains(&m.version))
+        .map(|m| m.version)
+        .collect())
+}
+
+/// Estimated row counts of the database's tables
+async fn table_row_estimates(pool: &MySqlPool) -> Result<HashMap<String, u64>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT table_name, CAST(COALESCE(table_rows, 0) AS UNSIGNED)
+        FROM information_schema.tables
+        WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE'
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .context("Failed to read table sizes")?;
+    rows.iter()
+        .map(|row| {
+            let name: String = row.try_get(0)?;
+            let count: u64 = row.try_get(1)?;
+            Ok((name.to_ascii_lowercase(), count))
+        })
+        .collect()
+}
+
+/// Check the migrations pending on the configured database
+pub async fn safety_report(config: &Config) -> Result<SafetyReport> {
+    let pool = MySqlPoolOptions::new()
+        .max_connections(1)
+        .connect(&config.database.url)
+        .await
+        .context("Failed to connect to database")?;
+    let migrator = sqlx::migrate!("./migrations");
+    let pending = pending_versions(&pool, &migrator).await?;
+    let table_rows = table_row_estimates(&pool).await?;
+    pool.close().await;
+
+    let sources: Vec<MigrationSource<'_>> = migrator
+        .iter()
+        .filter(|m| pending.contains(&m.version))
+        .map(|m| MigrationSource {
+            version: m.version,
+            description: &m.description,
+            sql: &m.sql,
+        })
+        .collect();
+    analyze(
+        &sources,
+        &table_rows,
+        config.migration_safety.large_table_rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(sql: &str, large: &[&str]) -> SafetyReport {
+        let rows = large
+            .iter()
+            .map(|t| (t.to_string(), 5_000_000))
+            .collect::<HashMap<_, _>>();
+        analyze(
+            &[MigrationSource {
+                version: 1,
+                description: "test",
+                sql,
+            }],
+            &rows,
+            1_000_000,
+        )
+        .unwrap()
+    }
+
+    fn rules(report: &SafetyReport) -> Vec<SafetyRule> {
+        report.findings.iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_split_statements_ignores_comments_and_quotes() {
+        let statements = split_statements(
+            "-- header; not a statement\nCREATE TABLE a (x VARCHAR(8) DEFAULT ';');\n\
+             /* block; */ UPDATE a SET x = 'it''s'; # trailing\n",
+        );
+        assert_eq!(
+            statements,
+            vec![
+                "CREATE TABLE a (x VARCHAR(8) DEFAULT ';')",
+                "UPDATE a SET x = 'it''s'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotations() {
+        let annotations = MigrationAnnotations::parse(
+            "-- migrate:phase contract\n-- migrate:allow locking_ddl, inline_backfill\n\
+             -- migrate:backfill users_locale\nALTER TABLE users DROP COLUMN legacy;",
+        )
+        .unwrap();
+        assert_eq!(annotations.phase, MigrationPhase::Contract);
+        assert_eq!(
+            annotations.allow,
+            vec![SafetyRule::LockingDdl, SafetyRule::InlineBackfill]
+        );
+        assert_eq!(annotations.backfills, vec!["users_locale"]);
+        assert!(MigrationAnnotations::parse("-- migrate:phase later").is_err());
+        assert!(MigrationAnnotations::parse("-- migrate:allow everything").is_err());
+    }
+
+    #[test]
+    fn test_additive_migration_is_safe() {
+        let report = check(
+            "CREATE TABLE t (id CHAR(36) PRIMARY KEY);\n\
+             ALTER TABLE users ADD COLUMN locale VARCHAR(16) NULL AFTER locked_until;\n\
+             ALTER TABLE users ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'active';",
+            &["users"],
+        );
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+        assert!(report.is_safe());
+    }
+
+    #[test]
+    fn test_destructive_changes_need_contract_phase() {
+        let sql = "ALTER TABLE users DROP COLUMN legacy_id, DROP INDEX idx_legacy;\n\
+                   ALTER TABLE users CHANGE COLUMN name display_name VARCHAR(255);\n\
+                   DROP TABLE IF EXISTS old_sessions;";
+        let report = check(sql, &[]);
+        assert_eq!(
+            rules(&report),
+            vec![SafetyRule::DestructiveInExpand; 3],
+            "{:?}",
+            report.findings
+        );
+        assert!(!report.is_safe());
+
+        let contract = format!("-- migrate:phase contract\n{}", sql);
+        assert!(check(&contract, &[]).findings.is_empty());
+    }
+
+    #[test]
+    fn test_locking_ddl_only_on_large_tables() {
+        let sql = "ALTER TABLE audit_logs MODIFY COLUMN action VARCHAR(255) NOT NULL;";
+        assert_eq!(
+            rules(&check(sql, &["audit_logs"])),
+            vec![SafetyRule::LockingDdl]
+        );
+        assert!(check(sql, &[]).findings.is_empty());
+        assert!(check(
+            "ALTER TABLE audit_logs MODIFY COLUMN action VARCHAR(255) NOT NULL, \
+             ALGORITHM=INPLACE, LOCK=NONE;",
+            &["audit_logs"]
+        )
+        .findings
+        .is_empty());
+    }
+
+    #[test]
+    fn test_index_build_on_large_table_is_warning() {
+        let report = check(
+            "CREATE INDEX idx_audit_actor ON audit_logs (actor_id);",
+            &["audit_logs"],
+        );
+        assert_eq!(rules(&report), vec![SafetyRule::LongIndexBuild]);
+        assert!(report.is_safe());
+        assert!(check(
+            "ALTER TABLE audit_logs ADD INDEX idx_audit_actor (actor_id), LOCK=NONE;",
+            &["audit_logs"]
+        )
+        .findings
+        .is_empty());
+    }
+
+    #[test]
+    fn test_not_null_without_default() {
+        let report = check(
+            "ALTER TABLE users ADD COLUMN region VARCHAR(32) NOT NULL;",
+            &[],
+        );
+        assert_eq!(rules(&report), vec![SafetyRule::NotNullWithoutDefault]);
+    }
+
+    #[test]
+    fn test_inline_backfill_and_acknowledgement() {
+        let sql = "UPDATE users SET locale = 'en' WHERE locale IS NULL;";
+        let report = check(sql, &["users"]);
+        assert_eq!(rules(&report), vec![SafetyRule::InlineBackfill]);
+        assert!(!report.is_safe());
+
+        let report = check(
+            &format!("-- migrate:allow inline_backfill\n{}", sql),
+            &["users"],
+        );
+        assert!(report.findings[0].acknowledged);
+        assert!(report.is_safe());
+    }
+
+    #[test]
+    fn test_contract_with_expand_in_same_deployment() {
+        let report = analyze(
+            &[
+                MigrationSource {
+                    version: 1,
+                    description: "add display_name",
+                    sql: "ALTER TABLE users ADD COLUMN display_name VARCHAR(255) NULL;",
+                },
+                MigrationSource {
+                    version: 2,
+                    description: "drop name",
+                    sql: "-- migrate:phase contract\nALTER TABLE users DROP COLUMN name;",
+                },
+            ],
+            &HashMap::new(),
+            1_000_000,
+        )
+        .unwrap();
+        assert_eq!(rules(&report), vec![SafetyRule::ContractWithExpand]);
+        assert_eq!(report.findings[0].version, 2);
+    }
+
+    #[test]
+    fn test_unknown_backfill() {
+        let report = check("-- migrate:backfill no_such_backfill\nSELECT 1;", &[]);
+        assert_eq!(rules(&report), vec![SafetyRule::UnknownBackfill]);
+    }
+
+    #[test]
+    fn test_existing_migrations_parse() {
+        let migrator = sqlx::migrate!("./migrations");
+        for migration in migrator.iter() {
+            MigrationAnnotations::parse(&migration.sql).unwrap();
+        }
+    }
+}
@@ -60,6 +60,16 @@ pub struct AbacPolicyVersionSummary {
     pub created_by: Option<String>,
     pub created_at: String,
     pub published_at: Option<String>,
+    /// Edit revision of the draft (exposed as ETag)
+    pub revision: i32,
+}
+
+/// A policy version with its document
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AbacPolicyVersionDetail {
+    #[serde(flatten)]
+    pub version: AbacPolicyVersionSummary,
+    pub policy: AbacPolicyDocument,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,46 @@
+//! Self-service account deletion models
+//!
+//! Deleting an account schedules it: until `purge_after` the user can sign in
+//! and restore it. After that an `account_deletion` job removes the user from
+//! the database and the identity backend.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// A scheduled account deletion
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AccountDeletionRequest {
+    pub user_id: StringUuid,
+    pub requested_at: DateTime<Utc>,
+    /// The account can be restored until this time
+    pub purge_after: DateTime<Utc>,
+    pub reminder_sent_at: Option<DateTime<Utc>>,
+    /// Job deleting the account; set once the grace period has ended
+    pub purge_job_id: Option<StringUuid>,
+}
+
+impl AccountDeletionRequest {
+    /// Whether the account can still be restored
+    pub fn is_restorable(&self) -> bool {
+        self.purge_job_id.is_none()
+    }
+}
+
+/// Recipient of a deletion reminder
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountDeletionNotice {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub locale: Option<String>,
+    pub purge_after: DateTime<Utc>,
+}
+
+/// Payload of an `account_deletion` job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDeletionJobPayload {
+    pub user_id: StringUuid,
+}
@@ -0,0 +1,210 @@
+//! Account recovery models
+//!
+//! Recovery paths for users who lost their password and can no longer read
+//! their email: a one-time recovery code, a verified secondary factor, or an
+//! admin-mediated request that two distinct platform admins must approve.
+//! Every path ends with a password reset token handed back to the caller.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Distinct admin approvals an admin-mediated request needs
+pub const REQUIRED_APPROVALS: usize = 2;
+
+/// How long an admin-mediated request stays open, in hours
+pub const RECOVERY_REQUEST_TTL_HOURS: i64 = 72;
+
+/// Lifecycle of an admin-mediated recovery request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountRecoveryStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Claimed,
+}
+
+impl AccountRecoveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Claimed => "claimed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            "claimed" => Some(Self::Claimed),
+            _ => None,
+        }
+    }
+}
+
+/// Admin-mediated recovery request
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AccountRecoveryRequest {
+    pub id: StringUuid,
+    pub user_id: StringUuid,
+    /// One of `pending`, `approved`, `rejected`, `claimed`
+    pub status: String,
+    pub reason: Option<String>,
+    #[serde(skip)]
+    pub claim_secret_hash: String,
+    pub requester_ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub claimed_at: Option<DateTime<Utc>>,
+}
+
+impl AccountRecoveryRequest {
+    pub fn recovery_status(&self) -> Option<AccountRecoveryStatus> {
+        AccountRecoveryStatus::parse(&self.status)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// One admin's decision on a recovery request
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AccountRecoveryApproval {
+    pub id: StringUuid,
+    pub request_id: StringUuid,
+    pub admin_id: StringUuid,
+    /// `approve` or `reject`
+    pub decision: String,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Recovery request with the decisions recorded so far
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountRecoveryRequestDetail {
+    #[serde(flatten)]
+    pub request: AccountRecoveryRequest,
+    pub approvals: Vec<AccountRecoveryApproval>,
+}
+
+/// Secondary factor accepted in place of email for recovery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryFactor {
+    /// Code from an enrolled authenticator app
+    Totp,
+}
+
+impl RecoveryFactor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Totp => "totp",
+        }
+    }
+}
+
+/// Recover with a one-time recovery code
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct RecoveryCodeRecoveryInput {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
+
+/// Recover with a verified secondary factor
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SecondaryFactorRecoveryInput {
+    #[validate(email)]
+    pub email: String,
+    pub factor: RecoveryFactor,
+    #[validate(length(min = 1, max = 64))]
+    pub code: String,
+}
+
+/// Open an admin-mediated recovery request
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateAccountRecoveryRequestInput {
+    #[validate(email)]
+    pub email: String,
+    /// Context for the reviewing admins (how to verify the requester)
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+}
+
+/// Claim the reset token of an approved request
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ClaimAccountRecoveryInput {
+    #[validate(length(min = 1, max = 128))]
+    pub claim_secret: String,
+}
+
+/// Admin decision on a recovery request
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct AccountRecoveryDecisionInput {
+    #[validate(length(max = 1000))]
+    pub comment: Option<String>,
+}
+
+/// Returned when a recovery request is opened. The claim secret is shown
+/// only once; the requester presents it to collect the reset token after
+/// approval.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountRecoveryRequestCreated {
+    pub request_id: StringUuid,
+    pub claim_secret: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Password reset token issued by a successful recovery; redeem it at
+/// `POST /api/v1/auth/reset-password`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountRecoveryTokenResponse {
+    pub reset_token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_roundtrip() {
+        for status in [
+            AccountRecoveryStatus::Pending,
+            AccountRecoveryStatus::Approved,
+            AccountRecoveryStatus::Rejected,
+            AccountRecoveryStatus::Claimed,
+        ] {
+            assert_eq!(AccountRecoveryStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(AccountRecoveryStatus::parse("expired"), None);
+    }
+
+    #[test]
+    fn test_request_hides_claim_secret_hash() {
+        let request = AccountRecoveryRequest {
+            id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            status: "pending".to_string(),
+            reason: None,
+            claim_secret_hash: "abc".to_string(),
+            requester_ip: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now(),
+            decided_at: None,
+            claimed_at: None,
+        };
+        let value = serde_json::to_value(&request).unwrap();
+        assert!(value.get("claim_secret_hash").is_none());
+        assert!(request.is_expired());
+    }
+}
@@ -0,0 +1,182 @@
+//! Scoped platform admin permissions
+//!
+//! Platform admin access is split into scopes so an operator can, for
+//! example, read tenants without being able to rewrite system settings.
+
+use super::common::StringUuid;
+use axum::http::Method;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// A platform admin permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum AdminScope {
+    #[serde(rename = "tenants:read")]
+    TenantsRead,
+    #[serde(rename = "tenants:write")]
+    TenantsWrite,
+    /// Take over another user's account: set their password, reset MFA,
+    /// or end their sessions
+    #[serde(rename = "users:impersonate")]
+    UsersImpersonate,
+    #[serde(rename = "settings:write")]
+    SettingsWrite,
+}
+
+impl AdminScope {
+    pub const ALL: [AdminScope; 4] = [
+        Self::TenantsRead,
+        Self::TenantsWrite,
+        Self::UsersImpersonate,
+        Self::SettingsWrite,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TenantsRead => "tenants:read",
+            Self::TenantsWrite => "tenants:write",
+            Self::UsersImpersonate => "users:impersonate",
+            Self::SettingsWrite => "settings:write",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    /// Whether using this scope changes state (and is therefore audited)
+    pub fn is_write(&self) -> bool {
+        !matches!(self, Self::TenantsRead)
+    }
+
+    /// Scope a platform admin needs for the given API request, if any
+    pub fn required_for(method: &Method, path: &str) -> Option<Self> {
+        let is_read = *method == Method::GET || *method == Method::HEAD;
+
+        if path == "/api/v1/tenants" || path.starts_with("/api/v1/tenants/") {
+            return Some(if is_read {
+                Self::TenantsRead
+            } else {
+                Self::TenantsWrite
+            });
+        }
+
+        if is_read {
+            return None;
+        }
+
+        if path.starts_with("/api/v1/system/") || path == "/api/v1/security/risk-policy" {
+            return Some(Self::SettingsWrite);
+        }
+
+        if let Some(rest) = path.strip_prefix("/api/v1/users/") {
+            let mut segments = rest.split('/');
+            let user = segments.next().unwrap_or_default();
+            let action = segments.next();
+            if user != "me" && matches!(action, Some("password") | Some("mfa")) {
+                return Some(Self::UsersImpersonate);
+            }
+        }
+        if path.starts_with("/api/v1/admin/users/") && path.ends_with("/logout") {
+            return Some(Self::UsersImpersonate);
+        }
+
+        None
+    }
+}
+
+/// A scope granted to a platform admin
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AdminScopeGrant {
+    pub id: StringUuid,
+    pub user_id: StringUuid,
+    pub scope: String,
+    pub granted_by: Option<StringUuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Replace the scopes granted to a platform admin
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetAdminScopesInput {
+    pub scopes: Vec<AdminScope>,
+}
+
+/// Scopes held by a platform admin
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminScopesResponse {
+    pub user_id: StringUuid,
+    pub scopes: Vec<AdminScope>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_roundtrip() {
+        for scope in AdminScope::ALL {
+            assert_eq!(AdminScope::parse(scope.as_str()), Some(scope));
+            let json = serde_json::to_value(scope).unwrap();
+            assert_eq!(json, scope.as_str());
+        }
+        assert_eq!(AdminScope::parse("tenants:*"), None);
+    }
+
+    #[test]
+    fn test_required_for_tenants() {
+        assert_eq!(
+            AdminScope::required_for(&Method::GET, "/api/v1/tenants"),
+            Some(AdminScope::TenantsRead)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::GET, "/api/v1/tenants/abc/users"),
+            Some(AdminScope::TenantsRead)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::POST, "/api/v1/tenants"),
+            Some(AdminScope::TenantsWrite)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::DELETE, "/api/v1/tenants/abc"),
+            Some(AdminScope::TenantsWrite)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::GET, "/api/v1/tenantsx"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_required_for_settings_and_impersonation() {
+        assert_eq!(
+            AdminScope::required_for(&Method::PUT, "/api/v1/system/email"),
+            Some(AdminScope::SettingsWrite)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::GET, "/api/v1/system/email"),
+            None
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::PUT, "/api/v1/users/abc/password"),
+            Some(AdminScope::UsersImpersonate)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::DELETE, "/api/v1/users/abc/mfa"),
+            Some(AdminScope::UsersImpersonate)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::POST, "/api/v1/admin/users/abc/logout"),
+            Some(AdminScope::UsersImpersonate)
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::POST, "/api/v1/users/me/password"),
+            None
+        );
+        assert_eq!(
+            AdminScope::required_for(&Method::PUT, "/api/v1/users/abc"),
+            None
+        );
+    }
+}
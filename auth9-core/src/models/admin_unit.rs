@@ -0,0 +1,91 @@
+//! Admin unit models
+//!
+//! An admin unit groups members of a tenant (typically a department). Tenant
+//! members made admin of one or more units become delegated admins: user
+//! management, role assignment and session revocation they perform in the
+//! tenant is limited to members of the units they administer. Tenant owners
+//! and platform admins are never limited, nor are admins without units.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Admin unit entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AdminUnit {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Admin unit with its admins and members, as returned by the API
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminUnitResponse {
+    #[serde(flatten)]
+    pub unit: AdminUnit,
+    /// Delegated admins limited to this unit's members
+    pub admin_ids: Vec<StringUuid>,
+    pub member_ids: Vec<StringUuid>,
+}
+
+/// Input for creating an admin unit
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateAdminUnitInput {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Input for updating an admin unit
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateAdminUnitInput {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+/// Tenant members (at most 100) to add to a unit's members or admins
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct AdminUnitUsersInput {
+    #[validate(length(min = 1, max = 100))]
+    pub user_ids: Vec<StringUuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_input_validation() {
+        let valid = CreateAdminUnitInput {
+            name: "Engineering".to_string(),
+            description: None,
+        };
+        assert!(valid.validate().is_ok());
+
+        let empty = CreateAdminUnitInput {
+            name: String::new(),
+            description: None,
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_users_input_limits() {
+        let none = AdminUnitUsersInput { user_ids: vec![] };
+        assert!(none.validate().is_err());
+
+        let too_many = AdminUnitUsersInput {
+            user_ids: (0..=100).map(|_| StringUuid::new_v4()).collect(),
+        };
+        assert!(too_many.validate().is_err());
+    }
+}
@@ -139,6 +139,8 @@ pub enum SecurityAlertType {
     ImpossibleTravel,
     SuspiciousIp,
     HighRiskLogin,
+    /// The user reported a sign-in as not theirs ("this wasn't me" link)
+    UserReportedSignIn,
 }
 
 impl std::str::FromStr for SecurityAlertType {
@@ -153,6 +155,7 @@ impl std::str::FromStr for SecurityAlertType {
             "impossible_travel" => Ok(SecurityAlertType::ImpossibleTravel),
             "suspicious_ip" => Ok(SecurityAlertType::SuspiciousIp),
             "high_risk_login" => Ok(SecurityAlertType::HighRiskLogin),
+            "user_reported_sign_in" => Ok(SecurityAlertType::UserReportedSignIn),
             _ => Err(format!("Unknown security alert type: {}", s)),
         }
     }
@@ -168,6 +171,7 @@ impl std::fmt::Display for SecurityAlertType {
             SecurityAlertType::ImpossibleTravel => write!(f, "impossible_travel"),
             SecurityAlertType::SuspiciousIp => write!(f, "suspicious_ip"),
             SecurityAlertType::HighRiskLogin => write!(f, "high_risk_login"),
+            SecurityAlertType::UserReportedSignIn => write!(f, "user_reported_sign_in"),
         }
     }
 }
@@ -199,8 +203,8 @@ impl<'q> sqlx::Encode<'q, sqlx::MySql> for SecurityAlertType {
     }
 }
 
-/// Security alert severity levels
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+/// Security alert severity levels, ordered from low to critical
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AlertSeverity {
     Low,
@@ -209,6 +213,16 @@ pub enum AlertSeverity {
     Critical,
 }
 
+impl AlertSeverity {
+    /// This severity and the ones above it
+    pub fn and_above(&self) -> Vec<AlertSeverity> {
+        [Self::Low, Self::Medium, Self::High, Self::Critical]
+            .into_iter()
+            .filter(|severity| severity >= self)
+            .collect()
+    }
+}
+
 impl std::str::FromStr for AlertSeverity {
     type Err = String;
 
@@ -306,15 +320,22 @@ pub struct CreateSecurityAlertInput {
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Webhook {
     pub id: StringUuid,
-    pub tenant_id: StringUuid,
+    /// Owning tenant; `None` for platform webhooks
+    pub tenant_id: Option<StringUuid>,
     pub name: String,
     pub url: String,
     pub secret: Option<String>,
     #[sqlx(json)]
     pub events: Vec<String>,
+    /// CEL filter evaluated against each event; only matching events are delivered
+    pub filter_expression: Option<String>,
     pub enabled: bool,
     pub last_triggered_at: Option<DateTime<Utc>>,
     pub failure_count: i32,
+    /// Optimistic concurrency version, bumped on every configuration change
+    /// (exposed as ETag)
+    #[serde(default)]
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -324,14 +345,16 @@ impl Default for Webhook {
         let now = Utc::now();
         Self {
             id: StringUuid::new_v4(),
-            tenant_id: StringUuid::new_v4(),
+            tenant_id: Some(StringUuid::new_v4()),
             name: String::new(),
             url: String::new(),
             secret: None,
             events: Vec::new(),
+            filter_expression: None,
             enabled: true,
             last_triggered_at: None,
             failure_count: 0,
+            version: 1,
             created_at: now,
             updated_at: now,
         }
@@ -358,6 +381,9 @@ pub struct CreateWebhookInput {
     pub secret: Option<String>,
     #[validate(length(min = 1))]
     pub events: Vec<String>,
+    /// CEL filter expression, e.g. `data.role == "admin"`
+    #[validate(length(max = 1024))]
+    pub filter_expression: Option<String>,
     #[serde(default = "default_true")]
     pub enabled: bool,
 }
@@ -375,6 +401,9 @@ pub struct UpdateWebhookInput {
     pub url: Option<String>,
     pub secret: Option<String>,
     pub events: Option<Vec<String>>,
+    /// New filter expression; an empty string removes the filter
+    #[validate(length(max = 1024))]
+    pub filter_expression: Option<String>,
     pub enabled: Option<bool>,
 }
 
@@ -389,6 +418,7 @@ pub const WEBHOOK_EVENTS: &[&str] = &[
     "mfa.enabled",
     "mfa.disabled",
     "session.revoked",
+    "session.revocation",
     "security.alert",
     "scim.user.provisioned",
     "scim.user.updated",
@@ -400,8 +430,13 @@ pub const WEBHOOK_EVENTS: &[&str] = &[
     "federation.login.failed",
     "identity.linked",
     "identity.unlinked",
+    "job.completed",
 ];
 
+/// Events platform webhooks may subscribe to
+pub const PLATFORM_WEBHOOK_EVENTS: &[&str] =
+    &["tenant.created", "tenant.deleted", "security.alert"];
+
 /// Webhook event payload sent to webhook endpoints
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebhookEvent {
@@ -516,6 +551,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: Some("secret123".to_string()),
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
         assert!(input.validate().is_ok());
@@ -528,6 +564,7 @@ mod tests {
             url: "not-a-url".to_string(),
             secret: None,
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
         assert!(input.validate().is_err());
@@ -540,6 +577,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: None,
             events: vec![],
+            filter_expression: None,
             enabled: true,
         };
         assert!(input.validate().is_err());
@@ -653,6 +691,12 @@ mod tests {
             "impossible_travel".parse::<SecurityAlertType>().unwrap(),
             SecurityAlertType::ImpossibleTravel
         );
+        assert_eq!(
+            "user_reported_sign_in"
+                .parse::<SecurityAlertType>()
+                .unwrap(),
+            SecurityAlertType::UserReportedSignIn
+        );
         assert!("unknown_alert".parse::<SecurityAlertType>().is_err());
     }
 
@@ -786,6 +830,7 @@ mod tests {
             url: Some("https://example.com/new-hook".to_string()),
             secret: Some("new-secret".to_string()),
             events: Some(vec!["user.created".to_string()]),
+            filter_expression: None,
             enabled: Some(false),
         };
         assert!(input.validate().is_ok());
@@ -1156,6 +1201,7 @@ mod tests {
             url: "https://example.com/webhook".to_string(),
             secret: None,
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
         };
         assert!(input.validate().is_err());
@@ -0,0 +1,111 @@
+//! Attribute release policy models
+//!
+//! A service lists the user attributes it may receive in ID tokens and
+//! userinfo. Release is deny-by-default: a claim reaches the service only if
+//! its granted scope releases it *and* the service's policy allows it. `sub`
+//! is always released.
+
+use super::common::StringUuid;
+use super::progressive_profiling::validate_profile_field_name;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeSet;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Attributes a service may receive
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AttributeReleasePolicy {
+    pub service_id: StringUuid,
+    /// Claim names released to the service; anything else is withheld
+    #[sqlx(json)]
+    pub released_attributes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AttributeReleasePolicy {
+    /// Policy of a service that has not configured one: nothing is released
+    pub fn deny_all(service_id: StringUuid) -> Self {
+        let now = Utc::now();
+        Self {
+            service_id,
+            released_attributes: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn allows(&self, claim: &str) -> bool {
+        self.released_attributes.iter().any(|a| a == claim)
+    }
+
+    /// Drop the claims the policy does not release
+    pub fn apply(
+        &self,
+        claims: serde_json::Map<String, serde_json::Value>,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        claims
+            .into_iter()
+            .filter(|(name, _)| self.allows(name))
+            .collect()
+    }
+}
+
+/// Replace the attribute release allowlist of a service
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SetAttributeReleasePolicyInput {
+    #[validate(
+        length(max = 64),
+        custom(function = "validate_released_attribute_names")
+    )]
+    pub released_attributes: Vec<String>,
+}
+
+fn validate_released_attribute_names(names: &[String]) -> Result<(), validator::ValidationError> {
+    let mut seen = BTreeSet::new();
+    for name in names {
+        validate_profile_field_name(name)?;
+        if !seen.insert(name.as_str()) {
+            let mut err = validator::ValidationError::new("duplicate_attribute");
+            err.message = Some(format!("Attribute '{}' is listed twice", name).into());
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_policy_apply_keeps_only_allowed_claims() {
+        let mut policy = AttributeReleasePolicy::deny_all(StringUuid::new_v4());
+        let claims = json!({ "email": "a@example.com", "department": "ops" })
+            .as_object()
+            .unwrap()
+            .clone();
+        assert!(policy.apply(claims.clone()).is_empty());
+
+        policy.released_attributes = vec!["email".to_string()];
+        let released = policy.apply(claims);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released["email"], "a@example.com");
+    }
+
+    #[test]
+    fn test_set_policy_input_validation() {
+        let input = |names: &[&str]| SetAttributeReleasePolicyInput {
+            released_attributes: names.iter().map(|s| s.to_string()).collect(),
+        };
+        assert!(input(&["email", "email_verified", "cost_center"])
+            .validate()
+            .is_ok());
+        assert!(input(&[]).validate().is_ok());
+        assert!(input(&["Email"]).validate().is_err());
+        assert!(input(&["email", "email"]).validate().is_err());
+    }
+}
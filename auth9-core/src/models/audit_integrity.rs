@@ -0,0 +1,586 @@
+//! Audit log hash chain and signed checkpoints
+//!
+//! Every audit entry carries its position in the chain (`chain_seq`), the
+//! hash of the entry before it and its own hash over the canonical form of
+//! its fields. Editing an entry changes its hash, and deleting, inserting or
+//! reordering entries breaks the link to the next one. Signed checkpoints of
+//! the chain head additionally detect truncation of the newest entries and a
+//! wholesale rewrite of the chain, which plain hashes cannot.
+//!
+//! The hash covers a digest of `actor_id` instead of the ID itself, so
+//! nullifying the actor of a deleted user keeps the chain verifiable.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// `prev_hash` of the first entry in the chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Entries read per batch while verifying
+pub const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Most issues listed in a report; further issues are only counted
+pub const MAX_REPORTED_ISSUES: usize = 100;
+
+/// Fields of an audit entry covered by its hash
+#[derive(Debug, Clone, Copy)]
+pub struct ChainedFields<'a> {
+    pub chain_seq: i64,
+    pub created_at: DateTime<Utc>,
+    pub actor_digest: Option<&'a str>,
+    pub action: &'a str,
+    pub resource_type: &'a str,
+    pub resource_id: Option<&'a str>,
+    pub old_value: Option<&'a Value>,
+    pub new_value: Option<&'a Value>,
+    pub ip_address: Option<&'a str>,
+}
+
+/// SHA-256 of an actor ID, hex encoded
+pub fn actor_digest(actor_id: &str) -> String {
+    hex::encode(Sha256::digest(actor_id.as_bytes()))
+}
+
+/// Hash of an entry chained to `prev_hash`. Fields are hashed as a JSON
+/// array with object keys sorted, so the result does not depend on how
+/// MySQL reorders JSON columns.
+pub fn entry_hash(prev_hash: &str, fields: &ChainedFields<'_>) -> String {
+    let canonical = json!([
+        prev_hash,
+        fields.chain_seq,
+        fields.created_at.timestamp(),
+        fields.actor_digest,
+        fields.action,
+        fields.resource_type,
+        fields.resource_id,
+        fields.old_value.map(canonical_json),
+        fields.new_value.map(canonical_json),
+        fields.ip_address,
+    ]);
+    hex::encode(Sha256::digest(canonical.to_string().as_bytes()))
+}
+
+fn canonical_json(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let sorted: BTreeMap<&String, Value> = object
+                .iter()
+                .map(|(key, value)| (key, canonical_json(value)))
+                .collect();
+            let mut map = Map::new();
+            for (key, value) in sorted {
+                map.insert(key.clone(), value);
+            }
+            Value::Object(map)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical_json).collect()),
+        other => other.clone(),
+    }
+}
+
+fn checkpoint_mac(
+    key: &str,
+    chain_seq: i64,
+    row_hash: &str,
+    created_at: DateTime<Utc>,
+) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}:{}", chain_seq, row_hash, created_at.timestamp()).as_bytes());
+    mac
+}
+
+/// Signature of a checkpoint, hex encoded
+pub fn checkpoint_signature(
+    key: &str,
+    chain_seq: i64,
+    row_hash: &str,
+    created_at: DateTime<Utc>,
+) -> String {
+    hex::encode(
+        checkpoint_mac(key, chain_seq, row_hash, created_at)
+            .finalize()
+            .into_bytes(),
+    )
+}
+
+/// A chained audit entry as stored
+#[derive(Debug, Clone)]
+pub struct ChainEntry {
+    pub id: i64,
+    pub chain_seq: i64,
+    pub actor_id: Option<String>,
+    pub actor_digest: Option<String>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub ip_address: Option<String>,
+    pub prev_hash: String,
+    pub row_hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ChainEntry {
+    pub fn fields(&self) -> ChainedFields<'_> {
+        ChainedFields {
+            chain_seq: self.chain_seq,
+            created_at: self.created_at,
+            actor_digest: self.actor_digest.as_deref(),
+            action: &self.action,
+            resource_type: &self.resource_type,
+            resource_id: self.resource_id.as_deref(),
+            old_value: self.old_value.as_ref(),
+            new_value: self.new_value.as_ref(),
+            ip_address: self.ip_address.as_deref(),
+        }
+    }
+}
+
+/// Signed record of the chain head at some point in time
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditCheckpoint {
+    pub id: i64,
+    pub chain_seq: i64,
+    pub row_hash: String,
+    pub signature: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditCheckpoint {
+    pub fn signature_valid(&self, key: &str) -> bool {
+        hex::decode(&self.signature).is_ok_and(|signature| {
+            checkpoint_mac(key, self.chain_seq, &self.row_hash, self.created_at)
+                .verify_slice(&signature)
+                .is_ok()
+        })
+    }
+}
+
+/// What is wrong with the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditIntegrityIssueKind {
+    /// The entry's fields do not hash to its stored hash (edited entry)
+    HashMismatch,
+    /// The entry does not link to the hash of the entry before it
+    ChainBreak,
+    /// Chain positions are missing (deleted entries)
+    SequenceGap,
+    /// A chain position occurs more than once, or out of order
+    DuplicateSequence,
+    /// The actor ID does not match the hashed actor digest
+    ActorMismatch,
+    /// A checkpoint's signature does not verify
+    CheckpointSignatureInvalid,
+    /// The entry at a checkpoint's position has a different hash
+    CheckpointMismatch,
+    /// A checkpoint refers to entries that no longer exist (truncation)
+    CheckpointMissingEntries,
+    /// The recorded chain head is ahead of the newest entry
+    HeadMismatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditIntegrityIssue {
+    pub kind: AuditIntegrityIssueKind,
+    /// Chain position the issue was found at
+    pub chain_seq: Option<i64>,
+    /// Audit log entry ID, when the entry exists
+    pub audit_log_id: Option<i64>,
+    pub checkpoint_id: Option<i64>,
+    pub detail: String,
+}
+
+/// Result of a chain verification
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditIntegrityReport {
+    /// No issue was found
+    pub valid: bool,
+    pub verified_at: DateTime<Utc>,
+    pub first_seq: Option<i64>,
+    pub last_seq: Option<i64>,
+    pub entries_checked: u64,
+    pub checkpoints_checked: u64,
+    /// Whether checkpoint signatures were verified (requires the checkpoint key)
+    pub signatures_verified: bool,
+    pub issue_count: u64,
+    /// The first issues found, at most 100
+    pub issues: Vec<AuditIntegrityIssue>,
+}
+
+/// Range of a verification
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditVerifyParams {
+    /// First chain position to verify (default: oldest retained entry)
+    pub from_seq: Option<i64>,
+    /// Last chain position to verify (default: chain head)
+    pub to_seq: Option<i64>,
+}
+
+/// Incremental chain verifier, fed entries in chain order
+pub struct ChainVerifier<'a> {
+    checkpoints: BTreeMap<i64, AuditCheckpoint>,
+    checkpoint_key: Option<&'a str>,
+    previous: Option<(i64, String)>,
+    report: AuditIntegrityReport,
+}
+
+impl<'a> ChainVerifier<'a> {
+    /// `checkpoints` are those inside the verified range. Without a key,
+    /// checkpoints are compared against the chain but not authenticated.
+    pub fn new(checkpoints: Vec<AuditCheckpoint>, checkpoint_key: Option<&'a str>) -> Self {
+        let mut verifier = Self {
+            checkpoints: BTreeMap::new(),
+            checkpoint_key,
+            previous: None,
+            report: AuditIntegrityReport {
+                valid: true,
+                verified_at: Utc::now(),
+                first_seq: None,
+                last_seq: None,
+                entries_checked: 0,
+                checkpoints_checked: 0,
+                signatures_verified: checkpoint_key.is_some(),
+                issue_count: 0,
+                issues: Vec::new(),
+            },
+        };
+        for checkpoint in checkpoints {
+            verifier.report.checkpoints_checked += 1;
+            if let Some(key) = checkpoint_key {
+                if !checkpoint.signature_valid(key) {
+                    verifier.issue(
+                        AuditIntegrityIssueKind::CheckpointSignatureInvalid,
+                        Some(checkpoint.chain_seq),
+                        None,
+                        Some(checkpoint.id),
+                        "checkpoint signature does not verify".to_string(),
+                    );
+                    continue;
+                }
+            }
+            verifier
+                .checkpoints
+                .insert(checkpoint.chain_seq, checkpoint);
+        }
+        verifier
+    }
+
+    fn issue(
+        &mut self,
+        kind: AuditIntegrityIssueKind,
+        chain_seq: Option<i64>,
+        audit_log_id: Option<i64>,
+        checkpoint_id: Option<i64>,
+        detail: String,
+    ) {
+        self.report.valid = false;
+        self.report.issue_count += 1;
+        if self.report.issues.len() < MAX_REPORTED_ISSUES {
+            self.report.issues.push(AuditIntegrityIssue {
+                kind,
+                chain_seq,
+                audit_log_id,
+                checkpoint_id,
+                detail,
+            });
+        }
+    }
+
+    /// Check the next entry. The first entry's link is only checked when it
+    /// starts the chain, since older entries may have been dropped by
+    /// retention.
+    pub fn push(&mut self, entry: &ChainEntry) {
+        let seq = Some(entry.chain_seq);
+        let id = Some(entry.id);
+        self.report.entries_checked += 1;
+        self.report.first_seq.get_or_insert(entry.chain_seq);
+
+        let expected_hash = entry_hash(&entry.prev_hash, &entry.fields());
+        if expected_hash != entry.row_hash {
+            self.issue(
+                AuditIntegrityIssueKind::HashMismatch,
+                seq,
+                id,
+                None,
+                "entry content does not match its hash".to_string(),
+            );
+        }
+
+        if let Some(actor_id) = &entry.actor_id {
+            if entry.actor_digest.as_deref() != Some(actor_digest(actor_id).as_str()) {
+                self.issue(
+                    AuditIntegrityIssueKind::ActorMismatch,
+                    seq,
+                    id,
+                    None,
+                    "actor_id does not match the hashed actor digest".to_string(),
+                );
+            }
+        }
+
+        match self.previous.take() {
+            Some((previous_seq, _)) if entry.chain_seq <= previous_seq => {
+                self.issue(
+                    AuditIntegrityIssueKind::DuplicateSequence,
+                    seq,
+                    id,
+                    None,
+                    format!("follows chain position {}", previous_seq),
+                );
+            }
+            Some((previous_seq, previous_hash)) => {
+                if entry.chain_seq > previous_seq + 1 {
+                    self.issue(
+                        AuditIntegrityIssueKind::SequenceGap,
+                        seq,
+                        id,
+                        None,
+                        format!(
+                            "{} entries missing after chain position {}",
+                            entry.chain_seq - previous_seq - 1,
+                            previous_seq
+                        ),
+                    );
+                } else if entry.prev_hash != previous_hash {
+                    self.issue(
+                        AuditIntegrityIssueKind::ChainBreak,
+                        seq,
+                        id,
+                        None,
+                        "previous hash does not match the preceding entry".to_string(),
+                    );
+                }
+            }
+            None if entry.chain_seq == 1 && entry.prev_hash != GENESIS_HASH => {
+                self.issue(
+                    AuditIntegrityIssueKind::ChainBreak,
+                    seq,
+                    id,
+                    None,
+                    "first entry does not start from the genesis hash".to_string(),
+                );
+            }
+            None => {}
+        }
+
+        if let Some(checkpoint) = self.checkpoints.remove(&entry.chain_seq) {
+            if checkpoint.row_hash != entry.row_hash {
+                self.issue(
+                    AuditIntegrityIssueKind::CheckpointMismatch,
+                    seq,
+                    id,
+                    Some(checkpoint.id),
+                    "entry hash differs from the checkpointed hash".to_string(),
+                );
+            }
+        }
+
+        self.report.last_seq = Some(entry.chain_seq);
+        self.previous = Some((entry.chain_seq, entry.row_hash.clone()));
+    }
+
+    /// Finish with the recorded chain head position (`None` when only part
+    /// of the chain was verified)
+    pub fn finish(mut self, head_seq: Option<i64>) -> AuditIntegrityReport {
+        let last_seq = self.report.last_seq.unwrap_or(0);
+        let first_seq = self.report.first_seq.unwrap_or(i64::MAX);
+        let checkpoints = std::mem::take(&mut self.checkpoints);
+        for checkpoint in checkpoints.into_values() {
+            // Checkpoints before the oldest retained entry expired with it
+            if checkpoint.chain_seq < first_seq && self.report.entries_checked > 0 {
+                continue;
+            }
+            self.issue(
+                AuditIntegrityIssueKind::CheckpointMissingEntries,
+                Some(checkpoint.chain_seq),
+                None,
+                Some(checkpoint.id),
+                if checkpoint.chain_seq > last_seq {
+                    format!(
+                        "checkpoint at position {} is beyond the newest entry {}",
+                        checkpoint.chain_seq, last_seq
+                    )
+                } else {
+                    "checkpointed entry is missing".to_string()
+                },
+            );
+        }
+        if let Some(head_seq) = head_seq {
+            if head_seq > last_seq {
+                self.issue(
+                    AuditIntegrityIssueKind::HeadMismatch,
+                    Some(head_seq),
+                    None,
+                    None,
+                    format!(
+                        "chain head is at position {} but the newest entry is {}",
+                        head_seq, last_seq
+                    ),
+                );
+            }
+        }
+        self.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "test-checkpoint-key";
+
+    fn chain(len: i64) -> Vec<ChainEntry> {
+        let created_at = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let mut prev_hash = GENESIS_HASH.to_string();
+        (1..=len)
+            .map(|seq| {
+                let actor_id = format!("00000000-0000-0000-0000-{:012}", seq);
+                let mut entry = ChainEntry {
+                    id: seq + 100,
+                    chain_seq: seq,
+                    actor_digest: Some(actor_digest(&actor_id)),
+                    actor_id: Some(actor_id),
+                    action: "user.update".to_string(),
+                    resource_type: "user".to_string(),
+                    resource_id: None,
+                    old_value: Some(json!({"b": 1, "a": [true, {"y": 2, "x": 1}]})),
+                    new_value: None,
+                    ip_address: Some("10.0.0.1".to_string()),
+                    prev_hash: prev_hash.clone(),
+                    row_hash: String::new(),
+                    created_at,
+                };
+                entry.row_hash = entry_hash(&entry.prev_hash, &entry.fields());
+                prev_hash = entry.row_hash.clone();
+                entry
+            })
+            .collect()
+    }
+
+    fn checkpoint(entry: &ChainEntry) -> AuditCheckpoint {
+        let created_at = DateTime::from_timestamp(1_760_003_600, 0).unwrap();
+        AuditCheckpoint {
+            id: entry.chain_seq,
+            chain_seq: entry.chain_seq,
+            row_hash: entry.row_hash.clone(),
+            signature: checkpoint_signature(KEY, entry.chain_seq, &entry.row_hash, created_at),
+            created_at,
+        }
+    }
+
+    fn verify(
+        entries: &[ChainEntry],
+        checkpoints: Vec<AuditCheckpoint>,
+        head: i64,
+    ) -> AuditIntegrityReport {
+        let mut verifier = ChainVerifier::new(checkpoints, Some(KEY));
+        for entry in entries {
+            verifier.push(entry);
+        }
+        verifier.finish(Some(head))
+    }
+
+    fn kinds(report: &AuditIntegrityReport) -> Vec<AuditIntegrityIssueKind> {
+        report.issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[test]
+    fn test_entry_hash_ignores_json_key_order() {
+        let entries = chain(1);
+        let mut reordered = entries[0].clone();
+        reordered.old_value = Some(json!({"a": [true, {"x": 1, "y": 2}], "b": 1}));
+        assert_eq!(
+            entry_hash(&reordered.prev_hash, &reordered.fields()),
+            entries[0].row_hash
+        );
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let entries = chain(5);
+        let report = verify(&entries, vec![checkpoint(&entries[2])], 5);
+        assert!(report.valid, "{:?}", report.issues);
+        assert_eq!(report.entries_checked, 5);
+        assert_eq!(report.checkpoints_checked, 1);
+        assert_eq!((report.first_seq, report.last_seq), (Some(1), Some(5)));
+
+        // Retention dropped the oldest entries and their checkpoint
+        let report = verify(&entries[2..], vec![checkpoint(&entries[0])], 5);
+        assert!(report.valid, "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_detects_edits_deletions_and_truncation() {
+        let entries = chain(5);
+
+        let mut edited = entries.clone();
+        edited[1].action = "user.delete".to_string();
+        assert_eq!(
+            kinds(&verify(&edited, vec![], 5)),
+            vec![AuditIntegrityIssueKind::HashMismatch]
+        );
+
+        let mut rehashed = entries.clone();
+        rehashed[1].action = "user.delete".to_string();
+        rehashed[1].row_hash = entry_hash(&rehashed[1].prev_hash, &rehashed[1].fields());
+        assert_eq!(
+            kinds(&verify(&rehashed, vec![], 5)),
+            vec![AuditIntegrityIssueKind::ChainBreak]
+        );
+
+        let mut deleted = entries.clone();
+        deleted.remove(2);
+        assert_eq!(
+            kinds(&verify(&deleted, vec![], 5)),
+            vec![AuditIntegrityIssueKind::SequenceGap]
+        );
+
+        let truncated = &entries[..3];
+        assert_eq!(
+            kinds(&verify(truncated, vec![checkpoint(&entries[4])], 3)),
+            vec![AuditIntegrityIssueKind::CheckpointMissingEntries]
+        );
+        assert_eq!(
+            kinds(&verify(truncated, vec![], 5)),
+            vec![AuditIntegrityIssueKind::HeadMismatch]
+        );
+
+        let mut reassigned = entries.clone();
+        reassigned[0].actor_id = Some("someone-else".to_string());
+        assert_eq!(
+            kinds(&verify(&reassigned, vec![], 5)),
+            vec![AuditIntegrityIssueKind::ActorMismatch]
+        );
+
+        // Erasing the actor of a deleted user is not tampering
+        let mut erased = entries.clone();
+        erased[0].actor_id = None;
+        assert!(verify(&erased, vec![], 5).valid);
+    }
+
+    #[test]
+    fn test_checkpoint_signature() {
+        let entries = chain(2);
+        let mut forged = checkpoint(&entries[1]);
+        assert!(forged.signature_valid(KEY));
+        assert!(!forged.signature_valid("other-key"));
+
+        forged.row_hash = entries[0].row_hash.clone();
+        assert_eq!(
+            kinds(&verify(&entries, vec![forged], 2)),
+            vec![AuditIntegrityIssueKind::CheckpointSignatureInvalid]
+        );
+    }
+}
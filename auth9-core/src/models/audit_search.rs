@@ -0,0 +1,335 @@
+//! Audit log full-text search, histogram buckets and CSV export
+//!
+//! `audit_logs` is partitioned, and MySQL cannot put a FULLTEXT index on a
+//! partitioned table, so every entry also gets a row in `audit_log_search`
+//! holding a flattened text document (action, resource and payload JSON)
+//! under an ngram FULLTEXT index.
+
+use crate::repository::audit::{AuditLogQuery, AuditLogWithActor};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+/// Longest document stored in the search index
+const MAX_DOCUMENT_CHARS: usize = 16_000;
+
+/// Most terms taken from a search query
+const MAX_QUERY_TERMS: usize = 8;
+
+/// Most buckets a histogram request may produce
+pub const MAX_HISTOGRAM_BUCKETS: i64 = 1000;
+
+/// Most rows an export job writes (bounded by the job result store)
+pub const MAX_EXPORT_ROWS: usize = crate::domains::platform::service::job::MAX_JOB_RESULTS;
+
+/// Flatten an entry into the text indexed for full-text search.
+/// Object keys and scalar values of the payload JSON are both included so
+/// that e.g. `email` and `alice@example.com` each match.
+pub fn search_document(
+    action: &str,
+    resource_type: &str,
+    resource_id: Option<&str>,
+    old_value: Option<&Value>,
+    new_value: Option<&Value>,
+) -> String {
+    let mut parts = vec![action.to_string(), resource_type.to_string()];
+    if let Some(id) = resource_id {
+        parts.push(id.to_string());
+    }
+    for value in [old_value, new_value].into_iter().flatten() {
+        flatten_json(value, &mut parts);
+    }
+
+    let mut document = parts.join(" ");
+    if document.len() > MAX_DOCUMENT_CHARS {
+        let mut end = MAX_DOCUMENT_CHARS;
+        while !document.is_char_boundary(end) {
+            end -= 1;
+        }
+        document.truncate(end);
+    }
+    document
+}
+
+fn flatten_json(value: &Value, parts: &mut Vec<String>) {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => parts.push(b.to_string()),
+        Value::Number(n) => parts.push(n.to_string()),
+        Value::String(s) => parts.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| flatten_json(v, parts)),
+        Value::Object(map) => {
+            for (key, v) in map {
+                parts.push(key.clone());
+                flatten_json(v, parts);
+            }
+        }
+    }
+}
+
+/// Turn free text into a MySQL boolean-mode query requiring every term.
+/// Each term is quoted as a phrase, so operator characters in user input are
+/// matched literally instead of changing the query. Returns `None` when
+/// nothing searchable is left.
+pub fn boolean_search_query(q: &str) -> Option<String> {
+    let terms: Vec<String> = q
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .take(MAX_QUERY_TERMS)
+        .map(|term| format!("+\"{}\"", term))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Histogram bucket width
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditHistogramInterval {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl AuditHistogramInterval {
+    /// MySQL expression mapping `created_at` to the start of its bucket (UTC)
+    pub fn bucket_sql(&self, column: &str) -> String {
+        match self {
+            Self::Hour => format!("DATE_FORMAT({}, '%Y-%m-%d %H:00:00')", column),
+            Self::Day => format!("DATE_FORMAT({}, '%Y-%m-%d 00:00:00')", column),
+            // Weeks start on Monday
+            Self::Week => format!(
+                "DATE_FORMAT(DATE_SUB({c}, INTERVAL WEEKDAY({c}) DAY), '%Y-%m-%d 00:00:00')",
+                c = column
+            ),
+            Self::Month => format!("DATE_FORMAT({}, '%Y-%m-01 00:00:00')", column),
+        }
+    }
+
+    /// Approximate bucket width, used to bound the number of buckets
+    pub fn approx_duration(&self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+            Self::Month => Duration::days(28),
+        }
+    }
+
+    /// Default lookback when the request has no `from_date`
+    pub fn default_lookback(&self) -> Duration {
+        match self {
+            Self::Hour => Duration::days(2),
+            Self::Day => Duration::days(30),
+            Self::Week => Duration::weeks(26),
+            Self::Month => Duration::days(730),
+        }
+    }
+}
+
+/// Histogram parameters (combined with the regular audit log filters)
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct AuditHistogramParams {
+    /// Bucket width: hour, day (default), week or month
+    pub interval: Option<AuditHistogramInterval>,
+}
+
+/// Number of matching entries in one time bucket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditHistogramBucket {
+    /// Start of the bucket (UTC)
+    pub bucket_start: DateTime<Utc>,
+    pub count: i64,
+}
+
+/// Histogram response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditHistogram {
+    pub interval: AuditHistogramInterval,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    /// Non-empty buckets in chronological order
+    pub buckets: Vec<AuditHistogramBucket>,
+}
+
+/// Fill in the histogram time range and check the bucket count.
+/// Returns the query with `from_date`/`to_date` set.
+pub fn histogram_range(
+    query: &AuditLogQuery,
+    interval: AuditHistogramInterval,
+    now: DateTime<Utc>,
+) -> Result<AuditLogQuery, String> {
+    let to_date = query.to_date.unwrap_or(now);
+    let from_date = query
+        .from_date
+        .unwrap_or(to_date - interval.default_lookback());
+    if from_date > to_date {
+        return Err("from_date must not be after to_date".to_string());
+    }
+    let buckets = (to_date - from_date).num_seconds() / interval.approx_duration().num_seconds();
+    if buckets > MAX_HISTOGRAM_BUCKETS {
+        return Err(format!(
+            "Time range spans about {} buckets (max {}); narrow it or use a wider interval",
+            buckets, MAX_HISTOGRAM_BUCKETS
+        ));
+    }
+    Ok(AuditLogQuery {
+        from_date: Some(from_date),
+        to_date: Some(to_date),
+        ..query.clone()
+    })
+}
+
+/// Header row of audit CSV exports
+pub const CSV_HEADER: &[&str] = &[
+    "id",
+    "created_at",
+    "actor_id",
+    "actor_email",
+    "action",
+    "resource_type",
+    "resource_id",
+    "ip_address",
+    "old_value",
+    "new_value",
+];
+
+/// One exported row, stored as a job result until downloaded
+pub fn export_row(log: &AuditLogWithActor) -> Value {
+    serde_json::json!({
+        "id": log.id,
+        "created_at": log.created_at.to_rfc3339(),
+        "actor_id": log.actor_id,
+        "actor_email": log.actor_email,
+        "action": log.action,
+        "resource_type": log.resource_type,
+        "resource_id": log.resource_id,
+        "ip_address": log.ip_address,
+        "old_value": log.old_value.as_ref().map(Value::to_string),
+        "new_value": log.new_value.as_ref().map(Value::to_string),
+    })
+}
+
+/// Render exported rows as RFC 4180 CSV
+pub fn render_csv(rows: &[Value]) -> String {
+    let mut out = CSV_HEADER.join(",");
+    out.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = CSV_HEADER
+            .iter()
+            .map(|column| match row.get(*column) {
+                None | Some(Value::Null) => String::new(),
+                Some(Value::String(s)) => csv_field(s),
+                Some(other) => csv_field(&other.to_string()),
+            })
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    // Leading formula characters are neutralized for spreadsheet safety
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_search_document_flattens_payload() {
+        let doc = search_document(
+            "user.update",
+            "user",
+            Some("abc"),
+            Some(&json!({ "email": "old@example.com" })),
+            Some(&json!({ "email": "new@example.com", "roles": ["admin", null], "mfa": true })),
+        );
+        assert!(doc.starts_with("user.update user abc"));
+        assert!(doc.contains("email old@example.com"));
+        assert!(doc.contains("new@example.com"));
+        assert!(doc.contains("roles admin"));
+        assert!(doc.contains("mfa true"));
+    }
+
+    #[test]
+    fn test_search_document_is_truncated_on_char_boundary() {
+        let long = "é".repeat(MAX_DOCUMENT_CHARS);
+        let doc = search_document("a", "b", None, Some(&json!(long)), None);
+        assert!(doc.len() <= MAX_DOCUMENT_CHARS);
+    }
+
+    #[test]
+    fn test_boolean_search_query_requires_all_terms() {
+        assert_eq!(
+            boolean_search_query("alice  role.assign").as_deref(),
+            Some("+\"alice\" +\"role.assign\"")
+        );
+        assert_eq!(
+            boolean_search_query("-admin@example.com \"x\"").as_deref(),
+            Some("+\"-admin@example.com\" +\"x\"")
+        );
+        assert_eq!(boolean_search_query("  \"\" "), None);
+    }
+
+    #[test]
+    fn test_histogram_range_defaults_and_limits() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap();
+        let query =
+            histogram_range(&AuditLogQuery::default(), AuditHistogramInterval::Day, now).unwrap();
+        assert_eq!(query.to_date, Some(now));
+        assert_eq!(query.from_date, Some(now - Duration::days(30)));
+
+        let too_wide = AuditLogQuery {
+            from_date: Some(now - Duration::days(365)),
+            ..Default::default()
+        };
+        assert!(histogram_range(&too_wide, AuditHistogramInterval::Hour, now).is_err());
+        assert!(histogram_range(&too_wide, AuditHistogramInterval::Day, now).is_ok());
+
+        let inverted = AuditLogQuery {
+            from_date: Some(now + Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(histogram_range(&inverted, AuditHistogramInterval::Day, now).is_err());
+    }
+
+    #[test]
+    fn test_render_csv_escapes_fields() {
+        let rows = vec![json!({
+            "id": 7,
+            "created_at": "2026-05-01T00:00:00+00:00",
+            "action": "user.update",
+            "resource_type": "user",
+            "actor_email": "=HYPERLINK(\"x\")",
+            "new_value": "{\"name\":\"A, B\"}",
+        })];
+        let csv = render_csv(&rows);
+        let mut lines = csv.split("\r\n");
+        assert_eq!(lines.next().unwrap(), CSV_HEADER.join(","));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("7,2026-05-01T00:00:00+00:00,,"));
+        assert!(row.contains("\"'=HYPERLINK(\"\"x\"\")\""));
+        assert!(row.ends_with(",\"{\"\"name\"\":\"\"A, B\"\"}\""));
+    }
+}
@@ -0,0 +1,312 @@
+//! Audit share link types
+//!
+//! A share link gives someone without an account — typically an external
+//! auditor — read-only access to one filtered slice of the audit log: a
+//! bounded time window, optionally narrowed to one actor, resource or
+//! action. Links expire, can be revoked, and every view is recorded.
+
+use super::common::StringUuid;
+use crate::repository::audit::AuditLogQuery;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Default link lifetime (3 days)
+pub const DEFAULT_SHARE_EXPIRES_IN_HOURS: i64 = 72;
+
+/// Longest time window a link can expose
+pub const MAX_SHARE_WINDOW_DAYS: i64 = 366;
+
+/// The slice of the audit log a link exposes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_window"))]
+pub struct AuditShareFilter {
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub actor_id: Option<Uuid>,
+    #[validate(length(max = 50))]
+    pub resource_type: Option<String>,
+    pub resource_id: Option<Uuid>,
+    #[validate(length(max = 100))]
+    pub action: Option<String>,
+    /// Full-text search, as in the audit log list
+    #[validate(length(max = 200))]
+    pub q: Option<String>,
+}
+
+fn validate_window(filter: &AuditShareFilter) -> Result<(), ValidationError> {
+    if filter.from_date >= filter.to_date {
+        return Err(ValidationError::new("from_date_after_to_date"));
+    }
+    if filter.to_date - filter.from_date > Duration::days(MAX_SHARE_WINDOW_DAYS) {
+        return Err(ValidationError::new("window_too_long"));
+    }
+    Ok(())
+}
+
+impl AuditShareFilter {
+    /// Audit log query for one page of the shared view
+    pub fn to_query(&self, offset: i64, limit: i64) -> AuditLogQuery {
+        AuditLogQuery {
+            q: self.q.clone(),
+            actor_id: self.actor_id,
+            resource_type: self.resource_type.clone(),
+            resource_id: self.resource_id,
+            action: self.action.clone(),
+            from_date: Some(self.from_date),
+            to_date: Some(self.to_date),
+            offset: Some(offset),
+            limit: Some(limit),
+            page: None,
+        }
+    }
+}
+
+/// Audit share link entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditShareLink {
+    pub id: StringUuid,
+    /// Label shown to administrators (e.g. "SOC 2 evidence – Q3")
+    pub name: Option<String>,
+    #[sqlx(json)]
+    pub filter: AuditShareFilter,
+    pub created_by: StringUuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub access_count: i32,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Effective state of an audit share link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditShareStatus {
+    Active,
+    Expired,
+    Revoked,
+}
+
+impl AuditShareLink {
+    pub fn status_at(&self, now: DateTime<Utc>) -> AuditShareStatus {
+        if self.revoked_at.is_some() {
+            AuditShareStatus::Revoked
+        } else if self.expires_at <= now {
+            AuditShareStatus::Expired
+        } else {
+            AuditShareStatus::Active
+        }
+    }
+
+    pub fn status(&self) -> AuditShareStatus {
+        self.status_at(Utc::now())
+    }
+}
+
+impl Default for AuditShareLink {
+    fn default() -> Self {
+        let now = Utc::now();
+        Self {
+            id: StringUuid::new_v4(),
+            name: None,
+            filter: AuditShareFilter {
+                from_date: now - Duration::days(1),
+                to_date: now,
+                actor_id: None,
+                resource_type: None,
+                resource_id: None,
+                action: None,
+                q: None,
+            },
+            created_by: StringUuid::new_v4(),
+            token_hash: String::new(),
+            access_count: 0,
+            last_accessed_at: None,
+            expires_at: now + Duration::hours(DEFAULT_SHARE_EXPIRES_IN_HOURS),
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Input for creating an audit share link
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateAuditShareLinkInput {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+
+    #[validate(nested)]
+    pub filter: AuditShareFilter,
+
+    /// Custom expiration in hours (default: 72, at most 30 days)
+    #[validate(range(min = 1, max = 720))]
+    pub expires_in_hours: Option<i64>,
+}
+
+/// API representation of an audit share link (without the token hash)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditShareLinkResponse {
+    pub id: StringUuid,
+    pub name: Option<String>,
+    pub filter: AuditShareFilter,
+    pub status: AuditShareStatus,
+    pub created_by: StringUuid,
+    pub access_count: i32,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditShareLink> for AuditShareLinkResponse {
+    fn from(link: AuditShareLink) -> Self {
+        Self {
+            status: link.status(),
+            id: link.id,
+            name: link.name,
+            filter: link.filter,
+            created_by: link.created_by,
+            access_count: link.access_count,
+            last_accessed_at: link.last_accessed_at,
+            expires_at: link.expires_at,
+            revoked_at: link.revoked_at,
+            created_at: link.created_at,
+        }
+    }
+}
+
+/// Newly created link; the token and URL are only returned here
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedAuditShareLink {
+    #[serde(flatten)]
+    pub link: AuditShareLinkResponse,
+    pub token: String,
+    pub url: String,
+}
+
+/// What a share link recipient sees about the link itself
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditSharePreview {
+    pub name: Option<String>,
+    pub filter: AuditShareFilter,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// One view of the audit log through a share link
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuditShareAccess {
+    pub id: StringUuid,
+    pub link_id: StringUuid,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> AuditShareFilter {
+        AuditShareLink::default().filter
+    }
+
+    #[test]
+    fn test_share_status() {
+        let now = Utc::now();
+        let link = AuditShareLink::default();
+        assert_eq!(link.status_at(now), AuditShareStatus::Active);
+
+        let expired = AuditShareLink {
+            expires_at: now - Duration::minutes(1),
+            ..Default::default()
+        };
+        assert_eq!(expired.status_at(now), AuditShareStatus::Expired);
+
+        let revoked = AuditShareLink {
+            revoked_at: Some(now),
+            expires_at: now - Duration::minutes(1),
+            ..Default::default()
+        };
+        assert_eq!(revoked.status_at(now), AuditShareStatus::Revoked);
+    }
+
+    #[test]
+    fn test_filter_window_validation() {
+        assert!(filter().validate().is_ok());
+
+        let reversed = AuditShareFilter {
+            from_date: filter().to_date,
+            to_date: filter().from_date,
+            ..filter()
+        };
+        assert!(reversed.validate().is_err());
+
+        let too_long = AuditShareFilter {
+            from_date: filter().to_date - Duration::days(MAX_SHARE_WINDOW_DAYS + 1),
+            ..filter()
+        };
+        assert!(too_long.validate().is_err());
+    }
+
+    #[test]
+    fn test_create_input_validates_filter() {
+        let input: CreateAuditShareLinkInput = serde_json::from_value(serde_json::json!({
+            "name": "Q3 review",
+            "filter": {
+                "from_date": "2026-07-01T00:00:00Z",
+                "to_date": "2026-06-01T00:00:00Z"
+            }
+        }))
+        .unwrap();
+        assert!(input.validate().is_err());
+
+        let input = CreateAuditShareLinkInput {
+            filter: filter(),
+            ..input
+        };
+        assert!(input.validate().is_ok());
+
+        let input = CreateAuditShareLinkInput {
+            expires_in_hours: Some(24 * 31),
+            ..input
+        };
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_to_query_pins_the_filter() {
+        let actor_id = Uuid::new_v4();
+        let filter = AuditShareFilter {
+            actor_id: Some(actor_id),
+            action: Some("user.updated".to_string()),
+            ..filter()
+        };
+        let query = filter.to_query(50, 25);
+        assert_eq!(query.actor_id, Some(actor_id));
+        assert_eq!(query.action.as_deref(), Some("user.updated"));
+        assert_eq!(query.from_date, Some(filter.from_date));
+        assert_eq!(query.to_date, Some(filter.to_date));
+        assert_eq!(
+            (query.offset, query.limit, query.page),
+            (Some(50), Some(25), None)
+        );
+    }
+
+    #[test]
+    fn test_response_hides_token_hash() {
+        let link = AuditShareLink {
+            token_hash: "secret-hash".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&AuditShareLinkResponse::from(link)).unwrap();
+        assert!(!json.contains("secret-hash"));
+        assert!(json.contains("\"status\":\"active\""));
+    }
+}
@@ -0,0 +1,398 @@
+//! Point-in-time resource state reconstructed from the audit log
+
+use crate::repository::audit::AuditLog;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Maximum audit entries replayed for one reconstruction
+pub const MAX_STATE_HISTORY: i64 = 1000;
+
+/// Fields that change without an audit entry of their own and are ignored
+/// when comparing consecutive snapshots
+const VOLATILE_FIELDS: &[&str] = &["updated_at", "last_login_at", "last_active_at"];
+
+/// Resource types whose audit entries carry full before/after snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStateResource {
+    User,
+    Tenant,
+    Role,
+}
+
+impl AuditStateResource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Tenant => "tenant",
+            Self::Role => "role",
+        }
+    }
+}
+
+impl std::str::FromStr for AuditStateResource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Self::User),
+            "tenant" => Ok(Self::Tenant),
+            "role" => Ok(Self::Role),
+            _ => Err(format!(
+                "Unsupported resource type '{}' (expected user, tenant or role)",
+                s
+            )),
+        }
+    }
+}
+
+/// Kind of audit coverage problem found while replaying history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditGapKind {
+    /// History starts after the resource was created
+    MissingCreation,
+    /// Older entries were not replayed because the history is too long
+    HistoryTruncated,
+    /// The before-image of an entry differs from the state after the
+    /// previous entry, so a change happened without being audited
+    StateMismatch,
+    /// An entry references the resource after it was deleted
+    ChangeAfterDeletion,
+    /// An entry records a change without before or after values
+    MissingValues,
+}
+
+/// A place where the audit log does not fully explain the resource's state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditCoverageGap {
+    pub kind: AuditGapKind,
+    /// Entry at which the gap was detected
+    pub audit_log_id: Option<i64>,
+    pub action: Option<String>,
+    pub at: Option<DateTime<Utc>>,
+    /// Fields whose values were not explained by earlier entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+/// State of a resource at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuditStateSnapshot {
+    pub resource_type: AuditStateResource,
+    pub resource_id: String,
+    pub at: DateTime<Utc>,
+    /// Whether the resource existed at `at` according to the audit log
+    pub exists: bool,
+    /// Reconstructed state; `None` before creation or after deletion
+    pub state: Option<Value>,
+    /// Last entry applied to the state
+    pub last_audit_log_id: Option<i64>,
+    pub last_changed_at: Option<DateTime<Utc>>,
+    pub entries_replayed: usize,
+    /// True when the replayed history has no coverage gaps
+    pub consistent: bool,
+    pub gaps: Vec<AuditCoverageGap>,
+}
+
+impl AuditCoverageGap {
+    fn at_entry(kind: AuditGapKind, entry: &AuditLog, fields: Vec<String>) -> Self {
+        Self {
+            kind,
+            audit_log_id: Some(entry.id),
+            action: Some(entry.action.clone()),
+            at: Some(entry.created_at),
+            fields,
+        }
+    }
+}
+
+/// Fields of `before` that differ from `current`, ignoring volatile fields.
+/// Non-object values are compared as a whole.
+fn mismatched_fields(current: &Value, before: &Value) -> Vec<String> {
+    match (current, before) {
+        (Value::Object(current), Value::Object(before)) => {
+            let mut fields: Vec<String> = before
+                .iter()
+                .filter(|(key, _)| !VOLATILE_FIELDS.contains(&key.as_str()))
+                .filter(|(key, value)| current.get(key.as_str()) != Some(value))
+                .map(|(key, _)| key.clone())
+                .collect();
+            fields.sort();
+            fields
+        }
+        (current, before) if current != before => vec!["*".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Apply an after-image: objects are merged key by key so partial
+/// after-images keep the fields they don't mention
+fn apply_after(state: Option<Value>, after: &Value) -> Value {
+    match (state, after) {
+        (Some(Value::Object(mut current)), Value::Object(after)) => {
+            for (key, value) in after {
+                current.insert(key.clone(), value.clone());
+            }
+            Value::Object(current)
+        }
+        (_, after) => after.clone(),
+    }
+}
+
+/// Replay audit entries (oldest first, all at or before `at`) into the
+/// state of a resource, recording every coverage gap found on the way.
+///
+/// `truncated` tells that older entries exist but were not loaded.
+pub fn reconstruct_state(
+    resource_type: AuditStateResource,
+    resource_id: &str,
+    at: DateTime<Utc>,
+    history: &[AuditLog],
+    truncated: bool,
+) -> AuditStateSnapshot {
+    let mut state: Option<Value> = None;
+    let mut deleted = false;
+    let mut gaps = Vec::new();
+    let mut last_entry: Option<&AuditLog> = None;
+
+    if truncated {
+        if let Some(first) = history.first() {
+            gaps.push(AuditCoverageGap::at_entry(
+                AuditGapKind::HistoryTruncated,
+                first,
+                Vec::new(),
+            ));
+        }
+    }
+
+    for (index, entry) in history.iter().enumerate() {
+        last_entry = Some(entry);
+        match (&entry.old_value, &entry.new_value) {
+            (None, None) => {
+                gaps.push(AuditCoverageGap::at_entry(
+                    AuditGapKind::MissingValues,
+                    entry,
+                    Vec::new(),
+                ));
+                continue;
+            }
+            (Some(before), _) => match &state {
+                Some(current) => {
+                    let fields = mismatched_fields(current, before);
+                    if !fields.is_empty() {
+                        gaps.push(AuditCoverageGap::at_entry(
+                            AuditGapKind::StateMismatch,
+                            entry,
+                            fields,
+                        ));
+                        // Trust the entry's own before-image from here on
+                        state = Some(apply_after(state.take(), before));
+                    }
+                }
+                None if deleted => {
+                    gaps.push(AuditCoverageGap::at_entry(
+                        AuditGapKind::ChangeAfterDeletion,
+                        entry,
+                        Vec::new(),
+                    ));
+                    state = Some(before.clone());
+                }
+                None => {
+                    if index == 0 && !truncated {
+                        gaps.push(AuditCoverageGap::at_entry(
+                            AuditGapKind::MissingCreation,
+                            entry,
+                            Vec::new(),
+                        ));
+                    }
+                    state = Some(before.clone());
+                }
+            },
+            // Creation: the after-image below becomes the whole state
+            (None, Some(_)) => state = None,
+        }
+
+        match &entry.new_value {
+            Some(after) => {
+                state = Some(apply_after(state.take(), after));
+                deleted = false;
+            }
+            None => {
+                state = None;
+                deleted = true;
+            }
+        }
+    }
+
+    AuditStateSnapshot {
+        resource_type,
+        resource_id: resource_id.to_string(),
+        at,
+        exists: state.is_some(),
+        state,
+        last_audit_log_id: last_entry.map(|entry| entry.id),
+        last_changed_at: last_entry.map(|entry| entry.created_at),
+        entries_replayed: history.len(),
+        consistent: gaps.is_empty(),
+        gaps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    fn entry(
+        id: i64,
+        action: &str,
+        old_value: Option<Value>,
+        new_value: Option<Value>,
+    ) -> AuditLog {
+        AuditLog {
+            id,
+            actor_id: None,
+            action: action.to_string(),
+            resource_type: "user".to_string(),
+            resource_id: Some("u1".to_string()),
+            old_value,
+            new_value,
+            ip_address: None,
+            created_at: Utc::now() - Duration::hours(10 - id),
+        }
+    }
+
+    fn replay(history: &[AuditLog]) -> AuditStateSnapshot {
+        reconstruct_state(AuditStateResource::User, "u1", Utc::now(), history, false)
+    }
+
+    #[test]
+    fn test_replays_create_and_updates() {
+        let history = vec![
+            entry(
+                1,
+                "user.create",
+                None,
+                Some(json!({"name": "a", "mfa": false})),
+            ),
+            entry(
+                2,
+                "user.update",
+                Some(json!({"name": "a", "mfa": false})),
+                Some(json!({"name": "b", "mfa": false})),
+            ),
+        ];
+
+        let snapshot = replay(&history);
+        assert!(snapshot.exists);
+        assert!(snapshot.consistent);
+        assert_eq!(snapshot.state, Some(json!({"name": "b", "mfa": false})));
+        assert_eq!(snapshot.last_audit_log_id, Some(2));
+        assert_eq!(snapshot.entries_replayed, 2);
+    }
+
+    #[test]
+    fn test_deletion_clears_state() {
+        let history = vec![
+            entry(1, "role.create", None, Some(json!({"name": "a"}))),
+            entry(2, "role.delete", Some(json!({"name": "a"})), None),
+        ];
+
+        let snapshot = replay(&history);
+        assert!(!snapshot.exists);
+        assert!(snapshot.state.is_none());
+        assert!(snapshot.consistent);
+    }
+
+    #[test]
+    fn test_flags_unaudited_change() {
+        let history = vec![
+            entry(
+                1,
+                "user.create",
+                None,
+                Some(json!({"name": "a", "updated_at": 1})),
+            ),
+            entry(
+                2,
+                "user.update",
+                Some(json!({"name": "x", "updated_at": 2})),
+                Some(json!({"name": "y", "updated_at": 3})),
+            ),
+        ];
+
+        let snapshot = replay(&history);
+        assert!(!snapshot.consistent);
+        assert_eq!(snapshot.gaps.len(), 1);
+        assert_eq!(snapshot.gaps[0].kind, AuditGapKind::StateMismatch);
+        assert_eq!(snapshot.gaps[0].fields, vec!["name"]);
+        assert_eq!(snapshot.state, Some(json!({"name": "y", "updated_at": 3})));
+    }
+
+    #[test]
+    fn test_flags_missing_creation_and_values() {
+        let history = vec![
+            entry(
+                1,
+                "tenant.update",
+                Some(json!({"name": "a"})),
+                Some(json!({"name": "b"})),
+            ),
+            entry(2, "tenant.settings", None, None),
+        ];
+
+        let snapshot = replay(&history);
+        let kinds: Vec<_> = snapshot.gaps.iter().map(|gap| gap.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![AuditGapKind::MissingCreation, AuditGapKind::MissingValues]
+        );
+        assert_eq!(snapshot.state, Some(json!({"name": "b"})));
+    }
+
+    #[test]
+    fn test_flags_change_after_deletion() {
+        let history = vec![
+            entry(1, "user.create", None, Some(json!({"name": "a"}))),
+            entry(2, "user.delete", Some(json!({"name": "a"})), None),
+            entry(
+                3,
+                "user.update",
+                Some(json!({"name": "a"})),
+                Some(json!({"name": "b"})),
+            ),
+        ];
+
+        let snapshot = replay(&history);
+        assert_eq!(snapshot.gaps[0].kind, AuditGapKind::ChangeAfterDeletion);
+        assert!(snapshot.exists);
+    }
+
+    #[test]
+    fn test_truncated_history_is_flagged_once() {
+        let history = vec![entry(
+            5,
+            "user.update",
+            Some(json!({"name": "a"})),
+            Some(json!({"name": "b"})),
+        )];
+
+        let snapshot =
+            reconstruct_state(AuditStateResource::User, "u1", Utc::now(), &history, true);
+        assert_eq!(snapshot.gaps.len(), 1);
+        assert_eq!(snapshot.gaps[0].kind, AuditGapKind::HistoryTruncated);
+    }
+
+    #[test]
+    fn test_resource_type_parse() {
+        assert_eq!(
+            "role".parse::<AuditStateResource>().unwrap(),
+            AuditStateResource::Role
+        );
+        assert!("webhook".parse::<AuditStateResource>().is_err());
+    }
+}
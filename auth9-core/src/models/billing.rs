@@ -0,0 +1,280 @@
+//! Billing integration event models
+//!
+//! Usage and lifecycle events are written to the `billing_events` ledger once
+//! per idempotency key, so replays (retried requests, repeated MAU counts)
+//! never bill a tenant twice.
+
+use super::common::StringUuid;
+use crate::config::BillingSinkKind;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Kind of billing event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum BillingEventType {
+    #[serde(rename = "tenant.created")]
+    TenantCreated,
+    #[serde(rename = "usage.mau_threshold_crossed")]
+    MauThresholdCrossed,
+    #[serde(rename = "tenant.sso_enabled")]
+    SsoEnabled,
+}
+
+impl BillingEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TenantCreated => "tenant.created",
+            Self::MauThresholdCrossed => "usage.mau_threshold_crossed",
+            Self::SsoEnabled => "tenant.sso_enabled",
+        }
+    }
+}
+
+/// Delivery status of a ledger entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BillingEventStatus {
+    /// Waiting for its next delivery attempt
+    Pending,
+    /// Claimed by a dispatcher
+    Sending,
+    Delivered,
+    /// Gave up after exhausting its attempts
+    Failed,
+}
+
+impl BillingEventStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sending => "sending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// A normalized billing event about to be recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewBillingEvent {
+    /// Identifies the billable fact; recording the same key twice is a no-op
+    pub idempotency_key: String,
+    pub tenant_id: StringUuid,
+    pub event_type: BillingEventType,
+    pub data: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl NewBillingEvent {
+    pub fn tenant_created(
+        tenant_id: StringUuid,
+        name: &str,
+        slug: &str,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            idempotency_key: format!("tenant.created:{}", tenant_id),
+            tenant_id,
+            event_type: BillingEventType::TenantCreated,
+            data: json!({ "name": name, "slug": slug }),
+            occurred_at: created_at,
+        }
+    }
+
+    /// Emitted once per tenant, billing period and threshold
+    pub fn mau_threshold_crossed(
+        tenant_id: StringUuid,
+        period: &str,
+        threshold: u64,
+        monthly_active_users: i64,
+        occurred_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            idempotency_key: format!("usage.mau:{}:{}:{}", tenant_id, period, threshold),
+            tenant_id,
+            event_type: BillingEventType::MauThresholdCrossed,
+            data: json!({
+                "period": period,
+                "threshold": threshold,
+                "monthly_active_users": monthly_active_users,
+            }),
+            occurred_at,
+        }
+    }
+
+    /// Emitted once per SSO connector the first time it is enabled
+    pub fn sso_enabled(
+        tenant_id: StringUuid,
+        connector_id: &str,
+        provider_type: &str,
+        occurred_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            idempotency_key: format!("tenant.sso_enabled:{}:{}", tenant_id, connector_id),
+            tenant_id,
+            event_type: BillingEventType::SsoEnabled,
+            data: json!({
+                "connector_id": connector_id,
+                "provider_type": provider_type,
+            }),
+            occurred_at,
+        }
+    }
+}
+
+/// Billing ledger entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BillingEvent {
+    pub id: StringUuid,
+    pub idempotency_key: String,
+    pub tenant_id: StringUuid,
+    pub event_type: String,
+    #[sqlx(json)]
+    pub data: Value,
+    pub occurred_at: DateTime<Utc>,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// Ledger listing filters
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct BillingEventFilter {
+    /// Only events in this status
+    pub status: Option<BillingEventStatus>,
+    /// Only events of this tenant
+    pub tenant_id: Option<uuid::Uuid>,
+}
+
+/// Billing period (`YYYY-MM`, UTC) containing `at`
+pub fn billing_period(at: DateTime<Utc>) -> String {
+    format!("{:04}-{:02}", at.year(), at.month())
+}
+
+/// Start of the billing period containing `at`
+pub fn billing_period_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+        .single()
+        .unwrap_or(at)
+}
+
+/// Configured thresholds reached by `monthly_active_users`
+pub fn crossed_thresholds(thresholds: &[u64], monthly_active_users: i64) -> Vec<u64> {
+    let mau = monthly_active_users.max(0) as u64;
+    thresholds.iter().copied().filter(|t| *t <= mau).collect()
+}
+
+/// Request body sent to the billing sink for a ledger entry
+pub fn sink_payload(kind: BillingSinkKind, event: &BillingEvent) -> Value {
+    match kind {
+        BillingSinkKind::Http => json!({
+            "id": event.id.to_string(),
+            "idempotency_key": event.idempotency_key,
+            "type": event.event_type,
+            "tenant_id": event.tenant_id.to_string(),
+            "occurred_at": event.occurred_at,
+            "data": event.data,
+        }),
+        BillingSinkKind::StripeRelay => json!({
+            "id": format!("evt_auth9_{}", event.id.0.simple()),
+            "object": "event",
+            "type": format!("auth9.{}", event.event_type),
+            "created": event.occurred_at.timestamp(),
+            "request": { "idempotency_key": event.idempotency_key },
+            "data": {
+                "object": {
+                    "tenant_id": event.tenant_id.to_string(),
+                    "attributes": event.data,
+                }
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger_entry(new: &NewBillingEvent) -> BillingEvent {
+        BillingEvent {
+            id: StringUuid::new_v4(),
+            idempotency_key: new.idempotency_key.clone(),
+            tenant_id: new.tenant_id,
+            event_type: new.event_type.as_str().to_string(),
+            data: new.data.clone(),
+            occurred_at: new.occurred_at,
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
+        }
+    }
+
+    #[test]
+    fn test_idempotency_keys_are_stable() {
+        let tenant_id = StringUuid::new_v4();
+        let now = Utc::now();
+        let a = NewBillingEvent::mau_threshold_crossed(tenant_id, "2026-05", 1000, 1200, now);
+        let b = NewBillingEvent::mau_threshold_crossed(tenant_id, "2026-05", 1000, 1350, now);
+        let c = NewBillingEvent::mau_threshold_crossed(tenant_id, "2026-06", 1000, 1350, now);
+        assert_eq!(a.idempotency_key, b.idempotency_key);
+        assert_ne!(a.idempotency_key, c.idempotency_key);
+
+        let created = NewBillingEvent::tenant_created(tenant_id, "Acme", "acme", now);
+        assert_eq!(
+            created.idempotency_key,
+            format!("tenant.created:{}", tenant_id)
+        );
+    }
+
+    #[test]
+    fn test_billing_period() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 17, 12, 30, 0).unwrap();
+        assert_eq!(billing_period(at), "2026-03");
+        assert_eq!(
+            billing_period_start(at),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_crossed_thresholds() {
+        let thresholds = [1_000, 10_000, 100_000];
+        assert!(crossed_thresholds(&thresholds, 999).is_empty());
+        assert_eq!(crossed_thresholds(&thresholds, 1_000), vec![1_000]);
+        assert_eq!(crossed_thresholds(&thresholds, 25_000), vec![1_000, 10_000]);
+        assert!(crossed_thresholds(&thresholds, -1).is_empty());
+    }
+
+    #[test]
+    fn test_sink_payload_formats() {
+        let event = ledger_entry(&NewBillingEvent::sso_enabled(
+            StringUuid::new_v4(),
+            "okta",
+            "oidc",
+            Utc::now(),
+        ));
+
+        let http = sink_payload(BillingSinkKind::Http, &event);
+        assert_eq!(http["type"], "tenant.sso_enabled");
+        assert_eq!(http["idempotency_key"], event.idempotency_key);
+        assert_eq!(http["data"]["provider_type"], "oidc");
+
+        let stripe = sink_payload(BillingSinkKind::StripeRelay, &event);
+        assert_eq!(stripe["object"], "event");
+        assert_eq!(stripe["type"], "auth9.tenant.sso_enabled");
+        assert_eq!(stripe["request"]["idempotency_key"], event.idempotency_key);
+        assert_eq!(
+            stripe["data"]["object"]["tenant_id"],
+            event.tenant_id.to_string()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Branding configuration domain types
 
-use super::common::validate_url_no_ssrf_strict_option;
+use super::common::{validate_url_no_ssrf_strict_option, StringUuid};
+use super::tenant::Tenant;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
@@ -43,6 +44,8 @@ pub const DEFAULT_SECONDARY_COLOR: &str = "#5856D6";
 pub const DEFAULT_BACKGROUND_COLOR: &str = "#F5F5F7";
 /// Default text color
 pub const DEFAULT_TEXT_COLOR: &str = "#1D1D1F";
+/// Brand name when neither the tenant nor the platform sets one
+pub const DEFAULT_BRAND_NAME: &str = "Auth9";
 /// Maximum custom CSS size (50KB)
 pub const MAX_CUSTOM_CSS_SIZE: usize = 50 * 1024;
 
@@ -52,7 +55,7 @@ lazy_static::lazy_static! {
 }
 
 /// Validate hex color format
-fn validate_hex_color(color: &str) -> Result<(), validator::ValidationError> {
+pub(crate) fn validate_hex_color(color: &str) -> Result<(), validator::ValidationError> {
     if HEX_COLOR_REGEX.is_match(color) {
         Ok(())
     } else {
@@ -127,6 +130,16 @@ pub struct BrandingConfig {
     /// Whether to enable Email OTP (passwordless) login (default: false)
     #[serde(default)]
     pub email_otp_enabled: bool,
+
+    /// Support contact shown on hosted pages and in emails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(email, length(max = 320))]
+    pub support_email: Option<String>,
+
+    /// Help center or support page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(url, length(max = 2048))]
+    pub support_url: Option<String>,
 }
 
 impl Default for BrandingConfig {
@@ -142,6 +155,8 @@ impl Default for BrandingConfig {
             favicon_url: None,
             allow_registration: false,
             email_otp_enabled: false,
+            support_email: None,
+            support_url: None,
         }
     }
 }
@@ -188,6 +203,102 @@ pub struct PublicBrandingQuery {
     pub client_id: Option<String>,
 }
 
+/// Query parameters for the resolved branding endpoint
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct BrandingContextQuery {
+    pub tenant_id: Option<StringUuid>,
+    pub tenant_slug: Option<String>,
+}
+
+/// Branding resolved for one tenant: its own name, logo, color and support
+/// links layered over the platform configuration. Email templates and hosted
+/// pages both render from this.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BrandingContext {
+    /// Tenant the branding was resolved for (None for platform defaults)
+    pub tenant_id: Option<StringUuid>,
+    pub name: String,
+    pub logo_url: Option<String>,
+    pub favicon_url: Option<String>,
+    pub primary_color: String,
+    pub secondary_color: String,
+    pub background_color: String,
+    pub text_color: String,
+    pub support_email: Option<String>,
+    pub support_url: Option<String>,
+}
+
+impl Default for BrandingContext {
+    fn default() -> Self {
+        Self::resolve(&BrandingConfig::default(), None)
+    }
+}
+
+impl BrandingContext {
+    /// Layer `tenant`'s branding over the platform configuration. Tenant
+    /// values that are unset, empty or not a hex color fall back.
+    pub fn resolve(platform: &BrandingConfig, tenant: Option<&Tenant>) -> Self {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        let platform_name =
+            non_empty(&platform.company_name).unwrap_or_else(|| DEFAULT_BRAND_NAME.to_string());
+
+        let Some(tenant) = tenant else {
+            return Self {
+                tenant_id: None,
+                name: platform_name,
+                logo_url: non_empty(&platform.logo_url),
+                favicon_url: non_empty(&platform.favicon_url),
+                primary_color: platform.primary_color.clone(),
+                secondary_color: platform.secondary_color.clone(),
+                background_color: platform.background_color.clone(),
+                text_color: platform.text_color.clone(),
+                support_email: non_empty(&platform.support_email),
+                support_url: non_empty(&platform.support_url),
+            };
+        };
+
+        let branding = &tenant.settings.branding;
+        Self {
+            tenant_id: Some(tenant.id),
+            name: Some(tenant.name.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or(platform_name),
+            logo_url: non_empty(&branding.logo_url)
+                .or_else(|| non_empty(&tenant.logo_url))
+                .or_else(|| non_empty(&platform.logo_url)),
+            favicon_url: non_empty(&platform.favicon_url),
+            primary_color: branding
+                .primary_color
+                .clone()
+                .filter(|c| validate_hex_color(c).is_ok())
+                .unwrap_or_else(|| platform.primary_color.clone()),
+            secondary_color: platform.secondary_color.clone(),
+            background_color: platform.background_color.clone(),
+            text_color: platform.text_color.clone(),
+            support_email: non_empty(&branding.support_email)
+                .or_else(|| non_empty(&platform.support_email)),
+            support_url: non_empty(&branding.support_url)
+                .or_else(|| non_empty(&platform.support_url)),
+        }
+    }
+
+    /// Email template variables for this branding. Unset values are empty
+    /// strings so placeholders never leak into a rendered email.
+    pub fn template_variables(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("app_name", self.name.clone()),
+            ("brand_name", self.name.clone()),
+            ("brand_logo_url", self.logo_url.clone().unwrap_or_default()),
+            ("brand_primary_color", self.primary_color.clone()),
+            (
+                "support_email",
+                self.support_email.clone().unwrap_or_default(),
+            ),
+            ("support_url", self.support_url.clone().unwrap_or_default()),
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,6 +343,8 @@ mod tests {
             favicon_url: None,
             allow_registration: false,
             email_otp_enabled: false,
+            support_email: None,
+            support_url: None,
         };
         assert!(config.validate().is_ok());
     }
@@ -331,6 +444,8 @@ mod tests {
             favicon_url: Some("https://example.com/favicon.ico".to_string()),
             allow_registration: true,
             email_otp_enabled: false,
+            support_email: None,
+            support_url: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -547,4 +662,91 @@ mod tests {
         assert_eq!(request.config.primary_color, "#FF0000");
         assert!(request.validate().is_ok());
     }
+
+    #[test]
+    fn test_support_links_validated() {
+        let config = BrandingConfig {
+            support_email: Some("not-an-email".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = BrandingConfig {
+            support_email: Some("help@example.com".to_string()),
+            support_url: Some("https://help.example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_context_falls_back_to_platform() {
+        let platform = BrandingConfig {
+            company_name: Some("Example Cloud".to_string()),
+            logo_url: Some("https://cdn.example.com/logo.png".to_string()),
+            support_email: Some("help@example.com".to_string()),
+            ..Default::default()
+        };
+        let context = BrandingContext::resolve(&platform, None);
+        assert_eq!(context.tenant_id, None);
+        assert_eq!(context.name, "Example Cloud");
+        assert_eq!(context.primary_color, DEFAULT_PRIMARY_COLOR);
+        assert_eq!(context.support_email.as_deref(), Some("help@example.com"));
+
+        assert_eq!(BrandingContext::default().name, DEFAULT_BRAND_NAME);
+    }
+
+    #[test]
+    fn test_context_layers_tenant_over_platform() {
+        let platform = BrandingConfig {
+            logo_url: Some("https://cdn.example.com/logo.png".to_string()),
+            support_email: Some("help@example.com".to_string()),
+            support_url: Some("https://help.example.com".to_string()),
+            ..Default::default()
+        };
+        let mut tenant = Tenant {
+            name: "Acme".to_string(),
+            logo_url: Some("https://acme.com/logo.png".to_string()),
+            ..Default::default()
+        };
+        tenant.settings.branding.primary_color = Some("#FF5733".to_string());
+        tenant.settings.branding.support_email = Some("it@acme.com".to_string());
+        tenant.settings.branding.support_url = Some(String::new());
+
+        let context = BrandingContext::resolve(&platform, Some(&tenant));
+        assert_eq!(context.tenant_id, Some(tenant.id));
+        assert_eq!(context.name, "Acme");
+        assert_eq!(
+            context.logo_url.as_deref(),
+            Some("https://acme.com/logo.png")
+        );
+        assert_eq!(context.primary_color, "#FF5733");
+        assert_eq!(context.support_email.as_deref(), Some("it@acme.com"));
+        assert_eq!(
+            context.support_url.as_deref(),
+            Some("https://help.example.com")
+        );
+    }
+
+    #[test]
+    fn test_context_ignores_invalid_tenant_color() {
+        let mut tenant = Tenant {
+            name: "Acme".to_string(),
+            ..Default::default()
+        };
+        tenant.settings.branding.primary_color = Some("red; } body { display: none".to_string());
+        let context = BrandingContext::resolve(&BrandingConfig::default(), Some(&tenant));
+        assert_eq!(context.primary_color, DEFAULT_PRIMARY_COLOR);
+    }
+
+    #[test]
+    fn test_context_template_variables() {
+        let vars: std::collections::HashMap<_, _> = BrandingContext::default()
+            .template_variables()
+            .into_iter()
+            .collect();
+        assert_eq!(vars["app_name"], DEFAULT_BRAND_NAME);
+        assert_eq!(vars["brand_primary_color"], DEFAULT_PRIMARY_COLOR);
+        assert_eq!(vars["support_email"], "");
+    }
 }
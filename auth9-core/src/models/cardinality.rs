@@ -0,0 +1,229 @@
+//! Cardinality caps on tenant membership and role assignments
+//!
+//! Unbounded relations (tens of thousands of roles on one user, permission
+//! lists nobody can review) have caused slow token issuance and RBAC
+//! queries. Growing a relation past its cap is rejected; nearing it emits a
+//! metric and a warning so operators can act before users hit the limit.
+
+use super::common::StringUuid;
+use crate::config::CardinalityConfig;
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A relation whose size is capped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CardinalityCap {
+    /// Members of a tenant
+    UsersPerTenant,
+    /// RBAC roles assigned to one user in a tenant
+    RolesPerUser,
+    /// Permissions granted directly to one role
+    PermissionsPerRole,
+}
+
+impl CardinalityCap {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UsersPerTenant => "users_per_tenant",
+            Self::RolesPerUser => "roles_per_user",
+            Self::PermissionsPerRole => "permissions_per_role",
+        }
+    }
+
+    /// What is being counted, for error messages
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::UsersPerTenant => "users in a tenant",
+            Self::RolesPerUser => "roles per user in a tenant",
+            Self::PermissionsPerRole => "permissions per role",
+        }
+    }
+}
+
+/// Per-tenant overrides of the platform caps, stored in tenant settings and
+/// only changeable by platform admins. `0` lifts a cap for the tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CardinalityLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_users_per_tenant: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_roles_per_user: Option<u64>,
+    /// Applies to roles of services owned by the tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_permissions_per_role: Option<u64>,
+}
+
+impl CardinalityLimits {
+    fn get(&self, cap: CardinalityCap) -> Option<u64> {
+        match cap {
+            CardinalityCap::UsersPerTenant => self.max_users_per_tenant,
+            CardinalityCap::RolesPerUser => self.max_roles_per_user,
+            CardinalityCap::PermissionsPerRole => self.max_permissions_per_role,
+        }
+    }
+}
+
+impl CardinalityConfig {
+    /// Effective cap for a tenant (`0` = unlimited)
+    pub fn limit(&self, cap: CardinalityCap, overrides: Option<&CardinalityLimits>) -> u64 {
+        overrides
+            .and_then(|limits| limits.get(cap))
+            .unwrap_or(match cap {
+                CardinalityCap::UsersPerTenant => self.max_users_per_tenant,
+                CardinalityCap::RolesPerUser => self.max_roles_per_user,
+                CardinalityCap::PermissionsPerRole => self.max_permissions_per_role,
+            })
+    }
+
+    /// Check that growing the relation of `subject` from `current` to
+    /// `proposed` entries stays within its cap. Writes that do not grow the
+    /// relation always pass, so lowering a cap never blocks cleanup.
+    pub fn check(
+        &self,
+        cap: CardinalityCap,
+        overrides: Option<&CardinalityLimits>,
+        subject: StringUuid,
+        current: u64,
+        proposed: u64,
+    ) -> Result<()> {
+        let limit = self.limit(cap, overrides);
+        if limit == 0 || proposed <= current {
+            return Ok(());
+        }
+        if proposed > limit {
+            metrics::counter!("auth9_cardinality_cap_rejected_total", "cap" => cap.as_str())
+                .increment(1);
+            tracing::warn!(
+                cap = cap.as_str(),
+                subject = %subject,
+                limit,
+                proposed,
+                "Rejected write exceeding cardinality cap"
+            );
+            return Err(AppError::CardinalityLimitExceeded {
+                cap,
+                limit,
+                requested: proposed,
+            });
+        }
+        if proposed.saturating_mul(100) >= limit.saturating_mul(self.warn_percent as u64) {
+            metrics::counter!("auth9_cardinality_cap_near_total", "cap" => cap.as_str())
+                .increment(1);
+            tracing::warn!(
+                cap = cap.as_str(),
+                subject = %subject,
+                limit,
+                proposed,
+                "Approaching cardinality cap"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CardinalityConfig {
+        CardinalityConfig {
+            max_users_per_tenant: 10,
+            max_roles_per_user: 5,
+            max_permissions_per_role: 0,
+            warn_percent: 80,
+        }
+    }
+
+    #[test]
+    fn test_limit_prefers_tenant_override() {
+        let overrides = CardinalityLimits {
+            max_roles_per_user: Some(50),
+            ..Default::default()
+        };
+        let config = config();
+        assert_eq!(
+            config.limit(CardinalityCap::RolesPerUser, Some(&overrides)),
+            50
+        );
+        assert_eq!(
+            config.limit(CardinalityCap::UsersPerTenant, Some(&overrides)),
+            10
+        );
+        assert_eq!(config.limit(CardinalityCap::RolesPerUser, None), 5);
+    }
+
+    #[test]
+    fn test_check_rejects_growth_past_cap() {
+        let subject = StringUuid::new_v4();
+        let config = config();
+        assert!(config
+            .check(CardinalityCap::UsersPerTenant, None, subject, 9, 10)
+            .is_ok());
+        let err = config
+            .check(CardinalityCap::UsersPerTenant, None, subject, 10, 11)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AppError::CardinalityLimitExceeded {
+                cap: CardinalityCap::UsersPerTenant,
+                limit: 10,
+                requested: 11,
+            }
+        ));
+    }
+
+    #[test]
+    fn test_check_allows_shrinking_over_cap() {
+        let config = config();
+        assert!(config
+            .check(
+                CardinalityCap::RolesPerUser,
+                None,
+                StringUuid::new_v4(),
+                8,
+                7
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_zero_is_unlimited() {
+        let config = config();
+        assert!(config
+            .check(
+                CardinalityCap::PermissionsPerRole,
+                None,
+                StringUuid::new_v4(),
+                0,
+                100_000
+            )
+            .is_ok());
+        let lifted = CardinalityLimits {
+            max_users_per_tenant: Some(0),
+            ..Default::default()
+        };
+        assert!(config
+            .check(
+                CardinalityCap::UsersPerTenant,
+                Some(&lifted),
+                StringUuid::new_v4(),
+                10,
+                11
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_limits_serde_omits_unset() {
+        let limits = CardinalityLimits {
+            max_users_per_tenant: Some(500_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(limits).unwrap(),
+            serde_json::json!({ "max_users_per_tenant": 500000 })
+        );
+    }
+}
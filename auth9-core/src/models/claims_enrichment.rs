@@ -0,0 +1,147 @@
+//! Token claims enrichment models
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Default time budget for one enrichment plugin call
+pub const DEFAULT_ENRICHMENT_TIMEOUT_MS: u32 = 500;
+
+/// What happens to token issuance when an enrichment plugin fails or times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichmentFailurePolicy {
+    /// Issue the token without the plugin's claims
+    #[default]
+    Skip,
+    /// Refuse to issue the token
+    Deny,
+}
+
+impl std::str::FromStr for EnrichmentFailurePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!("Unknown enrichment failure policy: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for EnrichmentFailurePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for EnrichmentFailurePolicy {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for EnrichmentFailurePolicy {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for EnrichmentFailurePolicy {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        let s = self.to_string();
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&s.as_str(), buf)
+    }
+}
+
+/// An enrichment plugin enabled for a service
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ServiceClaimsEnricher {
+    pub id: StringUuid,
+    pub service_id: StringUuid,
+    /// Name of a plugin in the compiled-in registry
+    pub plugin: String,
+    /// Plugin-specific settings
+    #[sqlx(json)]
+    pub settings: serde_json::Value,
+    pub timeout_ms: u32,
+    pub failure_policy: EnrichmentFailurePolicy,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for enabling or reconfiguring a plugin on a service
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct UpsertClaimsEnricherInput {
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+    #[validate(range(min = 10, max = 5000))]
+    pub timeout_ms: Option<u32>,
+    pub failure_policy: Option<EnrichmentFailurePolicy>,
+    pub enabled: Option<bool>,
+}
+
+/// Facts about the token being issued, passed to every enrichment plugin
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EnrichmentContext {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub tenant_id: StringUuid,
+    pub service_id: StringUuid,
+    pub client_id: String,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_policy_roundtrip() {
+        for policy in [EnrichmentFailurePolicy::Skip, EnrichmentFailurePolicy::Deny] {
+            assert_eq!(
+                policy
+                    .to_string()
+                    .parse::<EnrichmentFailurePolicy>()
+                    .unwrap(),
+                policy
+            );
+        }
+        assert!("retry".parse::<EnrichmentFailurePolicy>().is_err());
+        assert_eq!(
+            EnrichmentFailurePolicy::default(),
+            EnrichmentFailurePolicy::Skip
+        );
+    }
+
+    #[test]
+    fn test_upsert_input_timeout_bounds() {
+        let input = UpsertClaimsEnricherInput {
+            timeout_ms: Some(5),
+            ..Default::default()
+        };
+        assert!(input.validate().is_err());
+
+        let input: UpsertClaimsEnricherInput =
+            serde_json::from_str(r#"{"timeout_ms": 250, "failure_policy": "deny"}"#).unwrap();
+        assert!(input.validate().is_ok());
+        assert_eq!(input.failure_policy, Some(EnrichmentFailurePolicy::Deny));
+    }
+}
@@ -0,0 +1,250 @@
+//! OAuth 2.0 Dynamic Client Registration models (RFC 7591 / RFC 7592)
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Grant types a dynamically registered client may request
+pub const SUPPORTED_GRANT_TYPES: &[&str] =
+    &["authorization_code", "refresh_token", "client_credentials"];
+
+/// Response types a dynamically registered client may request
+pub const SUPPORTED_RESPONSE_TYPES: &[&str] = &["code"];
+
+/// Token endpoint authentication methods available to registered clients.
+/// Registered clients are always confidential.
+pub const SUPPORTED_AUTH_METHODS: &[&str] = &["client_secret_basic", "client_secret_post"];
+
+/// Issuer trusted to sign software statements (RS256)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SoftwareStatementIssuer {
+    /// Expected `iss` claim of the software statement
+    pub issuer: String,
+    /// PEM-encoded RSA public key
+    pub public_key_pem: String,
+}
+
+/// Per-tenant dynamic client registration policy
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ClientRegistrationPolicy {
+    pub tenant_id: StringUuid,
+    pub enabled: bool,
+    pub require_software_statement: bool,
+    #[sqlx(json)]
+    pub software_statement_issuers: Vec<SoftwareStatementIssuer>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ClientRegistrationPolicy {
+    /// Policy for tenants that never configured registration (disabled)
+    pub fn disabled(tenant_id: StringUuid) -> Self {
+        let now = Utc::now();
+        Self {
+            tenant_id,
+            enabled: false,
+            require_software_statement: false,
+            software_statement_issuers: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Input for replacing a tenant's registration policy
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateClientRegistrationPolicyInput {
+    pub enabled: bool,
+    #[serde(default)]
+    pub require_software_statement: bool,
+    #[serde(default)]
+    #[validate(length(max = 20))]
+    pub software_statement_issuers: Vec<SoftwareStatementIssuer>,
+}
+
+/// Initial access token record (hash only)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InitialAccessToken {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub description: Option<String>,
+    /// Maximum number of registrations (unlimited when absent)
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_by: Option<StringUuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl InitialAccessToken {
+    /// Whether the token can still authorize a registration at `now`
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| now < expires_at)
+            && self.max_uses.is_none_or(|max| self.use_count < max)
+    }
+}
+
+/// Input for issuing an initial access token
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateInitialAccessTokenInput {
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+    /// Lifetime in seconds (1 minute to 90 days); never expires when absent
+    #[validate(range(min = 60, max = 7_776_000))]
+    pub expires_in_secs: Option<i64>,
+    #[validate(range(min = 1))]
+    pub max_uses: Option<i32>,
+}
+
+/// Newly issued initial access token; the raw token is shown only once
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InitialAccessTokenCreated {
+    #[serde(flatten)]
+    pub token: InitialAccessToken,
+    pub access_token: String,
+}
+
+/// Client metadata (RFC 7591 Section 2)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ClientMetadata {
+    #[serde(default)]
+    pub redirect_uris: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_endpoint_auth_method: Option<String>,
+    #[serde(default)]
+    pub grant_types: Vec<String>,
+    #[serde(default)]
+    pub response_types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contacts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tos_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub software_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub software_version: Option<String>,
+    /// OpenID Connect RP-Initiated Logout redirect URIs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_logout_redirect_uris: Vec<String>,
+    /// Signed JWT asserting metadata values (RFC 7591 Section 2.3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub software_statement: Option<String>,
+}
+
+/// Dynamically registered client record
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ClientRegistration {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub service_id: StringUuid,
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub registration_token_hash: String,
+    pub initial_token_id: Option<StringUuid>,
+    pub software_id: Option<String>,
+    #[sqlx(json)]
+    pub metadata: ClientMetadata,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Client information response (RFC 7591 Section 3.2.1 / RFC 7592 Section 3)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientInformationResponse {
+    pub client_id: String,
+    /// Only returned when the secret is issued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub client_id_issued_at: i64,
+    /// 0: the secret does not expire
+    pub client_secret_expires_at: i64,
+    /// Only returned when the token is issued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_access_token: Option<String>,
+    pub registration_client_uri: String,
+    #[serde(flatten)]
+    pub metadata: ClientMetadata,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token() -> InitialAccessToken {
+        InitialAccessToken {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            token_hash: "hash".to_string(),
+            token_prefix: "dcr_iat_abcd".to_string(),
+            description: None,
+            max_uses: None,
+            use_count: 0,
+            expires_at: None,
+            revoked_at: None,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_initial_access_token_usable() {
+        let now = Utc::now();
+        assert!(token().is_usable(now));
+
+        let exhausted = InitialAccessToken {
+            max_uses: Some(1),
+            use_count: 1,
+            ..token()
+        };
+        assert!(!exhausted.is_usable(now));
+
+        let expired = InitialAccessToken {
+            expires_at: Some(now - chrono::Duration::seconds(1)),
+            ..token()
+        };
+        assert!(!expired.is_usable(now));
+
+        let revoked = InitialAccessToken {
+            revoked_at: Some(now),
+            ..token()
+        };
+        assert!(!revoked.is_usable(now));
+    }
+
+    #[test]
+    fn test_client_metadata_round_trip_omits_empty_fields() {
+        let metadata: ClientMetadata = serde_json::from_value(serde_json::json!({
+            "redirect_uris": ["https://partner.example.com/cb"],
+            "client_name": "Partner"
+        }))
+        .unwrap();
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["client_name"], "Partner");
+        assert!(json.get("software_statement").is_none());
+        assert!(json.get("contacts").is_none());
+    }
+
+    #[test]
+    fn test_token_hash_not_serialized() {
+        let json = serde_json::to_value(token()).unwrap();
+        assert!(json.get("token_hash").is_none());
+    }
+}
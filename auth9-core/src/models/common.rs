@@ -8,7 +8,7 @@ use validator::ValidationError;
 
 /// Wrapper type for UUID stored as CHAR(36) in MySQL/TiDB
 /// sqlx's uuid feature expects BINARY(16), but we use CHAR(36)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct StringUuid(pub Uuid);
 
@@ -111,6 +111,148 @@ impl ToSchema for StringUuid {
     }
 }
 
+/// Declare a typed entity ID.
+///
+/// Typed IDs share the storage and wire format of [`StringUuid`] (CHAR(36)
+/// in the database, a UUID string in JSON, paths and gRPC) but are distinct
+/// types, so passing a user ID where a tenant ID is expected fails to
+/// compile. Converting from an untyped [`StringUuid`] or [`Uuid`] is an
+/// explicit `From`, which keeps the boundary visible at the call site.
+///
+/// They are used on the RBAC, tenant membership and tenant-service paths,
+/// where signatures take several of these IDs and swapped arguments slip
+/// through: the RBAC, user and tenant-service repositories, the services and
+/// handlers built on them, and gRPC token exchange including its role cache
+/// trait. Other multi-ID signatures still take [`StringUuid`] or [`Uuid`]:
+/// the Redis cache manager, JWT minting, the HTTP client, the admin-unit,
+/// inactivity, MFA and security-digest repositories, and login identifier
+/// lookups. Signatures taking a single ID keep [`StringUuid`].
+macro_rules! typed_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub Uuid);
+
+        impl $name {
+            pub fn new_v4() -> Self {
+                $name(Uuid::new_v4())
+            }
+
+            pub fn nil() -> Self {
+                $name(Uuid::nil())
+            }
+
+            pub fn is_nil(&self) -> bool {
+                self.0.is_nil()
+            }
+
+            /// Parse a UUID string
+            pub fn parse_str(s: &str) -> Result<Self, uuid::Error> {
+                Ok($name(Uuid::parse_str(s)?))
+            }
+        }
+
+        impl From<Uuid> for $name {
+            fn from(uuid: Uuid) -> Self {
+                $name(uuid)
+            }
+        }
+
+        impl From<StringUuid> for $name {
+            fn from(id: StringUuid) -> Self {
+                $name(id.0)
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl From<$name> for StringUuid {
+            fn from(id: $name) -> Self {
+                StringUuid(id.0)
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = Uuid;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = uuid::Error;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse_str(s)
+            }
+        }
+
+        impl sqlx::Type<sqlx::MySql> for $name {
+            fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+                <StringUuid as sqlx::Type<sqlx::MySql>>::type_info()
+            }
+
+            fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+                <StringUuid as sqlx::Type<sqlx::MySql>>::compatible(ty)
+            }
+        }
+
+        impl<'r> sqlx::Decode<'r, sqlx::MySql> for $name {
+            fn decode(
+                value: sqlx::mysql::MySqlValueRef<'r>,
+            ) -> Result<Self, sqlx::error::BoxDynError> {
+                Ok(<StringUuid as sqlx::Decode<sqlx::MySql>>::decode(value)?.into())
+            }
+        }
+
+        impl<'q> sqlx::Encode<'q, sqlx::MySql> for $name {
+            fn encode_by_ref(
+                &self,
+                buf: &mut Vec<u8>,
+            ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+                <StringUuid as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&StringUuid(self.0), buf)
+            }
+        }
+
+        impl utoipa::PartialSchema for $name {
+            fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+                <StringUuid as utoipa::PartialSchema>::schema()
+            }
+        }
+
+        impl ToSchema for $name {
+            fn name() -> std::borrow::Cow<'static, str> {
+                std::borrow::Cow::Borrowed(stringify!($name))
+            }
+        }
+    };
+}
+
+typed_id!(
+    /// ID of a tenant (`tenants.id`)
+    TenantId
+);
+
+typed_id!(
+    /// ID of a user (`users.id`)
+    UserId
+);
+
+typed_id!(
+    /// ID of a service (`services.id`)
+    ServiceId
+);
+
 /// Validate a URL against SSRF attacks.
 /// Blocks cloud metadata endpoints, private/loopback IPs, and restricts HTTP to local networks.
 pub fn validate_url_no_ssrf(url: &str) -> Result<(), ValidationError> {
@@ -247,6 +389,74 @@ pub fn validate_url_no_ssrf_strict_option(url: &str) -> Result<(), ValidationErr
     validate_url_no_ssrf_strict(url)
 }
 
+/// Shortest token lifetime an override may request
+pub const MIN_TOKEN_TTL_SECS: i64 = 60;
+
+/// Access/refresh token lifetime overrides for a tenant or client.
+///
+/// Unset values fall back to the next level (client, then tenant, then the
+/// platform default). Values above the platform maxima are clamped when
+/// tokens are issued.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow, ToSchema,
+)]
+pub struct TokenTtlOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub access_token_ttl_secs: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub refresh_token_ttl_secs: Option<i64>,
+}
+
+impl TokenTtlOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.access_token_ttl_secs.is_none() && self.refresh_token_ttl_secs.is_none()
+    }
+
+    /// Fill unset values from a less specific level
+    pub fn or(self, fallback: TokenTtlOverrides) -> TokenTtlOverrides {
+        TokenTtlOverrides {
+            access_token_ttl_secs: self
+                .access_token_ttl_secs
+                .or(fallback.access_token_ttl_secs),
+            refresh_token_ttl_secs: self
+                .refresh_token_ttl_secs
+                .or(fallback.refresh_token_ttl_secs),
+        }
+    }
+
+    /// Reject values outside `MIN_TOKEN_TTL_SECS..=max`
+    pub fn validate_bounds(
+        &self,
+        max_access_ttl_secs: i64,
+        max_refresh_ttl_secs: i64,
+    ) -> std::result::Result<(), String> {
+        for (name, value, max) in [
+            (
+                "access_token_ttl_secs",
+                self.access_token_ttl_secs,
+                max_access_ttl_secs,
+            ),
+            (
+                "refresh_token_ttl_secs",
+                self.refresh_token_ttl_secs,
+                max_refresh_ttl_secs,
+            ),
+        ] {
+            if let Some(value) = value {
+                if !(MIN_TOKEN_TTL_SECS..=max).contains(&value) {
+                    return Err(format!(
+                        "{} must be between {} and {} seconds",
+                        name, MIN_TOKEN_TTL_SECS, max
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,4 +701,47 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err().code.as_ref(), "ssrf_blocked");
     }
+
+    #[test]
+    fn test_token_ttl_overrides_fallback_and_bounds() {
+        let client = TokenTtlOverrides {
+            access_token_ttl_secs: Some(300),
+            refresh_token_ttl_secs: None,
+        };
+        let tenant = TokenTtlOverrides {
+            access_token_ttl_secs: Some(1800),
+            refresh_token_ttl_secs: Some(86_400),
+        };
+        let merged = client.or(tenant);
+        assert_eq!(merged.access_token_ttl_secs, Some(300));
+        assert_eq!(merged.refresh_token_ttl_secs, Some(86_400));
+        assert!(TokenTtlOverrides::default().is_empty());
+
+        assert!(merged.validate_bounds(3600, 86_400).is_ok());
+        assert!(merged.validate_bounds(3600, 3600).is_err());
+        let too_short = TokenTtlOverrides {
+            access_token_ttl_secs: Some(10),
+            refresh_token_ttl_secs: None,
+        };
+        assert!(too_short.validate_bounds(3600, 3600).is_err());
+    }
+
+    #[test]
+    fn test_typed_id_conversions_and_serde() {
+        let uuid = Uuid::new_v4();
+        let tenant_id = TenantId::from(StringUuid(uuid));
+        assert_eq!(Uuid::from(tenant_id), uuid);
+        assert_eq!(StringUuid::from(tenant_id), StringUuid(uuid));
+        assert_eq!(*tenant_id, uuid);
+        assert_eq!(tenant_id.to_string(), uuid.to_string());
+
+        let json = serde_json::to_string(&tenant_id).unwrap();
+        assert_eq!(json, format!("\"{}\"", uuid));
+        let user_id: UserId = serde_json::from_str(&json).unwrap();
+        assert_eq!(*user_id, uuid);
+
+        assert!("not-a-uuid".parse::<ServiceId>().is_err());
+        assert!(ServiceId::nil().is_nil());
+        assert_eq!(<TenantId as ToSchema>::name(), "TenantId");
+    }
 }
@@ -0,0 +1,635 @@
+//! Conditional access policy models
+//!
+//! Tenants define rules that combine the requesting client, the network the
+//! user signs in from, whether the device is trusted and the time of day.
+//! Every enabled rule whose conditions match contributes its outcome; the
+//! strictest outcome (block > require MFA > allow) decides token issuance.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::net::IpAddr;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// Maximum number of conditional access policies per tenant
+pub const MAX_POLICIES_PER_TENANT: usize = 50;
+
+/// Result of a conditional access rule
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionalAccessOutcome {
+    Allow,
+    RequireMfa,
+    Block,
+}
+
+impl std::str::FromStr for ConditionalAccessOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "require_mfa" => Ok(Self::RequireMfa),
+            "block" => Ok(Self::Block),
+            _ => Err(format!("Unknown conditional access outcome: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for ConditionalAccessOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::RequireMfa => write!(f, "require_mfa"),
+            Self::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for ConditionalAccessOutcome {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for ConditionalAccessOutcome {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for ConditionalAccessOutcome {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        let s = self.to_string();
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&s.as_str(), buf)
+    }
+}
+
+/// Device trust state a rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceTrustCondition {
+    Trusted,
+    Untrusted,
+}
+
+/// Weekly time window, evaluated in a fixed UTC offset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct TimeWindow {
+    /// ISO weekdays (1 = Monday … 7 = Sunday); empty means every day
+    #[serde(default)]
+    #[validate(custom(function = "validate_weekdays"))]
+    pub days: Vec<u8>,
+    /// Window start, `HH:MM` (inclusive)
+    #[validate(custom(function = "validate_hhmm"))]
+    pub start: String,
+    /// Window end, `HH:MM` (exclusive). An end before the start wraps past midnight.
+    #[validate(custom(function = "validate_hhmm"))]
+    pub end: String,
+    /// Offset from UTC in minutes used to compute local time
+    #[serde(default)]
+    #[validate(range(min = -840, max = 840))]
+    pub utc_offset_minutes: i32,
+    /// Match when the request falls outside the window instead of inside it
+    #[serde(default)]
+    pub outside: bool,
+}
+
+impl TimeWindow {
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else {
+            return false;
+        };
+        let local = at + Duration::minutes(self.utc_offset_minutes as i64);
+        let weekday = local.weekday().number_from_monday() as u8;
+        let minute = local.hour() * 60 + local.minute();
+
+        let in_time = if start <= end {
+            minute >= start && minute < end
+        } else {
+            minute >= start || minute < end
+        };
+        let inside = in_time && (self.days.is_empty() || self.days.contains(&weekday));
+        inside != self.outside
+    }
+}
+
+/// Conditions of a rule. Empty lists and absent fields match everything;
+/// all present conditions must match for the rule to apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ConditionalAccessConditions {
+    /// OAuth client_ids the rule applies to
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub client_ids: Vec<String>,
+    /// OAuth client_ids exempt from the rule
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub exclude_client_ids: Vec<String>,
+    /// Networks (CIDR or single IP) the rule applies to
+    #[serde(default)]
+    #[validate(length(max = 100), custom(function = "validate_networks"))]
+    pub networks: Vec<String>,
+    /// Networks exempt from the rule
+    #[serde(default)]
+    #[validate(length(max = 100), custom(function = "validate_networks"))]
+    pub exclude_networks: Vec<String>,
+    #[serde(default)]
+    pub device_trust: Option<DeviceTrustCondition>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub time_window: Option<TimeWindow>,
+}
+
+/// Request attributes a policy is evaluated against
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConditionalAccessContext {
+    pub client_id: String,
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub device_trusted: bool,
+    /// Evaluation time; defaults to now
+    #[serde(default = "Utc::now")]
+    pub at: DateTime<Utc>,
+}
+
+impl ConditionalAccessConditions {
+    /// Evaluate the conditions, returning the names of the conditions that
+    /// did not match (empty when the rule applies).
+    pub fn mismatches(&self, ctx: &ConditionalAccessContext) -> Vec<&'static str> {
+        let mut failed = Vec::new();
+
+        if !self.client_ids.is_empty() && !self.client_ids.contains(&ctx.client_id) {
+            failed.push("client_ids");
+        }
+        if self.exclude_client_ids.contains(&ctx.client_id) {
+            failed.push("exclude_client_ids");
+        }
+
+        let ip = ctx
+            .ip_address
+            .as_deref()
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+        if !self.networks.is_empty() {
+            let in_network =
+                ip.is_some_and(|ip| self.networks.iter().any(|n| ip_in_network(ip, n)));
+            if !in_network {
+                failed.push("networks");
+            }
+        }
+        if let Some(ip) = ip {
+            if self.exclude_networks.iter().any(|n| ip_in_network(ip, n)) {
+                failed.push("exclude_networks");
+            }
+        }
+
+        match self.device_trust {
+            Some(DeviceTrustCondition::Trusted) if !ctx.device_trusted => {
+                failed.push("device_trust")
+            }
+            Some(DeviceTrustCondition::Untrusted) if ctx.device_trusted => {
+                failed.push("device_trust")
+            }
+            _ => {}
+        }
+
+        if let Some(window) = &self.time_window {
+            if !window.matches(ctx.at) {
+                failed.push("time_window");
+            }
+        }
+
+        failed
+    }
+}
+
+/// A stored conditional access policy
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ConditionalAccessPolicy {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    /// Lower values are listed and reported first
+    pub priority: i32,
+    #[sqlx(json)]
+    pub conditions: ConditionalAccessConditions,
+    pub outcome: ConditionalAccessOutcome,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateConditionalAccessPolicyInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 1024))]
+    pub description: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    #[validate(nested)]
+    pub conditions: ConditionalAccessConditions,
+    pub outcome: ConditionalAccessOutcome,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateConditionalAccessPolicyInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(max = 1024))]
+    pub description: Option<String>,
+    pub enabled: Option<bool>,
+    pub priority: Option<i32>,
+    #[validate(nested)]
+    pub conditions: Option<ConditionalAccessConditions>,
+    pub outcome: Option<ConditionalAccessOutcome>,
+}
+
+/// Per-policy line of a decision trace
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConditionalAccessPolicyTrace {
+    pub policy_id: StringUuid,
+    pub name: String,
+    pub enabled: bool,
+    pub matched: bool,
+    pub outcome: ConditionalAccessOutcome,
+    /// Conditions that did not match
+    pub unmatched_conditions: Vec<String>,
+}
+
+/// Outcome of evaluating a tenant's policies against a request
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConditionalAccessDecision {
+    pub outcome: ConditionalAccessOutcome,
+    /// Policies whose conditions matched, in evaluation order
+    pub matched_policy_ids: Vec<StringUuid>,
+    /// Policy that determined the outcome, if any matched
+    pub deciding_policy_id: Option<StringUuid>,
+    pub trace: Vec<ConditionalAccessPolicyTrace>,
+}
+
+/// Admin simulation request
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SimulateConditionalAccessInput {
+    #[validate(length(min = 1, max = 255))]
+    pub client_id: String,
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub device_trusted: bool,
+    /// Evaluation time; defaults to now
+    pub at: Option<DateTime<Utc>>,
+    /// Include disabled policies in the simulation
+    #[serde(default)]
+    pub include_disabled: bool,
+}
+
+/// Evaluate policies against a request context. Policies are expected in
+/// priority order; disabled policies are traced but never matched.
+pub fn evaluate_policies(
+    policies: &[ConditionalAccessPolicy],
+    ctx: &ConditionalAccessContext,
+) -> ConditionalAccessDecision {
+    let mut outcome = ConditionalAccessOutcome::Allow;
+    let mut deciding_policy_id = None;
+    let mut matched_policy_ids = Vec::new();
+    let mut trace = Vec::with_capacity(policies.len());
+
+    for policy in policies {
+        let unmatched = policy.conditions.mismatches(ctx);
+        let matched = policy.enabled && unmatched.is_empty();
+        if matched {
+            matched_policy_ids.push(policy.id);
+            if deciding_policy_id.is_none() || policy.outcome > outcome {
+                outcome = policy.outcome;
+                deciding_policy_id = Some(policy.id);
+            }
+        }
+        trace.push(ConditionalAccessPolicyTrace {
+            policy_id: policy.id,
+            name: policy.name.clone(),
+            enabled: policy.enabled,
+            matched,
+            outcome: policy.outcome,
+            unmatched_conditions: unmatched.into_iter().map(String::from).collect(),
+        });
+    }
+
+    ConditionalAccessDecision {
+        outcome,
+        matched_policy_ids,
+        deciding_policy_id,
+        trace,
+    }
+}
+
+/// Whether `ip` falls inside `network` (`a.b.c.d/n`, `v6::/n` or a bare address)
+pub fn ip_in_network(ip: IpAddr, network: &str) -> bool {
+    let (base, prefix) = match network.split_once('/') {
+        Some((base, prefix)) => match prefix.parse::<u8>() {
+            Ok(prefix) => (base, Some(prefix)),
+            Err(_) => return false,
+        },
+        None => (network, None),
+    };
+    let Ok(base) = base.trim().parse::<IpAddr>() else {
+        return false;
+    };
+
+    match (ip, base) {
+        (IpAddr::V4(ip), IpAddr::V4(base)) => {
+            let prefix = prefix.unwrap_or(32);
+            if prefix > 32 {
+                return false;
+            }
+            let mask = if prefix == 0 {
+                0
+            } else {
+                u32::MAX << (32 - prefix)
+            };
+            (u32::from(ip) & mask) == (u32::from(base) & mask)
+        }
+        (IpAddr::V6(ip), IpAddr::V6(base)) => {
+            let prefix = prefix.unwrap_or(128);
+            if prefix > 128 {
+                return false;
+            }
+            let mask = if prefix == 0 {
+                0
+            } else {
+                u128::MAX << (128 - prefix)
+            };
+            (u128::from(ip) & mask) == (u128::from(base) & mask)
+        }
+        _ => false,
+    }
+}
+
+fn parse_hhmm(value: &str) -> Option<u32> {
+    let (h, m) = value.split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+fn validate_hhmm(value: &str) -> Result<(), ValidationError> {
+    parse_hhmm(value)
+        .map(|_| ())
+        .ok_or_else(|| ValidationError::new("invalid_time"))
+}
+
+fn validate_weekdays(days: &[u8]) -> Result<(), ValidationError> {
+    if days.iter().all(|d| (1..=7).contains(d)) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_weekday"))
+    }
+}
+
+fn validate_networks(networks: &[String]) -> Result<(), ValidationError> {
+    let valid = networks.iter().all(|network| {
+        let (base, prefix) = network.split_once('/').unwrap_or((network, ""));
+        match base.trim().parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => prefix.is_empty() || prefix.parse::<u8>().is_ok_and(|p| p <= 32),
+            Ok(IpAddr::V6(_)) => prefix.is_empty() || prefix.parse::<u8>().is_ok_and(|p| p <= 128),
+            Err(_) => false,
+        }
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_network"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn policy(
+        name: &str,
+        conditions: ConditionalAccessConditions,
+        outcome: ConditionalAccessOutcome,
+    ) -> ConditionalAccessPolicy {
+        ConditionalAccessPolicy {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            name: name.to_string(),
+            description: None,
+            enabled: true,
+            priority: 0,
+            conditions,
+            outcome,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn ctx(client_id: &str, ip: &str) -> ConditionalAccessContext {
+        ConditionalAccessContext {
+            client_id: client_id.to_string(),
+            ip_address: Some(ip.to_string()),
+            device_trusted: false,
+            // Wednesday 2026-01-07 10:30 UTC
+            at: Utc.with_ymd_and_hms(2026, 1, 7, 10, 30, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_ip_in_network() {
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+        assert!(ip_in_network(ip, "10.0.0.0/8"));
+        assert!(ip_in_network(ip, "10.1.2.3"));
+        assert!(!ip_in_network(ip, "192.168.0.0/16"));
+        assert!(ip_in_network(ip, "0.0.0.0/0"));
+        assert!(!ip_in_network(ip, "10.0.0.0/33"));
+        assert!(!ip_in_network(ip, "garbage"));
+
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        assert!(ip_in_network(v6, "2001:db8::/32"));
+        assert!(!ip_in_network(v6, "10.0.0.0/8"));
+    }
+
+    #[test]
+    fn test_time_window_inside_and_outside() {
+        let at = Utc.with_ymd_and_hms(2026, 1, 7, 10, 30, 0).unwrap(); // Wed
+        let business_hours = TimeWindow {
+            days: vec![1, 2, 3, 4, 5],
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+            utc_offset_minutes: 0,
+            outside: false,
+        };
+        assert!(business_hours.matches(at));
+
+        let after_hours = TimeWindow {
+            outside: true,
+            ..business_hours.clone()
+        };
+        assert!(!after_hours.matches(at));
+
+        // 10:30 UTC is 19:30 in UTC+9
+        let tokyo = TimeWindow {
+            utc_offset_minutes: 540,
+            ..business_hours
+        };
+        assert!(!tokyo.matches(at));
+    }
+
+    #[test]
+    fn test_time_window_wraps_midnight() {
+        let night = TimeWindow {
+            days: vec![],
+            start: "22:00".to_string(),
+            end: "06:00".to_string(),
+            utc_offset_minutes: 0,
+            outside: false,
+        };
+        assert!(night.matches(Utc.with_ymd_and_hms(2026, 1, 7, 23, 0, 0).unwrap()));
+        assert!(night.matches(Utc.with_ymd_and_hms(2026, 1, 7, 5, 59, 0).unwrap()));
+        assert!(!night.matches(Utc.with_ymd_and_hms(2026, 1, 7, 12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_conditions_empty_match_everything() {
+        let conditions = ConditionalAccessConditions::default();
+        assert!(conditions.mismatches(&ctx("app", "203.0.113.5")).is_empty());
+    }
+
+    #[test]
+    fn test_conditions_report_mismatches() {
+        let conditions = ConditionalAccessConditions {
+            client_ids: vec!["admin-portal".to_string()],
+            networks: vec!["10.0.0.0/8".to_string()],
+            device_trust: Some(DeviceTrustCondition::Trusted),
+            ..Default::default()
+        };
+        assert_eq!(
+            conditions.mismatches(&ctx("app", "203.0.113.5")),
+            vec!["client_ids", "networks", "device_trust"]
+        );
+    }
+
+    #[test]
+    fn test_excluded_network_skips_rule() {
+        let conditions = ConditionalAccessConditions {
+            exclude_networks: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        };
+        assert!(!conditions.mismatches(&ctx("app", "10.2.3.4")).is_empty());
+        assert!(conditions.mismatches(&ctx("app", "203.0.113.5")).is_empty());
+    }
+
+    #[test]
+    fn test_evaluate_strictest_outcome_wins() {
+        let allow = policy(
+            "allow all",
+            ConditionalAccessConditions::default(),
+            ConditionalAccessOutcome::Allow,
+        );
+        let mfa = policy(
+            "mfa outside office",
+            ConditionalAccessConditions {
+                exclude_networks: vec!["10.0.0.0/8".to_string()],
+                ..Default::default()
+            },
+            ConditionalAccessOutcome::RequireMfa,
+        );
+        let block = policy(
+            "block legacy app",
+            ConditionalAccessConditions {
+                client_ids: vec!["legacy".to_string()],
+                ..Default::default()
+            },
+            ConditionalAccessOutcome::Block,
+        );
+        let policies = vec![allow.clone(), mfa.clone(), block.clone()];
+
+        let decision = evaluate_policies(&policies, &ctx("app", "203.0.113.5"));
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::RequireMfa);
+        assert_eq!(decision.deciding_policy_id, Some(mfa.id));
+        assert_eq!(decision.matched_policy_ids, vec![allow.id, mfa.id]);
+
+        let decision = evaluate_policies(&policies, &ctx("legacy", "10.0.0.1"));
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Block);
+        assert_eq!(decision.deciding_policy_id, Some(block.id));
+
+        let decision = evaluate_policies(&policies, &ctx("app", "10.0.0.1"));
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Allow);
+    }
+
+    #[test]
+    fn test_disabled_policy_never_matches() {
+        let mut block = policy(
+            "block",
+            ConditionalAccessConditions::default(),
+            ConditionalAccessOutcome::Block,
+        );
+        block.enabled = false;
+        let decision = evaluate_policies(&[block], &ctx("app", "203.0.113.5"));
+        assert_eq!(decision.outcome, ConditionalAccessOutcome::Allow);
+        assert!(decision.deciding_policy_id.is_none());
+        assert!(!decision.trace[0].matched);
+    }
+
+    #[test]
+    fn test_conditions_validation() {
+        let invalid = ConditionalAccessConditions {
+            networks: vec!["10.0.0.0/40".to_string()],
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let invalid_window = ConditionalAccessConditions {
+            time_window: Some(TimeWindow {
+                days: vec![8],
+                start: "25:00".to_string(),
+                end: "18:00".to_string(),
+                utc_offset_minutes: 0,
+                outside: false,
+            }),
+            ..Default::default()
+        };
+        assert!(invalid_window.validate().is_err());
+
+        let valid = ConditionalAccessConditions {
+            networks: vec!["10.0.0.0/8".to_string(), "2001:db8::1".to_string()],
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+    }
+
+    #[test]
+    fn test_outcome_roundtrip() {
+        for outcome in [
+            ConditionalAccessOutcome::Allow,
+            ConditionalAccessOutcome::RequireMfa,
+            ConditionalAccessOutcome::Block,
+        ] {
+            assert_eq!(
+                outcome.to_string().parse::<ConditionalAccessOutcome>(),
+                Ok(outcome)
+            );
+        }
+        assert!(ConditionalAccessOutcome::Block > ConditionalAccessOutcome::RequireMfa);
+    }
+}
@@ -0,0 +1,226 @@
+//! Tenant custom domain models
+//!
+//! A custom domain (e.g. `auth.customer.com`) serves a tenant's hosted
+//! login/consent pages. Ownership is proven with a DNS TXT record before
+//! the domain is routed or a certificate is provisioned for it.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// DNS label under which the verification TXT record must be published.
+pub const CUSTOM_DOMAIN_CHALLENGE_LABEL: &str = "_auth9-challenge";
+
+/// Verification lifecycle of a custom domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomDomainStatus {
+    Pending,
+    Verified,
+    Failed,
+}
+
+impl CustomDomainStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Verified => "verified",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Where the TLS certificate for a custom domain comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificateSource {
+    /// Issued automatically via ACME by the TLS-terminating edge
+    Acme,
+    /// Uploaded by a tenant administrator
+    Uploaded,
+}
+
+impl CertificateSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Acme => "acme",
+            Self::Uploaded => "uploaded",
+        }
+    }
+}
+
+/// Custom domain entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CustomDomain {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub domain: String,
+    pub verification_token: String,
+    /// One of `pending`, `verified`, `failed`
+    pub status: String,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub verified_at: Option<DateTime<Utc>>,
+    /// One of `acme`, `uploaded`
+    pub certificate_source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_pem: Option<String>,
+    #[serde(skip)]
+    pub private_key_encrypted: Option<String>,
+    pub certificate_expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CustomDomain {
+    pub fn is_verified(&self) -> bool {
+        self.status == CustomDomainStatus::Verified.as_str()
+    }
+
+    /// Fully-qualified name of the TXT record used for ownership verification
+    pub fn challenge_record_name(&self) -> String {
+        format!("{}.{}", CUSTOM_DOMAIN_CHALLENGE_LABEL, self.domain)
+    }
+}
+
+/// Custom domain with DNS setup instructions, as returned by the API
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomDomainResponse {
+    #[serde(flatten)]
+    pub domain: CustomDomain,
+    pub dns_records: Vec<DnsRecordInstruction>,
+}
+
+/// A DNS record the tenant must publish for the domain to work
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DnsRecordInstruction {
+    pub record_type: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// Input for registering a custom domain
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateCustomDomainInput {
+    #[validate(
+        length(min = 4, max = 253),
+        custom(function = "validate_custom_domain")
+    )]
+    pub domain: String,
+}
+
+/// Input for uploading an externally issued certificate
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UploadCertificateInput {
+    /// PEM-encoded leaf certificate, optionally followed by the chain
+    #[validate(length(min = 1, max = 65536))]
+    pub certificate_pem: String,
+    /// PEM-encoded private key for the leaf certificate
+    #[validate(length(min = 1, max = 16384))]
+    pub private_key_pem: String,
+}
+
+/// Answer for the edge proxy's on-demand TLS check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TlsAskResponse {
+    pub domain: String,
+    pub tenant_id: StringUuid,
+    pub certificate_source: String,
+}
+
+/// Tenant resolved from the request Host header.
+///
+/// Inserted as a request extension by the custom domain routing middleware.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomDomainTenant {
+    pub tenant_id: StringUuid,
+    pub domain: String,
+}
+
+/// Public tenant info for hosted pages served on a custom domain
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HostedDomainInfo {
+    pub domain: String,
+    pub tenant_id: StringUuid,
+    pub tenant_slug: String,
+    pub tenant_name: String,
+    pub logo_url: Option<String>,
+}
+
+/// Validate a custom domain: a lowercase hostname with at least three labels
+/// (apex domains cannot carry the CNAME needed for routing) and no wildcard.
+pub fn validate_custom_domain(domain: &str) -> Result<(), validator::ValidationError> {
+    let domain_regex =
+        regex::Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$")
+            .unwrap();
+    if !domain_regex.is_match(domain) || domain.split('.').any(|label| label.len() > 63) {
+        let mut err = validator::ValidationError::new("invalid_domain");
+        err.message = Some("Invalid domain format (e.g. 'auth.example.com')".into());
+        return Err(err);
+    }
+    if domain.split('.').count() < 3 {
+        let mut err = validator::ValidationError::new("apex_domain");
+        err.message = Some("Custom domain must be a subdomain (e.g. 'auth.example.com')".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_custom_domain_accepts_subdomain() {
+        assert!(validate_custom_domain("auth.customer.com").is_ok());
+        assert!(validate_custom_domain("login.eu.customer.co.uk").is_ok());
+    }
+
+    #[test]
+    fn test_validate_custom_domain_rejects_apex_and_garbage() {
+        assert!(validate_custom_domain("customer.com").is_err());
+        assert!(validate_custom_domain("*.customer.com").is_err());
+        assert!(validate_custom_domain("Auth.Customer.com").is_err());
+        assert!(validate_custom_domain("auth..customer.com").is_err());
+        assert!(validate_custom_domain("auth.customer.com:8443").is_err());
+    }
+
+    #[test]
+    fn test_create_input_validation() {
+        let input = CreateCustomDomainInput {
+            domain: "auth.customer.com".to_string(),
+        };
+        assert!(input.validate().is_ok());
+
+        let input = CreateCustomDomainInput {
+            domain: "localhost".to_string(),
+        };
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_challenge_record_name() {
+        let domain = CustomDomain {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            domain: "auth.customer.com".to_string(),
+            verification_token: "tok".to_string(),
+            status: "pending".to_string(),
+            last_checked_at: None,
+            verified_at: None,
+            certificate_source: "acme".to_string(),
+            certificate_pem: None,
+            private_key_encrypted: None,
+            certificate_expires_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert_eq!(
+            domain.challenge_record_name(),
+            "_auth9-challenge.auth.customer.com"
+        );
+        assert!(!domain.is_verified());
+    }
+}
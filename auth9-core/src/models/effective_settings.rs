@@ -0,0 +1,398 @@
+//! Effective settings of a tenant
+//!
+//! Behavior is configured at three scopes: platform configuration and
+//! defaults, tenant settings, and per-service overrides. The effective value
+//! of each setting is the most specific one set, reported together with the
+//! scope it came from.
+
+use super::branding::{validate_hex_color, BrandingConfig, DEFAULT_BRAND_NAME};
+use super::common::{StringUuid, MIN_TOKEN_TTL_SECS};
+use super::session::SessionLimitPolicy;
+use super::tenant::{Tenant, TenantSettings};
+use crate::config::Config;
+use crate::i18n::DEFAULT_LOCALE;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+/// Scope a setting's effective value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingSource {
+    /// Platform configuration or built-in default
+    Platform,
+    /// The tenant's settings
+    Tenant,
+    /// The service's own configuration
+    Service,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EffectiveSetting {
+    /// Dotted setting name, e.g. `session_limit.max_sessions`
+    pub key: String,
+    pub value: Value,
+    pub source: SettingSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EffectiveSettings {
+    pub tenant_id: StringUuid,
+    /// Service whose overrides were applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<StringUuid>,
+    pub settings: Vec<EffectiveSetting>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct EffectiveSettingsQuery {
+    /// Also apply this service's overrides (hosted login page branding)
+    pub service_id: Option<StringUuid>,
+}
+
+impl EffectiveSettings {
+    /// Resolve the tenant's settings over the platform configuration, and
+    /// the service's login page branding over the platform branding
+    pub fn resolve(
+        config: &Config,
+        platform_branding: &BrandingConfig,
+        tenant: &Tenant,
+        service: Option<(StringUuid, Option<&BrandingConfig>)>,
+    ) -> Self {
+        let settings = &tenant.settings;
+        let defaults = TenantSettings::default();
+        let mut out = Resolver::default();
+
+        out.set_unless_default("require_mfa", settings.require_mfa, defaults.require_mfa);
+        out.set_unless_default(
+            "allowed_auth_methods",
+            &settings.allowed_auth_methods,
+            &defaults.allowed_auth_methods,
+        );
+        out.set_unless_default(
+            "session_timeout_secs",
+            settings.session_timeout_secs,
+            defaults.session_timeout_secs,
+        );
+        out.set_unless_default(
+            "login_notifications",
+            settings.login_notifications,
+            defaults.login_notifications,
+        );
+        out.layer(
+            "default_locale",
+            DEFAULT_LOCALE.as_str(),
+            settings.default_locale.as_deref(),
+        );
+
+        // Overrides are clamped to the platform maxima when tokens are issued
+        let jwt = &config.jwt;
+        let clamp =
+            |value: i64, max: i64| value.clamp(MIN_TOKEN_TTL_SECS, max.max(MIN_TOKEN_TTL_SECS));
+        out.layer(
+            "access_token_ttl_secs",
+            jwt.access_token_ttl_secs,
+            settings
+                .token_ttls
+                .access_token_ttl_secs
+                .map(|v| clamp(v, jwt.max_access_token_ttl_secs)),
+        );
+        out.layer(
+            "refresh_token_ttl_secs",
+            jwt.refresh_token_ttl_secs,
+            settings
+                .token_ttls
+                .refresh_token_ttl_secs
+                .map(|v| clamp(v, jwt.max_refresh_token_ttl_secs)),
+        );
+
+        let session_limit = settings.session_limit;
+        let default_limit = SessionLimitPolicy::default();
+        out.layer(
+            "session_limit.max_sessions",
+            default_limit.max_sessions,
+            session_limit.map(|p| p.max_sessions),
+        );
+        out.layer(
+            "session_limit.on_limit",
+            default_limit.on_limit,
+            session_limit.map(|p| p.on_limit),
+        );
+
+        let caps = &config.cardinality;
+        let limits = settings.cardinality_limits.as_ref();
+        out.layer(
+            "cardinality_limits.max_users_per_tenant",
+            caps.max_users_per_tenant,
+            limits.and_then(|l| l.max_users_per_tenant),
+        );
+        out.layer(
+            "cardinality_limits.max_roles_per_user",
+            caps.max_roles_per_user,
+            limits.and_then(|l| l.max_roles_per_user),
+        );
+        out.layer(
+            "cardinality_limits.max_permissions_per_role",
+            caps.max_permissions_per_role,
+            limits.and_then(|l| l.max_permissions_per_role),
+        );
+
+        // The tenant's policy replaces the default as a whole
+        let policy_source = if tenant.password_policy.is_some() {
+            SettingSource::Tenant
+        } else {
+            SettingSource::Platform
+        };
+        let policy = tenant.password_policy.clone().unwrap_or_default();
+        out.set_object("password_policy", &policy, policy_source);
+
+        // Tenant-only policies; unset means off
+        out.layer(
+            "inactivity_policy",
+            Value::Null,
+            settings.inactivity_policy.as_ref(),
+        );
+        out.layer(
+            "security_digest",
+            Value::Null,
+            settings.security_digest.as_ref(),
+        );
+        out.layer(
+            "mfa_enrollment_campaign",
+            Value::Null,
+            settings.mfa_enrollment_campaign.as_ref(),
+        );
+        out.layer(
+            "login_identifiers",
+            Value::Null,
+            settings.login_identifiers.as_ref(),
+        );
+
+        // Emails and hosted pages (mirrors `BrandingContext::resolve`)
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        let branding = &settings.branding;
+        let tenant_name = Some(tenant.name.trim().to_string()).filter(|n| !n.is_empty());
+        out.layer(
+            "branding.name",
+            non_empty(&platform_branding.company_name)
+                .unwrap_or_else(|| DEFAULT_BRAND_NAME.to_string()),
+            tenant_name,
+        );
+        out.layer(
+            "branding.logo_url",
+            non_empty(&platform_branding.logo_url),
+            non_empty(&branding.logo_url).or_else(|| non_empty(&tenant.logo_url)),
+        );
+        out.layer(
+            "branding.primary_color",
+            &platform_branding.primary_color,
+            branding
+                .primary_color
+                .as_ref()
+                .filter(|c| validate_hex_color(c).is_ok()),
+        );
+        out.layer(
+            "branding.support_email",
+            non_empty(&platform_branding.support_email),
+            non_empty(&branding.support_email),
+        );
+        out.layer(
+            "branding.support_url",
+            non_empty(&platform_branding.support_url),
+            non_empty(&branding.support_url),
+        );
+
+        // A service's login page branding replaces the platform's as a whole
+        let service_id = service.map(|(id, login_branding)| {
+            match login_branding {
+                Some(config) => out.set_object("login_branding", config, SettingSource::Service),
+                None => {
+                    out.set_object("login_branding", platform_branding, SettingSource::Platform)
+                }
+            }
+            id
+        });
+
+        Self {
+            tenant_id: tenant.id,
+            service_id,
+            settings: out.settings,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&EffectiveSetting> {
+        self.settings.iter().find(|s| s.key == key)
+    }
+}
+
+#[derive(Default)]
+struct Resolver {
+    settings: Vec<EffectiveSetting>,
+}
+
+impl Resolver {
+    fn set(&mut self, key: &str, value: impl Serialize, source: SettingSource) {
+        self.settings.push(EffectiveSetting {
+            key: key.to_string(),
+            value: serde_json::to_value(value).unwrap_or(Value::Null),
+            source,
+        });
+    }
+
+    /// Tenant value when set, platform value otherwise
+    fn layer<T: Serialize>(&mut self, key: &str, platform: impl Serialize, tenant: Option<T>) {
+        match tenant {
+            Some(value) => self.set(key, value, SettingSource::Tenant),
+            None => self.set(key, platform, SettingSource::Platform),
+        }
+    }
+
+    /// Settings with a built-in default cannot tell "unset" from "set to the
+    /// default"; the default is reported as coming from the platform
+    fn set_unless_default<T: Serialize + PartialEq>(&mut self, key: &str, value: T, default: T) {
+        let source = if value == default {
+            SettingSource::Platform
+        } else {
+            SettingSource::Tenant
+        };
+        self.set(key, value, source);
+    }
+
+    /// One entry per field of a settings object
+    fn set_object(&mut self, prefix: &str, object: &impl Serialize, source: SettingSource) {
+        if let Ok(Value::Object(fields)) = serde_json::to_value(object) {
+            for (name, value) in fields {
+                self.set(&format!("{}.{}", prefix, name), value, source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::cardinality::CardinalityLimits;
+    use crate::models::common::TokenTtlOverrides;
+    use crate::models::password::PasswordPolicy;
+
+    fn source(settings: &EffectiveSettings, key: &str) -> (Value, SettingSource) {
+        let setting = settings.get(key).expect(key);
+        (setting.value.clone(), setting.source)
+    }
+
+    #[test]
+    fn test_platform_defaults() {
+        let config = Config::for_tests();
+        let tenant = Tenant::default();
+        let settings =
+            EffectiveSettings::resolve(&config, &BrandingConfig::for_tests(), &tenant, None);
+
+        assert_eq!(
+            source(&settings, "session_timeout_secs"),
+            (Value::from(3600), SettingSource::Platform)
+        );
+        assert_eq!(
+            source(&settings, "access_token_ttl_secs"),
+            (
+                Value::from(config.jwt.access_token_ttl_secs),
+                SettingSource::Platform
+            )
+        );
+        assert_eq!(
+            source(&settings, "password_policy.min_length").1,
+            SettingSource::Platform
+        );
+        assert_eq!(
+            source(&settings, "login_identifiers"),
+            (Value::Null, SettingSource::Platform)
+        );
+        assert!(settings.get("login_branding.primary_color").is_none());
+    }
+
+    #[test]
+    fn test_tenant_overrides_with_provenance() {
+        let mut config = Config::for_tests();
+        config.jwt.max_access_token_ttl_secs = 7200;
+        let mut tenant = Tenant::default();
+        tenant.settings.session_timeout_secs = 900;
+        tenant.settings.default_locale = Some("ja".to_string());
+        tenant.settings.token_ttls = TokenTtlOverrides {
+            access_token_ttl_secs: Some(86_400),
+            refresh_token_ttl_secs: None,
+        };
+        tenant.settings.cardinality_limits = Some(CardinalityLimits {
+            max_roles_per_user: Some(5),
+            ..Default::default()
+        });
+        tenant.password_policy = Some(PasswordPolicy {
+            min_length: 16,
+            ..Default::default()
+        });
+        let settings =
+            EffectiveSettings::resolve(&config, &BrandingConfig::for_tests(), &tenant, None);
+
+        assert_eq!(
+            source(&settings, "session_timeout_secs"),
+            (Value::from(900), SettingSource::Tenant)
+        );
+        assert_eq!(
+            source(&settings, "default_locale"),
+            (Value::from("ja"), SettingSource::Tenant)
+        );
+        // Clamped to the platform maximum, as when tokens are issued
+        assert_eq!(
+            source(&settings, "access_token_ttl_secs"),
+            (Value::from(7200), SettingSource::Tenant)
+        );
+        assert_eq!(
+            source(&settings, "refresh_token_ttl_secs").1,
+            SettingSource::Platform
+        );
+        assert_eq!(
+            source(&settings, "cardinality_limits.max_roles_per_user"),
+            (Value::from(5), SettingSource::Tenant)
+        );
+        assert_eq!(
+            source(&settings, "cardinality_limits.max_users_per_tenant").1,
+            SettingSource::Platform
+        );
+        assert_eq!(
+            source(&settings, "password_policy.min_length"),
+            (Value::from(16), SettingSource::Tenant)
+        );
+    }
+
+    #[test]
+    fn test_service_login_branding() {
+        let config = Config::for_tests();
+        let tenant = Tenant::default();
+        let service_id = StringUuid::new_v4();
+        let service_branding = BrandingConfig {
+            primary_color: "#112233".to_string(),
+            ..Default::default()
+        };
+
+        let settings = EffectiveSettings::resolve(
+            &config,
+            &BrandingConfig::for_tests(),
+            &tenant,
+            Some((service_id, Some(&service_branding))),
+        );
+        assert_eq!(settings.service_id, Some(service_id));
+        assert_eq!(
+            source(&settings, "login_branding.primary_color"),
+            (Value::from("#112233"), SettingSource::Service)
+        );
+
+        let settings = EffectiveSettings::resolve(
+            &config,
+            &BrandingConfig::for_tests(),
+            &tenant,
+            Some((service_id, None)),
+        );
+        assert_eq!(
+            source(&settings, "login_branding.primary_color").1,
+            SettingSource::Platform
+        );
+    }
+}
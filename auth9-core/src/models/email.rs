@@ -332,6 +332,8 @@ pub struct EmailMessage {
     pub subject: String,
     pub html_body: String,
     pub text_body: Option<String>,
+    /// One-click unsubscribe URL (RFC 8058), sent as `List-Unsubscribe`
+    pub list_unsubscribe: Option<String>,
 }
 
 impl EmailMessage {
@@ -341,6 +343,7 @@ impl EmailMessage {
             subject: subject.into(),
             html_body: html_body.into(),
             text_body: None,
+            list_unsubscribe: None,
         }
     }
 
@@ -348,6 +351,11 @@ impl EmailMessage {
         self.text_body = Some(text_body.into());
         self
     }
+
+    pub fn with_list_unsubscribe(mut self, url: impl Into<String>) -> Self {
+        self.list_unsubscribe = Some(url.into());
+        self
+    }
 }
 
 /// Result of sending an email
@@ -367,6 +375,24 @@ impl EmailSendResult {
         }
     }
 
+    /// Accepted into the persistent email queue for background delivery
+    pub fn accepted() -> Self {
+        Self {
+            success: true,
+            message_id: None,
+            error: None,
+        }
+    }
+
+    /// Accepted for deferred delivery because the provider was unavailable
+    pub fn queued() -> Self {
+        Self {
+            success: false,
+            message_id: None,
+            error: Some("queued for retry".to_string()),
+        }
+    }
+
     pub fn failure(error: impl Into<String>) -> Self {
         Self {
             success: false,
@@ -0,0 +1,388 @@
+//! Outbound email queue and suppression list models
+//!
+//! Emails are persisted to `email_queue` and delivered by a background worker.
+//! Addresses in `email_suppressions` (hard bounces, complaints, manual blocks)
+//! are never sent to.
+
+use super::common::StringUuid;
+use super::email::{EmailAddress, EmailMessage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Lifecycle of a queued email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailQueueStatus {
+    /// Waiting for its next delivery attempt
+    Pending,
+    /// Claimed by a worker
+    Sending,
+    Sent,
+    /// Gave up after exhausting its attempts
+    Failed,
+    /// Dropped because every recipient is suppressed
+    Suppressed,
+}
+
+impl EmailQueueStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sending => "sending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+            Self::Suppressed => "suppressed",
+        }
+    }
+}
+
+/// Recipient of a queued email
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedRecipient {
+    pub email: String,
+    pub name: Option<String>,
+}
+
+/// Message content of a queued email, persisted as JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedEmailPayload {
+    pub to: Vec<QueuedRecipient>,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: Option<String>,
+    pub list_unsubscribe: Option<String>,
+}
+
+impl From<&EmailMessage> for QueuedEmailPayload {
+    fn from(message: &EmailMessage) -> Self {
+        Self {
+            to: message
+                .to
+                .iter()
+                .map(|addr| QueuedRecipient {
+                    email: addr.email.clone(),
+                    name: addr.name.clone(),
+                })
+                .collect(),
+            subject: message.subject.clone(),
+            html_body: message.html_body.clone(),
+            text_body: message.text_body.clone(),
+            list_unsubscribe: message.list_unsubscribe.clone(),
+        }
+    }
+}
+
+impl QueuedEmailPayload {
+    /// Rebuild the message, keeping only recipients accepted by `keep`
+    pub fn to_message(&self, keep: impl Fn(&str) -> bool) -> EmailMessage {
+        EmailMessage {
+            to: self
+                .to
+                .iter()
+                .filter(|r| keep(&r.email))
+                .map(|r| EmailAddress {
+                    email: r.email.clone(),
+                    name: r.name.clone(),
+                })
+                .collect(),
+            subject: self.subject.clone(),
+            html_body: self.html_body.clone(),
+            text_body: self.text_body.clone(),
+            list_unsubscribe: self.list_unsubscribe.clone(),
+        }
+    }
+}
+
+/// A queued email
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct QueuedEmail {
+    pub id: StringUuid,
+    /// Normalized recipient addresses
+    #[sqlx(json)]
+    pub recipients: Vec<String>,
+    #[serde(skip)]
+    #[sqlx(json)]
+    pub payload: QueuedEmailPayload,
+    /// One of `pending`, `sending`, `sent`, `failed`, `suppressed`
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    /// Provider type used for the last attempt (`smtp`, `ses`, `oracle`)
+    pub provider: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Number of queued emails per status
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct EmailQueueStats {
+    pub pending: i64,
+    pub sending: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub suppressed: i64,
+}
+
+/// Why an address is suppressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SuppressionReason {
+    /// Permanent (hard) bounce reported by the provider
+    Bounce,
+    /// Recipient marked a message as spam
+    Complaint,
+    /// Added by an administrator
+    Manual,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+/// An address that must not receive mail
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EmailSuppression {
+    pub email: String,
+    /// One of `bounce`, `complaint`, `manual`
+    pub reason: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for suppressing an address manually
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateEmailSuppressionInput {
+    #[validate(email, length(max = 320))]
+    pub email: String,
+    #[validate(length(max = 512))]
+    pub detail: Option<String>,
+}
+
+/// Normalize an address for suppression lookups
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Delay before retry number `attempts` (1-based): `base * 2^(attempts-1)`,
+/// capped at `max`
+pub fn retry_delay_secs(attempts: u32, base: u64, max: u64) -> u64 {
+    let exponent = attempts.saturating_sub(1).min(32);
+    base.saturating_mul(1u64 << exponent).min(max)
+}
+
+/// Bounce or complaint reported by an email provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailFeedback {
+    pub kind: EmailFeedbackKind,
+    /// Normalized recipient addresses
+    pub recipients: Vec<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailFeedbackKind {
+    Bounce { permanent: bool },
+    Complaint,
+}
+
+impl EmailFeedback {
+    /// Suppression to record for this feedback; transient bounces are not suppressed
+    pub fn suppression_reason(&self) -> Option<SuppressionReason> {
+        match self.kind {
+            EmailFeedbackKind::Bounce { permanent: true } => Some(SuppressionReason::Bounce),
+            EmailFeedbackKind::Bounce { permanent: false } => None,
+            EmailFeedbackKind::Complaint => Some(SuppressionReason::Complaint),
+        }
+    }
+}
+
+/// Parse a bounce/complaint notification.
+///
+/// Accepts the generic format
+/// `{"type": "bounce"|"complaint", "bounce_type": "permanent"|"transient", "recipients": [...], "detail": "..."}`
+/// and AWS SES notifications, optionally wrapped in an SNS envelope.
+pub fn parse_email_feedback(body: &serde_json::Value) -> Option<EmailFeedback> {
+    // SNS envelope: the SES notification is a JSON string in `Message`
+    if body.get("Type").and_then(|t| t.as_str()) == Some("Notification") {
+        let message = body.get("Message")?.as_str()?;
+        let inner: serde_json::Value = serde_json::from_str(message).ok()?;
+        return parse_email_feedback(&inner);
+    }
+
+    if let Some(kind) = body
+        .get("notificationType")
+        .or_else(|| body.get("eventType"))
+        .and_then(|t| t.as_str())
+    {
+        return parse_ses_feedback(kind, body);
+    }
+
+    let kind = match body.get("type")?.as_str()? {
+        "bounce" => EmailFeedbackKind::Bounce {
+            permanent: body.get("bounce_type").and_then(|t| t.as_str()) != Some("transient"),
+        },
+        "complaint" => EmailFeedbackKind::Complaint,
+        _ => return None,
+    };
+    let recipients = body
+        .get("recipients")?
+        .as_array()?
+        .iter()
+        .filter_map(|r| r.as_str())
+        .map(normalize_email)
+        .collect();
+    Some(EmailFeedback {
+        kind,
+        recipients,
+        detail: body
+            .get("detail")
+            .and_then(|d| d.as_str())
+            .map(str::to_string),
+    })
+}
+
+fn parse_ses_feedback(kind: &str, body: &serde_json::Value) -> Option<EmailFeedback> {
+    let (kind, section, list) = match kind {
+        "Bounce" => {
+            let bounce = body.get("bounce")?;
+            let permanent = bounce.get("bounceType").and_then(|t| t.as_str()) == Some("Permanent");
+            (
+                EmailFeedbackKind::Bounce { permanent },
+                bounce,
+                "bouncedRecipients",
+            )
+        }
+        "Complaint" => (
+            EmailFeedbackKind::Complaint,
+            body.get("complaint")?,
+            "complainedRecipients",
+        ),
+        _ => return None,
+    };
+
+    let entries = section.get(list)?.as_array()?;
+    let recipients = entries
+        .iter()
+        .filter_map(|r| r.get("emailAddress").and_then(|e| e.as_str()))
+        .map(normalize_email)
+        .collect();
+    let detail = entries
+        .iter()
+        .find_map(|r| r.get("diagnosticCode").and_then(|d| d.as_str()))
+        .or_else(|| {
+            section
+                .get("complaintFeedbackType")
+                .and_then(|t| t.as_str())
+        })
+        .map(str::to_string);
+    Some(EmailFeedback {
+        kind,
+        recipients,
+        detail,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_retry_delay_doubles_and_caps() {
+        assert_eq!(retry_delay_secs(1, 30, 3600), 30);
+        assert_eq!(retry_delay_secs(2, 30, 3600), 60);
+        assert_eq!(retry_delay_secs(4, 30, 3600), 240);
+        assert_eq!(retry_delay_secs(10, 30, 3600), 3600);
+        assert_eq!(retry_delay_secs(200, 30, 3600), 3600);
+    }
+
+    #[test]
+    fn test_payload_roundtrip_filters_recipients() {
+        let message = EmailMessage {
+            to: vec![
+                EmailAddress::with_name("a@example.com", "A"),
+                EmailAddress::new("b@example.com"),
+            ],
+            subject: "Hi".to_string(),
+            html_body: "<p>Hi</p>".to_string(),
+            text_body: Some("Hi".to_string()),
+            list_unsubscribe: None,
+        };
+        let payload = QueuedEmailPayload::from(&message);
+        let rebuilt = payload.to_message(|email| email != "b@example.com");
+        assert_eq!(rebuilt.to.len(), 1);
+        assert_eq!(rebuilt.to[0].name.as_deref(), Some("A"));
+        assert_eq!(rebuilt.text_body.as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn test_parse_generic_feedback() {
+        let feedback = parse_email_feedback(&json!({
+            "type": "bounce",
+            "bounce_type": "transient",
+            "recipients": ["User@Example.com "],
+        }))
+        .unwrap();
+        assert_eq!(feedback.recipients, vec!["user@example.com"]);
+        assert_eq!(feedback.suppression_reason(), None);
+
+        let feedback =
+            parse_email_feedback(&json!({ "type": "complaint", "recipients": ["a@example.com"] }))
+                .unwrap();
+        assert_eq!(
+            feedback.suppression_reason(),
+            Some(SuppressionReason::Complaint)
+        );
+
+        assert!(parse_email_feedback(&json!({ "type": "delivery", "recipients": [] })).is_none());
+    }
+
+    #[test]
+    fn test_parse_ses_bounce_in_sns_envelope() {
+        let ses = json!({
+            "notificationType": "Bounce",
+            "bounce": {
+                "bounceType": "Permanent",
+                "bouncedRecipients": [
+                    { "emailAddress": "gone@example.com", "diagnosticCode": "550 5.1.1 user unknown" }
+                ]
+            }
+        });
+        let envelope = json!({ "Type": "Notification", "Message": ses.to_string() });
+
+        let feedback = parse_email_feedback(&envelope).unwrap();
+        assert_eq!(feedback.kind, EmailFeedbackKind::Bounce { permanent: true });
+        assert_eq!(feedback.recipients, vec!["gone@example.com"]);
+        assert_eq!(feedback.detail.as_deref(), Some("550 5.1.1 user unknown"));
+        assert_eq!(
+            feedback.suppression_reason(),
+            Some(SuppressionReason::Bounce)
+        );
+    }
+
+    #[test]
+    fn test_parse_ses_complaint() {
+        let feedback = parse_email_feedback(&json!({
+            "eventType": "Complaint",
+            "complaint": {
+                "complaintFeedbackType": "abuse",
+                "complainedRecipients": [{ "emailAddress": "a@example.com" }]
+            }
+        }))
+        .unwrap();
+        assert_eq!(feedback.kind, EmailFeedbackKind::Complaint);
+        assert_eq!(feedback.detail.as_deref(), Some("abuse"));
+    }
+}
@@ -26,6 +26,12 @@ pub enum EmailTemplateType {
     PasswordChanged,
     /// Security alert (new login, suspicious activity)
     SecurityAlert,
+    /// Periodic account activity digest
+    NotificationDigest,
+    /// New sign-in to the account, with a "this wasn't me" link
+    LoginNotification,
+    /// Periodic digest of a tenant's security alerts for its admins
+    SecurityAlertDigest,
 }
 
 impl EmailTemplateType {
@@ -39,6 +45,9 @@ impl EmailTemplateType {
             EmailTemplateType::EmailVerification,
             EmailTemplateType::PasswordChanged,
             EmailTemplateType::SecurityAlert,
+            EmailTemplateType::NotificationDigest,
+            EmailTemplateType::LoginNotification,
+            EmailTemplateType::SecurityAlertDigest,
         ]
     }
 
@@ -52,6 +61,9 @@ impl EmailTemplateType {
             Self::EmailVerification => "email_verification",
             Self::PasswordChanged => "password_changed",
             Self::SecurityAlert => "security_alert",
+            Self::NotificationDigest => "notification_digest",
+            Self::LoginNotification => "login_notification",
+            Self::SecurityAlertDigest => "security_alert_digest",
         }
     }
 
@@ -65,6 +77,9 @@ impl EmailTemplateType {
             Self::EmailVerification => "Email Verification",
             Self::PasswordChanged => "Password Changed",
             Self::SecurityAlert => "Security Alert",
+            Self::NotificationDigest => "Activity Digest",
+            Self::LoginNotification => "New Sign-in",
+            Self::SecurityAlertDigest => "Security Alert Digest",
         }
     }
 
@@ -78,6 +93,15 @@ impl EmailTemplateType {
             Self::EmailVerification => "Sent when a user needs to verify their email address",
             Self::PasswordChanged => "Sent when a user's password has been changed",
             Self::SecurityAlert => "Sent for security events like new login from unknown device",
+            Self::NotificationDigest => {
+                "Periodic summary of sign-ins and security alerts, for users who opted in"
+            }
+            Self::LoginNotification => {
+                "Sent for each new sign-in unless the user or all their tenants opted out"
+            }
+            Self::SecurityAlertDigest => {
+                "Periodic summary of a tenant's security alerts, sent to its admins and distribution list"
+            }
         }
     }
 
@@ -86,7 +110,7 @@ impl EmailTemplateType {
         let common = vec![
             TemplateVariable {
                 name: "app_name".to_string(),
-                description: "Application name (Auth9)".to_string(),
+                description: "Brand name (tenant name, or the platform company name)".to_string(),
                 example: "Auth9".to_string(),
             },
             TemplateVariable {
@@ -94,6 +118,31 @@ impl EmailTemplateType {
                 description: "Current year".to_string(),
                 example: "2026".to_string(),
             },
+            TemplateVariable {
+                name: "brand_name".to_string(),
+                description: "Tenant name, or the platform company name".to_string(),
+                example: "Acme Corp".to_string(),
+            },
+            TemplateVariable {
+                name: "brand_logo_url".to_string(),
+                description: "Tenant or platform logo URL (empty when unset)".to_string(),
+                example: "https://cdn.example.com/logo.png".to_string(),
+            },
+            TemplateVariable {
+                name: "brand_primary_color".to_string(),
+                description: "Tenant or platform primary color".to_string(),
+                example: "#2563eb".to_string(),
+            },
+            TemplateVariable {
+                name: "support_email".to_string(),
+                description: "Support contact (empty when unset)".to_string(),
+                example: "support@example.com".to_string(),
+            },
+            TemplateVariable {
+                name: "support_url".to_string(),
+                description: "Help center URL (empty when unset)".to_string(),
+                example: "https://help.example.com".to_string(),
+            },
         ];
 
         let mut vars = match self {
@@ -230,6 +279,116 @@ impl EmailTemplateType {
                     description: "Time of the event".to_string(),
                     example: "January 31, 2026 at 10:30 AM UTC".to_string(),
                 },
+                TemplateVariable {
+                    name: "unsubscribe_link".to_string(),
+                    description: "Link to stop receiving this kind of notification".to_string(),
+                    example: "https://auth9.example.com/api/v1/notifications/unsubscribe?token=..."
+                        .to_string(),
+                },
+            ],
+            Self::NotificationDigest => vec![
+                TemplateVariable {
+                    name: "user_name".to_string(),
+                    description: "Name of the user".to_string(),
+                    example: "Jane Smith".to_string(),
+                },
+                TemplateVariable {
+                    name: "period".to_string(),
+                    description: "Digest period".to_string(),
+                    example: "week".to_string(),
+                },
+                TemplateVariable {
+                    name: "successful_logins".to_string(),
+                    description: "Successful sign-ins during the period".to_string(),
+                    example: "12".to_string(),
+                },
+                TemplateVariable {
+                    name: "failed_logins".to_string(),
+                    description: "Failed sign-in attempts during the period".to_string(),
+                    example: "2".to_string(),
+                },
+                TemplateVariable {
+                    name: "security_alerts".to_string(),
+                    description: "Security alerts raised during the period".to_string(),
+                    example: "0".to_string(),
+                },
+                TemplateVariable {
+                    name: "unsubscribe_link".to_string(),
+                    description: "Link to stop receiving digest emails".to_string(),
+                    example: "https://auth9.example.com/api/v1/notifications/unsubscribe?token=..."
+                        .to_string(),
+                },
+            ],
+            Self::LoginNotification => vec![
+                TemplateVariable {
+                    name: "user_name".to_string(),
+                    description: "Name of the user".to_string(),
+                    example: "Jane Smith".to_string(),
+                },
+                TemplateVariable {
+                    name: "device_info".to_string(),
+                    description: "Browser, operating system and device type".to_string(),
+                    example: "Chrome on macOS (desktop)".to_string(),
+                },
+                TemplateVariable {
+                    name: "location".to_string(),
+                    description: "Approximate location from the IP address".to_string(),
+                    example: "Tokyo, JP".to_string(),
+                },
+                TemplateVariable {
+                    name: "ip_address".to_string(),
+                    description: "IP address of the sign-in".to_string(),
+                    example: "203.0.113.7".to_string(),
+                },
+                TemplateVariable {
+                    name: "timestamp".to_string(),
+                    description: "Time of the sign-in".to_string(),
+                    example: "2026-01-31 10:30:00 UTC".to_string(),
+                },
+                TemplateVariable {
+                    name: "report_link".to_string(),
+                    description: "\"This wasn't me\" link that signs the session out and raises a security alert".to_string(),
+                    example: "https://auth9.example.com/api/v1/notifications/sign-in-report?token=..."
+                        .to_string(),
+                },
+                TemplateVariable {
+                    name: "unsubscribe_link".to_string(),
+                    description: "Link to stop receiving sign-in emails".to_string(),
+                    example: "https://auth9.example.com/api/v1/notifications/unsubscribe?token=..."
+                        .to_string(),
+                },
+            ],
+            Self::SecurityAlertDigest => vec![
+                TemplateVariable {
+                    name: "tenant_name".to_string(),
+                    description: "Name of the tenant".to_string(),
+                    example: "Acme Corp".to_string(),
+                },
+                TemplateVariable {
+                    name: "period".to_string(),
+                    description: "Digest period".to_string(),
+                    example: "day".to_string(),
+                },
+                TemplateVariable {
+                    name: "alert_count".to_string(),
+                    description: "Alerts listed in the digest".to_string(),
+                    example: "5".to_string(),
+                },
+                TemplateVariable {
+                    name: "affected_users".to_string(),
+                    description: "Users with at least one alert".to_string(),
+                    example: "2".to_string(),
+                },
+                TemplateVariable {
+                    name: "min_severity".to_string(),
+                    description: "Lowest severity included".to_string(),
+                    example: "medium".to_string(),
+                },
+                TemplateVariable {
+                    name: "alert_summary".to_string(),
+                    description: "Alerts grouped by user, with profile and action links (plain text, one line per entry)".to_string(),
+                    example: "jane@example.com (1)\n  2026-01-31 10:30 UTC  impossible_travel  high\n  Force password reset: https://auth9.example.com/api/v1/security-digest/actions?token=...".to_string(),
+                },
             ],
         };
 
@@ -256,6 +415,9 @@ impl FromStr for EmailTemplateType {
             "email_verification" => Ok(Self::EmailVerification),
             "password_changed" => Ok(Self::PasswordChanged),
             "security_alert" => Ok(Self::SecurityAlert),
+            "notification_digest" => Ok(Self::NotificationDigest),
+            "login_notification" => Ok(Self::LoginNotification),
+            "security_alert_digest" => Ok(Self::SecurityAlertDigest),
             _ => Err(format!("Unknown email template type: {}", s)),
         }
     }
@@ -340,7 +502,7 @@ mod tests {
     #[test]
     fn test_template_type_all() {
         let all = EmailTemplateType::all();
-        assert_eq!(all.len(), 7);
+        assert_eq!(all.len(), 10);
     }
 
     #[test]
@@ -358,6 +520,18 @@ mod tests {
             "password_changed"
         );
         assert_eq!(EmailTemplateType::SecurityAlert.as_str(), "security_alert");
+        assert_eq!(
+            EmailTemplateType::NotificationDigest.as_str(),
+            "notification_digest"
+        );
+        assert_eq!(
+            EmailTemplateType::LoginNotification.as_str(),
+            "login_notification"
+        );
+        assert_eq!(
+            EmailTemplateType::SecurityAlertDigest.as_str(),
+            "security_alert_digest"
+        );
     }
 
     #[test]
@@ -0,0 +1,241 @@
+//! Expandable response objects
+//!
+//! Read endpoints that support it inline related objects on request:
+//! `expand[]=services&expand[]=services.roles` (or `expand=services,admin_users`).
+//! A dotted path expands inside an expanded object and implies its parents.
+//! Each endpoint declares which paths it can expand; every expansion is
+//! authorized like the endpoint that lists the same objects.
+
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use utoipa::IntoParams;
+
+/// Most segments of one expansion path (`services.roles` is 2)
+pub const MAX_EXPAND_DEPTH: usize = 2;
+
+/// Most distinct expansion paths in one request, implied parents included
+pub const MAX_EXPANSIONS: usize = 10;
+
+/// Most objects inlined by one list expansion
+pub const MAX_EXPANDED_ITEMS: i64 = crate::http_support::MAX_PER_PAGE;
+
+/// Requested expansions, with implied parent paths
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpandQuery {
+    paths: BTreeSet<String>,
+}
+
+/// Expansion query parameter, for the OpenAPI document
+#[derive(Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[allow(dead_code)]
+pub struct ExpandParams {
+    /// Related objects to inline, repeatable or comma-separated; dotted
+    /// paths expand nested objects (at most 2 levels)
+    #[param(rename = "expand[]")]
+    expand: Option<Vec<String>>,
+}
+
+fn is_segment(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 64
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl ExpandQuery {
+    /// Parse decoded query string pairs, reading `expand` and `expand[]`
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self> {
+        let mut query = Self::default();
+        for (key, value) in pairs {
+            if key != "expand" && key != "expand[]" {
+                continue;
+            }
+            for path in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                query.insert(path)?;
+            }
+        }
+        if query.paths.len() > MAX_EXPANSIONS {
+            return Err(AppError::BadRequest(format!(
+                "At most {} expansions are allowed",
+                MAX_EXPANSIONS
+            )));
+        }
+        Ok(query)
+    }
+
+    /// Parse a raw (percent-encoded) query string
+    pub fn from_query_string(raw: &str) -> Result<Self> {
+        let pairs: Vec<(String, String)> = url::form_urlencoded::parse(raw.as_bytes())
+            .into_owned()
+            .collect();
+        Self::from_pairs(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    }
+
+    fn insert(&mut self, path: &str) -> Result<()> {
+        let segments: Vec<&str> = path.split('.').collect();
+        if !segments.iter().all(|s| is_segment(s)) {
+            return Err(AppError::BadRequest(format!(
+                "Invalid expand path '{}'",
+                path
+            )));
+        }
+        if segments.len() > MAX_EXPAND_DEPTH {
+            return Err(AppError::BadRequest(format!(
+                "Expand path '{}' exceeds the maximum depth of {}",
+                path, MAX_EXPAND_DEPTH
+            )));
+        }
+        for depth in 1..=segments.len() {
+            self.paths.insert(segments[..depth].join("."));
+        }
+        Ok(())
+    }
+
+    /// Reject paths the endpoint cannot expand
+    pub fn allow(&self, allowed: &[&str]) -> Result<()> {
+        match self.paths.iter().find(|p| !allowed.contains(&p.as_str())) {
+            Some(path) => Err(AppError::BadRequest(format!(
+                "Cannot expand '{}'; expandable: {}",
+                path,
+                allowed.join(", ")
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.contains(path)
+    }
+
+    /// Expansions inside the object expanded at `prefix`
+    pub fn nested(&self, prefix: &str) -> Self {
+        let prefix = format!("{}.", prefix);
+        Self {
+            paths: self
+                .paths
+                .iter()
+                .filter_map(|p| p.strip_prefix(&prefix).map(str::to_string))
+                .collect(),
+        }
+    }
+}
+
+/// Serialize `object` and inline expansions into it
+pub fn expanded<T: Serialize>(
+    object: &T,
+    expansions: impl IntoIterator<Item = (&'static str, Value)>,
+) -> Result<Value> {
+    let mut value = serde_json::to_value(object).map_err(|e| AppError::Internal(e.into()))?;
+    if let Value::Object(fields) = &mut value {
+        for (key, expansion) in expansions {
+            fields.insert(key.to_string(), expansion);
+        }
+    }
+    Ok(value)
+}
+
+/// Expanded list: at most [`MAX_EXPANDED_ITEMS`] objects, with `has_more`
+/// when `total` says more exist (fetch them from the list endpoint)
+pub fn expanded_list<T: Serialize>(items: &[T], total: usize) -> Result<Value> {
+    let shown = &items[..items.len().min(MAX_EXPANDED_ITEMS as usize)];
+    Ok(serde_json::json!({
+        "data": serde_json::to_value(shown).map_err(|e| AppError::Internal(e.into()))?,
+        "has_more": total > shown.len(),
+    }))
+}
+
+/// Name the expansion in a denied permission check
+pub fn expansion_forbidden(path: &str, error: AppError) -> AppError {
+    match error {
+        AppError::Forbidden(message) => {
+            AppError::Forbidden(format!("Cannot expand '{}': {}", path, message))
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bracket_and_comma_forms() {
+        let query = ExpandQuery::from_query_string(
+            "expand%5B%5D=services.roles&expand=admin_users,%20services&page=2",
+        )
+        .unwrap();
+        assert!(query.contains("services"));
+        assert!(query.contains("services.roles"));
+        assert!(query.contains("admin_users"));
+        assert!(!query.contains("services.clients"));
+    }
+
+    #[test]
+    fn test_parent_paths_are_implied() {
+        let query = ExpandQuery::from_pairs([("expand[]", "services.roles")]).unwrap();
+        assert!(query.contains("services"));
+        assert_eq!(
+            query.nested("services"),
+            ExpandQuery::from_pairs([("expand", "roles")]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let err =
+            ExpandQuery::from_pairs([("expand[]", "services.roles.permissions")]).unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(msg) if msg.contains("maximum depth")));
+    }
+
+    #[test]
+    fn test_invalid_path() {
+        assert!(ExpandQuery::from_pairs([("expand[]", "Services")]).is_err());
+        assert!(ExpandQuery::from_pairs([("expand[]", "services..roles")]).is_err());
+    }
+
+    #[test]
+    fn test_expansion_count_limit() {
+        let value = (0..=MAX_EXPANSIONS)
+            .map(|i| format!("e{}", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(ExpandQuery::from_pairs([("expand", value.as_str())]).is_err());
+    }
+
+    #[test]
+    fn test_allow() {
+        let query = ExpandQuery::from_pairs([("expand[]", "services.roles")]).unwrap();
+        assert!(query.allow(&["services", "services.roles"]).is_ok());
+        let err = query.allow(&["services"]).unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(msg) if msg.contains("services.roles")));
+    }
+
+    #[test]
+    fn test_expanded_list_caps_items() {
+        let items: Vec<i64> = (0..MAX_EXPANDED_ITEMS + 5).collect();
+        let value = expanded_list(&items, items.len()).unwrap();
+        assert_eq!(
+            value["data"].as_array().unwrap().len(),
+            MAX_EXPANDED_ITEMS as usize
+        );
+        assert_eq!(value["has_more"], true);
+        assert_eq!(expanded_list(&[1, 2], 2).unwrap()["has_more"], false);
+    }
+
+    #[test]
+    fn test_expanded_inlines_fields() {
+        let value = expanded(
+            &serde_json::json!({ "id": "t1" }),
+            [("services", serde_json::json!([]))],
+        )
+        .unwrap();
+        assert_eq!(value, serde_json::json!({ "id": "t1", "services": [] }));
+    }
+}
@@ -0,0 +1,239 @@
+//! External authorizer models
+//!
+//! A service can delegate its permission checks to an OPA sidecar or an
+//! OpenFGA server. In `shadow` mode the built-in RBAC result still decides
+//! and the external decision is only compared against it; in `enforce` mode
+//! the external decision wins and the `fallback` policy applies when the
+//! authorizer is unreachable.
+
+use super::common::{validate_url_no_ssrf, ServiceId, StringUuid, TenantId, UserId};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// Default time budget for one external authorizer call
+pub const DEFAULT_EXTERNAL_AUTHZ_TIMEOUT_MS: u32 = 200;
+
+/// Default lifetime of a cached external decision
+pub const DEFAULT_EXTERNAL_AUTHZ_CACHE_TTL_SECS: u32 = 30;
+
+/// Kind of external authorizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalAuthzProvider {
+    /// Open Policy Agent data API (`POST /v1/data/{policy_path}`)
+    Opa,
+    /// OpenFGA check API (`POST /stores/{store_id}/check`)
+    Openfga,
+}
+
+impl ExternalAuthzProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Opa => "opa",
+            Self::Openfga => "openfga",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "opa" => Some(Self::Opa),
+            "openfga" => Some(Self::Openfga),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the external decision is authoritative
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalAuthzMode {
+    /// Built-in RBAC decides; the external decision is only compared
+    #[default]
+    Shadow,
+    /// The external decision decides
+    Enforce,
+}
+
+impl ExternalAuthzMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shadow => "shadow",
+            Self::Enforce => "enforce",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "shadow" => Some(Self::Shadow),
+            "enforce" => Some(Self::Enforce),
+            _ => None,
+        }
+    }
+}
+
+/// Decision used in `enforce` mode when the authorizer fails or times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalAuthzFallback {
+    /// Use the built-in RBAC result
+    #[default]
+    Local,
+    /// Deny the permission
+    Deny,
+}
+
+impl ExternalAuthzFallback {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Deny => "deny",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "local" => Some(Self::Local),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// External authorizer configured for a service
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ServiceExternalAuthorizer {
+    pub service_id: StringUuid,
+    /// `opa` or `openfga`
+    pub provider: String,
+    /// Base URL of the authorizer, e.g. `http://localhost:8181`
+    pub endpoint: String,
+    /// Provider settings: `policy_path` for OPA; `store_id`,
+    /// `authorization_model_id` and `object_type` for OpenFGA
+    #[sqlx(json)]
+    pub settings: serde_json::Value,
+    /// `shadow` or `enforce`
+    pub mode: String,
+    /// `local` or `deny`
+    pub fallback: String,
+    pub timeout_ms: u32,
+    pub cache_ttl_secs: u32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ServiceExternalAuthorizer {
+    pub fn authorizer_provider(&self) -> Option<ExternalAuthzProvider> {
+        ExternalAuthzProvider::parse(&self.provider)
+    }
+
+    pub fn authorizer_mode(&self) -> ExternalAuthzMode {
+        ExternalAuthzMode::parse(&self.mode).unwrap_or_default()
+    }
+
+    pub fn authorizer_fallback(&self) -> ExternalAuthzFallback {
+        ExternalAuthzFallback::parse(&self.fallback).unwrap_or_default()
+    }
+
+    /// String setting of the provider
+    pub fn setting(&self, key: &str) -> Option<&str> {
+        self.settings.get(key).and_then(serde_json::Value::as_str)
+    }
+}
+
+/// Input for configuring the external authorizer of a service
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpsertExternalAuthorizerInput {
+    pub provider: ExternalAuthzProvider,
+    #[validate(length(min = 1, max = 512), custom(function = "validate_url_no_ssrf"))]
+    pub endpoint: String,
+    #[serde(default)]
+    #[validate(custom(function = "validate_settings_object"))]
+    pub settings: Option<serde_json::Value>,
+    pub mode: Option<ExternalAuthzMode>,
+    pub fallback: Option<ExternalAuthzFallback>,
+    #[validate(range(min = 10, max = 5000))]
+    pub timeout_ms: Option<u32>,
+    /// 0 disables decision caching
+    #[validate(range(max = 3600))]
+    pub cache_ttl_secs: Option<u32>,
+    pub enabled: Option<bool>,
+}
+
+fn validate_settings_object(settings: &serde_json::Value) -> Result<(), ValidationError> {
+    if settings.is_object() {
+        Ok(())
+    } else {
+        Err(ValidationError::new("settings_not_object"))
+    }
+}
+
+/// Facts sent to the external authorizer for one permission check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExternalAuthzRequest {
+    pub user_id: UserId,
+    pub tenant_id: TenantId,
+    pub service_id: ServiceId,
+    pub permission: String,
+}
+
+/// How the external authorizer took part in a permission check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExternalAuthzOutcome {
+    pub provider: ExternalAuthzProvider,
+    pub mode: ExternalAuthzMode,
+    /// External decision; absent when the authorizer failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_allowed: Option<bool>,
+    /// Built-in RBAC decision
+    pub local_allowed: bool,
+    /// The decision came from the decision cache
+    pub cached: bool,
+    /// The authorizer failed and the fallback policy decided
+    pub fallback_applied: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enum_parse_roundtrip() {
+        for provider in [ExternalAuthzProvider::Opa, ExternalAuthzProvider::Openfga] {
+            assert_eq!(
+                ExternalAuthzProvider::parse(provider.as_str()),
+                Some(provider)
+            );
+        }
+        for mode in [ExternalAuthzMode::Shadow, ExternalAuthzMode::Enforce] {
+            assert_eq!(ExternalAuthzMode::parse(mode.as_str()), Some(mode));
+        }
+        for fallback in [ExternalAuthzFallback::Local, ExternalAuthzFallback::Deny] {
+            assert_eq!(
+                ExternalAuthzFallback::parse(fallback.as_str()),
+                Some(fallback)
+            );
+        }
+        assert!(ExternalAuthzProvider::parse("cedar").is_none());
+    }
+
+    #[test]
+    fn test_upsert_input_validation() {
+        let input: UpsertExternalAuthorizerInput = serde_json::from_str(
+            r#"{"provider": "opa", "endpoint": "http://localhost:8181",
+                "settings": {"policy_path": "auth9/allow"}, "mode": "enforce"}"#,
+        )
+        .unwrap();
+        assert!(input.validate().is_ok());
+        assert_eq!(input.mode, Some(ExternalAuthzMode::Enforce));
+
+        let input: UpsertExternalAuthorizerInput = serde_json::from_str(
+            r#"{"provider": "openfga", "endpoint": "http://169.254.169.254", "settings": []}"#,
+        )
+        .unwrap();
+        assert!(input.validate().is_err());
+    }
+}
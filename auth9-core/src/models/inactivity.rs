@@ -0,0 +1,314 @@
+//! Inactive member lifecycle
+//!
+//! A tenant's inactivity policy (`settings.inactivity_policy`) walks members
+//! without a successful login for `inactive_days` through three steps, one
+//! per scheduled sweep: a warning email, then (after `disable_after_days`)
+//! a locked account, then (after `delete_after_days`, when set) deletion.
+//! Signing in again, or an admin unlocking the account, starts over.
+//!
+//! Accounts that are also members of other tenants are never locked or
+//! deleted by one tenant's policy; at the delete step they are only removed
+//! from the tenant.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Longest inactivity period; login history older than the event retention
+/// (13 months by default) is gone, so longer periods cannot be measured
+pub const MAX_INACTIVE_DAYS: u32 = 365;
+
+/// Inactivity policy of a tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct InactivityPolicy {
+    /// Run the lifecycle in scheduled sweeps (a disabled policy can still
+    /// be previewed)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Days without a successful login before the member is warned
+    #[validate(range(min = 7, max = 365))]
+    pub inactive_days: u32,
+    /// Days between the warning and locking the account
+    #[serde(default = "default_disable_after_days")]
+    #[validate(range(min = 1, max = 90))]
+    pub disable_after_days: u32,
+    /// Days between locking and deleting the account; never deleted when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 365))]
+    pub delete_after_days: Option<u32>,
+    /// Members the policy never applies to
+    #[serde(default)]
+    #[validate(length(max = 1000))]
+    pub exempt_user_ids: Vec<StringUuid>,
+    /// `role_in_tenant` values the policy never applies to
+    #[serde(default = "default_exempt_roles")]
+    pub exempt_roles: Vec<String>,
+}
+
+fn default_disable_after_days() -> u32 {
+    14
+}
+
+fn default_exempt_roles() -> Vec<String> {
+    vec!["owner".to_string()]
+}
+
+impl InactivityPolicy {
+    pub fn is_exempt(&self, member: &InactiveMember) -> bool {
+        self.exempt_user_ids.contains(&member.user_id)
+            || self.exempt_roles.contains(&member.role_in_tenant)
+    }
+
+    /// Members whose last activity is before this are inactive
+    pub fn inactive_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.inactive_days as i64)
+    }
+
+    /// Next lifecycle step of an inactive member and when it is due; `None`
+    /// once the account is locked and no delete step is configured
+    pub fn next_step(&self, member: &InactiveMember) -> Option<(InactivityStep, DateTime<Utc>)> {
+        let last_activity = member.last_activity_at();
+        // Steps recorded before the member's latest activity belong to an
+        // earlier inactivity period
+        let recorded = |at: Option<DateTime<Utc>>| at.filter(|at| *at >= last_activity);
+        let (mut notified_at, mut disabled_at) =
+            (recorded(member.notified_at), recorded(member.disabled_at));
+        if disabled_at.is_some() && !member.shared_account && !member.locked {
+            // An admin lifted the lock: start over with a new warning
+            notified_at = None;
+            disabled_at = None;
+        }
+
+        match (notified_at, disabled_at) {
+            (Some(_), Some(disabled_at)) => self.delete_after_days.map(|days| {
+                (
+                    InactivityStep::Delete,
+                    disabled_at + Duration::days(days as i64),
+                )
+            }),
+            (Some(notified_at), None) => Some((
+                InactivityStep::Disable,
+                notified_at + Duration::days(self.disable_after_days as i64),
+            )),
+            (None, _) => Some((
+                InactivityStep::Notify,
+                last_activity + Duration::days(self.inactive_days as i64),
+            )),
+        }
+    }
+}
+
+/// Lifecycle step applied to an inactive member
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InactivityStep {
+    /// Warning email
+    Notify,
+    /// Account locked and sessions revoked (shared accounts are kept)
+    Disable,
+    /// Account deleted (shared accounts are removed from the tenant)
+    Delete,
+}
+
+impl InactivityStep {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Notify => "notify",
+            Self::Disable => "disable",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// Webhook event sent to the tenant when the step is applied
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::Notify => "member.inactivity_notified",
+            Self::Disable => "member.inactivity_disabled",
+            Self::Delete => "member.inactivity_deleted",
+        }
+    }
+}
+
+/// Tenant member without a successful login since the policy's cutoff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct InactiveMember {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub role_in_tenant: String,
+    pub joined_at: DateTime<Utc>,
+    /// Last successful login, if still in the login history
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub notified_at: Option<DateTime<Utc>>,
+    pub disabled_at: Option<DateTime<Utc>>,
+    /// Also a member of other tenants
+    pub shared_account: bool,
+    /// Account currently locked
+    pub locked: bool,
+}
+
+impl InactiveMember {
+    /// Latest of the last login and joining the tenant
+    pub fn last_activity_at(&self) -> DateTime<Utc> {
+        self.last_login_at
+            .map_or(self.joined_at, |at| at.max(self.joined_at))
+    }
+}
+
+/// Preview row: an inactive member and the step the policy takes next
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InactivityPreviewEntry {
+    #[serde(flatten)]
+    pub member: InactiveMember,
+    pub next_step: Option<InactivityStep>,
+    /// When the next step is due; steps due now run at the next sweep
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+/// Number of inactive members per next step
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct InactivitySummary {
+    /// Steps due at the next sweep
+    pub notify: u64,
+    pub disable: u64,
+    pub delete: u64,
+    /// Inactive members whose next step is not due yet
+    pub waiting: u64,
+}
+
+/// Who the policy affects now and next
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InactivityPreview {
+    pub policy: InactivityPolicy,
+    pub as_of: DateTime<Utc>,
+    pub summary: InactivitySummary,
+    /// Inactive members, exempt ones excluded (at most
+    /// [`MAX_PREVIEW_ENTRIES`], see `truncated`)
+    pub members: Vec<InactivityPreviewEntry>,
+    pub truncated: bool,
+}
+
+/// Most members listed by a preview
+pub const MAX_PREVIEW_ENTRIES: usize = 500;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> InactivityPolicy {
+        InactivityPolicy {
+            enabled: true,
+            inactive_days: 90,
+            disable_after_days: 14,
+            delete_after_days: Some(30),
+            exempt_user_ids: vec![],
+            exempt_roles: default_exempt_roles(),
+        }
+    }
+
+    fn member(last_login_days_ago: i64) -> InactiveMember {
+        let now = Utc::now();
+        InactiveMember {
+            user_id: StringUuid::new_v4(),
+            email: "idle@example.com".to_string(),
+            role_in_tenant: "member".to_string(),
+            joined_at: now - Duration::days(400),
+            last_login_at: Some(now - Duration::days(last_login_days_ago)),
+            notified_at: None,
+            disabled_at: None,
+            shared_account: false,
+            locked: false,
+        }
+    }
+
+    #[test]
+    fn test_policy_defaults_and_validation() {
+        let policy: InactivityPolicy =
+            serde_json::from_value(serde_json::json!({ "inactive_days": 90 })).unwrap();
+        assert!(!policy.enabled);
+        assert_eq!(policy.disable_after_days, 14);
+        assert_eq!(policy.delete_after_days, None);
+        assert_eq!(policy.exempt_roles, vec!["owner"]);
+        assert!(policy.validate().is_ok());
+
+        let too_long = InactivityPolicy {
+            inactive_days: MAX_INACTIVE_DAYS + 1,
+            ..policy
+        };
+        assert!(too_long.validate().is_err());
+    }
+
+    #[test]
+    fn test_steps_follow_each_other() {
+        let policy = policy();
+        let mut member = member(100);
+        let last_activity = member.last_activity_at();
+
+        let (step, due) = policy.next_step(&member).unwrap();
+        assert_eq!(step, InactivityStep::Notify);
+        assert_eq!(due, last_activity + Duration::days(90));
+
+        let notified_at = Utc::now();
+        member.notified_at = Some(notified_at);
+        assert_eq!(
+            policy.next_step(&member),
+            Some((InactivityStep::Disable, notified_at + Duration::days(14)))
+        );
+
+        member.disabled_at = Some(notified_at);
+        member.locked = true;
+        assert_eq!(
+            policy.next_step(&member),
+            Some((InactivityStep::Delete, notified_at + Duration::days(30)))
+        );
+
+        let keep = InactivityPolicy {
+            delete_after_days: None,
+            ..policy
+        };
+        assert_eq!(keep.next_step(&member), None);
+    }
+
+    #[test]
+    fn test_login_after_warning_starts_over() {
+        let policy = policy();
+        let mut member = member(100);
+        member.notified_at = Some(Utc::now() - Duration::days(120));
+        assert_eq!(policy.next_step(&member).unwrap().0, InactivityStep::Notify);
+    }
+
+    #[test]
+    fn test_unlocked_account_starts_over() {
+        let policy = policy();
+        let mut member = member(100);
+        member.notified_at = Some(Utc::now() - Duration::days(20));
+        member.disabled_at = Some(Utc::now() - Duration::days(5));
+        assert_eq!(policy.next_step(&member).unwrap().0, InactivityStep::Notify);
+
+        // Shared accounts are never locked, so the lock cannot be lifted
+        member.shared_account = true;
+        assert_eq!(policy.next_step(&member).unwrap().0, InactivityStep::Delete);
+    }
+
+    #[test]
+    fn test_member_who_never_logged_in_counts_from_joining() {
+        let mut member = member(0);
+        member.last_login_at = None;
+        assert_eq!(member.last_activity_at(), member.joined_at);
+    }
+
+    #[test]
+    fn test_exemptions() {
+        let mut policy = policy();
+        let mut member = member(100);
+        assert!(!policy.is_exempt(&member));
+
+        member.role_in_tenant = "owner".to_string();
+        assert!(policy.is_exempt(&member));
+
+        member.role_in_tenant = "member".to_string();
+        policy.exempt_user_ids.push(member.user_id);
+        assert!(policy.is_exempt(&member));
+    }
+}
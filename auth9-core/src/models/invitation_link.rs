@@ -0,0 +1,329 @@
+//! Shareable invitation link types
+//!
+//! Unlike an email invitation, a link is not bound to one address: anyone
+//! signed in who opens it joins the tenant with the link's roles, until it
+//! expires, runs out of uses or is revoked. Every join is recorded so new
+//! members can be attributed to the link they came through.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// Default link lifetime (7 days)
+pub const DEFAULT_LINK_EXPIRES_IN_HOURS: i64 = 168;
+
+/// Invitation link entity
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InvitationLink {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    /// Label shown to administrators (e.g. "Spring hiring")
+    pub name: Option<String>,
+    /// Roles assigned to everyone joining through the link
+    #[sqlx(json)]
+    pub role_ids: Vec<StringUuid>,
+    /// Email domains allowed to join; empty allows any
+    #[sqlx(json)]
+    pub allowed_email_domains: Vec<String>,
+    /// Maximum number of joins; unlimited when unset
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub created_by: StringUuid,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Effective state of an invitation link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationLinkStatus {
+    Active,
+    Expired,
+    Exhausted,
+    Revoked,
+}
+
+impl std::fmt::Display for InvitationLinkStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Active => write!(f, "active"),
+            Self::Expired => write!(f, "expired"),
+            Self::Exhausted => write!(f, "exhausted"),
+            Self::Revoked => write!(f, "revoked"),
+        }
+    }
+}
+
+impl InvitationLink {
+    pub fn status_at(&self, now: DateTime<Utc>) -> InvitationLinkStatus {
+        if self.revoked_at.is_some() {
+            InvitationLinkStatus::Revoked
+        } else if self.expires_at <= now {
+            InvitationLinkStatus::Expired
+        } else if self.max_uses.is_some_and(|max| self.use_count >= max) {
+            InvitationLinkStatus::Exhausted
+        } else {
+            InvitationLinkStatus::Active
+        }
+    }
+
+    pub fn status(&self) -> InvitationLinkStatus {
+        self.status_at(Utc::now())
+    }
+
+    /// Whether `email` satisfies the link's domain restriction
+    pub fn allows_email(&self, email: &str) -> bool {
+        if self.allowed_email_domains.is_empty() {
+            return true;
+        }
+        let Some((_, domain)) = email.rsplit_once('@') else {
+            return false;
+        };
+        let domain = domain.to_lowercase();
+        self.allowed_email_domains.iter().any(|d| *d == domain)
+    }
+}
+
+impl Default for InvitationLink {
+    fn default() -> Self {
+        let now = Utc::now();
+        Self {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            name: None,
+            role_ids: Vec::new(),
+            allowed_email_domains: Vec::new(),
+            max_uses: None,
+            use_count: 0,
+            created_by: StringUuid::new_v4(),
+            token_hash: String::new(),
+            expires_at: now + chrono::Duration::hours(DEFAULT_LINK_EXPIRES_IN_HOURS),
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Input for creating an invitation link
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateInvitationLinkInput {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+
+    /// Role IDs to assign when someone joins
+    #[serde(default)]
+    #[validate(length(max = 50))]
+    pub role_ids: Vec<StringUuid>,
+
+    /// Restrict joining to these email domains (e.g. `example.com`)
+    #[serde(default)]
+    #[validate(length(max = 50), custom(function = "validate_email_domains"))]
+    pub allowed_email_domains: Vec<String>,
+
+    /// Maximum number of joins (unlimited when omitted)
+    #[validate(range(min = 1, max = 100000))]
+    pub max_uses: Option<i32>,
+
+    /// Custom expiration in hours (default: 168)
+    #[validate(range(min = 1, max = 8760))]
+    pub expires_in_hours: Option<i64>,
+}
+
+fn validate_email_domains(domains: &[String]) -> Result<(), ValidationError> {
+    let valid = domains.iter().all(|d| {
+        let d = d.trim();
+        !d.is_empty()
+            && d.len() <= 253
+            && d.contains('.')
+            && !d.starts_with(['.', '-', '@'])
+            && d.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_email_domain"))
+    }
+}
+
+/// Lowercase, trim and deduplicate email domains
+pub fn normalize_email_domains(domains: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = domains.iter().map(|d| d.trim().to_lowercase()).collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// API representation of an invitation link (without the token hash)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvitationLinkResponse {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub name: Option<String>,
+    pub role_ids: Vec<StringUuid>,
+    pub allowed_email_domains: Vec<String>,
+    pub max_uses: Option<i32>,
+    pub use_count: i32,
+    pub status: InvitationLinkStatus,
+    pub created_by: StringUuid,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<InvitationLink> for InvitationLinkResponse {
+    fn from(link: InvitationLink) -> Self {
+        Self {
+            status: link.status(),
+            id: link.id,
+            tenant_id: link.tenant_id,
+            name: link.name,
+            role_ids: link.role_ids,
+            allowed_email_domains: link.allowed_email_domains,
+            max_uses: link.max_uses,
+            use_count: link.use_count,
+            created_by: link.created_by,
+            expires_at: link.expires_at,
+            revoked_at: link.revoked_at,
+            created_at: link.created_at,
+        }
+    }
+}
+
+/// Newly created link; the token and URL are only returned here
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedInvitationLink {
+    #[serde(flatten)]
+    pub link: InvitationLinkResponse,
+    pub token: String,
+    pub url: String,
+}
+
+/// A user who joined a tenant through an invitation link
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InvitationLinkRedemption {
+    pub id: StringUuid,
+    pub link_id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub user_id: StringUuid,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for joining a tenant through a link
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct JoinInvitationLinkInput {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_link_status() {
+        let now = Utc::now();
+        let link = InvitationLink::default();
+        assert_eq!(link.status_at(now), InvitationLinkStatus::Active);
+
+        let exhausted = InvitationLink {
+            max_uses: Some(2),
+            use_count: 2,
+            ..Default::default()
+        };
+        assert_eq!(exhausted.status_at(now), InvitationLinkStatus::Exhausted);
+
+        let expired = InvitationLink {
+            expires_at: now - Duration::minutes(1),
+            max_uses: Some(2),
+            use_count: 2,
+            ..Default::default()
+        };
+        assert_eq!(expired.status_at(now), InvitationLinkStatus::Expired);
+
+        let revoked = InvitationLink {
+            revoked_at: Some(now),
+            expires_at: now - Duration::minutes(1),
+            ..Default::default()
+        };
+        assert_eq!(revoked.status_at(now), InvitationLinkStatus::Revoked);
+    }
+
+    #[test]
+    fn test_allows_email() {
+        let open = InvitationLink::default();
+        assert!(open.allows_email("anyone@anywhere.io"));
+
+        let restricted = InvitationLink {
+            allowed_email_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(restricted.allows_email("alice@Example.COM"));
+        assert!(!restricted.allows_email("alice@sub.example.com"));
+        assert!(!restricted.allows_email("alice@example.com.evil.io"));
+        assert!(!restricted.allows_email("not-an-email"));
+    }
+
+    #[test]
+    fn test_create_input_validation() {
+        let input: CreateInvitationLinkInput = serde_json::from_value(serde_json::json!({
+            "allowed_email_domains": ["example.com", "Corp.Example.org"],
+            "max_uses": 10
+        }))
+        .unwrap();
+        assert!(input.validate().is_ok());
+        assert!(input.role_ids.is_empty());
+
+        for domains in [
+            vec!["@example.com"],
+            vec!["localhost"],
+            vec!["exa mple.com"],
+        ] {
+            let input = CreateInvitationLinkInput {
+                allowed_email_domains: domains.into_iter().map(String::from).collect(),
+                ..input.clone()
+            };
+            assert!(input.validate().is_err());
+        }
+
+        let input = CreateInvitationLinkInput {
+            max_uses: Some(0),
+            ..input.clone()
+        };
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_normalize_email_domains() {
+        let domains = vec![
+            " Example.com".to_string(),
+            "example.com".to_string(),
+            "b.io".to_string(),
+        ];
+        assert_eq!(
+            normalize_email_domains(&domains),
+            vec!["b.io".to_string(), "example.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_response_hides_token_hash() {
+        let link = InvitationLink {
+            token_hash: "secret-hash".to_string(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&InvitationLinkResponse::from(link)).unwrap();
+        assert!(!json.contains("secret-hash"));
+        assert!(json.contains("\"status\":\"active\""));
+    }
+}
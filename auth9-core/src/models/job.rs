@@ -0,0 +1,356 @@
+//! Async job models
+//!
+//! Long-running admin operations (bulk user import/export, tenant deletion,
+//! audit log exports, cohort password resets, password breach campaigns,
+//! schema backfills, inactive member sweeps, account deletions, data key
+//! rotations) are queued as jobs and executed by background workers.
+//! Callers get the job back immediately and poll `GET /api/v1/jobs/{id}` for
+//! progress.
+
+use super::common::StringUuid;
+use super::user::UserCohort;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::mysql::MySqlRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Operation a job performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    UserImport,
+    UserExport,
+    TenantDelete,
+    AuditExport,
+    ForcePasswordReset,
+    BreachCampaign,
+    /// Batched data backfill requested by a migration (platform-wide)
+    SchemaBackfill,
+    /// Scheduled run of a tenant's inactivity policy
+    InactivitySweep,
+    /// Removal of a self-deleted account after its grace period
+    AccountDeletion,
+    /// Batched re-encryption of stored secrets after a data or master key
+    /// rotation
+    KeyRotation,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UserImport => "user_import",
+            Self::UserExport => "user_export",
+            Self::TenantDelete => "tenant_delete",
+            Self::AuditExport => "audit_export",
+            Self::ForcePasswordReset => "force_password_reset",
+            Self::BreachCampaign => "breach_campaign",
+            Self::SchemaBackfill => "schema_backfill",
+            Self::InactivitySweep => "inactivity_sweep",
+            Self::AccountDeletion => "account_deletion",
+            Self::KeyRotation => "key_rotation",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user_import" => Some(Self::UserImport),
+            "user_export" => Some(Self::UserExport),
+            "tenant_delete" => Some(Self::TenantDelete),
+            "audit_export" => Some(Self::AuditExport),
+            "force_password_reset" => Some(Self::ForcePasswordReset),
+            "breach_campaign" => Some(Self::BreachCampaign),
+            "schema_backfill" => Some(Self::SchemaBackfill),
+            "inactivity_sweep" => Some(Self::InactivitySweep),
+            "account_deletion" => Some(Self::AccountDeletion),
+            "key_rotation" => Some(Self::KeyRotation),
+            _ => None,
+        }
+    }
+}
+
+/// Job lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(Self::Queued),
+            "running" => Some(Self::Running),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            "cancelled" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// Job entity
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Job {
+    pub id: StringUuid,
+    pub tenant_id: Option<StringUuid>,
+    /// One of `user_import`, `user_export`, `tenant_delete`, `audit_export`,
+    /// `force_password_reset`, `breach_campaign`, `schema_backfill`,
+    /// `inactivity_sweep`, `account_deletion`, `key_rotation`
+    pub kind: String,
+    /// One of `queued`, `running`, `succeeded`, `failed`, `cancelled`
+    pub status: String,
+    #[serde(skip)]
+    pub payload: serde_json::Value,
+    pub total_items: i32,
+    pub processed_items: i32,
+    pub failed_items: i32,
+    /// Per-item results recorded so far (available while the job is running)
+    pub results: Vec<serde_json::Value>,
+    pub error: Option<String>,
+    pub cancel_requested: bool,
+    #[serde(skip)]
+    pub claim_id: Option<String>,
+    /// Where a batched executor resumes after a requeue
+    #[serde(skip)]
+    pub resume_after: Option<String>,
+    pub created_by: Option<StringUuid>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Job {
+    pub fn job_kind(&self) -> Option<JobKind> {
+        JobKind::parse(&self.kind)
+    }
+
+    pub fn job_status(&self) -> Option<JobStatus> {
+        JobStatus::parse(&self.status)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.job_status().is_some_and(|s| s.is_terminal())
+    }
+
+    /// Completion percentage (0-100)
+    pub fn progress_percent(&self) -> u8 {
+        if self.status == JobStatus::Succeeded.as_str() {
+            return 100;
+        }
+        if self.total_items <= 0 {
+            return 0;
+        }
+        let done = self.processed_items.clamp(0, self.total_items) as i64;
+        (done * 100 / self.total_items as i64) as u8
+    }
+}
+
+impl<'r> FromRow<'r, MySqlRow> for Job {
+    fn from_row(row: &'r MySqlRow) -> sqlx::Result<Self> {
+        let payload: sqlx::types::Json<serde_json::Value> = row.try_get("payload")?;
+        let results: Option<sqlx::types::Json<Vec<serde_json::Value>>> = row.try_get("results")?;
+
+        Ok(Job {
+            id: row.try_get("id")?,
+            tenant_id: row.try_get("tenant_id")?,
+            kind: row.try_get("kind")?,
+            status: row.try_get("status")?,
+            payload: payload.0,
+            total_items: row.try_get("total_items")?,
+            processed_items: row.try_get("processed_items")?,
+            failed_items: row.try_get("failed_items")?,
+            results: results.map(|r| r.0).unwrap_or_default(),
+            error: row.try_get("error")?,
+            cancel_requested: row.try_get("cancel_requested")?,
+            claim_id: row.try_get("claim_id")?,
+            resume_after: row.try_get("resume_after")?,
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            started_at: row.try_get("started_at")?,
+            finished_at: row.try_get("finished_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// Job as returned by the API, with computed progress
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobResponse {
+    #[serde(flatten)]
+    pub job: Job,
+    pub progress_percent: u8,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        let progress_percent = job.progress_percent();
+        Self {
+            job,
+            progress_percent,
+        }
+    }
+}
+
+/// Input for enqueuing a job
+#[derive(Debug, Clone)]
+pub struct CreateJobInput {
+    pub kind: JobKind,
+    pub tenant_id: Option<StringUuid>,
+    pub payload: serde_json::Value,
+    pub created_by: Option<StringUuid>,
+}
+
+/// One user row of a bulk import
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UserImportRow {
+    #[validate(email)]
+    pub email: String,
+    #[validate(length(max = 255))]
+    pub display_name: Option<String>,
+    /// Role in the target tenant (`admin` or `member`; defaults to `member`)
+    pub role_in_tenant: Option<String>,
+}
+
+/// Bulk user import request
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UserImportInput {
+    /// At most 10,000 rows per job
+    #[validate(length(min = 1, max = 10000), nested)]
+    pub users: Vec<UserImportRow>,
+}
+
+/// Cohort password reset request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ForcePasswordResetInput {
+    /// Tenant members to flag
+    pub cohort: UserCohort,
+    /// Also revoke the users' active sessions (default false)
+    #[serde(default)]
+    pub revoke_sessions: bool,
+    /// Email each user a password reset link (default true)
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(status: JobStatus, processed: i32, total: i32) -> Job {
+        Job {
+            id: StringUuid::new_v4(),
+            tenant_id: None,
+            kind: JobKind::UserExport.as_str().to_string(),
+            status: status.as_str().to_string(),
+            payload: serde_json::json!({}),
+            total_items: total,
+            processed_items: processed,
+            failed_items: 0,
+            results: vec![],
+            error: None,
+            cancel_requested: false,
+            claim_id: None,
+            resume_after: None,
+            created_by: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_kind_and_status_roundtrip() {
+        for kind in [
+            JobKind::UserImport,
+            JobKind::UserExport,
+            JobKind::TenantDelete,
+            JobKind::AuditExport,
+            JobKind::ForcePasswordReset,
+            JobKind::BreachCampaign,
+            JobKind::SchemaBackfill,
+            JobKind::InactivitySweep,
+            JobKind::AccountDeletion,
+            JobKind::KeyRotation,
+        ] {
+            assert_eq!(JobKind::parse(kind.as_str()), Some(kind));
+        }
+        for status in [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ] {
+            assert_eq!(JobStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(JobKind::parse("reindex"), None);
+    }
+
+    #[test]
+    fn test_progress_percent() {
+        assert_eq!(job(JobStatus::Queued, 0, 0).progress_percent(), 0);
+        assert_eq!(job(JobStatus::Running, 25, 200).progress_percent(), 12);
+        assert_eq!(job(JobStatus::Running, 500, 200).progress_percent(), 100);
+        assert_eq!(job(JobStatus::Succeeded, 0, 0).progress_percent(), 100);
+        assert_eq!(job(JobStatus::Cancelled, 50, 100).progress_percent(), 50);
+    }
+
+    #[test]
+    fn test_terminal_statuses() {
+        assert!(!job(JobStatus::Queued, 0, 0).is_terminal());
+        assert!(!job(JobStatus::Running, 0, 0).is_terminal());
+        assert!(job(JobStatus::Failed, 0, 0).is_terminal());
+        assert!(job(JobStatus::Cancelled, 0, 0).is_terminal());
+    }
+
+    #[test]
+    fn test_force_password_reset_input_defaults() {
+        let input: ForcePasswordResetInput = serde_json::from_value(serde_json::json!({
+            "cohort": { "type": "role", "role": "admin" }
+        }))
+        .unwrap();
+        assert_eq!(
+            input.cohort,
+            UserCohort::Role {
+                role: "admin".to_string()
+            }
+        );
+        assert!(!input.revoke_sessions);
+        assert!(input.notify);
+    }
+
+    #[test]
+    fn test_response_hides_internal_fields() {
+        let value = serde_json::to_value(JobResponse::from(job(JobStatus::Running, 1, 4))).unwrap();
+        assert_eq!(value["progress_percent"], 25);
+        assert!(value.get("payload").is_none());
+        assert!(value.get("claim_id").is_none());
+        assert!(value.get("resume_after").is_none());
+    }
+}
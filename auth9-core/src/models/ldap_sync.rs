@@ -0,0 +1,340 @@
+//! LDAP/Active Directory directory sync models
+//!
+//! An LDAP connector can periodically import directory users into its tenant
+//! and keep their roles in line with the connector's group-role mappings.
+//! Sync settings live in the connector config next to the LDAP settings
+//! (`syncEnabled`, `syncIntervalMinutes`, `syncConflictPolicy`). Every run,
+//! including dry runs, is recorded with its counters and planned changes.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::mysql::MySqlRow;
+use sqlx::{FromRow, Row};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Default interval between scheduled syncs of a connector, in minutes
+pub const DEFAULT_SYNC_INTERVAL_MINUTES: u64 = 60;
+
+/// Shortest accepted interval between scheduled syncs, in minutes
+pub const MIN_SYNC_INTERVAL_MINUTES: u64 = 5;
+
+/// Most planned changes kept on a run record
+pub const MAX_RECORDED_CHANGES: usize = 500;
+
+/// What to do with a directory user whose email belongs to an Auth9 user
+/// that is not linked to the connector yet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LdapSyncConflictPolicy {
+    /// Leave the existing user alone and report a conflict
+    #[default]
+    Skip,
+    /// Link the existing user to the directory entry, keeping its profile
+    Link,
+    /// Link the existing user and take the profile from the directory
+    Overwrite,
+}
+
+impl LdapSyncConflictPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Link => "link",
+            Self::Overwrite => "overwrite",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skip" => Some(Self::Skip),
+            "link" => Some(Self::Link),
+            "overwrite" => Some(Self::Overwrite),
+            _ => None,
+        }
+    }
+}
+
+/// Sync settings of an LDAP connector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdapSyncSettings {
+    /// Whether the scheduler syncs the connector; manual runs are always allowed
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    pub conflict_policy: LdapSyncConflictPolicy,
+}
+
+impl LdapSyncSettings {
+    /// Read the sync settings from an LDAP connector config. Unknown conflict
+    /// policies fall back to `skip`.
+    pub fn from_connector_config(config: &HashMap<String, String>) -> Self {
+        Self {
+            enabled: config
+                .get("syncEnabled")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            interval_minutes: config
+                .get("syncIntervalMinutes")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_SYNC_INTERVAL_MINUTES)
+                .max(MIN_SYNC_INTERVAL_MINUTES),
+            conflict_policy: config
+                .get("syncConflictPolicy")
+                .and_then(|v| LdapSyncConflictPolicy::parse(v))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Full syncs read every directory user, incremental syncs only the entries
+/// changed since the cursor of the last successful run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LdapSyncMode {
+    Full,
+    Incremental,
+}
+
+impl LdapSyncMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Incremental => "incremental",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LdapSyncStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl LdapSyncStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Kind of change a sync applies (or would apply, in a dry run)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LdapSyncAction {
+    CreateUser,
+    UpdateUser,
+    LinkUser,
+    Conflict,
+    AssignRole,
+    RemoveRole,
+}
+
+/// One planned or applied change of a sync run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LdapSyncChange {
+    pub action: LdapSyncAction,
+    /// DN of the directory entry the change comes from
+    pub dn: String,
+    pub email: Option<String>,
+    pub user_id: Option<StringUuid>,
+    pub role_id: Option<StringUuid>,
+    pub detail: Option<String>,
+}
+
+/// Directory users read by a sync, with the cursor to resume from next time
+#[derive(Debug, Clone, Default)]
+pub struct LdapSyncBatch {
+    pub users: Vec<super::ldap::LdapUserProfile>,
+    /// Highest committed USN (Active Directory) or latest `modifyTimestamp`
+    /// (other servers) covered by this batch
+    pub cursor: Option<String>,
+}
+
+/// Record of one sync run of a connector
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LdapSyncRun {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub connector_id: StringUuid,
+    /// `manual` or `scheduled`
+    pub triggered_by: String,
+    /// One of `full`, `incremental`
+    pub mode: String,
+    pub dry_run: bool,
+    /// One of `running`, `succeeded`, `failed`
+    pub status: String,
+    pub conflict_policy: String,
+    pub cursor_before: Option<String>,
+    pub cursor_after: Option<String>,
+    pub users_seen: i32,
+    pub users_created: i32,
+    pub users_updated: i32,
+    pub users_linked: i32,
+    pub conflicts: i32,
+    pub roles_assigned: i32,
+    pub roles_removed: i32,
+    /// Changes applied by the run, or planned by a dry run (capped)
+    pub changes: Vec<LdapSyncChange>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl LdapSyncRun {
+    pub fn start(
+        tenant_id: StringUuid,
+        connector_id: StringUuid,
+        triggered_by: &str,
+        mode: LdapSyncMode,
+        dry_run: bool,
+        conflict_policy: LdapSyncConflictPolicy,
+        cursor_before: Option<String>,
+    ) -> Self {
+        Self {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            connector_id,
+            triggered_by: triggered_by.to_string(),
+            mode: mode.as_str().to_string(),
+            dry_run,
+            status: LdapSyncStatus::Running.as_str().to_string(),
+            conflict_policy: conflict_policy.as_str().to_string(),
+            cursor_before,
+            cursor_after: None,
+            users_seen: 0,
+            users_created: 0,
+            users_updated: 0,
+            users_linked: 0,
+            conflicts: 0,
+            roles_assigned: 0,
+            roles_removed: 0,
+            changes: Vec::new(),
+            error: None,
+            started_at: Utc::now(),
+            finished_at: None,
+        }
+    }
+
+    /// Count a change and keep it on the record while there is room
+    pub fn record(&mut self, change: LdapSyncChange) {
+        match change.action {
+            LdapSyncAction::CreateUser => self.users_created += 1,
+            LdapSyncAction::UpdateUser => self.users_updated += 1,
+            LdapSyncAction::LinkUser => self.users_linked += 1,
+            LdapSyncAction::Conflict => self.conflicts += 1,
+            LdapSyncAction::AssignRole => self.roles_assigned += 1,
+            LdapSyncAction::RemoveRole => self.roles_removed += 1,
+        }
+        if self.changes.len() < MAX_RECORDED_CHANGES {
+            self.changes.push(change);
+        }
+    }
+}
+
+impl<'r> FromRow<'r, MySqlRow> for LdapSyncRun {
+    fn from_row(row: &'r MySqlRow) -> sqlx::Result<Self> {
+        let changes: Option<sqlx::types::Json<Vec<LdapSyncChange>>> = row.try_get("changes")?;
+
+        Ok(LdapSyncRun {
+            id: row.try_get("id")?,
+            tenant_id: row.try_get("tenant_id")?,
+            connector_id: row.try_get("connector_id")?,
+            triggered_by: row.try_get("triggered_by")?,
+            mode: row.try_get("mode")?,
+            dry_run: row.try_get("dry_run")?,
+            status: row.try_get("status")?,
+            conflict_policy: row.try_get("conflict_policy")?,
+            cursor_before: row.try_get("cursor_before")?,
+            cursor_after: row.try_get("cursor_after")?,
+            users_seen: row.try_get("users_seen")?,
+            users_created: row.try_get("users_created")?,
+            users_updated: row.try_get("users_updated")?,
+            users_linked: row.try_get("users_linked")?,
+            conflicts: row.try_get("conflicts")?,
+            roles_assigned: row.try_get("roles_assigned")?,
+            roles_removed: row.try_get("roles_removed")?,
+            changes: changes.map(|c| c.0).unwrap_or_default(),
+            error: row.try_get("error")?,
+            started_at: row.try_get("started_at")?,
+            finished_at: row.try_get("finished_at")?,
+        })
+    }
+}
+
+/// Manual sync request
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct TriggerLdapSyncInput {
+    /// Plan the changes without applying them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Read every directory user instead of only the changed ones
+    #[serde(default)]
+    pub full: bool,
+}
+
+/// LDAP connector picked up by the sync scheduler
+#[derive(Debug, Clone)]
+pub struct LdapSyncConnector {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub alias: String,
+    pub config: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_settings_from_connector_config() {
+        let defaults = LdapSyncSettings::from_connector_config(&HashMap::new());
+        assert!(!defaults.enabled);
+        assert_eq!(defaults.interval_minutes, DEFAULT_SYNC_INTERVAL_MINUTES);
+        assert_eq!(defaults.conflict_policy, LdapSyncConflictPolicy::Skip);
+
+        let config: HashMap<String, String> = [
+            ("syncEnabled", "true"),
+            ("syncIntervalMinutes", "1"),
+            ("syncConflictPolicy", "overwrite"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let settings = LdapSyncSettings::from_connector_config(&config);
+        assert!(settings.enabled);
+        assert_eq!(settings.interval_minutes, MIN_SYNC_INTERVAL_MINUTES);
+        assert_eq!(settings.conflict_policy, LdapSyncConflictPolicy::Overwrite);
+    }
+
+    #[test]
+    fn test_run_record_counts_and_caps_changes() {
+        let mut run = LdapSyncRun::start(
+            StringUuid::new_v4(),
+            StringUuid::new_v4(),
+            "manual",
+            LdapSyncMode::Full,
+            true,
+            LdapSyncConflictPolicy::Skip,
+            None,
+        );
+        for _ in 0..MAX_RECORDED_CHANGES + 1 {
+            run.record(LdapSyncChange {
+                action: LdapSyncAction::AssignRole,
+                dn: "uid=a,dc=example,dc=com".to_string(),
+                email: None,
+                user_id: None,
+                role_id: None,
+                detail: None,
+            });
+        }
+        assert_eq!(run.roles_assigned as usize, MAX_RECORDED_CHANGES + 1);
+        assert_eq!(run.changes.len(), MAX_RECORDED_CHANGES);
+        assert_eq!(run.status, "running");
+    }
+}
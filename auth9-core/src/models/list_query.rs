@@ -0,0 +1,404 @@
+//! Standard list query grammar
+//!
+//! The tenant and service list endpoints accept one query string grammar on
+//! top of `page` / `per_page` (alias `limit`) and `search`:
+//!
+//! - `filter[field]=value` — equality; `filter[field][op]=value` with `op`
+//!   one of `eq`, `ne`, `contains`, `gt`, `gte`, `lt`, `lte`, `in`
+//!   (comma-separated values)
+//! - `sort=field,-other` — ascending, `-` for descending
+//! - `fields=id,name` — sparse fieldset of the returned objects
+//!
+//! Which fields can be filtered and sorted is decided per entity by the
+//! repository's allowlist, see [`crate::repository::list_query::ListSpec`].
+//!
+//! Other lists (users, roles, clients, audit logs, webhooks) still take their
+//! endpoint-specific parameters and extract [`ListGrammarUnsupported`], so
+//! `filter[...]`, `sort` and `fields` fail with 400 there instead of being
+//! ignored. An entity adopts the grammar by declaring a `ListSpec` and a
+//! `list_filtered` repository method, as tenants and services do.
+
+use crate::error::{AppError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+/// Largest `per_page` a list endpoint returns
+pub const MAX_PER_PAGE: i64 = crate::http_support::MAX_PER_PAGE;
+
+/// Most filter clauses in one query
+pub const MAX_FILTERS: usize = 10;
+
+/// Most sort keys in one query
+pub const MAX_SORT_KEYS: usize = 3;
+
+/// Most values of an `in` filter
+pub const MAX_IN_VALUES: usize = 50;
+
+/// Comparison of a filter clause
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Contains,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+}
+
+impl FilterOp {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "eq" => Some(Self::Eq),
+            "ne" => Some(Self::Ne),
+            "contains" => Some(Self::Contains),
+            "gt" => Some(Self::Gt),
+            "gte" => Some(Self::Gte),
+            "lt" => Some(Self::Lt),
+            "lte" => Some(Self::Lte),
+            "in" => Some(Self::In),
+            _ => None,
+        }
+    }
+
+    /// SQL operator for the scalar comparisons
+    pub fn sql(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "<>",
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Contains => "LIKE",
+            Self::In => "IN",
+        }
+    }
+}
+
+/// One `filter[field][op]=value` clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterClause {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+/// One `sort` key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: String,
+    pub descending: bool,
+}
+
+/// Parsed list query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListQuery {
+    pub page: i64,
+    pub per_page: i64,
+    pub search: Option<String>,
+    pub filters: Vec<FilterClause>,
+    pub sort: Vec<SortKey>,
+    pub fields: Option<Vec<String>>,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 20,
+            search: None,
+            filters: Vec::new(),
+            sort: Vec::new(),
+            fields: None,
+        }
+    }
+}
+
+/// Query parameters of the standard grammar, for the OpenAPI document
+#[derive(Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[allow(dead_code)]
+pub struct ListQueryParams {
+    /// Page number, starting at 1
+    page: Option<i64>,
+    /// Items per page (alias `limit`, at most 100)
+    per_page: Option<i64>,
+    /// Free-text search over the entity's search columns
+    search: Option<String>,
+    /// `filter[field]=value` or `filter[field][op]=value`
+    #[param(rename = "filter[field][op]")]
+    filter: Option<String>,
+    /// Comma-separated sort keys, `-` prefix for descending
+    sort: Option<String>,
+    /// Comma-separated fields to return
+    fields: Option<String>,
+}
+
+fn is_field_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 64
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn bad_request(message: impl Into<String>) -> AppError {
+    AppError::BadRequest(message.into())
+}
+
+impl ListQuery {
+    /// Parse decoded query string pairs; parameters outside the grammar
+    /// are left to endpoint-specific extractors
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<Self> {
+        let mut query = Self::default();
+        for (key, value) in pairs {
+            match key {
+                "page" => {
+                    query.page = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|page| *page >= 1)
+                        .ok_or_else(|| bad_request("page must be a positive integer (>= 1)"))?;
+                }
+                "per_page" | "limit" => {
+                    let per_page = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|per_page| *per_page >= 1)
+                        .ok_or_else(|| bad_request("per_page must be a positive integer (>= 1)"))?;
+                    query.per_page = per_page.min(MAX_PER_PAGE);
+                }
+                "search" => {
+                    let search = value.trim();
+                    query.search = (!search.is_empty()).then(|| search.to_string());
+                }
+                "sort" => query.sort = Self::parse_sort(value)?,
+                "fields" => query.fields = Some(Self::parse_fields(value)?),
+                _ if key.starts_with("filter[") => {
+                    query.filters.push(Self::parse_filter(key, value)?)
+                }
+                _ => {}
+            }
+        }
+        if query.filters.len() > MAX_FILTERS {
+            return Err(bad_request(format!(
+                "At most {} filters are allowed",
+                MAX_FILTERS
+            )));
+        }
+        Ok(query)
+    }
+
+    /// Parse a raw (percent-encoded) query string
+    pub fn from_query_string(raw: &str) -> Result<Self> {
+        let pairs: Vec<(String, String)> = url::form_urlencoded::parse(raw.as_bytes())
+            .into_owned()
+            .collect();
+        Self::from_pairs(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+    }
+
+    fn parse_filter(key: &str, value: &str) -> Result<FilterClause> {
+        let invalid = || bad_request(format!("Invalid filter parameter '{}'", key));
+        let inner = key
+            .strip_prefix("filter[")
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(invalid)?;
+        let (field, op) = match inner.split_once("][") {
+            Some((field, op)) => (field, FilterOp::parse(op).ok_or_else(invalid)?),
+            None => (inner, FilterOp::Eq),
+        };
+        if !is_field_name(field) {
+            return Err(invalid());
+        }
+        if op == FilterOp::In && value.split(',').count() > MAX_IN_VALUES {
+            return Err(bad_request(format!(
+                "An 'in' filter accepts at most {} values",
+                MAX_IN_VALUES
+            )));
+        }
+        Ok(FilterClause {
+            field: field.to_string(),
+            op,
+            value: value.to_string(),
+        })
+    }
+
+    fn parse_sort(value: &str) -> Result<Vec<SortKey>> {
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let (field, descending) = match key.strip_prefix('-') {
+                    Some(field) => (field, true),
+                    None => (key.strip_prefix('+').unwrap_or(key), false),
+                };
+                if is_field_name(field) {
+                    Ok(SortKey {
+                        field: field.to_string(),
+                        descending,
+                    })
+                } else {
+                    Err(bad_request(format!("Invalid sort key '{}'", key)))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if keys.len() > MAX_SORT_KEYS {
+            return Err(bad_request(format!(
+                "At most {} sort keys are allowed",
+                MAX_SORT_KEYS
+            )));
+        }
+        Ok(keys)
+    }
+
+    fn parse_fields(value: &str) -> Result<Vec<String>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                if is_field_name(field) {
+                    Ok(field.to_string())
+                } else {
+                    Err(bad_request(format!("Invalid field '{}'", field)))
+                }
+            })
+            .collect()
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+
+    /// Serialize `items`, keeping only the requested `fields` (and `id`)
+    pub fn select_fields<T: Serialize>(&self, items: Vec<T>) -> Result<Vec<Value>> {
+        items
+            .into_iter()
+            .map(|item| {
+                let mut value =
+                    serde_json::to_value(item).map_err(|e| AppError::Internal(e.into()))?;
+                if let (Some(fields), Value::Object(object)) = (&self.fields, &mut value) {
+                    object.retain(|key, _| key == "id" || fields.iter().any(|f| f == key));
+                }
+                Ok(value)
+            })
+            .collect()
+    }
+}
+
+/// Guard for list endpoints that have not adopted the grammar yet
+#[derive(Debug, Clone, Copy)]
+pub struct ListGrammarUnsupported;
+
+impl ListGrammarUnsupported {
+    /// Reject `filter[...]`, `sort` and `fields` in a raw query string
+    pub fn from_query_string(raw: &str) -> Result<Self> {
+        for (key, _) in url::form_urlencoded::parse(raw.as_bytes()) {
+            if key == "sort" || key == "fields" || key.starts_with("filter[") {
+                return Err(bad_request(format!(
+                    "'{}' is not supported by this list; filter, sort and fields \
+                     are only available on tenant and service lists",
+                    key
+                )));
+            }
+        }
+        Ok(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_full_grammar() {
+        let query = ListQuery::from_query_string(
+            "page=2&limit=500&search=%20acme%20&filter%5Bstatus%5D=active\
+             &filter[created_at][gte]=2026-01-01T00:00:00Z&sort=-created_at,name&fields=name,slug&tenant_id=x",
+        )
+        .unwrap();
+        assert_eq!(query.page, 2);
+        assert_eq!(query.per_page, MAX_PER_PAGE);
+        assert_eq!(query.offset(), MAX_PER_PAGE);
+        assert_eq!(query.search.as_deref(), Some("acme"));
+        assert_eq!(
+            query.filters,
+            vec![
+                FilterClause {
+                    field: "status".to_string(),
+                    op: FilterOp::Eq,
+                    value: "active".to_string(),
+                },
+                FilterClause {
+                    field: "created_at".to_string(),
+                    op: FilterOp::Gte,
+                    value: "2026-01-01T00:00:00Z".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            query.sort,
+            vec![
+                SortKey {
+                    field: "created_at".to_string(),
+                    descending: true,
+                },
+                SortKey {
+                    field: "name".to_string(),
+                    descending: false,
+                },
+            ]
+        );
+        assert_eq!(
+            query.fields,
+            Some(vec!["name".to_string(), "slug".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_parameters() {
+        for raw in [
+            "page=0",
+            "per_page=-1",
+            "filter[status][like]=x",
+            "filter[Status]=x",
+            "filter[status=x",
+            "sort=name;drop",
+            "sort=a,b,c,d",
+            "fields=name,*",
+        ] {
+            assert!(ListQuery::from_query_string(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_grammar_unsupported_rejects_grammar_parameters() {
+        for raw in ["sort=-created_at", "fields=id", "filter%5Bemail%5D=a@b.c"] {
+            assert!(
+                ListGrammarUnsupported::from_query_string(raw).is_err(),
+                "{}",
+                raw
+            );
+        }
+        assert!(ListGrammarUnsupported::from_query_string("page=2&per_page=10&search=x").is_ok());
+    }
+
+    #[test]
+    fn test_select_fields_keeps_id() {
+        let query = ListQuery::from_query_string("fields=name").unwrap();
+        let items = query
+            .select_fields(vec![json!({"id": "1", "name": "Acme", "slug": "acme"})])
+            .unwrap();
+        assert_eq!(items, vec![json!({"id": "1", "name": "Acme"})]);
+
+        let all = ListQuery::default()
+            .select_fields(vec![json!({"id": "1", "slug": "acme"})])
+            .unwrap();
+        assert_eq!(all[0]["slug"], "acme");
+    }
+}
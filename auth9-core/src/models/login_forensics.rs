@@ -0,0 +1,199 @@
+//! Failed sign-in forensic capture models
+//!
+//! Captures are kept in their own store with a short retention so incident
+//! responders can trace an attack without widening access to login events.
+//! The sign-in identifier (usually the email) is only stored as a hash.
+
+use super::analytics::LoginEventType;
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+/// Stage of the sign-in at which the attempt failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginFailureStage {
+    /// No account matches the identifier
+    UnknownUser,
+    AccountLocked,
+    Password,
+    Mfa,
+    Federation,
+}
+
+impl LoginFailureStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnknownUser => "unknown_user",
+            Self::AccountLocked => "account_locked",
+            Self::Password => "password",
+            Self::Mfa => "mfa",
+            Self::Federation => "federation",
+        }
+    }
+
+    /// Stage of a failed login event; `None` for successful events
+    pub fn from_event_type(event_type: &LoginEventType) -> Option<Self> {
+        match event_type {
+            LoginEventType::FailedPassword => Some(Self::Password),
+            LoginEventType::FailedMfa => Some(Self::Mfa),
+            LoginEventType::Locked => Some(Self::AccountLocked),
+            LoginEventType::FederationFailed => Some(Self::Federation),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for LoginFailureStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unknown_user" => Ok(Self::UnknownUser),
+            "account_locked" => Ok(Self::AccountLocked),
+            "password" => Ok(Self::Password),
+            "mfa" => Ok(Self::Mfa),
+            "federation" => Ok(Self::Federation),
+            _ => Err(format!("Unknown login failure stage: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for LoginFailureStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for LoginFailureStage {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for LoginFailureStage {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for LoginFailureStage {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+/// Hash of a sign-in identifier as stored in forensic captures
+pub fn hash_login_identifier(identifier: &str) -> String {
+    hex::encode(Sha256::digest(identifier.trim().to_lowercase().as_bytes()))
+}
+
+/// A captured failed sign-in
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LoginFailureRecord {
+    pub id: StringUuid,
+    pub user_id: Option<StringUuid>,
+    /// SHA-256 of the lowercased sign-in identifier
+    pub identifier_hash: Option<String>,
+    pub tenant_id: Option<StringUuid>,
+    pub client_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub failure_stage: LoginFailureStage,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Context of a failed sign-in handed to the forensic store
+#[derive(Debug, Clone)]
+pub struct LoginFailureCapture {
+    pub stage: LoginFailureStage,
+    pub identifier: Option<String>,
+    pub user_id: Option<StringUuid>,
+    pub tenant_id: Option<StringUuid>,
+    pub client_id: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub failure_reason: Option<String>,
+}
+
+impl LoginFailureCapture {
+    pub fn new(stage: LoginFailureStage) -> Self {
+        Self {
+            stage,
+            identifier: None,
+            user_id: None,
+            tenant_id: None,
+            client_id: None,
+            ip_address: None,
+            user_agent: None,
+            failure_reason: None,
+        }
+    }
+}
+
+/// Filter of `GET /api/v1/security/login-failures`
+#[derive(Debug, Clone, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LoginFailureQuery {
+    /// Client IP of the attempts
+    #[validate(length(max = 45))]
+    pub ip: Option<String>,
+    pub user_id: Option<StringUuid>,
+    /// Sign-in identifier (email); matched by hash
+    #[validate(length(max = 320))]
+    pub identifier: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum captures returned (default 100, max 500)
+    #[validate(range(min = 1, max = 500))]
+    pub limit: Option<i64>,
+    /// Why the captures are looked at; recorded in the audit log
+    #[validate(length(min = 3, max = 500))]
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_round_trip_and_event_mapping() {
+        for stage in [
+            LoginFailureStage::UnknownUser,
+            LoginFailureStage::AccountLocked,
+            LoginFailureStage::Password,
+            LoginFailureStage::Mfa,
+            LoginFailureStage::Federation,
+        ] {
+            assert_eq!(stage.as_str().parse::<LoginFailureStage>(), Ok(stage));
+        }
+        assert_eq!(
+            LoginFailureStage::from_event_type(&LoginEventType::FailedMfa),
+            Some(LoginFailureStage::Mfa)
+        );
+        assert_eq!(
+            LoginFailureStage::from_event_type(&LoginEventType::Success),
+            None
+        );
+    }
+
+    #[test]
+    fn test_identifier_hash_is_normalized() {
+        let hash = hash_login_identifier(" Alice@Example.com ");
+        assert_eq!(hash, hash_login_identifier("alice@example.com"));
+        assert_eq!(hash.len(), 64);
+        assert!(!hash.contains("alice"));
+    }
+}
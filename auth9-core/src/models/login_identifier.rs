@@ -0,0 +1,293 @@
+//! Login identifiers beyond email
+//!
+//! Tenants can let members sign in with a username or an E.164 phone number
+//! as well as their email. A user has at most one identifier of each kind.
+//! Identifiers are unique within a scope: the tenant that assigned them, or
+//! the platform-wide scope shared by every tenant that opts for global
+//! uniqueness. The scope is fixed when the identifier is assigned.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Scope of identifiers unique across all tenants
+pub const GLOBAL_SCOPE: &str = "*";
+
+const USERNAME_MIN_LEN: usize = 3;
+const USERNAME_MAX_LEN: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginIdentifierKind {
+    Username,
+    Phone,
+}
+
+impl LoginIdentifierKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Username => "username",
+            Self::Phone => "phone",
+        }
+    }
+
+    /// OIDC claim carrying the identifier
+    pub fn claim_name(&self) -> &'static str {
+        match self {
+            Self::Username => "preferred_username",
+            Self::Phone => "phone_number",
+        }
+    }
+
+    /// Canonical form of a value of this kind, or why it is invalid
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        match self {
+            Self::Username => normalize_username(value),
+            Self::Phone => normalize_phone(value),
+        }
+    }
+}
+
+impl std::str::FromStr for LoginIdentifierKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "username" => Ok(Self::Username),
+            "phone" => Ok(Self::Phone),
+            _ => Err(format!("Unknown login identifier kind: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for LoginIdentifierKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for LoginIdentifierKind {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for LoginIdentifierKind {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for LoginIdentifierKind {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+/// Lowercase; 3-64 letters, digits, `.`, `_` or `-`, starting with a letter
+/// or digit. `@` is not allowed so usernames never look like emails.
+fn normalize_username(value: &str) -> Result<String, String> {
+    let username = value.trim().to_lowercase();
+    let len = username.chars().count();
+    if !(USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&len) {
+        return Err(format!(
+            "Username must be between {} and {} characters",
+            USERNAME_MIN_LEN, USERNAME_MAX_LEN
+        ));
+    }
+    if !username.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+    {
+        return Err(
+            "Username may only contain letters, digits, '.', '_' and '-' and must start with a letter or digit"
+                .to_string(),
+        );
+    }
+    Ok(username)
+}
+
+/// E.164 after dropping spaces, dashes, dots and parentheses: `+`, a non-zero
+/// country code digit and 7 to 15 digits in total
+fn normalize_phone(value: &str) -> Result<String, String> {
+    let phone: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+    let digits = phone.strip_prefix('+').unwrap_or("");
+    if !(7..=15).contains(&digits.len())
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || digits.starts_with('0')
+    {
+        return Err("Phone number must be in E.164 format, e.g. +14155550100".to_string());
+    }
+    Ok(phone)
+}
+
+/// What a sign-in identifier refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedLoginIdentifier {
+    Email(String),
+    Other(LoginIdentifierKind, String),
+}
+
+impl ParsedLoginIdentifier {
+    /// Emails contain `@`, phone numbers start with `+`, anything else is a
+    /// username. `None` when the value is not valid for its kind.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        if raw.contains('@') {
+            return Some(Self::Email(raw.to_lowercase()));
+        }
+        let kind = if raw.starts_with('+') {
+            LoginIdentifierKind::Phone
+        } else {
+            LoginIdentifierKind::Username
+        };
+        kind.normalize(raw)
+            .ok()
+            .map(|value| Self::Other(kind, value))
+    }
+}
+
+/// How unique a tenant's identifiers are
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginIdentifierUniqueness {
+    /// Unique across all tenants opting for global uniqueness; members can
+    /// sign in without naming the tenant
+    #[default]
+    Global,
+    /// Unique within the tenant only; sign-in needs the tenant when another
+    /// tenant uses the same identifier
+    Tenant,
+}
+
+/// Identifier kinds a tenant's members can sign in with, besides email
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LoginIdentifierSettings {
+    #[serde(default)]
+    pub username: bool,
+    #[serde(default)]
+    pub phone: bool,
+    #[serde(default)]
+    pub uniqueness: LoginIdentifierUniqueness,
+}
+
+impl LoginIdentifierSettings {
+    pub fn allows(&self, kind: LoginIdentifierKind) -> bool {
+        match kind {
+            LoginIdentifierKind::Username => self.username,
+            LoginIdentifierKind::Phone => self.phone,
+        }
+    }
+
+    /// Scope new identifiers of the tenant are unique in
+    pub fn scope_for(&self, tenant_id: StringUuid) -> String {
+        match self.uniqueness {
+            LoginIdentifierUniqueness::Global => GLOBAL_SCOPE.to_string(),
+            LoginIdentifierUniqueness::Tenant => tenant_id.to_string(),
+        }
+    }
+}
+
+/// A user's username or phone number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LoginIdentifier {
+    pub user_id: StringUuid,
+    pub kind: LoginIdentifierKind,
+    /// Normalized value
+    pub value: String,
+    /// Tenant ID the value is unique in, or `*` for global uniqueness
+    pub scope: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LoginIdentifier {
+    /// Whether the tenant may change or remove the identifier: it assigned
+    /// it, or it is in the shared global scope
+    pub fn managed_by(&self, tenant_id: StringUuid) -> bool {
+        self.scope == GLOBAL_SCOPE || self.scope == tenant_id.to_string()
+    }
+}
+
+/// Assign a username or phone number
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetLoginIdentifierInput {
+    pub value: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_username_normalization() {
+        let kind = LoginIdentifierKind::Username;
+        assert_eq!(kind.normalize("  Jane.Doe_42 ").unwrap(), "jane.doe_42");
+        assert!(kind.normalize("ab").is_err());
+        assert!(kind.normalize("_jane").is_err());
+        assert!(kind.normalize("jane@corp").is_err());
+        assert!(kind.normalize("jane doe").is_err());
+        assert!(kind.normalize(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_phone_normalization() {
+        let kind = LoginIdentifierKind::Phone;
+        assert_eq!(kind.normalize("+1 (415) 555-0100").unwrap(), "+14155550100");
+        assert!(kind.normalize("4155550100").is_err());
+        assert!(kind.normalize("+0415550100").is_err());
+        assert!(kind.normalize("+12345").is_err());
+        assert!(kind.normalize("+1234567890123456").is_err());
+    }
+
+    #[test]
+    fn test_parse_login_identifier() {
+        assert_eq!(
+            ParsedLoginIdentifier::parse(" Jane@Example.com "),
+            Some(ParsedLoginIdentifier::Email("jane@example.com".to_string()))
+        );
+        assert_eq!(
+            ParsedLoginIdentifier::parse("+44 20 7946 0958"),
+            Some(ParsedLoginIdentifier::Other(
+                LoginIdentifierKind::Phone,
+                "+442079460958".to_string()
+            ))
+        );
+        assert_eq!(
+            ParsedLoginIdentifier::parse("JDoe"),
+            Some(ParsedLoginIdentifier::Other(
+                LoginIdentifierKind::Username,
+                "jdoe".to_string()
+            ))
+        );
+        assert_eq!(ParsedLoginIdentifier::parse("x"), None);
+    }
+
+    #[test]
+    fn test_settings_scope() {
+        let tenant_id = StringUuid::new_v4();
+        let mut settings = LoginIdentifierSettings {
+            username: true,
+            ..Default::default()
+        };
+        assert!(settings.allows(LoginIdentifierKind::Username));
+        assert!(!settings.allows(LoginIdentifierKind::Phone));
+        assert_eq!(settings.scope_for(tenant_id), GLOBAL_SCOPE);
+        settings.uniqueness = LoginIdentifierUniqueness::Tenant;
+        assert_eq!(settings.scope_for(tenant_id), tenant_id.to_string());
+    }
+}
@@ -0,0 +1,222 @@
+//! Response field masking
+//!
+//! Callers whose roles lack a rule's permission see the rule's field masked
+//! (`j***@acme.com`, `203.0.113.*`) wherever it appears in a JSON response
+//! body. Masking happens on the serialized response, so handlers and models
+//! stay unaware of it.
+
+use crate::error::{AppError, Result};
+use serde_json::Value;
+use std::net::IpAddr;
+
+/// Permission that unmasks the default rules
+pub const PII_READ_PERMISSION: &str = "pii:read";
+
+/// How a field value is masked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskStrategy {
+    /// Keep the first character of the local part and the domain
+    Email,
+    /// Drop the host part of the address
+    Ip,
+    /// Replace the whole value
+    Full,
+}
+
+impl MaskStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Ip => "ip",
+            Self::Full => "full",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "email" => Some(Self::Email),
+            "ip" => Some(Self::Ip),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    /// Masked form of a string value
+    pub fn mask(&self, value: &str) -> String {
+        match self {
+            Self::Email => mask_email(value),
+            Self::Ip => mask_ip(value),
+            Self::Full => "***".to_string(),
+        }
+    }
+}
+
+/// Mask the JSON field `field` unless the caller holds `permission`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskingRule {
+    pub field: String,
+    pub strategy: MaskStrategy,
+    pub permission: String,
+}
+
+impl MaskingRule {
+    pub fn new(field: &str, strategy: MaskStrategy, permission: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            strategy,
+            permission: permission.to_string(),
+        }
+    }
+
+    /// Parse `field=strategy:permission`, e.g. `email=email:pii:read`
+    pub fn parse(entry: &str) -> Result<Self> {
+        let invalid = || {
+            AppError::BadRequest(format!(
+                "Masking rule '{}' must look like field=strategy:permission",
+                entry
+            ))
+        };
+        let (field, rest) = entry.split_once('=').ok_or_else(invalid)?;
+        let (strategy, permission) = rest.split_once(':').ok_or_else(invalid)?;
+        let (field, permission) = (field.trim(), permission.trim());
+        if field.is_empty() || permission.is_empty() {
+            return Err(invalid());
+        }
+        let strategy = MaskStrategy::parse(strategy.trim()).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Unknown masking strategy '{}' (expected email, ip or full)",
+                strategy.trim()
+            ))
+        })?;
+        Ok(Self::new(field, strategy, permission))
+    }
+
+    /// Emails and IP addresses, unmasked by `pii:read`
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("email", MaskStrategy::Email, PII_READ_PERMISSION),
+            Self::new("ip_address", MaskStrategy::Ip, PII_READ_PERMISSION),
+        ]
+    }
+}
+
+/// `john@acme.com` -> `j***@acme.com`
+pub fn mask_email(email: &str) -> String {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            let first: String = local.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+/// `203.0.113.7` -> `203.0.113.*`; IPv6 keeps the first three groups
+pub fn mask_ip(ip: &str) -> String {
+    match ip.trim().parse::<IpAddr>() {
+        Ok(IpAddr::V4(v4)) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{}.{}.{}.*", a, b, c)
+        }
+        Ok(IpAddr::V6(v6)) => {
+            let s = v6.segments();
+            format!("{:x}:{:x}:{:x}:*", s[0], s[1], s[2])
+        }
+        Err(_) => "***".to_string(),
+    }
+}
+
+/// Mask every string value under a key named by one of `rules`, at any
+/// depth. Returns whether anything was masked.
+pub fn apply_masking(value: &mut Value, rules: &[&MaskingRule]) -> bool {
+    let mut masked = false;
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match (rules.iter().find(|r| r.field == *key), &*field) {
+                    (Some(rule), Value::String(s)) => {
+                        *field = Value::String(rule.strategy.mask(s));
+                        masked = true;
+                    }
+                    _ => masked |= apply_masking(field, rules),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                masked |= apply_masking(item, rules);
+            }
+        }
+        _ => {}
+    }
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mask_email() {
+        assert_eq!(mask_email("john@acme.com"), "j***@acme.com");
+        assert_eq!(mask_email("@acme.com"), "***@acme.com");
+        assert_eq!(mask_email("not-an-email"), "***");
+    }
+
+    #[test]
+    fn test_mask_ip() {
+        assert_eq!(mask_ip("203.0.113.7"), "203.0.113.*");
+        assert_eq!(mask_ip("2001:db8:85a3::8a2e:370:7334"), "2001:db8:85a3:*");
+        assert_eq!(mask_ip("unknown"), "***");
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!(
+            MaskingRule::parse("email=email:pii:read").unwrap(),
+            MaskingRule::new("email", MaskStrategy::Email, "pii:read")
+        );
+        assert_eq!(
+            MaskingRule::parse(" phone = full : pii:phone:read ")
+                .unwrap()
+                .permission,
+            "pii:phone:read"
+        );
+        assert!(MaskingRule::parse("email").is_err());
+        assert!(MaskingRule::parse("email=email").is_err());
+        assert!(MaskingRule::parse("email=hash:pii:read").is_err());
+    }
+
+    #[test]
+    fn test_apply_masking_nested() {
+        let rules = MaskingRule::defaults();
+        let rules: Vec<&MaskingRule> = rules.iter().collect();
+        let mut body = json!({
+            "data": [
+                { "email": "john@acme.com", "ip_address": "203.0.113.7", "name": "John" },
+                { "email": null, "user": { "email": "ann@acme.com" } }
+            ],
+            "pagination": { "total": 2 }
+        });
+
+        assert!(apply_masking(&mut body, &rules));
+        assert_eq!(
+            body,
+            json!({
+                "data": [
+                    { "email": "j***@acme.com", "ip_address": "203.0.113.*", "name": "John" },
+                    { "email": null, "user": { "email": "a***@acme.com" } }
+                ],
+                "pagination": { "total": 2 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_masking_without_matches() {
+        let rule = MaskingRule::new("email", MaskStrategy::Email, "pii:read");
+        let mut body = json!({ "data": { "name": "Acme" } });
+        assert!(!apply_masking(&mut body, &[&rule]));
+    }
+}
@@ -0,0 +1,226 @@
+//! MFA enrollment campaigns
+//!
+//! A tenant's campaign (`settings.mfa_enrollment_campaign`) requires members
+//! to enroll a second factor (TOTP or a passkey) by a deadline. Until then
+//! members without MFA are reminded at sign-in; afterwards they can still
+//! sign in and enroll, but cannot get tokens for the tenant.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Most pending members listed by a progress report
+pub const MAX_PROGRESS_ENTRIES: usize = 500;
+
+/// MFA enrollment campaign of a tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct MfaEnrollmentCampaign {
+    /// Remind and, after the deadline, block members without MFA (a
+    /// disabled campaign still reports progress)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Members without MFA cannot get tokens for the tenant after this
+    pub deadline: DateTime<Utc>,
+    /// Members the campaign never applies to
+    #[serde(default)]
+    #[validate(length(max = 1000))]
+    pub exempt_user_ids: Vec<StringUuid>,
+    /// `role_in_tenant` values the campaign never applies to
+    #[serde(default)]
+    pub exempt_roles: Vec<String>,
+}
+
+impl MfaEnrollmentCampaign {
+    pub fn is_exempt(&self, user_id: StringUuid, role_in_tenant: &str) -> bool {
+        self.exempt_user_ids.contains(&user_id)
+            || self.exempt_roles.iter().any(|r| r == role_in_tenant)
+    }
+
+    pub fn status(
+        &self,
+        user_id: StringUuid,
+        role_in_tenant: &str,
+        mfa_enrolled: bool,
+        now: DateTime<Utc>,
+    ) -> MfaEnrollmentStatus {
+        if self.is_exempt(user_id, role_in_tenant) {
+            MfaEnrollmentStatus::Exempt
+        } else if mfa_enrolled {
+            MfaEnrollmentStatus::Enrolled
+        } else if now < self.deadline {
+            MfaEnrollmentStatus::Pending
+        } else {
+            MfaEnrollmentStatus::Overdue
+        }
+    }
+}
+
+/// Where a member stands in a campaign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MfaEnrollmentStatus {
+    Exempt,
+    Enrolled,
+    /// Not enrolled, deadline ahead
+    Pending,
+    /// Not enrolled, deadline passed; token exchange for the tenant is refused
+    Overdue,
+}
+
+/// Campaign requirement of one of the caller's tenants
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaEnrollmentRequirement {
+    pub tenant_id: StringUuid,
+    pub tenant_name: String,
+    pub deadline: DateTime<Utc>,
+    pub status: MfaEnrollmentStatus,
+    /// Whole days left before the deadline (0 once it has passed)
+    pub days_remaining: i64,
+}
+
+impl MfaEnrollmentRequirement {
+    pub fn new(
+        tenant_id: StringUuid,
+        tenant_name: String,
+        deadline: DateTime<Utc>,
+        status: MfaEnrollmentStatus,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            tenant_id,
+            tenant_name,
+            deadline,
+            status,
+            days_remaining: (deadline - now).num_days().max(0),
+        }
+    }
+}
+
+/// Tenant member with their enrollment state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct MfaCampaignMember {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub role_in_tenant: String,
+    /// TOTP enabled or a passkey registered
+    pub mfa_enrolled: bool,
+}
+
+/// Number of members per enrollment status
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct MfaCampaignSummary {
+    pub total: u64,
+    pub enrolled: u64,
+    /// Not enrolled yet (overdue ones included once the deadline passed)
+    pub not_enrolled: u64,
+    pub exempt: u64,
+}
+
+impl MfaCampaignSummary {
+    /// Enrolled share of the members the campaign applies to, in percent
+    pub fn completion_percent(&self) -> f64 {
+        let applicable = self.enrolled + self.not_enrolled;
+        if applicable == 0 {
+            100.0
+        } else {
+            self.enrolled as f64 * 100.0 / applicable as f64
+        }
+    }
+}
+
+/// Campaign progress for tenant admins
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MfaCampaignProgress {
+    pub campaign: MfaEnrollmentCampaign,
+    pub as_of: DateTime<Utc>,
+    pub deadline_passed: bool,
+    pub summary: MfaCampaignSummary,
+    pub completion_percent: f64,
+    /// Members still to enroll (at most [`MAX_PROGRESS_ENTRIES`], see
+    /// `truncated`)
+    pub not_enrolled: Vec<MfaCampaignMember>,
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn campaign(deadline: DateTime<Utc>) -> MfaEnrollmentCampaign {
+        MfaEnrollmentCampaign {
+            enabled: true,
+            deadline,
+            exempt_user_ids: vec![],
+            exempt_roles: vec!["service".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let now = Utc::now();
+        let user_id = StringUuid::new_v4();
+        let before = campaign(now + Duration::days(3));
+        let after = campaign(now - Duration::seconds(1));
+
+        assert_eq!(
+            before.status(user_id, "member", false, now),
+            MfaEnrollmentStatus::Pending
+        );
+        assert_eq!(
+            after.status(user_id, "member", false, now),
+            MfaEnrollmentStatus::Overdue
+        );
+        assert_eq!(
+            after.status(user_id, "member", true, now),
+            MfaEnrollmentStatus::Enrolled
+        );
+        assert_eq!(
+            after.status(user_id, "service", false, now),
+            MfaEnrollmentStatus::Exempt
+        );
+
+        let mut exempt_user = after.clone();
+        exempt_user.exempt_user_ids.push(user_id);
+        assert_eq!(
+            exempt_user.status(user_id, "member", false, now),
+            MfaEnrollmentStatus::Exempt
+        );
+    }
+
+    #[test]
+    fn test_days_remaining() {
+        let now = Utc::now();
+        let requirement = MfaEnrollmentRequirement::new(
+            StringUuid::new_v4(),
+            "Acme".to_string(),
+            now + Duration::days(5) + Duration::hours(1),
+            MfaEnrollmentStatus::Pending,
+            now,
+        );
+        assert_eq!(requirement.days_remaining, 5);
+
+        let overdue = MfaEnrollmentRequirement::new(
+            StringUuid::new_v4(),
+            "Acme".to_string(),
+            now - Duration::days(2),
+            MfaEnrollmentStatus::Overdue,
+            now,
+        );
+        assert_eq!(overdue.days_remaining, 0);
+    }
+
+    #[test]
+    fn test_completion_percent() {
+        let summary = MfaCampaignSummary {
+            total: 10,
+            enrolled: 3,
+            not_enrolled: 1,
+            exempt: 6,
+        };
+        assert_eq!(summary.completion_percent(), 75.0);
+        assert_eq!(MfaCampaignSummary::default().completion_percent(), 100.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! MFA reset models
+//!
+//! A user who lost their second factor asks a tenant admin to reset it
+//! instead of having an admin disable MFA out-of-band. The request carries
+//! the user's identity proof; approval opens a time-boxed window in which
+//! the user clears their factors and enrolls new ones.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// How long a request waits for a tenant admin, in hours
+pub const MFA_RESET_REQUEST_TTL_HOURS: i64 = 72;
+
+/// How long the reset window stays open after approval, in hours
+pub const MFA_RESET_WINDOW_HOURS: i64 = 24;
+
+/// Lifecycle of an MFA reset request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MfaResetStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Completed,
+}
+
+impl MfaResetStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Completed => "completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "approved" => Some(Self::Approved),
+            "rejected" => Some(Self::Rejected),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// MFA reset request
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MfaResetRequest {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub user_id: StringUuid,
+    /// One of `pending`, `approved`, `rejected`, `completed`
+    pub status: String,
+    /// How the reviewing admin can verify the requester
+    pub identity_proof: String,
+    pub requester_ip: Option<String>,
+    pub reviewed_by: Option<StringUuid>,
+    pub review_comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Deadline for the admin decision
+    pub expires_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// End of the reset window opened by the approval
+    pub window_expires_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl MfaResetRequest {
+    pub fn reset_status(&self) -> Option<MfaResetStatus> {
+        MfaResetStatus::parse(&self.status)
+    }
+
+    /// Pending past its decision deadline
+    pub fn is_expired(&self) -> bool {
+        self.reset_status() == Some(MfaResetStatus::Pending) && self.expires_at <= Utc::now()
+    }
+
+    /// Approved and still inside the reset window
+    pub fn window_open(&self) -> bool {
+        self.reset_status() == Some(MfaResetStatus::Approved)
+            && self.window_expires_at.is_some_and(|end| end > Utc::now())
+    }
+}
+
+/// Ask a tenant admin to reset the caller's MFA
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateMfaResetRequestInput {
+    /// MFA session token from the password login step; proves the password
+    #[validate(length(min = 1, max = 512))]
+    pub mfa_session_token: String,
+    /// Tenant whose admins review the request
+    pub tenant_id: Uuid,
+    /// How an admin can verify the requester (employee ID, callback number,
+    /// ticket reference, ...)
+    #[validate(length(min = 1, max = 2000))]
+    pub identity_proof: String,
+}
+
+/// Clear the caller's factors inside an approved reset window
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CompleteMfaResetInput {
+    /// MFA session token from a fresh password login
+    #[validate(length(min = 1, max = 512))]
+    pub mfa_session_token: String,
+}
+
+/// Tenant admin decision on an MFA reset request
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct MfaResetDecisionInput {
+    #[validate(length(max = 1000))]
+    pub comment: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn request(status: MfaResetStatus) -> MfaResetRequest {
+        MfaResetRequest {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            user_id: StringUuid::new_v4(),
+            status: status.as_str().to_string(),
+            identity_proof: "Employee #42".to_string(),
+            requester_ip: None,
+            reviewed_by: None,
+            review_comment: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now() - Duration::minutes(1),
+            reviewed_at: None,
+            window_expires_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_status_roundtrip() {
+        for status in [
+            MfaResetStatus::Pending,
+            MfaResetStatus::Approved,
+            MfaResetStatus::Rejected,
+            MfaResetStatus::Completed,
+        ] {
+            assert_eq!(MfaResetStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(MfaResetStatus::parse("expired"), None);
+    }
+
+    #[test]
+    fn test_expiry_and_window() {
+        assert!(request(MfaResetStatus::Pending).is_expired());
+        assert!(!request(MfaResetStatus::Rejected).is_expired());
+
+        let mut approved = request(MfaResetStatus::Approved);
+        assert!(!approved.window_open());
+        approved.window_expires_at = Some(Utc::now() + Duration::hours(1));
+        assert!(approved.window_open());
+        approved.window_expires_at = Some(Utc::now() - Duration::seconds(1));
+        assert!(!approved.window_open());
+    }
+}
@@ -1,25 +1,70 @@
 //! Shared data models and value objects used across bounded contexts.
 
 pub mod abac;
+pub mod account_deletion;
+pub mod account_recovery;
 pub mod action;
+pub mod admin_scope;
+pub mod admin_unit;
 pub mod analytics;
+pub mod attribute_release;
+pub mod audit_integrity;
+pub mod audit_search;
+pub mod audit_share;
+pub mod audit_state;
+pub mod billing;
 pub mod branding;
+pub mod cardinality;
+pub mod claims_enrichment;
+pub mod client_registration;
 pub mod common;
+pub mod conditional_access;
+pub mod custom_domain;
+pub mod effective_settings;
 pub mod email;
+pub mod email_queue;
 pub mod email_template;
 pub mod enterprise_sso;
+pub mod expand;
+pub mod external_authz;
 pub mod identity_provider;
+pub mod inactivity;
 pub mod invitation;
+pub mod invitation_link;
+pub mod job;
 pub mod ldap;
+pub mod ldap_sync;
 pub mod linked_identity;
+pub mod list_query;
+pub mod login_forensics;
+pub mod login_identifier;
+pub mod masking;
+pub mod mfa_enrollment;
+pub mod mfa_reset;
+pub mod notification_preference;
+pub mod oauth_scope;
 pub mod password;
+pub mod password_breach;
+pub mod password_deny_list;
+pub mod permission_snapshot;
+pub mod progressive_profiling;
+pub mod rate_limit;
 pub mod rbac;
+pub mod role_preset;
+pub mod saga;
 pub mod saml_application;
 pub mod scim;
+pub mod security_digest;
 pub mod service;
 pub mod session;
+pub mod session_revocation;
 pub mod social_provider;
 pub mod system_settings;
 pub mod tenant;
+pub mod tenant_key;
+pub mod token_validation;
 pub mod user;
 pub mod webauthn;
+pub mod webhook_bulk;
+pub mod webhook_delivery;
+pub mod workload_identity;
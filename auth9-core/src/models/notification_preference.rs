@@ -0,0 +1,316 @@
+//! User notification preference models
+
+use super::common::StringUuid;
+use super::session::Session;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// How often a user receives the account activity digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFrequency {
+    #[default]
+    None,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    /// Interval between two digests (none for disabled digests)
+    pub fn period(&self) -> Option<Duration> {
+        match self {
+            Self::None => None,
+            Self::Daily => Some(Duration::days(1)),
+            Self::Weekly => Some(Duration::weeks(1)),
+        }
+    }
+}
+
+impl std::str::FromStr for DigestFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            _ => Err(format!("Unknown digest frequency: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for DigestFrequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Daily => write!(f, "daily"),
+            Self::Weekly => write!(f, "weekly"),
+        }
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for DigestFrequency {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for DigestFrequency {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for DigestFrequency {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        let s = self.to_string();
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&s.as_str(), buf)
+    }
+}
+
+/// Notification categories a user can opt out of (also used in unsubscribe links)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    SecurityAlerts,
+    LoginNotifications,
+    Digest,
+}
+
+impl NotificationCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SecurityAlerts => "security_alerts",
+            Self::LoginNotifications => "login_notifications",
+            Self::Digest => "digest",
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "security_alerts" => Ok(Self::SecurityAlerts),
+            "login_notifications" => Ok(Self::LoginNotifications),
+            "digest" => Ok(Self::Digest),
+            _ => Err(format!("Unknown notification category: {}", s)),
+        }
+    }
+}
+
+/// Per-user notification preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct NotificationPreferences {
+    pub user_id: StringUuid,
+    /// Emails for security alerts (new device, impossible travel, brute force)
+    pub security_alerts: bool,
+    /// Emails for every new sign-in session, unless all of the user's
+    /// tenants turned them off
+    pub login_notifications: bool,
+    pub digest_frequency: DigestFrequency,
+    pub last_digest_sent_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl NotificationPreferences {
+    /// Preferences of users who never changed them: security alerts and
+    /// login notifications on, digest opt-in
+    pub fn defaults(user_id: StringUuid) -> Self {
+        Self {
+            user_id,
+            security_alerts: true,
+            login_notifications: true,
+            digest_frequency: DigestFrequency::default(),
+            last_digest_sent_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn allows(&self, category: NotificationCategory) -> bool {
+        match category {
+            NotificationCategory::SecurityAlerts => self.security_alerts,
+            NotificationCategory::LoginNotifications => self.login_notifications,
+            NotificationCategory::Digest => self.digest_frequency != DigestFrequency::None,
+        }
+    }
+
+    /// Turn off a single category (unsubscribe link)
+    pub fn disable(&mut self, category: NotificationCategory) {
+        match category {
+            NotificationCategory::SecurityAlerts => self.security_alerts = false,
+            NotificationCategory::LoginNotifications => self.login_notifications = false,
+            NotificationCategory::Digest => self.digest_frequency = DigestFrequency::None,
+        }
+    }
+
+    pub fn apply(&mut self, input: UpdateNotificationPreferencesInput) {
+        if let Some(security_alerts) = input.security_alerts {
+            self.security_alerts = security_alerts;
+        }
+        if let Some(login_notifications) = input.login_notifications {
+            self.login_notifications = login_notifications;
+        }
+        if let Some(digest_frequency) = input.digest_frequency {
+            self.digest_frequency = digest_frequency;
+        }
+    }
+}
+
+/// Partial update of notification preferences
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesInput {
+    pub security_alerts: Option<bool>,
+    pub login_notifications: Option<bool>,
+    pub digest_frequency: Option<DigestFrequency>,
+}
+
+/// User due for an activity digest
+#[derive(Debug, Clone, FromRow)]
+pub struct DigestRecipient {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    #[sqlx(default)]
+    pub locale: Option<String>,
+    pub digest_frequency: DigestFrequency,
+    pub last_digest_sent_at: Option<DateTime<Utc>>,
+}
+
+/// User to email about a new sign-in
+#[derive(Debug, Clone, FromRow)]
+pub struct SignInRecipient {
+    pub user_id: StringUuid,
+    pub email: String,
+    pub display_name: Option<String>,
+    #[sqlx(default)]
+    pub locale: Option<String>,
+    /// Stored preference (none: the default applies)
+    pub login_notifications: Option<bool>,
+    /// Tenants the user belongs to
+    pub tenant_count: i64,
+    /// Of those, tenants that turned sign-in notifications off
+    pub tenants_opted_out: i64,
+}
+
+impl SignInRecipient {
+    /// Whether the user wants sign-in emails and at least one of their
+    /// tenants (or none, for users outside any tenant) allows them
+    pub fn wants_sign_in_email(&self) -> bool {
+        let user = self
+            .login_notifications
+            .unwrap_or_else(|| NotificationPreferences::defaults(self.user_id).login_notifications);
+        user && (self.tenant_count == 0 || self.tenants_opted_out < self.tenant_count)
+    }
+}
+
+/// A sign-in to tell the user about
+#[derive(Debug, Clone)]
+pub struct SignInNotice {
+    pub user_id: StringUuid,
+    /// Session the sign-in created; the "this wasn't me" link revokes it
+    pub session_id: Option<StringUuid>,
+    /// e.g. "Chrome on macOS (desktop)"
+    pub device: String,
+    /// Approximate location resolved from the IP address
+    pub location: Option<String>,
+    pub ip_address: Option<String>,
+    pub signed_in_at: DateTime<Utc>,
+}
+
+impl From<&Session> for SignInNotice {
+    fn from(session: &Session) -> Self {
+        Self {
+            user_id: session.user_id,
+            session_id: Some(session.id),
+            device: session.device_summary(),
+            location: session.location.clone(),
+            ip_address: session.ip_address.clone(),
+            signed_in_at: session.created_at,
+        }
+    }
+}
+
+/// Account activity since the previous digest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityDigest {
+    pub successful_logins: i64,
+    pub failed_logins: i64,
+    pub security_alerts: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_enable_alerts_and_sign_ins() {
+        let prefs = NotificationPreferences::defaults(StringUuid::new_v4());
+        assert!(prefs.allows(NotificationCategory::SecurityAlerts));
+        assert!(prefs.allows(NotificationCategory::LoginNotifications));
+        assert!(!prefs.allows(NotificationCategory::Digest));
+    }
+
+    #[test]
+    fn test_disable_and_apply() {
+        let mut prefs = NotificationPreferences {
+            digest_frequency: DigestFrequency::Weekly,
+            ..NotificationPreferences::defaults(StringUuid::new_v4())
+        };
+        prefs.disable(NotificationCategory::Digest);
+        assert_eq!(prefs.digest_frequency, DigestFrequency::None);
+
+        prefs.apply(UpdateNotificationPreferencesInput {
+            security_alerts: Some(false),
+            digest_frequency: Some(DigestFrequency::Daily),
+            ..Default::default()
+        });
+        assert!(!prefs.security_alerts);
+        assert!(prefs.login_notifications);
+        assert_eq!(prefs.digest_frequency, DigestFrequency::Daily);
+    }
+
+    #[test]
+    fn test_sign_in_email_needs_one_tenant_allowing_it() {
+        let recipient = |login_notifications, tenant_count, tenants_opted_out| SignInRecipient {
+            user_id: StringUuid::new_v4(),
+            email: "user@example.com".to_string(),
+            display_name: None,
+            locale: None,
+            login_notifications,
+            tenant_count,
+            tenants_opted_out,
+        };
+        assert!(recipient(None, 0, 0).wants_sign_in_email());
+        assert!(recipient(Some(true), 2, 1).wants_sign_in_email());
+        assert!(!recipient(Some(true), 2, 2).wants_sign_in_email());
+        assert!(!recipient(Some(false), 1, 0).wants_sign_in_email());
+    }
+
+    #[test]
+    fn test_digest_frequency_round_trip() {
+        for frequency in [
+            DigestFrequency::None,
+            DigestFrequency::Daily,
+            DigestFrequency::Weekly,
+        ] {
+            assert_eq!(
+                frequency.to_string().parse::<DigestFrequency>().unwrap(),
+                frequency
+            );
+        }
+        assert!(DigestFrequency::None.period().is_none());
+        assert_eq!(DigestFrequency::Daily.period(), Some(Duration::days(1)));
+    }
+}
@@ -0,0 +1,371 @@
+//! OAuth scope catalog models
+
+use super::common::StringUuid;
+use super::rbac::{permission_matches, validate_permission_code};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::{BTreeMap, BTreeSet};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Standard OIDC scopes every client may request, with their consent text
+pub const STANDARD_SCOPES: &[(&str, &str)] = &[
+    ("openid", "Sign you in with your account"),
+    ("profile", "View your name and basic profile"),
+    ("email", "View your email address"),
+    ("phone", "View your phone number"),
+    ("address", "View your postal address"),
+];
+
+/// Claims each standard scope releases in ID tokens and userinfo (OIDC Core 5.4)
+pub const STANDARD_SCOPE_CLAIMS: &[(&str, &[&str])] = &[
+    (
+        "profile",
+        &[
+            "name",
+            "family_name",
+            "given_name",
+            "middle_name",
+            "nickname",
+            "preferred_username",
+            "profile",
+            "picture",
+            "website",
+            "gender",
+            "birthdate",
+            "zoneinfo",
+            "locale",
+            "updated_at",
+        ],
+    ),
+    ("email", &["email", "email_verified"]),
+    ("phone", &["phone_number", "phone_number_verified"]),
+    ("address", &["address"]),
+];
+
+pub fn is_standard_scope(name: &str) -> bool {
+    STANDARD_SCOPES.iter().any(|(scope, _)| *scope == name)
+}
+
+/// A scope declared by a service
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ServiceScope {
+    pub id: StringUuid,
+    pub service_id: StringUuid,
+    pub name: String,
+    /// Shown to users on the consent screen
+    pub description: String,
+    /// Permissions a user must hold in the service's tenant to be granted the scope
+    #[sqlx(json)]
+    pub required_permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for declaring a scope or updating its declaration
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct UpsertServiceScopeInput {
+    #[validate(length(min = 1, max = 512))]
+    pub description: String,
+    #[serde(default)]
+    #[validate(length(max = 32), custom(function = "validate_required_permissions"))]
+    pub required_permissions: Vec<String>,
+}
+
+fn validate_required_permissions(codes: &[String]) -> Result<(), validator::ValidationError> {
+    codes
+        .iter()
+        .try_for_each(|code| validate_permission_code(code))
+}
+
+/// Validate a custom scope name: 1-128 characters from `[A-Za-z0-9:._/-]`,
+/// not clashing with a standard OIDC scope
+pub fn validate_scope_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 128 {
+        return Err("Scope name must be 1-128 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '.' | '_' | '/' | '-'))
+    {
+        return Err(
+            "Scope name may only contain letters, digits and the characters : . _ / -".to_string(),
+        );
+    }
+    if is_standard_scope(name) {
+        return Err(format!("'{}' is a standard OIDC scope", name));
+    }
+    Ok(())
+}
+
+/// One line of the consent screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScopeConsentItem {
+    pub name: String,
+    pub description: String,
+    /// False for standard OIDC scopes
+    pub custom: bool,
+}
+
+/// What the consent screen shows for a pending authorization request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConsentScreen {
+    pub client_id: String,
+    pub service_name: String,
+    pub scopes: Vec<ScopeConsentItem>,
+}
+
+/// Validate a space-separated scope request against the standard scopes and
+/// a service's catalog. Returns the normalized scope string (deduplicated,
+/// request order kept). `openid` is required and undeclared scopes are
+/// rejected.
+pub fn resolve_requested_scopes(
+    requested: &str,
+    catalog: &[ServiceScope],
+) -> Result<String, String> {
+    let mut scopes: Vec<&str> = Vec::new();
+    let mut undeclared: Vec<&str> = Vec::new();
+    for scope in requested.split_whitespace() {
+        if scopes.contains(&scope) || undeclared.contains(&scope) {
+            continue;
+        }
+        if is_standard_scope(scope) || catalog.iter().any(|s| s.name == scope) {
+            scopes.push(scope);
+        } else {
+            undeclared.push(scope);
+        }
+    }
+
+    if !undeclared.is_empty() {
+        return Err(format!(
+            "invalid_scope: scope(s) not declared by this service: {}",
+            undeclared.join(", ")
+        ));
+    }
+    if !scopes.contains(&"openid") {
+        return Err("scope must include 'openid'".to_string());
+    }
+    Ok(scopes.join(" "))
+}
+
+/// Whether a scope string names anything beyond the standard scopes
+pub fn has_custom_scopes(scope: &str) -> bool {
+    scope.split_whitespace().any(|s| !is_standard_scope(s))
+}
+
+/// Consent lines for a resolved scope string, with texts from the catalog
+pub fn consent_items(scope: &str, catalog: &[ServiceScope]) -> Vec<ScopeConsentItem> {
+    scope
+        .split_whitespace()
+        .filter_map(|name| {
+            if let Some((_, text)) = STANDARD_SCOPES.iter().find(|(s, _)| *s == name) {
+                return Some(ScopeConsentItem {
+                    name: name.to_string(),
+                    description: text.to_string(),
+                    custom: false,
+                });
+            }
+            catalog
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| ScopeConsentItem {
+                    name: s.name.clone(),
+                    description: s.description.clone(),
+                    custom: true,
+                })
+        })
+        .collect()
+}
+
+/// Drop catalog scopes whose required permissions the user does not hold
+pub fn grantable_scopes(scope: &str, catalog: &[ServiceScope], granted: &[String]) -> String {
+    scope
+        .split_whitespace()
+        .filter(|name| match catalog.iter().find(|s| s.name == *name) {
+            Some(entry) => entry.required_permissions.iter().all(|required| {
+                granted
+                    .iter()
+                    .any(|have| permission_matches(have, required))
+            }),
+            None => true,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether a claim is released by one of the standard scopes
+pub fn is_standard_claim(name: &str) -> bool {
+    STANDARD_SCOPE_CLAIMS
+        .iter()
+        .any(|(_, claims)| claims.contains(&name))
+}
+
+/// Names of the claims a granted scope string releases. `custom` maps a
+/// tenant's custom scopes to the profile attributes they release.
+pub fn released_claims(scope: &str, custom: &BTreeMap<String, Vec<String>>) -> BTreeSet<String> {
+    let mut claims = BTreeSet::new();
+    for name in scope.split_whitespace() {
+        if let Some((_, standard)) = STANDARD_SCOPE_CLAIMS.iter().find(|(s, _)| *s == name) {
+            claims.extend(standard.iter().map(|c| c.to_string()));
+        } else if let Some(attributes) = custom.get(name) {
+            claims.extend(attributes.iter().cloned());
+        }
+    }
+    claims
+}
+
+/// Keep only the claims covered by the granted scopes
+pub fn filter_claims_by_scope(
+    claims: serde_json::Map<String, serde_json::Value>,
+    scope: &str,
+    custom: &BTreeMap<String, Vec<String>>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let released = released_claims(scope, custom);
+    claims
+        .into_iter()
+        .filter(|(name, _)| released.contains(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(name: &str, required: &[&str]) -> ServiceScope {
+        ServiceScope {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            name: name.to_string(),
+            description: format!("Access {}", name),
+            required_permissions: required.iter().map(|s| s.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_accepts_standard_and_declared_scopes() {
+        let catalog = vec![scope("invoices:read", &[])];
+        assert_eq!(
+            resolve_requested_scopes("openid invoices:read openid email", &catalog).unwrap(),
+            "openid invoices:read email"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rejects_undeclared_scopes() {
+        let err = resolve_requested_scopes("openid admin root", &[]).unwrap_err();
+        assert!(err.starts_with("invalid_scope"));
+        assert!(err.contains("admin, root"));
+    }
+
+    #[test]
+    fn test_resolve_requires_openid() {
+        assert!(resolve_requested_scopes("profile email", &[]).is_err());
+        assert!(resolve_requested_scopes("", &[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_scope_name() {
+        assert!(validate_scope_name("invoices:read").is_ok());
+        assert!(validate_scope_name("api/v2.write").is_ok());
+        assert!(validate_scope_name("").is_err());
+        assert!(validate_scope_name("has space").is_err());
+        assert!(validate_scope_name("profile").is_err());
+        assert!(validate_scope_name(&"a".repeat(129)).is_err());
+    }
+
+    #[test]
+    fn test_upsert_input_validates_permission_codes() {
+        let valid = UpsertServiceScopeInput {
+            description: "Read invoices".to_string(),
+            required_permissions: vec!["billing:invoices:read".to_string()],
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = UpsertServiceScopeInput {
+            description: "Read invoices".to_string(),
+            required_permissions: vec!["Not A Code".to_string()],
+        };
+        assert!(invalid.validate().is_err());
+
+        let empty = UpsertServiceScopeInput::default();
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_consent_items_use_catalog_text() {
+        let catalog = vec![scope("invoices:read", &[])];
+        let items = consent_items("openid invoices:read", &catalog);
+        assert_eq!(items.len(), 2);
+        assert!(!items[0].custom);
+        assert_eq!(items[1].description, "Access invoices:read");
+        assert!(items[1].custom);
+    }
+
+    #[test]
+    fn test_grantable_scopes_checks_required_permissions() {
+        let catalog = vec![
+            scope("invoices:read", &["billing:invoices:read"]),
+            scope("invoices:write", &["billing:invoices:write"]),
+        ];
+        let granted = vec!["billing:invoices:read".to_string()];
+        assert_eq!(
+            grantable_scopes("openid invoices:read invoices:write", &catalog, &granted),
+            "openid invoices:read"
+        );
+
+        let wildcard = vec!["billing:invoices:*".to_string()];
+        assert_eq!(
+            grantable_scopes("openid invoices:read invoices:write", &catalog, &wildcard),
+            "openid invoices:read invoices:write"
+        );
+    }
+
+    #[test]
+    fn test_filter_claims_by_standard_scopes() {
+        let claims = serde_json::json!({
+            "name": "Jane",
+            "email": "jane@example.com",
+            "phone_number": "+1 555 0100",
+            "address": { "formatted": "1 Main St" },
+        });
+        let claims = claims.as_object().unwrap().clone();
+        let none = BTreeMap::new();
+
+        let filtered = filter_claims_by_scope(claims.clone(), "openid", &none);
+        assert!(filtered.is_empty());
+
+        let filtered = filter_claims_by_scope(claims.clone(), "openid email", &none);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered["email"], "jane@example.com");
+
+        let filtered = filter_claims_by_scope(claims, "openid profile phone address", &none);
+        assert!(filtered.contains_key("name"));
+        assert!(filtered.contains_key("phone_number"));
+        assert!(filtered.contains_key("address"));
+        assert!(!filtered.contains_key("email"));
+    }
+
+    #[test]
+    fn test_filter_claims_by_custom_scope() {
+        let claims = serde_json::json!({ "company_name": "Acme", "vat_id": "DE1" });
+        let claims = claims.as_object().unwrap().clone();
+        let mut custom = BTreeMap::new();
+        custom.insert("billing:profile".to_string(), vec!["vat_id".to_string()]);
+
+        let filtered = filter_claims_by_scope(claims.clone(), "openid billing:profile", &custom);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered["vat_id"], "DE1");
+
+        // A custom mapping only applies when its scope was granted
+        assert!(filter_claims_by_scope(claims, "openid profile", &custom).is_empty());
+    }
+
+    #[test]
+    fn test_has_custom_scopes() {
+        assert!(!has_custom_scopes("openid profile email phone address"));
+        assert!(has_custom_scopes("openid invoices:read"));
+    }
+}
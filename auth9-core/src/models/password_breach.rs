@@ -0,0 +1,247 @@
+//! Password breach campaign models
+//!
+//! A breach campaign is a `breach_campaign` job that checks a cohort of tenant
+//! members against the breached password corpus. Every account checked gets a
+//! `PasswordBreachCheck` row; together they form the campaign report.
+
+use super::common::StringUuid;
+use super::user::UserCohort;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// What a campaign does with accounts found to use a breached password
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreachCampaignAction {
+    /// Only record the accounts in the report
+    #[default]
+    ReportOnly,
+    /// Email the users a security alert asking them to change their password
+    Notify,
+    /// Require a password change at next login and email a reset link
+    ForceReset,
+}
+
+impl BreachCampaignAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReportOnly => "report_only",
+            Self::Notify => "notify",
+            Self::ForceReset => "force_reset",
+        }
+    }
+}
+
+/// Password breach campaign request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BreachCampaignInput {
+    /// Tenant members to check
+    pub cohort: UserCohort,
+    /// Action for accounts found breached (default `report_only`)
+    #[serde(default)]
+    pub action: BreachCampaignAction,
+    /// Check passwords that cannot be checked offline at the user's next
+    /// password login (default true)
+    #[serde(default = "default_check_on_next_login")]
+    pub check_on_next_login: bool,
+    /// With `force_reset`, also revoke the users' active sessions (default false)
+    #[serde(default)]
+    pub revoke_sessions: bool,
+}
+
+fn default_check_on_next_login() -> bool {
+    true
+}
+
+/// Outcome of a breach check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordBreachStatus {
+    /// Waiting for the user's next password login
+    Pending,
+    Breached,
+    Clean,
+}
+
+impl PasswordBreachStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Breached => "breached",
+            Self::Clean => "clean",
+        }
+    }
+}
+
+/// Where a breach check was performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordBreachSource {
+    /// From the stored hash while the campaign ran
+    Offline,
+    /// From the password entered at login
+    Login,
+}
+
+impl PasswordBreachSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Offline => "offline",
+            Self::Login => "login",
+        }
+    }
+}
+
+/// Breach check of one account in a campaign
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PasswordBreachCheck {
+    pub id: StringUuid,
+    pub job_id: StringUuid,
+    pub tenant_id: StringUuid,
+    pub user_id: StringUuid,
+    pub email: String,
+    /// One of `pending`, `breached`, `clean`
+    pub status: String,
+    /// `offline` or `login`
+    pub source: String,
+    /// Times the password appears in the breach corpus
+    pub breach_count: i64,
+    /// Campaign action applied to a breached account
+    pub action_taken: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl PasswordBreachCheck {
+    pub fn new(
+        job_id: StringUuid,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        email: &str,
+        status: PasswordBreachStatus,
+        breach_count: u64,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: StringUuid::new_v4(),
+            job_id,
+            tenant_id,
+            user_id,
+            email: email.to_string(),
+            status: status.as_str().to_string(),
+            source: PasswordBreachSource::Offline.as_str().to_string(),
+            breach_count: i64::try_from(breach_count).unwrap_or(i64::MAX),
+            action_taken: None,
+            created_at: now,
+            checked_at: (status != PasswordBreachStatus::Pending).then_some(now),
+        }
+    }
+}
+
+/// Campaign report: counts per outcome and the affected accounts
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BreachCampaignReport {
+    pub job_id: StringUuid,
+    pub checked: usize,
+    pub pending: usize,
+    pub breached: usize,
+    pub clean: usize,
+    /// Accounts whose password was found in the breach corpus
+    pub affected: Vec<PasswordBreachCheck>,
+}
+
+impl BreachCampaignReport {
+    pub fn from_checks(job_id: StringUuid, checks: Vec<PasswordBreachCheck>) -> Self {
+        let count = |status: PasswordBreachStatus| {
+            checks
+                .iter()
+                .filter(|c| c.status == status.as_str())
+                .count()
+        };
+        let (pending, breached, clean) = (
+            count(PasswordBreachStatus::Pending),
+            count(PasswordBreachStatus::Breached),
+            count(PasswordBreachStatus::Clean),
+        );
+        Self {
+            job_id,
+            checked: checks.len(),
+            pending,
+            breached,
+            clean,
+            affected: checks
+                .into_iter()
+                .filter(|c| c.status == PasswordBreachStatus::Breached.as_str())
+                .collect(),
+        }
+    }
+}
+
+/// Upper-case hex SHA-1 digest of the password, when the stored hash is an
+/// unsalted SHA-1 that can be checked against the breach corpus directly.
+///
+/// Accepts bare hex digests and the LDAP `{SHA}<base64>` form. Salted and
+/// slow hashes (argon2, bcrypt, `{SSHA}`, ...) return `None`; those accounts
+/// can only be checked when the user next enters their password.
+pub fn offline_checkable_sha1(stored_hash: &str) -> Option<String> {
+    let stored_hash = stored_hash.trim();
+    if stored_hash.len() == 40 && stored_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(stored_hash.to_ascii_uppercase());
+    }
+    let encoded = stored_hash.strip_prefix("{SHA}")?;
+    let digest = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    (digest.len() == 20).then(|| hex::encode_upper(digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_checkable_sha1() {
+        // SHA-1("password")
+        let hex = "5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8"; // pragma: allowlist secret
+        let upper = hex.to_ascii_uppercase();
+        assert_eq!(offline_checkable_sha1(hex).as_deref(), Some(upper.as_str()));
+        assert_eq!(
+            offline_checkable_sha1("{SHA}W6ph5Mm5Pz8GgiULbPgzG37mj9g=").as_deref(),
+            Some(upper.as_str())
+        );
+        assert!(offline_checkable_sha1("$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA").is_none());
+        assert!(offline_checkable_sha1("{SSHA}W6ph5Mm5Pz8GgiULbPgzG37mj9hzYWx0").is_none());
+        assert!(offline_checkable_sha1("{SHA}bm90LWEtZGlnZXN0").is_none());
+    }
+
+    #[test]
+    fn test_report_counts_and_affected() {
+        let job_id = StringUuid::new_v4();
+        let tenant_id = StringUuid::new_v4();
+        let check = |status| {
+            PasswordBreachCheck::new(
+                job_id,
+                tenant_id,
+                StringUuid::new_v4(),
+                "a@example.com",
+                status,
+                0,
+            )
+        };
+        let report = BreachCampaignReport::from_checks(
+            job_id,
+            vec![
+                check(PasswordBreachStatus::Breached),
+                check(PasswordBreachStatus::Clean),
+                check(PasswordBreachStatus::Pending),
+                check(PasswordBreachStatus::Pending),
+            ],
+        );
+        assert_eq!(report.checked, 4);
+        assert_eq!((report.pending, report.breached, report.clean), (2, 1, 1));
+        assert_eq!(report.affected.len(), 1);
+        assert!(report.affected[0].checked_at.is_some());
+    }
+}
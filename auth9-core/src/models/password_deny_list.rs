@@ -0,0 +1,338 @@
+//! Password deny lists
+//!
+//! Every password is checked against a built-in list of common passwords and,
+//! for tenant members, against terms the tenant uploaded (company name
+//! variants, product names). A tenant's terms are kept only as a Bloom filter:
+//! storage is about 3.6 bytes per term, a lookup is a handful of bit tests,
+//! and the terms cannot be read back. A false positive rejects a password
+//! that merely hashes like a term, with odds of about one in a million per
+//! lookup.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Shortest accepted term after normalization; shorter terms would match
+/// too many passwords
+pub const MIN_TERM_LENGTH: usize = 4;
+/// Longest accepted term after normalization
+pub const MAX_TERM_LENGTH: usize = 64;
+/// Most terms a tenant can upload
+pub const MAX_TERMS: usize = 10_000;
+
+const FALSE_POSITIVE_RATE: f64 = 1e-6;
+
+/// Common passwords rejected for every tenant. Passwords are compared after
+/// normalization, both whole and with trailing digits and symbols removed, so
+/// `P@ssw0rd2024!` matches `password`.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "passwd",
+    "passphrase",
+    "qwerty",
+    "qwertyuiop",
+    "qwertz",
+    "azerty",
+    "asdfgh",
+    "asdfghjkl",
+    "zxcvbnm",
+    "qazwsx",
+    "1qaz2wsx",
+    "zaq12wsx",
+    "1q2w3e4r",
+    "1q2w3e4r5t",
+    "123456",
+    "1234567",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "0987654321",
+    "987654321",
+    "654321",
+    "111111",
+    "000000",
+    "123123",
+    "121212",
+    "112233",
+    "666666",
+    "abc123",
+    "abcdef",
+    "abcdefg",
+    "letmein",
+    "welcome",
+    "changeme",
+    "default",
+    "login",
+    "admin",
+    "administrator",
+    "secret",
+    "iloveyou",
+    "loveme",
+    "trustno",
+    "monkey",
+    "dragon",
+    "master",
+    "shadow",
+    "sunshine",
+    "princess",
+    "superman",
+    "batman",
+    "starwars",
+    "football",
+    "baseball",
+    "basketball",
+    "soccer",
+    "hockey",
+    "whatever",
+    "freedom",
+    "computer",
+    "internet",
+    "flower",
+    "hello",
+    "summer",
+    "winter",
+    "michael",
+    "jennifer",
+    "jordan",
+    "hunter",
+    "charlie",
+];
+
+/// Lowercase, undo common character substitutions and keep letters and
+/// digits only, so `Acme-Corp` and `@cm3 corp` compare equal
+pub fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '@' | '4' => 'a',
+            '$' | '5' => 's',
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '7' => 't',
+            c => c,
+        })
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Whether the password is one of the built-in common passwords
+pub fn is_common_password(password: &str) -> bool {
+    let base = password.trim_end_matches(|c: char| !c.is_alphabetic());
+    [normalize(password), normalize(base)]
+        .iter()
+        .filter(|candidate| !candidate.is_empty())
+        .any(|candidate| COMMON_PASSWORDS.iter().any(|p| normalize(p) == *candidate))
+}
+
+/// Fixed-size Bloom filter over SHA-256 with double hashing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// Filter sized for `items` entries at the target false positive rate
+    pub fn with_capacity(items: usize) -> Self {
+        let items = items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-items * FALSE_POSITIVE_RATE.ln() / (ln2 * ln2)).ceil() as usize;
+        let hashes = ((bits as f64 / items) * ln2).round().max(1.0) as u32;
+        Self {
+            bits: vec![0; bits.div_ceil(8)],
+            hashes,
+        }
+    }
+
+    pub fn from_parts(bits: Vec<u8>, hashes: u32) -> Self {
+        Self { bits, hashes }
+    }
+
+    pub fn bits(&self) -> &[u8] {
+        &self.bits
+    }
+
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    fn positions(&self, item: &str) -> impl Iterator<Item = usize> {
+        let digest = Sha256::digest(item.as_bytes());
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().unwrap_or_default());
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().unwrap_or_default()) | 1;
+        let size = (self.bits.len() * 8) as u64;
+        (0..self.hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % size) as usize)
+    }
+
+    pub fn insert(&mut self, item: &str) {
+        if self.bits.is_empty() {
+            return;
+        }
+        for pos in self.positions(item).collect::<Vec<_>>() {
+            self.bits[pos / 8] |= 1 << (pos % 8);
+        }
+    }
+
+    pub fn contains(&self, item: &str) -> bool {
+        !self.bits.is_empty()
+            && self
+                .positions(item)
+                .all(|pos| self.bits[pos / 8] & (1 << (pos % 8)) != 0)
+    }
+}
+
+/// A tenant's deny list as stored
+#[derive(Debug, Clone, FromRow)]
+pub struct TenantPasswordDenyList {
+    pub tenant_id: StringUuid,
+    pub filter_bits: Vec<u8>,
+    pub hash_count: u32,
+    /// Bit `n` is set when a term of `n` characters is in the filter; only
+    /// those lengths are looked up
+    pub term_lengths: u64,
+    pub term_count: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TenantPasswordDenyList {
+    /// Normalize, validate and hash the terms into a filter
+    pub fn build(
+        tenant_id: StringUuid,
+        terms: &[String],
+        now: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        let mut normalized: Vec<String> = Vec::with_capacity(terms.len());
+        for term in terms {
+            let value = normalize(term);
+            let len = value.chars().count();
+            if !(MIN_TERM_LENGTH..=MAX_TERM_LENGTH).contains(&len) {
+                return Err(format!(
+                    "Term '{}' must have {} to {} letters or digits",
+                    term, MIN_TERM_LENGTH, MAX_TERM_LENGTH
+                ));
+            }
+            normalized.push(value);
+        }
+        normalized.sort();
+        normalized.dedup();
+        if normalized.is_empty() {
+            return Err("At least one term is required".to_string());
+        }
+        if normalized.len() > MAX_TERMS {
+            return Err(format!("At most {} terms are allowed", MAX_TERMS));
+        }
+
+        let mut filter = BloomFilter::with_capacity(normalized.len());
+        let mut term_lengths = 0u64;
+        for term in &normalized {
+            filter.insert(term);
+            term_lengths |= 1 << (term.chars().count() - 1);
+        }
+        Ok(Self {
+            tenant_id,
+            hash_count: filter.hashes(),
+            filter_bits: filter.bits,
+            term_lengths,
+            term_count: normalized.len() as u32,
+            updated_at: now,
+        })
+    }
+
+    /// Whether the password contains one of the terms
+    pub fn matches(&self, password: &str) -> bool {
+        let filter = BloomFilter::from_parts(self.filter_bits.clone(), self.hash_count);
+        let chars: Vec<char> = normalize(password).chars().collect();
+        (MIN_TERM_LENGTH..=MAX_TERM_LENGTH.min(chars.len()))
+            .filter(|len| self.term_lengths & (1 << (len - 1)) != 0)
+            .any(|len| {
+                chars
+                    .windows(len)
+                    .any(|window| filter.contains(&window.iter().collect::<String>()))
+            })
+    }
+
+    pub fn summary(&self) -> PasswordDenyListSummary {
+        PasswordDenyListSummary {
+            tenant_id: self.tenant_id,
+            term_count: self.term_count,
+            size_bytes: self.filter_bits.len() as u32,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// What is known about a tenant's deny list; the terms are not retained
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PasswordDenyListSummary {
+    pub tenant_id: StringUuid,
+    /// Distinct terms after normalization
+    pub term_count: u32,
+    /// Stored filter size
+    pub size_bytes: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Replace a tenant's deny list
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdatePasswordDenyListInput {
+    /// Terms that must not appear in passwords; matched case-insensitively,
+    /// ignoring symbols and common substitutions such as `@` for `a`
+    pub terms: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deny_list(terms: &[&str]) -> TenantPasswordDenyList {
+        let terms: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+        TenantPasswordDenyList::build(StringUuid::new_v4(), &terms, Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn test_common_passwords_match_variants() {
+        assert!(is_common_password("password"));
+        assert!(is_common_password("P@ssw0rd2024!"));
+        assert!(is_common_password("Qwerty123"));
+        assert!(is_common_password("12345678"));
+        assert!(!is_common_password("NewStrongPass1!"));
+        assert!(!is_common_password("correct horse battery staple"));
+    }
+
+    #[test]
+    fn test_deny_list_matches_terms_inside_passwords() {
+        let list = deny_list(&["Acme Corp", "RocketShip", "acme-corp"]);
+        assert_eq!(list.term_count, 2);
+        assert!(list.matches("Acm3Corp#2024"));
+        assert!(list.matches("myR0cketship!!"));
+        assert!(!list.matches("Unrelated#Passw0rd"));
+        assert!(!list.matches("acm"));
+    }
+
+    #[test]
+    fn test_deny_list_rejects_bad_terms() {
+        let now = Utc::now();
+        let tenant_id = StringUuid::new_v4();
+        assert!(TenantPasswordDenyList::build(tenant_id, &["ab!".to_string()], now).is_err());
+        assert!(TenantPasswordDenyList::build(tenant_id, &["x".repeat(65)], now).is_err());
+        assert!(TenantPasswordDenyList::build(tenant_id, &[], now).is_err());
+        let too_many: Vec<String> = (0..=MAX_TERMS).map(|i| format!("term{}", i)).collect();
+        assert!(TenantPasswordDenyList::build(tenant_id, &too_many, now).is_err());
+    }
+
+    #[test]
+    fn test_bloom_filter_sizing() {
+        let mut filter = BloomFilter::with_capacity(10_000);
+        assert_eq!(filter.hashes(), 20);
+        assert!(filter.bits().len() < 40_000);
+        filter.insert("acmecorp");
+        assert!(filter.contains("acmecorp"));
+        assert!(!filter.contains("acmecorq"));
+    }
+}
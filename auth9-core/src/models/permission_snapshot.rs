@@ -0,0 +1,286 @@
+//! Permission snapshots replicated to gRPC sidecars
+//!
+//! A tenant's permissions are one grant per (user, service): the user's
+//! roles of that service, with inherited roles resolved, and the permission
+//! codes they carry. Sidecars hold the grants in memory and keep them current
+//! from deltas; every update carries the hash of the grants after applying
+//! it, so a sidecar that diverged notices and downloads a new snapshot.
+
+use super::common::StringUuid;
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Grants of a tenant keyed by (user, service)
+pub type GrantMap = BTreeMap<(StringUuid, StringUuid), PermissionGrant>;
+
+/// A user's roles and permissions in one service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionGrant {
+    pub user_id: StringUuid,
+    pub service_id: StringUuid,
+    /// Sorted role names, inherited roles included
+    pub roles: Vec<String>,
+    /// Sorted permission codes
+    pub permissions: Vec<String>,
+}
+
+/// A role assigned to a tenant member
+#[derive(Debug, Clone, FromRow)]
+pub struct RoleAssignment {
+    pub user_id: StringUuid,
+    pub role_id: StringUuid,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SnapshotRole {
+    pub id: StringUuid,
+    pub service_id: StringUuid,
+    pub name: String,
+    pub parent_role_id: Option<StringUuid>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RolePermissionCode {
+    pub role_id: StringUuid,
+    pub code: String,
+}
+
+/// Everything needed to compute a tenant's grants
+#[derive(Debug, Clone, Default)]
+pub struct TenantPermissionData {
+    pub assignments: Vec<RoleAssignment>,
+    /// Assigned roles and their ancestors
+    pub roles: Vec<SnapshotRole>,
+    pub role_permissions: Vec<RolePermissionCode>,
+}
+
+impl TenantPermissionData {
+    /// Grants of every member with at least one role
+    pub fn grants(&self) -> GrantMap {
+        let roles: HashMap<StringUuid, &SnapshotRole> =
+            self.roles.iter().map(|r| (r.id, r)).collect();
+        let mut codes: HashMap<StringUuid, Vec<&str>> = HashMap::new();
+        for rp in &self.role_permissions {
+            codes.entry(rp.role_id).or_default().push(&rp.code);
+        }
+
+        let mut collected: BTreeMap<
+            (StringUuid, StringUuid),
+            (BTreeSet<String>, BTreeSet<String>),
+        > = BTreeMap::new();
+        for assignment in &self.assignments {
+            let Some(base) = roles.get(&assignment.role_id) else {
+                continue;
+            };
+            let (role_names, permissions) = collected
+                .entry((assignment.user_id, base.service_id))
+                .or_default();
+            // Walk up the parent chain, guarding against cycles
+            let mut visited = HashSet::new();
+            let mut next = Some(base.id);
+            while let Some(role_id) = next.filter(|id| visited.insert(*id)) {
+                let Some(role) = roles.get(&role_id) else {
+                    break;
+                };
+                role_names.insert(role.name.clone());
+                for code in codes.get(&role.id).into_iter().flatten() {
+                    permissions.insert(code.to_string());
+                }
+                next = role.parent_role_id;
+            }
+        }
+
+        collected
+            .into_iter()
+            .map(|((user_id, service_id), (roles, permissions))| {
+                (
+                    (user_id, service_id),
+                    PermissionGrant {
+                        user_id,
+                        service_id,
+                        roles: roles.into_iter().collect(),
+                        permissions: permissions.into_iter().collect(),
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// SHA-256 (hex) of the grants in canonical form: one line per grant in key
+/// order, `user_id \t service_id \t roles \t permissions \n`, with roles and
+/// permissions comma-joined
+pub fn grants_hash(grants: &GrantMap) -> String {
+    let mut hasher = Sha256::new();
+    for grant in grants.values() {
+        hasher.update(grant.user_id.to_string());
+        hasher.update(b"\t");
+        hasher.update(grant.service_id.to_string());
+        hasher.update(b"\t");
+        hasher.update(grant.roles.join(","));
+        hasher.update(b"\t");
+        hasher.update(grant.permissions.join(","));
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Changes between two versions of a tenant's grants
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionDelta {
+    /// New or changed grants, replacing the previous grant of their key
+    pub upserts: Vec<PermissionGrant>,
+    /// Keys whose grant is gone
+    pub removals: Vec<(StringUuid, StringUuid)>,
+}
+
+impl PermissionDelta {
+    pub fn between(old: &GrantMap, new: &GrantMap) -> Self {
+        let upserts = new
+            .iter()
+            .filter(|(key, grant)| old.get(key) != Some(grant))
+            .map(|(_, grant)| grant.clone())
+            .collect();
+        let removals = old
+            .keys()
+            .filter(|key| !new.contains_key(key))
+            .copied()
+            .collect();
+        Self { upserts, removals }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.removals.is_empty()
+    }
+
+    pub fn apply(&self, grants: &mut GrantMap) {
+        for key in &self.removals {
+            grants.remove(key);
+        }
+        for grant in &self.upserts {
+            grants.insert((grant.user_id, grant.service_id), grant.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(service_id: StringUuid, name: &str, parent: Option<StringUuid>) -> SnapshotRole {
+        SnapshotRole {
+            id: StringUuid::new_v4(),
+            service_id,
+            name: name.to_string(),
+            parent_role_id: parent,
+        }
+    }
+
+    fn permission(role: &SnapshotRole, code: &str) -> RolePermissionCode {
+        RolePermissionCode {
+            role_id: role.id,
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_grants_resolve_inheritance_per_service() {
+        let (service_a, service_b) = (StringUuid::new_v4(), StringUuid::new_v4());
+        let viewer = role(service_a, "viewer", None);
+        let editor = role(service_a, "editor", Some(viewer.id));
+        let billing = role(service_b, "billing", None);
+        let user_id = StringUuid::new_v4();
+        let data = TenantPermissionData {
+            assignments: vec![
+                RoleAssignment {
+                    user_id,
+                    role_id: editor.id,
+                },
+                RoleAssignment {
+                    user_id,
+                    role_id: billing.id,
+                },
+            ],
+            role_permissions: vec![
+                permission(&viewer, "doc:read"),
+                permission(&editor, "doc:write"),
+                permission(&editor, "doc:read"),
+                permission(&billing, "invoice:read"),
+            ],
+            roles: vec![viewer, editor, billing],
+        };
+
+        let grants = data.grants();
+        assert_eq!(grants.len(), 2);
+        let a = &grants[&(user_id, service_a)];
+        assert_eq!(a.roles, vec!["editor", "viewer"]);
+        assert_eq!(a.permissions, vec!["doc:read", "doc:write"]);
+        assert_eq!(
+            grants[&(user_id, service_b)].permissions,
+            vec!["invoice:read"]
+        );
+    }
+
+    #[test]
+    fn test_grants_survive_role_cycles() {
+        let service_id = StringUuid::new_v4();
+        let mut a = role(service_id, "a", None);
+        let b = role(service_id, "b", Some(a.id));
+        a.parent_role_id = Some(b.id);
+        let user_id = StringUuid::new_v4();
+        let data = TenantPermissionData {
+            assignments: vec![RoleAssignment {
+                user_id,
+                role_id: a.id,
+            }],
+            roles: vec![a, b],
+            role_permissions: vec![],
+        };
+        assert_eq!(data.grants()[&(user_id, service_id)].roles, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_delta_round_trip_matches_hash() {
+        let service_id = StringUuid::new_v4();
+        let grant = |user_id: StringUuid, permissions: &[&str]| PermissionGrant {
+            user_id,
+            service_id,
+            roles: vec!["member".to_string()],
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+        };
+        let (kept, changed, removed, added) = (
+            StringUuid::new_v4(),
+            StringUuid::new_v4(),
+            StringUuid::new_v4(),
+            StringUuid::new_v4(),
+        );
+        let old: GrantMap = [
+            grant(kept, &["a"]),
+            grant(changed, &["a"]),
+            grant(removed, &["a"]),
+        ]
+        .into_iter()
+        .map(|g| ((g.user_id, g.service_id), g))
+        .collect();
+        let new: GrantMap = [
+            grant(kept, &["a"]),
+            grant(changed, &["a", "b"]),
+            grant(added, &["c"]),
+        ]
+        .into_iter()
+        .map(|g| ((g.user_id, g.service_id), g))
+        .collect();
+
+        let delta = PermissionDelta::between(&old, &new);
+        assert_eq!(delta.upserts.len(), 2);
+        assert_eq!(delta.removals, vec![(removed, service_id)]);
+
+        let mut replica = old.clone();
+        delta.apply(&mut replica);
+        assert_eq!(replica, new);
+        assert_eq!(grants_hash(&replica), grants_hash(&new));
+        assert_ne!(grants_hash(&old), grants_hash(&new));
+        assert!(PermissionDelta::between(&new, &new).is_empty());
+    }
+}
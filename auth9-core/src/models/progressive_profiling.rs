@@ -0,0 +1,160 @@
+//! Progressive profiling models
+//!
+//! Services declare which profile fields they need for a given OAuth scope.
+//! Users missing one of those fields are sent to a hosted form before a token
+//! is issued; the collected values are stored on the user.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Requirement scope that applies regardless of the requested scopes.
+pub const ANY_SCOPE: &str = "*";
+
+/// Built-in field stored on `users.display_name` rather than as an attribute.
+pub const FIELD_DISPLAY_NAME: &str = "display_name";
+
+/// Maximum length of a collected attribute value.
+pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 1024;
+
+/// A profile field a service requires for a scope
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ProfileRequirement {
+    pub id: StringUuid,
+    pub service_id: StringUuid,
+    /// OAuth scope that triggers the requirement, or `*` for any scope
+    pub scope: String,
+    pub field_name: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProfileRequirement {
+    /// Whether this requirement applies to a space-delimited scope string
+    pub fn applies_to(&self, scope: &str) -> bool {
+        self.scope == ANY_SCOPE || scope.split_whitespace().any(|s| s == self.scope)
+    }
+}
+
+/// A single requirement in a [`SetProfileRequirementsInput`]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ProfileRequirementInput {
+    #[serde(default = "default_scope")]
+    #[validate(length(min = 1, max = 128))]
+    pub scope: String,
+    #[validate(custom(function = "validate_profile_field_name"))]
+    pub field_name: String,
+    #[validate(length(max = 255))]
+    pub label: Option<String>,
+}
+
+fn default_scope() -> String {
+    ANY_SCOPE.to_string()
+}
+
+/// Replace the full set of profile requirements for a service
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SetProfileRequirementsInput {
+    #[validate(length(max = 50), nested)]
+    pub requirements: Vec<ProfileRequirementInput>,
+}
+
+/// A stored user profile attribute
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserProfileAttribute {
+    pub user_id: StringUuid,
+    pub attr_key: String,
+    pub attr_value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A field rendered on the hosted progressive profiling form
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProfileFormField {
+    pub field_name: String,
+    pub label: Option<String>,
+    /// Current value, if the user already provided one
+    pub value: Option<String>,
+}
+
+/// Hosted progressive profiling form for a client and scope
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProfileFormResponse {
+    pub client_id: String,
+    pub scope: String,
+    pub fields: Vec<ProfileFormField>,
+    /// Field names that still need a value before a token can be issued
+    pub missing_fields: Vec<String>,
+}
+
+/// Attributes submitted from the hosted progressive profiling form
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SubmitProfileInput {
+    #[validate(length(min = 1, max = 255))]
+    pub client_id: String,
+    #[serde(default)]
+    pub scope: String,
+    pub attributes: HashMap<String, String>,
+}
+
+/// Validate a profile field name: lowercase snake_case, starting with a letter.
+pub fn validate_profile_field_name(name: &str) -> Result<(), validator::ValidationError> {
+    let valid = name.len() <= 64
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        let mut err = validator::ValidationError::new("invalid_field_name");
+        err.message = Some("Field name must be lowercase snake_case (e.g. 'company_name')".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(scope: &str) -> ProfileRequirement {
+        ProfileRequirement {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            scope: scope.to_string(),
+            field_name: "company".to_string(),
+            label: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_requirement_applies_to_scope() {
+        assert!(requirement("*").applies_to("openid"));
+        assert!(requirement("*").applies_to(""));
+        assert!(requirement("billing").applies_to("openid billing email"));
+        assert!(!requirement("billing").applies_to("openid billing_read"));
+    }
+
+    #[test]
+    fn test_validate_profile_field_name() {
+        assert!(validate_profile_field_name("company_name").is_ok());
+        assert!(validate_profile_field_name("phone2").is_ok());
+        assert!(validate_profile_field_name("").is_err());
+        assert!(validate_profile_field_name("2fa").is_err());
+        assert!(validate_profile_field_name("CompanyName").is_err());
+        assert!(validate_profile_field_name("company-name").is_err());
+        assert!(validate_profile_field_name(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_requirement_input_defaults_to_any_scope() {
+        let input: ProfileRequirementInput =
+            serde_json::from_str(r#"{"field_name": "company"}"#).unwrap();
+        assert_eq!(input.scope, ANY_SCOPE);
+        assert!(input.validate().is_ok());
+    }
+}
@@ -0,0 +1,37 @@
+//! Rate limit quota introspection
+//!
+//! Lets an API consumer see the buckets it is being counted against and how
+//! much of each window is left, so integrators can plan capacity instead of
+//! discovering limits through 429 responses.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One rate limit bucket the caller has used in its current window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitBucket {
+    /// Endpoint the bucket counts, as "METHOD:path"
+    pub endpoint: String,
+    /// Requests allowed per window
+    pub limit: u64,
+    /// Requests left in the current window
+    pub remaining: u64,
+    pub window_secs: u64,
+    /// Unix timestamp when the oldest counted request leaves the window
+    pub reset_at: u64,
+}
+
+/// The caller's rate limit status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RateLimitStatus {
+    /// False when rate limiting is disabled; no buckets are reported then
+    pub enabled: bool,
+    /// What the caller is counted by: `user`, `client` (tenant access
+    /// token) or `ip`
+    pub subject: String,
+    /// Limit applied to endpoints without their own rule
+    pub default_limit: u64,
+    pub default_window_secs: u64,
+    /// Buckets with requests in their current window
+    pub buckets: Vec<RateLimitBucket>,
+}
@@ -4,6 +4,7 @@ use super::common::StringUuid;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
@@ -40,6 +41,9 @@ pub struct Role {
     pub description: Option<String>,
     /// Parent role for inheritance (optional)
     pub parent_role_id: Option<StringUuid>,
+    /// Optimistic concurrency version, bumped on every update (exposed as ETag)
+    #[serde(default)]
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -53,6 +57,7 @@ impl Default for Role {
             name: String::new(),
             description: None,
             parent_role_id: None,
+            version: 1,
             created_at: now,
             updated_at: now,
         }
@@ -90,8 +95,12 @@ pub struct CreatePermissionInput {
     pub description: Option<String>,
 }
 
-/// Validate permission code format (e.g., "user:read", "report:export:pdf")
-fn validate_permission_code(code: &str) -> Result<(), validator::ValidationError> {
+/// Role names reserved for system roles; services cannot define them
+pub const RESERVED_ROLE_NAMES: &[&str] = &["platform_admin", "owner", "admin", "member"];
+
+/// Validate permission code format (e.g., "user:read", "report:export:pdf",
+/// "billing:invoices:*")
+pub(crate) fn validate_permission_code(code: &str) -> Result<(), validator::ValidationError> {
     if PERMISSION_CODE_REGEX.is_match(code) {
         Ok(())
     } else {
@@ -99,8 +108,64 @@ fn validate_permission_code(code: &str) -> Result<(), validator::ValidationError
     }
 }
 
-/// Input for creating a role
+/// Validate a service permission namespace (a single lowercase segment)
+fn validate_permission_namespace(namespace: &str) -> Result<(), validator::ValidationError> {
+    if PERMISSION_NAMESPACE_REGEX.is_match(namespace) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new(
+            "invalid_permission_namespace",
+        ))
+    }
+}
+
+/// Prefix a permission code with the service namespace unless it already
+/// starts with it. `invoices:read` becomes `billing:invoices:read` in the
+/// `billing` namespace.
+pub fn namespaced_permission_code(namespace: Option<&str>, code: &str) -> String {
+    match namespace {
+        Some(ns) if !code.starts_with(&format!("{}:", ns)) => format!("{}:{}", ns, code),
+        _ => code.to_string(),
+    }
+}
+
+/// Whether a granted permission satisfies a required one.
+///
+/// A trailing `*` segment grants every code below it (`billing:invoices:*`
+/// covers `billing:invoices:read`) and a bare `*` grants everything.
+pub fn permission_matches(granted: &str, required: &str) -> bool {
+    if granted == required || granted == "*" {
+        return true;
+    }
+    match granted.strip_suffix('*') {
+        Some(prefix) if prefix.ends_with(':') => {
+            required.len() > prefix.len() && required.starts_with(prefix)
+        }
+        _ => false,
+    }
+}
+
+/// Permission namespace configured for a service
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PermissionNamespace {
+    pub service_id: StringUuid,
+    /// Prefix applied to new permission codes; none when unset
+    pub namespace: Option<String>,
+}
+
+/// Input for setting a service's permission namespace
 #[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SetPermissionNamespaceInput {
+    /// Namespace such as "billing"; clears the namespace when null
+    #[validate(
+        length(min = 1, max = 32),
+        custom(function = "validate_permission_namespace")
+    )]
+    pub namespace: Option<String>,
+}
+
+/// Input for creating a role
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateRoleInput {
     pub service_id: Uuid,
     #[validate(length(min = 1, max = 100))]
@@ -111,7 +176,7 @@ pub struct CreateRoleInput {
 }
 
 /// Input for updating a role
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateRoleInput {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
@@ -125,7 +190,7 @@ pub struct UpdateRoleInput {
 }
 
 /// Input for assigning roles to a user in a tenant
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct AssignRolesInput {
     pub user_id: Uuid,
     pub tenant_id: Uuid,
@@ -137,7 +202,7 @@ pub struct AssignRolesInput {
 }
 
 /// Role with its permissions (for API responses)
-#[derive(Debug, Clone, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RoleWithPermissions {
     #[serde(flatten)]
     pub role: Role,
@@ -153,10 +218,242 @@ pub struct UserRolesInTenant {
     pub permissions: Vec<String>,
 }
 
-// Regex for permission code validation
+/// Input for checking whether a user holds a permission in a tenant
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CheckPermissionInput {
+    /// Required permission code (e.g., "report:export")
+    #[validate(length(min = 1, max = 128))]
+    pub permission: String,
+    /// Only consider roles of this service
+    #[serde(default)]
+    pub service_id: Option<Uuid>,
+}
+
+/// Why a permission check was decided the way it was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionCheckReason {
+    /// At least one of the user's roles grants the permission
+    Granted,
+    /// The user is not a member of the tenant
+    NotMember,
+    /// The user has no roles in the tenant (or service)
+    NoRoles,
+    /// None of the user's roles grants the permission
+    NotGranted,
+}
+
+/// A role evaluated during a permission check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PermissionCheckStep {
+    pub role_id: StringUuid,
+    pub role: String,
+    pub service_id: StringUuid,
+    /// Roles through which this role was inherited, starting with the role
+    /// assigned to the user; empty when assigned directly
+    pub inherited_via: Vec<String>,
+    /// Permissions of the role that satisfy the required permission
+    pub matched_permissions: Vec<String>,
+}
+
+/// Evaluation trace of a permission check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PermissionCheckExplanation {
+    pub reason: PermissionCheckReason,
+    /// Names of the roles granting the permission
+    pub granted_by: Vec<String>,
+    /// Every role evaluated, assigned roles first
+    pub evaluated_roles: Vec<PermissionCheckStep>,
+}
+
+/// Result of a permission check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PermissionCheckResult {
+    pub allowed: bool,
+    pub permission: String,
+    /// Evaluation trace, only returned with `explain=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<PermissionCheckExplanation>,
+    /// External authorizer participation, when the service has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external: Option<crate::models::external_authz::ExternalAuthzOutcome>,
+}
+
+impl PermissionCheckResult {
+    /// Build an explained result from the evaluated roles
+    pub fn explained(permission: &str, member: bool, steps: Vec<PermissionCheckStep>) -> Self {
+        let granted_by: Vec<String> = steps
+            .iter()
+            .filter(|step| !step.matched_permissions.is_empty())
+            .map(|step| step.role.clone())
+            .collect();
+        let reason = if !member {
+            PermissionCheckReason::NotMember
+        } else if steps.is_empty() {
+            PermissionCheckReason::NoRoles
+        } else if granted_by.is_empty() {
+            PermissionCheckReason::NotGranted
+        } else {
+            PermissionCheckReason::Granted
+        };
+        Self {
+            allowed: reason == PermissionCheckReason::Granted,
+            permission: permission.to_string(),
+            explanation: Some(PermissionCheckExplanation {
+                reason,
+                granted_by,
+                evaluated_roles: steps,
+            }),
+            external: None,
+        }
+    }
+}
+
+/// Node in a service's role hierarchy
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RoleTreeNode {
+    pub id: StringUuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Permission codes granted directly to this role
+    pub permissions: Vec<String>,
+    /// Permission codes granted to this role or inherited from its ancestors
+    pub effective_permissions: Vec<String>,
+    #[schema(no_recursion)]
+    pub children: Vec<RoleTreeNode>,
+}
+
+/// Role hierarchy of a service
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RoleTree {
+    pub service_id: StringUuid,
+    /// Roles without a parent in this service, with their descendants
+    pub roots: Vec<RoleTreeNode>,
+}
+
+/// Build the role hierarchy of a service from its roles and the permission
+/// codes granted directly to each role.
+///
+/// Roles whose parent is missing or belongs to another service become roots.
+/// Roles caught in a cycle (only possible with data written before cycle
+/// checks existed) are listed at the top level instead of being dropped.
+pub fn build_role_tree(
+    service_id: StringUuid,
+    roles: &[Role],
+    direct_permissions: &HashMap<StringUuid, Vec<String>>,
+) -> RoleTree {
+    let by_id: HashMap<StringUuid, &Role> = roles.iter().map(|role| (role.id, role)).collect();
+    let mut children: HashMap<StringUuid, Vec<&Role>> = HashMap::new();
+    let mut roots = Vec::new();
+    for role in roles {
+        match role
+            .parent_role_id
+            .filter(|parent| by_id.contains_key(parent))
+        {
+            Some(parent) => children.entry(parent).or_default().push(role),
+            None => roots.push(role),
+        }
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    roots.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let builder = RoleTreeBuilder {
+        by_id,
+        children,
+        direct_permissions,
+    };
+    let mut visited = HashSet::new();
+    let mut nodes: Vec<RoleTreeNode> = roots
+        .into_iter()
+        .map(|role| builder.node(role, &mut visited))
+        .collect();
+
+    let mut unreached: Vec<&Role> = roles
+        .iter()
+        .filter(|role| !visited.contains(&role.id))
+        .collect();
+    unreached.sort_by(|a, b| a.name.cmp(&b.name));
+    for role in unreached {
+        if !visited.contains(&role.id) {
+            nodes.push(builder.node(role, &mut visited));
+        }
+    }
+
+    RoleTree {
+        service_id,
+        roots: nodes,
+    }
+}
+
+struct RoleTreeBuilder<'a> {
+    by_id: HashMap<StringUuid, &'a Role>,
+    children: HashMap<StringUuid, Vec<&'a Role>>,
+    direct_permissions: &'a HashMap<StringUuid, Vec<String>>,
+}
+
+impl RoleTreeBuilder<'_> {
+    fn direct(&self, role_id: StringUuid) -> impl Iterator<Item = &String> {
+        self.direct_permissions.get(&role_id).into_iter().flatten()
+    }
+
+    fn effective(&self, role: &Role) -> Vec<String> {
+        let mut codes = BTreeSet::new();
+        let mut seen = HashSet::new();
+        let mut current = Some(role);
+        while let Some(role) = current {
+            if !seen.insert(role.id) {
+                break;
+            }
+            codes.extend(self.direct(role.id).cloned());
+            current = role
+                .parent_role_id
+                .and_then(|parent| self.by_id.get(&parent).copied());
+        }
+        codes.into_iter().collect()
+    }
+
+    fn node(&self, role: &Role, visited: &mut HashSet<StringUuid>) -> RoleTreeNode {
+        visited.insert(role.id);
+        let mut permissions: Vec<String> = self.direct(role.id).cloned().collect();
+        permissions.sort();
+        permissions.dedup();
+        let children = self
+            .children
+            .get(&role.id)
+            .map(|children| {
+                children
+                    .iter()
+                    .filter_map(|child| {
+                        if visited.contains(&child.id) {
+                            None
+                        } else {
+                            Some(self.node(child, visited))
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        RoleTreeNode {
+            id: role.id,
+            name: role.name.clone(),
+            description: role.description.clone(),
+            permissions,
+            effective_permissions: self.effective(role),
+            children,
+        }
+    }
+}
+
+// Regex for permission code validation: `resource:action` or
+// `service:resource:action`, where the last segment may be a `*` wildcard
 lazy_static::lazy_static! {
     pub static ref PERMISSION_CODE_REGEX: regex::Regex =
-        regex::Regex::new(r"^[a-z][a-z0-9]*(?::[a-z][a-z0-9]*)+$").unwrap();
+        regex::Regex::new(r"^[a-z][a-z0-9]*(?::[a-z][a-z0-9]*)?:(?:[a-z][a-z0-9]*|\*)$")
+            .unwrap();
+    pub static ref PERMISSION_NAMESPACE_REGEX: regex::Regex =
+        regex::Regex::new(r"^[a-z][a-z0-9]*$").unwrap();
 }
 
 #[cfg(test)]
@@ -230,6 +527,68 @@ mod tests {
         assert!(!PERMISSION_CODE_REGEX.is_match(""));
     }
 
+    #[test]
+    fn test_permission_code_regex_structure() {
+        assert!(PERMISSION_CODE_REGEX.is_match("billing:invoices:read"));
+        assert!(PERMISSION_CODE_REGEX.is_match("billing:invoices:*"));
+        assert!(PERMISSION_CODE_REGEX.is_match("user:*"));
+
+        assert!(!PERMISSION_CODE_REGEX.is_match("billing:invoices:read:all"));
+        assert!(!PERMISSION_CODE_REGEX.is_match("billing:*:read"));
+        assert!(!PERMISSION_CODE_REGEX.is_match("*"));
+    }
+
+    #[test]
+    fn test_namespaced_permission_code() {
+        assert_eq!(
+            namespaced_permission_code(Some("billing"), "invoices:read"),
+            "billing:invoices:read"
+        );
+        assert_eq!(
+            namespaced_permission_code(Some("billing"), "billing:invoices:read"),
+            "billing:invoices:read"
+        );
+        assert_eq!(namespaced_permission_code(None, "user:read"), "user:read");
+    }
+
+    #[test]
+    fn test_permission_matches_wildcards() {
+        assert!(permission_matches("user:read", "user:read"));
+        assert!(permission_matches("*", "billing:invoices:read"));
+        assert!(permission_matches(
+            "billing:invoices:*",
+            "billing:invoices:read"
+        ));
+        assert!(permission_matches("billing:*", "billing:invoices:read"));
+
+        assert!(!permission_matches(
+            "billing:invoices:*",
+            "billing:invoices"
+        ));
+        assert!(!permission_matches(
+            "billing:invoices:*",
+            "billing:payments:read"
+        ));
+        assert!(!permission_matches("bill*", "billing:invoices:read"));
+        assert!(!permission_matches("user:read", "user:write"));
+    }
+
+    #[test]
+    fn test_set_permission_namespace_input_validation() {
+        let valid = SetPermissionNamespaceInput {
+            namespace: Some("billing".to_string()),
+        };
+        assert!(valid.validate().is_ok());
+
+        let cleared = SetPermissionNamespaceInput { namespace: None };
+        assert!(cleared.validate().is_ok());
+
+        let invalid = SetPermissionNamespaceInput {
+            namespace: Some("billing:v2".to_string()),
+        };
+        assert!(invalid.validate().is_err());
+    }
+
     #[test]
     fn test_validate_permission_code_valid() {
         let result = validate_permission_code("user:read");
@@ -485,4 +844,77 @@ mod tests {
         assert_eq!(deserialized.roles, urit.roles);
         assert_eq!(deserialized.permissions, urit.permissions);
     }
+
+    fn tree_role(name: &str, parent: Option<StringUuid>) -> Role {
+        Role {
+            name: name.to_string(),
+            parent_role_id: parent,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_role_tree_inherits_permissions() {
+        let viewer = tree_role("viewer", None);
+        let editor = tree_role("editor", Some(viewer.id));
+        let admin = tree_role("admin", Some(editor.id));
+        let auditor = tree_role("auditor", None);
+        let mut direct = HashMap::new();
+        direct.insert(viewer.id, vec!["doc:read".to_string()]);
+        direct.insert(editor.id, vec!["doc:write".to_string()]);
+        direct.insert(admin.id, vec!["doc:delete".to_string()]);
+
+        let tree = build_role_tree(
+            StringUuid::nil(),
+            &[admin.clone(), editor, viewer, auditor],
+            &direct,
+        );
+
+        assert_eq!(tree.roots.len(), 2);
+        assert_eq!(tree.roots[0].name, "auditor");
+        assert!(tree.roots[0].effective_permissions.is_empty());
+        let viewer_node = &tree.roots[1];
+        assert_eq!(viewer_node.effective_permissions, vec!["doc:read"]);
+        let admin_node = &viewer_node.children[0].children[0];
+        assert_eq!(admin_node.id, admin.id);
+        assert_eq!(admin_node.permissions, vec!["doc:delete"]);
+        assert_eq!(
+            admin_node.effective_permissions,
+            vec!["doc:delete", "doc:read", "doc:write"]
+        );
+    }
+
+    #[test]
+    fn test_build_role_tree_treats_foreign_parent_as_root() {
+        let role = tree_role("editor", Some(StringUuid::new_v4()));
+
+        let tree = build_role_tree(
+            StringUuid::nil(),
+            std::slice::from_ref(&role),
+            &HashMap::new(),
+        );
+
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].id, role.id);
+    }
+
+    #[test]
+    fn test_build_role_tree_keeps_roles_in_cycle() {
+        let mut a = tree_role("a", None);
+        let b = tree_role("b", Some(a.id));
+        a.parent_role_id = Some(b.id);
+        let mut direct = HashMap::new();
+        direct.insert(a.id, vec!["x:read".to_string()]);
+        direct.insert(b.id, vec!["y:read".to_string()]);
+
+        let tree = build_role_tree(StringUuid::nil(), &[a, b], &direct);
+
+        assert_eq!(tree.roots.len(), 1);
+        assert_eq!(tree.roots[0].name, "a");
+        assert_eq!(tree.roots[0].children.len(), 1);
+        assert_eq!(
+            tree.roots[0].effective_permissions,
+            vec!["x:read", "y:read"]
+        );
+    }
 }
@@ -0,0 +1,236 @@
+//! Role preset templates
+//!
+//! A preset is a named set of permissions and roles (e.g. "standard SaaS":
+//! administrator / editor / viewer) that can be applied when a service is
+//! registered. Presets are versioned: an edit adds a version, and applying a
+//! preset copies its roles into the service, so editing a preset never
+//! changes services already created from it.
+
+use super::common::StringUuid;
+use super::rbac::{validate_permission_code, RESERVED_ROLE_NAMES};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+lazy_static! {
+    /// Preset keys are URL-safe slugs
+    static ref PRESET_KEY_REGEX: regex::Regex =
+        regex::Regex::new(r"^[a-z0-9][a-z0-9-]*$").unwrap();
+}
+
+/// A permission created by a preset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct PresetPermission {
+    #[validate(
+        length(min = 1, max = 100),
+        custom(function = "validate_permission_code")
+    )]
+    pub code: String,
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A role created by a preset, granted permissions of the same preset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct PresetRole {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Codes of the preset's permissions granted to the role
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// The permissions and roles a preset creates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_definition"))]
+pub struct RolePresetDefinition {
+    #[validate(length(max = 200), nested)]
+    #[serde(default)]
+    pub permissions: Vec<PresetPermission>,
+    #[validate(length(min = 1, max = 50), nested)]
+    pub roles: Vec<PresetRole>,
+}
+
+fn validate_definition(definition: &RolePresetDefinition) -> Result<(), ValidationError> {
+    let mut codes = HashSet::new();
+    for permission in &definition.permissions {
+        if !codes.insert(permission.code.as_str()) {
+            return Err(ValidationError::new("duplicate_permission_code"));
+        }
+    }
+    let mut names = HashSet::new();
+    for role in &definition.roles {
+        let normalized = role.name.trim().to_lowercase();
+        if RESERVED_ROLE_NAMES.contains(&normalized.as_str()) {
+            return Err(ValidationError::new("reserved_role_name"));
+        }
+        if !names.insert(normalized) {
+            return Err(ValidationError::new("duplicate_role_name"));
+        }
+        if role.permissions.iter().any(|c| !codes.contains(c.as_str())) {
+            return Err(ValidationError::new("unknown_permission_code"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_preset_key(key: &str) -> Result<(), ValidationError> {
+    if PRESET_KEY_REGEX.is_match(key) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_preset_key"))
+    }
+}
+
+/// One version of a role preset
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RolePreset {
+    pub id: StringUuid,
+    /// Stable identifier shared by all versions (e.g. "standard-saas")
+    #[sqlx(rename = "preset_key")]
+    pub key: String,
+    pub version: i32,
+    pub name: String,
+    pub description: Option<String>,
+    #[sqlx(json)]
+    pub definition: RolePresetDefinition,
+    pub created_by: Option<StringUuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for creating a role preset (its first version)
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateRolePresetInput {
+    #[validate(length(min = 1, max = 64), custom(function = "validate_preset_key"))]
+    pub key: String,
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(nested)]
+    pub definition: RolePresetDefinition,
+}
+
+/// Input for publishing a new version of a role preset. Omitted fields keep
+/// the values of the latest version.
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateRolePresetInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(nested)]
+    pub definition: Option<RolePresetDefinition>,
+}
+
+/// What applying a preset to a service created
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AppliedRolePreset {
+    pub key: String,
+    pub version: i32,
+    pub permission_ids: Vec<StringUuid>,
+    pub role_ids: Vec<StringUuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> RolePresetDefinition {
+        RolePresetDefinition {
+            permissions: vec![
+                PresetPermission {
+                    code: "doc:read".to_string(),
+                    name: "Read documents".to_string(),
+                    description: None,
+                },
+                PresetPermission {
+                    code: "doc:write".to_string(),
+                    name: "Write documents".to_string(),
+                    description: None,
+                },
+            ],
+            roles: vec![
+                PresetRole {
+                    name: "editor".to_string(),
+                    description: None,
+                    permissions: vec!["doc:read".to_string(), "doc:write".to_string()],
+                },
+                PresetRole {
+                    name: "viewer".to_string(),
+                    description: None,
+                    permissions: vec!["doc:read".to_string()],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_valid_definition() {
+        assert!(definition().validate().is_ok());
+    }
+
+    #[test]
+    fn test_role_must_use_preset_permissions() {
+        let mut def = definition();
+        def.roles[1].permissions.push("doc:delete".to_string());
+        assert!(def.validate().is_err());
+    }
+
+    #[test]
+    fn test_duplicate_codes_and_names_rejected() {
+        let mut def = definition();
+        def.permissions.push(def.permissions[0].clone());
+        assert!(def.validate().is_err());
+
+        let mut def = definition();
+        def.roles[1].name = "Editor".to_string();
+        assert!(def.validate().is_err());
+    }
+
+    #[test]
+    fn test_reserved_role_name_rejected() {
+        let mut def = definition();
+        def.roles[0].name = "admin".to_string();
+        assert!(def.validate().is_err());
+    }
+
+    #[test]
+    fn test_preset_requires_a_role() {
+        let mut def = definition();
+        def.roles.clear();
+        assert!(def.validate().is_err());
+    }
+
+    #[test]
+    fn test_preset_key_format() {
+        let input = |key: &str| CreateRolePresetInput {
+            key: key.to_string(),
+            name: "Docs".to_string(),
+            description: None,
+            definition: definition(),
+        };
+        assert!(input("standard-saas").validate().is_ok());
+        assert!(input("Standard SaaS").validate().is_err());
+        assert!(input("-saas").validate().is_err());
+    }
+
+    #[test]
+    fn test_definition_deserializes_seeded_shape() {
+        let def: RolePresetDefinition = serde_json::from_value(serde_json::json!({
+            "permissions": [{"code": "resource:read", "name": "Read resources"}],
+            "roles": [{"name": "viewer", "permissions": ["resource:read"]}]
+        }))
+        .unwrap();
+        assert!(def.validate().is_ok());
+        assert_eq!(def.roles[0].description, None);
+    }
+}
@@ -0,0 +1,237 @@
+//! Saga models
+//!
+//! A saga is a multi-step operation spanning the identity engine and the
+//! database (user creation, tenant provisioning, service deletion). Its
+//! progress is persisted after every step so an operation interrupted by a
+//! failure or a restart can be inspected, resumed or compensated instead of
+//! leaving the systems out of step (see [`crate::saga`]).
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::mysql::MySqlRow;
+use sqlx::{FromRow, Row};
+use utoipa::{IntoParams, ToSchema};
+
+/// Saga lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SagaStatus {
+    /// Executing its steps
+    Running,
+    /// Undoing its completed steps after a step failed
+    Compensating,
+    Completed,
+    /// Failed and fully undone
+    Compensated,
+    /// Needs an operator: retries or compensation were exhausted, or the
+    /// process running it stopped
+    Stuck,
+}
+
+impl SagaStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Compensating => "compensating",
+            Self::Completed => "completed",
+            Self::Compensated => "compensated",
+            Self::Stuck => "stuck",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(Self::Running),
+            "compensating" => Some(Self::Compensating),
+            "completed" => Some(Self::Completed),
+            "compensated" => Some(Self::Compensated),
+            "stuck" => Some(Self::Stuck),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Compensated)
+    }
+}
+
+/// Whether a saga is moving forward through its steps or undoing them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SagaDirection {
+    Forward,
+    Backward,
+}
+
+impl SagaDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Backward => "backward",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "forward" => Some(Self::Forward),
+            "backward" => Some(Self::Backward),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SagaStepStatus {
+    Pending,
+    Completed,
+    Failed,
+    Compensated,
+}
+
+/// Progress of one saga step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SagaStepState {
+    pub name: String,
+    pub status: SagaStepStatus,
+    /// Executions and compensations attempted so far
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Persisted state of a saga
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SagaRecord {
+    pub id: StringUuid,
+    /// Operation, e.g. `user_create`, `tenant_provision`, `service_delete`
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    pub status: SagaStatus,
+    pub direction: SagaDirection,
+    /// Index of the next step to execute
+    pub current_step: u32,
+    pub steps: Vec<SagaStepState>,
+    /// Operation input and the results of completed steps (secrets such as
+    /// passwords are never persisted)
+    pub context: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SagaRecord {
+    pub fn new(
+        kind: &str,
+        idempotency_key: Option<&str>,
+        step_names: impl IntoIterator<Item = &'static str>,
+        context: serde_json::Value,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: StringUuid::new_v4(),
+            kind: kind.to_string(),
+            idempotency_key: idempotency_key.map(str::to_string),
+            status: SagaStatus::Running,
+            direction: SagaDirection::Forward,
+            current_step: 0,
+            steps: step_names
+                .into_iter()
+                .map(|name| SagaStepState {
+                    name: name.to_string(),
+                    status: SagaStepStatus::Pending,
+                    attempts: 0,
+                    last_error: None,
+                })
+                .collect(),
+            context,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Deserialize the persisted context
+    pub fn context_as<C: DeserializeOwned>(&self) -> serde_json::Result<C> {
+        serde_json::from_value(self.context.clone())
+    }
+}
+
+impl<'r> FromRow<'r, MySqlRow> for SagaRecord {
+    fn from_row(row: &'r MySqlRow) -> sqlx::Result<Self> {
+        let decode_error = |column: &str, value: String| sqlx::Error::ColumnDecode {
+            index: column.to_string(),
+            source: format!("unknown value '{}'", value).into(),
+        };
+        let status: String = row.try_get("status")?;
+        let direction: String = row.try_get("direction")?;
+        let steps: sqlx::types::Json<Vec<SagaStepState>> = row.try_get("steps")?;
+        let context: sqlx::types::Json<serde_json::Value> = row.try_get("context")?;
+        let current_step: i32 = row.try_get("current_step")?;
+
+        Ok(SagaRecord {
+            id: row.try_get("id")?,
+            kind: row.try_get("kind")?,
+            idempotency_key: row.try_get("idempotency_key")?,
+            status: SagaStatus::parse(&status).ok_or_else(|| decode_error("status", status))?,
+            direction: SagaDirection::parse(&direction)
+                .ok_or_else(|| decode_error("direction", direction))?,
+            current_step: current_step.max(0) as u32,
+            steps: steps.0,
+            context: context.0,
+            error: row.try_get("error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// Filter of the saga list
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct SagaFilter {
+    /// `running`, `compensating`, `completed`, `compensated` or `stuck`
+    pub status: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            SagaStatus::Running,
+            SagaStatus::Compensating,
+            SagaStatus::Completed,
+            SagaStatus::Compensated,
+            SagaStatus::Stuck,
+        ] {
+            assert_eq!(SagaStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(SagaStatus::parse("failed"), None);
+        assert!(SagaStatus::Compensated.is_terminal());
+        assert!(!SagaStatus::Stuck.is_terminal());
+    }
+
+    #[test]
+    fn test_new_record() {
+        let record = SagaRecord::new(
+            "user_create",
+            Some("key-1"),
+            ["create_identity_user", "create_user"],
+            serde_json::json!({ "email": "a@example.com" }),
+        );
+        assert_eq!(record.status, SagaStatus::Running);
+        assert_eq!(record.direction, SagaDirection::Forward);
+        assert_eq!(record.steps.len(), 2);
+        assert!(record
+            .steps
+            .iter()
+            .all(|s| s.status == SagaStepStatus::Pending));
+        assert_eq!(record.idempotency_key.as_deref(), Some("key-1"));
+    }
+}
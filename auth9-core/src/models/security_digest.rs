@@ -0,0 +1,187 @@
+//! Security alert digests for tenant admins
+//!
+//! A tenant's digest policy (`settings.security_digest`) mails the security
+//! alerts raised for the tenant and its members since the previous digest,
+//! grouped by affected user, to a distribution list and (by default) the
+//! tenant's owners and admins. Each affected user comes with one-click
+//! actions authorized by short-lived signed tokens (see
+//! [`DIGEST_ACTION_TTL_HOURS`]).
+
+use super::analytics::{AlertSeverity, SecurityAlertType};
+use super::common::StringUuid;
+use super::notification_preference::DigestFrequency;
+use super::tenant::TenantSettings;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidateEmail};
+
+/// How long the action links of a digest stay valid
+pub const DIGEST_ACTION_TTL_HOURS: i64 = 24;
+
+/// Most alerts listed in one digest; the rest are only counted
+pub const MAX_DIGEST_ALERTS: i64 = 200;
+
+/// Security digest policy of a tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SecurityDigestPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// `daily` or `weekly`
+    #[validate(custom(function = "validate_digest_frequency"))]
+    pub frequency: DigestFrequency,
+    /// Alerts below this severity are left out (default: medium)
+    #[serde(default = "default_min_severity")]
+    pub min_severity: AlertSeverity,
+    /// Distribution list receiving every digest
+    #[serde(default)]
+    #[validate(custom(function = "validate_recipients"))]
+    pub recipients: Vec<String>,
+    /// Also send to the tenant's owners and admins (default: true)
+    #[serde(default = "default_include_tenant_admins")]
+    pub include_tenant_admins: bool,
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Medium
+}
+
+fn default_include_tenant_admins() -> bool {
+    true
+}
+
+fn validate_digest_frequency(
+    frequency: &DigestFrequency,
+) -> Result<(), validator::ValidationError> {
+    if frequency.period().is_none() {
+        let mut err = validator::ValidationError::new("invalid_digest_frequency");
+        err.message = Some("frequency must be daily or weekly".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_recipients(recipients: &Vec<String>) -> Result<(), validator::ValidationError> {
+    let invalid = |message: String| {
+        let mut err = validator::ValidationError::new("invalid_digest_recipients");
+        err.message = Some(message.into());
+        err
+    };
+    if recipients.len() > 50 {
+        return Err(invalid("At most 50 digest recipients".to_string()));
+    }
+    if let Some(email) = recipients.iter().find(|email| !email.validate_email()) {
+        return Err(invalid(format!("'{}' is not a valid email address", email)));
+    }
+    Ok(())
+}
+
+/// Action a digest recipient can take on an affected user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestAction {
+    /// Require a new password at the next sign-in and email a reset link
+    ForceReset,
+    /// Sign the user out of every session
+    RevokeSessions,
+}
+
+impl DigestAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ForceReset => "force_reset",
+            Self::RevokeSessions => "revoke_sessions",
+        }
+    }
+}
+
+impl std::str::FromStr for DigestAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "force_reset" => Ok(Self::ForceReset),
+            "revoke_sessions" => Ok(Self::RevokeSessions),
+            _ => Err(format!("Unknown digest action: {}", s)),
+        }
+    }
+}
+
+/// Tenant with an enabled digest policy
+#[derive(Debug, Clone)]
+pub struct DigestTenant {
+    pub tenant_id: StringUuid,
+    pub name: String,
+    pub settings: TenantSettings,
+    pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+impl DigestTenant {
+    pub fn policy(&self) -> Option<&SecurityDigestPolicy> {
+        self.settings
+            .security_digest
+            .as_ref()
+            .filter(|policy| policy.enabled)
+    }
+}
+
+/// Alert listed in a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestAlert {
+    pub id: StringUuid,
+    pub user_id: Option<StringUuid>,
+    /// Email of the affected user, if still on record
+    pub user_email: Option<String>,
+    pub alert_type: SecurityAlertType,
+    pub severity: AlertSeverity,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Verified action link of a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestActionClaims {
+    pub tenant_id: StringUuid,
+    pub user_id: StringUuid,
+    pub action: DigestAction,
+    /// Digest recipient the link was sent to
+    pub recipient: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(value: serde_json::Value) -> SecurityDigestPolicy {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_policy_defaults() {
+        let policy = policy(serde_json::json!({ "enabled": true, "frequency": "daily" }));
+        assert_eq!(policy.min_severity, AlertSeverity::Medium);
+        assert!(policy.include_tenant_admins);
+        assert!(policy.recipients.is_empty());
+        assert!(policy.validate().is_ok());
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        let no_frequency = policy(serde_json::json!({ "frequency": "none" }));
+        assert!(no_frequency.validate().is_err());
+
+        let bad_recipient = policy(serde_json::json!({
+            "frequency": "weekly",
+            "recipients": ["soc@example.com", "not-an-email"],
+        }));
+        assert!(bad_recipient.validate().is_err());
+    }
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in [DigestAction::ForceReset, DigestAction::RevokeSessions] {
+            assert_eq!(action.as_str().parse::<DigestAction>().unwrap(), action);
+        }
+        assert!("delete".parse::<DigestAction>().is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Service/Client domain model
 
-use super::common::StringUuid;
+use super::common::{StringUuid, TokenTtlOverrides};
+use super::role_preset::AppliedRolePreset;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -11,7 +12,7 @@ use validator::Validate;
 
 /// Validate a single redirect URI.
 /// HTTP is only allowed for localhost/127.0.0.1, all other hosts must use HTTPS.
-fn validate_single_redirect_uri(uri: &str) -> Result<(), validator::ValidationError> {
+pub(crate) fn validate_single_redirect_uri(uri: &str) -> Result<(), validator::ValidationError> {
     let parsed = Url::parse(uri).map_err(|_| {
         let mut err = validator::ValidationError::new("invalid_url");
         err.message = Some(format!("Invalid URL: {}", uri).into());
@@ -47,6 +48,53 @@ fn validate_redirect_uris(uris: &[String]) -> Result<(), validator::ValidationEr
     Ok(())
 }
 
+/// Most environment tags a service can carry
+pub const MAX_ENVIRONMENT_TAGS: usize = 10;
+
+/// Environment tags are short lowercase labels such as `production`
+fn validate_environment_tags(tags: &[String]) -> Result<(), validator::ValidationError> {
+    if tags.len() > MAX_ENVIRONMENT_TAGS {
+        let mut err = validator::ValidationError::new("too_many_environment_tags");
+        err.message = Some(format!("At most {} environment tags", MAX_ENVIRONMENT_TAGS).into());
+        return Err(err);
+    }
+    for tag in tags {
+        let valid = !tag.is_empty()
+            && tag.len() <= 32
+            && tag
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            let mut err = validator::ValidationError::new("invalid_environment_tag");
+            err.message = Some(
+                format!(
+                    "Environment tags are 1-32 lowercase letters, digits, '-' or '_': {}",
+                    tag
+                )
+                .into(),
+            );
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Catalog metadata shown in the service directory and the app launcher
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(default)]
+pub struct ServiceCatalog {
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    /// Team that owns the service
+    #[validate(length(min = 1, max = 128))]
+    pub owner_team: Option<String>,
+    #[validate(url)]
+    pub docs_url: Option<String>,
+    /// Environments the service runs in, e.g. `production`, `staging`
+    #[validate(custom(function = "validate_environment_tags"))]
+    pub environment_tags: Vec<String>,
+}
+
 /// Service status
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "lowercase")]
@@ -102,6 +150,12 @@ pub struct Service {
     #[sqlx(json)]
     pub logout_uris: Vec<String>,
     pub status: ServiceStatus,
+    /// Optimistic concurrency version, bumped on every update (exposed as ETag)
+    #[serde(default)]
+    pub version: i32,
+    #[serde(default)]
+    #[sqlx(json, default)]
+    pub catalog: ServiceCatalog,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -117,6 +171,8 @@ impl Default for Service {
             redirect_uris: Vec::new(),
             logout_uris: Vec::new(),
             status: ServiceStatus::default(),
+            version: 1,
+            catalog: ServiceCatalog::default(),
             created_at: now,
             updated_at: now,
         }
@@ -135,6 +191,10 @@ pub struct Client {
     pub name: Option<String>,
     #[serde(default)]
     pub public_client: bool,
+    /// Token lifetime overrides for tokens issued to this client
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub token_ttls: TokenTtlOverrides,
     pub created_at: DateTime<Utc>,
 }
 
@@ -153,6 +213,15 @@ pub struct CreateServiceInput {
     pub redirect_uris: Vec<String>,
     #[validate(custom(function = "validate_redirect_uris"))]
     pub logout_uris: Option<Vec<String>>,
+    /// Role preset to create the service's roles from
+    #[serde(default)]
+    pub role_preset: Option<String>,
+    /// Preset version to apply; latest when omitted
+    #[serde(default)]
+    pub role_preset_version: Option<i32>,
+    #[serde(default)]
+    #[validate(nested)]
+    pub catalog: ServiceCatalog,
 }
 
 /// Input for creating a new client
@@ -176,6 +245,9 @@ pub struct UpdateServiceInput {
     #[validate(custom(function = "validate_redirect_uris"))]
     pub logout_uris: Option<Vec<String>>,
     pub status: Option<ServiceStatus>,
+    /// Replaces the catalog metadata when set
+    #[validate(nested)]
+    pub catalog: Option<ServiceCatalog>,
 }
 
 /// Service response with initial client
@@ -184,6 +256,9 @@ pub struct ServiceWithClient {
     #[serde(flatten)]
     pub service: Service,
     pub client: ClientWithSecret,
+    /// Roles created from the requested role preset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role_preset: Option<AppliedRolePreset>,
 }
 
 /// Client response with generated secret
@@ -266,6 +341,7 @@ mod tests {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         };
 
         let json = serde_json::to_string(&client).unwrap();
@@ -284,6 +360,7 @@ mod tests {
             name: None,
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         };
 
         assert!(client.name.is_none());
@@ -325,6 +402,9 @@ mod tests {
             base_url: Some("https://example.com".to_string()),
             redirect_uris: vec!["https://example.com/callback".to_string()],
             logout_uris: Some(vec!["https://example.com/logout".to_string()]),
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         assert!(input.validate().is_ok());
@@ -339,6 +419,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         assert!(input.validate().is_ok());
@@ -353,6 +436,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         assert!(input.validate().is_err());
@@ -367,6 +453,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         assert!(input.validate().is_err());
@@ -381,6 +470,9 @@ mod tests {
             base_url: Some("not-a-url".to_string()),
             redirect_uris: vec![],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
 
         assert!(input.validate().is_err());
@@ -427,6 +519,7 @@ mod tests {
             redirect_uris: Some(vec!["https://new-callback.com".to_string()]),
             logout_uris: Some(vec!["https://new-logout.com".to_string()]),
             status: Some(ServiceStatus::Inactive),
+            catalog: None,
         };
 
         assert!(input.validate().is_ok());
@@ -440,6 +533,7 @@ mod tests {
             redirect_uris: None,
             logout_uris: None,
             status: Some(ServiceStatus::Inactive),
+            catalog: None,
         };
 
         assert!(input.validate().is_ok());
@@ -453,6 +547,7 @@ mod tests {
             redirect_uris: None,
             logout_uris: None,
             status: None,
+            catalog: None,
         };
 
         assert!(input.validate().is_err());
@@ -466,6 +561,7 @@ mod tests {
             redirect_uris: None,
             logout_uris: None,
             status: None,
+            catalog: None,
         };
 
         assert!(input.validate().is_err());
@@ -482,6 +578,7 @@ mod tests {
             name: None,
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         };
         let client_secret = "secret123".to_string();
 
@@ -491,6 +588,7 @@ mod tests {
                 client,
                 client_secret: client_secret.clone(),
             },
+            role_preset: None,
         };
 
         assert_eq!(swc.service.id, service.id);
@@ -511,6 +609,7 @@ mod tests {
             name: None,
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         };
 
         let swc = ServiceWithClient {
@@ -519,6 +618,7 @@ mod tests {
                 client,
                 client_secret: "secret123".to_string(),
             },
+            role_preset: None,
         };
 
         let json = serde_json::to_string(&swc).unwrap();
@@ -538,6 +638,7 @@ mod tests {
             name: Some("My Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         };
 
         let cws = ClientWithSecret {
@@ -565,6 +666,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec!["https://app.example.com/callback".to_string()],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
         assert!(input.validate().is_ok());
     }
@@ -581,6 +685,9 @@ mod tests {
                 "http://127.0.0.1:8080/callback".to_string(),
             ],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
         assert!(input.validate().is_ok());
     }
@@ -594,6 +701,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec!["http://app.example.com/callback".to_string()],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
         let result = input.validate();
         assert!(result.is_err());
@@ -611,6 +721,9 @@ mod tests {
             base_url: None,
             redirect_uris: vec!["not-a-valid-url".to_string()],
             logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
         };
         assert!(input.validate().is_err());
     }
@@ -623,7 +736,64 @@ mod tests {
             redirect_uris: Some(vec!["http://evil.com/callback".to_string()]),
             logout_uris: None,
             status: None,
+            catalog: None,
         };
         assert!(input.validate().is_err());
     }
+
+    #[test]
+    fn test_service_catalog_validation() {
+        let catalog = ServiceCatalog {
+            description: Some("Customer billing".to_string()),
+            owner_team: Some("payments".to_string()),
+            docs_url: Some("https://docs.example.com/billing".to_string()),
+            environment_tags: vec!["production".to_string(), "eu-west_1".to_string()],
+        };
+        assert!(catalog.validate().is_ok());
+
+        for tags in [
+            vec!["Production".to_string()],
+            vec![String::new()],
+            vec!["a".repeat(33)],
+            (0..=MAX_ENVIRONMENT_TAGS)
+                .map(|i| format!("env{}", i))
+                .collect(),
+        ] {
+            let catalog = ServiceCatalog {
+                environment_tags: tags,
+                ..Default::default()
+            };
+            assert!(catalog.validate().is_err());
+        }
+
+        let input = UpdateServiceInput {
+            name: None,
+            base_url: None,
+            redirect_uris: None,
+            logout_uris: None,
+            status: None,
+            catalog: Some(ServiceCatalog {
+                docs_url: Some("not a url".to_string()),
+                ..Default::default()
+            }),
+        };
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_service_without_catalog_deserializes() {
+        let service: Service = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "tenant_id": null,
+            "name": "Legacy",
+            "base_url": null,
+            "redirect_uris": [],
+            "logout_uris": [],
+            "status": "active",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(service.catalog, ServiceCatalog::default());
+    }
 }
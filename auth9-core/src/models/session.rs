@@ -5,6 +5,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
+use validator::Validate;
 
 /// User session entity
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -14,6 +15,8 @@ pub struct Session {
     pub provider_session_id: Option<String>,
     pub device_type: Option<String>,
     pub device_name: Option<String>,
+    /// Name the user gave this device
+    pub display_name: Option<String>,
     pub ip_address: Option<String>,
     pub location: Option<String>,
     pub user_agent: Option<String>,
@@ -22,6 +25,33 @@ pub struct Session {
     pub revoked_at: Option<DateTime<Utc>>,
 }
 
+impl Session {
+    /// Name shown to the user: their own name for the device, else the
+    /// browser/OS parsed from the user agent
+    pub fn friendly_name(&self) -> String {
+        self.display_name
+            .clone()
+            .or_else(|| self.device_name.clone())
+            .unwrap_or_else(|| "Unknown device".to_string())
+    }
+
+    /// Browser, OS and device type detected at sign-in, e.g.
+    /// "Chrome on macOS (desktop)"
+    pub fn device_summary(&self) -> String {
+        device_summary(self.device_name.as_deref(), self.device_type.as_deref())
+    }
+}
+
+/// Describe a device from the parts returned by [`parse_user_agent`]
+pub fn device_summary(device_name: Option<&str>, device_type: Option<&str>) -> String {
+    match (device_name, device_type) {
+        (Some(name), Some(kind)) => format!("{} ({})", name, kind),
+        (Some(name), None) => name.to_string(),
+        (None, Some(kind)) => format!("Unknown browser ({})", kind),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
+
 impl Default for Session {
     fn default() -> Self {
         let now = Utc::now();
@@ -31,6 +61,7 @@ impl Default for Session {
             provider_session_id: None,
             device_type: None,
             device_name: None,
+            display_name: None,
             ip_address: None,
             location: None,
             user_agent: None,
@@ -53,6 +84,38 @@ pub struct CreateSessionInput {
     pub user_agent: Option<String>,
 }
 
+/// Default rows per page of a tenant session export
+pub const DEFAULT_SESSION_EXPORT_PAGE: i64 = 1_000;
+
+/// Largest page of a tenant session export; bounds the memory of one request
+pub const MAX_SESSION_EXPORT_PAGE: i64 = 10_000;
+
+/// One page of a tenant session export
+#[derive(Debug, Clone)]
+pub struct SessionExportPage {
+    pub sessions: Vec<Session>,
+    /// Opaque cursor of the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Encode the continuation cursor pointing after `last_id`. Exports are
+/// ordered by session id so pages are keyset scans instead of OFFSET queries.
+pub fn encode_session_cursor(last_id: StringUuid) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(last_id.to_string())
+}
+
+/// Decode a continuation cursor produced by [`encode_session_cursor`]
+pub fn decode_session_cursor(cursor: &str) -> Result<StringUuid, String> {
+    use base64::Engine;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| "Invalid session export cursor".to_string())
+}
+
 /// Identity provider session representation
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -71,8 +134,11 @@ pub struct IdentityProviderSession {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SessionInfo {
     pub id: String,
+    /// Friendly device name (user-chosen or derived from the user agent)
+    pub name: String,
     pub device_type: Option<String>,
     pub device_name: Option<String>,
+    pub display_name: Option<String>,
     pub ip_address: Option<String>,
     pub location: Option<String>,
     pub last_active_at: DateTime<Utc>,
@@ -84,8 +150,10 @@ impl From<Session> for SessionInfo {
     fn from(session: Session) -> Self {
         Self {
             id: session.id.to_string(),
+            name: session.friendly_name(),
             device_type: session.device_type,
             device_name: session.device_name,
+            display_name: session.display_name,
             ip_address: session.ip_address,
             location: session.location,
             last_active_at: session.last_active_at,
@@ -95,6 +163,101 @@ impl From<Session> for SessionInfo {
     }
 }
 
+/// Input for renaming a device
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct RenameSessionInput {
+    /// New device name; `null` restores the name derived from the user agent
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+}
+
+/// Concurrent sessions per user when none of the user's tenants sets a limit
+pub const DEFAULT_MAX_SESSIONS_PER_USER: u32 = 10;
+
+/// What happens when a login would exceed the concurrent session limit.
+/// Variants are ordered from least to most strict.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionLimitAction {
+    /// End the least recently active sessions to make room
+    #[default]
+    EvictOldest,
+    /// Ask the user which sessions to end. Login flows that cannot ask
+    /// (social, enterprise SSO, passkey, ...) evict the oldest instead.
+    Prompt,
+    /// Reject the new login
+    DenyNew,
+}
+
+impl SessionLimitAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EvictOldest => "evict_oldest",
+            Self::Prompt => "prompt",
+            Self::DenyNew => "deny_new",
+        }
+    }
+}
+
+/// Per-user concurrent session limit of a tenant (`settings.session_limit`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SessionLimitPolicy {
+    /// Active sessions a user may hold at once
+    #[validate(range(min = 1, max = 100))]
+    pub max_sessions: u32,
+    #[serde(default)]
+    pub on_limit: SessionLimitAction,
+}
+
+impl Default for SessionLimitPolicy {
+    fn default() -> Self {
+        Self {
+            max_sessions: DEFAULT_MAX_SESSIONS_PER_USER,
+            on_limit: SessionLimitAction::EvictOldest,
+        }
+    }
+}
+
+impl SessionLimitPolicy {
+    /// Sessions are per user, not per tenant: a member of several tenants
+    /// gets the lowest limit and the strictest action among them
+    pub fn strictest(policies: &[SessionLimitPolicy]) -> Self {
+        policies
+            .iter()
+            .copied()
+            .reduce(|a, b| Self {
+                max_sessions: a.max_sessions.min(b.max_sessions),
+                on_limit: a.on_limit.max(b.on_limit),
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Limit applied by [`crate::repository::SessionRepository::create_within_limit`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionLimit {
+    pub max_sessions: u32,
+    /// Make room by revoking the least recently active sessions; otherwise
+    /// creation fails once the limit is reached
+    pub evict_oldest: bool,
+    /// The user's sessions to revoke first (chosen at a prompt)
+    pub revoke_session_ids: Vec<StringUuid>,
+}
+
+/// Result of creating a session within a limit
+#[derive(Debug, Clone)]
+pub enum SessionCreateOutcome {
+    /// Session created; `revoked` are the sessions ended to make room
+    Created {
+        session: Session,
+        revoked: Vec<Session>,
+    },
+    /// Nothing changed: the limit is reached and eviction is not allowed
+    LimitReached { active: Vec<Session> },
+}
+
 /// Parse user agent string to extract device info
 pub fn parse_user_agent(user_agent: &str) -> (Option<String>, Option<String>) {
     let ua = user_agent.to_lowercase();
@@ -184,9 +347,72 @@ mod tests {
         let info: SessionInfo = session.into();
         assert_eq!(info.device_type, Some("desktop".to_string()));
         assert_eq!(info.device_name, Some("Chrome on macOS".to_string()));
+        assert_eq!(info.name, "Chrome on macOS");
         assert!(!info.is_current);
     }
 
+    #[test]
+    fn test_friendly_name_prefers_display_name() {
+        let mut session = Session::default();
+        assert_eq!(session.friendly_name(), "Unknown device");
+
+        session.device_name = Some("Safari on iPhone".to_string());
+        assert_eq!(session.friendly_name(), "Safari on iPhone");
+
+        session.display_name = Some("Work phone".to_string());
+        assert_eq!(session.friendly_name(), "Work phone");
+    }
+
+    #[test]
+    fn test_device_summary() {
+        let mut session = Session::default();
+        assert_eq!(session.device_summary(), "Unknown device");
+
+        session.device_type = Some("mobile".to_string());
+        assert_eq!(session.device_summary(), "Unknown browser (mobile)");
+
+        session.device_name = Some("Safari on iPhone".to_string());
+        session.display_name = Some("Work phone".to_string());
+        assert_eq!(session.device_summary(), "Safari on iPhone (mobile)");
+    }
+
+    #[test]
+    fn test_session_limit_policy_strictest() {
+        assert_eq!(
+            SessionLimitPolicy::strictest(&[]),
+            SessionLimitPolicy::default()
+        );
+
+        let policies = [
+            SessionLimitPolicy {
+                max_sessions: 3,
+                on_limit: SessionLimitAction::EvictOldest,
+            },
+            SessionLimitPolicy {
+                max_sessions: 5,
+                on_limit: SessionLimitAction::DenyNew,
+            },
+            SessionLimitPolicy {
+                max_sessions: 8,
+                on_limit: SessionLimitAction::Prompt,
+            },
+        ];
+        let policy = SessionLimitPolicy::strictest(&policies);
+        assert_eq!(policy.max_sessions, 3);
+        assert_eq!(policy.on_limit, SessionLimitAction::DenyNew);
+    }
+
+    #[test]
+    fn test_session_limit_policy_deserialize() {
+        let policy: SessionLimitPolicy = serde_json::from_str(r#"{"max_sessions":2}"#).unwrap();
+        assert_eq!(policy.on_limit, SessionLimitAction::EvictOldest);
+
+        let policy: SessionLimitPolicy =
+            serde_json::from_str(r#"{"max_sessions":0,"on_limit":"prompt"}"#).unwrap();
+        assert_eq!(policy.on_limit, SessionLimitAction::Prompt);
+        assert!(policy.validate().is_err());
+    }
+
     #[test]
     fn test_parse_user_agent_chrome_windows() {
         let ua = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
@@ -282,8 +508,10 @@ mod tests {
     fn test_session_info_serialization() {
         let info = SessionInfo {
             id: "session-123".to_string(),
+            name: "Work laptop".to_string(),
             device_type: Some("desktop".to_string()),
             device_name: Some("Chrome".to_string()),
+            display_name: Some("Work laptop".to_string()),
             ip_address: Some("192.168.1.1".to_string()),
             location: Some("San Francisco, US".to_string()),
             last_active_at: Utc::now(),
@@ -296,4 +524,22 @@ mod tests {
         assert!(json.contains("desktop"));
         assert!(json.contains("is_current"));
     }
+
+    #[test]
+    fn test_session_cursor_roundtrip() {
+        let id = StringUuid::new_v4();
+        let cursor = encode_session_cursor(id);
+        assert_eq!(decode_session_cursor(&cursor).unwrap(), id);
+    }
+
+    #[test]
+    fn test_session_cursor_rejects_garbage() {
+        assert!(decode_session_cursor("not a cursor").is_err());
+        assert!(decode_session_cursor(&encode_session_cursor_raw("nope")).is_err());
+    }
+
+    fn encode_session_cursor_raw(value: &str) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(value)
+    }
 }
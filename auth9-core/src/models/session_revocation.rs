@@ -0,0 +1,146 @@
+//! Session revocation feed models
+//!
+//! Every revoked session is appended to a feed with a monotonically
+//! increasing sequence number. Relying parties receive the notices as
+//! webhooks or over the gRPC watch stream and catch up on missed ones with
+//! `GET /api/v1/revocations?since=<sequence>`.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+/// Largest page of the catch-up endpoint and the watch stream
+pub const MAX_REVOCATION_PAGE: i64 = 500;
+
+/// Why a session was revoked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionRevocationReason {
+    /// The user ended one of their sessions
+    UserRequest,
+    /// The user signed out their other devices
+    SignOutOthers,
+    SignOutEverywhere,
+    /// An administrator or automation logged the user out
+    AdminForceLogout,
+    /// Evicted by the tenant's concurrent session limit
+    SessionLimit,
+}
+
+impl SessionRevocationReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UserRequest => "user_request",
+            Self::SignOutOthers => "sign_out_others",
+            Self::SignOutEverywhere => "sign_out_everywhere",
+            Self::AdminForceLogout => "admin_force_logout",
+            Self::SessionLimit => "session_limit",
+        }
+    }
+}
+
+impl std::str::FromStr for SessionRevocationReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user_request" => Ok(Self::UserRequest),
+            "sign_out_others" => Ok(Self::SignOutOthers),
+            "sign_out_everywhere" => Ok(Self::SignOutEverywhere),
+            "admin_force_logout" => Ok(Self::AdminForceLogout),
+            "session_limit" => Ok(Self::SessionLimit),
+            _ => Err(format!("Unknown session revocation reason: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for SessionRevocationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for SessionRevocationReason {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for SessionRevocationReason {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for SessionRevocationReason {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&self.as_str(), buf)
+    }
+}
+
+/// A revoked session as published to relying parties
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RevocationNotice {
+    /// Position in the feed; strictly increasing
+    pub sequence: i64,
+    pub session_id: StringUuid,
+    pub user_id: StringUuid,
+    pub reason: SessionRevocationReason,
+    pub revoked_at: DateTime<Utc>,
+}
+
+/// Query of `GET /api/v1/revocations`
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevocationFeedQuery {
+    /// Last sequence the caller has seen; notices after it are returned
+    #[serde(default)]
+    pub since: i64,
+    /// Page size (default 100, max 500)
+    pub limit: Option<i64>,
+}
+
+/// One page of the revocation feed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevocationFeedPage {
+    pub revocations: Vec<RevocationNotice>,
+    /// Pass as `since` to read the next page
+    pub next_since: i64,
+    pub has_more: bool,
+    /// Notices after `since` were already purged by retention; the caller
+    /// missed revocations and should re-validate its sessions
+    pub gap: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason_round_trip() {
+        for reason in [
+            SessionRevocationReason::UserRequest,
+            SessionRevocationReason::SignOutOthers,
+            SessionRevocationReason::SignOutEverywhere,
+            SessionRevocationReason::AdminForceLogout,
+            SessionRevocationReason::SessionLimit,
+        ] {
+            assert_eq!(reason.as_str().parse(), Ok(reason));
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::json!(reason.as_str())
+            );
+        }
+        assert!("expired".parse::<SessionRevocationReason>().is_err());
+    }
+}
@@ -1,10 +1,17 @@
 //! Tenant domain model
 
-use super::common::{validate_url_no_ssrf_strict, StringUuid};
+use super::cardinality::CardinalityLimits;
+use super::common::{validate_url_no_ssrf_strict, StringUuid, TokenTtlOverrides};
+use super::inactivity::InactivityPolicy;
+use super::login_identifier::LoginIdentifierSettings;
+use super::mfa_enrollment::MfaEnrollmentCampaign;
 use super::password::PasswordPolicy;
+use super::security_digest::SecurityDigestPolicy;
+use super::session::SessionLimitPolicy;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 use validator::Validate;
 
@@ -99,12 +106,57 @@ pub struct TenantSettings {
     #[serde(default)]
     #[validate(nested)]
     pub branding: TenantBranding,
+    /// Default locale for emails and messages (`en-US`, `zh-CN`, `ja`),
+    /// used when neither the user nor the request specifies one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validate_supported_locale"))]
+    pub default_locale: Option<String>,
+    /// Token lifetime overrides for all clients of this tenant (a client's
+    /// own overrides take precedence)
+    #[serde(default, skip_serializing_if = "TokenTtlOverrides::is_empty")]
+    pub token_ttls: TokenTtlOverrides,
+    /// Custom OAuth scopes mapped to the profile attributes they release as
+    /// claims in ID tokens and userinfo (standard scopes are built in)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[validate(custom(function = "validate_custom_scope_claims"))]
+    pub custom_scope_claims: BTreeMap<String, Vec<String>>,
+    /// Per-user concurrent session limit (default: 10, evicting the oldest)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub session_limit: Option<SessionLimitPolicy>,
+    /// Overrides of the platform membership and role caps. Only platform
+    /// admins can change them; tenant owners' updates keep the stored value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cardinality_limits: Option<CardinalityLimits>,
+    /// Email members about new sign-ins (default: true). Members still get
+    /// them while any other tenant of theirs keeps them on.
+    #[serde(default = "default_login_notifications")]
+    pub login_notifications: bool,
+    /// Warn, lock and delete members who stop signing in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub inactivity_policy: Option<InactivityPolicy>,
+    /// Periodic digest of security alerts for admins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub security_digest: Option<SecurityDigestPolicy>,
+    /// Require members to enroll MFA by a deadline
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(nested)]
+    pub mfa_enrollment_campaign: Option<MfaEnrollmentCampaign>,
+    /// Usernames and phone numbers members can sign in with besides email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login_identifiers: Option<LoginIdentifierSettings>,
 }
 
 fn default_session_timeout() -> i64 {
     3600 // 1 hour
 }
 
+fn default_login_notifications() -> bool {
+    true
+}
+
 impl Default for TenantSettings {
     fn default() -> Self {
         Self {
@@ -112,6 +164,16 @@ impl Default for TenantSettings {
             allowed_auth_methods: Vec::new(),
             session_timeout_secs: default_session_timeout(),
             branding: TenantBranding::default(),
+            default_locale: None,
+            token_ttls: TokenTtlOverrides::default(),
+            custom_scope_claims: BTreeMap::new(),
+            session_limit: None,
+            cardinality_limits: None,
+            login_notifications: default_login_notifications(),
+            inactivity_policy: None,
+            security_digest: None,
+            mfa_enrollment_campaign: None,
+            login_identifiers: None,
         }
     }
 }
@@ -121,6 +183,13 @@ pub struct TenantBranding {
     pub primary_color: Option<String>,
     #[validate(custom(function = "validate_branding_logo_url"))]
     pub logo_url: Option<String>,
+    /// Support contact shown to members instead of the platform's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(email, length(max = 320))]
+    pub support_email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(url, length(max = 2048))]
+    pub support_url: Option<String>,
 }
 
 /// Validate branding logo_url - delegates to strict SSRF validation
@@ -144,6 +213,13 @@ pub struct Tenant {
     pub status: TenantStatus,
     #[sqlx(json, default)]
     pub password_policy: Option<PasswordPolicy>,
+    /// Data residency region holding this tenant's data (None = home region)
+    #[serde(default)]
+    #[sqlx(default)]
+    pub data_region: Option<String>,
+    /// Optimistic concurrency version, bumped on every update (exposed as ETag)
+    #[serde(default)]
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -160,6 +236,8 @@ impl Default for Tenant {
             settings: TenantSettings::default(),
             status: TenantStatus::default(),
             password_policy: None,
+            data_region: None,
+            version: 1,
             created_at: now,
             updated_at: now,
         }
@@ -167,10 +245,12 @@ impl Default for Tenant {
 }
 
 /// Input for creating a new tenant
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateTenantInput {
     #[validate(length(min = 1, max = 255), custom(function = "validate_no_html"))]
     pub name: String,
+    /// Omit (or send empty) to derive a unique slug from `name`
+    #[serde(default)]
     #[validate(length(min = 1, max = 63), custom(function = "validate_slug"))]
     pub slug: String,
     #[validate(custom(function = "validate_domain_format"))]
@@ -179,6 +259,10 @@ pub struct CreateTenantInput {
     pub logo_url: Option<String>,
     #[validate(nested)]
     pub settings: Option<TenantSettings>,
+    /// Pin the tenant's data to a configured region (defaults to the home region)
+    #[serde(default)]
+    #[validate(custom(function = "validate_data_region"))]
+    pub data_region: Option<String>,
 }
 
 /// Input for self-service organization creation (B2B onboarding)
@@ -186,6 +270,8 @@ pub struct CreateTenantInput {
 pub struct CreateOrganizationInput {
     #[validate(length(min = 1, max = 255), custom(function = "validate_no_html"))]
     pub name: String,
+    /// Omit (or send empty) to derive a unique slug from `name`
+    #[serde(default)]
     #[validate(length(min = 1, max = 63), custom(function = "validate_slug"))]
     pub slug: String,
     #[validate(
@@ -197,6 +283,61 @@ pub struct CreateOrganizationInput {
     pub logo_url: Option<String>,
 }
 
+/// How long a retired slug keeps resolving to the renamed tenant
+pub const SLUG_REDIRECT_GRACE_DAYS: i64 = 90;
+
+/// Length of generated slugs before a collision suffix is appended, so that
+/// base plus `-xxxxxx` stays within the 63 character limit
+const SLUGIFY_MAX_LEN: usize = 56;
+
+/// Derive a slug from a display name: lowercase ASCII alphanumerics joined by
+/// single hyphens. Names without any usable character become `tenant`.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= SLUGIFY_MAX_LEN {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "tenant".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Input for renaming a tenant. Changing the slug keeps the old one
+/// resolving for [`SLUG_REDIRECT_GRACE_DAYS`].
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct RenameTenantInput {
+    #[validate(length(min = 1, max = 255), custom(function = "validate_no_html"))]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 63), custom(function = "validate_slug"))]
+    pub slug: Option<String>,
+}
+
+/// Retired slug of a renamed tenant, still resolvable until `expires_at`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TenantSlugRedirect {
+    pub old_slug: String,
+    pub tenant_id: StringUuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of a rename; `slug_redirect` is set when the slug changed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TenantRenameResult {
+    pub tenant: Tenant,
+    pub slug_redirect: Option<TenantSlugRedirect>,
+}
+
 /// Validate slug format (lowercase alphanumeric with hyphens)
 fn validate_slug(slug: &str) -> Result<(), validator::ValidationError> {
     if SLUG_REGEX.is_match(slug) {
@@ -206,6 +347,21 @@ fn validate_slug(slug: &str) -> Result<(), validator::ValidationError> {
     }
 }
 
+/// Validate a data region name (lowercase alphanumeric with hyphens, max 32 chars)
+pub fn validate_data_region(region: &str) -> Result<(), validator::ValidationError> {
+    let valid = !region.is_empty()
+        && region.len() <= 32
+        && !region.starts_with('-')
+        && region
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("invalid_data_region"))
+    }
+}
+
 /// Validate domain format (e.g. "acme.com")
 fn validate_domain_format(domain: &str) -> Result<(), validator::ValidationError> {
     if domain.is_empty() {
@@ -223,6 +379,53 @@ fn validate_domain_format(domain: &str) -> Result<(), validator::ValidationError
     Ok(())
 }
 
+/// Validate that a locale tag maps to a supported locale
+fn validate_supported_locale(locale: &str) -> Result<(), validator::ValidationError> {
+    if crate::i18n::Locale::from_tag(locale).is_some() {
+        Ok(())
+    } else {
+        let mut err = validator::ValidationError::new("unsupported_locale");
+        err.message = Some("Unsupported locale (expected en-US, zh-CN or ja)".into());
+        Err(err)
+    }
+}
+
+/// Validate custom scope claim mappings: custom scope names map to at most
+/// 32 profile attribute names, none of which is a standard claim
+fn validate_custom_scope_claims(
+    mappings: &BTreeMap<String, Vec<String>>,
+) -> Result<(), validator::ValidationError> {
+    let invalid = |message: String| {
+        let mut err = validator::ValidationError::new("invalid_custom_scope_claims");
+        err.message = Some(message.into());
+        err
+    };
+    if mappings.len() > 50 {
+        return Err(invalid(
+            "At most 50 custom scopes can be mapped".to_string(),
+        ));
+    }
+    for (scope, attributes) in mappings {
+        super::oauth_scope::validate_scope_name(scope).map_err(invalid)?;
+        if attributes.is_empty() || attributes.len() > 32 {
+            return Err(invalid(format!(
+                "Scope '{}' must map to 1-32 attributes",
+                scope
+            )));
+        }
+        for attribute in attributes {
+            super::progressive_profiling::validate_profile_field_name(attribute)?;
+            if super::oauth_scope::is_standard_claim(attribute) {
+                return Err(invalid(format!(
+                    "'{}' is a standard claim released by a standard scope",
+                    attribute
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Validate that a string does not contain HTML tags (prevent stored XSS)
 fn validate_no_html(value: &str) -> Result<(), validator::ValidationError> {
     if value.contains('<') || value.contains('>') {
@@ -235,7 +438,7 @@ fn validate_no_html(value: &str) -> Result<(), validator::ValidationError> {
 }
 
 /// Input for updating a tenant
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateTenantInput {
     #[validate(length(min = 1, max = 255), custom(function = "validate_no_html"))]
     pub name: Option<String>,
@@ -316,6 +519,7 @@ mod tests {
         assert!(settings.allowed_auth_methods.is_empty());
         assert!(settings.branding.primary_color.is_none());
         assert!(settings.branding.logo_url.is_none());
+        assert!(settings.login_notifications);
     }
 
     #[test]
@@ -327,7 +531,18 @@ mod tests {
             branding: TenantBranding {
                 primary_color: Some("#FF5733".to_string()),
                 logo_url: Some("https://example.com/logo.png".to_string()),
+                ..Default::default()
             },
+            default_locale: Some("ja".to_string()),
+            token_ttls: Default::default(),
+            custom_scope_claims: Default::default(),
+            session_limit: None,
+            cardinality_limits: None,
+            login_notifications: true,
+            inactivity_policy: None,
+            security_digest: None,
+            mfa_enrollment_campaign: None,
+            login_identifiers: None,
         };
 
         assert!(settings.require_mfa);
@@ -473,6 +688,7 @@ mod tests {
             domain: Some("example.com".to_string()),
             logo_url: Some("https://example.com/logo.png".to_string()),
             settings: Some(TenantSettings::default()),
+            data_region: None,
         };
 
         assert!(input.validate().is_ok());
@@ -486,6 +702,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         assert!(input.validate().is_ok());
@@ -499,6 +716,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         assert!(input.validate().is_err());
@@ -512,11 +730,37 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         assert!(input.validate().is_err());
     }
 
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Acme Corp"), "acme-corp");
+        assert_eq!(slugify("  Müller & Söhne GmbH!  "), "m-ller-s-hne-gmbh");
+        assert_eq!(slugify("R&D -- Team 42"), "r-d-team-42");
+        assert_eq!(slugify("日本"), "tenant");
+        let long = slugify(&"a ".repeat(100));
+        assert!(long.len() <= 56);
+        assert!(SLUG_REGEX.is_match(&long));
+    }
+
+    #[test]
+    fn test_rename_tenant_input_validation() {
+        let valid = RenameTenantInput {
+            name: Some("Acme".to_string()),
+            slug: Some("acme-2".to_string()),
+        };
+        assert!(valid.validate().is_ok());
+        let invalid = RenameTenantInput {
+            name: None,
+            slug: Some("Acme 2".to_string()),
+        };
+        assert!(invalid.validate().is_err());
+    }
+
     #[test]
     fn test_create_tenant_input_invalid_slug() {
         let input = CreateTenantInput {
@@ -525,6 +769,7 @@ mod tests {
             domain: None,
             logo_url: None,
             settings: None,
+            data_region: None,
         };
 
         assert!(input.validate().is_err());
@@ -573,6 +818,16 @@ mod tests {
             allowed_auth_methods: vec!["password".to_string()],
             session_timeout_secs: 3600,
             branding: TenantBranding::default(),
+            default_locale: None,
+            token_ttls: Default::default(),
+            custom_scope_claims: Default::default(),
+            session_limit: None,
+            cardinality_limits: None,
+            login_notifications: true,
+            inactivity_policy: None,
+            security_digest: None,
+            mfa_enrollment_campaign: None,
+            login_identifiers: None,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -676,6 +931,7 @@ mod tests {
                 branding: TenantBranding {
                     primary_color: None,
                     logo_url: Some("javascript:alert(1)".to_string()),
+                    ..Default::default()
                 },
                 ..Default::default()
             }),
@@ -693,6 +949,7 @@ mod tests {
                 branding: TenantBranding {
                     primary_color: None,
                     logo_url: Some("https://example.com/logo.png".to_string()),
+                    ..Default::default()
                 },
                 ..Default::default()
             }),
@@ -710,6 +967,7 @@ mod tests {
                 branding: TenantBranding {
                     primary_color: None,
                     logo_url: Some("data:text/html,<script>alert(1)</script>".to_string()),
+                    ..Default::default()
                 },
                 ..Default::default()
             }),
@@ -819,4 +1077,37 @@ mod tests {
         };
         assert!(input.validate().is_ok());
     }
+
+    #[test]
+    fn test_default_locale_validation() {
+        let settings = |locale: &str| TenantSettings {
+            default_locale: Some(locale.to_string()),
+            ..Default::default()
+        };
+        assert!(settings("zh-CN").validate().is_ok());
+        assert!(settings("ja").validate().is_ok());
+        assert!(settings("fr-FR").validate().is_err());
+    }
+
+    #[test]
+    fn test_custom_scope_claims_validation() {
+        let settings = |scope: &str, attributes: &[&str]| TenantSettings {
+            custom_scope_claims: BTreeMap::from([(
+                scope.to_string(),
+                attributes.iter().map(|a| a.to_string()).collect(),
+            )]),
+            ..Default::default()
+        };
+        assert!(settings("billing:profile", &["vat_id", "company_name"])
+            .validate()
+            .is_ok());
+        assert!(settings("profile", &["vat_id"]).validate().is_err());
+        assert!(settings("billing:profile", &[]).validate().is_err());
+        assert!(settings("billing:profile", &["Not Valid"])
+            .validate()
+            .is_err());
+        assert!(settings("billing:profile", &["phone_number"])
+            .validate()
+            .is_err());
+    }
 }
@@ -0,0 +1,91 @@
+//! Per-tenant data keys for stored secrets
+//!
+//! Webhook secrets are sealed with a data key derived from the master key,
+//! the tenant and the tenant's key version (see
+//! [`crate::crypto::TenantKeyring`]). Rotating a tenant's key bumps the
+//! version and queues a `key_rotation` job that re-encrypts the tenant's
+//! secrets in batches.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+/// Current data key version of a tenant
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct TenantKeyVersion {
+    pub tenant_id: StringUuid,
+    pub key_version: u32,
+    /// `None` until the key is first rotated
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+/// Secret stored in the database, as stored
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct StoredSecret {
+    /// Webhook ID
+    pub id: StringUuid,
+    /// Owning tenant; `None` for platform webhooks
+    pub tenant_id: Option<StringUuid>,
+    pub secret: String,
+}
+
+/// Stored secrets of a tenant by the key they are sealed with
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct SecretKeyCounts {
+    pub total: u64,
+    /// Sealed with the current key version
+    pub current: u64,
+    /// Sealed with an older key version
+    pub outdated: u64,
+    /// Stored before encryption was enabled
+    pub plaintext: u64,
+}
+
+/// Data key status of a tenant
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TenantKeyStatus {
+    pub tenant_id: StringUuid,
+    pub key_version: u32,
+    pub rotated_at: Option<DateTime<Utc>>,
+    /// Whether a master key is configured; without one secrets are stored
+    /// in plaintext
+    pub encryption_enabled: bool,
+    pub secrets: SecretKeyCounts,
+}
+
+/// Outcome of re-encrypting stored secrets
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ReencryptReport {
+    /// Tenant whose secrets were re-encrypted; `None` for all secrets
+    pub tenant_id: Option<StringUuid>,
+    /// Key version the tenant's secrets are now sealed with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_version: Option<u32>,
+    pub reencrypted: u64,
+    /// Secrets changed by a concurrent update; they are already sealed with
+    /// the current key
+    pub skipped: u64,
+    /// Secrets that could not be decrypted (e.g. sealed with an unknown
+    /// master key)
+    pub failed: u64,
+}
+
+/// Result of re-encrypting one stored secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReencryptOutcome {
+    Reencrypted,
+    /// Changed by a concurrent update, which sealed it with the current key
+    Skipped,
+    /// Could not be decrypted
+    Failed,
+}
+
+/// Payload of a `key_rotation` job; the job's tenant (or every tenant and
+/// the platform when it has none) is re-encrypted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotationJobPayload {
+    pub batch_size: u32,
+    pub pause_ms: u64,
+}
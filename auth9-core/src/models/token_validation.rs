@@ -0,0 +1,216 @@
+//! Token validation policy profiles
+//!
+//! A profile tunes how strictly tenant access tokens issued for a service are
+//! validated: allowed clock skew, claims that must be present, accepted
+//! signing algorithms and the maximum age of a token. Profiles are defined by
+//! name in configuration (`JWT_VALIDATION_PROFILES`) and assigned to services
+//! in the database; services without an assignment use `default`.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// Profile used by services without an assignment
+pub const DEFAULT_VALIDATION_PROFILE: &str = "default";
+
+/// Signing algorithms a profile may accept
+pub const SUPPORTED_TOKEN_ALGORITHMS: &[&str] = &["HS256", "RS256"];
+
+/// How tenant access tokens of a service are validated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TokenValidationProfile {
+    /// Tolerated clock difference when checking `exp` and `nbf`
+    #[serde(default = "default_clock_skew_secs")]
+    #[validate(range(max = 300))]
+    pub clock_skew_secs: u64,
+    /// Claims that must be present and non-empty (e.g. `sid`, `tenant_id`)
+    #[serde(default)]
+    #[validate(custom(function = "validate_claim_names"))]
+    pub required_claims: Vec<String>,
+    /// Accepted signing algorithms; empty accepts the platform's algorithm
+    #[serde(default)]
+    #[validate(custom(function = "validate_algorithms"))]
+    pub allowed_algorithms: Vec<String>,
+    /// Reject tokens issued (`iat`) longer ago than this, whatever their expiry
+    #[serde(default)]
+    #[validate(range(min = 60))]
+    pub max_token_age_secs: Option<u64>,
+}
+
+fn default_clock_skew_secs() -> u64 {
+    5
+}
+
+impl Default for TokenValidationProfile {
+    fn default() -> Self {
+        Self {
+            clock_skew_secs: default_clock_skew_secs(),
+            required_claims: Vec::new(),
+            allowed_algorithms: Vec::new(),
+            max_token_age_secs: None,
+        }
+    }
+}
+
+fn validate_claim_names(claims: &[String]) -> Result<(), ValidationError> {
+    if claims.iter().any(|c| c.trim().is_empty()) {
+        return Err(ValidationError::new("empty_claim_name"));
+    }
+    Ok(())
+}
+
+fn validate_algorithms(algorithms: &[String]) -> Result<(), ValidationError> {
+    if algorithms
+        .iter()
+        .any(|a| !SUPPORTED_TOKEN_ALGORITHMS.contains(&a.as_str()))
+    {
+        return Err(ValidationError::new("unsupported_algorithm"));
+    }
+    Ok(())
+}
+
+impl TokenValidationProfile {
+    /// Whether tokens signed with `algorithm` (e.g. "RS256") are accepted
+    pub fn accepts_algorithm(&self, algorithm: &str) -> bool {
+        self.allowed_algorithms.is_empty() || self.allowed_algorithms.iter().any(|a| a == algorithm)
+    }
+
+    /// Check the profile's claim rules against verified claims. Signature,
+    /// issuer, audience and expiry are checked by the JWT verifier.
+    pub fn check_claims(&self, claims: &serde_json::Value, now: i64) -> Result<(), String> {
+        for name in &self.required_claims {
+            let present = match claims.get(name) {
+                None | Some(serde_json::Value::Null) => false,
+                Some(serde_json::Value::String(s)) => !s.is_empty(),
+                Some(serde_json::Value::Array(a)) => !a.is_empty(),
+                Some(_) => true,
+            };
+            if !present {
+                return Err(format!("Token is missing required claim '{}'", name));
+            }
+        }
+        if let Some(max_age) = self.max_token_age_secs {
+            let issued_at = claims
+                .get("iat")
+                .and_then(serde_json::Value::as_i64)
+                .ok_or_else(|| "Token has no issue time".to_string())?;
+            let skew = self.clock_skew_secs as i64;
+            if issued_at > now + skew || now - issued_at > max_age as i64 + skew {
+                return Err("Token is too old".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A service's profile assignment
+#[derive(Debug, Clone, FromRow)]
+pub struct TokenValidationAssignment {
+    pub service_id: StringUuid,
+    pub profile_name: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Input for assigning a validation profile to a service
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetTokenValidationProfileInput {
+    /// Profile name from `JWT_VALIDATION_PROFILES`; `null` restores `default`
+    pub profile: Option<String>,
+}
+
+/// The validation profile in effect for a service
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ServiceTokenValidationProfile {
+    pub service_id: StringUuid,
+    /// Assigned profile name, or `default`
+    pub profile_name: String,
+    pub profile: TokenValidationProfile,
+    /// When the assignment last changed; absent for the default
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_profile_defaults_match_platform_validation() {
+        let profile: TokenValidationProfile = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(profile, TokenValidationProfile::default());
+        assert_eq!(profile.clock_skew_secs, 5);
+        assert!(profile.accepts_algorithm("RS256"));
+    }
+
+    #[test]
+    fn test_profile_validation_bounds() {
+        let profile = |value| serde_json::from_value::<TokenValidationProfile>(value).unwrap();
+        assert!(profile(json!({"clock_skew_secs": 600})).validate().is_err());
+        assert!(profile(json!({"allowed_algorithms": ["none"]}))
+            .validate()
+            .is_err());
+        assert!(profile(json!({"max_token_age_secs": 10}))
+            .validate()
+            .is_err());
+        assert!(
+            profile(json!({"required_claims": ["sid"], "allowed_algorithms": ["RS256"]}))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            serde_json::from_value::<TokenValidationProfile>(json!({"clock_skew": 5})).is_err()
+        );
+    }
+
+    #[test]
+    fn test_required_claims() {
+        let profile = TokenValidationProfile {
+            required_claims: vec!["sid".to_string(), "roles".to_string()],
+            ..Default::default()
+        };
+        assert!(profile
+            .check_claims(&json!({"sid": "s1", "roles": ["admin"]}), 0)
+            .is_ok());
+        assert!(profile
+            .check_claims(&json!({"sid": "", "roles": ["admin"]}), 0)
+            .is_err());
+        assert!(profile
+            .check_claims(&json!({"sid": "s1", "roles": []}), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_max_token_age() {
+        let profile = TokenValidationProfile {
+            max_token_age_secs: Some(300),
+            clock_skew_secs: 5,
+            ..Default::default()
+        };
+        let now = 1_000_000;
+        assert!(profile
+            .check_claims(&json!({"iat": now - 300}), now)
+            .is_ok());
+        assert!(profile
+            .check_claims(&json!({"iat": now - 400}), now)
+            .is_err());
+        // Issued in the future beyond the skew
+        assert!(profile
+            .check_claims(&json!({"iat": now + 60}), now)
+            .is_err());
+        assert!(profile.check_claims(&json!({}), now).is_err());
+    }
+
+    #[test]
+    fn test_algorithm_allowlist() {
+        let profile = TokenValidationProfile {
+            allowed_algorithms: vec!["RS256".to_string()],
+            ..Default::default()
+        };
+        assert!(profile.accepts_algorithm("RS256"));
+        assert!(!profile.accepts_algorithm("HS256"));
+    }
+}
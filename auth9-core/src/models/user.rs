@@ -23,6 +23,12 @@ pub struct User {
     pub email_otp_enabled: bool,
     pub password_changed_at: Option<DateTime<Utc>>,
     pub locked_until: Option<DateTime<Utc>>,
+    /// Preferred locale for emails and messages (`None` = negotiate per request)
+    #[sqlx(default)]
+    pub locale: Option<String>,
+    /// Optimistic concurrency version, bumped on every update (exposed as ETag)
+    #[serde(default)]
+    pub version: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -42,6 +48,8 @@ impl Default for User {
             email_otp_enabled: false,
             password_changed_at: None,
             locked_until: None,
+            locale: None,
+            version: 1,
             created_at: now,
             updated_at: now,
         }
@@ -70,7 +78,7 @@ pub struct TenantUser {
 }
 
 /// Input for creating a new user
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateUserInput {
     #[validate(email)]
     pub email: String,
@@ -105,7 +113,7 @@ fn validate_avatar_url(url: &str) -> Result<(), validator::ValidationError> {
 }
 
 /// Input for updating a user
-#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateUserInput {
     #[validate(length(max = 255), custom(function = "validate_no_html"))]
     pub display_name: Option<String>,
@@ -117,6 +125,13 @@ pub struct UpdateUserInput {
     pub avatar_url: Option<String>,
 }
 
+/// Input for setting the current user's preferred locale
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateUserLocaleInput {
+    /// Preferred locale; `null` clears the preference
+    pub locale: Option<crate::i18n::Locale>,
+}
+
 /// Input for adding user to tenant
 #[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct AddUserToTenantInput {
@@ -163,6 +178,76 @@ pub struct TenantInfo {
     pub status: String,
 }
 
+/// Subset of a tenant's members targeted by a bulk operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserCohort {
+    /// Every member of the tenant
+    All,
+    /// Members holding this `role_in_tenant`
+    Role { role: String },
+    /// Members whose last successful login is older than `before`, including
+    /// members who never logged in
+    LastLoginBefore { before: DateTime<Utc> },
+}
+
+/// Source feeding a user activity timeline entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserTimelineSource {
+    /// Login attempt recorded in `login_events`
+    Login,
+    /// Audit entry where the user is the actor or the subject
+    Audit,
+    /// Session created or revoked
+    Session,
+    /// Role granted to the user in a tenant
+    RoleGrant,
+}
+
+impl UserTimelineSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Login => "login",
+            Self::Audit => "audit",
+            Self::Session => "session",
+            Self::RoleGrant => "role_grant",
+        }
+    }
+}
+
+impl std::str::FromStr for UserTimelineSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "login" => Ok(Self::Login),
+            "audit" => Ok(Self::Audit),
+            "session" => Ok(Self::Session),
+            "role_grant" => Ok(Self::RoleGrant),
+            _ => Err(format!("Unknown timeline source: {}", s)),
+        }
+    }
+}
+
+/// One entry of a user's activity timeline (newest first)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserTimelineEntry {
+    pub source: UserTimelineSource,
+    /// Event within the source: login event type, audit action,
+    /// `session_created` / `session_revoked` or `role_granted`
+    pub event: String,
+    pub occurred_at: DateTime<Utc>,
+    /// ID of the underlying record in its source table
+    pub source_id: String,
+    /// Who performed the action (audit actor or role grantor), if known
+    pub actor_id: Option<StringUuid>,
+    pub tenant_id: Option<StringUuid>,
+    pub ip_address: Option<String>,
+    /// Source-specific fields (device, location, resource, role, ...)
+    pub details: serde_json::Value,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
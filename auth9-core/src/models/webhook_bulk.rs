@@ -0,0 +1,238 @@
+//! Bulk webhook subscription management
+//!
+//! Operators managing many tenants copy subscriptions between tenants,
+//! enable or disable many webhooks at once and create subscriptions from a
+//! catalog of templates. Every operation reports a result per item and keeps
+//! going when an item fails.
+
+use super::common::StringUuid;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+/// A predefined webhook subscription
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookTemplate {
+    #[schema(value_type = String)]
+    pub key: &'static str,
+    #[schema(value_type = String)]
+    pub name: &'static str,
+    #[schema(value_type = String)]
+    pub description: &'static str,
+    #[schema(value_type = Vec<String>)]
+    pub events: &'static [&'static str],
+    /// CEL filter applied to subscriptions created from the template
+    #[schema(value_type = Option<String>)]
+    pub filter_expression: Option<&'static str>,
+}
+
+/// Templates offered by `POST /api/v1/webhooks/bulk/from-template`
+pub const WEBHOOK_TEMPLATES: &[WebhookTemplate] = &[
+    WebhookTemplate {
+        key: "user-lifecycle",
+        name: "User lifecycle",
+        description: "User creation, updates and deletion",
+        events: &["user.created", "user.updated", "user.deleted"],
+        filter_expression: None,
+    },
+    WebhookTemplate {
+        key: "security",
+        name: "Security events",
+        description: "Security alerts, failed logins and MFA changes",
+        events: &[
+            "security.alert",
+            "login.failed",
+            "mfa.enabled",
+            "mfa.disabled",
+            "password.changed",
+        ],
+        filter_expression: None,
+    },
+    WebhookTemplate {
+        key: "scim-provisioning",
+        name: "SCIM provisioning",
+        description: "Users and groups provisioned through SCIM",
+        events: &[
+            "scim.user.provisioned",
+            "scim.user.updated",
+            "scim.user.deprovisioned",
+            "scim.group.created",
+            "scim.group.updated",
+            "scim.group.deleted",
+        ],
+        filter_expression: None,
+    },
+    WebhookTemplate {
+        key: "sign-in-activity",
+        name: "Sign-in activity",
+        description: "Successful and failed sign-ins, including federated ones",
+        events: &[
+            "login.success",
+            "login.failed",
+            "federation.login.success",
+            "federation.login.failed",
+        ],
+        filter_expression: None,
+    },
+];
+
+/// Look up a catalog template by key
+pub fn find_webhook_template(key: &str) -> Option<&'static WebhookTemplate> {
+    WEBHOOK_TEMPLATES.iter().find(|t| t.key == key)
+}
+
+fn validate_unique_ids(ids: &[StringUuid]) -> Result<(), ValidationError> {
+    let mut seen = std::collections::HashSet::new();
+    if ids.iter().any(|id| !seen.insert(*id)) {
+        return Err(ValidationError::new("duplicate_id"));
+    }
+    Ok(())
+}
+
+/// Copy webhooks of one tenant to other tenants
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CopyWebhooksInput {
+    pub source_tenant_id: StringUuid,
+    /// Webhooks to copy; all webhooks of the source tenant when omitted
+    #[serde(default)]
+    #[validate(length(max = 100), custom(function = "validate_unique_ids"))]
+    pub webhook_ids: Option<Vec<StringUuid>>,
+    #[validate(length(min = 1, max = 100), custom(function = "validate_unique_ids"))]
+    pub target_tenant_ids: Vec<StringUuid>,
+}
+
+/// Enable or disable many webhooks, possibly of different tenants
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct BulkSetWebhooksEnabledInput {
+    #[validate(length(min = 1, max = 100), custom(function = "validate_unique_ids"))]
+    pub webhook_ids: Vec<StringUuid>,
+    pub enabled: bool,
+}
+
+/// Create a webhook from a catalog template in each tenant
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhooksFromTemplateInput {
+    /// Template key, see `GET /api/v1/webhook-templates`
+    pub template: String,
+    #[validate(length(min = 1, max = 100), custom(function = "validate_unique_ids"))]
+    pub tenant_ids: Vec<StringUuid>,
+    /// Receiver URL used for every created webhook
+    pub url: String,
+    /// Defaults to the template name
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Result of one item of a bulk operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkWebhookItemStatus {
+    Created,
+    Updated,
+    /// Nothing to do, e.g. the target already has the same subscription
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkWebhookItemResult {
+    pub tenant_id: Option<StringUuid>,
+    /// Webhook copied from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_webhook_id: Option<StringUuid>,
+    /// Webhook created or updated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_id: Option<StringUuid>,
+    pub status: BulkWebhookItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkWebhookItemResult {
+    pub fn failed(tenant_id: Option<StringUuid>, error: impl Into<String>) -> Self {
+        Self {
+            tenant_id,
+            source_webhook_id: None,
+            webhook_id: None,
+            status: BulkWebhookItemStatus::Failed,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Per-item results of a bulk operation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkWebhookResult {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkWebhookItemResult>,
+}
+
+impl From<Vec<BulkWebhookItemResult>> for BulkWebhookResult {
+    fn from(items: Vec<BulkWebhookItemResult>) -> Self {
+        let failed = items
+            .iter()
+            .filter(|i| i.status == BulkWebhookItemStatus::Failed)
+            .count();
+        Self {
+            succeeded: items.len() - failed,
+            failed,
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::analytics::WEBHOOK_EVENTS;
+
+    #[test]
+    fn test_templates_are_valid() {
+        assert!(WEBHOOK_TEMPLATES
+            .iter()
+            .all(|t| t.events.iter().all(|e| WEBHOOK_EVENTS.contains(e))));
+        let mut keys = std::collections::HashSet::new();
+        assert!(WEBHOOK_TEMPLATES.iter().all(|t| keys.insert(t.key)));
+        assert!(find_webhook_template("security").is_some());
+        assert!(find_webhook_template("unknown").is_none());
+    }
+
+    #[test]
+    fn test_bulk_inputs_reject_duplicates_and_empty_lists() {
+        let id = StringUuid::new_v4();
+        let input = BulkSetWebhooksEnabledInput {
+            webhook_ids: vec![id, id],
+            enabled: false,
+        };
+        assert!(input.validate().is_err());
+        let input = CopyWebhooksInput {
+            source_tenant_id: id,
+            webhook_ids: None,
+            target_tenant_ids: vec![],
+        };
+        assert!(input.validate().is_err());
+    }
+
+    #[test]
+    fn test_result_counts() {
+        let result = BulkWebhookResult::from(vec![
+            BulkWebhookItemResult::failed(None, "boom"),
+            BulkWebhookItemResult {
+                tenant_id: None,
+                source_webhook_id: None,
+                webhook_id: Some(StringUuid::new_v4()),
+                status: BulkWebhookItemStatus::Skipped,
+                error: None,
+            },
+        ]);
+        assert_eq!(result.succeeded, 1);
+        assert_eq!(result.failed, 1);
+    }
+}
@@ -0,0 +1,203 @@
+//! Webhook delivery log types
+//!
+//! Each attempt to deliver an event to a webhook endpoint is recorded so
+//! integrators can see what was sent, how the receiver answered and how long
+//! it took, and replay a delivery after fixing their endpoint.
+
+use super::common::StringUuid;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Maximum number of response body characters kept per delivery
+pub const RESPONSE_SNIPPET_MAX_CHARS: usize = 1024;
+
+/// What caused a delivery attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookDeliverySource {
+    /// A real event dispatched by Auth9
+    Event,
+    /// `POST .../test`
+    Test,
+    /// `POST .../ping`
+    Ping,
+    /// Replay of an earlier delivery
+    Replay,
+    /// Synthetic event sent from the console
+    Synthetic,
+}
+
+impl std::str::FromStr for WebhookDeliverySource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "event" => Ok(Self::Event),
+            "test" => Ok(Self::Test),
+            "ping" => Ok(Self::Ping),
+            "replay" => Ok(Self::Replay),
+            "synthetic" => Ok(Self::Synthetic),
+            _ => Err(format!("Unknown webhook delivery source: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for WebhookDeliverySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Event => write!(f, "event"),
+            Self::Test => write!(f, "test"),
+            Self::Ping => write!(f, "ping"),
+            Self::Replay => write!(f, "replay"),
+            Self::Synthetic => write!(f, "synthetic"),
+        }
+    }
+}
+
+impl<'r> sqlx::Decode<'r, sqlx::MySql> for WebhookDeliverySource {
+    fn decode(value: sqlx::mysql::MySqlValueRef<'r>) -> Result<Self, sqlx::error::BoxDynError> {
+        let s: String = sqlx::Decode::<'r, sqlx::MySql>::decode(value)?;
+        s.parse().map_err(|e: String| e.into())
+    }
+}
+
+impl sqlx::Type<sqlx::MySql> for WebhookDeliverySource {
+    fn type_info() -> sqlx::mysql::MySqlTypeInfo {
+        <String as sqlx::Type<sqlx::MySql>>::type_info()
+    }
+
+    fn compatible(ty: &sqlx::mysql::MySqlTypeInfo) -> bool {
+        <String as sqlx::Type<sqlx::MySql>>::compatible(ty)
+    }
+}
+
+impl<'q> sqlx::Encode<'q, sqlx::MySql> for WebhookDeliverySource {
+    fn encode_by_ref(
+        &self,
+        buf: &mut Vec<u8>,
+    ) -> Result<sqlx::encode::IsNull, Box<dyn std::error::Error + Send + Sync>> {
+        let s = self.to_string();
+        <&str as sqlx::Encode<sqlx::MySql>>::encode_by_ref(&s.as_str(), buf)
+    }
+}
+
+/// One delivery attempt to a webhook endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: StringUuid,
+    pub webhook_id: StringUuid,
+    /// `None` for deliveries of platform webhooks
+    pub tenant_id: Option<StringUuid>,
+    pub event_type: String,
+    /// Raw JSON body that was signed and sent
+    pub payload: String,
+    pub source: WebhookDeliverySource,
+    /// 1-based attempt number within a retried dispatch
+    pub attempt: i32,
+    pub success: bool,
+    pub status_code: Option<i32>,
+    pub response_time_ms: Option<i64>,
+    /// Start of the receiver's response body
+    pub response_snippet: Option<String>,
+    pub error: Option<String>,
+    /// Delivery this one replayed
+    pub replay_of: Option<StringUuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Default for WebhookDelivery {
+    fn default() -> Self {
+        Self {
+            id: StringUuid::new_v4(),
+            webhook_id: StringUuid::new_v4(),
+            tenant_id: Some(StringUuid::new_v4()),
+            event_type: String::new(),
+            payload: String::new(),
+            source: WebhookDeliverySource::Event,
+            attempt: 1,
+            success: false,
+            status_code: None,
+            response_time_ms: None,
+            response_snippet: None,
+            error: None,
+            replay_of: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Truncate a response body to [`RESPONSE_SNIPPET_MAX_CHARS`] characters
+pub fn response_snippet(body: &str) -> String {
+    match body.char_indices().nth(RESPONSE_SNIPPET_MAX_CHARS) {
+        Some((idx, _)) => format!("{}…", &body[..idx]),
+        None => body.to_string(),
+    }
+}
+
+/// Filters for listing deliveries
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct WebhookDeliveryFilter {
+    /// Only successful (`true`) or failed (`false`) attempts
+    pub success: Option<bool>,
+    pub event_type: Option<String>,
+}
+
+/// Input for sending a synthetic event to a webhook
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct SendSyntheticEventInput {
+    /// Any event type, e.g. `user.created`
+    #[validate(length(min = 1, max = 128))]
+    pub event_type: String,
+    /// Event data; a sample object is sent when omitted
+    pub data: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivery_source_roundtrip() {
+        for source in [
+            WebhookDeliverySource::Event,
+            WebhookDeliverySource::Test,
+            WebhookDeliverySource::Ping,
+            WebhookDeliverySource::Replay,
+            WebhookDeliverySource::Synthetic,
+        ] {
+            assert_eq!(
+                source.to_string().parse::<WebhookDeliverySource>().unwrap(),
+                source
+            );
+        }
+        assert!("bogus".parse::<WebhookDeliverySource>().is_err());
+    }
+
+    #[test]
+    fn test_response_snippet_truncates_on_char_boundary() {
+        assert_eq!(response_snippet("ok"), "ok");
+
+        let long = "é".repeat(RESPONSE_SNIPPET_MAX_CHARS + 10);
+        let snippet = response_snippet(&long);
+        assert_eq!(snippet.chars().count(), RESPONSE_SNIPPET_MAX_CHARS + 1);
+        assert!(snippet.ends_with('…'));
+    }
+
+    #[test]
+    fn test_synthetic_event_input_validation() {
+        let input = SendSyntheticEventInput {
+            event_type: "user.created".to_string(),
+            data: None,
+        };
+        assert!(input.validate().is_ok());
+
+        let input = SendSyntheticEventInput {
+            event_type: String::new(),
+            data: None,
+        };
+        assert!(input.validate().is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Workload identity federation models
+//!
+//! CI pipelines authenticate with the OIDC token their platform issues for
+//! each job (GitHub Actions, GitLab CI, ...) instead of a stored client
+//! secret. A tenant's federation rule names the trusted issuer, the expected
+//! audience and which subjects and claims may act as one of its services;
+//! a matching token is exchanged for a short-lived service token.
+
+use super::common::{validate_url_no_ssrf, StringUuid};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Maximum number of federation rules per tenant
+pub const MAX_RULES_PER_TENANT: usize = 50;
+
+/// Lifetime of an exchanged service token when the rule sets none
+pub const DEFAULT_FEDERATED_TOKEN_TTL_SECS: u32 = 900;
+
+/// Issuer of GitHub Actions OIDC tokens
+pub const GITHUB_ACTIONS_ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+/// Issuer of GitLab.com CI OIDC tokens (`id_tokens`)
+pub const GITLAB_ISSUER: &str = "https://gitlab.com";
+
+/// RFC 8693 grant type of the token endpoint
+pub const TOKEN_EXCHANGE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:token-exchange";
+
+/// Subject token types accepted by the token exchange grant
+pub const FEDERATED_SUBJECT_TOKEN_TYPES: &[&str] = &[
+    "urn:ietf:params:oauth:token-type:id_token",
+    "urn:ietf:params:oauth:token-type:jwt",
+];
+
+/// Trust rule mapping external workload tokens to a service
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WorkloadIdentityRule {
+    pub id: StringUuid,
+    pub tenant_id: StringUuid,
+    /// Service the exchanged token is issued for
+    pub service_id: StringUuid,
+    pub name: String,
+    /// Issuer (`iss`) of the external token, e.g. `https://token.actions.githubusercontent.com`
+    pub issuer: String,
+    /// Required audience (`aud`) of the external token
+    pub audience: String,
+    /// Pattern the token's `sub` must match; `*` matches any characters,
+    /// e.g. `repo:acme/deploy:ref:refs/heads/main`
+    pub subject_pattern: String,
+    /// Further claims the token must carry, as claim name to pattern,
+    /// e.g. `{"repository_owner": "acme", "environment": "prod*"}`
+    #[sqlx(json)]
+    pub claim_conditions: BTreeMap<String, String>,
+    /// Lifetime of the issued service token
+    pub token_ttl_secs: u32,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkloadIdentityRule {
+    /// Whether the (verified) claims satisfy the subject pattern and every
+    /// claim condition. Issuer and audience are checked during verification.
+    pub fn matches_claims(&self, claims: &Map<String, Value>) -> bool {
+        let subject_matches = claims
+            .get("sub")
+            .and_then(claim_as_string)
+            .is_some_and(|sub| wildcard_match(&self.subject_pattern, &sub));
+        subject_matches
+            && self.claim_conditions.iter().all(|(name, pattern)| {
+                claims
+                    .get(name)
+                    .and_then(claim_as_string)
+                    .is_some_and(|value| wildcard_match(pattern, &value))
+            })
+    }
+}
+
+/// String form of a scalar claim; arrays and objects never match
+fn claim_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Match `value` against `pattern`, where `*` stands for any (possibly
+/// empty) run of characters and everything else matches literally
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if value.len() < first.len() + last.len() || !value.starts_with(first) || !value.ends_with(last)
+    {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// A subject pattern must pin a literal prefix: issuers such as GitHub
+/// Actions sign tokens for every repository on the platform
+fn validate_subject_pattern(pattern: &str) -> Result<(), ValidationError> {
+    if pattern.starts_with('*') {
+        let mut err = ValidationError::new("subject_pattern_unanchored");
+        err.message = Some("subject_pattern must start with a literal prefix".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_issuer(issuer: &str) -> Result<(), ValidationError> {
+    validate_url_no_ssrf(issuer)?;
+    if !issuer.starts_with("https://") {
+        let mut err = ValidationError::new("issuer_not_https");
+        err.message = Some("issuer must be an https URL".into());
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn validate_claim_conditions(conditions: &BTreeMap<String, String>) -> Result<(), ValidationError> {
+    if conditions.len() > 20 {
+        return Err(ValidationError::new("too_many_claim_conditions"));
+    }
+    let reserved = ["iss", "aud", "exp", "nbf", "iat"];
+    if conditions.iter().any(|(name, pattern)| {
+        name.is_empty() || reserved.contains(&name.as_str()) || pattern.len() > 512
+    }) {
+        return Err(ValidationError::new("invalid_claim_condition"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateWorkloadIdentityRuleInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    pub service_id: Uuid,
+    #[validate(length(max = 512), custom(function = "validate_issuer"))]
+    pub issuer: String,
+    #[validate(length(min = 1, max = 512))]
+    pub audience: String,
+    #[validate(
+        length(min = 1, max = 512),
+        custom(function = "validate_subject_pattern")
+    )]
+    pub subject_pattern: String,
+    #[serde(default)]
+    #[validate(custom(function = "validate_claim_conditions"))]
+    pub claim_conditions: BTreeMap<String, String>,
+    #[validate(range(min = 60, max = 3600))]
+    pub token_ttl_secs: Option<u32>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateWorkloadIdentityRuleInput {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 512))]
+    pub audience: Option<String>,
+    #[validate(
+        length(min = 1, max = 512),
+        custom(function = "validate_subject_pattern")
+    )]
+    pub subject_pattern: Option<String>,
+    #[validate(custom(function = "validate_claim_conditions"))]
+    pub claim_conditions: Option<BTreeMap<String, String>>,
+    #[validate(range(min = 60, max = 3600))]
+    pub token_ttl_secs: Option<u32>,
+    pub enabled: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(
+            "repo:acme/api:*",
+            "repo:acme/api:ref:refs/heads/main"
+        ));
+        assert!(wildcard_match(
+            "repo:acme/*:environment:prod",
+            "repo:acme/api:environment:prod"
+        ));
+        assert!(wildcard_match("exact", "exact"));
+        assert!(wildcard_match("a*b*c", "abc"));
+        assert!(!wildcard_match("repo:acme/api:*", "repo:acme/api-evil"));
+        assert!(!wildcard_match(
+            "repo:acme/*:ref:main",
+            "repo:acme/x:ref:main2"
+        ));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+
+    #[test]
+    fn test_rule_matches_subject_and_claims() {
+        let now = Utc::now();
+        let rule = WorkloadIdentityRule {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::new_v4(),
+            service_id: StringUuid::new_v4(),
+            name: "deploy".to_string(),
+            issuer: GITHUB_ACTIONS_ISSUER.to_string(),
+            audience: "auth9".to_string(),
+            subject_pattern: "repo:acme/deploy:*".to_string(),
+            claim_conditions: BTreeMap::from([
+                ("repository_owner".to_string(), "acme".to_string()),
+                ("ref_protected".to_string(), "true".to_string()),
+            ]),
+            token_ttl_secs: DEFAULT_FEDERATED_TOKEN_TTL_SECS,
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        };
+        let claims = |sub: &str, owner: &str| {
+            json!({"sub": sub, "repository_owner": owner, "ref_protected": true})
+                .as_object()
+                .cloned()
+                .unwrap()
+        };
+        assert!(rule.matches_claims(&claims("repo:acme/deploy:ref:refs/heads/main", "acme")));
+        assert!(!rule.matches_claims(&claims("repo:other/deploy:ref:refs/heads/main", "acme")));
+        assert!(!rule.matches_claims(&claims("repo:acme/deploy:ref:refs/heads/main", "other")));
+        assert!(!rule.matches_claims(&Map::new()));
+    }
+
+    #[test]
+    fn test_create_input_validation() {
+        let input: CreateWorkloadIdentityRuleInput = serde_json::from_value(json!({
+            "name": "deploy",
+            "service_id": Uuid::new_v4(),
+            "issuer": GITHUB_ACTIONS_ISSUER,
+            "audience": "https://auth9.example.com",
+            "subject_pattern": "repo:acme/deploy:ref:refs/heads/main"
+        }))
+        .unwrap();
+        assert!(input.validate().is_ok());
+        assert!(input.enabled);
+
+        for (field, value) in [
+            ("subject_pattern", json!("*:ref:refs/heads/main")),
+            (
+                "issuer",
+                json!("http://token.actions.githubusercontent.com"),
+            ),
+            ("claim_conditions", json!({"aud": "x"})),
+            ("token_ttl_secs", json!(86400)),
+        ] {
+            let mut body = json!({
+                "name": "deploy",
+                "service_id": Uuid::new_v4(),
+                "issuer": GITLAB_ISSUER,
+                "audience": "auth9",
+                "subject_pattern": "project_path:acme/api:*"
+            });
+            body[field] = value;
+            let input: CreateWorkloadIdentityRuleInput = serde_json::from_value(body).unwrap();
+            assert!(input.validate().is_err(), "{}", field);
+        }
+    }
+}
@@ -20,7 +20,7 @@ use utoipa::OpenApi;
         (name = "Identity", description = "Authentication, sessions, passwords, WebAuthn, and identity providers"),
         (name = "Tenant Access", description = "Tenants, users, invitations, organizations, and SSO connectors"),
         (name = "Authorization", description = "Services, RBAC roles, permissions, and tenant-service associations"),
-        (name = "Platform", description = "System settings, email configuration, branding, and email templates"),
+        (name = "Platform", description = "System settings, email configuration, branding, email templates, and async jobs"),
         (name = "Integration", description = "Webhooks, actions, and identity event ingestion"),
         (name = "Security & Observability", description = "Audit logs, analytics, and security alerts"),
     ),
@@ -37,29 +37,75 @@ use utoipa::OpenApi;
 
             // ── Common ─────────────────────────────────────────────────
             crate::models::common::StringUuid,
+            crate::models::common::TokenTtlOverrides,
 
             // ── Tenant domain ──────────────────────────────────────────
             crate::models::tenant::Tenant,
             crate::models::tenant::TenantStatus,
             crate::models::tenant::TenantSettings,
+            crate::models::cardinality::CardinalityLimits,
+            crate::models::cardinality::CardinalityCap,
             crate::models::tenant::TenantBranding,
             crate::models::tenant::CreateTenantInput,
             crate::models::tenant::CreateOrganizationInput,
             crate::models::tenant::UpdateTenantInput,
+            crate::models::tenant::RenameTenantInput,
+            crate::models::tenant::TenantSlugRedirect,
+            crate::models::tenant::TenantRenameResult,
+            crate::models::effective_settings::SettingSource,
+            crate::models::effective_settings::EffectiveSetting,
+            crate::models::effective_settings::EffectiveSettings,
             crate::models::tenant::TenantServiceAssoc,
             crate::models::tenant::ServiceWithStatus,
             crate::models::tenant::ToggleServiceInput,
+            crate::models::custom_domain::CustomDomain,
+            crate::models::custom_domain::CustomDomainResponse,
+            crate::models::custom_domain::DnsRecordInstruction,
+            crate::models::custom_domain::CreateCustomDomainInput,
+            crate::models::custom_domain::UploadCertificateInput,
+            crate::models::custom_domain::TlsAskResponse,
+            crate::models::custom_domain::HostedDomainInfo,
+            crate::models::tenant_key::TenantKeyStatus,
+            crate::models::tenant_key::SecretKeyCounts,
+            crate::models::tenant_key::ReencryptReport,
+            crate::models::admin_unit::AdminUnit,
+            crate::models::admin_unit::AdminUnitResponse,
+            crate::models::admin_unit::CreateAdminUnitInput,
+            crate::models::admin_unit::UpdateAdminUnitInput,
+            crate::models::admin_unit::AdminUnitUsersInput,
+            crate::models::inactivity::InactivityPolicy,
+            crate::models::inactivity::InactivityStep,
+            crate::models::inactivity::InactiveMember,
+            crate::models::inactivity::InactivityPreviewEntry,
+            crate::models::inactivity::InactivitySummary,
+            crate::models::inactivity::InactivityPreview,
+            crate::models::mfa_enrollment::MfaEnrollmentCampaign,
+            crate::models::mfa_enrollment::MfaEnrollmentStatus,
+            crate::models::mfa_enrollment::MfaEnrollmentRequirement,
+            crate::models::mfa_enrollment::MfaCampaignMember,
+            crate::models::mfa_enrollment::MfaCampaignSummary,
+            crate::models::mfa_enrollment::MfaCampaignProgress,
 
             // ── User domain ────────────────────────────────────────────
             crate::models::user::User,
             crate::models::user::TenantUser,
             crate::models::user::CreateUserInput,
             crate::models::user::UpdateUserInput,
+            crate::models::user::UpdateUserLocaleInput,
             crate::models::user::AddUserToTenantInput,
             crate::models::user::AdminSetPasswordInput,
             crate::models::user::UserTenantInfo,
             crate::models::user::TenantUserWithTenant,
             crate::models::user::TenantInfo,
+            crate::models::user::UserTimelineSource,
+            crate::models::user::UserCohort,
+            crate::models::user::UserTimelineEntry,
+            crate::models::notification_preference::NotificationPreferences,
+            crate::models::notification_preference::UpdateNotificationPreferencesInput,
+            crate::models::notification_preference::DigestFrequency,
+            crate::models::notification_preference::NotificationCategory,
+            crate::domains::tenant_access::api::notification_preference::UnsubscribeResponse,
+            crate::domains::identity::api::session::SignInReportResponse,
 
             // ── Service / Client domain ────────────────────────────────
             crate::models::service::Service,
@@ -68,27 +114,82 @@ use utoipa::OpenApi;
             crate::models::service::CreateServiceInput,
             crate::models::service::CreateClientInput,
             crate::models::service::UpdateServiceInput,
+            crate::models::service::ServiceCatalog,
+            crate::domains::authorization::api::service::LauncherApp,
 
             // ── RBAC domain ────────────────────────────────────────────
+            crate::models::audit_state::AuditStateSnapshot,
+            crate::models::audit_state::AuditStateResource,
+            crate::models::audit_state::AuditCoverageGap,
+            crate::models::audit_state::AuditGapKind,
+            crate::models::audit_search::AuditHistogram,
+            crate::models::audit_integrity::AuditIntegrityReport,
+            crate::models::audit_integrity::AuditIntegrityIssue,
+            crate::models::audit_integrity::AuditIntegrityIssueKind,
+            crate::models::audit_search::AuditHistogramBucket,
+            crate::models::audit_search::AuditHistogramInterval,
             crate::models::rbac::Permission,
             crate::models::rbac::Role,
             crate::models::rbac::RolePermission,
             crate::models::rbac::UserTenantRole,
             crate::models::rbac::CreatePermissionInput,
+            crate::models::rbac::PermissionNamespace,
+            crate::models::rbac::SetPermissionNamespaceInput,
             crate::models::rbac::CreateRoleInput,
             crate::models::rbac::UpdateRoleInput,
             crate::models::rbac::AssignRolesInput,
             crate::models::rbac::UserRolesInTenant,
+            crate::models::rbac::CheckPermissionInput,
+            crate::models::rbac::PermissionCheckResult,
+            crate::models::external_authz::ExternalAuthzProvider,
+            crate::models::external_authz::ExternalAuthzMode,
+            crate::models::external_authz::ExternalAuthzFallback,
+            crate::models::external_authz::ExternalAuthzOutcome,
+            crate::models::external_authz::ServiceExternalAuthorizer,
+            crate::models::external_authz::UpsertExternalAuthorizerInput,
+            crate::models::token_validation::TokenValidationProfile,
+            crate::models::token_validation::ServiceTokenValidationProfile,
+            crate::models::token_validation::SetTokenValidationProfileInput,
+            crate::models::role_preset::RolePreset,
+            crate::models::role_preset::RolePresetDefinition,
+            crate::models::role_preset::PresetPermission,
+            crate::models::role_preset::PresetRole,
+            crate::models::role_preset::CreateRolePresetInput,
+            crate::models::role_preset::UpdateRolePresetInput,
+            crate::models::role_preset::AppliedRolePreset,
+            crate::models::rbac::PermissionCheckExplanation,
+            crate::models::rbac::PermissionCheckStep,
+            crate::models::rbac::PermissionCheckReason,
+            crate::models::rbac::RoleTree,
+            crate::models::rbac::RoleTreeNode,
+            crate::models::claims_enrichment::EnrichmentFailurePolicy,
+            crate::models::claims_enrichment::ServiceClaimsEnricher,
+            crate::models::claims_enrichment::UpsertClaimsEnricherInput,
+            crate::domains::authorization::api::claims_enricher::ClaimsEnricherList,
+            crate::models::oauth_scope::ServiceScope,
+            crate::models::oauth_scope::UpsertServiceScopeInput,
+            crate::models::oauth_scope::ScopeConsentItem,
+            crate::models::oauth_scope::ConsentScreen,
+            crate::domains::identity::api::auth::SessionCheckResponse,
             crate::models::abac::AbacMode,
             crate::models::abac::AbacEffect,
             crate::models::abac::AbacRule,
             crate::models::abac::AbacPolicyDocument,
             crate::models::abac::AbacPolicySetSummary,
             crate::models::abac::AbacPolicyVersionSummary,
+            crate::models::abac::AbacPolicyVersionDetail,
             crate::models::abac::AbacSimulationInput,
             crate::models::abac::AbacSimulationResult,
             crate::domains::authorization::api::abac::CreateAbacPolicyInput,
             crate::domains::authorization::api::abac::UpdateAbacPolicyInput,
+            crate::models::client_registration::SoftwareStatementIssuer,
+            crate::models::client_registration::ClientRegistrationPolicy,
+            crate::models::client_registration::UpdateClientRegistrationPolicyInput,
+            crate::models::client_registration::InitialAccessToken,
+            crate::models::client_registration::CreateInitialAccessTokenInput,
+            crate::models::client_registration::InitialAccessTokenCreated,
+            crate::models::client_registration::ClientMetadata,
+            crate::models::client_registration::ClientInformationResponse,
             crate::domains::authorization::api::abac::PublishAbacPolicyInput,
             crate::domains::authorization::api::abac::RollbackAbacPolicyInput,
             crate::domains::authorization::api::abac::SimulateAbacPolicyInput,
@@ -99,6 +200,23 @@ use utoipa::OpenApi;
             crate::models::invitation::CreateInvitationInput,
             crate::models::invitation::InvitationResponse,
             crate::models::invitation::AcceptInvitationInput,
+            crate::models::invitation_link::InvitationLinkStatus,
+            crate::models::invitation_link::CreateInvitationLinkInput,
+            crate::models::invitation_link::InvitationLinkResponse,
+            crate::models::invitation_link::CreatedInvitationLink,
+            crate::models::invitation_link::InvitationLinkRedemption,
+            crate::models::invitation_link::JoinInvitationLinkInput,
+            crate::domains::tenant_access::api::invitation_link::InvitationLinkPreview,
+            crate::models::webhook_delivery::WebhookDeliverySource,
+            crate::models::webhook_delivery::WebhookDelivery,
+            crate::models::webhook_delivery::SendSyntheticEventInput,
+            crate::models::webhook_bulk::WebhookTemplate,
+            crate::models::webhook_bulk::CopyWebhooksInput,
+            crate::models::webhook_bulk::BulkSetWebhooksEnabledInput,
+            crate::models::webhook_bulk::CreateWebhooksFromTemplateInput,
+            crate::models::webhook_bulk::BulkWebhookItemStatus,
+            crate::models::webhook_bulk::BulkWebhookItemResult,
+            crate::models::webhook_bulk::BulkWebhookResult,
 
             // ── Password domain ────────────────────────────────────────
             crate::models::password::PasswordPolicy,
@@ -106,9 +224,42 @@ use utoipa::OpenApi;
             crate::models::password::ResetPasswordInput,
             crate::models::password::ChangePasswordInput,
             crate::models::password::UpdatePasswordPolicyInput,
+            crate::models::password_deny_list::PasswordDenyListSummary,
+            crate::models::password_deny_list::UpdatePasswordDenyListInput,
+            crate::models::login_identifier::LoginIdentifierKind,
+            crate::models::login_identifier::LoginIdentifierUniqueness,
+            crate::models::login_identifier::LoginIdentifierSettings,
+            crate::models::login_identifier::LoginIdentifier,
+            crate::models::login_identifier::SetLoginIdentifierInput,
+
+            // ── Account recovery ───────────────────────────────────────
+            crate::models::account_recovery::AccountRecoveryStatus,
+            crate::models::account_recovery::AccountRecoveryRequest,
+            crate::models::account_recovery::AccountRecoveryApproval,
+            crate::models::account_recovery::AccountRecoveryRequestDetail,
+            crate::models::account_recovery::RecoveryFactor,
+            crate::models::account_recovery::RecoveryCodeRecoveryInput,
+            crate::models::account_recovery::SecondaryFactorRecoveryInput,
+            crate::models::account_recovery::CreateAccountRecoveryRequestInput,
+            crate::models::account_recovery::ClaimAccountRecoveryInput,
+            crate::models::account_recovery::AccountRecoveryDecisionInput,
+            crate::models::account_recovery::AccountRecoveryRequestCreated,
+            crate::models::account_recovery::AccountRecoveryTokenResponse,
+            crate::models::account_deletion::AccountDeletionRequest,
+            crate::models::session_revocation::RevocationNotice,
+            crate::models::session_revocation::RevocationFeedPage,
+            crate::models::session_revocation::SessionRevocationReason,
+            crate::models::mfa_reset::MfaResetStatus,
+            crate::models::mfa_reset::MfaResetRequest,
+            crate::models::mfa_reset::CreateMfaResetRequestInput,
+            crate::models::mfa_reset::CompleteMfaResetInput,
+            crate::models::mfa_reset::MfaResetDecisionInput,
 
             // ── Session domain ─────────────────────────────────────────
             crate::models::session::SessionInfo,
+            crate::models::session::SessionLimitPolicy,
+            crate::models::session::SessionLimitAction,
+            crate::models::session::RenameSessionInput,
 
             // ── Analytics domain ───────────────────────────────────────
             crate::models::analytics::LoginEvent,
@@ -118,8 +269,39 @@ use utoipa::OpenApi;
 
             // ── Security domain ────────────────────────────────────────
             crate::models::analytics::SecurityAlert,
+            crate::models::login_forensics::LoginFailureRecord,
+            crate::models::login_forensics::LoginFailureStage,
             crate::models::analytics::SecurityAlertType,
             crate::models::analytics::AlertSeverity,
+            crate::models::audit_share::AuditShareFilter,
+            crate::models::audit_share::AuditShareStatus,
+            crate::models::audit_share::CreateAuditShareLinkInput,
+            crate::models::audit_share::AuditShareLinkResponse,
+            crate::models::audit_share::CreatedAuditShareLink,
+            crate::models::audit_share::AuditSharePreview,
+            crate::models::audit_share::AuditShareAccess,
+            crate::models::security_digest::SecurityDigestPolicy,
+            crate::models::security_digest::DigestAction,
+            crate::domains::security_observability::api::security_digest::DigestActionResponse,
+
+            // ── Admin scope domain ─────────────────────────────────────
+            crate::models::admin_scope::AdminScope,
+            crate::models::admin_scope::AdminScopeGrant,
+            crate::models::admin_scope::SetAdminScopesInput,
+            crate::models::admin_scope::AdminScopesResponse,
+
+            // ── Job domain ─────────────────────────────────────────────
+            crate::models::job::Job,
+            crate::models::job::JobKind,
+            crate::models::job::JobStatus,
+            crate::models::job::JobResponse,
+            crate::models::job::UserImportRow,
+            crate::models::job::UserImportInput,
+            crate::models::job::ForcePasswordResetInput,
+            crate::models::password_breach::BreachCampaignInput,
+            crate::models::password_breach::BreachCampaignAction,
+            crate::models::password_breach::BreachCampaignReport,
+            crate::models::password_breach::PasswordBreachCheck,
 
             // ── Webhook domain ─────────────────────────────────────────
             crate::models::analytics::Webhook,
@@ -140,12 +322,28 @@ use utoipa::OpenApi;
 
             // ── Branding domain ────────────────────────────────────────
             crate::models::branding::BrandingConfig,
+            crate::models::branding::BrandingContext,
+
+            // ── Rate limits ────────────────────────────────────────────
+            crate::models::rate_limit::RateLimitBucket,
+            crate::models::rate_limit::RateLimitStatus,
 
             // ── Email domain ───────────────────────────────────────────
             crate::models::email::EmailProviderConfig,
             crate::models::email::SmtpConfig,
             crate::models::email::SesConfig,
             crate::models::email::TenantEmailSettings,
+            crate::models::email_queue::EmailQueueStats,
+            crate::models::email_queue::EmailSuppression,
+            crate::models::email_queue::CreateEmailSuppressionInput,
+            crate::models::saga::SagaRecord,
+            crate::models::saga::SagaStatus,
+            crate::models::saga::SagaDirection,
+            crate::models::saga::SagaStepState,
+            crate::models::saga::SagaStepStatus,
+            crate::models::billing::BillingEvent,
+            crate::models::billing::BillingEventType,
+            crate::models::billing::BillingEventStatus,
 
             // ── Email template domain ──────────────────────────────────
             crate::models::email_template::EmailTemplateType,
@@ -153,10 +351,15 @@ use utoipa::OpenApi;
             crate::models::email_template::EmailTemplateMetadata,
             crate::models::email_template::EmailTemplateWithContent,
             crate::models::email_template::RenderedEmailPreview,
+            crate::i18n::Locale,
 
             // ── Enterprise SSO domain ──────────────────────────────────
             crate::models::enterprise_sso::EnterpriseSsoConnector,
             crate::models::enterprise_sso::EnterpriseSsoDiscoveryResult,
+            crate::models::ldap_sync::LdapSyncRun,
+            crate::models::ldap_sync::LdapSyncChange,
+            crate::models::ldap_sync::LdapSyncAction,
+            crate::models::ldap_sync::TriggerLdapSyncInput,
 
             // ── Identity provider domain ───────────────────────────────
             crate::models::identity_provider::IdentityProviderType,
@@ -183,8 +386,44 @@ use utoipa::OpenApi;
             crate::domains::identity::api::hosted_login::HostedLoginTokenResponse,
             crate::domains::identity::api::hosted_login::HostedLoginLogoutRequest,
 
+            // ── Progressive profiling ──────────────────────────────────
+            crate::models::progressive_profiling::ProfileRequirement,
+            crate::models::progressive_profiling::ProfileRequirementInput,
+            crate::models::progressive_profiling::SetProfileRequirementsInput,
+            crate::models::progressive_profiling::ProfileFormField,
+            crate::models::progressive_profiling::ProfileFormResponse,
+            crate::models::progressive_profiling::SubmitProfileInput,
+            crate::models::attribute_release::AttributeReleasePolicy,
+            crate::models::attribute_release::SetAttributeReleasePolicyInput,
+
+            // ── Conditional access ─────────────────────────────────────
+            crate::models::conditional_access::ConditionalAccessOutcome,
+            crate::models::conditional_access::DeviceTrustCondition,
+            crate::models::conditional_access::TimeWindow,
+            crate::models::conditional_access::ConditionalAccessConditions,
+            crate::models::conditional_access::ConditionalAccessPolicy,
+            crate::models::conditional_access::CreateConditionalAccessPolicyInput,
+            crate::models::conditional_access::UpdateConditionalAccessPolicyInput,
+            crate::models::conditional_access::SimulateConditionalAccessInput,
+            crate::models::conditional_access::ConditionalAccessPolicyTrace,
+            crate::models::conditional_access::ConditionalAccessDecision,
+            crate::models::workload_identity::WorkloadIdentityRule,
+            crate::models::workload_identity::CreateWorkloadIdentityRuleInput,
+            crate::models::workload_identity::UpdateWorkloadIdentityRuleInput,
+
             // ── Health ─────────────────────────────────────────────────
             crate::domains::security_observability::api::health::HealthResponse,
+            crate::telemetry::sql::SlowQueryReport,
+            crate::telemetry::runtime::TelemetryStatus,
+            crate::telemetry::runtime::TelemetryOverrideInput,
+            crate::telemetry::metrics::MetricsCatalog,
+            crate::telemetry::metrics::MetricDescriptor,
+            crate::telemetry::metrics::MetricKind,
+            crate::telemetry::metrics::RedSignals,
+            crate::domains::security_observability::service::synthetic::SyntheticCheckReport,
+            crate::domains::security_observability::service::synthetic::SyntheticStage,
+            crate::telemetry::sql::SlowQueryEntry,
+            crate::telemetry::sql::QueryFingerprintStats,
         ),
     ),
     paths(
@@ -197,6 +436,8 @@ use utoipa::OpenApi;
         crate::domains::identity::api::auth::jwks,
         crate::domains::identity::api::auth::authorize,
         crate::domains::identity::api::auth::callback,
+        crate::domains::identity::api::auth::authorize_consent,
+        crate::domains::identity::api::auth::session_check,
         crate::domains::identity::api::auth::enterprise_sso_discovery,
         // token endpoint uses raw Bytes extractor (form-urlencoded + JSON), no utoipa path
         crate::domains::identity::api::auth::tenant_token,
@@ -211,12 +452,24 @@ use utoipa::OpenApi;
         crate::domains::identity::api::password::admin_set_password,
         crate::domains::identity::api::password::get_password_policy,
         crate::domains::identity::api::password::update_password_policy,
+        crate::domains::identity::api::password_deny_list::get_password_deny_list,
+        crate::domains::identity::api::password_deny_list::update_password_deny_list,
+        crate::domains::identity::api::password_deny_list::delete_password_deny_list,
+        crate::domains::identity::api::login_identifier::list_login_identifiers,
+        crate::domains::identity::api::login_identifier::set_login_identifier,
+        crate::domains::identity::api::login_identifier::delete_login_identifier,
 
         // ── Identity: Session ──────────────────────────────────────
         crate::domains::identity::api::session::list_my_sessions,
         crate::domains::identity::api::session::revoke_session,
         crate::domains::identity::api::session::revoke_other_sessions,
+        crate::domains::identity::api::session::rename_session,
+        crate::domains::identity::api::session::sign_out_everywhere,
         crate::domains::identity::api::session::force_logout_user,
+        crate::domains::identity::api::session::export_tenant_sessions,
+        crate::domains::identity::api::revocation::list_revocations,
+        crate::domains::identity::api::session::get_sign_in_report,
+        crate::domains::identity::api::session::report_sign_in,
 
         // ── Identity: WebAuthn ─────────────────────────────────────
         crate::domains::identity::api::webauthn::start_registration,
@@ -232,6 +485,46 @@ use utoipa::OpenApi;
         crate::domains::identity::api::hosted_login::start_password_reset,
         crate::domains::identity::api::hosted_login::complete_password_reset,
 
+        // ── Identity: Account Recovery ─────────────────────────────
+        crate::domains::identity::api::account_recovery::recover_with_code,
+        crate::domains::identity::api::account_recovery::recover_with_factor,
+        crate::domains::identity::api::account_recovery::create_recovery_request,
+        crate::domains::identity::api::account_recovery::claim_recovery_request,
+        crate::domains::identity::api::account_recovery::list_recovery_requests,
+        crate::domains::identity::api::account_recovery::get_recovery_request,
+        crate::domains::identity::api::account_recovery::approve_recovery_request,
+        crate::domains::identity::api::account_recovery::reject_recovery_request,
+        crate::domains::identity::api::account_deletion::schedule_account_deletion,
+        crate::domains::identity::api::account_deletion::get_account_deletion,
+        crate::domains::identity::api::account_deletion::restore_account,
+        crate::domains::identity::api::mfa_reset::create_mfa_reset_request,
+        crate::domains::identity::api::mfa_reset::complete_mfa_reset,
+        crate::domains::identity::api::mfa_reset::list_mfa_reset_requests,
+        crate::domains::identity::api::mfa_reset::get_mfa_reset_request,
+        crate::domains::identity::api::mfa_reset::approve_mfa_reset_request,
+        crate::domains::identity::api::mfa_reset::reject_mfa_reset_request,
+
+        // ── Identity: Progressive Profiling ────────────────────────
+        crate::domains::identity::api::progressive_profiling::list_requirements,
+        crate::domains::identity::api::progressive_profiling::set_requirements,
+        crate::domains::identity::api::progressive_profiling::get_profile_form,
+        crate::domains::identity::api::progressive_profiling::submit_profile,
+        crate::domains::identity::api::attribute_release::get_policy,
+        crate::domains::identity::api::attribute_release::set_policy,
+
+        // ── Identity: Conditional Access ───────────────────────────
+        crate::domains::identity::api::conditional_access::list_policies,
+        crate::domains::identity::api::conditional_access::create_policy,
+        crate::domains::identity::api::conditional_access::get_policy,
+        crate::domains::identity::api::conditional_access::update_policy,
+        crate::domains::identity::api::conditional_access::delete_policy,
+        crate::domains::identity::api::conditional_access::simulate,
+        crate::domains::identity::api::workload_identity::list_rules,
+        crate::domains::identity::api::workload_identity::create_rule,
+        crate::domains::identity::api::workload_identity::get_rule,
+        crate::domains::identity::api::workload_identity::update_rule,
+        crate::domains::identity::api::workload_identity::delete_rule,
+
         // ── Identity: Identity Provider ────────────────────────────
         crate::domains::identity::api::identity_provider::list_providers,
         crate::domains::identity::api::identity_provider::create_provider,
@@ -247,6 +540,9 @@ use utoipa::OpenApi;
         crate::domains::tenant_access::api::tenant::get,
         crate::domains::tenant_access::api::tenant::create,
         crate::domains::tenant_access::api::tenant::update,
+        crate::domains::tenant_access::api::tenant::get_by_slug,
+        crate::domains::tenant_access::api::tenant::rename,
+        crate::domains::tenant_access::api::tenant::effective_settings,
         crate::domains::tenant_access::api::tenant::delete,
         crate::domains::tenant_access::api::tenant::get_tenant_malicious_ip_blacklist,
         crate::domains::tenant_access::api::tenant::update_tenant_malicious_ip_blacklist,
@@ -257,15 +553,21 @@ use utoipa::OpenApi;
         crate::domains::tenant_access::api::user::create,
         crate::domains::tenant_access::api::user::get_me,
         crate::domains::tenant_access::api::user::update_me,
+        crate::domains::tenant_access::api::user::update_my_locale,
         crate::domains::tenant_access::api::user::update,
         crate::domains::tenant_access::api::user::delete,
         crate::domains::tenant_access::api::user::enable_mfa,
         crate::domains::tenant_access::api::user::disable_mfa,
         crate::domains::tenant_access::api::user::get_tenants,
+        crate::domains::tenant_access::api::user::timeline,
         crate::domains::tenant_access::api::user::add_to_tenant,
         crate::domains::tenant_access::api::user::remove_from_tenant,
         crate::domains::tenant_access::api::user::update_role_in_tenant,
         crate::domains::tenant_access::api::user::list_by_tenant,
+        crate::domains::tenant_access::api::notification_preference::get_notification_preferences,
+        crate::domains::tenant_access::api::notification_preference::update_notification_preferences,
+        crate::domains::tenant_access::api::notification_preference::get_unsubscribe,
+        crate::domains::tenant_access::api::notification_preference::unsubscribe,
 
         // ── Tenant Access: Invitation ──────────────────────────────
         crate::domains::tenant_access::api::invitation::list,
@@ -275,6 +577,12 @@ use utoipa::OpenApi;
         crate::domains::tenant_access::api::invitation::accept,
         crate::domains::tenant_access::api::invitation::revoke,
         crate::domains::tenant_access::api::invitation::resend,
+        crate::domains::tenant_access::api::invitation_link::create,
+        crate::domains::tenant_access::api::invitation_link::list,
+        crate::domains::tenant_access::api::invitation_link::revoke,
+        crate::domains::tenant_access::api::invitation_link::list_redemptions,
+        crate::domains::tenant_access::api::invitation_link::validate_token,
+        crate::domains::tenant_access::api::invitation_link::join,
 
         // ── Tenant Access: Organization ────────────────────────────
         crate::domains::tenant_access::api::organization::create_organization,
@@ -286,6 +594,34 @@ use utoipa::OpenApi;
         crate::domains::tenant_access::api::tenant_sso::update_connector,
         crate::domains::tenant_access::api::tenant_sso::delete_connector,
         crate::domains::tenant_access::api::tenant_sso::test_connector,
+        crate::domains::tenant_access::api::tenant_ldap_sync::trigger_sync,
+        crate::domains::tenant_access::api::tenant_ldap_sync::list_sync_runs,
+        crate::domains::tenant_access::api::tenant_ldap_sync::get_sync_run,
+
+        // ── Tenant Access: Custom Domains ──────────────────────────
+        crate::domains::tenant_access::api::custom_domain::list,
+        crate::domains::tenant_access::api::custom_domain::create,
+        crate::domains::tenant_access::api::custom_domain::verify,
+        crate::domains::tenant_access::api::custom_domain::upload_certificate,
+        crate::domains::tenant_access::api::custom_domain::delete_certificate,
+        crate::domains::tenant_access::api::custom_domain::delete,
+        crate::domains::tenant_access::api::custom_domain::tls_ask,
+        crate::domains::tenant_access::api::custom_domain::current,
+        crate::domains::tenant_access::api::tenant_key::status,
+        crate::domains::tenant_access::api::tenant_key::rotate,
+        crate::domains::tenant_access::api::tenant_key::reencrypt_all,
+        crate::domains::tenant_access::api::inactivity::preview,
+        crate::domains::tenant_access::api::mfa_enrollment::progress,
+        crate::domains::tenant_access::api::mfa_enrollment::my_requirements,
+        crate::domains::tenant_access::api::admin_unit::list,
+        crate::domains::tenant_access::api::admin_unit::create,
+        crate::domains::tenant_access::api::admin_unit::get,
+        crate::domains::tenant_access::api::admin_unit::update,
+        crate::domains::tenant_access::api::admin_unit::delete,
+        crate::domains::tenant_access::api::admin_unit::add_members,
+        crate::domains::tenant_access::api::admin_unit::remove_member,
+        crate::domains::tenant_access::api::admin_unit::add_admins,
+        crate::domains::tenant_access::api::admin_unit::remove_admin,
 
         // ── Authorization: Service ─────────────────────────────────
         crate::domains::authorization::api::service::list,
@@ -298,20 +634,43 @@ use utoipa::OpenApi;
         crate::domains::authorization::api::service::create_client,
         crate::domains::authorization::api::service::delete_client,
         crate::domains::authorization::api::service::regenerate_client_secret,
+        crate::domains::authorization::api::service::update_client_token_ttls,
+        crate::domains::authorization::api::service::list_my_apps,
 
         // ── Authorization: Role & Permission ───────────────────────
         crate::domains::authorization::api::role::create_permission,
         crate::domains::authorization::api::role::delete_permission,
         crate::domains::authorization::api::role::list_permissions,
+        crate::domains::authorization::api::role::get_permission_namespace,
+        crate::domains::authorization::api::role::set_permission_namespace,
         crate::domains::authorization::api::role::create_role,
         crate::domains::authorization::api::role::get_role,
         crate::domains::authorization::api::role::update_role,
         crate::domains::authorization::api::role::delete_role,
         crate::domains::authorization::api::role::list_roles,
+        crate::domains::authorization::api::role::get_role_tree,
+        crate::domains::authorization::api::claims_enricher::list_claims_enrichers,
+        crate::domains::authorization::api::claims_enricher::upsert_claims_enricher,
+        crate::domains::authorization::api::claims_enricher::delete_claims_enricher,
+        crate::domains::authorization::api::external_authorizer::get_external_authorizer,
+        crate::domains::authorization::api::external_authorizer::upsert_external_authorizer,
+        crate::domains::authorization::api::external_authorizer::delete_external_authorizer,
+        crate::domains::authorization::api::token_validation::get_token_validation_profile,
+        crate::domains::authorization::api::token_validation::set_token_validation_profile,
+        crate::domains::authorization::api::role_preset::list_role_presets,
+        crate::domains::authorization::api::role_preset::get_role_preset,
+        crate::domains::authorization::api::role_preset::list_role_preset_versions,
+        crate::domains::authorization::api::role_preset::create_role_preset,
+        crate::domains::authorization::api::role_preset::update_role_preset,
+        crate::domains::authorization::api::role_preset::delete_role_preset,
+        crate::domains::authorization::api::scope::list_scopes,
+        crate::domains::authorization::api::scope::upsert_scope,
+        crate::domains::authorization::api::scope::delete_scope,
         crate::domains::authorization::api::role::assign_permission,
         crate::domains::authorization::api::role::remove_permission,
         crate::domains::authorization::api::role::assign_roles,
         crate::domains::authorization::api::role::get_user_roles,
+        crate::domains::authorization::api::role::check_user_permission,
         crate::domains::authorization::api::role::get_user_assigned_roles,
         crate::domains::authorization::api::role::unassign_role,
 
@@ -321,11 +680,23 @@ use utoipa::OpenApi;
         crate::domains::authorization::api::tenant_service::get_enabled_services,
         crate::domains::authorization::api::abac::list_policies,
         crate::domains::authorization::api::abac::create_policy,
+        crate::domains::authorization::api::abac::get_policy,
         crate::domains::authorization::api::abac::update_policy,
         crate::domains::authorization::api::abac::publish_policy,
         crate::domains::authorization::api::abac::rollback_policy,
         crate::domains::authorization::api::abac::simulate_policy,
 
+        // ── Authorization: Dynamic Client Registration ─────────────
+        crate::domains::authorization::api::client_registration::register_client,
+        crate::domains::authorization::api::client_registration::get_registered_client,
+        crate::domains::authorization::api::client_registration::update_registered_client,
+        crate::domains::authorization::api::client_registration::delete_registered_client,
+        crate::domains::authorization::api::client_registration::get_registration_policy,
+        crate::domains::authorization::api::client_registration::update_registration_policy,
+        crate::domains::authorization::api::client_registration::list_initial_access_tokens,
+        crate::domains::authorization::api::client_registration::create_initial_access_token,
+        crate::domains::authorization::api::client_registration::revoke_initial_access_token,
+
         // ── Platform: System Settings ──────────────────────────────
         crate::domains::platform::api::system_settings::get_email_settings,
         crate::domains::platform::api::system_settings::update_email_settings,
@@ -334,11 +705,30 @@ use utoipa::OpenApi;
         crate::domains::platform::api::system_settings::get_malicious_ip_blacklist,
         crate::domains::platform::api::system_settings::update_malicious_ip_blacklist,
 
+        // ── Platform: Email Queue ──────────────────────────────────
+        crate::domains::platform::api::email_queue::get_queue_stats,
+        crate::domains::platform::api::email_queue::list_suppressions,
+        crate::domains::platform::api::email_queue::create_suppression,
+        crate::domains::platform::api::email_queue::delete_suppression,
+        crate::domains::platform::api::email_queue::receive_feedback,
+        crate::domains::platform::api::saga::list_sagas,
+        crate::domains::platform::api::saga::get_saga,
+        crate::domains::platform::api::saga::resume_saga,
+        crate::domains::platform::api::saga::compensate_saga,
+
+        // ── Platform: Billing ──────────────────────────────────────
+        crate::domains::platform::api::billing::list_billing_events,
+        crate::domains::platform::api::billing::redeliver_billing_event,
+
         // ── Platform: Branding ─────────────────────────────────────
         crate::domains::platform::api::branding::get_public_branding,
+        crate::domains::platform::api::branding::get_public_branding_context,
         crate::domains::platform::api::branding::get_branding,
         crate::domains::platform::api::branding::update_branding,
 
+        // ── Platform: Rate Limits ──────────────────────────────────
+        crate::domains::platform::api::rate_limit::get_own_rate_limits,
+
         // ── Platform: Email Templates ──────────────────────────────
         crate::domains::platform::api::email_template::list_templates,
         crate::domains::platform::api::email_template::get_template,
@@ -347,6 +737,20 @@ use utoipa::OpenApi;
         crate::domains::platform::api::email_template::preview_template,
         crate::domains::platform::api::email_template::send_test_email,
 
+        // ── Platform: Admin Scopes ─────────────────────────────────
+        crate::domains::platform::api::admin_scope::get_admin_scopes,
+        crate::domains::platform::api::admin_scope::set_admin_scopes,
+
+        // ── Platform: Jobs ─────────────────────────────────────────
+        crate::domains::platform::api::job::get_job,
+        crate::domains::platform::api::job::cancel_job,
+        crate::domains::platform::api::job::list_tenant_jobs,
+        crate::domains::platform::api::job::import_users,
+        crate::domains::platform::api::job::export_users,
+        crate::domains::platform::api::job::force_password_reset,
+        crate::domains::platform::api::job::start_breach_campaign,
+        crate::domains::platform::api::job::breach_campaign_report,
+
         // ── Integration: Webhook ───────────────────────────────────
         crate::domains::integration::api::webhook::list_webhooks,
         crate::domains::integration::api::webhook::create_webhook,
@@ -354,7 +758,23 @@ use utoipa::OpenApi;
         crate::domains::integration::api::webhook::update_webhook,
         crate::domains::integration::api::webhook::delete_webhook,
         crate::domains::integration::api::webhook::test_webhook,
+        crate::domains::integration::api::webhook::ping_webhook,
         crate::domains::integration::api::webhook::regenerate_webhook_secret,
+        crate::domains::integration::api::webhook::list_deliveries,
+        crate::domains::integration::api::webhook::get_delivery,
+        crate::domains::integration::api::webhook::replay_delivery,
+        crate::domains::integration::api::webhook::send_synthetic_event,
+        crate::domains::integration::api::webhook::list_platform_webhooks,
+        crate::domains::integration::api::webhook::create_platform_webhook,
+        crate::domains::integration::api::webhook::get_platform_webhook,
+        crate::domains::integration::api::webhook::update_platform_webhook,
+        crate::domains::integration::api::webhook::delete_platform_webhook,
+        crate::domains::integration::api::webhook::test_platform_webhook,
+        crate::domains::integration::api::webhook::list_platform_deliveries,
+        crate::domains::integration::api::webhook_bulk::list_webhook_templates,
+        crate::domains::integration::api::webhook_bulk::copy_webhooks,
+        crate::domains::integration::api::webhook_bulk::set_webhooks_enabled,
+        crate::domains::integration::api::webhook_bulk::create_webhooks_from_template,
 
         // ── Integration: Action ────────────────────────────────────
         crate::domains::integration::api::action::list_actions,
@@ -374,6 +794,17 @@ use utoipa::OpenApi;
 
         // ── Security & Observability: Audit ────────────────────────
         crate::domains::security_observability::api::audit::list,
+        crate::domains::security_observability::api::audit::histogram,
+        crate::domains::security_observability::api::audit::verify,
+        crate::domains::security_observability::api::audit::export,
+        crate::domains::security_observability::api::audit::download_export,
+        crate::domains::security_observability::api::audit::get_state,
+        crate::domains::security_observability::api::audit_share::create,
+        crate::domains::security_observability::api::audit_share::list,
+        crate::domains::security_observability::api::audit_share::revoke,
+        crate::domains::security_observability::api::audit_share::list_accesses,
+        crate::domains::security_observability::api::audit_share::preview,
+        crate::domains::security_observability::api::audit_share::logs,
 
         // ── Security & Observability: Analytics ────────────────────
         crate::domains::security_observability::api::analytics::get_stats,
@@ -383,6 +814,18 @@ use utoipa::OpenApi;
         // ── Security & Observability: Security Alerts ──────────────
         crate::domains::security_observability::api::security_alert::list_alerts,
         crate::domains::security_observability::api::security_alert::resolve_alert,
+        crate::domains::security_observability::api::login_forensics::search_login_failures,
+        crate::domains::security_observability::api::security_digest::get_action,
+        crate::domains::security_observability::api::security_digest::perform_action,
+
+        // ── Security & Observability: SQL Statistics ───────────────
+        crate::domains::security_observability::api::sql_stats::slow_queries,
+        crate::domains::security_observability::api::sql_stats::reset_slow_queries,
+        crate::domains::security_observability::api::telemetry::get_telemetry,
+        crate::domains::security_observability::api::telemetry::set_telemetry,
+        crate::domains::security_observability::api::telemetry::reset_telemetry,
+        crate::domains::security_observability::api::metrics_catalog::metrics_catalog,
+        crate::domains::security_observability::api::synthetic::login_check,
     ),
 )]
 pub struct ApiDoc;
@@ -402,6 +845,10 @@ impl ApiDoc {
                 ),
             );
         }
+        crate::http_support::deprecation::annotate_openapi(
+            &mut doc,
+            crate::http_support::deprecation::API_DEPRECATIONS,
+        );
         doc
     }
 }
@@ -0,0 +1,56 @@
+//! Admin unit boundaries for delegated user administration.
+//!
+//! Tenant owners and platform admins are never limited by admin units. Unit
+//! assignments come from the state's [`AdminUnitService`], so every state
+//! enforces the boundary; a failed lookup denies the request.
+//!
+//! [`AdminUnitService`]: crate::domains::tenant_access::service::AdminUnitService
+
+use super::{AuthzContext, PolicyResult};
+use crate::models::common::StringUuid;
+use crate::state::HasServices;
+
+async fn is_exempt<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: StringUuid,
+) -> PolicyResult<bool> {
+    Ok(authz.is_platform_admin(state).await
+        || authz.role_in_tenant(state, tenant_id).await? == Some("owner"))
+}
+
+/// Require the caller to be allowed to manage `target_user_id` in the tenant
+pub async fn enforce_admin_unit_boundary<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: StringUuid,
+    target_user_id: StringUuid,
+) -> PolicyResult<()> {
+    if is_exempt(state, authz, tenant_id).await? {
+        return Ok(());
+    }
+    state
+        .admin_unit_service()
+        .check_boundary(tenant_id, authz.user_id(), target_user_id)
+        .await
+}
+
+/// Require the caller to be allowed to create users in `unit_id` (or
+/// outside any unit when `None`), and the unit to belong to the tenant
+pub async fn enforce_admin_unit_create_boundary<S: HasServices>(
+    state: &S,
+    authz: &AuthzContext,
+    tenant_id: StringUuid,
+    unit_id: Option<StringUuid>,
+) -> PolicyResult<()> {
+    let service = state.admin_unit_service();
+    if is_exempt(state, authz, tenant_id).await? {
+        if let Some(unit_id) = unit_id {
+            service.get(tenant_id, unit_id).await?;
+        }
+        return Ok(());
+    }
+    service
+        .check_create_boundary(tenant_id, authz.user_id(), unit_id)
+        .await
+}
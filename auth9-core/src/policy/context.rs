@@ -0,0 +1,284 @@
+//! Request-scoped authorization context.
+//!
+//! `AuthzContext` is built once per request from the bearer token and
+//! memoizes the lookups policy checks depend on (tenant memberships,
+//! platform-admin status, latest login risk level). Handlers that run several
+//! checks against the same caller hit the database at most once per lookup.
+
+use super::{enforce_with_context, PolicyInput, PolicyResult};
+use crate::domains::security_observability::service::RiskLevel;
+use crate::error::Result;
+use crate::middleware::auth::{AuthError, AuthUser, TokenType};
+use crate::models::common::StringUuid;
+use crate::models::user::TenantUserWithTenant;
+use crate::state::{HasAnalytics, HasServices};
+use axum::{extract::FromRequestParts, http::request::Parts};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Slug of the platform tenant; its admins are platform admins
+pub const PLATFORM_TENANT_SLUG: &str = "auth9-platform";
+
+/// Caller identity plus lazily loaded, memoized authorization facts
+#[derive(Clone)]
+pub struct AuthzContext {
+    inner: Arc<AuthzContextInner>,
+}
+
+struct AuthzContextInner {
+    auth: AuthUser,
+    memberships: OnceCell<Vec<TenantUserWithTenant>>,
+    platform_admin: OnceCell<bool>,
+    risk_level: OnceCell<Option<RiskLevel>>,
+}
+
+impl AuthzContext {
+    pub fn new(auth: AuthUser) -> Self {
+        Self {
+            inner: Arc::new(AuthzContextInner {
+                auth,
+                memberships: OnceCell::new(),
+                platform_admin: OnceCell::new(),
+                risk_level: OnceCell::new(),
+            }),
+        }
+    }
+
+    pub fn auth(&self) -> &AuthUser {
+        &self.inner.auth
+    }
+
+    pub fn user_id(&self) -> StringUuid {
+        StringUuid::from(self.inner.auth.user_id)
+    }
+
+    pub fn tenant_id(&self) -> Option<StringUuid> {
+        self.inner.auth.tenant_id.map(StringUuid::from)
+    }
+
+    pub fn roles(&self) -> &[String] {
+        &self.inner.auth.roles
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.inner.auth.permissions
+    }
+
+    /// Caller's tenant memberships, loaded on first use
+    pub async fn memberships<S: HasServices>(&self, state: &S) -> Result<&[TenantUserWithTenant]> {
+        let user_id = self.user_id();
+        self.load_memberships(|| state.user_service().get_user_tenants_with_tenant(user_id))
+            .await
+    }
+
+    /// Caller's role in the given tenant according to membership records
+    pub async fn role_in_tenant<S: HasServices>(
+        &self,
+        state: &S,
+        tenant_id: StringUuid,
+    ) -> Result<Option<&str>> {
+        Ok(self
+            .memberships(state)
+            .await?
+            .iter()
+            .find(|tu| tu.tenant_id == tenant_id)
+            .map(|tu| tu.role_in_tenant.as_str()))
+    }
+
+    /// Platform admin via configured email or `auth9-platform` admin membership.
+    /// Service client tokens are never platform admins.
+    pub async fn is_platform_admin<S: HasServices>(&self, state: &S) -> bool {
+        *self
+            .inner
+            .platform_admin
+            .get_or_init(|| async {
+                let auth = self.auth();
+                if auth.token_type == TokenType::ServiceClient {
+                    return false;
+                }
+                if state.config().is_platform_admin_email(&auth.email) {
+                    return true;
+                }
+                match self.memberships(state).await {
+                    Ok(memberships) => memberships.iter().any(|tu| {
+                        tu.tenant.slug == PLATFORM_TENANT_SLUG && tu.role_in_tenant == "admin"
+                    }),
+                    Err(_) => false,
+                }
+            })
+            .await
+    }
+
+    /// Risk level of the caller's most recent login, when it was scored
+    pub async fn risk_level<S: HasAnalytics>(&self, state: &S) -> Result<Option<RiskLevel>> {
+        let user_id = self.user_id();
+        self.load_risk_level(|| state.analytics_service().latest_risk_score(user_id))
+            .await
+    }
+
+    /// Enforce a policy, reusing lookups already made for this request
+    pub async fn enforce<S: HasServices>(
+        &self,
+        state: &S,
+        input: &PolicyInput,
+    ) -> PolicyResult<()> {
+        enforce_with_context(state, self, input).await
+    }
+
+    async fn load_memberships<F, Fut>(&self, load: F) -> Result<&[TenantUserWithTenant]>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<TenantUserWithTenant>>>,
+    {
+        self.inner
+            .memberships
+            .get_or_try_init(load)
+            .await
+            .map(Vec::as_slice)
+    }
+
+    async fn load_risk_level<F, Fut>(&self, load: F) -> Result<Option<RiskLevel>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Option<u8>>>,
+    {
+        self.inner
+            .risk_level
+            .get_or_try_init(|| async { Ok(load().await?.map(RiskLevel::from_score)) })
+            .await
+            .copied()
+    }
+}
+
+impl<S> FromRequestParts<S> for AuthzContext
+where
+    S: HasServices + Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        if let Some(ctx) = parts.extensions.get::<AuthzContext>() {
+            return Ok(ctx.clone());
+        }
+        let auth = AuthUser::from_request_parts(parts, state).await?;
+        let ctx = AuthzContext::new(auth);
+        parts.extensions.insert(ctx.clone());
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use crate::models::user::TenantInfo;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn identity_user() -> AuthUser {
+        AuthUser {
+            user_id: uuid::Uuid::new_v4(),
+            email: "user@example.com".to_string(),
+            token_type: TokenType::Identity,
+            tenant_id: None,
+            aud: None,
+            roles: vec![],
+            permissions: vec![],
+            scope: None,
+        }
+    }
+
+    fn membership(user_id: StringUuid, role: &str) -> TenantUserWithTenant {
+        let tenant_id = StringUuid::new_v4();
+        TenantUserWithTenant {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            user_id,
+            role_in_tenant: role.to_string(),
+            joined_at: chrono::Utc::now(),
+            tenant: TenantInfo {
+                id: tenant_id,
+                name: "Acme".to_string(),
+                slug: "acme".to_string(),
+                logo_url: None,
+                status: "active".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memberships_loaded_once() {
+        let ctx = AuthzContext::new(identity_user());
+        let calls = AtomicUsize::new(0);
+        let user_id = ctx.user_id();
+
+        for _ in 0..3 {
+            let memberships = ctx
+                .load_memberships(|| async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(vec![membership(user_id, "owner")])
+                })
+                .await
+                .unwrap();
+            assert_eq!(memberships.len(), 1);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_membership_load_is_retried() {
+        let ctx = AuthzContext::new(identity_user());
+        let calls = AtomicUsize::new(0);
+
+        let first = ctx
+            .load_memberships(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(AppError::Internal(anyhow::anyhow!("db down")))
+            })
+            .await;
+        assert!(first.is_err());
+
+        let second = ctx
+            .load_memberships(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(vec![])
+            })
+            .await;
+        assert!(second.unwrap().is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_memoized_state() {
+        let ctx = AuthzContext::new(identity_user());
+        let clone = ctx.clone();
+        let calls = AtomicUsize::new(0);
+
+        ctx.load_risk_level(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(80))
+        })
+        .await
+        .unwrap();
+        let level = clone
+            .load_risk_level(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(Some(10))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(level, Some(RiskLevel::Critical));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unscored_login_has_no_risk_level() {
+        let ctx = AuthzContext::new(identity_user());
+        let level = ctx.load_risk_level(|| async { Ok(None) }).await.unwrap();
+        assert_eq!(level, None);
+    }
+}
@@ -1,6 +1,11 @@
 //! Centralized authorization policy engine for HTTP handlers.
 
 pub(crate) mod abac;
+mod admin_unit;
+mod context;
+
+pub use admin_unit::{enforce_admin_unit_boundary, enforce_admin_unit_create_boundary};
+pub use context::{AuthzContext, PLATFORM_TENANT_SLUG};
 
 use crate::config::Config;
 use crate::error::AppError;
@@ -22,8 +27,13 @@ pub enum PolicyAction {
     PlatformAdmin,
     AuditRead,
     SessionForceLogout,
+    /// Export the sessions of a tenant's members
+    SessionExport,
     WebhookRead,
     WebhookWrite,
+    /// Manage platform-scoped webhooks (tenant lifecycle, platform alerts)
+    PlatformWebhookRead,
+    PlatformWebhookWrite,
     TenantServiceRead,
     TenantServiceWrite,
     SecurityAlertRead,
@@ -45,6 +55,10 @@ pub enum PolicyAction {
     RbacAssignSelf,
     InvitationRead,
     InvitationWrite,
+    /// Review MFA reset requests of tenant members
+    MfaResetReview,
+    /// Assign usernames and phone numbers to tenant members
+    LoginIdentifierManage,
     UserManage,
     UserTenantRead,
     UserReadOther,
@@ -84,7 +98,13 @@ pub fn enforce(config: &Config, auth: &AuthUser, input: &PolicyInput) -> PolicyR
         | PolicyAction::SessionForceLogout
         | PolicyAction::SecurityAlertRead
         | PolicyAction::SecurityAlertResolve
-        | PolicyAction::UserWrite => require_platform_admin(config, auth),
+        | PolicyAction::UserWrite
+        | PolicyAction::PlatformWebhookRead
+        | PolicyAction::PlatformWebhookWrite => require_platform_admin(config, auth),
+        PolicyAction::SessionExport => {
+            let tenant_id = require_tenant_scope(&input.scope)?;
+            require_tenant_admin_or_permission(auth, tenant_id, &["session:read", "session:*"])
+        }
         PolicyAction::WebhookRead => {
             let tenant_id = require_tenant_scope(&input.scope)?;
             require_tenant_admin_or_permission(auth, tenant_id, &["webhook:read", "webhook:*"])
@@ -169,6 +189,10 @@ pub fn enforce(config: &Config, auth: &AuthUser, input: &PolicyInput) -> PolicyR
             let tenant_id = require_tenant_scope(&input.scope)?;
             require_tenant_admin_or_permission(auth, tenant_id, &["user:write", "user:*"])
         }
+        PolicyAction::MfaResetReview | PolicyAction::LoginIdentifierManage => {
+            let tenant_id = require_tenant_scope(&input.scope)?;
+            require_tenant_admin_or_permission(auth, tenant_id, &["user:write", "user:*"])
+        }
         PolicyAction::AbacRead => {
             let tenant_id = require_tenant_scope(&input.scope)?;
             require_tenant_admin_or_permission(
@@ -230,22 +254,9 @@ pub fn enforce(config: &Config, auth: &AuthUser, input: &PolicyInput) -> PolicyR
 }
 
 pub async fn is_platform_admin_with_db<S: HasServices>(state: &S, auth: &AuthUser) -> bool {
-    if auth.token_type == TokenType::ServiceClient {
-        return false;
-    }
-    if state.config().is_platform_admin_email(&auth.email) {
-        return true;
-    }
-    if let Ok(user_tenants) = state
-        .user_service()
-        .get_user_tenants_with_tenant(StringUuid::from(auth.user_id))
+    AuthzContext::new(auth.clone())
+        .is_platform_admin(state)
         .await
-    {
-        return user_tenants
-            .iter()
-            .any(|tu| tu.tenant.slug == "auth9-platform" && tu.role_in_tenant == "admin");
-    }
-    false
 }
 
 pub async fn require_platform_admin_with_db<S: HasServices>(
@@ -295,17 +306,30 @@ pub async fn require_platform_admin_identity<S: HasServices>(
     Err(AppError::Forbidden("Platform admin required".to_string()))
 }
 
+/// Enforce a policy that may need database lookups.
+///
+/// Builds a throwaway [`AuthzContext`]; handlers running several checks should
+/// extract an `AuthzContext` and call [`AuthzContext::enforce`] instead.
 pub async fn enforce_with_state<S: HasServices>(
     state: &S,
     auth: &AuthUser,
     input: &PolicyInput,
 ) -> PolicyResult<()> {
+    enforce_with_context(state, &AuthzContext::new(auth.clone()), input).await
+}
+
+pub(crate) async fn enforce_with_context<S: HasServices>(
+    state: &S,
+    ctx: &AuthzContext,
+    input: &PolicyInput,
+) -> PolicyResult<()> {
+    let auth = ctx.auth();
     if input.action == PolicyAction::UserTenantRead {
         let tenant_id = require_tenant_scope(&input.scope)?;
-        return require_user_tenant_read_with_state(state, auth, tenant_id).await;
+        return require_user_tenant_read_with_state(state, ctx, tenant_id).await;
     }
     if input.action == PolicyAction::RbacAssignSelf {
-        if is_platform_admin_with_db(state, auth).await {
+        if ctx.is_platform_admin(state).await {
             return Ok(());
         }
         return Err(AppError::Forbidden(
@@ -314,7 +338,13 @@ pub async fn enforce_with_state<S: HasServices>(
     }
     if input.action == PolicyAction::UserReadOther {
         let target_user_id = require_user_scope(&input.scope)?;
-        return require_user_read_other_with_state(state, auth, target_user_id).await;
+        return require_user_read_other_with_state(state, ctx, target_user_id).await;
+    }
+    if input.action == PolicyAction::SessionForceLogout
+        && auth.token_type == TokenType::TenantAccess
+    {
+        let target_user_id = require_user_scope(&input.scope)?;
+        return require_session_revoke_with_state(state, ctx, target_user_id).await;
     }
     if matches!(
         input.action,
@@ -323,16 +353,14 @@ pub async fn enforce_with_state<S: HasServices>(
         let tenant_id = require_tenant_scope(&input.scope)?;
         return require_tenant_owner_with_state(
             state,
-            auth,
+            ctx,
             tenant_id,
             matches!(input.action, PolicyAction::TenantOwner),
         )
         .await;
     }
 
-    if action_supports_db_platform_admin(input.action)
-        && is_platform_admin_with_db(state, auth).await
-    {
+    if action_supports_db_platform_admin(input.action) && ctx.is_platform_admin(state).await {
         // Platform admin bypass granted, but TenantAccess tokens are always
         // scoped to their issuing tenant — a token for tenant A must not
         // access tenant B's resources regardless of platform admin status.
@@ -419,10 +447,11 @@ fn require_user_scope(scope: &ResourceScope) -> PolicyResult<StringUuid> {
 
 async fn require_user_tenant_read_with_state<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    ctx: &AuthzContext,
     tenant_id: StringUuid,
 ) -> PolicyResult<()> {
-    if is_platform_admin_with_db(state, auth).await {
+    let auth = ctx.auth();
+    if ctx.is_platform_admin(state).await {
         return Ok(());
     }
     match auth.token_type {
@@ -455,10 +484,11 @@ async fn require_user_tenant_read_with_state<S: HasServices>(
 
 async fn require_user_read_other_with_state<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    ctx: &AuthzContext,
     target_user_id: StringUuid,
 ) -> PolicyResult<()> {
-    if is_platform_admin_with_db(state, auth).await {
+    let auth = ctx.auth();
+    if ctx.is_platform_admin(state).await {
         return Ok(());
     }
     match auth.token_type {
@@ -483,8 +513,7 @@ async fn require_user_read_other_with_state<S: HasServices>(
             }
         }
         TokenType::Identity => {
-            let auth_user_id = StringUuid::from(auth.user_id);
-            let auth_user_tenants = state.user_service().get_user_tenants(auth_user_id).await?;
+            let auth_user_tenants = ctx.memberships(state).await?;
             let target_user_tenants = state
                 .user_service()
                 .get_user_tenants(target_user_id)
@@ -513,19 +542,57 @@ async fn require_user_read_other_with_state<S: HasServices>(
     }
 }
 
+/// Platform admins revoke any user's sessions; tenant admins those of members
+/// of their tenant, within their admin units
+async fn require_session_revoke_with_state<S: HasServices>(
+    state: &S,
+    ctx: &AuthzContext,
+    target_user_id: StringUuid,
+) -> PolicyResult<()> {
+    if ctx.is_platform_admin(state).await {
+        return Ok(());
+    }
+    let auth = ctx.auth();
+    let tenant_id = auth
+        .tenant_id
+        .ok_or_else(|| AppError::Forbidden("No tenant context in token".to_string()))?;
+    enforce(
+        state.config(),
+        auth,
+        &PolicyInput {
+            action: PolicyAction::UserManage,
+            scope: ResourceScope::Global,
+        },
+    )?;
+    let is_member = state
+        .user_service()
+        .get_user_tenants(target_user_id)
+        .await?
+        .iter()
+        .any(|tu| *tu.tenant_id == tenant_id);
+    if !is_member {
+        return Err(AppError::NotFound(format!(
+            "User {} not found",
+            target_user_id
+        )));
+    }
+    enforce_admin_unit_boundary(state, ctx, StringUuid::from(tenant_id), target_user_id).await
+}
+
 async fn require_tenant_owner_with_state<S: HasServices>(
     state: &S,
-    auth: &AuthUser,
+    ctx: &AuthzContext,
     tenant_id: StringUuid,
     allow_platform_admin_bypass: bool,
 ) -> PolicyResult<()> {
+    let auth = ctx.auth();
     if auth.token_type == TokenType::ServiceClient {
         return Err(AppError::Forbidden(
             "Service client tokens cannot perform tenant owner operations".to_string(),
         ));
     }
 
-    if allow_platform_admin_bypass && is_platform_admin_with_db(state, auth).await {
+    if allow_platform_admin_bypass && ctx.is_platform_admin(state).await {
         return Ok(());
     }
 
@@ -538,12 +605,7 @@ async fn require_tenant_owner_with_state<S: HasServices>(
         ));
     }
 
-    let user_id = StringUuid::from(auth.user_id);
-    let tenant_users = state.user_service().get_user_tenants(user_id).await?;
-    if tenant_users
-        .iter()
-        .any(|tu| tu.tenant_id == tenant_id && tu.role_in_tenant == "owner")
-    {
+    if ctx.role_in_tenant(state, tenant_id).await? == Some("owner") {
         Ok(())
     } else {
         Err(AppError::Forbidden(
@@ -587,7 +649,7 @@ fn require_tenant_admin_or_permission(
             let is_admin = auth.roles.iter().any(|r| r == "owner" || r == "admin");
             let has_permission = permissions
                 .iter()
-                .any(|permission| auth.permissions.iter().any(|p| p == permission));
+                .any(|permission| auth.has_permission(permission));
 
             if is_admin || has_permission {
                 Ok(())
@@ -643,15 +705,20 @@ fn action_supports_db_platform_admin(action: PolicyAction) -> bool {
         PolicyAction::PlatformAdmin
             | PolicyAction::AuditRead
             | PolicyAction::SessionForceLogout
+            | PolicyAction::SessionExport
             | PolicyAction::SecurityAlertRead
             | PolicyAction::SecurityAlertResolve
             | PolicyAction::UserWrite
+            | PolicyAction::PlatformWebhookRead
+            | PolicyAction::PlatformWebhookWrite
             | PolicyAction::SystemConfigRead
             | PolicyAction::SystemConfigWrite
             | PolicyAction::TenantRead
             | PolicyAction::TenantWrite
             | PolicyAction::InvitationRead
             | PolicyAction::InvitationWrite
+            | PolicyAction::MfaResetReview
+            | PolicyAction::LoginIdentifierManage
             | PolicyAction::UserManage
             | PolicyAction::UserTenantRead
             | PolicyAction::UserReadOther
@@ -753,6 +820,7 @@ mod tests {
             aud: None,
             roles: vec![],
             permissions: vec![],
+            scope: None,
         }
     }
 
@@ -765,6 +833,7 @@ mod tests {
             aud: None,
             roles: vec!["admin".to_string()],
             permissions: vec![],
+            scope: None,
         }
     }
 
@@ -777,6 +846,7 @@ mod tests {
             aud: None,
             roles: vec!["owner".to_string()],
             permissions: vec![],
+            scope: None,
         }
     }
 
@@ -789,6 +859,7 @@ mod tests {
             aud: None,
             roles: vec!["member".to_string()],
             permissions,
+            scope: None,
         }
     }
 
@@ -801,6 +872,7 @@ mod tests {
             aud: None,
             roles: vec![],
             permissions: vec![],
+            scope: None,
         }
     }
 
@@ -856,6 +928,25 @@ mod tests {
         assert!(enforce(&config, &admin, &input).is_ok());
     }
 
+    #[test]
+    fn test_platform_webhook_requires_platform_admin() {
+        let config = create_test_config(vec!["admin@platform.com".to_string()]);
+        let tenant_id = StringUuid::new_v4();
+        let input = PolicyInput {
+            action: PolicyAction::PlatformWebhookWrite,
+            scope: ResourceScope::Global,
+        };
+
+        assert!(enforce(&config, &create_platform_admin(), &input).is_ok());
+        // Tenant webhook permissions do not extend to platform webhooks
+        let tenant_admin = create_tenant_user(tenant_id, vec!["webhook:*".to_string()]);
+        assert!(matches!(
+            enforce(&config, &tenant_admin, &input),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(enforce(&config, &create_service_client(Some(tenant_id)), &input).is_err());
+    }
+
     #[test]
     fn test_webhook_read_tenant_admin_can_access() {
         let config = create_test_config(vec![]);
@@ -869,6 +960,25 @@ mod tests {
         assert!(enforce(&config, &admin, &input).is_ok());
     }
 
+    #[test]
+    fn test_session_export_requires_tenant_admin_or_session_read() {
+        let config = create_test_config(vec![]);
+        let tenant_id = StringUuid::new_v4();
+        let input = PolicyInput {
+            action: PolicyAction::SessionExport,
+            scope: ResourceScope::Tenant(tenant_id),
+        };
+
+        assert!(enforce(&config, &create_tenant_admin(tenant_id), &input).is_ok());
+        let reader = create_tenant_user(tenant_id, vec!["session:read".to_string()]);
+        assert!(enforce(&config, &reader, &input).is_ok());
+        let member = create_tenant_user(tenant_id, vec![]);
+        assert!(matches!(
+            enforce(&config, &member, &input),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
     #[test]
     fn test_webhook_read_with_permission() {
         let config = create_test_config(vec![]);
@@ -1399,6 +1509,35 @@ mod tests {
         assert!(enforce(&config, &admin, &input).is_ok());
     }
 
+    #[test]
+    fn test_mfa_reset_review_requires_admin_of_same_tenant() {
+        let config = create_test_config(vec![]);
+        let tenant_id = StringUuid::new_v4();
+        let input = PolicyInput {
+            action: PolicyAction::MfaResetReview,
+            scope: ResourceScope::Tenant(tenant_id),
+        };
+
+        assert!(enforce(&config, &create_tenant_admin(tenant_id), &input).is_ok());
+        assert!(enforce(&config, &create_tenant_user(tenant_id, vec![]), &input).is_err());
+        assert!(enforce(&config, &create_tenant_admin(StringUuid::new_v4()), &input).is_err());
+    }
+
+    #[test]
+    fn test_login_identifier_manage_allows_user_writers_of_same_tenant() {
+        let config = create_test_config(vec![]);
+        let tenant_id = StringUuid::new_v4();
+        let input = PolicyInput {
+            action: PolicyAction::LoginIdentifierManage,
+            scope: ResourceScope::Tenant(tenant_id),
+        };
+
+        let writer = create_tenant_user(tenant_id, vec!["user:write".to_string()]);
+        assert!(enforce(&config, &writer, &input).is_ok());
+        assert!(enforce(&config, &create_tenant_user(tenant_id, vec![]), &input).is_err());
+        assert!(enforce(&config, &create_tenant_admin(StringUuid::new_v4()), &input).is_err());
+    }
+
     #[test]
     fn test_action_write_with_permission() {
         let config = create_test_config(vec![]);
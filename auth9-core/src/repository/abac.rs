@@ -24,6 +24,15 @@ pub struct AbacPolicyVersionRecord {
     pub created_by: Option<StringUuid>,
     pub created_at: DateTime<Utc>,
     pub published_at: Option<DateTime<Utc>>,
+    /// Optimistic concurrency revision, bumped on every draft edit
+    pub revision: i32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct AbacPolicyVersionDocumentRecord {
+    #[sqlx(flatten)]
+    pub version: AbacPolicyVersionRecord,
+    pub policy_json: String,
 }
 
 #[derive(Debug, Clone)]
@@ -59,10 +68,17 @@ pub trait AbacRepository: Send + Sync {
         change_note: Option<String>,
         created_by: StringUuid,
     ) -> Result<AbacDraftCreateResult>;
+    async fn fetch_version_for_tenant(
+        &self,
+        tenant_id: StringUuid,
+        version_id: StringUuid,
+    ) -> Result<Option<AbacPolicyVersionDocumentRecord>>;
+    /// Replace a draft if it is still at `revision`
     async fn update_draft_for_tenant(
         &self,
         tenant_id: StringUuid,
         version_id: StringUuid,
+        revision: i32,
         policy_json: String,
         change_note: Option<String>,
     ) -> Result<bool>;
@@ -112,7 +128,8 @@ impl AbacRepository for AbacRepositoryImpl {
     ) -> Result<Vec<AbacPolicyVersionRecord>> {
         sqlx::query_as::<_, AbacPolicyVersionRecord>(
             r#"
-            SELECT id, policy_set_id, version_no, status, change_note, created_by, created_at, published_at
+            SELECT id, policy_set_id, version_no, status, change_note, created_by, created_at,
+                   published_at, revision
             FROM abac_policy_set_versions
             WHERE policy_set_id = ?
             ORDER BY version_no DESC
@@ -185,10 +202,33 @@ impl AbacRepository for AbacRepositoryImpl {
         })
     }
 
+    async fn fetch_version_for_tenant(
+        &self,
+        tenant_id: StringUuid,
+        version_id: StringUuid,
+    ) -> Result<Option<AbacPolicyVersionDocumentRecord>> {
+        sqlx::query_as::<_, AbacPolicyVersionDocumentRecord>(
+            r#"
+            SELECT psv.id, psv.policy_set_id, psv.version_no, psv.status, psv.change_note,
+                   psv.created_by, psv.created_at, psv.published_at, psv.revision,
+                   CAST(psv.policy_json AS CHAR) as policy_json
+            FROM abac_policy_set_versions psv
+            JOIN abac_policy_sets ps ON ps.id = psv.policy_set_id
+            WHERE psv.id = ? AND ps.tenant_id = ?
+            "#,
+        )
+        .bind(version_id)
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Into::into)
+    }
+
     async fn update_draft_for_tenant(
         &self,
         tenant_id: StringUuid,
         version_id: StringUuid,
+        revision: i32,
         policy_json: String,
         change_note: Option<String>,
     ) -> Result<bool> {
@@ -196,14 +236,16 @@ impl AbacRepository for AbacRepositoryImpl {
             r#"
             UPDATE abac_policy_set_versions psv
             JOIN abac_policy_sets ps ON ps.id = psv.policy_set_id
-            SET psv.policy_json = ?, psv.change_note = ?
+            SET psv.policy_json = ?, psv.change_note = ?, psv.revision = psv.revision + 1
             WHERE psv.id = ? AND ps.tenant_id = ? AND psv.status = 'draft'
+              AND psv.revision = ?
             "#,
         )
         .bind(policy_json)
         .bind(change_note)
         .bind(version_id)
         .bind(tenant_id)
+        .bind(revision)
         .execute(&self.pool)
         .await?;
 
@@ -0,0 +1,143 @@
+//! Account deletion request repository
+
+use crate::error::Result;
+use crate::models::account_deletion::{AccountDeletionNotice, AccountDeletionRequest};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AccountDeletionRepository: Send + Sync {
+    /// Insert a request; an existing request for the user is a `Conflict`
+    async fn create(&self, request: &AccountDeletionRequest) -> Result<()>;
+    async fn find(&self, user_id: StringUuid) -> Result<Option<AccountDeletionRequest>>;
+    /// Remove a request; returns whether one existed
+    async fn delete(&self, user_id: StringUuid) -> Result<bool>;
+    /// Requests purged before `purge_before` that have not been reminded
+    async fn list_due_reminders(
+        &self,
+        purge_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionNotice>>;
+    async fn mark_reminded(&self, user_id: StringUuid, at: DateTime<Utc>) -> Result<()>;
+    /// Requests whose grace period ended before `now` and have no purge job
+    async fn list_due_purges(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionRequest>>;
+    async fn set_purge_job(&self, user_id: StringUuid, job_id: StringUuid) -> Result<()>;
+}
+
+pub struct AccountDeletionRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AccountDeletionRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AccountDeletionRepository for AccountDeletionRepositoryImpl {
+    async fn create(&self, request: &AccountDeletionRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_deletion_requests (user_id, requested_at, purge_after)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(request.user_id)
+        .bind(request.requested_at)
+        .bind(request.purge_after)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find(&self, user_id: StringUuid) -> Result<Option<AccountDeletionRequest>> {
+        let request = sqlx::query_as::<_, AccountDeletionRequest>(
+            r#"
+            SELECT user_id, requested_at, purge_after, reminder_sent_at, purge_job_id
+            FROM account_deletion_requests
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(request)
+    }
+
+    async fn delete(&self, user_id: StringUuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM account_deletion_requests WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_due_reminders(
+        &self,
+        purge_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionNotice>> {
+        let notices = sqlx::query_as::<_, AccountDeletionNotice>(
+            r#"
+            SELECT r.user_id, u.email, u.display_name, u.locale, r.purge_after
+            FROM account_deletion_requests r
+            JOIN users u ON u.id = r.user_id
+            WHERE r.purge_after <= ? AND r.reminder_sent_at IS NULL AND r.purge_job_id IS NULL
+            ORDER BY r.purge_after
+            LIMIT ?
+            "#,
+        )
+        .bind(purge_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(notices)
+    }
+
+    async fn mark_reminded(&self, user_id: StringUuid, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE account_deletion_requests SET reminder_sent_at = ? WHERE user_id = ?")
+            .bind(at)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_due_purges(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionRequest>> {
+        let requests = sqlx::query_as::<_, AccountDeletionRequest>(
+            r#"
+            SELECT user_id, requested_at, purge_after, reminder_sent_at, purge_job_id
+            FROM account_deletion_requests
+            WHERE purge_after <= ? AND purge_job_id IS NULL
+            ORDER BY purge_after
+            LIMIT ?
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(requests)
+    }
+
+    async fn set_purge_job(&self, user_id: StringUuid, job_id: StringUuid) -> Result<()> {
+        sqlx::query("UPDATE account_deletion_requests SET purge_job_id = ? WHERE user_id = ?")
+            .bind(job_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
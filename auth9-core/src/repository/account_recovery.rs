@@ -0,0 +1,177 @@
+//! Account recovery request repository
+
+use crate::error::Result;
+use crate::models::account_recovery::{AccountRecoveryApproval, AccountRecoveryRequest};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AccountRecoveryRepository: Send + Sync {
+    async fn create(&self, request: &AccountRecoveryRequest) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AccountRecoveryRequest>>;
+    /// Pending or approved request of the user that has not expired yet
+    async fn find_open_by_user(
+        &self,
+        user_id: StringUuid,
+    ) -> Result<Option<AccountRecoveryRequest>>;
+    async fn list(&self, status: Option<String>, limit: i64)
+        -> Result<Vec<AccountRecoveryRequest>>;
+    /// Record an admin's decision. A second decision by the same admin fails
+    /// with a conflict.
+    async fn add_approval(&self, approval: &AccountRecoveryApproval) -> Result<()>;
+    async fn list_approvals(&self, request_id: StringUuid) -> Result<Vec<AccountRecoveryApproval>>;
+    /// Move a request from `from` to `to`. Returns false if it was no longer
+    /// in `from`, so concurrent deciders and claimers cannot both win.
+    async fn transition(&self, id: StringUuid, from: &str, to: &str) -> Result<bool>;
+}
+
+pub struct AccountRecoveryRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AccountRecoveryRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, user_id, status, reason, claim_secret_hash, requester_ip, created_at,
+           expires_at, decided_at, claimed_at
+    FROM account_recovery_requests
+"#;
+
+#[async_trait]
+impl AccountRecoveryRepository for AccountRecoveryRepositoryImpl {
+    async fn create(&self, request: &AccountRecoveryRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_recovery_requests
+                (id, user_id, status, reason, claim_secret_hash, requester_ip, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.id)
+        .bind(request.user_id)
+        .bind(&request.status)
+        .bind(&request.reason)
+        .bind(&request.claim_secret_hash)
+        .bind(&request.requester_ip)
+        .bind(request.created_at)
+        .bind(request.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AccountRecoveryRequest>> {
+        let request = sqlx::query_as::<_, AccountRecoveryRequest>(&format!(
+            "{} WHERE id = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(request)
+    }
+
+    async fn find_open_by_user(
+        &self,
+        user_id: StringUuid,
+    ) -> Result<Option<AccountRecoveryRequest>> {
+        let request = sqlx::query_as::<_, AccountRecoveryRequest>(&format!(
+            "{} WHERE user_id = ? AND status IN ('pending', 'approved') AND expires_at > NOW() \
+             ORDER BY created_at DESC LIMIT 1",
+            SELECT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(request)
+    }
+
+    async fn list(
+        &self,
+        status: Option<String>,
+        limit: i64,
+    ) -> Result<Vec<AccountRecoveryRequest>> {
+        let requests = match status {
+            Some(status) => {
+                sqlx::query_as::<_, AccountRecoveryRequest>(&format!(
+                    "{} WHERE status = ? ORDER BY created_at DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(status)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, AccountRecoveryRequest>(&format!(
+                    "{} ORDER BY created_at DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(requests)
+    }
+
+    async fn add_approval(&self, approval: &AccountRecoveryApproval) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_recovery_approvals
+                (id, request_id, admin_id, decision, comment, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(approval.id)
+        .bind(approval.request_id)
+        .bind(approval.admin_id)
+        .bind(&approval.decision)
+        .bind(&approval.comment)
+        .bind(approval.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_approvals(&self, request_id: StringUuid) -> Result<Vec<AccountRecoveryApproval>> {
+        let approvals = sqlx::query_as::<_, AccountRecoveryApproval>(
+            r#"
+            SELECT id, request_id, admin_id, decision, comment, created_at
+            FROM account_recovery_approvals
+            WHERE request_id = ?
+            ORDER BY created_at
+            "#,
+        )
+        .bind(request_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(approvals)
+    }
+
+    async fn transition(&self, id: StringUuid, from: &str, to: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE account_recovery_requests
+            SET status = ?,
+                decided_at = CASE WHEN ? IN ('approved', 'rejected') THEN NOW() ELSE decided_at END,
+                claimed_at = CASE WHEN ? = 'claimed' THEN NOW() ELSE claimed_at END
+            WHERE id = ? AND status = ?
+            "#,
+        )
+        .bind(to)
+        .bind(to)
+        .bind(to)
+        .bind(id)
+        .bind(from)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
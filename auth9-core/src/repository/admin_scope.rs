@@ -0,0 +1,80 @@
+//! Platform admin scope repository
+
+use crate::error::Result;
+use crate::models::admin_scope::{AdminScope, AdminScopeGrant};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AdminScopeRepository: Send + Sync {
+    async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<AdminScopeGrant>>;
+    /// Replace every scope granted to the user
+    async fn replace_for_user(
+        &self,
+        user_id: StringUuid,
+        scopes: &[AdminScope],
+        granted_by: Option<StringUuid>,
+    ) -> Result<()>;
+}
+
+pub struct AdminScopeRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AdminScopeRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AdminScopeRepository for AdminScopeRepositoryImpl {
+    async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<AdminScopeGrant>> {
+        let grants = sqlx::query_as::<_, AdminScopeGrant>(
+            r#"
+            SELECT id, user_id, scope, granted_by, created_at
+            FROM platform_admin_scopes
+            WHERE user_id = ?
+            ORDER BY scope
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(grants)
+    }
+
+    async fn replace_for_user(
+        &self,
+        user_id: StringUuid,
+        scopes: &[AdminScope],
+        granted_by: Option<StringUuid>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM platform_admin_scopes WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for scope in scopes {
+            sqlx::query(
+                r#"
+                INSERT INTO platform_admin_scopes (id, user_id, scope, granted_by)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+            .bind(StringUuid::new_v4())
+            .bind(user_id)
+            .bind(scope.as_str())
+            .bind(granted_by)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
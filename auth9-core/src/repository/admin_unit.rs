@@ -0,0 +1,252 @@
+//! Admin unit repository
+
+use crate::error::{AppError, Result};
+use crate::models::admin_unit::{AdminUnit, CreateAdminUnitInput, UpdateAdminUnitInput};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AdminUnitRepository: Send + Sync {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: &CreateAdminUnitInput,
+    ) -> Result<AdminUnit>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AdminUnit>>;
+    async fn find_by_name(&self, tenant_id: StringUuid, name: &str) -> Result<Option<AdminUnit>>;
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<AdminUnit>>;
+    async fn update(&self, id: StringUuid, input: &UpdateAdminUnitInput) -> Result<AdminUnit>;
+    /// Delete a unit with its members and admins
+    async fn delete(&self, id: StringUuid) -> Result<()>;
+    async fn list_members(&self, id: StringUuid) -> Result<Vec<StringUuid>>;
+    async fn add_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()>;
+    async fn remove_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()>;
+    async fn list_admins(&self, id: StringUuid) -> Result<Vec<StringUuid>>;
+    async fn add_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()>;
+    async fn remove_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()>;
+    async fn is_tenant_member(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<bool>;
+    async fn administered_unit_ids(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+    ) -> Result<Vec<StringUuid>>;
+    /// Whether `user_id` is a tenant member in a unit administered by `admin_id`
+    async fn manages_member(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<bool>;
+}
+
+pub struct AdminUnitRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AdminUnitRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, name, description, created_at, updated_at
+    FROM admin_units
+"#;
+
+#[async_trait]
+impl AdminUnitRepository for AdminUnitRepositoryImpl {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: &CreateAdminUnitInput,
+    ) -> Result<AdminUnit> {
+        let id = StringUuid::new_v4();
+        sqlx::query(
+            "INSERT INTO admin_units (id, tenant_id, name, description) VALUES (?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to create admin unit")))
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AdminUnit>> {
+        let query = format!("{} WHERE id = ?", SELECT_COLUMNS);
+        let unit = sqlx::query_as::<_, AdminUnit>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(unit)
+    }
+
+    async fn find_by_name(&self, tenant_id: StringUuid, name: &str) -> Result<Option<AdminUnit>> {
+        let query = format!("{} WHERE tenant_id = ? AND name = ?", SELECT_COLUMNS);
+        let unit = sqlx::query_as::<_, AdminUnit>(&query)
+            .bind(tenant_id)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(unit)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<AdminUnit>> {
+        let query = format!("{} WHERE tenant_id = ? ORDER BY name", SELECT_COLUMNS);
+        let units = sqlx::query_as::<_, AdminUnit>(&query)
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(units)
+    }
+
+    async fn update(&self, id: StringUuid, input: &UpdateAdminUnitInput) -> Result<AdminUnit> {
+        sqlx::query(
+            r#"
+            UPDATE admin_units
+            SET name = COALESCE(?, name), description = COALESCE(?, description)
+            WHERE id = ?
+            "#,
+        )
+        .bind(&input.name)
+        .bind(&input.description)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Admin unit {} not found", id)))
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for query in [
+            "DELETE FROM admin_unit_members WHERE admin_unit_id = ?",
+            "DELETE FROM admin_unit_admins WHERE admin_unit_id = ?",
+            "DELETE FROM admin_units WHERE id = ?",
+        ] {
+            sqlx::query(query).bind(id).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn list_members(&self, id: StringUuid) -> Result<Vec<StringUuid>> {
+        let ids = sqlx::query_scalar(
+            "SELECT user_id FROM admin_unit_members WHERE admin_unit_id = ? ORDER BY user_id",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn add_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        sqlx::query("INSERT IGNORE INTO admin_unit_members (admin_unit_id, user_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        sqlx::query("DELETE FROM admin_unit_members WHERE admin_unit_id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_admins(&self, id: StringUuid) -> Result<Vec<StringUuid>> {
+        let ids = sqlx::query_scalar(
+            "SELECT user_id FROM admin_unit_admins WHERE admin_unit_id = ? ORDER BY user_id",
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn add_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        sqlx::query("INSERT IGNORE INTO admin_unit_admins (admin_unit_id, user_id) VALUES (?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        sqlx::query("DELETE FROM admin_unit_admins WHERE admin_unit_id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_tenant_member(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<bool> {
+        let member: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM tenant_users WHERE tenant_id = ? AND user_id = ?)",
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(member)
+    }
+
+    async fn administered_unit_ids(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+    ) -> Result<Vec<StringUuid>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT u.id FROM admin_units u
+            INNER JOIN admin_unit_admins a ON a.admin_unit_id = u.id
+            WHERE u.tenant_id = ? AND a.user_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(admin_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn manages_member(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<bool> {
+        // Memberships left behind by users removed from the tenant do not count
+        let managed: Option<StringUuid> = sqlx::query_scalar(
+            r#"
+            SELECT m.user_id FROM admin_units u
+            INNER JOIN admin_unit_admins a ON a.admin_unit_id = u.id
+            INNER JOIN admin_unit_members m ON m.admin_unit_id = u.id
+            INNER JOIN tenant_users tu ON tu.tenant_id = u.tenant_id AND tu.user_id = m.user_id
+            WHERE u.tenant_id = ? AND a.user_id = ? AND m.user_id = ?
+            LIMIT 1
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(admin_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(managed.is_some())
+    }
+}
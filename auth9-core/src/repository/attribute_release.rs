@@ -0,0 +1,75 @@
+//! Service attribute release policy repository
+
+use crate::error::{AppError, Result};
+use crate::models::attribute_release::AttributeReleasePolicy;
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AttributeReleaseRepository: Send + Sync {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<AttributeReleasePolicy>>;
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        released_attributes: &[String],
+    ) -> Result<AttributeReleasePolicy>;
+}
+
+pub struct AttributeReleaseRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AttributeReleaseRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AttributeReleaseRepository for AttributeReleaseRepositoryImpl {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<AttributeReleasePolicy>> {
+        let policy = sqlx::query_as::<_, AttributeReleasePolicy>(
+            r#"
+            SELECT service_id, released_attributes, created_at, updated_at
+            FROM service_attribute_release_policies
+            WHERE service_id = ?
+            "#,
+        )
+        .bind(service_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(policy)
+    }
+
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        released_attributes: &[String],
+    ) -> Result<AttributeReleasePolicy> {
+        let released =
+            serde_json::to_string(released_attributes).map_err(|e| AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO service_attribute_release_policies (service_id, released_attributes)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE released_attributes = VALUES(released_attributes)
+            "#,
+        )
+        .bind(service_id)
+        .bind(&released)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_service(service_id).await?.ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Failed to store attribute release policy"))
+        })
+    }
+}
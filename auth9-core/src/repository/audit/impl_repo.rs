@@ -4,9 +4,18 @@ use super::{
     AuditLog, AuditLogQuery, AuditLogWithActor, AuditRepository, AuditRepositoryImpl,
     CreateAuditLogInput,
 };
-use crate::error::Result;
+use crate::error::{AppError, Result};
+use crate::models::audit_integrity::{self, ChainedFields};
+use crate::models::audit_search::{
+    boolean_search_query, search_document, AuditHistogramBucket, AuditHistogramInterval,
+};
 use crate::models::common::StringUuid;
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+/// Restricts a query to entries whose search document matches
+const SEARCH_CONDITION: &str = "IN (SELECT audit_log_id FROM audit_log_search \
+     WHERE MATCH(document) AGAINST (? IN BOOLEAN MODE))";
 
 #[async_trait]
 impl AuditRepository for AuditRepositoryImpl {
@@ -14,10 +23,46 @@ impl AuditRepository for AuditRepositoryImpl {
         let actor_id = input.actor_id.map(|id| id.to_string());
         let resource_id = input.resource_id.map(|id| id.to_string());
 
-        sqlx::query(
+        let document = search_document(
+            &input.action,
+            &input.resource_type,
+            resource_id.as_deref(),
+            input.old_value.as_ref(),
+            input.new_value.as_ref(),
+        );
+
+        // Appends to the hash chain are serialized by the lock on its head
+        let mut tx = self.pool.begin().await?;
+        let (last_seq, last_hash): (i64, String) = sqlx::query_as(
+            "SELECT last_seq, last_hash FROM audit_chain_head WHERE id = 1 FOR UPDATE",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Stored with second precision, so hash exactly what is stored
+        let created_at = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap_or_default();
+        let chain_seq = last_seq + 1;
+        let actor_digest = actor_id.as_deref().map(audit_integrity::actor_digest);
+        let row_hash = audit_integrity::entry_hash(
+            &last_hash,
+            &ChainedFields {
+                chain_seq,
+                created_at,
+                actor_digest: actor_digest.as_deref(),
+                action: &input.action,
+                resource_type: &input.resource_type,
+                resource_id: resource_id.as_deref(),
+                old_value: input.old_value.as_ref(),
+                new_value: input.new_value.as_ref(),
+                ip_address: input.ip_address.as_deref(),
+            },
+        );
+
+        let result = sqlx::query(
             r#"
-            INSERT INTO audit_logs (actor_id, action, resource_type, resource_id, old_value, new_value, ip_address, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, NOW())
+            INSERT INTO audit_logs (actor_id, action, resource_type, resource_id, old_value, new_value, ip_address,
+                                    created_at, chain_seq, actor_digest, prev_hash, row_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(actor_id)
@@ -27,9 +72,34 @@ impl AuditRepository for AuditRepositoryImpl {
         .bind(input.old_value.as_ref().map(sqlx::types::Json))
         .bind(input.new_value.as_ref().map(sqlx::types::Json))
         .bind(&input.ip_address)
-        .execute(&self.pool)
+        .bind(created_at)
+        .bind(chain_seq)
+        .bind(actor_digest)
+        .bind(&last_hash)
+        .bind(&row_hash)
+        .execute(&mut *tx)
         .await?;
 
+        sqlx::query("UPDATE audit_chain_head SET last_seq = ?, last_hash = ? WHERE id = 1")
+            .bind(chain_seq)
+            .bind(&row_hash)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        // The entry itself is already recorded; a missing search row only
+        // hides it from full-text queries
+        if let Err(e) = sqlx::query(
+            "INSERT INTO audit_log_search (audit_log_id, created_at, document) VALUES (?, NOW(), ?)",
+        )
+        .bind(result.last_insert_id() as i64)
+        .bind(document)
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(error = %e, "Failed to index audit log entry for search");
+        }
+
         Ok(())
     }
 
@@ -56,6 +126,11 @@ impl AuditRepository for AuditRepositoryImpl {
         if query.to_date.is_some() {
             sql.push_str(" AND created_at <= ?");
         }
+        let search = query.q.as_deref().and_then(boolean_search_query);
+        if search.is_some() {
+            sql.push_str(" AND id ");
+            sql.push_str(SEARCH_CONDITION);
+        }
 
         sql.push_str(" ORDER BY created_at DESC LIMIT ? OFFSET ?");
 
@@ -79,6 +154,9 @@ impl AuditRepository for AuditRepositoryImpl {
         if let Some(to_date) = query.to_date {
             query_builder = query_builder.bind(to_date);
         }
+        if let Some(ref search) = search {
+            query_builder = query_builder.bind(search);
+        }
 
         let limit = query.limit.unwrap_or(50).min(100);
         let offset = query.offset.unwrap_or(0);
@@ -115,6 +193,11 @@ impl AuditRepository for AuditRepositoryImpl {
         if query.to_date.is_some() {
             sql.push_str(" AND al.created_at <= ?");
         }
+        let search = query.q.as_deref().and_then(boolean_search_query);
+        if search.is_some() {
+            sql.push_str(" AND al.id ");
+            sql.push_str(SEARCH_CONDITION);
+        }
 
         sql.push_str(" ORDER BY al.created_at DESC LIMIT ? OFFSET ?");
 
@@ -138,6 +221,9 @@ impl AuditRepository for AuditRepositoryImpl {
         if let Some(to_date) = query.to_date {
             query_builder = query_builder.bind(to_date);
         }
+        if let Some(ref search) = search {
+            query_builder = query_builder.bind(search);
+        }
 
         let limit = query.limit.unwrap_or(50).min(100);
         let offset = query.offset.unwrap_or(0);
@@ -168,6 +254,11 @@ impl AuditRepository for AuditRepositoryImpl {
         if query.to_date.is_some() {
             sql.push_str(" AND created_at <= ?");
         }
+        let search = query.q.as_deref().and_then(boolean_search_query);
+        if search.is_some() {
+            sql.push_str(" AND id ");
+            sql.push_str(SEARCH_CONDITION);
+        }
 
         let mut query_builder = sqlx::query_as::<_, (i64,)>(&sql);
 
@@ -189,11 +280,120 @@ impl AuditRepository for AuditRepositoryImpl {
         if let Some(to_date) = query.to_date {
             query_builder = query_builder.bind(to_date);
         }
+        if let Some(ref search) = search {
+            query_builder = query_builder.bind(search);
+        }
 
         let (count,) = query_builder.fetch_one(&self.pool).await?;
         Ok(count)
     }
 
+    async fn histogram(
+        &self,
+        query: &AuditLogQuery,
+        interval: AuditHistogramInterval,
+    ) -> Result<Vec<AuditHistogramBucket>> {
+        let bucket = interval.bucket_sql("created_at");
+        let mut sql = format!(
+            "SELECT {} AS bucket_start, COUNT(*) AS count FROM audit_logs WHERE 1=1",
+            bucket
+        );
+
+        if query.actor_id.is_some() {
+            sql.push_str(" AND actor_id = ?");
+        }
+        if query.resource_type.is_some() {
+            sql.push_str(" AND resource_type = ?");
+        }
+        if query.resource_id.is_some() {
+            sql.push_str(" AND resource_id = ?");
+        }
+        if query.action.is_some() {
+            sql.push_str(" AND action = ?");
+        }
+        if query.from_date.is_some() {
+            sql.push_str(" AND created_at >= ?");
+        }
+        if query.to_date.is_some() {
+            sql.push_str(" AND created_at <= ?");
+        }
+        let search = query.q.as_deref().and_then(boolean_search_query);
+        if search.is_some() {
+            sql.push_str(" AND id ");
+            sql.push_str(SEARCH_CONDITION);
+        }
+
+        sql.push_str(" GROUP BY bucket_start ORDER BY bucket_start");
+
+        let mut query_builder = sqlx::query_as::<_, (String, i64)>(&sql);
+
+        if let Some(actor_id) = query.actor_id {
+            query_builder = query_builder.bind(actor_id.to_string());
+        }
+        if let Some(ref resource_type) = query.resource_type {
+            query_builder = query_builder.bind(resource_type);
+        }
+        if let Some(resource_id) = query.resource_id {
+            query_builder = query_builder.bind(resource_id.to_string());
+        }
+        if let Some(ref action) = query.action {
+            query_builder = query_builder.bind(action);
+        }
+        if let Some(from_date) = query.from_date {
+            query_builder = query_builder.bind(from_date);
+        }
+        if let Some(to_date) = query.to_date {
+            query_builder = query_builder.bind(to_date);
+        }
+        if let Some(ref search) = search {
+            query_builder = query_builder.bind(search);
+        }
+
+        let rows = query_builder.fetch_all(&self.pool).await?;
+        rows.into_iter()
+            .map(|(bucket_start, count)| {
+                let bucket_start =
+                    NaiveDateTime::parse_from_str(&bucket_start, "%Y-%m-%d %H:%M:%S")
+                        .map_err(|e| {
+                            AppError::Internal(anyhow::anyhow!(
+                                "Invalid histogram bucket '{}': {}",
+                                bucket_start,
+                                e
+                            ))
+                        })?
+                        .and_utc();
+                Ok(AuditHistogramBucket {
+                    bucket_start,
+                    count,
+                })
+            })
+            .collect()
+    }
+
+    async fn find_resource_history(
+        &self,
+        resource_type: &str,
+        resource_id: &str,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AuditLog>> {
+        let mut logs = sqlx::query_as::<_, AuditLog>(
+            "SELECT id, actor_id, action, resource_type, resource_id, old_value, new_value, ip_address, created_at \
+             FROM audit_logs \
+             WHERE resource_type = ? AND resource_id = ? AND created_at <= ? \
+             ORDER BY created_at DESC, id DESC LIMIT ?",
+        )
+        .bind(resource_type)
+        .bind(resource_id)
+        .bind(until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        logs.reverse();
+        Ok(logs)
+    }
+
     async fn nullify_actor_id(&self, actor_id: StringUuid) -> Result<u64> {
         let result = sqlx::query("UPDATE audit_logs SET actor_id = NULL WHERE actor_id = ?")
             .bind(actor_id.to_string())
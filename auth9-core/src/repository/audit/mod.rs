@@ -1,6 +1,7 @@
 //! Audit log repository
 
 use crate::error::Result;
+use crate::models::audit_search::{AuditHistogramBucket, AuditHistogramInterval};
 use crate::models::common::StringUuid;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -133,8 +134,10 @@ pub struct CreateAuditLogInput {
 }
 
 /// Audit log query parameters
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuditLogQuery {
+    /// Full-text search over action, resource and payload JSON; every term must match
+    pub q: Option<String>,
     pub actor_id: Option<Uuid>,
     pub resource_type: Option<String>,
     pub resource_id: Option<Uuid>,
@@ -157,6 +160,22 @@ pub trait AuditRepository: Send + Sync {
     /// Find audit logs with actor information (email, display_name) for API responses
     async fn find_with_actor(&self, query: &AuditLogQuery) -> Result<Vec<AuditLogWithActor>>;
     async fn count(&self, query: &AuditLogQuery) -> Result<i64>;
+    /// Count matching entries per time bucket within the query's date range
+    async fn histogram(
+        &self,
+        query: &AuditLogQuery,
+        interval: AuditHistogramInterval,
+    ) -> Result<Vec<AuditHistogramBucket>>;
+
+    /// Most recent `limit` entries for one resource at or before `until`,
+    /// returned oldest first
+    async fn find_resource_history(
+        &self,
+        resource_type: &str,
+        resource_id: &str,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AuditLog>>;
 
     /// Nullify actor_id for audit logs (preserve audit trail when user is deleted)
     async fn nullify_actor_id(&self, actor_id: StringUuid) -> Result<u64>;
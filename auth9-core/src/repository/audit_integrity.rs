@@ -0,0 +1,167 @@
+//! Audit log hash chain and checkpoint repository
+
+use crate::error::{AppError, Result};
+use crate::models::audit_integrity::{AuditCheckpoint, ChainEntry};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlRow;
+use sqlx::{FromRow, MySqlPool, Row};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditIntegrityRepository: Send + Sync {
+    /// Position and hash of the newest chained entry
+    async fn chain_head(&self) -> Result<(i64, String)>;
+    /// Chained entries from `from_seq` on, in chain order
+    async fn list_chain(
+        &self,
+        from_seq: i64,
+        to_seq: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<ChainEntry>>;
+    async fn list_checkpoints(
+        &self,
+        from_seq: i64,
+        to_seq: Option<i64>,
+    ) -> Result<Vec<AuditCheckpoint>>;
+    async fn latest_checkpoint(&self) -> Result<Option<AuditCheckpoint>>;
+    async fn create_checkpoint(
+        &self,
+        chain_seq: i64,
+        row_hash: &str,
+        signature: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<AuditCheckpoint>;
+}
+
+pub struct AuditIntegrityRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AuditIntegrityRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl<'r> FromRow<'r, MySqlRow> for ChainEntry {
+    fn from_row(row: &'r MySqlRow) -> sqlx::Result<Self> {
+        let old_value: Option<sqlx::types::Json<serde_json::Value>> = row.try_get("old_value")?;
+        let new_value: Option<sqlx::types::Json<serde_json::Value>> = row.try_get("new_value")?;
+        Ok(ChainEntry {
+            id: row.try_get("id")?,
+            chain_seq: row.try_get("chain_seq")?,
+            actor_id: row.try_get("actor_id")?,
+            actor_digest: row.try_get("actor_digest")?,
+            action: row.try_get("action")?,
+            resource_type: row.try_get("resource_type")?,
+            resource_id: row.try_get("resource_id")?,
+            old_value: old_value.map(|v| v.0),
+            new_value: new_value.map(|v| v.0),
+            ip_address: row.try_get("ip_address")?,
+            prev_hash: row.try_get("prev_hash")?,
+            row_hash: row.try_get("row_hash")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditIntegrityRepository for AuditIntegrityRepositoryImpl {
+    async fn chain_head(&self) -> Result<(i64, String)> {
+        let head: (i64, String) =
+            sqlx::query_as("SELECT last_seq, last_hash FROM audit_chain_head WHERE id = 1")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(head)
+    }
+
+    async fn list_chain(
+        &self,
+        from_seq: i64,
+        to_seq: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<ChainEntry>> {
+        let entries = sqlx::query_as::<_, ChainEntry>(
+            r#"
+            SELECT id, chain_seq, actor_id, actor_digest, action, resource_type, resource_id,
+                   old_value, new_value, ip_address, prev_hash, row_hash, created_at
+            FROM audit_logs
+            WHERE chain_seq >= ? AND (? IS NULL OR chain_seq <= ?)
+            ORDER BY chain_seq ASC, id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(from_seq)
+        .bind(to_seq)
+        .bind(to_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(entries)
+    }
+
+    async fn list_checkpoints(
+        &self,
+        from_seq: i64,
+        to_seq: Option<i64>,
+    ) -> Result<Vec<AuditCheckpoint>> {
+        let checkpoints = sqlx::query_as::<_, AuditCheckpoint>(
+            r#"
+            SELECT id, chain_seq, row_hash, signature, created_at
+            FROM audit_checkpoints
+            WHERE chain_seq >= ? AND (? IS NULL OR chain_seq <= ?)
+            ORDER BY chain_seq ASC
+            "#,
+        )
+        .bind(from_seq)
+        .bind(to_seq)
+        .bind(to_seq)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(checkpoints)
+    }
+
+    async fn latest_checkpoint(&self) -> Result<Option<AuditCheckpoint>> {
+        let checkpoint = sqlx::query_as::<_, AuditCheckpoint>(
+            r#"
+            SELECT id, chain_seq, row_hash, signature, created_at
+            FROM audit_checkpoints
+            ORDER BY chain_seq DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(checkpoint)
+    }
+
+    async fn create_checkpoint(
+        &self,
+        chain_seq: i64,
+        row_hash: &str,
+        signature: &str,
+        created_at: DateTime<Utc>,
+    ) -> Result<AuditCheckpoint> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audit_checkpoints (chain_seq, row_hash, signature, created_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(chain_seq)
+        .bind(row_hash)
+        .bind(signature)
+        .bind(created_at)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query_as::<_, AuditCheckpoint>(
+            "SELECT id, chain_seq, row_hash, signature, created_at FROM audit_checkpoints WHERE id = ?",
+        )
+        .bind(result.last_insert_id() as i64)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to create audit checkpoint")))
+    }
+}
@@ -0,0 +1,172 @@
+//! Audit share link repository
+
+use crate::error::Result;
+use crate::models::audit_share::{AuditShareAccess, AuditShareLink};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditShareRepository: Send + Sync {
+    async fn create(&self, link: &AuditShareLink) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AuditShareLink>>;
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<AuditShareLink>>;
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<AuditShareLink>>;
+    async fn count(&self) -> Result<i64>;
+    async fn revoke(&self, id: StringUuid) -> Result<()>;
+    /// Record a view through a link and bump its access counter
+    async fn record_access(&self, access: &AuditShareAccess) -> Result<()>;
+    async fn list_accesses(
+        &self,
+        link_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditShareAccess>>;
+    async fn count_accesses(&self, link_id: StringUuid) -> Result<i64>;
+}
+
+pub struct AuditShareRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl AuditShareRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, name, filter, created_by, token_hash, access_count, last_accessed_at,
+           expires_at, revoked_at, created_at, updated_at
+    FROM audit_share_links
+"#;
+
+#[async_trait]
+impl AuditShareRepository for AuditShareRepositoryImpl {
+    async fn create(&self, link: &AuditShareLink) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_share_links
+                (id, name, filter, created_by, token_hash, access_count, expires_at,
+                 created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, 0, ?, NOW(), NOW())
+            "#,
+        )
+        .bind(link.id)
+        .bind(&link.name)
+        .bind(sqlx::types::Json(&link.filter))
+        .bind(link.created_by)
+        .bind(&link.token_hash)
+        .bind(link.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AuditShareLink>> {
+        let link = sqlx::query_as::<_, AuditShareLink>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(link)
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<AuditShareLink>> {
+        let link = sqlx::query_as::<_, AuditShareLink>(&format!(
+            "{} WHERE token_hash = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(link)
+    }
+
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<AuditShareLink>> {
+        let links = sqlx::query_as::<_, AuditShareLink>(&format!(
+            "{} ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            SELECT_COLUMNS
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM audit_share_links")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn revoke(&self, id: StringUuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE audit_share_links SET revoked_at = NOW() WHERE id = ? AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_access(&self, access: &AuditShareAccess) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_share_accesses (id, link_id, ip_address, user_agent, created_at)
+            VALUES (?, ?, ?, ?, NOW())
+            "#,
+        )
+        .bind(access.id)
+        .bind(access.link_id)
+        .bind(&access.ip_address)
+        .bind(&access.user_agent)
+        .execute(&self.pool)
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE audit_share_links
+            SET access_count = access_count + 1, last_accessed_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(access.link_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_accesses(
+        &self,
+        link_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditShareAccess>> {
+        let accesses = sqlx::query_as::<_, AuditShareAccess>(
+            r#"
+            SELECT id, link_id, ip_address, user_agent, created_at
+            FROM audit_share_accesses
+            WHERE link_id = ?
+            ORDER BY created_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(link_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(accesses)
+    }
+
+    async fn count_accesses(&self, link_id: StringUuid) -> Result<i64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM audit_share_accesses WHERE link_id = ?")
+                .bind(link_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,275 @@
+//! Billing event ledger repository
+
+use crate::error::{AppError, Result};
+use crate::models::billing::{BillingEvent, BillingEventFilter, NewBillingEvent};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait BillingEventRepository: Send + Sync {
+    /// Insert an event unless its idempotency key is already in the ledger.
+    /// Returns whether a new entry was written.
+    async fn record(&self, event: &NewBillingEvent) -> Result<bool>;
+    /// Atomically move up to `limit` due events to `sending` under `claim_id`
+    async fn claim_due(&self, claim_id: &str, limit: usize) -> Result<Vec<BillingEvent>>;
+    async fn mark_delivered(&self, id: StringUuid) -> Result<()>;
+    /// Record a failed attempt and schedule the next one
+    async fn mark_retry(
+        &self,
+        id: StringUuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()>;
+    /// Record a failed final attempt
+    async fn mark_failed(&self, id: StringUuid, error: &str) -> Result<()>;
+    /// Return claimed events whose dispatcher stopped reporting back
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64>;
+    /// Reset a failed event so it is delivered again
+    async fn redeliver(&self, id: StringUuid) -> Result<BillingEvent>;
+    async fn list(
+        &self,
+        filter: &BillingEventFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<BillingEvent>>;
+    async fn count(&self, filter: &BillingEventFilter) -> Result<i64>;
+    /// Distinct users with a successful login per tenant since `since`
+    async fn monthly_active_users(&self, since: DateTime<Utc>) -> Result<Vec<(StringUuid, i64)>>;
+}
+
+pub struct BillingEventRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl BillingEventRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<BillingEvent>> {
+        let event = sqlx::query_as::<_, BillingEvent>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(event)
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, idempotency_key, tenant_id, event_type, data, occurred_at, status, attempts,
+           next_attempt_at, last_error, created_at, delivered_at
+    FROM billing_events
+"#;
+
+/// `WHERE` clause and bind values for a listing filter
+fn filter_clause(filter: &BillingEventFilter) -> (String, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut binds = Vec::new();
+    if let Some(status) = filter.status {
+        conditions.push("status = ?");
+        binds.push(status.as_str().to_string());
+    }
+    if let Some(tenant_id) = filter.tenant_id {
+        conditions.push("tenant_id = ?");
+        binds.push(tenant_id.to_string());
+    }
+    if conditions.is_empty() {
+        (String::new(), binds)
+    } else {
+        (format!(" WHERE {}", conditions.join(" AND ")), binds)
+    }
+}
+
+#[async_trait]
+impl BillingEventRepository for BillingEventRepositoryImpl {
+    async fn record(&self, event: &NewBillingEvent) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO billing_events
+                (id, idempotency_key, tenant_id, event_type, data, occurred_at, status, next_attempt_at)
+            VALUES (?, ?, ?, ?, ?, ?, 'pending', NOW())
+            "#,
+        )
+        .bind(StringUuid::new_v4())
+        .bind(&event.idempotency_key)
+        .bind(event.tenant_id)
+        .bind(event.event_type.as_str())
+        .bind(sqlx::types::Json(&event.data))
+        .bind(event.occurred_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn claim_due(&self, claim_id: &str, limit: usize) -> Result<Vec<BillingEvent>> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'sending', claim_id = ?, claimed_at = NOW()
+            WHERE status = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT ?
+            "#,
+        )
+        .bind(claim_id)
+        .bind(limit as u64)
+        .execute(&self.pool)
+        .await?;
+
+        if claimed.rows_affected() == 0 {
+            return Ok(Vec::new());
+        }
+
+        let events = sqlx::query_as::<_, BillingEvent>(&format!(
+            "{} WHERE claim_id = ? AND status = 'sending' ORDER BY occurred_at",
+            SELECT_COLUMNS
+        ))
+        .bind(claim_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(events)
+    }
+
+    async fn mark_delivered(&self, id: StringUuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'delivered', attempts = attempts + 1, last_error = NULL,
+                claim_id = NULL, delivered_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: StringUuid,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'pending', attempts = attempts + 1, last_error = ?,
+                next_attempt_at = ?, claim_id = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: StringUuid, error: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'failed', attempts = attempts + 1, last_error = ?, claim_id = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'pending', claim_id = NULL
+            WHERE status = 'sending' AND claimed_at < ?
+            "#,
+        )
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn redeliver(&self, id: StringUuid) -> Result<BillingEvent> {
+        let event = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Billing event {} not found", id)))?;
+        if event.status != "failed" {
+            return Err(AppError::Conflict(format!(
+                "Billing event {} is {}, only failed events can be redelivered",
+                id, event.status
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE billing_events
+            SET status = 'pending', attempts = 0, next_attempt_at = NOW()
+            WHERE id = ? AND status = 'failed'
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Billing event {} not found", id)))
+    }
+
+    async fn list(
+        &self,
+        filter: &BillingEventFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<BillingEvent>> {
+        let (clause, binds) = filter_clause(filter);
+        let query = format!(
+            "{}{} ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            SELECT_COLUMNS, clause
+        );
+        let mut q = sqlx::query_as::<_, BillingEvent>(&query);
+        for value in binds {
+            q = q.bind(value);
+        }
+        Ok(q.bind(limit).bind(offset).fetch_all(&self.pool).await?)
+    }
+
+    async fn count(&self, filter: &BillingEventFilter) -> Result<i64> {
+        let (clause, binds) = filter_clause(filter);
+        let query = format!("SELECT COUNT(*) FROM billing_events{}", clause);
+        let mut q = sqlx::query_scalar::<_, i64>(&query);
+        for value in binds {
+            q = q.bind(value);
+        }
+        Ok(q.fetch_one(&self.pool).await?)
+    }
+
+    async fn monthly_active_users(&self, since: DateTime<Utc>) -> Result<Vec<(StringUuid, i64)>> {
+        let rows: Vec<(StringUuid, i64)> = sqlx::query_as(
+            r#"
+            SELECT tenant_id, CAST(COUNT(DISTINCT user_id) AS SIGNED)
+            FROM login_events
+            WHERE created_at >= ?
+              AND tenant_id IS NOT NULL
+              AND user_id IS NOT NULL
+              AND event_type IN ('success', 'social', 'federation_success')
+            GROUP BY tenant_id
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,126 @@
+//! Service claims enricher repository
+
+use crate::error::{AppError, Result};
+use crate::models::claims_enrichment::{EnrichmentFailurePolicy, ServiceClaimsEnricher};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ClaimsEnricherRepository: Send + Sync {
+    /// Enrichers configured for a service, in the order they were added
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<ServiceClaimsEnricher>>;
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        plugin: &str,
+        settings: &serde_json::Value,
+        timeout_ms: u32,
+        failure_policy: EnrichmentFailurePolicy,
+        enabled: bool,
+    ) -> Result<ServiceClaimsEnricher>;
+    async fn delete(&self, service_id: StringUuid, plugin: &str) -> Result<()>;
+}
+
+pub struct ClaimsEnricherRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ClaimsEnricherRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find(
+        &self,
+        service_id: StringUuid,
+        plugin: &str,
+    ) -> Result<Option<ServiceClaimsEnricher>> {
+        let enricher = sqlx::query_as::<_, ServiceClaimsEnricher>(
+            r#"
+            SELECT id, service_id, plugin, settings, timeout_ms, failure_policy, enabled,
+                   created_at, updated_at
+            FROM service_claims_enrichers
+            WHERE service_id = ? AND plugin = ?
+            "#,
+        )
+        .bind(service_id)
+        .bind(plugin)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(enricher)
+    }
+}
+
+#[async_trait]
+impl ClaimsEnricherRepository for ClaimsEnricherRepositoryImpl {
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<ServiceClaimsEnricher>> {
+        let enrichers = sqlx::query_as::<_, ServiceClaimsEnricher>(
+            r#"
+            SELECT id, service_id, plugin, settings, timeout_ms, failure_policy, enabled,
+                   created_at, updated_at
+            FROM service_claims_enrichers
+            WHERE service_id = ?
+            ORDER BY created_at, plugin
+            "#,
+        )
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(enrichers)
+    }
+
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        plugin: &str,
+        settings: &serde_json::Value,
+        timeout_ms: u32,
+        failure_policy: EnrichmentFailurePolicy,
+        enabled: bool,
+    ) -> Result<ServiceClaimsEnricher> {
+        let settings = serde_json::to_string(settings).map_err(|e| AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO service_claims_enrichers
+                (id, service_id, plugin, settings, timeout_ms, failure_policy, enabled)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                settings = VALUES(settings),
+                timeout_ms = VALUES(timeout_ms),
+                failure_policy = VALUES(failure_policy),
+                enabled = VALUES(enabled)
+            "#,
+        )
+        .bind(StringUuid::new_v4())
+        .bind(service_id)
+        .bind(plugin)
+        .bind(&settings)
+        .bind(timeout_ms)
+        .bind(failure_policy)
+        .bind(enabled)
+        .execute(&self.pool)
+        .await?;
+
+        self.find(service_id, plugin)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to save claims enricher")))
+    }
+
+    async fn delete(&self, service_id: StringUuid, plugin: &str) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM service_claims_enrichers WHERE service_id = ? AND plugin = ?")
+                .bind(service_id)
+                .bind(plugin)
+                .execute(&self.pool)
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Claims enricher '{}' is not configured for this service",
+                plugin
+            )));
+        }
+        Ok(())
+    }
+}
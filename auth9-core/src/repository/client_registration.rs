@@ -0,0 +1,270 @@
+//! Dynamic client registration repository
+
+use crate::error::Result;
+use crate::models::client_registration::{
+    ClientMetadata, ClientRegistration, ClientRegistrationPolicy, InitialAccessToken,
+    UpdateClientRegistrationPolicyInput,
+};
+use crate::models::common::StringUuid;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ClientRegistrationRepository: Send + Sync {
+    async fn find_policy(&self, tenant_id: StringUuid) -> Result<Option<ClientRegistrationPolicy>>;
+    async fn upsert_policy(
+        &self,
+        tenant_id: StringUuid,
+        input: &UpdateClientRegistrationPolicyInput,
+    ) -> Result<ClientRegistrationPolicy>;
+
+    async fn create_initial_token(&self, token: &InitialAccessToken) -> Result<()>;
+    async fn find_initial_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<InitialAccessToken>>;
+    async fn list_initial_tokens(&self, tenant_id: StringUuid) -> Result<Vec<InitialAccessToken>>;
+    /// Returns false when the token does not belong to the tenant
+    async fn revoke_initial_token(&self, tenant_id: StringUuid, id: StringUuid) -> Result<bool>;
+    /// Count one registration against the token. Returns false when the
+    /// token is revoked, expired or has no uses left.
+    async fn consume_initial_token(&self, id: StringUuid) -> Result<bool>;
+
+    async fn create_registration(&self, registration: &ClientRegistration) -> Result<()>;
+    async fn find_registration_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ClientRegistration>>;
+    async fn update_registration_metadata(
+        &self,
+        id: StringUuid,
+        metadata: &ClientMetadata,
+    ) -> Result<()>;
+    async fn delete_registration(&self, id: StringUuid) -> Result<()>;
+}
+
+pub struct ClientRegistrationRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ClientRegistrationRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ClientRegistrationRepository for ClientRegistrationRepositoryImpl {
+    async fn find_policy(&self, tenant_id: StringUuid) -> Result<Option<ClientRegistrationPolicy>> {
+        let policy = sqlx::query_as::<_, ClientRegistrationPolicy>(
+            r#"
+            SELECT tenant_id, enabled, require_software_statement, software_statement_issuers,
+                   created_at, updated_at
+            FROM client_registration_policies
+            WHERE tenant_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(policy)
+    }
+
+    async fn upsert_policy(
+        &self,
+        tenant_id: StringUuid,
+        input: &UpdateClientRegistrationPolicyInput,
+    ) -> Result<ClientRegistrationPolicy> {
+        let issuers = serde_json::to_string(&input.software_statement_issuers)
+            .map_err(|e| crate::error::AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO client_registration_policies
+                (tenant_id, enabled, require_software_statement, software_statement_issuers)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                enabled = VALUES(enabled),
+                require_software_statement = VALUES(require_software_statement),
+                software_statement_issuers = VALUES(software_statement_issuers)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(input.enabled)
+        .bind(input.require_software_statement)
+        .bind(issuers)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_policy(tenant_id).await?.ok_or_else(|| {
+            crate::error::AppError::Internal(anyhow::anyhow!(
+                "Failed to read back client registration policy"
+            ))
+        })
+    }
+
+    async fn create_initial_token(&self, token: &InitialAccessToken) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO client_registration_initial_tokens
+                (id, tenant_id, token_hash, token_prefix, description, max_uses, use_count,
+                 expires_at, created_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, 0, ?, ?, ?)
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.tenant_id)
+        .bind(&token.token_hash)
+        .bind(&token.token_prefix)
+        .bind(&token.description)
+        .bind(token.max_uses)
+        .bind(token.expires_at)
+        .bind(token.created_by)
+        .bind(token.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_initial_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<InitialAccessToken>> {
+        let token = sqlx::query_as::<_, InitialAccessToken>(
+            r#"
+            SELECT id, tenant_id, token_hash, token_prefix, description, max_uses, use_count,
+                   expires_at, revoked_at, created_by, created_at
+            FROM client_registration_initial_tokens
+            WHERE token_hash = ?
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(token)
+    }
+
+    async fn list_initial_tokens(&self, tenant_id: StringUuid) -> Result<Vec<InitialAccessToken>> {
+        let tokens = sqlx::query_as::<_, InitialAccessToken>(
+            r#"
+            SELECT id, tenant_id, token_hash, token_prefix, description, max_uses, use_count,
+                   expires_at, revoked_at, created_by, created_at
+            FROM client_registration_initial_tokens
+            WHERE tenant_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tokens)
+    }
+
+    async fn revoke_initial_token(&self, tenant_id: StringUuid, id: StringUuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE client_registration_initial_tokens
+            SET revoked_at = COALESCE(revoked_at, NOW())
+            WHERE id = ? AND tenant_id = ?
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn consume_initial_token(&self, id: StringUuid) -> Result<bool> {
+        // Single conditional UPDATE so concurrent registrations cannot
+        // exceed max_uses
+        let result = sqlx::query(
+            r#"
+            UPDATE client_registration_initial_tokens
+            SET use_count = use_count + 1
+            WHERE id = ?
+              AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+              AND (max_uses IS NULL OR use_count < max_uses)
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn create_registration(&self, registration: &ClientRegistration) -> Result<()> {
+        let metadata = serde_json::to_string(&registration.metadata)
+            .map_err(|e| crate::error::AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO client_registrations
+                (id, tenant_id, service_id, client_id, registration_token_hash,
+                 initial_token_id, software_id, metadata, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(registration.id)
+        .bind(registration.tenant_id)
+        .bind(registration.service_id)
+        .bind(&registration.client_id)
+        .bind(&registration.registration_token_hash)
+        .bind(registration.initial_token_id)
+        .bind(&registration.software_id)
+        .bind(metadata)
+        .bind(registration.created_at)
+        .bind(registration.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_registration_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<ClientRegistration>> {
+        let registration = sqlx::query_as::<_, ClientRegistration>(
+            r#"
+            SELECT id, tenant_id, service_id, client_id, registration_token_hash,
+                   initial_token_id, software_id, metadata, created_at, updated_at
+            FROM client_registrations
+            WHERE registration_token_hash = ?
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(registration)
+    }
+
+    async fn update_registration_metadata(
+        &self,
+        id: StringUuid,
+        metadata: &ClientMetadata,
+    ) -> Result<()> {
+        let json = serde_json::to_string(metadata)
+            .map_err(|e| crate::error::AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            UPDATE client_registrations
+            SET metadata = ?, software_id = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(json)
+        .bind(&metadata.software_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_registration(&self, id: StringUuid) -> Result<()> {
+        sqlx::query("DELETE FROM client_registrations WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
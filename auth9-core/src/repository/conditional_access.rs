@@ -0,0 +1,185 @@
+//! Conditional access policy repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::conditional_access::{
+    ConditionalAccessConditions, ConditionalAccessOutcome, ConditionalAccessPolicy,
+};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+/// Values written by create/update
+#[derive(Debug, Clone)]
+pub struct ConditionalAccessPolicyRecord {
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub priority: i32,
+    pub conditions: ConditionalAccessConditions,
+    pub outcome: ConditionalAccessOutcome,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ConditionalAccessRepository: Send + Sync {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        record: &ConditionalAccessPolicyRecord,
+    ) -> Result<ConditionalAccessPolicy>;
+    async fn find(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<ConditionalAccessPolicy>>;
+    /// All policies of a tenant in evaluation order
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<ConditionalAccessPolicy>>;
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64>;
+    async fn update(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        record: &ConditionalAccessPolicyRecord,
+    ) -> Result<ConditionalAccessPolicy>;
+    async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()>;
+}
+
+pub struct ConditionalAccessRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ConditionalAccessRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, name, description, enabled, priority, conditions, outcome,
+           created_at, updated_at
+    FROM conditional_access_policies
+"#;
+
+#[async_trait]
+impl ConditionalAccessRepository for ConditionalAccessRepositoryImpl {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        record: &ConditionalAccessPolicyRecord,
+    ) -> Result<ConditionalAccessPolicy> {
+        let id = StringUuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO conditional_access_policies
+                (id, tenant_id, name, description, enabled, priority, conditions, outcome)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(&record.name)
+        .bind(&record.description)
+        .bind(record.enabled)
+        .bind(record.priority)
+        .bind(sqlx::types::Json(&record.conditions))
+        .bind(record.outcome)
+        .execute(&self.pool)
+        .await?;
+
+        self.find(tenant_id, id).await?.ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!(
+                "Failed to create conditional access policy"
+            ))
+        })
+    }
+
+    async fn find(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<ConditionalAccessPolicy>> {
+        let policy = sqlx::query_as::<_, ConditionalAccessPolicy>(&format!(
+            "{} WHERE tenant_id = ? AND id = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(policy)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<ConditionalAccessPolicy>> {
+        let policies = sqlx::query_as::<_, ConditionalAccessPolicy>(&format!(
+            "{} WHERE tenant_id = ? ORDER BY priority ASC, created_at ASC",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(policies)
+    }
+
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM conditional_access_policies WHERE tenant_id = ?")
+                .bind(tenant_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(count.0)
+    }
+
+    async fn update(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+        record: &ConditionalAccessPolicyRecord,
+    ) -> Result<ConditionalAccessPolicy> {
+        let result = sqlx::query(
+            r#"
+            UPDATE conditional_access_policies
+            SET name = ?, description = ?, enabled = ?, priority = ?, conditions = ?,
+                outcome = ?, updated_at = NOW()
+            WHERE tenant_id = ? AND id = ?
+            "#,
+        )
+        .bind(&record.name)
+        .bind(&record.description)
+        .bind(record.enabled)
+        .bind(record.priority)
+        .bind(sqlx::types::Json(&record.conditions))
+        .bind(record.outcome)
+        .bind(tenant_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Conditional access policy {} not found",
+                id
+            )));
+        }
+
+        self.find(tenant_id, id).await?.ok_or_else(|| {
+            AppError::NotFound(format!("Conditional access policy {} not found", id))
+        })
+    }
+
+    async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM conditional_access_policies WHERE tenant_id = ? AND id = ?")
+                .bind(tenant_id)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Conditional access policy {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Tenant custom domain repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::custom_domain::CustomDomain;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CustomDomainRepository: Send + Sync {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        domain: &str,
+        verification_token: &str,
+    ) -> Result<CustomDomain>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<CustomDomain>>;
+    async fn find_by_domain(&self, domain: &str) -> Result<Option<CustomDomain>>;
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<CustomDomain>>;
+    async fn update_verification(
+        &self,
+        id: StringUuid,
+        status: &str,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+    async fn update_certificate(
+        &self,
+        id: StringUuid,
+        certificate_source: &str,
+        certificate_pem: Option<String>,
+        private_key_encrypted: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+    async fn delete(&self, id: StringUuid) -> Result<()>;
+}
+
+pub struct CustomDomainRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl CustomDomainRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, domain, verification_token, status, last_checked_at, verified_at,
+           certificate_source, certificate_pem, private_key_encrypted, certificate_expires_at,
+           created_at, updated_at
+    FROM tenant_custom_domains
+"#;
+
+#[async_trait]
+impl CustomDomainRepository for CustomDomainRepositoryImpl {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        domain: &str,
+        verification_token: &str,
+    ) -> Result<CustomDomain> {
+        let id = StringUuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_custom_domains (id, tenant_id, domain, verification_token)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(domain)
+        .bind(verification_token)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to create custom domain")))
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<CustomDomain>> {
+        let query = format!("{} WHERE id = ?", SELECT_COLUMNS);
+        let domain = sqlx::query_as::<_, CustomDomain>(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(domain)
+    }
+
+    async fn find_by_domain(&self, domain: &str) -> Result<Option<CustomDomain>> {
+        let query = format!("{} WHERE domain = ?", SELECT_COLUMNS);
+        let domain = sqlx::query_as::<_, CustomDomain>(&query)
+            .bind(domain)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(domain)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<CustomDomain>> {
+        let query = format!(
+            "{} WHERE tenant_id = ? ORDER BY created_at ASC",
+            SELECT_COLUMNS
+        );
+        let domains = sqlx::query_as::<_, CustomDomain>(&query)
+            .bind(tenant_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(domains)
+    }
+
+    async fn update_verification(
+        &self,
+        id: StringUuid,
+        status: &str,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE tenant_custom_domains
+            SET status = ?, verified_at = ?, last_checked_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(status)
+        .bind(verified_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update_certificate(
+        &self,
+        id: StringUuid,
+        certificate_source: &str,
+        certificate_pem: Option<String>,
+        private_key_encrypted: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE tenant_custom_domains
+            SET certificate_source = ?, certificate_pem = ?, private_key_encrypted = ?,
+                certificate_expires_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(certificate_source)
+        .bind(certificate_pem)
+        .bind(private_key_encrypted)
+        .bind(expires_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM tenant_custom_domains WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Custom domain {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+//! Outbound email queue and suppression list repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::email_queue::{
+    EmailQueueStats, EmailSuppression, QueuedEmail, QueuedEmailPayload, SuppressionReason,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EmailQueueRepository: Send + Sync {
+    async fn enqueue(
+        &self,
+        recipients: &[String],
+        payload: &QueuedEmailPayload,
+    ) -> Result<StringUuid>;
+    /// Atomically move up to `limit` due emails to `sending` under `claim_id`
+    async fn claim_due(&self, claim_id: &str, limit: usize) -> Result<Vec<QueuedEmail>>;
+    async fn mark_sent(
+        &self,
+        id: StringUuid,
+        provider: &str,
+        provider_message_id: Option<String>,
+    ) -> Result<()>;
+    /// Record a failed attempt and schedule the next one
+    async fn mark_retry(
+        &self,
+        id: StringUuid,
+        provider: Option<String>,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()>;
+    /// Record a failed final attempt
+    async fn mark_failed(
+        &self,
+        id: StringUuid,
+        provider: Option<String>,
+        error: &str,
+    ) -> Result<()>;
+    async fn mark_suppressed(&self, id: StringUuid) -> Result<()>;
+    /// Return claimed emails whose worker stopped reporting back to the queue
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64>;
+    async fn stats(&self) -> Result<EmailQueueStats>;
+
+    /// Which of the given (normalized) addresses are suppressed
+    async fn find_suppressed(&self, emails: &[String]) -> Result<Vec<String>>;
+    async fn list_suppressions(&self, offset: i64, limit: i64) -> Result<Vec<EmailSuppression>>;
+    async fn count_suppressions(&self) -> Result<i64>;
+    async fn add_suppression(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        detail: Option<String>,
+    ) -> Result<EmailSuppression>;
+    async fn remove_suppression(&self, email: &str) -> Result<()>;
+}
+
+pub struct EmailQueueRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl EmailQueueRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find_suppression(&self, email: &str) -> Result<Option<EmailSuppression>> {
+        let suppression = sqlx::query_as::<_, EmailSuppression>(
+            "SELECT email, reason, detail, created_at FROM email_suppressions WHERE email = ?",
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(suppression)
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, recipients, payload, status, attempts, next_attempt_at, last_error, provider,
+           provider_message_id, created_at, sent_at, updated_at
+    FROM email_queue
+"#;
+
+#[async_trait]
+impl EmailQueueRepository for EmailQueueRepositoryImpl {
+    async fn enqueue(
+        &self,
+        recipients: &[String],
+        payload: &QueuedEmailPayload,
+    ) -> Result<StringUuid> {
+        let id = StringUuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO email_queue (id, recipients, payload, status, next_attempt_at)
+            VALUES (?, ?, ?, 'pending', NOW())
+            "#,
+        )
+        .bind(id)
+        .bind(sqlx::types::Json(recipients))
+        .bind(sqlx::types::Json(payload))
+        .execute(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    async fn claim_due(&self, claim_id: &str, limit: usize) -> Result<Vec<QueuedEmail>> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE email_queue
+            SET status = 'sending', claim_id = ?, claimed_at = NOW()
+            WHERE status = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT ?
+            "#,
+        )
+        .bind(claim_id)
+        .bind(limit as u64)
+        .execute(&self.pool)
+        .await?;
+
+        if claimed.rows_affected() == 0 {
+            return Ok(Vec::new());
+        }
+
+        let emails = sqlx::query_as::<_, QueuedEmail>(&format!(
+            "{} WHERE claim_id = ? AND status = 'sending' ORDER BY next_attempt_at",
+            SELECT_COLUMNS
+        ))
+        .bind(claim_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(emails)
+    }
+
+    async fn mark_sent(
+        &self,
+        id: StringUuid,
+        provider: &str,
+        provider_message_id: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_queue
+            SET status = 'sent', attempts = attempts + 1, provider = ?, provider_message_id = ?,
+                last_error = NULL, claim_id = NULL, sent_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(provider)
+        .bind(provider_message_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_retry(
+        &self,
+        id: StringUuid,
+        provider: Option<String>,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_queue
+            SET status = 'pending', attempts = attempts + 1, provider = COALESCE(?, provider),
+                last_error = ?, next_attempt_at = ?, claim_id = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(provider)
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: StringUuid,
+        provider: Option<String>,
+        error: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE email_queue
+            SET status = 'failed', attempts = attempts + 1, provider = COALESCE(?, provider),
+                last_error = ?, claim_id = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(provider)
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_suppressed(&self, id: StringUuid) -> Result<()> {
+        sqlx::query("UPDATE email_queue SET status = 'suppressed', claim_id = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE email_queue
+            SET status = 'pending', claim_id = NULL
+            WHERE status = 'sending' AND claimed_at < ?
+            "#,
+        )
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn stats(&self) -> Result<EmailQueueStats> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT status, COUNT(*) FROM email_queue GROUP BY status")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut stats = EmailQueueStats::default();
+        for (status, count) in rows {
+            match status.as_str() {
+                "pending" => stats.pending = count,
+                "sending" => stats.sending = count,
+                "sent" => stats.sent = count,
+                "failed" => stats.failed = count,
+                "suppressed" => stats.suppressed = count,
+                _ => {}
+            }
+        }
+        Ok(stats)
+    }
+
+    async fn find_suppressed(&self, emails: &[String]) -> Result<Vec<String>> {
+        if emails.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let placeholders: Vec<&str> = emails.iter().map(|_| "?").collect();
+        let query = format!(
+            "SELECT email FROM email_suppressions WHERE email IN ({})",
+            placeholders.join(",")
+        );
+
+        let mut q = sqlx::query_scalar::<_, String>(&query);
+        for email in emails {
+            q = q.bind(email);
+        }
+        Ok(q.fetch_all(&self.pool).await?)
+    }
+
+    async fn list_suppressions(&self, offset: i64, limit: i64) -> Result<Vec<EmailSuppression>> {
+        let suppressions = sqlx::query_as::<_, EmailSuppression>(
+            r#"
+            SELECT email, reason, detail, created_at
+            FROM email_suppressions
+            ORDER BY created_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(suppressions)
+    }
+
+    async fn count_suppressions(&self) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM email_suppressions")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    async fn add_suppression(
+        &self,
+        email: &str,
+        reason: SuppressionReason,
+        detail: Option<String>,
+    ) -> Result<EmailSuppression> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_suppressions (email, reason, detail)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE reason = VALUES(reason), detail = VALUES(detail)
+            "#,
+        )
+        .bind(email)
+        .bind(reason.as_str())
+        .bind(detail)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_suppression(email)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to save suppression")))
+    }
+
+    async fn remove_suppression(&self, email: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM email_suppressions WHERE email = ?")
+            .bind(email)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Email address {} is not suppressed",
+                email
+            )));
+        }
+        Ok(())
+    }
+}
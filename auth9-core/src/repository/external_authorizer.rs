@@ -0,0 +1,96 @@
+//! Service external authorizer repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::external_authz::ServiceExternalAuthorizer;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ExternalAuthorizerRepository: Send + Sync {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<ServiceExternalAuthorizer>>;
+    async fn upsert(&self, authorizer: &ServiceExternalAuthorizer) -> Result<()>;
+    async fn delete(&self, service_id: StringUuid) -> Result<()>;
+}
+
+pub struct ExternalAuthorizerRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ExternalAuthorizerRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ExternalAuthorizerRepository for ExternalAuthorizerRepositoryImpl {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<ServiceExternalAuthorizer>> {
+        let authorizer = sqlx::query_as::<_, ServiceExternalAuthorizer>(
+            r#"
+            SELECT service_id, provider, endpoint, settings, mode, fallback, timeout_ms,
+                   cache_ttl_secs, enabled, created_at, updated_at
+            FROM service_external_authorizers
+            WHERE service_id = ?
+            "#,
+        )
+        .bind(service_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(authorizer)
+    }
+
+    async fn upsert(&self, authorizer: &ServiceExternalAuthorizer) -> Result<()> {
+        let settings = serde_json::to_string(&authorizer.settings)
+            .map_err(|e| AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO service_external_authorizers
+                (service_id, provider, endpoint, settings, mode, fallback, timeout_ms,
+                 cache_ttl_secs, enabled)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                provider = VALUES(provider),
+                endpoint = VALUES(endpoint),
+                settings = VALUES(settings),
+                mode = VALUES(mode),
+                fallback = VALUES(fallback),
+                timeout_ms = VALUES(timeout_ms),
+                cache_ttl_secs = VALUES(cache_ttl_secs),
+                enabled = VALUES(enabled)
+            "#,
+        )
+        .bind(authorizer.service_id)
+        .bind(&authorizer.provider)
+        .bind(&authorizer.endpoint)
+        .bind(&settings)
+        .bind(&authorizer.mode)
+        .bind(&authorizer.fallback)
+        .bind(authorizer.timeout_ms)
+        .bind(authorizer.cache_ttl_secs)
+        .bind(authorizer.enabled)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, service_id: StringUuid) -> Result<()> {
+        let result = sqlx::query("DELETE FROM service_external_authorizers WHERE service_id = ?")
+            .bind(service_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "No external authorizer is configured for this service".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
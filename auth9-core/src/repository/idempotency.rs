@@ -0,0 +1,151 @@
+//! Idempotency key repository for gRPC provisioning calls
+
+use crate::error::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySqlPool};
+
+/// A call already made with an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct IdempotencyRecord {
+    /// SHA-256 of the request the key was first used for
+    pub request_hash: String,
+    /// Encoded response; `None` while the first call is still running
+    pub response: Option<Vec<u8>>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Claim a key for a new call. Returns the earlier call instead when the
+    /// key was used after `expired_before`; older uses are forgotten.
+    async fn claim(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>>;
+    /// Store the response of a claimed call
+    async fn complete(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        response: &[u8],
+    ) -> Result<()>;
+    /// Drop the claim of a failed call so it can be retried
+    async fn release(&self, client_id: &str, method: &str, key: &str) -> Result<()>;
+    async fn delete_expired(&self, expired_before: DateTime<Utc>) -> Result<u64>;
+}
+
+pub struct IdempotencyRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl IdempotencyRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for IdempotencyRepositoryImpl {
+    async fn claim(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Option<IdempotencyRecord>> {
+        sqlx::query(
+            r#"
+            DELETE FROM grpc_idempotency_keys
+            WHERE client_id = ? AND method = ? AND idempotency_key = ? AND created_at < ?
+            "#,
+        )
+        .bind(client_id)
+        .bind(method)
+        .bind(key)
+        .bind(expired_before)
+        .execute(&self.pool)
+        .await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT IGNORE INTO grpc_idempotency_keys
+                (client_id, method, idempotency_key, request_hash)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(client_id)
+        .bind(method)
+        .bind(key)
+        .bind(request_hash)
+        .execute(&self.pool)
+        .await?;
+        if inserted.rows_affected() > 0 {
+            return Ok(None);
+        }
+
+        let existing = sqlx::query_as::<_, IdempotencyRecord>(
+            r#"
+            SELECT request_hash, response FROM grpc_idempotency_keys
+            WHERE client_id = ? AND method = ? AND idempotency_key = ?
+            "#,
+        )
+        .bind(client_id)
+        .bind(method)
+        .bind(key)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(Some(existing))
+    }
+
+    async fn complete(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        response: &[u8],
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE grpc_idempotency_keys SET response = ?, completed_at = NOW()
+            WHERE client_id = ? AND method = ? AND idempotency_key = ?
+            "#,
+        )
+        .bind(response)
+        .bind(client_id)
+        .bind(method)
+        .bind(key)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn release(&self, client_id: &str, method: &str, key: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM grpc_idempotency_keys
+            WHERE client_id = ? AND method = ? AND idempotency_key = ? AND response IS NULL
+            "#,
+        )
+        .bind(client_id)
+        .bind(method)
+        .bind(key)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_expired(&self, expired_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM grpc_idempotency_keys WHERE created_at < ?")
+            .bind(expired_before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,191 @@
+//! Tenant member inactivity repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::inactivity::InactiveMember;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySqlPool};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait InactivityRepository: Send + Sync {
+    /// Members of a tenant without a successful login (or joining) since
+    /// `inactive_before`, in user ID order after `after`
+    async fn find_inactive_members(
+        &self,
+        tenant_id: StringUuid,
+        inactive_before: DateTime<Utc>,
+        after: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<InactiveMember>>;
+    async fn mark_notified(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        at: DateTime<Utc>,
+    ) -> Result<()>;
+    async fn mark_disabled(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        at: DateTime<Utc>,
+    ) -> Result<()>;
+    /// Forget a member's progress (after the delete step)
+    async fn clear(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<()>;
+    /// Active tenants whose inactivity policy is enabled
+    async fn tenants_with_enabled_policy(&self) -> Result<Vec<StringUuid>>;
+}
+
+pub struct InactivityRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl InactivityRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(FromRow)]
+struct InactiveMemberRow {
+    user_id: StringUuid,
+    email: String,
+    role_in_tenant: String,
+    joined_at: DateTime<Utc>,
+    last_login_at: Option<DateTime<Utc>>,
+    notified_at: Option<DateTime<Utc>>,
+    disabled_at: Option<DateTime<Utc>>,
+    shared_account: i64,
+    locked: i64,
+}
+
+impl From<InactiveMemberRow> for InactiveMember {
+    fn from(row: InactiveMemberRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            email: row.email,
+            role_in_tenant: row.role_in_tenant,
+            joined_at: row.joined_at,
+            last_login_at: row.last_login_at,
+            notified_at: row.notified_at,
+            disabled_at: row.disabled_at,
+            shared_account: row.shared_account != 0,
+            locked: row.locked != 0,
+        }
+    }
+}
+
+#[async_trait]
+impl InactivityRepository for InactivityRepositoryImpl {
+    async fn find_inactive_members(
+        &self,
+        tenant_id: StringUuid,
+        inactive_before: DateTime<Utc>,
+        after: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<InactiveMember>> {
+        let rows = sqlx::query_as::<_, InactiveMemberRow>(
+            r#"
+            SELECT tu.user_id, u.email, tu.role_in_tenant, tu.joined_at,
+                   (SELECT MAX(le.created_at) FROM login_events le
+                    WHERE le.user_id = tu.user_id
+                      AND le.event_type IN ('success', 'social', 'federation_success')
+                   ) AS last_login_at,
+                   tmi.notified_at, tmi.disabled_at,
+                   CAST(EXISTS(
+                       SELECT 1 FROM tenant_users o
+                       WHERE o.user_id = tu.user_id AND o.tenant_id <> tu.tenant_id
+                   ) AS SIGNED) AS shared_account,
+                   CAST(COALESCE(u.locked_until > NOW(), 0) AS SIGNED) AS locked
+            FROM tenant_users tu
+            INNER JOIN users u ON u.id = tu.user_id
+            LEFT JOIN tenant_member_inactivity tmi
+                ON tmi.tenant_id = tu.tenant_id AND tmi.user_id = tu.user_id
+            WHERE tu.tenant_id = ?
+              AND tu.joined_at < ?
+              AND (? IS NULL OR tu.user_id > ?)
+              AND NOT EXISTS (
+                  SELECT 1 FROM login_events le
+                  WHERE le.user_id = tu.user_id
+                    AND le.event_type IN ('success', 'social', 'federation_success')
+                    AND le.created_at >= ?
+              )
+            ORDER BY tu.user_id
+            LIMIT ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(inactive_before)
+        .bind(after)
+        .bind(after)
+        .bind(inactive_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(InactiveMember::from).collect())
+    }
+
+    async fn mark_notified(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_member_inactivity (tenant_id, user_id, notified_at, disabled_at)
+            VALUES (?, ?, ?, NULL)
+            ON DUPLICATE KEY UPDATE notified_at = VALUES(notified_at), disabled_at = NULL
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .bind(at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_disabled(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE tenant_member_inactivity SET disabled_at = ?
+            WHERE tenant_id = ? AND user_id = ?
+            "#,
+        )
+        .bind(at)
+        .bind(tenant_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn clear(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<()> {
+        sqlx::query("DELETE FROM tenant_member_inactivity WHERE tenant_id = ? AND user_id = ?")
+            .bind(tenant_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn tenants_with_enabled_policy(&self) -> Result<Vec<StringUuid>> {
+        let ids = sqlx::query_scalar(
+            r#"
+            SELECT id FROM tenants
+            WHERE status = 'active'
+              AND JSON_EXTRACT(settings, '$.inactivity_policy.enabled') = CAST('true' AS JSON)
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+}
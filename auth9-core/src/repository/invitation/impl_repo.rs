@@ -326,6 +326,16 @@ impl InvitationRepository for InvitationRepositoryImpl {
             .execute(&self.pool)
             .await?;
 
+        // Shareable links and their join records go with the tenant's invitations
+        sqlx::query("DELETE FROM invitation_link_redemptions WHERE tenant_id = ?")
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM invitation_links WHERE tenant_id = ?")
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await?;
+
         Ok(result.rows_affected())
     }
 }
@@ -0,0 +1,213 @@
+//! Invitation link repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::invitation_link::{InvitationLink, InvitationLinkRedemption};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait InvitationLinkRepository: Send + Sync {
+    async fn create(&self, link: &InvitationLink) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<InvitationLink>>;
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<InvitationLink>>;
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InvitationLink>>;
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64>;
+    async fn revoke(&self, id: StringUuid) -> Result<()>;
+    /// Atomically take one use of an active link. Returns false when the
+    /// link was revoked, expired or used up in the meantime.
+    async fn claim_use(&self, id: StringUuid) -> Result<bool>;
+    /// Give back a use taken by [`InvitationLinkRepository::claim_use`]
+    async fn release_use(&self, id: StringUuid) -> Result<()>;
+    /// Record a join. Returns false when the user already joined through the link.
+    async fn record_redemption(&self, redemption: &InvitationLinkRedemption) -> Result<bool>;
+    async fn list_redemptions(
+        &self,
+        link_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InvitationLinkRedemption>>;
+    async fn count_redemptions(&self, link_id: StringUuid) -> Result<i64>;
+}
+
+pub struct InvitationLinkRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl InvitationLinkRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, name, role_ids, allowed_email_domains, max_uses, use_count,
+           created_by, token_hash, expires_at, revoked_at, created_at, updated_at
+    FROM invitation_links
+"#;
+
+#[async_trait]
+impl InvitationLinkRepository for InvitationLinkRepositoryImpl {
+    async fn create(&self, link: &InvitationLink) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO invitation_links
+                (id, tenant_id, name, role_ids, allowed_email_domains, max_uses, use_count,
+                 created_by, token_hash, expires_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, 0, ?, ?, ?, NOW(), NOW())
+            "#,
+        )
+        .bind(link.id)
+        .bind(link.tenant_id)
+        .bind(&link.name)
+        .bind(sqlx::types::Json(&link.role_ids))
+        .bind(sqlx::types::Json(&link.allowed_email_domains))
+        .bind(link.max_uses)
+        .bind(link.created_by)
+        .bind(&link.token_hash)
+        .bind(link.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<InvitationLink>> {
+        let link = sqlx::query_as::<_, InvitationLink>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(link)
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<InvitationLink>> {
+        let link = sqlx::query_as::<_, InvitationLink>(&format!(
+            "{} WHERE token_hash = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(link)
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InvitationLink>> {
+        let links = sqlx::query_as::<_, InvitationLink>(&format!(
+            "{} WHERE tenant_id = ? ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(links)
+    }
+
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM invitation_links WHERE tenant_id = ?")
+                .bind(tenant_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(count)
+    }
+
+    async fn revoke(&self, id: StringUuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE invitation_links SET revoked_at = NOW() WHERE id = ? AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn claim_use(&self, id: StringUuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE invitation_links
+            SET use_count = use_count + 1
+            WHERE id = ?
+              AND revoked_at IS NULL
+              AND expires_at > NOW()
+              AND (max_uses IS NULL OR use_count < max_uses)
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_use(&self, id: StringUuid) -> Result<()> {
+        sqlx::query(
+            "UPDATE invitation_links SET use_count = use_count - 1 WHERE id = ? AND use_count > 0",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_redemption(&self, redemption: &InvitationLinkRedemption) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO invitation_link_redemptions
+                (id, link_id, tenant_id, user_id, email, created_at)
+            VALUES (?, ?, ?, ?, ?, NOW())
+            "#,
+        )
+        .bind(redemption.id)
+        .bind(redemption.link_id)
+        .bind(redemption.tenant_id)
+        .bind(redemption.user_id)
+        .bind(&redemption.email)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_redemptions(
+        &self,
+        link_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InvitationLinkRedemption>> {
+        let redemptions = sqlx::query_as::<_, InvitationLinkRedemption>(
+            r#"
+            SELECT id, link_id, tenant_id, user_id, email, created_at
+            FROM invitation_link_redemptions
+            WHERE link_id = ?
+            ORDER BY created_at DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(link_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(redemptions)
+    }
+
+    async fn count_redemptions(&self, link_id: StringUuid) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM invitation_link_redemptions WHERE link_id = ?",
+        )
+        .bind(link_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+}
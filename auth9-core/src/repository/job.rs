@@ -0,0 +1,216 @@
+//! Async job repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::job::{CreateJobInput, Job, JobStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    async fn create(&self, input: &CreateJobInput) -> Result<Job>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Job>>;
+    async fn list_by_tenant(&self, tenant_id: StringUuid, limit: i64) -> Result<Vec<Job>>;
+    /// Atomically move the oldest queued job to `running` under `claim_id`
+    async fn claim_next(&self, claim_id: &str) -> Result<Option<Job>>;
+    async fn update_progress(
+        &self,
+        id: StringUuid,
+        total_items: i32,
+        processed_items: i32,
+        failed_items: i32,
+        results: &[serde_json::Value],
+        resume_after: Option<String>,
+    ) -> Result<()>;
+    async fn is_cancel_requested(&self, id: StringUuid) -> Result<bool>;
+    /// Cancel a job that has not started yet. Returns false if it was not queued.
+    async fn cancel_queued(&self, id: StringUuid) -> Result<bool>;
+    /// Flag a running job for cancellation. Returns false if it was not running.
+    async fn request_cancel(&self, id: StringUuid) -> Result<bool>;
+    async fn finish(&self, id: StringUuid, status: JobStatus, error: Option<String>) -> Result<()>;
+    /// Return running jobs whose worker stopped reporting back to the queue
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64>;
+}
+
+pub struct JobRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl JobRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, kind, status, payload, total_items, processed_items, failed_items,
+           results, error, cancel_requested, claim_id, resume_after, created_by, created_at,
+           started_at, finished_at, updated_at
+    FROM jobs
+"#;
+
+#[async_trait]
+impl JobRepository for JobRepositoryImpl {
+    async fn create(&self, input: &CreateJobInput) -> Result<Job> {
+        let id = StringUuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, tenant_id, kind, status, payload, created_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(input.tenant_id)
+        .bind(input.kind.as_str())
+        .bind(JobStatus::Queued.as_str())
+        .bind(sqlx::types::Json(&input.payload))
+        .bind(input.created_by)
+        .execute(&self.pool)
+        .await?;
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to create job")))
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Job>> {
+        let job = sqlx::query_as::<_, Job>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(job)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid, limit: i64) -> Result<Vec<Job>> {
+        let jobs = sqlx::query_as::<_, Job>(&format!(
+            "{} WHERE tenant_id = ? ORDER BY created_at DESC LIMIT ?",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(jobs)
+    }
+
+    async fn claim_next(&self, claim_id: &str) -> Result<Option<Job>> {
+        let claimed = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'running', claim_id = ?, started_at = COALESCE(started_at, NOW())
+            WHERE status = 'queued'
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(claim_id)
+        .execute(&self.pool)
+        .await?;
+
+        if claimed.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let job = sqlx::query_as::<_, Job>(&format!(
+            "{} WHERE claim_id = ? AND status = 'running'",
+            SELECT_COLUMNS
+        ))
+        .bind(claim_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(job)
+    }
+
+    async fn update_progress(
+        &self,
+        id: StringUuid,
+        total_items: i32,
+        processed_items: i32,
+        failed_items: i32,
+        results: &[serde_json::Value],
+        resume_after: Option<String>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET total_items = ?, processed_items = ?, failed_items = ?, results = ?, resume_after = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(total_items)
+        .bind(processed_items)
+        .bind(failed_items)
+        .bind(sqlx::types::Json(results))
+        .bind(resume_after)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn is_cancel_requested(&self, id: StringUuid) -> Result<bool> {
+        let flag: Option<bool> =
+            sqlx::query_scalar("SELECT cancel_requested FROM jobs WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(flag.unwrap_or(false))
+    }
+
+    async fn cancel_queued(&self, id: StringUuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'cancelled', cancel_requested = TRUE, finished_at = NOW()
+            WHERE id = ? AND status = 'queued'
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn request_cancel(&self, id: StringUuid) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE jobs SET cancel_requested = TRUE WHERE id = ? AND status = 'running'",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn finish(&self, id: StringUuid, status: JobStatus, error: Option<String>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = ?, error = ?, finished_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn requeue_stale(&self, stale_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'queued', claim_id = NULL
+            WHERE status = 'running' AND updated_at < ?
+            "#,
+        )
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,204 @@
+//! LDAP sync run repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::ldap_sync::{LdapSyncConnector, LdapSyncRun};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlPool, Row};
+use std::collections::HashMap;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LdapSyncRunRepository: Send + Sync {
+    /// Record a new running run. Returns false without inserting when another
+    /// run of the connector started less than `stale_after_secs` ago is still
+    /// running, so a connector is never synced twice at the same time.
+    async fn start(&self, run: &LdapSyncRun, stale_after_secs: i64) -> Result<bool>;
+    /// Store the final status, counters, changes and cursor of a run
+    async fn finish(&self, run: &LdapSyncRun) -> Result<()>;
+    async fn find_by_id(
+        &self,
+        connector_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<LdapSyncRun>>;
+    async fn list_by_connector(
+        &self,
+        connector_id: StringUuid,
+        limit: i64,
+    ) -> Result<Vec<LdapSyncRun>>;
+    /// Cursor of the latest successful non-dry run
+    async fn last_cursor(&self, connector_id: StringUuid) -> Result<Option<String>>;
+    /// Start time of the latest non-dry run, whatever its outcome
+    async fn last_started_at(&self, connector_id: StringUuid) -> Result<Option<DateTime<Utc>>>;
+    /// Enabled LDAP connectors of every tenant
+    async fn list_ldap_connectors(&self) -> Result<Vec<LdapSyncConnector>>;
+}
+
+pub struct LdapSyncRunRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl LdapSyncRunRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, connector_id, triggered_by, mode, dry_run, status, conflict_policy,
+           cursor_before, cursor_after, users_seen, users_created, users_updated, users_linked,
+           conflicts, roles_assigned, roles_removed, changes, error, started_at, finished_at
+    FROM ldap_sync_runs
+"#;
+
+#[async_trait]
+impl LdapSyncRunRepository for LdapSyncRunRepositoryImpl {
+    async fn start(&self, run: &LdapSyncRun, stale_after_secs: i64) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO ldap_sync_runs
+                (id, tenant_id, connector_id, triggered_by, mode, dry_run, status,
+                 conflict_policy, cursor_before, started_at)
+            SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+            FROM DUAL
+            WHERE NOT EXISTS (
+                SELECT 1 FROM ldap_sync_runs
+                WHERE connector_id = ? AND status = 'running'
+                  AND started_at > NOW() - INTERVAL ? SECOND
+            )
+            "#,
+        )
+        .bind(run.id)
+        .bind(run.tenant_id)
+        .bind(run.connector_id)
+        .bind(&run.triggered_by)
+        .bind(&run.mode)
+        .bind(run.dry_run)
+        .bind(&run.status)
+        .bind(&run.conflict_policy)
+        .bind(&run.cursor_before)
+        .bind(run.started_at)
+        .bind(run.connector_id)
+        .bind(stale_after_secs)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn finish(&self, run: &LdapSyncRun) -> Result<()> {
+        let changes = serde_json::to_string(&run.changes)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Serialize sync changes: {}", e)))?;
+        sqlx::query(
+            r#"
+            UPDATE ldap_sync_runs
+            SET status = ?, cursor_after = ?, users_seen = ?, users_created = ?,
+                users_updated = ?, users_linked = ?, conflicts = ?, roles_assigned = ?,
+                roles_removed = ?, changes = ?, error = ?, finished_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&run.status)
+        .bind(&run.cursor_after)
+        .bind(run.users_seen)
+        .bind(run.users_created)
+        .bind(run.users_updated)
+        .bind(run.users_linked)
+        .bind(run.conflicts)
+        .bind(run.roles_assigned)
+        .bind(run.roles_removed)
+        .bind(changes)
+        .bind(&run.error)
+        .bind(run.finished_at)
+        .bind(run.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(
+        &self,
+        connector_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<LdapSyncRun>> {
+        let run = sqlx::query_as::<_, LdapSyncRun>(&format!(
+            "{} WHERE id = ? AND connector_id = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(id)
+        .bind(connector_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(run)
+    }
+
+    async fn list_by_connector(
+        &self,
+        connector_id: StringUuid,
+        limit: i64,
+    ) -> Result<Vec<LdapSyncRun>> {
+        let runs = sqlx::query_as::<_, LdapSyncRun>(&format!(
+            "{} WHERE connector_id = ? ORDER BY started_at DESC LIMIT ?",
+            SELECT_COLUMNS
+        ))
+        .bind(connector_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(runs)
+    }
+
+    async fn last_cursor(&self, connector_id: StringUuid) -> Result<Option<String>> {
+        let cursor: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            SELECT cursor_after FROM ldap_sync_runs
+            WHERE connector_id = ? AND status = 'succeeded' AND dry_run = FALSE
+            ORDER BY started_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(connector_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(cursor.flatten())
+    }
+
+    async fn last_started_at(&self, connector_id: StringUuid) -> Result<Option<DateTime<Utc>>> {
+        let started_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            SELECT MAX(started_at) FROM ldap_sync_runs
+            WHERE connector_id = ? AND dry_run = FALSE
+            "#,
+        )
+        .bind(connector_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(started_at)
+    }
+
+    async fn list_ldap_connectors(&self) -> Result<Vec<LdapSyncConnector>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, alias, config
+            FROM enterprise_sso_connectors
+            WHERE provider_type = 'ldap' AND enabled = TRUE
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| -> Result<LdapSyncConnector> {
+                let config_value: serde_json::Value = row.try_get("config")?;
+                let config: HashMap<String, String> =
+                    serde_json::from_value(config_value).unwrap_or_default();
+                Ok(LdapSyncConnector {
+                    id: row.try_get("id")?,
+                    tenant_id: row.try_get("tenant_id")?,
+                    alias: row.try_get("alias")?,
+                    config,
+                })
+            })
+            .collect()
+    }
+}
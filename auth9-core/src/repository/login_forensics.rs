@@ -0,0 +1,110 @@
+//! Failed sign-in forensic capture repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::login_forensics::LoginFailureRecord;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+
+/// Filter of a capture search; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct LoginFailureFilter {
+    pub ip_address: Option<String>,
+    pub user_id: Option<StringUuid>,
+    pub identifier_hash: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LoginForensicsRepository: Send + Sync {
+    async fn create(&self, record: &LoginFailureRecord) -> Result<()>;
+    /// Newest captures first
+    async fn search(
+        &self,
+        filter: &LoginFailureFilter,
+        limit: i64,
+    ) -> Result<Vec<LoginFailureRecord>>;
+    /// Delete captures created before `before`; returns the number deleted
+    async fn delete_before(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+pub struct LoginForensicsRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl LoginForensicsRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LoginForensicsRepository for LoginForensicsRepositoryImpl {
+    async fn create(&self, record: &LoginFailureRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_failure_forensics
+                (id, user_id, identifier_hash, tenant_id, client_id, ip_address,
+                 user_agent, failure_stage, failure_reason, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(record.id)
+        .bind(record.user_id)
+        .bind(&record.identifier_hash)
+        .bind(record.tenant_id)
+        .bind(&record.client_id)
+        .bind(&record.ip_address)
+        .bind(&record.user_agent)
+        .bind(record.failure_stage)
+        .bind(&record.failure_reason)
+        .bind(record.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        filter: &LoginFailureFilter,
+        limit: i64,
+    ) -> Result<Vec<LoginFailureRecord>> {
+        let mut builder = QueryBuilder::<MySql>::new(
+            "SELECT id, user_id, identifier_hash, tenant_id, client_id, ip_address, \
+             user_agent, failure_stage, failure_reason, created_at \
+             FROM login_failure_forensics WHERE 1 = 1",
+        );
+        if let Some(ip) = &filter.ip_address {
+            builder.push(" AND ip_address = ").push_bind(ip.clone());
+        }
+        if let Some(user_id) = filter.user_id {
+            builder.push(" AND user_id = ").push_bind(user_id);
+        }
+        if let Some(hash) = &filter.identifier_hash {
+            builder
+                .push(" AND identifier_hash = ")
+                .push_bind(hash.clone());
+        }
+        if let Some(since) = filter.since {
+            builder.push(" AND created_at >= ").push_bind(since);
+        }
+        builder
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(limit);
+        let records = builder
+            .build_query_as::<LoginFailureRecord>()
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(records)
+    }
+
+    async fn delete_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM login_failure_forensics WHERE created_at < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
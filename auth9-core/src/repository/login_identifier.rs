@@ -0,0 +1,130 @@
+//! Login identifier repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::login_identifier::{LoginIdentifier, LoginIdentifierKind, GLOBAL_SCOPE};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait LoginIdentifierRepository: Send + Sync {
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<LoginIdentifier>>;
+    /// Identifiers with the value, at most two. With a tenant, only those in
+    /// the tenant's scope or the global scope.
+    async fn find_by_value(
+        &self,
+        kind: LoginIdentifierKind,
+        value: &str,
+        tenant_id: Option<StringUuid>,
+    ) -> Result<Vec<LoginIdentifier>>;
+    /// Replace the user's identifier of the kind. Fails with a conflict when
+    /// the value is taken in its scope.
+    async fn assign(&self, identifier: &LoginIdentifier) -> Result<()>;
+    async fn remove(&self, user_id: StringUuid, kind: LoginIdentifierKind) -> Result<bool>;
+}
+
+pub struct LoginIdentifierRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl LoginIdentifierRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LoginIdentifierRepository for LoginIdentifierRepositoryImpl {
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<LoginIdentifier>> {
+        let identifiers = sqlx::query_as::<_, LoginIdentifier>(
+            r#"
+            SELECT user_id, kind, value, scope, created_at
+            FROM user_login_identifiers
+            WHERE user_id = ?
+            ORDER BY kind
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(identifiers)
+    }
+
+    async fn find_by_value(
+        &self,
+        kind: LoginIdentifierKind,
+        value: &str,
+        tenant_id: Option<StringUuid>,
+    ) -> Result<Vec<LoginIdentifier>> {
+        let identifiers = match tenant_id {
+            Some(tenant_id) => {
+                sqlx::query_as::<_, LoginIdentifier>(
+                    r#"
+                    SELECT user_id, kind, value, scope, created_at
+                    FROM user_login_identifiers
+                    WHERE kind = ? AND value = ? AND scope IN (?, ?)
+                    LIMIT 2
+                    "#,
+                )
+                .bind(kind)
+                .bind(value)
+                .bind(tenant_id.to_string())
+                .bind(GLOBAL_SCOPE)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, LoginIdentifier>(
+                    r#"
+                    SELECT user_id, kind, value, scope, created_at
+                    FROM user_login_identifiers
+                    WHERE kind = ? AND value = ?
+                    LIMIT 2
+                    "#,
+                )
+                .bind(kind)
+                .bind(value)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(identifiers)
+    }
+
+    async fn assign(&self, identifier: &LoginIdentifier) -> Result<()> {
+        // Delete and insert rather than upsert: ON DUPLICATE KEY UPDATE would
+        // also match, and overwrite, another user's row holding the value
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM user_login_identifiers WHERE user_id = ? AND kind = ?")
+            .bind(identifier.user_id)
+            .bind(identifier.kind)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_login_identifiers (user_id, kind, value, scope, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(identifier.user_id)
+        .bind(identifier.kind)
+        .bind(&identifier.value)
+        .bind(&identifier.scope)
+        .bind(identifier.created_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn remove(&self, user_id: StringUuid, kind: LoginIdentifierKind) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM user_login_identifiers WHERE user_id = ? AND kind = ?")
+                .bind(user_id)
+                .bind(kind)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,128 @@
+//! MFA enrollment campaign repository
+//!
+//! A member counts as enrolled with TOTP enabled or a registered passkey.
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::mfa_enrollment::MfaCampaignMember;
+use async_trait::async_trait;
+use sqlx::{FromRow, MySqlPool};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MfaEnrollmentRepository: Send + Sync {
+    /// Members of a tenant with their enrollment, in user ID order after
+    /// `after`
+    async fn list_members(
+        &self,
+        tenant_id: StringUuid,
+        after: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<MfaCampaignMember>>;
+    async fn find_member(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<Option<MfaCampaignMember>>;
+    async fn is_enrolled(&self, user_id: StringUuid) -> Result<bool>;
+}
+
+pub struct MfaEnrollmentRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl MfaEnrollmentRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(FromRow)]
+struct MfaCampaignMemberRow {
+    user_id: StringUuid,
+    email: String,
+    role_in_tenant: String,
+    mfa_enrolled: i64,
+}
+
+impl From<MfaCampaignMemberRow> for MfaCampaignMember {
+    fn from(row: MfaCampaignMemberRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            email: row.email,
+            role_in_tenant: row.role_in_tenant,
+            mfa_enrolled: row.mfa_enrolled != 0,
+        }
+    }
+}
+
+#[async_trait]
+impl MfaEnrollmentRepository for MfaEnrollmentRepositoryImpl {
+    async fn list_members(
+        &self,
+        tenant_id: StringUuid,
+        after: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<MfaCampaignMember>> {
+        let rows = sqlx::query_as::<_, MfaCampaignMemberRow>(
+            r#"
+            SELECT tu.user_id, u.email, tu.role_in_tenant,
+                   CAST((u.mfa_enabled OR EXISTS(
+                       SELECT 1 FROM webauthn_credentials wc WHERE wc.user_id = tu.user_id
+                   )) AS SIGNED) AS mfa_enrolled
+            FROM tenant_users tu
+            INNER JOIN users u ON u.id = tu.user_id
+            WHERE tu.tenant_id = ?
+              AND (? IS NULL OR tu.user_id > ?)
+            ORDER BY tu.user_id
+            LIMIT ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(after)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(MfaCampaignMember::from).collect())
+    }
+
+    async fn find_member(
+        &self,
+        tenant_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<Option<MfaCampaignMember>> {
+        let row = sqlx::query_as::<_, MfaCampaignMemberRow>(
+            r#"
+            SELECT tu.user_id, u.email, tu.role_in_tenant,
+                   CAST((u.mfa_enabled OR EXISTS(
+                       SELECT 1 FROM webauthn_credentials wc WHERE wc.user_id = tu.user_id
+                   )) AS SIGNED) AS mfa_enrolled
+            FROM tenant_users tu
+            INNER JOIN users u ON u.id = tu.user_id
+            WHERE tu.tenant_id = ? AND tu.user_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(MfaCampaignMember::from))
+    }
+
+    async fn is_enrolled(&self, user_id: StringUuid) -> Result<bool> {
+        let enrolled: i64 = sqlx::query_scalar(
+            r#"
+            SELECT CAST((
+                EXISTS(SELECT 1 FROM users WHERE id = ? AND mfa_enabled)
+                OR EXISTS(SELECT 1 FROM webauthn_credentials WHERE user_id = ?)
+            ) AS SIGNED)
+            "#,
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(enrolled != 0)
+    }
+}
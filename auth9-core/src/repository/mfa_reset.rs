@@ -0,0 +1,171 @@
+//! MFA reset request repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::mfa_reset::MfaResetRequest;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MfaResetRepository: Send + Sync {
+    async fn create(&self, request: &MfaResetRequest) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<MfaResetRequest>>;
+    /// Pending request awaiting a decision, or approved request whose reset
+    /// window is still open
+    async fn find_open_by_user(&self, user_id: StringUuid) -> Result<Option<MfaResetRequest>>;
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        status: Option<String>,
+        limit: i64,
+    ) -> Result<Vec<MfaResetRequest>>;
+    /// Record a tenant admin's decision on a pending request. Returns false
+    /// if the request was no longer pending.
+    async fn decide(
+        &self,
+        id: StringUuid,
+        status: &str,
+        reviewed_by: StringUuid,
+        comment: Option<String>,
+        window_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool>;
+    /// Mark an approved request completed while its window is open. Returns
+    /// false if the request was not approved or the window has closed.
+    async fn complete(&self, id: StringUuid) -> Result<bool>;
+}
+
+pub struct MfaResetRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl MfaResetRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, user_id, status, identity_proof, requester_ip, reviewed_by,
+           review_comment, created_at, expires_at, reviewed_at, window_expires_at, completed_at
+    FROM mfa_reset_requests
+"#;
+
+#[async_trait]
+impl MfaResetRepository for MfaResetRepositoryImpl {
+    async fn create(&self, request: &MfaResetRequest) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_reset_requests
+                (id, tenant_id, user_id, status, identity_proof, requester_ip, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.id)
+        .bind(request.tenant_id)
+        .bind(request.user_id)
+        .bind(&request.status)
+        .bind(&request.identity_proof)
+        .bind(&request.requester_ip)
+        .bind(request.created_at)
+        .bind(request.expires_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<MfaResetRequest>> {
+        let request =
+            sqlx::query_as::<_, MfaResetRequest>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(request)
+    }
+
+    async fn find_open_by_user(&self, user_id: StringUuid) -> Result<Option<MfaResetRequest>> {
+        let request = sqlx::query_as::<_, MfaResetRequest>(&format!(
+            "{} WHERE user_id = ? AND ((status = 'pending' AND expires_at > NOW()) \
+             OR (status = 'approved' AND window_expires_at > NOW())) \
+             ORDER BY created_at DESC LIMIT 1",
+            SELECT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(request)
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        status: Option<String>,
+        limit: i64,
+    ) -> Result<Vec<MfaResetRequest>> {
+        let requests = match status {
+            Some(status) => {
+                sqlx::query_as::<_, MfaResetRequest>(&format!(
+                    "{} WHERE tenant_id = ? AND status = ? ORDER BY created_at DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(tenant_id)
+                .bind(status)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, MfaResetRequest>(&format!(
+                    "{} WHERE tenant_id = ? ORDER BY created_at DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(tenant_id)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(requests)
+    }
+
+    async fn decide(
+        &self,
+        id: StringUuid,
+        status: &str,
+        reviewed_by: StringUuid,
+        comment: Option<String>,
+        window_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_reset_requests
+            SET status = ?, reviewed_by = ?, review_comment = ?, reviewed_at = NOW(),
+                window_expires_at = ?
+            WHERE id = ? AND status = 'pending' AND expires_at > NOW()
+            "#,
+        )
+        .bind(status)
+        .bind(reviewed_by)
+        .bind(comment)
+        .bind(window_expires_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn complete(&self, id: StringUuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE mfa_reset_requests
+            SET status = 'completed', completed_at = NOW()
+            WHERE id = ? AND status = 'approved' AND window_expires_at > NOW()
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
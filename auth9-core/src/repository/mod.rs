@@ -1,64 +1,141 @@
 //! Data access layer (Repository pattern)
 
 pub mod abac;
+pub mod account_deletion;
+pub mod account_recovery;
 pub mod action;
 pub mod adaptive_mfa_policy;
+pub mod admin_scope;
+pub mod admin_unit;
+pub mod attribute_release;
 pub mod audit;
+pub mod audit_integrity;
+pub mod audit_share;
+pub mod billing;
+pub mod claims_enricher;
+pub mod client_registration;
+pub mod conditional_access;
+pub mod custom_domain;
+pub mod email_queue;
+pub mod external_authorizer;
+pub mod idempotency;
+pub mod inactivity;
 pub mod invitation;
+pub mod invitation_link;
+pub mod job;
 pub mod ldap_group_mapping;
+pub mod ldap_sync_run;
 pub mod linked_identity;
+pub mod list_query;
 pub mod login_event;
+pub mod login_forensics;
+pub mod login_identifier;
 pub mod malicious_ip_blacklist;
+pub mod mfa_enrollment;
+pub mod mfa_reset;
+pub mod notification_preference;
+pub mod password_breach_check;
+pub mod password_deny_list;
 pub mod password_reset;
+pub mod permission_snapshot;
+pub mod progressive_profiling;
 pub mod rbac;
+pub mod region_router;
+pub mod role_preset;
+pub mod saga;
 pub mod saml_application;
 pub mod scim_group_mapping;
 pub mod scim_log;
 pub mod scim_token;
 pub mod security_alert;
+pub mod security_digest;
 pub mod service;
 pub mod service_branding;
+pub mod service_scope;
 pub mod session;
+pub mod session_revocation;
 pub mod social_provider;
 pub mod system_settings;
 pub mod tenant;
+pub mod tenant_key;
 pub mod tenant_risk_policy;
 pub mod tenant_service;
+pub mod token_validation_profile;
 pub mod trusted_device;
 pub mod user;
 pub mod user_login_profile;
 pub mod webauthn;
 pub mod webhook;
+pub mod webhook_delivery;
+pub mod workload_identity;
 
 pub use abac::AbacRepository;
+pub use account_deletion::AccountDeletionRepository;
+pub use account_recovery::AccountRecoveryRepository;
 pub use action::ActionRepository;
 pub use adaptive_mfa_policy::AdaptiveMfaPolicyRepository;
+pub use admin_scope::AdminScopeRepository;
+pub use admin_unit::AdminUnitRepository;
+pub use attribute_release::AttributeReleaseRepository;
 pub use audit::AuditRepository;
+pub use audit_integrity::AuditIntegrityRepository;
+pub use audit_share::AuditShareRepository;
+pub use billing::BillingEventRepository;
+pub use claims_enricher::ClaimsEnricherRepository;
+pub use client_registration::ClientRegistrationRepository;
+pub use conditional_access::ConditionalAccessRepository;
+pub use custom_domain::CustomDomainRepository;
+pub use email_queue::EmailQueueRepository;
+pub use external_authorizer::ExternalAuthorizerRepository;
+pub use idempotency::IdempotencyRepository;
+pub use inactivity::InactivityRepository;
 pub use invitation::InvitationRepository;
+pub use invitation_link::InvitationLinkRepository;
+pub use job::JobRepository;
 pub use ldap_group_mapping::LdapGroupRoleMappingRepository;
+pub use ldap_sync_run::LdapSyncRunRepository;
 pub use linked_identity::LinkedIdentityRepository;
 pub use login_event::LoginEventRepository;
+pub use login_forensics::LoginForensicsRepository;
+pub use login_identifier::LoginIdentifierRepository;
 pub use malicious_ip_blacklist::MaliciousIpBlacklistRepository;
+pub use mfa_enrollment::MfaEnrollmentRepository;
+pub use mfa_reset::MfaResetRepository;
+pub use notification_preference::NotificationPreferenceRepository;
+pub use password_breach_check::PasswordBreachCheckRepository;
+pub use password_deny_list::PasswordDenyListRepository;
 pub use password_reset::PasswordResetRepository;
+pub use permission_snapshot::PermissionSnapshotRepository;
+pub use progressive_profiling::ProgressiveProfilingRepository;
 pub use rbac::RbacRepository;
+pub use region_router::RegionRouter;
+pub use role_preset::RolePresetRepository;
+pub use saga::SagaRepository;
 pub use saml_application::SamlApplicationRepository;
 pub use scim_group_mapping::ScimGroupRoleMappingRepository;
 pub use scim_log::ScimProvisioningLogRepository;
 pub use scim_token::ScimTokenRepository;
 pub use security_alert::SecurityAlertRepository;
+pub use security_digest::SecurityDigestRepository;
 pub use service::ServiceRepository;
 pub use service_branding::ServiceBrandingRepository;
+pub use service_scope::ServiceScopeRepository;
 pub use session::SessionRepository;
+pub use session_revocation::SessionRevocationRepository;
 pub use social_provider::SocialProviderRepository;
 pub use system_settings::SystemSettingsRepository;
 pub use tenant::TenantRepository;
+pub use tenant_key::TenantKeyRepository;
 pub use tenant_risk_policy::TenantRiskPolicyRepository;
 pub use tenant_service::TenantServiceRepository;
+pub use token_validation_profile::TokenValidationProfileRepository;
 pub use trusted_device::TrustedDeviceRepository;
 pub use user::UserRepository;
 pub use user_login_profile::UserLoginProfileRepository;
 pub use webauthn::WebAuthnRepository;
 pub use webhook::WebhookRepository;
+pub use webhook_delivery::WebhookDeliveryRepository;
+pub use workload_identity::WorkloadIdentityRepository;
 
 use sqlx::MySqlPool;
 
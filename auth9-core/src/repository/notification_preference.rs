@@ -0,0 +1,180 @@
+//! User notification preference repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::notification_preference::{
+    ActivityDigest, DigestRecipient, NotificationPreferences, SignInRecipient,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait NotificationPreferenceRepository: Send + Sync {
+    async fn find(&self, user_id: StringUuid) -> Result<Option<NotificationPreferences>>;
+    async fn upsert(&self, prefs: &NotificationPreferences) -> Result<()>;
+    /// Users whose digest period has elapsed since the last one was sent
+    async fn list_due_for_digest(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DigestRecipient>>;
+    async fn activity_since(
+        &self,
+        user_id: StringUuid,
+        since: DateTime<Utc>,
+    ) -> Result<ActivityDigest>;
+    async fn mark_digest_sent(&self, user_id: StringUuid, at: DateTime<Utc>) -> Result<()>;
+    /// The user with their sign-in notification preference and how many of
+    /// their tenants turned sign-in notifications off
+    async fn find_sign_in_recipient(&self, user_id: StringUuid) -> Result<Option<SignInRecipient>>;
+}
+
+pub struct NotificationPreferenceRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl NotificationPreferenceRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceRepository for NotificationPreferenceRepositoryImpl {
+    async fn find(&self, user_id: StringUuid) -> Result<Option<NotificationPreferences>> {
+        let prefs = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            SELECT user_id, security_alerts, login_notifications, digest_frequency,
+                   last_digest_sent_at, updated_at
+            FROM user_notification_preferences
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(prefs)
+    }
+
+    async fn upsert(&self, prefs: &NotificationPreferences) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_notification_preferences
+                (user_id, security_alerts, login_notifications, digest_frequency,
+                 last_digest_sent_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                security_alerts = VALUES(security_alerts),
+                login_notifications = VALUES(login_notifications),
+                digest_frequency = VALUES(digest_frequency)
+            "#,
+        )
+        .bind(prefs.user_id)
+        .bind(prefs.security_alerts)
+        .bind(prefs.login_notifications)
+        .bind(prefs.digest_frequency)
+        .bind(prefs.last_digest_sent_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_due_for_digest(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DigestRecipient>> {
+        let recipients = sqlx::query_as::<_, DigestRecipient>(
+            r#"
+            SELECT p.user_id, u.email, u.display_name, u.locale, p.digest_frequency,
+                   p.last_digest_sent_at
+            FROM user_notification_preferences p
+            JOIN users u ON u.id = p.user_id
+            WHERE (p.digest_frequency = 'daily'
+                   AND (p.last_digest_sent_at IS NULL
+                        OR p.last_digest_sent_at <= DATE_SUB(?, INTERVAL 1 DAY)))
+               OR (p.digest_frequency = 'weekly'
+                   AND (p.last_digest_sent_at IS NULL
+                        OR p.last_digest_sent_at <= DATE_SUB(?, INTERVAL 7 DAY)))
+            ORDER BY p.last_digest_sent_at IS NOT NULL, p.last_digest_sent_at
+            LIMIT ?
+            "#,
+        )
+        .bind(now)
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(recipients)
+    }
+
+    async fn activity_since(
+        &self,
+        user_id: StringUuid,
+        since: DateTime<Utc>,
+    ) -> Result<ActivityDigest> {
+        let (successful_logins, failed_logins): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                CAST(COALESCE(SUM(CASE WHEN event_type IN ('success', 'social') THEN 1 ELSE 0 END), 0) AS SIGNED),
+                CAST(COALESCE(SUM(CASE WHEN event_type IN ('failed_password', 'failed_mfa', 'locked') THEN 1 ELSE 0 END), 0) AS SIGNED)
+            FROM login_events
+            WHERE user_id = ? AND created_at >= ?
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let security_alerts: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM security_alerts WHERE user_id = ? AND created_at >= ?",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ActivityDigest {
+            successful_logins,
+            failed_logins,
+            security_alerts,
+        })
+    }
+
+    async fn mark_digest_sent(&self, user_id: StringUuid, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            "UPDATE user_notification_preferences SET last_digest_sent_at = ? WHERE user_id = ?",
+        )
+        .bind(at)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_sign_in_recipient(&self, user_id: StringUuid) -> Result<Option<SignInRecipient>> {
+        let recipient = sqlx::query_as::<_, SignInRecipient>(
+            r#"
+            SELECT u.id AS user_id, u.email, u.display_name, u.locale,
+                   p.login_notifications,
+                   CAST((SELECT COUNT(*) FROM tenant_users tu
+                         WHERE tu.user_id = u.id) AS SIGNED) AS tenant_count,
+                   CAST((SELECT COUNT(*) FROM tenant_users tu
+                         INNER JOIN tenants t ON t.id = tu.tenant_id
+                         WHERE tu.user_id = u.id
+                           AND JSON_EXTRACT(t.settings, '$.login_notifications') = CAST('false' AS JSON)
+                        ) AS SIGNED) AS tenants_opted_out
+            FROM users u
+            LEFT JOIN user_notification_preferences p ON p.user_id = u.id
+            WHERE u.id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(recipient)
+    }
+}
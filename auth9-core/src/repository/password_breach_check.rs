@@ -0,0 +1,120 @@
+//! Password breach check repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::password_breach::{
+    PasswordBreachCheck, PasswordBreachSource, PasswordBreachStatus,
+};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PasswordBreachCheckRepository: Send + Sync {
+    /// Store the check of an account; a retried job overwrites its earlier
+    /// result for the same account
+    async fn upsert(&self, check: &PasswordBreachCheck) -> Result<()>;
+    async fn list_by_job(&self, job_id: StringUuid) -> Result<Vec<PasswordBreachCheck>>;
+    /// Whether a campaign is waiting for the user's next password login
+    async fn has_pending(&self, user_id: StringUuid) -> Result<bool>;
+    /// Record the result of a login-time check on every pending check of the
+    /// user. Returns the number of checks resolved.
+    async fn resolve_pending(
+        &self,
+        user_id: StringUuid,
+        status: PasswordBreachStatus,
+        breach_count: u64,
+    ) -> Result<u64>;
+}
+
+pub struct PasswordBreachCheckRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl PasswordBreachCheckRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PasswordBreachCheckRepository for PasswordBreachCheckRepositoryImpl {
+    async fn upsert(&self, check: &PasswordBreachCheck) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO password_breach_checks
+                (id, job_id, tenant_id, user_id, email, status, source, breach_count,
+                 action_taken, created_at, checked_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                status = VALUES(status),
+                source = VALUES(source),
+                breach_count = VALUES(breach_count),
+                action_taken = VALUES(action_taken),
+                checked_at = VALUES(checked_at)
+            "#,
+        )
+        .bind(check.id)
+        .bind(check.job_id)
+        .bind(check.tenant_id)
+        .bind(check.user_id)
+        .bind(&check.email)
+        .bind(&check.status)
+        .bind(&check.source)
+        .bind(check.breach_count)
+        .bind(&check.action_taken)
+        .bind(check.created_at)
+        .bind(check.checked_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_by_job(&self, job_id: StringUuid) -> Result<Vec<PasswordBreachCheck>> {
+        let checks = sqlx::query_as::<_, PasswordBreachCheck>(
+            r#"
+            SELECT id, job_id, tenant_id, user_id, email, status, source, breach_count,
+                   action_taken, created_at, checked_at
+            FROM password_breach_checks
+            WHERE job_id = ?
+            ORDER BY created_at, email
+            "#,
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(checks)
+    }
+
+    async fn has_pending(&self, user_id: StringUuid) -> Result<bool> {
+        let pending: Option<i32> = sqlx::query_scalar(
+            "SELECT 1 FROM password_breach_checks WHERE user_id = ? AND status = 'pending' LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(pending.is_some())
+    }
+
+    async fn resolve_pending(
+        &self,
+        user_id: StringUuid,
+        status: PasswordBreachStatus,
+        breach_count: u64,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE password_breach_checks
+            SET status = ?, source = ?, breach_count = ?, checked_at = NOW()
+            WHERE user_id = ? AND status = 'pending'
+            "#,
+        )
+        .bind(status.as_str())
+        .bind(PasswordBreachSource::Login.as_str())
+        .bind(i64::try_from(breach_count).unwrap_or(i64::MAX))
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,94 @@
+//! Tenant password deny list repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::password_deny_list::TenantPasswordDenyList;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PasswordDenyListRepository: Send + Sync {
+    async fn find(&self, tenant_id: StringUuid) -> Result<Option<TenantPasswordDenyList>>;
+    /// Deny lists of every tenant the user is a member of
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<TenantPasswordDenyList>>;
+    async fn upsert(&self, list: &TenantPasswordDenyList) -> Result<()>;
+    /// Returns whether a list was deleted
+    async fn delete(&self, tenant_id: StringUuid) -> Result<bool>;
+}
+
+pub struct PasswordDenyListRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl PasswordDenyListRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PasswordDenyListRepository for PasswordDenyListRepositoryImpl {
+    async fn find(&self, tenant_id: StringUuid) -> Result<Option<TenantPasswordDenyList>> {
+        let list = sqlx::query_as::<_, TenantPasswordDenyList>(
+            r#"
+            SELECT tenant_id, filter_bits, hash_count, term_lengths, term_count, updated_at
+            FROM tenant_password_deny_lists
+            WHERE tenant_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(list)
+    }
+
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<TenantPasswordDenyList>> {
+        let lists = sqlx::query_as::<_, TenantPasswordDenyList>(
+            r#"
+            SELECT d.tenant_id, d.filter_bits, d.hash_count, d.term_lengths, d.term_count,
+                   d.updated_at
+            FROM tenant_password_deny_lists d
+            INNER JOIN tenant_users tu ON tu.tenant_id = d.tenant_id
+            WHERE tu.user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(lists)
+    }
+
+    async fn upsert(&self, list: &TenantPasswordDenyList) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_password_deny_lists
+                (tenant_id, filter_bits, hash_count, term_lengths, term_count, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                filter_bits = VALUES(filter_bits),
+                hash_count = VALUES(hash_count),
+                term_lengths = VALUES(term_lengths),
+                term_count = VALUES(term_count),
+                updated_at = VALUES(updated_at)
+            "#,
+        )
+        .bind(list.tenant_id)
+        .bind(&list.filter_bits)
+        .bind(list.hash_count)
+        .bind(list.term_lengths)
+        .bind(list.term_count)
+        .bind(list.updated_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, tenant_id: StringUuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM tenant_password_deny_lists WHERE tenant_id = ?")
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,80 @@
+//! Permission snapshot repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::permission_snapshot::{
+    RoleAssignment, RolePermissionCode, SnapshotRole, TenantPermissionData,
+};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PermissionSnapshotRepository: Send + Sync {
+    /// Role assignments of the tenant's members with the roles and
+    /// permissions of the services they belong to
+    async fn load_tenant(&self, tenant_id: StringUuid) -> Result<TenantPermissionData>;
+}
+
+pub struct PermissionSnapshotRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl PermissionSnapshotRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Services with a role assigned in the tenant (parent roles live in the
+/// same service)
+const ASSIGNED_SERVICES: &str = r#"
+    SELECT DISTINCT ar.service_id
+    FROM roles ar
+    INNER JOIN user_tenant_roles autr ON autr.role_id = ar.id
+    INNER JOIN tenant_users atu ON atu.id = autr.tenant_user_id
+    WHERE atu.tenant_id = ?
+"#;
+
+#[async_trait]
+impl PermissionSnapshotRepository for PermissionSnapshotRepositoryImpl {
+    async fn load_tenant(&self, tenant_id: StringUuid) -> Result<TenantPermissionData> {
+        let assignments = sqlx::query_as::<_, RoleAssignment>(
+            r#"
+            SELECT tu.user_id, utr.role_id
+            FROM user_tenant_roles utr
+            INNER JOIN tenant_users tu ON tu.id = utr.tenant_user_id
+            WHERE tu.tenant_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let roles = sqlx::query_as::<_, SnapshotRole>(&format!(
+            "SELECT r.id, r.service_id, r.name, r.parent_role_id FROM roles r \
+             WHERE r.service_id IN ({})",
+            ASSIGNED_SERVICES
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let role_permissions = sqlx::query_as::<_, RolePermissionCode>(&format!(
+            "SELECT rp.role_id, p.code FROM role_permissions rp \
+             INNER JOIN permissions p ON p.id = rp.permission_id \
+             INNER JOIN roles r ON r.id = rp.role_id \
+             WHERE r.service_id IN ({})",
+            ASSIGNED_SERVICES
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(TenantPermissionData {
+            assignments,
+            roles,
+            role_permissions,
+        })
+    }
+}
@@ -0,0 +1,126 @@
+//! Progressive profiling repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::progressive_profiling::{
+    ProfileRequirement, ProfileRequirementInput, UserProfileAttribute,
+};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ProgressiveProfilingRepository: Send + Sync {
+    async fn list_requirements(&self, service_id: StringUuid) -> Result<Vec<ProfileRequirement>>;
+    /// Atomically replace all requirements of a service
+    async fn replace_requirements(
+        &self,
+        service_id: StringUuid,
+        requirements: &[ProfileRequirementInput],
+    ) -> Result<Vec<ProfileRequirement>>;
+    async fn get_user_attributes(&self, user_id: StringUuid) -> Result<Vec<UserProfileAttribute>>;
+    async fn upsert_user_attributes(
+        &self,
+        user_id: StringUuid,
+        attributes: &HashMap<String, String>,
+    ) -> Result<()>;
+}
+
+pub struct ProgressiveProfilingRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ProgressiveProfilingRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ProgressiveProfilingRepository for ProgressiveProfilingRepositoryImpl {
+    async fn list_requirements(&self, service_id: StringUuid) -> Result<Vec<ProfileRequirement>> {
+        let requirements = sqlx::query_as::<_, ProfileRequirement>(
+            r#"
+            SELECT id, service_id, scope, field_name, label, created_at
+            FROM service_profile_requirements
+            WHERE service_id = ?
+            ORDER BY scope ASC, field_name ASC
+            "#,
+        )
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(requirements)
+    }
+
+    async fn replace_requirements(
+        &self,
+        service_id: StringUuid,
+        requirements: &[ProfileRequirementInput],
+    ) -> Result<Vec<ProfileRequirement>> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM service_profile_requirements WHERE service_id = ?")
+            .bind(service_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for requirement in requirements {
+            sqlx::query(
+                r#"
+                INSERT INTO service_profile_requirements (id, service_id, scope, field_name, label)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(StringUuid::new_v4())
+            .bind(service_id)
+            .bind(&requirement.scope)
+            .bind(&requirement.field_name)
+            .bind(&requirement.label)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        self.list_requirements(service_id).await
+    }
+
+    async fn get_user_attributes(&self, user_id: StringUuid) -> Result<Vec<UserProfileAttribute>> {
+        let attributes = sqlx::query_as::<_, UserProfileAttribute>(
+            r#"
+            SELECT user_id, attr_key, attr_value, updated_at
+            FROM user_profile_attributes
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(attributes)
+    }
+
+    async fn upsert_user_attributes(
+        &self,
+        user_id: StringUuid,
+        attributes: &HashMap<String, String>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for (key, value) in attributes {
+            sqlx::query(
+                r#"
+                INSERT INTO user_profile_attributes (user_id, attr_key, attr_value)
+                VALUES (?, ?, ?)
+                ON DUPLICATE KEY UPDATE attr_value = VALUES(attr_value)
+                "#,
+            )
+            .bind(user_id)
+            .bind(key)
+            .bind(value)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
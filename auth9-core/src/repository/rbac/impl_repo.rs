@@ -2,7 +2,8 @@
 
 use super::{RbacRepository, RbacRepositoryImpl};
 use crate::error::{AppError, Result};
-use crate::models::common::StringUuid;
+use crate::models::cardinality::CardinalityLimits;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
 use crate::models::rbac::{
     AssignRolesInput, CreatePermissionInput, CreateRoleInput, Permission, Role, UpdateRoleInput,
     UserRolesInTenant,
@@ -119,6 +120,45 @@ impl RbacRepository for RbacRepositoryImpl {
         Ok(())
     }
 
+    async fn find_permission_namespace(&self, service_id: StringUuid) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT namespace FROM service_permission_namespaces WHERE service_id = ?",
+        )
+        .bind(service_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(namespace,)| namespace))
+    }
+
+    async fn set_permission_namespace(
+        &self,
+        service_id: StringUuid,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        match namespace {
+            Some(namespace) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO service_permission_namespaces (service_id, namespace)
+                    VALUES (?, ?)
+                    ON DUPLICATE KEY UPDATE namespace = VALUES(namespace)
+                    "#,
+                )
+                .bind(service_id)
+                .bind(namespace)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM service_permission_namespaces WHERE service_id = ?")
+                    .bind(service_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     async fn create_role(&self, input: &CreateRoleInput) -> Result<Role> {
         let id = StringUuid::new_v4();
         let service_id = StringUuid::from(input.service_id);
@@ -153,7 +193,7 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_role_by_id(&self, id: StringUuid) -> Result<Option<Role>> {
         let role = sqlx::query_as::<_, Role>(
-            "SELECT id, service_id, name, description, NULLIF(TRIM(parent_role_id), '') AS parent_role_id, created_at, updated_at FROM roles WHERE REPLACE(id, '-', '') = REPLACE(?, '-', '')",
+            "SELECT id, service_id, name, description, NULLIF(TRIM(parent_role_id), '') AS parent_role_id, version, created_at, updated_at FROM roles WHERE REPLACE(id, '-', '') = REPLACE(?, '-', '')",
         )
         .bind(id)
         .fetch_optional(&self.pool)
@@ -164,7 +204,7 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_roles_by_service(&self, service_id: StringUuid) -> Result<Vec<Role>> {
         let roles = sqlx::query_as::<_, Role>(
-            "SELECT id, service_id, name, description, NULLIF(TRIM(parent_role_id), '') AS parent_role_id, created_at, updated_at FROM roles WHERE service_id = ?",
+            "SELECT id, service_id, name, description, NULLIF(TRIM(parent_role_id), '') AS parent_role_id, version, created_at, updated_at FROM roles WHERE service_id = ?",
         )
         .bind(service_id)
         .fetch_all(&self.pool)
@@ -173,11 +213,17 @@ impl RbacRepository for RbacRepositoryImpl {
         Ok(roles)
     }
 
-    async fn update_role(&self, id: StringUuid, input: &UpdateRoleInput) -> Result<Role> {
+    async fn update_role(
+        &self,
+        id: StringUuid,
+        input: &UpdateRoleInput,
+        expected_version: Option<i32>,
+    ) -> Result<Role> {
         let existing = self
             .find_role_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Role {} not found", id)))?;
+        AppError::check_version("role", expected_version, existing.version)?;
 
         let name = input.name.as_ref().unwrap_or(&existing.name);
         let description = input.description.as_ref().or(existing.description.as_ref());
@@ -191,20 +237,28 @@ impl RbacRepository for RbacRepositoryImpl {
             None => existing.parent_role_id,
         };
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             UPDATE roles
-            SET name = ?, description = ?, parent_role_id = ?, updated_at = NOW()
-            WHERE id = ?
+            SET name = ?, description = ?, parent_role_id = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(name)
         .bind(description)
         .bind(parent_role_id)
         .bind(id)
+        .bind(existing.version)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::VersionConflict {
+                resource: "role".to_string(),
+                current_version: self.find_role_by_id(id).await?.map(|r| r.version),
+            });
+        }
+
         self.find_role_by_id(id)
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update role")))
@@ -378,8 +432,8 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_tenant_user_id(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<Option<StringUuid>> {
         let result: Option<(StringUuid,)> =
             sqlx::query_as("SELECT id FROM tenant_users WHERE user_id = ? AND tenant_id = ?")
@@ -392,8 +446,8 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_role_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<Option<String>> {
         let result: Option<(String,)> = sqlx::query_as(
             "SELECT role_in_tenant FROM tenant_users WHERE user_id = ? AND tenant_id = ?",
@@ -407,8 +461,8 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_user_roles_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<UserRolesInTenant> {
         let base_roles = self
             .find_user_role_records_in_tenant(user_id, tenant_id, None)
@@ -425,9 +479,9 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_user_roles_in_tenant_for_service(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> Result<UserRolesInTenant> {
         let base_roles = self
             .find_user_role_records_in_tenant(user_id, tenant_id, Some(service_id))
@@ -444,12 +498,12 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_user_role_records_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-        service_id: Option<StringUuid>,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: Option<ServiceId>,
     ) -> Result<Vec<Role>> {
         let mut sql = String::from(
-            "SELECT r.id, r.service_id, r.name, r.description, r.parent_role_id, r.created_at, r.updated_at \
+            "SELECT r.id, r.service_id, r.name, r.description, r.parent_role_id, r.version, r.created_at, r.updated_at \
              FROM roles r \
              INNER JOIN user_tenant_roles utr ON r.id = utr.role_id \
              INNER JOIN tenant_users tu ON utr.tenant_user_id = tu.id \
@@ -487,6 +541,11 @@ impl RbacRepository for RbacRepositoryImpl {
             .execute(&self.pool)
             .await?;
 
+        sqlx::query("DELETE FROM service_permission_namespaces WHERE service_id = ?")
+            .bind(service_id)
+            .execute(&self.pool)
+            .await?;
+
         Ok(result.rows_affected())
     }
 
@@ -518,7 +577,7 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn clear_parent_role_references(&self, service_id: StringUuid) -> Result<u64> {
         let result = sqlx::query(
-            "UPDATE roles SET parent_role_id = NULL WHERE service_id = ? AND parent_role_id IS NOT NULL",
+            "UPDATE roles SET parent_role_id = NULL, version = version + 1 WHERE service_id = ? AND parent_role_id IS NOT NULL",
         )
         .bind(service_id)
         .execute(&self.pool)
@@ -537,7 +596,7 @@ impl RbacRepository for RbacRepositoryImpl {
     }
 
     async fn clear_parent_role_reference_by_id(&self, role_id: StringUuid) -> Result<u64> {
-        let result = sqlx::query("UPDATE roles SET parent_role_id = NULL WHERE parent_role_id = ?")
+        let result = sqlx::query("UPDATE roles SET parent_role_id = NULL, version = version + 1 WHERE parent_role_id = ?")
             .bind(role_id)
             .execute(&self.pool)
             .await?;
@@ -547,7 +606,7 @@ impl RbacRepository for RbacRepositoryImpl {
 
     async fn find_user_ids_by_role_in_tenant(
         &self,
-        tenant_id: StringUuid,
+        tenant_id: TenantId,
         role_id: StringUuid,
     ) -> Result<Vec<StringUuid>> {
         let rows: Vec<(StringUuid,)> = sqlx::query_as(
@@ -565,4 +624,44 @@ impl RbacRepository for RbacRepositoryImpl {
 
         Ok(rows.into_iter().map(|(id,)| id).collect())
     }
+
+    async fn find_tenant_cardinality_limits(
+        &self,
+        tenant_id: TenantId,
+    ) -> Result<Option<CardinalityLimits>> {
+        let row: Option<(Option<sqlx::types::Json<serde_json::Value>>,)> = sqlx::query_as(
+            "SELECT JSON_EXTRACT(settings, '$.cardinality_limits') FROM tenants WHERE id = ?",
+        )
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(parse_cardinality_limits(row))
+    }
+
+    async fn find_service_cardinality_limits(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<CardinalityLimits>> {
+        let row: Option<(Option<sqlx::types::Json<serde_json::Value>>,)> = sqlx::query_as(
+            r#"
+            SELECT JSON_EXTRACT(t.settings, '$.cardinality_limits')
+            FROM services s
+            INNER JOIN tenants t ON t.id = s.tenant_id
+            WHERE s.id = ?
+            "#,
+        )
+        .bind(service_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(parse_cardinality_limits(row))
+    }
+}
+
+fn parse_cardinality_limits(
+    row: Option<(Option<sqlx::types::Json<serde_json::Value>>,)>,
+) -> Option<CardinalityLimits> {
+    row.and_then(|(value,)| value)
+        .and_then(|value| serde_json::from_value(value.0).ok())
 }
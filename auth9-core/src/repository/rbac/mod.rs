@@ -1,7 +1,8 @@
 //! RBAC repository
 
 use crate::error::Result;
-use crate::models::common::StringUuid;
+use crate::models::cardinality::CardinalityLimits;
+use crate::models::common::{ServiceId, StringUuid, TenantId, UserId};
 use crate::models::rbac::{
     AssignRolesInput, CreatePermissionInput, CreateRoleInput, Permission, Role, UpdateRoleInput,
     UserRolesInTenant,
@@ -22,12 +23,26 @@ pub trait RbacRepository: Send + Sync {
     async fn find_permission_by_id(&self, id: StringUuid) -> Result<Option<Permission>>;
     async fn find_permissions_by_service(&self, service_id: StringUuid) -> Result<Vec<Permission>>;
     async fn delete_permission(&self, id: StringUuid) -> Result<()>;
+    async fn find_permission_namespace(&self, service_id: StringUuid) -> Result<Option<String>>;
+    /// Set or clear (`None`) the namespace prefixed to new permission codes
+    async fn set_permission_namespace(
+        &self,
+        service_id: StringUuid,
+        namespace: Option<String>,
+    ) -> Result<()>;
 
     // Roles
     async fn create_role(&self, input: &CreateRoleInput) -> Result<Role>;
     async fn find_role_by_id(&self, id: StringUuid) -> Result<Option<Role>>;
     async fn find_roles_by_service(&self, service_id: StringUuid) -> Result<Vec<Role>>;
-    async fn update_role(&self, id: StringUuid, input: &UpdateRoleInput) -> Result<Role>;
+    /// Apply `input`; with `expected_version` set the write only succeeds if
+    /// the stored version still matches.
+    async fn update_role(
+        &self,
+        id: StringUuid,
+        input: &UpdateRoleInput,
+        expected_version: Option<i32>,
+    ) -> Result<Role>;
     async fn delete_role(&self, id: StringUuid) -> Result<()>;
 
     // Role-Permission mapping
@@ -56,36 +71,37 @@ pub trait RbacRepository: Send + Sync {
     ) -> Result<()>;
     async fn find_tenant_user_id(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<Option<StringUuid>>;
     /// Fetch the tenant-level role (owner/admin/member) from tenant_users
     async fn find_role_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<Option<String>>;
     async fn find_user_roles_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> Result<UserRolesInTenant>;
     async fn find_user_roles_in_tenant_for_service(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> Result<UserRolesInTenant>;
     async fn find_user_role_records_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
-        service_id: Option<StringUuid>,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: Option<ServiceId>,
     ) -> Result<Vec<Role>>;
 
     // Cascade delete methods
 
-    /// Delete all permissions for a service (including role_permissions mappings)
+    /// Delete all permissions for a service (including role_permissions mappings
+    /// and the permission namespace)
     async fn delete_permissions_by_service(&self, service_id: StringUuid) -> Result<u64>;
 
     /// Delete all roles for a service (including role_permissions and user_tenant_roles mappings)
@@ -103,9 +119,23 @@ pub trait RbacRepository: Send + Sync {
     /// Find user IDs that have a specific role assigned in a tenant (via user_tenant_roles)
     async fn find_user_ids_by_role_in_tenant(
         &self,
-        tenant_id: StringUuid,
+        tenant_id: TenantId,
         role_id: StringUuid,
     ) -> Result<Vec<StringUuid>>;
+
+    // Cardinality caps
+
+    /// Cap overrides from the tenant's settings
+    async fn find_tenant_cardinality_limits(
+        &self,
+        tenant_id: TenantId,
+    ) -> Result<Option<CardinalityLimits>>;
+
+    /// Cap overrides of the tenant owning a service (`None` for global services)
+    async fn find_service_cardinality_limits(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<CardinalityLimits>>;
 }
 
 pub struct RbacRepositoryImpl {
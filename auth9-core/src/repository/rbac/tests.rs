@@ -105,8 +105,8 @@ async fn test_mock_rbac_find_role_permissions() {
 #[tokio::test]
 async fn test_mock_rbac_find_user_roles_in_tenant() {
     let mut mock = MockRbacRepository::new();
-    let user_id = StringUuid::new_v4();
-    let tenant_id = StringUuid::new_v4();
+    let user_id = UserId::new_v4();
+    let tenant_id = TenantId::new_v4();
 
     mock.expect_find_user_roles_in_tenant()
         .with(eq(user_id), eq(tenant_id))
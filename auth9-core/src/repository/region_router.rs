@@ -0,0 +1,182 @@
+//! Data residency routing: picks the database pool holding a tenant's data
+//!
+//! Control-plane tables (tenants, users, sessions) always live in the home
+//! region's database. Tenant-scoped rows of a tenant pinned to another region
+//! live in that region's database, and repositories working on them must use
+//! the pool returned here. Operations spanning tenants in different regions
+//! are rejected with [`AppError::CrossRegionAccess`] rather than silently
+//! reading from the wrong database.
+
+use crate::config::{DataResidencyConfig, DatabaseConfig};
+use crate::error::{AppError, Result};
+use crate::models::tenant::Tenant;
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::MySqlPool;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Regional database pools keyed by region name
+#[derive(Clone)]
+pub struct RegionRouter {
+    home_region: String,
+    pools: BTreeMap<String, MySqlPool>,
+}
+
+impl RegionRouter {
+    /// Router serving only the home region
+    pub fn new(home_region: impl Into<String>, home_pool: MySqlPool) -> Self {
+        let home_region = home_region.into();
+        let mut pools = BTreeMap::new();
+        pools.insert(home_region.clone(), home_pool);
+        Self { home_region, pools }
+    }
+
+    /// Add the pool of another region
+    pub fn with_region(mut self, region: impl Into<String>, pool: MySqlPool) -> Self {
+        self.pools.insert(region.into(), pool);
+        self
+    }
+
+    /// Build the router from config. Regional pools connect lazily so an
+    /// unreachable region does not block startup of the others.
+    pub fn from_config(
+        residency: &DataResidencyConfig,
+        database: &DatabaseConfig,
+        home_pool: MySqlPool,
+    ) -> Result<Self> {
+        let mut router = Self::new(residency.home_region.clone(), home_pool);
+        for (region, url) in &residency.region_database_urls {
+            if *region == residency.home_region {
+                return Err(AppError::Internal(anyhow::anyhow!(
+                    "Region '{}' is the home region and cannot have its own database URL",
+                    region
+                )));
+            }
+            let pool = MySqlPoolOptions::new()
+                .max_connections(database.max_connections)
+                .min_connections(0)
+                .acquire_timeout(Duration::from_secs(database.acquire_timeout_secs))
+                .idle_timeout(Duration::from_secs(database.idle_timeout_secs))
+                .connect_lazy(url)?;
+            router = router.with_region(region.clone(), pool);
+        }
+        Ok(router)
+    }
+
+    pub fn home_region(&self) -> &str {
+        &self.home_region
+    }
+
+    /// Every routable region, in name order
+    pub fn regions(&self) -> impl Iterator<Item = &str> {
+        self.pools.keys().map(String::as_str)
+    }
+
+    /// Region holding the tenant's data
+    pub fn region_of<'a>(&'a self, tenant: &'a Tenant) -> &'a str {
+        tenant.data_region.as_deref().unwrap_or(&self.home_region)
+    }
+
+    /// Pool of a region, failing with [`AppError::UnknownDataRegion`] when
+    /// this deployment has no database for it
+    pub fn pool_for_region(&self, region: &str) -> Result<&MySqlPool> {
+        self.pools
+            .get(region)
+            .ok_or_else(|| AppError::UnknownDataRegion(region.to_string()))
+    }
+
+    /// Pool holding the tenant's tenant-scoped data
+    pub fn pool_for_tenant(&self, tenant: &Tenant) -> Result<&MySqlPool> {
+        self.pool_for_region(self.region_of(tenant))
+    }
+
+    /// Fail unless the tenant's data lives in `region`
+    pub fn ensure_tenant_in_region(&self, tenant: &Tenant, region: &str) -> Result<()> {
+        let tenant_region = self.region_of(tenant);
+        if tenant_region == region {
+            Ok(())
+        } else {
+            Err(AppError::CrossRegionAccess {
+                resource: format!("tenant {}", tenant.id),
+                region: tenant_region.to_string(),
+                requested_region: region.to_string(),
+            })
+        }
+    }
+
+    /// Single pool for an operation touching several tenants, rejecting
+    /// queries that would span residency regions
+    pub fn pool_for_tenants(&self, tenants: &[&Tenant]) -> Result<&MySqlPool> {
+        let Some((first, rest)) = tenants.split_first() else {
+            return self.pool_for_region(&self.home_region);
+        };
+        let region = self.region_of(first);
+        for tenant in rest {
+            self.ensure_tenant_in_region(tenant, region)?;
+        }
+        self.pool_for_region(region)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lazy_pool() -> MySqlPool {
+        MySqlPoolOptions::new()
+            .connect_lazy("mysql://auth9@localhost:4000/auth9")
+            .unwrap()
+    }
+
+    fn tenant_in(region: Option<&str>) -> Tenant {
+        Tenant {
+            data_region: region.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routes_tenants_to_their_region() {
+        let router = RegionRouter::new("us-east", lazy_pool()).with_region("eu-west", lazy_pool());
+
+        assert_eq!(router.region_of(&tenant_in(None)), "us-east");
+        assert_eq!(router.region_of(&tenant_in(Some("eu-west"))), "eu-west");
+        assert!(router.pool_for_tenant(&tenant_in(Some("eu-west"))).is_ok());
+        assert!(matches!(
+            router.pool_for_tenant(&tenant_in(Some("ap-south"))),
+            Err(AppError::UnknownDataRegion(region)) if region == "ap-south"
+        ));
+        assert_eq!(
+            router.regions().collect::<Vec<_>>(),
+            vec!["eu-west", "us-east"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_cross_region_queries() {
+        let router = RegionRouter::new("us-east", lazy_pool()).with_region("eu-west", lazy_pool());
+        let home = tenant_in(None);
+        let also_home = tenant_in(Some("us-east"));
+        let eu = tenant_in(Some("eu-west"));
+
+        assert!(router.pool_for_tenants(&[&home, &also_home]).is_ok());
+        assert!(router.pool_for_tenants(&[]).is_ok());
+        assert!(matches!(
+            router.pool_for_tenants(&[&home, &eu]),
+            Err(AppError::CrossRegionAccess { region, requested_region, .. })
+                if region == "eu-west" && requested_region == "us-east"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_from_config_rejects_home_region_override() {
+        let mut residency = DataResidencyConfig::default();
+        residency.region_database_urls.insert(
+            "default".to_string(),
+            "mysql://auth9@localhost:4000/auth9".to_string(),
+        );
+        let database = crate::config::Config::for_tests().database;
+
+        assert!(RegionRouter::from_config(&residency, &database, lazy_pool()).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Role preset repository
+
+use crate::error::Result;
+use crate::models::role_preset::RolePreset;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait RolePresetRepository: Send + Sync {
+    /// Insert a preset version; a duplicate (key, version) is a `Conflict`
+    async fn create(&self, preset: &RolePreset) -> Result<()>;
+    /// Latest version of every preset, by key
+    async fn list_latest(&self) -> Result<Vec<RolePreset>>;
+    async fn find_latest(&self, key: &str) -> Result<Option<RolePreset>>;
+    async fn find_version(&self, key: &str, version: i32) -> Result<Option<RolePreset>>;
+    /// All versions of a preset, newest first
+    async fn list_versions(&self, key: &str) -> Result<Vec<RolePreset>>;
+    /// Delete every version of a preset; returns the number removed
+    async fn delete(&self, key: &str) -> Result<u64>;
+}
+
+pub struct RolePresetRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl RolePresetRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, preset_key, version, name, description, definition, created_by, created_at
+    FROM role_presets
+"#;
+
+#[async_trait]
+impl RolePresetRepository for RolePresetRepositoryImpl {
+    async fn create(&self, preset: &RolePreset) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO role_presets
+                (id, preset_key, version, name, description, definition, created_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, NOW())
+            "#,
+        )
+        .bind(preset.id)
+        .bind(&preset.key)
+        .bind(preset.version)
+        .bind(&preset.name)
+        .bind(&preset.description)
+        .bind(sqlx::types::Json(&preset.definition))
+        .bind(preset.created_by)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_latest(&self) -> Result<Vec<RolePreset>> {
+        let presets = sqlx::query_as::<_, RolePreset>(&format!(
+            r#"{SELECT_COLUMNS}
+            WHERE (preset_key, version) IN
+                (SELECT preset_key, MAX(version) FROM role_presets GROUP BY preset_key)
+            ORDER BY preset_key"#
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(presets)
+    }
+
+    async fn find_latest(&self, key: &str) -> Result<Option<RolePreset>> {
+        let preset = sqlx::query_as::<_, RolePreset>(&format!(
+            "{SELECT_COLUMNS} WHERE preset_key = ? ORDER BY version DESC LIMIT 1"
+        ))
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(preset)
+    }
+
+    async fn find_version(&self, key: &str, version: i32) -> Result<Option<RolePreset>> {
+        let preset = sqlx::query_as::<_, RolePreset>(&format!(
+            "{SELECT_COLUMNS} WHERE preset_key = ? AND version = ?"
+        ))
+        .bind(key)
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(preset)
+    }
+
+    async fn list_versions(&self, key: &str) -> Result<Vec<RolePreset>> {
+        let presets = sqlx::query_as::<_, RolePreset>(&format!(
+            "{SELECT_COLUMNS} WHERE preset_key = ? ORDER BY version DESC"
+        ))
+        .bind(key)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(presets)
+    }
+
+    async fn delete(&self, key: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM role_presets WHERE preset_key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
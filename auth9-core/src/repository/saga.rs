@@ -0,0 +1,195 @@
+//! Saga state repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::saga::{SagaFilter, SagaRecord, SagaStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SagaRepository: Send + Sync {
+    /// Insert a new saga; fails with `Conflict` if a saga of the same kind
+    /// already uses the idempotency key
+    async fn create(&self, record: &SagaRecord) -> Result<()>;
+    /// Persist the progress of a saga
+    async fn update(&self, record: &SagaRecord) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<SagaRecord>>;
+    async fn find_by_key(&self, kind: &str, idempotency_key: &str) -> Result<Option<SagaRecord>>;
+    async fn list(&self, filter: &SagaFilter, offset: i64, limit: i64) -> Result<Vec<SagaRecord>>;
+    async fn count(&self, filter: &SagaFilter) -> Result<i64>;
+    /// Atomically move a saga from `from` to `to`; returns false if it was
+    /// no longer in `from`, i.e. someone else claimed it first
+    async fn transition(&self, id: StringUuid, from: SagaStatus, to: SagaStatus) -> Result<bool>;
+    /// Flag running or compensating sagas not updated since `stale_before`
+    /// as stuck; their process stopped before finishing them
+    async fn mark_stale_stuck(&self, stale_before: DateTime<Utc>) -> Result<u64>;
+    /// Delete completed and compensated sagas last updated before `before`
+    async fn delete_finished_before(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+pub struct SagaRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl SagaRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, kind, idempotency_key, status, direction, current_step, steps, context, error,
+           created_at, updated_at
+    FROM sagas
+"#;
+
+fn push_filter(builder: &mut QueryBuilder<'_, MySql>, filter: &SagaFilter) {
+    let mut separator = " WHERE ";
+    if let Some(status) = &filter.status {
+        builder
+            .push(separator)
+            .push("status = ")
+            .push_bind(status.clone());
+        separator = " AND ";
+    }
+    if let Some(kind) = &filter.kind {
+        builder
+            .push(separator)
+            .push("kind = ")
+            .push_bind(kind.clone());
+    }
+}
+
+#[async_trait]
+impl SagaRepository for SagaRepositoryImpl {
+    async fn create(&self, record: &SagaRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO sagas (id, kind, idempotency_key, status, direction, current_step,
+                               steps, context, error)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(record.id)
+        .bind(&record.kind)
+        .bind(&record.idempotency_key)
+        .bind(record.status.as_str())
+        .bind(record.direction.as_str())
+        .bind(record.current_step)
+        .bind(sqlx::types::Json(&record.steps))
+        .bind(sqlx::types::Json(&record.context))
+        .bind(&record.error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db_err)
+                if matches!(db_err.code().as_deref(), Some("23000") | Some("1062")) =>
+            {
+                AppError::Conflict(format!(
+                    "A '{}' operation with this idempotency key is already in progress",
+                    record.kind
+                ))
+            }
+            _ => AppError::Database(e),
+        })?;
+        Ok(())
+    }
+
+    async fn update(&self, record: &SagaRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE sagas
+            SET status = ?, direction = ?, current_step = ?, steps = ?, context = ?, error = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(record.status.as_str())
+        .bind(record.direction.as_str())
+        .bind(record.current_step)
+        .bind(sqlx::types::Json(&record.steps))
+        .bind(sqlx::types::Json(&record.context))
+        .bind(&record.error)
+        .bind(record.id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<SagaRecord>> {
+        let record = sqlx::query_as::<_, SagaRecord>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(record)
+    }
+
+    async fn find_by_key(&self, kind: &str, idempotency_key: &str) -> Result<Option<SagaRecord>> {
+        let record = sqlx::query_as::<_, SagaRecord>(&format!(
+            "{} WHERE kind = ? AND idempotency_key = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(kind)
+        .bind(idempotency_key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    async fn list(&self, filter: &SagaFilter, offset: i64, limit: i64) -> Result<Vec<SagaRecord>> {
+        let mut select = QueryBuilder::<MySql>::new(SELECT_COLUMNS);
+        push_filter(&mut select, filter);
+        select
+            .push(" ORDER BY updated_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let records = select
+            .build_query_as::<SagaRecord>()
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(records)
+    }
+
+    async fn count(&self, filter: &SagaFilter) -> Result<i64> {
+        let mut count = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM sagas");
+        push_filter(&mut count, filter);
+        let (total,): (i64,) = count.build_query_as().fetch_one(&self.pool).await?;
+        Ok(total)
+    }
+
+    async fn transition(&self, id: StringUuid, from: SagaStatus, to: SagaStatus) -> Result<bool> {
+        let result = sqlx::query("UPDATE sagas SET status = ? WHERE id = ? AND status = ?")
+            .bind(to.as_str())
+            .bind(id)
+            .bind(from.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_stale_stuck(&self, stale_before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sagas
+            SET status = 'stuck', error = COALESCE(error, 'Interrupted before finishing')
+            WHERE status IN ('running', 'compensating') AND updated_at < ?
+            "#,
+        )
+        .bind(stale_before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_finished_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM sagas WHERE status IN ('completed', 'compensated') AND updated_at < ?",
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
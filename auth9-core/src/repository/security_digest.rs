@@ -0,0 +1,185 @@
+//! Tenant security alert digest repository
+
+use crate::error::Result;
+use crate::models::analytics::AlertSeverity;
+use crate::models::common::StringUuid;
+use crate::models::security_digest::{DigestAlert, DigestTenant};
+use crate::models::tenant::TenantSettings;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySqlPool};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SecurityDigestRepository: Send + Sync {
+    /// Active tenants whose digest policy is enabled
+    async fn tenants_with_enabled_digest(&self) -> Result<Vec<DigestTenant>>;
+    /// Unresolved alerts of the tenant and its members raised since `since`
+    /// with one of `severities`, newest first
+    async fn alerts_since(
+        &self,
+        tenant_id: StringUuid,
+        since: DateTime<Utc>,
+        severities: Vec<AlertSeverity>,
+        limit: i64,
+    ) -> Result<Vec<DigestAlert>>;
+    /// Emails of the tenant's owners and admins
+    async fn admin_emails(&self, tenant_id: StringUuid) -> Result<Vec<String>>;
+    async fn is_member(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<bool>;
+    async fn mark_sent(&self, tenant_id: StringUuid, at: DateTime<Utc>) -> Result<()>;
+}
+
+pub struct SecurityDigestRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl SecurityDigestRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(FromRow)]
+struct DigestTenantRow {
+    id: StringUuid,
+    name: String,
+    #[sqlx(json)]
+    settings: TenantSettings,
+    last_sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow)]
+struct DigestAlertRow {
+    id: StringUuid,
+    user_id: Option<StringUuid>,
+    user_email: Option<String>,
+    alert_type: String,
+    severity: AlertSeverity,
+    created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+impl SecurityDigestRepository for SecurityDigestRepositoryImpl {
+    async fn tenants_with_enabled_digest(&self) -> Result<Vec<DigestTenant>> {
+        let rows = sqlx::query_as::<_, DigestTenantRow>(
+            r#"
+            SELECT t.id, t.name, t.settings, d.last_sent_at
+            FROM tenants t
+            LEFT JOIN tenant_security_digests d ON d.tenant_id = t.id
+            WHERE t.status = 'active'
+              AND JSON_EXTRACT(t.settings, '$.security_digest.enabled') = CAST('true' AS JSON)
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| DigestTenant {
+                tenant_id: row.id,
+                name: row.name,
+                settings: row.settings,
+                last_sent_at: row.last_sent_at,
+            })
+            .collect())
+    }
+
+    async fn alerts_since(
+        &self,
+        tenant_id: StringUuid,
+        since: DateTime<Utc>,
+        severities: Vec<AlertSeverity>,
+        limit: i64,
+    ) -> Result<Vec<DigestAlert>> {
+        if severities.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Alerts without a tenant (e.g. brute force on the account) count
+        // for every tenant the user is a member of
+        let sql = format!(
+            r#"
+            SELECT sa.id, sa.user_id, u.email AS user_email, sa.alert_type, sa.severity,
+                   sa.created_at
+            FROM security_alerts sa
+            LEFT JOIN users u ON u.id = sa.user_id
+            WHERE sa.created_at >= ?
+              AND sa.resolved_at IS NULL
+              AND sa.severity IN ({})
+              AND (sa.tenant_id = ?
+                   OR (sa.tenant_id IS NULL AND EXISTS (
+                       SELECT 1 FROM tenant_users tu
+                       WHERE tu.tenant_id = ? AND tu.user_id = sa.user_id
+                   )))
+            ORDER BY sa.created_at DESC
+            LIMIT ?
+            "#,
+            vec!["?"; severities.len()].join(", ")
+        );
+        let mut query = sqlx::query_as::<_, DigestAlertRow>(&sql).bind(since);
+        for severity in &severities {
+            query = query.bind(severity);
+        }
+        let rows = query
+            .bind(tenant_id)
+            .bind(tenant_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        // Alert types written by newer releases are skipped, not fatal
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(DigestAlert {
+                    id: row.id,
+                    user_id: row.user_id,
+                    user_email: row.user_email,
+                    alert_type: row.alert_type.parse().ok()?,
+                    severity: row.severity,
+                    created_at: row.created_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn admin_emails(&self, tenant_id: StringUuid) -> Result<Vec<String>> {
+        let emails = sqlx::query_scalar(
+            r#"
+            SELECT u.email
+            FROM tenant_users tu
+            INNER JOIN users u ON u.id = tu.user_id
+            WHERE tu.tenant_id = ? AND tu.role_in_tenant IN ('owner', 'admin')
+            ORDER BY u.email
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(emails)
+    }
+
+    async fn is_member(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<bool> {
+        let member: Option<i64> = sqlx::query_scalar(
+            "SELECT 1 FROM tenant_users WHERE tenant_id = ? AND user_id = ? LIMIT 1",
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(member.is_some())
+    }
+
+    async fn mark_sent(&self, tenant_id: StringUuid, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_security_digests (tenant_id, last_sent_at)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE last_sent_at = VALUES(last_sent_at)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,11 +1,28 @@
 //! Service repository
 
 use crate::error::{AppError, Result};
+use crate::models::common::TokenTtlOverrides;
+use crate::models::list_query::ListQuery;
 use crate::models::service::{CreateServiceInput, Service, ServiceStatus, UpdateServiceInput};
+use crate::repository::list_query::{FieldKind, FieldSpec, ListSpec};
 use async_trait::async_trait;
-use sqlx::MySqlPool;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
 use uuid::Uuid;
 
+/// Fields of `GET /api/v1/services` filters and sort keys
+pub const SERVICE_LIST_SPEC: ListSpec = ListSpec {
+    fields: &[
+        FieldSpec::new("id", "id", FieldKind::Uuid),
+        FieldSpec::new("name", "name", FieldKind::Text).sortable(),
+        FieldSpec::new("base_url", "base_url", FieldKind::Text),
+        FieldSpec::new("status", "status", FieldKind::Text).sortable(),
+        FieldSpec::new("created_at", "created_at", FieldKind::Timestamp).sortable(),
+        FieldSpec::new("updated_at", "updated_at", FieldKind::Timestamp).sortable(),
+    ],
+    search_columns: &["name", "base_url"],
+    default_order: "created_at DESC, id",
+};
+
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait ServiceRepository: Send + Sync {
@@ -27,11 +44,32 @@ pub trait ServiceRepository: Send + Sync {
     async fn list(&self, tenant_id: Option<Uuid>, offset: i64, limit: i64) -> Result<Vec<Service>>;
     async fn list_clients(&self, service_id: Uuid) -> Result<Vec<crate::models::service::Client>>;
     async fn count(&self, tenant_id: Option<Uuid>) -> Result<i64>;
-    async fn update(&self, id: Uuid, input: &UpdateServiceInput) -> Result<Service>;
+    /// Page of services (of `tenant_id` if set) matching the standard list
+    /// query, with the total
+    async fn list_filtered(
+        &self,
+        tenant_id: Option<Uuid>,
+        query: &ListQuery,
+    ) -> Result<(Vec<Service>, i64)>;
+    /// Apply `input`; with `expected_version` set the write only succeeds if
+    /// the stored version still matches.
+    async fn update(
+        &self,
+        id: Uuid,
+        input: &UpdateServiceInput,
+        expected_version: Option<i32>,
+    ) -> Result<Service>;
     async fn delete(&self, id: Uuid) -> Result<()>;
     async fn delete_client(&self, service_id: Uuid, client_id: &str) -> Result<()>;
     async fn update_client_secret_hash(&self, client_id: &str, new_secret_hash: &str)
         -> Result<()>;
+    /// Replace the client's token lifetime overrides
+    async fn update_client_token_ttls(
+        &self,
+        service_id: Uuid,
+        client_id: &str,
+        token_ttls: &TokenTtlOverrides,
+    ) -> Result<crate::models::service::Client>;
 
     /// List all services for a tenant (for cascade delete)
     async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<Service>>;
@@ -40,6 +78,14 @@ pub trait ServiceRepository: Send + Sync {
     async fn delete_clients_by_service(&self, service_id: Uuid) -> Result<u64>;
 }
 
+/// `WHERE tenant_id = ?` when listing one tenant's services
+fn push_tenant_scope(builder: &mut QueryBuilder<'_, MySql>, tenant_id: Option<Uuid>) {
+    if let Some(tid) = tenant_id {
+        builder.push(" WHERE tenant_id = ");
+        builder.push_bind(tid.to_string());
+    }
+}
+
 pub struct ServiceRepositoryImpl {
     pool: MySqlPool,
 }
@@ -58,11 +104,13 @@ impl ServiceRepository for ServiceRepositoryImpl {
             .map_err(|e| AppError::Internal(e.into()))?;
         let logout_uris = serde_json::to_string(&input.logout_uris.clone().unwrap_or_default())
             .map_err(|e| AppError::Internal(e.into()))?;
+        let catalog =
+            serde_json::to_string(&input.catalog).map_err(|e| AppError::Internal(e.into()))?;
 
         sqlx::query(
             r#"
-            INSERT INTO services (id, tenant_id, name, base_url, redirect_uris, logout_uris, status, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, 'active', NOW(), NOW())
+            INSERT INTO services (id, tenant_id, name, base_url, redirect_uris, logout_uris, status, catalog, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, 'active', ?, NOW(), NOW())
             "#,
         )
         // UUID must be converted to string for CHAR(36) columns
@@ -72,6 +120,7 @@ impl ServiceRepository for ServiceRepositoryImpl {
         .bind(&input.base_url)
         .bind(&redirect_uris)
         .bind(&logout_uris)
+        .bind(&catalog)
         .execute(&self.pool)
         .await?;
 
@@ -117,7 +166,7 @@ impl ServiceRepository for ServiceRepositoryImpl {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Service>> {
         let service = sqlx::query_as::<_, Service>(
             r#"
-            SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, created_at, updated_at
+            SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, version, COALESCE(catalog, JSON_OBJECT()) AS catalog, created_at, updated_at
             FROM services
             WHERE id = ?
             "#,
@@ -132,7 +181,7 @@ impl ServiceRepository for ServiceRepositoryImpl {
     async fn find_by_client_id(&self, client_id: &str) -> Result<Option<Service>> {
         let service = sqlx::query_as::<_, Service>(
             r#"
-            SELECT s.id, s.tenant_id, s.name, s.base_url, s.redirect_uris, s.logout_uris, s.status, s.created_at, s.updated_at
+            SELECT s.id, s.tenant_id, s.name, s.base_url, s.redirect_uris, s.logout_uris, s.status, s.version, COALESCE(s.catalog, JSON_OBJECT()) AS catalog, s.created_at, s.updated_at
             FROM services s
             JOIN clients c ON s.id = c.service_id
             WHERE c.client_id = ?
@@ -163,7 +212,7 @@ impl ServiceRepository for ServiceRepositoryImpl {
         let services = if let Some(tid) = tenant_id {
             sqlx::query_as::<_, Service>(
                 r#"
-                SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, created_at, updated_at
+                SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, version, COALESCE(catalog, JSON_OBJECT()) AS catalog, created_at, updated_at
                 FROM services
                 WHERE tenant_id = ?
                 ORDER BY created_at DESC
@@ -178,7 +227,7 @@ impl ServiceRepository for ServiceRepositoryImpl {
         } else {
             sqlx::query_as::<_, Service>(
                 r#"
-                SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, created_at, updated_at
+                SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, version, COALESCE(catalog, JSON_OBJECT()) AS catalog, created_at, updated_at
                 FROM services
                 ORDER BY created_at DESC
                 LIMIT ? OFFSET ?
@@ -217,11 +266,40 @@ impl ServiceRepository for ServiceRepositoryImpl {
         Ok(row.0)
     }
 
-    async fn update(&self, id: Uuid, input: &UpdateServiceInput) -> Result<Service> {
+    async fn list_filtered(
+        &self,
+        tenant_id: Option<Uuid>,
+        query: &ListQuery,
+    ) -> Result<(Vec<Service>, i64)> {
+        let mut select = QueryBuilder::<MySql>::new(
+            "SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, version, COALESCE(catalog, JSON_OBJECT()) AS catalog, created_at, updated_at FROM services",
+        );
+        push_tenant_scope(&mut select, tenant_id);
+        SERVICE_LIST_SPEC.push_conditions(&mut select, query, tenant_id.is_some())?;
+        SERVICE_LIST_SPEC.push_order_and_page(&mut select, query);
+        let services = select
+            .build_query_as::<Service>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut count = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM services");
+        push_tenant_scope(&mut count, tenant_id);
+        SERVICE_LIST_SPEC.push_conditions(&mut count, query, tenant_id.is_some())?;
+        let (total,): (i64,) = count.build_query_as().fetch_one(&self.pool).await?;
+        Ok((services, total))
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        input: &UpdateServiceInput,
+        expected_version: Option<i32>,
+    ) -> Result<Service> {
         let existing = self
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Service {} not found", id)))?;
+        AppError::check_version("service", expected_version, existing.version)?;
 
         let name = input.name.as_ref().unwrap_or(&existing.name);
         let base_url = input.base_url.as_ref().or(existing.base_url.as_ref());
@@ -231,22 +309,25 @@ impl ServiceRepository for ServiceRepositoryImpl {
             .unwrap_or(&existing.redirect_uris);
         let logout_uris = input.logout_uris.as_ref().unwrap_or(&existing.logout_uris);
         let status = input.status.as_ref().unwrap_or(&existing.status);
+        let catalog = input.catalog.as_ref().unwrap_or(&existing.catalog);
 
         let redirect_uris_json =
             serde_json::to_string(&redirect_uris).map_err(|e| AppError::Internal(e.into()))?;
         let logout_uris_json =
             serde_json::to_string(&logout_uris).map_err(|e| AppError::Internal(e.into()))?;
+        let catalog_json =
+            serde_json::to_string(catalog).map_err(|e| AppError::Internal(e.into()))?;
 
         let status_str = match status {
             ServiceStatus::Active => "active",
             ServiceStatus::Inactive => "inactive",
         };
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             UPDATE services
-            SET name = ?, base_url = ?, redirect_uris = ?, logout_uris = ?, status = ?, updated_at = NOW()
-            WHERE id = ?
+            SET name = ?, base_url = ?, redirect_uris = ?, logout_uris = ?, status = ?, catalog = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(name)
@@ -254,16 +335,51 @@ impl ServiceRepository for ServiceRepositoryImpl {
         .bind(&redirect_uris_json)
         .bind(&logout_uris_json)
         .bind(status_str)
+        .bind(&catalog_json)
         .bind(id.to_string())
+        .bind(existing.version)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::VersionConflict {
+                resource: "service".to_string(),
+                current_version: self.find_by_id(id).await?.map(|s| s.version),
+            });
+        }
+
         self.find_by_id(id)
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update service")))
     }
 
     async fn delete(&self, id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM service_claims_enrichers WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM service_external_authorizers WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM service_attribute_release_policies WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM service_token_validation_profiles WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM workload_identity_rules WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("DELETE FROM service_scopes WHERE service_id = ?")
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+
         let result = sqlx::query("DELETE FROM services WHERE id = ?")
             .bind(id.to_string())
             .execute(&self.pool)
@@ -312,10 +428,40 @@ impl ServiceRepository for ServiceRepositoryImpl {
         Ok(())
     }
 
+    async fn update_client_token_ttls(
+        &self,
+        service_id: Uuid,
+        client_id: &str,
+        token_ttls: &TokenTtlOverrides,
+    ) -> Result<crate::models::service::Client> {
+        let result = sqlx::query(
+            r#"
+            UPDATE clients SET access_token_ttl_secs = ?, refresh_token_ttl_secs = ?
+            WHERE service_id = ? AND client_id = ?
+            "#,
+        )
+        .bind(token_ttls.access_token_ttl_secs)
+        .bind(token_ttls.refresh_token_ttl_secs)
+        .bind(service_id.to_string())
+        .bind(client_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Client {} not found",
+                client_id
+            )));
+        }
+        self.find_client_by_client_id(client_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Client {} not found", client_id)))
+    }
+
     async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<Service>> {
         let services = sqlx::query_as::<_, Service>(
             r#"
-            SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, created_at, updated_at
+            SELECT id, tenant_id, name, base_url, redirect_uris, logout_uris, status, version, COALESCE(catalog, JSON_OBJECT()) AS catalog, created_at, updated_at
             FROM services
             WHERE tenant_id = ?
             "#,
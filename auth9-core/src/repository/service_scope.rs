@@ -0,0 +1,110 @@
+//! Service OAuth scope catalog repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::oauth_scope::ServiceScope;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ServiceScopeRepository: Send + Sync {
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<ServiceScope>>;
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        name: &str,
+        description: &str,
+        required_permissions: &[String],
+    ) -> Result<ServiceScope>;
+    async fn delete(&self, service_id: StringUuid, name: &str) -> Result<()>;
+}
+
+pub struct ServiceScopeRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl ServiceScopeRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    async fn find(&self, service_id: StringUuid, name: &str) -> Result<Option<ServiceScope>> {
+        let scope = sqlx::query_as::<_, ServiceScope>(
+            r#"
+            SELECT id, service_id, name, description, required_permissions, created_at, updated_at
+            FROM service_scopes
+            WHERE service_id = ? AND name = ?
+            "#,
+        )
+        .bind(service_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(scope)
+    }
+}
+
+#[async_trait]
+impl ServiceScopeRepository for ServiceScopeRepositoryImpl {
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<ServiceScope>> {
+        let scopes = sqlx::query_as::<_, ServiceScope>(
+            r#"
+            SELECT id, service_id, name, description, required_permissions, created_at, updated_at
+            FROM service_scopes
+            WHERE service_id = ?
+            ORDER BY name
+            "#,
+        )
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(scopes)
+    }
+
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        name: &str,
+        description: &str,
+        required_permissions: &[String],
+    ) -> Result<ServiceScope> {
+        let required_permissions = serde_json::to_string(required_permissions)
+            .map_err(|e| AppError::Internal(e.into()))?;
+        sqlx::query(
+            r#"
+            INSERT INTO service_scopes (id, service_id, name, description, required_permissions)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                description = VALUES(description),
+                required_permissions = VALUES(required_permissions)
+            "#,
+        )
+        .bind(StringUuid::new_v4())
+        .bind(service_id)
+        .bind(name)
+        .bind(description)
+        .bind(&required_permissions)
+        .execute(&self.pool)
+        .await?;
+
+        self.find(service_id, name)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to save service scope")))
+    }
+
+    async fn delete(&self, service_id: StringUuid, name: &str) -> Result<()> {
+        let result = sqlx::query("DELETE FROM service_scopes WHERE service_id = ? AND name = ?")
+            .bind(service_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Scope '{}' is not declared by this service",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
@@ -3,8 +3,11 @@
 use super::{SessionRepository, SessionRepositoryImpl};
 use crate::error::{AppError, Result};
 use crate::models::common::StringUuid;
-use crate::models::session::{CreateSessionInput, Session};
+use crate::models::session::{
+    CreateSessionInput, Session, SessionCreateOutcome, SessionLimit, SessionLimitPolicy,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 #[async_trait]
 impl SessionRepository for SessionRepositoryImpl {
@@ -37,7 +40,7 @@ impl SessionRepository for SessionRepositoryImpl {
     async fn find_by_id(&self, id: StringUuid) -> Result<Option<Session>> {
         let session = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, provider_session_id, device_type, device_name,
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
                    ip_address, location, user_agent, last_active_at, created_at, revoked_at
             FROM sessions
             WHERE id = ?
@@ -56,7 +59,7 @@ impl SessionRepository for SessionRepositoryImpl {
     ) -> Result<Option<Session>> {
         let session = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, provider_session_id, device_type, device_name,
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
                    ip_address, location, user_agent, last_active_at, created_at, revoked_at
             FROM sessions
             WHERE provider_session_id = ?
@@ -72,7 +75,7 @@ impl SessionRepository for SessionRepositoryImpl {
     async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>> {
         let sessions = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, provider_session_id, device_type, device_name,
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
                    ip_address, location, user_agent, last_active_at, created_at, revoked_at
             FROM sessions
             WHERE user_id = ?
@@ -89,7 +92,7 @@ impl SessionRepository for SessionRepositoryImpl {
     async fn list_active_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>> {
         let sessions = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, provider_session_id, device_type, device_name,
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
                    ip_address, location, user_agent, last_active_at, created_at, revoked_at
             FROM sessions
             WHERE user_id = ? AND revoked_at IS NULL
@@ -103,6 +106,37 @@ impl SessionRepository for SessionRepositoryImpl {
         Ok(sessions)
     }
 
+    async fn list_by_tenant_after(
+        &self,
+        tenant_id: StringUuid,
+        after: Option<StringUuid>,
+        active_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Session>> {
+        let sessions = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT s.id, s.user_id, s.provider_session_id, s.device_type, s.device_name,
+                   s.display_name, s.ip_address, s.location, s.user_agent, s.last_active_at,
+                   s.created_at, s.revoked_at
+            FROM sessions s
+            WHERE s.user_id IN (SELECT tu.user_id FROM tenant_users tu WHERE tu.tenant_id = ?)
+              AND (? IS NULL OR s.id > ?)
+              AND (? = FALSE OR s.revoked_at IS NULL)
+            ORDER BY s.id
+            LIMIT ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(after)
+        .bind(after)
+        .bind(active_only)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
     async fn update_last_active(&self, id: StringUuid) -> Result<()> {
         sqlx::query(
             r#"
@@ -118,6 +152,58 @@ impl SessionRepository for SessionRepositoryImpl {
         Ok(())
     }
 
+    async fn update_last_active_batch(
+        &self,
+        updates: &[(StringUuid, DateTime<Utc>)],
+    ) -> Result<u64> {
+        if updates.is_empty() {
+            return Ok(0);
+        }
+
+        // One statement per batch: CASE picks each session's timestamp
+        let mut sql =
+            String::from("UPDATE sessions SET last_active_at = GREATEST(last_active_at, CASE id");
+        for _ in updates {
+            sql.push_str(" WHEN ? THEN ?");
+        }
+        sql.push_str(" END) WHERE revoked_at IS NULL AND id IN (");
+        sql.push_str(&vec!["?"; updates.len()].join(", "));
+        sql.push(')');
+
+        let mut query = sqlx::query(&sql);
+        for (id, at) in updates {
+            query = query.bind(*id).bind(*at);
+        }
+        for (id, _) in updates {
+            query = query.bind(*id);
+        }
+        let result = query.execute(&self.pool).await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn rename(&self, id: StringUuid, display_name: Option<String>) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions
+            SET display_name = ?
+            WHERE id = ? AND revoked_at IS NULL
+            "#,
+        )
+        .bind(display_name)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Session not found or already revoked".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     async fn revoke(&self, id: StringUuid) -> Result<()> {
         let result = sqlx::query(
             r#"
@@ -210,7 +296,7 @@ impl SessionRepository for SessionRepositoryImpl {
     async fn find_oldest_active_by_user(&self, user_id: StringUuid) -> Result<Option<Session>> {
         let session = sqlx::query_as::<_, Session>(
             r#"
-            SELECT id, user_id, provider_session_id, device_type, device_name,
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
                    ip_address, location, user_agent, last_active_at, created_at, revoked_at
             FROM sessions
             WHERE user_id = ? AND revoked_at IS NULL
@@ -224,4 +310,115 @@ impl SessionRepository for SessionRepositoryImpl {
 
         Ok(session)
     }
+
+    async fn list_session_limit_policies(
+        &self,
+        user_id: StringUuid,
+    ) -> Result<Vec<SessionLimitPolicy>> {
+        let rows: Vec<(Option<sqlx::types::Json<serde_json::Value>>,)> = sqlx::query_as(
+            r#"
+            SELECT JSON_EXTRACT(t.settings, '$.session_limit')
+            FROM tenant_users tu
+            INNER JOIN tenants t ON t.id = tu.tenant_id
+            WHERE tu.user_id = ?
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(value,)| value)
+            .filter_map(|value| serde_json::from_value(value.0).ok())
+            .collect())
+    }
+
+    async fn create_within_limit(
+        &self,
+        input: &CreateSessionInput,
+        limit: &SessionLimit,
+    ) -> Result<SessionCreateOutcome> {
+        let mut tx = self.pool.begin().await?;
+
+        // Serializes concurrent logins of the same user until commit
+        sqlx::query("SELECT id FROM users WHERE id = ? FOR UPDATE")
+            .bind(input.user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut active = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT id, user_id, provider_session_id, device_type, device_name, display_name,
+                   ip_address, location, user_agent, last_active_at, created_at, revoked_at
+            FROM sessions
+            WHERE user_id = ? AND revoked_at IS NULL
+            ORDER BY last_active_at ASC
+            "#,
+        )
+        .bind(input.user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut to_revoke: Vec<Session> = Vec::new();
+        active.retain(|s| {
+            if limit.revoke_session_ids.contains(&s.id) {
+                to_revoke.push(s.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        let excess = (active.len() + 1).saturating_sub(limit.max_sessions.max(1) as usize);
+        if excess > 0 {
+            if !limit.evict_oldest {
+                tx.rollback().await?;
+                active.extend(to_revoke);
+                active.sort_by_key(|s| s.last_active_at);
+                return Ok(SessionCreateOutcome::LimitReached { active });
+            }
+            to_revoke.extend(active.drain(..excess));
+        }
+
+        for session in &to_revoke {
+            sqlx::query(
+                "UPDATE sessions SET revoked_at = NOW() WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+            )
+            .bind(session.id)
+            .bind(input.user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let id = StringUuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO sessions (id, user_id, provider_session_id, device_type, device_name,
+                                  ip_address, location, user_agent, last_active_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
+            "#,
+        )
+        .bind(id)
+        .bind(input.user_id)
+        .bind(&input.provider_session_id)
+        .bind(&input.device_type)
+        .bind(&input.device_name)
+        .bind(&input.ip_address)
+        .bind(&input.location)
+        .bind(&input.user_agent)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let session = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to create session")))?;
+        Ok(SessionCreateOutcome::Created {
+            session,
+            revoked: to_revoke,
+        })
+    }
 }
@@ -2,8 +2,11 @@
 
 use crate::error::Result;
 use crate::models::common::StringUuid;
-use crate::models::session::{CreateSessionInput, Session};
+use crate::models::session::{
+    CreateSessionInput, Session, SessionCreateOutcome, SessionLimit, SessionLimitPolicy,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::MySqlPool;
 
 mod impl_repo;
@@ -22,7 +25,25 @@ pub trait SessionRepository: Send + Sync {
     ) -> Result<Option<Session>>;
     async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>>;
     async fn list_active_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>>;
+
+    /// Sessions of the tenant's members ordered by id, starting after
+    /// `after`. A keyset scan, so deep pages cost the same as the first.
+    async fn list_by_tenant_after(
+        &self,
+        tenant_id: StringUuid,
+        after: Option<StringUuid>,
+        active_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Session>>;
     async fn update_last_active(&self, id: StringUuid) -> Result<()>;
+
+    /// Apply buffered activity timestamps; never moves `last_active_at` backwards
+    async fn update_last_active_batch(
+        &self,
+        updates: &[(StringUuid, DateTime<Utc>)],
+    ) -> Result<u64>;
+    /// Set or clear the user-chosen device name of an active session
+    async fn rename(&self, id: StringUuid, display_name: Option<String>) -> Result<()>;
     async fn revoke(&self, id: StringUuid) -> Result<()>;
     async fn revoke_all_by_user(&self, user_id: StringUuid) -> Result<u64>;
     async fn revoke_all_except(&self, user_id: StringUuid, except_id: StringUuid) -> Result<u64>;
@@ -36,6 +57,21 @@ pub trait SessionRepository: Send + Sync {
 
     /// Find the oldest active session for a user (for evicting when limit exceeded)
     async fn find_oldest_active_by_user(&self, user_id: StringUuid) -> Result<Option<Session>>;
+
+    /// Session limit policies of the tenants the user belongs to
+    async fn list_session_limit_policies(
+        &self,
+        user_id: StringUuid,
+    ) -> Result<Vec<SessionLimitPolicy>>;
+
+    /// Create a session without exceeding `limit`. The check, evictions and
+    /// insert run in one transaction serialized on the user row, so parallel
+    /// logins cannot overshoot the limit.
+    async fn create_within_limit(
+        &self,
+        input: &CreateSessionInput,
+        limit: &SessionLimit,
+    ) -> Result<SessionCreateOutcome>;
 }
 
 pub struct SessionRepositoryImpl {
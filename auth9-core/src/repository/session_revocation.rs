@@ -0,0 +1,239 @@
+//! Session revocation feed repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::session_revocation::{RevocationNotice, SessionRevocationReason};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySqlConnection, MySqlPool};
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SessionRevocationRepository: Send + Sync {
+    /// Append a revocation to the feed and return its notice
+    async fn append(
+        &self,
+        session_id: StringUuid,
+        user_id: StringUuid,
+        reason: SessionRevocationReason,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<RevocationNotice>;
+    /// Notices after `since` in sequence order. With a tenant, only notices
+    /// of the tenant's members.
+    async fn list_since(
+        &self,
+        since: i64,
+        tenant_id: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<RevocationNotice>>;
+    /// Smallest sequence still in the feed
+    async fn oldest_sequence(&self) -> Result<Option<i64>>;
+    /// Tenants the user is a member of
+    async fn list_user_tenant_ids(&self, user_id: StringUuid) -> Result<Vec<StringUuid>>;
+    /// Delete notices revoked before `before`; returns the number deleted
+    async fn delete_before(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+pub struct SessionRevocationRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl SessionRevocationRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Insert a notice under the next sequence. The lock on the head row is held
+/// until the caller's transaction ends, so sequences commit in the order
+/// they are allocated and a reader never passes one that commits later.
+async fn append_notice(
+    conn: &mut MySqlConnection,
+    session_id: StringUuid,
+    user_id: StringUuid,
+    reason: SessionRevocationReason,
+    revoked_at: DateTime<Utc>,
+) -> Result<RevocationNotice> {
+    let last_sequence: i64 = sqlx::query_scalar(
+        "SELECT last_sequence FROM session_revocation_head WHERE id = 1 FOR UPDATE",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let sequence = last_sequence + 1;
+
+    sqlx::query(
+        r#"
+        INSERT INTO session_revocations (sequence, session_id, user_id, reason, revoked_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(sequence)
+    .bind(session_id)
+    .bind(user_id)
+    .bind(reason)
+    .bind(revoked_at)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query("UPDATE session_revocation_head SET last_sequence = ? WHERE id = 1")
+        .bind(sequence)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(RevocationNotice {
+        sequence,
+        session_id,
+        user_id,
+        reason,
+        revoked_at,
+    })
+}
+
+#[async_trait]
+impl SessionRevocationRepository for SessionRevocationRepositoryImpl {
+    async fn append(
+        &self,
+        session_id: StringUuid,
+        user_id: StringUuid,
+        reason: SessionRevocationReason,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<RevocationNotice> {
+        let mut tx = self.pool.begin().await?;
+        let notice = append_notice(&mut tx, session_id, user_id, reason, revoked_at).await?;
+        tx.commit().await?;
+        Ok(notice)
+    }
+
+    async fn list_since(
+        &self,
+        since: i64,
+        tenant_id: Option<StringUuid>,
+        limit: i64,
+    ) -> Result<Vec<RevocationNotice>> {
+        let notices = match tenant_id {
+            Some(tenant_id) => {
+                sqlx::query_as::<_, RevocationNotice>(
+                    r#"
+                    SELECT r.sequence, r.session_id, r.user_id, r.reason, r.revoked_at
+                    FROM session_revocations r
+                    WHERE r.sequence > ?
+                      AND r.user_id IN (SELECT tu.user_id FROM tenant_users tu WHERE tu.tenant_id = ?)
+                    ORDER BY r.sequence
+                    LIMIT ?
+                    "#,
+                )
+                .bind(since)
+                .bind(tenant_id)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, RevocationNotice>(
+                    r#"
+                    SELECT sequence, session_id, user_id, reason, revoked_at
+                    FROM session_revocations
+                    WHERE sequence > ?
+                    ORDER BY sequence
+                    LIMIT ?
+                    "#,
+                )
+                .bind(since)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(notices)
+    }
+
+    async fn oldest_sequence(&self) -> Result<Option<i64>> {
+        let oldest: Option<i64> =
+            sqlx::query_scalar("SELECT MIN(sequence) FROM session_revocations")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(oldest)
+    }
+
+    async fn list_user_tenant_ids(&self, user_id: StringUuid) -> Result<Vec<StringUuid>> {
+        let tenant_ids = sqlx::query_scalar::<_, StringUuid>(
+            "SELECT tenant_id FROM tenant_users WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tenant_ids)
+    }
+
+    async fn delete_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM session_revocations WHERE revoked_at < ?")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    #[ignore = "requires real DB — run with DATABASE_URL pointing at a migrated database"]
+    async fn test_list_since_never_skips_a_later_committing_notice() {
+        let pool = MySqlPool::connect(&std::env::var("DATABASE_URL").unwrap())
+            .await
+            .unwrap();
+        let repo = Arc::new(SessionRevocationRepositoryImpl::new(pool.clone()));
+        let since = repo.list_since(0, None, i64::MAX).await.unwrap();
+        let since = since.last().map_or(0, |n| n.sequence);
+
+        // A slow writer allocates the next sequence and has not committed
+        let mut slow = pool.begin().await.unwrap();
+        let first = append_notice(
+            &mut slow,
+            StringUuid::new_v4(),
+            StringUuid::new_v4(),
+            SessionRevocationReason::UserRequest,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        // A second writer starts after it and must wait for the first commit
+        let fast = tokio::spawn({
+            let repo = repo.clone();
+            async move {
+                repo.append(
+                    StringUuid::new_v4(),
+                    StringUuid::new_v4(),
+                    SessionRevocationReason::UserRequest,
+                    Utc::now(),
+                )
+                .await
+                .unwrap()
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!fast.is_finished());
+
+        // A reader in the meantime sees nothing past `since`, so its cursor
+        // cannot move beyond the uncommitted notice
+        assert!(repo.list_since(since, None, 100).await.unwrap().is_empty());
+
+        slow.commit().await.unwrap();
+        let second = fast.await.unwrap();
+        assert_eq!(second.sequence, first.sequence + 1);
+
+        let sequences: Vec<i64> = repo
+            .list_since(since, None, 100)
+            .await
+            .unwrap()
+            .iter()
+            .map(|n| n.sequence)
+            .collect();
+        assert_eq!(sequences, vec![first.sequence, second.sequence]);
+    }
+}
@@ -2,10 +2,33 @@
 
 use crate::error::{AppError, Result};
 use crate::models::common::StringUuid;
+use crate::models::list_query::ListQuery;
 use crate::models::password::PasswordPolicy;
-use crate::models::tenant::{CreateTenantInput, Tenant, UpdateTenantInput};
+use crate::models::tenant::{
+    CreateTenantInput, RenameTenantInput, Tenant, TenantSlugRedirect, UpdateTenantInput,
+};
+use crate::repository::list_query::{FieldKind, FieldSpec, ListSpec};
 use async_trait::async_trait;
-use sqlx::MySqlPool;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+
+/// Fields of `GET /api/v1/tenants` filters and sort keys
+pub const TENANT_LIST_SPEC: ListSpec = ListSpec {
+    fields: &[
+        FieldSpec::new("id", "id", FieldKind::Uuid),
+        FieldSpec::new("name", "name", FieldKind::Text).sortable(),
+        FieldSpec::new("slug", "slug", FieldKind::Text).sortable(),
+        FieldSpec::new("domain", "domain", FieldKind::Text),
+        FieldSpec::new("status", "status", FieldKind::Text).sortable(),
+        FieldSpec::new("data_region", "data_region", FieldKind::Text),
+        FieldSpec::new("created_at", "created_at", FieldKind::Timestamp).sortable(),
+        FieldSpec::new("updated_at", "updated_at", FieldKind::Timestamp).sortable(),
+    ],
+    search_columns: &["name", "slug"],
+    default_order: "created_at DESC, id",
+};
+
+const TENANT_COLUMNS: &str = "id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, data_region, version, created_at, updated_at";
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -17,13 +40,37 @@ pub trait TenantRepository: Send + Sync {
     async fn count(&self) -> Result<i64>;
     async fn search(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<Tenant>>;
     async fn count_search(&self, query: &str) -> Result<i64>;
-    async fn update(&self, id: StringUuid, input: &UpdateTenantInput) -> Result<Tenant>;
+    /// Page of tenants matching the standard list query, with the total
+    async fn list_filtered(&self, query: &ListQuery) -> Result<(Vec<Tenant>, i64)>;
+    /// Apply `input`; with `expected_version` set the write only succeeds if
+    /// the stored version still matches.
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateTenantInput,
+        expected_version: Option<i32>,
+    ) -> Result<Tenant>;
     async fn delete(&self, id: StringUuid) -> Result<()>;
     async fn update_password_policy(
         &self,
         id: StringUuid,
         policy: &PasswordPolicy,
     ) -> Result<Tenant>;
+    /// Re-pin the tenant to `data_region` (None = home region). Only the
+    /// region migration tooling calls this, after the data has moved.
+    async fn set_data_region(&self, id: StringUuid, data_region: Option<String>) -> Result<Tenant>;
+    /// Unexpired redirect left behind by a rename away from `slug`
+    async fn find_slug_redirect(&self, slug: &str) -> Result<Option<TenantSlugRedirect>>;
+    /// Change name and/or slug in one transaction. A slug change records a
+    /// redirect from the old slug until `redirect_expires_at` and rewrites
+    /// the slug-derived enterprise SSO provider aliases. A slug another
+    /// tenant still redirects from is a conflict.
+    async fn rename(
+        &self,
+        id: StringUuid,
+        input: &RenameTenantInput,
+        redirect_expires_at: DateTime<Utc>,
+    ) -> Result<(Tenant, Option<TenantSlugRedirect>)>;
 }
 
 pub struct TenantRepositoryImpl {
@@ -46,8 +93,8 @@ impl TenantRepository for TenantRepositoryImpl {
 
         sqlx::query(
             r#"
-            INSERT INTO tenants (id, name, slug, domain, logo_url, settings, status, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, 'active', NOW(), NOW())
+            INSERT INTO tenants (id, name, slug, domain, logo_url, settings, status, data_region, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, 'active', ?, NOW(), NOW())
             "#,
         )
         .bind(id)
@@ -56,6 +103,7 @@ impl TenantRepository for TenantRepositoryImpl {
         .bind(&input.domain)
         .bind(&input.logo_url)
         .bind(&settings_json)
+        .bind(&input.data_region)
         .execute(&self.pool)
         .await?;
 
@@ -67,7 +115,7 @@ impl TenantRepository for TenantRepositoryImpl {
     async fn find_by_id(&self, id: StringUuid) -> Result<Option<Tenant>> {
         let tenant = sqlx::query_as::<_, Tenant>(
             r#"
-            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, created_at, updated_at
+            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, data_region, version, created_at, updated_at
             FROM tenants
             WHERE id = ?
             "#,
@@ -82,7 +130,7 @@ impl TenantRepository for TenantRepositoryImpl {
     async fn find_by_slug(&self, slug: &str) -> Result<Option<Tenant>> {
         let tenant = sqlx::query_as::<_, Tenant>(
             r#"
-            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, created_at, updated_at
+            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, data_region, version, created_at, updated_at
             FROM tenants
             WHERE slug = ?
             "#,
@@ -97,7 +145,7 @@ impl TenantRepository for TenantRepositoryImpl {
     async fn list(&self, offset: i64, limit: i64) -> Result<Vec<Tenant>> {
         let tenants = sqlx::query_as::<_, Tenant>(
             r#"
-            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, created_at, updated_at
+            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, data_region, version, created_at, updated_at
             FROM tenants
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
@@ -122,7 +170,7 @@ impl TenantRepository for TenantRepositoryImpl {
         let search_pattern = format!("%{}%", query);
         let tenants = sqlx::query_as::<_, Tenant>(
             r#"
-            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, created_at, updated_at
+            SELECT id, name, slug, domain, logo_url, settings, status, COALESCE(password_policy, CAST('null' AS JSON)) as password_policy, data_region, version, created_at, updated_at
             FROM tenants
             WHERE name LIKE ? OR slug LIKE ?
             ORDER BY created_at DESC
@@ -150,11 +198,33 @@ impl TenantRepository for TenantRepositoryImpl {
         Ok(row.0)
     }
 
-    async fn update(&self, id: StringUuid, input: &UpdateTenantInput) -> Result<Tenant> {
+    async fn list_filtered(&self, query: &ListQuery) -> Result<(Vec<Tenant>, i64)> {
+        let mut select =
+            QueryBuilder::<MySql>::new(format!("SELECT {} FROM tenants", TENANT_COLUMNS));
+        TENANT_LIST_SPEC.push_conditions(&mut select, query, false)?;
+        TENANT_LIST_SPEC.push_order_and_page(&mut select, query);
+        let tenants = select
+            .build_query_as::<Tenant>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut count = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM tenants");
+        TENANT_LIST_SPEC.push_conditions(&mut count, query, false)?;
+        let (total,): (i64,) = count.build_query_as().fetch_one(&self.pool).await?;
+        Ok((tenants, total))
+    }
+
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateTenantInput,
+        expected_version: Option<i32>,
+    ) -> Result<Tenant> {
         let existing = self
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+        AppError::check_version("tenant", expected_version, existing.version)?;
 
         let name = input.name.as_ref().unwrap_or(&existing.name);
         let logo_url = input.logo_url.as_ref().or(existing.logo_url.as_ref());
@@ -166,11 +236,13 @@ impl TenantRepository for TenantRepositoryImpl {
 
         let status_str = status.to_string();
 
-        sqlx::query(
+        // Compare-and-swap on the version read above so concurrent edits
+        // can't silently overwrite each other
+        let result = sqlx::query(
             r#"
             UPDATE tenants
-            SET name = ?, logo_url = ?, settings = ?, status = ?, updated_at = NOW()
-            WHERE id = ?
+            SET name = ?, logo_url = ?, settings = ?, status = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(name)
@@ -178,9 +250,17 @@ impl TenantRepository for TenantRepositoryImpl {
         .bind(&settings_json)
         .bind(status_str)
         .bind(id)
+        .bind(existing.version)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::VersionConflict {
+                resource: "tenant".to_string(),
+                current_version: self.find_by_id(id).await?.map(|t| t.version),
+            });
+        }
+
         self.find_by_id(id)
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update tenant")))
@@ -210,7 +290,7 @@ impl TenantRepository for TenantRepositoryImpl {
         let result = sqlx::query(
             r#"
             UPDATE tenants
-            SET password_policy = ?, updated_at = NOW()
+            SET password_policy = ?, version = version + 1, updated_at = NOW()
             WHERE id = ?
             "#,
         )
@@ -227,6 +307,133 @@ impl TenantRepository for TenantRepositoryImpl {
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update tenant")))
     }
+
+    async fn set_data_region(&self, id: StringUuid, data_region: Option<String>) -> Result<Tenant> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tenants
+            SET data_region = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(&data_region)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("Tenant {} not found", id)));
+        }
+
+        self.find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update tenant")))
+    }
+
+    async fn find_slug_redirect(&self, slug: &str) -> Result<Option<TenantSlugRedirect>> {
+        let redirect = sqlx::query_as::<_, TenantSlugRedirect>(
+            r#"
+            SELECT old_slug, tenant_id, expires_at, created_at
+            FROM tenant_slug_redirects
+            WHERE old_slug = ? AND expires_at > NOW()
+            "#,
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(redirect)
+    }
+
+    async fn rename(
+        &self,
+        id: StringUuid,
+        input: &RenameTenantInput,
+        redirect_expires_at: DateTime<Utc>,
+    ) -> Result<(Tenant, Option<TenantSlugRedirect>)> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, Tenant>(&format!(
+            "SELECT {} FROM tenants WHERE id = ? FOR UPDATE",
+            TENANT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+
+        let name = input.name.as_deref().unwrap_or(&existing.name);
+        let new_slug = input.slug.as_deref().filter(|slug| *slug != existing.slug);
+
+        if let Some(new_slug) = new_slug {
+            // Locking the redirect row serializes concurrent renames onto
+            // the same retired slug
+            let holder: Option<(StringUuid,)> = sqlx::query_as(
+                "SELECT tenant_id FROM tenant_slug_redirects WHERE old_slug = ? AND expires_at > NOW() FOR UPDATE",
+            )
+            .bind(new_slug)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if holder.is_some_and(|(tenant_id,)| tenant_id != id) {
+                return Err(AppError::Conflict(format!(
+                    "Slug '{}' is reserved by a recently renamed tenant",
+                    new_slug
+                )));
+            }
+            // Taking back an own retired slug (or an expired one) ends its redirect
+            sqlx::query("DELETE FROM tenant_slug_redirects WHERE old_slug = ?")
+                .bind(new_slug)
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_slug_redirects (old_slug, tenant_id, expires_at, created_at)
+                VALUES (?, ?, ?, NOW())
+                ON DUPLICATE KEY UPDATE tenant_id = VALUES(tenant_id),
+                    expires_at = VALUES(expires_at), created_at = NOW()
+                "#,
+            )
+            .bind(&existing.slug)
+            .bind(id)
+            .bind(redirect_expires_at)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE enterprise_sso_connectors SET provider_alias = CONCAT(?, '--', alias) WHERE tenant_id = ?",
+            )
+            .bind(new_slug)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // The unique index on slug rejects a slug taken in the meantime
+        sqlx::query(
+            r#"
+            UPDATE tenants
+            SET name = ?, slug = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(name)
+        .bind(new_slug.unwrap_or(&existing.slug))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let tenant = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to rename tenant")))?;
+        let redirect = match new_slug {
+            Some(_) => self.find_slug_redirect(&existing.slug).await?,
+            None => None,
+        };
+        Ok((tenant, redirect))
+    }
 }
 
 #[cfg(test)]
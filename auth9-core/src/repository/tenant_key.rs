@@ -0,0 +1,187 @@
+//! Tenant data key repository
+//!
+//! Webhook secrets are the only reversible secrets stored per tenant, so the
+//! re-encryption queries work on the `webhooks` table directly.
+
+use crate::crypto::tenant_keys::INITIAL_KEY_VERSION;
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::tenant_key::{StoredSecret, TenantKeyVersion};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait TenantKeyRepository: Send + Sync {
+    /// Current key version of a tenant; `None` if never rotated
+    async fn find(&self, tenant_id: StringUuid) -> Result<Option<TenantKeyVersion>>;
+    /// Start using the next key version of a tenant
+    async fn bump_version(&self, tenant_id: StringUuid) -> Result<TenantKeyVersion>;
+    /// Stored webhook secrets of a tenant, or of every tenant and the
+    /// platform with `None`
+    async fn stored_secrets(&self, tenant_id: Option<StringUuid>) -> Result<Vec<StoredSecret>>;
+    /// Up to `limit` stored secrets after `after` in ID order, for batched
+    /// re-encryption
+    async fn stored_secrets_after(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+        limit: u32,
+    ) -> Result<Vec<StoredSecret>>;
+    /// Number of stored secrets after `after` in ID order
+    async fn count_secrets_after(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+    ) -> Result<u64>;
+    /// Replace a stored secret unless it changed since it was read; returns
+    /// whether it was replaced
+    async fn replace_secret(&self, id: StringUuid, expected: &str, secret: &str) -> Result<bool>;
+}
+
+pub struct TenantKeyRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl TenantKeyRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Key version new secrets of `tenant_id` are sealed with; platform secrets
+/// (`None`) always use the initial version
+pub(crate) async fn current_key_version(
+    pool: &MySqlPool,
+    tenant_id: Option<StringUuid>,
+) -> Result<u32> {
+    let Some(tenant_id) = tenant_id else {
+        return Ok(INITIAL_KEY_VERSION);
+    };
+    let version: Option<u32> =
+        sqlx::query_scalar("SELECT key_version FROM tenant_encryption_keys WHERE tenant_id = ?")
+            .bind(tenant_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(version.unwrap_or(INITIAL_KEY_VERSION))
+}
+
+#[async_trait]
+impl TenantKeyRepository for TenantKeyRepositoryImpl {
+    async fn find(&self, tenant_id: StringUuid) -> Result<Option<TenantKeyVersion>> {
+        let key = sqlx::query_as::<_, TenantKeyVersion>(
+            r#"
+            SELECT tenant_id, key_version, rotated_at
+            FROM tenant_encryption_keys
+            WHERE tenant_id = ?
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(key)
+    }
+
+    async fn bump_version(&self, tenant_id: StringUuid) -> Result<TenantKeyVersion> {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_encryption_keys (tenant_id, key_version, rotated_at)
+            VALUES (?, ?, NOW())
+            ON DUPLICATE KEY UPDATE key_version = key_version + 1, rotated_at = NOW()
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(INITIAL_KEY_VERSION + 1)
+        .execute(&self.pool)
+        .await?;
+
+        self.find(tenant_id)
+            .await?
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to rotate tenant key")))
+    }
+
+    async fn stored_secrets(&self, tenant_id: Option<StringUuid>) -> Result<Vec<StoredSecret>> {
+        let secrets = match tenant_id {
+            Some(tenant_id) => {
+                sqlx::query_as::<_, StoredSecret>(
+                    r#"
+                    SELECT id, tenant_id, secret
+                    FROM webhooks
+                    WHERE tenant_id = ? AND secret IS NOT NULL
+                    "#,
+                )
+                .bind(tenant_id)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, StoredSecret>(
+                    "SELECT id, tenant_id, secret FROM webhooks WHERE secret IS NOT NULL",
+                )
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(secrets)
+    }
+
+    async fn stored_secrets_after(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+        limit: u32,
+    ) -> Result<Vec<StoredSecret>> {
+        let secrets = sqlx::query_as::<_, StoredSecret>(
+            r#"
+            SELECT id, tenant_id, secret
+            FROM webhooks
+            WHERE secret IS NOT NULL
+              AND (? IS NULL OR tenant_id = ?)
+              AND (? IS NULL OR id > ?)
+            ORDER BY id
+            LIMIT ?
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(tenant_id)
+        .bind(after)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(secrets)
+    }
+
+    async fn count_secrets_after(
+        &self,
+        tenant_id: Option<StringUuid>,
+        after: Option<StringUuid>,
+    ) -> Result<u64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM webhooks
+            WHERE secret IS NOT NULL
+              AND (? IS NULL OR tenant_id = ?)
+              AND (? IS NULL OR id > ?)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(tenant_id)
+        .bind(after)
+        .bind(after)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count.max(0) as u64)
+    }
+
+    async fn replace_secret(&self, id: StringUuid, expected: &str, secret: &str) -> Result<bool> {
+        let result = sqlx::query("UPDATE webhooks SET secret = ? WHERE id = ? AND secret = ?")
+            .bind(secret)
+            .bind(id)
+            .bind(expected)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
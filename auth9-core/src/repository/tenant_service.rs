@@ -1,7 +1,7 @@
 //! Tenant-Service association repository
 
 use crate::error::Result;
-use crate::models::common::StringUuid;
+use crate::models::common::{ServiceId, StringUuid, TenantId};
 use crate::models::tenant::ServiceWithStatus;
 use async_trait::async_trait;
 use sqlx::MySqlPool;
@@ -18,8 +18,8 @@ pub trait TenantServiceRepository: Send + Sync {
     /// Enable or disable a service for a tenant
     async fn toggle_service(
         &self,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
+        tenant_id: TenantId,
+        service_id: ServiceId,
         enabled: bool,
     ) -> Result<()>;
 
@@ -27,11 +27,7 @@ pub trait TenantServiceRepository: Send + Sync {
     async fn get_enabled_services(&self, tenant_id: StringUuid) -> Result<Vec<ServiceWithStatus>>;
 
     /// Check if a service is enabled for a tenant
-    async fn is_service_enabled(
-        &self,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
-    ) -> Result<bool>;
+    async fn is_service_enabled(&self, tenant_id: TenantId, service_id: ServiceId) -> Result<bool>;
 }
 
 pub struct TenantServiceRepositoryImpl {
@@ -74,8 +70,8 @@ impl TenantServiceRepository for TenantServiceRepositoryImpl {
 
     async fn toggle_service(
         &self,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
+        tenant_id: TenantId,
+        service_id: ServiceId,
         enabled: bool,
     ) -> Result<()> {
         // Use INSERT ... ON DUPLICATE KEY UPDATE for upsert
@@ -117,11 +113,7 @@ impl TenantServiceRepository for TenantServiceRepositoryImpl {
         Ok(services)
     }
 
-    async fn is_service_enabled(
-        &self,
-        tenant_id: StringUuid,
-        service_id: StringUuid,
-    ) -> Result<bool> {
+    async fn is_service_enabled(&self, tenant_id: TenantId, service_id: ServiceId) -> Result<bool> {
         let row: Option<(bool,)> = sqlx::query_as(
             r#"
             SELECT enabled FROM tenant_services
@@ -158,8 +150,8 @@ mod tests {
     #[tokio::test]
     async fn test_mock_toggle_service() {
         let mut mock = MockTenantServiceRepository::new();
-        let tenant_id = StringUuid::new_v4();
-        let service_id = StringUuid::new_v4();
+        let tenant_id = TenantId::new_v4();
+        let service_id = ServiceId::new_v4();
 
         mock.expect_toggle_service()
             .with(eq(tenant_id), eq(service_id), eq(true))
@@ -172,8 +164,8 @@ mod tests {
     #[tokio::test]
     async fn test_mock_is_service_enabled() {
         let mut mock = MockTenantServiceRepository::new();
-        let tenant_id = StringUuid::new_v4();
-        let service_id = StringUuid::new_v4();
+        let tenant_id = TenantId::new_v4();
+        let service_id = ServiceId::new_v4();
 
         mock.expect_is_service_enabled()
             .with(eq(tenant_id), eq(service_id))
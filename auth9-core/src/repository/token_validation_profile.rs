@@ -0,0 +1,90 @@
+//! Service token validation profile assignment repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::token_validation::TokenValidationAssignment;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait TokenValidationProfileRepository: Send + Sync {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<TokenValidationAssignment>>;
+    /// Profile name assigned to the service owning an OAuth client
+    async fn find_profile_name_by_client_id(&self, client_id: &str) -> Result<Option<String>>;
+    async fn upsert(&self, service_id: StringUuid, profile_name: &str) -> Result<()>;
+    /// Remove the assignment; returns whether one existed
+    async fn delete(&self, service_id: StringUuid) -> Result<bool>;
+}
+
+pub struct TokenValidationProfileRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl TokenValidationProfileRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TokenValidationProfileRepository for TokenValidationProfileRepositoryImpl {
+    async fn find_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<TokenValidationAssignment>> {
+        let assignment = sqlx::query_as::<_, TokenValidationAssignment>(
+            r#"
+            SELECT service_id, profile_name, updated_at
+            FROM service_token_validation_profiles
+            WHERE service_id = ?
+            "#,
+        )
+        .bind(service_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(assignment)
+    }
+
+    async fn find_profile_name_by_client_id(&self, client_id: &str) -> Result<Option<String>> {
+        let name: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT p.profile_name
+            FROM clients c
+            JOIN service_token_validation_profiles p ON p.service_id = c.service_id
+            WHERE c.client_id = ?
+            "#,
+        )
+        .bind(client_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(name.map(|(name,)| name))
+    }
+
+    async fn upsert(&self, service_id: StringUuid, profile_name: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO service_token_validation_profiles (service_id, profile_name)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE profile_name = VALUES(profile_name)
+            "#,
+        )
+        .bind(service_id)
+        .bind(profile_name)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, service_id: StringUuid) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM service_token_validation_profiles WHERE service_id = ?")
+                .bind(service_id)
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
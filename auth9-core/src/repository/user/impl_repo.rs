@@ -2,12 +2,81 @@
 
 use super::{UserRepository, UserRepositoryImpl};
 use crate::error::{AppError, Result};
-use crate::models::common::StringUuid;
+use crate::models::common::{StringUuid, TenantId, UserId};
 use crate::models::user::{
     AddUserToTenantInput, CreateUserInput, TenantInfo, TenantUser, TenantUserWithTenant,
-    UpdateUserInput, User,
+    UpdateUserInput, User, UserCohort, UserTimelineEntry, UserTimelineSource,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::{MySql, MySqlArguments};
+use sqlx::query::QueryAs;
+use sqlx::Row;
+
+/// Union of every timeline source for one user. Binds the user ID six times.
+/// Session rows contribute a second, `session_revoked` entry once revoked.
+const TIMELINE_UNION_SQL: &str = r#"
+    SELECT 'login' AS source, event_type AS event, created_at AS occurred_at,
+           CAST(id AS CHAR) AS source_id, NULL AS actor_id, tenant_id, ip_address,
+           JSON_OBJECT('email', email, 'device_type', device_type, 'location', location,
+                       'failure_reason', failure_reason, 'provider_alias', provider_alias,
+                       'risk_score', risk_score) AS details
+    FROM login_events
+    WHERE user_id = ?
+    UNION ALL
+    SELECT 'audit', action, created_at, CAST(id AS CHAR), actor_id, NULL, ip_address,
+           JSON_OBJECT('resource_type', resource_type, 'resource_id', resource_id)
+    FROM audit_logs
+    WHERE actor_id = ? OR (resource_type = 'user' AND resource_id = ?)
+    UNION ALL
+    SELECT 'session', 'session_created', created_at, CAST(id AS CHAR), NULL, NULL, ip_address,
+           JSON_OBJECT('device_type', device_type, 'device_name', device_name,
+                       'location', location)
+    FROM sessions
+    WHERE user_id = ?
+    UNION ALL
+    SELECT 'session', 'session_revoked', revoked_at, CAST(id AS CHAR), NULL, NULL, ip_address,
+           JSON_OBJECT('device_type', device_type, 'device_name', device_name,
+                       'location', location)
+    FROM sessions
+    WHERE user_id = ? AND revoked_at IS NOT NULL
+    UNION ALL
+    SELECT 'role_grant', 'role_granted', utr.granted_at, CAST(utr.id AS CHAR), utr.granted_by,
+           tu.tenant_id, NULL,
+           JSON_OBJECT('role_id', r.id, 'role_name', r.name, 'service_id', r.service_id)
+    FROM user_tenant_roles utr
+    INNER JOIN tenant_users tu ON utr.tenant_user_id = tu.id
+    INNER JOIN roles r ON utr.role_id = r.id
+    WHERE tu.user_id = ?
+"#;
+
+/// Extra `WHERE` clause selecting a cohort of a tenant's members
+/// (`users u` joined with `tenant_users tu`); bind with [`bind_cohort`]
+fn cohort_filter(cohort: &UserCohort) -> &'static str {
+    match cohort {
+        UserCohort::All => "",
+        UserCohort::Role { .. } => " AND tu.role_in_tenant = ?",
+        UserCohort::LastLoginBefore { .. } => {
+            r#" AND NOT EXISTS (
+                SELECT 1 FROM login_events le
+                WHERE le.user_id = u.id
+                  AND le.event_type IN ('success', 'social', 'federation_success')
+                  AND le.created_at >= ?
+            )"#
+        }
+    }
+}
+
+fn bind_cohort<'q, O>(
+    query: QueryAs<'q, MySql, O, MySqlArguments>,
+    cohort: &'q UserCohort,
+) -> QueryAs<'q, MySql, O, MySqlArguments> {
+    match cohort {
+        UserCohort::All => query,
+        UserCohort::Role { role } => query.bind(role),
+        UserCohort::LastLoginBefore { before } => query.bind(*before),
+    }
+}
 
 #[async_trait]
 impl UserRepository for UserRepositoryImpl {
@@ -38,7 +107,8 @@ impl UserRepository for UserRepositoryImpl {
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             WHERE id = ?
             "#,
@@ -55,7 +125,8 @@ impl UserRepository for UserRepositoryImpl {
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             WHERE email = ?
             "#,
@@ -72,7 +143,8 @@ impl UserRepository for UserRepositoryImpl {
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             WHERE identity_subject = ?
             "#,
@@ -89,7 +161,8 @@ impl UserRepository for UserRepositoryImpl {
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             ORDER BY created_at DESC
             LIMIT ? OFFSET ?
@@ -116,7 +189,8 @@ impl UserRepository for UserRepositoryImpl {
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             WHERE email LIKE ? OR display_name LIKE ?
             ORDER BY created_at DESC
@@ -144,11 +218,17 @@ impl UserRepository for UserRepositoryImpl {
         Ok(row.0)
     }
 
-    async fn update(&self, id: StringUuid, input: &UpdateUserInput) -> Result<User> {
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateUserInput,
+        expected_version: Option<i32>,
+    ) -> Result<User> {
         let existing = self
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        AppError::check_version("user", expected_version, existing.version)?;
 
         let display_name = input
             .display_name
@@ -156,19 +236,27 @@ impl UserRepository for UserRepositoryImpl {
             .or(existing.display_name.as_ref());
         let avatar_url = input.avatar_url.as_ref().or(existing.avatar_url.as_ref());
 
-        sqlx::query(
+        let result = sqlx::query(
             r#"
             UPDATE users
-            SET display_name = ?, avatar_url = ?, updated_at = NOW()
-            WHERE id = ?
+            SET display_name = ?, avatar_url = ?, version = version + 1, updated_at = NOW()
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(display_name)
         .bind(avatar_url)
         .bind(id)
+        .bind(existing.version)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::VersionConflict {
+                resource: "user".to_string(),
+                current_version: self.find_by_id(id).await?.map(|u| u.version),
+            });
+        }
+
         self.find_by_id(id)
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update user")))
@@ -178,7 +266,7 @@ impl UserRepository for UserRepositoryImpl {
         let result = sqlx::query(
             r#"
             UPDATE users
-            SET mfa_enabled = ?, updated_at = NOW()
+            SET mfa_enabled = ?, version = version + 1, updated_at = NOW()
             WHERE id = ?
             "#,
         )
@@ -200,7 +288,7 @@ impl UserRepository for UserRepositoryImpl {
         let result = sqlx::query(
             r#"
             UPDATE users
-            SET email_otp_enabled = ?, updated_at = NOW()
+            SET email_otp_enabled = ?, version = version + 1, updated_at = NOW()
             WHERE id = ?
             "#,
         )
@@ -263,8 +351,8 @@ impl UserRepository for UserRepositoryImpl {
 
     async fn update_role_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
         role: &str,
     ) -> Result<TenantUser> {
         let result = sqlx::query(
@@ -301,7 +389,7 @@ impl UserRepository for UserRepositoryImpl {
         Ok(tenant_user)
     }
 
-    async fn remove_from_tenant(&self, user_id: StringUuid, tenant_id: StringUuid) -> Result<()> {
+    async fn remove_from_tenant(&self, user_id: UserId, tenant_id: TenantId) -> Result<()> {
         let result = sqlx::query("DELETE FROM tenant_users WHERE user_id = ? AND tenant_id = ?")
             .bind(user_id)
             .bind(tenant_id)
@@ -328,7 +416,7 @@ impl UserRepository for UserRepositoryImpl {
             SELECT u.id, u.identity_subject,
                    u.scim_external_id, u.scim_provisioned_by, u.email, u.display_name,
                    u.avatar_url, u.mfa_enabled, u.email_otp_enabled, u.password_changed_at, u.locked_until,
-                   u.created_at, u.updated_at
+                   u.locale, u.version, u.created_at, u.updated_at
             FROM users u
             INNER JOIN tenant_users tu ON u.id = tu.user_id
             WHERE tu.tenant_id = ?
@@ -358,7 +446,7 @@ impl UserRepository for UserRepositoryImpl {
             SELECT u.id, u.identity_subject,
                    u.scim_external_id, u.scim_provisioned_by, u.email, u.display_name,
                    u.avatar_url, u.mfa_enabled, u.email_otp_enabled, u.password_changed_at, u.locked_until,
-                   u.created_at, u.updated_at
+                   u.locale, u.version, u.created_at, u.updated_at
             FROM users u
             INNER JOIN tenant_users tu ON u.id = tu.user_id
             WHERE tu.tenant_id = ? AND (u.email LIKE ? OR u.display_name LIKE ?)
@@ -415,6 +503,52 @@ impl UserRepository for UserRepositoryImpl {
         Ok(tenant_users)
     }
 
+    async fn find_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>> {
+        let sql = format!(
+            r#"
+            SELECT u.id, u.identity_subject,
+                   u.scim_external_id, u.scim_provisioned_by, u.email, u.display_name,
+                   u.avatar_url, u.mfa_enabled, u.email_otp_enabled, u.password_changed_at, u.locked_until,
+                   u.locale, u.version, u.created_at, u.updated_at
+            FROM users u
+            INNER JOIN tenant_users tu ON u.id = tu.user_id
+            WHERE tu.tenant_id = ?{}
+            ORDER BY u.id
+            LIMIT ? OFFSET ?
+            "#,
+            cohort_filter(cohort)
+        );
+        let query = sqlx::query_as::<_, User>(&sql).bind(tenant_id);
+        let users = bind_cohort(query, cohort)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(users)
+    }
+
+    async fn count_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+    ) -> Result<i64> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM users u INNER JOIN tenant_users tu ON u.id = tu.user_id WHERE tu.tenant_id = ?{}",
+            cohort_filter(cohort)
+        );
+        let query = sqlx::query_as::<_, (i64,)>(&sql).bind(tenant_id);
+        let row = bind_cohort(query, cohort).fetch_one(&self.pool).await?;
+
+        Ok(row.0)
+    }
+
     async fn find_user_tenants_with_tenant(
         &self,
         user_id: StringUuid,
@@ -525,7 +659,7 @@ impl UserRepository for UserRepositoryImpl {
 
     async fn update_password_changed_at(&self, id: StringUuid) -> Result<()> {
         let result = sqlx::query(
-            "UPDATE users SET password_changed_at = NOW(), updated_at = NOW() WHERE id = ?",
+            "UPDATE users SET password_changed_at = NOW(), version = version + 1, updated_at = NOW() WHERE id = ?",
         )
         .bind(id)
         .execute(&self.pool)
@@ -543,7 +677,7 @@ impl UserRepository for UserRepositoryImpl {
         locked_until: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<()> {
         let result =
-            sqlx::query("UPDATE users SET locked_until = ?, updated_at = NOW() WHERE id = ?")
+            sqlx::query("UPDATE users SET locked_until = ?, version = version + 1, updated_at = NOW() WHERE id = ?")
                 .bind(locked_until)
                 .bind(id)
                 .execute(&self.pool)
@@ -555,12 +689,28 @@ impl UserRepository for UserRepositoryImpl {
         Ok(())
     }
 
+    async fn update_locale(&self, id: StringUuid, locale: Option<String>) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE users SET locale = ?, version = version + 1, updated_at = NOW() WHERE id = ?",
+        )
+        .bind(locale)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!("User {} not found", id)));
+        }
+        Ok(())
+    }
+
     async fn find_by_scim_external_id(&self, scim_external_id: String) -> Result<Option<User>> {
         let user = sqlx::query_as::<_, User>(
             r#"
             SELECT id, identity_subject,
                    scim_external_id, scim_provisioned_by, email, display_name, avatar_url,
-                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, created_at, updated_at
+                   mfa_enabled, email_otp_enabled, password_changed_at, locked_until, locale,
+                   version, created_at, updated_at
             FROM users
             WHERE scim_external_id = ?
             "#,
@@ -579,7 +729,7 @@ impl UserRepository for UserRepositoryImpl {
         scim_provisioned_by: Option<StringUuid>,
     ) -> Result<()> {
         let result = sqlx::query(
-            "UPDATE users SET scim_external_id = ?, scim_provisioned_by = ?, updated_at = NOW() WHERE id = ?",
+            "UPDATE users SET scim_external_id = ?, scim_provisioned_by = ?, version = version + 1, updated_at = NOW() WHERE id = ?",
         )
         .bind(&scim_external_id)
         .bind(scim_provisioned_by)
@@ -592,4 +742,52 @@ impl UserRepository for UserRepositoryImpl {
         }
         Ok(())
     }
+
+    async fn find_timeline(
+        &self,
+        user_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<UserTimelineEntry>> {
+        let sql = format!(
+            "SELECT * FROM ({}) AS timeline \
+             ORDER BY occurred_at DESC, source, source_id DESC LIMIT ? OFFSET ?",
+            TIMELINE_UNION_SQL
+        );
+        let mut query = sqlx::query(&sql);
+        for _ in 0..6 {
+            query = query.bind(user_id);
+        }
+        let rows = query.bind(limit).bind(offset).fetch_all(&self.pool).await?;
+
+        rows.iter()
+            .map(|row| -> Result<UserTimelineEntry> {
+                let source: String = row.try_get("source")?;
+                let details: Option<sqlx::types::Json<serde_json::Value>> =
+                    row.try_get("details")?;
+                Ok(UserTimelineEntry {
+                    source: source
+                        .parse::<UserTimelineSource>()
+                        .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?,
+                    event: row.try_get("event")?,
+                    occurred_at: row.try_get::<DateTime<Utc>, _>("occurred_at")?,
+                    source_id: row.try_get("source_id")?,
+                    actor_id: row.try_get("actor_id")?,
+                    tenant_id: row.try_get("tenant_id")?,
+                    ip_address: row.try_get("ip_address")?,
+                    details: details.map(|d| d.0).unwrap_or(serde_json::Value::Null),
+                })
+            })
+            .collect()
+    }
+
+    async fn count_timeline(&self, user_id: StringUuid) -> Result<i64> {
+        let sql = format!("SELECT COUNT(*) FROM ({}) AS timeline", TIMELINE_UNION_SQL);
+        let mut query = sqlx::query_as::<_, (i64,)>(&sql);
+        for _ in 0..6 {
+            query = query.bind(user_id);
+        }
+        let row = query.fetch_one(&self.pool).await?;
+        Ok(row.0)
+    }
 }
@@ -1,9 +1,10 @@
 //! User repository
 
 use crate::error::Result;
-use crate::models::common::StringUuid;
+use crate::models::common::{StringUuid, TenantId, UserId};
 use crate::models::user::{
     AddUserToTenantInput, CreateUserInput, TenantUser, TenantUserWithTenant, UpdateUserInput, User,
+    UserCohort, UserTimelineEntry,
 };
 use async_trait::async_trait;
 use sqlx::MySqlPool;
@@ -24,7 +25,14 @@ pub trait UserRepository: Send + Sync {
     async fn count(&self) -> Result<i64>;
     async fn search(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<User>>;
     async fn search_count(&self, query: &str) -> Result<i64>;
-    async fn update(&self, id: StringUuid, input: &UpdateUserInput) -> Result<User>;
+    /// Apply `input`; with `expected_version` set the write only succeeds if
+    /// the stored version still matches.
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateUserInput,
+        expected_version: Option<i32>,
+    ) -> Result<User>;
     async fn update_mfa_enabled(&self, id: StringUuid, enabled: bool) -> Result<User>;
     async fn update_email_otp_enabled(&self, id: StringUuid, enabled: bool) -> Result<User>;
     async fn delete(&self, id: StringUuid) -> Result<()>;
@@ -33,11 +41,11 @@ pub trait UserRepository: Send + Sync {
     async fn add_to_tenant(&self, input: &AddUserToTenantInput) -> Result<TenantUser>;
     async fn update_role_in_tenant(
         &self,
-        user_id: StringUuid,
-        tenant_id: StringUuid,
+        user_id: UserId,
+        tenant_id: TenantId,
         role: &str,
     ) -> Result<TenantUser>;
-    async fn remove_from_tenant(&self, user_id: StringUuid, tenant_id: StringUuid) -> Result<()>;
+    async fn remove_from_tenant(&self, user_id: UserId, tenant_id: TenantId) -> Result<()>;
     async fn find_tenant_users(
         &self,
         tenant_id: StringUuid,
@@ -55,6 +63,21 @@ pub trait UserRepository: Send + Sync {
     async fn search_tenant_users_count(&self, tenant_id: StringUuid, query: &str) -> Result<i64>;
     async fn find_user_tenants(&self, user_id: StringUuid) -> Result<Vec<TenantUser>>;
 
+    /// Members of a tenant matching `cohort`, in stable ID order so callers
+    /// can page through them while acting on each user
+    async fn find_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>>;
+    async fn count_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+    ) -> Result<i64>;
+
     /// Find user's tenants with tenant data (for API responses)
     async fn find_user_tenants_with_tenant(
         &self,
@@ -86,6 +109,9 @@ pub trait UserRepository: Send + Sync {
         locked_until: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<()>;
 
+    /// Update the preferred locale (None to clear)
+    async fn update_locale(&self, id: StringUuid, locale: Option<String>) -> Result<()>;
+
     // SCIM provisioning methods
 
     /// Find user by SCIM external ID
@@ -98,6 +124,20 @@ pub trait UserRepository: Send + Sync {
         scim_external_id: Option<String>,
         scim_provisioned_by: Option<StringUuid>,
     ) -> Result<()>;
+
+    // Activity timeline
+
+    /// Login events, audit entries (as actor or subject), session changes and
+    /// role grants of a user, merged newest first
+    async fn find_timeline(
+        &self,
+        user_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<UserTimelineEntry>>;
+
+    /// Total number of timeline entries of a user
+    async fn count_timeline(&self, user_id: StringUuid) -> Result<i64>;
 }
 
 pub struct UserRepositoryImpl {
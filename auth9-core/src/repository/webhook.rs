@@ -1,5 +1,10 @@
 //! Webhook repository
+//!
+//! With a [`TenantKeyring`] secrets are sealed with the owning tenant's data
+//! key on write and opened on read, so callers only see plaintext.
 
+use super::tenant_key::current_key_version;
+use crate::crypto::tenant_keys::{sealed_version, TenantKeyring};
 use crate::error::{AppError, Result};
 use crate::models::analytics::{CreateWebhookInput, UpdateWebhookInput, Webhook};
 use crate::models::common::StringUuid;
@@ -9,11 +14,25 @@ use sqlx::MySqlPool;
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait WebhookRepository: Send + Sync {
-    async fn create(&self, tenant_id: StringUuid, input: &CreateWebhookInput) -> Result<Webhook>;
+    /// Create a webhook of `tenant_id`, or a platform webhook with `None`
+    async fn create(
+        &self,
+        tenant_id: Option<StringUuid>,
+        input: &CreateWebhookInput,
+    ) -> Result<Webhook>;
     async fn find_by_id(&self, id: StringUuid) -> Result<Option<Webhook>>;
     async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<Webhook>>;
+    /// Webhooks not bound to any tenant
+    async fn list_platform(&self) -> Result<Vec<Webhook>>;
     async fn list_enabled_for_event(&self, event: &str) -> Result<Vec<Webhook>>;
-    async fn update(&self, id: StringUuid, input: &UpdateWebhookInput) -> Result<Webhook>;
+    /// Update a webhook; `expected_version` (from `If-Match`) rejects the
+    /// write if the webhook has changed since the caller read it
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateWebhookInput,
+        expected_version: Option<i32>,
+    ) -> Result<Webhook>;
     async fn update_triggered(&self, id: StringUuid, success: bool) -> Result<()>;
     async fn delete(&self, id: StringUuid) -> Result<()>;
 
@@ -23,34 +42,97 @@ pub trait WebhookRepository: Send + Sync {
 
 pub struct WebhookRepositoryImpl {
     pool: MySqlPool,
+    /// `None` stores secrets in plaintext
+    keyring: Option<TenantKeyring>,
 }
 
 impl WebhookRepositoryImpl {
     pub fn new(pool: MySqlPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            keyring: None,
+        }
+    }
+
+    /// Seal secrets with per-tenant data keys
+    pub fn with_keyring(mut self, keyring: Option<TenantKeyring>) -> Self {
+        self.keyring = keyring;
+        self
+    }
+
+    /// Value to store for a secret of `tenant_id`
+    async fn seal_secret(
+        &self,
+        tenant_id: Option<StringUuid>,
+        secret: Option<&String>,
+    ) -> Result<Option<String>> {
+        let (Some(keyring), Some(secret)) = (&self.keyring, secret) else {
+            return Ok(secret.cloned());
+        };
+        let version = current_key_version(&self.pool, tenant_id).await?;
+        keyring
+            .seal(tenant_id.map(Into::into), version, secret)
+            .map(Some)
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to encrypt webhook secret: {}", e))
+            })
+    }
+
+    /// Replace the stored secret of a webhook read from the database with
+    /// its plaintext
+    fn open_secret(&self, mut webhook: Webhook) -> Result<Webhook> {
+        let Some(stored) = webhook.secret.take() else {
+            return Ok(webhook);
+        };
+        let secret = match &self.keyring {
+            Some(keyring) => keyring
+                .open(webhook.tenant_id.map(Into::into), &stored)
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!(
+                        "Failed to decrypt secret of webhook {}: {}",
+                        webhook.id,
+                        e
+                    ))
+                })?,
+            None if sealed_version(&stored).is_some() => {
+                return Err(AppError::Internal(anyhow::anyhow!(
+                    "Secret of webhook {} is encrypted but SETTINGS_ENCRYPTION_KEY is not configured",
+                    webhook.id
+                )))
+            }
+            None => stored,
+        };
+        webhook.secret = Some(secret);
+        Ok(webhook)
     }
 }
 
 #[async_trait]
 impl WebhookRepository for WebhookRepositoryImpl {
-    async fn create(&self, tenant_id: StringUuid, input: &CreateWebhookInput) -> Result<Webhook> {
+    async fn create(
+        &self,
+        tenant_id: Option<StringUuid>,
+        input: &CreateWebhookInput,
+    ) -> Result<Webhook> {
         let id = StringUuid::new_v4();
         let events_json =
             serde_json::to_string(&input.events).map_err(|e| AppError::Internal(e.into()))?;
+        let secret = self.seal_secret(tenant_id, input.secret.as_ref()).await?;
 
         sqlx::query(
             r#"
-            INSERT INTO webhooks (id, tenant_id, name, url, secret, events, enabled,
-                                  created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
+            INSERT INTO webhooks (id, tenant_id, name, url, secret, events, filter_expression,
+                                  enabled, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
             "#,
         )
         .bind(id)
         .bind(tenant_id)
         .bind(&input.name)
         .bind(&input.url)
-        .bind(&input.secret)
+        .bind(&secret)
         .bind(&events_json)
+        .bind(&input.filter_expression)
         .bind(input.enabled)
         .execute(&self.pool)
         .await?;
@@ -63,8 +145,8 @@ impl WebhookRepository for WebhookRepositoryImpl {
     async fn find_by_id(&self, id: StringUuid) -> Result<Option<Webhook>> {
         let webhook = sqlx::query_as::<_, Webhook>(
             r#"
-            SELECT id, tenant_id, name, url, secret, events, enabled,
-                   last_triggered_at, failure_count, created_at, updated_at
+            SELECT id, tenant_id, name, url, secret, events, filter_expression, enabled,
+                   last_triggered_at, failure_count, version, created_at, updated_at
             FROM webhooks
             WHERE id = ?
             "#,
@@ -73,14 +155,14 @@ impl WebhookRepository for WebhookRepositoryImpl {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(webhook)
+        webhook.map(|w| self.open_secret(w)).transpose()
     }
 
     async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<Webhook>> {
         let webhooks = sqlx::query_as::<_, Webhook>(
             r#"
-            SELECT id, tenant_id, name, url, secret, events, enabled,
-                   last_triggered_at, failure_count, created_at, updated_at
+            SELECT id, tenant_id, name, url, secret, events, filter_expression, enabled,
+                   last_triggered_at, failure_count, version, created_at, updated_at
             FROM webhooks
             WHERE tenant_id = ?
             ORDER BY created_at DESC
@@ -90,15 +172,31 @@ impl WebhookRepository for WebhookRepositoryImpl {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(webhooks)
+        webhooks.into_iter().map(|w| self.open_secret(w)).collect()
+    }
+
+    async fn list_platform(&self) -> Result<Vec<Webhook>> {
+        let webhooks = sqlx::query_as::<_, Webhook>(
+            r#"
+            SELECT id, tenant_id, name, url, secret, events, filter_expression, enabled,
+                   last_triggered_at, failure_count, version, created_at, updated_at
+            FROM webhooks
+            WHERE tenant_id IS NULL
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        webhooks.into_iter().map(|w| self.open_secret(w)).collect()
     }
 
     async fn list_enabled_for_event(&self, event: &str) -> Result<Vec<Webhook>> {
         // Use JSON_CONTAINS to find webhooks that have this event in their events array
         let webhooks = sqlx::query_as::<_, Webhook>(
             r#"
-            SELECT id, tenant_id, name, url, secret, events, enabled,
-                   last_triggered_at, failure_count, created_at, updated_at
+            SELECT id, tenant_id, name, url, secret, events, filter_expression, enabled,
+                   last_triggered_at, failure_count, version, created_at, updated_at
             FROM webhooks
             WHERE enabled = true AND JSON_CONTAINS(events, ?)
             "#,
@@ -107,40 +205,80 @@ impl WebhookRepository for WebhookRepositoryImpl {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(webhooks)
+        // A secret that cannot be decrypted only stops its own webhook
+        Ok(webhooks
+            .into_iter()
+            .filter_map(|w| match self.open_secret(w) {
+                Ok(webhook) => Some(webhook),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping webhook with unreadable secret");
+                    None
+                }
+            })
+            .collect())
     }
 
-    async fn update(&self, id: StringUuid, input: &UpdateWebhookInput) -> Result<Webhook> {
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateWebhookInput,
+        expected_version: Option<i32>,
+    ) -> Result<Webhook> {
         let existing = self
             .find_by_id(id)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("Webhook {} not found", id)))?;
+        AppError::check_version("webhook", expected_version, existing.version)?;
 
         let name = input.name.as_ref().unwrap_or(&existing.name);
         let url = input.url.as_ref().unwrap_or(&existing.url);
-        let secret = input.secret.as_ref().or(existing.secret.as_ref());
+        // Re-sealed with the tenant's current key
+        let secret = self
+            .seal_secret(
+                existing.tenant_id,
+                input.secret.as_ref().or(existing.secret.as_ref()),
+            )
+            .await?;
         let events = input.events.as_ref().unwrap_or(&existing.events);
+        // An empty filter removes it
+        let filter_expression = match &input.filter_expression {
+            Some(filter) if filter.trim().is_empty() => None,
+            Some(filter) => Some(filter),
+            None => existing.filter_expression.as_ref(),
+        };
         let enabled = input.enabled.unwrap_or(existing.enabled);
 
         let events_json =
             serde_json::to_string(&events).map_err(|e| AppError::Internal(e.into()))?;
 
-        sqlx::query(
+        // Compare-and-swap on the version read above so concurrent edits
+        // can't silently overwrite each other
+        let result = sqlx::query(
             r#"
             UPDATE webhooks
-            SET name = ?, url = ?, secret = ?, events = ?, enabled = ?, updated_at = NOW()
-            WHERE id = ?
+            SET name = ?, url = ?, secret = ?, events = ?, filter_expression = ?, enabled = ?,
+                version = version + 1, updated_at = NOW()
+            WHERE id = ? AND version = ?
             "#,
         )
         .bind(name)
         .bind(url)
-        .bind(secret)
+        .bind(&secret)
         .bind(&events_json)
+        .bind(filter_expression)
         .bind(enabled)
         .bind(id)
+        .bind(existing.version)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::VersionConflict {
+                resource: "webhook".to_string(),
+                current_version: self.find_by_id(id).await?.map(|w| w.version),
+            });
+        }
+
         self.find_by_id(id)
             .await?
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Failed to update webhook")))
@@ -189,10 +327,20 @@ impl WebhookRepository for WebhookRepositoryImpl {
             return Err(AppError::NotFound("Webhook not found".to_string()));
         }
 
+        sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
         Ok(())
     }
 
     async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE tenant_id = ?")
+            .bind(tenant_id)
+            .execute(&self.pool)
+            .await?;
+
         let result = sqlx::query("DELETE FROM webhooks WHERE tenant_id = ?")
             .bind(tenant_id)
             .execute(&self.pool)
@@ -269,12 +417,13 @@ mod tests {
             url: "https://example.com/hook".to_string(),
             secret: Some("secret123".to_string()),
             events: vec!["user.created".to_string()],
+            filter_expression: None,
             enabled: true,
         };
 
-        let webhook = mock.create(tenant_id, &input).await.unwrap();
+        let webhook = mock.create(Some(tenant_id), &input).await.unwrap();
         assert_eq!(webhook.name, "New Webhook");
-        assert_eq!(webhook.tenant_id, tenant_id);
+        assert_eq!(webhook.tenant_id, Some(tenant_id));
     }
 
     #[tokio::test]
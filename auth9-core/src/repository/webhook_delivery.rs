@@ -0,0 +1,138 @@
+//! Webhook delivery log repository
+
+use crate::error::Result;
+use crate::models::common::StringUuid;
+use crate::models::webhook_delivery::{WebhookDelivery, WebhookDeliveryFilter};
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WebhookDeliveryRepository: Send + Sync {
+    async fn create(&self, delivery: &WebhookDelivery) -> Result<()>;
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<WebhookDelivery>>;
+    async fn list_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>>;
+    async fn count_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<i64>;
+}
+
+pub struct WebhookDeliveryRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl WebhookDeliveryRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, webhook_id, tenant_id, event_type, payload, source, attempt, success,
+           status_code, response_time_ms, response_snippet, error, replay_of, created_at
+    FROM webhook_deliveries
+"#;
+
+/// `WHERE` clause for a webhook's deliveries; bind order: webhook_id,
+/// success, event_type (each optional filter only when set)
+fn filter_clause(filter: &WebhookDeliveryFilter) -> String {
+    let mut clause = String::from(" WHERE webhook_id = ?");
+    if filter.success.is_some() {
+        clause.push_str(" AND success = ?");
+    }
+    if filter.event_type.is_some() {
+        clause.push_str(" AND event_type = ?");
+    }
+    clause
+}
+
+#[async_trait]
+impl WebhookDeliveryRepository for WebhookDeliveryRepositoryImpl {
+    async fn create(&self, delivery: &WebhookDelivery) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries
+                (id, webhook_id, tenant_id, event_type, payload, source, attempt, success,
+                 status_code, response_time_ms, response_snippet, error, replay_of, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.tenant_id)
+        .bind(&delivery.event_type)
+        .bind(&delivery.payload)
+        .bind(delivery.source)
+        .bind(delivery.attempt)
+        .bind(delivery.success)
+        .bind(delivery.status_code)
+        .bind(delivery.response_time_ms)
+        .bind(&delivery.response_snippet)
+        .bind(&delivery.error)
+        .bind(delivery.replay_of)
+        .bind(delivery.created_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<WebhookDelivery>> {
+        let delivery =
+            sqlx::query_as::<_, WebhookDelivery>(&format!("{} WHERE id = ?", SELECT_COLUMNS))
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(delivery)
+    }
+
+    async fn list_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let sql = format!(
+            "{}{} ORDER BY created_at DESC LIMIT ? OFFSET ?",
+            SELECT_COLUMNS,
+            filter_clause(filter)
+        );
+        let mut query = sqlx::query_as::<_, WebhookDelivery>(&sql).bind(webhook_id);
+        if let Some(success) = filter.success {
+            query = query.bind(success);
+        }
+        if let Some(ref event_type) = filter.event_type {
+            query = query.bind(event_type);
+        }
+        let deliveries = query.bind(limit).bind(offset).fetch_all(&self.pool).await?;
+        Ok(deliveries)
+    }
+
+    async fn count_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<i64> {
+        let sql = format!(
+            "SELECT COUNT(*) FROM webhook_deliveries{}",
+            filter_clause(filter)
+        );
+        let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(webhook_id);
+        if let Some(success) = filter.success {
+            query = query.bind(success);
+        }
+        if let Some(ref event_type) = filter.event_type {
+            query = query.bind(event_type);
+        }
+        let count = query.fetch_one(&self.pool).await?;
+        Ok(count)
+    }
+}
@@ -0,0 +1,172 @@
+//! Workload identity federation rule repository
+
+use crate::error::{AppError, Result};
+use crate::models::common::StringUuid;
+use crate::models::workload_identity::WorkloadIdentityRule;
+use async_trait::async_trait;
+use sqlx::MySqlPool;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait WorkloadIdentityRepository: Send + Sync {
+    async fn create(&self, rule: &WorkloadIdentityRule) -> Result<WorkloadIdentityRule>;
+    async fn find(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<WorkloadIdentityRule>>;
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<WorkloadIdentityRule>>;
+    /// Enabled rules that issue tokens for a service
+    async fn list_enabled_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Vec<WorkloadIdentityRule>>;
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64>;
+    async fn update(&self, rule: &WorkloadIdentityRule) -> Result<WorkloadIdentityRule>;
+    async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()>;
+}
+
+pub struct WorkloadIdentityRepositoryImpl {
+    pool: MySqlPool,
+}
+
+impl WorkloadIdentityRepositoryImpl {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT id, tenant_id, service_id, name, issuer, audience, subject_pattern,
+           claim_conditions, token_ttl_secs, enabled, created_at, updated_at
+    FROM workload_identity_rules
+"#;
+
+#[async_trait]
+impl WorkloadIdentityRepository for WorkloadIdentityRepositoryImpl {
+    async fn create(&self, rule: &WorkloadIdentityRule) -> Result<WorkloadIdentityRule> {
+        sqlx::query(
+            r#"
+            INSERT INTO workload_identity_rules
+                (id, tenant_id, service_id, name, issuer, audience, subject_pattern,
+                 claim_conditions, token_ttl_secs, enabled)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(rule.id)
+        .bind(rule.tenant_id)
+        .bind(rule.service_id)
+        .bind(&rule.name)
+        .bind(&rule.issuer)
+        .bind(&rule.audience)
+        .bind(&rule.subject_pattern)
+        .bind(sqlx::types::Json(&rule.claim_conditions))
+        .bind(rule.token_ttl_secs)
+        .bind(rule.enabled)
+        .execute(&self.pool)
+        .await?;
+
+        self.find(rule.tenant_id, rule.id).await?.ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Failed to create workload identity rule"))
+        })
+    }
+
+    async fn find(
+        &self,
+        tenant_id: StringUuid,
+        id: StringUuid,
+    ) -> Result<Option<WorkloadIdentityRule>> {
+        let rule = sqlx::query_as::<_, WorkloadIdentityRule>(&format!(
+            "{} WHERE tenant_id = ? AND id = ?",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(rule)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<WorkloadIdentityRule>> {
+        let rules = sqlx::query_as::<_, WorkloadIdentityRule>(&format!(
+            "{} WHERE tenant_id = ? ORDER BY created_at ASC",
+            SELECT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rules)
+    }
+
+    async fn list_enabled_by_service(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Vec<WorkloadIdentityRule>> {
+        let rules = sqlx::query_as::<_, WorkloadIdentityRule>(&format!(
+            "{} WHERE service_id = ? AND enabled = TRUE ORDER BY created_at ASC",
+            SELECT_COLUMNS
+        ))
+        .bind(service_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rules)
+    }
+
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM workload_identity_rules WHERE tenant_id = ?")
+                .bind(tenant_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(count.0)
+    }
+
+    async fn update(&self, rule: &WorkloadIdentityRule) -> Result<WorkloadIdentityRule> {
+        let result = sqlx::query(
+            r#"
+            UPDATE workload_identity_rules
+            SET name = ?, audience = ?, subject_pattern = ?, claim_conditions = ?,
+                token_ttl_secs = ?, enabled = ?, updated_at = NOW()
+            WHERE tenant_id = ? AND id = ?
+            "#,
+        )
+        .bind(&rule.name)
+        .bind(&rule.audience)
+        .bind(&rule.subject_pattern)
+        .bind(sqlx::types::Json(&rule.claim_conditions))
+        .bind(rule.token_ttl_secs)
+        .bind(rule.enabled)
+        .bind(rule.tenant_id)
+        .bind(rule.id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Workload identity rule {} not found",
+                rule.id
+            )));
+        }
+
+        self.find(rule.tenant_id, rule.id).await?.ok_or_else(|| {
+            AppError::NotFound(format!("Workload identity rule {} not found", rule.id))
+        })
+    }
+
+    async fn delete(&self, tenant_id: StringUuid, id: StringUuid) -> Result<()> {
+        let result =
+            sqlx::query("DELETE FROM workload_identity_rules WHERE tenant_id = ? AND id = ?")
+                .bind(tenant_id)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Workload identity rule {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+}
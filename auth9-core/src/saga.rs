@@ -0,0 +1,653 @@
+//! Saga engine for operations spanning the identity engine and the database
+//!
+//! The identity engine and the database cannot share a transaction, so an
+//! operation touching both (creating a user, provisioning a tenant, deleting
+//! a service) is split into steps. After every step the saga's progress and
+//! context are persisted; when a step fails the saga either undoes the
+//! completed steps in reverse order (`Recovery::Compensate`) or stops and
+//! waits to be resumed (`Recovery::RetryForward`, for operations such as
+//! deletions that cannot be undone). A saga whose retries or compensations
+//! are exhausted, or whose process stopped while running it, is left `stuck`
+//! for an operator to resume or compensate through the platform saga API.
+//!
+//! Steps may run more than once after an interruption, so they should
+//! tolerate finding their work already done.
+
+use crate::error::{AppError, Result};
+use crate::models::saga::{SagaDirection, SagaRecord, SagaStatus, SagaStepState, SagaStepStatus};
+use crate::repository::SagaRepository;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub type StepFuture<T> = Pin<Box<dyn Future<Output = Result<T>> + Send>>;
+type Action<C> = Arc<dyn Fn(C) -> StepFuture<C> + Send + Sync>;
+type Compensation<C> = Arc<dyn Fn(C) -> StepFuture<()> + Send + Sync>;
+
+/// What a saga does when a step fails for good
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// Undo the completed steps in reverse order
+    Compensate,
+    /// Stop and wait to be resumed; for operations that cannot be undone
+    RetryForward,
+}
+
+/// How often a step (or its compensation) is attempted before giving up.
+/// Only transient errors are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Attempt once
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay before retry number `attempt` (1-based), doubling each time
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Whether an error may go away on retry: database and cache outages and
+/// identity engine failures, not validation or conflicts
+fn is_transient(error: &AppError) -> bool {
+    match error {
+        AppError::Database(e) => !matches!(
+            e,
+            sqlx::Error::Database(_) | sqlx::Error::RowNotFound | sqlx::Error::ColumnDecode { .. }
+        ),
+        AppError::Redis(_) | AppError::IdentityBackend(_) | AppError::Internal(_) => true,
+        _ => false,
+    }
+}
+
+struct Step<C> {
+    name: &'static str,
+    action: Action<C>,
+    compensation: Option<Compensation<C>>,
+    retry: RetryPolicy,
+}
+
+/// Definition of a saga over a context `C`. Each step receives the context
+/// and returns it with its results added; compensations receive the context
+/// as it was after the saga's last completed step.
+pub struct Saga<C> {
+    kind: &'static str,
+    recovery: Recovery,
+    steps: Vec<Step<C>>,
+}
+
+impl<C> Saga<C>
+where
+    C: Serialize + DeserializeOwned + Clone + Send + 'static,
+{
+    pub fn new(kind: &'static str, recovery: Recovery) -> Self {
+        Self {
+            kind,
+            recovery,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    pub fn recovery(&self) -> Recovery {
+        self.recovery
+    }
+
+    /// Append a step, retried with the default policy
+    pub fn step<F, Fut>(mut self, name: &'static str, action: F) -> Self
+    where
+        F: Fn(C) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<C>> + Send + 'static,
+    {
+        self.steps.push(Step {
+            name,
+            action: Arc::new(move |ctx: C| -> StepFuture<C> { Box::pin(action(ctx)) }),
+            compensation: None,
+            retry: RetryPolicy::default(),
+        });
+        self
+    }
+
+    /// Set how the last added step is undone
+    pub fn compensate_with<F, Fut>(mut self, compensation: F) -> Self
+    where
+        F: Fn(C) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        if let Some(step) = self.steps.last_mut() {
+            step.compensation = Some(Arc::new(move |ctx: C| -> StepFuture<()> {
+                Box::pin(compensation(ctx))
+            }));
+        }
+        self
+    }
+
+    /// Set the retry policy of the last added step
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        if let Some(step) = self.steps.last_mut() {
+            step.retry = policy;
+        }
+        self
+    }
+
+    fn step_names(&self) -> Vec<&'static str> {
+        self.steps.iter().map(|step| step.name).collect()
+    }
+}
+
+/// Runs sagas, persisting their state when a store is configured
+#[derive(Clone, Default)]
+pub struct SagaRunner {
+    store: Option<Arc<dyn SagaRepository>>,
+}
+
+impl SagaRunner {
+    pub fn new(store: Option<Arc<dyn SagaRepository>>) -> Self {
+        Self { store }
+    }
+
+    /// Run a saga to the end and return its final context.
+    ///
+    /// With an idempotency key a saga of the same kind already completed
+    /// under the key returns its stored context instead of running again,
+    /// and one that was compensated runs again. A key whose saga is still in
+    /// progress or stuck is rejected with `Conflict`.
+    pub async fn run<C>(&self, saga: &Saga<C>, idempotency_key: Option<&str>, ctx: C) -> Result<C>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        let mut record = SagaRecord::new(
+            saga.kind,
+            idempotency_key,
+            saga.step_names(),
+            to_context(&ctx)?,
+        );
+
+        if let Some(store) = &self.store {
+            let existing = match idempotency_key {
+                Some(key) => store.find_by_key(saga.kind, key).await?,
+                None => None,
+            };
+            match existing {
+                Some(existing) if existing.status == SagaStatus::Completed => {
+                    return existing.context_as().map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Invalid saga context: {}", e))
+                    });
+                }
+                Some(existing) => {
+                    // A compensated saga left nothing behind and may run again
+                    if existing.status != SagaStatus::Compensated
+                        || !store
+                            .transition(existing.id, SagaStatus::Compensated, SagaStatus::Running)
+                            .await?
+                    {
+                        return Err(AppError::Conflict(format!(
+                            "A '{}' operation with this idempotency key is {} (saga {})",
+                            saga.kind,
+                            existing.status.as_str(),
+                            existing.id
+                        )));
+                    }
+                    record.id = existing.id;
+                    record.created_at = existing.created_at;
+                    store.update(&record).await?;
+                }
+                None => store.create(&record).await?,
+            }
+        }
+
+        self.drive(saga, &mut record, ctx).await
+    }
+
+    /// Continue a stuck saga in the direction it was going. Returns the
+    /// saga's state afterwards, which is `stuck` again if it failed again.
+    pub async fn resume<C>(&self, saga: &Saga<C>, mut record: SagaRecord) -> Result<SagaRecord>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        let status = match record.direction {
+            SagaDirection::Forward => SagaStatus::Running,
+            SagaDirection::Backward => SagaStatus::Compensating,
+        };
+        let ctx = self.claim_stuck(saga, &mut record, status).await?;
+        let _ = match record.direction {
+            SagaDirection::Forward => self.drive(saga, &mut record, ctx).await.map(|_| ()),
+            SagaDirection::Backward => self.compensate(saga, &mut record, ctx).await,
+        };
+        Ok(record)
+    }
+
+    /// Undo the completed steps of a stuck saga
+    pub async fn abort<C>(&self, saga: &Saga<C>, mut record: SagaRecord) -> Result<SagaRecord>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        if saga.recovery == Recovery::RetryForward {
+            return Err(AppError::BadRequest(format!(
+                "A '{}' saga cannot be compensated; resume it instead",
+                saga.kind
+            )));
+        }
+        let ctx = self
+            .claim_stuck(saga, &mut record, SagaStatus::Compensating)
+            .await?;
+        let _ = self.compensate(saga, &mut record, ctx).await;
+        Ok(record)
+    }
+
+    /// Take over a stuck saga so no other operator acts on it concurrently
+    async fn claim_stuck<C>(
+        &self,
+        saga: &Saga<C>,
+        record: &mut SagaRecord,
+        status: SagaStatus,
+    ) -> Result<C>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        if record.kind != saga.kind || record.steps.len() != saga.steps.len() {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Saga {} does not match the '{}' definition",
+                record.id,
+                saga.kind
+            )));
+        }
+        if record.status != SagaStatus::Stuck {
+            return Err(AppError::Conflict(format!(
+                "Only stuck sagas can be resumed or compensated; saga {} is {}",
+                record.id,
+                record.status.as_str()
+            )));
+        }
+        let ctx = record
+            .context_as()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid saga context: {}", e)))?;
+        if let Some(store) = &self.store {
+            if !store
+                .transition(record.id, SagaStatus::Stuck, status)
+                .await?
+            {
+                return Err(AppError::Conflict(format!(
+                    "Saga {} was taken over by someone else",
+                    record.id
+                )));
+            }
+        }
+        record.status = status;
+        Ok(ctx)
+    }
+
+    /// Execute the remaining steps; on failure recover as the saga defines
+    async fn drive<C>(&self, saga: &Saga<C>, record: &mut SagaRecord, mut ctx: C) -> Result<C>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        while (record.current_step as usize) < saga.steps.len() {
+            let index = record.current_step as usize;
+            let step = &saga.steps[index];
+            let action = step.action.clone();
+            let outcome = attempt(step, &mut record.steps[index], || action(ctx.clone())).await;
+            match outcome {
+                Ok(next) => {
+                    ctx = next;
+                    let state = &mut record.steps[index];
+                    state.status = SagaStepStatus::Completed;
+                    state.last_error = None;
+                    record.current_step += 1;
+                    record.context = to_context(&ctx)?;
+                    self.persist(record).await;
+                }
+                Err(e) => {
+                    record.steps[index].status = SagaStepStatus::Failed;
+                    record.error = Some(format!("Step '{}' failed: {}", step.name, e));
+                    match saga.recovery {
+                        Recovery::RetryForward => self.finish(record, SagaStatus::Stuck).await,
+                        Recovery::Compensate => {
+                            record.direction = SagaDirection::Backward;
+                            record.status = SagaStatus::Compensating;
+                            self.persist(record).await;
+                            let _ = self.compensate(saga, record, ctx).await;
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        record.error = None;
+        self.finish(record, SagaStatus::Completed).await;
+        Ok(ctx)
+    }
+
+    /// Undo the completed steps, last first
+    async fn compensate<C>(&self, saga: &Saga<C>, record: &mut SagaRecord, ctx: C) -> Result<()>
+    where
+        C: Serialize + DeserializeOwned + Clone + Send + 'static,
+    {
+        record.direction = SagaDirection::Backward;
+        for index in (0..record.current_step as usize).rev() {
+            let step = &saga.steps[index];
+            if record.steps[index].status == SagaStepStatus::Completed {
+                if let Some(compensation) = step.compensation.clone() {
+                    let outcome =
+                        attempt(step, &mut record.steps[index], || compensation(ctx.clone())).await;
+                    if let Err(e) = outcome {
+                        let failure = format!("Compensation of '{}' failed: {}", step.name, e);
+                        record.error = Some(match record.error.take() {
+                            Some(cause) => format!("{}; {}", cause, failure),
+                            None => failure,
+                        });
+                        self.finish(record, SagaStatus::Stuck).await;
+                        return Err(e);
+                    }
+                }
+                record.steps[index].status = SagaStepStatus::Compensated;
+            }
+            record.current_step = index as u32;
+            self.persist(record).await;
+        }
+
+        self.finish(record, SagaStatus::Compensated).await;
+        Ok(())
+    }
+
+    async fn finish(&self, record: &mut SagaRecord, status: SagaStatus) {
+        record.status = status;
+        self.persist(record).await;
+        metrics::counter!(
+            "auth9_saga_finished_total",
+            "kind" => record.kind.clone(),
+            "status" => status.as_str()
+        )
+        .increment(1);
+        if status == SagaStatus::Stuck {
+            tracing::error!(
+                saga_id = %record.id,
+                kind = %record.kind,
+                error = record.error.as_deref().unwrap_or_default(),
+                "Saga is stuck and needs an operator"
+            );
+        }
+    }
+
+    /// Save progress. A failed write is logged rather than failing the
+    /// operation: the step already happened, and a saga whose state falls
+    /// behind is picked up as stuck later.
+    async fn persist(&self, record: &mut SagaRecord) {
+        record.updated_at = chrono::Utc::now();
+        if let Some(store) = &self.store {
+            if let Err(e) = store.update(record).await {
+                tracing::warn!(saga_id = %record.id, error = %e, "Failed to persist saga state");
+            }
+        }
+    }
+}
+
+/// Run `call` under the step's retry policy, counting attempts in `state`
+async fn attempt<C, T, F>(step: &Step<C>, state: &mut SagaStepState, call: F) -> Result<T>
+where
+    F: Fn() -> StepFuture<T>,
+{
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        state.attempts += 1;
+        match call().await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                state.last_error = Some(e.to_string());
+                if attempt >= step.retry.max_attempts || !is_transient(&e) {
+                    return Err(e);
+                }
+                tracing::warn!(step = step.name, attempt, error = %e, "Saga step failed, retrying");
+                tokio::time::sleep(step.retry.backoff(attempt)).await;
+            }
+        }
+    }
+}
+
+fn to_context<C: Serialize>(ctx: &C) -> Result<serde_json::Value> {
+    serde_json::to_value(ctx)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize saga context: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::saga::MockSagaRepository;
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    struct Ctx {
+        done: Vec<String>,
+    }
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    fn quick() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        }
+    }
+
+    /// Saga of steps `a`, `b`, `c` logging every action and compensation;
+    /// `fail` names a step that always fails with the error it makes
+    fn saga(
+        recovery: Recovery,
+        log: &Log,
+        fail: Option<(&'static str, fn() -> AppError)>,
+    ) -> Saga<Ctx> {
+        let mut saga = Saga::new("test", recovery);
+        for name in ["a", "b", "c"] {
+            let (log_do, log_undo) = (log.clone(), log.clone());
+            saga = saga
+                .step(name, move |mut ctx: Ctx| {
+                    let log = log_do.clone();
+                    let error = fail
+                        .filter(|(failing, _)| *failing == name)
+                        .map(|(_, make)| make());
+                    async move {
+                        log.lock().unwrap().push(format!("do {}", name));
+                        if let Some(e) = error {
+                            return Err(e);
+                        }
+                        ctx.done.push(name.to_string());
+                        Ok(ctx)
+                    }
+                })
+                .compensate_with(move |ctx: Ctx| {
+                    let log = log_undo.clone();
+                    async move {
+                        assert!(ctx.done.contains(&name.to_string()));
+                        log.lock().unwrap().push(format!("undo {}", name));
+                        Ok(())
+                    }
+                })
+                .retry(quick());
+        }
+        saga
+    }
+
+    fn entries(log: &Log) -> Vec<String> {
+        log.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn test_runs_all_steps() {
+        let log = Log::default();
+        let ctx = SagaRunner::default()
+            .run(
+                &saga(Recovery::Compensate, &log, None),
+                None,
+                Ctx::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(ctx.done, vec!["a", "b", "c"]);
+        assert_eq!(entries(&log), vec!["do a", "do b", "do c"]);
+    }
+
+    #[tokio::test]
+    async fn test_failure_compensates_completed_steps_in_reverse() {
+        let log = Log::default();
+        let saga = saga(
+            Recovery::Compensate,
+            &log,
+            Some(("c", || AppError::BadRequest("nope".into()))),
+        );
+        let result = SagaRunner::default().run(&saga, None, Ctx::default()).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        // Non-transient errors are not retried
+        assert_eq!(
+            entries(&log),
+            vec!["do a", "do b", "do c", "undo b", "undo a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let log = Log::default();
+        let saga = saga(
+            Recovery::Compensate,
+            &log,
+            Some(("b", || AppError::IdentityBackend("down".into()))),
+        );
+        let _ = SagaRunner::default().run(&saga, None, Ctx::default()).await;
+        assert_eq!(
+            entries(&log),
+            vec!["do a", "do b", "do b", "do b", "undo a"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_forward_saga_gets_stuck_and_resumes() {
+        let log = Log::default();
+        let saved: Arc<Mutex<Vec<SagaRecord>>> = Arc::default();
+        let mut store = MockSagaRepository::new();
+        store.expect_create().returning(|_| Ok(()));
+        let sink = saved.clone();
+        store.expect_update().returning(move |record| {
+            sink.lock().unwrap().push(record.clone());
+            Ok(())
+        });
+        store
+            .expect_transition()
+            .withf(|_, from, to| *from == SagaStatus::Stuck && *to == SagaStatus::Running)
+            .times(1)
+            .returning(|_, _, _| Ok(true));
+        let runner = SagaRunner::new(Some(Arc::new(store)));
+
+        let failing = saga(
+            Recovery::RetryForward,
+            &log,
+            Some(("b", || AppError::BadRequest("nope".into()))),
+        );
+        assert!(runner.run(&failing, None, Ctx::default()).await.is_err());
+        let stuck = saved.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(stuck.status, SagaStatus::Stuck);
+        assert_eq!(stuck.current_step, 1);
+        assert_eq!(stuck.steps[1].status, SagaStepStatus::Failed);
+        assert!(stuck.error.as_deref().unwrap().contains("Step 'b' failed"));
+        // Compensation is not available for retry-forward sagas
+        assert!(matches!(
+            runner.abort(&failing, stuck.clone()).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        // Once the cause is fixed, resuming finishes the remaining steps
+        let resumed = runner
+            .resume(&saga(Recovery::RetryForward, &log, None), stuck)
+            .await
+            .unwrap();
+        assert_eq!(resumed.status, SagaStatus::Completed);
+        assert_eq!(
+            resumed.context_as::<Ctx>().unwrap().done,
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(entries(&log), vec!["do a", "do b", "do b", "do c"]);
+    }
+
+    #[tokio::test]
+    async fn test_completed_key_returns_stored_context() {
+        let log = Log::default();
+        let saga = saga(Recovery::Compensate, &log, None);
+        let mut done = SagaRecord::new(
+            "test",
+            Some("key"),
+            ["a", "b", "c"],
+            serde_json::json!({ "done": ["a", "b", "c"] }),
+        );
+        done.status = SagaStatus::Completed;
+
+        let mut store = MockSagaRepository::new();
+        store
+            .expect_find_by_key()
+            .returning(move |_, _| Ok(Some(done.clone())));
+        store.expect_create().never();
+        let ctx = SagaRunner::new(Some(Arc::new(store)))
+            .run(&saga, Some("key"), Ctx::default())
+            .await
+            .unwrap();
+        assert_eq!(ctx.done, vec!["a", "b", "c"]);
+        assert!(entries(&log).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_key_of_stuck_saga_conflicts() {
+        let log = Log::default();
+        let mut stuck = SagaRecord::new("test", Some("key"), ["a"], serde_json::json!({}));
+        stuck.status = SagaStatus::Stuck;
+
+        let mut store = MockSagaRepository::new();
+        store
+            .expect_find_by_key()
+            .returning(move |_, _| Ok(Some(stuck.clone())));
+        let result = SagaRunner::new(Some(Arc::new(store)))
+            .run(
+                &saga(Recovery::Compensate, &log, None),
+                Some("key"),
+                Ctx::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,879 @@
+//! Versioned schema artifacts for API consumers
+//!
+//! `auth9-core schema export` writes the gRPC descriptor set, the proto
+//! source and the OpenAPI document into `<out>/v<version>/` together with a
+//! `manifest.json` carrying SHA-256 checksums. Before writing, and on
+//! `auth9-core schema check`, the current schema is compared against every
+//! previously exported version it must stay compatible with (same major, or
+//! same minor while the major is 0) and any breaking change fails the run.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use prost::Message;
+use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const DESCRIPTOR_FILE: &str = "auth9_descriptor.bin";
+pub const PROTO_FILE: &str = "auth9.proto";
+pub const OPENAPI_FILE: &str = "openapi.json";
+
+const PROTO_SOURCE: &str = include_str!("../proto/auth9.proto");
+
+/// Checksummed listing of one exported version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaManifest {
+    pub version: String,
+    pub generated_at: DateTime<Utc>,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Schema artifacts of one version, held in memory
+#[derive(Debug, Clone)]
+pub struct SchemaArtifacts {
+    pub version: String,
+    pub descriptor: Vec<u8>,
+    pub proto: String,
+    pub openapi: Value,
+}
+
+impl SchemaArtifacts {
+    /// Artifacts of the running build
+    pub fn current() -> Result<Self> {
+        let openapi = serde_json::to_value(crate::openapi::ApiDoc::build())
+            .context("Failed to serialize OpenAPI spec")?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            descriptor: crate::server::FILE_DESCRIPTOR_SET.to_vec(),
+            proto: PROTO_SOURCE.to_string(),
+            openapi,
+        })
+    }
+
+    fn files(&self) -> Result<Vec<(&'static str, Vec<u8>)>> {
+        let openapi =
+            serde_json::to_vec_pretty(&self.openapi).context("Failed to serialize OpenAPI spec")?;
+        Ok(vec![
+            (DESCRIPTOR_FILE, self.descriptor.clone()),
+            (PROTO_FILE, self.proto.as_bytes().to_vec()),
+            (OPENAPI_FILE, openapi),
+        ])
+    }
+
+    /// Load a previously exported version, verifying its checksums
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest: SchemaManifest = serde_json::from_slice(
+            &std::fs::read(dir.join(MANIFEST_FILE))
+                .with_context(|| format!("Missing {} in {}", MANIFEST_FILE, dir.display()))?,
+        )
+        .with_context(|| format!("Invalid {} in {}", MANIFEST_FILE, dir.display()))?;
+
+        let mut contents = BTreeMap::new();
+        for entry in &manifest.files {
+            let path = dir.join(&entry.path);
+            let bytes =
+                std::fs::read(&path).with_context(|| format!("Missing {}", path.display()))?;
+            if sha256_hex(&bytes) != entry.sha256 {
+                bail!("Checksum mismatch for {}", path.display());
+            }
+            contents.insert(entry.path.as_str(), bytes);
+        }
+
+        let mut take = |name: &str| {
+            contents
+                .remove(name)
+                .with_context(|| format!("{} is not listed in {}", name, dir.display()))
+        };
+        let descriptor = take(DESCRIPTOR_FILE)?;
+        let proto = String::from_utf8(take(PROTO_FILE)?).context("Proto source is not UTF-8")?;
+        let openapi =
+            serde_json::from_slice(&take(OPENAPI_FILE)?).context("Invalid exported OpenAPI")?;
+
+        Ok(Self {
+            version: manifest.version,
+            descriptor,
+            proto,
+            openapi,
+        })
+    }
+}
+
+/// Result of [`export_schemas`]
+#[derive(Debug)]
+pub struct ExportReport {
+    pub version: String,
+    pub dir: PathBuf,
+    /// Versions the export was checked against
+    pub checked_against: Vec<String>,
+    /// False when the same version was already exported with identical content
+    pub written: bool,
+}
+
+/// A change that breaks clients built against an earlier version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    pub against_version: String,
+    pub artifact: &'static str,
+    pub location: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[v{}] {} {}: {}",
+            self.against_version, self.artifact, self.location, self.reason
+        )
+    }
+}
+
+/// Write the artifacts into `<out_dir>/v<version>/`. Fails on breaking
+/// changes against compatible earlier exports, and when the version was
+/// already exported with different content unless `overwrite` is set.
+pub fn export_schemas(
+    artifacts: &SchemaArtifacts,
+    out_dir: &Path,
+    overwrite: bool,
+) -> Result<ExportReport> {
+    let (checked_against, incompatibilities) = check_compatibility(artifacts, out_dir)?;
+    if !incompatibilities.is_empty() {
+        bail!(format_incompatibilities(&incompatibilities));
+    }
+
+    let dir = out_dir.join(format!("v{}", artifacts.version));
+    let files = artifacts.files()?;
+    if dir.join(MANIFEST_FILE).exists() {
+        let existing = SchemaArtifacts::load(&dir)?.files()?;
+        if existing == files {
+            return Ok(ExportReport {
+                version: artifacts.version.clone(),
+                dir,
+                checked_against,
+                written: false,
+            });
+        }
+        if !overwrite {
+            bail!(
+                "Schema v{} was already exported with different content; bump the crate version or pass --overwrite",
+                artifacts.version
+            );
+        }
+    }
+
+    std::fs::create_dir_all(&dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+    let mut entries = Vec::with_capacity(files.len());
+    for (name, bytes) in &files {
+        std::fs::write(dir.join(name), bytes)
+            .with_context(|| format!("Cannot write {}", dir.join(name).display()))?;
+        entries.push(ManifestEntry {
+            path: name.to_string(),
+            sha256: sha256_hex(bytes),
+            size: bytes.len() as u64,
+        });
+    }
+    let manifest = SchemaManifest {
+        version: artifacts.version.clone(),
+        generated_at: Utc::now(),
+        files: entries,
+    };
+    std::fs::write(
+        dir.join(MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    Ok(ExportReport {
+        version: artifacts.version.clone(),
+        dir,
+        checked_against,
+        written: true,
+    })
+}
+
+/// Compare the artifacts against every compatible earlier export in
+/// `out_dir`. Returns the versions compared and the breaking changes found.
+pub fn check_compatibility(
+    artifacts: &SchemaArtifacts,
+    out_dir: &Path,
+) -> Result<(Vec<String>, Vec<Incompatibility>)> {
+    let current = parse_version(&artifacts.version)
+        .with_context(|| format!("Invalid crate version '{}'", artifacts.version))?;
+
+    let mut checked = Vec::new();
+    let mut incompatibilities = Vec::new();
+    for (version, dir) in exported_versions(out_dir)? {
+        if version >= current || !same_compat_line(version, current) {
+            continue;
+        }
+        let previous = SchemaArtifacts::load(&dir)?;
+        incompatibilities.extend(diff_artifacts(&previous, artifacts)?);
+        checked.push(previous.version);
+    }
+    Ok((checked, incompatibilities))
+}
+
+pub fn format_incompatibilities(incompatibilities: &[Incompatibility]) -> String {
+    let mut message = format!("{} breaking schema change(s):", incompatibilities.len());
+    for incompatibility in incompatibilities {
+        message.push_str("\n  ");
+        message.push_str(&incompatibility.to_string());
+    }
+    message
+}
+
+fn diff_artifacts(
+    previous: &SchemaArtifacts,
+    current: &SchemaArtifacts,
+) -> Result<Vec<Incompatibility>> {
+    let old = FileDescriptorSet::decode(previous.descriptor.as_slice())
+        .with_context(|| format!("Invalid descriptor set in v{}", previous.version))?;
+    let new = FileDescriptorSet::decode(current.descriptor.as_slice())
+        .context("Invalid current descriptor set")?;
+
+    let mut found: Vec<Incompatibility> = diff_descriptors(&old, &new)
+        .into_iter()
+        .map(|(location, reason)| Incompatibility {
+            against_version: previous.version.clone(),
+            artifact: "protobuf",
+            location,
+            reason,
+        })
+        .collect();
+    found.extend(
+        diff_openapi(&previous.openapi, &current.openapi)
+            .into_iter()
+            .map(|(location, reason)| Incompatibility {
+                against_version: previous.version.clone(),
+                artifact: "openapi",
+                location,
+                reason,
+            }),
+    );
+    Ok(found)
+}
+
+// ── Protobuf ──────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct ProtoIndex {
+    messages: BTreeMap<String, MessageShape>,
+    enums: BTreeMap<String, EnumShape>,
+    methods: BTreeMap<String, MethodShape>,
+    services: BTreeSet<String>,
+}
+
+#[derive(Default)]
+struct MessageShape {
+    /// Field number → (name, type, type name, label)
+    fields: BTreeMap<i32, (String, i32, String, i32)>,
+    reserved: Vec<(i32, i32)>,
+}
+
+#[derive(Default)]
+struct EnumShape {
+    values: BTreeMap<i32, String>,
+    reserved: Vec<(i32, i32)>,
+}
+
+#[derive(PartialEq)]
+struct MethodShape {
+    input: String,
+    output: String,
+    client_streaming: bool,
+    server_streaming: bool,
+}
+
+impl ProtoIndex {
+    fn build(set: &FileDescriptorSet) -> Self {
+        let mut index = Self::default();
+        for file in &set.file {
+            let prefix = match file.package() {
+                "" => String::new(),
+                package => format!(".{}", package),
+            };
+            for message in &file.message_type {
+                index.add_message(&prefix, message);
+            }
+            for enumeration in &file.enum_type {
+                index.add_enum(&prefix, enumeration);
+            }
+            for service in &file.service {
+                let service_name = format!("{}.{}", prefix, service.name());
+                for method in &service.method {
+                    index.methods.insert(
+                        format!("{}/{}", service_name, method.name()),
+                        MethodShape {
+                            input: method.input_type().to_string(),
+                            output: method.output_type().to_string(),
+                            client_streaming: method.client_streaming(),
+                            server_streaming: method.server_streaming(),
+                        },
+                    );
+                }
+                index.services.insert(service_name);
+            }
+        }
+        index
+    }
+
+    fn add_message(&mut self, prefix: &str, message: &DescriptorProto) {
+        let name = format!("{}.{}", prefix, message.name());
+        let shape = MessageShape {
+            fields: message
+                .field
+                .iter()
+                .map(|field| {
+                    (
+                        field.number(),
+                        (
+                            field.name().to_string(),
+                            field.r#type.unwrap_or_default(),
+                            field.type_name().to_string(),
+                            field.label.unwrap_or_default(),
+                        ),
+                    )
+                })
+                .collect(),
+            reserved: message
+                .reserved_range
+                .iter()
+                // Message reserved ranges are end-exclusive
+                .map(|range| (range.start(), range.end() - 1))
+                .collect(),
+        };
+        for nested in &message.nested_type {
+            self.add_message(&name, nested);
+        }
+        for enumeration in &message.enum_type {
+            self.add_enum(&name, enumeration);
+        }
+        self.messages.insert(name, shape);
+    }
+
+    fn add_enum(&mut self, prefix: &str, enumeration: &EnumDescriptorProto) {
+        self.enums.insert(
+            format!("{}.{}", prefix, enumeration.name()),
+            EnumShape {
+                values: enumeration
+                    .value
+                    .iter()
+                    .map(|value| (value.number(), value.name().to_string()))
+                    .collect(),
+                // Enum reserved ranges are end-inclusive
+                reserved: enumeration
+                    .reserved_range
+                    .iter()
+                    .map(|range| (range.start(), range.end()))
+                    .collect(),
+            },
+        );
+    }
+}
+
+fn is_reserved(ranges: &[(i32, i32)], number: i32) -> bool {
+    ranges
+        .iter()
+        .any(|(start, end)| (*start..=*end).contains(&number))
+}
+
+fn diff_descriptors(old: &FileDescriptorSet, new: &FileDescriptorSet) -> Vec<(String, String)> {
+    let old = ProtoIndex::build(old);
+    let new = ProtoIndex::build(new);
+    let mut found = Vec::new();
+
+    for (name, old_message) in &old.messages {
+        let Some(new_message) = new.messages.get(name) else {
+            found.push((name.clone(), "message removed".to_string()));
+            continue;
+        };
+        for (number, (field_name, field_type, type_name, label)) in &old_message.fields {
+            let location = format!("{}.{} (#{})", name, field_name, number);
+            match new_message.fields.get(number) {
+                None if is_reserved(&new_message.reserved, *number) => {}
+                None => found.push((
+                    location,
+                    "field removed without reserving its number".to_string(),
+                )),
+                Some((new_name, new_type, new_type_name, new_label)) => {
+                    if new_type != field_type || new_type_name != type_name {
+                        found.push((location.clone(), "field type changed".to_string()));
+                    }
+                    if new_label != label {
+                        found.push((location.clone(), "field cardinality changed".to_string()));
+                    }
+                    if new_name != field_name {
+                        found.push((
+                            location,
+                            format!("field renamed to '{}' (breaks JSON mapping)", new_name),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    for (name, old_enum) in &old.enums {
+        let Some(new_enum) = new.enums.get(name) else {
+            found.push((name.clone(), "enum removed".to_string()));
+            continue;
+        };
+        for (number, value) in &old_enum.values {
+            if !new_enum.values.contains_key(number) && !is_reserved(&new_enum.reserved, *number) {
+                found.push((
+                    format!("{}.{} (={})", name, value, number),
+                    "enum value removed without reserving its number".to_string(),
+                ));
+            }
+        }
+    }
+
+    for service in &old.services {
+        if !new.services.contains(service) {
+            found.push((service.clone(), "service removed".to_string()));
+        }
+    }
+    for (name, old_method) in &old.methods {
+        match new.methods.get(name) {
+            None => {
+                let service = name.split('/').next().unwrap_or_default();
+                if new.services.contains(service) {
+                    found.push((name.clone(), "rpc removed".to_string()));
+                }
+            }
+            Some(new_method) if new_method != old_method => {
+                found.push((
+                    name.clone(),
+                    "rpc request/response type or streaming changed".to_string(),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    found
+}
+
+// ── OpenAPI ───────────────────────────────────────────────────────────────
+
+const HTTP_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+fn diff_openapi(old: &Value, new: &Value) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let empty = serde_json::Map::new();
+    let new_paths = new["paths"].as_object().unwrap_or(&empty);
+
+    for (path, old_item) in old["paths"].as_object().unwrap_or(&empty) {
+        let Some(new_item) = new_paths.get(path) else {
+            found.push((path.clone(), "path removed".to_string()));
+            continue;
+        };
+        for method in HTTP_METHODS {
+            let Some(old_operation) = old_item.get(method) else {
+                continue;
+            };
+            let location = format!("{} {}", method.to_uppercase(), path);
+            let Some(new_operation) = new_item.get(method) else {
+                found.push((location, "operation removed".to_string()));
+                continue;
+            };
+            let old_params = parameter_keys(old_operation, false);
+            for (name, location_in) in parameter_keys(new_operation, true) {
+                if !old_params.contains(&(name.clone(), location_in.clone())) {
+                    found.push((
+                        location.clone(),
+                        format!("new required {} parameter '{}'", location_in, name),
+                    ));
+                }
+            }
+            if old_operation.get("requestBody").is_none()
+                && new_operation["requestBody"]["required"] == Value::Bool(true)
+            {
+                found.push((location, "request body became required".to_string()));
+            }
+        }
+    }
+
+    let new_schemas = new["components"]["schemas"].as_object().unwrap_or(&empty);
+    for (name, old_schema) in old["components"]["schemas"].as_object().unwrap_or(&empty) {
+        let location = format!("#/components/schemas/{}", name);
+        let Some(new_schema) = new_schemas.get(name) else {
+            found.push((location, "schema removed".to_string()));
+            continue;
+        };
+        if schema_kind(old_schema) != schema_kind(new_schema) {
+            found.push((location.clone(), "schema type changed".to_string()));
+            continue;
+        }
+        let new_properties = new_schema["properties"].as_object().unwrap_or(&empty);
+        for (property, old_property) in old_schema["properties"].as_object().unwrap_or(&empty) {
+            match new_properties.get(property) {
+                None => found.push((
+                    format!("{}.{}", location, property),
+                    "property removed".to_string(),
+                )),
+                Some(new_property) if schema_kind(old_property) != schema_kind(new_property) => {
+                    found.push((
+                        format!("{}.{}", location, property),
+                        "property type changed".to_string(),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        let old_required = string_set(&old_schema["required"]);
+        for required in string_set(&new_schema["required"]) {
+            if !old_required.contains(&required) {
+                found.push((
+                    format!("{}.{}", location, required),
+                    "property became required".to_string(),
+                ));
+            }
+        }
+        if let Some(old_values) = old_schema["enum"].as_array() {
+            let new_values = new_schema["enum"].as_array().cloned().unwrap_or_default();
+            for value in old_values {
+                if !new_values.contains(value) {
+                    found.push((location.clone(), format!("enum value {} removed", value)));
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// `(name, in)` of an operation's parameters, optionally only required ones
+fn parameter_keys(operation: &Value, required_only: bool) -> BTreeSet<(String, String)> {
+    operation["parameters"]
+        .as_array()
+        .map(|params| {
+            params
+                .iter()
+                .filter(|param| !required_only || param["required"] == Value::Bool(true))
+                .map(|param| {
+                    (
+                        param["name"].as_str().unwrap_or_default().to_string(),
+                        param["in"].as_str().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The parts of a schema that decide its wire shape
+fn schema_kind(schema: &Value) -> (Value, Value) {
+    let ty = match &schema["type"] {
+        // `["string", "null"]` only widens nullability
+        Value::Array(types) => types
+            .iter()
+            .find(|ty| ty.as_str() != Some("null"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        other => other.clone(),
+    };
+    let reference = schema
+        .get("$ref")
+        .or_else(|| {
+            schema["allOf"]
+                .as_array()
+                .and_then(|all| all.iter().find_map(|s| s.get("$ref")))
+        })
+        .or_else(|| {
+            schema["oneOf"]
+                .as_array()
+                .and_then(|one| one.iter().find_map(|s| s.get("$ref")))
+        })
+        .cloned()
+        .unwrap_or(Value::Null);
+    (ty, reference)
+}
+
+fn string_set(value: &Value) -> BTreeSet<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ── Versions ──────────────────────────────────────────────────────────────
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// Whether clients of `older` expect `newer` to stay compatible
+fn same_compat_line(older: (u64, u64, u64), newer: (u64, u64, u64)) -> bool {
+    if older.0 == 0 && newer.0 == 0 {
+        older.1 == newer.1
+    } else {
+        older.0 == newer.0
+    }
+}
+
+/// Exported version directories (`v<semver>` with a manifest), oldest first
+fn exported_versions(out_dir: &Path) -> Result<Vec<((u64, u64, u64), PathBuf)>> {
+    if !out_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut versions = Vec::new();
+    for entry in
+        std::fs::read_dir(out_dir).with_context(|| format!("Cannot read {}", out_dir.display()))?
+    {
+        let path = entry?.path();
+        let Some(version) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix('v'))
+            .and_then(parse_version)
+        else {
+            continue;
+        };
+        if path.join(MANIFEST_FILE).is_file() {
+            versions.push((version, path));
+        }
+    }
+    versions.sort();
+    Ok(versions)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{
+        descriptor_proto::ReservedRange,
+        field_descriptor_proto::{Label, Type},
+        FieldDescriptorProto, FileDescriptorProto,
+    };
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("auth9-schema-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn field(name: &str, number: i32, ty: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(Label::Optional as i32),
+            ..Default::default()
+        }
+    }
+
+    fn descriptor(fields: Vec<FieldDescriptorProto>, reserved: Vec<ReservedRange>) -> Vec<u8> {
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".to_string()),
+                package: Some("auth9".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Token".to_string()),
+                    field: fields,
+                    reserved_range: reserved,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    fn artifacts(version: &str, descriptor: Vec<u8>, openapi: Value) -> SchemaArtifacts {
+        SchemaArtifacts {
+            version: version.to_string(),
+            descriptor,
+            proto: "syntax = \"proto3\";".to_string(),
+            openapi,
+        }
+    }
+
+    fn openapi(properties: Value, required: Value) -> Value {
+        json!({
+            "paths": {
+                "/api/v1/tenants": { "get": { "parameters": [] } }
+            },
+            "components": {
+                "schemas": {
+                    "Tenant": { "type": "object", "properties": properties, "required": required }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_export_writes_checksummed_manifest() {
+        let dir = temp_dir();
+        let current = artifacts(
+            "0.9.0",
+            descriptor(vec![field("access_token", 1, Type::String)], vec![]),
+            openapi(json!({ "id": { "type": "string" } }), json!(["id"])),
+        );
+
+        let report = export_schemas(&current, &dir, false).unwrap();
+        assert!(report.written);
+        let loaded = SchemaArtifacts::load(&dir.join("v0.9.0")).unwrap();
+        assert_eq!(loaded.descriptor, current.descriptor);
+        assert_eq!(loaded.openapi, current.openapi);
+
+        // Re-exporting identical content is a no-op
+        assert!(!export_schemas(&current, &dir, false).unwrap().written);
+
+        // Tampered artifacts are rejected
+        std::fs::write(dir.join("v0.9.0").join(OPENAPI_FILE), b"{}").unwrap();
+        assert!(SchemaArtifacts::load(&dir.join("v0.9.0")).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_export_refuses_changed_content_for_same_version() {
+        let dir = temp_dir();
+        let first = artifacts(
+            "0.9.0",
+            descriptor(vec![], vec![]),
+            openapi(json!({}), json!([])),
+        );
+        export_schemas(&first, &dir, false).unwrap();
+
+        let changed = artifacts(
+            "0.9.0",
+            descriptor(vec![field("extra", 2, Type::String)], vec![]),
+            openapi(json!({}), json!([])),
+        );
+        assert!(export_schemas(&changed, &dir, false).is_err());
+        assert!(export_schemas(&changed, &dir, true).unwrap().written);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_detects_breaking_protobuf_changes() {
+        let dir = temp_dir();
+        let old = artifacts(
+            "0.9.0",
+            descriptor(
+                vec![
+                    field("access_token", 1, Type::String),
+                    field("expires_in", 2, Type::Int64),
+                    field("legacy", 3, Type::String),
+                ],
+                vec![],
+            ),
+            openapi(json!({}), json!([])),
+        );
+        export_schemas(&old, &dir, false).unwrap();
+
+        // Removing a reserved field and adding new ones is compatible
+        let compatible = artifacts(
+            "0.9.1",
+            descriptor(
+                vec![
+                    field("access_token", 1, Type::String),
+                    field("expires_in", 2, Type::Int64),
+                    field("scope", 4, Type::String),
+                ],
+                vec![ReservedRange {
+                    start: Some(3),
+                    end: Some(4),
+                }],
+            ),
+            openapi(json!({}), json!([])),
+        );
+        let (checked, found) = check_compatibility(&compatible, &dir).unwrap();
+        assert_eq!(checked, vec!["0.9.0".to_string()]);
+        assert!(found.is_empty(), "{:?}", found);
+
+        let breaking = artifacts(
+            "0.9.1",
+            descriptor(vec![field("access_token", 1, Type::Bytes)], vec![]),
+            openapi(json!({}), json!([])),
+        );
+        let (_, found) = check_compatibility(&breaking, &dir).unwrap();
+        let reasons: Vec<_> = found.iter().map(|i| i.reason.as_str()).collect();
+        assert!(reasons.contains(&"field type changed"));
+        assert!(reasons.contains(&"field removed without reserving its number"));
+        assert!(export_schemas(&breaking, &dir, false).is_err());
+
+        // A new minor on 0.x starts a new compatibility line
+        let next_line = artifacts(
+            "0.10.0",
+            breaking.descriptor.clone(),
+            breaking.openapi.clone(),
+        );
+        assert!(check_compatibility(&next_line, &dir).unwrap().1.is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_detects_breaking_openapi_changes() {
+        let old = openapi(
+            json!({ "id": { "type": "string" }, "name": { "type": "string" } }),
+            json!(["id"]),
+        );
+
+        let compatible = openapi(
+            json!({
+                "id": { "type": "string" },
+                "name": { "type": ["string", "null"] },
+                "slug": { "type": "string" }
+            }),
+            json!(["id"]),
+        );
+        assert!(diff_openapi(&old, &compatible).is_empty());
+
+        let mut breaking = openapi(
+            json!({ "id": { "type": "integer" } }),
+            json!(["id", "slug"]),
+        );
+        breaking["paths"]["/api/v1/tenants"] = json!({
+            "get": { "parameters": [{ "name": "region", "in": "query", "required": true }] }
+        });
+        let reasons: Vec<_> = diff_openapi(&old, &breaking)
+            .into_iter()
+            .map(|(_, reason)| reason)
+            .collect();
+        assert!(reasons.contains(&"property removed".to_string()));
+        assert!(reasons.contains(&"property type changed".to_string()));
+        assert!(reasons.contains(&"property became required".to_string()));
+        assert!(reasons.contains(&"new required query parameter 'region'".to_string()));
+
+        let removed = json!({ "paths": {}, "components": { "schemas": {} } });
+        let reasons: Vec<_> = diff_openapi(&old, &removed)
+            .into_iter()
+            .map(|(_, reason)| reason)
+            .collect();
+        assert_eq!(reasons, vec!["path removed", "schema removed"]);
+    }
+
+    #[test]
+    fn test_current_artifacts_are_self_compatible() {
+        let current = SchemaArtifacts::current().unwrap();
+        assert_eq!(current.version, env!("CARGO_PKG_VERSION"));
+        assert!(diff_artifacts(&current, &current).unwrap().is_empty());
+    }
+}
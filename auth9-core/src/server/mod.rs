@@ -1,18 +1,32 @@
 //! Server initialization and routing
 
+mod startup;
+
 use crate::cache::{CacheManager, CacheOperations};
 use crate::config::Config;
-use crate::crypto::EncryptionKey;
+use crate::crypto::{EncryptionKey, TenantKeyring};
 use crate::domains;
 use crate::grpc::interceptor::{ApiKeyAuthenticator, AuthInterceptor};
+use crate::grpc::proto::login_telemetry_server::LoginTelemetryServer;
+use crate::grpc::proto::permission_replication_server::PermissionReplicationServer;
+use crate::grpc::proto::provisioning_server::ProvisioningServer;
+use crate::grpc::proto::revocation_feed_server::RevocationFeedServer;
 use crate::grpc::proto::token_exchange_server::TokenExchangeServer;
-use crate::grpc::TokenExchangeService;
+use crate::grpc::{
+    LoginTelemetryService, PermissionReplicationService, ProvisioningService,
+    RevocationWatchService, TokenExchangeService,
+};
 
 /// File descriptor set for gRPC reflection
 pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("auth9_descriptor");
-use crate::domains::authorization::service::{ClientService, RbacService};
+use crate::domains::authorization::service::{ClientService, RbacService, ScopeCatalogService};
+use crate::domains::events::{
+    AuditSubscriber, CacheInvalidationSubscriber, EventBus, RevocationFeedSubscriber,
+    WebhookSubscriber,
+};
 use crate::domains::identity::service::{
-    BreachedPasswordService, EmailVerificationService, IdentityProviderService, PasswordService,
+    AccountDeletionService, BreachedPasswordService, EmailVerificationService,
+    IdentityProviderService, LoginIdentifierService, PasswordDenyListService, PasswordService,
     RecoveryCodeService, RequiredActionService, SessionService, TotpService, WebAuthnService,
 };
 use crate::domains::integration::service::{ActionEngine, ActionService, WebhookService};
@@ -21,30 +35,35 @@ use crate::domains::platform::service::{
 };
 use crate::domains::provisioning::service::{ScimService, ScimTokenService};
 use crate::domains::security_observability::service::{
-    AnalyticsService, SecurityDetectionConfig, SecurityDetectionService,
+    AnalyticsService, LoginForensicsService, SecurityDetectionConfig, SecurityDetectionService,
 };
 use crate::domains::tenant_access::service::{
-    InvitationService, SamlApplicationService, TenantRepositoryBundle, TenantService,
-    UserRepositoryBundle, UserService,
+    AdminUnitService, InvitationService, SamlApplicationService, TenantRepositoryBundle,
+    TenantService, UserRepositoryBundle, UserService,
 };
+use crate::identity_engine::adapters::audited::AuditedIdentityEngine;
 use crate::identity_engine::adapters::auth9_oidc::{
     Auth9OidcFederationBrokerAdapter, Auth9OidcIdentityEngineAdapter, Auth9OidcSessionStoreAdapter,
 };
 use crate::identity_engine::{FederationBroker, IdentityEngine, IdentitySessionStore};
 use crate::jwt::JwtManager;
 use crate::repository::{
-    action::ActionRepositoryImpl, audit::AuditRepositoryImpl, invitation::InvitationRepositoryImpl,
-    linked_identity::LinkedIdentityRepositoryImpl, login_event::LoginEventRepositoryImpl,
+    account_deletion::AccountDeletionRepositoryImpl, action::ActionRepositoryImpl,
+    admin_unit::AdminUnitRepositoryImpl, audit::AuditRepositoryImpl,
+    invitation::InvitationRepositoryImpl, linked_identity::LinkedIdentityRepositoryImpl,
+    login_event::LoginEventRepositoryImpl, login_forensics::LoginForensicsRepositoryImpl,
     malicious_ip_blacklist::MaliciousIpBlacklistRepositoryImpl,
+    password_breach_check::PasswordBreachCheckRepositoryImpl,
     password_reset::PasswordResetRepositoryImpl, rbac::RbacRepositoryImpl,
-    saml_application::SamlApplicationRepositoryImpl,
+    saga::SagaRepositoryImpl, saml_application::SamlApplicationRepositoryImpl,
     scim_group_mapping::ScimGroupRoleMappingRepositoryImpl,
     scim_log::ScimProvisioningLogRepositoryImpl, scim_token::ScimTokenRepositoryImpl,
     security_alert::SecurityAlertRepositoryImpl, service::ServiceRepositoryImpl,
-    service_branding::ServiceBrandingRepositoryImpl, session::SessionRepositoryImpl,
-    system_settings::SystemSettingsRepositoryImpl, tenant::TenantRepositoryImpl,
-    tenant_risk_policy::TenantRiskPolicyRepositoryImpl, user::UserRepositoryImpl,
-    webhook::WebhookRepositoryImpl,
+    service_branding::ServiceBrandingRepositoryImpl, service_scope::ServiceScopeRepositoryImpl,
+    session::SessionRepositoryImpl, system_settings::SystemSettingsRepositoryImpl,
+    tenant::TenantRepositoryImpl, tenant_risk_policy::TenantRiskPolicyRepositoryImpl,
+    user::UserRepositoryImpl, webhook::WebhookRepositoryImpl,
+    webhook_delivery::WebhookDeliveryRepositoryImpl, PasswordBreachCheckRepository, SagaRepository,
 };
 use crate::state::{
     HasAnalytics, HasBranding, HasCache, HasDbPool, HasEmailTemplates, HasIdentityProviders,
@@ -60,7 +79,12 @@ use std::time::Duration;
 use tokio::net::TcpListener;
 use tonic::transport::Server as TonicServer;
 use tower::ServiceBuilder;
-use tower_http::{timeout::TimeoutLayer, trace::TraceLayer};
+use tower_http::{
+    compression::{predicate::SizeAbove, CompressionLayer, Predicate},
+    decompression::RequestDecompressionLayer,
+    timeout::TimeoutLayer,
+    trace::TraceLayer,
+};
 use tracing::info;
 use utoipa_redoc::{Redoc, Servable};
 use utoipa_swagger_ui::SwaggerUi;
@@ -73,6 +97,7 @@ use crate::middleware::rate_limit::{
 };
 use crate::middleware::require_auth::AuthMiddlewareState;
 use crate::middleware::security_headers::security_headers_middleware;
+use startup::StartupGate;
 
 // ============================================================
 // Production Service Type Aliases
@@ -112,6 +137,7 @@ pub struct AppState {
     pub user_service: Arc<ProductionUserService>,
     pub client_service: Arc<ClientService<ServiceRepositoryImpl, RbacRepositoryImpl>>,
     pub rbac_service: Arc<RbacService<RbacRepositoryImpl>>,
+    pub scope_catalog_service: Arc<ScopeCatalogService<ServiceScopeRepositoryImpl>>,
     pub audit_repo: Arc<AuditRepositoryImpl>,
     pub jwt_manager: JwtManager,
     pub cache_manager: CacheManager,
@@ -163,6 +189,12 @@ pub struct AppState {
     pub scim_group_mapping_repo: Arc<ScimGroupRoleMappingRepositoryImpl>,
     pub scim_log_repo: Arc<ScimProvisioningLogRepositoryImpl>,
     pub saml_application_service: Arc<SamlApplicationService<SamlApplicationRepositoryImpl>>,
+    pub admin_unit_service: Arc<AdminUnitService<AdminUnitRepositoryImpl>>,
+    pub login_identifier_service: Arc<LoginIdentifierService>,
+    pub password_breach_check_repo: Arc<dyn PasswordBreachCheckRepository>,
+    pub account_deletion_service: Arc<AccountDeletionService<AccountDeletionRepositoryImpl>>,
+    pub password_deny_list_service: Arc<PasswordDenyListService>,
+    pub login_forensics_service: Arc<LoginForensicsService<LoginForensicsRepositoryImpl>>,
     pub email_verification_service: Arc<EmailVerificationService>,
     pub required_actions_service: Arc<RequiredActionService>,
     pub totp_service: Arc<TotpService>,
@@ -195,6 +227,10 @@ impl HasServices for AppState {
     type CascadeInvitationRepo = InvitationRepositoryImpl;
     type ActionRepo = ActionRepositoryImpl;
     type SamlApplicationRepo = SamlApplicationRepositoryImpl;
+    type AdminUnitRepo = AdminUnitRepositoryImpl;
+    type AccountDeletionRepo = AccountDeletionRepositoryImpl;
+    type LoginForensicsRepo = LoginForensicsRepositoryImpl;
+    type ServiceScopeRepo = ServiceScopeRepositoryImpl;
 
     fn config(&self) -> &Config {
         &self.config
@@ -239,6 +275,10 @@ impl HasServices for AppState {
         &self.rbac_service
     }
 
+    fn scope_catalog_service(&self) -> &ScopeCatalogService<Self::ServiceScopeRepo> {
+        &self.scope_catalog_service
+    }
+
     fn audit_repo(&self) -> &Self::AuditRepo {
         &self.audit_repo
     }
@@ -259,6 +299,30 @@ impl HasServices for AppState {
         &self.saml_application_service
     }
 
+    fn admin_unit_service(&self) -> &AdminUnitService<Self::AdminUnitRepo> {
+        &self.admin_unit_service
+    }
+
+    fn login_identifier_service(&self) -> &LoginIdentifierService {
+        &self.login_identifier_service
+    }
+
+    fn password_breach_check_repo(&self) -> &Arc<dyn PasswordBreachCheckRepository> {
+        &self.password_breach_check_repo
+    }
+
+    fn account_deletion_service(&self) -> &AccountDeletionService<Self::AccountDeletionRepo> {
+        &self.account_deletion_service
+    }
+
+    fn password_deny_list_service(&self) -> &PasswordDenyListService {
+        &self.password_deny_list_service
+    }
+
+    fn login_forensics_service(&self) -> &LoginForensicsService<Self::LoginForensicsRepo> {
+        &self.login_forensics_service
+    }
+
     async fn check_ready(&self) -> (bool, bool) {
         let db_ok = sqlx::query("SELECT 1").execute(&self.db_pool).await.is_ok();
         let cache_ok = self.cache_manager.ping().await.is_ok();
@@ -276,6 +340,10 @@ impl HasServices for AppState {
     fn maybe_cache(&self) -> Option<&dyn CacheOperations> {
         Some(&self.cache_manager)
     }
+
+    fn saga_store(&self) -> Option<Arc<dyn SagaRepository>> {
+        Some(Arc::new(SagaRepositoryImpl::new(self.db_pool.clone())))
+    }
 }
 
 /// Implement HasSystemSettings trait for production AppState
@@ -523,21 +591,74 @@ impl HasScimServices for AppState {
 }
 
 /// Run the server
+///
+/// The HTTP port is bound first and answers health probes while the
+/// database and Redis are still being connected; see [`StartupGate`].
 pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) -> Result<()> {
-    // Create database connection pool
-    let db_pool = MySqlPoolOptions::new()
-        .max_connections(config.database.max_connections)
-        .min_connections(config.database.min_connections)
-        .acquire_timeout(Duration::from_secs(config.database.acquire_timeout_secs))
-        .idle_timeout(Duration::from_secs(config.database.idle_timeout_secs))
-        .connect(&config.database.url)
+    let gate = StartupGate::new();
+    let http_addr = config.http_addr();
+    let listener = TcpListener::bind(&http_addr).await?;
+    info!("HTTP server listening on {} (starting)", http_addr);
+
+    let http_server = async {
+        axum::serve(
+            listener,
+            gate.router()
+                .into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal())
         .await?;
+        Ok::<_, anyhow::Error>(())
+    };
 
-    info!("Connected to database");
+    // A shutdown during startup abandons it; once started, the gRPC server
+    // shuts down gracefully on its own
+    let services = async {
+        tokio::select! {
+            result = start(config, prometheus_handle, gate.clone()) => result,
+            _ = async {
+                shutdown_signal().await;
+                if gate.is_started() {
+                    std::future::pending::<()>().await;
+                }
+            } => {
+                info!("Shutdown requested before startup finished");
+                Ok(())
+            }
+        }
+    };
+
+    tokio::try_join!(http_server, services)?;
+
+    Ok(())
+}
+
+/// Connect to dependencies, build the application and run the gRPC server
+async fn start(
+    config: Config,
+    prometheus_handle: Option<PrometheusHandle>,
+    gate: StartupGate,
+) -> Result<()> {
+    // Create database connection pool
+    let db_pool = gate
+        .connect("database", &config.startup, || {
+            let options = MySqlPoolOptions::new()
+                .max_connections(config.database.max_connections)
+                .min_connections(config.database.min_connections)
+                .acquire_timeout(Duration::from_secs(config.database.acquire_timeout_secs))
+                .idle_timeout(Duration::from_secs(config.database.idle_timeout_secs));
+            #[cfg(feature = "fault-injection")]
+            let options = crate::fault_injection::with_database_faults(options);
+            options.connect(&config.database.url)
+        })
+        .await?;
 
     // Create cache manager
-    let cache_manager = CacheManager::new(&config.redis).await?;
-    info!("Connected to Redis");
+    let cache_manager = gate
+        .connect("redis", &config.startup, || {
+            CacheManager::new(&config.redis)
+        })
+        .await?;
 
     // Seed audience validation set: load all registered client_ids into Redis
     {
@@ -575,7 +696,12 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     let session_repo = Arc::new(SessionRepositoryImpl::new(db_pool.clone()));
     let linked_identity_repo = Arc::new(LinkedIdentityRepositoryImpl::new(db_pool.clone()));
     let login_event_repo = Arc::new(LoginEventRepositoryImpl::new(db_pool.clone()));
-    let webhook_repo = Arc::new(WebhookRepositoryImpl::new(db_pool.clone()));
+    // Webhook secrets are sealed with per-tenant data keys derived from
+    // SETTINGS_ENCRYPTION_KEY when it is set
+    let webhook_repo = Arc::new(
+        WebhookRepositoryImpl::new(db_pool.clone())
+            .with_keyring(TenantKeyring::from_config(&config.settings_encryption)),
+    );
     let security_alert_repo = Arc::new(SecurityAlertRepositoryImpl::new(db_pool.clone()));
     let action_repo = Arc::new(ActionRepositoryImpl::new(db_pool.clone()));
     let service_branding_repo = Arc::new(ServiceBrandingRepositoryImpl::new(db_pool.clone()));
@@ -596,14 +722,48 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     let federation_broker: Arc<dyn FederationBroker> = Arc::new(
         Auth9OidcFederationBrokerAdapter::new(social_provider_repo.clone()),
     );
-    let identity_engine: Arc<dyn IdentityEngine> = Arc::new(Auth9OidcIdentityEngineAdapter::new(
-        db_pool.clone(),
-        social_provider_repo,
-        config.core_public_url.clone(),
+    let identity_engine: Arc<dyn IdentityEngine> = Arc::new(AuditedIdentityEngine::new(
+        Arc::new(Auth9OidcIdentityEngineAdapter::new(
+            db_pool.clone(),
+            social_provider_repo,
+            config.core_public_url.clone(),
+        )),
+        audit_repo.clone(),
     ));
+    #[cfg(feature = "fault-injection")]
+    crate::fault_injection::log_config();
+    #[cfg(feature = "fault-injection")]
+    let identity_engine: Arc<dyn IdentityEngine> = Arc::new(
+        crate::identity_engine::adapters::fault_injection::FaultInjectingIdentityEngine::new(
+            identity_engine,
+        ),
+    );
 
     // Create webhook service first (needed for webhook event publishing)
-    let webhook_service = Arc::new(WebhookService::new(webhook_repo.clone()));
+    let webhook_service = Arc::new(
+        WebhookService::new(webhook_repo.clone()).with_delivery_repo(Arc::new(
+            WebhookDeliveryRepositoryImpl::new(db_pool.clone()),
+        )),
+    );
+
+    // Domain event bus: services publish, side effects subscribe
+    let event_bus = Arc::new(
+        EventBus::new()
+            .with_subscriber(Arc::new(WebhookSubscriber::new(webhook_service.clone())))
+            .with_subscriber(Arc::new(CacheInvalidationSubscriber::new(
+                Arc::new(cache_manager.clone()),
+                config.jwt.access_token_ttl_secs.unsigned_abs(),
+            )))
+            .with_subscriber(Arc::new(AuditSubscriber::new(audit_repo.clone())))
+            .with_subscriber(Arc::new(RevocationFeedSubscriber::new(
+                Arc::new(
+                    crate::repository::session_revocation::SessionRevocationRepositoryImpl::new(
+                        db_pool.clone(),
+                    ),
+                ),
+                webhook_service.clone(),
+            ))),
+    );
 
     // Create ActionEngine (for Auth9 Actions system)
     let action_engine = Arc::new(ActionEngine::with_config(
@@ -635,7 +795,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         action_repo.clone(),
     );
     let tenant_service = Arc::new(
-        TenantService::new(tenant_repos, Some(cache_manager.clone())).with_pool(db_pool.clone()),
+        TenantService::new(tenant_repos, Some(cache_manager.clone()))
+            .with_pool(db_pool.clone())
+            .with_webhook_publisher(webhook_service.clone()),
     );
 
     // Create UserService with repository bundle
@@ -654,7 +816,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
             user_repos,
             Some(webhook_service.clone()), // webhook event publisher
         )
-        .with_pool(db_pool.clone()),
+        .with_pool(db_pool.clone())
+        .with_cardinality_limits(config.cardinality.clone())
+        .with_event_bus(event_bus.clone()),
     );
     let client_service = Arc::new(
         ClientService::new(
@@ -664,19 +828,21 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         )
         .with_cascade_repos(action_repo.clone(), service_branding_repo.clone()),
     );
-    let rbac_service = Arc::new(RbacService::new(
-        rbac_repo.clone(),
-        Some(cache_manager.clone()),
-    ));
+    let rbac_service = Arc::new(
+        RbacService::new(rbac_repo.clone(), Some(cache_manager.clone()))
+            .with_cardinality_limits(config.cardinality.clone())
+            .with_event_bus(event_bus.clone()),
+    );
+    let scope_catalog_service = Arc::new(ScopeCatalogService::from_pool(db_pool.clone()));
 
     // Load encryption key for settings (optional, but must be valid if set)
-    let encryption_key = match std::env::var("SETTINGS_ENCRYPTION_KEY") {
-        Ok(encoded) if encoded.is_empty() => {
+    let encryption_key = match config.settings_encryption.key.as_deref() {
+        Some("") => {
             info!("SETTINGS_ENCRYPTION_KEY is empty, sensitive settings will not be encrypted");
             None
         }
-        Ok(encoded) => {
-            let key = EncryptionKey::from_base64(&encoded).unwrap_or_else(|e| {
+        Some(encoded) => {
+            let key = EncryptionKey::from_base64(encoded).unwrap_or_else(|e| {
                 panic!(
                     "SETTINGS_ENCRYPTION_KEY is set but invalid: {e}. \
                      Key must be exactly 32 bytes, base64-encoded (44 characters). \
@@ -686,7 +852,7 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
             info!("Encryption key loaded, sensitive settings will be encrypted");
             Some(key)
         }
-        Err(_) => {
+        None => {
             info!("SETTINGS_ENCRYPTION_KEY not set, sensitive settings will not be encrypted");
             None
         }
@@ -706,12 +872,6 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     // Create email template service
     let email_template_service = Arc::new(EmailTemplateService::new(system_settings_repo.clone()));
 
-    // Create email service (with template service for customizable templates)
-    let email_service = Arc::new(
-        EmailService::new(system_settings_service.clone())
-            .with_template_service(email_template_service.clone()),
-    );
-
     // Create branding service with identity sync
     let branding_service = Arc::new(
         BrandingService::with_sync_service(
@@ -720,9 +880,48 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
             identity_sync_service.clone(),
         )
         .with_allowed_domains(config.branding_allowed_domains.clone())
-        .with_service_repo(service_repo.clone()),
+        .with_service_repo(service_repo.clone())
+        .with_tenant_repo(tenant_repo.clone()),
     );
 
+    // Create email service (with template service for customizable templates
+    // and tenant branding)
+    let email_queue_repo: Arc<dyn crate::repository::EmailQueueRepository> =
+        Arc::new(crate::repository::email_queue::EmailQueueRepositoryImpl::new(db_pool.clone()));
+    let mut email_service = EmailService::new(system_settings_service.clone())
+        .with_template_service(email_template_service.clone())
+        .with_branding(branding_service.clone())
+        .with_failure_policy(config.dependency_policy.email);
+    if config.email_queue.enabled {
+        email_service = email_service.with_queue(email_queue_repo.clone());
+    }
+    let email_service = Arc::new(email_service);
+
+    // Deliver queued emails in the background
+    if config.email_queue.enabled {
+        crate::domains::platform::service::EmailQueueWorker::new(
+            email_service.clone(),
+            email_queue_repo,
+            config.email_queue.clone(),
+        )
+        .spawn();
+    }
+
+    // Retry emails deferred while the provider was unreachable
+    if config.dependency_policy.email == crate::config::FailurePolicy::Queue {
+        let deferred_email_service = email_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+            loop {
+                interval.tick().await;
+                if deferred_email_service.deferred_len() > 0 {
+                    let delivered = deferred_email_service.retry_deferred().await;
+                    tracing::info!(delivered, "Retried deferred emails");
+                }
+            }
+        });
+    }
+
     // Get app base URL for invitation links
     let app_base_url =
         std::env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
@@ -738,6 +937,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     // Create breached password detection service (HIBP)
     let breached_password_service = Arc::new(BreachedPasswordService::new(&config.hibp));
 
+    // Create tenant password deny list service
+    let password_deny_list_service = Arc::new(PasswordDenyListService::from_pool(db_pool.clone()));
+
     // Create new services for 5 features
     let password_service = Arc::new(
         PasswordService::with_action_engine(
@@ -750,15 +952,40 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
             identity_sync_service.clone(),
             config.password_reset.hmac_key.clone(),
         )
-        .with_breached_password_service(breached_password_service.clone()),
+        .with_breached_password_service(breached_password_service.clone())
+        .with_password_deny_list(password_deny_list_service.clone()),
     );
 
-    let session_service = Arc::new(SessionService::new(
+    let mut session_service = SessionService::new(
         session_repo.clone(),
         user_repo.clone(),
         identity_sessions,
-        Some(webhook_service.clone()), // webhook event publisher
-    ));
+        Some(event_bus.clone()),
+    )
+    .with_cache(Arc::new(cache_manager.clone()));
+    let geoip = if config.geoip.enabled {
+        config
+            .geoip
+            .database_path
+            .as_deref()
+            .and_then(crate::domains::security_observability::service::GeoIpService::new)
+            .map(Arc::new)
+    } else {
+        None
+    };
+    if let Some(ref geoip) = geoip {
+        session_service = session_service.with_geoip(geoip.clone());
+    }
+    // Email users about new sign-ins, with a "this wasn't me" link
+    let session_service = Arc::new(session_service.with_sign_in_notifier(Arc::new(
+        crate::domains::platform::service::SignInEmailNotifier::new(
+            crate::domains::platform::service::NotificationPreferenceService::from_config(
+                db_pool.clone(),
+                &config,
+            ),
+            email_service.clone(),
+        ),
+    )));
 
     // Create WebAuthn service with native passkey support
     let webauthn_repo = Arc::new(crate::repository::webauthn::WebAuthnRepositoryImpl::new(
@@ -791,6 +1018,38 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
 
     let analytics_service = Arc::new(AnalyticsService::new(login_event_repo.clone()));
 
+    // gRPC login telemetry for edge gateways
+    let mut login_telemetry_service = LoginTelemetryService::new(
+        login_event_repo.clone(),
+        client_service.clone() as Arc<dyn crate::grpc::login_event::ClientCredentialVerifier>,
+    );
+    if let Some(ref geoip) = geoip {
+        login_telemetry_service = login_telemetry_service.with_geoip(geoip.clone());
+    }
+
+    // gRPC revocation watch stream for relying parties
+    let revocation_watch_service = RevocationWatchService::new(
+        Arc::new(
+            crate::repository::session_revocation::SessionRevocationRepositoryImpl::new(
+                db_pool.clone(),
+            ),
+        ),
+        client_service.clone() as Arc<dyn crate::grpc::login_event::ClientCredentialVerifier>,
+        std::time::Duration::from_millis(config.revocation_feed.watch_poll_interval_ms),
+    );
+
+    // gRPC permission snapshot replication for authorization sidecars
+    let permission_replication_service = PermissionReplicationService::new(
+        Arc::new(
+            crate::repository::permission_snapshot::PermissionSnapshotRepositoryImpl::new(
+                db_pool.clone(),
+            ),
+        ),
+        client_service.clone() as Arc<dyn crate::grpc::login_event::ClientCredentialVerifier>,
+        std::time::Duration::from_millis(config.permission_replication.poll_interval_ms),
+        config.permission_replication.max_tenants_per_watch,
+    );
+
     let security_detection_service = Arc::new(SecurityDetectionService::new_with_blacklist(
         login_event_repo,
         security_alert_repo,
@@ -816,6 +1075,26 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         identity_engine.clone(),
     ));
 
+    // Create admin unit service (delegated admin boundaries)
+    let admin_unit_service = Arc::new(AdminUnitService::new(Arc::new(
+        AdminUnitRepositoryImpl::new(db_pool.clone()),
+    )));
+
+    // Create login identifier service (usernames and phone numbers)
+    let login_identifier_service = Arc::new(LoginIdentifierService::from_pool(db_pool.clone()));
+
+    // Create account deletion service (grace period before purge)
+    let account_deletion_service = Arc::new(AccountDeletionService::from_pool(
+        db_pool.clone(),
+        &config.account_deletion,
+    ));
+
+    // Create failed sign-in forensics service
+    let login_forensics_service = Arc::new(LoginForensicsService::from_pool(
+        db_pool.clone(),
+        &config.login_forensics,
+    ));
+
     // Create email verification and required actions services
     let email_verification_service = Arc::new(EmailVerificationService::new(
         identity_engine.clone(),
@@ -832,9 +1111,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         ),
     );
 
-    let totp_encryption_key = match std::env::var("SETTINGS_ENCRYPTION_KEY") {
-        Ok(encoded) if !encoded.is_empty() => {
-            EncryptionKey::from_base64(&encoded).unwrap_or_else(|_| {
+    let totp_encryption_key = match config.settings_encryption.key.as_deref() {
+        Some(encoded) if !encoded.is_empty() => {
+            EncryptionKey::from_base64(encoded).unwrap_or_else(|_| {
                 EncryptionKey::new([0u8; 32]) // Fallback for dev — will fail to decrypt on restart
             })
         }
@@ -862,6 +1141,7 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         user_service,
         client_service,
         rbac_service,
+        scope_catalog_service,
         audit_repo: audit_repo.clone(),
         jwt_manager: jwt_manager.clone(),
         cache_manager: cache_manager.clone(),
@@ -886,6 +1166,14 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         scim_group_mapping_repo,
         scim_log_repo,
         saml_application_service,
+        admin_unit_service,
+        login_identifier_service,
+        password_breach_check_repo: Arc::new(PasswordBreachCheckRepositoryImpl::new(
+            db_pool.clone(),
+        )),
+        account_deletion_service,
+        password_deny_list_service,
+        login_forensics_service,
         email_verification_service,
         required_actions_service,
         totp_service,
@@ -929,6 +1217,23 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
                 window_secs: 60,
             },
         );
+        // Add strict rate limit for account recovery and MFA reset (5 requests per minute)
+        for endpoint in [
+            "POST:/api/v1/auth/account-recovery/recovery-code",
+            "POST:/api/v1/auth/account-recovery/secondary-factor",
+            "POST:/api/v1/auth/account-recovery/requests",
+            "POST:/api/v1/auth/account-recovery/requests/{id}/claim",
+            "POST:/api/v1/mfa/reset-requests",
+            "POST:/api/v1/mfa/reset-requests/{id}/complete",
+        ] {
+            endpoints.insert(
+                endpoint.to_string(),
+                RateLimitRule {
+                    requests: 5,
+                    window_secs: 60,
+                },
+            );
+        }
         // Add rate limit for invitation endpoint (10 requests per minute per caller)
         endpoints.insert(
             "POST:/api/v1/tenants/{tenant_id}/invitations".to_string(),
@@ -952,6 +1257,7 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
             cache_manager.get_connection_manager(),
             jwt_manager.clone(),
         )
+        .with_failure_policy(config.dependency_policy.cache)
     } else {
         RateLimitState::noop()
     };
@@ -970,11 +1276,51 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     .with_action_executor(
         action_service.clone() as std::sync::Arc<dyn crate::grpc::token_exchange::ActionExecutor>
     )
+    .with_claims_enrichment(Arc::new(
+        crate::domains::identity::service::ClaimsEnrichmentService::from_pool(db_pool.clone()),
+    ))
+    .with_mfa_enrollment(Arc::new(
+        crate::domains::tenant_access::service::MfaEnrollmentService::from_pool(db_pool.clone()),
+    ))
     .with_rate_limiter(crate::grpc::token_exchange::GrpcRateLimiter::new(
         config.grpc_security.exchange_rate_limit_requests,
         config.grpc_security.exchange_rate_limit_window_secs,
+    ))
+    .with_validation_policies(Arc::new(
+        crate::domains::authorization::service::TokenValidationPolicies::from_pool(
+            db_pool.clone(),
+            &config.token_validation,
+        ),
     ));
 
+    // gRPC provisioning for internal platform services (off unless clients are allowlisted)
+    let provisioning_service = if config.grpc_security.provisioning_client_ids.is_empty() {
+        None
+    } else {
+        let idempotency_repo = Arc::new(
+            crate::repository::idempotency::IdempotencyRepositoryImpl::new(db_pool.clone()),
+        );
+        let purge_repo = idempotency_repo.clone();
+        tokio::spawn(async move {
+            use crate::repository::IdempotencyRepository;
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                let expired_before = chrono::Utc::now()
+                    - chrono::Duration::hours(crate::grpc::provisioning::IDEMPOTENCY_KEY_TTL_HOURS);
+                if let Err(e) = purge_repo.delete_expired(expired_before).await {
+                    tracing::warn!("Failed to purge expired idempotency keys: {}", e);
+                }
+            }
+        });
+        Some(ProvisioningService::new(
+            state.clone(),
+            state.client_service.clone()
+                as Arc<dyn crate::grpc::login_event::ClientCredentialVerifier>,
+            idempotency_repo,
+        ))
+    };
+
     // Wrap prometheus handle in Arc for sharing
     let prom_handle = Arc::new(prometheus_handle);
 
@@ -996,9 +1342,379 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         crate::middleware::CaptchaState::disabled()
     };
 
+    // Start async job workers (bulk import/export, tenant deletion)
+    if config.jobs.workers > 0 {
+        let job_publisher: Arc<dyn crate::domains::integration::service::WebhookEventPublisher> =
+            state.webhook_service.clone();
+        crate::domains::platform::service::JobWorkerPool::new(
+            Arc::new(crate::domains::platform::service::JobService::new(
+                Arc::new(crate::repository::job::JobRepositoryImpl::new(
+                    db_pool.clone(),
+                )),
+            )),
+            Arc::new(crate::domains::platform::api::job::StateJobExecutor::new(
+                state.clone(),
+            )),
+            config.jobs.clone(),
+        )
+        .with_webhook_publisher(job_publisher)
+        .spawn();
+        info!("Async job workers started ({})", config.jobs.workers);
+    }
+
+    // Queue inactivity sweeps for tenants with an enabled inactivity policy
+    if config.jobs.workers > 0 {
+        let inactivity_service = crate::domains::tenant_access::service::InactivityService::new(
+            Arc::new(crate::repository::inactivity::InactivityRepositoryImpl::new(db_pool.clone())),
+        );
+        let sweep_jobs = crate::repository::job::JobRepositoryImpl::new(db_pool.clone());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match inactivity_service
+                    .schedule_sweeps(&sweep_jobs, chrono::Utc::now())
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(queued) => tracing::info!(queued, "Queued inactivity sweeps"),
+                    Err(e) => tracing::warn!("Inactivity sweep scheduling failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Remind users of pending account deletions and queue deletions whose
+    // grace period has ended
+    if config.jobs.workers > 0 {
+        let deletion_service = state.account_deletion_service.clone();
+        let deletion_jobs = crate::repository::job::JobRepositoryImpl::new(db_pool.clone());
+        let deletion_email_service = state.email_service.clone();
+        tokio::spawn(async move {
+            use crate::repository::AccountDeletionRepository;
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                let now = chrono::Utc::now();
+                match deletion_service.due_reminders(now).await {
+                    Ok(notices) => {
+                        for notice in notices {
+                            let message = format!(
+                                "Your account will be permanently deleted on {}. Sign in before then to restore it",
+                                notice.purge_after.format("%Y-%m-%d")
+                            );
+                            if let Err(e) = deletion_email_service
+                                .send_account_deletion_notice(
+                                    &notice.email,
+                                    notice.display_name.as_deref(),
+                                    crate::i18n::resolve_locale(
+                                        notice.locale.as_deref(),
+                                        None,
+                                        None,
+                                    ),
+                                    &message,
+                                )
+                                .await
+                            {
+                                tracing::warn!(user_id = %notice.user_id, "Failed to send account deletion reminder: {}", e);
+                                continue;
+                            }
+                            if let Err(e) = deletion_service
+                                .repo()
+                                .mark_reminded(notice.user_id, now)
+                                .await
+                            {
+                                tracing::warn!("Failed to record account deletion reminder: {}", e);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("Account deletion reminder run failed: {}", e),
+                }
+                match deletion_service.queue_due_purges(&deletion_jobs, now).await {
+                    Ok(0) => {}
+                    Ok(queued) => tracing::info!(queued, "Queued account deletions"),
+                    Err(e) => tracing::warn!("Account deletion scheduling failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Drop revocation notices past the catch-up retention period
+    {
+        let revocation_feed = crate::domains::identity::service::RevocationFeedService::from_pool(
+            db_pool.clone(),
+            &config.revocation_feed,
+        );
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match revocation_feed.purge_expired(chrono::Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!(purged, "Purged expired revocation notices"),
+                    Err(e) => tracing::warn!("Revocation feed purge failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Drop failed sign-in captures past their retention period. Runs even
+    // when capture is disabled so older captures do not linger.
+    {
+        let forensics_service = state.login_forensics_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match forensics_service.purge_expired(chrono::Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!(purged, "Purged expired sign-in captures"),
+                    Err(e) => tracing::warn!("Sign-in capture purge failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Flag sagas abandoned by a stopped replica as stuck and drop old finished ones
+    {
+        let saga_repo = SagaRepositoryImpl::new(db_pool.clone());
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(300));
+            loop {
+                interval.tick().await;
+                let now = chrono::Utc::now();
+                match saga_repo
+                    .mark_stale_stuck(now - chrono::Duration::minutes(15))
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(stuck) => tracing::warn!(stuck, "Sagas interrupted before finishing"),
+                    Err(e) => tracing::warn!("Saga sweep failed: {}", e),
+                }
+                if let Err(e) = saga_repo
+                    .delete_finished_before(now - chrono::Duration::days(30))
+                    .await
+                {
+                    tracing::warn!("Saga cleanup failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Keep monthly event partitions ahead of time and drop expired ones
+    if config.event_partitions.enabled {
+        let partition_pool = db_pool.clone();
+        let partition_config = config.event_partitions.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                partition_config.check_interval_secs,
+            ));
+            loop {
+                interval.tick().await;
+                if let Err(e) = crate::migration::partition::maintain_partitions(
+                    &partition_pool,
+                    &partition_config,
+                )
+                .await
+                {
+                    tracing::warn!("Event partition maintenance failed: {:#}", e);
+                }
+            }
+        });
+    }
+
+    // Sign checkpoints of the audit log hash chain
+    if config.audit_integrity.checkpoint_key.is_some() {
+        let checkpoint_service =
+            crate::domains::security_observability::service::AuditIntegrityService::from_pool(
+                db_pool.clone(),
+                &config.audit_integrity,
+            );
+        let checkpoint_interval_secs = config.audit_integrity.checkpoint_interval_secs;
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(checkpoint_interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = checkpoint_service
+                    .record_checkpoint(chrono::Utc::now())
+                    .await
+                {
+                    tracing::warn!("Audit checkpoint failed: {}", e);
+                }
+            }
+        });
+    } else if config.is_production() {
+        tracing::warn!("AUDIT_CHECKPOINT_KEY is not set; audit log truncation cannot be detected");
+    }
+
+    // Record MAU thresholds and deliver billing events to the billing sink
+    if let Some(sink) =
+        crate::domains::platform::service::HttpBillingSink::from_config(&config.billing)
+    {
+        crate::domains::platform::service::BillingDispatcher::new(
+            Arc::new(crate::repository::billing::BillingEventRepositoryImpl::new(
+                db_pool.clone(),
+            )),
+            Arc::new(sink),
+            config.billing.clone(),
+        )
+        .spawn();
+        info!(
+            "Billing event dispatcher started ({:?} sink)",
+            config.billing.sink_kind
+        );
+    }
+
+    // Flush buffered session activity to the database
+    {
+        let activity_flusher = crate::domains::identity::service::SessionActivityFlusher::new(
+            Arc::new(state.cache_manager.clone()),
+            session_repo.clone(),
+            &config.session_activity,
+        );
+        let flush_interval_secs = config.session_activity.flush_interval_secs;
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(flush_interval_secs));
+            loop {
+                interval.tick().await;
+                if let Err(e) = activity_flusher.flush().await {
+                    tracing::warn!("Session activity flush failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Send account activity digests to users who opted in
+    {
+        let digest_service =
+            crate::domains::platform::service::NotificationPreferenceService::from_config(
+                db_pool.clone(),
+                &config,
+            );
+        let digest_email_service = state.email_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match digest_service
+                    .send_due_digests(&digest_email_service, chrono::Utc::now())
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::info!(sent, "Sent account activity digests"),
+                    Err(e) => tracing::warn!("Activity digest run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Send security alert digests to tenant admins
+    {
+        let security_digest_service =
+            crate::domains::security_observability::service::SecurityDigestService::from_config(
+                db_pool.clone(),
+                &config,
+            );
+        let security_digest_email_service = state.email_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+            loop {
+                interval.tick().await;
+                match security_digest_service
+                    .send_due_digests(&security_digest_email_service, chrono::Utc::now())
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::info!(sent, "Sent security alert digests"),
+                    Err(e) => tracing::warn!("Security alert digest run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Sync LDAP connectors that have directory sync enabled
+    if config.ldap_sync.enabled {
+        let sync_service = crate::domains::identity::service::LdapSyncService::new(
+            Arc::new(
+                crate::repository::ldap_sync_run::LdapSyncRunRepositoryImpl::new(db_pool.clone()),
+            ),
+            Arc::new(
+                crate::repository::ldap_group_mapping::LdapGroupRoleMappingRepositoryImpl::new(
+                    db_pool.clone(),
+                ),
+            ),
+            Arc::new(crate::repository::user::UserRepositoryImpl::new(
+                db_pool.clone(),
+            )),
+            Arc::new(
+                crate::repository::linked_identity::LinkedIdentityRepositoryImpl::new(
+                    db_pool.clone(),
+                ),
+            ),
+            Arc::new(crate::repository::rbac::RbacRepositoryImpl::new(
+                db_pool.clone(),
+            )),
+            config.ldap_sync.stale_after_secs,
+        );
+        let ldap_authenticator = state.ldap_authenticator.clone();
+        let check_interval_secs = config.ldap_sync.check_interval_secs;
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(check_interval_secs));
+            loop {
+                interval.tick().await;
+                match sync_service
+                    .sync_due_connectors(ldap_authenticator.as_ref(), chrono::Utc::now())
+                    .await
+                {
+                    Ok(0) => {}
+                    Ok(runs) => tracing::info!(runs, "Ran scheduled LDAP syncs"),
+                    Err(e) => tracing::warn!("Scheduled LDAP sync check failed: {}", e),
+                }
+            }
+        });
+    }
+
     // Build HTTP router with all features and rate limiting
+    #[cfg(unix)]
+    let rotating_jwt = state.jwt_manager.clone();
     let app = build_full_router(state, rate_limit_state, captcha_state, prom_handle.clone());
 
+    // Re-read JWT signing keys from the external secrets provider on SIGHUP
+    #[cfg(unix)]
+    if let Some(provider) = crate::config::secrets::provider_from_env()? {
+        let jwt_config = config.jwt.clone();
+        tokio::spawn(async move {
+            let mut hangup =
+                match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+                    Ok(signal) => signal,
+                    Err(e) => {
+                        tracing::error!("Failed to install SIGHUP handler: {}", e);
+                        return;
+                    }
+                };
+            while hangup.recv().await.is_some() {
+                let rotated = match provider.fetch().await {
+                    Ok(values) => values.apply_to_jwt(&jwt_config),
+                    Err(e) => {
+                        tracing::error!(
+                            provider = provider.name(),
+                            "Failed to re-read secrets: {}",
+                            e
+                        );
+                        continue;
+                    }
+                };
+                match rotating_jwt.rotate_keys(&rotated) {
+                    Ok(()) => info!(provider = provider.name(), "Rotated JWT signing keys"),
+                    Err(e) => tracing::error!("Rejected rotated JWT keys: {}", e),
+                }
+            }
+        });
+    }
+
     // Start background metrics tasks (DB pool + business gauges)
     if prom_handle.is_some() {
         let pool_clone = db_pool.clone();
@@ -1046,7 +1762,6 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
     }
 
     // Get addresses
-    let http_addr = config.http_addr();
     let grpc_addr = config.grpc_addr();
 
     // Log security configuration warnings
@@ -1076,8 +1791,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
 
     // Log server resource limits
     info!(
-        "Server resource limits: body={}KB, concurrency={}, timeout={}s",
+        "Server resource limits: body={}KB, bulk body={}KB, concurrency={}, timeout={}s",
         config.server.body_limit_bytes / 1024,
+        config.server.bulk_body_limit_bytes / 1024,
         config.server.concurrency_limit,
         config.server.request_timeout_secs
     );
@@ -1089,18 +1805,9 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         config.database.idle_timeout_secs
     );
 
-    // Run HTTP and gRPC servers concurrently
-    let http_server = async {
-        let listener = TcpListener::bind(&http_addr).await?;
-        info!("HTTP server started on {}", http_addr);
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
-        )
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
-        Ok::<_, anyhow::Error>(())
-    };
+    // Hand HTTP traffic to the application
+    gate.install(app);
+    info!("HTTP server started on {}", config.http_addr());
 
     // Create gRPC authentication interceptor based on config
     let grpc_auth_interceptor = create_grpc_auth_interceptor(&config)?;
@@ -1188,16 +1895,46 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
                 .add_service(reflection_service)
                 .add_service(TokenExchangeServer::with_interceptor(
                     grpc_service,
-                    grpc_auth_interceptor,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_service(LoginTelemetryServer::with_interceptor(
+                    login_telemetry_service,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_service(RevocationFeedServer::with_interceptor(
+                    revocation_watch_service,
+                    grpc_auth_interceptor.clone(),
                 ))
+                .add_service(PermissionReplicationServer::with_interceptor(
+                    permission_replication_service,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_optional_service(provisioning_service.map(|service| {
+                    ProvisioningServer::with_interceptor(service, grpc_auth_interceptor)
+                }))
                 .serve_with_shutdown(addr, shutdown_signal())
                 .await?;
         } else {
             server_builder
                 .add_service(TokenExchangeServer::with_interceptor(
                     grpc_service,
-                    grpc_auth_interceptor,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_service(LoginTelemetryServer::with_interceptor(
+                    login_telemetry_service,
+                    grpc_auth_interceptor.clone(),
                 ))
+                .add_service(RevocationFeedServer::with_interceptor(
+                    revocation_watch_service,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_service(PermissionReplicationServer::with_interceptor(
+                    permission_replication_service,
+                    grpc_auth_interceptor.clone(),
+                ))
+                .add_optional_service(provisioning_service.map(|service| {
+                    ProvisioningServer::with_interceptor(service, grpc_auth_interceptor)
+                }))
                 .serve_with_shutdown(addr, shutdown_signal())
                 .await?;
         }
@@ -1205,9 +1942,7 @@ pub async fn run(config: Config, prometheus_handle: Option<PrometheusHandle>) ->
         Ok::<_, anyhow::Error>(())
     };
 
-    tokio::try_join!(http_server, grpc_server)?;
-
-    Ok(())
+    grpc_server.await
 }
 
 /// Create gRPC authentication interceptor based on configuration
@@ -1288,7 +2023,10 @@ async fn shutdown_signal() {
 }
 
 const CORS_ALLOW_METHODS: &str = "GET,POST,PUT,DELETE,PATCH,OPTIONS";
-const CORS_ALLOW_HEADERS: &str = "authorization,content-type,accept,origin,x-tenant-id,x-api-key";
+const CORS_ALLOW_HEADERS: &str =
+    "authorization,content-type,accept,origin,x-tenant-id,x-api-key,if-match";
+/// Response headers readable by browser clients (ETag for optimistic concurrency)
+const CORS_EXPOSE_HEADERS: &str = "etag";
 
 /// Custom CORS middleware service that only returns CORS headers when the origin matches.
 #[derive(Clone)]
@@ -1360,6 +2098,10 @@ where
                     );
                 }
             }
+            headers.insert(
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::from_static(CORS_EXPOSE_HEADERS),
+            );
             headers.insert(header::VARY, HeaderValue::from_static("origin"));
         };
 
@@ -1451,18 +2193,37 @@ where
 
     // Create auth middleware state with cache for token blacklist checking
     let auth_state = AuthMiddlewareState::new(HasServices::jwt_manager(&state).clone())
-        .with_cache(std::sync::Arc::new(state.cache().clone()));
+        .with_cache(std::sync::Arc::new(state.cache().clone()))
+        .with_failure_policy(state.config().dependency_policy.auth)
+        .with_session_activity(state.config().session_activity.precision_secs)
+        .with_validation_policies(Arc::new(
+            crate::domains::authorization::service::TokenValidationPolicies::from_pool(
+                state.db_pool().clone(),
+                &state.config().token_validation,
+            ),
+        ));
+
+    // Nonce/timestamp replay protection for signed public endpoints and SCIM
+    let replay_state = crate::middleware::ReplayProtectionState::new(
+        state.config().replay_protection.clone(),
+        std::sync::Arc::new(state.cache().clone()),
+    )
+    .with_failure_policy(state.config().dependency_policy.auth);
 
     // ============================================================
     // SCIM PROTOCOL ROUTES (Bearer Token auth, separate from JWT)
     // ============================================================
     let scim_state = state.clone();
-    let scim_protocol_routes = domains::provisioning::routes::scim_routes::<S>().layer(
-        axum::middleware::from_fn_with_state(
+    let scim_protocol_routes = domains::provisioning::routes::scim_routes::<S>()
+        // Runs after SCIM auth so connectors are keyed by connector ID
+        .layer(axum::middleware::from_fn_with_state(
+            replay_state.clone(),
+            crate::middleware::replay_protection_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
             scim_state,
             crate::middleware::scim_auth::scim_auth_middleware::<S>,
-        ),
-    );
+        ));
 
     // ============================================================
     // PUBLIC ROUTES (no authentication required)
@@ -1473,6 +2234,12 @@ where
         .merge(domains::platform::routes::public_routes::<S>())
         .merge(domains::integration::routes::public_routes::<S>())
         .merge(domains::tenant_access::routes::public_routes::<S>())
+        .merge(domains::authorization::routes::public_routes::<S>())
+        // Replay protection on signed webhook endpoints
+        .layer(axum::middleware::from_fn_with_state(
+            replay_state,
+            crate::middleware::replay_protection_middleware,
+        ))
         // CAPTCHA verification on protected public endpoints (login, register, etc.)
         .layer(crate::middleware::CaptchaLayer {
             state: captcha_state,
@@ -1489,6 +2256,16 @@ where
         .merge(domains::integration::routes::protected_routes::<S>())
         .merge(domains::security_observability::routes::protected_routes::<S>())
         .merge(domains::provisioning::routes::protected_routes::<S>())
+        // Mask personal data for tenant roles without the unmasking permission
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::response_masking_middleware::<S>,
+        ))
+        // Scoped platform admin permissions (runs after authentication)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::admin_scope_middleware::<S>,
+        ))
         // Apply authentication middleware to all protected routes
         .layer(axum::middleware::from_fn_with_state(
             auth_state,
@@ -1528,8 +2305,23 @@ where
         )
         .with_state(effective_metrics_state);
 
+    // Host-header routing for tenant custom domains
+    let custom_domain_state =
+        crate::middleware::CustomDomainRoutingState::new(state.db_pool().clone(), state.config());
+
     // Server resource limits
-    let body_limit = state.config().server.body_limit_bytes;
+    let body_limit_state = crate::middleware::BodyLimitState::from_config(&state.config().server);
+    let body_limit = body_limit_state.max_limit();
+    let compression_enabled = state.config().server.compression_enabled;
+    let compression = CompressionLayer::new()
+        .gzip(compression_enabled)
+        .br(compression_enabled)
+        .compress_when(
+            SizeAbove::new(state.config().server.compression_min_bytes)
+                .and(tower_http::compression::predicate::NotForContentType::GRPC)
+                .and(tower_http::compression::predicate::NotForContentType::IMAGES)
+                .and(tower_http::compression::predicate::NotForContentType::SSE),
+        );
     let concurrency_limit = state.config().server.concurrency_limit;
     let request_timeout = Duration::from_secs(state.config().server.request_timeout_secs);
 
@@ -1537,7 +2329,7 @@ where
     // COMBINE ROUTES AND APPLY GLOBAL MIDDLEWARE
     // ============================================================
     // Layers are applied bottom-to-top: the last `.layer()` call is the outermost.
-    Router::new()
+    let router = Router::new()
         .merge(public_routes)
         .merge(protected_routes)
         .merge(scim_protocol_routes)
@@ -1554,19 +2346,48 @@ where
         .layer(axum::middleware::from_fn(
             crate::middleware::path_guard::path_guard_middleware,
         ))
-        // 1. Body size limit - reject oversized request bodies (prevents OOM)
+        // 0c. Custom domain routing - tag requests on a verified tenant domain
+        //     with the owning tenant (request extension)
+        .layer(axum::middleware::from_fn_with_state(
+            custom_domain_state,
+            crate::middleware::custom_domain_middleware,
+        ))
+        // 0d. Deprecation signalling - Deprecation/Sunset headers and per-client
+        //     usage metrics for deprecated endpoints and fields
+        .layer(axum::middleware::from_fn(
+            crate::middleware::deprecation_middleware,
+        ))
+        // 1. Body size limit - reject oversized request bodies (prevents OOM).
+        //    The middleware enforces the per-route limit; DefaultBodyLimit only
+        //    raises the extractor ceiling to the largest route limit.
+        .layer(axum::middleware::from_fn_with_state(
+            body_limit_state,
+            crate::middleware::body_limit_middleware,
+        ))
         .layer(DefaultBodyLimit::max(body_limit))
+        // 1b. Request decompression - decode gzip/br request bodies (outside the
+        //     body limit so the limit applies to the decoded size)
+        .layer(RequestDecompressionLayer::new())
         // 2. Error response normalization - consistent JSON error format
         //    (must be inner to security headers so headers are added AFTER
         //     normalization potentially replaces the response object)
         .layer(axum::middleware::from_fn(
             crate::middleware::normalize_error_response,
         ))
+        // 2b. Error localization - translate error messages for the
+        //     Accept-Language locale (outside normalization so framework
+        //     rejections are translated too)
+        .layer(axum::middleware::from_fn(
+            crate::middleware::localize_error_response,
+        ))
         // 3. Security headers - adds security headers to all responses
         .layer(axum::middleware::from_fn_with_state(
             security_headers_config,
             security_headers_middleware,
         ))
+        // 3b. Response compression - gzip/br per Accept-Encoding (outside the
+        //     error normalization and localization layers, which read bodies)
+        .layer(compression)
         // 4. Tracing - for request logging (with sensitive query param sanitization)
         .layer(
             TraceLayer::new_for_http().make_span_with(crate::middleware::trace::SanitizedMakeSpan),
@@ -1585,7 +2406,14 @@ where
         .layer(axum::middleware::from_fn_with_state(
             rate_limit_state,
             rate_limit_middleware,
-        ))
+        ));
+    // 7b. Fault injection - scope X-Auth9-Fault header faults to the request
+    //     (outside rate limiting so its Redis calls see them too)
+    #[cfg(feature = "fault-injection")]
+    let router = router.layer(axum::middleware::from_fn(
+        crate::fault_injection::fault_injection_middleware,
+    ));
+    router
         // 8. Concurrency limit with load shedding - returns 503 when at capacity.
         //    HandleErrorLayer converts tower BoxError → HTTP response.
         //    load_shed() rejects immediately when inner service is not ready.
@@ -0,0 +1,263 @@
+//! Startup readiness gate
+//!
+//! The HTTP port is bound before the database and Redis are reachable, so
+//! liveness probes pass while a slow dependency is still coming up instead of
+//! the pod being restarted in a loop. Until the full application is
+//! installed the gate answers the health probes itself — live, but not ready
+//! — and rejects every other request with 503.
+
+use crate::config::StartupConfig;
+use crate::domains::security_observability::api::health;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tower::ServiceExt;
+use tracing::info;
+
+/// Connection progress of one dependency
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyState {
+    pub ready: bool,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StartupStatus {
+    status: &'static str,
+    dependencies: BTreeMap<&'static str, DependencyState>,
+}
+
+/// Serves health probes while the server starts and hands requests to the
+/// application once it is installed
+#[derive(Clone, Default)]
+pub struct StartupGate {
+    app: Arc<OnceLock<Router>>,
+    dependencies: Arc<Mutex<BTreeMap<&'static str, DependencyState>>>,
+}
+
+impl StartupGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Router to serve from the start; forwards to the application once
+    /// installed
+    pub fn router(&self) -> Router {
+        let gate = self.clone();
+        Router::new().fallback(move |request: Request| {
+            let gate = gate.clone();
+            async move { gate.handle(request).await }
+        })
+    }
+
+    /// Start sending requests to the fully initialized application
+    pub fn install(&self, app: Router) {
+        if self.app.set(app).is_err() {
+            tracing::warn!("Application router installed twice; keeping the first");
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.app.get().is_some()
+    }
+
+    /// Call `connect` until it succeeds, backing off exponentially between
+    /// attempts. Fails once `config.timeout_secs` have passed (if set).
+    pub async fn connect<T, E, F, Fut>(
+        &self,
+        dependency: &'static str,
+        config: &StartupConfig,
+        connect: F,
+    ) -> anyhow::Result<T>
+    where
+        E: Display,
+        F: Fn() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        let started = Instant::now();
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let max_delay = Duration::from_secs(config.retry_max_secs);
+        let mut delay = Duration::from_millis(config.retry_initial_ms).min(max_delay);
+
+        loop {
+            match connect().await {
+                Ok(value) => {
+                    self.update(dependency, |state| {
+                        state.ready = true;
+                        state.attempts += 1;
+                        state.last_error = None;
+                    });
+                    info!(dependency, "Connected to {}", dependency);
+                    return Ok(value);
+                }
+                Err(e) => {
+                    let message = e.to_string();
+                    self.update(dependency, |state| {
+                        state.attempts += 1;
+                        state.last_error = Some(message.clone());
+                    });
+                    if config.timeout_secs > 0 && started.elapsed() + delay > timeout {
+                        anyhow::bail!(
+                            "{} not reachable after {}s: {}",
+                            dependency,
+                            config.timeout_secs,
+                            message
+                        );
+                    }
+                    tracing::warn!(
+                        dependency,
+                        retry_in_ms = delay.as_millis() as u64,
+                        error = %message,
+                        "Dependency not reachable yet, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(max_delay);
+                }
+            }
+        }
+    }
+
+    fn update(&self, dependency: &'static str, apply: impl FnOnce(&mut DependencyState)) {
+        if let Ok(mut dependencies) = self.dependencies.lock() {
+            apply(dependencies.entry(dependency).or_default());
+        }
+    }
+
+    fn status(&self) -> StartupStatus {
+        StartupStatus {
+            status: "starting",
+            dependencies: self
+                .dependencies
+                .lock()
+                .map(|dependencies| dependencies.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn handle(&self, request: Request) -> Response {
+        if let Some(app) = self.app.get() {
+            return match app.clone().oneshot(request).await {
+                Ok(response) => response,
+                Err(never) => match never {},
+            };
+        }
+        match request.uri().path() {
+            "/health" | "/health/live" => health::health().await.into_response(),
+            "/ready" | "/health/ready" => {
+                (StatusCode::SERVICE_UNAVAILABLE, Json(self.status())).into_response()
+            }
+            _ => Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(header::RETRY_AFTER, "5")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r#"{"error":"service_unavailable","message":"Server is starting"}"#,
+                ))
+                .unwrap_or_else(|_| StatusCode::SERVICE_UNAVAILABLE.into_response()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+
+    fn fast() -> StartupConfig {
+        StartupConfig {
+            retry_initial_ms: 1,
+            retry_max_secs: 1,
+            timeout_secs: 0,
+        }
+    }
+
+    async fn get_status(router: &Router, path: &str) -> StatusCode {
+        router
+            .clone()
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_live_but_not_ready_while_starting() {
+        let router = StartupGate::new().router();
+        assert_eq!(get_status(&router, "/health/live").await, StatusCode::OK);
+        assert_eq!(get_status(&router, "/health").await, StatusCode::OK);
+        assert_eq!(
+            get_status(&router, "/health/ready").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            get_status(&router, "/api/v1/tenants").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forwards_to_installed_app() {
+        let gate = StartupGate::new();
+        let router = gate.router();
+        gate.install(Router::new().route("/ready", get(|| async { "ready" })));
+        assert!(gate.is_started());
+        assert_eq!(get_status(&router, "/ready").await, StatusCode::OK);
+        assert_eq!(get_status(&router, "/missing").await, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_connect_retries_until_success() {
+        let gate = StartupGate::new();
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let value = gate
+            .connect("database", &fast(), || {
+                let counter = counter.clone();
+                async move {
+                    let mut calls = counter.lock().unwrap();
+                    *calls += 1;
+                    if *calls < 3 {
+                        Err("connection refused")
+                    } else {
+                        Ok(42)
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 42);
+        let status = gate.status();
+        let database = &status.dependencies["database"];
+        assert!(database.ready);
+        assert_eq!(database.attempts, 3);
+        assert!(database.last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connect_gives_up_after_timeout() {
+        let gate = StartupGate::new();
+        let config = StartupConfig {
+            retry_initial_ms: 2000,
+            retry_max_secs: 2,
+            timeout_secs: 1,
+        };
+        let result: anyhow::Result<()> = gate
+            .connect("redis", &config, || async { Err("connection refused") })
+            .await;
+        assert!(result.unwrap_err().to_string().contains("redis"));
+        let status = gate.status();
+        assert!(!status.dependencies["redis"].ready);
+    }
+}
@@ -6,9 +6,10 @@
 
 use crate::cache::CacheOperations;
 use crate::config::Config;
-use crate::domains::authorization::service::{ClientService, RbacService};
+use crate::domains::authorization::service::{ClientService, RbacService, ScopeCatalogService};
 use crate::domains::identity::service::{
-    EmailVerificationService, IdentityProviderService, PasswordService, RequiredActionService,
+    AccountDeletionService, EmailVerificationService, IdentityProviderService,
+    LoginIdentifierService, PasswordDenyListService, PasswordService, RequiredActionService,
     SessionService, WebAuthnService,
 };
 use crate::domains::integration::service::{ActionService, WebhookService};
@@ -16,9 +17,11 @@ use crate::domains::platform::service::{
     BrandingService, EmailService, EmailTemplateService, SystemSettingsService,
 };
 use crate::domains::provisioning::service::{ScimService, ScimTokenService};
-use crate::domains::security_observability::service::{AnalyticsService, SecurityDetectionService};
+use crate::domains::security_observability::service::{
+    AnalyticsService, LoginForensicsService, SecurityDetectionService,
+};
 use crate::domains::tenant_access::service::{
-    InvitationService, SamlApplicationService, TenantService, UserService,
+    AdminUnitService, InvitationService, SamlApplicationService, TenantService, UserService,
 };
 use crate::identity_engine::IdentityEngine;
 use crate::jwt::JwtManager;
@@ -27,11 +30,12 @@ use crate::repository::scim_group_mapping::ScimGroupRoleMappingRepository;
 use crate::repository::scim_log::ScimProvisioningLogRepository;
 use crate::repository::scim_token::ScimTokenRepository;
 use crate::repository::{
-    ActionRepository, InvitationRepository, LinkedIdentityRepository, LoginEventRepository,
-    MaliciousIpBlacklistRepository, PasswordResetRepository, RbacRepository,
-    SamlApplicationRepository, SecurityAlertRepository, ServiceBrandingRepository,
-    ServiceRepository, SessionRepository, SystemSettingsRepository, TenantRepository,
-    UserRepository, WebhookRepository,
+    AccountDeletionRepository, ActionRepository, AdminUnitRepository, InvitationRepository,
+    LinkedIdentityRepository, LoginEventRepository, LoginForensicsRepository,
+    MaliciousIpBlacklistRepository, PasswordBreachCheckRepository, PasswordResetRepository,
+    RbacRepository, SamlApplicationRepository, SecurityAlertRepository, ServiceBrandingRepository,
+    ServiceRepository, ServiceScopeRepository, SessionRepository, SystemSettingsRepository,
+    TenantRepository, UserRepository, WebhookRepository,
 };
 
 // ============================================================
@@ -97,6 +101,14 @@ pub trait HasServices: Clone + Send + Sync + 'static {
     type ActionRepo: ActionRepository;
     /// The SAML application repository type
     type SamlApplicationRepo: SamlApplicationRepository;
+    /// The admin unit repository type (delegated admin boundaries)
+    type AdminUnitRepo: AdminUnitRepository;
+    /// The account deletion request repository type
+    type AccountDeletionRepo: AccountDeletionRepository;
+    /// The failed sign-in capture repository type
+    type LoginForensicsRepo: LoginForensicsRepository;
+    /// The per-service OAuth scope catalog repository type
+    type ServiceScopeRepo: ServiceScopeRepository;
 
     /// Get the application configuration
     fn config(&self) -> &Config;
@@ -113,6 +125,9 @@ pub trait HasServices: Clone + Send + Sync + 'static {
     /// Get the RBAC service
     fn rbac_service(&self) -> &RbacService<Self::RbacRepo>;
 
+    /// Get the per-service OAuth scope catalog
+    fn scope_catalog_service(&self) -> &ScopeCatalogService<Self::ServiceScopeRepo>;
+
     /// Get the audit repository
     fn audit_repo(&self) -> &Self::AuditRepo;
 
@@ -128,6 +143,24 @@ pub trait HasServices: Clone + Send + Sync + 'static {
     /// Get the SAML application service
     fn saml_application_service(&self) -> &SamlApplicationService<Self::SamlApplicationRepo>;
 
+    /// Get the admin unit service
+    fn admin_unit_service(&self) -> &AdminUnitService<Self::AdminUnitRepo>;
+
+    /// Get the username and phone number login identifier service
+    fn login_identifier_service(&self) -> &LoginIdentifierService;
+
+    /// Get the password breach campaign check repository
+    fn password_breach_check_repo(&self) -> &std::sync::Arc<dyn PasswordBreachCheckRepository>;
+
+    /// Get the deferred account deletion service
+    fn account_deletion_service(&self) -> &AccountDeletionService<Self::AccountDeletionRepo>;
+
+    /// Get the tenant password deny list service
+    fn password_deny_list_service(&self) -> &PasswordDenyListService;
+
+    /// Get the failed sign-in forensics service
+    fn login_forensics_service(&self) -> &LoginForensicsService<Self::LoginForensicsRepo>;
+
     /// Check if the system is ready (database and cache are healthy)
     /// Returns (db_ok, cache_ok) tuple
     fn check_ready(&self) -> impl std::future::Future<Output = (bool, bool)> + Send;
@@ -149,6 +182,12 @@ pub trait HasServices: Clone + Send + Sync + 'static {
     fn maybe_cache(&self) -> Option<&dyn CacheOperations> {
         None
     }
+
+    /// Optional store for saga state. Without one, sagas still compensate
+    /// failed steps but their progress is not persisted for operators.
+    fn saga_store(&self) -> Option<std::sync::Arc<dyn crate::repository::SagaRepository>> {
+        None
+    }
 }
 
 /// Trait for states that provide system settings and email services
@@ -0,0 +1,278 @@
+//! Trace exemplars for latency histograms
+//!
+//! Latency recorded through [`record_latency`] inside a sampled trace keeps the
+//! trace ID of the latest observation per histogram bucket. When `/metrics` is
+//! scraped with `Accept: application/openmetrics-text`, the Prometheus output
+//! is converted to OpenMetrics and those trace IDs are attached to the bucket
+//! lines, so a latency spike on a dashboard links straight to a trace.
+
+use super::metrics::LATENCY_BUCKETS;
+use metrics::Label;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Histograms recorded through [`record_latency`]
+pub const EXEMPLAR_METRICS: &[&str] = &[
+    "auth9_http_request_duration_seconds",
+    "auth9_grpc_request_duration_seconds",
+    "auth9_hosted_login_duration_seconds",
+];
+
+/// Content type of the OpenMetrics exposition format
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Upper bound on stored exemplars; label cardinality is already bounded, this
+/// only guards against a runaway label
+const MAX_EXEMPLARS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Exemplar {
+    trace_id: String,
+    value: f64,
+    timestamp: f64,
+}
+
+/// Key of one histogram series bucket: metric name, canonical label set
+/// (sorted, without `le`) and bucket index (`LATENCY_BUCKETS.len()` = +Inf)
+type ExemplarKey = (String, String, usize);
+
+#[derive(Default)]
+struct ExemplarStore {
+    exemplars: Mutex<HashMap<ExemplarKey, Exemplar>>,
+}
+
+impl ExemplarStore {
+    fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64, trace_id: String) {
+        let key = (
+            name.to_string(),
+            canonical_labels(labels.iter().copied()),
+            bucket_index(value),
+        );
+        let exemplar = Exemplar {
+            trace_id,
+            value,
+            timestamp: chrono::Utc::now().timestamp_millis() as f64 / 1000.0,
+        };
+        let mut exemplars = self.exemplars.lock().unwrap_or_else(|e| e.into_inner());
+        if exemplars.len() >= MAX_EXEMPLARS && !exemplars.contains_key(&key) {
+            return;
+        }
+        exemplars.insert(key, exemplar);
+    }
+
+    fn get(&self, name: &str, labels: &str, bucket: usize) -> Option<Exemplar> {
+        self.exemplars
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(name.to_string(), labels.to_string(), bucket))
+            .cloned()
+    }
+}
+
+fn store() -> &'static ExemplarStore {
+    static STORE: OnceLock<ExemplarStore> = OnceLock::new();
+    STORE.get_or_init(ExemplarStore::default)
+}
+
+/// Record a latency observation in seconds, keeping the current trace ID as
+/// the exemplar of its bucket when the request is traced
+pub fn record_latency(name: &'static str, labels: &[(&'static str, String)], seconds: f64) {
+    if let Some(trace_id) = current_trace_id() {
+        let pairs: Vec<(&str, &str)> = labels.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        store().observe(name, &pairs, seconds, trace_id);
+    }
+    let labels: Vec<Label> = labels
+        .iter()
+        .map(|(k, v)| Label::new(*k, v.clone()))
+        .collect();
+    metrics::histogram!(name, labels).record(seconds);
+}
+
+/// Trace ID of the current span, if it belongs to a sampled trace
+fn current_trace_id() -> Option<String> {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = tracing::Span::current().context();
+    let span = context.span();
+    let span_context = span.span_context();
+    (span_context.is_valid() && span_context.is_sampled())
+        .then(|| span_context.trace_id().to_string())
+}
+
+fn bucket_index(value: f64) -> usize {
+    LATENCY_BUCKETS
+        .iter()
+        .position(|bound| value <= *bound)
+        .unwrap_or(LATENCY_BUCKETS.len())
+}
+
+fn canonical_labels<'a>(labels: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut labels: Vec<(&str, &str)> = labels.filter(|(k, _)| *k != "le").collect();
+    labels.sort();
+    labels
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("\u{1f}")
+}
+
+/// Convert Prometheus text output to OpenMetrics with exemplars on latency
+/// histogram buckets
+pub fn render_openmetrics(prometheus_text: &str) -> String {
+    render_with_store(prometheus_text, store())
+}
+
+fn render_with_store(prometheus_text: &str, store: &ExemplarStore) -> String {
+    // OpenMetrics names counter families without the `_total` suffix
+    let counters: Vec<&str> = prometheus_text
+        .lines()
+        .filter_map(|line| line.strip_prefix("# TYPE "))
+        .filter_map(|rest| rest.strip_suffix(" counter"))
+        .filter(|name| name.ends_with("_total"))
+        .collect();
+
+    let mut out = String::with_capacity(prometheus_text.len() + 64);
+    for line in prometheus_text.lines() {
+        if let Some(line) = rewrite_counter_metadata(line, &counters) {
+            out.push_str(&line);
+        } else {
+            out.push_str(line);
+            if let Some(exemplar) = bucket_exemplar(line, store) {
+                out.push_str(&format!(
+                    " # {{trace_id=\"{}\"}} {} {:.3}",
+                    exemplar.trace_id, exemplar.value, exemplar.timestamp
+                ));
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn rewrite_counter_metadata(line: &str, counters: &[&str]) -> Option<String> {
+    for prefix in ["# TYPE ", "# HELP "] {
+        if let Some(rest) = line.strip_prefix(prefix) {
+            let (name, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+            if counters.contains(&name) {
+                let family = name.trim_end_matches("_total");
+                return Some(format!("{}{} {}", prefix, family, tail));
+            }
+        }
+    }
+    None
+}
+
+fn bucket_exemplar(line: &str, store: &ExemplarStore) -> Option<Exemplar> {
+    let (series, _) = line.split_once(' ')?;
+    let (name, labels) = series.split_once('{')?;
+    let metric = name.strip_suffix("_bucket")?;
+    if !EXEMPLAR_METRICS.contains(&metric) {
+        return None;
+    }
+    let labels = parse_labels(labels.strip_suffix('}')?)?;
+    let le = labels.iter().find(|(k, _)| k == "le")?.1.as_str();
+    let bucket = if le == "+Inf" {
+        LATENCY_BUCKETS.len()
+    } else {
+        let bound: f64 = le.parse().ok()?;
+        LATENCY_BUCKETS.iter().position(|b| *b == bound)?
+    };
+    let canonical = canonical_labels(labels.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    store.get(metric, &canonical, bucket)
+}
+
+/// Parse `a="x",b="y"` with Prometheus escaping into unescaped pairs
+fn parse_labels(input: &str) -> Option<Vec<(String, String)>> {
+    let mut labels = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let (key, after) = rest.split_once("=\"")?;
+        let mut value = String::new();
+        let mut chars = after.char_indices();
+        let end = loop {
+            let (i, c) = chars.next()?;
+            match c {
+                '\\' => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                '"' => break i,
+                c => value.push(c),
+            }
+        };
+        labels.push((key.to_string(), value));
+        rest = after[end + 1..].trim_start_matches(',');
+    }
+    Some(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "\
+# HELP auth9_http_requests_total Total number of HTTP requests
+# TYPE auth9_http_requests_total counter
+auth9_http_requests_total{method=\"GET\",path=\"/health\",status=\"200\"} 3
+# TYPE auth9_http_request_duration_seconds histogram
+auth9_http_request_duration_seconds_bucket{method=\"GET\",path=\"/health\",le=\"0.001\"} 0
+auth9_http_request_duration_seconds_bucket{method=\"GET\",path=\"/health\",le=\"0.005\"} 3
+auth9_http_request_duration_seconds_bucket{method=\"GET\",path=\"/health\",le=\"+Inf\"} 3
+auth9_http_request_duration_seconds_sum{method=\"GET\",path=\"/health\"} 0.009
+";
+
+    #[test]
+    fn test_render_attaches_exemplar_to_matching_bucket() {
+        let store = ExemplarStore::default();
+        store.observe(
+            "auth9_http_request_duration_seconds",
+            &[("path", "/health"), ("method", "GET")],
+            0.003,
+            "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+        );
+
+        let rendered = render_with_store(TEXT, &store);
+        let bucket = rendered
+            .lines()
+            .find(|l| l.contains("le=\"0.005\""))
+            .unwrap();
+        assert!(bucket.contains("3 # {trace_id=\"4bf92f3577b34da6a3ce929d0e0e4736\"} 0.003 "));
+        let other = rendered
+            .lines()
+            .find(|l| l.contains("le=\"0.001\""))
+            .unwrap();
+        assert!(!other.contains('#'));
+        assert!(rendered.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_render_renames_counter_families() {
+        let rendered = render_with_store(TEXT, &ExemplarStore::default());
+        assert!(rendered.contains("# TYPE auth9_http_requests counter"));
+        assert!(rendered.contains("# HELP auth9_http_requests Total number of HTTP requests"));
+        assert!(rendered.contains("auth9_http_requests_total{method=\"GET\""));
+    }
+
+    #[test]
+    fn test_bucket_index() {
+        assert_eq!(bucket_index(0.0005), 0);
+        assert_eq!(bucket_index(0.001), 0);
+        assert_eq!(bucket_index(0.003), 1);
+        assert_eq!(bucket_index(60.0), LATENCY_BUCKETS.len());
+    }
+
+    #[test]
+    fn test_parse_labels_unescapes_values() {
+        let labels = parse_labels(r#"path="/a\"b",le="0.1""#).unwrap();
+        assert_eq!(labels[0], ("path".to_string(), "/a\"b".to_string()));
+        assert_eq!(labels[1], ("le".to_string(), "0.1".to_string()));
+    }
+
+    #[test]
+    fn test_no_trace_id_outside_traced_span() {
+        assert!(current_trace_id().is_none());
+    }
+}
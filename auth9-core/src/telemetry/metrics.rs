@@ -2,115 +2,630 @@
 
 use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::config::FailurePolicy;
+
+/// Default histogram buckets (seconds) for HTTP/gRPC/Redis latency metrics.
+/// These match common Prometheus defaults plus sub-millisecond buckets for fast endpoints.
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
 
 /// Install the Prometheus recorder and return a handle for rendering metrics.
 pub fn install_prometheus_recorder() -> PrometheusHandle {
-    // Default histogram buckets (seconds) for HTTP/gRPC/Redis latency metrics.
-    // These match common Prometheus defaults plus sub-millisecond buckets for fast endpoints.
-    let buckets = vec![
-        0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
-    ];
-
     PrometheusBuilder::new()
-        .set_buckets(&buckets)
+        .set_buckets(LATENCY_BUCKETS)
         .expect("failed to set histogram buckets")
         .install_recorder()
         .expect("failed to install Prometheus recorder")
 }
 
-/// Register metric descriptions and emit initial zero values so Prometheus output
-/// includes HELP/TYPE lines for all metrics from startup (not just after first use).
-pub fn describe_metrics() {
+/// Kind of an emitted metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// A metric emitted by this service, as listed in the metrics catalog
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MetricDescriptor {
+    #[schema(value_type = String)]
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: MetricKind,
+    /// Label names the metric is recorded with
+    #[schema(value_type = Vec<String>)]
+    pub labels: &'static [&'static str],
+    #[schema(value_type = String)]
+    pub description: &'static str,
+}
+
+const fn metric(
+    name: &'static str,
+    kind: MetricKind,
+    labels: &'static [&'static str],
+    description: &'static str,
+) -> MetricDescriptor {
+    MetricDescriptor {
+        name,
+        kind,
+        labels,
+        description,
+    }
+}
+
+/// Every metric this service emits. `describe_metrics` registers HELP text
+/// from this table and `GET /api/v1/admin/metrics/catalog` serves it, so a
+/// new metric must be added here.
+pub const METRIC_CATALOG: &[MetricDescriptor] = &[
     // HTTP metrics
-    describe_counter!("auth9_http_requests_total", "Total number of HTTP requests");
-    describe_histogram!(
+    metric(
+        "auth9_http_requests_total",
+        MetricKind::Counter,
+        &["method", "path", "status"],
+        "Total number of HTTP requests",
+    ),
+    metric(
         "auth9_http_request_duration_seconds",
-        "HTTP request duration in seconds"
-    );
-    describe_gauge!(
+        MetricKind::Histogram,
+        &["method", "path"],
+        "HTTP request duration in seconds",
+    ),
+    metric(
         "auth9_http_requests_in_flight",
-        "Number of HTTP requests currently being processed"
-    );
-
+        MetricKind::Gauge,
+        &[],
+        "Number of HTTP requests currently being processed",
+    ),
     // gRPC metrics
-    describe_counter!("auth9_grpc_requests_total", "Total number of gRPC requests");
-    describe_histogram!(
+    metric(
+        "auth9_grpc_requests_total",
+        MetricKind::Counter,
+        &["service", "method", "status"],
+        "Total number of gRPC requests",
+    ),
+    metric(
         "auth9_grpc_request_duration_seconds",
-        "gRPC request duration in seconds"
-    );
-
+        MetricKind::Histogram,
+        &["service", "method"],
+        "gRPC request duration in seconds",
+    ),
+    metric(
+        "auth9_grpc_rate_limit_exceeded_total",
+        MetricKind::Counter,
+        &["method"],
+        "gRPC requests rejected by the rate limiter",
+    ),
+    metric(
+        "auth9_grpc_idempotent_replays_total",
+        MetricKind::Counter,
+        &["method"],
+        "gRPC provisioning calls answered from a stored idempotent response",
+    ),
+    // Synthetic monitoring
+    metric(
+        "auth9_synthetic_check_stage_duration_seconds",
+        MetricKind::Histogram,
+        &["stage", "result"],
+        "Synthetic login check stage duration in seconds, by stage and result",
+    ),
+    // Session activity write-behind
+    metric(
+        "auth9_session_activity_flush_lag_seconds",
+        MetricKind::Histogram,
+        &[],
+        "Age of the oldest session activity marker at flush time",
+    ),
+    metric(
+        "auth9_session_activity_flushed_total",
+        MetricKind::Counter,
+        &[],
+        "Session activity markers written to the database",
+    ),
+    metric(
+        "auth9_session_activity_flush_errors_total",
+        MetricKind::Counter,
+        &[],
+        "Failed session activity flushes",
+    ),
     // Database pool metrics
-    describe_gauge!(
+    metric(
         "auth9_db_pool_connections_active",
-        "Number of active database connections"
-    );
-    describe_gauge!(
+        MetricKind::Gauge,
+        &[],
+        "Number of active database connections",
+    ),
+    metric(
         "auth9_db_pool_connections_idle",
-        "Number of idle database connections"
-    );
-
+        MetricKind::Gauge,
+        &[],
+        "Number of idle database connections",
+    ),
+    // SQL query metrics
+    metric(
+        "auth9_db_query_duration_seconds",
+        MetricKind::Histogram,
+        &["table", "operation"],
+        "SQL statement duration in seconds, by table and operation",
+    ),
+    metric(
+        "auth9_db_query_rows_total",
+        MetricKind::Counter,
+        &["table", "operation"],
+        "Rows returned or affected by SQL statements",
+    ),
+    metric(
+        "auth9_db_slow_queries_total",
+        MetricKind::Counter,
+        &["table", "operation"],
+        "SQL statements exceeding the slow query threshold",
+    ),
     // Redis metrics
-    describe_counter!(
+    metric(
         "auth9_redis_operations_total",
-        "Total number of Redis operations"
-    );
-    describe_histogram!(
+        MetricKind::Counter,
+        &["operation"],
+        "Total number of Redis operations",
+    ),
+    metric(
         "auth9_redis_operation_duration_seconds",
-        "Redis operation duration in seconds"
-    );
+        MetricKind::Histogram,
+        &["operation"],
+        "Redis operation duration in seconds",
+    ),
     // Auth metrics
-    describe_counter!("auth9_auth_login_total", "Total number of login attempts");
-    describe_histogram!(
+    metric(
+        "auth9_auth_login_total",
+        MetricKind::Counter,
+        &["result", "backend"],
+        "Total number of login attempts",
+    ),
+    metric(
         "auth9_hosted_login_duration_seconds",
-        "Hosted login password validation duration in seconds"
-    );
-    describe_counter!(
+        MetricKind::Histogram,
+        &["method"],
+        "Hosted login password validation duration in seconds",
+    ),
+    metric(
         "auth9_auth_token_exchange_total",
-        "Total number of token exchange requests"
-    );
-    describe_counter!(
+        MetricKind::Counter,
+        &["result"],
+        "Total number of token exchange requests",
+    ),
+    metric(
         "auth9_auth_token_validation_total",
-        "Total number of token validation requests"
-    );
-    describe_counter!(
+        MetricKind::Counter,
+        &["result"],
+        "Total number of token validation requests",
+    ),
+    metric(
+        "auth9_auth_code_replay_total",
+        MetricKind::Counter,
+        &[],
+        "Authorization codes presented again after being redeemed",
+    ),
+    metric(
         "auth9_auth_invalid_state_total",
-        "Total number of invalid OIDC callback state events"
-    );
-
+        MetricKind::Counter,
+        &["reason"],
+        "Total number of invalid OIDC callback state events",
+    ),
+    metric(
+        "auth9_social_login_total",
+        MetricKind::Counter,
+        &["action", "provider"],
+        "Social login redirects and callbacks, by provider",
+    ),
+    metric(
+        "auth9_enterprise_sso_total",
+        MetricKind::Counter,
+        &["action", "connector"],
+        "Enterprise SSO redirects and callbacks, by connector",
+    ),
+    metric(
+        "auth9_conditional_access_decisions_total",
+        MetricKind::Counter,
+        &["outcome"],
+        "Conditional access policy decisions at token issuance",
+    ),
+    metric(
+        "auth9_edge_login_events_total",
+        MetricKind::Counter,
+        &["outcome"],
+        "Login events reported by edge gateways",
+    ),
+    metric(
+        "auth9_edge_login_latency_seconds",
+        MetricKind::Histogram,
+        &["outcome"],
+        "Login latency reported by edge gateways in seconds",
+    ),
+    metric(
+        "auth9_workload_identity_exchanges_total",
+        MetricKind::Counter,
+        &["result"],
+        "Workload identity token exchanges by result",
+    ),
+    metric(
+        "auth9_session_limit_evictions_total",
+        MetricKind::Counter,
+        &[],
+        "Sessions revoked to make room under the per-user session limit",
+    ),
+    metric(
+        "auth9_session_limit_rejections_total",
+        MetricKind::Counter,
+        &["action"],
+        "Sign-ins rejected by the per-user session limit, by configured action",
+    ),
+    metric(
+        "auth9_auth_silent_total",
+        MetricKind::Counter,
+        &["result"],
+        "Silent (prompt=none) authentication attempts by result",
+    ),
     // Security metrics
-    describe_counter!(
+    metric(
         "auth9_security_alerts_total",
-        "Total number of security alerts"
-    );
-    describe_counter!(
+        MetricKind::Counter,
+        &["type", "severity"],
+        "Total number of security alerts",
+    ),
+    metric(
         "auth9_rate_limit_throttled_total",
-        "Total number of rate-limited requests"
-    );
-    describe_counter!(
+        MetricKind::Counter,
+        &["endpoint"],
+        "Total number of rate-limited requests",
+    ),
+    metric(
         "auth9_rate_limit_unavailable_total",
-        "Total number of requests fail-closed because rate-limit backend was unavailable"
-    );
-
+        MetricKind::Counter,
+        &["endpoint", "mode"],
+        "Total number of requests fail-closed because rate-limit backend was unavailable",
+    ),
+    metric(
+        "auth9_captcha_verified_total",
+        MetricKind::Counter,
+        &["endpoint"],
+        "CAPTCHA tokens verified successfully",
+    ),
+    metric(
+        "auth9_captcha_failed_total",
+        MetricKind::Counter,
+        &["endpoint"],
+        "CAPTCHA tokens rejected by the provider",
+    ),
+    metric(
+        "auth9_captcha_missing_total",
+        MetricKind::Counter,
+        &["endpoint"],
+        "Protected requests without a CAPTCHA token",
+    ),
+    metric(
+        "auth9_captcha_low_score_total",
+        MetricKind::Counter,
+        &[],
+        "CAPTCHA verifications below the score threshold",
+    ),
+    metric(
+        "auth9_degraded_operations_total",
+        MetricKind::Counter,
+        &["dependency", "operation", "policy"],
+        "Total number of operations handled under a dependency failure policy",
+    ),
+    metric(
+        "auth9_audit_integrity_verifications_total",
+        MetricKind::Counter,
+        &["result"],
+        "Audit log hash chain verifications by result (valid, tampered)",
+    ),
+    metric(
+        "auth9_response_masked_total",
+        MetricKind::Counter,
+        &[],
+        "API responses with PII fields masked for the caller",
+    ),
+    // Async jobs
+    metric(
+        "auth9_jobs_finished_total",
+        MetricKind::Counter,
+        &["kind", "status"],
+        "Total number of async jobs that reached a terminal status",
+    ),
+    metric(
+        "auth9_job_duration_seconds",
+        MetricKind::Histogram,
+        &["kind"],
+        "Async job run time from claim to completion",
+    ),
+    metric(
+        "auth9_inactivity_steps_total",
+        MetricKind::Counter,
+        &["step", "status"],
+        "Tenant inactivity policy steps (warn, lock, delete) by status",
+    ),
+    // Sagas
+    metric(
+        "auth9_saga_finished_total",
+        MetricKind::Counter,
+        &["kind", "status"],
+        "Total number of sagas that completed, were compensated or got stuck",
+    ),
+    // Domain events
+    metric(
+        "auth9_domain_events_total",
+        MetricKind::Counter,
+        &["event", "subscriber", "outcome"],
+        "Total number of domain events handled by each subscriber",
+    ),
+    metric(
+        "auth9_password_breach_checks_total",
+        MetricKind::Counter,
+        &["source", "status"],
+        "Breach campaign account checks by source (offline, login) and outcome",
+    ),
+    // Email delivery
+    metric(
+        "auth9_email_queue_enqueued_total",
+        MetricKind::Counter,
+        &[],
+        "Emails queued for asynchronous delivery",
+    ),
+    metric(
+        "auth9_email_queue_sent_total",
+        MetricKind::Counter,
+        &["provider"],
+        "Queued emails delivered, by provider",
+    ),
+    metric(
+        "auth9_email_queue_retried_total",
+        MetricKind::Counter,
+        &[],
+        "Queued email deliveries scheduled for retry",
+    ),
+    metric(
+        "auth9_email_queue_failed_total",
+        MetricKind::Counter,
+        &[],
+        "Queued emails that exhausted their retries",
+    ),
+    metric(
+        "auth9_email_queue_delivery_lag_seconds",
+        MetricKind::Histogram,
+        &[],
+        "Time from enqueue to delivery of queued emails",
+    ),
+    metric(
+        "auth9_email_suppressed_total",
+        MetricKind::Counter,
+        &[],
+        "Emails not sent because every recipient is suppressed",
+    ),
+    metric(
+        "auth9_email_feedback_total",
+        MetricKind::Counter,
+        &["kind"],
+        "Bounce and complaint notifications received from providers",
+    ),
+    // Billing
+    metric(
+        "auth9_billing_events_recorded_total",
+        MetricKind::Counter,
+        &["type"],
+        "Billable usage events recorded",
+    ),
+    metric(
+        "auth9_billing_events_delivered_total",
+        MetricKind::Counter,
+        &[],
+        "Billable usage events delivered to the billing webhook",
+    ),
+    metric(
+        "auth9_billing_events_failed_total",
+        MetricKind::Counter,
+        &[],
+        "Billable usage events that exhausted their delivery retries",
+    ),
+    // Webhooks
+    metric(
+        "auth9_webhook_filter_evaluations_total",
+        MetricKind::Counter,
+        &["result"],
+        "Webhook event filter evaluations by result (matched, filtered, error)",
+    ),
+    // API deprecations
+    metric(
+        "auth9_deprecated_api_usage_total",
+        MetricKind::Counter,
+        &["kind", "name", "client_id"],
+        "Requests relying on deprecated endpoints or fields, by calling client_id",
+    ),
+    metric(
+        "auth9_tenant_slug_redirect_total",
+        MetricKind::Counter,
+        &[],
+        "Requests served through a renamed tenant slug redirect",
+    ),
+    // Directory sync
+    metric(
+        "auth9_ldap_sync_runs_total",
+        MetricKind::Counter,
+        &["trigger", "status"],
+        "LDAP directory sync runs by trigger (manual, scheduled) and final status",
+    ),
+    // Platform admin scopes
+    metric(
+        "auth9_admin_scope_checks_total",
+        MetricKind::Counter,
+        &["scope", "outcome"],
+        "Platform admin requests checked against admin scopes, by scope and outcome",
+    ),
+    // External authorization
+    metric(
+        "auth9_external_authz_checks_total",
+        MetricKind::Counter,
+        &["provider", "result", "cached"],
+        "External authorizer decisions by provider, result and whether they came from cache",
+    ),
+    metric(
+        "auth9_external_authz_shadow_comparisons_total",
+        MetricKind::Counter,
+        &["service_id", "mode", "outcome"],
+        "Shadow-mode comparisons of external and built-in authorization decisions",
+    ),
+    // Cardinality caps
+    metric(
+        "auth9_cardinality_cap_near_total",
+        MetricKind::Counter,
+        &["cap"],
+        "Writes that brought a cardinality cap close to its limit",
+    ),
+    metric(
+        "auth9_cardinality_cap_rejected_total",
+        MetricKind::Counter,
+        &["cap"],
+        "Writes rejected for exceeding a cardinality cap",
+    ),
+    // Fault injection
+    metric(
+        "auth9_fault_injected_total",
+        MetricKind::Counter,
+        &["target", "kind"],
+        "Faults injected by the fault-injection build, by target and kind (latency, error)",
+    ),
     // Business metrics
-    describe_gauge!("auth9_tenants_active_total", "Number of active tenants");
-    describe_gauge!("auth9_users_active_total", "Number of active users");
-    describe_gauge!("auth9_sessions_active_total", "Number of active sessions");
-
+    metric(
+        "auth9_tenants_active_total",
+        MetricKind::Gauge,
+        &[],
+        "Number of active tenants",
+    ),
+    metric(
+        "auth9_users_active_total",
+        MetricKind::Gauge,
+        &[],
+        "Number of active users",
+    ),
+    metric(
+        "auth9_sessions_active_total",
+        MetricKind::Gauge,
+        &[],
+        "Number of active sessions",
+    ),
     // Action metrics
-    describe_counter!(
+    metric(
         "auth9_action_operations_total",
-        "Total action CRUD operations"
-    );
-    describe_histogram!(
+        MetricKind::Counter,
+        &["operation", "result"],
+        "Total action CRUD operations",
+    ),
+    metric(
         "auth9_action_operation_duration_seconds",
-        "Action operation duration in seconds"
-    );
-    describe_counter!("auth9_action_executions_total", "Total action executions");
-    describe_histogram!(
+        MetricKind::Histogram,
+        &["operation"],
+        "Action operation duration in seconds",
+    ),
+    metric(
+        "auth9_action_executions_total",
+        MetricKind::Counter,
+        &["trigger", "result"],
+        "Total action executions",
+    ),
+    metric(
         "auth9_action_execution_duration_seconds",
-        "Action execution duration in seconds"
-    );
-    describe_gauge!("auth9_actions_enabled_total", "Enabled actions per tenant");
+        MetricKind::Histogram,
+        &["trigger"],
+        "Action execution duration in seconds",
+    ),
+    metric(
+        "auth9_actions_enabled_total",
+        MetricKind::Gauge,
+        &["tenant_id"],
+        "Enabled actions per tenant",
+    ),
+    metric(
+        "auth9_claims_enrichment_total",
+        MetricKind::Counter,
+        &["plugin", "result"],
+        "Claims enrichment plugin calls by plugin and result",
+    ),
+];
+
+/// Rate/errors/duration signals of one request-serving surface, for
+/// generating RED dashboards
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RedSignals {
+    #[schema(value_type = String)]
+    pub name: &'static str,
+    /// Counter of handled requests
+    #[schema(value_type = String)]
+    pub requests_metric: &'static str,
+    /// PromQL label matcher selecting failed requests on `requests_metric`
+    #[schema(value_type = String)]
+    pub errors_selector: &'static str,
+    /// Latency histogram (carries trace exemplars)
+    #[schema(value_type = String)]
+    pub duration_metric: &'static str,
+    /// Labels to break panels down by
+    #[schema(value_type = Vec<String>)]
+    pub group_by: &'static [&'static str],
+}
+
+pub const RED_SIGNALS: &[RedSignals] = &[
+    RedSignals {
+        name: "http",
+        requests_metric: "auth9_http_requests_total",
+        errors_selector: r#"status=~"5.."#,
+        duration_metric: "auth9_http_request_duration_seconds",
+        group_by: &["method", "path"],
+    },
+    RedSignals {
+        name: "grpc",
+        requests_metric: "auth9_grpc_requests_total",
+        errors_selector: r#"status!="ok""#,
+        duration_metric: "auth9_grpc_request_duration_seconds",
+        group_by: &["service", "method"],
+    },
+];
+
+/// Machine-readable catalog of emitted metrics
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MetricsCatalog {
+    pub metrics: Vec<MetricDescriptor>,
+    pub red: Vec<RedSignals>,
+    /// Histogram buckets (seconds) shared by all latency histograms
+    pub latency_buckets: Vec<f64>,
+    /// Histograms whose buckets carry trace exemplars in OpenMetrics output
+    #[schema(value_type = Vec<String>)]
+    pub exemplar_metrics: Vec<&'static str>,
+}
+
+impl MetricsCatalog {
+    pub fn current() -> Self {
+        Self {
+            metrics: METRIC_CATALOG.to_vec(),
+            red: RED_SIGNALS.to_vec(),
+            latency_buckets: LATENCY_BUCKETS.to_vec(),
+            exemplar_metrics: super::exemplars::EXEMPLAR_METRICS.to_vec(),
+        }
+    }
+}
+
+/// Register metric descriptions and emit initial zero values so Prometheus output
+/// includes HELP/TYPE lines for all metrics from startup (not just after first use).
+pub fn describe_metrics() {
+    for metric in METRIC_CATALOG {
+        match metric.kind {
+            MetricKind::Counter => describe_counter!(metric.name, metric.description),
+            MetricKind::Gauge => describe_gauge!(metric.name, metric.description),
+            MetricKind::Histogram => describe_histogram!(metric.name, metric.description),
+        }
+    }
 
     // Emit initial zero values for lazily-registered metrics so that
     // HELP/TYPE lines appear in Prometheus output from startup.
@@ -135,6 +650,14 @@ pub fn describe_metrics() {
         "mode" => "fail_close"
     )
     .absolute(0);
+    counter!("auth9_db_slow_queries_total", "table" => "", "operation" => "").absolute(0);
+    counter!(
+        "auth9_degraded_operations_total",
+        "dependency" => "redis",
+        "operation" => "token_revocation_check",
+        "policy" => "fail_closed"
+    )
+    .absolute(0);
     counter!("auth9_redis_operations_total", "operation" => "get").absolute(0);
     histogram!("auth9_redis_operation_duration_seconds", "operation" => "get").record(0.0);
     gauge!("auth9_http_requests_in_flight").set(0.0);
@@ -148,3 +671,101 @@ pub fn describe_metrics() {
     histogram!("auth9_action_execution_duration_seconds", "trigger" => "post-login").record(0.0);
     gauge!("auth9_actions_enabled_total", "tenant_id" => "").set(0.0);
 }
+
+/// Record an operation handled under a dependency failure policy, e.g. a request
+/// rejected because the revocation list was unreachable or an email queued for retry.
+pub fn record_degraded_operation(
+    dependency: &'static str,
+    operation: &'static str,
+    policy: FailurePolicy,
+) {
+    counter!(
+        "auth9_degraded_operations_total",
+        "dependency" => dependency,
+        "operation" => operation,
+        "policy" => policy.as_str()
+    )
+    .increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalog_names_are_unique() {
+        let mut seen = HashSet::new();
+        for metric in METRIC_CATALOG {
+            assert!(seen.insert(metric.name), "duplicate {}", metric.name);
+        }
+    }
+
+    /// Names passed as string literals to `counter!`, `histogram!` and
+    /// `gauge!` anywhere under `dir`
+    fn emitted_metric_names(dir: &std::path::Path, names: &mut Vec<(String, String)>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                emitted_metric_names(&path, names);
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for macro_name in ["counter!(", "histogram!(", "gauge!("] {
+                for (start, _) in source.match_indices(macro_name) {
+                    let rest = source[start + macro_name.len()..].trim_start();
+                    let Some(literal) = rest.strip_prefix('"') else {
+                        continue;
+                    };
+                    let name = &literal[..literal.find('"').unwrap()];
+                    // Skips mentions of the macros in strings, like the ones above
+                    if !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                    {
+                        names.push((name.to_string(), path.display().to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_emitted_metrics_are_in_catalog() {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut emitted = Vec::new();
+        emitted_metric_names(&src, &mut emitted);
+        assert!(!emitted.is_empty());
+
+        let missing: Vec<_> = emitted
+            .iter()
+            .filter(|(name, _)| !METRIC_CATALOG.iter().any(|m| m.name == name.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "metrics missing from METRIC_CATALOG: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn test_red_signals_reference_catalog_metrics() {
+        let kind = |name: &str| {
+            METRIC_CATALOG
+                .iter()
+                .find(|m| m.name == name)
+                .map(|m| m.kind)
+        };
+        for red in RED_SIGNALS {
+            assert_eq!(kind(red.requests_metric), Some(MetricKind::Counter));
+            assert_eq!(kind(red.duration_metric), Some(MetricKind::Histogram));
+        }
+        for name in super::super::exemplars::EXEMPLAR_METRICS {
+            assert_eq!(kind(name), Some(MetricKind::Histogram));
+        }
+    }
+}
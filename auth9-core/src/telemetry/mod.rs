@@ -1,11 +1,14 @@
 //! Telemetry initialization: metrics, tracing, and structured logging
 
+pub mod exemplars;
 pub mod metrics;
+pub mod runtime;
+pub mod sql;
 pub mod tracing_setup;
 
 use crate::config::TelemetryConfig;
 use metrics_exporter_prometheus::PrometheusHandle;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, Layer};
 
 /// Initialise the full telemetry stack.
 ///
@@ -15,6 +18,16 @@ pub fn init(config: &TelemetryConfig) -> Option<PrometheusHandle> {
     // 1. Build the env filter (same logic as the old init_tracing)
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| "auth9_core=info,tower_http=debug".into());
+    let default_filter = env_filter.to_string();
+    // Wrapped in a reload layer so platform admins can change it at runtime
+    let (env_filter, filter_handle) = reload::Layer::new(env_filter);
+    runtime::runtime().install(
+        default_filter,
+        config.trace_sampling_ratio,
+        Some(Box::new(move |filter| {
+            filter_handle.reload(filter).map_err(|e| e.to_string())
+        })),
+    );
 
     // 2. Optionally install Prometheus recorder
     let prometheus_handle = if config.metrics_enabled {
@@ -25,8 +38,11 @@ pub fn init(config: &TelemetryConfig) -> Option<PrometheusHandle> {
         None
     };
 
-    // 3. Build the subscriber with conditional layers
-    let registry = tracing_subscriber::registry().with(env_filter);
+    // 3. SQL instrumentation sees every sqlx statement event regardless of the
+    //    log filter, which only applies to the fmt/OTel layers below.
+    sql::global_stats().set_threshold_ms(config.slow_query_threshold_ms);
+    let registry = tracing_subscriber::registry()
+        .with(sql::SqlQueryLayer.with_filter(sql::sql_query_filter()));
 
     let is_json = config.log_format == "json";
 
@@ -38,11 +54,15 @@ pub fn init(config: &TelemetryConfig) -> Option<PrometheusHandle> {
         // flatten event fields so `message` is consistently top-level.
         let fmt_layer = tracing_subscriber::fmt::layer().json().flatten_event(true);
         let otel_layer = tracing_setup::create_otel_layer(config);
-        registry.with(fmt_layer).with(otel_layer).init();
+        registry
+            .with(fmt_layer.and_then(otel_layer).with_filter(env_filter))
+            .init();
     } else {
         let fmt_layer = tracing_subscriber::fmt::layer();
         let otel_layer = tracing_setup::create_otel_layer(config);
-        registry.with(fmt_layer).with(otel_layer).init();
+        registry
+            .with(fmt_layer.and_then(otel_layer).with_filter(env_filter))
+            .init();
     }
 
     prometheus_handle
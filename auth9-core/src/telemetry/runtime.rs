@@ -0,0 +1,325 @@
+//! Runtime telemetry overrides
+//!
+//! Platform admins can change the log filter and the trace sampling ratio of a
+//! replica while debugging an incident. Every override carries a TTL after
+//! which the startup configuration is restored, so a forgotten debug setting
+//! cannot keep flooding logs or the trace backend.
+
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Duration, Utc};
+use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceId};
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::trace::{Sampler, ShouldSample};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tracing_subscriber::EnvFilter;
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Lifetime of an override when the request does not set one
+pub const DEFAULT_OVERRIDE_TTL_SECS: u64 = 900;
+
+static RUNTIME: LazyLock<RuntimeTelemetry> = LazyLock::new(RuntimeTelemetry::new);
+
+/// Process-wide runtime telemetry settings
+pub fn runtime() -> &'static RuntimeTelemetry {
+    &RUNTIME
+}
+
+/// Swaps the active log filter of the installed subscriber
+pub type FilterReloader = Box<dyn Fn(EnvFilter) -> std::result::Result<(), String> + Send + Sync>;
+
+/// Current telemetry settings of this replica
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TelemetryStatus {
+    pub log_filter: String,
+    pub trace_sampling_ratio: f64,
+    pub default_log_filter: String,
+    pub default_trace_sampling_ratio: f64,
+    /// When the active override reverts; `None` when running the defaults
+    pub override_expires_at: Option<DateTime<Utc>>,
+}
+
+/// Temporary change of log filter and/or trace sampling ratio
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct TelemetryOverrideInput {
+    /// `EnvFilter` directives, e.g. `auth9_core=info,auth9_core::domains::identity=trace`
+    #[validate(length(min = 1, max = 1024))]
+    pub log_filter: Option<String>,
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub trace_sampling_ratio: Option<f64>,
+    /// Seconds until the override reverts (default 900, max 14400)
+    #[validate(range(min = 1, max = 14400))]
+    pub ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Settings {
+    log_filter: String,
+    sampling_ratio: f64,
+}
+
+#[derive(Debug)]
+struct State {
+    defaults: Settings,
+    current: Settings,
+    expires_at: Option<DateTime<Utc>>,
+    /// Bumped on every change so a stale revert timer does nothing
+    generation: u64,
+}
+
+pub struct RuntimeTelemetry {
+    sampling_ratio: AtomicU64,
+    reloader: OnceLock<FilterReloader>,
+    state: Mutex<State>,
+}
+
+impl RuntimeTelemetry {
+    fn new() -> Self {
+        let defaults = Settings {
+            log_filter: String::new(),
+            sampling_ratio: 1.0,
+        };
+        Self {
+            sampling_ratio: AtomicU64::new(1.0f64.to_bits()),
+            reloader: OnceLock::new(),
+            state: Mutex::new(State {
+                current: defaults.clone(),
+                defaults,
+                expires_at: None,
+                generation: 0,
+            }),
+        }
+    }
+
+    /// Record the startup configuration. `reloader` is `None` when the log
+    /// filter cannot be swapped (e.g. no subscriber was installed).
+    pub fn install(
+        &self,
+        log_filter: String,
+        sampling_ratio: f64,
+        reloader: Option<FilterReloader>,
+    ) {
+        if let Some(reloader) = reloader {
+            let _ = self.reloader.set(reloader);
+        }
+        let defaults = Settings {
+            log_filter,
+            sampling_ratio: sampling_ratio.clamp(0.0, 1.0),
+        };
+        self.sampling_ratio
+            .store(defaults.sampling_ratio.to_bits(), Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.current = defaults.clone();
+        state.defaults = defaults;
+        state.expires_at = None;
+        state.generation += 1;
+    }
+
+    /// Ratio of root traces currently sampled
+    pub fn sampling_ratio(&self) -> f64 {
+        f64::from_bits(self.sampling_ratio.load(Ordering::Relaxed))
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> TelemetryStatus {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.expires_at.is_some_and(|at| at <= now) {
+            self.restore(&mut state);
+        }
+        TelemetryStatus {
+            log_filter: state.current.log_filter.clone(),
+            trace_sampling_ratio: state.current.sampling_ratio,
+            default_log_filter: state.defaults.log_filter.clone(),
+            default_trace_sampling_ratio: state.defaults.sampling_ratio,
+            override_expires_at: state.expires_at,
+        }
+    }
+
+    /// Apply an override. Returns the new status and the generation to pass
+    /// to [`revert_if_current`](Self::revert_if_current) once the TTL elapses.
+    pub fn apply(
+        &self,
+        input: TelemetryOverrideInput,
+        now: DateTime<Utc>,
+    ) -> Result<(TelemetryStatus, u64)> {
+        input.validate()?;
+        if input.log_filter.is_none() && input.trace_sampling_ratio.is_none() {
+            return Err(AppError::Validation(
+                "Set log_filter and/or trace_sampling_ratio".to_string(),
+            ));
+        }
+        let filter = match input.log_filter.as_deref() {
+            Some(directives) => Some(parse_filter(directives)?),
+            None => None,
+        };
+
+        let ttl_secs = input.ttl_secs.unwrap_or(DEFAULT_OVERRIDE_TTL_SECS);
+        let generation = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if let (Some(filter), Some(directives)) = (filter, input.log_filter) {
+                self.reload_filter(filter)?;
+                state.current.log_filter = directives;
+            }
+            if let Some(ratio) = input.trace_sampling_ratio {
+                state.current.sampling_ratio = ratio;
+                self.sampling_ratio
+                    .store(ratio.to_bits(), Ordering::Relaxed);
+            }
+            state.expires_at = Some(now + Duration::seconds(ttl_secs as i64));
+            state.generation += 1;
+            state.generation
+        };
+
+        let status = self.status(now);
+        tracing::warn!(
+            log_filter = %status.log_filter,
+            trace_sampling_ratio = status.trace_sampling_ratio,
+            ttl_secs,
+            "Runtime telemetry override applied"
+        );
+        Ok((status, generation))
+    }
+
+    /// Restore the startup configuration
+    pub fn revert(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.restore(&mut state);
+    }
+
+    /// Restore the startup configuration unless the override was replaced
+    /// since `generation` was handed out
+    pub fn revert_if_current(&self, generation: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.generation == generation {
+            self.restore(&mut state);
+        }
+    }
+
+    fn restore(&self, state: &mut State) {
+        if state.expires_at.is_none() {
+            return;
+        }
+        if state.current.log_filter != state.defaults.log_filter {
+            if let Ok(filter) = parse_filter(&state.defaults.log_filter) {
+                if let Err(e) = self.reload_filter(filter) {
+                    tracing::error!("Failed to restore default log filter: {}", e);
+                }
+            }
+        }
+        self.sampling_ratio
+            .store(state.defaults.sampling_ratio.to_bits(), Ordering::Relaxed);
+        state.current = state.defaults.clone();
+        state.expires_at = None;
+        state.generation += 1;
+        tracing::warn!("Runtime telemetry override reverted to defaults");
+    }
+
+    fn reload_filter(&self, filter: EnvFilter) -> Result<()> {
+        let reloader = self.reloader.get().ok_or_else(|| {
+            AppError::BadRequest("Log filter cannot be changed on this replica".to_string())
+        })?;
+        reloader(filter).map_err(|e| AppError::Internal(anyhow::anyhow!(e)))
+    }
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter> {
+    EnvFilter::try_new(directives)
+        .map_err(|e| AppError::Validation(format!("Invalid log filter: {}", e)))
+}
+
+/// Root-span sampler whose ratio follows [`RuntimeTelemetry::sampling_ratio`]
+#[derive(Debug, Clone)]
+pub struct RuntimeRatioSampler;
+
+impl ShouldSample for RuntimeRatioSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&opentelemetry::Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        Sampler::TraceIdRatioBased(runtime().sampling_ratio()).should_sample(
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(ratio: Option<f64>, ttl_secs: Option<u64>) -> TelemetryOverrideInput {
+        TelemetryOverrideInput {
+            log_filter: None,
+            trace_sampling_ratio: ratio,
+            ttl_secs,
+        }
+    }
+
+    #[test]
+    fn test_override_reverts_after_ttl() {
+        let telemetry = RuntimeTelemetry::new();
+        telemetry.install("auth9_core=info".to_string(), 0.1, None);
+        let now = Utc::now();
+
+        let (status, _) = telemetry.apply(input(Some(1.0), Some(60)), now).unwrap();
+        assert_eq!(status.trace_sampling_ratio, 1.0);
+        assert_eq!(telemetry.sampling_ratio(), 1.0);
+        assert!(status.override_expires_at.is_some());
+
+        let later = telemetry.status(now + Duration::seconds(61));
+        assert_eq!(later.trace_sampling_ratio, 0.1);
+        assert_eq!(telemetry.sampling_ratio(), 0.1);
+        assert!(later.override_expires_at.is_none());
+    }
+
+    #[test]
+    fn test_stale_revert_timer_is_ignored() {
+        let telemetry = RuntimeTelemetry::new();
+        telemetry.install("auth9_core=info".to_string(), 0.1, None);
+        let now = Utc::now();
+
+        let (_, first) = telemetry.apply(input(Some(0.5), None), now).unwrap();
+        let (_, second) = telemetry.apply(input(Some(0.8), None), now).unwrap();
+        telemetry.revert_if_current(first);
+        assert_eq!(telemetry.sampling_ratio(), 0.8);
+        telemetry.revert_if_current(second);
+        assert_eq!(telemetry.sampling_ratio(), 0.1);
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_input() {
+        let telemetry = RuntimeTelemetry::new();
+        telemetry.install("auth9_core=info".to_string(), 1.0, None);
+        let now = Utc::now();
+
+        assert!(telemetry.apply(input(None, None), now).is_err());
+        assert!(telemetry.apply(input(Some(1.5), None), now).is_err());
+        let bad_filter = TelemetryOverrideInput {
+            log_filter: Some("auth9_core=loud".to_string()),
+            ..input(None, None)
+        };
+        assert!(matches!(
+            telemetry.apply(bad_filter, now),
+            Err(AppError::Validation(_))
+        ));
+        // Without an installed subscriber the filter cannot be swapped
+        let filter = TelemetryOverrideInput {
+            log_filter: Some("auth9_core=debug".to_string()),
+            ..input(None, None)
+        };
+        assert!(matches!(
+            telemetry.apply(filter, now),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
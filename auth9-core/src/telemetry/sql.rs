@@ -0,0 +1,444 @@
+//! SQL query instrumentation and slow query reporting
+//!
+//! sqlx emits a `sqlx::query` tracing event for every statement it executes.
+//! [`SqlQueryLayer`] turns those events into per-table latency histograms,
+//! per-fingerprint aggregates and a bounded log of slow queries that platform
+//! admins can inspect through the API. Repositories map onto their primary
+//! table, so the `table` label attributes latency to the repository that
+//! issued the query without touching every call site.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer};
+use utoipa::ToSchema;
+
+/// Tracing target sqlx uses for per-statement events
+pub const SQL_QUERY_TARGET: &str = "sqlx::query";
+
+/// Default threshold above which a statement is reported as slow
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 200;
+
+/// Maximum number of distinct fingerprints aggregated in memory
+const MAX_FINGERPRINTS: usize = 500;
+
+/// Number of slow queries retained for the admin endpoint
+const SLOW_LOG_CAPACITY: usize = 100;
+
+static GLOBAL_STATS: LazyLock<SqlQueryStats> =
+    LazyLock::new(|| SqlQueryStats::new(DEFAULT_SLOW_QUERY_THRESHOLD_MS));
+
+static STRING_LITERAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"'(?:[^'\\]|\\.|'')*'").unwrap());
+static NUMBER_LITERAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d+(?:\.\d+)?\b").unwrap());
+static PLACEHOLDER_LIST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\s*\?(?:\s*,\s*\?)+\s*\)").unwrap());
+static REPEATED_TUPLES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\?\+\)(?:\s*,\s*\(\?\+\))+").unwrap());
+static TABLE_REF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:from|into|update|join)\s+`?([a-z_][a-z0-9_]*)`?").unwrap());
+
+/// Process-wide SQL statistics fed by [`SqlQueryLayer`]
+pub fn global_stats() -> &'static SqlQueryStats {
+    &GLOBAL_STATS
+}
+
+/// Aggregated timings for one statement fingerprint
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QueryFingerprintStats {
+    pub fingerprint_id: String,
+    pub fingerprint: String,
+    pub table: String,
+    pub operation: String,
+    pub count: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub rows: u64,
+    pub slow_count: u64,
+}
+
+/// A single statement that exceeded the slow query threshold
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlowQueryEntry {
+    pub fingerprint_id: String,
+    pub fingerprint: String,
+    pub table: String,
+    pub operation: String,
+    pub duration_ms: f64,
+    pub rows: u64,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Slow query log and the most expensive fingerprints
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlowQueryReport {
+    pub threshold_ms: u64,
+    /// Most recent first
+    pub slow_queries: Vec<SlowQueryEntry>,
+    /// Ordered by cumulative time spent, descending
+    pub top_fingerprints: Vec<QueryFingerprintStats>,
+}
+
+#[derive(Default)]
+struct StatsInner {
+    fingerprints: HashMap<String, QueryFingerprintStats>,
+    slow: VecDeque<SlowQueryEntry>,
+}
+
+/// In-memory SQL statistics
+pub struct SqlQueryStats {
+    threshold_ms: AtomicU64,
+    inner: Mutex<StatsInner>,
+}
+
+impl SqlQueryStats {
+    pub fn new(threshold_ms: u64) -> Self {
+        Self {
+            threshold_ms: AtomicU64::new(threshold_ms),
+            inner: Mutex::new(StatsInner::default()),
+        }
+    }
+
+    pub fn threshold_ms(&self) -> u64 {
+        self.threshold_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn set_threshold_ms(&self, threshold_ms: u64) {
+        self.threshold_ms.store(threshold_ms, Ordering::Relaxed);
+    }
+
+    /// Record one executed statement
+    pub fn record(&self, statement: &str, rows: u64, elapsed: Duration) {
+        let fingerprint = fingerprint(statement);
+        let (operation, table) = classify(&fingerprint);
+        let duration_ms = elapsed.as_secs_f64() * 1000.0;
+        let is_slow = duration_ms >= self.threshold_ms() as f64;
+
+        metrics::histogram!(
+            "auth9_db_query_duration_seconds",
+            "table" => table.clone(),
+            "operation" => operation.clone()
+        )
+        .record(elapsed.as_secs_f64());
+        metrics::counter!(
+            "auth9_db_query_rows_total",
+            "table" => table.clone(),
+            "operation" => operation.clone()
+        )
+        .increment(rows);
+        if is_slow {
+            metrics::counter!(
+                "auth9_db_slow_queries_total",
+                "table" => table.clone(),
+                "operation" => operation.clone()
+            )
+            .increment(1);
+        }
+
+        let fingerprint_id = fingerprint_id(&fingerprint);
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+
+        if inner.fingerprints.len() < MAX_FINGERPRINTS
+            || inner.fingerprints.contains_key(&fingerprint_id)
+        {
+            let entry = inner
+                .fingerprints
+                .entry(fingerprint_id.clone())
+                .or_insert_with(|| QueryFingerprintStats {
+                    fingerprint_id: fingerprint_id.clone(),
+                    fingerprint: fingerprint.clone(),
+                    table: table.clone(),
+                    operation: operation.clone(),
+                    count: 0,
+                    total_ms: 0.0,
+                    mean_ms: 0.0,
+                    max_ms: 0.0,
+                    rows: 0,
+                    slow_count: 0,
+                });
+            entry.count += 1;
+            entry.total_ms += duration_ms;
+            entry.mean_ms = entry.total_ms / entry.count as f64;
+            entry.max_ms = entry.max_ms.max(duration_ms);
+            entry.rows += rows;
+            if is_slow {
+                entry.slow_count += 1;
+            }
+        }
+
+        if is_slow {
+            tracing::warn!(
+                fingerprint_id = %fingerprint_id,
+                table = %table,
+                duration_ms,
+                rows,
+                "Slow SQL query"
+            );
+            if inner.slow.len() == SLOW_LOG_CAPACITY {
+                inner.slow.pop_back();
+            }
+            inner.slow.push_front(SlowQueryEntry {
+                fingerprint_id,
+                fingerprint,
+                table,
+                operation,
+                duration_ms,
+                rows,
+                occurred_at: Utc::now(),
+            });
+        }
+    }
+
+    /// Snapshot of the slow query log and the `limit` most expensive fingerprints
+    pub fn report(&self, limit: usize) -> SlowQueryReport {
+        let (slow_queries, mut top_fingerprints) = match self.inner.lock() {
+            Ok(inner) => (
+                inner.slow.iter().cloned().collect(),
+                inner.fingerprints.values().cloned().collect::<Vec<_>>(),
+            ),
+            Err(_) => (vec![], vec![]),
+        };
+        top_fingerprints.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        top_fingerprints.truncate(limit);
+
+        SlowQueryReport {
+            threshold_ms: self.threshold_ms(),
+            slow_queries,
+            top_fingerprints,
+        }
+    }
+
+    /// Clear aggregates and the slow query log
+    pub fn reset(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            *inner = StatsInner::default();
+        }
+    }
+}
+
+/// Normalise a statement so queries differing only in literals or
+/// placeholder-list length share a fingerprint.
+pub fn fingerprint(sql: &str) -> String {
+    let normalized = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    let normalized = normalized.to_lowercase();
+    let normalized = STRING_LITERAL.replace_all(&normalized, "?");
+    let normalized = NUMBER_LITERAL.replace_all(&normalized, "?");
+    let normalized = PLACEHOLDER_LIST.replace_all(&normalized, "(?+)");
+    REPEATED_TUPLES
+        .replace_all(&normalized, "(?+), ...")
+        .into_owned()
+}
+
+/// Short stable identifier for a fingerprint
+pub fn fingerprint_id(fingerprint: &str) -> String {
+    let digest = Sha256::digest(fingerprint.as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// Statement operation and primary table of a fingerprint
+fn classify(fingerprint: &str) -> (String, String) {
+    let operation = match fingerprint.split_whitespace().next() {
+        Some(op @ ("select" | "insert" | "update" | "delete" | "replace")) => op,
+        Some("with") => "select",
+        _ => "other",
+    };
+    let table = TABLE_REF
+        .captures(fingerprint)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str())
+        .unwrap_or("other");
+    (operation.to_string(), table.to_string())
+}
+
+/// Filter that routes only sqlx statement events to [`SqlQueryLayer`]
+pub fn sql_query_filter() -> Targets {
+    Targets::new().with_target(SQL_QUERY_TARGET, Level::TRACE)
+}
+
+/// Tracing layer that records sqlx statement events into [`global_stats`]
+pub struct SqlQueryLayer;
+
+impl<S: Subscriber> Layer<S> for SqlQueryLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != SQL_QUERY_TARGET {
+            return;
+        }
+        let mut visitor = SqlEventVisitor::default();
+        event.record(&mut visitor);
+
+        let statement = if visitor.statement.trim().is_empty() {
+            visitor.summary
+        } else {
+            visitor.statement
+        };
+        if statement.trim().is_empty() {
+            return;
+        }
+        global_stats().record(
+            &statement,
+            visitor.rows_returned + visitor.rows_affected,
+            Duration::from_secs_f64(visitor.elapsed_secs.max(0.0)),
+        );
+    }
+}
+
+#[derive(Default)]
+struct SqlEventVisitor {
+    summary: String,
+    statement: String,
+    rows_affected: u64,
+    rows_returned: u64,
+    elapsed_secs: f64,
+}
+
+impl Visit for SqlEventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "rows_affected" => self.rows_affected = value,
+            "rows_returned" => self.rows_returned = value,
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_normalises_literals_and_lists() {
+        assert_eq!(
+            fingerprint(
+                "SELECT *\n  FROM users WHERE email = 'a@b.c' AND id IN (?, ?, ?) LIMIT 10"
+            ),
+            "select * from users where email = ? and id in (?+) limit ?"
+        );
+        assert_eq!(
+            fingerprint("INSERT INTO t (a, b) VALUES (?, ?), (?, ?), (?, ?)"),
+            "insert into t (a, b) values (?+), ..."
+        );
+        assert_eq!(
+            fingerprint("SELECT id FROM users WHERE id IN (?, ?)"),
+            fingerprint("select id from users where id in (?, ?, ?, ?)")
+        );
+    }
+
+    #[test]
+    fn test_classify_operation_and_table() {
+        let fp = fingerprint("SELECT u.id FROM users u JOIN tenant_users tu ON tu.user_id = u.id");
+        assert_eq!(classify(&fp), ("select".into(), "users".into()));
+        let fp = fingerprint("UPDATE `sessions` SET revoked_at = NOW() WHERE id = ?");
+        assert_eq!(classify(&fp), ("update".into(), "sessions".into()));
+        let fp = fingerprint("DELETE FROM webhooks WHERE tenant_id = ?");
+        assert_eq!(classify(&fp), ("delete".into(), "webhooks".into()));
+        assert_eq!(
+            classify("set names utf8mb4"),
+            ("other".into(), "other".into())
+        );
+    }
+
+    #[test]
+    fn test_record_aggregates_and_logs_slow_queries() {
+        let stats = SqlQueryStats::new(50);
+        stats.record(
+            "SELECT * FROM users WHERE id = ?",
+            1,
+            Duration::from_millis(10),
+        );
+        stats.record(
+            "SELECT * FROM users WHERE id = ?",
+            1,
+            Duration::from_millis(80),
+        );
+        stats.record("SELECT * FROM sessions", 5, Duration::from_millis(5));
+
+        let report = stats.report(10);
+        assert_eq!(report.threshold_ms, 50);
+        assert_eq!(report.slow_queries.len(), 1);
+        assert_eq!(report.slow_queries[0].table, "users");
+        assert_eq!(report.top_fingerprints.len(), 2);
+
+        let users = &report.top_fingerprints[0];
+        assert_eq!(users.table, "users");
+        assert_eq!(users.count, 2);
+        assert_eq!(users.slow_count, 1);
+        assert_eq!(users.rows, 2);
+        assert!((users.max_ms - 80.0).abs() < 1.0);
+
+        stats.reset();
+        assert!(stats.report(10).top_fingerprints.is_empty());
+    }
+
+    #[test]
+    fn test_slow_log_is_bounded() {
+        let stats = SqlQueryStats::new(0);
+        for i in 0..(SLOW_LOG_CAPACITY + 10) {
+            stats.record(
+                &format!("SELECT * FROM t{}", i),
+                0,
+                Duration::from_millis(1),
+            );
+        }
+        let report = stats.report(5);
+        assert_eq!(report.slow_queries.len(), SLOW_LOG_CAPACITY);
+        assert_eq!(
+            report.slow_queries[0].table,
+            format!("t{}", SLOW_LOG_CAPACITY + 9)
+        );
+        assert_eq!(report.top_fingerprints.len(), 5);
+    }
+
+    #[test]
+    fn test_layer_records_sqlx_events() {
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::Layer as _;
+
+        let subscriber =
+            tracing_subscriber::registry().with(SqlQueryLayer.with_filter(sql_query_filter()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(
+                target: "sqlx::query",
+                summary = "select * from layer_test_table",
+                db.statement = "",
+                rows_affected = 0u64,
+                rows_returned = 3u64,
+                elapsed_secs = 0.002f64,
+            );
+        });
+
+        let report = global_stats().report(MAX_FINGERPRINTS);
+        assert!(report
+            .top_fingerprints
+            .iter()
+            .any(|f| f.table == "layer_test_table" && f.rows == 3));
+    }
+}
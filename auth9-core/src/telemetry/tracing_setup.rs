@@ -46,7 +46,13 @@ where
         )])
         .build();
 
+    // Child spans follow the caller's decision; root spans are sampled at the
+    // ratio the runtime telemetry settings currently hold
+    let sampler = opentelemetry_sdk::trace::Sampler::ParentBased(Box::new(
+        super::runtime::RuntimeRatioSampler,
+    ));
     let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_sampler(sampler)
         .with_batch_exporter(exporter)
         .with_resource(resource)
         .build();
@@ -8,45 +8,51 @@
 //! - Uses production `build_full_router()` with `TestAppState` for actual handler coverage
 //! - Helper functions for making HTTP requests (get_json, post_json, etc.)
 
-use crate::support::TestSamlApplicationRepository;
-use crate::support::{
+use super::{
     create_test_jwt_manager, TestActionRepository, TestAuditRepository, TestInvitationRepository,
     TestLinkedIdentityRepository, TestLoginEventRepository, TestMaliciousIpBlacklistRepository,
     TestPasswordResetRepository, TestRbacRepository, TestSecurityAlertRepository,
     TestServiceBrandingRepository, TestServiceRepository, TestSessionRepository,
-    TestSystemSettingsRepository, TestTenantRepository, TestUserRepository, TestWebhookRepository,
+    TestSystemSettingsRepository, TestTenantRepository, TestUserRepository,
+    TestWebhookDeliveryRepository, TestWebhookRepository,
 };
-use crate::support::{
-    TestScimGroupMappingRepository, TestScimLogRepository, TestScimTokenRepository,
+use super::{
+    TestAccountDeletionRepository, TestAdminUnitRepository, TestLoginForensicsRepository,
+    TestLoginIdentifierRepository, TestPasswordBreachCheckRepository,
+    TestPasswordDenyListRepository, TestSamlApplicationRepository, TestServiceScopeRepository,
 };
-use auth9_core::cache::{CacheOperations, NoOpCacheManager};
-use auth9_core::config::{
+use super::{TestScimGroupMappingRepository, TestScimLogRepository, TestScimTokenRepository};
+use crate::cache::{CacheOperations, NoOpCacheManager};
+use crate::config::{
     Config, CorsConfig, DatabaseConfig, GrpcSecurityConfig, JwtConfig, RateLimitConfig,
     RedisConfig, ServerConfig,
 };
-use auth9_core::domains::authorization::service::{ClientService, RbacService};
-use auth9_core::domains::identity::service::{
-    EmailVerificationService, IdentityProviderService, PasswordService, RequiredActionService,
+use crate::domains::authorization::service::{ClientService, RbacService, ScopeCatalogService};
+use crate::domains::events::{EventBus, WebhookSubscriber};
+use crate::domains::identity::service::{
+    AccountDeletionService, EmailVerificationService, IdentityProviderService,
+    LoginIdentifierService, PasswordDenyListService, PasswordService, RequiredActionService,
     SessionService, WebAuthnService,
 };
-use auth9_core::domains::integration::service::{ActionService, WebhookService};
-use auth9_core::domains::platform::service::{
+use crate::domains::integration::service::{ActionService, WebhookService};
+use crate::domains::platform::service::{
     BrandingService, EmailService, EmailTemplateService, IdentitySyncService, SystemSettingsService,
 };
-use auth9_core::domains::provisioning::service::{ScimService, ScimTokenService};
-use auth9_core::domains::security_observability::service::{
-    AnalyticsService, SecurityDetectionService,
+use crate::domains::provisioning::service::{ScimService, ScimTokenService};
+use crate::domains::security_observability::service::{
+    AnalyticsService, LoginForensicsService, SecurityDetectionService,
 };
-use auth9_core::domains::tenant_access::service::{
-    InvitationService, SamlApplicationService, TenantRepositoryBundle, TenantService,
-    UserRepositoryBundle, UserService,
+use crate::domains::tenant_access::service::{
+    AdminUnitService, InvitationService, SamlApplicationService, TenantRepositoryBundle,
+    TenantService, UserRepositoryBundle, UserService,
 };
-use auth9_core::identity_engine::{FederationBroker, IdentityEngine, IdentitySessionStore};
-use auth9_core::jwt::JwtManager;
-use auth9_core::middleware::RateLimitState;
-use auth9_core::server::build_full_router;
-use auth9_core::state::HasScimServices;
-use auth9_core::state::{
+use crate::identity_engine::{FederationBroker, IdentityEngine, IdentitySessionStore};
+use crate::jwt::JwtManager;
+use crate::middleware::RateLimitState;
+use crate::repository::PasswordBreachCheckRepository;
+use crate::server::build_full_router;
+use crate::state::HasScimServices;
+use crate::state::{
     HasAnalytics, HasBranding, HasCache, HasDbPool, HasEmailTemplates, HasIdentityProviders,
     HasInvitations, HasPasswordManagement, HasSecurityAlerts, HasServices, HasSessionManagement,
     HasSystemSettings, HasWebAuthn, HasWebhooks,
@@ -64,8 +70,8 @@ use tower::ServiceExt;
 // Test Configuration
 // ============================================================================
 
-/// Create a test config (url parameter is retained for call-site compatibility)
-pub fn create_test_config(_keycloak_url: &str) -> Config {
+/// Create a test config
+pub fn create_test_config() -> Config {
     Config {
         environment: "development".to_string(),
         http_host: "127.0.0.1".to_string(),
@@ -90,6 +96,8 @@ pub fn create_test_config(_keycloak_url: &str) -> Config {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         },
         core_public_url: None,
         portal_url: None,
@@ -97,9 +105,10 @@ pub fn create_test_config(_keycloak_url: &str) -> Config {
         grpc_security: GrpcSecurityConfig::default(),
         rate_limit: RateLimitConfig::default(),
         cors: CorsConfig::default(),
-        telemetry: auth9_core::config::TelemetryConfig::default(),
+        telemetry: crate::config::TelemetryConfig::default(),
         platform_admin_emails: vec!["admin@auth9.local".to_string()],
-        webauthn: auth9_core::config::WebAuthnConfig {
+        platform_admin_scopes_enforced: false,
+        webauthn: crate::config::WebAuthnConfig {
             rp_id: "localhost".to_string(),
             rp_name: "Auth9 Test".to_string(),
             rp_origin: "http://localhost:3000".to_string(),
@@ -107,21 +116,47 @@ pub fn create_test_config(_keycloak_url: &str) -> Config {
         },
         server: ServerConfig::default(),
         jwt_tenant_access_allowed_audiences: vec![],
-        security_headers: auth9_core::config::SecurityHeadersConfig::default(),
+        security_headers: crate::config::SecurityHeadersConfig::default(),
         portal_client_id: None,
-        password_reset: auth9_core::config::PasswordResetConfig {
+        password_reset: crate::config::PasswordResetConfig {
             hmac_key: "test-password-reset-hmac-key".to_string(),
             token_ttl_secs: 3600,
         },
-        hibp: auth9_core::config::HibpConfig {
+        hibp: crate::config::HibpConfig {
             enabled: false,
-            ..auth9_core::config::HibpConfig::default()
+            ..crate::config::HibpConfig::default()
         },
-        captcha: auth9_core::config::CaptchaConfig::default(),
-        geoip: auth9_core::config::GeoIpConfig::default(),
-        async_action: auth9_core::models::action::AsyncActionConfig::default(),
+        captcha: crate::config::CaptchaConfig::default(),
+        geoip: crate::config::GeoIpConfig::default(),
+        async_action: crate::models::action::AsyncActionConfig::default(),
         branding_allowed_domains: vec![],
         admin_password: None,
+        custom_domain: crate::config::CustomDomainConfig::default(),
+        dependency_policy: crate::config::DependencyPolicyConfig::default(),
+        jobs: crate::config::JobWorkerConfig::default(),
+        event_partitions: crate::config::EventPartitionConfig::default(),
+        synthetic_check: crate::config::SyntheticCheckConfig::default(),
+        session_activity: crate::config::SessionActivityConfig::default(),
+        email_queue: crate::config::EmailQueueConfig::default(),
+        billing: crate::config::BillingConfig::default(),
+        allow_weak_secrets: false,
+        data_residency: crate::config::DataResidencyConfig::default(),
+        replay_protection: crate::config::ReplayProtectionConfig::default(),
+        ldap_sync: crate::config::LdapSyncConfig::default(),
+        audit_integrity: crate::config::AuditIntegrityConfig::default(),
+        cardinality: crate::config::CardinalityConfig::default(),
+        migration_safety: crate::config::MigrationSafetyConfig::default(),
+        response_masking: crate::config::ResponseMaskingConfig::default(),
+        startup: crate::config::StartupConfig::default(),
+        oauth: crate::config::OAuthConfig::default(),
+        token_validation: crate::config::TokenValidationConfig::default(),
+        account_deletion: crate::config::AccountDeletionConfig::default(),
+        login_forensics: crate::config::LoginForensicsConfig::default(),
+        revocation_feed: crate::config::RevocationFeedConfig::default(),
+        permission_replication: crate::config::PermissionReplicationConfig::default(),
+        settings_encryption: crate::config::SettingsEncryptionConfig::default(),
+        flow_state: crate::config::FlowStateConfig::default(),
+        backup: crate::config::BackupConfig::default(),
     }
 }
 
@@ -161,6 +196,8 @@ pub struct TestAppState {
     pub user_service: Arc<TestUserService>,
     pub client_service: Arc<ClientService<TestServiceRepository, TestRbacRepository>>,
     pub rbac_service: Arc<RbacService<TestRbacRepository>>,
+    pub scope_catalog_service: Arc<ScopeCatalogService<TestServiceScopeRepository>>,
+    pub service_scope_repo: Arc<TestServiceScopeRepository>,
     pub system_settings_service: Arc<SystemSettingsService<TestSystemSettingsRepository>>,
     pub email_service: Arc<EmailService<TestSystemSettingsRepository>>,
     pub email_template_service: Arc<EmailTemplateService<TestSystemSettingsRepository>>,
@@ -196,7 +233,7 @@ pub struct TestAppState {
     >,
     pub action_service: Arc<ActionService<TestActionRepository>>,
     pub audit_repo: Arc<TestAuditRepository>,
-    pub jwt_manager: auth9_core::jwt::JwtManager,
+    pub jwt_manager: crate::jwt::JwtManager,
     pub identity_engine: Arc<dyn IdentityEngine>,
     #[allow(dead_code)]
     pub cache_manager: NoOpCacheManager,
@@ -214,6 +251,7 @@ pub struct TestAppState {
     pub session_repo: Arc<TestSessionRepository>,
     pub linked_identity_repo: Arc<TestLinkedIdentityRepository>,
     pub webhook_repo: Arc<TestWebhookRepository>,
+    pub webhook_delivery_repo: Arc<TestWebhookDeliveryRepository>,
     pub login_event_repo: Arc<TestLoginEventRepository>,
     pub security_alert_repo: Arc<TestSecurityAlertRepository>,
     #[allow(dead_code)]
@@ -226,25 +264,32 @@ pub struct TestAppState {
     pub scim_group_mapping_repo: Arc<TestScimGroupMappingRepository>,
     pub scim_log_repo: Arc<TestScimLogRepository>,
     pub saml_application_service: Arc<SamlApplicationService<TestSamlApplicationRepository>>,
+    pub admin_unit_service: Arc<AdminUnitService<TestAdminUnitRepository>>,
+    pub admin_unit_repo: Arc<TestAdminUnitRepository>,
+    pub login_identifier_service: Arc<LoginIdentifierService>,
+    pub login_identifier_repo: Arc<TestLoginIdentifierRepository>,
+    pub password_breach_check_repo: Arc<dyn PasswordBreachCheckRepository>,
+    pub account_deletion_service: Arc<AccountDeletionService<TestAccountDeletionRepository>>,
+    pub account_deletion_repo: Arc<TestAccountDeletionRepository>,
+    pub password_deny_list_service: Arc<PasswordDenyListService>,
+    pub login_forensics_service: Arc<LoginForensicsService<TestLoginForensicsRepository>>,
+    pub login_forensics_repo: Arc<TestLoginForensicsRepository>,
     pub email_verification_service: Arc<EmailVerificationService>,
     pub required_actions_service: Arc<RequiredActionService>,
-    pub totp_service: Arc<auth9_core::domains::identity::service::TotpService>,
-    pub recovery_code_service: Arc<auth9_core::domains::identity::service::RecoveryCodeService>,
-    pub ldap_authenticator:
-        Arc<dyn auth9_core::domains::identity::service::ldap::LdapAuthenticator>,
-    pub risk_policy_repo: Arc<crate::support::TestTenantRiskPolicyRepository>,
+    pub totp_service: Arc<crate::domains::identity::service::TotpService>,
+    pub recovery_code_service: Arc<crate::domains::identity::service::RecoveryCodeService>,
+    pub ldap_authenticator: Arc<dyn crate::domains::identity::service::ldap::LdapAuthenticator>,
+    pub risk_policy_repo: Arc<super::TestTenantRiskPolicyRepository>,
     pub trusted_device_service: Arc<
-        auth9_core::domains::identity::service::TrustedDeviceService<
-            crate::support::TestTrustedDeviceRepository,
-        >,
+        crate::domains::identity::service::TrustedDeviceService<super::TestTrustedDeviceRepository>,
     >,
-    pub adaptive_mfa_policy_repo: Arc<crate::support::TestAdaptiveMfaPolicyRepository>,
+    pub adaptive_mfa_policy_repo: Arc<super::TestAdaptiveMfaPolicyRepository>,
 }
 
 impl TestAppState {
-    /// Create a new test app state with the given base URL
-    pub fn new(keycloak_url: &str) -> Self {
-        let config = Arc::new(create_test_config(keycloak_url));
+    /// Create a new test app state backed by in-memory repositories
+    pub fn new() -> Self {
+        let config = Arc::new(create_test_config());
         let tenant_repo = Arc::new(TestTenantRepository::new());
         let user_repo = Arc::new(TestUserRepository::new());
         let service_repo = Arc::new(TestServiceRepository::new());
@@ -256,6 +301,7 @@ impl TestAppState {
         let session_repo = Arc::new(TestSessionRepository::new());
         let linked_identity_repo = Arc::new(TestLinkedIdentityRepository::new());
         let webhook_repo = Arc::new(TestWebhookRepository::new());
+        let webhook_delivery_repo = Arc::new(TestWebhookDeliveryRepository::new());
         let login_event_repo = Arc::new(TestLoginEventRepository::new());
         let security_alert_repo = Arc::new(TestSecurityAlertRepository::new());
         let invitation_repo = Arc::new(TestInvitationRepository::new());
@@ -263,7 +309,16 @@ impl TestAppState {
         let service_branding_repo = Arc::new(TestServiceBrandingRepository::new());
 
         // Create webhook service first (needed for webhook event publishing)
-        let webhook_service = Arc::new(WebhookService::new(webhook_repo.clone()));
+        let webhook_service = Arc::new(
+            WebhookService::new(webhook_repo.clone())
+                .with_delivery_repo(webhook_delivery_repo.clone()),
+        );
+
+        // Domain events fan out to webhooks only; tests run without a cache
+        let event_bus = Arc::new(
+            EventBus::new()
+                .with_subscriber(Arc::new(WebhookSubscriber::new(webhook_service.clone()))),
+        );
 
         // Create TenantService with repository bundle
         let tenant_repos = TenantRepositoryBundle::new(
@@ -290,16 +345,22 @@ impl TestAppState {
             audit_repo.clone(),
             rbac_repo.clone(),
         );
-        let user_service = Arc::new(UserService::new(
-            user_repos,
-            Some(webhook_service.clone()), // webhook event publisher
-        ));
+        let user_service = Arc::new(
+            UserService::new(
+                user_repos,
+                Some(webhook_service.clone()), // webhook event publisher
+            )
+            .with_event_bus(event_bus.clone()),
+        );
         let client_service = Arc::new(ClientService::new(
             service_repo.clone(),
             rbac_repo.clone(),
             None,
         ));
-        let rbac_service = Arc::new(RbacService::new(rbac_repo.clone(), None));
+        let rbac_service =
+            Arc::new(RbacService::new(rbac_repo.clone(), None).with_event_bus(event_bus.clone()));
+        let service_scope_repo = Arc::new(TestServiceScopeRepository::new());
+        let scope_catalog_service = Arc::new(ScopeCatalogService::new(service_scope_repo.clone()));
         let system_settings_service = Arc::new(SystemSettingsService::new_with_blacklist(
             system_settings_repo.clone(),
             malicious_ip_blacklist_repo.clone(),
@@ -317,11 +378,11 @@ impl TestAppState {
         let cache_manager = NoOpCacheManager::new();
         let db_pool = sqlx::MySqlPool::connect_lazy(&config.database.url).unwrap();
         let identity_sessions: Arc<dyn IdentitySessionStore> =
-            Arc::new(crate::support::noop_identity_engine::NoOpSessionStore);
+            Arc::new(super::noop_identity_engine::NoOpSessionStore);
         let federation_broker: Arc<dyn FederationBroker> =
-            Arc::new(crate::support::noop_identity_engine::NoOpFederationBroker);
+            Arc::new(super::noop_identity_engine::NoOpFederationBroker);
         let identity_engine: Arc<dyn IdentityEngine> =
-            Arc::new(crate::support::noop_identity_engine::NoOpIdentityEngine);
+            Arc::new(super::noop_identity_engine::NoOpIdentityEngine);
 
         // Create identity sync service for tests
         let identity_sync_service = Arc::new(IdentitySyncService::new(identity_engine.clone()));
@@ -340,7 +401,7 @@ impl TestAppState {
             session_repo.clone(),
             user_repo.clone(),
             identity_sessions,
-            Some(webhook_service.clone()), // webhook event publisher
+            Some(event_bus.clone()),
         ));
         let identity_provider_service = Arc::new(IdentityProviderService::new(
             linked_identity_repo.clone(),
@@ -359,7 +420,7 @@ impl TestAppState {
             Arc::new(WebAuthnService::new(
                 webauthn_instance,
                 webauthn_repo,
-                Arc::new(auth9_core::cache::NoOpCacheManager::new()),
+                Arc::new(crate::cache::NoOpCacheManager::new()),
                 Some(identity_engine.clone()),
                 config.webauthn.challenge_ttl_secs,
             ))
@@ -388,6 +449,24 @@ impl TestAppState {
             Arc::new(TestSamlApplicationRepository::new()),
             identity_engine.clone(),
         ));
+        let admin_unit_repo = Arc::new(TestAdminUnitRepository::new(user_repo.clone()));
+        let admin_unit_service = Arc::new(AdminUnitService::new(admin_unit_repo.clone()));
+        let login_identifier_repo = Arc::new(TestLoginIdentifierRepository::new());
+        let account_deletion_repo = Arc::new(TestAccountDeletionRepository::new(user_repo.clone()));
+        let account_deletion_service = Arc::new(AccountDeletionService::new(
+            account_deletion_repo.clone(),
+            config.account_deletion.clone(),
+        ));
+        let password_deny_list_service = Arc::new(PasswordDenyListService::new(Arc::new(
+            TestPasswordDenyListRepository::new(user_repo.clone()),
+        )));
+        let login_forensics_repo = Arc::new(TestLoginForensicsRepository::new());
+        let login_forensics_service = Arc::new(LoginForensicsService::new(
+            login_forensics_repo.clone(),
+            config.login_forensics.clone(),
+        ));
+        let login_identifier_service =
+            Arc::new(LoginIdentifierService::new(login_identifier_repo.clone()));
 
         let scim_token_service = Arc::new(ScimTokenService::new(scim_token_repo));
         let scim_service = Arc::new(ScimService::new(
@@ -406,19 +485,19 @@ impl TestAppState {
 
         // MFA services with mock credential repo
         let mock_cred_repo: Arc<
-            dyn auth9_core::identity_engine::repository::credential::CredentialRepository,
+            dyn crate::identity_engine::repository::credential::CredentialRepository,
         > = Arc::new(
-            auth9_core::identity_engine::repository::credential::CredentialRepositoryImpl::new(
+            crate::identity_engine::repository::credential::CredentialRepositoryImpl::new(
                 db_pool.clone(),
             ),
         );
-        let totp_service = Arc::new(auth9_core::domains::identity::service::TotpService::new(
+        let totp_service = Arc::new(crate::domains::identity::service::TotpService::new(
             mock_cred_repo.clone(),
             Arc::new(cache_manager.clone()),
-            auth9_core::crypto::EncryptionKey::new([0u8; 32]),
+            crate::crypto::EncryptionKey::new([0u8; 32]),
         ));
         let recovery_code_service = Arc::new(
-            auth9_core::domains::identity::service::RecoveryCodeService::new(mock_cred_repo),
+            crate::domains::identity::service::RecoveryCodeService::new(mock_cred_repo),
         );
 
         Self {
@@ -427,6 +506,8 @@ impl TestAppState {
             user_service,
             client_service,
             rbac_service,
+            scope_catalog_service,
+            service_scope_repo,
             system_settings_service,
             email_service,
             email_template_service,
@@ -455,6 +536,7 @@ impl TestAppState {
             session_repo,
             linked_identity_repo,
             webhook_repo,
+            webhook_delivery_repo,
             login_event_repo,
             security_alert_repo,
             invitation_repo,
@@ -464,28 +546,36 @@ impl TestAppState {
             scim_group_mapping_repo,
             scim_log_repo,
             saml_application_service,
+            admin_unit_service,
+            admin_unit_repo,
+            login_identifier_service,
+            login_identifier_repo,
+            password_breach_check_repo: Arc::new(TestPasswordBreachCheckRepository::new()),
+            account_deletion_service,
+            account_deletion_repo,
+            password_deny_list_service,
+            login_forensics_service,
+            login_forensics_repo,
             email_verification_service,
             required_actions_service,
             totp_service,
             recovery_code_service,
             ldap_authenticator: Arc::new(
-                auth9_core::domains::identity::service::ldap::DefaultLdapAuthenticator::new(),
+                crate::domains::identity::service::ldap::DefaultLdapAuthenticator::new(),
             ),
-            risk_policy_repo: Arc::new(crate::support::TestTenantRiskPolicyRepository::new()),
+            risk_policy_repo: Arc::new(super::TestTenantRiskPolicyRepository::new()),
             trusted_device_service: Arc::new(
-                auth9_core::domains::identity::service::TrustedDeviceService::new(Arc::new(
-                    crate::support::TestTrustedDeviceRepository::new(),
+                crate::domains::identity::service::TrustedDeviceService::new(Arc::new(
+                    super::TestTrustedDeviceRepository::new(),
                 )),
             ),
-            adaptive_mfa_policy_repo: Arc::new(
-                crate::support::TestAdaptiveMfaPolicyRepository::new(),
-            ),
+            adaptive_mfa_policy_repo: Arc::new(super::TestAdaptiveMfaPolicyRepository::new()),
         }
     }
 
     /// Enable public registration by setting allow_registration to true in branding config
     pub async fn enable_public_registration(&self) {
-        use auth9_core::models::branding::BrandingConfig;
+        use crate::models::branding::BrandingConfig;
         let config = BrandingConfig {
             allow_registration: true,
             ..Default::default()
@@ -494,6 +584,12 @@ impl TestAppState {
     }
 }
 
+impl Default for TestAppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Implement HasServices trait for TestAppState
 /// This allows using production handlers with test repositories
 impl HasServices for TestAppState {
@@ -511,6 +607,10 @@ impl HasServices for TestAppState {
     type CascadeInvitationRepo = TestInvitationRepository;
     type ActionRepo = TestActionRepository;
     type SamlApplicationRepo = TestSamlApplicationRepository;
+    type AdminUnitRepo = TestAdminUnitRepository;
+    type AccountDeletionRepo = TestAccountDeletionRepository;
+    type LoginForensicsRepo = TestLoginForensicsRepository;
+    type ServiceScopeRepo = TestServiceScopeRepository;
 
     fn config(&self) -> &Config {
         &self.config
@@ -555,6 +655,10 @@ impl HasServices for TestAppState {
         &self.rbac_service
     }
 
+    fn scope_catalog_service(&self) -> &ScopeCatalogService<Self::ServiceScopeRepo> {
+        &self.scope_catalog_service
+    }
+
     fn audit_repo(&self) -> &Self::AuditRepo {
         &self.audit_repo
     }
@@ -575,6 +679,30 @@ impl HasServices for TestAppState {
         &self.saml_application_service
     }
 
+    fn admin_unit_service(&self) -> &AdminUnitService<Self::AdminUnitRepo> {
+        &self.admin_unit_service
+    }
+
+    fn login_identifier_service(&self) -> &LoginIdentifierService {
+        &self.login_identifier_service
+    }
+
+    fn password_breach_check_repo(&self) -> &Arc<dyn PasswordBreachCheckRepository> {
+        &self.password_breach_check_repo
+    }
+
+    fn account_deletion_service(&self) -> &AccountDeletionService<Self::AccountDeletionRepo> {
+        &self.account_deletion_service
+    }
+
+    fn password_deny_list_service(&self) -> &PasswordDenyListService {
+        &self.password_deny_list_service
+    }
+
+    fn login_forensics_service(&self) -> &LoginForensicsService<Self::LoginForensicsRepo> {
+        &self.login_forensics_service
+    }
+
     async fn check_ready(&self) -> (bool, bool) {
         // In tests, always return ready
         (true, true)
@@ -748,58 +876,55 @@ impl HasCache for TestAppState {
     }
 }
 
-impl auth9_core::state::HasEmailVerification for TestAppState {
+impl crate::state::HasEmailVerification for TestAppState {
     fn email_verification_service(&self) -> &EmailVerificationService {
         &self.email_verification_service
     }
 }
 
-impl auth9_core::state::HasRequiredActions for TestAppState {
+impl crate::state::HasRequiredActions for TestAppState {
     fn required_actions_service(&self) -> &RequiredActionService {
         &self.required_actions_service
     }
 }
 
-impl auth9_core::state::HasMfa for TestAppState {
-    fn totp_service(&self) -> &auth9_core::domains::identity::service::TotpService {
+impl crate::state::HasMfa for TestAppState {
+    fn totp_service(&self) -> &crate::domains::identity::service::TotpService {
         &self.totp_service
     }
 
-    fn recovery_code_service(
-        &self,
-    ) -> &auth9_core::domains::identity::service::RecoveryCodeService {
+    fn recovery_code_service(&self) -> &crate::domains::identity::service::RecoveryCodeService {
         &self.recovery_code_service
     }
 }
 
 /// Implement HasLdapAuth trait for TestAppState
-impl auth9_core::state::HasLdapAuth for TestAppState {
+impl crate::state::HasLdapAuth for TestAppState {
     fn ldap_authenticator(
         &self,
-    ) -> &dyn auth9_core::domains::identity::service::ldap::LdapAuthenticator {
+    ) -> &dyn crate::domains::identity::service::ldap::LdapAuthenticator {
         &*self.ldap_authenticator
     }
 }
 
-impl auth9_core::domains::security_observability::api::risk::HasRiskPolicy for TestAppState {
-    type RiskPolicyRepo = crate::support::TestTenantRiskPolicyRepository;
+impl crate::domains::security_observability::api::risk::HasRiskPolicy for TestAppState {
+    type RiskPolicyRepo = super::TestTenantRiskPolicyRepository;
     fn risk_policy_repo(&self) -> &Self::RiskPolicyRepo {
         &self.risk_policy_repo
     }
 }
 
-impl auth9_core::state::HasTrustedDevices for TestAppState {
-    type TrustedDeviceRepo = crate::support::TestTrustedDeviceRepository;
+impl crate::state::HasTrustedDevices for TestAppState {
+    type TrustedDeviceRepo = super::TestTrustedDeviceRepository;
     fn trusted_device_service(
         &self,
-    ) -> &auth9_core::domains::identity::service::TrustedDeviceService<Self::TrustedDeviceRepo>
-    {
+    ) -> &crate::domains::identity::service::TrustedDeviceService<Self::TrustedDeviceRepo> {
         &self.trusted_device_service
     }
 }
 
-impl auth9_core::state::HasAdaptiveMfa for TestAppState {
-    type AdaptiveMfaPolicyRepo = crate::support::TestAdaptiveMfaPolicyRepository;
+impl crate::state::HasAdaptiveMfa for TestAppState {
+    type AdaptiveMfaPolicyRepo = super::TestAdaptiveMfaPolicyRepository;
     fn adaptive_mfa_policy_repo(&self) -> &Self::AdaptiveMfaPolicyRepo {
         &self.adaptive_mfa_policy_repo
     }
@@ -839,7 +964,7 @@ impl HasScimServices for TestAppState {
 
 /// Build a router for HTTP handler tests using the PRODUCTION router.
 ///
-/// This uses the actual `build_full_router` from `auth9_core::server` with TestAppState,
+/// This uses the actual `build_full_router` from `crate::server` with TestAppState,
 /// which means these tests cover the real production handler code in `src/api/*.rs`.
 pub fn build_test_router(state: TestAppState) -> Router {
     // Use the production router with TestAppState and disabled rate limiting
@@ -847,7 +972,7 @@ pub fn build_test_router(state: TestAppState) -> Router {
     build_full_router(
         state,
         RateLimitState::noop(),
-        auth9_core::middleware::CaptchaState::disabled(),
+        crate::middleware::CaptchaState::disabled(),
         std::sync::Arc::new(None),
     )
 }
@@ -857,7 +982,7 @@ pub fn build_test_router(state: TestAppState) -> Router {
 /// This creates a minimal router that includes the email template handlers,
 /// allowing us to test them without implementing all traits required by build_full_router.
 pub fn build_email_template_test_router(state: TestAppState) -> Router {
-    use auth9_core::domains::platform::api::email_template;
+    use crate::domains::platform::api::email_template;
     use axum::routing::{get, post};
 
     Router::new()
@@ -882,7 +1007,7 @@ pub fn build_email_template_test_router(state: TestAppState) -> Router {
 ///
 /// This creates a minimal router that includes the system settings handlers.
 pub fn build_system_settings_test_router(state: TestAppState) -> Router {
-    use auth9_core::domains::platform::api::system_settings;
+    use crate::domains::platform::api::system_settings;
     use axum::routing::{get, post};
 
     Router::new()
@@ -911,7 +1036,7 @@ pub fn build_system_settings_test_router(state: TestAppState) -> Router {
 ///
 /// This creates a minimal router that includes the branding handlers.
 pub fn build_branding_test_router(state: TestAppState) -> Router {
-    use auth9_core::domains::platform::api::branding;
+    use crate::domains::platform::api::branding;
     use axum::routing::get;
 
     Router::new()
@@ -1259,13 +1384,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_test_config() {
-        let config = create_test_config("http://localhost:8080");
+        let config = create_test_config();
         assert_eq!(config.jwt.issuer, "https://auth9.test");
     }
 
     #[tokio::test]
     async fn test_test_app_state_creation() {
-        let state = TestAppState::new("http://localhost:8080");
+        let state = TestAppState::new();
         assert_eq!(state.config.jwt.issuer, "https://auth9.test");
     }
 }
@@ -0,0 +1,5476 @@
+//! In-process Auth9 for integration tests
+//!
+//! Enabled with the `test-harness` feature. Provides in-memory repositories,
+//! a no-op identity engine and [`http::TestAppState`], which runs the
+//! production router without external dependencies (no database, no Redis,
+//! no identity backend). Auth9's own API tests use the same harness, so
+//! downstream services test against the real handlers:
+//!
+//! ```no_run
+//! # async fn example() {
+//! use auth9_core::test_harness::http::{build_test_router, get_json_with_auth, TestAppState};
+//! use auth9_core::test_harness::{create_test_admin_token_for_user, create_test_tenant};
+//!
+//! let state = TestAppState::new();
+//! state.tenant_repo.add_tenant(create_test_tenant(None)).await;
+//! let app = build_test_router(state);
+//!
+//! let token = create_test_admin_token_for_user(uuid::Uuid::new_v4());
+//! let (status, body) =
+//!     get_json_with_auth::<serde_json::Value>(&app, "/api/v1/tenants", &token).await;
+//! # }
+//! ```
+//!
+//! Tokens are signed with [`test_jwt_config`], which the harness config uses.
+
+pub mod http;
+pub mod noop_identity_engine;
+pub use noop_identity_engine::NoOpIdentityEngine;
+
+pub use crate::cache::NoOpCacheManager;
+pub use crate::config::JwtConfig;
+pub use crate::domains::authorization::service::{ClientService, RbacService};
+pub use crate::domains::tenant_access::service::{
+    TenantRepositoryBundle, TenantService, UserRepositoryBundle, UserService,
+};
+pub use crate::error::{AppError, Result};
+pub use crate::jwt::JwtManager;
+pub use crate::models::action::{Action, CreateActionInput, UpdateActionInput};
+pub use crate::models::analytics::{
+    AlertSeverity, CreateLoginEventInput, CreateSecurityAlertInput, CreateWebhookInput, LoginEvent,
+    LoginEventType, LoginStats, SecurityAlert, SecurityAlertType, UpdateWebhookInput, Webhook,
+};
+use crate::models::audit_search::{AuditHistogramBucket, AuditHistogramInterval};
+use crate::models::cardinality::CardinalityLimits;
+pub use crate::models::common::StringUuid;
+use crate::models::common::{ServiceId, TenantId, TokenTtlOverrides, UserId};
+pub use crate::models::invitation::{CreateInvitationInput, Invitation, InvitationStatus};
+pub use crate::models::linked_identity::{CreateLinkedIdentityInput, LinkedIdentity};
+use crate::models::list_query::ListQuery;
+pub use crate::models::password::{CreatePasswordResetTokenInput, PasswordResetToken};
+pub use crate::models::rbac::{
+    AssignRolesInput, CreatePermissionInput, CreateRoleInput, Permission, Role, UpdateRoleInput,
+    UserRolesInTenant,
+};
+pub use crate::models::service::{
+    Client, CreateServiceInput, Service, ServiceStatus, UpdateServiceInput,
+};
+pub use crate::models::session::{
+    CreateSessionInput, Session, SessionCreateOutcome, SessionLimit, SessionLimitPolicy,
+};
+pub use crate::models::system_settings::{
+    MaliciousIpBlacklistEntry, SystemSettingRow, TenantMaliciousIpBlacklistEntry,
+    UpsertSystemSettingInput,
+};
+pub use crate::models::tenant::{
+    CreateTenantInput, RenameTenantInput, Tenant, TenantSettings, TenantSlugRedirect, TenantStatus,
+    UpdateTenantInput,
+};
+pub use crate::models::user::{
+    AddUserToTenantInput, CreateUserInput, TenantUser, UpdateUserInput, User, UserCohort,
+    UserTimelineEntry, UserTimelineSource,
+};
+pub use crate::models::webauthn::{CreatePasskeyInput, StoredPasskey};
+pub use crate::models::webhook_delivery::{WebhookDelivery, WebhookDeliveryFilter};
+use crate::repository::audit::{AuditLog, AuditLogQuery, AuditRepository, CreateAuditLogInput};
+use crate::repository::service::SERVICE_LIST_SPEC;
+use crate::repository::tenant::TENANT_LIST_SPEC;
+pub use crate::repository::{
+    ActionRepository, InvitationRepository, LinkedIdentityRepository, LoginEventRepository,
+    MaliciousIpBlacklistRepository, PasswordResetRepository, RbacRepository,
+    SecurityAlertRepository, ServiceBrandingRepository, ServiceRepository, SessionRepository,
+    SystemSettingsRepository, TenantRepository, UserRepository, WebAuthnRepository,
+    WebhookDeliveryRepository, WebhookRepository,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use std::collections::HashMap;
+pub use std::sync::Arc;
+use tokio::sync::RwLock;
+pub use uuid::Uuid;
+
+// ============================================================================
+// Test Configuration
+// ============================================================================
+
+pub fn test_jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret: "test-secret-key-for-api-testing-purposes".to_string(), // pragma: allowlist secret
+        issuer: "https://auth9.test".to_string(),
+        access_token_ttl_secs: 3600,
+        refresh_token_ttl_secs: 604800,
+        private_key_pem: None,
+        public_key_pem: None,
+        previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
+    }
+}
+
+pub fn create_test_jwt_manager() -> JwtManager {
+    JwtManager::new(test_jwt_config())
+}
+
+/// Create an identity token for platform-level testing (platform admin)
+///
+/// Uses "admin@auth9.local" to match the `platform_admin_emails` allowlist
+/// in test configs, so the token passes `is_platform_admin_email()` checks.
+pub fn create_test_identity_token() -> String {
+    let jwt_manager = create_test_jwt_manager();
+    let user_id = Uuid::new_v4();
+    jwt_manager
+        .create_identity_token(user_id, "admin@auth9.local", Some("Platform Admin"))
+        .expect("Failed to create test identity token")
+}
+
+/// Create an identity token for a specific user ID (platform-level)
+pub fn create_test_identity_token_for_user(user_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_identity_token(user_id, "test-user@test.com", Some("Test User"))
+        .expect("Failed to create test identity token")
+}
+
+/// Create an identity token for a specific user ID with platform admin privileges
+#[allow(dead_code)]
+pub fn create_test_admin_token_for_user(user_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_identity_token(user_id, "admin@auth9.local", Some("Platform Admin"))
+        .expect("Failed to create test identity token")
+}
+
+/// Create a tenant access token for API testing (platform admin)
+///
+/// Uses "admin@auth9.local" email with admin role, suitable for testing
+/// endpoints that require tenant-scoped authentication after the refactor
+/// that restricts identity tokens to auth/profile paths only.
+pub fn create_test_tenant_access_token() -> String {
+    let jwt_manager = create_test_jwt_manager();
+    let user_id = Uuid::new_v4();
+    let tenant_id = Uuid::new_v4();
+    jwt_manager
+        .create_tenant_access_token(
+            user_id,
+            "admin@auth9.local",
+            tenant_id,
+            "auth9-test-service",
+            vec!["admin".to_string()],
+            vec![
+                "rbac:*".to_string(),
+                "user:*".to_string(),
+                "service:*".to_string(),
+            ],
+        )
+        .expect("Failed to create test tenant access token")
+}
+
+/// Create a tenant access token for a specific tenant (platform admin)
+pub fn create_test_tenant_access_token_for_tenant(tenant_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    let user_id = Uuid::new_v4();
+    jwt_manager
+        .create_tenant_access_token(
+            user_id,
+            "admin@auth9.local",
+            tenant_id,
+            "auth9-test-service",
+            vec!["admin".to_string()],
+            vec![
+                "rbac:*".to_string(),
+                "user:*".to_string(),
+                "service:*".to_string(),
+            ],
+        )
+        .expect("Failed to create test tenant access token")
+}
+
+/// Create a tenant access token for a specific user and tenant
+pub fn create_test_tenant_access_token_for_user(user_id: Uuid, tenant_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            user_id,
+            "admin@auth9.local",
+            tenant_id,
+            "auth9-test-service",
+            vec!["admin".to_string()],
+            vec![
+                "rbac:*".to_string(),
+                "user:*".to_string(),
+                "service:*".to_string(),
+            ],
+        )
+        .expect("Failed to create test tenant access token")
+}
+
+#[allow(dead_code)]
+pub fn create_test_cache() -> NoOpCacheManager {
+    NoOpCacheManager::new()
+}
+
+// ============================================================================
+// Test Repository Implementations
+// ============================================================================
+
+/// Configurable test tenant repository
+pub struct TestTenantRepository {
+    tenants: RwLock<Vec<Tenant>>,
+    slug_redirects: RwLock<Vec<TenantSlugRedirect>>,
+}
+
+impl TestTenantRepository {
+    pub fn new() -> Self {
+        Self {
+            tenants: RwLock::new(vec![]),
+            slug_redirects: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_tenant(&self, tenant: Tenant) {
+        self.tenants.write().await.push(tenant);
+    }
+
+    #[allow(dead_code)]
+    pub async fn set_tenants(&self, tenants: Vec<Tenant>) {
+        *self.tenants.write().await = tenants;
+    }
+
+    #[allow(dead_code)]
+    pub async fn clear(&self) {
+        self.tenants.write().await.clear();
+    }
+}
+
+impl Default for TestTenantRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TenantRepository for TestTenantRepository {
+    async fn create(&self, input: &CreateTenantInput) -> Result<Tenant> {
+        let tenant = Tenant {
+            id: StringUuid::new_v4(),
+            name: input.name.clone(),
+            slug: input.slug.clone(),
+            domain: input.domain.clone(),
+            logo_url: input.logo_url.clone(),
+            settings: input.settings.clone().unwrap_or_default(),
+            status: TenantStatus::Active,
+            password_policy: None,
+            data_region: input.data_region.clone(),
+            version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        self.tenants.write().await.push(tenant.clone());
+        Ok(tenant)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Tenant>> {
+        let tenants = self.tenants.read().await;
+        Ok(tenants.iter().find(|t| t.id == id).cloned())
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Tenant>> {
+        let tenants = self.tenants.read().await;
+        Ok(tenants.iter().find(|t| t.slug == slug).cloned())
+    }
+
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<Tenant>> {
+        let tenants = self.tenants.read().await;
+        let start = offset as usize;
+        let end = (offset + limit) as usize;
+        Ok(tenants
+            .iter()
+            .skip(start)
+            .take(end - start)
+            .cloned()
+            .collect())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.tenants.read().await.len() as i64)
+    }
+
+    async fn search(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<Tenant>> {
+        let tenants = self.tenants.read().await;
+        let query_lower = query.to_lowercase();
+        let filtered: Vec<Tenant> = tenants
+            .iter()
+            .filter(|t| {
+                t.name.to_lowercase().contains(&query_lower)
+                    || t.slug.to_lowercase().contains(&query_lower)
+            })
+            .cloned()
+            .collect();
+        let start = offset as usize;
+        let end = (offset + limit) as usize;
+        Ok(filtered.into_iter().skip(start).take(end - start).collect())
+    }
+
+    async fn count_search(&self, query: &str) -> Result<i64> {
+        let tenants = self.tenants.read().await;
+        let query_lower = query.to_lowercase();
+        let count = tenants
+            .iter()
+            .filter(|t| {
+                t.name.to_lowercase().contains(&query_lower)
+                    || t.slug.to_lowercase().contains(&query_lower)
+            })
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn list_filtered(&self, query: &ListQuery) -> Result<(Vec<Tenant>, i64)> {
+        // Validates like the SQL implementation; applies search and paging only
+        TENANT_LIST_SPEC.validate(query)?;
+        let tenants = self.tenants.read().await;
+        let search = query.search.as_deref().map(str::to_lowercase);
+        let filtered: Vec<Tenant> = tenants
+            .iter()
+            .filter(|t| {
+                search.as_ref().is_none_or(|q| {
+                    t.name.to_lowercase().contains(q) || t.slug.to_lowercase().contains(q)
+                })
+            })
+            .cloned()
+            .collect();
+        let total = filtered.len() as i64;
+        Ok((
+            filtered
+                .into_iter()
+                .skip(query.offset() as usize)
+                .take(query.per_page as usize)
+                .collect(),
+            total,
+        ))
+    }
+
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateTenantInput,
+        expected_version: Option<i32>,
+    ) -> Result<Tenant> {
+        let mut tenants = self.tenants.write().await;
+        let tenant = tenants
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+        AppError::check_version("tenant", expected_version, tenant.version)?;
+
+        if let Some(name) = &input.name {
+            tenant.name = name.clone();
+        }
+        if let Some(logo_url) = &input.logo_url {
+            tenant.logo_url = Some(logo_url.clone());
+        }
+        if let Some(settings) = &input.settings {
+            tenant.settings = settings.clone();
+        }
+        if let Some(status) = &input.status {
+            tenant.status = status.clone();
+        }
+        tenant.version += 1;
+        tenant.updated_at = Utc::now();
+        Ok(tenant.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut tenants = self.tenants.write().await;
+        let pos = tenants
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+        tenants.remove(pos);
+        Ok(())
+    }
+
+    async fn update_password_policy(
+        &self,
+        id: StringUuid,
+        policy: &crate::models::password::PasswordPolicy,
+    ) -> Result<Tenant> {
+        let mut tenants = self.tenants.write().await;
+        let tenant = tenants
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+        tenant.password_policy = Some(policy.clone());
+        tenant.version += 1;
+        tenant.updated_at = Utc::now();
+        Ok(tenant.clone())
+    }
+
+    async fn set_data_region(&self, id: StringUuid, data_region: Option<String>) -> Result<Tenant> {
+        let mut tenants = self.tenants.write().await;
+        let tenant = tenants
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+        tenant.data_region = data_region;
+        tenant.version += 1;
+        tenant.updated_at = Utc::now();
+        Ok(tenant.clone())
+    }
+
+    async fn find_slug_redirect(&self, slug: &str) -> Result<Option<TenantSlugRedirect>> {
+        let redirects = self.slug_redirects.read().await;
+        Ok(redirects
+            .iter()
+            .find(|r| r.old_slug == slug && r.expires_at > Utc::now())
+            .cloned())
+    }
+
+    async fn rename(
+        &self,
+        id: StringUuid,
+        input: &RenameTenantInput,
+        redirect_expires_at: DateTime<Utc>,
+    ) -> Result<(Tenant, Option<TenantSlugRedirect>)> {
+        let mut tenants = self.tenants.write().await;
+        let mut redirects = self.slug_redirects.write().await;
+        let old_slug = tenants
+            .iter()
+            .find(|t| t.id == id)
+            .map(|t| t.slug.clone())
+            .ok_or_else(|| AppError::NotFound(format!("Tenant {} not found", id)))?;
+
+        let new_slug = input.slug.clone().filter(|slug| *slug != old_slug);
+        let mut redirect = None;
+        if let Some(new_slug) = &new_slug {
+            if tenants.iter().any(|t| &t.slug == new_slug)
+                || redirects
+                    .iter()
+                    .any(|r| &r.old_slug == new_slug && r.tenant_id != id)
+            {
+                return Err(AppError::Conflict(format!(
+                    "Slug '{}' is already taken",
+                    new_slug
+                )));
+            }
+            redirects.retain(|r| &r.old_slug != new_slug && r.old_slug != old_slug);
+            let entry = TenantSlugRedirect {
+                old_slug: old_slug.clone(),
+                tenant_id: id,
+                expires_at: redirect_expires_at,
+                created_at: Utc::now(),
+            };
+            redirects.push(entry.clone());
+            redirect = Some(entry);
+        }
+
+        let tenant = tenants.iter_mut().find(|t| t.id == id).unwrap();
+        if let Some(name) = &input.name {
+            tenant.name = name.clone();
+        }
+        if let Some(new_slug) = new_slug {
+            tenant.slug = new_slug;
+        }
+        tenant.version += 1;
+        tenant.updated_at = Utc::now();
+        Ok((tenant.clone(), redirect))
+    }
+}
+
+/// Configurable test user repository
+pub struct TestUserRepository {
+    users: RwLock<Vec<User>>,
+    tenant_users: RwLock<Vec<TenantUser>>,
+    timeline: RwLock<Vec<(StringUuid, UserTimelineEntry)>>,
+}
+
+impl TestUserRepository {
+    pub fn new() -> Self {
+        Self {
+            users: RwLock::new(vec![]),
+            tenant_users: RwLock::new(vec![]),
+            timeline: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_user(&self, user: User) {
+        self.users.write().await.push(user);
+    }
+
+    #[allow(dead_code)]
+    pub async fn set_users(&self, users: Vec<User>) {
+        *self.users.write().await = users;
+    }
+
+    pub async fn add_tenant_user(&self, tenant_user: TenantUser) {
+        self.tenant_users.write().await.push(tenant_user);
+    }
+
+    #[allow(dead_code)]
+    pub async fn add_timeline_entry(&self, user_id: StringUuid, entry: UserTimelineEntry) {
+        self.timeline.write().await.push((user_id, entry));
+    }
+}
+
+impl Default for TestUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl UserRepository for TestUserRepository {
+    async fn create(&self, identity_subject: &str, input: &CreateUserInput) -> Result<User> {
+        let user = User {
+            id: StringUuid::new_v4(),
+            email: input.email.clone(),
+            display_name: input.display_name.clone(),
+            avatar_url: input.avatar_url.clone(),
+            identity_subject: identity_subject.to_string(),
+            scim_external_id: None,
+            scim_provisioned_by: None,
+            mfa_enabled: false,
+            email_otp_enabled: false,
+            password_changed_at: None,
+            locked_until: None,
+            locale: None,
+            version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        self.users.write().await.push(user.clone());
+        Ok(user)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<User>> {
+        let users = self.users.read().await;
+        Ok(users.iter().find(|u| u.id == id).cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>> {
+        let users = self.users.read().await;
+        Ok(users.iter().find(|u| u.email == email).cloned())
+    }
+
+    async fn find_by_identity_subject(&self, identity_subject: &str) -> Result<Option<User>> {
+        let users = self.users.read().await;
+        Ok(users
+            .iter()
+            .find(|u| u.identity_subject == identity_subject)
+            .cloned())
+    }
+
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<User>> {
+        let users = self.users.read().await;
+        let start = offset as usize;
+        Ok(users
+            .iter()
+            .skip(start)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.users.read().await.len() as i64)
+    }
+
+    async fn search(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<User>> {
+        let users = self.users.read().await;
+        let query_lower = query.to_lowercase();
+        let filtered: Vec<User> = users
+            .iter()
+            .filter(|u| {
+                u.email.to_lowercase().contains(&query_lower)
+                    || u.display_name
+                        .as_ref()
+                        .map(|n| n.to_lowercase().contains(&query_lower))
+                        .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        let start = offset as usize;
+        Ok(filtered
+            .into_iter()
+            .skip(start)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn search_count(&self, query: &str) -> Result<i64> {
+        let users = self.users.read().await;
+        let query_lower = query.to_lowercase();
+        let count = users
+            .iter()
+            .filter(|u| {
+                u.email.to_lowercase().contains(&query_lower)
+                    || u.display_name
+                        .as_ref()
+                        .map(|n| n.to_lowercase().contains(&query_lower))
+                        .unwrap_or(false)
+            })
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateUserInput,
+        expected_version: Option<i32>,
+    ) -> Result<User> {
+        let mut users = self.users.write().await;
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        AppError::check_version("user", expected_version, user.version)?;
+
+        if let Some(display_name) = &input.display_name {
+            user.display_name = Some(display_name.clone());
+        }
+        if let Some(avatar_url) = &input.avatar_url {
+            user.avatar_url = Some(avatar_url.clone());
+        }
+        user.version += 1;
+        user.updated_at = Utc::now();
+        Ok(user.clone())
+    }
+
+    async fn update_mfa_enabled(&self, id: StringUuid, enabled: bool) -> Result<User> {
+        let mut users = self.users.write().await;
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        user.mfa_enabled = enabled;
+        user.version += 1;
+        user.updated_at = Utc::now();
+        Ok(user.clone())
+    }
+
+    async fn update_email_otp_enabled(&self, id: StringUuid, enabled: bool) -> Result<User> {
+        let mut users = self.users.write().await;
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        user.email_otp_enabled = enabled;
+        user.version += 1;
+        user.updated_at = Utc::now();
+        Ok(user.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut users = self.users.write().await;
+        let pos = users
+            .iter()
+            .position(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        users.remove(pos);
+        Ok(())
+    }
+
+    async fn add_to_tenant(&self, input: &AddUserToTenantInput) -> Result<TenantUser> {
+        let tenant_user = TenantUser {
+            id: StringUuid::new_v4(),
+            user_id: StringUuid::from(input.user_id),
+            tenant_id: StringUuid::from(input.tenant_id),
+            role_in_tenant: input.role_in_tenant.clone(),
+            joined_at: Utc::now(),
+        };
+        self.tenant_users.write().await.push(tenant_user.clone());
+        Ok(tenant_user)
+    }
+
+    async fn update_role_in_tenant(
+        &self,
+        user_id: UserId,
+        tenant_id: TenantId,
+        role: &str,
+    ) -> Result<TenantUser> {
+        let mut tenant_users = self.tenant_users.write().await;
+        let tu = tenant_users
+            .iter_mut()
+            .find(|tu| *tu.user_id == *user_id && *tu.tenant_id == *tenant_id)
+            .ok_or_else(|| AppError::NotFound("User-tenant relationship not found".to_string()))?;
+        tu.role_in_tenant = role.to_string();
+        Ok(tu.clone())
+    }
+
+    async fn remove_from_tenant(&self, user_id: UserId, tenant_id: TenantId) -> Result<()> {
+        let mut tenant_users = self.tenant_users.write().await;
+        let pos = tenant_users
+            .iter()
+            .position(|tu| *tu.user_id == *user_id && *tu.tenant_id == *tenant_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("User {} not in tenant {}", user_id, tenant_id))
+            })?;
+        tenant_users.remove(pos);
+        Ok(())
+    }
+
+    async fn find_tenant_users(
+        &self,
+        tenant_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>> {
+        let tenant_users = self.tenant_users.read().await;
+        let users = self.users.read().await;
+        let user_ids: Vec<StringUuid> = tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .map(|tu| tu.user_id)
+            .collect();
+        Ok(users
+            .iter()
+            .filter(|u| user_ids.contains(&u.id))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn search_tenant_users(
+        &self,
+        tenant_id: StringUuid,
+        query: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>> {
+        let tenant_users = self.tenant_users.read().await;
+        let users = self.users.read().await;
+        let user_ids: Vec<StringUuid> = tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .map(|tu| tu.user_id)
+            .collect();
+        let query_lower = query.to_lowercase();
+        Ok(users
+            .iter()
+            .filter(|u| {
+                user_ids.contains(&u.id)
+                    && (u.email.to_lowercase().contains(&query_lower)
+                        || u.display_name
+                            .as_ref()
+                            .map(|n| n.to_lowercase().contains(&query_lower))
+                            .unwrap_or(false))
+            })
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_tenant_users(&self, tenant_id: StringUuid) -> Result<i64> {
+        let tenant_users = self.tenant_users.read().await;
+        let count = tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn find_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<User>> {
+        // Login history is not tracked here, so every member counts as never
+        // having logged in for `LastLoginBefore`
+        let tenant_users = self.tenant_users.read().await;
+        let users = self.users.read().await;
+        let user_ids: Vec<StringUuid> = tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .filter(|tu| match cohort {
+                UserCohort::Role { role } => tu.role_in_tenant == *role,
+                UserCohort::All | UserCohort::LastLoginBefore { .. } => true,
+            })
+            .map(|tu| tu.user_id)
+            .collect();
+        let mut matching: Vec<User> = users
+            .iter()
+            .filter(|u| user_ids.contains(&u.id))
+            .cloned()
+            .collect();
+        matching.sort_by_key(|u| u.id.to_string());
+        Ok(matching
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_tenant_users_in_cohort(
+        &self,
+        tenant_id: StringUuid,
+        cohort: &UserCohort,
+    ) -> Result<i64> {
+        let users = self
+            .find_tenant_users_in_cohort(tenant_id, cohort, 0, i64::MAX)
+            .await?;
+        Ok(users.len() as i64)
+    }
+
+    async fn search_tenant_users_count(&self, tenant_id: StringUuid, query: &str) -> Result<i64> {
+        let tenant_users = self.tenant_users.read().await;
+        let users = self.users.read().await;
+        let user_ids: Vec<StringUuid> = tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .map(|tu| tu.user_id)
+            .collect();
+        let query_lower = query.to_lowercase();
+        let count = users
+            .iter()
+            .filter(|u| {
+                user_ids.contains(&u.id)
+                    && (u.email.to_lowercase().contains(&query_lower)
+                        || u.display_name
+                            .as_ref()
+                            .map(|n| n.to_lowercase().contains(&query_lower))
+                            .unwrap_or(false))
+            })
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn find_user_tenants(&self, user_id: StringUuid) -> Result<Vec<TenantUser>> {
+        let tenant_users = self.tenant_users.read().await;
+        Ok(tenant_users
+            .iter()
+            .filter(|tu| tu.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_user_tenants_with_tenant(
+        &self,
+        user_id: StringUuid,
+    ) -> Result<Vec<crate::models::user::TenantUserWithTenant>> {
+        // Create mock TenantUserWithTenant from TenantUser data
+        let tenant_users = self.tenant_users.read().await;
+        Ok(tenant_users
+            .iter()
+            .filter(|tu| tu.user_id == user_id)
+            .map(|tu| crate::models::user::TenantUserWithTenant {
+                id: tu.id,
+                tenant_id: tu.tenant_id,
+                user_id: tu.user_id,
+                role_in_tenant: tu.role_in_tenant.clone(),
+                joined_at: tu.joined_at,
+                tenant: crate::models::user::TenantInfo {
+                    id: tu.tenant_id,
+                    name: format!("Tenant {}", tu.tenant_id),
+                    slug: format!("tenant-{}", tu.tenant_id),
+                    logo_url: None,
+                    status: "active".to_string(),
+                },
+            })
+            .collect())
+    }
+
+    async fn delete_all_tenant_memberships(&self, user_id: StringUuid) -> Result<u64> {
+        let mut tenant_users = self.tenant_users.write().await;
+        let before = tenant_users.len();
+        tenant_users.retain(|tu| tu.user_id != user_id);
+        Ok((before - tenant_users.len()) as u64)
+    }
+
+    async fn list_tenant_user_ids(&self, user_id: StringUuid) -> Result<Vec<StringUuid>> {
+        let tenant_users = self.tenant_users.read().await;
+        Ok(tenant_users
+            .iter()
+            .filter(|tu| tu.user_id == user_id)
+            .map(|tu| tu.id)
+            .collect())
+    }
+
+    async fn list_tenant_user_ids_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+    ) -> Result<Vec<StringUuid>> {
+        let tenant_users = self.tenant_users.read().await;
+        Ok(tenant_users
+            .iter()
+            .filter(|tu| tu.tenant_id == tenant_id)
+            .map(|tu| tu.id)
+            .collect())
+    }
+
+    async fn delete_tenant_memberships_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut tenant_users = self.tenant_users.write().await;
+        let before = tenant_users.len();
+        tenant_users.retain(|tu| tu.tenant_id != tenant_id);
+        Ok((before - tenant_users.len()) as u64)
+    }
+
+    async fn update_password_changed_at(&self, id: StringUuid) -> Result<()> {
+        let mut users = self.users.write().await;
+        if let Some(user) = users.iter_mut().find(|u| u.id == id) {
+            user.password_changed_at = Some(Utc::now());
+            user.version += 1;
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn update_locked_until(
+        &self,
+        id: StringUuid,
+        locked_until: Option<chrono::DateTime<Utc>>,
+    ) -> Result<()> {
+        let mut users = self.users.write().await;
+        if let Some(user) = users.iter_mut().find(|u| u.id == id) {
+            user.locked_until = locked_until;
+            user.version += 1;
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn update_locale(&self, id: StringUuid, locale: Option<String>) -> Result<()> {
+        let mut users = self.users.write().await;
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        user.locale = locale;
+        user.version += 1;
+        user.updated_at = Utc::now();
+        Ok(())
+    }
+
+    async fn find_by_scim_external_id(&self, scim_external_id: String) -> Result<Option<User>> {
+        let users = self.users.read().await;
+        Ok(users
+            .iter()
+            .find(|u| u.scim_external_id.as_deref() == Some(scim_external_id.as_str()))
+            .cloned())
+    }
+
+    async fn update_scim_fields(
+        &self,
+        id: StringUuid,
+        scim_external_id: Option<String>,
+        scim_provisioned_by: Option<StringUuid>,
+    ) -> Result<()> {
+        let mut users = self.users.write().await;
+        if let Some(user) = users.iter_mut().find(|u| u.id == id) {
+            user.scim_external_id = scim_external_id;
+            user.scim_provisioned_by = scim_provisioned_by;
+            user.version += 1;
+            user.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn find_timeline(
+        &self,
+        user_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<UserTimelineEntry>> {
+        let timeline = self.timeline.read().await;
+        let mut entries: Vec<UserTimelineEntry> = timeline
+            .iter()
+            .filter(|(id, _)| *id == user_id)
+            .map(|(_, e)| e.clone())
+            .collect();
+        entries.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+        Ok(entries
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_timeline(&self, user_id: StringUuid) -> Result<i64> {
+        let timeline = self.timeline.read().await;
+        Ok(timeline.iter().filter(|(id, _)| *id == user_id).count() as i64)
+    }
+}
+
+/// Configurable test service repository
+pub struct TestServiceRepository {
+    services: RwLock<Vec<Service>>,
+    clients: RwLock<Vec<Client>>,
+}
+
+impl TestServiceRepository {
+    pub fn new() -> Self {
+        Self {
+            services: RwLock::new(vec![]),
+            clients: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_service(&self, service: Service) {
+        self.services.write().await.push(service);
+    }
+
+    pub async fn add_client(&self, client: Client) {
+        self.clients.write().await.push(client);
+    }
+}
+
+impl Default for TestServiceRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ServiceRepository for TestServiceRepository {
+    async fn create(&self, input: &CreateServiceInput) -> Result<Service> {
+        let service = Service {
+            id: StringUuid::new_v4(),
+            tenant_id: input.tenant_id.map(StringUuid::from),
+            name: input.name.clone(),
+            base_url: input.base_url.clone(),
+            redirect_uris: input.redirect_uris.clone(),
+            logout_uris: input.logout_uris.clone().unwrap_or_default(),
+            status: ServiceStatus::Active,
+            version: 1,
+            catalog: input.catalog.clone(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        self.services.write().await.push(service.clone());
+        Ok(service)
+    }
+
+    async fn create_client(
+        &self,
+        service_id: Uuid,
+        client_id: &str,
+        secret_hash: &str,
+        name: Option<String>,
+        public_client: bool,
+    ) -> Result<Client> {
+        let client = Client {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::from(service_id),
+            client_id: client_id.to_string(),
+            name,
+            client_secret_hash: secret_hash.to_string(),
+            public_client,
+            created_at: Utc::now(),
+            token_ttls: Default::default(),
+        };
+        self.clients.write().await.push(client.clone());
+        Ok(client)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Service>> {
+        let services = self.services.read().await;
+        Ok(services.iter().find(|s| s.id.0 == id).cloned())
+    }
+
+    async fn find_by_client_id(&self, client_id: &str) -> Result<Option<Service>> {
+        let clients = self.clients.read().await;
+        let client = clients.iter().find(|c| c.client_id == client_id);
+        if let Some(c) = client {
+            let services = self.services.read().await;
+            Ok(services.iter().find(|s| s.id == c.service_id).cloned())
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn find_client_by_client_id(&self, client_id: &str) -> Result<Option<Client>> {
+        let clients = self.clients.read().await;
+        Ok(clients.iter().find(|c| c.client_id == client_id).cloned())
+    }
+
+    async fn list(&self, tenant_id: Option<Uuid>, offset: i64, limit: i64) -> Result<Vec<Service>> {
+        let services = self.services.read().await;
+        let filtered: Vec<Service> = if let Some(tid) = tenant_id {
+            services
+                .iter()
+                .filter(|s| s.tenant_id.map(|t| t.0) == Some(tid))
+                .cloned()
+                .collect()
+        } else {
+            services.clone()
+        };
+        Ok(filtered
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn list_clients(&self, service_id: Uuid) -> Result<Vec<Client>> {
+        let clients = self.clients.read().await;
+        Ok(clients
+            .iter()
+            .filter(|c| c.service_id.0 == service_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn count(&self, tenant_id: Option<Uuid>) -> Result<i64> {
+        let services = self.services.read().await;
+        if let Some(tid) = tenant_id {
+            Ok(services
+                .iter()
+                .filter(|s| s.tenant_id.map(|t| t.0) == Some(tid))
+                .count() as i64)
+        } else {
+            Ok(services.len() as i64)
+        }
+    }
+
+    async fn list_filtered(
+        &self,
+        tenant_id: Option<Uuid>,
+        query: &ListQuery,
+    ) -> Result<(Vec<Service>, i64)> {
+        // Validates like the SQL implementation; applies search and paging only
+        SERVICE_LIST_SPEC.validate(query)?;
+        let services = self.services.read().await;
+        let search = query.search.as_deref().map(str::to_lowercase);
+        let filtered: Vec<Service> = services
+            .iter()
+            .filter(|s| tenant_id.is_none() || s.tenant_id.map(|t| t.0) == tenant_id)
+            .filter(|s| {
+                search.as_ref().is_none_or(|q| {
+                    s.name.to_lowercase().contains(q)
+                        || s.base_url
+                            .as_deref()
+                            .is_some_and(|u| u.to_lowercase().contains(q))
+                })
+            })
+            .cloned()
+            .collect();
+        let total = filtered.len() as i64;
+        Ok((
+            filtered
+                .into_iter()
+                .skip(query.offset() as usize)
+                .take(query.per_page as usize)
+                .collect(),
+            total,
+        ))
+    }
+
+    async fn update(
+        &self,
+        id: Uuid,
+        input: &UpdateServiceInput,
+        expected_version: Option<i32>,
+    ) -> Result<Service> {
+        let mut services = self.services.write().await;
+        let service = services
+            .iter_mut()
+            .find(|s| s.id.0 == id)
+            .ok_or_else(|| AppError::NotFound(format!("Service {} not found", id)))?;
+        AppError::check_version("service", expected_version, service.version)?;
+
+        if let Some(name) = &input.name {
+            service.name = name.clone();
+        }
+        if let Some(base_url) = &input.base_url {
+            service.base_url = Some(base_url.clone());
+        }
+        if let Some(redirect_uris) = &input.redirect_uris {
+            service.redirect_uris = redirect_uris.clone();
+        }
+        if let Some(logout_uris) = &input.logout_uris {
+            service.logout_uris = logout_uris.clone();
+        }
+        if let Some(status) = &input.status {
+            service.status = status.clone();
+        }
+        if let Some(catalog) = &input.catalog {
+            service.catalog = catalog.clone();
+        }
+        service.version += 1;
+        service.updated_at = Utc::now();
+        Ok(service.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<()> {
+        let mut services = self.services.write().await;
+        let pos = services
+            .iter()
+            .position(|s| s.id.0 == id)
+            .ok_or_else(|| AppError::NotFound(format!("Service {} not found", id)))?;
+        services.remove(pos);
+        Ok(())
+    }
+
+    async fn delete_client(&self, _service_id: Uuid, client_id: &str) -> Result<()> {
+        let mut clients = self.clients.write().await;
+        let pos = clients
+            .iter()
+            .position(|c| c.client_id == client_id)
+            .ok_or_else(|| AppError::NotFound(format!("Client {} not found", client_id)))?;
+        clients.remove(pos);
+        Ok(())
+    }
+
+    async fn update_client_secret_hash(
+        &self,
+        client_id: &str,
+        new_secret_hash: &str,
+    ) -> Result<()> {
+        let mut clients = self.clients.write().await;
+        let client = clients
+            .iter_mut()
+            .find(|c| c.client_id == client_id)
+            .ok_or_else(|| AppError::NotFound(format!("Client {} not found", client_id)))?;
+        client.client_secret_hash = new_secret_hash.to_string();
+        Ok(())
+    }
+
+    async fn update_client_token_ttls(
+        &self,
+        service_id: Uuid,
+        client_id: &str,
+        token_ttls: &TokenTtlOverrides,
+    ) -> Result<Client> {
+        let mut clients = self.clients.write().await;
+        let client = clients
+            .iter_mut()
+            .find(|c| c.service_id.0 == service_id && c.client_id == client_id)
+            .ok_or_else(|| AppError::NotFound(format!("Client {} not found", client_id)))?;
+        client.token_ttls = *token_ttls;
+        Ok(client.clone())
+    }
+
+    async fn list_by_tenant(&self, tenant_id: Uuid) -> Result<Vec<Service>> {
+        let services = self.services.read().await;
+        Ok(services
+            .iter()
+            .filter(|s| s.tenant_id.map(|t| t.0) == Some(tenant_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_clients_by_service(&self, service_id: Uuid) -> Result<u64> {
+        let mut clients = self.clients.write().await;
+        let before = clients.len();
+        clients.retain(|c| c.service_id.0 != service_id);
+        Ok((before - clients.len()) as u64)
+    }
+}
+
+/// Configurable test RBAC repository
+pub struct TestRbacRepository {
+    permissions: RwLock<Vec<Permission>>,
+    roles: RwLock<Vec<Role>>,
+    role_permissions: RwLock<Vec<(StringUuid, StringUuid)>>, // (role_id, permission_id)
+    user_roles: RwLock<Vec<(Uuid, Uuid, UserRolesInTenant)>>,
+    user_roles_for_service: RwLock<Vec<(Uuid, Uuid, Uuid, UserRolesInTenant)>>,
+    tenant_user_roles: RwLock<Vec<(StringUuid, StringUuid)>>, // (tenant_user_id, role_id)
+    permission_namespaces: RwLock<HashMap<StringUuid, String>>,
+}
+
+impl TestRbacRepository {
+    pub fn new() -> Self {
+        Self {
+            permissions: RwLock::new(vec![]),
+            roles: RwLock::new(vec![]),
+            role_permissions: RwLock::new(vec![]),
+            user_roles: RwLock::new(vec![]),
+            user_roles_for_service: RwLock::new(vec![]),
+            tenant_user_roles: RwLock::new(vec![]),
+            permission_namespaces: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn add_role(&self, role: Role) {
+        self.roles.write().await.push(role);
+    }
+
+    pub async fn add_permission(&self, permission: Permission) {
+        self.permissions.write().await.push(permission);
+    }
+
+    pub async fn set_user_roles(&self, user_id: Uuid, tenant_id: Uuid, roles: UserRolesInTenant) {
+        self.user_roles
+            .write()
+            .await
+            .push((user_id, tenant_id, roles));
+    }
+
+    pub async fn set_user_roles_for_service(
+        &self,
+        user_id: Uuid,
+        tenant_id: Uuid,
+        service_id: Uuid,
+        roles: UserRolesInTenant,
+    ) {
+        self.user_roles_for_service
+            .write()
+            .await
+            .push((user_id, tenant_id, service_id, roles));
+    }
+}
+
+impl Default for TestRbacRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RbacRepository for TestRbacRepository {
+    async fn create_permission(&self, input: &CreatePermissionInput) -> Result<Permission> {
+        let permission = Permission {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::from(input.service_id),
+            code: input.code.clone(),
+            name: input.name.clone(),
+            description: input.description.clone(),
+        };
+        self.permissions.write().await.push(permission.clone());
+        Ok(permission)
+    }
+
+    async fn find_permission_by_id(&self, id: StringUuid) -> Result<Option<Permission>> {
+        let permissions = self.permissions.read().await;
+        Ok(permissions.iter().find(|p| p.id == id).cloned())
+    }
+
+    async fn find_permissions_by_service(&self, service_id: StringUuid) -> Result<Vec<Permission>> {
+        let permissions = self.permissions.read().await;
+        Ok(permissions
+            .iter()
+            .filter(|p| p.service_id == service_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_permission(&self, id: StringUuid) -> Result<()> {
+        let mut permissions = self.permissions.write().await;
+        let pos = permissions
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Permission {} not found", id)))?;
+        permissions.remove(pos);
+        Ok(())
+    }
+
+    async fn find_permission_namespace(&self, service_id: StringUuid) -> Result<Option<String>> {
+        Ok(self
+            .permission_namespaces
+            .read()
+            .await
+            .get(&service_id)
+            .cloned())
+    }
+
+    async fn set_permission_namespace(
+        &self,
+        service_id: StringUuid,
+        namespace: Option<String>,
+    ) -> Result<()> {
+        let mut namespaces = self.permission_namespaces.write().await;
+        match namespace {
+            Some(namespace) => {
+                namespaces.insert(service_id, namespace);
+            }
+            None => {
+                namespaces.remove(&service_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_role(&self, input: &CreateRoleInput) -> Result<Role> {
+        let role = Role {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::from(input.service_id),
+            name: input.name.clone(),
+            description: input.description.clone(),
+            parent_role_id: input.parent_role_id.map(StringUuid::from),
+            version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        self.roles.write().await.push(role.clone());
+        Ok(role)
+    }
+
+    async fn find_role_by_id(&self, id: StringUuid) -> Result<Option<Role>> {
+        let roles = self.roles.read().await;
+        Ok(roles.iter().find(|r| r.id == id).cloned())
+    }
+
+    async fn find_roles_by_service(&self, service_id: StringUuid) -> Result<Vec<Role>> {
+        let roles = self.roles.read().await;
+        Ok(roles
+            .iter()
+            .filter(|r| r.service_id == service_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_role(
+        &self,
+        id: StringUuid,
+        input: &UpdateRoleInput,
+        expected_version: Option<i32>,
+    ) -> Result<Role> {
+        let mut roles = self.roles.write().await;
+        let role = roles
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Role {} not found", id)))?;
+        AppError::check_version("role", expected_version, role.version)?;
+
+        if let Some(name) = &input.name {
+            role.name = name.clone();
+        }
+        if let Some(description) = &input.description {
+            role.description = Some(description.clone());
+        }
+        role.version += 1;
+        role.updated_at = Utc::now();
+        Ok(role.clone())
+    }
+
+    async fn delete_role(&self, id: StringUuid) -> Result<()> {
+        let mut roles = self.roles.write().await;
+        let pos = roles
+            .iter()
+            .position(|r| r.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Role {} not found", id)))?;
+        roles.remove(pos);
+        Ok(())
+    }
+
+    async fn assign_permission_to_role(
+        &self,
+        role_id: StringUuid,
+        permission_id: StringUuid,
+    ) -> Result<()> {
+        self.role_permissions
+            .write()
+            .await
+            .push((role_id, permission_id));
+        Ok(())
+    }
+
+    async fn remove_permission_from_role(
+        &self,
+        role_id: StringUuid,
+        permission_id: StringUuid,
+    ) -> Result<()> {
+        let mut role_permissions = self.role_permissions.write().await;
+        let pos = role_permissions
+            .iter()
+            .position(|(rid, pid)| *rid == role_id && *pid == permission_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Permission {} not assigned to role {}",
+                    permission_id, role_id
+                ))
+            })?;
+        role_permissions.remove(pos);
+        Ok(())
+    }
+
+    async fn find_role_permissions(&self, role_id: StringUuid) -> Result<Vec<Permission>> {
+        let role_permissions = self.role_permissions.read().await;
+        let permissions = self.permissions.read().await;
+        let permission_ids: Vec<StringUuid> = role_permissions
+            .iter()
+            .filter(|(rid, _)| *rid == role_id)
+            .map(|(_, pid)| *pid)
+            .collect();
+        Ok(permissions
+            .iter()
+            .filter(|p| permission_ids.contains(&p.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn assign_roles_to_user(
+        &self,
+        input: &AssignRolesInput,
+        _granted_by: Option<StringUuid>,
+    ) -> Result<()> {
+        let mut tenant_user_roles = self.tenant_user_roles.write().await;
+        // Use user_id as a stand-in for tenant_user_id in tests
+        let tenant_user_id = StringUuid::from(input.user_id);
+        for role_id in &input.role_ids {
+            tenant_user_roles.push((tenant_user_id, StringUuid::from(*role_id)));
+        }
+        Ok(())
+    }
+
+    async fn remove_role_from_user(
+        &self,
+        tenant_user_id: StringUuid,
+        role_id: StringUuid,
+    ) -> Result<()> {
+        let mut tenant_user_roles = self.tenant_user_roles.write().await;
+        let pos = tenant_user_roles
+            .iter()
+            .position(|(tuid, rid)| *tuid == tenant_user_id && *rid == role_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Role {} not assigned to tenant user {}",
+                    role_id, tenant_user_id
+                ))
+            })?;
+        tenant_user_roles.remove(pos);
+        Ok(())
+    }
+
+    async fn find_tenant_user_id(
+        &self,
+        _user_id: UserId,
+        _tenant_id: TenantId,
+    ) -> Result<Option<StringUuid>> {
+        // Simplified: return a new UUID for testing
+        Ok(Some(StringUuid::new_v4()))
+    }
+
+    async fn find_role_in_tenant(
+        &self,
+        _user_id: UserId,
+        _tenant_id: TenantId,
+    ) -> Result<Option<String>> {
+        Ok(Some("member".to_string()))
+    }
+
+    async fn find_user_roles_in_tenant(
+        &self,
+        user_id: UserId,
+        tenant_id: TenantId,
+    ) -> Result<UserRolesInTenant> {
+        let roles = self.user_roles.read().await;
+        for (uid, tid, r) in roles.iter() {
+            if *uid == user_id.0 && *tid == tenant_id.0 {
+                return Ok(r.clone());
+            }
+        }
+        Ok(UserRolesInTenant {
+            user_id: user_id.0,
+            tenant_id: tenant_id.0,
+            roles: vec![],
+            permissions: vec![],
+        })
+    }
+
+    async fn find_user_roles_in_tenant_for_service(
+        &self,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
+    ) -> Result<UserRolesInTenant> {
+        let roles = self.user_roles_for_service.read().await;
+        for (uid, tid, sid, r) in roles.iter() {
+            if *uid == user_id.0 && *tid == tenant_id.0 && *sid == service_id.0 {
+                return Ok(r.clone());
+            }
+        }
+        Ok(UserRolesInTenant {
+            user_id: user_id.0,
+            tenant_id: tenant_id.0,
+            roles: vec![],
+            permissions: vec![],
+        })
+    }
+
+    async fn find_user_role_records_in_tenant(
+        &self,
+        _user_id: UserId,
+        _tenant_id: TenantId,
+        _service_id: Option<ServiceId>,
+    ) -> Result<Vec<Role>> {
+        Ok(self.roles.read().await.clone())
+    }
+
+    async fn delete_permissions_by_service(&self, service_id: StringUuid) -> Result<u64> {
+        let mut permissions = self.permissions.write().await;
+        let before = permissions.len();
+        permissions.retain(|p| p.service_id != service_id);
+        Ok((before - permissions.len()) as u64)
+    }
+
+    async fn delete_roles_by_service(&self, service_id: StringUuid) -> Result<u64> {
+        let mut roles = self.roles.write().await;
+        let before = roles.len();
+        // First, collect role IDs to delete
+        let role_ids_to_delete: Vec<StringUuid> = roles
+            .iter()
+            .filter(|r| r.service_id == service_id)
+            .map(|r| r.id)
+            .collect();
+
+        // Remove role_permissions for these roles
+        {
+            let mut role_permissions = self.role_permissions.write().await;
+            role_permissions.retain(|(rid, _)| !role_ids_to_delete.contains(rid));
+        }
+
+        // Remove tenant_user_roles for these roles
+        {
+            let mut tenant_user_roles = self.tenant_user_roles.write().await;
+            tenant_user_roles.retain(|(_, rid)| !role_ids_to_delete.contains(rid));
+        }
+
+        // Remove the roles
+        roles.retain(|r| r.service_id != service_id);
+        Ok((before - roles.len()) as u64)
+    }
+
+    async fn clear_parent_role_references(&self, service_id: StringUuid) -> Result<u64> {
+        let mut roles = self.roles.write().await;
+        let mut count = 0u64;
+        for role in roles.iter_mut() {
+            if role.service_id == service_id && role.parent_role_id.is_some() {
+                role.parent_role_id = None;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn delete_user_roles_by_tenant_user(&self, tenant_user_id: StringUuid) -> Result<u64> {
+        let mut tenant_user_roles = self.tenant_user_roles.write().await;
+        let before = tenant_user_roles.len();
+        tenant_user_roles.retain(|(tuid, _)| *tuid != tenant_user_id);
+        Ok((before - tenant_user_roles.len()) as u64)
+    }
+
+    async fn clear_parent_role_reference_by_id(&self, role_id: StringUuid) -> Result<u64> {
+        let mut roles = self.roles.write().await;
+        let mut count = 0u64;
+        for role in roles.iter_mut() {
+            if role.parent_role_id == Some(role_id) {
+                role.parent_role_id = None;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn find_user_ids_by_role_in_tenant(
+        &self,
+        _tenant_id: TenantId,
+        _role_id: StringUuid,
+    ) -> Result<Vec<StringUuid>> {
+        // Test stub - real implementation joins user_tenant_roles with tenant_users
+        Ok(vec![])
+    }
+
+    async fn find_tenant_cardinality_limits(
+        &self,
+        _tenant_id: TenantId,
+    ) -> Result<Option<CardinalityLimits>> {
+        Ok(None)
+    }
+
+    async fn find_service_cardinality_limits(
+        &self,
+        _service_id: StringUuid,
+    ) -> Result<Option<CardinalityLimits>> {
+        Ok(None)
+    }
+}
+
+/// Configurable test audit repository
+pub struct TestAuditRepository {
+    logs: RwLock<Vec<AuditLog>>,
+    next_id: RwLock<i64>,
+}
+
+impl TestAuditRepository {
+    pub fn new() -> Self {
+        Self {
+            logs: RwLock::new(vec![]),
+            next_id: RwLock::new(1),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_logs(&self) -> Vec<AuditLog> {
+        self.logs.read().await.clone()
+    }
+
+    /// Insert an entry as-is, keeping its id and timestamp
+    #[allow(dead_code)]
+    pub async fn insert_log(&self, log: AuditLog) {
+        let mut next_id = self.next_id.write().await;
+        *next_id = (*next_id).max(log.id + 1);
+        self.logs.write().await.push(log);
+    }
+}
+
+/// In-memory equivalent of the SQL filters, with case-insensitive substring
+/// matching standing in for the full-text index
+fn audit_log_matches(log: &AuditLog, query: &AuditLogQuery) -> bool {
+    if let Some(actor_id) = query.actor_id {
+        if log.actor_id.as_deref() != Some(actor_id.to_string().as_str()) {
+            return false;
+        }
+    }
+    if let Some(ref resource_type) = query.resource_type {
+        if &log.resource_type != resource_type {
+            return false;
+        }
+    }
+    if let Some(ref action) = query.action {
+        if &log.action != action {
+            return false;
+        }
+    }
+    if query.from_date.is_some_and(|from| log.created_at < from)
+        || query.to_date.is_some_and(|to| log.created_at > to)
+    {
+        return false;
+    }
+    if let Some(ref q) = query.q {
+        let document = crate::models::audit_search::search_document(
+            &log.action,
+            &log.resource_type,
+            log.resource_id.as_deref(),
+            log.old_value.as_ref(),
+            log.new_value.as_ref(),
+        )
+        .to_lowercase();
+        if !q
+            .split_whitespace()
+            .all(|term| document.contains(&term.to_lowercase()))
+        {
+            return false;
+        }
+    }
+    true
+}
+
+impl Default for TestAuditRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AuditRepository for TestAuditRepository {
+    async fn create(&self, input: &CreateAuditLogInput) -> Result<()> {
+        let mut next_id = self.next_id.write().await;
+        let log = AuditLog {
+            id: *next_id,
+            actor_id: input.actor_id.map(|id| id.to_string()),
+            action: input.action.clone(),
+            resource_type: input.resource_type.clone(),
+            resource_id: input.resource_id.map(|id| id.to_string()),
+            old_value: input.old_value.clone(),
+            new_value: input.new_value.clone(),
+            ip_address: input.ip_address.clone(),
+            created_at: Utc::now(),
+        };
+        *next_id += 1;
+        self.logs.write().await.push(log);
+        Ok(())
+    }
+
+    async fn find(&self, query: &AuditLogQuery) -> Result<Vec<AuditLog>> {
+        let logs = self.logs.read().await;
+        let filtered: Vec<AuditLog> = logs
+            .iter()
+            .filter(|log| audit_log_matches(log, query))
+            .cloned()
+            .collect();
+        let offset = query.offset.unwrap_or(0) as usize;
+        let limit = query.limit.unwrap_or(50) as usize;
+        Ok(filtered.into_iter().skip(offset).take(limit).collect())
+    }
+
+    async fn count(&self, query: &AuditLogQuery) -> Result<i64> {
+        // Count should return total matching records WITHOUT pagination (like the real impl)
+        let logs = self.logs.read().await;
+        let count = logs
+            .iter()
+            .filter(|log| audit_log_matches(log, query))
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn histogram(
+        &self,
+        query: &AuditLogQuery,
+        interval: AuditHistogramInterval,
+    ) -> Result<Vec<AuditHistogramBucket>> {
+        let mut buckets: std::collections::BTreeMap<DateTime<Utc>, i64> = Default::default();
+        for log in self.logs.read().await.iter() {
+            if !audit_log_matches(log, query) {
+                continue;
+            }
+            let day = log.created_at.date_naive();
+            let start = match interval {
+                AuditHistogramInterval::Hour => {
+                    day.and_hms_opt(log.created_at.hour(), 0, 0).unwrap()
+                }
+                AuditHistogramInterval::Day => day.and_hms_opt(0, 0, 0).unwrap(),
+                AuditHistogramInterval::Week => (day
+                    - chrono::Duration::days(day.weekday().num_days_from_monday() as i64))
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+                AuditHistogramInterval::Month => {
+                    day.with_day(1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+                }
+            };
+            *buckets.entry(start.and_utc()).or_default() += 1;
+        }
+        Ok(buckets
+            .into_iter()
+            .map(|(bucket_start, count)| AuditHistogramBucket {
+                bucket_start,
+                count,
+            })
+            .collect())
+    }
+
+    async fn find_resource_history(
+        &self,
+        resource_type: &str,
+        resource_id: &str,
+        until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AuditLog>> {
+        let mut history: Vec<AuditLog> = self
+            .logs
+            .read()
+            .await
+            .iter()
+            .filter(|log| {
+                log.resource_type == resource_type
+                    && log.resource_id.as_deref() == Some(resource_id)
+                    && log.created_at <= until
+            })
+            .cloned()
+            .collect();
+        history.sort_by_key(|log| (log.created_at, log.id));
+        let skip = history.len().saturating_sub(limit as usize);
+        Ok(history.into_iter().skip(skip).collect())
+    }
+
+    async fn find_with_actor(
+        &self,
+        query: &AuditLogQuery,
+    ) -> Result<Vec<crate::repository::audit::AuditLogWithActor>> {
+        // Convert AuditLog to AuditLogWithActor (without actor email for tests)
+        let logs = self.find(query).await?;
+        Ok(logs
+            .into_iter()
+            .map(|log| crate::repository::audit::AuditLogWithActor {
+                id: log.id,
+                actor_id: log.actor_id,
+                actor_email: None,
+                actor_display_name: None,
+                action: log.action,
+                resource_type: log.resource_type,
+                resource_id: log.resource_id,
+                old_value: log.old_value,
+                new_value: log.new_value,
+                ip_address: log.ip_address,
+                created_at: log.created_at,
+            })
+            .collect())
+    }
+
+    async fn nullify_actor_id(&self, user_id: StringUuid) -> Result<u64> {
+        let mut logs = self.logs.write().await;
+        let user_id_str = user_id.to_string();
+        let mut count = 0u64;
+        for log in logs.iter_mut() {
+            if log.actor_id.as_ref() == Some(&user_id_str) {
+                log.actor_id = None;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Configurable test system settings repository
+pub struct TestSystemSettingsRepository {
+    settings: RwLock<Vec<SystemSettingRow>>,
+    next_id: RwLock<i32>,
+}
+
+impl TestSystemSettingsRepository {
+    pub fn new() -> Self {
+        Self {
+            settings: RwLock::new(vec![]),
+            next_id: RwLock::new(1),
+        }
+    }
+
+    pub async fn add_setting(&self, setting: SystemSettingRow) {
+        self.settings.write().await.push(setting);
+    }
+
+    #[allow(dead_code)]
+    pub async fn clear(&self) {
+        self.settings.write().await.clear();
+    }
+}
+
+impl Default for TestSystemSettingsRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SystemSettingsRepository for TestSystemSettingsRepository {
+    async fn get(&self, category: &str, key: &str) -> Result<Option<SystemSettingRow>> {
+        let settings = self.settings.read().await;
+        Ok(settings
+            .iter()
+            .find(|s| s.category == category && s.setting_key == key)
+            .cloned())
+    }
+
+    async fn list_by_category(&self, category: &str) -> Result<Vec<SystemSettingRow>> {
+        let settings = self.settings.read().await;
+        Ok(settings
+            .iter()
+            .filter(|s| s.category == category)
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert(&self, input: &UpsertSystemSettingInput) -> Result<SystemSettingRow> {
+        let mut settings = self.settings.write().await;
+
+        // Check if exists
+        if let Some(existing) = settings
+            .iter_mut()
+            .find(|s| s.category == input.category && s.setting_key == input.setting_key)
+        {
+            existing.value = input.value.clone();
+            existing.encrypted = input.encrypted;
+            existing.description = input.description.clone();
+            existing.updated_at = Utc::now();
+            return Ok(existing.clone());
+        }
+
+        // Create new
+        let mut next_id = self.next_id.write().await;
+        let setting = SystemSettingRow {
+            id: *next_id,
+            category: input.category.clone(),
+            setting_key: input.setting_key.clone(),
+            value: input.value.clone(),
+            encrypted: input.encrypted,
+            description: input.description.clone(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        *next_id += 1;
+        settings.push(setting.clone());
+        Ok(setting)
+    }
+
+    async fn delete(&self, category: &str, key: &str) -> Result<()> {
+        let mut settings = self.settings.write().await;
+        settings.retain(|s| !(s.category == category && s.setting_key == key));
+        Ok(())
+    }
+}
+
+pub struct TestMaliciousIpBlacklistRepository {
+    entries: RwLock<Vec<MaliciousIpBlacklistEntry>>,
+    tenant_entries: RwLock<Vec<TenantMaliciousIpBlacklistEntry>>,
+}
+
+impl TestMaliciousIpBlacklistRepository {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(vec![]),
+            tenant_entries: RwLock::new(vec![]),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn add_entry(&self, entry: MaliciousIpBlacklistEntry) {
+        self.entries.write().await.push(entry);
+    }
+
+    #[allow(dead_code)]
+    pub async fn add_tenant_entry(&self, entry: TenantMaliciousIpBlacklistEntry) {
+        self.tenant_entries.write().await.push(entry);
+    }
+}
+
+impl Default for TestMaliciousIpBlacklistRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MaliciousIpBlacklistRepository for TestMaliciousIpBlacklistRepository {
+    async fn list(&self) -> Result<Vec<MaliciousIpBlacklistEntry>> {
+        Ok(self.entries.read().await.clone())
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+    ) -> Result<Vec<TenantMaliciousIpBlacklistEntry>> {
+        Ok(self
+            .tenant_entries
+            .read()
+            .await
+            .iter()
+            .filter(|entry| entry.tenant_id == tenant_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn replace_all(
+        &self,
+        entries: &[MaliciousIpBlacklistEntry],
+        _created_by: Option<StringUuid>,
+    ) -> Result<Vec<MaliciousIpBlacklistEntry>> {
+        let mut stored = self.entries.write().await;
+        *stored = entries.to_vec();
+        Ok(stored.clone())
+    }
+
+    async fn replace_all_for_tenant(
+        &self,
+        tenant_id: StringUuid,
+        entries: &[TenantMaliciousIpBlacklistEntry],
+        _created_by: Option<StringUuid>,
+    ) -> Result<Vec<TenantMaliciousIpBlacklistEntry>> {
+        let mut stored = self.tenant_entries.write().await;
+        stored.retain(|entry| entry.tenant_id != tenant_id);
+        stored.extend(entries.iter().cloned());
+        Ok(stored
+            .iter()
+            .filter(|entry| entry.tenant_id == tenant_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_ip(&self, ip_address: &str) -> Result<Option<MaliciousIpBlacklistEntry>> {
+        Ok(self
+            .entries
+            .read()
+            .await
+            .iter()
+            .find(|entry| entry.ip_address == ip_address)
+            .cloned())
+    }
+
+    async fn find_by_ip_in_tenant(
+        &self,
+        tenant_id: StringUuid,
+        ip_address: &str,
+    ) -> Result<Option<TenantMaliciousIpBlacklistEntry>> {
+        Ok(self
+            .tenant_entries
+            .read()
+            .await
+            .iter()
+            .find(|entry| entry.tenant_id == tenant_id && entry.ip_address == ip_address)
+            .cloned())
+    }
+}
+
+// ============================================================================
+// Test Password Reset Repository
+// ============================================================================
+
+/// Configurable test password reset repository
+pub struct TestPasswordResetRepository {
+    tokens: RwLock<Vec<PasswordResetToken>>,
+}
+
+impl TestPasswordResetRepository {
+    pub fn new() -> Self {
+        Self {
+            tokens: RwLock::new(vec![]),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn add_token(&self, token: PasswordResetToken) {
+        self.tokens.write().await.push(token);
+    }
+}
+
+impl Default for TestPasswordResetRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for TestPasswordResetRepository {
+    async fn create(&self, input: &CreatePasswordResetTokenInput) -> Result<PasswordResetToken> {
+        let token = PasswordResetToken {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            token_hash: input.token_hash.clone(),
+            expires_at: input.expires_at,
+            used_at: None,
+            created_at: Utc::now(),
+        };
+        self.tokens.write().await.push(token.clone());
+        Ok(token)
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<PasswordResetToken>> {
+        let tokens = self.tokens.read().await;
+        let now = Utc::now();
+        Ok(tokens
+            .iter()
+            .find(|t| t.token_hash == token_hash && t.used_at.is_none() && t.expires_at > now)
+            .cloned())
+    }
+
+    async fn find_valid_by_user(&self, user_id: StringUuid) -> Result<Option<PasswordResetToken>> {
+        let tokens = self.tokens.read().await;
+        let now = Utc::now();
+        Ok(tokens
+            .iter()
+            .find(|t| t.user_id == user_id && t.used_at.is_none() && t.expires_at > now)
+            .cloned())
+    }
+
+    async fn mark_used(&self, id: StringUuid) -> Result<()> {
+        let mut tokens = self.tokens.write().await;
+        let token = tokens
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| AppError::NotFound("Password reset token not found".to_string()))?;
+        token.used_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn claim_by_token_hash(&self, token_hash: &str) -> Result<Option<PasswordResetToken>> {
+        let mut tokens = self.tokens.write().await;
+        let now = Utc::now();
+        let token = tokens
+            .iter_mut()
+            .find(|t| t.token_hash == token_hash && t.used_at.is_none() && t.expires_at > now);
+        match token {
+            Some(t) => {
+                t.used_at = Some(now);
+                Ok(Some(t.clone()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_expired(&self) -> Result<u64> {
+        let mut tokens = self.tokens.write().await;
+        let now = Utc::now();
+        let before = tokens.len();
+        tokens.retain(|t| t.expires_at > now && t.used_at.is_none());
+        Ok((before - tokens.len()) as u64)
+    }
+
+    async fn delete_by_user(&self, user_id: StringUuid) -> Result<()> {
+        let mut tokens = self.tokens.write().await;
+        tokens.retain(|t| t.user_id != user_id);
+        Ok(())
+    }
+
+    async fn replace_for_user(
+        &self,
+        input: &CreatePasswordResetTokenInput,
+    ) -> Result<PasswordResetToken> {
+        // Atomically delete old tokens and create new one
+        let mut tokens = self.tokens.write().await;
+        tokens.retain(|t| t.user_id != input.user_id);
+        let token = PasswordResetToken {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            token_hash: input.token_hash.clone(),
+            expires_at: input.expires_at,
+            used_at: None,
+            created_at: Utc::now(),
+        };
+        tokens.push(token.clone());
+        Ok(token)
+    }
+
+    async fn store_password_hash(&self, _user_id: StringUuid, _password_hash: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_password_history(&self, _user_id: StringUuid, _limit: u32) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+}
+
+// ============================================================================
+// Test Session Repository
+// ============================================================================
+
+/// Configurable test session repository
+pub struct TestSessionRepository {
+    sessions: RwLock<Vec<Session>>,
+    session_limit_policies: RwLock<Vec<SessionLimitPolicy>>,
+    /// (tenant_id, user_id) memberships used by tenant session exports
+    tenant_members: RwLock<Vec<(StringUuid, StringUuid)>>,
+}
+
+impl TestSessionRepository {
+    pub fn new() -> Self {
+        Self {
+            sessions: RwLock::new(vec![]),
+            session_limit_policies: RwLock::new(vec![]),
+            tenant_members: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_tenant_member(&self, tenant_id: StringUuid, user_id: StringUuid) {
+        self.tenant_members.write().await.push((tenant_id, user_id));
+    }
+
+    pub async fn add_session(&self, session: Session) {
+        self.sessions.write().await.push(session);
+    }
+
+    pub async fn add_session_limit_policy(&self, policy: SessionLimitPolicy) {
+        self.session_limit_policies.write().await.push(policy);
+    }
+}
+
+impl Default for TestSessionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SessionRepository for TestSessionRepository {
+    async fn create(&self, input: &CreateSessionInput) -> Result<Session> {
+        let now = Utc::now();
+        let session = Session {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            provider_session_id: input.provider_session_id.clone(),
+            device_type: input.device_type.clone(),
+            device_name: input.device_name.clone(),
+            display_name: None,
+            ip_address: input.ip_address.clone(),
+            location: input.location.clone(),
+            user_agent: input.user_agent.clone(),
+            last_active_at: now,
+            created_at: now,
+            revoked_at: None,
+        };
+        self.sessions.write().await.push(session.clone());
+        Ok(session)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Session>> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions.iter().find(|s| s.id == id).cloned())
+    }
+
+    async fn find_by_provider_session_id(
+        &self,
+        provider_session_id: &str,
+    ) -> Result<Option<Session>> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions
+            .iter()
+            .find(|s| s.provider_session_id.as_deref() == Some(provider_session_id))
+            .cloned())
+    }
+
+    async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions
+            .iter()
+            .filter(|s| s.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_active_by_user(&self, user_id: StringUuid) -> Result<Vec<Session>> {
+        let sessions = self.sessions.read().await;
+        Ok(sessions
+            .iter()
+            .filter(|s| s.user_id == user_id && s.revoked_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_tenant_after(
+        &self,
+        tenant_id: StringUuid,
+        after: Option<StringUuid>,
+        active_only: bool,
+        limit: i64,
+    ) -> Result<Vec<Session>> {
+        let members = self.tenant_members.read().await;
+        let after = after.map(|id| id.to_string());
+        let mut sessions: Vec<Session> = self
+            .sessions
+            .read()
+            .await
+            .iter()
+            .filter(|s| members.contains(&(tenant_id, s.user_id)))
+            .filter(|s| !active_only || s.revoked_at.is_none())
+            .filter(|s| after.as_ref().is_none_or(|a| s.id.to_string() > *a))
+            .cloned()
+            .collect();
+        // Ids are compared as strings, like the CHAR(36) column
+        sessions.sort_by_key(|s| s.id.to_string());
+        sessions.truncate(limit as usize);
+        Ok(sessions)
+    }
+
+    async fn update_last_active(&self, id: StringUuid) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.iter_mut().find(|s| s.id == id) {
+            session.last_active_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn update_last_active_batch(
+        &self,
+        updates: &[(StringUuid, DateTime<Utc>)],
+    ) -> Result<u64> {
+        let mut sessions = self.sessions.write().await;
+        let mut updated = 0;
+        for (id, at) in updates {
+            if let Some(session) = sessions
+                .iter_mut()
+                .find(|s| s.id == *id && s.revoked_at.is_none())
+            {
+                session.last_active_at = session.last_active_at.max(*at);
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn rename(&self, id: StringUuid, display_name: Option<String>) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.revoked_at.is_none())
+            .ok_or_else(|| {
+                AppError::NotFound("Session not found or already revoked".to_string())
+            })?;
+        session.display_name = display_name;
+        Ok(())
+    }
+
+    async fn revoke(&self, id: StringUuid) -> Result<()> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.id == id && s.revoked_at.is_none())
+            .ok_or_else(|| {
+                AppError::NotFound("Session not found or already revoked".to_string())
+            })?;
+        session.revoked_at = Some(Utc::now());
+        Ok(())
+    }
+
+    async fn revoke_all_by_user(&self, user_id: StringUuid) -> Result<u64> {
+        let mut sessions = self.sessions.write().await;
+        let now = Utc::now();
+        let mut count = 0u64;
+        for session in sessions.iter_mut() {
+            if session.user_id == user_id && session.revoked_at.is_none() {
+                session.revoked_at = Some(now);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn revoke_all_except(&self, user_id: StringUuid, except_id: StringUuid) -> Result<u64> {
+        let mut sessions = self.sessions.write().await;
+        let now = Utc::now();
+        let mut count = 0u64;
+        for session in sessions.iter_mut() {
+            if session.user_id == user_id && session.id != except_id && session.revoked_at.is_none()
+            {
+                session.revoked_at = Some(now);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn delete_old(&self, days: i64) -> Result<u64> {
+        let mut sessions = self.sessions.write().await;
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+        let before = sessions.len();
+        sessions.retain(|s| s.created_at > cutoff);
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn delete_by_user(&self, user_id: StringUuid) -> Result<u64> {
+        let mut sessions = self.sessions.write().await;
+        let before = sessions.len();
+        sessions.retain(|s| s.user_id != user_id);
+        Ok((before - sessions.len()) as u64)
+    }
+
+    async fn count_active_by_user(&self, user_id: StringUuid) -> Result<i64> {
+        let sessions = self.sessions.read().await;
+        let count = sessions
+            .iter()
+            .filter(|s| s.user_id == user_id && s.revoked_at.is_none())
+            .count();
+        Ok(count as i64)
+    }
+
+    async fn find_oldest_active_by_user(&self, user_id: StringUuid) -> Result<Option<Session>> {
+        let sessions = self.sessions.read().await;
+        let oldest = sessions
+            .iter()
+            .filter(|s| s.user_id == user_id && s.revoked_at.is_none())
+            .min_by_key(|s| s.created_at)
+            .cloned();
+        Ok(oldest)
+    }
+
+    async fn list_session_limit_policies(
+        &self,
+        _user_id: StringUuid,
+    ) -> Result<Vec<SessionLimitPolicy>> {
+        Ok(self.session_limit_policies.read().await.clone())
+    }
+
+    async fn create_within_limit(
+        &self,
+        input: &CreateSessionInput,
+        limit: &SessionLimit,
+    ) -> Result<SessionCreateOutcome> {
+        let mut sessions = self.sessions.write().await;
+        let mut active: Vec<Session> = sessions
+            .iter()
+            .filter(|s| s.user_id == input.user_id && s.revoked_at.is_none())
+            .cloned()
+            .collect();
+        active.sort_by_key(|s| s.last_active_at);
+
+        let (mut revoked, mut kept): (Vec<Session>, Vec<Session>) = active
+            .into_iter()
+            .partition(|s| limit.revoke_session_ids.contains(&s.id));
+        let excess = (kept.len() + 1).saturating_sub(limit.max_sessions.max(1) as usize);
+        if excess > 0 {
+            if !limit.evict_oldest {
+                kept.extend(revoked);
+                kept.sort_by_key(|s| s.last_active_at);
+                return Ok(SessionCreateOutcome::LimitReached { active: kept });
+            }
+            revoked.extend(kept.drain(..excess));
+        }
+
+        let now = Utc::now();
+        for session in sessions.iter_mut() {
+            if revoked.iter().any(|r| r.id == session.id) {
+                session.revoked_at = Some(now);
+            }
+        }
+        let session = Session {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            provider_session_id: input.provider_session_id.clone(),
+            device_type: input.device_type.clone(),
+            device_name: input.device_name.clone(),
+            display_name: None,
+            ip_address: input.ip_address.clone(),
+            location: input.location.clone(),
+            user_agent: input.user_agent.clone(),
+            last_active_at: now,
+            created_at: now,
+            revoked_at: None,
+        };
+        sessions.push(session.clone());
+        Ok(SessionCreateOutcome::Created { session, revoked })
+    }
+}
+
+// ============================================================================
+// Test Linked Identity Repository
+// ============================================================================
+
+/// Configurable test linked identity repository
+pub struct TestLinkedIdentityRepository {
+    identities: RwLock<Vec<LinkedIdentity>>,
+}
+
+impl TestLinkedIdentityRepository {
+    pub fn new() -> Self {
+        Self {
+            identities: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_identity(&self, identity: LinkedIdentity) {
+        self.identities.write().await.push(identity);
+    }
+}
+
+impl Default for TestLinkedIdentityRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LinkedIdentityRepository for TestLinkedIdentityRepository {
+    async fn create(&self, input: &CreateLinkedIdentityInput) -> Result<LinkedIdentity> {
+        let identity = LinkedIdentity {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            provider_type: input.provider_type.clone(),
+            provider_alias: input.provider_alias.clone(),
+            external_user_id: input.external_user_id.clone(),
+            external_email: input.external_email.clone(),
+            linked_at: Utc::now(),
+        };
+        self.identities.write().await.push(identity.clone());
+        Ok(identity)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<LinkedIdentity>> {
+        let identities = self.identities.read().await;
+        Ok(identities.iter().find(|i| i.id == id).cloned())
+    }
+
+    async fn find_by_provider(
+        &self,
+        provider_alias: &str,
+        external_user_id: &str,
+    ) -> Result<Option<LinkedIdentity>> {
+        let identities = self.identities.read().await;
+        Ok(identities
+            .iter()
+            .find(|i| i.provider_alias == provider_alias && i.external_user_id == external_user_id)
+            .cloned())
+    }
+
+    async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<LinkedIdentity>> {
+        let identities = self.identities.read().await;
+        Ok(identities
+            .iter()
+            .filter(|i| i.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut identities = self.identities.write().await;
+        let pos = identities
+            .iter()
+            .position(|i| i.id == id)
+            .ok_or_else(|| AppError::NotFound("Linked identity not found".to_string()))?;
+        identities.remove(pos);
+        Ok(())
+    }
+
+    async fn delete_by_user(&self, user_id: StringUuid) -> Result<u64> {
+        let mut identities = self.identities.write().await;
+        let before = identities.len();
+        identities.retain(|i| i.user_id != user_id);
+        Ok((before - identities.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test Webhook Repository
+// ============================================================================
+
+/// Configurable test webhook repository
+pub struct TestWebhookRepository {
+    webhooks: RwLock<Vec<Webhook>>,
+}
+
+impl TestWebhookRepository {
+    pub fn new() -> Self {
+        Self {
+            webhooks: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_webhook(&self, webhook: Webhook) {
+        self.webhooks.write().await.push(webhook);
+    }
+}
+
+impl Default for TestWebhookRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for TestWebhookRepository {
+    async fn create(
+        &self,
+        tenant_id: Option<StringUuid>,
+        input: &CreateWebhookInput,
+    ) -> Result<Webhook> {
+        let now = Utc::now();
+        let webhook = Webhook {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            name: input.name.clone(),
+            url: input.url.clone(),
+            secret: input.secret.clone(),
+            events: input.events.clone(),
+            filter_expression: input.filter_expression.clone(),
+            enabled: input.enabled,
+            last_triggered_at: None,
+            failure_count: 0,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        self.webhooks.write().await.push(webhook.clone());
+        Ok(webhook)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Webhook>> {
+        let webhooks = self.webhooks.read().await;
+        Ok(webhooks.iter().find(|w| w.id == id).cloned())
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<Webhook>> {
+        let webhooks = self.webhooks.read().await;
+        Ok(webhooks
+            .iter()
+            .filter(|w| w.tenant_id == Some(tenant_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_platform(&self) -> Result<Vec<Webhook>> {
+        let webhooks = self.webhooks.read().await;
+        Ok(webhooks
+            .iter()
+            .filter(|w| w.tenant_id.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list_enabled_for_event(&self, event: &str) -> Result<Vec<Webhook>> {
+        let webhooks = self.webhooks.read().await;
+        Ok(webhooks
+            .iter()
+            .filter(|w| w.enabled && w.events.contains(&event.to_string()))
+            .cloned()
+            .collect())
+    }
+
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateWebhookInput,
+        expected_version: Option<i32>,
+    ) -> Result<Webhook> {
+        let mut webhooks = self.webhooks.write().await;
+        let webhook = webhooks
+            .iter_mut()
+            .find(|w| w.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Webhook {} not found", id)))?;
+        AppError::check_version("webhook", expected_version, webhook.version)?;
+
+        if let Some(name) = &input.name {
+            webhook.name = name.clone();
+        }
+        if let Some(url) = &input.url {
+            webhook.url = url.clone();
+        }
+        if let Some(secret) = &input.secret {
+            webhook.secret = Some(secret.clone());
+        }
+        if let Some(events) = &input.events {
+            webhook.events = events.clone();
+        }
+        if let Some(filter) = &input.filter_expression {
+            webhook.filter_expression = Some(filter.clone()).filter(|f| !f.trim().is_empty());
+        }
+        if let Some(enabled) = input.enabled {
+            webhook.enabled = enabled;
+        }
+        webhook.version += 1;
+        webhook.updated_at = Utc::now();
+        Ok(webhook.clone())
+    }
+
+    async fn update_triggered(&self, id: StringUuid, success: bool) -> Result<()> {
+        let mut webhooks = self.webhooks.write().await;
+        if let Some(webhook) = webhooks.iter_mut().find(|w| w.id == id) {
+            webhook.last_triggered_at = Some(Utc::now());
+            if success {
+                webhook.failure_count = 0;
+            } else {
+                webhook.failure_count += 1;
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut webhooks = self.webhooks.write().await;
+        let pos = webhooks
+            .iter()
+            .position(|w| w.id == id)
+            .ok_or_else(|| AppError::NotFound("Webhook not found".to_string()))?;
+        webhooks.remove(pos);
+        Ok(())
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut webhooks = self.webhooks.write().await;
+        let before = webhooks.len();
+        webhooks.retain(|w| w.tenant_id != Some(tenant_id));
+        Ok((before - webhooks.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test Webhook Delivery Repository
+// ============================================================================
+
+pub struct TestWebhookDeliveryRepository {
+    deliveries: RwLock<Vec<WebhookDelivery>>,
+}
+
+impl TestWebhookDeliveryRepository {
+    pub fn new() -> Self {
+        Self {
+            deliveries: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_delivery(&self, delivery: WebhookDelivery) {
+        self.deliveries.write().await.push(delivery);
+    }
+
+    fn matches(
+        delivery: &WebhookDelivery,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> bool {
+        delivery.webhook_id == webhook_id
+            && filter.success.is_none_or(|s| delivery.success == s)
+            && filter
+                .event_type
+                .as_ref()
+                .is_none_or(|t| delivery.event_type == *t)
+    }
+}
+
+impl Default for TestWebhookDeliveryRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebhookDeliveryRepository for TestWebhookDeliveryRepository {
+    async fn create(&self, delivery: &WebhookDelivery) -> Result<()> {
+        self.deliveries.write().await.push(delivery.clone());
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<WebhookDelivery>> {
+        let deliveries = self.deliveries.read().await;
+        Ok(deliveries.iter().find(|d| d.id == id).cloned())
+    }
+
+    async fn list_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = self.deliveries.read().await;
+        let mut matching: Vec<WebhookDelivery> = deliveries
+            .iter()
+            .filter(|d| Self::matches(d, webhook_id, filter))
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(matching
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_by_webhook(
+        &self,
+        webhook_id: StringUuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<i64> {
+        let deliveries = self.deliveries.read().await;
+        Ok(deliveries
+            .iter()
+            .filter(|d| Self::matches(d, webhook_id, filter))
+            .count() as i64)
+    }
+}
+
+// ============================================================================
+// Test Action Repository
+// ============================================================================
+
+pub struct TestActionRepository {
+    actions: RwLock<Vec<Action>>,
+}
+
+impl TestActionRepository {
+    pub fn new() -> Self {
+        Self {
+            actions: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_action(&self, action: Action) {
+        self.actions.write().await.push(action);
+    }
+}
+
+impl Default for TestActionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ActionRepository for TestActionRepository {
+    async fn create(
+        &self,
+        tenant_id: Option<StringUuid>,
+        service_id: StringUuid,
+        input: &CreateActionInput,
+    ) -> Result<Action> {
+        let now = Utc::now();
+        let action = Action {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            service_id,
+            name: input.name.clone(),
+            description: input.description.clone(),
+            trigger_id: input.trigger_id.clone(),
+            script: input.script.clone(),
+            enabled: input.enabled,
+            strict_mode: input.strict_mode,
+            execution_order: input.execution_order,
+            timeout_ms: input.timeout_ms,
+            last_executed_at: None,
+            execution_count: 0,
+            error_count: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.actions.write().await.push(action.clone());
+        Ok(action)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Action>> {
+        let actions = self.actions.read().await;
+        Ok(actions.iter().find(|a| a.id == id).cloned())
+    }
+
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<Action>> {
+        let actions = self.actions.read().await;
+        Ok(actions
+            .iter()
+            .filter(|a| a.service_id == service_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_trigger(
+        &self,
+        service_id: StringUuid,
+        trigger_id: &str,
+        enabled_only: bool,
+    ) -> Result<Vec<Action>> {
+        let actions = self.actions.read().await;
+        Ok(actions
+            .iter()
+            .filter(|a| {
+                a.service_id == service_id
+                    && a.trigger_id == trigger_id
+                    && (!enabled_only || a.enabled)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_tenant_trigger(
+        &self,
+        tenant_id: StringUuid,
+        trigger_id: &str,
+        enabled_only: bool,
+    ) -> Result<Vec<Action>> {
+        let actions = self.actions.read().await;
+        Ok(actions
+            .iter()
+            .filter(|a| {
+                a.tenant_id == Some(tenant_id)
+                    && a.trigger_id == trigger_id
+                    && (!enabled_only || a.enabled)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, id: StringUuid, input: &UpdateActionInput) -> Result<Action> {
+        let mut actions = self.actions.write().await;
+        let action = actions
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("Action {} not found", id)))?;
+
+        if let Some(name) = &input.name {
+            action.name = name.clone();
+        }
+        if let Some(description) = &input.description {
+            action.description = Some(description.clone());
+        }
+        if let Some(script) = &input.script {
+            action.script = script.clone();
+        }
+        if let Some(enabled) = input.enabled {
+            action.enabled = enabled;
+        }
+        if let Some(strict_mode) = input.strict_mode {
+            action.strict_mode = strict_mode;
+        }
+        if let Some(execution_order) = input.execution_order {
+            action.execution_order = execution_order;
+        }
+        if let Some(timeout_ms) = input.timeout_ms {
+            action.timeout_ms = timeout_ms;
+        }
+        action.updated_at = Utc::now();
+        Ok(action.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut actions = self.actions.write().await;
+        let pos = actions
+            .iter()
+            .position(|a| a.id == id)
+            .ok_or_else(|| AppError::NotFound("Action not found".to_string()))?;
+        actions.remove(pos);
+        Ok(())
+    }
+
+    async fn delete_by_service(&self, service_id: StringUuid) -> Result<u64> {
+        let mut actions = self.actions.write().await;
+        let before = actions.len();
+        actions.retain(|a| a.service_id != service_id);
+        Ok((before - actions.len()) as u64)
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut actions = self.actions.write().await;
+        let before = actions.len();
+        actions.retain(|a| a.tenant_id != Some(tenant_id));
+        Ok((before - actions.len()) as u64)
+    }
+
+    async fn record_execution(
+        &self,
+        _action_id: StringUuid,
+        _tenant_id: Option<StringUuid>,
+        _service_id: StringUuid,
+        _trigger_id: String,
+        _user_id: Option<StringUuid>,
+        _success: bool,
+        _duration_ms: i32,
+        _error: Option<String>,
+    ) -> Result<()> {
+        // For test repository, just record in memory (not to action_executions table)
+        Ok(())
+    }
+
+    async fn update_execution_stats(
+        &self,
+        id: StringUuid,
+        success: bool,
+        error: Option<String>,
+    ) -> Result<()> {
+        let mut actions = self.actions.write().await;
+        if let Some(action) = actions.iter_mut().find(|a| a.id == id) {
+            action.last_executed_at = Some(Utc::now());
+            action.execution_count += 1;
+            if !success {
+                action.error_count += 1;
+                action.last_error = error;
+            } else {
+                action.last_error = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_execution_by_id(
+        &self,
+        _id: StringUuid,
+    ) -> Result<Option<crate::models::action::ActionExecution>> {
+        Ok(None)
+    }
+
+    async fn query_logs(
+        &self,
+        _filter: &crate::models::action::LogQueryFilter,
+    ) -> Result<Vec<crate::models::action::ActionExecution>> {
+        // For test repository, return empty logs
+        Ok(vec![])
+    }
+
+    async fn count_logs(&self, _filter: &crate::models::action::LogQueryFilter) -> Result<i64> {
+        Ok(0)
+    }
+
+    async fn get_stats(&self, action_id: StringUuid) -> Result<Option<(i64, i64, f64, i64)>> {
+        let actions = self.actions.read().await;
+        if let Some(action) = actions.iter().find(|a| a.id == action_id) {
+            Ok(Some((
+                action.execution_count,
+                action.error_count,
+                0.0, // avg_duration_ms
+                0,   // last_24h_count
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// ============================================================================
+// Test Invitation Repository
+// ============================================================================
+
+/// Configurable test invitation repository
+pub struct TestInvitationRepository {
+    invitations: RwLock<HashMap<StringUuid, Invitation>>,
+}
+
+impl TestInvitationRepository {
+    pub fn new() -> Self {
+        Self {
+            invitations: RwLock::new(HashMap::new()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn add_invitation(&self, invitation: Invitation) {
+        self.invitations
+            .write()
+            .await
+            .insert(invitation.id, invitation);
+    }
+}
+
+impl Default for TestInvitationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for TestInvitationRepository {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        invited_by: StringUuid,
+        input: &CreateInvitationInput,
+        token_hash: &str,
+    ) -> Result<Invitation> {
+        let now = Utc::now();
+        let expires_in = input.expires_in_hours.unwrap_or(72);
+        let invitation = Invitation {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            email: input.email.clone(),
+            role_ids: input.role_ids.clone(),
+            invited_by,
+            token_hash: token_hash.to_string(),
+            status: InvitationStatus::Pending,
+            expires_at: now + chrono::Duration::hours(expires_in),
+            accepted_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.invitations
+            .write()
+            .await
+            .insert(invitation.id, invitation.clone());
+        Ok(invitation)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<Invitation>> {
+        let invitations = self.invitations.read().await;
+        Ok(invitations.get(&id).cloned())
+    }
+
+    async fn find_by_email_and_tenant(
+        &self,
+        email: &str,
+        tenant_id: StringUuid,
+    ) -> Result<Option<Invitation>> {
+        let invitations = self.invitations.read().await;
+        Ok(invitations
+            .values()
+            .find(|i| {
+                i.email == email
+                    && i.tenant_id == tenant_id
+                    && i.status == InvitationStatus::Pending
+            })
+            .cloned())
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        status: Option<InvitationStatus>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Invitation>> {
+        let invitations = self.invitations.read().await;
+        let mut filtered: Vec<_> = invitations
+            .values()
+            .filter(|i| i.tenant_id == tenant_id && status.as_ref().is_none_or(|s| &i.status == s))
+            .cloned()
+            .collect();
+        filtered.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(filtered
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        status: Option<InvitationStatus>,
+    ) -> Result<i64> {
+        let invitations = self.invitations.read().await;
+        Ok(invitations
+            .values()
+            .filter(|i| i.tenant_id == tenant_id && status.as_ref().is_none_or(|s| &i.status == s))
+            .count() as i64)
+    }
+
+    async fn update_status(&self, id: StringUuid, status: InvitationStatus) -> Result<Invitation> {
+        let mut invitations = self.invitations.write().await;
+        let invitation = invitations
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("Invitation {} not found", id)))?;
+        invitation.status = status;
+        invitation.updated_at = Utc::now();
+        Ok(invitation.clone())
+    }
+
+    async fn list_pending(&self) -> Result<Vec<Invitation>> {
+        let invitations = self.invitations.read().await;
+        Ok(invitations
+            .values()
+            .filter(|i| i.status == InvitationStatus::Pending)
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_accepted(&self, id: StringUuid) -> Result<Invitation> {
+        let mut invitations = self.invitations.write().await;
+        let invitation = invitations
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("Invitation {} not found", id)))?;
+        invitation.status = InvitationStatus::Accepted;
+        invitation.accepted_at = Some(Utc::now());
+        invitation.updated_at = Utc::now();
+        Ok(invitation.clone())
+    }
+
+    async fn update_token_hash(&self, id: StringUuid, token_hash: &str) -> Result<Invitation> {
+        let mut invitations = self.invitations.write().await;
+        let invitation = invitations
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("Invitation {} not found", id)))?;
+        invitation.token_hash = token_hash.to_string();
+        invitation.updated_at = Utc::now();
+        Ok(invitation.clone())
+    }
+
+    async fn touch_updated_at(&self, id: StringUuid) -> Result<Invitation> {
+        let mut invitations = self.invitations.write().await;
+        let invitation = invitations
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("Invitation {} not found", id)))?;
+        invitation.updated_at = Utc::now();
+        Ok(invitation.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut invitations = self.invitations.write().await;
+        invitations
+            .remove(&id)
+            .ok_or_else(|| AppError::NotFound(format!("Invitation {} not found", id)))?;
+        Ok(())
+    }
+
+    async fn expire_pending(&self) -> Result<u64> {
+        let mut invitations = self.invitations.write().await;
+        let now = Utc::now();
+        let mut count = 0u64;
+        for invitation in invitations.values_mut() {
+            if invitation.status == InvitationStatus::Pending && invitation.expires_at <= now {
+                invitation.status = InvitationStatus::Expired;
+                invitation.updated_at = now;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut invitations = self.invitations.write().await;
+        let before = invitations.len();
+        invitations.retain(|_, i| i.tenant_id != tenant_id);
+        Ok((before - invitations.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test Login Event Repository
+// ============================================================================
+
+/// Configurable test login event repository
+pub struct TestLoginEventRepository {
+    events: RwLock<Vec<LoginEvent>>,
+    next_id: RwLock<i64>,
+}
+
+impl TestLoginEventRepository {
+    pub fn new() -> Self {
+        Self {
+            events: RwLock::new(vec![]),
+            next_id: RwLock::new(1),
+        }
+    }
+
+    pub async fn add_event(&self, event: LoginEvent) {
+        self.events.write().await.push(event);
+    }
+}
+
+impl Default for TestLoginEventRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LoginEventRepository for TestLoginEventRepository {
+    async fn create(&self, input: &CreateLoginEventInput) -> Result<i64> {
+        let mut next_id = self.next_id.write().await;
+        let event = LoginEvent {
+            id: *next_id,
+            user_id: input.user_id,
+            email: input.email.clone(),
+            tenant_id: input.tenant_id,
+            event_type: input.event_type.clone(),
+            ip_address: input.ip_address.clone(),
+            user_agent: input.user_agent.clone(),
+            device_type: input.device_type.clone(),
+            location: input.location.clone(),
+            session_id: input.session_id,
+            failure_reason: input.failure_reason.clone(),
+            provider_alias: input.provider_alias.clone(),
+            provider_type: input.provider_type.clone(),
+            latitude: input.latitude,
+            longitude: input.longitude,
+            country_code: input.country_code.clone(),
+            risk_score: None,
+            created_at: Utc::now(),
+        };
+        self.events.write().await.push(event);
+        let id = *next_id;
+        *next_id += 1;
+        Ok(id)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<LoginEvent>> {
+        let events = self.events.read().await;
+        Ok(events.iter().find(|e| e.id == id).cloned())
+    }
+
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<LoginEvent>> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<LoginEvent>> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.user_id == Some(user_id))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<LoginEvent>> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.tenant_id == Some(tenant_id))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.events.read().await.len() as i64)
+    }
+
+    async fn count_by_user(&self, user_id: StringUuid) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events.iter().filter(|e| e.user_id == Some(user_id)).count() as i64)
+    }
+
+    async fn list_by_email(&self, email: &str, offset: i64, limit: i64) -> Result<Vec<LoginEvent>> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.email.as_deref() == Some(email))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_tenant(&self, tenant_id: StringUuid) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.tenant_id == Some(tenant_id))
+            .count() as i64)
+    }
+
+    async fn count_by_email(&self, email: &str) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| e.email.as_deref() == Some(email))
+            .count() as i64)
+    }
+
+    async fn get_stats(
+        &self,
+        _tenant_id: Option<StringUuid>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<LoginStats> {
+        let events = self.events.read().await;
+        let filtered: Vec<_> = events
+            .iter()
+            .filter(|e| e.created_at >= start && e.created_at <= end)
+            .collect();
+
+        let total_logins = filtered.len() as i64;
+        let successful_logins = filtered
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event_type,
+                    LoginEventType::Success | LoginEventType::Social
+                )
+            })
+            .count() as i64;
+        let failed_logins = filtered
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e.event_type,
+                    LoginEventType::FailedPassword
+                        | LoginEventType::FailedMfa
+                        | LoginEventType::Locked
+                )
+            })
+            .count() as i64;
+
+        let unique_users: std::collections::HashSet<_> =
+            filtered.iter().filter_map(|e| e.user_id).collect();
+
+        let mut by_event_type: HashMap<String, i64> = HashMap::new();
+        let mut by_device_type: HashMap<String, i64> = HashMap::new();
+
+        for event in &filtered {
+            *by_event_type
+                .entry(format!("{:?}", event.event_type).to_lowercase())
+                .or_insert(0) += 1;
+            let device = event
+                .device_type
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string());
+            *by_device_type.entry(device).or_insert(0) += 1;
+        }
+
+        Ok(LoginStats {
+            total_logins,
+            successful_logins,
+            failed_logins,
+            unique_users: unique_users.len() as i64,
+            by_event_type,
+            by_device_type,
+            period_start: start,
+            period_end: end,
+        })
+    }
+
+    async fn count_failed_by_ip(&self, ip_address: &str, since: DateTime<Utc>) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| {
+                e.ip_address.as_deref() == Some(ip_address)
+                    && e.created_at >= since
+                    && matches!(
+                        e.event_type,
+                        LoginEventType::FailedPassword | LoginEventType::FailedMfa
+                    )
+            })
+            .count() as i64)
+    }
+
+    async fn count_failed_by_ip_multi_user(
+        &self,
+        ip_address: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let events = self.events.read().await;
+        let unique_users: std::collections::HashSet<_> = events
+            .iter()
+            .filter(|e| {
+                e.ip_address.as_deref() == Some(ip_address)
+                    && e.created_at >= since
+                    && matches!(
+                        e.event_type,
+                        LoginEventType::FailedPassword | LoginEventType::FailedMfa
+                    )
+            })
+            .filter_map(|e| {
+                e.user_id
+                    .or_else(|| e.email.as_ref().map(|_| StringUuid::new_v4()))
+            })
+            .collect();
+        Ok(unique_users.len() as i64)
+    }
+
+    async fn count_failed_by_user(&self, email: &str, since: DateTime<Utc>) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| {
+                e.email.as_deref() == Some(email)
+                    && e.created_at >= since
+                    && matches!(
+                        e.event_type,
+                        LoginEventType::FailedPassword | LoginEventType::FailedMfa
+                    )
+            })
+            .count() as i64)
+    }
+
+    async fn delete_old(&self, days: i64) -> Result<u64> {
+        let mut events = self.events.write().await;
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+        let before = events.len();
+        events.retain(|e| e.created_at > cutoff);
+        Ok((before - events.len()) as u64)
+    }
+
+    async fn nullify_user_id(&self, user_id: StringUuid) -> Result<u64> {
+        let mut events = self.events.write().await;
+        let mut count = 0u64;
+        for event in events.iter_mut() {
+            if event.user_id == Some(user_id) {
+                event.user_id = None;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut events = self.events.write().await;
+        let before = events.len();
+        events.retain(|e| e.tenant_id != Some(tenant_id));
+        Ok((before - events.len()) as u64)
+    }
+
+    async fn count_federation_failed_by_provider(
+        &self,
+        provider_alias: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64> {
+        let events = self.events.read().await;
+        Ok(events
+            .iter()
+            .filter(|e| {
+                e.provider_alias.as_deref() == Some(provider_alias)
+                    && e.event_type == crate::models::analytics::LoginEventType::FederationFailed
+                    && e.created_at >= since
+            })
+            .count() as i64)
+    }
+
+    async fn get_daily_trend(
+        &self,
+        _tenant_id: Option<StringUuid>,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<Vec<crate::models::analytics::DailyTrendPoint>> {
+        Ok(vec![])
+    }
+}
+
+// ============================================================================
+// Test Security Alert Repository
+// ============================================================================
+
+/// Configurable test security alert repository
+pub struct TestSecurityAlertRepository {
+    alerts: RwLock<Vec<SecurityAlert>>,
+}
+
+impl TestSecurityAlertRepository {
+    pub fn new() -> Self {
+        Self {
+            alerts: RwLock::new(vec![]),
+        }
+    }
+
+    pub async fn add_alert(&self, alert: SecurityAlert) {
+        self.alerts.write().await.push(alert);
+    }
+}
+
+impl Default for TestSecurityAlertRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SecurityAlertRepository for TestSecurityAlertRepository {
+    async fn create(&self, input: &CreateSecurityAlertInput) -> Result<SecurityAlert> {
+        let alert = SecurityAlert {
+            id: StringUuid::new_v4(),
+            user_id: input.user_id,
+            tenant_id: input.tenant_id,
+            alert_type: input.alert_type.clone(),
+            severity: input.severity.clone(),
+            details: input.details.clone(),
+            resolved_at: None,
+            resolved_by: None,
+            created_at: Utc::now(),
+        };
+        self.alerts.write().await.push(alert.clone());
+        Ok(alert)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts.iter().find(|a| a.id == id).cloned())
+    }
+
+    async fn list(&self, offset: i64, limit: i64) -> Result<Vec<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_unresolved(&self, offset: i64, limit: i64) -> Result<Vec<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .filter(|a| a.resolved_at.is_none())
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .filter(|a| a.user_id == Some(user_id))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_by_severity(
+        &self,
+        severity: AlertSeverity,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .filter(|a| a.severity == severity)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_filtered(
+        &self,
+        offset: i64,
+        limit: i64,
+        resolved_filter: Option<bool>,
+        severity: Option<AlertSeverity>,
+        alert_type: Option<SecurityAlertType>,
+    ) -> Result<Vec<SecurityAlert>> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .filter(|a| match resolved_filter {
+                Some(true) => a.resolved_at.is_some(),
+                Some(false) => a.resolved_at.is_none(),
+                None => true,
+            })
+            .filter(|a| severity.as_ref().is_none_or(|s| a.severity == *s))
+            .filter(|a| alert_type.as_ref().is_none_or(|t| a.alert_type == *t))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_filtered(
+        &self,
+        resolved_filter: Option<bool>,
+        severity: Option<AlertSeverity>,
+        alert_type: Option<SecurityAlertType>,
+    ) -> Result<i64> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts
+            .iter()
+            .filter(|a| match resolved_filter {
+                Some(true) => a.resolved_at.is_some(),
+                Some(false) => a.resolved_at.is_none(),
+                None => true,
+            })
+            .filter(|a| severity.as_ref().is_none_or(|s| a.severity == *s))
+            .filter(|a| alert_type.as_ref().is_none_or(|t| a.alert_type == *t))
+            .count() as i64)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        Ok(self.alerts.read().await.len() as i64)
+    }
+
+    async fn count_unresolved(&self) -> Result<i64> {
+        let alerts = self.alerts.read().await;
+        Ok(alerts.iter().filter(|a| a.resolved_at.is_none()).count() as i64)
+    }
+
+    async fn resolve(&self, id: StringUuid, resolved_by: StringUuid) -> Result<SecurityAlert> {
+        let mut alerts = self.alerts.write().await;
+        let alert = alerts
+            .iter_mut()
+            .find(|a| a.id == id && a.resolved_at.is_none())
+            .ok_or_else(|| {
+                AppError::NotFound("Security alert not found or already resolved".to_string())
+            })?;
+        alert.resolved_at = Some(Utc::now());
+        alert.resolved_by = Some(resolved_by);
+        Ok(alert.clone())
+    }
+
+    async fn delete_old(&self, days: i64) -> Result<u64> {
+        let mut alerts = self.alerts.write().await;
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+        let before = alerts.len();
+        alerts.retain(|a| a.resolved_at.is_none() || a.created_at > cutoff);
+        Ok((before - alerts.len()) as u64)
+    }
+
+    async fn nullify_user_id(&self, user_id: StringUuid) -> Result<u64> {
+        let mut alerts = self.alerts.write().await;
+        let mut count = 0u64;
+        for alert in alerts.iter_mut() {
+            if alert.user_id == Some(user_id) {
+                alert.user_id = None;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut alerts = self.alerts.write().await;
+        let before = alerts.len();
+        alerts.retain(|a| a.tenant_id != Some(tenant_id));
+        Ok((before - alerts.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test WebAuthn Repository
+// ============================================================================
+
+pub struct TestWebAuthnRepository {
+    credentials: RwLock<Vec<StoredPasskey>>,
+}
+
+impl TestWebAuthnRepository {
+    pub fn new() -> Self {
+        Self {
+            credentials: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestWebAuthnRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl WebAuthnRepository for TestWebAuthnRepository {
+    async fn create(&self, input: &CreatePasskeyInput) -> Result<StoredPasskey> {
+        let stored = StoredPasskey {
+            id: input.id.clone(),
+            user_id: input.user_id.clone(),
+            credential_id: input.credential_id.clone(),
+            credential_data: input.credential_data.clone(),
+            user_label: input.user_label.clone(),
+            aaguid: input.aaguid.clone(),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        self.credentials.write().await.push(stored.clone());
+        Ok(stored)
+    }
+
+    async fn find_by_credential_id(&self, credential_id: &str) -> Result<Option<StoredPasskey>> {
+        let creds = self.credentials.read().await;
+        Ok(creds
+            .iter()
+            .find(|c| c.credential_id == credential_id)
+            .cloned())
+    }
+
+    async fn list_by_user(&self, user_id: &str) -> Result<Vec<StoredPasskey>> {
+        let creds = self.credentials.read().await;
+        Ok(creds
+            .iter()
+            .filter(|c| c.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete(&self, id: &str, user_id: &str) -> Result<()> {
+        let mut creds = self.credentials.write().await;
+        let before = creds.len();
+        creds.retain(|c| !(c.id == id && c.user_id == user_id));
+        if creds.len() == before {
+            return Err(AppError::NotFound(
+                "WebAuthn credential not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn delete_by_user(&self, user_id: &str) -> Result<u64> {
+        let mut creds = self.credentials.write().await;
+        let before = creds.len();
+        creds.retain(|c| c.user_id != user_id);
+        Ok((before - creds.len()) as u64)
+    }
+
+    async fn update_last_used(&self, id: &str) -> Result<()> {
+        let mut creds = self.credentials.write().await;
+        if let Some(cred) = creds.iter_mut().find(|c| c.id == id) {
+            cred.last_used_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn update_credential_data(&self, id: &str, data: &serde_json::Value) -> Result<()> {
+        let mut creds = self.credentials.write().await;
+        if let Some(cred) = creds.iter_mut().find(|c| c.id == id) {
+            cred.credential_data = data.clone();
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Test Service Branding Repository
+// ============================================================================
+
+pub struct TestServiceBrandingRepository {
+    brandings: RwLock<Vec<crate::models::branding::ServiceBranding>>,
+}
+
+impl TestServiceBrandingRepository {
+    pub fn new() -> Self {
+        Self {
+            brandings: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestServiceBrandingRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ServiceBrandingRepository for TestServiceBrandingRepository {
+    async fn get_by_service_id(
+        &self,
+        service_id: StringUuid,
+    ) -> Result<Option<crate::models::branding::ServiceBranding>> {
+        let brandings = self.brandings.read().await;
+        Ok(brandings
+            .iter()
+            .find(|b| b.service_id == service_id.to_string())
+            .cloned())
+    }
+
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        config: &crate::models::branding::BrandingConfig,
+    ) -> Result<crate::models::branding::ServiceBranding> {
+        let mut brandings = self.brandings.write().await;
+        brandings.retain(|b| b.service_id != service_id.to_string());
+        let branding = crate::models::branding::ServiceBranding {
+            id: StringUuid::new_v4().to_string(),
+            service_id: service_id.to_string(),
+            config: config.clone(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        brandings.push(branding.clone());
+        Ok(branding)
+    }
+
+    async fn delete_by_service_id(&self, service_id: StringUuid) -> Result<()> {
+        let mut brandings = self.brandings.write().await;
+        brandings.retain(|b| b.service_id != service_id.to_string());
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Test Data Helpers
+// ============================================================================
+
+pub fn create_test_tenant(id: Option<Uuid>) -> Tenant {
+    Tenant {
+        id: StringUuid::from(id.unwrap_or_else(Uuid::new_v4)),
+        name: "Test Tenant".to_string(),
+        slug: "test-tenant".to_string(),
+        domain: None,
+        logo_url: None,
+        settings: TenantSettings::default(),
+        status: TenantStatus::Active,
+        password_policy: None,
+        data_region: None,
+        version: 1,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub fn create_test_user(id: Option<Uuid>) -> User {
+    User {
+        id: StringUuid::from(id.unwrap_or_else(Uuid::new_v4)),
+        email: "test@example.com".to_string(),
+        display_name: Some("Test User".to_string()),
+        avatar_url: None,
+        identity_subject: "kc-user-test".to_string(),
+        scim_external_id: None,
+        scim_provisioned_by: None,
+        mfa_enabled: false,
+        email_otp_enabled: false,
+        password_changed_at: None,
+        locked_until: None,
+        locale: None,
+        version: 1,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub fn create_test_service(id: Option<Uuid>, tenant_id: Option<Uuid>) -> Service {
+    Service {
+        id: StringUuid::from(id.unwrap_or_else(Uuid::new_v4)),
+        tenant_id: tenant_id.map(StringUuid::from),
+        name: "Test Service".to_string(),
+        base_url: Some("https://test.example.com".to_string()),
+        redirect_uris: vec!["https://test.example.com/callback".to_string()],
+        logout_uris: vec![],
+        status: ServiceStatus::Active,
+        version: 1,
+        catalog: Default::default(),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub fn create_test_action(tenant_id: Uuid, name: &str) -> Action {
+    Action {
+        id: StringUuid::new_v4(),
+        tenant_id: Some(StringUuid::from(tenant_id)),
+        service_id: StringUuid::new_v4(),
+        name: name.to_string(),
+        description: Some("Test action description".to_string()),
+        trigger_id: "post-login".to_string(),
+        script: "export default async function(ctx) { return ctx; }".to_string(),
+        enabled: true,
+        strict_mode: false,
+        execution_order: 0,
+        timeout_ms: 5000,
+        last_executed_at: None,
+        execution_count: 0,
+        error_count: 0,
+        last_error: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub fn create_test_role(id: Option<Uuid>, service_id: Uuid) -> Role {
+    Role {
+        id: StringUuid::from(id.unwrap_or_else(Uuid::new_v4)),
+        service_id: StringUuid::from(service_id),
+        name: "test-role".to_string(),
+        description: Some("Test role description".to_string()),
+        parent_role_id: None,
+        version: 1,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+pub fn create_test_permission(id: Option<Uuid>, service_id: Uuid) -> Permission {
+    Permission {
+        id: StringUuid::from(id.unwrap_or_else(Uuid::new_v4)),
+        service_id: StringUuid::from(service_id),
+        code: "test:permission".to_string(),
+        name: "Test Permission".to_string(),
+        description: Some("Test permission".to_string()),
+    }
+}
+
+// ============================================================================
+// Test Services Builder
+// ============================================================================
+
+/// Builder for creating test services with mocked repositories
+#[allow(dead_code)]
+pub struct TestServicesBuilder {
+    pub tenant_repo: Arc<TestTenantRepository>,
+    pub user_repo: Arc<TestUserRepository>,
+    pub service_repo: Arc<TestServiceRepository>,
+    pub rbac_repo: Arc<TestRbacRepository>,
+    pub audit_repo: Arc<TestAuditRepository>,
+    pub webhook_repo: Arc<TestWebhookRepository>,
+    #[allow(dead_code)]
+    pub invitation_repo: Arc<TestInvitationRepository>,
+    pub session_repo: Arc<TestSessionRepository>,
+    #[allow(dead_code)]
+    pub password_reset_repo: Arc<TestPasswordResetRepository>,
+    pub linked_identity_repo: Arc<TestLinkedIdentityRepository>,
+    pub login_event_repo: Arc<TestLoginEventRepository>,
+    pub security_alert_repo: Arc<TestSecurityAlertRepository>,
+    pub action_repo: Arc<TestActionRepository>,
+}
+
+#[allow(dead_code)]
+impl TestServicesBuilder {
+    pub fn new() -> Self {
+        Self {
+            tenant_repo: Arc::new(TestTenantRepository::new()),
+            user_repo: Arc::new(TestUserRepository::new()),
+            service_repo: Arc::new(TestServiceRepository::new()),
+            rbac_repo: Arc::new(TestRbacRepository::new()),
+            audit_repo: Arc::new(TestAuditRepository::new()),
+            webhook_repo: Arc::new(TestWebhookRepository::new()),
+            invitation_repo: Arc::new(TestInvitationRepository::new()),
+            session_repo: Arc::new(TestSessionRepository::new()),
+            password_reset_repo: Arc::new(TestPasswordResetRepository::new()),
+            linked_identity_repo: Arc::new(TestLinkedIdentityRepository::new()),
+            login_event_repo: Arc::new(TestLoginEventRepository::new()),
+            security_alert_repo: Arc::new(TestSecurityAlertRepository::new()),
+            action_repo: Arc::new(TestActionRepository::new()),
+        }
+    }
+
+    pub fn build_tenant_service(
+        &self,
+    ) -> TenantService<
+        TestTenantRepository,
+        TestServiceRepository,
+        TestWebhookRepository,
+        TestInvitationRepository,
+        TestUserRepository,
+        TestRbacRepository,
+        TestLoginEventRepository,
+        TestSecurityAlertRepository,
+        TestActionRepository,
+    > {
+        let repos = TenantRepositoryBundle::new(
+            self.tenant_repo.clone(),
+            self.service_repo.clone(),
+            self.webhook_repo.clone(),
+            self.invitation_repo.clone(),
+            self.user_repo.clone(),
+            self.rbac_repo.clone(),
+            self.login_event_repo.clone(),
+            self.security_alert_repo.clone(),
+            self.action_repo.clone(),
+        );
+        TenantService::new(repos, None)
+    }
+
+    pub fn build_user_service(
+        &self,
+    ) -> UserService<
+        TestUserRepository,
+        TestSessionRepository,
+        TestPasswordResetRepository,
+        TestLinkedIdentityRepository,
+        TestLoginEventRepository,
+        TestSecurityAlertRepository,
+        TestAuditRepository,
+        TestRbacRepository,
+    > {
+        let repos = UserRepositoryBundle::new(
+            self.user_repo.clone(),
+            self.session_repo.clone(),
+            self.password_reset_repo.clone(),
+            self.linked_identity_repo.clone(),
+            self.login_event_repo.clone(),
+            self.security_alert_repo.clone(),
+            self.audit_repo.clone(),
+            self.rbac_repo.clone(),
+        );
+        UserService::new(repos, None)
+    }
+
+    pub fn build_client_service(&self) -> ClientService<TestServiceRepository, TestRbacRepository> {
+        ClientService::new(self.service_repo.clone(), self.rbac_repo.clone(), None)
+    }
+
+    pub fn build_rbac_service(&self) -> RbacService<TestRbacRepository> {
+        RbacService::new(self.rbac_repo.clone(), None)
+    }
+}
+
+impl Default for TestServicesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Test SCIM Repositories
+// ============================================================================
+
+use crate::models::scim::{
+    CreateScimLogInput, ScimGroupRoleMapping, ScimProvisioningLog, ScimToken,
+};
+use crate::repository::scim_group_mapping::ScimGroupRoleMappingRepository;
+use crate::repository::scim_log::ScimProvisioningLogRepository;
+use crate::repository::scim_token::ScimTokenRepository;
+
+pub struct TestScimTokenRepository {
+    tokens: RwLock<Vec<ScimToken>>,
+}
+
+impl TestScimTokenRepository {
+    pub fn new() -> Self {
+        Self {
+            tokens: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestScimTokenRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ScimTokenRepository for TestScimTokenRepository {
+    async fn create(&self, token: &ScimToken) -> Result<ScimToken> {
+        self.tokens.write().await.push(token.clone());
+        Ok(token.clone())
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<ScimToken>> {
+        Ok(self
+            .tokens
+            .read()
+            .await
+            .iter()
+            .find(|t| t.token_hash == token_hash)
+            .cloned())
+    }
+
+    async fn list_by_connector(&self, connector_id: StringUuid) -> Result<Vec<ScimToken>> {
+        Ok(self
+            .tokens
+            .read()
+            .await
+            .iter()
+            .filter(|t| t.connector_id == connector_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update_last_used(&self, _id: StringUuid) -> Result<()> {
+        Ok(())
+    }
+
+    async fn revoke(&self, id: StringUuid) -> Result<()> {
+        let mut tokens = self.tokens.write().await;
+        if let Some(token) = tokens.iter_mut().find(|t| t.id == id) {
+            token.revoked_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn delete_by_connector(&self, connector_id: StringUuid) -> Result<u64> {
+        let mut tokens = self.tokens.write().await;
+        let before = tokens.len();
+        tokens.retain(|t| t.connector_id != connector_id);
+        Ok((before - tokens.len()) as u64)
+    }
+}
+
+pub struct TestScimGroupMappingRepository {
+    mappings: RwLock<Vec<ScimGroupRoleMapping>>,
+}
+
+impl TestScimGroupMappingRepository {
+    pub fn new() -> Self {
+        Self {
+            mappings: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestScimGroupMappingRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ScimGroupRoleMappingRepository for TestScimGroupMappingRepository {
+    async fn find_by_scim_group(
+        &self,
+        connector_id: StringUuid,
+        scim_group_id: &str,
+    ) -> Result<Option<ScimGroupRoleMapping>> {
+        Ok(self
+            .mappings
+            .read()
+            .await
+            .iter()
+            .find(|m| m.connector_id == connector_id && m.scim_group_id == scim_group_id)
+            .cloned())
+    }
+
+    async fn list_by_connector(
+        &self,
+        connector_id: StringUuid,
+    ) -> Result<Vec<ScimGroupRoleMapping>> {
+        Ok(self
+            .mappings
+            .read()
+            .await
+            .iter()
+            .filter(|m| m.connector_id == connector_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert(&self, mapping: &ScimGroupRoleMapping) -> Result<ScimGroupRoleMapping> {
+        let mut mappings = self.mappings.write().await;
+        mappings.retain(|m| {
+            !(m.connector_id == mapping.connector_id && m.scim_group_id == mapping.scim_group_id)
+        });
+        mappings.push(mapping.clone());
+        Ok(mapping.clone())
+    }
+
+    async fn update_display_name(&self, id: StringUuid, display_name: &str) -> Result<()> {
+        let mut mappings = self.mappings.write().await;
+        if let Some(m) = mappings.iter_mut().find(|m| m.id == id) {
+            m.scim_group_display_name = Some(display_name.to_string());
+            Ok(())
+        } else {
+            Err(AppError::NotFound(format!(
+                "SCIM group mapping {} not found",
+                id
+            )))
+        }
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        self.mappings.write().await.retain(|m| m.id != id);
+        Ok(())
+    }
+
+    async fn delete_by_connector(&self, connector_id: StringUuid) -> Result<u64> {
+        let mut mappings = self.mappings.write().await;
+        let before = mappings.len();
+        mappings.retain(|m| m.connector_id != connector_id);
+        Ok((before - mappings.len()) as u64)
+    }
+}
+
+pub struct TestScimLogRepository {
+    logs: RwLock<Vec<ScimProvisioningLog>>,
+}
+
+impl TestScimLogRepository {
+    pub fn new() -> Self {
+        Self {
+            logs: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestScimLogRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ScimProvisioningLogRepository for TestScimLogRepository {
+    async fn create(&self, input: &CreateScimLogInput) -> Result<ScimProvisioningLog> {
+        let log = ScimProvisioningLog {
+            id: StringUuid::new_v4(),
+            tenant_id: input.tenant_id,
+            connector_id: input.connector_id,
+            operation: input.operation.clone(),
+            resource_type: input.resource_type.clone(),
+            scim_resource_id: input.scim_resource_id.clone(),
+            auth9_resource_id: input.auth9_resource_id,
+            status: input.status.clone(),
+            error_detail: input.error_detail.clone(),
+            response_status: input.response_status,
+            created_at: Utc::now(),
+        };
+        self.logs.write().await.push(log.clone());
+        Ok(log)
+    }
+
+    async fn list_by_connector(
+        &self,
+        connector_id: StringUuid,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ScimProvisioningLog>> {
+        Ok(self
+            .logs
+            .read()
+            .await
+            .iter()
+            .filter(|l| l.connector_id == connector_id)
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_connector(&self, connector_id: StringUuid) -> Result<i64> {
+        Ok(self
+            .logs
+            .read()
+            .await
+            .iter()
+            .filter(|l| l.connector_id == connector_id)
+            .count() as i64)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tenant_repository_crud() {
+        let repo = TestTenantRepository::new();
+
+        // Create
+        let input = CreateTenantInput {
+            name: "Test".to_string(),
+            slug: "test".to_string(),
+            domain: None,
+            logo_url: None,
+            settings: None,
+            data_region: None,
+        };
+        let tenant = repo.create(&input).await.unwrap();
+        assert_eq!(tenant.name, "Test");
+
+        // Read
+        let found = repo.find_by_id(tenant.id).await.unwrap();
+        assert!(found.is_some());
+
+        // List
+        let list = repo.list(0, 10).await.unwrap();
+        assert_eq!(list.len(), 1);
+
+        // Update
+        let update_input = UpdateTenantInput {
+            name: Some("Updated".to_string()),
+            logo_url: None,
+            settings: None,
+            status: None,
+        };
+        let updated = repo.update(tenant.id, &update_input, None).await.unwrap();
+        assert_eq!(updated.name, "Updated");
+
+        // Delete
+        repo.delete(tenant.id).await.unwrap();
+        let deleted = repo.find_by_id(tenant.id).await.unwrap();
+        assert!(deleted.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_user_repository_crud() {
+        let repo = TestUserRepository::new();
+
+        // Create
+        let input = CreateUserInput {
+            email: "test@example.com".to_string(),
+            display_name: Some("Test".to_string()),
+            avatar_url: None,
+        };
+        let user = repo.create("kc-123", &input).await.unwrap();
+        assert_eq!(user.email, "test@example.com");
+
+        // Read
+        let found = repo.find_by_id(user.id).await.unwrap();
+        assert!(found.is_some());
+
+        // Find by email
+        let by_email = repo.find_by_email("test@example.com").await.unwrap();
+        assert!(by_email.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_service_repository_crud() {
+        let repo = TestServiceRepository::new();
+
+        // Create
+        let input = CreateServiceInput {
+            tenant_id: Some(Uuid::new_v4()),
+            name: "Test Service".to_string(),
+            client_id: "test-client".to_string(),
+            base_url: Some("https://test.com".to_string()),
+            redirect_uris: vec!["https://test.com/cb".to_string()],
+            logout_uris: None,
+            role_preset: None,
+            role_preset_version: None,
+            catalog: Default::default(),
+        };
+        let service = repo.create(&input).await.unwrap();
+        assert_eq!(service.name, "Test Service");
+
+        // Create client
+        let client = repo
+            .create_client(
+                *service.id,
+                "client-1",
+                "hash",
+                Some("Client".to_string()),
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(client.client_id, "client-1");
+    }
+
+    #[tokio::test]
+    async fn test_rbac_repository_operations() {
+        let repo = TestRbacRepository::new();
+        let service_id = Uuid::new_v4();
+
+        // Create role
+        let role_input = CreateRoleInput {
+            service_id,
+            name: "admin".to_string(),
+            description: Some("Admin role".to_string()),
+            parent_role_id: None,
+            permission_ids: None,
+        };
+        let role = repo.create_role(&role_input).await.unwrap();
+        assert_eq!(role.name, "admin");
+
+        // Create permission
+        let perm_input = CreatePermissionInput {
+            service_id,
+            code: "user:read".to_string(),
+            name: "Read Users".to_string(),
+            description: Some("Read users".to_string()),
+        };
+        let perm = repo.create_permission(&perm_input).await.unwrap();
+        assert_eq!(perm.code, "user:read");
+
+        // Assign permission to role
+        repo.assign_permission_to_role(role.id, perm.id)
+            .await
+            .unwrap();
+
+        // Find role permissions
+        let perms = repo.find_role_permissions(role.id).await.unwrap();
+        assert_eq!(perms.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_services_builder() {
+        let builder = TestServicesBuilder::new();
+
+        // Add test data
+        let tenant = create_test_tenant(None);
+        builder.tenant_repo.add_tenant(tenant.clone()).await;
+
+        // Build service and verify
+        let tenant_service = builder.build_tenant_service();
+        let result = tenant_service.get(tenant.id).await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().name, "Test Tenant");
+    }
+}
+
+// =============================================================================
+// TestSamlApplicationRepository
+// =============================================================================
+
+use crate::models::saml_application::{SamlApplication, UpdateSamlApplicationInput};
+use crate::repository::saml_application::SamlApplicationRepository;
+
+pub struct TestSamlApplicationRepository {
+    apps: RwLock<Vec<SamlApplication>>,
+}
+
+impl TestSamlApplicationRepository {
+    pub fn new() -> Self {
+        Self {
+            apps: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestSamlApplicationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SamlApplicationRepository for TestSamlApplicationRepository {
+    async fn create(&self, app: &SamlApplication) -> Result<SamlApplication> {
+        let mut apps = self.apps.write().await;
+        apps.push(app.clone());
+        Ok(app.clone())
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<SamlApplication>> {
+        let apps = self.apps.read().await;
+        Ok(apps.iter().find(|a| a.id == id).cloned())
+    }
+
+    async fn find_by_tenant_and_entity_id(
+        &self,
+        tenant_id: StringUuid,
+        entity_id: &str,
+    ) -> Result<Option<SamlApplication>> {
+        let apps = self.apps.read().await;
+        Ok(apps
+            .iter()
+            .find(|a| a.tenant_id == tenant_id && a.entity_id == entity_id)
+            .cloned())
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<SamlApplication>> {
+        let apps = self.apps.read().await;
+        Ok(apps
+            .iter()
+            .filter(|a| a.tenant_id == tenant_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(
+        &self,
+        id: StringUuid,
+        input: &UpdateSamlApplicationInput,
+    ) -> Result<SamlApplication> {
+        let mut apps = self.apps.write().await;
+        let app = apps
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| AppError::NotFound("SAML application not found".to_string()))?;
+        if let Some(ref name) = input.name {
+            app.name = name.clone();
+        }
+        if let Some(ref acs_url) = input.acs_url {
+            app.acs_url = acs_url.clone();
+        }
+        if let Some(enabled) = input.enabled {
+            app.enabled = enabled;
+        }
+        Ok(app.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        let mut apps = self.apps.write().await;
+        let len_before = apps.len();
+        apps.retain(|a| a.id != id);
+        if apps.len() == len_before {
+            return Err(AppError::NotFound("SAML application not found".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn delete_by_tenant(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut apps = self.apps.write().await;
+        let len_before = apps.len();
+        apps.retain(|a| a.tenant_id != tenant_id);
+        Ok((len_before - apps.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test TenantRiskPolicyRepository
+// ============================================================================
+
+use crate::repository::tenant_risk_policy::{TenantRiskPolicyRepository, TenantRiskPolicyRow};
+
+pub struct TestTenantRiskPolicyRepository {
+    rows: RwLock<Vec<TenantRiskPolicyRow>>,
+}
+
+impl TestTenantRiskPolicyRepository {
+    pub fn new() -> Self {
+        Self {
+            rows: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestTenantRiskPolicyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TenantRiskPolicyRepository for TestTenantRiskPolicyRepository {
+    async fn find_by_tenant_id(
+        &self,
+        tenant_id: StringUuid,
+    ) -> Result<Option<TenantRiskPolicyRow>> {
+        let rows = self.rows.read().await;
+        Ok(rows.iter().find(|r| r.tenant_id == tenant_id).cloned())
+    }
+
+    async fn upsert(&self, row: &TenantRiskPolicyRow) -> Result<()> {
+        let mut rows = self.rows.write().await;
+        rows.retain(|r| r.tenant_id != row.tenant_id);
+        rows.push(row.clone());
+        Ok(())
+    }
+
+    async fn delete_by_tenant_id(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut rows = self.rows.write().await;
+        let len_before = rows.len();
+        rows.retain(|r| r.tenant_id != tenant_id);
+        Ok((len_before - rows.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test TrustedDeviceRepository
+// ============================================================================
+
+use crate::domains::identity::service::trusted_device::TrustedDevice;
+use crate::repository::trusted_device::TrustedDeviceRepository;
+
+pub struct TestTrustedDeviceRepository {
+    devices: RwLock<Vec<TrustedDevice>>,
+}
+
+impl TestTrustedDeviceRepository {
+    pub fn new() -> Self {
+        Self {
+            devices: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestTrustedDeviceRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TrustedDeviceRepository for TestTrustedDeviceRepository {
+    async fn find_by_user_and_fingerprint(
+        &self,
+        user_id: StringUuid,
+        fingerprint: &str,
+    ) -> Result<Option<TrustedDevice>> {
+        let devices = self.devices.read().await;
+        Ok(devices
+            .iter()
+            .find(|d| {
+                d.user_id == user_id
+                    && d.device_fingerprint == fingerprint
+                    && !d.revoked
+                    && d.expires_at > chrono::Utc::now()
+            })
+            .cloned())
+    }
+
+    async fn create(&self, device: &TrustedDevice) -> Result<()> {
+        self.devices.write().await.push(device.clone());
+        Ok(())
+    }
+
+    async fn list_by_user(&self, user_id: StringUuid) -> Result<Vec<TrustedDevice>> {
+        let devices = self.devices.read().await;
+        Ok(devices
+            .iter()
+            .filter(|d| d.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn revoke(&self, device_id: StringUuid) -> Result<()> {
+        let mut devices = self.devices.write().await;
+        if let Some(d) = devices.iter_mut().find(|d| d.id == device_id) {
+            d.revoked = true;
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_by_user(&self, user_id: StringUuid) -> Result<u64> {
+        let mut devices = self.devices.write().await;
+        let mut count = 0u64;
+        for d in devices
+            .iter_mut()
+            .filter(|d| d.user_id == user_id && !d.revoked)
+        {
+            d.revoked = true;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    async fn update_last_used(&self, device_id: StringUuid) -> Result<()> {
+        let mut devices = self.devices.write().await;
+        if let Some(d) = devices.iter_mut().find(|d| d.id == device_id) {
+            d.last_used_at = chrono::Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn delete_expired(&self) -> Result<u64> {
+        let mut devices = self.devices.write().await;
+        let len_before = devices.len();
+        devices.retain(|d| d.expires_at > chrono::Utc::now());
+        Ok((len_before - devices.len()) as u64)
+    }
+
+    async fn delete_by_user(&self, user_id: StringUuid) -> Result<u64> {
+        let mut devices = self.devices.write().await;
+        let len_before = devices.len();
+        devices.retain(|d| d.user_id != user_id);
+        Ok((len_before - devices.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test AdaptiveMfaPolicyRepository
+// ============================================================================
+
+use crate::repository::adaptive_mfa_policy::{AdaptiveMfaPolicyRepository, AdaptiveMfaPolicyRow};
+
+pub struct TestAdaptiveMfaPolicyRepository {
+    rows: RwLock<Vec<AdaptiveMfaPolicyRow>>,
+}
+
+impl TestAdaptiveMfaPolicyRepository {
+    pub fn new() -> Self {
+        Self {
+            rows: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestAdaptiveMfaPolicyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AdaptiveMfaPolicyRepository for TestAdaptiveMfaPolicyRepository {
+    async fn find_by_tenant_id(
+        &self,
+        tenant_id: StringUuid,
+    ) -> Result<Option<AdaptiveMfaPolicyRow>> {
+        let rows = self.rows.read().await;
+        Ok(rows.iter().find(|r| r.tenant_id == tenant_id).cloned())
+    }
+
+    async fn upsert(&self, row: &AdaptiveMfaPolicyRow) -> Result<()> {
+        let mut rows = self.rows.write().await;
+        rows.retain(|r| r.tenant_id != row.tenant_id);
+        rows.push(row.clone());
+        Ok(())
+    }
+
+    async fn delete_by_tenant_id(&self, tenant_id: StringUuid) -> Result<u64> {
+        let mut rows = self.rows.write().await;
+        let len_before = rows.len();
+        rows.retain(|r| r.tenant_id != tenant_id);
+        Ok((len_before - rows.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test AdminUnitRepository
+// ============================================================================
+
+use crate::models::admin_unit::{AdminUnit, CreateAdminUnitInput, UpdateAdminUnitInput};
+use crate::repository::admin_unit::AdminUnitRepository;
+
+/// Admin units kept in memory; tenant membership is read from the user repository
+pub struct TestAdminUnitRepository {
+    user_repo: Arc<TestUserRepository>,
+    units: RwLock<Vec<AdminUnit>>,
+    members: RwLock<Vec<(StringUuid, StringUuid)>>,
+    admins: RwLock<Vec<(StringUuid, StringUuid)>>,
+}
+
+impl TestAdminUnitRepository {
+    pub fn new(user_repo: Arc<TestUserRepository>) -> Self {
+        Self {
+            user_repo,
+            units: RwLock::new(vec![]),
+            members: RwLock::new(vec![]),
+            admins: RwLock::new(vec![]),
+        }
+    }
+}
+
+#[async_trait]
+impl AdminUnitRepository for TestAdminUnitRepository {
+    async fn create(
+        &self,
+        tenant_id: StringUuid,
+        input: &CreateAdminUnitInput,
+    ) -> Result<AdminUnit> {
+        let now = Utc::now();
+        let unit = AdminUnit {
+            id: StringUuid::new_v4(),
+            tenant_id,
+            name: input.name.clone(),
+            description: input.description.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.units.write().await.push(unit.clone());
+        Ok(unit)
+    }
+
+    async fn find_by_id(&self, id: StringUuid) -> Result<Option<AdminUnit>> {
+        let units = self.units.read().await;
+        Ok(units.iter().find(|u| u.id == id).cloned())
+    }
+
+    async fn find_by_name(&self, tenant_id: StringUuid, name: &str) -> Result<Option<AdminUnit>> {
+        let units = self.units.read().await;
+        Ok(units
+            .iter()
+            .find(|u| u.tenant_id == tenant_id && u.name == name)
+            .cloned())
+    }
+
+    async fn list_by_tenant(&self, tenant_id: StringUuid) -> Result<Vec<AdminUnit>> {
+        let units = self.units.read().await;
+        Ok(units
+            .iter()
+            .filter(|u| u.tenant_id == tenant_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, id: StringUuid, input: &UpdateAdminUnitInput) -> Result<AdminUnit> {
+        let mut units = self.units.write().await;
+        let unit = units
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| AppError::NotFound("Admin unit not found".to_string()))?;
+        if let Some(ref name) = input.name {
+            unit.name = name.clone();
+        }
+        if input.description.is_some() {
+            unit.description = input.description.clone();
+        }
+        unit.updated_at = Utc::now();
+        Ok(unit.clone())
+    }
+
+    async fn delete(&self, id: StringUuid) -> Result<()> {
+        self.units.write().await.retain(|u| u.id != id);
+        self.members.write().await.retain(|(unit, _)| *unit != id);
+        self.admins.write().await.retain(|(unit, _)| *unit != id);
+        Ok(())
+    }
+
+    async fn list_members(&self, id: StringUuid) -> Result<Vec<StringUuid>> {
+        let members = self.members.read().await;
+        Ok(members
+            .iter()
+            .filter(|(unit, _)| *unit == id)
+            .map(|(_, user)| *user)
+            .collect())
+    }
+
+    async fn add_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        let mut members = self.members.write().await;
+        if !members.contains(&(id, user_id)) {
+            members.push((id, user_id));
+        }
+        Ok(())
+    }
+
+    async fn remove_member(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        self.members.write().await.retain(|m| *m != (id, user_id));
+        Ok(())
+    }
+
+    async fn list_admins(&self, id: StringUuid) -> Result<Vec<StringUuid>> {
+        let admins = self.admins.read().await;
+        Ok(admins
+            .iter()
+            .filter(|(unit, _)| *unit == id)
+            .map(|(_, user)| *user)
+            .collect())
+    }
+
+    async fn add_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        let mut admins = self.admins.write().await;
+        if !admins.contains(&(id, user_id)) {
+            admins.push((id, user_id));
+        }
+        Ok(())
+    }
+
+    async fn remove_admin(&self, id: StringUuid, user_id: StringUuid) -> Result<()> {
+        self.admins.write().await.retain(|a| *a != (id, user_id));
+        Ok(())
+    }
+
+    async fn is_tenant_member(&self, tenant_id: StringUuid, user_id: StringUuid) -> Result<bool> {
+        let tenant_users = self.user_repo.tenant_users.read().await;
+        Ok(tenant_users
+            .iter()
+            .any(|tu| tu.tenant_id == tenant_id && tu.user_id == user_id))
+    }
+
+    async fn administered_unit_ids(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+    ) -> Result<Vec<StringUuid>> {
+        let units = self.units.read().await;
+        let admins = self.admins.read().await;
+        Ok(units
+            .iter()
+            .filter(|u| u.tenant_id == tenant_id && admins.contains(&(u.id, admin_id)))
+            .map(|u| u.id)
+            .collect())
+    }
+
+    async fn manages_member(
+        &self,
+        tenant_id: StringUuid,
+        admin_id: StringUuid,
+        user_id: StringUuid,
+    ) -> Result<bool> {
+        if !self.is_tenant_member(tenant_id, user_id).await? {
+            return Ok(false);
+        }
+        let members = self.members.read().await;
+        Ok(self
+            .administered_unit_ids(tenant_id, admin_id)
+            .await?
+            .iter()
+            .any(|unit| members.contains(&(*unit, user_id))))
+    }
+}
+
+// ============================================================================
+// Test LoginIdentifierRepository
+// ============================================================================
+
+use crate::models::login_identifier::{LoginIdentifier, LoginIdentifierKind, GLOBAL_SCOPE};
+use crate::repository::LoginIdentifierRepository;
+
+/// Usernames and phone numbers kept in memory
+pub struct TestLoginIdentifierRepository {
+    identifiers: RwLock<Vec<LoginIdentifier>>,
+}
+
+impl TestLoginIdentifierRepository {
+    pub fn new() -> Self {
+        Self {
+            identifiers: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestLoginIdentifierRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LoginIdentifierRepository for TestLoginIdentifierRepository {
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<LoginIdentifier>> {
+        let identifiers = self.identifiers.read().await;
+        Ok(identifiers
+            .iter()
+            .filter(|i| i.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_value(
+        &self,
+        kind: LoginIdentifierKind,
+        value: &str,
+        tenant_id: Option<StringUuid>,
+    ) -> Result<Vec<LoginIdentifier>> {
+        let identifiers = self.identifiers.read().await;
+        Ok(identifiers
+            .iter()
+            .filter(|i| i.kind == kind && i.value == value)
+            .filter(|i| match tenant_id {
+                Some(tenant_id) => i.scope == GLOBAL_SCOPE || i.scope == tenant_id.to_string(),
+                None => true,
+            })
+            .take(2)
+            .cloned()
+            .collect())
+    }
+
+    async fn assign(&self, identifier: &LoginIdentifier) -> Result<()> {
+        let mut identifiers = self.identifiers.write().await;
+        let taken = identifiers.iter().any(|i| {
+            i.user_id != identifier.user_id
+                && i.kind == identifier.kind
+                && i.value == identifier.value
+                && i.scope == identifier.scope
+        });
+        if taken {
+            return Err(AppError::Conflict(format!(
+                "This {} is already in use",
+                identifier.kind
+            )));
+        }
+        identifiers.retain(|i| !(i.user_id == identifier.user_id && i.kind == identifier.kind));
+        identifiers.push(identifier.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, user_id: StringUuid, kind: LoginIdentifierKind) -> Result<bool> {
+        let mut identifiers = self.identifiers.write().await;
+        let len_before = identifiers.len();
+        identifiers.retain(|i| !(i.user_id == user_id && i.kind == kind));
+        Ok(identifiers.len() < len_before)
+    }
+}
+
+// ============================================================================
+// Test PasswordBreachCheckRepository
+// ============================================================================
+
+use crate::models::password_breach::{
+    PasswordBreachCheck, PasswordBreachSource, PasswordBreachStatus,
+};
+use crate::repository::PasswordBreachCheckRepository;
+
+/// Breach campaign checks kept in memory, one per job and account
+pub struct TestPasswordBreachCheckRepository {
+    checks: RwLock<Vec<PasswordBreachCheck>>,
+}
+
+impl TestPasswordBreachCheckRepository {
+    pub fn new() -> Self {
+        Self {
+            checks: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestPasswordBreachCheckRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PasswordBreachCheckRepository for TestPasswordBreachCheckRepository {
+    async fn upsert(&self, check: &PasswordBreachCheck) -> Result<()> {
+        let mut checks = self.checks.write().await;
+        match checks
+            .iter_mut()
+            .find(|c| c.job_id == check.job_id && c.user_id == check.user_id)
+        {
+            Some(existing) => {
+                existing.status = check.status.clone();
+                existing.source = check.source.clone();
+                existing.breach_count = check.breach_count;
+                existing.action_taken = check.action_taken.clone();
+                existing.checked_at = check.checked_at;
+            }
+            None => checks.push(check.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list_by_job(&self, job_id: StringUuid) -> Result<Vec<PasswordBreachCheck>> {
+        let checks = self.checks.read().await;
+        let mut found: Vec<_> = checks
+            .iter()
+            .filter(|c| c.job_id == job_id)
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| (a.created_at, &a.email).cmp(&(b.created_at, &b.email)));
+        Ok(found)
+    }
+
+    async fn has_pending(&self, user_id: StringUuid) -> Result<bool> {
+        let checks = self.checks.read().await;
+        Ok(checks
+            .iter()
+            .any(|c| c.user_id == user_id && c.status == PasswordBreachStatus::Pending.as_str()))
+    }
+
+    async fn resolve_pending(
+        &self,
+        user_id: StringUuid,
+        status: PasswordBreachStatus,
+        breach_count: u64,
+    ) -> Result<u64> {
+        let mut checks = self.checks.write().await;
+        let mut resolved = 0;
+        for check in checks
+            .iter_mut()
+            .filter(|c| c.user_id == user_id && c.status == PasswordBreachStatus::Pending.as_str())
+        {
+            check.status = status.as_str().to_string();
+            check.source = PasswordBreachSource::Login.as_str().to_string();
+            check.breach_count = i64::try_from(breach_count).unwrap_or(i64::MAX);
+            check.checked_at = Some(Utc::now());
+            resolved += 1;
+        }
+        Ok(resolved)
+    }
+}
+
+// ============================================================================
+// Test AccountDeletionRepository
+// ============================================================================
+
+use crate::models::account_deletion::{AccountDeletionNotice, AccountDeletionRequest};
+use crate::repository::AccountDeletionRepository;
+
+/// Account deletion requests kept in memory; reminder recipients are read
+/// from the user repository
+pub struct TestAccountDeletionRepository {
+    user_repo: Arc<TestUserRepository>,
+    requests: RwLock<Vec<AccountDeletionRequest>>,
+}
+
+impl TestAccountDeletionRepository {
+    pub fn new(user_repo: Arc<TestUserRepository>) -> Self {
+        Self {
+            user_repo,
+            requests: RwLock::new(vec![]),
+        }
+    }
+}
+
+#[async_trait]
+impl AccountDeletionRepository for TestAccountDeletionRepository {
+    async fn create(&self, request: &AccountDeletionRequest) -> Result<()> {
+        let mut requests = self.requests.write().await;
+        if requests.iter().any(|r| r.user_id == request.user_id) {
+            return Err(AppError::Conflict(
+                "Account deletion is already scheduled".to_string(),
+            ));
+        }
+        requests.push(request.clone());
+        Ok(())
+    }
+
+    async fn find(&self, user_id: StringUuid) -> Result<Option<AccountDeletionRequest>> {
+        let requests = self.requests.read().await;
+        Ok(requests.iter().find(|r| r.user_id == user_id).cloned())
+    }
+
+    async fn delete(&self, user_id: StringUuid) -> Result<bool> {
+        let mut requests = self.requests.write().await;
+        let len_before = requests.len();
+        requests.retain(|r| r.user_id != user_id);
+        Ok(requests.len() < len_before)
+    }
+
+    async fn list_due_reminders(
+        &self,
+        purge_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionNotice>> {
+        let requests = self.requests.read().await;
+        let users = self.user_repo.users.read().await;
+        let mut due: Vec<_> = requests
+            .iter()
+            .filter(|r| {
+                r.purge_after <= purge_before
+                    && r.reminder_sent_at.is_none()
+                    && r.purge_job_id.is_none()
+            })
+            .filter_map(|r| {
+                let user = users.iter().find(|u| u.id == r.user_id)?;
+                Some(AccountDeletionNotice {
+                    user_id: r.user_id,
+                    email: user.email.clone(),
+                    display_name: user.display_name.clone(),
+                    locale: user.locale.clone(),
+                    purge_after: r.purge_after,
+                })
+            })
+            .collect();
+        due.sort_by_key(|n| n.purge_after);
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+
+    async fn mark_reminded(&self, user_id: StringUuid, at: DateTime<Utc>) -> Result<()> {
+        let mut requests = self.requests.write().await;
+        if let Some(request) = requests.iter_mut().find(|r| r.user_id == user_id) {
+            request.reminder_sent_at = Some(at);
+        }
+        Ok(())
+    }
+
+    async fn list_due_purges(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<AccountDeletionRequest>> {
+        let requests = self.requests.read().await;
+        let mut due: Vec<_> = requests
+            .iter()
+            .filter(|r| r.purge_after <= now && r.purge_job_id.is_none())
+            .cloned()
+            .collect();
+        due.sort_by_key(|r| r.purge_after);
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+
+    async fn set_purge_job(&self, user_id: StringUuid, job_id: StringUuid) -> Result<()> {
+        let mut requests = self.requests.write().await;
+        if let Some(request) = requests.iter_mut().find(|r| r.user_id == user_id) {
+            request.purge_job_id = Some(job_id);
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Test PasswordDenyListRepository
+// ============================================================================
+
+use crate::models::password_deny_list::TenantPasswordDenyList;
+use crate::repository::PasswordDenyListRepository;
+
+/// Tenant deny lists kept in memory; tenant membership is read from the user
+/// repository
+pub struct TestPasswordDenyListRepository {
+    user_repo: Arc<TestUserRepository>,
+    lists: RwLock<Vec<TenantPasswordDenyList>>,
+}
+
+impl TestPasswordDenyListRepository {
+    pub fn new(user_repo: Arc<TestUserRepository>) -> Self {
+        Self {
+            user_repo,
+            lists: RwLock::new(vec![]),
+        }
+    }
+}
+
+#[async_trait]
+impl PasswordDenyListRepository for TestPasswordDenyListRepository {
+    async fn find(&self, tenant_id: StringUuid) -> Result<Option<TenantPasswordDenyList>> {
+        let lists = self.lists.read().await;
+        Ok(lists.iter().find(|l| l.tenant_id == tenant_id).cloned())
+    }
+
+    async fn list_for_user(&self, user_id: StringUuid) -> Result<Vec<TenantPasswordDenyList>> {
+        let lists = self.lists.read().await;
+        let tenant_users = self.user_repo.tenant_users.read().await;
+        Ok(lists
+            .iter()
+            .filter(|l| {
+                tenant_users
+                    .iter()
+                    .any(|tu| tu.user_id == user_id && tu.tenant_id == l.tenant_id)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert(&self, list: &TenantPasswordDenyList) -> Result<()> {
+        let mut lists = self.lists.write().await;
+        lists.retain(|l| l.tenant_id != list.tenant_id);
+        lists.push(list.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, tenant_id: StringUuid) -> Result<bool> {
+        let mut lists = self.lists.write().await;
+        let len_before = lists.len();
+        lists.retain(|l| l.tenant_id != tenant_id);
+        Ok(lists.len() < len_before)
+    }
+}
+
+// ============================================================================
+// Test LoginForensicsRepository
+// ============================================================================
+
+use crate::models::login_forensics::LoginFailureRecord;
+use crate::repository::login_forensics::{LoginFailureFilter, LoginForensicsRepository};
+
+/// Failed sign-in captures kept in memory
+pub struct TestLoginForensicsRepository {
+    records: RwLock<Vec<LoginFailureRecord>>,
+}
+
+impl TestLoginForensicsRepository {
+    pub fn new() -> Self {
+        Self {
+            records: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestLoginForensicsRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LoginForensicsRepository for TestLoginForensicsRepository {
+    async fn create(&self, record: &LoginFailureRecord) -> Result<()> {
+        self.records.write().await.push(record.clone());
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        filter: &LoginFailureFilter,
+        limit: i64,
+    ) -> Result<Vec<LoginFailureRecord>> {
+        let records = self.records.read().await;
+        let mut found: Vec<_> = records
+            .iter()
+            .filter(|r| {
+                filter
+                    .ip_address
+                    .as_ref()
+                    .is_none_or(|ip| r.ip_address.as_ref() == Some(ip))
+                    && filter.user_id.is_none_or(|id| r.user_id == Some(id))
+                    && filter
+                        .identifier_hash
+                        .as_ref()
+                        .is_none_or(|hash| r.identifier_hash.as_ref() == Some(hash))
+                    && filter.since.is_none_or(|since| r.created_at >= since)
+            })
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        found.truncate(limit as usize);
+        Ok(found)
+    }
+
+    async fn delete_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let mut records = self.records.write().await;
+        let len_before = records.len();
+        records.retain(|r| r.created_at >= before);
+        Ok((len_before - records.len()) as u64)
+    }
+}
+
+// ============================================================================
+// Test ServiceScopeRepository
+// ============================================================================
+
+use crate::models::oauth_scope::ServiceScope;
+use crate::repository::ServiceScopeRepository;
+
+/// Per-service scope declarations kept in memory
+pub struct TestServiceScopeRepository {
+    scopes: RwLock<Vec<ServiceScope>>,
+}
+
+impl TestServiceScopeRepository {
+    pub fn new() -> Self {
+        Self {
+            scopes: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl Default for TestServiceScopeRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ServiceScopeRepository for TestServiceScopeRepository {
+    async fn list_by_service(&self, service_id: StringUuid) -> Result<Vec<ServiceScope>> {
+        let scopes = self.scopes.read().await;
+        let mut found: Vec<_> = scopes
+            .iter()
+            .filter(|s| s.service_id == service_id)
+            .cloned()
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(found)
+    }
+
+    async fn upsert(
+        &self,
+        service_id: StringUuid,
+        name: &str,
+        description: &str,
+        required_permissions: &[String],
+    ) -> Result<ServiceScope> {
+        let mut scopes = self.scopes.write().await;
+        let now = Utc::now();
+        if let Some(existing) = scopes
+            .iter_mut()
+            .find(|s| s.service_id == service_id && s.name == name)
+        {
+            existing.description = description.to_string();
+            existing.required_permissions = required_permissions.to_vec();
+            existing.updated_at = now;
+            return Ok(existing.clone());
+        }
+        let scope = ServiceScope {
+            id: StringUuid::new_v4(),
+            service_id,
+            name: name.to_string(),
+            description: description.to_string(),
+            required_permissions: required_permissions.to_vec(),
+            created_at: now,
+            updated_at: now,
+        };
+        scopes.push(scope.clone());
+        Ok(scope)
+    }
+
+    async fn delete(&self, service_id: StringUuid, name: &str) -> Result<()> {
+        let mut scopes = self.scopes.write().await;
+        let len_before = scopes.len();
+        scopes.retain(|s| !(s.service_id == service_id && s.name == name));
+        if scopes.len() == len_before {
+            return Err(AppError::NotFound(format!(
+                "Scope '{}' is not declared by this service",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
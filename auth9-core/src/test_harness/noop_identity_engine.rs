@@ -3,19 +3,19 @@
 //! Used in HTTP handler tests where the identity engine is wired into
 //! `AppState` but never actually called.
 
-use async_trait::async_trait;
-use auth9_core::error::Result;
-use auth9_core::identity_engine::{
+use crate::error::Result;
+use crate::identity_engine::{
     FederationBroker, IdentityActionStore, IdentityClientStore, IdentityCredentialStore,
     IdentityEngine, IdentityEventSource, IdentitySessionStore, IdentityUserStore,
     IdentityVerificationStore,
 };
-use auth9_core::identity_engine::{
+use crate::identity_engine::{
     IdentityCredentialRepresentation, IdentityProviderRepresentation,
     IdentitySamlClientRepresentation, IdentityUserCreateInput, IdentityUserRepresentation,
     IdentityUserUpdateInput, OidcClientRepresentation, PendingActionInfo, RealmSettingsUpdate,
     VerificationTokenInfo,
 };
+use async_trait::async_trait;
 use std::collections::HashMap;
 
 // ============================================================================
@@ -0,0 +1,242 @@
+//! Webhook signature verification for receivers
+//!
+//! Auth9 signs every webhook delivery with the webhook's secret:
+//!
+//! - `X-Webhook-Signature: sha256=<hex HMAC-SHA256 of the raw request body>`
+//! - `X-Webhook-Timestamp: <RFC 3339 time the event was created>`
+//! - `X-Webhook-Event: <event type>`
+//!
+//! The body itself carries the same `timestamp`, so it is covered by the
+//! signature. Receivers should:
+//!
+//! 1. Verify the signature over the raw body bytes (before any JSON parsing)
+//!    using a constant-time comparison.
+//! 2. Reject deliveries whose timestamp is outside a small tolerance window
+//!    ([`DEFAULT_TOLERANCE`], 5 minutes) to stop captured requests from being
+//!    replayed later.
+//! 3. Treat deliveries as at-least-once: Auth9 retries failed deliveries, so
+//!    deduplicate on the event contents if processing is not idempotent.
+//!
+//! [`verify_signature`] performs the first two steps and returns the parsed
+//! event. Use `POST /api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/ping`
+//! to send a signed sample delivery to a receiver under test.
+
+use crate::models::analytics::WebhookEvent;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// Maximum accepted clock difference between the event and the receiver
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Why a webhook delivery failed verification
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SignatureError {
+    #[error("signature header is malformed")]
+    MalformedSignature,
+    #[error("signature does not match payload")]
+    SignatureMismatch,
+    #[error("timestamp header is malformed")]
+    MalformedTimestamp,
+    #[error("timestamp is outside the tolerance window")]
+    TimestampOutOfTolerance,
+    #[error("payload is not a webhook event")]
+    MalformedPayload,
+    #[error("timestamp header does not match the signed payload")]
+    TimestampMismatch,
+}
+
+/// Compute the `X-Webhook-Signature` value for a payload
+pub fn sign(payload: &[u8], secret: &str) -> String {
+    let mut mac = new_mac(secret);
+    mac.update(payload);
+    format!(
+        "{}{}",
+        SIGNATURE_PREFIX,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Verify a webhook delivery and return the event it carries.
+///
+/// `payload` must be the raw request body. `signature` and `timestamp` are the
+/// `X-Webhook-Signature` and `X-Webhook-Timestamp` header values.
+pub fn verify_signature(
+    payload: &[u8],
+    signature: &str,
+    timestamp: &str,
+    secret: &str,
+    tolerance: Duration,
+) -> Result<WebhookEvent, SignatureError> {
+    verify_signature_at(payload, signature, timestamp, secret, tolerance, Utc::now())
+}
+
+fn verify_signature_at(
+    payload: &[u8],
+    signature: &str,
+    timestamp: &str,
+    secret: &str,
+    tolerance: Duration,
+    now: DateTime<Utc>,
+) -> Result<WebhookEvent, SignatureError> {
+    let expected = signature
+        .trim()
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(|hex_sig| hex::decode(hex_sig).ok())
+        .ok_or(SignatureError::MalformedSignature)?;
+    let mut mac = new_mac(secret);
+    mac.update(payload);
+    mac.verify_slice(&expected)
+        .map_err(|_| SignatureError::SignatureMismatch)?;
+
+    let sent_at = DateTime::parse_from_rfc3339(timestamp.trim())
+        .map_err(|_| SignatureError::MalformedTimestamp)?
+        .with_timezone(&Utc);
+    let skew = (now - sent_at).abs().to_std().unwrap_or(Duration::MAX);
+    if skew > tolerance {
+        return Err(SignatureError::TimestampOutOfTolerance);
+    }
+
+    // The header is not signed; the body's timestamp is.
+    let event: WebhookEvent =
+        serde_json::from_slice(payload).map_err(|_| SignatureError::MalformedPayload)?;
+    if event.timestamp != sent_at {
+        return Err(SignatureError::TimestampMismatch);
+    }
+    Ok(event)
+}
+
+fn new_mac(secret: &str) -> HmacSha256 {
+    // HMAC accepts keys of any length
+    HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_event(secret: &str, at: DateTime<Utc>) -> (Vec<u8>, String, String) {
+        let event = WebhookEvent {
+            event_type: "user.created".to_string(),
+            timestamp: at,
+            data: serde_json::json!({ "user_id": "u1" }),
+        };
+        let payload = serde_json::to_vec(&event).unwrap();
+        let signature = sign(&payload, secret);
+        (payload, signature, at.to_rfc3339())
+    }
+
+    #[test]
+    fn test_verify_valid_delivery() {
+        let now = Utc::now();
+        let (payload, signature, timestamp) = signed_event("s3cret", now);
+
+        let event = verify_signature_at(
+            &payload,
+            &signature,
+            &timestamp,
+            "s3cret",
+            DEFAULT_TOLERANCE,
+            now,
+        )
+        .unwrap();
+        assert_eq!(event.event_type, "user.created");
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_secret_and_tampering() {
+        let now = Utc::now();
+        let (payload, signature, timestamp) = signed_event("s3cret", now);
+
+        assert_eq!(
+            verify_signature_at(
+                &payload,
+                &signature,
+                &timestamp,
+                "other",
+                DEFAULT_TOLERANCE,
+                now
+            )
+            .unwrap_err(),
+            SignatureError::SignatureMismatch
+        );
+
+        let mut tampered = payload.clone();
+        tampered.extend_from_slice(b" ");
+        assert_eq!(
+            verify_signature_at(
+                &tampered,
+                &signature,
+                &timestamp,
+                "s3cret",
+                DEFAULT_TOLERANCE,
+                now
+            )
+            .unwrap_err(),
+            SignatureError::SignatureMismatch
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_signature() {
+        let now = Utc::now();
+        let (payload, _, timestamp) = signed_event("s3cret", now);
+
+        for bad in ["", "abcdef", "sha256=not-hex", "sha1=abcd"] {
+            assert_eq!(
+                verify_signature_at(&payload, bad, &timestamp, "s3cret", DEFAULT_TOLERANCE, now)
+                    .unwrap_err(),
+                SignatureError::MalformedSignature
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_replayed_delivery() {
+        let sent = Utc::now() - chrono::Duration::minutes(10);
+        let (payload, signature, timestamp) = signed_event("s3cret", sent);
+
+        assert_eq!(
+            verify_signature_at(
+                &payload,
+                &signature,
+                &timestamp,
+                "s3cret",
+                DEFAULT_TOLERANCE,
+                Utc::now()
+            )
+            .unwrap_err(),
+            SignatureError::TimestampOutOfTolerance
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_forged_timestamp_header() {
+        // A replayed body with a fresh, unsigned timestamp header is rejected
+        let sent = Utc::now() - chrono::Duration::minutes(10);
+        let (payload, signature, _) = signed_event("s3cret", sent);
+        let now = Utc::now();
+
+        assert_eq!(
+            verify_signature_at(
+                &payload,
+                &signature,
+                &now.to_rfc3339(),
+                "s3cret",
+                DEFAULT_TOLERANCE,
+                now
+            )
+            .unwrap_err(),
+            SignatureError::TimestampMismatch
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! This crate tests the API handlers using mock repositories.
 //! No external dependencies (database, Redis, etc.) are required.
 
+mod contract;
 mod domains;
 mod grpc;
 mod support;
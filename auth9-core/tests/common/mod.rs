@@ -4,9 +4,8 @@
 //! For unit tests, use mock repositories from the service layer.
 
 use auth9_core::config::{
-    Config, CorsConfig, DatabaseConfig, GrpcSecurityConfig, JwtConfig,
-    RateLimitConfig, RedisConfig, SecurityHeadersConfig, ServerConfig,
-    TelemetryConfig, WebAuthnConfig,
+    Config, CorsConfig, DatabaseConfig, GrpcSecurityConfig, JwtConfig, RateLimitConfig,
+    RedisConfig, SecurityHeadersConfig, ServerConfig, TelemetryConfig, WebAuthnConfig,
 };
 
 /// Test configuration (no real connections needed)
@@ -35,6 +34,8 @@ pub fn test_config() -> Config {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         },
         core_public_url: None,
         portal_url: None,
@@ -51,6 +52,7 @@ pub fn test_config() -> Config {
         server: ServerConfig::default(),
         telemetry: TelemetryConfig::default(),
         platform_admin_emails: vec!["admin@auth9.local".to_string()],
+        platform_admin_scopes_enforced: false,
         jwt_tenant_access_allowed_audiences: vec![],
         security_headers: SecurityHeadersConfig::default(),
         portal_client_id: None,
@@ -67,6 +69,32 @@ pub fn test_config() -> Config {
         async_action: auth9_core::models::action::AsyncActionConfig::default(),
         branding_allowed_domains: vec![],
         admin_password: None,
+        custom_domain: auth9_core::config::CustomDomainConfig::default(),
+        dependency_policy: auth9_core::config::DependencyPolicyConfig::default(),
+        jobs: auth9_core::config::JobWorkerConfig::default(),
+        event_partitions: auth9_core::config::EventPartitionConfig::default(),
+        synthetic_check: auth9_core::config::SyntheticCheckConfig::default(),
+        session_activity: auth9_core::config::SessionActivityConfig::default(),
+        email_queue: auth9_core::config::EmailQueueConfig::default(),
+        billing: auth9_core::config::BillingConfig::default(),
+        allow_weak_secrets: false,
+        data_residency: auth9_core::config::DataResidencyConfig::default(),
+        replay_protection: auth9_core::config::ReplayProtectionConfig::default(),
+        ldap_sync: auth9_core::config::LdapSyncConfig::default(),
+        audit_integrity: auth9_core::config::AuditIntegrityConfig::default(),
+        cardinality: auth9_core::config::CardinalityConfig::default(),
+        migration_safety: auth9_core::config::MigrationSafetyConfig::default(),
+        response_masking: auth9_core::config::ResponseMaskingConfig::default(),
+        startup: auth9_core::config::StartupConfig::default(),
+        oauth: auth9_core::config::OAuthConfig::default(),
+        token_validation: auth9_core::config::TokenValidationConfig::default(),
+        account_deletion: auth9_core::config::AccountDeletionConfig::default(),
+        login_forensics: auth9_core::config::LoginForensicsConfig::default(),
+        revocation_feed: auth9_core::config::RevocationFeedConfig::default(),
+        permission_replication: auth9_core::config::PermissionReplicationConfig::default(),
+        settings_encryption: auth9_core::config::SettingsEncryptionConfig::default(),
+        flow_state: auth9_core::config::FlowStateConfig::default(),
+        backup: auth9_core::config::BackupConfig::default(),
     }
 }
 
@@ -80,5 +108,7 @@ pub fn test_jwt_config() -> JwtConfig {
         private_key_pem: None,
         public_key_pem: None,
         previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
     }
 }
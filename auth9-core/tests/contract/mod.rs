@@ -0,0 +1,327 @@
+//! OpenAPI contract tests
+//!
+//! Replays the recorded request/response fixtures in `tests/contract/fixtures`
+//! against the production router and checks every response against the
+//! published OpenAPI document: the operation must be documented, the status
+//! must be one of its documented responses, and a JSON body must match the
+//! documented schema. A fixture also pins the recorded status and, optionally,
+//! a subset of the recorded body.
+//!
+//! Fixtures are added one endpoint at a time; each file is a scenario whose
+//! steps run in order against a fresh in-memory app. A step can `capture`
+//! values from its response (JSON pointer) for later steps to use as
+//! `{{name}}` in paths, bodies and `auth`. `auth` is `platform_admin`,
+//! `tenant_admin` or `tenant_admin:<tenant id>`.
+
+mod schema;
+
+use crate::support::http::{build_test_router, TestAppState};
+use crate::support::{
+    create_test_identity_token, create_test_tenant_access_token,
+    create_test_tenant_access_token_for_tenant,
+};
+use auth9_core::openapi::ApiDoc;
+use axum::body::Body;
+use axum::http::{Method, Request};
+use axum::Router;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/contract/fixtures");
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Step {
+    name: String,
+    request: RecordedRequest,
+    response: RecordedResponse,
+    #[serde(default)]
+    capture: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default)]
+    auth: Option<String>,
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    /// Fields the response body must contain with these values
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+fn fixture_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(FIXTURE_DIR)
+        .expect("contract fixture directory should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn substitute(text: &str, vars: &HashMap<String, String>) -> String {
+    vars.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+fn substitute_value(value: &Value, vars: &HashMap<String, String>) -> Value {
+    serde_json::from_str(&substitute(&value.to_string(), vars))
+        .expect("substituted fixture value should stay valid JSON")
+}
+
+fn bearer_token(auth: &str) -> String {
+    match auth.split_once(':') {
+        Some(("tenant_admin", tenant_id)) => create_test_tenant_access_token_for_tenant(
+            Uuid::parse_str(tenant_id).expect("tenant_admin needs a tenant UUID"),
+        ),
+        _ if auth == "tenant_admin" => create_test_tenant_access_token(),
+        _ if auth == "platform_admin" => create_test_identity_token(),
+        _ => panic!("Unknown fixture auth: {}", auth),
+    }
+}
+
+async fn send(
+    app: &Router,
+    request: &RecordedRequest,
+    vars: &HashMap<String, String>,
+) -> (u16, Value) {
+    let method = Method::from_bytes(request.method.as_bytes()).expect("valid HTTP method");
+    let mut builder = Request::builder()
+        .method(method)
+        .uri(substitute(&request.path, vars));
+    if let Some(auth) = &request.auth {
+        builder = builder.header(
+            "Authorization",
+            format!("Bearer {}", bearer_token(&substitute(auth, vars))),
+        );
+    }
+    let body = match &request.body {
+        Some(body) => {
+            builder = builder.header("Content-Type", "application/json");
+            Body::from(substitute_value(body, vars).to_string())
+        }
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap_or_default();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+/// Documented path template of an operation for a concrete request path,
+/// preferring the template with the most literal segments
+/// (`/tenants/by-slug/{slug}` over `/tenants/{id}/{x}`)
+fn match_path_template<'a>(doc: &'a Value, method: &str, path: &str) -> Option<&'a str> {
+    let path = path.split('?').next().unwrap_or(path);
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let method = method.to_lowercase();
+    doc["paths"]
+        .as_object()?
+        .iter()
+        .filter(|(_, item)| item.get(&method).is_some())
+        .filter_map(|(template, _)| {
+            let parts: Vec<&str> = template.trim_matches('/').split('/').collect();
+            if parts.len() != segments.len() {
+                return None;
+            }
+            let mut literals = 0;
+            for (part, segment) in parts.iter().zip(&segments) {
+                if part.starts_with('{') && part.ends_with('}') {
+                    continue;
+                }
+                if part != segment {
+                    return None;
+                }
+                literals += 1;
+            }
+            Some((literals, template.as_str()))
+        })
+        .max_by_key(|(literals, _)| *literals)
+        .map(|(_, template)| template)
+}
+
+/// Whether `expected` is contained in `actual`: objects by key, arrays
+/// element-wise, scalars by equality
+fn is_subset(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| is_subset(value, a))),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected.iter().zip(actual).all(|(e, a)| is_subset(e, a))
+        }
+        _ => expected == actual,
+    }
+}
+
+/// Check one replayed step against its fixture and the OpenAPI document
+fn check_step(
+    doc: &Value,
+    step: &Step,
+    method: &str,
+    path: &str,
+    status: u16,
+    body: &Value,
+    vars: &HashMap<String, String>,
+) -> Result<String, String> {
+    let template = match_path_template(doc, method, path)
+        .ok_or_else(|| format!("{} {} is not in the OpenAPI document", method, path))?;
+    let operation_id = format!("{} {}", method, template);
+    let operation = &doc["paths"][template][method.to_lowercase()];
+
+    if status != step.response.status {
+        return Err(format!(
+            "{}: recorded status {} but got {} ({})",
+            operation_id, step.response.status, status, body
+        ));
+    }
+    let responses = &operation["responses"];
+    let response = responses
+        .get(status.to_string())
+        .or_else(|| responses.get("default"))
+        .ok_or_else(|| {
+            let documented: Vec<&String> = responses
+                .as_object()
+                .map(|r| r.keys().collect())
+                .unwrap_or_default();
+            format!(
+                "{}: status {} is not documented (documented: {:?})",
+                operation_id, status, documented
+            )
+        })?;
+
+    if let Some(schema) = response.pointer("/content/application~1json/schema") {
+        let errors = schema::validate_response(doc, schema, body);
+        if !errors.is_empty() {
+            return Err(format!(
+                "{}: response does not match the documented schema:\n    {}",
+                operation_id,
+                errors.join("\n    ")
+            ));
+        }
+    }
+
+    if let Some(expected) = &step.response.body {
+        if !is_subset(&substitute_value(expected, vars), body) {
+            return Err(format!(
+                "{}: response differs from the recorded body\n    recorded: {}\n    actual: {}",
+                operation_id, expected, body
+            ));
+        }
+    }
+    Ok(operation_id)
+}
+
+async fn replay(doc: &Value, file: &Path, covered: &mut BTreeSet<String>) -> Vec<String> {
+    let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(file).unwrap())
+        .unwrap_or_else(|e| panic!("{}: invalid fixture: {}", file.display(), e));
+    let app = build_test_router(TestAppState::new());
+    let name = file.file_name().unwrap().to_string_lossy().to_string();
+
+    let mut vars = HashMap::new();
+    let mut failures = Vec::new();
+    for step in &fixture.steps {
+        let (status, body) = send(&app, &step.request, &vars).await;
+        let path = substitute(&step.request.path, &vars);
+        match check_step(doc, step, &step.request.method, &path, status, &body, &vars) {
+            Ok(operation_id) => {
+                covered.insert(operation_id);
+            }
+            Err(e) => failures.push(format!("{} / {}: {}", name, step.name, e)),
+        }
+        for (var, pointer) in &step.capture {
+            match body.pointer(pointer) {
+                Some(Value::String(value)) => {
+                    vars.insert(var.clone(), value.clone());
+                }
+                Some(value) => {
+                    vars.insert(var.clone(), value.to_string());
+                }
+                None => failures.push(format!(
+                    "{} / {}: nothing to capture at {}",
+                    name, step.name, pointer
+                )),
+            }
+        }
+    }
+    failures
+}
+
+#[tokio::test]
+async fn test_recorded_fixtures_match_openapi_contract() {
+    let doc = serde_json::to_value(ApiDoc::build()).unwrap();
+    let files = fixture_files();
+    assert!(!files.is_empty(), "no contract fixtures in {}", FIXTURE_DIR);
+
+    let mut covered = BTreeSet::new();
+    let mut failures = Vec::new();
+    for file in &files {
+        failures.extend(replay(&doc, file, &mut covered).await);
+    }
+
+    let documented: usize = doc["paths"]
+        .as_object()
+        .map(|paths| {
+            paths
+                .values()
+                .map(|item| item.as_object().map_or(0, |ops| ops.len()))
+                .sum()
+        })
+        .unwrap_or(0);
+    eprintln!(
+        "contract fixtures cover {} of {} documented operations",
+        covered.len(),
+        documented
+    );
+    assert!(
+        failures.is_empty(),
+        "handlers drifted from the OpenAPI contract:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_path_template_prefers_literal_segments() {
+    let doc = serde_json::json!({
+        "paths": {
+            "/api/v1/tenants/{id}": { "get": {} },
+            "/api/v1/tenants/by-slug/{slug}": { "get": {} },
+            "/api/v1/tenants/{id}/{section}": { "get": {} }
+        }
+    });
+    assert_eq!(
+        match_path_template(&doc, "GET", "/api/v1/tenants/by-slug/acme"),
+        Some("/api/v1/tenants/by-slug/{slug}")
+    );
+    assert_eq!(
+        match_path_template(&doc, "GET", "/api/v1/tenants/42?expand[]=services"),
+        Some("/api/v1/tenants/{id}")
+    );
+    assert_eq!(match_path_template(&doc, "GET", "/api/v1/unknown"), None);
+}
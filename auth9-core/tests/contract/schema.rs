@@ -0,0 +1,177 @@
+//! Minimal JSON Schema checks for the OpenAPI 3.1 subset utoipa emits:
+//! `$ref`, `type` (including `["string", "null"]`), `enum`, `properties`,
+//! `required`, `additionalProperties`, `items` and `allOf`/`oneOf`/`anyOf`.
+//! Formats are not checked.
+
+use serde_json::{json, Value};
+
+/// Validate a response body against its documented schema.
+///
+/// Handlers document the payload type while `SuccessResponse` and
+/// `PaginatedResponse` wrap it in `data`, so a `data` envelope is unwrapped
+/// unless the schema itself has a `data` property.
+pub fn validate_response(doc: &Value, schema: &Value, body: &Value) -> Vec<String> {
+    let resolved = resolve(doc, schema);
+    let documents_envelope = resolved
+        .get("properties")
+        .is_some_and(|p| p.get("data").is_some());
+    let payload = match body.get("data") {
+        Some(data) if !documents_envelope => data,
+        _ => body,
+    };
+    let mut errors = Vec::new();
+    validate(doc, schema, payload, "$", &mut errors);
+    errors
+}
+
+fn resolve<'a>(doc: &'a Value, schema: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(reference) => {
+            let pointer = reference.trim_start_matches('#');
+            let target = doc
+                .pointer(pointer)
+                .unwrap_or_else(|| panic!("unresolvable schema reference {}", reference));
+            resolve(doc, target)
+        }
+        None => schema,
+    }
+}
+
+fn type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn matches(doc: &Value, schema: &Value, value: &Value) -> bool {
+    let mut errors = Vec::new();
+    validate(doc, schema, value, "$", &mut errors);
+    errors.is_empty()
+}
+
+fn validate(doc: &Value, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    let schema = resolve(doc, schema);
+
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            validate(doc, sub, value, at, errors);
+        }
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(options) = schema.get(keyword).and_then(Value::as_array) {
+            if !options.iter().any(|sub| matches(doc, sub, value)) {
+                errors.push(format!(
+                    "{}: {} matches none of the {} options",
+                    at, value, keyword
+                ));
+            }
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: {} is not one of {:?}", at, value, allowed));
+        }
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|name| type_matches(name, value)) {
+        errors.push(format!(
+            "{}: expected {} but got {}",
+            at,
+            types.join(" or "),
+            value
+        ));
+        return;
+    }
+
+    if let Value::Object(object) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(required) {
+                errors.push(format!("{}: missing required property `{}`", at, required));
+            }
+        }
+        for (key, item) in object {
+            let path = format!("{}.{}", at, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(sub) => validate(doc, sub, item, &path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        errors.push(format!("{}: undocumented property", path))
+                    }
+                    Some(sub @ Value::Object(_)) => validate(doc, sub, item, &path, errors),
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate(doc, item_schema, item, &format!("{}[{}]", at, i), errors);
+        }
+    }
+}
+
+fn sample_doc() -> Value {
+    json!({
+        "components": { "schemas": {
+            "Tenant": {
+                "type": "object",
+                "required": ["id", "status"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "logo_url": { "type": ["string", "null"] },
+                    "status": { "type": "string", "enum": ["active", "suspended"] }
+                }
+            }
+        }}
+    })
+}
+
+#[test]
+fn test_envelope_payload_is_validated() {
+    let doc = sample_doc();
+    let schema = json!({ "$ref": "#/components/schemas/Tenant" });
+    let body = json!({ "data": { "id": "t1", "logo_url": null, "status": "active" } });
+    assert!(validate_response(&doc, &schema, &body).is_empty());
+
+    let list = json!({ "type": "array", "items": schema });
+    let page = json!({ "data": [], "pagination": { "page": 1 } });
+    assert!(validate_response(&doc, &list, &page).is_empty());
+}
+
+#[test]
+fn test_drift_is_reported() {
+    let doc = sample_doc();
+    let schema = json!({ "$ref": "#/components/schemas/Tenant" });
+    let body = json!({ "data": { "id": 7, "status": "archived" } });
+    let errors = validate_response(&doc, &schema, &body);
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors[0].contains("$.id"));
+    assert!(errors[1].contains("$.status"));
+
+    let missing = json!({ "data": { "id": "t1" } });
+    assert_eq!(
+        validate_response(&doc, &schema, &missing),
+        vec!["$: missing required property `status`".to_string()]
+    );
+}
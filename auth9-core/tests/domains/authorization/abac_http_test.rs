@@ -22,7 +22,7 @@ fn create_member_token_for_tenant(tenant_id: Uuid) -> String {
 
 #[tokio::test]
 async fn test_abac_list_policies_forbidden_without_permission() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let tenant_id = Uuid::new_v4();
@@ -40,7 +40,7 @@ async fn test_abac_list_policies_forbidden_without_permission() {
 
 #[tokio::test]
 async fn test_abac_create_policy_forbidden_without_permission() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let tenant_id = Uuid::new_v4();
@@ -63,7 +63,7 @@ async fn test_abac_create_policy_forbidden_without_permission() {
 
 #[tokio::test]
 async fn test_abac_simulate_forbidden_without_permission() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let tenant_id = Uuid::new_v4();
@@ -89,3 +89,25 @@ async fn test_abac_simulate_forbidden_without_permission() {
 
     assert_eq!(status, StatusCode::FORBIDDEN);
 }
+
+#[tokio::test]
+async fn test_abac_get_policy_forbidden_without_permission() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_member_token_for_tenant(tenant_id);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/abac/policies/{}",
+            tenant_id,
+            Uuid::new_v4()
+        ),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,106 @@
+//! Claims enrichment plugin API HTTP handler tests
+
+use crate::support::create_test_identity_token;
+use crate::support::create_test_service;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, put_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_list_claims_enrichers_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json(
+        &app,
+        &format!("/api/v1/services/{}/claims-enrichers", Uuid::new_v4()),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_claims_enrichers_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/claims-enrichers", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_upsert_claims_enricher_service_not_found() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/services/{}/claims-enrichers/static",
+            Uuid::new_v4()
+        ),
+        &json!({ "settings": { "claims": { "plan": "gold" } } }),
+        &create_test_identity_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_upsert_claims_enricher_rejects_invalid_input() {
+    let state = TestAppState::new();
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+    let app = build_test_router(state);
+    let token = create_test_identity_token();
+
+    for (plugin, body) in [
+        ("unknown", json!({})),
+        ("static", json!({ "settings": { "claims": "gold" } })),
+        (
+            "http",
+            json!({ "settings": { "url": "https://example.com" }, "timeout_ms": 1 }),
+        ),
+        (
+            "http",
+            json!({ "settings": { "url": "https://169.254.169.254/latest" } }),
+        ),
+    ] {
+        let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+            &app,
+            &format!(
+                "/api/v1/services/{}/claims-enrichers/{}",
+                service_id, plugin
+            ),
+            &body,
+            &token,
+        )
+        .await;
+
+        assert_eq!(
+            status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{} {}",
+            plugin,
+            body
+        );
+    }
+}
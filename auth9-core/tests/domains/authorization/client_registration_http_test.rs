@@ -0,0 +1,104 @@
+//! Dynamic Client Registration HTTP Handler Tests
+
+use crate::support::create_test_jwt_manager;
+use crate::support::create_test_tenant_access_token_for_tenant;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::json;
+use uuid::Uuid;
+
+fn create_member_token_for_tenant(tenant_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "member@auth9.local",
+            tenant_id,
+            "auth9-test-service",
+            vec!["member".to_string()],
+            vec!["user:read".to_string()],
+        )
+        .expect("failed to create member token")
+}
+
+#[tokio::test]
+async fn test_read_registered_client_requires_registration_token() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) = get_json(
+        &app,
+        &format!(
+            "/api/v1/oauth/tenants/{}/register/some-client",
+            Uuid::new_v4()
+        ),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body.unwrap()["error"], "invalid_token");
+}
+
+#[tokio::test]
+async fn test_registration_policy_forbidden_without_permission() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_member_token_for_tenant(tenant_id);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/client-registration/policy", tenant_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_create_initial_access_token_forbidden_without_permission() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_member_token_for_tenant(tenant_id);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/client-registration/initial-access-tokens",
+            tenant_id
+        ),
+        &json!({ "max_uses": 1 }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_create_initial_access_token_rejects_short_lifetime() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/client-registration/initial-access-tokens",
+            tenant_id
+        ),
+        &json!({ "expires_in_secs": 10 }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
@@ -1,5 +1,8 @@
 mod abac_http_test;
+mod claims_enricher_http_test;
+mod client_registration_http_test;
 mod rbac_cross_service_test;
 mod role_http_test;
 mod role_service_test;
+mod scope_http_test;
 mod service_http_test;
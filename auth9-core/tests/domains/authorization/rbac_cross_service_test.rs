@@ -11,7 +11,7 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_assign_permission_different_service_fails() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin
 
     let service1_id = Uuid::new_v4();
@@ -11,7 +11,7 @@ use crate::support::{
     create_test_tenant_access_token_for_tenant,
 };
 use auth9_core::http_support::{MessageResponse, SuccessResponse};
-use auth9_core::models::rbac::{Permission, Role, UserRolesInTenant};
+use auth9_core::models::rbac::{Permission, Role, RoleTree, UserRolesInTenant};
 use auth9_core::repository::RbacRepository;
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -33,7 +33,7 @@ pub struct TestRoleWithPermissions {
 
 #[tokio::test]
 async fn test_list_permissions() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
 
@@ -71,7 +71,7 @@ async fn test_list_permissions() {
 
 #[tokio::test]
 async fn test_list_permissions_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     state
@@ -98,7 +98,7 @@ async fn test_list_permissions_empty() {
 
 #[tokio::test]
 async fn test_create_permission() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create permissions
 
     let service_id = Uuid::new_v4();
@@ -129,9 +129,45 @@ async fn test_create_permission() {
     );
 }
 
+#[tokio::test]
+async fn test_create_permission_with_service_namespace() {
+    let state = TestAppState::new();
+    let token = create_test_tenant_access_token();
+
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<serde_json::Value>>) =
+        put_json_with_auth(
+            &app,
+            &format!("/api/v1/services/{}/permission-namespace", service_id),
+            &json!({ "namespace": "billing" }),
+            &token,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data["namespace"], "billing");
+
+    let input = json!({
+        "service_id": service_id.to_string(),
+        "code": "invoices:read",
+        "name": "Read Invoices"
+    });
+    let (status, body): (StatusCode, Option<SuccessResponse<Permission>>) =
+        post_json_with_auth(&app, "/api/v1/permissions", &input, &token).await;
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body.unwrap().data.code, "billing:invoices:read");
+}
+
 #[tokio::test]
 async fn test_create_permission_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create permissions
 
     let service_id = Uuid::new_v4();
@@ -159,7 +195,7 @@ async fn test_create_permission_minimal() {
 
 #[tokio::test]
 async fn test_delete_permission() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete permissions
 
     let service_id = Uuid::new_v4();
@@ -184,7 +220,7 @@ async fn test_delete_permission() {
 
 #[tokio::test]
 async fn test_delete_permission_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete permissions
     let app = build_test_router(state);
 
@@ -205,7 +241,7 @@ async fn test_delete_permission_not_found() {
 
 #[tokio::test]
 async fn test_list_roles() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
 
@@ -241,7 +277,7 @@ async fn test_list_roles() {
 
 #[tokio::test]
 async fn test_list_roles_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     state
@@ -265,9 +301,83 @@ async fn test_list_roles_empty() {
     assert!(response.data.is_empty());
 }
 
+#[tokio::test]
+async fn test_get_role_tree() {
+    let state = TestAppState::new();
+
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+
+    let mut viewer = create_test_role(None, service_id);
+    viewer.name = "viewer".to_string();
+    let mut editor = create_test_role(None, service_id);
+    editor.name = "editor".to_string();
+    editor.parent_role_id = Some(viewer.id);
+    let mut read = create_test_permission(None, service_id);
+    read.code = "doc:read".to_string();
+    let mut write = create_test_permission(None, service_id);
+    write.code = "doc:write".to_string();
+
+    state.rbac_repo.add_role(viewer.clone()).await;
+    state.rbac_repo.add_role(editor.clone()).await;
+    state.rbac_repo.add_permission(read.clone()).await;
+    state.rbac_repo.add_permission(write.clone()).await;
+    state
+        .rbac_repo
+        .assign_permission_to_role(viewer.id, read.id)
+        .await
+        .unwrap();
+    state
+        .rbac_repo
+        .assign_permission_to_role(editor.id, write.id)
+        .await
+        .unwrap();
+
+    let app = build_test_router(state);
+    let token = create_test_tenant_access_token();
+
+    let (status, body): (StatusCode, Option<SuccessResponse<RoleTree>>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/roles/tree", service_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let tree = body.unwrap().data;
+    assert_eq!(tree.roots.len(), 1);
+    assert_eq!(tree.roots[0].id, viewer.id);
+    let editor_node = &tree.roots[0].children[0];
+    assert_eq!(editor_node.id, editor.id);
+    assert_eq!(editor_node.permissions, vec!["doc:write"]);
+    assert_eq!(
+        editor_node.effective_permissions,
+        vec!["doc:read", "doc:write"]
+    );
+}
+
+#[tokio::test]
+async fn test_get_role_tree_service_not_found() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+    let token = create_test_tenant_access_token();
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/roles/tree", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn test_get_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
 
@@ -302,7 +412,7 @@ async fn test_get_role() {
 
 #[tokio::test]
 async fn test_get_role_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -315,7 +425,7 @@ async fn test_get_role_not_found() {
 
 #[tokio::test]
 async fn test_create_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create roles
 
     let service_id = Uuid::new_v4();
@@ -342,7 +452,7 @@ async fn test_create_role() {
 
 #[tokio::test]
 async fn test_create_role_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create roles
 
     let service_id = Uuid::new_v4();
@@ -369,7 +479,7 @@ async fn test_create_role_minimal() {
 
 #[tokio::test]
 async fn test_update_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can update roles
 
     let service_id = Uuid::new_v4();
@@ -397,9 +507,29 @@ async fn test_update_role() {
     );
 }
 
+#[tokio::test]
+async fn test_update_role_parent_from_other_service_rejected() {
+    let state = TestAppState::new();
+    let token = create_test_tenant_access_token();
+
+    let role = create_test_role(None, Uuid::new_v4());
+    let other_parent = create_test_role(None, Uuid::new_v4());
+    let role_id = role.id;
+    state.rbac_repo.add_role(role).await;
+    state.rbac_repo.add_role(other_parent.clone()).await;
+
+    let app = build_test_router(state);
+
+    let input = json!({ "parent_role_id": other_parent.id.to_string() });
+    let (status, _body): (StatusCode, Option<serde_json::Value>) =
+        put_json_with_auth(&app, &format!("/api/v1/roles/{}", role_id), &input, &token).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_update_role_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can update roles
     let app = build_test_router(state);
 
@@ -421,7 +551,7 @@ async fn test_update_role_not_found() {
 
 #[tokio::test]
 async fn test_delete_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete roles
 
     let service_id = Uuid::new_v4();
@@ -442,7 +572,7 @@ async fn test_delete_role() {
 
 #[tokio::test]
 async fn test_delete_role_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete roles
     let app = build_test_router(state);
 
@@ -459,7 +589,7 @@ async fn test_delete_role_not_found() {
 
 #[tokio::test]
 async fn test_assign_permission_to_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can assign permissions
 
     let service_id = Uuid::new_v4();
@@ -493,7 +623,7 @@ async fn test_assign_permission_to_role() {
 
 #[tokio::test]
 async fn test_remove_permission_from_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can remove permissions
 
     let service_id = Uuid::new_v4();
@@ -534,7 +664,7 @@ async fn test_remove_permission_from_role() {
 
 #[tokio::test]
 async fn test_assign_roles_to_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -572,7 +702,7 @@ async fn test_assign_roles_to_user() {
 
 #[tokio::test]
 async fn test_get_user_roles() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -609,7 +739,7 @@ async fn test_get_user_roles() {
 
 #[tokio::test]
 async fn test_get_user_roles_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -634,7 +764,7 @@ async fn test_get_user_roles_empty() {
 
 #[tokio::test]
 async fn test_get_user_assigned_roles() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let role = create_test_role(None, service_id);
@@ -662,7 +792,7 @@ async fn test_get_user_assigned_roles() {
 
 #[tokio::test]
 async fn test_unassign_role_from_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let role = create_test_role(None, service_id);
@@ -699,7 +829,7 @@ async fn test_unassign_role_from_user() {
 
 #[tokio::test]
 async fn test_create_role_with_parent() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create roles
 
     let service_id = Uuid::new_v4();
@@ -736,7 +866,7 @@ async fn test_create_role_with_parent() {
 
 #[tokio::test]
 async fn test_permission_code_formats() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can create permissions
 
     let service_id = Uuid::new_v4();
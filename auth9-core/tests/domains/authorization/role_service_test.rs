@@ -349,7 +349,7 @@ async fn test_update_role_name() {
         parent_role_id: None,
     };
 
-    let result = service.update_role(role_id, input).await;
+    let result = service.update_role(role_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(result.unwrap().name, "updated-role");
 }
@@ -371,7 +371,7 @@ async fn test_update_role_description() {
         parent_role_id: None,
     };
 
-    let result = service.update_role(role_id, input).await;
+    let result = service.update_role(role_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap().description,
@@ -390,7 +390,7 @@ async fn test_update_role_not_found() {
         parent_role_id: None,
     };
 
-    let result = service.update_role(StringUuid::new_v4(), input).await;
+    let result = service.update_role(StringUuid::new_v4(), input, None).await;
     assert!(matches!(result, Err(AppError::NotFound(_))));
 }
 
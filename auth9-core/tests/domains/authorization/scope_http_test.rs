@@ -0,0 +1,130 @@
+//! Service OAuth scope catalog API HTTP handler tests
+
+use crate::support::create_test_identity_token;
+use crate::support::create_test_service;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, put_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_list_scopes_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, &format!("/api/v1/services/{}/scopes", Uuid::new_v4())).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_scopes_of_global_service_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/scopes", service_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_upsert_scope_service_not_found() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/scopes/invoices:read", Uuid::new_v4()),
+        &json!({ "description": "Read your invoices" }),
+        &create_test_identity_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_upsert_scope_rejects_invalid_input() {
+    let state = TestAppState::new();
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+    let app = build_test_router(state);
+    let token = create_test_identity_token();
+
+    for (name, body) in [
+        ("email", json!({ "description": "Standard scope" })),
+        ("invoices:read", json!({ "description": "" })),
+        (
+            "invoices:read",
+            json!({ "description": "Read", "required_permissions": ["Not A Code"] }),
+        ),
+    ] {
+        let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+            &app,
+            &format!("/api/v1/services/{}/scopes/{}", service_id, name),
+            &body,
+            &token,
+        )
+        .await;
+
+        assert_eq!(
+            status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{} {}",
+            name,
+            body
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_upsert_then_list_scopes() {
+    let state = TestAppState::new();
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), None))
+        .await;
+    let app = build_test_router(state);
+    let token = create_test_identity_token();
+
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/scopes/invoices:read", service_id),
+        &json!({ "description": "Read your invoices" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/scopes", service_id),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let scopes = body.unwrap()["data"].as_array().unwrap().clone();
+    assert_eq!(scopes.len(), 1);
+    assert_eq!(scopes[0]["name"], "invoices:read");
+}
@@ -7,10 +7,18 @@ use crate::support::http::{
     build_test_router, delete_json_with_auth, get_json_with_auth, post_json_with_auth,
     put_json_with_auth, TestAppState,
 };
-use crate::support::{create_test_tenant_access_token, create_test_tenant_access_token_for_tenant};
+use crate::support::{
+    create_test_identity_token_for_user, create_test_tenant_access_token,
+    create_test_tenant_access_token_for_tenant,
+};
+use auth9_core::domains::authorization::api::service::LauncherApp;
 use auth9_core::http_support::{MessageResponse, PaginatedResponse, SuccessResponse};
-use auth9_core::models::service::{Client, Service, ServiceStatus};
+use auth9_core::models::common::StringUuid;
+use auth9_core::models::rbac::UserRolesInTenant;
+use auth9_core::models::service::{Client, Service, ServiceCatalog, ServiceStatus};
+use auth9_core::models::user::TenantUser;
 use axum::http::StatusCode;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use uuid::Uuid;
@@ -55,7 +63,7 @@ pub struct TestClientWithSecret {
 
 #[tokio::test]
 async fn test_list_services() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
 
@@ -82,7 +90,7 @@ async fn test_list_services() {
 
 #[tokio::test]
 async fn test_list_services_with_tenant_filter() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant1 = Uuid::new_v4();
     let tenant2 = Uuid::new_v4();
@@ -114,7 +122,7 @@ async fn test_list_services_with_tenant_filter() {
 
 #[tokio::test]
 async fn test_list_services_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -129,7 +137,7 @@ async fn test_list_services_empty() {
 
 #[tokio::test]
 async fn test_list_services_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add 25 services
     for i in 1..=25 {
@@ -158,7 +166,7 @@ async fn test_list_services_pagination() {
 
 #[tokio::test]
 async fn test_get_service() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -179,7 +187,7 @@ async fn test_get_service() {
 
 #[tokio::test]
 async fn test_get_service_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -200,7 +208,7 @@ async fn test_get_service_not_found() {
 
 #[tokio::test]
 async fn test_create_service() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -229,7 +237,7 @@ async fn test_create_service_with_logout_uris() {
     let _client_uuid = "kc-client-logout";
     let _client_secret = "secret-with-logout";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -257,7 +265,7 @@ async fn test_create_service_with_logout_uris() {
 
 #[tokio::test]
 async fn test_create_service_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -287,7 +295,7 @@ async fn test_create_service_minimal() {
 
 #[tokio::test]
 async fn test_update_service() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -316,7 +324,7 @@ async fn test_update_service() {
 
 #[tokio::test]
 async fn test_update_service_status() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -345,7 +353,7 @@ async fn test_update_service_status() {
 
 #[tokio::test]
 async fn test_update_service_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -371,7 +379,7 @@ async fn test_update_service_not_found() {
 
 #[tokio::test]
 async fn test_delete_service() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -391,7 +399,7 @@ async fn test_delete_service() {
 
 #[tokio::test]
 async fn test_delete_service_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -412,7 +420,7 @@ async fn test_delete_service_not_found() {
 
 #[tokio::test]
 async fn test_list_clients() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -427,6 +435,7 @@ async fn test_list_clients() {
         client_secret_hash: "hash1".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     let client2 = Client {
         id: auth9_core::models::common::StringUuid::new_v4(),
@@ -436,6 +445,7 @@ async fn test_list_clients() {
         client_secret_hash: "hash2".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
 
     state.service_repo.add_client(client1).await;
@@ -465,7 +475,7 @@ async fn test_list_clients() {
 
 #[tokio::test]
 async fn test_create_client() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -496,7 +506,7 @@ async fn test_create_client() {
 
 #[tokio::test]
 async fn test_create_client_without_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -522,7 +532,7 @@ async fn test_create_client_without_name() {
 
 #[tokio::test]
 async fn test_delete_client() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -536,6 +546,7 @@ async fn test_delete_client() {
         client_secret_hash: "hash".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -555,11 +566,61 @@ async fn test_delete_client() {
     assert!(response.message.contains("deleted"));
 }
 
+#[tokio::test]
+async fn test_update_client_token_ttls() {
+    let state = TestAppState::new();
+
+    let service_id = Uuid::new_v4();
+    let service = create_test_service(Some(service_id), None);
+    state.service_repo.add_service(service).await;
+
+    let client = Client {
+        id: auth9_core::models::common::StringUuid::new_v4(),
+        service_id: auth9_core::models::common::StringUuid::from(service_id),
+        client_id: "batch-client".to_string(),
+        name: Some("Batch Jobs".to_string()),
+        client_secret_hash: "hash".to_string(),
+        public_client: false,
+        created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
+    };
+    state.service_repo.add_client(client).await;
+
+    let app = build_test_router(state);
+    let token = create_test_tenant_access_token();
+    let path = format!(
+        "/api/v1/services/{}/clients/batch-client/token-ttls",
+        service_id
+    );
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &path,
+        &json!({ "access_token_ttl_secs": 43200, "refresh_token_ttl_secs": 2592000 }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let data = &body.unwrap()["data"];
+    assert_eq!(data["access_token_ttl_secs"], 43200);
+    assert_eq!(data["refresh_token_ttl_secs"], 2592000);
+
+    // Above the platform maximum
+    let (status, _): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &path,
+        &json!({ "access_token_ttl_secs": 86401 }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
 #[tokio::test]
 async fn test_regenerate_client_secret() {
     let _kc_uuid = "kc-client-uuid";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -573,6 +634,7 @@ async fn test_regenerate_client_secret() {
         client_secret_hash: "old-hash".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -605,7 +667,7 @@ async fn test_regenerate_client_secret() {
 
 #[tokio::test]
 async fn test_service_with_multiple_redirect_uris() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -639,7 +701,7 @@ async fn test_service_with_multiple_redirect_uris() {
 async fn test_integration_info_success() {
     // Register specific mock first (wiremock matches FIFO)
     // Then register broader client lookup mock
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -654,6 +716,7 @@ async fn test_integration_info_success() {
         client_secret_hash: "hash".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -713,7 +776,7 @@ async fn test_integration_info_success() {
 
 #[tokio::test]
 async fn test_integration_info_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let token = create_test_tenant_access_token();
 
@@ -730,7 +793,7 @@ async fn test_integration_info_not_found() {
 
 #[tokio::test]
 async fn test_integration_info_no_clients() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -759,7 +822,7 @@ async fn test_integration_info_no_clients() {
 
 #[tokio::test]
 async fn test_update_service_redirect_uris() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -788,3 +851,89 @@ async fn test_update_service_redirect_uris() {
     let response = body.unwrap();
     assert_eq!(response.data.redirect_uris.len(), 2);
 }
+
+// ============================================================================
+// App Launcher Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_list_my_apps() {
+    let state = TestAppState::new();
+
+    let user_id = Uuid::new_v4();
+    let tenant_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_tenant_user(TenantUser {
+            id: StringUuid::new_v4(),
+            user_id: StringUuid::from(user_id),
+            tenant_id: StringUuid::from(tenant_id),
+            role_in_tenant: "member".to_string(),
+            joined_at: Utc::now(),
+        })
+        .await;
+
+    let mut billing = create_test_service(None, Some(tenant_id));
+    billing.name = "Billing".to_string();
+    billing.catalog = ServiceCatalog {
+        owner_team: Some("payments".to_string()),
+        environment_tags: vec!["production".to_string()],
+        ..Default::default()
+    };
+    let mut staging = create_test_service(None, Some(tenant_id));
+    staging.name = "Billing (staging)".to_string();
+    staging.catalog.environment_tags = vec!["staging".to_string()];
+    let mut retired = create_test_service(None, Some(tenant_id));
+    retired.status = ServiceStatus::Inactive;
+    // Another tenant's service is not the user's to launch
+    let other = create_test_service(None, Some(Uuid::new_v4()));
+
+    state
+        .rbac_repo
+        .set_user_roles_for_service(
+            user_id,
+            tenant_id,
+            billing.id.into(),
+            UserRolesInTenant {
+                user_id,
+                tenant_id,
+                roles: vec!["billing-admin".to_string()],
+                permissions: vec![],
+            },
+        )
+        .await;
+    for service in [billing.clone(), staging, retired, other] {
+        state.service_repo.add_service(service).await;
+    }
+
+    let app = build_test_router(state);
+    let token = create_test_identity_token_for_user(user_id);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Vec<LauncherApp>>>) =
+        get_json_with_auth(&app, "/api/v1/users/me/apps", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let apps = body.unwrap().data;
+    let names: Vec<&str> = apps.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, vec!["Billing", "Billing (staging)"]);
+    assert_eq!(apps[0].roles, vec!["billing-admin".to_string()]);
+    assert_eq!(apps[0].launch_url, billing.base_url);
+    assert_eq!(apps[0].catalog.owner_team.as_deref(), Some("payments"));
+    assert!(apps[1].roles.is_empty());
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Vec<LauncherApp>>>) =
+        get_json_with_auth(&app, "/api/v1/users/me/apps?environment=production", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let apps = body.unwrap().data;
+    assert_eq!(apps.len(), 1);
+    assert_eq!(apps[0].service_id, billing.id);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Vec<LauncherApp>>>) =
+        get_json_with_auth(
+            &app,
+            &format!("/api/v1/users/me/apps?tenant_id={}", Uuid::new_v4()),
+            &token,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.unwrap().data.is_empty());
+}
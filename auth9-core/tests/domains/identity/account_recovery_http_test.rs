@@ -0,0 +1,99 @@
+//! Account recovery HTTP API handler tests
+//!
+//! Recovery requests live in the database, so these cover input validation,
+//! enumeration resistance and admin access control that run before any
+//! request row is read.
+
+use crate::support::create_test_identity_token_for_user;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json, post_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_recover_with_code_unknown_email_returns_401() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        "/api/v1/auth/account-recovery/recovery-code",
+        &json!({ "email": "nobody@example.com", "code": "abcde12345" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_recover_with_factor_rejects_invalid_input() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        "/api/v1/auth/account-recovery/secondary-factor",
+        &json!({ "email": "not-an-email", "factor": "totp", "code": "123456" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_create_recovery_request_unknown_email_looks_like_success() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        "/api/v1/auth/account-recovery/requests",
+        &json!({ "email": "nobody@example.com", "reason": "Lost my phone and mailbox" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let data = &body.unwrap()["data"];
+    assert!(data["request_id"].is_string());
+    assert_eq!(data["claim_secret"].as_str().unwrap().len(), 32);
+}
+
+#[tokio::test]
+async fn test_list_recovery_requests_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/admin/account-recovery/requests").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_approve_recovery_request_requires_platform_admin() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token_for_user(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        "/api/v1/admin/account-recovery/requests?status=pending",
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/admin/account-recovery/requests/{}/approve",
+            Uuid::new_v4()
+        ),
+        &json!({ "comment": "Verified by video call" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
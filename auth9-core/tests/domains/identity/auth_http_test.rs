@@ -2,13 +2,15 @@
 //!
 //! Tests for the auth HTTP endpoints using mock repositories.
 
-use crate::support::create_test_service;
 use crate::support::http::{
     build_test_router, get_json, get_json_with_auth, get_raw, post_json, TestAppState,
 };
+use crate::support::{create_test_service, create_test_user};
 use auth9_core::domains::identity::api::auth::{OpenIdConfiguration, TokenResponse};
 use auth9_core::models::common::StringUuid;
 use auth9_core::models::service::Client;
+use auth9_core::models::session::Session;
+use auth9_core::repository::ServiceScopeRepository;
 use axum::http::StatusCode;
 use base64::Engine;
 use chrono::Utc;
@@ -21,7 +23,7 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_openid_configuration_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, body): (StatusCode, Option<OpenIdConfiguration>) =
@@ -35,7 +37,7 @@ async fn test_openid_configuration_success() {
 
 #[tokio::test]
 async fn test_openid_configuration_endpoints() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, body): (StatusCode, Option<OpenIdConfiguration>) =
@@ -63,11 +65,17 @@ async fn test_openid_configuration_endpoints() {
         .grant_types_supported
         .contains(&"client_credentials".to_string()));
     assert!(config.scopes_supported.contains(&"openid".to_string()));
+    assert!(config.scopes_supported.contains(&"phone".to_string()));
+    assert!(config.scopes_supported.contains(&"address".to_string()));
+    assert!(config
+        .claims_supported
+        .contains(&"phone_number".to_string()));
+    assert!(config.claims_supported.contains(&"address".to_string()));
 }
 
 #[tokio::test]
 async fn test_openid_configuration_hmac_algorithm() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, body): (StatusCode, Option<OpenIdConfiguration>) =
@@ -86,7 +94,7 @@ async fn test_openid_configuration_hmac_algorithm() {
 
 #[tokio::test]
 async fn test_jwks_empty_without_rsa() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, body) = get_raw(&app, "/.well-known/jwks.json").await;
@@ -104,7 +112,7 @@ async fn test_jwks_empty_without_rsa() {
 
 #[tokio::test]
 async fn test_authorize_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a service with redirect_uris
     let service_id = Uuid::new_v4();
@@ -121,6 +129,7 @@ async fn test_authorize_success() {
         name: Some("Test Client".to_string()),
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -139,7 +148,7 @@ async fn test_authorize_success() {
 
 #[tokio::test]
 async fn test_authorize_missing_state() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -154,6 +163,7 @@ async fn test_authorize_missing_state() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -170,7 +180,7 @@ async fn test_authorize_missing_state() {
 
 #[tokio::test]
 async fn test_authorize_client_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(
@@ -184,7 +194,7 @@ async fn test_authorize_client_not_found() {
 
 #[tokio::test]
 async fn test_authorize_invalid_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create service with specific redirect_uris
     let service_id = Uuid::new_v4();
@@ -200,6 +210,7 @@ async fn test_authorize_invalid_redirect_uri() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -217,7 +228,7 @@ async fn test_authorize_invalid_redirect_uri() {
 
 #[tokio::test]
 async fn test_authorize_with_state() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -232,6 +243,7 @@ async fn test_authorize_with_state() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -248,7 +260,7 @@ async fn test_authorize_with_state() {
 
 #[tokio::test]
 async fn test_authorize_with_nonce() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -263,6 +275,7 @@ async fn test_authorize_with_nonce() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -282,7 +295,7 @@ async fn test_authorize_with_nonce() {
 
 #[tokio::test]
 async fn test_logout_get_without_redirect_uri_returns_ok() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(&app, "/api/v1/auth/logout").await;
@@ -293,7 +306,7 @@ async fn test_logout_get_without_redirect_uri_returns_ok() {
 
 #[tokio::test]
 async fn test_logout_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let request = axum::http::Request::builder()
@@ -310,7 +323,7 @@ async fn test_logout_minimal() {
 
 #[tokio::test]
 async fn test_logout_with_id_token_hint() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let request = axum::http::Request::builder()
@@ -327,7 +340,7 @@ async fn test_logout_with_id_token_hint() {
 
 #[tokio::test]
 async fn test_logout_with_post_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Set up a service with allowed logout_uris and a linked client
     let service_id = Uuid::new_v4();
@@ -344,6 +357,7 @@ async fn test_logout_with_post_redirect_uri() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -362,7 +376,7 @@ async fn test_logout_with_post_redirect_uri() {
 
 #[tokio::test]
 async fn test_logout_with_post_redirect_uri_rejected_without_client_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let request = axum::http::Request::builder()
@@ -378,7 +392,7 @@ async fn test_logout_with_post_redirect_uri_rejected_without_client_id() {
 
 #[tokio::test]
 async fn test_logout_with_invalid_post_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -394,6 +408,7 @@ async fn test_logout_with_invalid_post_redirect_uri() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -412,7 +427,7 @@ async fn test_logout_with_invalid_post_redirect_uri() {
 
 #[tokio::test]
 async fn test_logout_full_params() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -428,6 +443,7 @@ async fn test_logout_full_params() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -450,7 +466,7 @@ async fn test_logout_full_params() {
 
 #[tokio::test]
 async fn test_userinfo_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a valid identity token
     let user_id = Uuid::new_v4();
@@ -472,7 +488,7 @@ async fn test_userinfo_success() {
 
 #[tokio::test]
 async fn test_userinfo_no_auth_header() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body): (StatusCode, Option<serde_json::Value>) =
@@ -484,7 +500,7 @@ async fn test_userinfo_no_auth_header() {
 
 #[tokio::test]
 async fn test_userinfo_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body): (StatusCode, Option<serde_json::Value>) =
@@ -496,7 +512,7 @@ async fn test_userinfo_invalid_token() {
 
 #[tokio::test]
 async fn test_userinfo_malformed_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // Token with invalid format
@@ -512,7 +528,7 @@ async fn test_userinfo_malformed_token() {
 
 #[tokio::test]
 async fn test_callback_missing_state() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(&app, "/api/v1/auth/callback?code=auth-code-123").await;
@@ -523,7 +539,7 @@ async fn test_callback_missing_state() {
 
 #[tokio::test]
 async fn test_callback_invalid_state() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(
@@ -538,7 +554,7 @@ async fn test_callback_invalid_state() {
 
 #[tokio::test]
 async fn test_callback_invalid_state_json() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // Valid base64 but invalid JSON
@@ -563,7 +579,7 @@ async fn test_callback_invalid_state_json() {
 
 #[tokio::test]
 async fn test_token_unsupported_grant_type() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -581,7 +597,7 @@ async fn test_token_unsupported_grant_type() {
 
 #[tokio::test]
 async fn test_token_auth_code_missing_code() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -599,7 +615,7 @@ async fn test_token_auth_code_missing_code() {
 
 #[tokio::test]
 async fn test_token_auth_code_missing_client_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -617,7 +633,7 @@ async fn test_token_auth_code_missing_client_id() {
 
 #[tokio::test]
 async fn test_token_auth_code_missing_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -635,7 +651,7 @@ async fn test_token_auth_code_missing_redirect_uri() {
 
 #[tokio::test]
 async fn test_token_client_credentials_missing_client_secret() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -652,7 +668,7 @@ async fn test_token_client_credentials_missing_client_secret() {
 
 #[tokio::test]
 async fn test_token_client_credentials_missing_client_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -669,7 +685,7 @@ async fn test_token_client_credentials_missing_client_id() {
 
 #[tokio::test]
 async fn test_token_client_credentials_client_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -687,7 +703,7 @@ async fn test_token_client_credentials_client_not_found() {
 
 #[tokio::test]
 async fn test_token_refresh_missing_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -704,7 +720,7 @@ async fn test_token_refresh_missing_token() {
 
 #[tokio::test]
 async fn test_token_refresh_missing_client_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -758,7 +774,7 @@ async fn test_token_response_without_optional_fields() {
 
 #[tokio::test]
 async fn test_authorize_multiple_redirect_uris() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -777,6 +793,7 @@ async fn test_authorize_multiple_redirect_uris() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -806,7 +823,7 @@ async fn test_authorize_multiple_redirect_uris() {
 
 #[tokio::test]
 async fn test_userinfo_with_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let token = state
@@ -827,7 +844,7 @@ async fn test_userinfo_with_name() {
 
 #[tokio::test]
 async fn test_userinfo_without_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let token = state
@@ -851,7 +868,7 @@ async fn test_userinfo_without_name() {
 
 #[tokio::test]
 async fn test_health_endpoint_via_router() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, body): (StatusCode, Option<serde_json::Value>) = get_json(&app, "/health").await;
@@ -863,7 +880,7 @@ async fn test_health_endpoint_via_router() {
 
 #[tokio::test]
 async fn test_ready_endpoint_via_router() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(&app, "/ready").await;
@@ -877,7 +894,7 @@ async fn test_ready_endpoint_via_router() {
 
 #[tokio::test]
 async fn test_logout_with_valid_token_and_session() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a user and session first
     let user_id = Uuid::new_v4();
@@ -912,7 +929,7 @@ async fn test_logout_with_valid_token_and_session() {
 
 #[tokio::test]
 async fn test_logout_with_expired_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // Use an obviously invalid token (not a real JWT)
@@ -931,7 +948,7 @@ async fn test_logout_with_expired_token() {
 
 #[tokio::test]
 async fn test_logout_with_token_no_session_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create identity token WITHOUT session ID
     let user_id = Uuid::new_v4();
@@ -961,7 +978,7 @@ async fn test_logout_with_token_no_session_id() {
 
 #[tokio::test]
 async fn test_authorize_with_invalid_scope_rejects() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -976,6 +993,7 @@ async fn test_authorize_with_invalid_scope_rejects() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -996,7 +1014,7 @@ async fn test_authorize_with_invalid_scope_rejects() {
 
 #[tokio::test]
 async fn test_token_client_credentials_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a service and client with a properly hashed secret
     let service_id = Uuid::new_v4();
@@ -1019,6 +1037,7 @@ async fn test_token_client_credentials_success() {
         name: Some("CC Test Client".to_string()),
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -1045,7 +1064,7 @@ async fn test_token_client_credentials_success() {
 
 #[tokio::test]
 async fn test_token_client_credentials_wrong_secret() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let service = create_test_service(Some(service_id), None);
@@ -1066,6 +1085,7 @@ async fn test_token_client_credentials_wrong_secret() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -1085,7 +1105,7 @@ async fn test_token_client_credentials_wrong_secret() {
 
 #[tokio::test]
 async fn test_token_client_credentials_with_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let service_id = Uuid::new_v4();
@@ -1109,6 +1129,7 @@ async fn test_token_client_credentials_with_tenant() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -1158,7 +1179,7 @@ async fn test_callback_success_existing_user() {
     let kc_sub = "kc-existing-user-123";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Pre-create the user in the repo
     let user = auth9_core::models::user::User {
@@ -1173,6 +1194,8 @@ async fn test_callback_success_existing_user() {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -1204,7 +1227,7 @@ async fn test_callback_success_new_user_created() {
     let _kc_sub = "kc-new-user-456";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let state_nonce = create_callback_state_nonce(
         &state,
@@ -1230,7 +1253,7 @@ async fn test_callback_success_new_user_created() {
 async fn test_callback_missing_cached_state_returns_error() {
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let (status, _body) = get_raw(
@@ -1250,7 +1273,7 @@ async fn test_callback_missing_cached_state_returns_error() {
 async fn test_callback_does_not_depend_on_userinfo() {
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let state_nonce = create_callback_state_nonce(
         &state,
         "https://app.example.com/callback",
@@ -1282,7 +1305,7 @@ async fn test_token_authorization_code_success_existing_user() {
     let kc_sub = "kc-token-user-789";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Pre-create the user
     let user = auth9_core::models::user::User {
@@ -1297,6 +1320,8 @@ async fn test_token_authorization_code_success_existing_user() {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -1330,7 +1355,7 @@ async fn test_token_authorization_code_new_user() {
     let _kc_sub = "kc-new-token-user-abc";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     // Don't pre-create user
     let app = build_test_router(state);
 
@@ -1360,7 +1385,7 @@ async fn test_token_refresh_success() {
     let kc_sub = "kc-refresh-user-xyz";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state
         .cache_manager
         .bind_refresh_token_session(
@@ -1384,6 +1409,8 @@ async fn test_token_refresh_success() {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -1414,7 +1441,7 @@ async fn test_token_refresh_new_user() {
     let _kc_sub = "kc-refresh-new-user";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state
         .cache_manager
         .bind_refresh_token_session(
@@ -1442,12 +1469,12 @@ async fn test_token_refresh_new_user() {
 }
 
 // ============================================================================
-// Authorize scope filtering edge cases
+// Authorize scope validation edge cases
 // ============================================================================
 
 #[tokio::test]
-async fn test_authorize_filters_unsafe_scopes() {
-    let state = TestAppState::new("http://localhost:8081");
+async fn test_authorize_rejects_undeclared_scopes() {
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -1462,18 +1489,58 @@ async fn test_authorize_filters_unsafe_scopes() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
     let app = build_test_router(state);
 
-    // Include unsafe scopes that should be filtered out - openid is still present so should succeed
-    let (status, _body) = get_raw(
+    // Scopes the service has not declared are no longer silently dropped
+    let (status, body) = get_raw(
         &app,
         "/api/v1/auth/authorize?response_type=code&client_id=scope-filter-client&redirect_uri=https://app.example.com/callback&scope=openid+admin+offline_access+profile&state=csrf-state",
     ).await;
 
-    // Should succeed since openid is present (unsafe scopes are just filtered out)
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let message = body["message"].as_str().unwrap();
+    assert!(message.starts_with("invalid_scope"));
+    assert!(message.contains("admin"));
+}
+
+#[tokio::test]
+async fn test_authorize_accepts_declared_scopes() {
+    let state = TestAppState::new();
+
+    let service_id = Uuid::new_v4();
+    let mut service = create_test_service(Some(service_id), None);
+    service.redirect_uris = vec!["https://app.example.com/callback".to_string()];
+    state.service_repo.add_service(service.clone()).await;
+
+    let client = Client {
+        id: StringUuid::new_v4(),
+        service_id: StringUuid::from(service_id),
+        client_id: "declared-scope-client".to_string(),
+        client_secret_hash: "hash".to_string(),
+        name: None,
+        public_client: false,
+        created_at: Utc::now(),
+        token_ttls: Default::default(),
+    };
+    state.service_repo.add_client(client).await;
+    state
+        .service_scope_repo
+        .upsert(StringUuid::from(service_id), "admin", "Admin access", &[])
+        .await
+        .unwrap();
+
+    let app = build_test_router(state);
+
+    let (status, _body) = get_raw(
+        &app,
+        "/api/v1/auth/authorize?response_type=code&client_id=declared-scope-client&redirect_uri=https://app.example.com/callback&scope=openid+admin&state=csrf-state",
+    ).await;
+
     assert_eq!(status, StatusCode::TEMPORARY_REDIRECT);
 }
 
@@ -1483,7 +1550,7 @@ async fn test_authorize_filters_unsafe_scopes() {
 
 #[tokio::test]
 async fn test_logout_with_session_and_all_params() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let session_id = Uuid::new_v4();
@@ -1503,6 +1570,7 @@ async fn test_logout_with_session_and_all_params() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -1539,7 +1607,7 @@ async fn test_logout_with_session_and_all_params() {
 
 #[tokio::test]
 async fn test_logout_get_with_valid_post_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -1555,6 +1623,7 @@ async fn test_logout_get_with_valid_post_redirect_uri() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -1571,7 +1640,7 @@ async fn test_logout_get_with_valid_post_redirect_uri() {
 
 #[tokio::test]
 async fn test_logout_get_with_post_redirect_uri_rejected_without_client_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // GET logout with post_logout_redirect_uri but no client_id should fail
@@ -1586,7 +1655,7 @@ async fn test_logout_get_with_post_redirect_uri_rejected_without_client_id() {
 
 #[tokio::test]
 async fn test_logout_get_with_invalid_post_redirect_uri() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -1602,6 +1671,7 @@ async fn test_logout_get_with_invalid_post_redirect_uri() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -1618,7 +1688,7 @@ async fn test_logout_get_with_invalid_post_redirect_uri() {
 
 #[tokio::test]
 async fn test_logout_get_with_all_params() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -1634,6 +1704,7 @@ async fn test_logout_get_with_all_params() {
             name: Some("Test Client".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -1654,7 +1725,7 @@ async fn test_logout_get_with_all_params() {
 
 #[tokio::test]
 async fn test_authorize_with_empty_state_rejected() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let service_id = Uuid::new_v4();
     let mut service = create_test_service(Some(service_id), None);
@@ -1669,6 +1740,7 @@ async fn test_authorize_with_empty_state_rejected() {
         name: None,
         public_client: false,
         created_at: Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -1705,7 +1777,7 @@ async fn test_jwks_with_rsa_keys() {
         .unwrap();
 
     // Create state with RSA-based JWT manager
-    let mut state = TestAppState::new("http://localhost:8081");
+    let mut state = TestAppState::new();
     let jwt_config = auth9_core::config::JwtConfig {
         secret: "unused-with-rsa".to_string(),
         issuer: "https://auth9.test".to_string(),
@@ -1714,6 +1786,8 @@ async fn test_jwks_with_rsa_keys() {
         private_key_pem: Some(private_key_pem),
         public_key_pem: Some(public_key_pem),
         previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
     };
     state.jwt_manager = auth9_core::jwt::JwtManager::new(jwt_config);
     let app = build_test_router(state);
@@ -1760,7 +1834,7 @@ async fn test_jwks_with_rsa_keys_and_previous_key() {
         .to_public_key_pem(rsa::pkcs8::LineEnding::LF)
         .unwrap();
 
-    let mut state = TestAppState::new("http://localhost:8081");
+    let mut state = TestAppState::new();
     state.jwt_manager = auth9_core::jwt::JwtManager::new(auth9_core::config::JwtConfig {
         secret: "unused-with-rsa".to_string(),
         issuer: "https://auth9.test".to_string(),
@@ -1769,6 +1843,8 @@ async fn test_jwks_with_rsa_keys_and_previous_key() {
         private_key_pem: Some(current_private_pem),
         public_key_pem: Some(current_public_pem),
         previous_public_key_pem: Some(previous_public_pem),
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
     });
     let app = build_test_router(state);
 
@@ -1803,7 +1879,7 @@ async fn test_openid_configuration_rsa_algorithm() {
         .to_public_key_pem(rsa::pkcs8::LineEnding::LF)
         .unwrap();
 
-    let mut state = TestAppState::new("http://localhost:8081");
+    let mut state = TestAppState::new();
     state.jwt_manager = auth9_core::jwt::JwtManager::new(auth9_core::config::JwtConfig {
         secret: "unused".to_string(),
         issuer: "https://auth9.test".to_string(),
@@ -1812,6 +1888,8 @@ async fn test_openid_configuration_rsa_algorithm() {
         private_key_pem: Some(private_key_pem),
         public_key_pem: Some(public_key_pem),
         previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
     });
     let app = build_test_router(state);
 
@@ -1839,7 +1917,7 @@ async fn test_token_authorization_code_new_user_with_demo_tenant_auto_assign() {
     let _kc_sub = "kc-new-user-demo-tenant";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a "demo" tenant so auto-assign kicks in
     state
@@ -1882,7 +1960,7 @@ async fn test_token_refresh_no_bound_session() {
     let kc_sub = "kc-refresh-no-session";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     // Don't bind refresh token to any session - this triggers the error path
 
     let user = auth9_core::models::user::User {
@@ -1897,6 +1975,8 @@ async fn test_token_refresh_no_bound_session() {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -1925,7 +2005,7 @@ async fn test_token_refresh_no_bound_session() {
 async fn test_token_auth_code_keycloak_exchange_failure() {
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -1946,7 +2026,7 @@ async fn test_token_auth_code_keycloak_exchange_failure() {
 async fn test_token_refresh_keycloak_exchange_failure() {
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state
         .cache_manager
         .bind_refresh_token_session(
@@ -1977,7 +2057,7 @@ async fn test_token_auth_code_userinfo_failure() {
     let _client_uuid = Uuid::new_v4().to_string();
     // Mock userinfo to fail
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -2004,7 +2084,7 @@ async fn test_token_auth_code_with_tenant_triggers_post_login_actions() {
     let kc_sub = "kc-action-user";
     let _client_uuid = Uuid::new_v4().to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a tenant
     let tenant_id = Uuid::new_v4();
@@ -2033,6 +2113,7 @@ async fn test_token_auth_code_with_tenant_triggers_post_login_actions() {
             name: None,
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -2049,6 +2130,8 @@ async fn test_token_auth_code_with_tenant_triggers_post_login_actions() {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -2072,3 +2155,204 @@ async fn test_token_auth_code_with_tenant_triggers_post_login_actions() {
     assert_eq!(body["token_type"], "Bearer");
     assert!(body["access_token"].as_str().is_some());
 }
+
+// ============================================================================
+// Silent authentication (prompt=none) Tests
+// ============================================================================
+
+const SILENT_REDIRECT_URI: &str = "https://spa.example.com/silent-renew";
+
+/// Register a client whose service accepts SILENT_REDIRECT_URI
+async fn add_silent_client(state: &TestAppState) {
+    let service_id = Uuid::new_v4();
+    let mut service = create_test_service(Some(service_id), None);
+    service.redirect_uris = vec![SILENT_REDIRECT_URI.to_string()];
+    state.service_repo.add_service(service).await;
+    state
+        .service_repo
+        .add_client(Client {
+            id: StringUuid::new_v4(),
+            service_id: StringUuid::from(service_id),
+            client_id: "spa-client".to_string(),
+            client_secret_hash: "hash".to_string(),
+            name: Some("SPA".to_string()),
+            public_client: false,
+            created_at: Utc::now(),
+            token_ttls: Default::default(),
+        })
+        .await;
+}
+
+/// Create a user with a session and return an ID token issued in it
+async fn add_session(state: &TestAppState, revoked: bool) -> (Uuid, String) {
+    let user = create_test_user(None);
+    let session = Session {
+        id: StringUuid::new_v4(),
+        user_id: user.id,
+        revoked_at: revoked.then(Utc::now),
+        ..Default::default()
+    };
+    let id_token = state
+        .jwt_manager
+        .create_id_token(
+            *user.id,
+            serde_json::Map::new(),
+            None,
+            "spa-client",
+            Some(*session.id),
+            "access-token",
+        )
+        .unwrap();
+    let session_id = *session.id;
+    state.user_repo.add_user(user).await;
+    state.session_repo.add_session(session).await;
+    (session_id, id_token)
+}
+
+fn silent_authorize_path(id_token_hint: Option<&str>) -> String {
+    let mut path = format!(
+        "/api/v1/auth/authorize?response_type=code&client_id=spa-client&redirect_uri={}&scope=openid&state=renew-1&prompt=none",
+        SILENT_REDIRECT_URI
+    );
+    if let Some(hint) = id_token_hint {
+        path.push_str(&format!("&id_token_hint={}", hint));
+    }
+    path
+}
+
+/// GET `path` and return the status and the redirect target
+async fn get_location(app: &axum::Router, path: &str) -> (StatusCode, url::Url) {
+    let request = axum::http::Request::builder()
+        .uri(path)
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = tower::ServiceExt::oneshot(app.clone(), request)
+        .await
+        .unwrap();
+    let location = response.headers()["location"].to_str().unwrap();
+    (response.status(), url::Url::parse(location).unwrap())
+}
+
+fn query_param(url: &url::Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+#[tokio::test]
+async fn test_authorize_prompt_none_without_hint_returns_login_required() {
+    let state = TestAppState::new();
+    add_silent_client(&state).await;
+    let app = build_test_router(state);
+
+    let (status, location) = get_location(&app, &silent_authorize_path(None)).await;
+
+    assert_eq!(status, StatusCode::TEMPORARY_REDIRECT);
+    assert!(location.as_str().starts_with(SILENT_REDIRECT_URI));
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("login_required")
+    );
+    assert_eq!(query_param(&location, "state").as_deref(), Some("renew-1"));
+}
+
+#[tokio::test]
+async fn test_authorize_prompt_none_without_consent_returns_consent_required() {
+    let state = TestAppState::new();
+    add_silent_client(&state).await;
+    let (_, id_token) = add_session(&state, false).await;
+    let app = build_test_router(state);
+
+    let (_, location) = get_location(&app, &silent_authorize_path(Some(&id_token))).await;
+
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("consent_required")
+    );
+}
+
+#[tokio::test]
+async fn test_authorize_prompt_none_issues_code_for_live_session() {
+    let state = TestAppState::new();
+    add_silent_client(&state).await;
+    let (session_id, id_token) = add_session(&state, false).await;
+    state
+        .cache_manager
+        .store_session_consent(&session_id.to_string(), "spa-client", "openid email", 3600)
+        .await
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, location) = get_location(&app, &silent_authorize_path(Some(&id_token))).await;
+
+    assert_eq!(status, StatusCode::TEMPORARY_REDIRECT);
+    assert!(query_param(&location, "error").is_none());
+    assert!(query_param(&location, "code").is_some());
+    assert_eq!(query_param(&location, "state").as_deref(), Some("renew-1"));
+}
+
+#[tokio::test]
+async fn test_authorize_prompt_none_with_revoked_session_returns_login_required() {
+    let state = TestAppState::new();
+    add_silent_client(&state).await;
+    let (session_id, id_token) = add_session(&state, true).await;
+    state
+        .cache_manager
+        .store_session_consent(&session_id.to_string(), "spa-client", "openid", 3600)
+        .await
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (_, location) = get_location(&app, &silent_authorize_path(Some(&id_token))).await;
+
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("login_required")
+    );
+}
+
+#[tokio::test]
+async fn test_authorize_prompt_none_combined_with_login_is_invalid() {
+    let state = TestAppState::new();
+    add_silent_client(&state).await;
+    let app = build_test_router(state);
+
+    let path = silent_authorize_path(None).replace("prompt=none", "prompt=none%20login");
+    let (_, location) = get_location(&app, &path).await;
+
+    assert_eq!(
+        query_param(&location, "error").as_deref(),
+        Some("invalid_request")
+    );
+}
+
+#[tokio::test]
+async fn test_session_check_reports_session_state() {
+    let state = TestAppState::new();
+    let (_, live_token) = add_session(&state, false).await;
+    let (_, revoked_token) = add_session(&state, true).await;
+    let app = build_test_router(state);
+    let path = |token: &str| {
+        format!(
+            "/api/v1/auth/session/check?client_id=spa-client&id_token_hint={}",
+            token
+        )
+    };
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) =
+        get_json(&app, &path(&live_token)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap()["data"]["active"], true);
+
+    let (_, body): (StatusCode, Option<serde_json::Value>) =
+        get_json(&app, &path(&revoked_token)).await;
+    assert_eq!(body.unwrap()["data"]["active"], false);
+
+    // ID tokens of other clients do not identify the session for this one
+    let (_, body): (StatusCode, Option<serde_json::Value>) = get_json(
+        &app,
+        &path(&live_token).replace("client_id=spa-client", "client_id=other-client"),
+    )
+    .await;
+    assert_eq!(body.unwrap()["data"]["active"], false);
+}
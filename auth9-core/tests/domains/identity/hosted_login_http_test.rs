@@ -1,14 +1,22 @@
 //! Hosted Login API HTTP handler tests
 //!
 //! Tests for the /api/v1/hosted-login/* endpoints:
-//! - password login (success, wrong password, user not found, invalid input)
+//! - password login (success, wrong password, user not found, invalid input,
+//!   username sign-in, pending account deletion, forensic capture)
 //! - logout (with token, without token)
 //! - start/complete password reset
 
 use crate::support::create_test_user;
 use crate::support::http::{post_json, post_json_with_auth, TestAppState};
+use auth9_core::config::LoginForensicsConfig;
 use auth9_core::domains::identity::api::hosted_login::HostedLoginTokenResponse;
+use auth9_core::domains::security_observability::service::LoginForensicsService;
 use auth9_core::http_support::MessageResponse;
+use auth9_core::models::account_deletion::AccountDeletionRequest;
+use auth9_core::models::login_forensics::LoginFailureStage;
+use auth9_core::models::login_identifier::{LoginIdentifier, LoginIdentifierKind, GLOBAL_SCOPE};
+use auth9_core::repository::login_forensics::{LoginFailureFilter, LoginForensicsRepository};
+use auth9_core::repository::{AccountDeletionRepository, LoginIdentifierRepository};
 use axum::http::StatusCode;
 
 // ============================================================================
@@ -19,7 +27,7 @@ use axum::http::StatusCode;
 async fn test_password_login_success() {
     // Mock Keycloak get-user (needed for validate_user_password → get_user)
     // Mock Keycloak token endpoint (simulates valid password)
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user with known identity_subject
     let user = create_test_user(None);
@@ -46,7 +54,7 @@ async fn test_password_login_success() {
 #[ignore = "requires identity engine that can reject passwords — NoOp always returns Ok(true)"]
 async fn test_password_login_wrong_password() {
     // Mock Keycloak get-user + failed password validation
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     state.user_repo.add_user(user).await;
@@ -66,7 +74,7 @@ async fn test_password_login_wrong_password() {
 
 #[tokio::test]
 async fn test_password_login_user_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // No user added to repository
     let app = build_hosted_login_test_router(state);
@@ -82,9 +90,120 @@ async fn test_password_login_user_not_found() {
     assert_eq!(status, StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+async fn test_password_login_with_username() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    state.user_repo.add_user(user.clone()).await;
+    state
+        .login_identifier_repo
+        .assign(&LoginIdentifier {
+            user_id: user.id,
+            kind: LoginIdentifierKind::Username,
+            value: "jdoe".to_string(),
+            scope: GLOBAL_SCOPE.to_string(),
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+    let app = build_hosted_login_test_router(state);
+
+    let input = serde_json::json!({
+        "identifier": "JDoe",
+        "password": "CorrectPassword123!" // pragma: allowlist secret
+    });
+    let (status, body): (StatusCode, Option<HostedLoginTokenResponse>) =
+        post_json(&app, "/api/v1/hosted-login/password", &input).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.unwrap().access_token.is_empty());
+}
+
+#[tokio::test]
+async fn test_password_login_unknown_username() {
+    let state = TestAppState::new();
+    state.user_repo.add_user(create_test_user(None)).await;
+
+    let app = build_hosted_login_test_router(state);
+
+    let input = serde_json::json!({
+        "identifier": "nobody",
+        "password": "SomePassword123!" // pragma: allowlist secret
+    });
+    let (status, _): (StatusCode, Option<MessageResponse>) =
+        post_json(&app, "/api/v1/hosted-login/password", &input).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_password_login_offers_restore_during_deletion_grace_period() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    state.user_repo.add_user(user.clone()).await;
+    let now = chrono::Utc::now();
+    state
+        .account_deletion_repo
+        .create(&AccountDeletionRequest {
+            user_id: user.id,
+            requested_at: now,
+            purge_after: now + chrono::Duration::days(30),
+            reminder_sent_at: None,
+            purge_job_id: None,
+        })
+        .await
+        .unwrap();
+
+    let app = build_hosted_login_test_router(state);
+
+    let input = serde_json::json!({
+        "email": "test@example.com",
+        "password": "CorrectPassword123!" // pragma: allowlist secret
+    });
+    let (status, body): (StatusCode, Option<HostedLoginTokenResponse>) =
+        post_json(&app, "/api/v1/hosted-login/password", &input).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body
+        .unwrap()
+        .pending_actions
+        .iter()
+        .any(|action| action.id == "account_deletion"));
+}
+
+#[tokio::test]
+async fn test_password_login_failure_is_captured_when_forensics_enabled() {
+    let mut state = TestAppState::new();
+    state.login_forensics_service = std::sync::Arc::new(LoginForensicsService::new(
+        state.login_forensics_repo.clone(),
+        LoginForensicsConfig {
+            enabled: true,
+            ..Default::default()
+        },
+    ));
+    let repo = state.login_forensics_repo.clone();
+    let app = build_hosted_login_test_router(state);
+
+    let input = serde_json::json!({
+        "email": "nonexistent@example.com",
+        "password": "SomePassword123!" // pragma: allowlist secret
+    });
+    let (status, _): (StatusCode, Option<MessageResponse>) =
+        post_json(&app, "/api/v1/hosted-login/password", &input).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let captured = repo
+        .search(&LoginFailureFilter::default(), 10)
+        .await
+        .unwrap();
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].failure_stage, LoginFailureStage::UnknownUser);
+}
+
 #[tokio::test]
 async fn test_password_login_empty_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -99,7 +218,7 @@ async fn test_password_login_empty_email() {
 
 #[tokio::test]
 async fn test_password_login_invalid_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -114,7 +233,7 @@ async fn test_password_login_invalid_email() {
 
 #[tokio::test]
 async fn test_password_login_empty_password() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -134,7 +253,7 @@ async fn test_password_login_empty_password() {
 #[tokio::test]
 async fn test_hosted_logout_with_valid_token() {
     // Mock session deletion in Keycloak
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a valid identity token with session
     let session_id = uuid::Uuid::new_v4();
@@ -162,7 +281,7 @@ async fn test_hosted_logout_with_valid_token() {
 
 #[tokio::test]
 async fn test_hosted_logout_without_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({});
@@ -177,7 +296,7 @@ async fn test_hosted_logout_without_token() {
 
 #[tokio::test]
 async fn test_hosted_logout_with_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({});
@@ -195,7 +314,7 @@ async fn test_hosted_logout_with_invalid_token() {
 
 #[tokio::test]
 async fn test_start_password_reset_existing_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let mut user = create_test_user(None);
     user.email = "test@example.com".to_string();
@@ -214,7 +333,7 @@ async fn test_start_password_reset_existing_user() {
 
 #[tokio::test]
 async fn test_start_password_reset_unknown_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -230,7 +349,7 @@ async fn test_start_password_reset_unknown_user() {
 
 #[tokio::test]
 async fn test_start_password_reset_invalid_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -248,7 +367,7 @@ async fn test_start_password_reset_invalid_email() {
 
 #[tokio::test]
 async fn test_complete_password_reset_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
@@ -263,7 +382,7 @@ async fn test_complete_password_reset_invalid_token() {
 
 #[tokio::test]
 async fn test_complete_password_reset_short_password() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_hosted_login_test_router(state);
 
     let input = serde_json::json!({
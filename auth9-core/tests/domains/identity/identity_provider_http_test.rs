@@ -4,6 +4,7 @@
 
 use crate::support::create_test_user;
 use crate::support::http::{delete_json, get_json, post_json, put_json, TestAppState};
+use auth9_core::crypto::{FlowKind, FlowStateCodec};
 use auth9_core::http_support::{MessageResponse, SuccessResponse};
 use auth9_core::models::common::StringUuid;
 use auth9_core::models::identity_provider::{IdentityProvider, IdentityProviderTemplate};
@@ -18,7 +19,7 @@ use chrono::Utc;
 
 #[tokio::test]
 async fn test_get_templates() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -63,7 +64,7 @@ async fn test_get_templates() {
 
 #[tokio::test]
 async fn test_list_linked_identities_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -89,7 +90,7 @@ async fn test_list_linked_identities_empty() {
 
 #[tokio::test]
 async fn test_list_linked_identities_with_data() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -157,7 +158,7 @@ async fn test_list_linked_identities_with_data() {
 
 #[tokio::test]
 async fn test_list_linked_identities_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -174,7 +175,7 @@ async fn test_list_linked_identities_unauthorized() {
 
 #[tokio::test]
 async fn test_unlink_identity_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -231,7 +232,7 @@ async fn test_unlink_identity_success() {
 #[tokio::test]
 #[ignore = "requires federation broker with seeded providers — NoOp returns empty list"]
 async fn test_list_providers_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -246,7 +247,7 @@ async fn test_list_providers_success() {
 
 #[tokio::test]
 async fn test_list_providers_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -266,7 +267,7 @@ async fn test_list_providers_empty() {
 #[tokio::test]
 #[ignore = "requires federation broker with seeded providers — NoOp returns stub with empty alias"]
 async fn test_get_provider_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -282,7 +283,7 @@ async fn test_get_provider_success() {
 #[tokio::test]
 #[ignore = "requires federation broker that returns NotFound — NoOp always returns Ok"]
 async fn test_get_provider_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -299,7 +300,7 @@ async fn test_get_provider_not_found() {
 #[tokio::test]
 #[ignore = "requires federation broker that persists providers — NoOp create succeeds but get returns stub"]
 async fn test_create_provider_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -331,7 +332,7 @@ async fn test_create_provider_success() {
 
 #[tokio::test]
 async fn test_create_provider_validation_error() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -350,7 +351,7 @@ async fn test_create_provider_validation_error() {
 
 #[tokio::test]
 async fn test_create_provider_missing_config_fields() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -376,7 +377,7 @@ async fn test_create_provider_missing_config_fields() {
 
 #[tokio::test]
 async fn test_update_provider_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -395,7 +396,7 @@ async fn test_update_provider_success() {
 #[tokio::test]
 #[ignore = "requires federation broker that returns NotFound — NoOp always returns Ok"]
 async fn test_update_provider_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -415,7 +416,7 @@ async fn test_update_provider_not_found() {
 
 #[tokio::test]
 async fn test_delete_provider_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -436,7 +437,7 @@ async fn test_delete_provider_success() {
 
 #[tokio::test]
 async fn test_confirm_link_expired_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_idp_test_router(state);
 
@@ -451,17 +452,35 @@ async fn test_confirm_link_expired_token() {
     assert_eq!(status, StatusCode::BAD_REQUEST);
 }
 
+fn seal_test_login_challenge(state: &TestAppState) -> String {
+    let challenge = serde_json::json!({
+        "client_id": "test-client",
+        "redirect_uri": "http://localhost:3000/callback",
+        "scope": "openid",
+        "nonce": null,
+        "code_challenge": null,
+        "code_challenge_method": null,
+        "original_state": null
+    });
+    FlowStateCodec::from_config(&state.config.flow_state, &state.config.jwt.secret)
+        .seal(FlowKind::LoginChallenge, &challenge, 600)
+        .unwrap()
+}
+
 #[tokio::test]
 async fn test_confirm_link_success_link_existing() {
     use auth9_core::models::linked_identity::PendingMergeData;
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a user to be the "existing" user
     let user = create_test_user(None);
     let user_id = user.id;
     state.user_repo.add_user(user.clone()).await;
 
+    // Seal the login challenge the confirm flow will consume
+    let login_challenge = seal_test_login_challenge(&state);
+
     // Store pending merge data in cache
     let pending = PendingMergeData {
         existing_user_id: user_id.to_string(),
@@ -471,7 +490,7 @@ async fn test_confirm_link_success_link_existing() {
         provider_type: "google".to_string(),
         external_email: Some("ext@gmail.com".to_string()),
         display_name: Some("External User".to_string()),
-        login_challenge_id: "test-challenge-1".to_string(),
+        login_challenge_id: login_challenge,
         tenant_id: None,
         ip_address: None,
         user_agent: None,
@@ -483,22 +502,6 @@ async fn test_confirm_link_success_link_existing() {
         .await
         .unwrap();
 
-    // Also store the login challenge so the confirm flow can consume it
-    let challenge = serde_json::json!({
-        "client_id": "test-client",
-        "redirect_uri": "http://localhost:3000/callback",
-        "scope": "openid",
-        "nonce": null,
-        "code_challenge": null,
-        "code_challenge_method": null,
-        "original_state": null
-    });
-    state
-        .cache_manager
-        .store_login_challenge("test-challenge-1", &challenge.to_string(), 600)
-        .await
-        .unwrap();
-
     let app = build_idp_test_router(state);
 
     let input = serde_json::json!({
@@ -520,13 +523,15 @@ async fn test_confirm_link_success_link_existing() {
 async fn test_confirm_link_create_new_account() {
     use auth9_core::models::linked_identity::PendingMergeData;
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create an existing user (the one the merge would target)
     let user = create_test_user(None);
     let user_id = user.id;
     state.user_repo.add_user(user.clone()).await;
 
+    let login_challenge = seal_test_login_challenge(&state);
+
     // Store pending merge
     let pending = PendingMergeData {
         existing_user_id: user_id.to_string(),
@@ -536,7 +541,7 @@ async fn test_confirm_link_create_new_account() {
         provider_type: "github".to_string(),
         external_email: Some("newuser@github.com".to_string()),
         display_name: Some("New User".to_string()),
-        login_challenge_id: "test-challenge-2".to_string(),
+        login_challenge_id: login_challenge,
         tenant_id: None,
         ip_address: None,
         user_agent: None,
@@ -548,22 +553,6 @@ async fn test_confirm_link_create_new_account() {
         .await
         .unwrap();
 
-    // Store login challenge
-    let challenge = serde_json::json!({
-        "client_id": "test-client",
-        "redirect_uri": "http://localhost:3000/callback",
-        "scope": "openid",
-        "nonce": null,
-        "code_challenge": null,
-        "code_challenge_method": null,
-        "original_state": null
-    });
-    state
-        .cache_manager
-        .store_login_challenge("test-challenge-2", &challenge.to_string(), 600)
-        .await
-        .unwrap();
-
     let app = build_idp_test_router(state);
 
     // POST with action="create_new" → should create new user instead of linking
@@ -1,6 +1,8 @@
+mod account_recovery_http_test;
 mod auth_http_test;
 mod hosted_login_http_test;
 mod identity_provider_http_test;
 mod password_http_test;
+mod progressive_profiling_http_test;
 mod session_http_test;
 mod webauthn_http_test;
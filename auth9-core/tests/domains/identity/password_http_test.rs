@@ -17,7 +17,7 @@ use axum::http::StatusCode;
 
 #[tokio::test]
 async fn test_forgot_password_existing_user_no_email_configured() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let mut user = create_test_user(None);
@@ -41,7 +41,7 @@ async fn test_forgot_password_existing_user_no_email_configured() {
 
 #[tokio::test]
 async fn test_forgot_password_nonexistent_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -60,7 +60,7 @@ async fn test_forgot_password_nonexistent_user() {
 
 #[tokio::test]
 async fn test_forgot_password_invalid_email_format() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -80,7 +80,7 @@ async fn test_forgot_password_invalid_email_format() {
 
 #[tokio::test]
 async fn test_reset_password_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -97,7 +97,7 @@ async fn test_reset_password_invalid_token() {
 
 #[tokio::test]
 async fn test_reset_password_short_password() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -118,7 +118,7 @@ async fn test_reset_password_short_password() {
 
 #[tokio::test]
 async fn test_get_password_policy_default() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a tenant first so it can be found
     let tenant = crate::support::create_test_tenant(None);
@@ -143,7 +143,7 @@ async fn test_get_password_policy_default() {
 
 #[tokio::test]
 async fn test_update_password_policy() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a tenant first so it can be found
     let tenant = crate::support::create_test_tenant(None);
@@ -182,7 +182,7 @@ async fn test_update_password_policy() {
 
 #[tokio::test]
 async fn test_update_password_policy_invalid_min_length() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
     let token = create_test_identity_token();
@@ -210,7 +210,7 @@ async fn test_update_password_policy_invalid_min_length() {
 
 #[tokio::test]
 async fn test_update_password_policy_member_forbidden() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = crate::support::create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -253,7 +253,7 @@ async fn test_update_password_policy_member_forbidden() {
 
 #[tokio::test]
 async fn test_update_password_policy_owner_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = crate::support::create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -299,7 +299,7 @@ async fn test_update_password_policy_owner_success() {
 
 #[tokio::test]
 async fn test_update_password_policy_admin_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = crate::support::create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -345,7 +345,7 @@ async fn test_update_password_policy_admin_success() {
 
 #[tokio::test]
 async fn test_update_password_policy_service_client_forbidden() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = crate::support::create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -381,7 +381,7 @@ async fn test_update_password_policy_service_client_forbidden() {
 
 #[tokio::test]
 async fn test_get_password_policy_member_allowed() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = crate::support::create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -419,7 +419,7 @@ async fn test_get_password_policy_member_allowed() {
 
 #[tokio::test]
 async fn test_change_password_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -436,7 +436,7 @@ async fn test_change_password_unauthorized() {
 
 #[tokio::test]
 async fn test_change_password_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_password_test_router(state);
 
@@ -452,7 +452,7 @@ async fn test_change_password_invalid_token() {
 
 #[tokio::test]
 async fn test_change_password_user_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a token for a user that doesn't exist in the repository
     let user_id = StringUuid::new_v4();
@@ -475,7 +475,7 @@ async fn test_change_password_user_not_found() {
 
 #[tokio::test]
 async fn test_change_password_validation_error_short_password() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -503,7 +503,7 @@ async fn test_change_password_validation_error_short_password() {
 
 #[tokio::test]
 async fn test_change_password_validation_error_empty_current() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -0,0 +1,111 @@
+//! Progressive profiling API HTTP handler tests
+//!
+//! Covers authentication, service access control and input validation for
+//! the profile requirement and hosted profile form endpoints.
+
+use crate::support::create_test_jwt_manager;
+use crate::support::create_test_service;
+use crate::support::create_test_tenant_access_token;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, put_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn create_non_platform_token(tenant_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "admin@example.com",
+            tenant_id,
+            "test-client",
+            vec!["admin".to_string()],
+            vec!["service:*".to_string()],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_list_profile_requirements_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json(
+        &app,
+        &format!("/api/v1/services/{}/profile-requirements", Uuid::new_v4()),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_profile_requirements_service_not_found() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+    let token = create_test_tenant_access_token();
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/profile-requirements", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_set_profile_requirements_hidden_from_other_tenant() {
+    let state = TestAppState::new();
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), Some(Uuid::new_v4())))
+        .await;
+    let app = build_test_router(state);
+
+    let token = create_non_platform_token(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/profile-requirements", service_id),
+        &json!({ "requirements": [{ "field_name": "company" }] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_set_profile_requirements_rejects_invalid_field_name() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+    let token = create_test_tenant_access_token();
+
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/services/{}/profile-requirements", Uuid::new_v4()),
+        &json!({ "requirements": [{ "scope": "billing", "field_name": "Company Name" }] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_hosted_profile_form_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json(
+        &app,
+        "/api/v1/hosted-login/profile?client_id=app&scope=openid",
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -1,16 +1,17 @@
 //! Session management HTTP API handler tests
 //!
-//! Tests for session listing and revocation endpoints.
+//! Tests for session listing, renaming and revocation endpoints.
 
 use crate::support::create_test_user;
 use crate::support::http::{
     delete_json_with_auth, get_json, get_json_with_auth, post_json, post_json_with_auth,
-    TestAppState,
+    put_json_with_auth, TestAppState,
 };
 use auth9_core::domains::identity::api::session::RevokeSessionsResponse;
 use auth9_core::http_support::{MessageResponse, SuccessResponse};
 use auth9_core::models::common::StringUuid;
 use auth9_core::models::session::{Session, SessionInfo};
+use auth9_core::models::user::TenantUser;
 use auth9_core::repository::SessionRepository;
 use axum::http::StatusCode;
 use chrono::Utc;
@@ -21,7 +22,7 @@ use chrono::Utc;
 
 #[tokio::test]
 async fn test_list_user_sessions_admin() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -44,6 +45,7 @@ async fn test_list_user_sessions_admin() {
             provider_session_id: Some(format!("kc-session-{}", i)),
             device_type: Some("desktop".to_string()),
             device_name: Some(format!("Chrome on macOS {}", i)),
+            display_name: None,
             ip_address: Some("192.168.1.1".to_string()),
             location: Some("San Francisco, US".to_string()),
             user_agent: None,
@@ -72,7 +74,7 @@ async fn test_list_user_sessions_admin() {
 
 #[tokio::test]
 async fn test_list_user_sessions_admin_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -110,7 +112,7 @@ async fn test_list_user_sessions_admin_empty() {
 #[tokio::test]
 async fn test_force_logout_user() {
     // Mock the logout endpoint in Keycloak
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -133,6 +135,7 @@ async fn test_force_logout_user() {
             provider_session_id: Some(format!("kc-session-{}", i)),
             device_type: Some("desktop".to_string()),
             device_name: None,
+            display_name: None,
             ip_address: None,
             location: None,
             user_agent: None,
@@ -171,7 +174,7 @@ async fn test_force_logout_user() {
 #[tokio::test]
 async fn test_force_logout_user_no_sessions() {
     // Mock the logout endpoint in Keycloak
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -204,7 +207,7 @@ async fn test_force_logout_user_no_sessions() {
 
 #[tokio::test]
 async fn test_force_logout_user_rejects_non_admin() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a tenant access token with viewer role (non-admin)
     let tenant_id = uuid::Uuid::new_v4();
@@ -238,13 +241,83 @@ async fn test_force_logout_user_rejects_non_admin() {
     assert_eq!(status, StatusCode::FORBIDDEN);
 }
 
+fn create_tenant_admin_token(state: &TestAppState, tenant_id: uuid::Uuid) -> String {
+    state
+        .jwt_manager
+        .create_tenant_access_token(
+            uuid::Uuid::new_v4(),
+            "admin@acme.com",
+            tenant_id,
+            "test-client",
+            vec!["admin".to_string()],
+            vec![],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_force_logout_user_by_tenant_admin() {
+    let state = TestAppState::new();
+    let tenant_id = uuid::Uuid::new_v4();
+    let token = create_tenant_admin_token(&state, tenant_id);
+
+    let target_user = create_test_user(None);
+    let target_user_id = target_user.id;
+    state.user_repo.add_user(target_user).await;
+    state
+        .user_repo
+        .add_tenant_user(TenantUser {
+            id: StringUuid::new_v4(),
+            user_id: target_user_id,
+            tenant_id: StringUuid::from(tenant_id),
+            role_in_tenant: "member".to_string(),
+            joined_at: Utc::now(),
+        })
+        .await;
+
+    let app = build_session_test_router(state);
+
+    let (status, _body): (StatusCode, Option<SuccessResponse<RevokeSessionsResponse>>) =
+        post_json_with_auth(
+            &app,
+            &format!("/api/v1/admin/users/{}/logout", target_user_id),
+            &(),
+            &token,
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_force_logout_user_outside_tenant_by_tenant_admin() {
+    let state = TestAppState::new();
+    let token = create_tenant_admin_token(&state, uuid::Uuid::new_v4());
+
+    let target_user = create_test_user(None);
+    let target_user_id = target_user.id;
+    state.user_repo.add_user(target_user).await;
+
+    let app = build_session_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/admin/users/{}/logout", target_user_id),
+        &(),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 // ============================================================================
 // Session Info Tests
 // ============================================================================
 
 #[tokio::test]
 async fn test_session_info_device_details() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -264,6 +337,7 @@ async fn test_session_info_device_details() {
         provider_session_id: Some("kc-123".to_string()),
         device_type: Some("mobile".to_string()),
         device_name: Some("Safari on iPhone".to_string()),
+        display_name: None,
         ip_address: Some("10.0.0.1".to_string()),
         location: Some("New York, US".to_string()),
         user_agent: Some("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X)".to_string()),
@@ -304,7 +378,7 @@ async fn test_session_info_device_details() {
 
 #[tokio::test]
 async fn test_list_my_sessions_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -324,6 +398,7 @@ async fn test_list_my_sessions_success() {
             provider_session_id: Some(format!("kc-session-{}", i)),
             device_type: Some("desktop".to_string()),
             device_name: Some(format!("Chrome on macOS {}", i)),
+            display_name: None,
             ip_address: Some("192.168.1.1".to_string()),
             location: Some("San Francisco, US".to_string()),
             user_agent: None,
@@ -358,7 +433,7 @@ async fn test_list_my_sessions_success() {
 
 #[tokio::test]
 async fn test_list_my_sessions_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user with no sessions
     let user = create_test_user(None);
@@ -390,7 +465,7 @@ async fn test_list_my_sessions_empty() {
 
 #[tokio::test]
 async fn test_list_my_sessions_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_my_session_test_router(state);
 
@@ -403,7 +478,7 @@ async fn test_list_my_sessions_unauthorized() {
 
 #[tokio::test]
 async fn test_list_my_sessions_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_my_session_test_router(state);
 
@@ -416,7 +491,7 @@ async fn test_list_my_sessions_invalid_token() {
 
 #[tokio::test]
 async fn test_revoke_session_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -431,6 +506,7 @@ async fn test_revoke_session_success() {
         provider_session_id: Some("kc-current-session".to_string()),
         device_type: Some("desktop".to_string()),
         device_name: None,
+        display_name: None,
         ip_address: None,
         location: None,
         user_agent: None,
@@ -448,6 +524,7 @@ async fn test_revoke_session_success() {
         provider_session_id: Some("kc-session-to-revoke".to_string()),
         device_type: Some("desktop".to_string()),
         device_name: None,
+        display_name: None,
         ip_address: None,
         location: None,
         user_agent: None,
@@ -489,7 +566,7 @@ async fn test_revoke_session_success() {
 
 #[tokio::test]
 async fn test_revoke_current_session_rejected() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -502,6 +579,7 @@ async fn test_revoke_current_session_rejected() {
         provider_session_id: Some("kc-current".to_string()),
         device_type: Some("desktop".to_string()),
         device_name: Some("Chrome".to_string()),
+        display_name: None,
         ip_address: None,
         location: None,
         user_agent: None,
@@ -552,7 +630,7 @@ async fn test_revoke_current_session_rejected() {
 
 #[tokio::test]
 async fn test_revoke_session_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user (but no sessions)
     let user = create_test_user(None);
@@ -586,7 +664,7 @@ async fn test_revoke_session_not_found() {
 
 #[tokio::test]
 async fn test_revoke_session_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_my_session_test_router(state);
 
@@ -601,7 +679,7 @@ async fn test_revoke_session_unauthorized() {
 
 #[tokio::test]
 async fn test_revoke_other_sessions_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -616,6 +694,7 @@ async fn test_revoke_other_sessions_success() {
         provider_session_id: Some("kc-current-session".to_string()),
         device_type: Some("desktop".to_string()),
         device_name: None,
+        display_name: None,
         ip_address: None,
         location: None,
         user_agent: None,
@@ -633,6 +712,7 @@ async fn test_revoke_other_sessions_success() {
             provider_session_id: Some(format!("kc-session-{}", i)),
             device_type: Some("desktop".to_string()),
             device_name: None,
+            display_name: None,
             ip_address: None,
             location: None,
             user_agent: None,
@@ -678,7 +758,7 @@ async fn test_revoke_other_sessions_success() {
 
 #[tokio::test]
 async fn test_revoke_other_sessions_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_my_session_test_router(state);
 
@@ -691,7 +771,7 @@ async fn test_revoke_other_sessions_unauthorized() {
 
 #[tokio::test]
 async fn test_revoke_other_sessions_no_other_sessions() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user with no sessions
     let user = create_test_user(None);
@@ -721,6 +801,239 @@ async fn test_revoke_other_sessions_no_other_sessions() {
     assert_eq!(response.revoked_count, 0);
 }
 
+// ============================================================================
+// Device Management Tests
+// ============================================================================
+
+fn test_session(user_id: StringUuid, device_name: Option<&str>) -> Session {
+    Session {
+        id: StringUuid::new_v4(),
+        user_id,
+        provider_session_id: None,
+        device_type: Some("desktop".to_string()),
+        device_name: device_name.map(str::to_string),
+        display_name: None,
+        ip_address: Some("203.0.113.7".to_string()),
+        location: Some("Berlin, Germany".to_string()),
+        user_agent: None,
+        last_active_at: Utc::now(),
+        created_at: Utc::now(),
+        revoked_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_rename_session_success() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+
+    let current = test_session(user_id, Some("Chrome on macOS"));
+    let current_session_id = current.id;
+    state.session_repo.add_session(current).await;
+    let other = test_session(user_id, Some("Safari on iOS"));
+    let other_id = other.id;
+    state.session_repo.add_session(other).await;
+
+    let token = state
+        .jwt_manager
+        .create_identity_token_with_session(
+            *user_id,
+            "test@example.com",
+            Some("Test User"),
+            Some(*current_session_id),
+        )
+        .unwrap();
+    let app = build_my_session_test_router(state.clone());
+
+    let (status, body): (StatusCode, Option<SuccessResponse<SessionInfo>>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/me/sessions/{}", other_id),
+        &serde_json::json!({ "name": "  My iPhone " }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let info = body.unwrap().data;
+    assert_eq!(info.name, "My iPhone");
+    assert_eq!(info.device_name.as_deref(), Some("Safari on iOS"));
+    assert!(!info.is_current);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Vec<SessionInfo>>>) =
+        get_json_with_auth(&app, "/api/v1/me/sessions", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let sessions = body.unwrap().data;
+    let renamed = sessions
+        .iter()
+        .find(|s| s.id == other_id.to_string())
+        .unwrap();
+    assert_eq!(renamed.name, "My iPhone");
+    assert_eq!(renamed.location.as_deref(), Some("Berlin, Germany"));
+
+    // Clearing the name falls back to the detected device name
+    let (status, body): (StatusCode, Option<SuccessResponse<SessionInfo>>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/me/sessions/{}", other_id),
+        &serde_json::json!({}),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.name, "Safari on iOS");
+}
+
+#[tokio::test]
+async fn test_rename_session_of_other_user_forbidden() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+
+    let foreign = test_session(StringUuid::new_v4(), Some("Firefox on Linux"));
+    let foreign_id = foreign.id;
+    state.session_repo.add_session(foreign).await;
+
+    let token = state
+        .jwt_manager
+        .create_identity_token_with_session(
+            *user_id,
+            "test@example.com",
+            Some("Test User"),
+            Some(uuid::Uuid::new_v4()),
+        )
+        .unwrap();
+    let app = build_my_session_test_router(state);
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/me/sessions/{}", foreign_id),
+        &serde_json::json!({ "name": "Mine now" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_rename_session_rejects_invalid_name() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+
+    let session = test_session(user_id, None);
+    let session_id = session.id;
+    state.session_repo.add_session(session).await;
+
+    let token = state
+        .jwt_manager
+        .create_identity_token_with_session(
+            *user_id,
+            "test@example.com",
+            Some("Test User"),
+            Some(*session_id),
+        )
+        .unwrap();
+    let app = build_my_session_test_router(state);
+    let path = format!("/api/v1/me/sessions/{}", session_id);
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &path,
+        &serde_json::json!({ "name": "x".repeat(101) }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) =
+        put_json_with_auth(&app, &path, &serde_json::json!({ "name": "   " }), &token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_sign_out_everywhere_revokes_current_session_too() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+
+    let current = test_session(user_id, Some("Chrome on macOS"));
+    let current_session_id = current.id;
+    state.session_repo.add_session(current).await;
+    for _ in 0..2 {
+        state
+            .session_repo
+            .add_session(test_session(user_id, None))
+            .await;
+    }
+
+    let token = state
+        .jwt_manager
+        .create_identity_token_with_session(
+            *user_id,
+            "test@example.com",
+            Some("Test User"),
+            Some(*current_session_id),
+        )
+        .unwrap();
+    let app = build_my_session_test_router(state.clone());
+
+    let (status, body): (StatusCode, Option<SuccessResponse<RevokeSessionsResponse>>) =
+        post_json_with_auth(&app, "/api/v1/me/sessions/sign-out-everywhere", &(), &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.revoked_count, 3);
+
+    let remaining = state
+        .session_repo
+        .list_active_by_user(user_id)
+        .await
+        .unwrap();
+    assert!(remaining.is_empty());
+}
+
+#[tokio::test]
+async fn test_sign_out_everywhere_unauthorized() {
+    let state = TestAppState::new();
+    let app = build_my_session_test_router(state);
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) =
+        post_json(&app, "/api/v1/me/sessions/sign-out-everywhere", &()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_sign_in_report_rejects_forged_token() {
+    let state = TestAppState::new();
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+    state
+        .session_repo
+        .add_session(test_session(user_id, None))
+        .await;
+    let app = build_sign_in_report_test_router(state.clone());
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = post_json(
+        &app,
+        &format!(
+            "/api/v1/notifications/sign-in-report?token={}.forged",
+            user_id
+        ),
+        &(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let remaining = state
+        .session_repo
+        .list_active_by_user(user_id)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 1);
+}
+
 // ============================================================================
 // Test Router Builder
 // ============================================================================
@@ -752,11 +1065,29 @@ fn build_my_session_test_router(state: TestAppState) -> axum::Router {
         )
         .route(
             "/api/v1/me/sessions/{session_id}",
-            delete(session::revoke_session::<TestAppState>),
+            delete(session::revoke_session::<TestAppState>)
+                .put(session::rename_session::<TestAppState>),
         )
         .route(
             "/api/v1/me/sessions/revoke-others",
             post(session::revoke_other_sessions::<TestAppState>),
         )
+        .route(
+            "/api/v1/me/sessions/sign-out-everywhere",
+            post(session::sign_out_everywhere::<TestAppState>),
+        )
+        .with_state(state)
+}
+
+fn build_sign_in_report_test_router(state: TestAppState) -> axum::Router {
+    use auth9_core::domains::identity::api::session;
+    use axum::routing::get;
+
+    axum::Router::new()
+        .route(
+            "/api/v1/notifications/sign-in-report",
+            get(session::get_sign_in_report::<TestAppState>)
+                .post(session::report_sign_in::<TestAppState>),
+        )
         .with_state(state)
 }
@@ -16,7 +16,7 @@ use axum::http::StatusCode;
 
 #[tokio::test]
 async fn test_list_passkeys_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add a test user
     let user = create_test_user(None);
@@ -43,7 +43,7 @@ async fn test_list_passkeys_success() {
 
 #[tokio::test]
 async fn test_list_passkeys_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -67,7 +67,7 @@ async fn test_list_passkeys_empty() {
 
 #[tokio::test]
 async fn test_list_passkeys_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_passkey_test_router(state);
 
@@ -79,7 +79,7 @@ async fn test_list_passkeys_unauthorized() {
 
 #[tokio::test]
 async fn test_list_passkeys_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_passkey_test_router(state);
 
@@ -95,7 +95,7 @@ async fn test_list_passkeys_invalid_token() {
 
 #[tokio::test]
 async fn test_delete_passkey_keycloak_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -119,7 +119,7 @@ async fn test_delete_passkey_keycloak_success() {
 
 #[tokio::test]
 async fn test_delete_passkey_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_passkey_test_router(state);
 
@@ -131,7 +131,7 @@ async fn test_delete_passkey_unauthorized() {
 
 #[tokio::test]
 async fn test_delete_passkey_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_passkey_test_router(state);
 
@@ -61,6 +61,7 @@ async fn setup_tenant_and_service(state: &TestAppState, tenant_id: Uuid) -> Uuid
         name: Some("Test Client".to_string()),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client).await;
 
@@ -80,7 +81,7 @@ fn create_action_for_service(tenant_id: Uuid, service_id: Uuid, name: &str) -> A
 
 #[tokio::test]
 async fn test_create_action_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -123,7 +124,7 @@ async fn test_create_action_returns_200() {
 
 #[tokio::test]
 async fn test_create_action_allows_portal_audience_token_across_services() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -168,7 +169,7 @@ async fn test_create_action_allows_portal_audience_token_across_services() {
 
 #[tokio::test]
 async fn test_create_action_validates_input() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -208,7 +209,7 @@ async fn test_create_action_validates_input() {
 
 #[tokio::test]
 async fn test_create_action_rejects_duplicate_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -251,7 +252,7 @@ async fn test_create_action_rejects_duplicate_name() {
 
 #[tokio::test]
 async fn test_create_action_validates_trigger_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -299,7 +300,7 @@ async fn test_create_action_validates_trigger_id() {
 
 #[tokio::test]
 async fn test_list_actions_returns_all() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -335,7 +336,7 @@ async fn test_list_actions_returns_all() {
 
 #[tokio::test]
 async fn test_list_actions_filters_by_trigger() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -382,7 +383,7 @@ async fn test_list_actions_filters_by_trigger() {
 
 #[tokio::test]
 async fn test_list_actions_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -416,7 +417,7 @@ async fn test_list_actions_empty() {
 
 #[tokio::test]
 async fn test_get_action_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -451,7 +452,7 @@ async fn test_get_action_returns_200() {
 
 #[tokio::test]
 async fn test_get_action_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -482,7 +483,7 @@ async fn test_get_action_returns_404() {
 
 #[tokio::test]
 async fn test_update_action_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -528,7 +529,7 @@ async fn test_update_action_returns_200() {
 
 #[tokio::test]
 async fn test_update_action_validates_input() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -571,7 +572,7 @@ async fn test_update_action_validates_input() {
 
 #[tokio::test]
 async fn test_update_action_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -613,7 +614,7 @@ async fn test_update_action_returns_404() {
 
 #[tokio::test]
 async fn test_delete_action_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -645,7 +646,7 @@ async fn test_delete_action_returns_200() {
 
 #[tokio::test]
 async fn test_delete_action_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -680,7 +681,7 @@ async fn test_delete_action_returns_404() {
 
 #[tokio::test]
 async fn test_batch_upsert_creates_new() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -726,7 +727,7 @@ async fn test_batch_upsert_creates_new() {
 
 #[tokio::test]
 async fn test_batch_upsert_updates_existing() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -770,7 +771,7 @@ async fn test_batch_upsert_updates_existing() {
 
 #[tokio::test]
 async fn test_batch_upsert_handles_errors() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -812,7 +813,7 @@ async fn test_batch_upsert_handles_errors() {
 
 #[tokio::test]
 async fn test_query_logs_returns_all() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -841,7 +842,7 @@ async fn test_query_logs_returns_all() {
 
 #[tokio::test]
 async fn test_query_logs_filters_by_action_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -875,7 +876,7 @@ async fn test_query_logs_filters_by_action_id() {
 
 #[tokio::test]
 async fn test_query_logs_filters_by_user_id() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -905,7 +906,7 @@ async fn test_query_logs_filters_by_user_id() {
 
 #[tokio::test]
 async fn test_query_logs_filters_by_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -932,7 +933,7 @@ async fn test_query_logs_filters_by_success() {
 
 #[tokio::test]
 async fn test_get_stats_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -967,7 +968,7 @@ async fn test_get_stats_returns_200() {
 
 #[tokio::test]
 async fn test_get_stats_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -998,7 +999,7 @@ async fn test_get_stats_returns_404() {
 
 #[tokio::test]
 async fn test_get_triggers_returns_list() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token();
 
     let app = build_test_router(state.clone());
@@ -1017,7 +1018,7 @@ async fn test_get_triggers_returns_list() {
 
 #[tokio::test]
 async fn test_platform_admin_can_create_action() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
 
     let service_id = setup_tenant_and_service(&state, tenant_id).await;
@@ -1049,7 +1050,7 @@ async fn test_platform_admin_can_create_action() {
 
 #[tokio::test]
 async fn test_platform_admin_can_read_action() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
 
     let service_id = setup_tenant_and_service(&state, tenant_id).await;
@@ -1073,7 +1074,7 @@ async fn test_platform_admin_can_read_action() {
 
 #[tokio::test]
 async fn test_tenant_admin_can_manage_actions() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1112,7 +1113,7 @@ async fn test_tenant_admin_can_manage_actions() {
 
 #[tokio::test]
 async fn test_tenant_owner_can_manage_actions() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1151,7 +1152,7 @@ async fn test_tenant_owner_can_manage_actions() {
 
 #[tokio::test]
 async fn test_action_read_permission_allows_read() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1182,7 +1183,7 @@ async fn test_action_read_permission_allows_read() {
 
 #[tokio::test]
 async fn test_action_write_permission_allows_write() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1221,7 +1222,7 @@ async fn test_action_write_permission_allows_write() {
 
 #[tokio::test]
 async fn test_action_wildcard_permission_allows_all() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1260,7 +1261,7 @@ async fn test_action_wildcard_permission_allows_all() {
 
 #[tokio::test]
 async fn test_missing_permission_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1299,7 +1300,7 @@ async fn test_missing_permission_returns_403() {
 
 #[tokio::test]
 async fn test_cross_service_access_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1318,6 +1319,7 @@ async fn test_cross_service_access_returns_403() {
         name: Some("Service B Client".to_string()),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     };
     state.service_repo.add_client(client_b).await;
 
@@ -1381,7 +1383,7 @@ async fn test_cross_service_access_returns_403() {
 
 #[tokio::test]
 async fn test_cross_service_access_allowed_for_same_service() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1414,7 +1416,7 @@ async fn test_cross_service_access_allowed_for_same_service() {
 
 #[tokio::test]
 async fn test_missing_token_returns_401() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let service_id = Uuid::new_v4();
 
     let input = CreateActionInput {
@@ -1448,7 +1450,7 @@ async fn test_missing_token_returns_401() {
 
 #[tokio::test]
 async fn test_invalid_token_returns_401() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let service_id = Uuid::new_v4();
 
     let input = CreateActionInput {
@@ -1480,7 +1482,7 @@ async fn test_invalid_token_returns_401() {
 
 #[tokio::test]
 async fn test_get_action_from_different_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id_1 = Uuid::new_v4();
     let tenant_id_2 = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -1515,7 +1517,7 @@ async fn test_get_action_from_different_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_update_action_from_different_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id_1 = Uuid::new_v4();
     let tenant_id_2 = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -1563,7 +1565,7 @@ async fn test_update_action_from_different_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_delete_action_from_different_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id_1 = Uuid::new_v4();
     let tenant_id_2 = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -1598,7 +1600,7 @@ async fn test_delete_action_from_different_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_list_actions_only_returns_own_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id_1 = Uuid::new_v4();
     let tenant_id_2 = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -1648,7 +1650,7 @@ async fn test_list_actions_only_returns_own_tenant() {
 
 #[tokio::test]
 async fn test_query_logs_only_returns_own_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id_1 = Uuid::new_v4();
     let tenant_id_2 = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -1684,7 +1686,7 @@ async fn test_query_logs_only_returns_own_tenant() {
 
 #[tokio::test]
 async fn test_test_action_returns_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1746,7 +1748,7 @@ async fn test_test_action_returns_success() {
 
 #[tokio::test]
 async fn test_test_action_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
@@ -1804,7 +1806,7 @@ async fn test_test_action_not_found() {
 
 #[tokio::test]
 async fn test_get_action_log_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
 
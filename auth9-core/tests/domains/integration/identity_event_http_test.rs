@@ -70,7 +70,7 @@ fn compute_signature(secret: &str, body: &[u8]) -> String {
 
 #[tokio::test]
 async fn test_receive_login_event_success() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -96,7 +96,7 @@ async fn test_receive_login_event_success() {
 
 #[tokio::test]
 async fn test_receive_login_error_event() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -122,7 +122,7 @@ async fn test_receive_login_error_event() {
 
 #[tokio::test]
 async fn test_receive_mfa_failure_event() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -146,7 +146,7 @@ async fn test_receive_mfa_failure_event() {
 
 #[tokio::test]
 async fn test_receive_social_login_event() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -170,7 +170,7 @@ async fn test_receive_social_login_event() {
 
 #[tokio::test]
 async fn test_receive_lockout_event() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -191,7 +191,7 @@ async fn test_receive_lockout_event() {
 
 #[tokio::test]
 async fn test_receive_multi_tenant_event_without_explicit_context_keeps_tenant_empty() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let user_id = StringUuid::new_v4();
@@ -252,7 +252,7 @@ async fn test_receive_multi_tenant_event_without_explicit_context_keeps_tenant_e
 
 #[tokio::test]
 async fn test_receive_multi_tenant_event_uses_tenant_scoped_client_match() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let user_id = StringUuid::new_v4();
@@ -301,6 +301,7 @@ async fn test_receive_multi_tenant_event_uses_tenant_scoped_client_match() {
             name: Some("Tenant B Key".to_string()),
             public_client: false,
             created_at: Utc::now(),
+            token_ttls: Default::default(),
         })
         .await;
 
@@ -332,7 +333,7 @@ async fn test_receive_multi_tenant_event_uses_tenant_scoped_client_match() {
 
 #[tokio::test]
 async fn test_receive_admin_event_skipped() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -357,7 +358,7 @@ async fn test_receive_admin_event_skipped() {
 
 #[tokio::test]
 async fn test_receive_non_login_event_skipped() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -381,7 +382,7 @@ async fn test_receive_non_login_event_skipped() {
 
 #[tokio::test]
 async fn test_receive_malformed_json() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state);
 
     let body = b"not valid json at all{{{";
@@ -396,7 +397,7 @@ async fn test_receive_malformed_json() {
 
 #[tokio::test]
 async fn test_receive_with_valid_signature() {
-    let mut state = TestAppState::new("http://mock-keycloak");
+    let mut state = TestAppState::new();
     let secret = "test-webhook-secret";
     // Set webhook secret in config
     let mut config = (*state.config).clone();
@@ -428,7 +429,7 @@ async fn test_receive_with_valid_signature() {
 
 #[tokio::test]
 async fn test_receive_with_invalid_signature() {
-    let mut state = TestAppState::new("http://mock-keycloak");
+    let mut state = TestAppState::new();
     let mut config = (*state.config).clone();
     config.webhook_secret = Some("real-secret".to_string());
     state.config = std::sync::Arc::new(config);
@@ -455,7 +456,7 @@ async fn test_receive_with_invalid_signature() {
 
 #[tokio::test]
 async fn test_receive_missing_signature_when_required() {
-    let mut state = TestAppState::new("http://mock-keycloak");
+    let mut state = TestAppState::new();
     let mut config = (*state.config).clone();
     config.webhook_secret = Some("secret".to_string());
     state.config = std::sync::Arc::new(config);
@@ -479,7 +480,7 @@ async fn test_receive_missing_signature_when_required() {
 
 #[tokio::test]
 async fn test_receive_email_from_username_fallback() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -507,7 +508,7 @@ async fn test_receive_email_from_username_fallback() {
 
 #[tokio::test]
 async fn test_receive_extracts_user_agent() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -536,7 +537,7 @@ async fn test_receive_extracts_user_agent() {
 
 #[tokio::test]
 async fn test_receive_prefers_forwarded_user_agent() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -569,7 +570,7 @@ async fn test_receive_prefers_forwarded_user_agent() {
 
 #[tokio::test]
 async fn test_receive_ip_from_keycloak_payload() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -593,7 +594,7 @@ async fn test_receive_ip_from_keycloak_payload() {
 
 #[tokio::test]
 async fn test_receive_ip_fallback_from_headers() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     // No ipAddress in payload — should fall back to X-Forwarded-For
@@ -621,7 +622,7 @@ async fn test_receive_ip_fallback_from_headers() {
 
 #[tokio::test]
 async fn test_receive_private_ip_location_is_local_network() {
-    let state = TestAppState::new("http://mock-keycloak");
+    let state = TestAppState::new();
     let app = build_identity_event_test_router(state.clone());
 
     let body = serde_json::json!({
@@ -6,12 +6,15 @@ use crate::support::http::{
     delete_json_with_auth, get_json_with_auth, post_json_with_auth, put_json_with_auth,
     TestAppState,
 };
-use crate::support::{create_test_identity_token, create_test_tenant};
-use auth9_core::domains::integration::service::WebhookTestResult;
-use auth9_core::http_support::{MessageResponse, SuccessResponse};
-use auth9_core::models::analytics::Webhook;
+use crate::support::{
+    create_test_identity_token, create_test_identity_token_for_user, create_test_tenant,
+};
+use auth9_core::domains::integration::service::{WebhookPingResult, WebhookTestResult};
+use auth9_core::http_support::{MessageResponse, PaginatedResponse, SuccessResponse};
+use auth9_core::models::analytics::{Webhook, WebhookEvent};
 use auth9_core::models::common::StringUuid;
-use auth9_core::repository::WebhookRepository;
+use auth9_core::models::webhook_delivery::{WebhookDelivery, WebhookDeliverySource};
+use auth9_core::repository::{WebhookDeliveryRepository, WebhookRepository};
 use axum::http::StatusCode;
 use chrono::Utc;
 use serde::{de::DeserializeOwned, Serialize};
@@ -56,7 +59,7 @@ where
 
 #[tokio::test]
 async fn test_list_webhooks_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -73,9 +76,28 @@ async fn test_list_webhooks_empty() {
     assert!(webhooks.is_empty());
 }
 
+#[tokio::test]
+async fn test_list_webhooks_rejects_list_grammar_parameters() {
+    let state = TestAppState::new();
+
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks?sort=name", tenant_id),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_list_webhooks_with_data() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -85,14 +107,16 @@ async fn test_list_webhooks_with_data() {
     for i in 0..3 {
         let webhook = Webhook {
             id: StringUuid::new_v4(),
-            tenant_id,
+            tenant_id: Some(tenant_id),
             name: format!("Webhook {}", i),
             url: format!("https://example.com/hook/{}", i),
             secret: Some("secret".to_string()),
             events: vec!["login.success".to_string()],
+            filter_expression: None,
             enabled: true,
             last_triggered_at: None,
             failure_count: 0,
+            version: 1,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -116,7 +140,7 @@ async fn test_list_webhooks_with_data() {
 
 #[tokio::test]
 async fn test_get_webhook_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -124,14 +148,16 @@ async fn test_get_webhook_success() {
 
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id,
+        tenant_id: Some(tenant_id),
         name: "Test Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some("secret123".to_string()),
         events: vec!["login.success".to_string(), "login.failed".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -155,7 +181,7 @@ async fn test_get_webhook_success() {
 
 #[tokio::test]
 async fn test_get_webhook_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -175,7 +201,7 @@ async fn test_get_webhook_not_found() {
 
 #[tokio::test]
 async fn test_get_webhook_wrong_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant1 = create_test_tenant(None);
     let tenant1_id = tenant1.id;
@@ -188,14 +214,16 @@ async fn test_get_webhook_wrong_tenant() {
     // Webhook belongs to tenant1
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id: tenant1_id,
+        tenant_id: Some(tenant1_id),
         name: "Test Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: None,
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -220,7 +248,7 @@ async fn test_get_webhook_wrong_tenant() {
 
 #[tokio::test]
 async fn test_create_webhook_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -252,9 +280,67 @@ async fn test_create_webhook_success() {
     assert!(webhook.enabled);
 }
 
+#[tokio::test]
+async fn test_create_webhook_with_filter_expression() {
+    let state = TestAppState::new();
+
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_webhook_test_router(state);
+
+    let input = serde_json::json!({
+        "name": "Admin changes",
+        "url": "https://example.com/admin-hook",
+        "events": ["user.updated"],
+        "filter_expression": "data.role == \"admin\""
+    });
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Webhook>>) = post_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks", tenant_id),
+        &input,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body.unwrap().data.filter_expression.as_deref(),
+        Some("data.role == \"admin\"")
+    );
+}
+
+#[tokio::test]
+async fn test_create_webhook_rejects_invalid_filter_expression() {
+    let state = TestAppState::new();
+
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_webhook_test_router(state);
+
+    let input = serde_json::json!({
+        "name": "Broken filter",
+        "url": "https://example.com/hook",
+        "events": ["user.updated"],
+        "filter_expression": "data.role =="
+    });
+
+    let (status, _): (StatusCode, Option<SuccessResponse<Webhook>>) = post_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks", tenant_id),
+        &input,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_create_webhook_validation_error_empty_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -282,7 +368,7 @@ async fn test_create_webhook_validation_error_empty_name() {
 
 #[tokio::test]
 async fn test_create_webhook_validation_error_invalid_url() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -314,7 +400,7 @@ async fn test_create_webhook_validation_error_invalid_url() {
 
 #[tokio::test]
 async fn test_update_webhook_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -322,14 +408,16 @@ async fn test_update_webhook_success() {
 
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id,
+        tenant_id: Some(tenant_id),
         name: "Original Name".to_string(),
         url: "https://example.com/original".to_string(),
         secret: None,
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -357,6 +445,72 @@ async fn test_update_webhook_success() {
     assert_eq!(webhook.name, "Updated Name");
     assert!(!webhook.enabled);
     assert_eq!(webhook.events, vec!["user.created".to_string()]);
+    assert_eq!(webhook.version, 2);
+}
+
+#[tokio::test]
+async fn test_update_webhook_with_stale_if_match_returns_412() {
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use tower::ServiceExt;
+
+    let state = TestAppState::new();
+
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let webhook = Webhook {
+        tenant_id: Some(tenant_id),
+        name: "Original Name".to_string(),
+        url: "https://example.com/original".to_string(),
+        events: vec!["login.success".to_string()],
+        ..Default::default()
+    };
+    let webhook_id = webhook.id;
+    state.webhook_repo.add_webhook(webhook).await;
+
+    let app = build_webhook_test_router(state);
+    let token = create_test_identity_token();
+    let put_if_match = |url: &'static str| {
+        Request::builder()
+            .method(Method::PUT)
+            .uri(format!(
+                "/api/v1/tenants/{}/webhooks/{}",
+                tenant_id, webhook_id
+            ))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("If-Match", "\"1\"")
+            .body(Body::from(serde_json::json!({ "url": url }).to_string()))
+            .unwrap()
+    };
+
+    // First editor holds the current version and succeeds
+    let response = app
+        .clone()
+        .oneshot(put_if_match("https://example.com/first"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("etag").unwrap(), "\"2\"");
+
+    // Second editor read version 1 and must not overwrite the change
+    let response = app
+        .clone()
+        .oneshot(put_if_match("https://example.com/second"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(response.headers().get("etag").unwrap(), "\"2\"");
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Webhook>>) = get_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks/{}", tenant_id, webhook_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.url, "https://example.com/first");
 }
 
 // ============================================================================
@@ -365,7 +519,7 @@ async fn test_update_webhook_success() {
 
 #[tokio::test]
 async fn test_delete_webhook_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -373,14 +527,16 @@ async fn test_delete_webhook_success() {
 
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id,
+        tenant_id: Some(tenant_id),
         name: "To Delete".to_string(),
         url: "https://example.com/delete".to_string(),
         secret: None,
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -406,7 +562,7 @@ async fn test_delete_webhook_success() {
 
 #[tokio::test]
 async fn test_delete_webhook_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -430,7 +586,7 @@ async fn test_delete_webhook_not_found() {
 
 #[tokio::test]
 async fn test_update_webhook_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -457,7 +613,7 @@ async fn test_update_webhook_not_found() {
 
 #[tokio::test]
 async fn test_update_webhook_wrong_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant1 = create_test_tenant(None);
     let tenant1_id = tenant1.id;
@@ -470,14 +626,16 @@ async fn test_update_webhook_wrong_tenant() {
     // Webhook belongs to tenant1
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id: tenant1_id,
+        tenant_id: Some(tenant1_id),
         name: "Tenant1 Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some("secret123".to_string()),
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -508,7 +666,7 @@ async fn test_update_webhook_wrong_tenant() {
 
 #[tokio::test]
 async fn test_create_webhook_tenant_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Don't create a tenant - use a random ID
     let nonexistent_tenant_id = StringUuid::new_v4();
@@ -539,7 +697,7 @@ async fn test_create_webhook_tenant_not_found() {
 
 #[tokio::test]
 async fn test_regenerate_webhook_secret_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -548,14 +706,16 @@ async fn test_regenerate_webhook_secret_success() {
     let original_secret = "original-secret-value".to_string();
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id,
+        tenant_id: Some(tenant_id),
         name: "Regenerate Test".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some(original_secret.clone()),
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -587,7 +747,7 @@ async fn test_regenerate_webhook_secret_success() {
 
 #[tokio::test]
 async fn test_regenerate_webhook_secret_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -611,7 +771,7 @@ async fn test_regenerate_webhook_secret_not_found() {
 
 #[tokio::test]
 async fn test_regenerate_webhook_secret_wrong_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant1 = create_test_tenant(None);
     let tenant1_id = tenant1.id;
@@ -624,14 +784,16 @@ async fn test_regenerate_webhook_secret_wrong_tenant() {
     // Webhook belongs to tenant1
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id: tenant1_id,
+        tenant_id: Some(tenant1_id),
         name: "Tenant1 Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some("secret123".to_string()),
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -660,7 +822,7 @@ async fn test_regenerate_webhook_secret_wrong_tenant() {
 
 #[tokio::test]
 async fn test_webhook_test_endpoint() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -668,14 +830,16 @@ async fn test_webhook_test_endpoint() {
 
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id,
+        tenant_id: Some(tenant_id),
         name: "Test Endpoint Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some("secret123".to_string()),
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -703,7 +867,7 @@ async fn test_webhook_test_endpoint() {
 
 #[tokio::test]
 async fn test_webhook_test_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -727,7 +891,7 @@ async fn test_webhook_test_not_found() {
 
 #[tokio::test]
 async fn test_webhook_test_wrong_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant1 = create_test_tenant(None);
     let tenant1_id = tenant1.id;
@@ -740,14 +904,16 @@ async fn test_webhook_test_wrong_tenant() {
     // Webhook belongs to tenant1
     let webhook = Webhook {
         id: StringUuid::new_v4(),
-        tenant_id: tenant1_id,
+        tenant_id: Some(tenant1_id),
         name: "Tenant1 Webhook".to_string(),
         url: "https://example.com/webhook".to_string(),
         secret: Some("secret123".to_string()),
         events: vec!["login.success".to_string()],
+        filter_expression: None,
         enabled: true,
         last_triggered_at: None,
         failure_count: 0,
+        version: 1,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
@@ -770,6 +936,378 @@ async fn test_webhook_test_wrong_tenant() {
     assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_webhook_ping_returns_verifiable_payload() {
+    let state = TestAppState::new();
+
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let webhook = Webhook {
+        id: StringUuid::new_v4(),
+        tenant_id: Some(tenant_id),
+        name: "Ping Webhook".to_string(),
+        url: "https://example.com/webhook".to_string(),
+        secret: Some("secret123".to_string()),
+        events: vec!["login.success".to_string()],
+        filter_expression: None,
+        enabled: true,
+        last_triggered_at: None,
+        failure_count: 0,
+        version: 1,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    let webhook_id = webhook.id;
+    state.webhook_repo.add_webhook(webhook).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<WebhookPingResult>>) = post_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks/{}/ping", tenant_id, webhook_id),
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let result = body.unwrap().data;
+    let event = auth9_core::webhook::verify_signature(
+        result.payload.as_bytes(),
+        result.signature.as_deref().unwrap(),
+        &result.timestamp,
+        "secret123",
+        auth9_core::webhook::DEFAULT_TOLERANCE,
+    )
+    .unwrap();
+    assert_eq!(event.event_type, "webhook.ping");
+}
+
+#[tokio::test]
+async fn test_webhook_ping_wrong_tenant() {
+    let state = TestAppState::new();
+
+    let tenant1 = create_test_tenant(None);
+    let tenant1_id = tenant1.id;
+    state.tenant_repo.add_tenant(tenant1).await;
+
+    let tenant2 = create_test_tenant(None);
+    let tenant2_id = tenant2.id;
+    state.tenant_repo.add_tenant(tenant2).await;
+
+    let webhook = Webhook {
+        id: StringUuid::new_v4(),
+        tenant_id: Some(tenant1_id),
+        name: "Tenant1 Webhook".to_string(),
+        url: "https://example.com/webhook".to_string(),
+        secret: Some("secret123".to_string()),
+        events: vec!["login.success".to_string()],
+        filter_expression: None,
+        enabled: true,
+        last_triggered_at: None,
+        failure_count: 0,
+        version: 1,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    let webhook_id = webhook.id;
+    state.webhook_repo.add_webhook(webhook).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<WebhookPingResult>>) = post_json(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/webhooks/{}/ping",
+            tenant2_id, webhook_id
+        ),
+        &serde_json::json!({}),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// Delivery Console Tests
+// ============================================================================
+
+async fn add_tenant_webhook(state: &TestAppState) -> (StringUuid, StringUuid) {
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let webhook = Webhook {
+        tenant_id: Some(tenant_id),
+        name: "Console Webhook".to_string(),
+        url: "https://example.com/webhook".to_string(),
+        secret: Some("secret123".to_string()),
+        events: vec!["login.success".to_string()],
+        ..Default::default()
+    };
+    let webhook_id = webhook.id;
+    state.webhook_repo.add_webhook(webhook).await;
+    (tenant_id, webhook_id)
+}
+
+fn recorded_delivery(webhook_id: StringUuid, success: bool) -> WebhookDelivery {
+    let event = WebhookEvent {
+        event_type: "login.success".to_string(),
+        timestamp: Utc::now(),
+        data: serde_json::json!({ "user_id": "u-1" }),
+    };
+    WebhookDelivery {
+        webhook_id,
+        event_type: event.event_type.clone(),
+        payload: serde_json::to_string(&event).unwrap(),
+        success,
+        status_code: Some(if success { 200 } else { 500 }),
+        response_time_ms: Some(42),
+        response_snippet: Some("receiver said hi".to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_list_webhook_deliveries_with_filter() {
+    let state = TestAppState::new();
+    let (tenant_id, webhook_id) = add_tenant_webhook(&state).await;
+    state
+        .webhook_delivery_repo
+        .add_delivery(recorded_delivery(webhook_id, true))
+        .await;
+    state
+        .webhook_delivery_repo
+        .add_delivery(recorded_delivery(webhook_id, false))
+        .await;
+    state
+        .webhook_delivery_repo
+        .add_delivery(recorded_delivery(StringUuid::new_v4(), false))
+        .await;
+
+    let app = build_webhook_test_router(state);
+    let path = format!(
+        "/api/v1/tenants/{}/webhooks/{}/deliveries",
+        tenant_id, webhook_id
+    );
+
+    let (status, body): (StatusCode, Option<PaginatedResponse<WebhookDelivery>>) =
+        get_json(&app, &path).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().pagination.total, 2);
+
+    let (status, body): (StatusCode, Option<PaginatedResponse<WebhookDelivery>>) =
+        get_json(&app, &format!("{}?success=false", path)).await;
+    assert_eq!(status, StatusCode::OK);
+    let body = body.unwrap();
+    assert_eq!(body.data.len(), 1);
+    assert!(!body.data[0].success);
+    assert_eq!(body.data[0].status_code, Some(500));
+    assert_eq!(body.data[0].response_time_ms, Some(42));
+}
+
+#[tokio::test]
+async fn test_get_delivery_of_other_webhook_not_found() {
+    let state = TestAppState::new();
+    let (tenant_id, webhook_id) = add_tenant_webhook(&state).await;
+    let foreign = recorded_delivery(StringUuid::new_v4(), true);
+    let foreign_id = foreign.id;
+    state.webhook_delivery_repo.add_delivery(foreign).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<WebhookDelivery>>) = get_json(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/webhooks/{}/deliveries/{}",
+            tenant_id, webhook_id, foreign_id
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_replay_delivery_records_new_attempt() {
+    let state = TestAppState::new();
+    let (tenant_id, webhook_id) = add_tenant_webhook(&state).await;
+    let original = recorded_delivery(webhook_id, false);
+    let original_id = original.id;
+    state.webhook_delivery_repo.add_delivery(original).await;
+    let deliveries = state.webhook_delivery_repo.clone();
+
+    let app = build_webhook_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<WebhookTestResult>>) = post_json(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/webhooks/{}/deliveries/{}/replay",
+            tenant_id, webhook_id, original_id
+        ),
+        &serde_json::json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.unwrap().data.response_time_ms.is_some());
+
+    let filter = Default::default();
+    let recorded = deliveries
+        .list_by_webhook(webhook_id, &filter, 0, 10)
+        .await
+        .unwrap();
+    let replay = recorded
+        .iter()
+        .find(|d| d.source == WebhookDeliverySource::Replay)
+        .unwrap();
+    assert_eq!(replay.replay_of, Some(original_id));
+    assert_eq!(replay.event_type, "login.success");
+}
+
+#[tokio::test]
+async fn test_send_synthetic_event_records_delivery() {
+    let state = TestAppState::new();
+    let (tenant_id, webhook_id) = add_tenant_webhook(&state).await;
+    let deliveries = state.webhook_delivery_repo.clone();
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<WebhookTestResult>>) = post_json(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/webhooks/{}/send-event",
+            tenant_id, webhook_id
+        ),
+        &serde_json::json!({ "event_type": "user.deleted", "data": { "user_id": "u-2" } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let filter = Default::default();
+    let recorded = deliveries
+        .list_by_webhook(webhook_id, &filter, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(recorded.len(), 1);
+    assert_eq!(recorded[0].source, WebhookDeliverySource::Synthetic);
+    assert_eq!(recorded[0].event_type, "user.deleted");
+    assert!(recorded[0].payload.contains("u-2"));
+}
+
+#[tokio::test]
+async fn test_send_synthetic_event_requires_event_type() {
+    let state = TestAppState::new();
+    let (tenant_id, webhook_id) = add_tenant_webhook(&state).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<WebhookTestResult>>) = post_json(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/webhooks/{}/send-event",
+            tenant_id, webhook_id
+        ),
+        &serde_json::json!({ "event_type": "" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// ============================================================================
+// Platform Webhook Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_create_platform_webhook_is_not_tenant_bound() {
+    let state = TestAppState::new();
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+    let webhook_repo = state.webhook_repo.clone();
+
+    let app = build_webhook_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Webhook>>) = post_json(
+        &app,
+        "/api/v1/platform/webhooks",
+        &serde_json::json!({
+            "name": "Provisioning",
+            "url": "https://example.com/hooks/tenants",
+            "events": ["tenant.created", "tenant.deleted"],
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let webhook = body.unwrap().data;
+    assert!(webhook.tenant_id.is_none());
+
+    assert_eq!(webhook_repo.list_platform().await.unwrap().len(), 1);
+    assert!(webhook_repo
+        .list_by_tenant(tenant_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_create_platform_webhook_rejects_tenant_events() {
+    let state = TestAppState::new();
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<Webhook>>) = post_json(
+        &app,
+        "/api/v1/platform/webhooks",
+        &serde_json::json!({
+            "name": "Logins",
+            "url": "https://example.com/hooks/logins",
+            "events": ["login.success"],
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_platform_webhooks_require_platform_admin() {
+    let state = TestAppState::new();
+    let app = build_webhook_test_router(state);
+
+    let token = create_test_identity_token_for_user(uuid::Uuid::new_v4());
+    let (status, _): (StatusCode, Option<SuccessResponse<Vec<Webhook>>>) =
+        get_json_with_auth(&app, "/api/v1/platform/webhooks", &token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_tenant_route_hides_platform_webhook() {
+    let state = TestAppState::new();
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let webhook = Webhook {
+        tenant_id: None,
+        events: vec!["tenant.created".to_string()],
+        ..Default::default()
+    };
+    let webhook_id = webhook.id;
+    state.webhook_repo.add_webhook(webhook).await;
+
+    let app = build_webhook_test_router(state);
+
+    let (status, _): (StatusCode, Option<SuccessResponse<Webhook>>) = get_json(
+        &app,
+        &format!("/api/v1/tenants/{}/webhooks/{}", tenant_id, webhook_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Webhook>>) =
+        get_json(&app, &format!("/api/v1/platform/webhooks/{}", webhook_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.id, webhook_id);
+}
+
 // ============================================================================
 // Test Router Builder
 // ============================================================================
@@ -794,9 +1332,40 @@ fn build_webhook_test_router(state: TestAppState) -> axum::Router {
             "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/test",
             post(webhook::test_webhook::<TestAppState>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/ping",
+            post(webhook::ping_webhook::<TestAppState>),
+        )
         .route(
             "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/regenerate-secret",
             post(webhook::regenerate_webhook_secret::<TestAppState>),
         )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/send-event",
+            post(webhook::send_synthetic_event::<TestAppState>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries",
+            get(webhook::list_deliveries::<TestAppState>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}",
+            get(webhook::get_delivery::<TestAppState>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/replay",
+            post(webhook::replay_delivery::<TestAppState>),
+        )
+        .route(
+            "/api/v1/platform/webhooks",
+            get(webhook::list_platform_webhooks::<TestAppState>)
+                .post(webhook::create_platform_webhook::<TestAppState>),
+        )
+        .route(
+            "/api/v1/platform/webhooks/{webhook_id}",
+            get(webhook::get_platform_webhook::<TestAppState>)
+                .put(webhook::update_platform_webhook::<TestAppState>)
+                .delete(webhook::delete_platform_webhook::<TestAppState>),
+        )
         .with_state(state)
 }
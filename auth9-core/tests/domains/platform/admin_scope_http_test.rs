@@ -0,0 +1,109 @@
+//! Platform admin scope API and middleware HTTP tests
+//!
+//! Grants live in the database, so these cover root-admin paths and access
+//! control that run without a scope lookup.
+
+use crate::support::http::{
+    build_test_router, get_json_with_auth, put_json_with_auth, TestAppState,
+};
+use crate::support::{
+    create_test_admin_token_for_user, create_test_identity_token,
+    create_test_identity_token_for_user, create_test_tenant,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_set_admin_scopes_requires_root_admin() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token_for_user(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/admin/users/{}/scopes", Uuid::new_v4()),
+        &json!({ "scopes": ["tenants:read"] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_set_admin_scopes_rejects_unknown_scope() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token();
+    let (status, _body): (StatusCode, Option<Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/admin/users/{}/scopes", Uuid::new_v4()),
+        &json!({ "scopes": ["tenants:*"] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_get_other_admin_scopes_requires_root_admin() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token_for_user(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/admin/users/{}/scopes", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_root_admin_holds_all_scopes() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let user_id = Uuid::new_v4();
+    let token = create_test_admin_token_for_user(user_id);
+    let (status, body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/admin/users/{}/scopes", user_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let scopes = body.unwrap()["data"]["scopes"].clone();
+    assert_eq!(
+        scopes,
+        json!([
+            "tenants:read",
+            "tenants:write",
+            "users:impersonate",
+            "settings:write"
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_root_admin_bypasses_enforced_scopes() {
+    let mut state = TestAppState::new();
+    let mut config = (*state.config).clone();
+    config.platform_admin_scopes_enforced = true;
+    state.config = std::sync::Arc::new(config);
+
+    state.tenant_repo.add_tenant(create_test_tenant(None)).await;
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token();
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, "/api/v1/tenants", &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+}
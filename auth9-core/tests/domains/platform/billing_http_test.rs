@@ -0,0 +1,54 @@
+//! Billing event ledger HTTP handler tests
+
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn non_admin_token(state: &TestAppState) -> String {
+    state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_list_billing_events_requires_auth() {
+    let app = build_test_router(TestAppState::new());
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/system/billing/events").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_billing_events_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = non_admin_token(&state);
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, "/api/v1/system/billing/events?status=failed", &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_redeliver_billing_event_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = non_admin_token(&state);
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/system/billing/events/{}/redeliver", Uuid::new_v4()),
+        &json!({}),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
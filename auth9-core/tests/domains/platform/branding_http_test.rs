@@ -17,7 +17,7 @@ use serde_json::json;
 
 #[tokio::test]
 async fn test_get_public_branding_defaults() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
 
     let (status, body): (StatusCode, Option<serde_json::Value>) =
@@ -49,7 +49,7 @@ async fn test_get_public_branding_defaults() {
 
 #[tokio::test]
 async fn test_get_public_branding_custom() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Pre-populate with custom branding config
     let branding_config = json!({
@@ -96,7 +96,7 @@ async fn test_get_public_branding_custom() {
 
 #[tokio::test]
 async fn test_get_admin_branding_defaults() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -117,7 +117,7 @@ async fn test_get_admin_branding_defaults() {
 
 #[tokio::test]
 async fn test_update_branding_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -151,7 +151,7 @@ async fn test_update_branding_success() {
 
 #[tokio::test]
 async fn test_update_branding_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -178,7 +178,7 @@ async fn test_update_branding_minimal() {
 
 #[tokio::test]
 async fn test_update_branding_with_custom_css() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -206,7 +206,7 @@ async fn test_update_branding_with_custom_css() {
 
 #[tokio::test]
 async fn test_update_branding_invalid_color() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -228,7 +228,7 @@ async fn test_update_branding_invalid_color() {
 
 #[tokio::test]
 async fn test_update_branding_invalid_color_no_hash() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -250,7 +250,7 @@ async fn test_update_branding_invalid_color_no_hash() {
 
 #[tokio::test]
 async fn test_update_branding_invalid_color_wrong_length() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -272,7 +272,7 @@ async fn test_update_branding_invalid_color_wrong_length() {
 
 #[tokio::test]
 async fn test_update_branding_invalid_logo_url() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -295,7 +295,7 @@ async fn test_update_branding_invalid_logo_url() {
 
 #[tokio::test]
 async fn test_update_branding_invalid_favicon_url() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -322,7 +322,7 @@ async fn test_update_branding_invalid_favicon_url() {
 
 #[tokio::test]
 async fn test_branding_roundtrip() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -362,7 +362,7 @@ async fn test_branding_roundtrip() {
 
 #[tokio::test]
 async fn test_branding_public_and_admin_match() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -404,7 +404,7 @@ async fn test_branding_public_and_admin_match() {
 
 #[tokio::test]
 async fn test_update_branding_lowercase_color() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -432,7 +432,7 @@ async fn test_update_branding_lowercase_color() {
 
 #[tokio::test]
 async fn test_branding_allow_registration_default_false() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
 
     // Get defaults - allow_registration should be false
@@ -449,7 +449,7 @@ async fn test_branding_allow_registration_default_false() {
 
 #[tokio::test]
 async fn test_update_branding_with_allow_registration() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -483,7 +483,7 @@ async fn test_update_branding_with_allow_registration() {
 
 #[tokio::test]
 async fn test_update_branding_without_allow_registration_defaults_false() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_branding_test_router(state);
     let token = create_test_identity_token();
 
@@ -0,0 +1,120 @@
+//! Email queue, suppression list and feedback webhook HTTP handler tests
+
+use crate::support::create_test_identity_token;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json, post_json_with_auth, TestAppState,
+};
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn with_feedback_secret(mut state: TestAppState, secret: &str) -> TestAppState {
+    let mut config = (*state.config).clone();
+    config.email_queue.feedback_webhook_secret = Some(secret.to_string());
+    state.config = std::sync::Arc::new(config);
+    state
+}
+
+async fn post_feedback(app: &axum::Router, body: &[u8], signature: Option<&str>) -> StatusCode {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri("/api/v1/email/feedback")
+        .header("Content-Type", "application/json");
+    if let Some(signature) = signature {
+        builder = builder.header("x-webhook-signature", signature);
+    }
+    let request = builder.body(Body::from(body.to_vec())).unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[tokio::test]
+async fn test_queue_stats_requires_auth() {
+    let app = build_test_router(TestAppState::new());
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/system/email/queue").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_suppressions_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, "/api/v1/system/email/suppressions", &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_create_suppression_rejects_invalid_email() {
+    let app = build_test_router(TestAppState::new());
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        "/api/v1/system/email/suppressions",
+        &json!({ "email": "not-an-email" }),
+        &create_test_identity_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_feedback_not_configured() {
+    let app = build_test_router(TestAppState::new());
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        "/api/v1/email/feedback",
+        &json!({ "type": "complaint", "recipients": ["a@example.com"] }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_feedback_rejects_bad_signature() {
+    let secret = "feedback-secret"; // pragma: allowlist secret
+    let app = build_test_router(with_feedback_secret(TestAppState::new(), secret));
+    let body = br#"{"type":"complaint","recipients":["a@example.com"]}"#;
+
+    assert_eq!(
+        post_feedback(&app, body, None).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        post_feedback(&app, body, Some(&sign("other-secret", body))).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn test_feedback_ignores_unrelated_notifications() {
+    let secret = "feedback-secret"; // pragma: allowlist secret
+    let app = build_test_router(with_feedback_secret(TestAppState::new(), secret));
+    let body = br#"{"notificationType":"Delivery","delivery":{}}"#;
+
+    assert_eq!(
+        post_feedback(&app, body, Some(&sign(secret, body))).await,
+        StatusCode::OK
+    );
+}
@@ -29,7 +29,7 @@ struct ListTemplatesResponse {
 
 #[tokio::test]
 async fn test_list_templates_returns_all_default_templates() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -51,7 +51,7 @@ async fn test_list_templates_returns_all_default_templates() {
 
 #[tokio::test]
 async fn test_list_templates_shows_customized() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
 
     // Add a custom template to the repository
     let custom_content = EmailTemplateContent {
@@ -98,7 +98,7 @@ async fn test_list_templates_shows_customized() {
 
 #[tokio::test]
 async fn test_get_template_default() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -121,7 +121,7 @@ async fn test_get_template_default() {
 
 #[tokio::test]
 async fn test_get_template_customized() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
 
     // Add custom template
     let custom_content = EmailTemplateContent {
@@ -158,7 +158,7 @@ async fn test_get_template_customized() {
 
 #[tokio::test]
 async fn test_get_template_not_found() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -170,7 +170,7 @@ async fn test_get_template_not_found() {
 
 #[tokio::test]
 async fn test_get_all_template_types() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state.clone());
     let token = create_test_identity_token();
 
@@ -204,7 +204,7 @@ async fn test_get_all_template_types() {
 
 #[tokio::test]
 async fn test_update_template_success() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -237,7 +237,7 @@ async fn test_update_template_success() {
 
 #[tokio::test]
 async fn test_update_template_invalid_type() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -260,7 +260,7 @@ async fn test_update_template_invalid_type() {
 
 #[tokio::test]
 async fn test_update_template_empty_subject() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -283,7 +283,7 @@ async fn test_update_template_empty_subject() {
 
 #[tokio::test]
 async fn test_update_template_empty_html_body() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -310,7 +310,7 @@ async fn test_update_template_empty_html_body() {
 
 #[tokio::test]
 async fn test_reset_template_success() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
 
     // First, add a custom template
     let custom_content = EmailTemplateContent {
@@ -356,7 +356,7 @@ async fn test_reset_template_success() {
 
 #[tokio::test]
 async fn test_reset_template_not_customized() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -372,7 +372,7 @@ async fn test_reset_template_not_customized() {
 
 #[tokio::test]
 async fn test_reset_template_invalid_type() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -392,7 +392,7 @@ async fn test_reset_template_invalid_type() {
 
 #[tokio::test]
 async fn test_preview_template_success() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -427,7 +427,7 @@ async fn test_preview_template_success() {
 
 #[tokio::test]
 async fn test_preview_template_password_reset() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -454,7 +454,7 @@ async fn test_preview_template_password_reset() {
 
 #[tokio::test]
 async fn test_preview_template_email_mfa() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
@@ -481,7 +481,7 @@ async fn test_preview_template_email_mfa() {
 
 #[tokio::test]
 async fn test_preview_template_invalid_type() {
-    let state = TestAppState::new("http://mock-keycloak:8080");
+    let state = TestAppState::new();
     let app = build_email_template_test_router(state);
     let token = create_test_identity_token();
 
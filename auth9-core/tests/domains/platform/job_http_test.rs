@@ -0,0 +1,180 @@
+//! Async job API HTTP handler tests
+//!
+//! Job rows live in the database, so these cover authentication, tenant
+//! access control and input validation that run before any job is queued.
+
+use crate::support::create_test_jwt_manager;
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json, post_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn create_tenant_access_token(tenant_id: Uuid, roles: Vec<String>) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "admin@example.com",
+            tenant_id,
+            "test-client",
+            roles,
+            vec![],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_job_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, &format!("/api/v1/jobs/{}", Uuid::new_v4())).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_cancel_job_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        &format!("/api/v1/jobs/{}/cancel", Uuid::new_v4()),
+        &json!({}),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_tenant_jobs_rejects_other_tenant() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_tenant_access_token(Uuid::new_v4(), vec!["admin".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/jobs", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_import_users_requires_tenant_owner() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_tenant_access_token(tenant_id, vec!["admin".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/users/import", tenant_id),
+        &json!({ "users": [{ "email": "new@example.com" }] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_import_users_rejects_empty_and_invalid_rows() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_tenant_access_token(tenant_id, vec!["owner".to_string()]);
+
+    for body in [
+        json!({ "users": [] }),
+        json!({ "users": [{ "email": "not-an-email" }] }),
+    ] {
+        let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+            &app,
+            &format!("/api/v1/tenants/{}/users/import", tenant_id),
+            &body,
+            &token,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
+#[tokio::test]
+async fn test_export_users_rejects_member_token() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_tenant_access_token(tenant_id, vec!["member".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/users/export", tenant_id),
+        &json!({}),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_force_password_reset_requires_tenant_owner() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_tenant_access_token(tenant_id, vec!["admin".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/users:force-password-reset", tenant_id),
+        &json!({ "cohort": { "type": "all" } }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_force_password_reset_rejects_empty_role_cohort() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_tenant_access_token(tenant_id, vec!["owner".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/users:force-password-reset", tenant_id),
+        &json!({ "cohort": { "type": "role", "role": " " }, "revoke_sessions": true }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_reencrypt_all_requires_platform_admin() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_tenant_access_token(Uuid::new_v4(), vec!["owner".to_string()]);
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        "/api/v1/system/encryption-key/reencrypt",
+        &json!({}),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -1,3 +1,7 @@
+mod admin_scope_http_test;
+mod billing_http_test;
 mod branding_http_test;
+mod email_queue_http_test;
 mod email_template_http_test;
+mod job_http_test;
 mod system_settings_http_test;
@@ -20,7 +20,7 @@ use serde_json::json;
 
 #[tokio::test]
 async fn test_get_email_settings_none() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -37,7 +37,7 @@ async fn test_get_email_settings_none() {
 
 #[tokio::test]
 async fn test_get_email_settings_smtp_masked() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Pre-populate with SMTP config
     let smtp_config = json!({
@@ -88,7 +88,7 @@ async fn test_get_email_settings_smtp_masked() {
 
 #[tokio::test]
 async fn test_get_email_settings_oracle() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let oracle_config = json!({
         "type": "oracle",
@@ -130,7 +130,7 @@ async fn test_get_email_settings_oracle() {
 
 #[tokio::test]
 async fn test_get_email_settings_ses() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let ses_config = json!({
         "type": "ses",
@@ -178,7 +178,7 @@ async fn test_get_email_settings_ses() {
 
 #[tokio::test]
 async fn test_update_email_settings_smtp() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -213,7 +213,7 @@ async fn test_update_email_settings_smtp() {
 
 #[tokio::test]
 async fn test_update_email_settings_none() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // First set up some config
     let smtp_config = json!({
@@ -256,7 +256,7 @@ async fn test_update_email_settings_none() {
 
 #[tokio::test]
 async fn test_update_email_settings_oracle() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -287,7 +287,7 @@ async fn test_update_email_settings_oracle() {
 
 #[tokio::test]
 async fn test_update_email_settings_ses() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -317,7 +317,7 @@ async fn test_update_email_settings_ses() {
 
 #[tokio::test]
 async fn test_update_email_settings_invalid_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -340,7 +340,7 @@ async fn test_update_email_settings_invalid_email() {
 
 #[tokio::test]
 async fn test_update_email_settings_missing_host() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -365,7 +365,7 @@ async fn test_update_email_settings_missing_host() {
 
 #[tokio::test]
 async fn test_email_connection_not_configured() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -413,7 +413,7 @@ async fn test_email_connection_failure_response() {
 
 #[tokio::test]
 async fn test_send_email_not_configured() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -434,7 +434,7 @@ async fn test_send_email_not_configured() {
 
 #[tokio::test]
 async fn test_send_email_invalid_address() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Set up email config
     let smtp_config = json!({
@@ -489,7 +489,7 @@ async fn test_send_email_response_with_message_id() {
 
 #[tokio::test]
 async fn test_update_preserves_password_on_masked_input() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // First set up config with real password
     let smtp_config = json!({
@@ -541,7 +541,7 @@ async fn test_update_preserves_password_on_masked_input() {
 
 #[tokio::test]
 async fn test_email_settings_roundtrip() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -574,7 +574,7 @@ async fn test_email_settings_roundtrip() {
 
 #[tokio::test]
 async fn test_email_provider_type_switch() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
     let token = create_test_identity_token();
 
@@ -616,7 +616,7 @@ async fn test_email_provider_type_switch() {
 
 #[tokio::test]
 async fn test_get_email_settings_requires_auth() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
 
     // No auth token
@@ -628,7 +628,7 @@ async fn test_get_email_settings_requires_auth() {
 
 #[tokio::test]
 async fn test_update_email_settings_requires_auth() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state);
 
     let input = json!({
@@ -648,7 +648,7 @@ async fn test_update_email_settings_requires_auth() {
 
 #[tokio::test]
 async fn test_get_malicious_ip_blacklist_returns_entries() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state
         .malicious_ip_blacklist_repo
         .add_entry(
@@ -679,7 +679,7 @@ async fn test_get_malicious_ip_blacklist_returns_entries() {
 
 #[tokio::test]
 async fn test_update_malicious_ip_blacklist_deduplicates_entries() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_system_settings_test_router(state.clone());
     let token = create_test_identity_token();
 
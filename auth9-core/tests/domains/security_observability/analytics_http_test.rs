@@ -16,7 +16,7 @@ use chrono::Utc;
 
 #[tokio::test]
 async fn test_get_stats_daily_period() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add some login events
     add_test_login_events(&state, 5).await;
@@ -34,7 +34,7 @@ async fn test_get_stats_daily_period() {
 
 #[tokio::test]
 async fn test_get_stats_weekly_period() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 10).await;
 
@@ -51,7 +51,7 @@ async fn test_get_stats_weekly_period() {
 
 #[tokio::test]
 async fn test_get_stats_monthly_period() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 15).await;
 
@@ -68,7 +68,7 @@ async fn test_get_stats_monthly_period() {
 
 #[tokio::test]
 async fn test_get_stats_custom_days() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 8).await;
 
@@ -85,7 +85,7 @@ async fn test_get_stats_custom_days() {
 
 #[tokio::test]
 async fn test_get_stats_default() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 3).await;
 
@@ -103,7 +103,7 @@ async fn test_get_stats_default() {
 
 #[tokio::test]
 async fn test_get_stats_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_analytics_test_router(state);
 
@@ -124,7 +124,7 @@ async fn test_get_stats_empty() {
 
 #[tokio::test]
 async fn test_list_events_with_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 25).await;
 
@@ -144,7 +144,7 @@ async fn test_list_events_with_pagination() {
 
 #[tokio::test]
 async fn test_list_events_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_analytics_test_router(state);
 
@@ -160,7 +160,7 @@ async fn test_list_events_empty() {
 
 #[tokio::test]
 async fn test_list_user_events() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -185,7 +185,7 @@ async fn test_list_user_events() {
 
 #[tokio::test]
 async fn test_list_tenant_events() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -213,7 +213,7 @@ async fn test_list_tenant_events() {
 
 #[tokio::test]
 async fn test_get_stats_with_start_end_dates() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 12).await;
 
@@ -238,7 +238,7 @@ async fn test_get_stats_with_start_end_dates() {
 
 #[tokio::test]
 async fn test_get_stats_with_date_only_format() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 8).await;
 
@@ -263,7 +263,7 @@ async fn test_get_stats_with_date_only_format() {
 
 #[tokio::test]
 async fn test_get_stats_future_date_returns_zero() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 5).await;
 
@@ -284,7 +284,7 @@ async fn test_get_stats_future_date_returns_zero() {
 
 #[tokio::test]
 async fn test_get_stats_with_invalid_dates_falls_back() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 5).await;
 
@@ -303,7 +303,7 @@ async fn test_get_stats_with_invalid_dates_falls_back() {
 
 #[tokio::test]
 async fn test_get_stats_period_aliases() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 6).await;
 
@@ -330,7 +330,7 @@ async fn test_get_stats_period_aliases() {
 
 #[tokio::test]
 async fn test_list_events_filter_by_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Add events with specific email
     for i in 0..5 {
@@ -377,7 +377,7 @@ async fn test_list_events_filter_by_email() {
 
 #[tokio::test]
 async fn test_list_events_second_page() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     add_test_login_events(&state, 30).await;
 
@@ -396,7 +396,7 @@ async fn test_list_events_second_page() {
 
 #[tokio::test]
 async fn test_list_user_events_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user = create_test_user(None);
     let user_id = user.id;
@@ -1,10 +1,14 @@
 //! Audit HTTP API handler tests
 
-use crate::support::http::{get_json_with_auth, TestAppState};
-use auth9_core::http_support::PaginatedResponse;
-use auth9_core::repository::audit::{AuditLogWithActor, CreateAuditLogInput};
+use crate::support::http::{get_json_with_auth, post_json_with_auth, TestAppState};
+use auth9_core::http_support::{PaginatedResponse, SuccessResponse};
+use auth9_core::models::audit_search::{AuditHistogram, AuditHistogramInterval};
+use auth9_core::models::audit_state::{AuditGapKind, AuditStateSnapshot};
+use auth9_core::repository::audit::{AuditLog, AuditLogWithActor, CreateAuditLogInput};
 use auth9_core::repository::AuditRepository;
 use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use serde_json::json;
 use uuid::Uuid;
 
 // ============================================================================
@@ -13,7 +17,7 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_list_audit_logs_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -33,7 +37,7 @@ async fn test_list_audit_logs_empty() {
 
 #[tokio::test]
 async fn test_list_audit_logs_with_data() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -70,7 +74,7 @@ async fn test_list_audit_logs_with_data() {
 
 #[tokio::test]
 async fn test_list_audit_logs_with_resource_type_filter() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -118,7 +122,7 @@ async fn test_list_audit_logs_with_resource_type_filter() {
 
 #[tokio::test]
 async fn test_list_audit_logs_with_action_filter() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -165,7 +169,7 @@ async fn test_list_audit_logs_with_action_filter() {
 
 #[tokio::test]
 async fn test_list_audit_logs_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -202,7 +206,7 @@ async fn test_list_audit_logs_pagination() {
 
 #[tokio::test]
 async fn test_list_audit_logs_with_actor_filter() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
@@ -252,15 +256,297 @@ async fn test_list_audit_logs_with_actor_filter() {
     assert_eq!(response.data.len(), 1);
 }
 
+// ============================================================================
+// Audit State Reconstruction Tests
+// ============================================================================
+
+fn role_log(
+    id: i64,
+    role_id: Uuid,
+    action: &str,
+    hours_ago: i64,
+    old_value: Option<serde_json::Value>,
+    new_value: Option<serde_json::Value>,
+) -> AuditLog {
+    AuditLog {
+        id,
+        actor_id: None,
+        action: action.to_string(),
+        resource_type: "role".to_string(),
+        resource_id: Some(role_id.to_string()),
+        old_value,
+        new_value,
+        ip_address: None,
+        created_at: Utc::now() - Duration::hours(hours_ago),
+    }
+}
+
+#[tokio::test]
+async fn test_audit_state_at_past_timestamp() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+    let role_id = Uuid::new_v4();
+
+    state
+        .audit_repo
+        .insert_log(role_log(
+            1,
+            role_id,
+            "role.create",
+            10,
+            None,
+            Some(json!({"name": "viewer"})),
+        ))
+        .await;
+    state
+        .audit_repo
+        .insert_log(role_log(
+            2,
+            role_id,
+            "role.update",
+            2,
+            Some(json!({"name": "viewer"})),
+            Some(json!({"name": "reader"})),
+        ))
+        .await;
+
+    let app = build_audit_test_router(state);
+    let at = (Utc::now() - Duration::hours(5)).to_rfc3339();
+    let path = format!(
+        "/api/v1/audit/state?resource=role:{}&at={}",
+        role_id,
+        urlencoding::encode(&at)
+    );
+
+    let (status, body): (StatusCode, Option<SuccessResponse<AuditStateSnapshot>>) =
+        get_json_with_auth(&app, &path, &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let snapshot = body.unwrap().data;
+    assert!(snapshot.exists);
+    assert!(snapshot.consistent);
+    assert_eq!(snapshot.state, Some(json!({"name": "viewer"})));
+    assert_eq!(snapshot.last_audit_log_id, Some(1));
+}
+
+#[tokio::test]
+async fn test_audit_state_reports_gaps() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+    let role_id = Uuid::new_v4();
+
+    state
+        .audit_repo
+        .insert_log(role_log(
+            1,
+            role_id,
+            "role.create",
+            3,
+            None,
+            Some(json!({"name": "viewer"})),
+        ))
+        .await;
+    state
+        .audit_repo
+        .insert_log(role_log(
+            2,
+            role_id,
+            "role.update",
+            1,
+            Some(json!({"name": "editor"})),
+            Some(json!({"name": "admin"})),
+        ))
+        .await;
+
+    let app = build_audit_test_router(state);
+    let path = format!("/api/v1/audit/state?resource=role:{}", role_id);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<AuditStateSnapshot>>) =
+        get_json_with_auth(&app, &path, &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let snapshot = body.unwrap().data;
+    assert!(!snapshot.consistent);
+    assert_eq!(snapshot.gaps[0].kind, AuditGapKind::StateMismatch);
+    assert_eq!(snapshot.gaps[0].audit_log_id, Some(2));
+    assert_eq!(snapshot.state, Some(json!({"name": "admin"})));
+}
+
+#[tokio::test]
+async fn test_audit_state_rejects_unsupported_resource() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+
+    let app = build_audit_test_router(state);
+    let path = format!("/api/v1/audit/state?resource=webhook:{}", Uuid::new_v4());
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) =
+        get_json_with_auth(&app, &path, &token).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+// ============================================================================
+// Search, Histogram and Export Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_list_audit_logs_full_text_search() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+
+    for (action, email) in [
+        ("user.update", "alice@example.com"),
+        ("user.update", "bob@example.com"),
+        ("tenant.update", "alice@example.com"),
+    ] {
+        state
+            .audit_repo
+            .create(&CreateAuditLogInput {
+                actor_id: None,
+                action: action.to_string(),
+                resource_type: "user".to_string(),
+                resource_id: Some(Uuid::new_v4()),
+                old_value: None,
+                new_value: Some(json!({ "email": email })),
+                ip_address: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let app = build_audit_test_router(state);
+
+    let (status, body): (StatusCode, Option<PaginatedResponse<AuditLogWithActor>>) =
+        get_json_with_auth(&app, "/api/v1/audit-logs?q=alice", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().pagination.total, 2);
+
+    let (status, body): (StatusCode, Option<PaginatedResponse<AuditLogWithActor>>) =
+        get_json_with_auth(&app, "/api/v1/audit-logs?q=alice%20user.update", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let response = body.unwrap();
+    assert_eq!(response.pagination.total, 1);
+    assert_eq!(response.data[0].action, "user.update");
+}
+
+#[tokio::test]
+async fn test_audit_histogram_buckets_by_day() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+
+    let now = Utc::now();
+    for (id, days_ago) in [(1, 0), (2, 0), (3, 2), (4, 40)] {
+        state
+            .audit_repo
+            .insert_log(AuditLog {
+                id,
+                actor_id: None,
+                action: "user.update".to_string(),
+                resource_type: "user".to_string(),
+                resource_id: None,
+                old_value: None,
+                new_value: None,
+                ip_address: None,
+                created_at: now - Duration::days(days_ago),
+            })
+            .await;
+    }
+
+    let app = build_audit_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<AuditHistogram>>) =
+        get_json_with_auth(&app, "/api/v1/audit-logs/histogram?interval=day", &token).await;
+    assert_eq!(status, StatusCode::OK);
+    let histogram = body.unwrap().data;
+    assert_eq!(histogram.interval, AuditHistogramInterval::Day);
+    // The 40-day-old entry is outside the default 30 day range
+    let counts: Vec<i64> = histogram.buckets.iter().map(|b| b.count).collect();
+    assert_eq!(counts, vec![1, 2]);
+    assert!(histogram.buckets[0].bucket_start < histogram.buckets[1].bucket_start);
+}
+
+#[tokio::test]
+async fn test_audit_histogram_rejects_too_many_buckets() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+
+    let app = build_audit_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        "/api/v1/audit-logs/histogram?interval=hour&from_date=2020-01-01T00:00:00Z",
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_audit_export_requires_audit_read() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+
+    let app = build_audit_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        "/api/v1/audit-logs/exports?q=alice",
+        &json!({}),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let path = format!("/api/v1/audit-logs/exports/{}/download", Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<serde_json::Value>) =
+        get_json_with_auth(&app, &path, &token).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
 // ============================================================================
 // Test Router Builder
 // ============================================================================
 
 fn build_audit_test_router(state: TestAppState) -> axum::Router {
     use auth9_core::domains::security_observability::api::audit;
-    use axum::routing::get;
+    use axum::routing::{get, post};
 
     axum::Router::new()
         .route("/api/v1/audit-logs", get(audit::list::<TestAppState>))
+        .route(
+            "/api/v1/audit-logs/histogram",
+            get(audit::histogram::<TestAppState>),
+        )
+        .route(
+            "/api/v1/audit-logs/exports",
+            post(audit::export::<TestAppState>),
+        )
+        .route(
+            "/api/v1/audit-logs/exports/{id}/download",
+            get(audit::download_export::<TestAppState>),
+        )
+        .route("/api/v1/audit/state", get(audit::get_state::<TestAppState>))
         .with_state(state)
 }
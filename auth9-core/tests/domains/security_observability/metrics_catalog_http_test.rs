@@ -0,0 +1,62 @@
+//! Metrics catalog HTTP API handler tests
+
+use crate::support::http::{build_test_router, get_json, get_json_with_auth, TestAppState};
+use auth9_core::http_support::SuccessResponse;
+use axum::http::StatusCode;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_metrics_catalog_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/admin/metrics/catalog").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_metrics_catalog_platform_admin() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Value>>) =
+        get_json_with_auth(&app, "/api/v1/admin/metrics/catalog", &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let catalog = body.unwrap().data;
+    let http = catalog["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == "auth9_http_request_duration_seconds")
+        .unwrap();
+    assert_eq!(http["type"], "histogram");
+    assert_eq!(http["labels"], serde_json::json!(["method", "path"]));
+    assert_eq!(catalog["red"][0]["name"], "http");
+    assert!(catalog["exemplar_metrics"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("auth9_http_request_duration_seconds")));
+}
+
+#[tokio::test]
+async fn test_metrics_catalog_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, "/api/v1/admin/metrics/catalog", &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -1,3 +1,6 @@
 mod analytics_http_test;
 mod audit_http_test;
+mod metrics_catalog_http_test;
 mod security_alert_http_test;
+mod sql_stats_http_test;
+mod synthetic_http_test;
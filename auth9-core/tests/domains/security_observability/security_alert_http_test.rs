@@ -19,7 +19,7 @@ use tower::ServiceExt;
 
 #[tokio::test]
 async fn test_list_alerts_default() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -46,7 +46,7 @@ async fn test_list_alerts_default() {
 
 #[tokio::test]
 async fn test_list_alerts_with_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -74,7 +74,7 @@ async fn test_list_alerts_with_pagination() {
 
 #[tokio::test]
 async fn test_list_alerts_unresolved_only() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -102,7 +102,7 @@ async fn test_list_alerts_unresolved_only() {
 
 #[tokio::test]
 async fn test_list_alerts_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -130,7 +130,7 @@ async fn test_list_alerts_empty() {
 
 #[tokio::test]
 async fn test_get_alert_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -173,7 +173,7 @@ async fn test_get_alert_success() {
 
 #[tokio::test]
 async fn test_get_alert_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -202,7 +202,7 @@ async fn test_get_alert_not_found() {
 
 #[tokio::test]
 async fn test_resolve_alert_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Create a user for authorization
     let user = create_test_user(None);
@@ -249,7 +249,7 @@ async fn test_resolve_alert_success() {
 
 #[tokio::test]
 async fn test_resolve_alert_unauthorized() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let alert_id = StringUuid::new_v4();
     let alert = SecurityAlert {
@@ -284,7 +284,7 @@ async fn test_resolve_alert_unauthorized() {
 
 #[tokio::test]
 async fn test_get_unresolved_count() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
@@ -311,7 +311,7 @@ async fn test_get_unresolved_count() {
 
 #[tokio::test]
 async fn test_get_unresolved_count_zero() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = state
         .jwt_manager
         .create_identity_token(
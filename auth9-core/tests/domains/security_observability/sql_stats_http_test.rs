@@ -0,0 +1,52 @@
+//! SQL statistics HTTP API handler tests
+
+use crate::support::http::{build_test_router, get_json, get_json_with_auth, TestAppState};
+use auth9_core::http_support::SuccessResponse;
+use axum::http::StatusCode;
+use serde_json::Value;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_slow_queries_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/admin/sql/slow-queries").await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_slow_queries_platform_admin() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Value>>) =
+        get_json_with_auth(&app, "/api/v1/admin/sql/slow-queries?limit=5", &token).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let report = body.unwrap().data;
+    assert!(report["threshold_ms"].is_u64());
+    assert!(report["slow_queries"].is_array());
+    assert!(report["top_fingerprints"].as_array().unwrap().len() <= 5);
+}
+
+#[tokio::test]
+async fn test_slow_queries_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, "/api/v1/admin/sql/slow-queries", &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,75 @@
+//! Synthetic login check HTTP API handler tests
+
+use crate::support::http::{build_test_router, get_json, get_json_with_auth, TestAppState};
+use axum::http::StatusCode;
+use serde_json::Value;
+use uuid::Uuid;
+
+const LOGIN_CHECK_PATH: &str = "/api/v1/admin/synthetic/login-check";
+
+fn admin_token(state: &TestAppState) -> String {
+    state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "admin@auth9.local", Some("Platform Admin"))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_login_check_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json(&app, LOGIN_CHECK_PATH).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_login_check_non_admin_forbidden() {
+    let state = TestAppState::new();
+    let token = state
+        .jwt_manager
+        .create_identity_token(Uuid::new_v4(), "user@example.com", None)
+        .unwrap();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, LOGIN_CHECK_PATH, &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_login_check_not_configured() {
+    let state = TestAppState::new();
+    let token = admin_token(&state);
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, LOGIN_CHECK_PATH, &token).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_login_check_reports_failed_login_stage() {
+    let mut state = TestAppState::new();
+    let mut config = (*state.config).clone();
+    config.synthetic_check.user_email = Some("synthetic@example.com".to_string());
+    config.synthetic_check.user_password = Some("secret".to_string());
+    config.synthetic_check.tenant_slug = Some("test-tenant".to_string());
+    state.config = std::sync::Arc::new(config);
+    let token = admin_token(&state);
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, LOGIN_CHECK_PATH, &token).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    let report = &body.unwrap()["data"];
+    assert_eq!(report["ok"], false);
+    let stages = report["stages"].as_array().unwrap();
+    assert_eq!(stages.len(), 1);
+    assert_eq!(stages[0]["name"], "login");
+    assert_eq!(stages[0]["ok"], false);
+}
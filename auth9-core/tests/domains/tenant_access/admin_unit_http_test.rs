@@ -0,0 +1,203 @@
+//! Admin unit API HTTP handler tests
+//!
+//! Covers access control on the unit APIs and the boundary delegated admins
+//! hit on user management.
+
+use crate::support::http::{
+    build_test_router, delete_json_with_auth, get_json_with_auth, post_json_with_auth, TestAppState,
+};
+use crate::support::{create_test_jwt_manager, create_test_user};
+use auth9_core::http_support::MessageResponse;
+use auth9_core::models::admin_unit::{AdminUnitUsersInput, CreateAdminUnitInput};
+use auth9_core::models::common::StringUuid;
+use auth9_core::models::user::TenantUser;
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn tenant_token(tenant_id: Uuid, roles: &[&str]) -> String {
+    create_test_jwt_manager()
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "admin@example.com",
+            tenant_id,
+            "test-client",
+            roles.iter().map(|r| r.to_string()).collect(),
+            vec![],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_create_admin_unit_requires_owner() {
+    let app = build_test_router(TestAppState::new());
+    let tenant_id = Uuid::new_v4();
+    let token = tenant_token(tenant_id, &["admin"]);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/admin-units", tenant_id),
+        &json!({ "name": "Engineering" }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_add_unit_admins_requires_owner() {
+    let app = build_test_router(TestAppState::new());
+    let tenant_id = Uuid::new_v4();
+    let token = tenant_token(tenant_id, &["admin"]);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/admin-units/{}/admins",
+            tenant_id,
+            Uuid::new_v4()
+        ),
+        &json!({ "user_ids": [Uuid::new_v4()] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_list_admin_units_of_other_tenant_forbidden() {
+    let app = build_test_router(TestAppState::new());
+    let token = tenant_token(Uuid::new_v4(), &["owner"]);
+
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/admin-units", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+async fn add_tenant_member(state: &TestAppState, tenant_id: Uuid, user_id: Uuid, role: &str) {
+    state
+        .user_repo
+        .add_user(create_test_user(Some(user_id)))
+        .await;
+    state
+        .user_repo
+        .add_tenant_user(TenantUser {
+            id: StringUuid::new_v4(),
+            tenant_id: StringUuid::from(tenant_id),
+            user_id: StringUuid::from(user_id),
+            role_in_tenant: role.to_string(),
+            joined_at: chrono::Utc::now(),
+        })
+        .await;
+}
+
+/// Tenant with units A and B; the delegated admin administers A only
+async fn delegated_admin_state(
+    tenant_id: Uuid,
+    admin_id: Uuid,
+    member_of_a: Uuid,
+    member_of_b: Uuid,
+) -> TestAppState {
+    let state = TestAppState::new();
+    add_tenant_member(&state, tenant_id, admin_id, "admin").await;
+    add_tenant_member(&state, tenant_id, member_of_a, "member").await;
+    add_tenant_member(&state, tenant_id, member_of_b, "member").await;
+
+    let tenant = StringUuid::from(tenant_id);
+    for (name, admins, members) in [
+        ("Unit A", vec![admin_id], member_of_a),
+        ("Unit B", vec![], member_of_b),
+    ] {
+        let unit = state
+            .admin_unit_service
+            .create(
+                tenant,
+                CreateAdminUnitInput {
+                    name: name.to_string(),
+                    description: None,
+                },
+            )
+            .await
+            .unwrap();
+        state
+            .admin_unit_service
+            .add_members(
+                tenant,
+                unit.id,
+                AdminUnitUsersInput {
+                    user_ids: vec![StringUuid::from(members)],
+                },
+            )
+            .await
+            .unwrap();
+        if !admins.is_empty() {
+            state
+                .admin_unit_service
+                .add_admins(
+                    tenant,
+                    unit.id,
+                    AdminUnitUsersInput {
+                        user_ids: admins.into_iter().map(StringUuid::from).collect(),
+                    },
+                )
+                .await
+                .unwrap();
+        }
+    }
+    state
+}
+
+fn delegated_admin_token(admin_id: Uuid, tenant_id: Uuid) -> String {
+    create_test_jwt_manager()
+        .create_tenant_access_token(
+            admin_id,
+            "delegate@example.com",
+            tenant_id,
+            "test-client",
+            vec!["admin".to_string()],
+            vec!["user:*".to_string()],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_unit_admin_denied_user_in_other_unit() {
+    let (tenant_id, admin_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let (member_of_a, member_of_b) = (Uuid::new_v4(), Uuid::new_v4());
+    let state = delegated_admin_state(tenant_id, admin_id, member_of_a, member_of_b).await;
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = delete_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}", member_of_b),
+        &delegated_admin_token(admin_id, tenant_id),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_unit_admin_manages_user_in_own_unit() {
+    let (tenant_id, admin_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let (member_of_a, member_of_b) = (Uuid::new_v4(), Uuid::new_v4());
+    let state = delegated_admin_state(tenant_id, admin_id, member_of_a, member_of_b).await;
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<MessageResponse>) = delete_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}", member_of_a),
+        &delegated_admin_token(admin_id, tenant_id),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.unwrap().message.contains("deleted"));
+}
@@ -0,0 +1,116 @@
+//! Tenant custom domain API HTTP handler tests
+//!
+//! Covers authentication, tenant access control and the public
+//! edge/hosted-page endpoints that do not require a database hit.
+
+use crate::support::http::{
+    build_test_router, get_json, get_json_with_auth, post_json, post_json_with_auth, TestAppState,
+};
+use crate::support::{create_test_identity_token_for_user, create_test_jwt_manager};
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn create_tenant_access_token(tenant_id: Uuid) -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "admin@example.com",
+            tenant_id,
+            "test-client",
+            vec!["admin".to_string()],
+            vec![],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_create_custom_domain_requires_auth() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) = post_json(
+        &app,
+        &format!("/api/v1/tenants/{}/custom-domains", Uuid::new_v4()),
+        &json!({ "domain": "auth.customer.com" }),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_list_custom_domains_hidden_from_other_tenant() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_tenant_access_token(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/custom-domains", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_verify_custom_domain_hidden_from_other_tenant() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_tenant_access_token(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = post_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}/custom-domains/{}/verify",
+            Uuid::new_v4(),
+            Uuid::new_v4()
+        ),
+        &json!({}),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_list_custom_domains_identity_token_non_admin_returns_403() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let token = create_test_identity_token_for_user(Uuid::new_v4());
+    let (status, _body): (StatusCode, Option<Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/custom-domains", Uuid::new_v4()),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_current_custom_domain_not_found_on_platform_host() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/public/custom-domains/current").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_tls_ask_requires_domain_param() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, "/api/v1/public/custom-domains/tls-ask").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,77 @@
+//! Tenant inactivity policy preview HTTP tests
+//!
+//! Covers access control and tenants without a policy; the preview itself
+//! queries the database and is covered by the service tests.
+
+use crate::support::http::{build_test_router, get_json, get_json_with_auth, TestAppState};
+use crate::support::{create_test_jwt_manager, create_test_tenant};
+use axum::http::StatusCode;
+use serde_json::Value;
+use uuid::Uuid;
+
+fn tenant_token(tenant_id: Uuid, roles: &[&str]) -> String {
+    create_test_jwt_manager()
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "admin@example.com",
+            tenant_id,
+            "test-client",
+            roles.iter().map(|r| r.to_string()).collect(),
+            vec![],
+        )
+        .unwrap()
+}
+
+fn preview_path(tenant_id: Uuid) -> String {
+    format!("/api/v1/tenants/{}/inactivity-policy/preview", tenant_id)
+}
+
+#[tokio::test]
+async fn test_preview_requires_auth() {
+    let app = build_test_router(TestAppState::new());
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json(&app, &preview_path(Uuid::new_v4())).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_preview_of_other_tenant_forbidden() {
+    let app = build_test_router(TestAppState::new());
+    let token = tenant_token(Uuid::new_v4(), &["admin"]);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, &preview_path(Uuid::new_v4()), &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_preview_requires_admin_role() {
+    let app = build_test_router(TestAppState::new());
+    let tenant_id = Uuid::new_v4();
+    let token = tenant_token(tenant_id, &["viewer"]);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, &preview_path(tenant_id), &token).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_preview_without_policy_returns_404() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    let app = build_test_router(state);
+    let token = tenant_token(tenant_id, &["admin"]);
+
+    let (status, _body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, &preview_path(tenant_id), &token).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -18,7 +18,7 @@ use chrono::Utc;
 
 #[tokio::test]
 async fn test_list_invitations_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -44,7 +44,7 @@ async fn test_list_invitations_empty() {
 
 #[tokio::test]
 async fn test_list_invitations_with_data() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -87,7 +87,7 @@ async fn test_list_invitations_with_data() {
 
 #[tokio::test]
 async fn test_list_invitations_filter_by_status() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -153,7 +153,7 @@ async fn test_list_invitations_filter_by_status() {
 
 #[tokio::test]
 async fn test_create_invitation_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -196,7 +196,7 @@ async fn test_create_invitation_success() {
 
 #[tokio::test]
 async fn test_create_invitation_no_auth() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -227,7 +227,7 @@ async fn test_create_invitation_no_auth() {
 
 #[tokio::test]
 async fn test_get_invitation_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let invitation = Invitation {
         id: StringUuid::new_v4(),
@@ -264,7 +264,7 @@ async fn test_get_invitation_success() {
 
 #[tokio::test]
 async fn test_get_invitation_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
     let token = create_test_identity_token();
@@ -287,7 +287,7 @@ async fn test_get_invitation_not_found() {
 
 #[tokio::test]
 async fn test_revoke_invitation_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let invitation = Invitation {
         id: StringUuid::new_v4(),
@@ -325,7 +325,7 @@ async fn test_revoke_invitation_success() {
 
 #[tokio::test]
 async fn test_revoke_invitation_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
     let token = create_test_identity_token();
@@ -349,7 +349,7 @@ async fn test_revoke_invitation_not_found() {
 
 #[tokio::test]
 async fn test_delete_invitation_success() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let invitation = Invitation {
         id: StringUuid::new_v4(),
@@ -385,7 +385,7 @@ async fn test_delete_invitation_success() {
 
 #[tokio::test]
 async fn test_delete_invitation_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
     let token = create_test_identity_token();
@@ -409,7 +409,7 @@ async fn test_delete_invitation_not_found() {
 #[tokio::test]
 async fn test_resend_invitation_no_email_provider() {
     // When email provider is not configured, resend should return 400
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -448,7 +448,7 @@ async fn test_resend_invitation_no_email_provider() {
 
 #[tokio::test]
 async fn test_resend_invitation_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
     let token = create_test_identity_token();
@@ -467,7 +467,7 @@ async fn test_resend_invitation_not_found() {
 
 #[tokio::test]
 async fn test_resend_revoked_invitation_fails() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let invitation = Invitation {
         id: StringUuid::new_v4(),
@@ -506,7 +506,7 @@ async fn test_resend_revoked_invitation_fails() {
 
 #[tokio::test]
 async fn test_accept_invitation_empty_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
 
@@ -523,7 +523,7 @@ async fn test_accept_invitation_empty_token() {
 
 #[tokio::test]
 async fn test_accept_invitation_invalid_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let app = build_invitation_test_router(state);
 
@@ -544,7 +544,7 @@ async fn test_accept_invitation_invalid_token() {
 
 #[tokio::test]
 async fn test_list_invitations_service_client_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -569,7 +569,7 @@ async fn test_list_invitations_service_client_returns_403() {
 
 #[tokio::test]
 async fn test_list_invitations_non_admin_identity_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -591,7 +591,7 @@ async fn test_list_invitations_non_admin_identity_returns_403() {
 
 #[tokio::test]
 async fn test_list_invitations_tenant_access_wrong_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -624,7 +624,7 @@ async fn test_list_invitations_tenant_access_wrong_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_list_invitations_tenant_access_same_tenant_succeeds() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -658,7 +658,7 @@ async fn test_list_invitations_tenant_access_same_tenant_succeeds() {
 
 #[tokio::test]
 async fn test_create_invitation_service_client_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -689,7 +689,7 @@ async fn test_create_invitation_service_client_returns_403() {
 
 #[tokio::test]
 async fn test_create_invitation_tenant_access_member_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = *tenant.id;
@@ -728,7 +728,7 @@ async fn test_create_invitation_tenant_access_member_returns_403() {
 
 #[tokio::test]
 async fn test_create_invitation_cross_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -767,7 +767,7 @@ async fn test_create_invitation_cross_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_create_invitation_non_admin_identity_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -794,7 +794,7 @@ async fn test_create_invitation_non_admin_identity_returns_403() {
 
 #[tokio::test]
 async fn test_create_invitation_user_already_member_returns_409() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -837,7 +837,7 @@ async fn test_create_invitation_user_already_member_returns_409() {
 
 #[tokio::test]
 async fn test_create_invitation_invalid_role_returns_400() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -869,7 +869,7 @@ async fn test_create_invitation_invalid_role_returns_400() {
 
 #[tokio::test]
 async fn test_list_invitations_with_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -960,7 +960,7 @@ fn create_pending_invitation(
 async fn test_accept_invitation_existing_user_success() {
     use auth9_core::models::user::User;
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -997,7 +997,7 @@ async fn test_accept_invitation_existing_user_success() {
 
 #[tokio::test]
 async fn test_accept_invitation_new_user_with_keycloak_creation() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1027,7 +1027,7 @@ async fn test_accept_invitation_new_user_with_keycloak_creation() {
 
 #[tokio::test]
 async fn test_accept_invitation_new_user_without_password_fails() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1052,7 +1052,7 @@ async fn test_accept_invitation_new_user_without_password_fails() {
 
 #[tokio::test]
 async fn test_accept_invitation_expired() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1077,7 +1077,7 @@ async fn test_accept_invitation_expired() {
 
 #[tokio::test]
 async fn test_accept_invitation_already_accepted() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1104,7 +1104,7 @@ async fn test_accept_invitation_already_accepted() {
 
 #[tokio::test]
 async fn test_accept_invitation_email_mismatch() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1131,7 +1131,7 @@ async fn test_accept_invitation_email_mismatch() {
 async fn test_accept_invitation_with_role_assignment() {
     use auth9_core::models::user::User;
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1181,7 +1181,7 @@ async fn test_accept_invitation_with_role_assignment() {
 
 #[tokio::test]
 async fn test_create_invitation_role_service_not_found() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -1216,7 +1216,7 @@ async fn test_create_invitation_role_service_not_found() {
 
 #[tokio::test]
 async fn test_create_invitation_role_from_different_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant = create_test_tenant(None);
     let tenant_id = tenant.id;
@@ -0,0 +1,104 @@
+//! Invitation link HTTP API handler tests
+
+use crate::support::create_test_tenant;
+use crate::support::http::{get_json, post_json_with_auth, TestAppState};
+use axum::http::StatusCode;
+
+#[tokio::test]
+async fn test_create_invitation_link_non_admin_returns_403() {
+    let state = TestAppState::new();
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_invitation_link_test_router(state);
+    let token = crate::support::create_test_identity_token_for_user(uuid::Uuid::new_v4());
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/invitation-links", tenant_id),
+        &serde_json::json!({ "max_uses": 10 }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_create_invitation_link_invalid_domain_returns_422() {
+    let state = TestAppState::new();
+    let tenant = create_test_tenant(None);
+    let tenant_id = tenant.id;
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_invitation_link_test_router(state);
+    let token = crate::support::create_test_identity_token();
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/invitation-links", tenant_id),
+        &serde_json::json!({ "allowed_email_domains": ["@example.com"] }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_join_invitation_link_service_client_returns_403() {
+    let state = TestAppState::new();
+    let app = build_invitation_link_test_router(state);
+
+    let jwt_manager = crate::support::create_test_jwt_manager();
+    let token = jwt_manager
+        .create_service_client_token(uuid::Uuid::new_v4(), "svc@test.com", None)
+        .unwrap();
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        "/api/v1/invitation-links/join",
+        &serde_json::json!({ "token": "abc" }),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_validate_invitation_link_empty_token_returns_422() {
+    let state = TestAppState::new();
+    let app = build_invitation_link_test_router(state);
+
+    let (status, _): (StatusCode, Option<serde_json::Value>) =
+        get_json(&app, "/api/v1/invitation-links/validate?token=").await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+fn build_invitation_link_test_router(state: TestAppState) -> axum::Router {
+    use auth9_core::domains::tenant_access::api::invitation_link;
+    use axum::routing::{get, post};
+
+    axum::Router::new()
+        .route(
+            "/api/v1/tenants/{tenant_id}/invitation-links",
+            get(invitation_link::list::<TestAppState>)
+                .post(invitation_link::create::<TestAppState>),
+        )
+        .route(
+            "/api/v1/tenants/{tenant_id}/invitation-links/{id}/revoke",
+            post(invitation_link::revoke::<TestAppState>),
+        )
+        .route(
+            "/api/v1/invitation-links/validate",
+            get(invitation_link::validate_token::<TestAppState>),
+        )
+        .route(
+            "/api/v1/invitation-links/join",
+            post(invitation_link::join::<TestAppState>),
+        )
+        .with_state(state)
+}
@@ -1,4 +1,10 @@
+mod admin_unit_http_test;
+mod custom_domain_http_test;
+mod inactivity_http_test;
 mod invitation_http_test;
+mod invitation_link_http_test;
+mod notification_preference_http_test;
+mod response_masking_http_test;
 mod tenant_http_test;
 mod tenant_service_test;
 mod tenant_sso_http_test;
@@ -0,0 +1,85 @@
+//! Notification Preference HTTP Handler Tests
+
+use crate::support::create_test_jwt_manager;
+use crate::support::http::{
+    build_test_router, get_json_with_auth, post_json, put_json_with_auth, TestAppState,
+};
+use axum::http::StatusCode;
+use serde_json::json;
+use uuid::Uuid;
+
+fn create_unprivileged_token() -> String {
+    let jwt_manager = create_test_jwt_manager();
+    jwt_manager
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "member@auth9.local",
+            Uuid::new_v4(),
+            "auth9-test-service",
+            vec!["member".to_string()],
+            vec![],
+        )
+        .expect("failed to create member token")
+}
+
+#[tokio::test]
+async fn test_get_other_users_notification_preferences_forbidden() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}/notification-preferences", Uuid::new_v4()),
+        &create_unprivileged_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_update_other_users_notification_preferences_forbidden() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}/notification-preferences", Uuid::new_v4()),
+        &json!({ "security_alerts": false }),
+        &create_unprivileged_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_update_notification_preferences_rejects_unknown_frequency() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}/notification-preferences", Uuid::new_v4()),
+        &json!({ "digest_frequency": "hourly" }),
+        &create_unprivileged_token(),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_unsubscribe_rejects_forged_token() {
+    let state = TestAppState::new();
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json(
+        &app,
+        "/api/v1/notifications/unsubscribe?token=dXNlcjpkaWdlc3Q.c2lnbmF0dXJl",
+        &json!({}),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,164 @@
+//! Response masking HTTP tests
+//!
+//! Lists tenant members with tenant access tokens that do and do not hold
+//! `pii:read` while masking is enabled, and exports their sessions as NDJSON.
+
+use crate::support::http::{build_test_router, get_json_with_auth, TestAppState};
+use crate::support::{create_test_jwt_manager, create_test_user};
+use auth9_core::middleware::response_masking::MASKED_FIELDS_HEADER;
+use auth9_core::models::common::StringUuid;
+use auth9_core::models::session::Session;
+use auth9_core::models::user::TenantUser;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use serde_json::Value;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn masking_state(tenant_id: Uuid) -> TestAppState {
+    let mut state = TestAppState::new();
+    let mut config = (*state.config).clone();
+    config.response_masking.enabled = true;
+    state.config = Arc::new(config);
+
+    let mut user = create_test_user(None);
+    user.email = "john@acme.com".to_string();
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+    state
+        .session_repo
+        .add_tenant_member(StringUuid::from(tenant_id), user_id)
+        .await;
+    state
+        .session_repo
+        .add_session(Session {
+            id: StringUuid::new_v4(),
+            user_id,
+            provider_session_id: None,
+            device_type: Some("desktop".to_string()),
+            device_name: None,
+            display_name: None,
+            ip_address: Some("203.0.113.7".to_string()),
+            location: None,
+            user_agent: None,
+            last_active_at: chrono::Utc::now(),
+            created_at: chrono::Utc::now(),
+            revoked_at: None,
+        })
+        .await;
+    state
+        .user_repo
+        .add_tenant_user(TenantUser {
+            id: StringUuid::new_v4(),
+            user_id,
+            tenant_id: StringUuid::from(tenant_id),
+            role_in_tenant: "member".to_string(),
+            joined_at: chrono::Utc::now(),
+        })
+        .await;
+    state
+}
+
+fn tenant_token(tenant_id: Uuid, permissions: &[&str]) -> String {
+    create_test_jwt_manager()
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "viewer@acme.com",
+            tenant_id,
+            "auth9-test-service",
+            vec!["viewer".to_string()],
+            permissions.iter().map(|p| p.to_string()).collect(),
+        )
+        .unwrap()
+}
+
+async fn first_member_email(state: TestAppState, tenant_id: Uuid, token: &str) -> String {
+    let app = build_test_router(state);
+    let (status, body): (StatusCode, Option<Value>) =
+        get_json_with_auth(&app, &format!("/api/v1/tenants/{}/users", tenant_id), token).await;
+    assert_eq!(status, StatusCode::OK);
+    body.unwrap()["data"][0]["email"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_member_list_masked_without_pii_permission() {
+    let tenant_id = Uuid::new_v4();
+    let state = masking_state(tenant_id).await;
+    let token = tenant_token(tenant_id, &["user:*"]);
+
+    assert_eq!(
+        first_member_email(state, tenant_id, &token).await,
+        "j***@acme.com"
+    );
+}
+
+#[tokio::test]
+async fn test_member_list_unmasked_with_pii_permission() {
+    let tenant_id = Uuid::new_v4();
+    let state = masking_state(tenant_id).await;
+    let token = tenant_token(tenant_id, &["user:*", "pii:read"]);
+
+    assert_eq!(
+        first_member_email(state, tenant_id, &token).await,
+        "john@acme.com"
+    );
+}
+
+/// Export the tenant's sessions; returns the masked-fields header and the
+/// `ip_address` of every line
+async fn exported_ips(
+    state: TestAppState,
+    tenant_id: Uuid,
+    token: &str,
+) -> (Option<String>, Vec<String>) {
+    let app = build_test_router(state);
+    let request = Request::builder()
+        .uri(format!("/api/v1/tenants/{}/sessions/export", tenant_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let masked_fields = response
+        .headers()
+        .get(MASKED_FIELDS_HEADER)
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let ips = String::from_utf8(body.to_vec())
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let session: Value = serde_json::from_str(line).unwrap();
+            session["ip_address"].as_str().unwrap().to_string()
+        })
+        .collect();
+    (masked_fields, ips)
+}
+
+#[tokio::test]
+async fn test_session_export_masked_without_pii_permission() {
+    let tenant_id = Uuid::new_v4();
+    let state = masking_state(tenant_id).await;
+    let token = tenant_token(tenant_id, &["session:read"]);
+
+    let (masked_fields, ips) = exported_ips(state, tenant_id, &token).await;
+    assert_eq!(ips, vec!["203.0.113.*"]);
+    assert_eq!(masked_fields.as_deref(), Some("email,ip_address"));
+}
+
+#[tokio::test]
+async fn test_session_export_unmasked_with_pii_permission() {
+    let tenant_id = Uuid::new_v4();
+    let state = masking_state(tenant_id).await;
+    let token = tenant_token(tenant_id, &["session:read", "pii:read"]);
+
+    let (masked_fields, ips) = exported_ips(state, tenant_id, &token).await;
+    assert_eq!(ips, vec!["203.0.113.7"]);
+    assert_eq!(masked_fields, None);
+}
@@ -7,12 +7,15 @@ use crate::support::http::{
     put_json_with_auth, TestAppState,
 };
 use crate::support::{
-    create_test_identity_token, create_test_jwt_manager, create_test_tenant,
-    create_test_tenant_access_token, create_test_tenant_access_token_for_tenant,
+    create_test_identity_token, create_test_jwt_manager, create_test_service, create_test_tenant,
+    create_test_tenant_access_token, create_test_tenant_access_token_for_tenant, create_test_user,
 };
 use auth9_core::http_support::{MessageResponse, PaginatedResponse, SuccessResponse};
+use auth9_core::models::cardinality::CardinalityLimits;
+use auth9_core::models::common::StringUuid;
 use auth9_core::models::system_settings::TenantMaliciousIpBlacklistEntry;
 use auth9_core::models::tenant::{Tenant, TenantStatus};
+use auth9_core::models::user::TenantUser;
 use auth9_core::repository::MaliciousIpBlacklistRepository;
 use axum::body::Body;
 use axum::http::{Method, Request, StatusCode};
@@ -26,7 +29,7 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_list_tenants_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin token
 
     // Add some test tenants
@@ -52,7 +55,7 @@ async fn test_list_tenants_returns_200() {
 
 #[tokio::test]
 async fn test_list_tenants_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token();
 
     // Add 25 tenants
@@ -81,7 +84,7 @@ async fn test_list_tenants_pagination() {
 
 #[tokio::test]
 async fn test_list_tenants_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token();
     let app = build_test_router(state);
 
@@ -101,7 +104,7 @@ async fn test_list_tenants_empty() {
 
 #[tokio::test]
 async fn test_get_tenant_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
@@ -124,7 +127,7 @@ async fn test_get_tenant_returns_200() {
 
 #[tokio::test]
 async fn test_get_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let nonexistent_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(nonexistent_id);
     let app = build_test_router(state);
@@ -135,13 +138,132 @@ async fn test_get_tenant_returns_404() {
     assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_get_tenant_with_expansions() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    let service_id = Uuid::new_v4();
+    state
+        .service_repo
+        .add_service(create_test_service(Some(service_id), Some(tenant_id)))
+        .await;
+    let owner_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_user(create_test_user(Some(owner_id)))
+        .await;
+    state
+        .user_repo
+        .add_tenant_user(TenantUser {
+            id: StringUuid::new_v4(),
+            user_id: StringUuid::from(owner_id),
+            tenant_id: StringUuid::from(tenant_id),
+            role_in_tenant: "owner".to_string(),
+            joined_at: chrono::Utc::now(),
+        })
+        .await;
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!(
+            "/api/v1/tenants/{}?expand[]=services.roles&expand[]=admin_users",
+            tenant_id
+        ),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let data = &body.unwrap()["data"];
+    assert_eq!(data["id"], tenant_id.to_string());
+    assert_eq!(data["services"]["has_more"], false);
+    let service = &data["services"]["data"][0];
+    assert_eq!(service["id"], service_id.to_string());
+    assert!(service["roles"]["data"].is_array());
+    assert!(service.get("clients").is_none());
+    let admin = &data["admin_users"]["data"][0];
+    assert_eq!(admin["id"], owner_id.to_string());
+    assert_eq!(admin["role_in_tenant"], "owner");
+}
+
+#[tokio::test]
+async fn test_get_tenant_rejects_unknown_or_deep_expansions() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    let app = build_test_router(state);
+
+    for expand in ["invitations", "services.roles.permissions"] {
+        let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+            &app,
+            &format!("/api/v1/tenants/{}?expand[]={}", tenant_id, expand),
+            &token,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "expand={}", expand);
+    }
+}
+
+#[tokio::test]
+async fn test_get_tenant_expansion_checks_permission() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_jwt_manager()
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "member@test.com",
+            tenant_id,
+            "auth9-test-service",
+            vec!["member".to_string()],
+            vec![],
+        )
+        .unwrap();
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    state
+        .service_repo
+        .add_service(create_test_service(None, Some(tenant_id)))
+        .await;
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}?expand[]=services", tenant_id),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}?expand[]=services.clients", tenant_id),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let message = body.unwrap()["message"].as_str().unwrap().to_string();
+    assert!(message.contains("services.clients"));
+}
+
 // ============================================================================
 // Create Tenant Tests
 // ============================================================================
 
 #[tokio::test]
 async fn test_create_tenant_returns_201() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token(); // Platform admin Identity token required
     let app = build_test_router(state);
 
@@ -166,9 +288,61 @@ async fn test_create_tenant_returns_201() {
     assert_eq!(response.data.status, TenantStatus::Active);
 }
 
+#[tokio::test]
+async fn test_create_tenant_pinned_to_data_region() {
+    let mut state = TestAppState::new();
+    let mut config = (*state.config).clone();
+    config.data_residency.region_database_urls.insert(
+        "eu-west".to_string(),
+        "mysql://auth9@eu-db:4000/auth9".to_string(),
+    );
+    state.config = std::sync::Arc::new(config);
+    let token = create_test_identity_token();
+    let app = build_test_router(state);
+
+    let input = json!({
+        "name": "EU Tenant",
+        "slug": "eu-tenant",
+        "data_region": "eu-west"
+    });
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) =
+        post_json_with_auth(&app, "/api/v1/tenants", &input, &token).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body.unwrap().data.data_region, Some("eu-west".to_string()));
+
+    // The home region is stored unpinned
+    let input = json!({
+        "name": "Home Tenant",
+        "slug": "home-tenant",
+        "data_region": "default"
+    });
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) =
+        post_json_with_auth(&app, "/api/v1/tenants", &input, &token).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body.unwrap().data.data_region, None);
+}
+
+#[tokio::test]
+async fn test_create_tenant_unknown_data_region_returns_400() {
+    let state = TestAppState::new();
+    let token = create_test_identity_token();
+    let app = build_test_router(state);
+
+    let input = json!({
+        "name": "Mars Tenant",
+        "slug": "mars-tenant",
+        "data_region": "mars-1"
+    });
+    let (status, body): (StatusCode, Option<serde_json::Value>) =
+        post_json_with_auth(&app, "/api/v1/tenants", &input, &token).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body.unwrap()["error"], "unknown_data_region");
+}
+
 #[tokio::test]
 async fn test_create_tenant_duplicate_slug() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
 
     // Add existing tenant with slug "existing-tenant"
@@ -192,7 +366,7 @@ async fn test_create_tenant_duplicate_slug() {
 
 #[tokio::test]
 async fn test_create_tenant_validation_error_empty_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
@@ -209,14 +383,14 @@ async fn test_create_tenant_validation_error_empty_name() {
 }
 
 #[tokio::test]
-async fn test_create_tenant_validation_error_empty_slug() {
-    let state = TestAppState::new("http://localhost:8081");
+async fn test_create_tenant_validation_error_invalid_slug() {
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
     let input = json!({
         "name": "Valid Name",
-        "slug": ""  // empty slug should fail validation
+        "slug": "Not A Slug"
     });
 
     let (status, _body): (StatusCode, Option<serde_json::Value>) =
@@ -225,9 +399,42 @@ async fn test_create_tenant_validation_error_empty_slug() {
     assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+#[tokio::test]
+async fn test_create_tenant_without_slug_generates_unique_slug() {
+    let state = TestAppState::new();
+    let token = create_test_identity_token();
+
+    let mut existing = create_test_tenant(None);
+    existing.slug = "acme-corp".to_string();
+    state.tenant_repo.add_tenant(existing).await;
+
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) = post_json_with_auth(
+        &app,
+        "/api/v1/tenants",
+        &json!({ "name": "Acme Corp" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let slug = body.unwrap().data.slug;
+    assert!(slug.starts_with("acme-corp-"), "unexpected slug {}", slug);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) = post_json_with_auth(
+        &app,
+        "/api/v1/tenants",
+        &json!({ "name": "Globex", "slug": "" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body.unwrap().data.slug, "globex");
+}
+
 #[tokio::test]
 async fn test_create_tenant_with_settings() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
@@ -257,7 +464,7 @@ async fn test_create_tenant_with_settings() {
 
 #[tokio::test]
 async fn test_update_tenant_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
@@ -287,7 +494,7 @@ async fn test_update_tenant_returns_200() {
 
 #[tokio::test]
 async fn test_update_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let nonexistent_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(nonexistent_id);
     let app = build_test_router(state);
@@ -309,7 +516,7 @@ async fn test_update_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_update_tenant_status() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
@@ -338,7 +545,7 @@ async fn test_update_tenant_status() {
 
 #[tokio::test]
 async fn test_update_tenant_logo_url() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
@@ -368,13 +575,243 @@ async fn test_update_tenant_logo_url() {
     );
 }
 
+fn create_test_owner_token(tenant_id: Uuid) -> String {
+    create_test_jwt_manager()
+        .create_tenant_access_token(
+            Uuid::new_v4(),
+            "owner@test.com",
+            tenant_id,
+            "auth9-test-service",
+            vec!["owner".to_string()],
+            vec![],
+        )
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_update_tenant_cardinality_limits_requires_platform_admin() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    let app = build_test_router(state);
+
+    let input = json!({
+        "settings": { "cardinality_limits": { "max_users_per_tenant": 1000000 } }
+    });
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}", tenant_id),
+        &input,
+        &create_test_owner_token(tenant_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}", tenant_id),
+        &input,
+        &create_test_tenant_access_token_for_tenant(tenant_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let limits = body.unwrap().data.settings.cardinality_limits.unwrap();
+    assert_eq!(limits.max_users_per_tenant, Some(1_000_000));
+}
+
+#[tokio::test]
+async fn test_update_tenant_settings_by_owner_keeps_cardinality_limits() {
+    let state = TestAppState::new();
+    let tenant_id = Uuid::new_v4();
+    let mut tenant = create_test_tenant(Some(tenant_id));
+    tenant.settings.cardinality_limits = Some(CardinalityLimits {
+        max_roles_per_user: Some(500),
+        ..Default::default()
+    });
+    state.tenant_repo.add_tenant(tenant).await;
+    let app = build_test_router(state);
+
+    let input = json!({ "settings": { "require_mfa": true } });
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) = put_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}", tenant_id),
+        &input,
+        &create_test_owner_token(tenant_id),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let settings = body.unwrap().data.settings;
+    assert!(settings.require_mfa);
+    assert_eq!(
+        settings.cardinality_limits.unwrap().max_roles_per_user,
+        Some(500)
+    );
+}
+
+#[tokio::test]
+async fn test_update_tenant_with_stale_if_match_returns_412() {
+    let state = TestAppState::new();
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+    let tenant = create_test_tenant(Some(tenant_id));
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_test_router(state);
+
+    let put_if_match = |name: &'static str, if_match: &'static str| {
+        Request::builder()
+            .method(Method::PUT)
+            .uri(format!("/api/v1/tenants/{}", tenant_id))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("If-Match", if_match)
+            .body(Body::from(json!({ "name": name }).to_string()))
+            .unwrap()
+    };
+
+    // First writer holds the current version and succeeds
+    let response = app
+        .clone()
+        .oneshot(put_if_match("First", "\"1\""))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("etag").unwrap(), "\"2\"");
+
+    // Second writer still holds version 1 and must not overwrite the change
+    let response = app
+        .clone()
+        .oneshot(put_if_match("Second", "\"1\""))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(response.headers().get("etag").unwrap(), "\"2\"");
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["error"], "precondition_failed");
+    assert_eq!(body["details"]["current_version"], 2);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<Tenant>>) =
+        get_json_with_auth(&app, &format!("/api/v1/tenants/{}", tenant_id), &token).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.name, "First");
+}
+
+// ============================================================================
+// Rename Tenant Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_rename_tenant_slug_keeps_old_slug_resolving() {
+    let state = TestAppState::new();
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+    let mut tenant = create_test_tenant(Some(tenant_id));
+    tenant.slug = "acme".to_string();
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/rename", tenant_id),
+        &json!({ "name": "Acme Labs", "slug": "acme-labs" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let body = body.unwrap();
+    assert_eq!(body["data"]["tenant"]["slug"], "acme-labs");
+    assert_eq!(body["data"]["tenant"]["name"], "Acme Labs");
+    assert_eq!(body["data"]["slug_redirect"]["old_slug"], "acme");
+
+    // The old slug still resolves, flagged as deprecated
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/tenants/by-slug/acme")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("deprecation"));
+    assert!(response.headers().contains_key("sunset"));
+    assert_eq!(
+        response.headers().get("link").unwrap(),
+        "</api/v1/tenants/by-slug/acme-labs>; rel=\"successor-version\""
+    );
+
+    // The current slug resolves without deprecation headers
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/tenants/by-slug/acme-labs")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key("deprecation"));
+
+    // The retired slug stays reserved for the renamed tenant
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        "/api/v1/tenants",
+        &json!({ "name": "Other", "slug": "acme" }),
+        &create_test_identity_token(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_rename_tenant_to_taken_slug_returns_409() {
+    let state = TestAppState::new();
+
+    let tenant_id = Uuid::new_v4();
+    let token = create_test_tenant_access_token_for_tenant(tenant_id);
+    state
+        .tenant_repo
+        .add_tenant(create_test_tenant(Some(tenant_id)))
+        .await;
+    let mut other = create_test_tenant(None);
+    other.slug = "globex".to_string();
+    state.tenant_repo.add_tenant(other).await;
+
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = post_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}/rename", tenant_id),
+        &json!({ "slug": "globex" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
 // ============================================================================
 // Delete Tenant Tests
 // ============================================================================
 
 #[tokio::test]
 async fn test_delete_tenant_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token(); // Platform admin Identity token required
 
     let tenant_id = Uuid::new_v4();
@@ -414,7 +851,7 @@ async fn test_delete_tenant_returns_200() {
 
 #[tokio::test]
 async fn test_delete_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
@@ -436,7 +873,7 @@ async fn test_delete_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_delete_tenant_requires_confirmation_header() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
 
     let tenant_id = Uuid::new_v4();
@@ -452,13 +889,39 @@ async fn test_delete_tenant_requires_confirmation_header() {
     assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
 }
 
+#[tokio::test]
+async fn test_async_delete_tenant_requires_confirmation_header() {
+    let state = TestAppState::new();
+    let token = create_test_identity_token();
+
+    let tenant_id = Uuid::new_v4();
+    let tenant = create_test_tenant(Some(tenant_id));
+    state.tenant_repo.add_tenant(tenant).await;
+
+    let app = build_test_router(state.clone());
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = delete_json_with_auth(
+        &app,
+        &format!("/api/v1/tenants/{}?async=true", tenant_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(state
+        .tenant_service
+        .get(auth9_core::models::common::StringUuid::from(tenant_id))
+        .await
+        .is_ok());
+}
+
 // ============================================================================
 // Edge Cases and Special Scenarios
 // ============================================================================
 
 #[tokio::test]
 async fn test_tenant_with_unicode_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
@@ -478,7 +941,7 @@ async fn test_tenant_with_unicode_name() {
 
 #[tokio::test]
 async fn test_tenant_with_special_chars_in_name() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_identity_token();
     let app = build_test_router(state);
 
@@ -498,7 +961,7 @@ async fn test_tenant_with_special_chars_in_name() {
 
 #[tokio::test]
 async fn test_list_tenants_default_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token();
 
     // Add 5 tenants
@@ -525,7 +988,7 @@ async fn test_list_tenants_default_pagination() {
 
 #[tokio::test]
 async fn test_get_tenant_malicious_ip_blacklist_returns_entries() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
     state
@@ -560,7 +1023,7 @@ async fn test_get_tenant_malicious_ip_blacklist_returns_entries() {
 
 #[tokio::test]
 async fn test_update_tenant_malicious_ip_blacklist_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
     state
@@ -600,7 +1063,7 @@ async fn test_update_tenant_malicious_ip_blacklist_returns_200() {
 
 #[tokio::test]
 async fn test_update_tenant_malicious_ip_blacklist_denies_cross_tenant_access() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let other_tenant_id = Uuid::new_v4();
     let token = create_test_jwt_manager()
@@ -190,6 +190,7 @@ async fn test_create_tenant_success() {
         domain: None,
         logo_url: Some("https://example.com/logo.png".to_string()),
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -211,6 +212,16 @@ async fn test_create_tenant_with_settings() {
         session_timeout_secs: 7200,
         allowed_auth_methods: vec!["password".to_string(), "sso".to_string()],
         branding: TenantBranding::default(),
+        default_locale: None,
+        token_ttls: Default::default(),
+        custom_scope_claims: Default::default(),
+        session_limit: None,
+        cardinality_limits: None,
+        login_notifications: true,
+        inactivity_policy: None,
+        security_digest: None,
+        mfa_enrollment_campaign: None,
+        login_identifiers: None,
     };
 
     let input = CreateTenantInput {
@@ -219,6 +230,7 @@ async fn test_create_tenant_with_settings() {
         domain: None,
         logo_url: None,
         settings: Some(settings),
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -246,6 +258,7 @@ async fn test_create_tenant_duplicate_slug() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -263,6 +276,7 @@ async fn test_create_tenant_invalid_slug_with_spaces() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -280,6 +294,7 @@ async fn test_create_tenant_invalid_slug_uppercase() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -297,6 +312,7 @@ async fn test_create_tenant_empty_name() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -314,6 +330,7 @@ async fn test_create_tenant_empty_slug() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -341,7 +358,7 @@ async fn test_update_tenant_name_success() {
         status: None,
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(result.unwrap().name, "Updated Name");
 }
@@ -363,7 +380,7 @@ async fn test_update_tenant_logo_success() {
         status: None,
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap().logo_url,
@@ -388,7 +405,7 @@ async fn test_update_tenant_status_to_inactive() {
         status: Some(TenantStatus::Inactive),
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(result.unwrap().status, TenantStatus::Inactive);
 }
@@ -410,7 +427,7 @@ async fn test_update_tenant_status_to_suspended() {
         status: Some(TenantStatus::Suspended),
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(result.unwrap().status, TenantStatus::Suspended);
 }
@@ -430,6 +447,16 @@ async fn test_update_tenant_settings() {
         session_timeout_secs: 1800,
         allowed_auth_methods: vec!["password".to_string()],
         branding: TenantBranding::default(),
+        default_locale: None,
+        token_ttls: Default::default(),
+        custom_scope_claims: Default::default(),
+        session_limit: None,
+        cardinality_limits: None,
+        login_notifications: true,
+        inactivity_policy: None,
+        security_digest: None,
+        mfa_enrollment_campaign: None,
+        login_identifiers: None,
     };
 
     let input = UpdateTenantInput {
@@ -439,7 +466,7 @@ async fn test_update_tenant_settings() {
         status: None,
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
     let updated = result.unwrap();
     assert!(updated.settings.require_mfa);
@@ -458,7 +485,7 @@ async fn test_update_tenant_not_found() {
         status: None,
     };
 
-    let result = service.update(StringUuid::new_v4(), input).await;
+    let result = service.update(StringUuid::new_v4(), input, None).await;
     assert!(matches!(result, Err(AppError::NotFound(_))));
 }
 
@@ -474,7 +501,7 @@ async fn test_update_tenant_invalid_empty_name() {
         status: None,
     };
 
-    let result = service.update(StringUuid::new_v4(), input).await;
+    let result = service.update(StringUuid::new_v4(), input, None).await;
     assert!(matches!(result, Err(AppError::Validation(_))));
 }
 
@@ -549,6 +576,7 @@ async fn test_create_tenant_with_unicode_name() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -567,6 +595,7 @@ async fn test_create_tenant_with_special_chars_name() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -585,6 +614,7 @@ async fn test_create_tenant_slug_with_numbers() {
         domain: None,
         logo_url: None,
         settings: None,
+        data_region: None,
     };
 
     let result = service.create(input).await;
@@ -610,11 +640,21 @@ async fn test_update_multiple_fields_at_once() {
             session_timeout_secs: 7200,
             allowed_auth_methods: vec!["sso".to_string()],
             branding: TenantBranding::default(),
+            default_locale: None,
+            token_ttls: Default::default(),
+            custom_scope_claims: Default::default(),
+            session_limit: None,
+            cardinality_limits: None,
+            login_notifications: true,
+            inactivity_policy: None,
+            security_digest: None,
+            mfa_enrollment_campaign: None,
+            login_identifiers: None,
         }),
         status: Some(TenantStatus::Inactive),
     };
 
-    let result = service.update(tenant_id, input).await;
+    let result = service.update(tenant_id, input, None).await;
     assert!(result.is_ok());
 
     let updated = result.unwrap();
@@ -69,7 +69,7 @@ fn valid_saml_create_input() -> Value {
 
 #[tokio::test]
 async fn test_list_connectors_unauthenticated_returns_401() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let tenant_id = Uuid::new_v4();
 
@@ -80,7 +80,7 @@ async fn test_list_connectors_unauthenticated_returns_401() {
 
 #[tokio::test]
 async fn test_create_connector_unauthenticated_returns_401() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
     let tenant_id = Uuid::new_v4();
 
@@ -100,7 +100,7 @@ async fn test_create_connector_unauthenticated_returns_401() {
 
 #[tokio::test]
 async fn test_list_connectors_wrong_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let token_tenant_id = Uuid::new_v4();
@@ -114,7 +114,7 @@ async fn test_list_connectors_wrong_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_create_connector_wrong_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let token_tenant_id = Uuid::new_v4();
@@ -133,7 +133,7 @@ async fn test_create_connector_wrong_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_update_connector_wrong_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let token_tenant_id = Uuid::new_v4();
@@ -153,7 +153,7 @@ async fn test_update_connector_wrong_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_delete_connector_wrong_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let token_tenant_id = Uuid::new_v4();
@@ -172,7 +172,7 @@ async fn test_delete_connector_wrong_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_identity_token_non_admin_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // Identity token for a non-admin user
@@ -190,7 +190,7 @@ async fn test_identity_token_non_admin_returns_403() {
 
 #[tokio::test]
 async fn test_create_connector_invalid_provider_type() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -215,7 +215,7 @@ async fn test_create_connector_invalid_provider_type() {
 
 #[tokio::test]
 async fn test_create_connector_empty_alias() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -243,7 +243,7 @@ async fn test_create_connector_empty_alias() {
 
 #[tokio::test]
 async fn test_create_connector_empty_domains() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -271,7 +271,7 @@ async fn test_create_connector_empty_domains() {
 
 #[tokio::test]
 async fn test_create_connector_invalid_domain_format() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -299,7 +299,7 @@ async fn test_create_connector_invalid_domain_format() {
 
 #[tokio::test]
 async fn test_create_connector_missing_saml_config_fields() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -326,7 +326,7 @@ async fn test_create_connector_missing_saml_config_fields() {
 
 #[tokio::test]
 async fn test_create_connector_missing_oidc_config_fields() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
@@ -357,7 +357,7 @@ async fn test_create_connector_missing_oidc_config_fields() {
 
 #[tokio::test]
 async fn test_platform_admin_can_access_any_tenant_sso() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
 
     // Platform admin with a TenantAccess token scoped to the target tenant.
@@ -381,7 +381,7 @@ async fn test_platform_admin_can_access_any_tenant_sso() {
 
 #[tokio::test]
 async fn test_create_connector_normalizes_saml_sso_url_alias() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_tenant_access_token(tenant_id);
 
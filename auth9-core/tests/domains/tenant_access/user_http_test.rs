@@ -12,7 +12,9 @@ use crate::support::{
     create_test_user,
 };
 use auth9_core::http_support::{MessageResponse, PaginatedResponse, SuccessResponse};
-use auth9_core::models::user::{TenantUser, TenantUserWithTenant, User};
+use auth9_core::models::user::{
+    TenantUser, TenantUserWithTenant, User, UserTimelineEntry, UserTimelineSource,
+};
 use axum::http::StatusCode;
 use serde_json::json;
 use uuid::Uuid;
@@ -23,7 +25,7 @@ use uuid::Uuid;
 
 #[tokio::test]
 async fn test_list_users_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
 
@@ -60,7 +62,7 @@ async fn test_list_users_returns_200() {
 
 #[tokio::test]
 async fn test_list_users_pagination() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
 
@@ -97,7 +99,7 @@ async fn test_list_users_pagination() {
 
 #[tokio::test]
 async fn test_list_users_empty() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token();
     let app = build_test_router(state);
 
@@ -117,7 +119,7 @@ async fn test_list_users_empty() {
 
 #[tokio::test]
 async fn test_get_user_returns_200() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let mut user = create_test_user(Some(user_id));
@@ -141,7 +143,7 @@ async fn test_get_user_returns_200() {
 
 #[tokio::test]
 async fn test_get_user_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     // Use token with same user_id as the requested user to pass auth check
@@ -159,7 +161,7 @@ async fn test_get_user_returns_404() {
 
 #[tokio::test]
 async fn test_create_user_returns_201() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state.enable_public_registration().await;
     let app = build_test_router(state);
 
@@ -185,7 +187,7 @@ async fn test_create_user_returns_201() {
 async fn test_create_user_without_password() {
     let _keycloak_user_id = "kc-user-no-pwd";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state.enable_public_registration().await;
     let app = build_test_router(state);
 
@@ -213,7 +215,7 @@ async fn test_create_user_without_password() {
 async fn test_update_user_returns_200() {
     let keycloak_user_id = "kc-user-to-update";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can update users
 
     let user_id = Uuid::new_v4();
@@ -240,7 +242,7 @@ async fn test_update_user_returns_200() {
 #[tokio::test]
 async fn test_update_user_avatar_only() {
     // No Keycloak mock needed for avatar-only update (display_name not changed)
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can update users
 
     let user_id = Uuid::new_v4();
@@ -267,7 +269,7 @@ async fn test_update_user_avatar_only() {
 
 #[tokio::test]
 async fn test_update_user_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can update users
     let app = build_test_router(state);
 
@@ -295,7 +297,7 @@ async fn test_update_user_returns_404() {
 async fn test_delete_user_returns_200() {
     let keycloak_user_id = "kc-user-to-delete";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete users
 
     let user_id = Uuid::new_v4();
@@ -316,7 +318,7 @@ async fn test_delete_user_returns_200() {
 
 #[tokio::test]
 async fn test_delete_user_keycloak_not_found_still_succeeds() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete users
 
     let user_id = Uuid::new_v4();
@@ -336,7 +338,7 @@ async fn test_delete_user_keycloak_not_found_still_succeeds() {
 
 #[tokio::test]
 async fn test_delete_user_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token(); // Platform admin can delete users
     let app = build_test_router(state);
 
@@ -353,7 +355,7 @@ async fn test_delete_user_returns_404() {
 
 #[tokio::test]
 async fn test_add_user_to_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let auth_user_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -400,7 +402,7 @@ async fn test_add_user_to_tenant() {
 
 #[tokio::test]
 async fn test_remove_user_from_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let auth_user_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -447,7 +449,7 @@ async fn test_remove_user_from_tenant() {
 
 #[tokio::test]
 async fn test_get_user_tenants() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id1 = Uuid::new_v4();
@@ -492,7 +494,7 @@ async fn test_get_user_tenants() {
 
 #[tokio::test]
 async fn test_list_users_by_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let user1_id = Uuid::new_v4();
@@ -551,7 +553,7 @@ async fn test_enable_mfa() {
     let admin_id = Uuid::new_v4();
     let admin_kc_id = admin_id.to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token_for_user(admin_id, Uuid::new_v4());
 
     let mut admin_user = create_test_user(Some(admin_id));
@@ -586,7 +588,7 @@ async fn test_disable_mfa() {
     let admin_id = Uuid::new_v4();
     let admin_kc_id = admin_id.to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token_for_user(admin_id, Uuid::new_v4());
 
     // Add admin user to repo (needed for password verification lookup)
@@ -621,7 +623,7 @@ async fn test_enable_mfa_user_not_found() {
     let admin_id = Uuid::new_v4();
     let _admin_kc_id = admin_id.to_string();
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let token = create_test_tenant_access_token_for_user(admin_id, Uuid::new_v4());
     let app = build_test_router(state);
 
@@ -643,7 +645,7 @@ async fn test_enable_mfa_user_not_found() {
 
 #[tokio::test]
 async fn test_get_me_returns_current_user() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let mut user = create_test_user(Some(user_id));
@@ -672,7 +674,7 @@ async fn test_get_me_returns_current_user() {
 async fn test_update_me_changes_display_name() {
     let keycloak_user_id = "kc-me-update";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let mut user = create_test_user(Some(user_id));
@@ -696,6 +698,67 @@ async fn test_update_me_changes_display_name() {
     assert_eq!(response.data.display_name, Some("Updated Name".to_string()));
 }
 
+// ============================================================================
+// PUT /api/v1/users/me/locale Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_update_my_locale_sets_and_clears_preference() {
+    let state = TestAppState::new();
+
+    let user_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_user(create_test_user(Some(user_id)))
+        .await;
+
+    let token = create_test_tenant_access_token_for_user(user_id, Uuid::new_v4());
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<SuccessResponse<User>>) = put_json_with_auth(
+        &app,
+        "/api/v1/users/me/locale",
+        &json!({ "locale": "zh-CN" }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.locale.as_deref(), Some("zh-CN"));
+
+    let (status, body): (StatusCode, Option<SuccessResponse<User>>) = put_json_with_auth(
+        &app,
+        "/api/v1/users/me/locale",
+        &json!({ "locale": null }),
+        &token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.unwrap().data.locale, None);
+}
+
+#[tokio::test]
+async fn test_update_my_locale_rejects_unsupported_locale() {
+    let state = TestAppState::new();
+
+    let user_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_user(create_test_user(Some(user_id)))
+        .await;
+
+    let token = create_test_tenant_access_token_for_user(user_id, Uuid::new_v4());
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = put_json_with_auth(
+        &app,
+        "/api/v1/users/me/locale",
+        &json!({ "locale": "fr-FR" }),
+        &token,
+    )
+    .await;
+    assert!(status.is_client_error());
+}
+
 // ============================================================================
 // Self-Update via PUT /api/v1/users/:id Tests
 // ============================================================================
@@ -704,7 +767,7 @@ async fn test_update_me_changes_display_name() {
 async fn test_self_update_succeeds_without_admin() {
     let keycloak_user_id = "kc-self-update";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let mut user = create_test_user(Some(user_id));
@@ -731,7 +794,7 @@ async fn test_self_update_succeeds_without_admin() {
 
 #[tokio::test]
 async fn test_update_other_user_requires_admin() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let other_user_id = Uuid::new_v4();
@@ -757,13 +820,94 @@ async fn test_update_other_user_requires_admin() {
     assert_eq!(status, StatusCode::FORBIDDEN);
 }
 
+// ============================================================================
+// GET /api/v1/users/:id/timeline Tests
+// ============================================================================
+
+fn timeline_entry(source: UserTimelineSource, event: &str, minutes_ago: i64) -> UserTimelineEntry {
+    UserTimelineEntry {
+        source,
+        event: event.to_string(),
+        occurred_at: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+        source_id: Uuid::new_v4().to_string(),
+        actor_id: None,
+        tenant_id: None,
+        ip_address: None,
+        details: json!({}),
+    }
+}
+
+#[tokio::test]
+async fn test_get_own_timeline_is_paginated_newest_first() {
+    let state = TestAppState::new();
+
+    let user_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_user(create_test_user(Some(user_id)))
+        .await;
+    let sid = auth9_core::models::common::StringUuid::from(user_id);
+    for entry in [
+        timeline_entry(UserTimelineSource::Login, "success", 30),
+        timeline_entry(UserTimelineSource::Session, "session_created", 29),
+        timeline_entry(UserTimelineSource::Audit, "user.update", 10),
+        timeline_entry(UserTimelineSource::RoleGrant, "role_granted", 5),
+    ] {
+        state.user_repo.add_timeline_entry(sid, entry).await;
+    }
+
+    let token = create_test_tenant_access_token_for_user(user_id, Uuid::new_v4());
+    let app = build_test_router(state);
+
+    let (status, body): (StatusCode, Option<PaginatedResponse<UserTimelineEntry>>) =
+        get_json_with_auth(
+            &app,
+            &format!("/api/v1/users/{}/timeline?page=1&per_page=3", user_id),
+            &token,
+        )
+        .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let response = body.unwrap();
+    assert_eq!(response.pagination.total, 4);
+    assert_eq!(response.pagination.total_pages, 2);
+    let events: Vec<&str> = response.data.iter().map(|e| e.event.as_str()).collect();
+    assert_eq!(
+        events,
+        vec!["role_granted", "user.update", "session_created"]
+    );
+}
+
+#[tokio::test]
+async fn test_get_other_user_timeline_requires_permission() {
+    let state = TestAppState::new();
+
+    let other_user_id = Uuid::new_v4();
+    state
+        .user_repo
+        .add_user(create_test_user(Some(other_user_id)))
+        .await;
+
+    let token = create_test_identity_token_for_user(Uuid::new_v4());
+    let app = build_test_router(state);
+
+    let (status, _body): (StatusCode, Option<serde_json::Value>) = get_json_with_auth(
+        &app,
+        &format!("/api/v1/users/{}/timeline", other_user_id),
+        &token,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
 // ============================================================================
 // Authorization Tests
 // ============================================================================
 
 #[tokio::test]
 async fn test_list_users_non_admin_identity_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // Non-admin identity token (email not in platform_admin_emails)
     let token = create_test_identity_token_for_user(Uuid::new_v4());
@@ -777,7 +921,7 @@ async fn test_list_users_non_admin_identity_returns_403() {
 
 #[tokio::test]
 async fn test_list_users_service_client_with_tenant() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
 
@@ -798,7 +942,7 @@ async fn test_list_users_service_client_with_tenant() {
 
 #[tokio::test]
 async fn test_list_users_service_client_no_tenant_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     // ServiceClient token without tenant context
     let jwt_manager = crate::support::create_test_jwt_manager();
@@ -816,7 +960,7 @@ async fn test_list_users_service_client_no_tenant_returns_403() {
 
 #[tokio::test]
 async fn test_list_users_tenant_access_token() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let tenant_id = Uuid::new_v4();
     let user_id = Uuid::new_v4();
@@ -857,7 +1001,7 @@ async fn test_list_users_tenant_access_token() {
 
 #[tokio::test]
 async fn test_get_user_service_client_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let user = create_test_user(Some(user_id));
@@ -878,7 +1022,7 @@ async fn test_get_user_service_client_returns_403() {
 
 #[tokio::test]
 async fn test_get_user_tenant_access_no_admin_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let other_user_id = Uuid::new_v4();
@@ -908,7 +1052,7 @@ async fn test_get_user_tenant_access_no_admin_returns_403() {
 
 #[tokio::test]
 async fn test_get_user_tenant_access_with_admin_role() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let other_user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -950,7 +1094,7 @@ async fn test_get_user_tenant_access_with_admin_role() {
 
 #[tokio::test]
 async fn test_get_user_cross_tenant_returns_404() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let other_user_id = Uuid::new_v4();
     let user = create_test_user(Some(other_user_id));
@@ -993,7 +1137,7 @@ async fn test_get_user_cross_tenant_returns_404() {
 
 #[tokio::test]
 async fn test_create_user_public_registration_disabled_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     // Don't call enable_public_registration - it's disabled by default
     let app = build_test_router(state);
 
@@ -1009,7 +1153,7 @@ async fn test_create_user_public_registration_disabled_returns_403() {
 
 #[tokio::test]
 async fn test_create_user_with_invalid_auth_header_returns_401() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let app = build_test_router(state);
 
     let input = json!({
@@ -1025,7 +1169,7 @@ async fn test_create_user_with_invalid_auth_header_returns_401() {
 
 #[tokio::test]
 async fn test_create_user_admin_can_create_without_registration() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     // Don't enable public registration - admin can always create
     // Add active tenant matching the token's tenant_id (require_active check)
     let tenant_id = Uuid::new_v4();
@@ -1047,7 +1191,7 @@ async fn test_create_user_admin_can_create_without_registration() {
 
 #[tokio::test]
 async fn test_delete_user_non_admin_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let user = create_test_user(Some(user_id));
@@ -1065,7 +1209,7 @@ async fn test_delete_user_non_admin_returns_403() {
 
 #[tokio::test]
 async fn test_delete_user_service_client_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let user = create_test_user(Some(user_id));
@@ -1086,7 +1230,7 @@ async fn test_delete_user_service_client_returns_403() {
 
 #[tokio::test]
 async fn test_add_user_to_tenant_invalid_role_returns_400() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -1126,7 +1270,7 @@ async fn test_add_user_to_tenant_invalid_role_returns_400() {
 
 #[tokio::test]
 async fn test_add_user_to_tenant_service_client_returns_403() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -1159,7 +1303,7 @@ async fn test_add_user_to_tenant_service_client_returns_403() {
 
 #[tokio::test]
 async fn test_list_users_with_search() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
 
@@ -1194,7 +1338,7 @@ async fn test_list_users_with_search() {
 
 #[tokio::test]
 async fn test_list_users_with_empty_search() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     let tenant_id = Uuid::new_v4();
     let token = create_test_tenant_access_token_for_tenant(tenant_id);
 
@@ -1224,11 +1368,29 @@ async fn test_list_users_with_empty_search() {
     assert_eq!(response.data.len(), 1);
 }
 
+#[tokio::test]
+async fn test_list_users_rejects_list_grammar_parameters() {
+    let state = TestAppState::new();
+    let token = create_test_tenant_access_token_for_tenant(Uuid::new_v4());
+    let app = build_test_router(state);
+
+    for query in [
+        "sort=-created_at",
+        "fields=email",
+        "filter%5Bemail%5D=a%40b.c",
+    ] {
+        let (status, _body): (StatusCode, Option<serde_json::Value>) =
+            get_json_with_auth(&app, &format!("/api/v1/users?{}", query), &token).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
 #[tokio::test]
 async fn test_tenant_access_with_user_write_permission_can_delete() {
     let keycloak_user_id = "kc-user-to-delete-by-perm";
 
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
 
     let user_id = Uuid::new_v4();
     let tenant_id = Uuid::new_v4();
@@ -1274,7 +1436,7 @@ async fn test_tenant_access_with_user_write_permission_can_delete() {
 
 #[tokio::test]
 async fn test_create_user_with_special_email() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state.enable_public_registration().await;
     let app = build_test_router(state);
 
@@ -1294,7 +1456,7 @@ async fn test_create_user_with_special_email() {
 
 #[tokio::test]
 async fn test_create_user_minimal() {
-    let state = TestAppState::new("http://localhost:8081");
+    let state = TestAppState::new();
     state.enable_public_registration().await;
     let app = build_test_router(state);
 
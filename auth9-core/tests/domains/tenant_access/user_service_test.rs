@@ -4,6 +4,7 @@
 
 use crate::support::*;
 use auth9_core::error::AppError;
+use auth9_core::models::common::{TenantId, UserId};
 use auth9_core::models::user::{AddUserToTenantInput, CreateUserInput, UpdateUserInput};
 
 // ============================================================================
@@ -296,7 +297,7 @@ async fn test_update_user_display_name() {
         avatar_url: None,
     };
 
-    let result = service.update(user_id, input).await;
+    let result = service.update(user_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap().display_name,
@@ -319,7 +320,7 @@ async fn test_update_user_avatar() {
         avatar_url: Some("https://new-avatar.com/img.png".to_string()),
     };
 
-    let result = service.update(user_id, input).await;
+    let result = service.update(user_id, input, None).await;
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap().avatar_url,
@@ -337,7 +338,7 @@ async fn test_update_user_not_found() {
         avatar_url: None,
     };
 
-    let result = service.update(StringUuid::new_v4(), input).await;
+    let result = service.update(StringUuid::new_v4(), input, None).await;
     assert!(matches!(result, Err(AppError::NotFound(_))));
 }
 
@@ -499,7 +500,9 @@ async fn test_remove_user_from_tenant_success() {
     let service = builder.build_user_service();
 
     // Remove
-    let result = service.remove_from_tenant(user_id, tenant_id).await;
+    let result = service
+        .remove_from_tenant(UserId::from(user_id), TenantId::from(tenant_id))
+        .await;
     assert!(result.is_ok());
 }
 
@@ -509,7 +512,7 @@ async fn test_remove_user_from_tenant_not_found() {
     let service = builder.build_user_service();
 
     let result = service
-        .remove_from_tenant(StringUuid::new_v4(), StringUuid::new_v4())
+        .remove_from_tenant(UserId::new_v4(), TenantId::new_v4())
         .await;
     assert!(matches!(result, Err(AppError::NotFound(_))));
 }
@@ -613,7 +616,7 @@ async fn test_update_user_multiple_fields() {
         avatar_url: Some("https://new.com/avatar.png".to_string()),
     };
 
-    let result = service.update(user_id, input).await;
+    let result = service.update(user_id, input, None).await;
     assert!(result.is_ok());
 
     let updated = result.unwrap();
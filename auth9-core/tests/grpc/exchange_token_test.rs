@@ -60,6 +60,49 @@ async fn test_exchange_token_success() {
     assert!(!response.refresh_token.is_empty());
 }
 
+#[tokio::test]
+async fn test_exchange_token_honors_client_ttl_override() {
+    let user_id = Uuid::new_v4();
+    let tenant_id = Uuid::new_v4();
+    let service_id = Uuid::new_v4();
+
+    let builder = GrpcTestBuilder::new();
+    let identity_token = builder
+        .jwt_manager
+        .create_identity_token(user_id, "test@example.com", Some("Test User"))
+        .unwrap();
+
+    let mut client = create_test_client(Uuid::new_v4(), service_id, "batch-client");
+    client.token_ttls.access_token_ttl_secs = Some(43_200);
+    let service = builder
+        .with_user(create_test_user(user_id))
+        .await
+        .with_service(create_test_service(service_id, tenant_id))
+        .await
+        .with_client(client)
+        .await
+        .with_user_roles(
+            user_id,
+            tenant_id,
+            service_id,
+            create_user_roles(user_id, tenant_id, vec![], vec![]),
+        )
+        .await
+        .build_with_noop_cache();
+
+    let response = service
+        .exchange_token(Request::new(ExchangeTokenRequest {
+            identity_token,
+            tenant_id: tenant_id.to_string(),
+            service_id: "batch-client".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.expires_in, 43_200);
+}
+
 #[tokio::test]
 async fn test_exchange_token_invalid_identity_token() {
     let tenant_id = Uuid::new_v4();
@@ -0,0 +1,192 @@
+//! LoginTelemetry gRPC service tests
+
+use super::*;
+use crate::support::TestLoginEventRepository;
+use auth9_core::error::AppError;
+use auth9_core::grpc::login_event::ClientCredentialVerifier;
+use auth9_core::grpc::proto::login_telemetry_server::LoginTelemetry;
+use auth9_core::grpc::proto::{
+    LoginEventReport, RecordLoginEventRequest, RecordLoginEventsRequest,
+};
+use auth9_core::grpc::LoginTelemetryService;
+use auth9_core::models::analytics::LoginEventType;
+use auth9_core::repository::LoginEventRepository;
+use tonic::{Code, Request};
+use uuid::Uuid;
+
+/// Accepts a single client_id/secret pair and returns the configured service
+struct StaticVerifier {
+    service: Service,
+}
+
+#[async_trait::async_trait]
+impl ClientCredentialVerifier for StaticVerifier {
+    async fn verify_client(
+        &self,
+        client_id: &str,
+        secret: &str,
+    ) -> auth9_core::error::Result<Service> {
+        if client_id == "edge-gateway" && secret == "edge-secret" {
+            Ok(self.service.clone())
+        } else {
+            Err(AppError::Unauthorized(
+                "Invalid client credentials".to_string(),
+            ))
+        }
+    }
+}
+
+fn build_service(
+    tenant_id: Option<Uuid>,
+) -> (
+    LoginTelemetryService<TestLoginEventRepository>,
+    Arc<TestLoginEventRepository>,
+) {
+    let service = match tenant_id {
+        Some(tenant_id) => create_test_service(Uuid::new_v4(), tenant_id),
+        None => create_test_service_without_tenant(Uuid::new_v4()),
+    };
+    let repo = Arc::new(TestLoginEventRepository::new());
+    let telemetry = LoginTelemetryService::new(repo.clone(), Arc::new(StaticVerifier { service }));
+    (telemetry, repo)
+}
+
+fn authed<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("x-client-id", "edge-gateway".parse().unwrap());
+    request
+        .metadata_mut()
+        .insert("x-client-secret", "edge-secret".parse().unwrap());
+    request
+}
+
+fn report(outcome: &str) -> LoginEventReport {
+    LoginEventReport {
+        email: "user@example.com".to_string(),
+        outcome: outcome.to_string(),
+        ip_address: "10.0.0.1".to_string(),
+        user_agent: "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) Mobile".to_string(),
+        latency_ms: 240,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_record_login_event_enriches_and_stores() {
+    let tenant_id = Uuid::new_v4();
+    let (service, repo) = build_service(Some(tenant_id));
+
+    let response = service
+        .record_login_event(authed(RecordLoginEventRequest {
+            event: Some(report("success")),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let stored = repo.find_by_id(response.event_id).await.unwrap().unwrap();
+    assert_eq!(stored.event_type, LoginEventType::Success);
+    assert_eq!(stored.tenant_id, Some(StringUuid::from(tenant_id)));
+    assert_eq!(stored.device_type.as_deref(), Some("mobile"));
+    assert_eq!(stored.email.as_deref(), Some("user@example.com"));
+    assert!(stored.failure_reason.is_none());
+}
+
+#[tokio::test]
+async fn test_record_login_event_requires_client_credentials() {
+    let (service, _) = build_service(None);
+
+    let err = service
+        .record_login_event(Request::new(RecordLoginEventRequest {
+            event: Some(report("success")),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let mut request = authed(RecordLoginEventRequest {
+        event: Some(report("success")),
+    });
+    request
+        .metadata_mut()
+        .insert("x-client-secret", "wrong".parse().unwrap());
+    let err = service.record_login_event(request).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_record_login_event_rejects_foreign_tenant() {
+    let (service, repo) = build_service(Some(Uuid::new_v4()));
+
+    let mut event = report("failed_password");
+    event.tenant_id = Uuid::new_v4().to_string();
+    let err = service
+        .record_login_event(authed(RecordLoginEventRequest { event: Some(event) }))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.code(), Code::PermissionDenied);
+    assert_eq!(repo.count().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_record_login_events_batch() {
+    let (service, repo) = build_service(None);
+
+    let mut failed = report("failed_password");
+    failed.failure_reason = "Invalid password".to_string();
+    let response = service
+        .record_login_events(authed(RecordLoginEventsRequest {
+            events: vec![report("success"), failed],
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.event_ids.len(), 2);
+    let stored = repo
+        .find_by_id(response.event_ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.event_type, LoginEventType::FailedPassword);
+    assert_eq!(stored.failure_reason.as_deref(), Some("Invalid password"));
+}
+
+#[tokio::test]
+async fn test_record_login_events_rejects_whole_batch_on_invalid_entry() {
+    let (service, repo) = build_service(None);
+
+    let err = service
+        .record_login_events(authed(RecordLoginEventsRequest {
+            events: vec![report("success"), report("identity_linked")],
+        }))
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert!(err.message().starts_with("events[1]"));
+    assert_eq!(repo.count().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_record_login_events_enforces_batch_limits() {
+    let (service, _) = build_service(None);
+
+    let err = service
+        .record_login_events(authed(RecordLoginEventsRequest { events: vec![] }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let events = (0..=auth9_core::grpc::login_event::MAX_BATCH_SIZE)
+        .map(|_| report("success"))
+        .collect();
+    let err = service
+        .record_login_events(authed(RecordLoginEventsRequest { events }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
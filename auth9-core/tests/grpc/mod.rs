@@ -6,6 +6,8 @@
 pub mod exchange_token_test;
 pub mod get_user_roles_test;
 pub mod introspect_token_test;
+pub mod login_event_test;
+pub mod provisioning_test;
 pub mod validate_token_test;
 
 use auth9_core::cache::NoOpCacheManager;
@@ -13,7 +15,7 @@ use auth9_core::config::JwtConfig;
 use auth9_core::grpc::token_exchange::TokenExchangeCache;
 use auth9_core::grpc::TokenExchangeService;
 use auth9_core::jwt::JwtManager;
-use auth9_core::models::common::StringUuid;
+use auth9_core::models::common::{ServiceId, StringUuid, TenantId, UserId};
 use auth9_core::models::rbac::{Permission, Role, UserRolesInTenant};
 use auth9_core::models::service::{Client, Service, ServiceStatus};
 use auth9_core::models::user::User;
@@ -39,6 +41,8 @@ pub fn test_jwt_config() -> JwtConfig {
         private_key_pem: None,
         public_key_pem: None,
         previous_public_key_pem: None,
+        max_access_token_ttl_secs: 86400,
+        max_refresh_token_ttl_secs: 7776000,
     }
 }
 
@@ -119,36 +123,36 @@ impl Default for MockCacheManager {
 impl TokenExchangeCache for MockCacheManager {
     async fn get_user_roles_for_service(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
-        service_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
+        service_id: ServiceId,
     ) -> auth9_core::error::Result<Option<UserRolesInTenant>> {
         self.get_count.fetch_add(1, Ordering::Relaxed);
         let cache = self.cached_roles_for_service.read().await;
-        Ok(cache.get(&(user_id, tenant_id, service_id)).cloned())
+        Ok(cache.get(&(user_id.0, tenant_id.0, service_id.0)).cloned())
     }
 
     async fn set_user_roles_for_service(
         &self,
         roles: &UserRolesInTenant,
-        service_id: Uuid,
+        service_id: ServiceId,
     ) -> auth9_core::error::Result<()> {
         self.set_count.fetch_add(1, Ordering::Relaxed);
-        self.cached_roles_for_service
-            .write()
-            .await
-            .insert((roles.user_id, roles.tenant_id, service_id), roles.clone());
+        self.cached_roles_for_service.write().await.insert(
+            (roles.user_id, roles.tenant_id, service_id.0),
+            roles.clone(),
+        );
         Ok(())
     }
 
     async fn get_user_roles(
         &self,
-        user_id: Uuid,
-        tenant_id: Uuid,
+        user_id: UserId,
+        tenant_id: TenantId,
     ) -> auth9_core::error::Result<Option<UserRolesInTenant>> {
         self.get_count.fetch_add(1, Ordering::Relaxed);
         let cache = self.cached_roles.read().await;
-        Ok(cache.get(&(user_id, tenant_id)).cloned())
+        Ok(cache.get(&(user_id.0, tenant_id.0)).cloned())
     }
 
     async fn set_user_roles(&self, roles: &UserRolesInTenant) -> auth9_core::error::Result<()> {
@@ -178,6 +182,8 @@ pub fn create_test_user(id: Uuid) -> User {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
@@ -196,6 +202,8 @@ pub fn create_test_user_with_email(id: Uuid, email: &str) -> User {
         email_otp_enabled: false,
         password_changed_at: None,
         locked_until: None,
+        locale: None,
+        version: 1,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
@@ -210,6 +218,8 @@ pub fn create_test_service(id: Uuid, tenant_id: Uuid) -> Service {
         redirect_uris: vec!["https://test.example.com/callback".to_string()],
         logout_uris: vec![],
         status: ServiceStatus::Active,
+        version: 1,
+        catalog: Default::default(),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
@@ -224,6 +234,8 @@ pub fn create_test_service_without_tenant(id: Uuid) -> Service {
         redirect_uris: vec!["https://global.example.com/callback".to_string()],
         logout_uris: vec![],
         status: ServiceStatus::Active,
+        version: 1,
+        catalog: Default::default(),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
@@ -238,6 +250,7 @@ pub fn create_test_client(id: Uuid, service_id: Uuid, client_id: &str) -> Client
         client_secret_hash: "hash".to_string(),
         public_client: false,
         created_at: chrono::Utc::now(),
+        token_ttls: Default::default(),
     }
 }
 
@@ -248,6 +261,7 @@ pub fn create_test_role(id: Uuid, service_id: Uuid, name: &str) -> Role {
         name: name.to_string(),
         description: Some(format!("{} role", name)),
         parent_role_id: None,
+        version: 1,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
     }
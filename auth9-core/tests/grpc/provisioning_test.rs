@@ -0,0 +1,323 @@
+//! Provisioning gRPC service tests
+
+use super::*;
+use crate::support::create_test_user;
+use crate::support::http::TestAppState;
+use auth9_core::error::AppError;
+use auth9_core::grpc::login_event::ClientCredentialVerifier;
+use auth9_core::grpc::proto::provisioning_server::Provisioning;
+use auth9_core::grpc::proto::{AddUserToTenantRequest, CreateTenantRequest, CreateUserRequest};
+use auth9_core::grpc::ProvisioningService;
+use auth9_core::repository::idempotency::IdempotencyRecord;
+use auth9_core::repository::{IdempotencyRepository, TenantRepository, UserRepository};
+use chrono::{DateTime, Utc};
+use tonic::{Code, Request};
+
+/// Accepts the secret "secret" for two clients and returns the configured service
+struct StaticVerifier {
+    service: Service,
+}
+
+#[async_trait::async_trait]
+impl ClientCredentialVerifier for StaticVerifier {
+    async fn verify_client(
+        &self,
+        client_id: &str,
+        secret: &str,
+    ) -> auth9_core::error::Result<Service> {
+        if secret == "secret" && (client_id == "platform-svc" || client_id == "other-svc") {
+            Ok(self.service.clone())
+        } else {
+            Err(AppError::Unauthorized(
+                "Invalid client credentials".to_string(),
+            ))
+        }
+    }
+}
+
+/// In-memory idempotency keys (never expire)
+#[derive(Default)]
+struct TestIdempotencyRepository {
+    records: RwLock<HashMap<(String, String, String), IdempotencyRecord>>,
+}
+
+#[async_trait::async_trait]
+impl IdempotencyRepository for TestIdempotencyRepository {
+    async fn claim(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        request_hash: &str,
+        _expired_before: DateTime<Utc>,
+    ) -> auth9_core::error::Result<Option<IdempotencyRecord>> {
+        let mut records = self.records.write().await;
+        let id = (client_id.to_string(), method.to_string(), key.to_string());
+        if let Some(existing) = records.get(&id) {
+            return Ok(Some(existing.clone()));
+        }
+        records.insert(
+            id,
+            IdempotencyRecord {
+                request_hash: request_hash.to_string(),
+                response: None,
+            },
+        );
+        Ok(None)
+    }
+
+    async fn complete(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+        response: &[u8],
+    ) -> auth9_core::error::Result<()> {
+        let id = (client_id.to_string(), method.to_string(), key.to_string());
+        if let Some(record) = self.records.write().await.get_mut(&id) {
+            record.response = Some(response.to_vec());
+        }
+        Ok(())
+    }
+
+    async fn release(
+        &self,
+        client_id: &str,
+        method: &str,
+        key: &str,
+    ) -> auth9_core::error::Result<()> {
+        let id = (client_id.to_string(), method.to_string(), key.to_string());
+        self.records.write().await.remove(&id);
+        Ok(())
+    }
+
+    async fn delete_expired(
+        &self,
+        _expired_before: DateTime<Utc>,
+    ) -> auth9_core::error::Result<u64> {
+        Ok(0)
+    }
+}
+
+fn build_service(
+    service_tenant: Option<Uuid>,
+) -> (
+    ProvisioningService<TestAppState, TestIdempotencyRepository>,
+    TestAppState,
+) {
+    let mut state = TestAppState::new();
+    let mut config = (*state.config).clone();
+    config.grpc_security.provisioning_client_ids = vec!["platform-svc".to_string()];
+    state.config = Arc::new(config);
+
+    let service = match service_tenant {
+        Some(tenant_id) => create_test_service(Uuid::new_v4(), tenant_id),
+        None => create_test_service_without_tenant(Uuid::new_v4()),
+    };
+    let provisioning = ProvisioningService::new(
+        state.clone(),
+        Arc::new(StaticVerifier { service }),
+        Arc::new(TestIdempotencyRepository::default()),
+    );
+    (provisioning, state)
+}
+
+fn authed<T>(client_id: &str, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("x-client-id", client_id.parse().unwrap());
+    request
+        .metadata_mut()
+        .insert("x-client-secret", "secret".parse().unwrap());
+    request
+}
+
+fn tenant_request(key: &str, slug: &str) -> CreateTenantRequest {
+    CreateTenantRequest {
+        idempotency_key: key.to_string(),
+        name: "Acme".to_string(),
+        slug: slug.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_create_tenant_replays_with_same_idempotency_key() {
+    let (service, state) = build_service(None);
+
+    let first = service
+        .create_tenant(authed("platform-svc", tenant_request("key-1", "acme")))
+        .await
+        .unwrap()
+        .into_inner();
+    let retried = service
+        .create_tenant(authed("platform-svc", tenant_request("key-1", "acme")))
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(first, retried);
+    assert_eq!(first.slug, "acme");
+    let stored = state.tenant_repo.find_by_slug("acme").await.unwrap();
+    assert_eq!(stored.unwrap().id.to_string(), first.tenant_id);
+}
+
+#[tokio::test]
+async fn test_idempotency_key_reused_for_different_request() {
+    let (service, _) = build_service(None);
+
+    service
+        .create_tenant(authed("platform-svc", tenant_request("key-1", "acme")))
+        .await
+        .unwrap();
+    let err = service
+        .create_tenant(authed("platform-svc", tenant_request("key-1", "globex")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[tokio::test]
+async fn test_failed_call_releases_idempotency_key() {
+    let (service, _) = build_service(None);
+
+    let invalid = CreateTenantRequest {
+        settings_json: "{not json".to_string(),
+        ..tenant_request("key-1", "acme")
+    };
+    let err = service
+        .create_tenant(authed("platform-svc", invalid))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    // The same key is usable once the request is fixed
+    service
+        .create_tenant(authed("platform-svc", tenant_request("key-1", "acme")))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_create_tenant_validates_like_rest() {
+    let (service, _) = build_service(None);
+
+    let err = service
+        .create_tenant(authed("platform-svc", tenant_request("", "Not A Slug")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+
+    let unknown_region = CreateTenantRequest {
+        data_region: "mars-1".to_string(),
+        ..tenant_request("", "acme")
+    };
+    let err = service
+        .create_tenant(authed("platform-svc", unknown_region))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_create_tenant_requires_platform_service() {
+    let (service, _) = build_service(Some(Uuid::new_v4()));
+
+    let err = service
+        .create_tenant(authed("platform-svc", tenant_request("", "acme")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_client_must_be_allowlisted() {
+    let (service, _) = build_service(None);
+
+    let err = service
+        .create_tenant(authed("other-svc", tenant_request("", "acme")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+
+    let err = service
+        .create_tenant(Request::new(tenant_request("", "acme")))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_create_user_joins_service_tenant() {
+    let (platform, state) = build_service(None);
+    let tenant = platform
+        .create_tenant(authed("platform-svc", tenant_request("", "acme")))
+        .await
+        .unwrap()
+        .into_inner();
+    let tenant_id: Uuid = tenant.tenant_id.parse().unwrap();
+
+    let service = ProvisioningService::new(
+        state.clone(),
+        Arc::new(StaticVerifier {
+            service: create_test_service(Uuid::new_v4(), tenant_id),
+        }),
+        Arc::new(TestIdempotencyRepository::default()),
+    );
+    let created = service
+        .create_user(authed(
+            "platform-svc",
+            CreateUserRequest {
+                email: "new@example.com".to_string(),
+                ..Default::default()
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let memberships = state
+        .user_repo
+        .find_user_tenants(created.user_id.parse::<StringUuid>().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(memberships.len(), 1);
+    assert_eq!(memberships[0].tenant_id.to_string(), tenant.tenant_id);
+    assert_eq!(memberships[0].role_in_tenant, "member");
+
+    let err = service
+        .create_user(authed(
+            "platform-svc",
+            CreateUserRequest {
+                email: "other@example.com".to_string(),
+                tenant_id: Uuid::new_v4().to_string(),
+                ..Default::default()
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_add_user_to_tenant_rejects_unknown_role() {
+    let (service, state) = build_service(None);
+    let user = create_test_user(None);
+    let user_id = user.id;
+    state.user_repo.add_user(user).await;
+
+    let err = service
+        .add_user_to_tenant(authed(
+            "platform-svc",
+            AddUserToTenantRequest {
+                user_id: user_id.to_string(),
+                tenant_id: Uuid::new_v4().to_string(),
+                role_in_tenant: "superuser".to_string(),
+                ..Default::default()
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+}
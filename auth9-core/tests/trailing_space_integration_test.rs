@@ -25,6 +25,8 @@ mod trailing_space_tests {
             private_key_pem: None,
             public_key_pem: None,
             previous_public_key_pem: None,
+            max_access_token_ttl_secs: 86400,
+            max_refresh_token_ttl_secs: 7776000,
         };
         JwtManager::new(config)
     }